@@ -11,31 +11,9 @@
 use crate::code_digester::{CodeElement, CodeElementKind};
 use tree_sitter::{Language, Parser, Query, QueryCursor, StreamingIterator as _};
 
-/// Supported languages for AST extraction.
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum CodeLanguage {
-    Rust,
-    Python,
-    JavaScript,
-}
+pub use crate::code_digester::CodeLanguage;
 
 impl CodeLanguage {
-    /// Detect language from file extension.
-    pub fn from_extension(ext: &str) -> Option<Self> {
-        match ext {
-            "rs" => Some(Self::Rust),
-            "py" | "pyi" => Some(Self::Python),
-            "js" | "jsx" | "ts" | "tsx" | "mjs" => Some(Self::JavaScript),
-            _ => None,
-        }
-    }
-
-    /// Detect language from filename.
-    pub fn from_filename(filename: &str) -> Option<Self> {
-        let ext = filename.rsplit('.').next()?;
-        Self::from_extension(ext)
-    }
-
     fn tree_sitter_language(&self) -> Language {
         match self {
             Self::Rust => tree_sitter_rust::LANGUAGE.into(),
@@ -158,6 +136,7 @@ impl AstDigester {
                         kind,
                         file: filename.to_string(),
                         line,
+                        target: None,
                     });
                 }
             }
@@ -1,8 +1,60 @@
 //! Code-aware digester agent for source code analysis.
 //!
 //! Extracts function names, type definitions, imports, and structural
-//! patterns from Rust source code. Builds a code knowledge graph
+//! patterns from Rust, Python and JavaScript/TypeScript source code,
+//! choosing the extractor by file extension. Builds a code knowledge graph
 //! where concepts are identifiers and edges are co-occurrence relations.
+//!
+//! [`CodeDigester`] presents a source file's definitions qualified by the
+//! file's path, so that the `new` of one file is not the `new` of another,
+//! and what the file imports unqualified, linking it to where those names
+//! are defined.
+
+use crate::digester::Digester;
+use phago_core::agent::Agent;
+use phago_core::primitives::digest::DigestionLimits;
+use phago_core::primitives::symbiose::AgentProfile;
+use phago_core::primitives::{Apoptose, Digest, Sense};
+use phago_core::substrate::Substrate;
+use phago_core::tokenize::Tokenizer;
+use phago_core::types::*;
+use std::collections::{HashMap, HashSet};
+
+/// Source languages with a line-based extractor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    /// JavaScript and TypeScript.
+    JavaScript,
+}
+
+impl CodeLanguage {
+    /// All supported languages.
+    pub const ALL: [CodeLanguage; 3] = [Self::Rust, Self::Python, Self::JavaScript];
+
+    /// Detect language from file extension.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|lang| lang.extensions().contains(&ext))
+    }
+
+    /// Detect language from filename.
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let ext = filename.rsplit('.').next()?;
+        Self::from_extension(ext)
+    }
+
+    /// File extensions handled by this language.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::Rust => &["rs"],
+            Self::Python => &["py", "pyi"],
+            Self::JavaScript => &["js", "jsx", "ts", "tsx", "mjs", "cjs"],
+        }
+    }
+}
 
 /// A code element extracted from source files.
 #[derive(Debug, Clone)]
@@ -11,6 +63,9 @@ pub struct CodeElement {
     pub kind: CodeElementKind,
     pub file: String,
     pub line: usize,
+    /// The element this one belongs to: the class of a method, or the
+    /// definition a decorator is applied to.
+    pub target: Option<String>,
 }
 
 /// Types of code elements we extract.
@@ -24,6 +79,9 @@ pub enum CodeElementKind {
     Use,
     Const,
     Module,
+    Interface,
+    Method,
+    Decorator,
 }
 
 impl CodeElementKind {
@@ -37,12 +95,51 @@ impl CodeElementKind {
             Self::Use => "use",
             Self::Const => "const",
             Self::Module => "mod",
+            Self::Interface => "interface",
+            Self::Method => "method",
+            Self::Decorator => "decorator",
         }
     }
+
+    /// Whether the element defines its name, rather than referring to a
+    /// name defined elsewhere.
+    pub fn is_definition(&self) -> bool {
+        !matches!(self, Self::Impl | Self::Use | Self::Decorator)
+    }
 }
 
-/// Extract code elements from Rust source code.
+/// Extract code elements, choosing the extractor from the file extension.
+///
+/// Files with an unrecognised extension are parsed as Rust.
 pub fn extract_code_elements(source: &str, filename: &str) -> Vec<CodeElement> {
+    let language = CodeLanguage::from_filename(filename).unwrap_or(CodeLanguage::Rust);
+    extract_language_elements(language, source, filename)
+}
+
+/// Extract code elements from source in a known language.
+pub fn extract_language_elements(
+    language: CodeLanguage,
+    source: &str,
+    filename: &str,
+) -> Vec<CodeElement> {
+    match language {
+        CodeLanguage::Rust => extract_rust_elements(source, filename),
+        CodeLanguage::Python => extract_python_elements(source, filename),
+        CodeLanguage::JavaScript => extract_javascript_elements(source, filename),
+    }
+}
+
+fn element(name: String, kind: CodeElementKind, file: &str, line: usize) -> CodeElement {
+    CodeElement {
+        name,
+        kind,
+        file: file.to_string(),
+        line,
+        target: None,
+    }
+}
+
+fn extract_rust_elements(source: &str, filename: &str) -> Vec<CodeElement> {
     let mut elements = Vec::new();
 
     for (line_num, line) in source.lines().enumerate() {
@@ -54,84 +151,167 @@ pub fn extract_code_elements(source: &str, filename: &str) -> Vec<CodeElement> {
             || trimmed.starts_with("pub(crate) fn ")
         {
             if let Some(name) = extract_identifier(trimmed, "fn ") {
-                elements.push(CodeElement {
+                elements.push(element(
                     name,
-                    kind: CodeElementKind::Function,
-                    file: filename.to_string(),
-                    line: line_num + 1,
-                });
+                    CodeElementKind::Function,
+                    filename,
+                    line_num + 1,
+                ));
             }
         }
 
         // Struct definitions
         if trimmed.starts_with("pub struct ") || trimmed.starts_with("struct ") {
             if let Some(name) = extract_identifier(trimmed, "struct ") {
-                elements.push(CodeElement {
+                elements.push(element(
                     name,
-                    kind: CodeElementKind::Struct,
-                    file: filename.to_string(),
-                    line: line_num + 1,
-                });
+                    CodeElementKind::Struct,
+                    filename,
+                    line_num + 1,
+                ));
             }
         }
 
         // Enum definitions
         if trimmed.starts_with("pub enum ") || trimmed.starts_with("enum ") {
             if let Some(name) = extract_identifier(trimmed, "enum ") {
-                elements.push(CodeElement {
-                    name,
-                    kind: CodeElementKind::Enum,
-                    file: filename.to_string(),
-                    line: line_num + 1,
-                });
+                elements.push(element(name, CodeElementKind::Enum, filename, line_num + 1));
             }
         }
 
         // Trait definitions
         if trimmed.starts_with("pub trait ") || trimmed.starts_with("trait ") {
             if let Some(name) = extract_identifier(trimmed, "trait ") {
-                elements.push(CodeElement {
+                elements.push(element(
                     name,
-                    kind: CodeElementKind::Trait,
-                    file: filename.to_string(),
-                    line: line_num + 1,
-                });
+                    CodeElementKind::Trait,
+                    filename,
+                    line_num + 1,
+                ));
             }
         }
 
         // Impl blocks
         if trimmed.starts_with("impl ") || trimmed.starts_with("impl<") {
             if let Some(name) = extract_impl_name(trimmed) {
-                elements.push(CodeElement {
-                    name,
-                    kind: CodeElementKind::Impl,
-                    file: filename.to_string(),
-                    line: line_num + 1,
-                });
+                elements.push(element(name, CodeElementKind::Impl, filename, line_num + 1));
             }
         }
 
         // Use statements
         if trimmed.starts_with("use ") || trimmed.starts_with("pub use ") {
             if let Some(name) = extract_use_path(trimmed) {
-                elements.push(CodeElement {
-                    name,
-                    kind: CodeElementKind::Use,
-                    file: filename.to_string(),
-                    line: line_num + 1,
-                });
+                elements.push(element(name, CodeElementKind::Use, filename, line_num + 1));
             }
         }
 
         // Module declarations
         if trimmed.starts_with("pub mod ") || trimmed.starts_with("mod ") {
             if let Some(name) = extract_identifier(trimmed, "mod ") {
-                elements.push(CodeElement {
+                elements.push(element(
                     name,
-                    kind: CodeElementKind::Module,
-                    file: filename.to_string(),
-                    line: line_num + 1,
-                });
+                    CodeElementKind::Module,
+                    filename,
+                    line_num + 1,
+                ));
+            }
+        }
+    }
+
+    elements
+}
+
+/// Extract code elements from Python source.
+///
+/// Functions defined directly in a class body become methods of that
+/// class; decorators are recorded against the definition they wrap.
+fn extract_python_elements(source: &str, filename: &str) -> Vec<CodeElement> {
+    let mut elements = Vec::new();
+    // Enclosing `def`/`class` blocks: (indent, class name if a class)
+    let mut scopes: Vec<(usize, Option<String>)> = Vec::new();
+    let mut decorators: Vec<(String, usize)> = Vec::new();
+
+    for (line_num, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        while scopes.last().is_some_and(|(scope, _)| indent <= *scope) {
+            scopes.pop();
+        }
+        let line = line_num + 1;
+
+        if let Some(rest) = trimmed.strip_prefix('@') {
+            if let Some(name) = dotted_last_segment(rest) {
+                decorators.push((name, line));
+            }
+            continue;
+        }
+
+        let def = trimmed
+            .strip_prefix("async def ")
+            .or_else(|| trimmed.strip_prefix("def "));
+        if let Some(name) = def.and_then(leading_identifier) {
+            match scopes.last() {
+                Some((_, Some(class))) => elements.push(CodeElement {
+                    target: Some(class.clone()),
+                    ..element(name.clone(), CodeElementKind::Method, filename, line)
+                }),
+                _ => elements.push(element(
+                    name.clone(),
+                    CodeElementKind::Function,
+                    filename,
+                    line,
+                )),
+            }
+            attach_decorators(&mut elements, &mut decorators, &name, filename);
+            scopes.push((indent, None));
+            continue;
+        }
+
+        if let Some(name) = trimmed.strip_prefix("class ").and_then(leading_identifier) {
+            elements.push(element(
+                name.clone(),
+                CodeElementKind::Struct,
+                filename,
+                line,
+            ));
+            attach_decorators(&mut elements, &mut decorators, &name, filename);
+            scopes.push((indent, Some(name)));
+            continue;
+        }
+        decorators.clear();
+
+        if let Some(rest) = trimmed.strip_prefix("import ") {
+            for part in rest.split(',') {
+                let module = part.split(" as ").next().unwrap_or(part);
+                if let Some(name) = dotted_last_segment(module.trim()) {
+                    elements.push(element(name, CodeElementKind::Use, filename, line));
+                }
+            }
+        } else if let Some((_, names)) = trimmed
+            .strip_prefix("from ")
+            .and_then(|rest| rest.split_once(" import "))
+        {
+            for part in names.trim_matches(['(', ')']).split(',') {
+                let imported = part.split(" as ").next().unwrap_or(part).trim();
+                if let Some(name) = leading_identifier(imported) {
+                    elements.push(element(name, CodeElementKind::Use, filename, line));
+                }
+            }
+        } else if indent == 0 {
+            // Module-level UPPER_CASE assignments are constants
+            if let Some((lhs, _)) = trimmed.split_once('=') {
+                let lhs = lhs.split(':').next().unwrap_or(lhs).trim();
+                if is_constant_name(lhs) {
+                    elements.push(element(
+                        lhs.to_string(),
+                        CodeElementKind::Const,
+                        filename,
+                        line,
+                    ));
+                }
             }
         }
     }
@@ -139,6 +319,229 @@ pub fn extract_code_elements(source: &str, filename: &str) -> Vec<CodeElement> {
     elements
 }
 
+/// Extract code elements from JavaScript or TypeScript source.
+///
+/// Tracks brace depth to recognise methods declared directly in a class
+/// body. Re-exports (`export { .. } from`) are recorded like imports.
+fn extract_javascript_elements(source: &str, filename: &str) -> Vec<CodeElement> {
+    let mut elements = Vec::new();
+    let mut depth: usize = 0;
+    // Open classes: (brace depth of the class body, class name)
+    let mut classes: Vec<(usize, String)> = Vec::new();
+    let mut decorators: Vec<(String, usize)> = Vec::new();
+    let mut in_comment = false;
+
+    for (line_num, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        let line = line_num + 1;
+        if in_comment {
+            in_comment = !trimmed.contains("*/");
+            continue;
+        }
+        if trimmed.starts_with("/*") {
+            in_comment = !trimmed.contains("*/");
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with("//") || trimmed.starts_with('*') {
+            continue;
+        }
+        while classes.last().is_some_and(|(body, _)| *body > depth) {
+            classes.pop();
+        }
+
+        if let Some(rest) = trimmed.strip_prefix('@') {
+            if let Some(name) = dotted_last_segment(rest) {
+                decorators.push((name, line));
+            }
+        } else if let Some((_, class)) = classes.last().filter(|(body, _)| *body == depth) {
+            let class = class.clone();
+            let member = strip_prefixes(
+                trimmed,
+                &[
+                    "public ",
+                    "private ",
+                    "protected ",
+                    "static ",
+                    "readonly ",
+                    "override ",
+                    "abstract ",
+                    "async ",
+                    "get ",
+                    "set ",
+                    "*",
+                ],
+            );
+            if let Some(name) = leading_identifier(member) {
+                let after = member[name.len()..].trim_start_matches(['?', '!']);
+                if (after.starts_with('(') || after.starts_with('<'))
+                    && !matches!(
+                        name.as_str(),
+                        "if" | "for" | "while" | "switch" | "return" | "catch" | "constructor"
+                    )
+                {
+                    elements.push(CodeElement {
+                        target: Some(class),
+                        ..element(name.clone(), CodeElementKind::Method, filename, line)
+                    });
+                    attach_decorators(&mut elements, &mut decorators, &name, filename);
+                }
+            }
+        } else {
+            let stmt = strip_prefixes(
+                trimmed,
+                &["export ", "default ", "declare ", "abstract ", "async "],
+            );
+            let definition = [
+                ("function* ", CodeElementKind::Function),
+                ("function ", CodeElementKind::Function),
+                ("class ", CodeElementKind::Struct),
+                ("interface ", CodeElementKind::Interface),
+                ("const enum ", CodeElementKind::Enum),
+                ("enum ", CodeElementKind::Enum),
+                ("namespace ", CodeElementKind::Module),
+            ]
+            .into_iter()
+            .find_map(|(keyword, kind)| {
+                let name = stmt.strip_prefix(keyword).and_then(leading_identifier)?;
+                Some((name, kind))
+            });
+
+            if let Some((name, kind)) = definition {
+                if kind == CodeElementKind::Struct {
+                    classes.push((depth + 1, name.clone()));
+                }
+                elements.push(element(name.clone(), kind, filename, line));
+                attach_decorators(&mut elements, &mut decorators, &name, filename);
+            } else if let Some(rest) = trimmed.strip_prefix("import ") {
+                for name in import_names(rest) {
+                    elements.push(element(name, CodeElementKind::Use, filename, line));
+                }
+            } else if trimmed.starts_with("export ") && trimmed.contains(" from ") {
+                for name in import_names(&trimmed["export ".len()..]) {
+                    elements.push(element(name, CodeElementKind::Use, filename, line));
+                }
+            } else if depth == 0 {
+                // Top-level `const` bindings: arrow functions or constants
+                if let Some((name, rhs)) = stmt
+                    .strip_prefix("const ")
+                    .and_then(|rest| rest.split_once('='))
+                {
+                    if let Some(name) = leading_identifier(name.trim()) {
+                        let rhs = rhs.trim_start();
+                        let kind = if rhs.contains("=>") || rhs.starts_with("function") {
+                            CodeElementKind::Function
+                        } else {
+                            CodeElementKind::Const
+                        };
+                        elements.push(element(name, kind, filename, line));
+                    }
+                }
+            }
+        }
+
+        if !trimmed.starts_with('@') {
+            decorators.clear();
+        }
+        let opened = trimmed.matches('{').count();
+        let closed = trimmed.matches('}').count();
+        depth = (depth + opened).saturating_sub(closed);
+    }
+
+    elements
+}
+
+/// Names bound by a JS/TS import (or re-export) clause, without the
+/// leading keyword: `{ a, b as c } from './x'` yields `a` and `b`;
+/// namespace and side-effect imports yield the module name.
+fn import_names(clause: &str) -> Vec<String> {
+    let clause = clause.strip_prefix("type ").unwrap_or(clause).trim();
+    let (bindings, module) = match clause.split_once(" from ") {
+        Some((bindings, module)) => (bindings, module),
+        None => ("", clause),
+    };
+    let module_name = module
+        .trim()
+        .trim_end_matches(';')
+        .trim_matches(['\'', '"', '`'])
+        .rsplit('/')
+        .next()
+        .and_then(leading_identifier);
+
+    let mut names = Vec::new();
+    for part in bindings
+        .split(',')
+        .map(|p| p.trim_matches(|c: char| c == '{' || c == '}' || c.is_whitespace()))
+        .filter(|p| !p.is_empty())
+    {
+        if part.starts_with('*') {
+            names.extend(module_name.clone());
+        } else {
+            let part = part.strip_prefix("type ").unwrap_or(part);
+            names.extend(leading_identifier(
+                part.split(" as ").next().unwrap_or(part),
+            ));
+        }
+    }
+    if bindings.is_empty() {
+        names.extend(module_name);
+    }
+    names
+}
+
+/// Record pending decorators as applied to `target`.
+fn attach_decorators(
+    elements: &mut Vec<CodeElement>,
+    decorators: &mut Vec<(String, usize)>,
+    target: &str,
+    filename: &str,
+) {
+    for (name, line) in decorators.drain(..) {
+        elements.push(CodeElement {
+            target: Some(target.to_string()),
+            ..element(name, CodeElementKind::Decorator, filename, line)
+        });
+    }
+}
+
+/// Repeatedly strip any of `prefixes` from the start of `s`.
+fn strip_prefixes<'a>(mut s: &'a str, prefixes: &[&str]) -> &'a str {
+    while let Some(rest) = prefixes.iter().find_map(|p| s.strip_prefix(p)) {
+        s = rest.trim_start();
+    }
+    s
+}
+
+/// The identifier at the start of `s`, if any.
+fn leading_identifier(s: &str) -> Option<String> {
+    let name: String = s
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+        .collect();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// The last segment of a dotted name at the start of `s`
+/// (`app.route(...)` → `route`).
+fn dotted_last_segment(s: &str) -> Option<String> {
+    let dotted: String = s
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+        .collect();
+    dotted.rsplit('.').next().and_then(leading_identifier)
+}
+
+/// Python convention: `UPPER_CASE` names are constants.
+fn is_constant_name(name: &str) -> bool {
+    name.chars().any(|c| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
 /// Extract identifier after a keyword like "fn ", "struct ", etc.
 fn extract_identifier(line: &str, keyword: &str) -> Option<String> {
     let rest = line.split(keyword).nth(1)?;
@@ -202,16 +605,280 @@ fn extract_use_path(line: &str) -> Option<String> {
 pub fn elements_to_document(elements: &[CodeElement], filename: &str) -> String {
     let mut doc = format!("Source file: {}. ", filename);
     for elem in elements {
-        doc.push_str(&format!(
-            "{} {} defined at line {}. ",
-            elem.kind.as_str(),
-            elem.name,
-            elem.line
-        ));
+        match &elem.target {
+            Some(target) => doc.push_str(&format!(
+                "{} {} {} {} defined at line {}. ",
+                elem.kind.as_str(),
+                elem.name,
+                if elem.kind == CodeElementKind::Decorator {
+                    "on"
+                } else {
+                    "of"
+                },
+                target,
+                elem.line
+            )),
+            None => doc.push_str(&format!(
+                "{} {} defined at line {}. ",
+                elem.kind.as_str(),
+                elem.name,
+                elem.line
+            )),
+        }
     }
     doc
 }
 
+/// Labels to present for a file's elements, lowercase and in order, with
+/// the namespace of each qualified one.
+///
+/// Definitions are qualified by `filename`. Imports, impl blocks and
+/// decorators are too when they name something the file defines, and stay
+/// unqualified references otherwise.
+pub fn qualified_labels(
+    elements: &[CodeElement],
+    filename: &str,
+) -> (Vec<String>, HashMap<String, String>) {
+    let defined: HashSet<String> = elements
+        .iter()
+        .filter(|e| e.kind.is_definition())
+        .map(|e| e.name.to_lowercase())
+        .collect();
+    let mut labels = Vec::new();
+    let mut seen = HashSet::new();
+    for elem in elements {
+        let label = elem.name.to_lowercase();
+        if seen.insert(label.clone()) {
+            labels.push(label);
+        }
+    }
+    let namespaces = defined
+        .into_iter()
+        .map(|label| (label, filename.to_string()))
+        .collect();
+    (labels, namespaces)
+}
+
+/// A digester for source files.
+///
+/// Documents titled with a recognised source file name are digested into
+/// their code elements: definitions are presented under the file's
+/// namespace, references to names defined elsewhere unqualified. Other
+/// documents are digested like plain text, by keywords.
+pub struct CodeDigester {
+    inner: Digester,
+    /// Namespace of each qualified label of the current presentation.
+    namespaces: HashMap<String, String>,
+    /// Documents digested into code elements.
+    code_documents: u64,
+}
+
+impl CodeDigester {
+    /// Create a new code digester at a position.
+    pub fn new(position: Position) -> Self {
+        Self::wrapping(Digester::new(position))
+    }
+
+    /// Create with deterministic ID for testing.
+    pub fn with_seed(position: Position, seed: u64) -> Self {
+        Self::wrapping(Digester::with_seed(position, seed))
+    }
+
+    fn wrapping(inner: Digester) -> Self {
+        Self {
+            inner,
+            namespaces: HashMap::new(),
+            code_documents: 0,
+        }
+    }
+
+    /// Set max idle threshold.
+    pub fn with_max_idle(mut self, max_idle: u64) -> Self {
+        self.inner = self.inner.with_max_idle(max_idle);
+        self
+    }
+
+    /// Total fragments presented in lifetime.
+    pub fn total_fragments(&self) -> usize {
+        self.inner.total_fragments()
+    }
+
+    /// Documents digested into code elements rather than keywords.
+    pub fn code_documents(&self) -> u64 {
+        self.code_documents
+    }
+
+    /// The labels and namespaces to present for the document being
+    /// digested, if it is a source file with code elements.
+    fn extract(&self, substrate: &dyn Substrate) -> Option<(Vec<String>, HashMap<String, String>)> {
+        let doc = substrate.get_document(&self.inner.current_document()?)?;
+        CodeLanguage::from_filename(&doc.title)?;
+        let elements = extract_code_elements(&doc.content, &doc.title);
+        if elements.is_empty() {
+            return None;
+        }
+        Some(qualified_labels(&elements, &doc.title))
+    }
+
+    /// Qualify the fragments of a presentation by their namespace.
+    fn qualify(&self, mut action: AgentAction) -> AgentAction {
+        if let AgentAction::PresentFragments(fragments) = &mut action {
+            for fragment in fragments.iter_mut() {
+                fragment.namespace = self.namespaces.get(&fragment.label).cloned();
+            }
+        }
+        action
+    }
+}
+
+// --- Trait Implementations ---
+
+impl Digest for CodeDigester {
+    type Input = String;
+    type Fragment = String;
+    type Presentation = Vec<String>;
+
+    fn engulf(&mut self, input: String) -> DigestionResult {
+        self.inner.engulf(input)
+    }
+
+    /// Digests by keywords; code elements are only extracted over colony
+    /// ticks, where the document's file name is known.
+    fn lyse(&mut self) -> Vec<String> {
+        self.inner.lyse()
+    }
+
+    fn present(&self) -> Vec<String> {
+        self.inner.present()
+    }
+}
+
+impl Apoptose for CodeDigester {
+    fn self_assess(&self) -> CellHealth {
+        self.inner.self_assess()
+    }
+
+    fn prepare_death_signal(&self) -> DeathSignal {
+        self.inner.prepare_death_signal()
+    }
+}
+
+impl Sense for CodeDigester {
+    fn sense_radius(&self) -> f64 {
+        self.inner.sense_radius()
+    }
+
+    fn sense_position(&self) -> Position {
+        self.inner.sense_position()
+    }
+
+    fn gradient(&self, substrate: &dyn Substrate) -> Vec<Gradient> {
+        self.inner.gradient(substrate)
+    }
+
+    fn orient(&self, gradients: &[Gradient]) -> Orientation {
+        self.inner.orient(gradients)
+    }
+}
+
+impl Agent for CodeDigester {
+    fn id(&self) -> AgentId {
+        self.inner.id()
+    }
+
+    fn position(&self) -> Position {
+        self.inner.position()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.inner.set_position(position);
+    }
+
+    fn agent_type(&self) -> &str {
+        "code_digester"
+    }
+
+    fn tick(&mut self, substrate: &dyn Substrate) -> AgentAction {
+        if self.inner.is_digesting() {
+            match self.extract(substrate) {
+                Some((labels, namespaces)) => {
+                    self.namespaces = namespaces;
+                    self.code_documents += 1;
+                    let action = self.inner.present_extracted(labels);
+                    return self.qualify(action);
+                }
+                // Not source code: digest by keywords, unqualified
+                None => self.namespaces.clear(),
+            }
+        }
+        // Later ticks present what the per-tick limit held back
+        let action = self.inner.tick(substrate);
+        self.qualify(action)
+    }
+
+    fn age(&self) -> Tick {
+        self.inner.age()
+    }
+
+    fn release_undigested(&mut self) -> Option<(DocumentId, String)> {
+        self.inner.release_undigested()
+    }
+
+    fn adopt_tokenizer(&mut self, tokenizer: &Tokenizer) {
+        self.inner.adopt_tokenizer(tokenizer);
+    }
+
+    fn adopt_digestion_limits(&mut self, limits: &DigestionLimits) {
+        self.inner.adopt_digestion_limits(limits);
+    }
+
+    // --- Transfer / Symbiose / Dissolve: as a plain digester ---
+
+    fn export_vocabulary(&self) -> Option<Vec<u8>> {
+        self.inner.export_vocabulary()
+    }
+
+    fn integrate_vocabulary(&mut self, data: &[u8]) -> bool {
+        self.inner.integrate_vocabulary(data)
+    }
+
+    fn profile(&self) -> AgentProfile {
+        self.inner.profile()
+    }
+
+    fn evaluate_symbiosis(&self, other: &AgentProfile) -> Option<SymbiosisEval> {
+        self.inner.evaluate_symbiosis(other)
+    }
+
+    fn absorb_symbiont(&mut self, profile: AgentProfile, data: Vec<u8>) -> bool {
+        self.inner.absorb_symbiont(profile, data)
+    }
+
+    fn permeability(&self) -> f64 {
+        self.inner.permeability()
+    }
+
+    fn modulate_boundary(&mut self, context: &BoundaryContext) {
+        self.inner.modulate_boundary(context);
+    }
+
+    fn externalize_vocabulary(&self) -> Vec<String> {
+        self.inner.externalize_vocabulary()
+    }
+
+    fn internalize_vocabulary(&mut self, terms: &[String]) {
+        self.inner.internalize_vocabulary(terms);
+    }
+
+    fn vocabulary_size(&self) -> usize {
+        self.inner.vocabulary_size()
+    }
+
+    fn vocabulary_snapshot(&self, top_n: usize) -> Vec<(String, f64)> {
+        self.inner.vocabulary_snapshot(top_n)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,4 +912,162 @@ mod tests {
             .collect();
         assert!(impls.len() >= 2);
     }
+
+    fn of_kind(elements: &[CodeElement], kind: CodeElementKind) -> Vec<&CodeElement> {
+        elements.iter().filter(|e| e.kind == kind).collect()
+    }
+
+    #[test]
+    fn language_detection_from_filename() {
+        assert_eq!(
+            CodeLanguage::from_filename("src/main.rs"),
+            Some(CodeLanguage::Rust)
+        );
+        assert_eq!(
+            CodeLanguage::from_filename("model.py"),
+            Some(CodeLanguage::Python)
+        );
+        assert_eq!(
+            CodeLanguage::from_filename("app.tsx"),
+            Some(CodeLanguage::JavaScript)
+        );
+        assert_eq!(CodeLanguage::from_filename("data.csv"), None);
+    }
+
+    #[test]
+    fn python_functions_classes_methods_and_imports() {
+        let source = r#"
+import os.path
+from typing import List, Optional as Opt
+from .membrane import (Channel, Pump)
+
+MAX_FLUX = 10
+
+@dataclass
+class Cell:
+    def __init__(self):
+        self.channels = []
+
+    @property
+    def permeability(self):
+        def helper():
+            return 1
+        return helper()
+
+async def transport(cell):
+    pass
+"#;
+        let elements = extract_code_elements(source, "cell.py");
+
+        let classes = of_kind(&elements, CodeElementKind::Struct);
+        assert_eq!(classes.len(), 1);
+        assert_eq!(classes[0].name, "Cell");
+
+        let methods = of_kind(&elements, CodeElementKind::Method);
+        let method_names: Vec<&str> = methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(method_names, ["__init__", "permeability"]);
+        assert!(methods.iter().all(|m| m.target.as_deref() == Some("Cell")));
+
+        let fns: Vec<&str> = of_kind(&elements, CodeElementKind::Function)
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(fns, ["helper", "transport"]);
+
+        let decorators = of_kind(&elements, CodeElementKind::Decorator);
+        let applied: Vec<(&str, &str)> = decorators
+            .iter()
+            .map(|d| (d.name.as_str(), d.target.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            applied,
+            [("dataclass", "Cell"), ("property", "permeability")]
+        );
+
+        let imports: Vec<&str> = of_kind(&elements, CodeElementKind::Use)
+            .iter()
+            .map(|u| u.name.as_str())
+            .collect();
+        assert_eq!(imports, ["path", "List", "Optional", "Channel", "Pump"]);
+
+        let consts = of_kind(&elements, CodeElementKind::Const);
+        assert_eq!(consts.len(), 1);
+        assert_eq!(consts[0].name, "MAX_FLUX");
+    }
+
+    #[test]
+    fn typescript_declarations_methods_and_imports() {
+        let source = r#"
+import { Colony, Agent as A } from './colony';
+import * as d3 from "d3";
+import type { Position } from '../types';
+export { Membrane } from './membrane';
+
+/* Block comment with class Fake { */
+
+export interface Signal {
+  intensity: number;
+}
+
+export enum Kind { Concept, Document }
+
+@Component({ selector: 'app' })
+export default class Dashboard extends Base {
+  private colony: Colony;
+
+  constructor(colony: Colony) {
+    super();
+  }
+
+  async refresh(): Promise<void> {
+    if (this.colony) {
+      return;
+    }
+  }
+
+  static create<T>(x: T) {}
+}
+
+export function render(el: HTMLElement) {
+  const inner = () => 1;
+}
+
+export const tick = async (n: number) => n + 1;
+const MAX = 5;
+"#;
+        let elements = extract_code_elements(source, "dashboard.ts");
+
+        let names = |kind| -> Vec<String> {
+            of_kind(&elements, kind)
+                .iter()
+                .map(|e| e.name.clone())
+                .collect()
+        };
+        assert_eq!(names(CodeElementKind::Interface), ["Signal"]);
+        assert_eq!(names(CodeElementKind::Enum), ["Kind"]);
+        assert_eq!(names(CodeElementKind::Struct), ["Dashboard"]);
+        assert_eq!(names(CodeElementKind::Method), ["refresh", "create"]);
+        assert_eq!(names(CodeElementKind::Function), ["render", "tick"]);
+        assert_eq!(names(CodeElementKind::Const), ["MAX"]);
+        assert_eq!(
+            names(CodeElementKind::Use),
+            ["Colony", "Agent", "d3", "Position", "Membrane"]
+        );
+
+        let decorator = &of_kind(&elements, CodeElementKind::Decorator)[0];
+        assert_eq!(decorator.name, "Component");
+        assert_eq!(decorator.target.as_deref(), Some("Dashboard"));
+        assert!(of_kind(&elements, CodeElementKind::Method)
+            .iter()
+            .all(|m| m.target.as_deref() == Some("Dashboard")));
+    }
+
+    #[test]
+    fn document_links_members_to_their_targets() {
+        let source = "@dataclass\nclass Cell:\n    def divide(self):\n        pass\n";
+        let elements = extract_code_elements(source, "cell.py");
+        let doc = elements_to_document(&elements, "cell.py");
+        assert!(doc.contains("decorator dataclass on Cell defined at line 1."));
+        assert!(doc.contains("method divide of Cell defined at line 3."));
+    }
 }
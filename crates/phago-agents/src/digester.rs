@@ -8,14 +8,23 @@
 //! Biological analog: a macrophage that patrols tissue, engulfs foreign
 //! material, and presents antigen fragments on its surface.
 
-use phago_core::agent::Agent;
+use phago_core::agent::{agent_roll, top_terms, Agent};
+use phago_core::primitives::digest::{is_noise_term, DigestionLimits, FragmentRanking};
 use phago_core::primitives::symbiose::AgentProfile;
 use phago_core::primitives::{Apoptose, Digest, Sense};
-use phago_core::signal::compute_gradient;
+use phago_core::signal::{blend_gradients, compute_gradient_in, AnomalyWarning, SignalPayload};
 use phago_core::substrate::Substrate;
+use phago_core::tokenize::Tokenizer;
 use phago_core::types::*;
 use std::collections::{HashMap, HashSet};
 
+/// Concepts of its latest digestions an exploiting digester reinforces
+/// the edges between.
+const ANCHOR_TERMS: usize = 16;
+
+/// Weight added to each of those edges per consolidating tick.
+const CONSOLIDATION_WEIGHT: f64 = 0.05;
+
 /// Internal state machine for the digester's lifecycle.
 #[derive(Debug, Clone, PartialEq)]
 enum DigesterState {
@@ -43,6 +52,8 @@ pub struct Digester {
     current_document: Option<DocumentId>,
     /// Fragments extracted from the last digestion.
     fragments: Vec<String>,
+    /// Sentences of the last digested text each fragment occurs in.
+    passages: HashMap<String, Vec<TextSpan>>,
     /// Cumulative presentation: all fragments this agent has ever produced.
     all_presentations: Vec<String>,
 
@@ -69,6 +80,35 @@ pub struct Digester {
     max_idle_ticks: u64,
     /// Sensing radius.
     sense_radius: f64,
+    /// Per-type weights for blending signal gradients (SENSE).
+    signal_weights: HashMap<SignalType, f64>,
+    /// Chance of striding off in a random direction instead of following
+    /// gradients, before the colony's exploitation scales it down.
+    explore_bias: f64,
+    /// Graph nodes of the latest digestions' concepts, for consolidation.
+    anchors: Vec<NodeId>,
+    /// Presentations made when the anchors were looked up.
+    anchored_at: usize,
+    /// Put off documents mentioning terms from nearby anomaly warnings.
+    heed_warnings: bool,
+    /// Splits text into terms; a colony replaces it with its own on spawn.
+    tokenizer: Tokenizer,
+    /// Which terms of a document are presented.
+    digestion: DigestionLimits,
+    /// Set explicitly, so colony defaults don't replace it.
+    digestion_configured: bool,
+    /// Documents we asked to engulf but were not fed (e.g. vetoed by a
+    /// colony plugin). Tried again only when nothing else is in range.
+    refused: HashSet<DocumentId>,
+    /// Fragments presented per tick at most; `None` presents a digestion at once.
+    max_fragments_per_tick: Option<usize>,
+    /// Fragments of the last digestion left for the following ticks.
+    unpresented: Vec<FragmentPresentation>,
+}
+
+/// By default a digester only follows Input signals.
+fn default_signal_weights() -> HashMap<SignalType, f64> {
+    HashMap::from([(SignalType::Input, 1.0)])
 }
 
 impl Digester {
@@ -81,6 +121,7 @@ impl Digester {
             engulfed: None,
             current_document: None,
             fragments: Vec::new(),
+            passages: HashMap::new(),
             all_presentations: Vec::new(),
             idle_ticks: 0,
             useful_outputs: 0,
@@ -91,6 +132,17 @@ impl Digester {
             symbionts: Vec::new(),
             max_idle_ticks: 30,
             sense_radius: 10.0,
+            signal_weights: default_signal_weights(),
+            explore_bias: 0.0,
+            anchors: Vec::new(),
+            anchored_at: 0,
+            heed_warnings: true,
+            tokenizer: Tokenizer::default(),
+            digestion: DigestionLimits::default(),
+            digestion_configured: false,
+            refused: HashSet::new(),
+            max_fragments_per_tick: None,
+            unpresented: Vec::new(),
         }
     }
 
@@ -104,6 +156,7 @@ impl Digester {
             engulfed: None,
             current_document: None,
             fragments: Vec::new(),
+            passages: HashMap::new(),
             all_presentations: Vec::new(),
             idle_ticks: 0,
             useful_outputs: 0,
@@ -114,6 +167,17 @@ impl Digester {
             symbionts: Vec::new(),
             max_idle_ticks: 30,
             sense_radius: 10.0,
+            signal_weights: default_signal_weights(),
+            explore_bias: 0.0,
+            anchors: Vec::new(),
+            anchored_at: 0,
+            heed_warnings: true,
+            tokenizer: Tokenizer::default(),
+            digestion: DigestionLimits::default(),
+            digestion_configured: false,
+            refused: HashSet::new(),
+            max_fragments_per_tick: None,
+            unpresented: Vec::new(),
         }
     }
 
@@ -123,6 +187,168 @@ impl Digester {
         self
     }
 
+    /// Replace the signal-type weights used to blend gradients.
+    ///
+    /// Types left out are ignored. When the blended pull is dominated by
+    /// something other than Input, the digester follows it instead of
+    /// walking to the nearest undigested document.
+    pub fn with_signal_weights(mut self, weights: HashMap<SignalType, f64>) -> Self {
+        self.signal_weights = weights;
+        self
+    }
+
+    /// Set the weight of a single signal type.
+    pub fn with_signal_weight(mut self, signal_type: SignalType, weight: f64) -> Self {
+        self.signal_weights.insert(signal_type, weight);
+        self
+    }
+
+    /// Current signal-type weights.
+    pub fn signal_weights(&self) -> &HashMap<SignalType, f64> {
+        &self.signal_weights
+    }
+
+    /// Chance per tick of striding a sensing radius in a random direction
+    /// while no document is in range, instead of following gradients
+    /// (default: 0.0). A colony with an exploration schedule scales it
+    /// down as it matures (see [`Ambient`](phago_core::substrate::Ambient)).
+    pub fn with_explore_bias(mut self, bias: f64) -> Self {
+        self.explore_bias = bias.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Explore bias before the colony scales it.
+    pub fn explore_bias(&self) -> f64 {
+        self.explore_bias
+    }
+
+    /// Whether to put off documents that mention terms named by nearby
+    /// [`AnomalyWarning`] signals (default: true). Warned documents are
+    /// still digested once no unwarned document is in range.
+    pub fn with_heed_warnings(mut self, heed: bool) -> Self {
+        self.heed_warnings = heed;
+        self
+    }
+
+    /// Tokenizer used to extract keywords (default: English). A colony
+    /// replaces it with its configured one on spawn.
+    pub fn with_tokenizer(mut self, tokenizer: Tokenizer) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    /// Bounds on the fragments presented per document. Overrides the
+    /// colony's `ColonyConfig::digestion` defaults.
+    pub fn with_digestion_limits(mut self, limits: DigestionLimits) -> Self {
+        self.digestion = limits;
+        self.digestion_configured = true;
+        self
+    }
+
+    /// Present at most `max` fragments per document, best ranked first.
+    pub fn with_max_fragments_per_document(mut self, max: usize) -> Self {
+        self.digestion.max_fragments_per_document = Some(max);
+        self.digestion_configured = true;
+        self
+    }
+
+    /// Rank terms by `ranking` when `max_fragments_per_document` applies.
+    pub fn with_fragment_ranking(mut self, ranking: FragmentRanking) -> Self {
+        self.digestion.ranking = ranking;
+        self.digestion_configured = true;
+        self
+    }
+
+    /// Only present terms occurring at least `min` times in a document.
+    pub fn with_min_term_frequency(mut self, min: usize) -> Self {
+        self.digestion.min_term_frequency = min;
+        self.digestion_configured = true;
+        self
+    }
+
+    /// Wire at most `max` concept pairs per presentation.
+    pub fn with_max_pairs_per_presentation(mut self, max: usize) -> Self {
+        self.digestion.max_pairs_per_presentation = Some(max);
+        self.digestion_configured = true;
+        self
+    }
+
+    /// Present at most `max` fragments per tick, the rest of a digestion
+    /// over the following ticks, so one large document cannot flood the
+    /// graph in a tick. The colony wires each tick's fragments among
+    /// themselves; to wire a whole document as one presentation, cap
+    /// presentations with the colony's `max_fragments_per_agent_per_tick`
+    /// instead.
+    pub fn with_max_fragments_per_tick(mut self, max: usize) -> Self {
+        self.max_fragments_per_tick = Some(max.max(1));
+        self
+    }
+
+    /// Whether to drop numbers, hex blobs and overlong tokens (default: true).
+    pub fn with_token_shape_filter(mut self, filter: bool) -> Self {
+        self.digestion.filter_token_shapes = filter;
+        self.digestion_configured = true;
+        self
+    }
+
+    /// Seed the known vocabulary with the top `fraction` of a parent's
+    /// exported vocabulary (the bytes of `export_vocabulary`). Inherited
+    /// terms get the same keyword boost as transferred ones, and the parent
+    /// counts as already integrated. Invalid bytes are ignored.
+    pub fn with_inherited_vocabulary(mut self, vocabulary: &[u8], fraction: f64) -> Self {
+        self.inherit_vocabulary(vocabulary, fraction);
+        self
+    }
+
+    /// Current digestion limits.
+    pub fn digestion_limits(&self) -> &DigestionLimits {
+        &self.digestion
+    }
+
+    /// Terms named by anomaly warnings within sensing range.
+    fn warned_terms(&self, substrate: &dyn Substrate) -> HashSet<String> {
+        substrate
+            .signals_with_payload_near(&self.position, self.sense_radius, AnomalyWarning::KIND)
+            .into_iter()
+            .filter_map(|s| s.payload::<AnomalyWarning>())
+            .flat_map(|w| w.terms)
+            .map(|t| t.to_lowercase())
+            .collect()
+    }
+
+    /// Reinforce the edges among the concepts of the latest digestions,
+    /// if the graph still has any, instead of wandering off.
+    fn consolidate(&mut self, substrate: &dyn Substrate) -> Option<AgentAction> {
+        if self.anchored_at != self.all_presentations.len() {
+            let mut labels = HashSet::new();
+            for term in self.all_presentations.iter().rev() {
+                if labels.len() == ANCHOR_TERMS {
+                    break;
+                }
+                labels.insert(term.as_str());
+            }
+            self.anchors = substrate
+                .all_nodes()
+                .into_iter()
+                .filter(|id| {
+                    substrate
+                        .get_node(id)
+                        .is_some_and(|n| labels.contains(n.label.as_str()))
+                })
+                .collect();
+            self.anchored_at = self.all_presentations.len();
+        }
+        let mut wiring = Vec::new();
+        for (i, a) in self.anchors.iter().enumerate() {
+            for b in &self.anchors[i + 1..] {
+                if substrate.get_edge(a, b).is_some() {
+                    wiring.push((*a, *b, CONSOLIDATION_WEIGHT));
+                }
+            }
+        }
+        (!wiring.is_empty()).then_some(AgentAction::WireNodes(wiring))
+    }
+
     /// Total fragments produced in lifetime.
     pub fn total_fragments(&self) -> usize {
         self.all_presentations.len()
@@ -157,37 +383,145 @@ impl Digester {
         self.current_document = Some(doc_id);
         self.engulf(content);
     }
+
+    /// Whether the next tick lyses engulfed material.
+    pub(crate) fn is_digesting(&self) -> bool {
+        self.state == DigesterState::Digesting
+    }
+
+    /// The document being engulfed, digested or presented, if any.
+    pub(crate) fn current_document(&self) -> Option<DocumentId> {
+        self.current_document
+    }
+
+    /// Spend a tick waiting on work happening elsewhere. Ages the digester
+    /// without counting the tick as idle.
+    #[cfg(feature = "llm")]
+    pub(crate) fn wait(&mut self) -> AgentAction {
+        self.age_ticks += 1;
+        if self.should_die() {
+            AgentAction::Apoptose
+        } else {
+            AgentAction::Idle
+        }
+    }
+
+    /// Stand-in for the digesting tick: present `fragments` chosen
+    /// elsewhere instead of the keywords of the engulfed material.
+    pub(crate) fn present_extracted(&mut self, fragments: Vec<String>) -> AgentAction {
+        self.age_ticks += 1;
+        if self.should_die() {
+            return AgentAction::Apoptose;
+        }
+        let text = self.engulfed.take().unwrap_or_default();
+        let mut fragments = fragments;
+        if let Some(max) = self.digestion.max_fragments_per_document {
+            fragments.truncate(max);
+        }
+        self.passages = keyword_passages(&self.tokenizer, &text, &fragments);
+        self.fragments = fragments;
+        if self.fragments.is_empty() {
+            self.state = DigesterState::Seeking;
+            self.idle_ticks += 1;
+            return AgentAction::Idle;
+        }
+        self.useful_outputs += 1;
+        self.idle_ticks = 0;
+        self.all_presentations.extend(self.fragments.clone());
+        self.state = DigesterState::Presenting;
+        self.present_in_turn(self.fragment_presentations())
+    }
+
+    /// Present as many of `presentations` as the per-tick limit allows,
+    /// keeping the rest for the following ticks.
+    fn present_in_turn(&mut self, mut presentations: Vec<FragmentPresentation>) -> AgentAction {
+        if let Some(max) = self.max_fragments_per_tick {
+            if presentations.len() > max {
+                self.unpresented = presentations.split_off(max);
+            }
+        }
+        AgentAction::PresentFragments(presentations)
+    }
+
+    /// The last digestion's fragments as concepts for the graph.
+    fn fragment_presentations(&self) -> Vec<FragmentPresentation> {
+        let doc_id = self.current_document.unwrap_or_default();
+        self.fragments
+            .iter()
+            .map(|label| FragmentPresentation {
+                label: label.clone(),
+                source_document: doc_id,
+                position: self.position,
+                node_type: NodeType::Concept,
+                derived_from: Vec::new(),
+                passages: self.passages.get(label).cloned().unwrap_or_default(),
+                access_weight: 1,
+                score: None,
+                namespace: None,
+            })
+            .collect()
+    }
+}
+
+/// Whether `text` contains any of the (lowercase) `terms` as a word.
+fn mentions_any(tokenizer: &Tokenizer, text: &str, terms: &HashSet<String>) -> bool {
+    !terms.is_empty() && tokenizer.words(text).any(|word| terms.contains(&word))
+}
+
+/// How many documents the colony has seen each concept in, for
+/// [`FragmentRanking::TfIdf`].
+#[derive(Debug, Clone, Default)]
+struct DocumentFrequencies {
+    documents: usize,
+    counts: HashMap<String, u64>,
+}
+
+impl DocumentFrequencies {
+    /// Read off the substrate: a concept's access count stands in for the
+    /// number of documents that presented it.
+    fn from_substrate(substrate: &dyn Substrate) -> Self {
+        let counts = substrate
+            .all_nodes()
+            .iter()
+            .filter_map(|id| substrate.get_node(id))
+            .filter(|n| n.node_type == NodeType::Concept)
+            .map(|n| (n.label.clone(), n.access_count))
+            .collect();
+        Self {
+            documents: substrate.all_documents().len(),
+            counts,
+        }
+    }
+
+    /// Smoothed inverse document frequency of `term`.
+    fn idf(&self, term: &str) -> f64 {
+        let seen = self.counts.get(term).copied().unwrap_or(0) as f64;
+        ((1.0 + self.documents as f64) / (1.0 + seen)).ln().max(0.0) + 1.0
+    }
 }
 
 /// Extract keywords from text using a simple frequency-based approach.
 ///
 /// This is deterministic — no LLMs in v0.1. We extract meaningful words
-/// by filtering stopwords, short words, and ranking by frequency.
+/// by filtering stopwords, short words, and ranking by frequency (or
+/// TF-IDF, given `frequencies`), then apply `limits`.
 /// Words in `known_vocabulary` receive a +3 frequency boost (Transfer effect).
-fn extract_keywords(text: &str, known_vocabulary: Option<&HashSet<String>>) -> Vec<String> {
-    let stopwords: std::collections::HashSet<&str> = [
-        "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
-        "do", "does", "did", "will", "would", "shall", "should", "may", "might", "must", "can",
-        "could", "of", "in", "to", "for", "with", "on", "at", "from", "by", "about", "as", "into",
-        "through", "during", "before", "after", "above", "below", "between", "out", "off", "over",
-        "under", "again", "further", "then", "once", "here", "there", "when", "where", "why",
-        "how", "all", "each", "every", "both", "few", "more", "most", "other", "some", "such",
-        "no", "nor", "not", "only", "own", "same", "so", "than", "too", "very", "just", "because",
-        "but", "and", "or", "if", "while", "that", "this", "these", "those", "it", "its", "they",
-        "them", "their", "we", "our", "you", "your", "he", "she", "his", "her", "which", "what",
-        "who", "whom",
-    ]
-    .into_iter()
-    .collect();
-
-    // Tokenize: lowercase, split on non-alphanumeric, filter short and stopwords
+fn extract_keywords(
+    tokenizer: &Tokenizer,
+    text: &str,
+    known_vocabulary: Option<&HashSet<String>>,
+    limits: &DigestionLimits,
+    frequencies: Option<&DocumentFrequencies>,
+) -> Vec<String> {
+    let filter_shapes = limits.filter_token_shapes && !tokenizer.config().keep_identifiers;
     let mut freq: HashMap<String, usize> = HashMap::new();
-    for word in text.split(|c: char| !c.is_alphanumeric()) {
-        let word = word.to_lowercase();
-        if word.len() >= 3 && !stopwords.contains(word.as_str()) {
-            *freq.entry(word).or_insert(0) += 1;
+    for word in tokenizer.tokens(text) {
+        if filter_shapes && is_noise_term(&word) {
+            continue;
         }
+        *freq.entry(word).or_insert(0) += 1;
     }
+    freq.retain(|_, count| *count >= limits.min_term_frequency);
 
     // Boost words that are in the known vocabulary (Transfer effect)
     if let Some(vocab) = known_vocabulary {
@@ -198,13 +532,76 @@ fn extract_keywords(text: &str, known_vocabulary: Option<&HashSet<String>>) -> V
         }
     }
 
-    // Sort by frequency (descending), take top keywords
-    let mut words: Vec<(String, usize)> = freq.into_iter().collect();
-    words.sort_by(|a, b| b.1.cmp(&a.1));
+    // Sort by score (descending), ties alphabetically, take top keywords
+    let mut words: Vec<(String, f64)> = freq
+        .into_iter()
+        .map(|(word, count)| {
+            let score = match frequencies {
+                Some(df) => count as f64 * df.idf(&word),
+                None => count as f64,
+            };
+            (word, score)
+        })
+        .collect();
+    words.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    if let Some(max) = limits.max_fragments_per_document {
+        words.truncate(max);
+    }
 
     words.into_iter().map(|(word, _)| word).collect()
 }
 
+/// Split `text` into sentences: a sentence ends at `.`, `!` or `?`
+/// followed by whitespace, or at a line break. Spans are trimmed and
+/// blank ones dropped.
+fn sentence_spans(text: &str) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    let mut push_trimmed = |start: usize, end: usize| {
+        let raw = &text[start..end];
+        let trimmed = raw.trim();
+        if !trimmed.is_empty() {
+            let lead = raw.len() - raw.trim_start().len();
+            spans.push(TextSpan::new(start + lead, start + lead + trimmed.len()));
+        }
+    };
+
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let ends_sentence = match c {
+            '\n' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if ends_sentence {
+            let end = i + c.len_utf8();
+            push_trimmed(start, end);
+            start = end;
+        }
+    }
+    push_trimmed(start, text.len());
+    spans
+}
+
+/// The sentences of `text` each of `keywords` occurs in, in order.
+fn keyword_passages(
+    tokenizer: &Tokenizer,
+    text: &str,
+    keywords: &[String],
+) -> HashMap<String, Vec<TextSpan>> {
+    let wanted: HashSet<&str> = keywords.iter().map(String::as_str).collect();
+    let mut passages: HashMap<String, Vec<TextSpan>> = HashMap::new();
+    for span in sentence_spans(text) {
+        let mut seen = HashSet::new();
+        for word in tokenizer.words(&text[span.start..span.end]) {
+            if wanted.contains(word.as_str()) && seen.insert(word.clone()) {
+                passages.entry(word).or_default().push(span);
+            }
+        }
+    }
+    passages
+}
+
 // --- Trait Implementations ---
 
 impl Digest for Digester {
@@ -224,6 +621,17 @@ impl Digest for Digester {
     }
 
     fn lyse(&mut self) -> Vec<String> {
+        self.lyse_ranked(None)
+    }
+
+    fn present(&self) -> Vec<String> {
+        self.fragments.clone()
+    }
+}
+
+impl Digester {
+    /// Lyse, ranking terms by TF-IDF against `frequencies` when given.
+    fn lyse_ranked(&mut self, frequencies: Option<&DocumentFrequencies>) -> Vec<String> {
         let Some(text) = self.engulfed.take() else {
             return Vec::new();
         };
@@ -233,7 +641,9 @@ impl Digest for Digester {
         } else {
             Some(&self.known_vocabulary)
         };
-        let keywords = extract_keywords(&text, vocab);
+        let keywords =
+            extract_keywords(&self.tokenizer, &text, vocab, &self.digestion, frequencies);
+        self.passages = keyword_passages(&self.tokenizer, &text, &keywords);
         self.fragments = keywords.clone();
 
         if !self.fragments.is_empty() {
@@ -244,10 +654,6 @@ impl Digest for Digester {
 
         keywords
     }
-
-    fn present(&self) -> Vec<String> {
-        self.fragments.clone()
-    }
 }
 
 impl Apoptose for Digester {
@@ -272,6 +678,7 @@ impl Apoptose for Digester {
                 .map(|s| s.as_bytes().to_vec())
                 .collect(),
             cause: DeathCause::SelfAssessed(self.self_assess()),
+            had_pending_work: self.engulfed.is_some(),
         }
     }
 }
@@ -287,6 +694,7 @@ impl Sense for Digester {
 
     fn gradient(&self, substrate: &dyn Substrate) -> Vec<Gradient> {
         let signals = substrate.signals_near(&self.position, self.sense_radius);
+        let geometry = substrate.geometry();
 
         // Group signals by type and compute gradient for each
         let mut by_type: HashMap<String, Vec<&Signal>> = HashMap::new();
@@ -297,22 +705,13 @@ impl Sense for Digester {
 
         by_type
             .values()
-            .filter_map(|sigs| compute_gradient(sigs, &self.position))
+            .filter_map(|sigs| compute_gradient_in(sigs, &self.position, &geometry))
             .collect()
     }
 
     fn orient(&self, gradients: &[Gradient]) -> Orientation {
-        // Move toward the strongest Input signal gradient
-        let strongest = gradients
-            .iter()
-            .filter(|g| matches!(g.signal_type, SignalType::Input))
-            .max_by(|a, b| {
-                a.magnitude
-                    .partial_cmp(&b.magnitude)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-
-        match strongest {
+        // Move along the weighted blend of the sensed gradients
+        match blend_gradients(gradients, &self.signal_weights) {
             Some(g) => Orientation::Toward(Position::new(
                 self.position.x + g.direction.x,
                 self.position.y + g.direction.y,
@@ -370,20 +769,43 @@ impl Agent for Digester {
                     }
                 }
 
-                // Look for nearby undigested documents
+                // Another weighted signal may outrank nearby documents
+                let gradients = self.gradient(substrate);
+                let chasing_other = blend_gradients(&gradients, &self.signal_weights)
+                    .is_some_and(|g| g.signal_type != SignalType::Input);
+
+                // Look for nearby undigested documents, putting off ones
+                // we were refused and ones nearby anomaly warnings are about
                 let docs = substrate.undigested_documents();
-                let nearby_doc = docs
-                    .iter()
-                    .find(|d| d.position.distance_to(&self.position) <= self.sense_radius);
+                let warned = if self.heed_warnings {
+                    self.warned_terms(substrate)
+                } else {
+                    HashSet::new()
+                };
+                let nearby_doc = if chasing_other {
+                    None
+                } else {
+                    docs.iter()
+                        .filter(|d| {
+                            substrate.distance(&d.position, &self.position) <= self.sense_radius
+                        })
+                        .min_by_key(|d| {
+                            (
+                                self.refused.contains(&d.id),
+                                mentions_any(&self.tokenizer, &d.content, &warned),
+                            )
+                        })
+                };
 
                 if let Some(doc) = nearby_doc {
                     // Found a document — move toward it and request engulf
                     let doc_id = doc.id;
                     let doc_pos = doc.position;
 
-                    if doc_pos.distance_to(&self.position) < 1.0 {
+                    if substrate.distance(&doc_pos, &self.position) < 1.0 {
                         // Close enough — engulf next tick
                         self.state = DigesterState::FoundTarget(doc_id);
+                        self.current_document = Some(doc_id);
                         return AgentAction::EngulfDocument(doc_id);
                     } else {
                         // Move toward document
@@ -392,11 +814,27 @@ impl Agent for Digester {
                     }
                 }
 
-                // No documents nearby — follow signal gradients
-                let gradients = self.gradient(substrate);
+                // No documents nearby — stride off with the explore bias
+                // the colony leaves us, or stay and deepen the wiring of
+                // what we digested as often as a maturing colony exploits;
+                // else follow signal gradients
                 let orientation = self.orient(&gradients);
+                let ambient = substrate.ambient();
+                let roll = agent_roll(&self.id, self.age_ticks);
 
                 self.idle_ticks += 1;
+                if roll < ambient.explore_bias(self.explore_bias) {
+                    let angle = agent_roll(&self.id, !self.age_ticks) * std::f64::consts::TAU;
+                    return AgentAction::Move(Position::new(
+                        self.position.x + angle.cos() * self.sense_radius,
+                        self.position.y + angle.sin() * self.sense_radius,
+                    ));
+                }
+                if roll >= 1.0 - ambient.exploitation {
+                    if let Some(action) = self.consolidate(substrate) {
+                        return action;
+                    }
+                }
                 match orientation {
                     Orientation::Toward(pos) => AgentAction::Move(pos),
                     Orientation::Stay => AgentAction::Idle,
@@ -418,6 +856,9 @@ impl Agent for Digester {
                     AgentAction::Idle // Digesting takes one tick
                 } else {
                     // Colony didn't feed us (maybe doc was already taken)
+                    if let Some(doc_id) = self.current_document.take() {
+                        self.refused.insert(doc_id);
+                    }
                     self.state = DigesterState::Seeking;
                     self.idle_ticks += 1;
                     AgentAction::Idle
@@ -426,28 +867,26 @@ impl Agent for Digester {
 
             DigesterState::Digesting => {
                 // Break down the engulfed material
-                let fragments = self.lyse();
+                let frequencies = (self.digestion.ranking == FragmentRanking::TfIdf)
+                    .then(|| DocumentFrequencies::from_substrate(substrate));
+                let fragments = self.lyse_ranked(frequencies.as_ref());
                 if fragments.is_empty() {
                     self.state = DigesterState::Seeking;
                     self.idle_ticks += 1;
                     AgentAction::Idle
                 } else {
                     self.state = DigesterState::Presenting;
-                    let doc_id = self.current_document.unwrap_or(DocumentId::new());
-                    let presentations: Vec<FragmentPresentation> = fragments
-                        .iter()
-                        .map(|label| FragmentPresentation {
-                            label: label.clone(),
-                            source_document: doc_id,
-                            position: self.position,
-                            node_type: NodeType::Concept,
-                        })
-                        .collect();
-                    AgentAction::PresentFragments(presentations)
+                    self.present_in_turn(self.fragment_presentations())
                 }
             }
 
             DigesterState::Presenting => {
+                // Fragments held back by the per-tick limit go first
+                if !self.unpresented.is_empty() {
+                    let rest = std::mem::take(&mut self.unpresented);
+                    return self.present_in_turn(rest);
+                }
+
                 // After presenting, check if we should export vocabulary
                 if self.useful_outputs >= 2 && !self.has_exported {
                     self.has_exported = true;
@@ -478,17 +917,33 @@ impl Agent for Digester {
         self.age_ticks
     }
 
+    fn release_undigested(&mut self) -> Option<(DocumentId, String)> {
+        let doc_id = self.current_document?;
+        let content = self.engulfed.take()?;
+        self.current_document = None;
+        self.unpresented.clear();
+        self.state = DigesterState::Seeking;
+        Some((doc_id, content))
+    }
+
+    fn adopt_tokenizer(&mut self, tokenizer: &Tokenizer) {
+        self.tokenizer = tokenizer.clone();
+    }
+
+    fn adopt_digestion_limits(&mut self, limits: &DigestionLimits) {
+        if !self.digestion_configured {
+            self.digestion = limits.clone();
+        }
+    }
+
     // --- Transfer overrides ---
 
     fn export_vocabulary(&self) -> Option<Vec<u8>> {
         if self.all_presentations.is_empty() {
             return None;
         }
-        let cap = VocabularyCapability {
-            terms: self.all_presentations.clone(),
-            origin: self.id,
-            document_count: self.useful_outputs,
-        };
+        let cap =
+            VocabularyCapability::new(self.all_presentations.clone(), self.id, self.useful_outputs);
         serde_json::to_vec(&cap).ok()
     }
 
@@ -564,8 +1019,9 @@ impl Agent for Digester {
 
     fn externalize_vocabulary(&self) -> Vec<String> {
         let mut terms: Vec<String> = self.all_presentations.clone();
+        let mut seen: HashSet<&str> = self.all_presentations.iter().map(String::as_str).collect();
         for term in &self.known_vocabulary {
-            if !terms.contains(term) {
+            if seen.insert(term) {
                 terms.push(term.clone());
             }
         }
@@ -574,13 +1030,34 @@ impl Agent for Digester {
 
     fn internalize_vocabulary(&mut self, terms: &[String]) {
         for term in terms {
-            self.known_vocabulary.insert(term.clone());
+            if !self.known_vocabulary.contains(term) {
+                self.known_vocabulary.insert(term.clone());
+            }
         }
     }
 
+    fn inherit_vocabulary(&mut self, vocabulary: &[u8], fraction: f64) -> usize {
+        let Some((origin, terms)) = crate::lamarckian::inherited_terms(vocabulary, fraction) else {
+            return 0;
+        };
+        self.integrated_from.insert(origin);
+        let known = self.known_vocabulary.len();
+        self.known_vocabulary.extend(terms);
+        self.known_vocabulary.len() - known
+    }
+
     fn vocabulary_size(&self) -> usize {
         self.known_vocabulary.len() + self.all_presentations.len()
     }
+
+    fn vocabulary_snapshot(&self, top_n: usize) -> Vec<(String, f64)> {
+        // Times presented, plus one for a term learned from others
+        let mut weights: HashMap<&str, f64> = HashMap::new();
+        for term in self.all_presentations.iter().chain(&self.known_vocabulary) {
+            *weights.entry(term.as_str()).or_insert(0.0) += 1.0;
+        }
+        top_terms(weights, top_n)
+    }
 }
 
 // --- Serialization ---
@@ -604,6 +1081,15 @@ impl SerializableAgent for Digester {
             boundary_permeability: self.boundary_permeability,
             max_idle_ticks: self.max_idle_ticks,
             sense_radius: self.sense_radius,
+            signal_weights: self
+                .signal_weights
+                .iter()
+                .map(|(t, w)| (t.clone(), *w))
+                .collect(),
+            explore_bias: self.explore_bias,
+            heed_warnings: self.heed_warnings,
+            digestion: self.digestion_configured.then(|| self.digestion.clone()),
+            max_fragments_per_tick: self.max_fragments_per_tick,
         })
     }
 
@@ -617,6 +1103,7 @@ impl SerializableAgent for Digester {
                 engulfed: None,
                 current_document: None,
                 fragments: Vec::new(),
+                passages: HashMap::new(),
                 all_presentations: s.all_presentations.clone(),
                 idle_ticks: s.idle_ticks,
                 useful_outputs: s.useful_outputs,
@@ -627,6 +1114,21 @@ impl SerializableAgent for Digester {
                 symbionts: Vec::new(),
                 max_idle_ticks: s.max_idle_ticks,
                 sense_radius: s.sense_radius,
+                signal_weights: if s.signal_weights.is_empty() {
+                    default_signal_weights()
+                } else {
+                    s.signal_weights.iter().cloned().collect()
+                },
+                explore_bias: s.explore_bias,
+                anchors: Vec::new(),
+                anchored_at: 0,
+                heed_warnings: s.heed_warnings,
+                tokenizer: Tokenizer::default(),
+                digestion: s.digestion.clone().unwrap_or_default(),
+                digestion_configured: s.digestion.is_some(),
+                refused: HashSet::new(),
+                max_fragments_per_tick: s.max_fragments_per_tick,
+                unpresented: Vec::new(),
             }),
             _ => None,
         }
@@ -636,6 +1138,7 @@ impl SerializableAgent for Digester {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use phago_core::tokenize::TokenizerConfig;
 
     #[test]
     fn digest_text_extracts_keywords() {
@@ -768,10 +1271,13 @@ mod tests {
     #[test]
     fn extract_keywords_handles_varied_text() {
         let keywords = extract_keywords(
+            &Tokenizer::default(),
             "Rust programming language provides memory safety \
              without garbage collection. Rust achieves memory safety \
              through its ownership system.",
             None,
+            &DigestionLimits::default(),
+            None,
         );
         assert!(keywords.contains(&"rust".to_string()));
         assert!(keywords.contains(&"memory".to_string()));
@@ -780,6 +1286,125 @@ mod tests {
         assert!(keywords.iter().position(|w| w == "rust").unwrap() < 5);
     }
 
+    #[test]
+    fn digestion_limits_filter_and_bound_keywords() {
+        let text = "Kernel scheduler kernel memory 2024 kernel deadbeef42 scheduler \
+                    pseudoantidisestablishmentarianism paging";
+        let keywords = |limits: &DigestionLimits| {
+            extract_keywords(&Tokenizer::default(), text, None, limits, None)
+        };
+
+        let all = keywords(&DigestionLimits::default());
+        assert_eq!(all, vec!["kernel", "scheduler", "memory", "paging"]);
+
+        let repeated = keywords(&DigestionLimits {
+            min_term_frequency: 2,
+            ..Default::default()
+        });
+        assert_eq!(repeated, vec!["kernel", "scheduler"]);
+
+        let top = keywords(&DigestionLimits {
+            max_fragments_per_document: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(top, vec!["kernel"]);
+
+        let unfiltered = keywords(&DigestionLimits {
+            filter_token_shapes: false,
+            ..Default::default()
+        });
+        assert!(unfiltered.contains(&"2024".to_string()));
+        assert!(unfiltered.contains(&"deadbeef42".to_string()));
+        assert!(unfiltered.contains(&"pseudoantidisestablishmentarianism".to_string()));
+
+        // Code digestion keeps identifiers whole and skips the filter
+        let code = Tokenizer::new(TokenizerConfig {
+            keep_identifiers: true,
+            ..Default::default()
+        });
+        let idents = extract_keywords(
+            &code,
+            "fn load_0xdeadbeef42() { let build_2024 = 2024; }",
+            None,
+            &DigestionLimits::default(),
+            None,
+        );
+        assert!(idents.contains(&"2024".to_string()));
+    }
+
+    #[test]
+    fn tf_idf_ranking_discounts_terms_the_colony_has_seen_often() {
+        let text = "cell cell cell membrane membrane";
+        let frequencies = DocumentFrequencies {
+            documents: 100,
+            counts: HashMap::from([("cell".to_string(), 90)]),
+        };
+        let limits = DigestionLimits {
+            max_fragments_per_document: Some(1),
+            ranking: FragmentRanking::TfIdf,
+            ..Default::default()
+        };
+        let tokenizer = Tokenizer::default();
+        assert_eq!(
+            extract_keywords(&tokenizer, text, None, &limits, None),
+            vec!["cell"]
+        );
+        assert_eq!(
+            extract_keywords(&tokenizer, text, None, &limits, Some(&frequencies)),
+            vec!["membrane"]
+        );
+    }
+
+    #[test]
+    fn explicit_digestion_limits_survive_colony_defaults() {
+        let colony_defaults = DigestionLimits {
+            max_fragments_per_document: Some(50),
+            ..Default::default()
+        };
+
+        let mut adopted = Digester::new(Position::new(0.0, 0.0));
+        adopted.adopt_digestion_limits(&colony_defaults);
+        assert_eq!(adopted.digestion_limits(), &colony_defaults);
+
+        let mut explicit =
+            Digester::new(Position::new(0.0, 0.0)).with_max_fragments_per_document(3);
+        explicit.adopt_digestion_limits(&colony_defaults);
+        assert_eq!(
+            explicit.digestion_limits().max_fragments_per_document,
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn passages_point_at_the_sentences_mentioning_each_keyword() {
+        let text =
+            "Cell membranes hold lipids. Version 2.0 adds proteins!\n\nMembrane proteins move";
+        let sentences: Vec<&str> = sentence_spans(text)
+            .iter()
+            .map(|s| s.slice(text).unwrap())
+            .collect();
+        assert_eq!(
+            sentences,
+            vec![
+                "Cell membranes hold lipids.",
+                "Version 2.0 adds proteins!",
+                "Membrane proteins move"
+            ]
+        );
+
+        let keywords = vec!["proteins".to_string(), "lipids".to_string()];
+        let passages = keyword_passages(&Tokenizer::default(), text, &keywords);
+        let proteins: Vec<&str> = passages["proteins"]
+            .iter()
+            .map(|s| s.slice(text).unwrap())
+            .collect();
+        assert_eq!(
+            proteins,
+            vec!["Version 2.0 adds proteins!", "Membrane proteins move"]
+        );
+        assert_eq!(passages["lipids"].len(), 1);
+    }
+
     #[test]
     fn digest_full_cycle() {
         let mut digester = Digester::new(Position::new(0.0, 0.0));
@@ -793,4 +1418,41 @@ mod tests {
         assert_eq!(digester.useful_outputs, 1);
         assert_eq!(digester.total_fragments(), presentation.len());
     }
+
+    #[test]
+    fn death_hands_back_the_engulfed_document() {
+        let mut digester = Digester::new(Position::new(0.0, 0.0)).with_max_idle(2);
+        let doc_id = DocumentId::new();
+        digester.feed_document(doc_id, "cell membrane transport".to_string());
+        digester.set_idle_ticks(2);
+
+        assert!(digester.should_die());
+        assert!(digester.prepare_death_signal().had_pending_work);
+        assert_eq!(
+            digester.release_undigested(),
+            Some((doc_id, "cell membrane transport".to_string()))
+        );
+        assert_eq!(digester.release_undigested(), None);
+        assert!(!digester.prepare_death_signal().had_pending_work);
+    }
+
+    #[test]
+    fn offspring_inherit_the_parents_top_terms() {
+        let mut parent = Digester::new(Position::new(0.0, 0.0));
+        parent.digest("cell membrane protein membrane cell".to_string());
+        parent.digest("cell membrane lipid".to_string());
+        let vocabulary = parent.export_vocabulary().unwrap();
+
+        let child =
+            Digester::new(Position::new(1.0, 0.0)).with_inherited_vocabulary(&vocabulary, 0.5);
+        assert_eq!(child.vocabulary_size(), 2);
+        assert!(child.known_vocabulary.contains("cell"));
+        assert!(child.known_vocabulary.contains("membrane"));
+        // The parent's vocabulary is not integrated a second time
+        let mut child = child;
+        assert!(!child.integrate_vocabulary(&vocabulary));
+
+        let orphan = Digester::new(Position::new(1.0, 0.0)).with_inherited_vocabulary(b"{}", 0.5);
+        assert_eq!(orphan.vocabulary_size(), 0);
+    }
 }
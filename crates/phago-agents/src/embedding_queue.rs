@@ -0,0 +1,269 @@
+//! EmbeddingQueue — embedding terms off the tick.
+//!
+//! An embedder may take a network round trip per call, and a colony tick
+//! waits for every agent. Agents therefore never embed during a tick: they
+//! [`request`](EmbeddingQueue::request) the terms they want embedded, a
+//! worker thread embeds them one by one, and on a later tick each agent
+//! [`collect`](EmbeddingQueue::collect)s what arrived for it and attaches it
+//! with [`AgentAction::EmbedConcepts`], which also caches it colony-wide.
+//!
+//! Agents sharing an embedder should share a queue: a term that is queued,
+//! or was embedded so recently that the colony may not have cached it yet,
+//! is not embedded again whichever agent asks for it. Agents check the
+//! colony's cache ([`Substrate::cached_embedding`]) before asking at all.
+//! A failed call is retried up to [`EmbeddingQueue::with_max_retries`]
+//! times; after that the failure is handed back for the colony to report.
+//!
+//! [`Substrate::cached_embedding`]: phago_core::substrate::Substrate::cached_embedding
+
+use phago_core::types::{AgentId, ConceptEmbedding};
+use phago_embeddings::Embedder;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+/// Terms remembered as recently embedded, so they are not queued again
+/// before the colony caches them.
+const RECENT_TERMS: usize = 1024;
+
+#[derive(Default)]
+struct QueueState {
+    /// Terms queued or being embedded, with the agents waiting for them.
+    pending: HashMap<String, Vec<AgentId>>,
+    /// Embeddings not yet collected, by agent.
+    ready: HashMap<AgentId, Vec<ConceptEmbedding>>,
+    /// Recently embedded terms, oldest first.
+    recent: VecDeque<String>,
+    recent_set: HashSet<String>,
+    /// Embedder calls made, retries included.
+    calls: u64,
+}
+
+impl QueueState {
+    fn finish(&mut self, term: String, embedding: Result<Vec<f32>, String>) {
+        for agent in self.pending.remove(&term).unwrap_or_default() {
+            self.ready.entry(agent).or_default().push(ConceptEmbedding {
+                label: term.clone(),
+                embedding: embedding.clone(),
+            });
+        }
+        if self.recent_set.insert(term.clone()) {
+            self.recent.push_back(term);
+        }
+        while self.recent.len() > RECENT_TERMS {
+            if let Some(old) = self.recent.pop_front() {
+                self.recent_set.remove(&old);
+            }
+        }
+    }
+}
+
+/// Embeds requested terms on a worker thread, started on the first
+/// request and stopped when the queue is dropped.
+pub struct EmbeddingQueue {
+    embedder: Arc<dyn Embedder>,
+    max_retries: u32,
+    state: Arc<Mutex<QueueState>>,
+    worker: OnceLock<Option<Mutex<Sender<String>>>>,
+}
+
+impl EmbeddingQueue {
+    pub fn new(embedder: Arc<dyn Embedder>) -> Self {
+        Self {
+            embedder,
+            max_retries: 2,
+            state: Arc::new(Mutex::new(QueueState::default())),
+            worker: OnceLock::new(),
+        }
+    }
+
+    /// Retry a failed embedder call up to `retries` times (default: 2)
+    /// before handing the failure back.
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    pub fn embedder(&self) -> &Arc<dyn Embedder> {
+        &self.embedder
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        // A panicking embedder must not stop the colony
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Embed `term` for `agent`, unless it is already queued (then the
+    /// agent gets it too) or was embedded recently. Returns whether the
+    /// agent will be handed an embedding.
+    pub fn request(&self, agent: AgentId, term: &str) -> bool {
+        let term = term.trim().to_lowercase();
+        if term.is_empty() {
+            return false;
+        }
+        let mut state = self.state();
+        if let Some(waiting) = state.pending.get_mut(&term) {
+            if !waiting.contains(&agent) {
+                waiting.push(agent);
+            }
+            return true;
+        }
+        if state.recent_set.contains(&term) {
+            return false;
+        }
+        let Some(sender) = self.worker() else {
+            return false;
+        };
+        state.pending.insert(term.clone(), vec![agent]);
+        let sent = sender.lock().is_ok_and(|s| s.send(term.clone()).is_ok());
+        if !sent {
+            state.pending.remove(&term);
+        }
+        sent
+    }
+
+    /// Embeddings, and failures, that have arrived for `agent`.
+    pub fn collect(&self, agent: AgentId) -> Vec<ConceptEmbedding> {
+        self.state().ready.remove(&agent).unwrap_or_default()
+    }
+
+    /// Drop what is waiting for `agent`, e.g. because it died.
+    pub fn forget(&self, agent: AgentId) {
+        let mut state = self.state();
+        state.ready.remove(&agent);
+        for waiting in state.pending.values_mut() {
+            waiting.retain(|a| *a != agent);
+        }
+    }
+
+    /// Terms queued or being embedded.
+    pub fn pending(&self) -> usize {
+        self.state().pending.len()
+    }
+
+    /// Embedder calls made so far, retries included.
+    pub fn calls(&self) -> u64 {
+        self.state().calls
+    }
+
+    /// The worker's job channel, starting the worker if need be. `None` if
+    /// no thread could be spawned.
+    fn worker(&self) -> Option<&Mutex<Sender<String>>> {
+        self.worker
+            .get_or_init(|| {
+                let (sender, receiver) = mpsc::channel::<String>();
+                let embedder = Arc::clone(&self.embedder);
+                let state = Arc::clone(&self.state);
+                let attempts = self.max_retries + 1;
+                thread::Builder::new()
+                    .name("phago-embedding-queue".to_string())
+                    .spawn(move || {
+                        for term in receiver {
+                            let mut result = Err(String::new());
+                            for _ in 0..attempts {
+                                result = embedder.embed(&term).map_err(|e| e.to_string());
+                                if let Ok(mut state) = state.lock() {
+                                    state.calls += 1;
+                                }
+                                if result.is_ok() {
+                                    break;
+                                }
+                            }
+                            let result =
+                                result.map_err(|e| format!("{e} (after {attempts} attempts)"));
+                            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                            state.finish(term, result);
+                        }
+                    })
+                    .ok()
+                    .map(|_| Mutex::new(sender))
+            })
+            .as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_embeddings::{EmbeddingError, EmbeddingResult};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::{Duration, Instant};
+
+    /// Fails its first `failures` calls, each taking `delay`.
+    struct Flaky {
+        failures: AtomicU32,
+        delay: Duration,
+    }
+
+    fn flaky(failures: u32, delay_ms: u64) -> Arc<dyn Embedder> {
+        Arc::new(Flaky {
+            failures: AtomicU32::new(failures),
+            delay: Duration::from_millis(delay_ms),
+        })
+    }
+
+    impl Embedder for Flaky {
+        fn embed(&self, _text: &str) -> EmbeddingResult<Vec<f32>> {
+            thread::sleep(self.delay);
+            let left = self.failures.load(Ordering::SeqCst);
+            if left > 0 {
+                self.failures.store(left - 1, Ordering::SeqCst);
+                return Err(EmbeddingError::ApiError("rate limited".to_string()));
+            }
+            Ok(vec![1.0, 0.0])
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+
+        fn model_name(&self) -> &str {
+            "flaky"
+        }
+    }
+
+    fn wait_for(queue: &EmbeddingQueue, agent: AgentId) -> Vec<ConceptEmbedding> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let ready = queue.collect(agent);
+            if !ready.is_empty() || Instant::now() > deadline {
+                return ready;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn failures_are_retried_a_bounded_number_of_times() {
+        let agent = AgentId::from_seed(1);
+        let queue = EmbeddingQueue::new(flaky(2, 0));
+        assert!(queue.request(agent, "membrane"));
+        let ready = wait_for(&queue, agent);
+        assert_eq!(ready[0].embedding, Ok(vec![1.0, 0.0]));
+        assert_eq!(queue.calls(), 3);
+
+        let queue = EmbeddingQueue::new(flaky(10, 0)).with_max_retries(1);
+        assert!(queue.request(agent, "membrane"));
+        let ready = wait_for(&queue, agent);
+        let reason = ready[0].embedding.clone().unwrap_err();
+        assert!(reason.contains("rate limited"), "{reason}");
+        assert!(reason.contains("after 2 attempts"), "{reason}");
+        assert_eq!(queue.calls(), 2);
+    }
+
+    #[test]
+    fn agents_asking_for_the_same_term_share_one_call() {
+        let (a, b) = (AgentId::from_seed(1), AgentId::from_seed(2));
+        let queue = EmbeddingQueue::new(flaky(0, 50));
+        assert!(queue.request(a, "Membrane"));
+        assert!(queue.request(b, "membrane"));
+        assert_eq!(queue.pending(), 1);
+        assert_eq!(wait_for(&queue, a)[0].label, "membrane");
+        assert_eq!(wait_for(&queue, b)[0].label, "membrane");
+
+        // Embedded recently, so the colony caches it: not queued again
+        assert!(!queue.request(b, "membrane"));
+        assert_eq!(queue.calls(), 1);
+    }
+}
@@ -3,13 +3,22 @@
 //! Tracks per-agent graph contributions to compute fitness scores.
 //! Fitness determines which genomes propagate: fitter agents live longer
 //! (higher max_idle effectively) and their genomes seed new agents.
+//!
+//! How counters become a score is pluggable through [`FitnessFunction`].
+//! The default [`BuiltinFitness::Cumulative`] never forgets: an agent that
+//! was productive long ago keeps ranking high while idle. The decayed
+//! built-ins score recent activity, which the tracker keeps per tick over
+//! a sliding window.
 
 use phago_core::types::AgentId;
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Default length of the per-agent activity window, in ticks.
+pub const DEFAULT_ACTIVITY_WINDOW: u64 = 500;
 
 /// Per-agent fitness data.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentFitness {
     pub agent_id: AgentId,
     /// Total concepts added to the knowledge graph.
@@ -30,17 +39,220 @@ pub struct AgentFitness {
     pub strong_edges: u64,
 }
 
+/// Contributions (concepts + edges) bucketed per tick over a sliding window.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityWindow {
+    /// `(tick, contributions)`, oldest first.
+    buckets: VecDeque<(u64, u64)>,
+}
+
+impl ActivityWindow {
+    /// Add `count` contributions at `tick`, dropping buckets that fell out
+    /// of the last `window_ticks` ticks.
+    pub fn record(&mut self, tick: u64, count: u64, window_ticks: u64) {
+        match self.buckets.back_mut() {
+            Some((t, c)) if *t == tick => *c += count,
+            _ => self.buckets.push_back((tick, count)),
+        }
+        self.trim(tick, window_ticks);
+    }
+
+    fn trim(&mut self, now: u64, window_ticks: u64) {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(t, _)| now.saturating_sub(*t) >= window_ticks)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    /// `(tick, contributions)` buckets, oldest first.
+    pub fn buckets(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.buckets.iter().copied()
+    }
+
+    /// Contributions inside the window.
+    pub fn total(&self) -> u64 {
+        self.buckets.iter().map(|(_, c)| c).sum()
+    }
+
+    /// Contributions weighted by `2^(-(now - tick) / half_life_ticks)`.
+    pub fn decayed_total(&self, now: u64, half_life_ticks: f64) -> f64 {
+        let half_life = half_life_ticks.max(f64::MIN_POSITIVE);
+        self.buckets
+            .iter()
+            .map(|(t, c)| *c as f64 * 0.5_f64.powf(now.saturating_sub(*t) as f64 / half_life))
+            .sum()
+    }
+}
+
+/// Everything a fitness function may look at for one agent.
+#[derive(Debug, Clone, Copy)]
+pub struct FitnessInput<'a> {
+    /// Cumulative counters of the agent.
+    pub counters: &'a AgentFitness,
+    /// Ticks the agent has been alive.
+    pub age: u64,
+    /// The tracker's current tick.
+    pub now: u64,
+    /// Length of the activity window, in ticks.
+    pub window_ticks: u64,
+    /// Contributions per tick over the last `window_ticks` ticks.
+    pub recent: &'a ActivityWindow,
+}
+
+/// Turns an agent's counters and recent activity into a fitness score.
+pub trait FitnessFunction: std::fmt::Debug + Send + Sync {
+    fn fitness(&self, input: &FitnessInput<'_>) -> f64;
+
+    /// The built-in this function is, if any. Only built-ins are recorded
+    /// in [`FitnessState`]; custom functions restore as `Cumulative`.
+    fn builtin(&self) -> Option<BuiltinFitness> {
+        None
+    }
+}
+
+/// Built-in fitness functions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum BuiltinFitness {
+    /// Weighted multi-objective combination of all-time counters:
+    /// - 30% productivity: (concepts + edges) / ticks  (throughput)
+    /// - 30% novelty: novel_concepts / concepts_added  (exploration value)
+    /// - 20% quality: strong_edges / edges_contributed  (reinforcement signal)
+    /// - 20% connectivity: bridge_edges / edges_contributed  (integration value)
+    #[default]
+    Cumulative,
+    /// Contributions weighted by age: one contribution `half_life_ticks`
+    /// ago counts half as much as one this tick. Contributions older than
+    /// the activity window no longer count at all.
+    ExponentialDecay { half_life_ticks: f64 },
+    /// Contributions per tick over the activity window (or the agent's
+    /// lifetime, if shorter).
+    RatePerTick,
+}
+
+impl FitnessFunction for BuiltinFitness {
+    fn fitness(&self, input: &FitnessInput<'_>) -> f64 {
+        match *self {
+            BuiltinFitness::Cumulative => cumulative_fitness(input.counters),
+            BuiltinFitness::ExponentialDecay { half_life_ticks } => {
+                input.recent.decayed_total(input.now, half_life_ticks)
+            }
+            BuiltinFitness::RatePerTick => {
+                let span = input.age.min(input.window_ticks).max(1);
+                input.recent.total() as f64 / span as f64
+            }
+        }
+    }
+
+    fn builtin(&self) -> Option<BuiltinFitness> {
+        Some(*self)
+    }
+}
+
+fn cumulative_fitness(f: &AgentFitness) -> f64 {
+    let productivity =
+        (f.concepts_added as f64 + f.edges_contributed as f64) / f.ticks_alive as f64;
+
+    let novelty = if f.concepts_added > 0 {
+        f.novel_concepts as f64 / f.concepts_added as f64
+    } else {
+        0.0
+    };
+
+    let quality = if f.edges_contributed > 0 {
+        f.strong_edges as f64 / f.edges_contributed as f64
+    } else {
+        0.0
+    };
+
+    let connectivity = if f.edges_contributed > 0 {
+        f.bridge_edges as f64 / f.edges_contributed as f64
+    } else {
+        0.0
+    };
+
+    0.3 * productivity + 0.3 * novelty + 0.2 * quality + 0.2 * connectivity
+}
+
+/// Serializable state of a [`FitnessTracker`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FitnessState {
+    /// `None` if the tracker used a custom function.
+    pub function: Option<BuiltinFitness>,
+    pub window_ticks: u64,
+    pub tick: u64,
+    pub generation_counter: u32,
+    pub agents: Vec<AgentFitness>,
+    pub activity: Vec<(AgentId, ActivityWindow)>,
+}
+
 /// Tracks fitness across all agents in a colony.
 pub struct FitnessTracker {
     data: HashMap<AgentId, AgentFitness>,
+    activity: HashMap<AgentId, ActivityWindow>,
     generation_counter: u32,
+    function: Box<dyn FitnessFunction>,
+    window_ticks: u64,
+    /// Number of `tick_all` calls so far; contributions are bucketed by it.
+    tick: u64,
 }
 
 impl FitnessTracker {
     pub fn new() -> Self {
         Self {
             data: HashMap::new(),
+            activity: HashMap::new(),
             generation_counter: 0,
+            function: Box::new(BuiltinFitness::Cumulative),
+            window_ticks: DEFAULT_ACTIVITY_WINDOW,
+            tick: 0,
+        }
+    }
+
+    /// Score agents with `function` instead of `Cumulative`.
+    pub fn with_function(mut self, function: impl FitnessFunction + 'static) -> Self {
+        self.function = Box::new(function);
+        self
+    }
+
+    /// Keep per-tick activity for the last `ticks` ticks (at least 1).
+    pub fn with_window(mut self, ticks: u64) -> Self {
+        self.window_ticks = ticks.max(1);
+        self
+    }
+
+    /// The fitness function in use.
+    pub fn function(&self) -> &dyn FitnessFunction {
+        self.function.as_ref()
+    }
+
+    /// Export the tracker's state for persistence.
+    pub fn export_state(&self) -> FitnessState {
+        FitnessState {
+            function: self.function.builtin(),
+            window_ticks: self.window_ticks,
+            tick: self.tick,
+            generation_counter: self.generation_counter,
+            agents: self.data.values().cloned().collect(),
+            activity: self
+                .activity
+                .iter()
+                .map(|(id, w)| (*id, w.clone()))
+                .collect(),
+        }
+    }
+
+    /// Restore a tracker from exported state.
+    pub fn from_state(state: FitnessState) -> Self {
+        Self {
+            data: state.agents.into_iter().map(|f| (f.agent_id, f)).collect(),
+            activity: state.activity.into_iter().collect(),
+            generation_counter: state.generation_counter,
+            function: Box::new(state.function.unwrap_or_default()),
+            window_ticks: state.window_ticks.max(1),
+            tick: state.tick,
         }
     }
 
@@ -60,92 +272,95 @@ impl FitnessTracker {
                 strong_edges: 0,
             },
         );
+        self.activity.insert(agent_id, ActivityWindow::default());
     }
 
     /// Record that an agent added concepts to the graph.
     pub fn record_concepts(&mut self, agent_id: &AgentId, count: u64) {
         if let Some(f) = self.data.get_mut(agent_id) {
             f.concepts_added += count;
-            Self::recompute_fitness(f);
         }
+        self.record_activity(agent_id, count);
     }
 
     /// Record that an agent contributed edges.
     pub fn record_edges(&mut self, agent_id: &AgentId, count: u64) {
         if let Some(f) = self.data.get_mut(agent_id) {
             f.edges_contributed += count;
-            Self::recompute_fitness(f);
         }
+        self.record_activity(agent_id, count);
+    }
+
+    /// Bucket `count` contributions at the current tick and rescore.
+    fn record_activity(&mut self, agent_id: &AgentId, count: u64) {
+        if !self.data.contains_key(agent_id) {
+            return;
+        }
+        self.activity
+            .entry(*agent_id)
+            .or_default()
+            .record(self.tick, count, self.window_ticks);
+        self.recompute_fitness(agent_id);
     }
 
     /// Record novel concepts (concepts that didn't exist in the graph before).
     pub fn record_novel_concepts(&mut self, agent_id: &AgentId, count: u64) {
         if let Some(f) = self.data.get_mut(agent_id) {
             f.novel_concepts += count;
-            Self::recompute_fitness(f);
         }
+        self.recompute_fitness(agent_id);
     }
 
     /// Record bridge edges (edges connecting previously isolated clusters).
     pub fn record_bridge_edges(&mut self, agent_id: &AgentId, count: u64) {
         if let Some(f) = self.data.get_mut(agent_id) {
             f.bridge_edges += count;
-            Self::recompute_fitness(f);
         }
+        self.recompute_fitness(agent_id);
     }
 
     /// Record strong edges (co_activations >= 2).
     pub fn record_strong_edges(&mut self, agent_id: &AgentId, count: u64) {
         if let Some(f) = self.data.get_mut(agent_id) {
             f.strong_edges += count;
-            Self::recompute_fitness(f);
         }
+        self.recompute_fitness(agent_id);
     }
 
     /// Record a tick for all registered agents.
     pub fn tick_all(&mut self, alive_ids: &[AgentId]) {
+        self.tick += 1;
         for id in alive_ids {
             if let Some(f) = self.data.get_mut(id) {
                 f.ticks_alive += 1;
-                Self::recompute_fitness(f);
             }
+            if let Some(w) = self.activity.get_mut(id) {
+                w.trim(self.tick, self.window_ticks);
+            }
+            self.recompute_fitness(id);
         }
     }
 
-    /// Multi-objective fitness function.
-    ///
-    /// Weights:
-    /// - 30% productivity: (concepts + edges) / ticks  (throughput)
-    /// - 30% novelty: novel_concepts / concepts_added  (exploration value)
-    /// - 20% quality: strong_edges / edges_contributed  (reinforcement signal)
-    /// - 20% connectivity: bridge_edges / edges_contributed  (integration value)
-    fn recompute_fitness(f: &mut AgentFitness) {
+    /// Rescore an agent with the configured fitness function.
+    fn recompute_fitness(&mut self, agent_id: &AgentId) {
+        let Some(f) = self.data.get(agent_id) else {
+            return;
+        };
         if f.ticks_alive == 0 {
             return;
         }
-
-        let productivity =
-            (f.concepts_added as f64 + f.edges_contributed as f64) / f.ticks_alive as f64;
-
-        let novelty = if f.concepts_added > 0 {
-            f.novel_concepts as f64 / f.concepts_added as f64
-        } else {
-            0.0
+        let empty = ActivityWindow::default();
+        let input = FitnessInput {
+            counters: f,
+            age: f.ticks_alive,
+            now: self.tick,
+            window_ticks: self.window_ticks,
+            recent: self.activity.get(agent_id).unwrap_or(&empty),
         };
-
-        let quality = if f.edges_contributed > 0 {
-            f.strong_edges as f64 / f.edges_contributed as f64
-        } else {
-            0.0
-        };
-
-        let connectivity = if f.edges_contributed > 0 {
-            f.bridge_edges as f64 / f.edges_contributed as f64
-        } else {
-            0.0
-        };
-
-        f.fitness = 0.3 * productivity + 0.3 * novelty + 0.2 * quality + 0.2 * connectivity;
+        let fitness = self.function.fitness(&input);
+        if let Some(f) = self.data.get_mut(agent_id) {
+            f.fitness = fitness;
+        }
     }
 
     /// Get the fittest living agent.
@@ -234,4 +449,78 @@ mod tests {
         let best = tracker.fittest(&[id1, id2]).unwrap();
         assert_eq!(best.agent_id, id1);
     }
+
+    /// `old` contributes 150 in the first tick and then idles for 300
+    /// ticks while `recent` contributes 5 per tick during the last 20.
+    fn old_vs_recent(tracker: FitnessTracker) -> (FitnessTracker, AgentId, AgentId) {
+        let mut tracker = tracker;
+        let old = AgentId::new();
+        let recent = AgentId::new();
+        tracker.register(old, 0);
+        tracker.register(recent, 0);
+        for tick in 0..300 {
+            tracker.tick_all(&[old, recent]);
+            if tick == 0 {
+                tracker.record_concepts(&old, 60);
+                tracker.record_edges(&old, 90);
+            }
+            if tick >= 280 {
+                tracker.record_concepts(&recent, 5);
+            }
+        }
+        (tracker, old, recent)
+    }
+
+    #[test]
+    fn cumulative_keeps_old_high_scorer_fittest() {
+        let (mut tracker, old, recent) = old_vs_recent(FitnessTracker::new());
+        assert_eq!(tracker.fittest(&[old, recent]).unwrap().agent_id, old);
+        assert_eq!(tracker.next_generation(), 1);
+    }
+
+    #[test]
+    fn exponential_decay_prefers_recent_activity() {
+        let tracker = FitnessTracker::new().with_function(BuiltinFitness::ExponentialDecay {
+            half_life_ticks: 50.0,
+        });
+        let (tracker, old, recent) = old_vs_recent(tracker);
+        assert_eq!(tracker.fittest(&[old, recent]).unwrap().agent_id, recent);
+
+        // 150 contributions decayed over ~6 half-lives
+        let old_fitness = tracker.get(&old).unwrap().fitness;
+        assert!(old_fitness > 0.0 && old_fitness < 3.0, "{old_fitness}");
+    }
+
+    #[test]
+    fn rate_per_tick_only_counts_the_window() {
+        let tracker = FitnessTracker::new()
+            .with_function(BuiltinFitness::RatePerTick)
+            .with_window(100);
+        let (tracker, old, recent) = old_vs_recent(tracker);
+        assert_eq!(tracker.get(&old).unwrap().fitness, 0.0);
+        assert!((tracker.get(&recent).unwrap().fitness - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn state_roundtrip_keeps_function_and_activity() {
+        let tracker = FitnessTracker::new().with_function(BuiltinFitness::ExponentialDecay {
+            half_life_ticks: 50.0,
+        });
+        let (tracker, old, recent) = old_vs_recent(tracker);
+
+        let json = serde_json::to_string(&tracker.export_state()).unwrap();
+        let mut restored = FitnessTracker::from_state(serde_json::from_str(&json).unwrap());
+        assert_eq!(
+            restored.function().builtin(),
+            Some(BuiltinFitness::ExponentialDecay {
+                half_life_ticks: 50.0
+            })
+        );
+
+        // Recent keeps decaying from where it left off
+        let before = restored.get(&recent).unwrap().fitness;
+        restored.tick_all(&[old, recent]);
+        let after = restored.get(&recent).unwrap().fitness;
+        assert!((after - before * 0.5_f64.powf(1.0 / 50.0)).abs() < 1e-9);
+    }
 }
@@ -5,7 +5,9 @@
 //! with random mutations. Natural selection occurs through apoptosis:
 //! agents with poor fitness die faster, removing their genomes.
 
+use phago_core::types::SignalType;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Evolvable parameters for an agent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +31,18 @@ pub struct AgentGenome {
     /// Fraction of concept pairs to wire per document. Range: [0.1, 1.0].
     /// 1.0 = wire all pairs, 0.5 = wire ~half (probabilistic), etc.
     pub wiring_selectivity: f64,
+
+    // Chemotaxis weights
+    /// Pull of Input signals (unprocessed documents). Range: [0.0, 3.0].
+    #[serde(default = "default_input_signal_weight")]
+    pub input_signal_weight: f64,
+    /// Pull of Capability signals (vocabulary deposits). Range: [0.0, 3.0].
+    #[serde(default)]
+    pub capability_signal_weight: f64,
+}
+
+fn default_input_signal_weight() -> f64 {
+    1.0
 }
 
 impl AgentGenome {
@@ -43,9 +57,23 @@ impl AgentGenome {
             tentative_weight: 0.1,
             reinforcement_boost: 0.1,
             wiring_selectivity: 1.0,
+            input_signal_weight: default_input_signal_weight(),
+            capability_signal_weight: 0.0,
         }
     }
 
+    /// Per-type signal weights for SENSE blending, as used by
+    /// `Digester::with_signal_weights`. Zero weights are left out.
+    pub fn signal_weights(&self) -> HashMap<SignalType, f64> {
+        [
+            (SignalType::Input, self.input_signal_weight),
+            (SignalType::Capability, self.capability_signal_weight),
+        ]
+        .into_iter()
+        .filter(|(_, w)| *w != 0.0)
+        .collect()
+    }
+
     /// Create a mutated copy of this genome.
     ///
     /// Each parameter is perturbed by ±mutation_rate (as a fraction).
@@ -73,6 +101,10 @@ impl AgentGenome {
                 .clamp(0.01, 0.3),
             wiring_selectivity: (self.wiring_selectivity + next() * mutation_rate * 0.3)
                 .clamp(0.1, 1.0),
+            input_signal_weight: (self.input_signal_weight + next() * mutation_rate)
+                .clamp(0.0, 3.0),
+            capability_signal_weight: (self.capability_signal_weight + next() * mutation_rate)
+                .clamp(0.0, 3.0),
         }
     }
 
@@ -106,6 +138,14 @@ impl AgentGenome {
                 (self.wiring_selectivity - 0.1) / 0.9,
                 (other.wiring_selectivity - 0.1) / 0.9,
             ),
+            (
+                self.input_signal_weight / 3.0,
+                other.input_signal_weight / 3.0,
+            ),
+            (
+                self.capability_signal_weight / 3.0,
+                other.capability_signal_weight / 3.0,
+            ),
         ];
 
         let sum_sq: f64 = dims.iter().map(|(a, b)| (a - b).powi(2)).sum();
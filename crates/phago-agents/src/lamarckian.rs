@@ -4,6 +4,12 @@
 //! to suggest targeted genome patches. This runs alongside Darwinian
 //! mutation (secondary pathway, not replacement).
 //!
+//! Offspring can also inherit what the parent learned: the top fraction of
+//! the vocabulary it exported (see [`inherited_terms`] and
+//! [`Digester::with_inherited_vocabulary`]).
+//!
+//! [`Digester::with_inherited_vocabulary`]: crate::digester::Digester::with_inherited_vocabulary
+//!
 //! # Design
 //!
 //! The `GenomeAdvisor` trait abstracts the LLM call so it can be:
@@ -33,7 +39,9 @@
 //! ```
 
 use crate::genome::AgentGenome;
+use phago_core::types::{AgentId, VocabularyCapability};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Context about an agent's death, used to inform LLM suggestions.
 #[derive(Debug, Clone, Serialize)]
@@ -102,6 +110,12 @@ pub fn apply_patches(genome: &AgentGenome, patches: &[GenomePatch]) -> AgentGeno
             "wiring_selectivity" => {
                 result.wiring_selectivity = patch.value.clamp(0.1, 1.0);
             }
+            "input_signal_weight" => {
+                result.input_signal_weight = patch.value.clamp(0.0, 3.0);
+            }
+            "capability_signal_weight" => {
+                result.capability_signal_weight = patch.value.clamp(0.0, 3.0);
+            }
             _ => {} // Unknown parameter — ignore
         }
     }
@@ -129,6 +143,8 @@ pub fn build_advice_prompt(death: &DeathContext, genome: &AgentGenome) -> String
 - tentative_weight: {:.3} (range: 0.05-0.5) — initial weight for new edges
 - reinforcement_boost: {:.3} (range: 0.01-0.3) — weight boost per co-activation
 - wiring_selectivity: {:.2} (range: 0.1-1.0) — fraction of concept pairs to wire
+- input_signal_weight: {:.2} (range: 0.0-3.0) — pull toward unprocessed documents
+- capability_signal_weight: {:.2} (range: 0.0-3.0) — pull toward vocabulary deposits
 
 ## Instructions
 Suggest 1-3 parameter changes that would help the next generation agent survive longer and be more productive. Return a JSON array of patches:
@@ -152,6 +168,8 @@ Only include parameters you want to change. Values must be within the specified
         genome.tentative_weight,
         genome.reinforcement_boost,
         genome.wiring_selectivity,
+        genome.input_signal_weight,
+        genome.capability_signal_weight,
     )
 }
 
@@ -249,10 +267,56 @@ pub fn evolve_genome(
     patched.mutate(effective_rate, seed)
 }
 
+/// The terms an offspring inherits from a parent's exported vocabulary
+/// (the bytes of `export_vocabulary`): the top `fraction` of its distinct
+/// terms, most often presented first, ties in the order they were first
+/// presented. Returns the parent's id with the terms, or `None` if the
+/// bytes are not a vocabulary capability.
+pub fn inherited_terms(vocabulary: &[u8], fraction: f64) -> Option<(AgentId, Vec<String>)> {
+    let capability: VocabularyCapability = serde_json::from_slice(vocabulary).ok()?;
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for (position, term) in capability.terms.iter().enumerate() {
+        counts.entry(term).or_insert((0, position)).0 += 1;
+    }
+    let mut ranked: Vec<(&str, (usize, usize))> = counts.into_iter().collect();
+    ranked.sort_by(|(_, (ca, pa)), (_, (cb, pb))| cb.cmp(ca).then(pa.cmp(pb)));
+
+    let keep = (ranked.len() as f64 * fraction.clamp(0.0, 1.0)).ceil() as usize;
+    let terms = ranked
+        .into_iter()
+        .take(keep)
+        .map(|(term, _)| term.to_string())
+        .collect();
+    Some((capability.origin, terms))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn exported(terms: &[&str]) -> Vec<u8> {
+        serde_json::to_vec(&VocabularyCapability::new(
+            terms.iter().map(|t| t.to_string()).collect(),
+            AgentId::from_seed(7),
+            2,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn inherited_terms_keep_the_most_presented_fraction() {
+        let vocabulary = exported(&["cell", "membrane", "protein", "membrane", "lipid", "cell"]);
+
+        let (origin, half) = inherited_terms(&vocabulary, 0.5).unwrap();
+        assert_eq!(origin, AgentId::from_seed(7));
+        // Two of four distinct terms, by count then first appearance
+        assert_eq!(half, vec!["cell", "membrane"]);
+        assert_eq!(inherited_terms(&vocabulary, 0.6).unwrap().1.len(), 3);
+        assert_eq!(inherited_terms(&vocabulary, 2.0).unwrap().1.len(), 4);
+        assert!(inherited_terms(&vocabulary, 0.0).unwrap().1.is_empty());
+        assert!(inherited_terms(b"not json", 0.5).is_none());
+    }
+
     fn make_death(cause: &str, fitness: f64) -> DeathContext {
         DeathContext {
             cause: cause.to_string(),
@@ -8,6 +8,8 @@
 //! - **Digester** — DIGEST + SENSE + APOPTOSE — consumes and processes text input
 //! - **Synthesizer** — EMERGE + SENSE + APOPTOSE — collective intelligence through quorum sensing
 //! - **Sentinel** — NEGATE + SENSE + APOPTOSE — anomaly detection through negative selection
+//! - **CodeDigester** — a Digester that presents source files' code elements, qualified by file
+//! - **LlmDigester** (`llm` feature) — a Digester that extracts typed concepts and relations with an LLM
 //!
 //! ## Quick Start
 //!
@@ -24,6 +26,7 @@ pub mod digester;
 pub mod fitness;
 pub mod genome;
 pub mod prelude;
+pub mod registry;
 pub mod sentinel;
 pub mod serialize;
 pub mod spawn;
@@ -31,8 +34,13 @@ pub mod synthesizer;
 
 pub mod lamarckian;
 
+#[cfg(feature = "semantic")]
+pub mod embedding_queue;
 #[cfg(feature = "semantic")]
 pub mod semantic_digester;
 
 #[cfg(feature = "ast")]
 pub mod ast_digester;
+
+#[cfg(feature = "llm")]
+pub mod llm_digester;
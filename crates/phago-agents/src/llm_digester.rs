@@ -0,0 +1,503 @@
+//! LlmDigester — LLM-backed concept and relationship extraction.
+//!
+//! Moves, engulfs and presents exactly like the standard [`Digester`], but
+//! hands engulfed text to an [`LlmBackend`] for typed concepts and the
+//! relationships between them. Concepts are presented with an access
+//! weight derived from the model's confidence; relationships are wired on
+//! the following tick with [`AgentAction::WireRelations`], so the graph
+//! knows that mitochondria *produce* ATP rather than merely co-occur with it.
+//!
+//! Backends are async and colony ticks are not. Each extraction runs on its
+//! own thread and the digester checks for the result once per tick while it
+//! digests. If the backend fails, or takes longer than
+//! [`LlmDigesterConfig::max_wait_ticks`], the document is digested on the
+//! plain keyword path instead.
+//!
+//! Biological analog: a dendritic cell — it engulfs the same material as a
+//! macrophage but takes longer over it, and presents not just fragments
+//! but how they fit together.
+
+use crate::digester::Digester;
+use phago_core::agent::Agent;
+use phago_core::primitives::digest::DigestionLimits;
+use phago_core::primitives::symbiose::AgentProfile;
+use phago_core::primitives::{Apoptose, Digest, Sense};
+use phago_core::substrate::Substrate;
+use phago_core::tokenize::Tokenizer;
+use phago_core::types::*;
+use phago_llm::{Concept, ExtractionResponse, LlmBackend, LlmResult, RelationType, Relationship};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+/// Configuration for LLM digestion.
+#[derive(Debug, Clone)]
+pub struct LlmDigesterConfig {
+    /// Ticks to wait for an extraction before falling back to keywords.
+    pub max_wait_ticks: u64,
+    /// Access weight of a concept extracted with full confidence. Weights
+    /// scale down with confidence, to a minimum of 1.
+    pub max_access_weight: u64,
+    /// Base edge weight of a relationship extracted with full confidence.
+    pub relation_weight: f64,
+}
+
+impl Default for LlmDigesterConfig {
+    fn default() -> Self {
+        Self {
+            max_wait_ticks: 20,
+            max_access_weight: 3,
+            relation_weight: 0.5,
+        }
+    }
+}
+
+/// An extraction running on its own thread.
+struct PendingExtraction {
+    receiver: Receiver<LlmResult<ExtractionResponse>>,
+    waited: u64,
+}
+
+/// A relationship waiting to be wired once its concepts are in the graph.
+#[derive(Debug, Clone)]
+struct PendingRelation {
+    source: String,
+    target: String,
+    relation: String,
+    weight: f64,
+}
+
+/// A digester that extracts concepts and relationships with an LLM.
+pub struct LlmDigester {
+    inner: Digester,
+    backend: Arc<dyn LlmBackend>,
+    config: LlmDigesterConfig,
+    pending: Option<PendingExtraction>,
+    /// Relationships of the last presentation, wired on the next tick.
+    unwired: Vec<PendingRelation>,
+    /// Documents digested on the keyword path because extraction failed.
+    fallbacks: u64,
+}
+
+impl LlmDigester {
+    /// Create a new LLM digester extracting with `backend`.
+    pub fn new(position: Position, backend: Arc<dyn LlmBackend>) -> Self {
+        Self::wrapping(Digester::new(position), backend)
+    }
+
+    /// Create with deterministic ID for testing.
+    pub fn with_seed(position: Position, backend: Arc<dyn LlmBackend>, seed: u64) -> Self {
+        Self::wrapping(Digester::with_seed(position, seed), backend)
+    }
+
+    fn wrapping(inner: Digester, backend: Arc<dyn LlmBackend>) -> Self {
+        Self {
+            inner,
+            backend,
+            config: LlmDigesterConfig::default(),
+            pending: None,
+            unwired: Vec::new(),
+            fallbacks: 0,
+        }
+    }
+
+    /// Create with custom config.
+    pub fn with_config(mut self, config: LlmDigesterConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set max idle threshold.
+    pub fn with_max_idle(mut self, max_idle: u64) -> Self {
+        self.inner = self.inner.with_max_idle(max_idle);
+        self
+    }
+
+    /// Total concepts presented in lifetime.
+    pub fn total_fragments(&self) -> usize {
+        self.inner.total_fragments()
+    }
+
+    /// Documents digested on the keyword path because the backend failed
+    /// or did not answer in time.
+    pub fn fallbacks(&self) -> u64 {
+        self.fallbacks
+    }
+
+    /// Start extracting `text` in the background.
+    fn start_extraction(&mut self, text: String) {
+        let backend = Arc::clone(&self.backend);
+        let (sender, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("phago-llm-extract".to_string())
+            .spawn(move || {
+                // The digester may have given up waiting and dropped the receiver
+                let _ = sender.send(block_on(backend.extract(&text)));
+            });
+        // Without a thread there is nothing to wait for; digest by keywords
+        if spawned.is_ok() {
+            self.pending = Some(PendingExtraction {
+                receiver,
+                waited: 0,
+            });
+        }
+    }
+
+    /// Present the extracted concepts and remember their relationships.
+    fn present_extraction(&mut self, response: ExtractionResponse) -> AgentAction {
+        let mut labels = Vec::new();
+        let mut access_weights = HashMap::new();
+        for concept in &response.concepts {
+            let label = normalize_label(&concept.label);
+            if label.is_empty() || access_weights.contains_key(&label) {
+                continue;
+            }
+            access_weights.insert(label.clone(), self.access_weight(concept));
+            labels.push(label);
+        }
+
+        let mut action = self.inner.present_extracted(labels);
+        if let AgentAction::PresentFragments(fragments) = &mut action {
+            for fragment in fragments.iter_mut() {
+                fragment.access_weight = access_weights[&fragment.label];
+            }
+            self.unwired = response
+                .relationships
+                .iter()
+                .filter_map(|r| self.pending_relation(r))
+                .collect();
+        }
+        action
+    }
+
+    fn access_weight(&self, concept: &Concept) -> u64 {
+        let scaled = (concept.confidence as f64 * self.config.max_access_weight as f64).round();
+        (scaled as u64).max(1)
+    }
+
+    fn pending_relation(&self, relationship: &Relationship) -> Option<PendingRelation> {
+        let source = normalize_label(&relationship.source);
+        let target = normalize_label(&relationship.target);
+        let weight = self.config.relation_weight * relationship.confidence as f64;
+        if source.is_empty() || target.is_empty() || source == target || weight <= 0.0 {
+            return None;
+        }
+        Some(PendingRelation {
+            source,
+            target,
+            relation: relation_label(relationship),
+            weight,
+        })
+    }
+
+    /// Resolve the unwired relationships against the graph. Concepts the
+    /// colony merged into a differently labelled node are skipped.
+    fn resolve_relations(&mut self, substrate: &dyn Substrate) -> Vec<RelationWiring> {
+        let relations = std::mem::take(&mut self.unwired);
+        let wanted: HashSet<&str> = relations
+            .iter()
+            .flat_map(|r| [r.source.as_str(), r.target.as_str()])
+            .collect();
+        let mut nodes: HashMap<String, NodeId> = HashMap::new();
+        for id in substrate.all_nodes() {
+            if let Some(node) = substrate.get_node(&id) {
+                let label = node.label.to_lowercase();
+                if node.node_type == NodeType::Concept && wanted.contains(label.as_str()) {
+                    nodes.entry(label).or_insert(id);
+                }
+            }
+        }
+
+        relations
+            .into_iter()
+            .filter_map(|r| {
+                Some(RelationWiring {
+                    from: *nodes.get(&r.source)?,
+                    to: *nodes.get(&r.target)?,
+                    weight: r.weight,
+                    relation: r.relation,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Concept labels are compared the way keyword fragments are: lowercase.
+fn normalize_label(label: &str) -> String {
+    label.trim().to_lowercase()
+}
+
+/// The predicate an extracted relationship is wired with: the typed
+/// relation in snake case, or the model's own label for untyped ones.
+fn relation_label(relationship: &Relationship) -> String {
+    let typed = match &relationship.relation_type {
+        RelationType::IsA => "is_a",
+        RelationType::PartOf => "part_of",
+        RelationType::Causes => "causes",
+        RelationType::Enables => "enables",
+        RelationType::Requires => "requires",
+        RelationType::Produces => "produces",
+        RelationType::Regulates => "regulates",
+        RelationType::InteractsWith => "interacts_with",
+        RelationType::LocatedIn => "located_in",
+        RelationType::Custom(label) => return snake_case(label),
+        RelationType::RelatedTo => return snake_case(&relationship.label),
+    };
+    typed.to_string()
+}
+
+fn snake_case(label: &str) -> String {
+    let words: Vec<String> = label.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        "related_to".to_string()
+    } else {
+        words.join("_")
+    }
+}
+
+/// Drive `future` to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(Thread);
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+// --- Trait Implementations ---
+
+impl Digest for LlmDigester {
+    type Input = String;
+    type Fragment = String;
+    type Presentation = Vec<String>;
+
+    fn engulf(&mut self, input: String) -> DigestionResult {
+        let result = self.inner.engulf(input.clone());
+        if result == DigestionResult::Engulfed {
+            self.start_extraction(input);
+        }
+        result
+    }
+
+    /// Digests by keywords; extraction only happens over colony ticks.
+    fn lyse(&mut self) -> Vec<String> {
+        self.pending = None;
+        self.inner.lyse()
+    }
+
+    fn present(&self) -> Vec<String> {
+        self.inner.present()
+    }
+}
+
+impl Apoptose for LlmDigester {
+    fn self_assess(&self) -> CellHealth {
+        self.inner.self_assess()
+    }
+
+    fn prepare_death_signal(&self) -> DeathSignal {
+        self.inner.prepare_death_signal()
+    }
+}
+
+impl Sense for LlmDigester {
+    fn sense_radius(&self) -> f64 {
+        self.inner.sense_radius()
+    }
+
+    fn sense_position(&self) -> Position {
+        self.inner.sense_position()
+    }
+
+    fn gradient(&self, substrate: &dyn Substrate) -> Vec<Gradient> {
+        self.inner.gradient(substrate)
+    }
+
+    fn orient(&self, gradients: &[Gradient]) -> Orientation {
+        self.inner.orient(gradients)
+    }
+}
+
+impl Agent for LlmDigester {
+    fn id(&self) -> AgentId {
+        self.inner.id()
+    }
+
+    fn position(&self) -> Position {
+        self.inner.position()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.inner.set_position(position);
+    }
+
+    fn agent_type(&self) -> &str {
+        "llm_digester"
+    }
+
+    fn tick(&mut self, substrate: &dyn Substrate) -> AgentAction {
+        // Wire the relationships of last tick's presentation
+        if !self.unwired.is_empty() {
+            let relations = self.resolve_relations(substrate);
+            if !relations.is_empty() {
+                return match self.inner.wait() {
+                    AgentAction::Idle => AgentAction::WireRelations(relations),
+                    action => action,
+                };
+            }
+        }
+
+        if self.inner.is_digesting() {
+            if let Some(pending) = &mut self.pending {
+                match pending.receiver.try_recv() {
+                    Ok(Ok(response)) if !response.concepts.is_empty() => {
+                        self.pending = None;
+                        return self.present_extraction(response);
+                    }
+                    Err(TryRecvError::Empty) if pending.waited < self.config.max_wait_ticks => {
+                        pending.waited += 1;
+                        return self.inner.wait();
+                    }
+                    // Failed, empty, or too slow: digest by keywords
+                    _ => {
+                        self.pending = None;
+                        self.fallbacks += 1;
+                    }
+                }
+            }
+        }
+
+        self.inner.tick(substrate)
+    }
+
+    fn age(&self) -> Tick {
+        self.inner.age()
+    }
+
+    fn release_undigested(&mut self) -> Option<(DocumentId, String)> {
+        self.pending = None;
+        self.inner.release_undigested()
+    }
+
+    fn adopt_tokenizer(&mut self, tokenizer: &Tokenizer) {
+        self.inner.adopt_tokenizer(tokenizer);
+    }
+
+    fn adopt_digestion_limits(&mut self, limits: &DigestionLimits) {
+        self.inner.adopt_digestion_limits(limits);
+    }
+
+    // --- Transfer / Symbiose / Dissolve: as a plain digester ---
+
+    fn export_vocabulary(&self) -> Option<Vec<u8>> {
+        self.inner.export_vocabulary()
+    }
+
+    fn integrate_vocabulary(&mut self, data: &[u8]) -> bool {
+        self.inner.integrate_vocabulary(data)
+    }
+
+    fn profile(&self) -> AgentProfile {
+        self.inner.profile()
+    }
+
+    fn evaluate_symbiosis(&self, other: &AgentProfile) -> Option<SymbiosisEval> {
+        self.inner.evaluate_symbiosis(other)
+    }
+
+    fn absorb_symbiont(&mut self, profile: AgentProfile, data: Vec<u8>) -> bool {
+        self.inner.absorb_symbiont(profile, data)
+    }
+
+    fn permeability(&self) -> f64 {
+        self.inner.permeability()
+    }
+
+    fn modulate_boundary(&mut self, context: &BoundaryContext) {
+        self.inner.modulate_boundary(context);
+    }
+
+    fn externalize_vocabulary(&self) -> Vec<String> {
+        self.inner.externalize_vocabulary()
+    }
+
+    fn internalize_vocabulary(&mut self, terms: &[String]) {
+        self.inner.internalize_vocabulary(terms);
+    }
+
+    fn vocabulary_size(&self) -> usize {
+        self.inner.vocabulary_size()
+    }
+
+    fn vocabulary_snapshot(&self, top_n: usize) -> Vec<(String, f64)> {
+        self.inner.vocabulary_snapshot(top_n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_llm::MockBackend;
+
+    fn canned() -> ExtractionResponse {
+        ExtractionResponse {
+            concepts: vec![
+                Concept::new("Mitochondria").with_confidence(0.9),
+                Concept::new("ATP").with_confidence(0.2),
+            ],
+            relationships: vec![Relationship::new("mitochondria", "ATP", "produces")
+                .with_type(RelationType::Produces)],
+            raw_response: None,
+            tokens_used: None,
+        }
+    }
+
+    #[test]
+    fn block_on_drives_backend_futures() {
+        let backend = MockBackend::new().with_extraction(canned());
+        let response = block_on(backend.extract("text")).unwrap();
+        assert_eq!(response.concepts.len(), 2);
+    }
+
+    #[test]
+    fn relation_labels_prefer_the_type() {
+        let typed = Relationship::new("a", "b", "makes").with_type(RelationType::Produces);
+        assert_eq!(relation_label(&typed), "produces");
+        assert_eq!(
+            relation_label(&Relationship::new("a", "b", "Binds To")),
+            "binds_to"
+        );
+        let custom =
+            Relationship::new("a", "b", "x").with_type(RelationType::Custom("Inhibits".into()));
+        assert_eq!(relation_label(&custom), "inhibits");
+    }
+
+    #[test]
+    fn access_weight_follows_confidence() {
+        let digester = LlmDigester::new(Position::new(0.0, 0.0), Arc::new(MockBackend::new()));
+        assert_eq!(digester.access_weight(&Concept::new("a")), 3);
+        assert_eq!(
+            digester.access_weight(&Concept::new("a").with_confidence(0.5)),
+            2
+        );
+        assert_eq!(
+            digester.access_weight(&Concept::new("a").with_confidence(0.0)),
+            1
+        );
+    }
+}
@@ -6,18 +6,22 @@
 
 // Re-export agent types
 pub use crate::code_digester::{
-    elements_to_document, extract_code_elements, CodeElement, CodeElementKind,
+    elements_to_document, extract_code_elements, extract_language_elements, qualified_labels,
+    CodeDigester, CodeElement, CodeElementKind, CodeLanguage,
 };
 pub use crate::digester::Digester;
-pub use crate::fitness::{AgentFitness, FitnessTracker};
+pub use crate::fitness::{AgentFitness, BuiltinFitness, FitnessFunction, FitnessTracker};
 pub use crate::genome::AgentGenome;
-pub use crate::sentinel::Sentinel;
+pub use crate::registry::{AgentRegistry, AgentSpec, BoxedAgent, RegistryError};
+pub use crate::sentinel::{AnomalyGrade, AnomalyThresholds, PatrolStrategy, Sentinel};
 pub use crate::serialize::{AgentType, SerializableAgent, SerializedAgent};
-pub use crate::spawn::{FitnessSpawnPolicy, SpawnPolicy};
+pub use crate::spawn::{FitnessSpawnPolicy, LamarckianSpawnPolicy, Offspring, SpawnPolicy};
 pub use crate::synthesizer::Synthesizer;
 
 // Semantic digester (requires "semantic" feature)
 #[cfg(feature = "semantic")]
+pub use crate::embedding_queue::EmbeddingQueue;
+#[cfg(feature = "semantic")]
 pub use crate::semantic_digester::{SemanticConcept, SemanticConfig, SemanticDigester};
 
 // Re-export from core
@@ -0,0 +1,373 @@
+//! Agent construction by type name.
+//!
+//! Configuration files, saved sessions and language bindings describe an
+//! agent as data — a type name and a bag of parameters — rather than as a
+//! Rust value. An [`AgentSpec`] is that description, and an
+//! [`AgentRegistry`] turns it into a boxed agent with the constructor
+//! registered for its type.
+//!
+//! [`AgentRegistry::new`] knows the built-in types: `"digester"`,
+//! `"sentinel"` and `"synthesizer"`, with parameters [`DigesterParams`],
+//! [`SentinelParams`] and [`SynthesizerParams`]. Applications add their
+//! own types with [`AgentRegistry::register`]:
+//!
+//! ```rust
+//! use phago_agents::prelude::*;
+//! use phago_agents::registry::{AgentRegistry, AgentSpec};
+//! use serde_json::json;
+//!
+//! let mut registry = AgentRegistry::new();
+//! registry.register("patient_digester", |position: Position| {
+//!     Box::new(Digester::new(position).with_max_idle(500))
+//! });
+//!
+//! let spec = AgentSpec::new("digester", json!({"position": {"x": 1.0, "y": 2.0}, "max_idle": 50}));
+//! let agent = registry.build(&spec).unwrap();
+//! assert_eq!(agent.agent_type(), "digester");
+//! ```
+
+use crate::digester::Digester;
+use crate::genome::AgentGenome;
+use crate::sentinel::{AnomalyThresholds, PatrolStrategy, Sentinel};
+use crate::synthesizer::Synthesizer;
+use phago_core::agent::Agent;
+use phago_core::primitives::EmergeConfig;
+use phago_core::types::Position;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// An agent as the colony holds it.
+pub type BoxedAgent = Box<dyn Agent<Input = String, Fragment = String, Presentation = Vec<String>>>;
+
+type Constructor = Box<dyn Fn(Value) -> Result<BoxedAgent, serde_json::Error> + Send + Sync>;
+
+/// An agent described by its type name and constructor parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentSpec {
+    pub agent_type: String,
+    /// Parameters for the type's constructor; null means all defaults.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub params: Value,
+}
+
+impl AgentSpec {
+    pub fn new(agent_type: impl Into<String>, params: Value) -> Self {
+        Self {
+            agent_type: agent_type.into(),
+            params,
+        }
+    }
+}
+
+/// Why an [`AgentSpec`] could not be turned into an agent.
+#[derive(Debug)]
+pub enum RegistryError {
+    /// No constructor is registered for the type.
+    UnknownType(String),
+    /// The parameters do not fit the type's constructor.
+    InvalidParams {
+        agent_type: String,
+        error: serde_json::Error,
+    },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::UnknownType(name) => write!(f, "unknown agent type '{name}'"),
+            RegistryError::InvalidParams { agent_type, error } => {
+                write!(
+                    f,
+                    "invalid parameters for agent type '{agent_type}': {error}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Constructors for agents, keyed by type name.
+pub struct AgentRegistry {
+    constructors: BTreeMap<String, Constructor>,
+}
+
+impl AgentRegistry {
+    /// A registry of the built-in agent types.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register("digester", DigesterParams::build);
+        registry.register("sentinel", SentinelParams::build);
+        registry.register("synthesizer", SynthesizerParams::build);
+        registry
+    }
+
+    /// A registry that knows no agent types.
+    pub fn empty() -> Self {
+        Self {
+            constructors: BTreeMap::new(),
+        }
+    }
+
+    /// Construct agents of `agent_type` from parameters deserialized into
+    /// `P`, replacing any earlier constructor for the type. A null
+    /// parameter blob deserializes as an empty object, so parameter
+    /// structs with all-default fields accept it.
+    pub fn register<P, F>(&mut self, agent_type: impl Into<String>, constructor: F)
+    where
+        P: DeserializeOwned,
+        F: Fn(P) -> BoxedAgent + Send + Sync + 'static,
+    {
+        let constructor = move |params: Value| {
+            let params = match params {
+                Value::Null => serde_json::from_value(Value::Object(Default::default()))?,
+                params => serde_json::from_value(params)?,
+            };
+            Ok(constructor(params))
+        };
+        self.constructors
+            .insert(agent_type.into(), Box::new(constructor));
+    }
+
+    /// Whether agents of `agent_type` can be built.
+    pub fn contains(&self, agent_type: &str) -> bool {
+        self.constructors.contains_key(agent_type)
+    }
+
+    /// The registered type names, in order.
+    pub fn agent_types(&self) -> impl Iterator<Item = &str> {
+        self.constructors.keys().map(String::as_str)
+    }
+
+    /// Build the agent `spec` describes.
+    pub fn build(&self, spec: &AgentSpec) -> Result<BoxedAgent, RegistryError> {
+        let constructor = self
+            .constructors
+            .get(&spec.agent_type)
+            .ok_or_else(|| RegistryError::UnknownType(spec.agent_type.clone()))?;
+        constructor(spec.params.clone()).map_err(|error| RegistryError::InvalidParams {
+            agent_type: spec.agent_type.clone(),
+            error,
+        })
+    }
+}
+
+impl Default for AgentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for AgentRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AgentRegistry")
+            .field("agent_types", &self.constructors.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn origin() -> Position {
+    Position::new(0.0, 0.0)
+}
+
+/// Parameters of a `"digester"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DigesterParams {
+    #[serde(default = "origin")]
+    pub position: Position,
+    /// Seed for a deterministic agent ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Idle threshold, signal weights and explore bias; `max_idle`
+    /// overrides the first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genome: Option<AgentGenome>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_idle: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heed_warnings: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fragments_per_tick: Option<usize>,
+}
+
+impl DigesterParams {
+    pub fn build(self) -> BoxedAgent {
+        let mut digester = match self.seed {
+            Some(seed) => Digester::with_seed(self.position, seed),
+            None => Digester::new(self.position),
+        };
+        if let Some(genome) = &self.genome {
+            digester = digester
+                .with_max_idle(genome.max_idle)
+                .with_signal_weights(genome.signal_weights())
+                .with_explore_bias(genome.explore_bias);
+        }
+        if let Some(max_idle) = self.max_idle {
+            digester = digester.with_max_idle(max_idle);
+        }
+        if let Some(heed) = self.heed_warnings {
+            digester = digester.with_heed_warnings(heed);
+        }
+        if let Some(max) = self.max_fragments_per_tick {
+            digester = digester.with_max_fragments_per_tick(max);
+        }
+        Box::new(digester)
+    }
+}
+
+/// Parameters of a `"sentinel"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SentinelParams {
+    #[serde(default = "origin")]
+    pub position: Position,
+    /// Seed for a deterministic agent ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_idle: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sense_radius: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patrol: Option<PatrolStrategy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patrol_step: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly_thresholds: Option<AnomalyThresholds>,
+}
+
+impl SentinelParams {
+    pub fn build(self) -> BoxedAgent {
+        let mut sentinel = match self.seed {
+            Some(seed) => Sentinel::with_seed(self.position, seed),
+            None => Sentinel::new(self.position),
+        };
+        if let Some(max_idle) = self.max_idle {
+            sentinel = sentinel.with_max_idle(max_idle);
+        }
+        if let Some(radius) = self.sense_radius {
+            sentinel = sentinel.with_sense_radius(radius);
+        }
+        if let Some(patrol) = self.patrol {
+            sentinel = sentinel.with_patrol(patrol);
+        }
+        if let Some(step) = self.patrol_step {
+            sentinel = sentinel.with_patrol_step(step);
+        }
+        if let Some(thresholds) = self.anomaly_thresholds {
+            sentinel = sentinel.with_anomaly_thresholds(thresholds.low, thresholds.high);
+        }
+        Box::new(sentinel)
+    }
+}
+
+/// Parameters of a `"synthesizer"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SynthesizerParams {
+    #[serde(default = "origin")]
+    pub position: Position,
+    /// Seed for a deterministic agent ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_idle: Option<u64>,
+    /// Quorum and insight thresholds; without them the colony's apply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emerge: Option<EmergeConfig>,
+    /// Chance of drifting while dormant; without it the synthesizer stays.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explore_bias: Option<f64>,
+}
+
+impl SynthesizerParams {
+    pub fn build(self) -> BoxedAgent {
+        let mut synthesizer = match self.seed {
+            Some(seed) => Synthesizer::with_seed(self.position, seed),
+            None => Synthesizer::new(self.position),
+        };
+        if let Some(max_idle) = self.max_idle {
+            synthesizer = synthesizer.with_max_idle(max_idle);
+        }
+        if let Some(emerge) = self.emerge {
+            synthesizer = synthesizer.with_emerge_config(emerge);
+        }
+        if let Some(bias) = self.explore_bias {
+            synthesizer = synthesizer.with_explore_bias(bias);
+        }
+        Box::new(synthesizer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn builds_builtin_types_from_specs() {
+        let registry = AgentRegistry::new();
+        assert_eq!(
+            registry.agent_types().collect::<Vec<_>>(),
+            ["digester", "sentinel", "synthesizer"]
+        );
+        for agent_type in ["digester", "sentinel", "synthesizer"] {
+            let spec = AgentSpec::new(agent_type, json!({"position": {"x": 3.0, "y": -1.0}}));
+            let agent = registry.build(&spec).unwrap();
+            assert_eq!(agent.agent_type(), agent_type);
+            assert_eq!(agent.position(), Position::new(3.0, -1.0));
+        }
+        let defaults = registry
+            .build(&AgentSpec::new("digester", Value::Null))
+            .unwrap();
+        assert_eq!(defaults.position(), origin());
+    }
+
+    #[test]
+    fn rejects_unknown_types_and_bad_params() {
+        let registry = AgentRegistry::new();
+        let err = registry
+            .build(&AgentSpec::new("macrophage", Value::Null))
+            .err()
+            .unwrap();
+        assert!(matches!(err, RegistryError::UnknownType(ref name) if name == "macrophage"));
+
+        let err = registry
+            .build(&AgentSpec::new("digester", json!({"max_idel": 5})))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("max_idel"), "{err}");
+        let err = registry
+            .build(&AgentSpec::new("sentinel", json!({"max_idle": "long"})))
+            .err()
+            .unwrap();
+        assert!(matches!(err, RegistryError::InvalidParams { .. }));
+    }
+
+    #[test]
+    fn registered_types_take_their_own_params() {
+        #[derive(Deserialize)]
+        struct Patient {
+            x: f64,
+            patience: u64,
+        }
+
+        let mut registry = AgentRegistry::empty();
+        registry.register("patient", |p: Patient| {
+            Box::new(Digester::new(Position::new(p.x, 0.0)).with_max_idle(p.patience))
+        });
+        assert!(registry.contains("patient"));
+        assert!(!registry.contains("digester"));
+
+        let spec = AgentSpec::new("patient", json!({"x": 4.0, "patience": 90}));
+        let agent = registry.build(&spec).unwrap();
+        assert_eq!(agent.position(), Position::new(4.0, 0.0));
+        assert!(registry
+            .build(&AgentSpec::new("patient", Value::Null))
+            .is_err());
+    }
+}
@@ -7,14 +7,25 @@
 //! - Better concept extraction from scientific/technical text
 //! - Cross-document concept linking based on meaning
 //!
+//! Embedding never happens inside a tick. Concepts are presented as soon
+//! as they are extracted, and the embeddings of those the colony has not
+//! cached are requested from an [`EmbeddingQueue`], which computes them on
+//! a worker thread; a later tick attaches them with
+//! [`AgentAction::EmbedConcepts`]. Ticks therefore take as long with a
+//! remote embedder as with a local one, and across a run each term is
+//! embedded once. Digesters sharing an embedder should also share a queue
+//! (see [`SemanticDigester::with_queue`]).
+//!
 //! Biological analog: a more evolved macrophage with pattern recognition
 //! receptors that recognize semantic patterns, not just surface keywords.
 
+use crate::embedding_queue::EmbeddingQueue;
 use phago_core::agent::Agent;
 use phago_core::primitives::symbiose::AgentProfile;
 use phago_core::primitives::{Apoptose, Digest, Sense};
-use phago_core::signal::compute_gradient;
+use phago_core::signal::compute_gradient_in;
 use phago_core::substrate::Substrate;
+use phago_core::tokenize::Tokenizer;
 use phago_core::types::*;
 use phago_embeddings::{cosine_similarity, ChunkConfig, Chunker, Embedder};
 use std::collections::{HashMap, HashSet};
@@ -25,9 +36,11 @@ use std::sync::Arc;
 pub struct SemanticConcept {
     /// The concept label (representative text).
     pub label: String,
-    /// Vector embedding for semantic comparison.
-    pub embedding: Vec<f32>,
-    /// Confidence score (0.0-1.0) based on extraction quality.
+    /// Vector embedding for semantic comparison, if the colony had one
+    /// cached when the concept was extracted.
+    pub embedding: Option<Vec<f32>>,
+    /// Confidence score (0.0-1.0): how prominent the term is in its chunk,
+    /// boosted if it is close to vocabulary learned from other agents.
     pub confidence: f32,
     /// Source chunk index.
     pub source_chunk: usize,
@@ -84,8 +97,10 @@ pub struct SemanticDigester {
     age_ticks: Tick,
     state: SemanticState,
 
-    // Embedder (shared across digesters for efficiency)
+    // Embedder (shared across digesters for efficiency), and the queue
+    // that calls it off the tick
     embedder: Arc<dyn Embedder>,
+    queue: Arc<EmbeddingQueue>,
     chunker: Chunker,
     config: SemanticConfig,
 
@@ -93,7 +108,6 @@ pub struct SemanticDigester {
     engulfed: Option<String>,
     current_document: Option<DocumentId>,
     chunks: Vec<String>,
-    chunk_embeddings: Vec<Vec<f32>>,
     concepts: Vec<SemanticConcept>,
     fragments: Vec<String>,
     all_presentations: Vec<String>,
@@ -104,6 +118,10 @@ pub struct SemanticDigester {
 
     // Transfer / Symbiose state
     known_concepts: HashMap<String, Vec<f32>>,
+    /// Internalized terms whose embeddings have not been looked up yet.
+    unlearned: HashSet<String>,
+    /// Internalized terms whose embeddings are queued.
+    awaiting: HashSet<String>,
     integrated_from: HashSet<AgentId>,
     boundary_permeability: f64,
     symbionts: Vec<SymbiontInfo>,
@@ -111,6 +129,12 @@ pub struct SemanticDigester {
     // Configuration
     max_idle_ticks: u64,
     sense_radius: f64,
+    /// Splits chunks into key terms; a colony replaces it on spawn.
+    tokenizer: Tokenizer,
+    /// Fragments presented per tick at most; `None` presents all at once.
+    max_fragments_per_tick: Option<usize>,
+    /// Extracted fragments left for the following ticks.
+    unpresented: Vec<FragmentPresentation>,
 }
 
 impl SemanticDigester {
@@ -121,24 +145,29 @@ impl SemanticDigester {
             position,
             age_ticks: 0,
             state: SemanticState::Seeking,
+            queue: Arc::new(EmbeddingQueue::new(Arc::clone(&embedder))),
             embedder,
             chunker: Chunker::default(),
             config: SemanticConfig::default(),
             engulfed: None,
             current_document: None,
             chunks: Vec::new(),
-            chunk_embeddings: Vec::new(),
             concepts: Vec::new(),
             fragments: Vec::new(),
             all_presentations: Vec::new(),
             idle_ticks: 0,
             useful_outputs: 0,
             known_concepts: HashMap::new(),
+            unlearned: HashSet::new(),
+            awaiting: HashSet::new(),
             integrated_from: HashSet::new(),
             boundary_permeability: 0.0,
             symbionts: Vec::new(),
             max_idle_ticks: 30,
             sense_radius: 10.0,
+            tokenizer: Tokenizer::default(),
+            max_fragments_per_tick: None,
+            unpresented: Vec::new(),
         }
     }
 
@@ -156,38 +185,71 @@ impl SemanticDigester {
             position,
             age_ticks: 0,
             state: SemanticState::Seeking,
+            queue: Arc::new(EmbeddingQueue::new(Arc::clone(&embedder))),
             embedder,
             chunker: Chunker::default(),
             config: SemanticConfig::default(),
             engulfed: None,
             current_document: None,
             chunks: Vec::new(),
-            chunk_embeddings: Vec::new(),
             concepts: Vec::new(),
             fragments: Vec::new(),
             all_presentations: Vec::new(),
             idle_ticks: 0,
             useful_outputs: 0,
             known_concepts: HashMap::new(),
+            unlearned: HashSet::new(),
+            awaiting: HashSet::new(),
             integrated_from: HashSet::new(),
             boundary_permeability: 0.0,
             symbionts: Vec::new(),
             max_idle_ticks: 30,
             sense_radius: 10.0,
+            tokenizer: Tokenizer::default(),
+            max_fragments_per_tick: None,
+            unpresented: Vec::new(),
         }
     }
 
+    /// Embed through `queue` instead of a queue of its own, so terms
+    /// another digester sharing it has queued are not embedded twice. The
+    /// queue's embedder replaces the digester's.
+    pub fn with_queue(mut self, queue: Arc<EmbeddingQueue>) -> Self {
+        self.embedder = Arc::clone(queue.embedder());
+        self.queue = queue;
+        self
+    }
+
     /// Set max idle threshold.
     pub fn with_max_idle(mut self, max_idle: u64) -> Self {
         self.max_idle_ticks = max_idle;
         self
     }
 
+    /// Present at most `max` fragments per tick, the rest over the
+    /// following ticks. As with [`crate::digester::Digester`], each tick's
+    /// fragments are wired among themselves.
+    pub fn with_max_fragments_per_tick(mut self, max: usize) -> Self {
+        self.max_fragments_per_tick = Some(max.max(1));
+        self
+    }
+
     /// Total concepts extracted in lifetime.
     pub fn total_concepts(&self) -> usize {
         self.all_presentations.len()
     }
 
+    /// Present as many of `presentations` as the per-tick limit allows,
+    /// keeping the rest for the following ticks.
+    fn present_in_turn(&mut self, mut presentations: Vec<FragmentPresentation>) -> AgentAction {
+        if let Some(max) = self.max_fragments_per_tick {
+            if presentations.len() > max {
+                self.unpresented = presentations.split_off(max);
+            }
+        }
+        AgentAction::PresentFragments(presentations)
+    }
+
     /// Get idle tick count.
     pub fn idle_ticks(&self) -> u64 {
         self.idle_ticks
@@ -207,12 +269,13 @@ impl SemanticDigester {
     /// Direct digestion for testing.
     pub fn digest_text(&mut self, text: String) -> Vec<String> {
         self.engulf(text);
-        self.process_digestion();
+        self.process_digestion(None);
         self.present()
     }
 
-    /// Process the digestion pipeline (chunking → embedding → extraction).
-    fn process_digestion(&mut self) {
+    /// Process the digestion pipeline (chunking → extraction), using the
+    /// embeddings `substrate` has cached. Never calls the embedder.
+    fn process_digestion(&mut self, substrate: Option<&dyn Substrate>) {
         let Some(text) = self.engulfed.take() else {
             return;
         };
@@ -221,17 +284,10 @@ impl SemanticDigester {
         let chunk_data = self.chunker.chunk(&text);
         self.chunks = chunk_data.iter().map(|c| c.text.clone()).collect();
 
-        // Step 2: Embed all chunks
-        self.chunk_embeddings = self
-            .chunks
-            .iter()
-            .filter_map(|chunk| self.embedder.embed(chunk).ok())
-            .collect();
-
-        // Step 3: Extract concepts via semantic clustering
-        self.extract_concepts();
+        // Step 2: Extract concepts, clustering those with cached embeddings
+        self.extract_concepts(substrate);
 
-        // Step 4: Generate labels for presentation
+        // Step 3: Generate labels for presentation
         self.fragments = self.concepts.iter().map(|c| c.label.clone()).collect();
 
         if !self.fragments.is_empty() {
@@ -241,51 +297,42 @@ impl SemanticDigester {
         }
     }
 
-    /// Extract concepts by finding semantically similar chunks and extracting key terms.
-    fn extract_concepts(&mut self) {
+    /// Extract key terms from each chunk as concepts, merging terms whose
+    /// cached embeddings are similar.
+    fn extract_concepts(&mut self, substrate: Option<&dyn Substrate>) {
         self.concepts.clear();
+        let cached = |term: &str| substrate.and_then(|s| s.cached_embedding(term));
 
-        if self.chunk_embeddings.is_empty() {
-            return;
-        }
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            let terms = extract_key_terms(&self.tokenizer, chunk);
+            let top = terms.first().map_or(1, |(_, count)| *count) as f32;
 
-        // Find representative concepts from each chunk
-        for (i, (chunk, embedding)) in self
-            .chunks
-            .iter()
-            .zip(self.chunk_embeddings.iter())
-            .enumerate()
-        {
-            // Extract key terms from this chunk
-            let terms = extract_key_terms(chunk);
-
-            for term in terms {
+            for (term, count) in terms {
+                let embedding = cached(&term);
                 // Check if we already have a similar concept
-                let is_duplicate = self.concepts.iter().any(|c| {
-                    if let Ok(term_emb) = self.embedder.embed(&term) {
-                        cosine_similarity(&term_emb, &c.embedding)
-                            > self.config.similarity_threshold
-                    } else {
-                        c.label.to_lowercase() == term.to_lowercase()
-                    }
-                });
-
-                if !is_duplicate {
-                    if let Ok(term_embedding) = self.embedder.embed(&term) {
-                        // Calculate confidence based on embedding quality
-                        let confidence = self.calculate_confidence(&term_embedding, embedding);
-
-                        self.concepts.push(SemanticConcept {
-                            label: term,
-                            embedding: term_embedding,
-                            confidence,
-                            source_chunk: i,
-                        });
-
-                        if self.concepts.len() >= self.config.max_concepts {
-                            break;
+                let is_duplicate = self
+                    .concepts
+                    .iter()
+                    .any(|c| match (&embedding, &c.embedding) {
+                        (Some(a), Some(b)) => {
+                            cosine_similarity(a, b) > self.config.similarity_threshold
                         }
-                    }
+                        _ => c.label.to_lowercase() == term.to_lowercase(),
+                    });
+                if is_duplicate {
+                    continue;
+                }
+
+                let confidence =
+                    self.calculate_confidence(count as f32 / top, embedding.as_deref());
+                self.concepts.push(SemanticConcept {
+                    label: term,
+                    embedding,
+                    confidence,
+                    source_chunk: i,
+                });
+                if self.concepts.len() >= self.config.max_concepts {
+                    break;
                 }
             }
 
@@ -303,28 +350,70 @@ impl SemanticDigester {
         self.concepts.truncate(self.config.max_concepts);
     }
 
-    /// Calculate concept confidence based on semantic similarity to chunk.
-    fn calculate_confidence(&self, term_embedding: &[f32], chunk_embedding: &[f32]) -> f32 {
-        let similarity = cosine_similarity(term_embedding, chunk_embedding);
-
+    /// Concept confidence from how prominent the term is in its chunk
+    /// (`salience`, 1.0 for the most frequent term).
+    fn calculate_confidence(&self, salience: f32, embedding: Option<&[f32]>) -> f32 {
         // Check if this matches any known concepts (Transfer boost)
-        let known_boost = self
-            .known_concepts
-            .values()
-            .filter_map(|known_emb| {
-                let sim = cosine_similarity(term_embedding, known_emb);
-                if sim > 0.8 {
-                    Some(sim * 0.2)
-                } else {
-                    None
+        let known_boost = embedding.map_or(0.0, |embedding| {
+            self.known_concepts
+                .values()
+                .filter_map(|known_emb| {
+                    let sim = cosine_similarity(embedding, known_emb);
+                    if sim > 0.8 {
+                        Some(sim * 0.2)
+                    } else {
+                        None
+                    }
+                })
+                .sum::<f32>()
+        });
+
+        (salience + known_boost).min(1.0)
+    }
+
+    /// Queue the embeddings of the extracted concepts the colony has not
+    /// cached, for a later tick to attach.
+    fn request_embeddings(&self, substrate: &dyn Substrate) {
+        for concept in &self.concepts {
+            if concept.embedding.is_none() && substrate.cached_embedding(&concept.label).is_none() {
+                self.queue.request(self.id, &concept.label);
+            }
+        }
+    }
+
+    /// Attach the embeddings that arrived since the last tick, if any,
+    /// learning those of vocabulary internalized from other agents.
+    fn attach_embeddings(&mut self, substrate: &dyn Substrate) -> Option<AgentAction> {
+        for term in std::mem::take(&mut self.unlearned) {
+            match substrate.cached_embedding(&term) {
+                Some(embedding) => {
+                    self.known_concepts.insert(term, embedding);
                 }
-            })
-            .sum::<f32>();
+                None => {
+                    if self.queue.request(self.id, &term) {
+                        self.awaiting.insert(term);
+                    }
+                }
+            }
+        }
 
-        (similarity + known_boost).min(1.0)
+        let arrived = self.queue.collect(self.id);
+        if arrived.is_empty() {
+            return None;
+        }
+        for concept in &arrived {
+            if self.awaiting.remove(&concept.label) {
+                if let Ok(embedding) = &concept.embedding {
+                    self.known_concepts
+                        .insert(concept.label.clone(), embedding.clone());
+                }
+            }
+        }
+        Some(AgentAction::EmbedConcepts(arrived))
     }
 
     /// Find semantically similar concepts in this digester's knowledge.
+    /// Calls the embedder directly, so it is meant for use between ticks.
     pub fn find_similar(&self, query: &str, top_k: usize) -> Vec<(String, f32)> {
         let query_embedding = match self.embedder.embed(query) {
             Ok(emb) => emb,
@@ -352,34 +441,18 @@ impl SemanticDigester {
     }
 }
 
-/// Extract key terms from a text chunk.
-fn extract_key_terms(text: &str) -> Vec<String> {
-    let stopwords: HashSet<&str> = [
-        "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
-        "do", "does", "did", "will", "would", "shall", "should", "may", "might", "must", "can",
-        "could", "of", "in", "to", "for", "with", "on", "at", "from", "by", "about", "as", "into",
-        "through", "during", "before", "after", "above", "below", "between", "out", "off", "over",
-        "under", "again", "further", "then", "once", "here", "there", "when", "where", "why",
-        "how", "all", "each", "every", "both", "few", "more", "most", "other", "some", "such",
-        "no", "nor", "not", "only", "own", "same", "so", "than", "too", "very", "just", "because",
-        "but", "and", "or", "if", "while", "that", "this", "these", "those", "it", "its", "they",
-        "them", "their", "we", "our", "you", "your", "he", "she", "his", "her", "which", "what",
-        "who", "whom",
-    ]
-    .into_iter()
-    .collect();
-
+/// Extract key terms from a text chunk, with their counts, most frequent
+/// first.
+fn extract_key_terms(tokenizer: &Tokenizer, text: &str) -> Vec<(String, usize)> {
     let mut freq: HashMap<String, usize> = HashMap::new();
-    for word in text.split(|c: char| !c.is_alphanumeric()) {
-        let word = word.to_lowercase();
-        if word.len() >= 3 && !stopwords.contains(word.as_str()) {
-            *freq.entry(word).or_insert(0) += 1;
-        }
+    for word in tokenizer.tokens(text) {
+        *freq.entry(word).or_insert(0) += 1;
     }
 
     let mut words: Vec<(String, usize)> = freq.into_iter().collect();
-    words.sort_by(|a, b| b.1.cmp(&a.1));
-    words.into_iter().take(10).map(|(w, _)| w).collect()
+    words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    words.truncate(10);
+    words
 }
 
 // --- Trait Implementations ---
@@ -401,7 +474,7 @@ impl Digest for SemanticDigester {
     }
 
     fn lyse(&mut self) -> Vec<String> {
-        self.process_digestion();
+        self.process_digestion(None);
         self.fragments.clone()
     }
 
@@ -432,6 +505,7 @@ impl Apoptose for SemanticDigester {
                 .map(|s| s.as_bytes().to_vec())
                 .collect(),
             cause: DeathCause::SelfAssessed(self.self_assess()),
+            had_pending_work: self.engulfed.is_some(),
         }
     }
 }
@@ -447,6 +521,7 @@ impl Sense for SemanticDigester {
 
     fn gradient(&self, substrate: &dyn Substrate) -> Vec<Gradient> {
         let signals = substrate.signals_near(&self.position, self.sense_radius);
+        let geometry = substrate.geometry();
 
         let mut by_type: HashMap<String, Vec<&Signal>> = HashMap::new();
         for signal in &signals {
@@ -456,7 +531,7 @@ impl Sense for SemanticDigester {
 
         by_type
             .values()
-            .filter_map(|sigs| compute_gradient(sigs, &self.position))
+            .filter_map(|sigs| compute_gradient_in(sigs, &self.position, &geometry))
             .collect()
     }
 
@@ -509,20 +584,24 @@ impl Agent for SemanticDigester {
         if self.should_die() {
             return AgentAction::Apoptose;
         }
+        if let Some(action) = self.attach_embeddings(substrate) {
+            return action;
+        }
 
         match self.state.clone() {
             SemanticState::Seeking => {
                 let docs = substrate.undigested_documents();
                 let nearby_doc = docs
                     .iter()
-                    .find(|d| d.position.distance_to(&self.position) <= self.sense_radius);
+                    .find(|d| substrate.distance(&d.position, &self.position) <= self.sense_radius);
 
                 if let Some(doc) = nearby_doc {
                     let doc_id = doc.id;
                     let doc_pos = doc.position;
 
-                    if doc_pos.distance_to(&self.position) < 1.0 {
+                    if substrate.distance(&doc_pos, &self.position) < 1.0 {
                         self.state = SemanticState::FoundTarget(doc_id);
+                        self.current_document = Some(doc_id);
                         return AgentAction::EngulfDocument(doc_id);
                     } else {
                         self.idle_ticks += 1;
@@ -553,6 +632,7 @@ impl Agent for SemanticDigester {
                     AgentAction::Idle
                 } else {
                     self.state = SemanticState::Seeking;
+                    self.current_document = None;
                     self.idle_ticks += 1;
                     AgentAction::Idle
                 }
@@ -570,7 +650,9 @@ impl Agent for SemanticDigester {
             }
 
             SemanticState::Extracting => {
-                let fragments = self.lyse();
+                self.process_digestion(Some(substrate));
+                let fragments = self.fragments.clone();
+                self.request_embeddings(substrate);
                 if fragments.is_empty() {
                     self.state = SemanticState::Seeking;
                     self.idle_ticks += 1;
@@ -585,13 +667,22 @@ impl Agent for SemanticDigester {
                             source_document: doc_id,
                             position: self.position,
                             node_type: NodeType::Concept,
+                            derived_from: Vec::new(),
+                            passages: Vec::new(),
+                            access_weight: 1,
+                            score: None,
+                            namespace: None,
                         })
                         .collect();
-                    AgentAction::PresentFragments(presentations)
+                    self.present_in_turn(presentations)
                 }
             }
 
             SemanticState::Presenting => {
+                if !self.unpresented.is_empty() {
+                    let rest = std::mem::take(&mut self.unpresented);
+                    return self.present_in_turn(rest);
+                }
                 self.state = SemanticState::Seeking;
                 self.current_document = None;
                 let trace = Trace {
@@ -610,6 +701,19 @@ impl Agent for SemanticDigester {
         self.age_ticks
     }
 
+    fn release_undigested(&mut self) -> Option<(DocumentId, String)> {
+        let doc_id = self.current_document?;
+        let content = self.engulfed.take()?;
+        self.current_document = None;
+        self.unpresented.clear();
+        self.state = SemanticState::Seeking;
+        Some((doc_id, content))
+    }
+
+    fn adopt_tokenizer(&mut self, tokenizer: &Tokenizer) {
+        self.tokenizer = tokenizer.clone();
+    }
+
     fn profile(&self) -> AgentProfile {
         AgentProfile {
             id: self.id,
@@ -636,10 +740,13 @@ impl Agent for SemanticDigester {
         self.all_presentations.clone()
     }
 
+    /// Terms are learned with their embeddings over the following ticks,
+    /// from the colony's cache or the queue.
     fn internalize_vocabulary(&mut self, terms: &[String]) {
         for term in terms {
-            if let Ok(embedding) = self.embedder.embed(term) {
-                self.known_concepts.insert(term.clone(), embedding);
+            let term = term.trim().to_lowercase();
+            if !self.known_concepts.contains_key(&term) && !self.awaiting.contains(&term) {
+                self.unlearned.insert(term);
             }
         }
     }
@@ -649,6 +756,12 @@ impl Agent for SemanticDigester {
     }
 }
 
+impl Drop for SemanticDigester {
+    fn drop(&mut self) {
+        self.queue.forget(self.id);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
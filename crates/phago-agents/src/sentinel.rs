@@ -10,13 +10,26 @@
 //!
 //! The Sentinel learns what "normal" looks like (finite, learnable) and
 //! flags everything that deviates — without needing to enumerate threats.
+//!
+//! A sentinel only sees concepts within its sense radius. It matures where
+//! it was spawned and then stays there, unless given a [`PatrolStrategy`]:
+//! patrolling sentinels move a fixed step per tick along their route, so
+//! how far they see and how fast they cover ground are set separately.
+//!
+//! Findings are scored rather than flagged: documents by the share of their
+//! terms the self-model has never seen, weighted toward terms still rare in
+//! the graph. [`AnomalyThresholds`] decide what is ignored, what is
+//! presented as a `[REVIEW]` node, and what becomes a full `[ANOMALY]`.
 
-use phago_core::agent::Agent;
+use phago_core::agent::{top_terms, Agent};
 use phago_core::primitives::symbiose::AgentProfile;
 use phago_core::primitives::{Apoptose, Digest, Negate, Sense};
+use phago_core::signal::{blend_gradients, compute_gradient_in, AnomalyWarning};
 use phago_core::substrate::Substrate;
 use phago_core::types::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
 /// How many ticks of observation before the self-model is considered mature.
 const MATURATION_TICKS: u64 = 10;
@@ -24,6 +37,207 @@ const MATURATION_TICKS: u64 = 10;
 const ANOMALY_THRESHOLD: f64 = 0.5;
 /// Maximum anomalies to report per scan cycle.
 const MAX_ANOMALIES_PER_SCAN: usize = 10;
+/// Default distance a patrolling sentinel moves per tick.
+const DEFAULT_PATROL_STEP: f64 = 2.0;
+
+/// Novelty scores separating ignored findings from ones flagged for
+/// review, and those from full anomalies.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AnomalyThresholds {
+    /// Findings scored below this are ignored.
+    pub low: f64,
+    /// Findings scored at or above this are anomalies; those in between are
+    /// flagged for review.
+    pub high: f64,
+}
+
+impl Default for AnomalyThresholds {
+    fn default() -> Self {
+        Self {
+            low: 0.25,
+            high: 0.5,
+        }
+    }
+}
+
+impl AnomalyThresholds {
+    /// How a finding with this novelty score is treated.
+    pub fn grade(&self, score: f64) -> AnomalyGrade {
+        if score >= self.high {
+            AnomalyGrade::Anomaly
+        } else if score >= self.low {
+            AnomalyGrade::Review
+        } else {
+            AnomalyGrade::Ignore
+        }
+    }
+}
+
+/// How a scored finding is treated (see [`AnomalyThresholds`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnomalyGrade {
+    /// Too ordinary to report.
+    Ignore,
+    /// Presented as a `[REVIEW]` node, without warning other agents.
+    Review,
+    /// Presented as an `[ANOMALY]` node, and its terms signalled as suspect.
+    Anomaly,
+}
+
+impl fmt::Display for AnomalyGrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AnomalyGrade::Ignore => "ignore",
+            AnomalyGrade::Review => "review",
+            AnomalyGrade::Anomaly => "anomaly",
+        })
+    }
+}
+
+/// Something a scan turned up.
+#[derive(Debug, Clone)]
+struct Finding {
+    description: String,
+    /// The terms it is about.
+    terms: Vec<String>,
+    /// Novelty from 0.0 to 1.0.
+    score: f64,
+    /// The document it is about, if any.
+    document: Option<DocumentId>,
+}
+
+/// Rarity weight of a term the graph has seen `count` times: 1.0 for a
+/// term seen once, falling off logarithmically.
+fn rarity(count: u64) -> f64 {
+    1.0 / (1.0 + (count.max(1) as f64).ln())
+}
+
+/// How a sentinel moves once its self-model has matured.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PatrolStrategy {
+    /// Visit the positions in order, starting over after the last.
+    Waypoints(Vec<Position>),
+    /// Sweep `bounds` back and forth in horizontal lanes `lane_spacing`
+    /// apart, starting over from the first lane.
+    CoverageSweep { bounds: Rect, lane_spacing: f64 },
+    /// Follow anomaly warnings and input signals, weighted as given; with
+    /// nothing sensed, head for the least-visited neighbouring region.
+    SignalSeeking {
+        weight_on_warning: f64,
+        weight_on_input: f64,
+    },
+}
+
+impl PatrolStrategy {
+    /// The positions to visit in turn; empty for signal seeking.
+    fn route(&self) -> Vec<Position> {
+        match self {
+            PatrolStrategy::Waypoints(points) => points.clone(),
+            PatrolStrategy::CoverageSweep {
+                bounds,
+                lane_spacing,
+            } => {
+                // A non-positive spacing sweeps the top and bottom edges only
+                let spacing = if *lane_spacing > 0.0 {
+                    *lane_spacing
+                } else {
+                    f64::INFINITY
+                };
+                let mut route = Vec::new();
+                let mut y = bounds.min.y;
+                let mut forward = true;
+                loop {
+                    let (from, to) = if forward {
+                        (bounds.min.x, bounds.max.x)
+                    } else {
+                        (bounds.max.x, bounds.min.x)
+                    };
+                    route.push(Position::new(from, y));
+                    route.push(Position::new(to, y));
+                    if y >= bounds.max.y {
+                        return route;
+                    }
+                    y = (y + spacing).min(bounds.max.y);
+                    forward = !forward;
+                }
+            }
+            PatrolStrategy::SignalSeeking { .. } => Vec::new(),
+        }
+    }
+}
+
+/// Visits per grid cell, for steering toward the regions seen least.
+#[derive(Debug, Clone)]
+struct VisitGrid {
+    cell_size: f64,
+    visits: HashMap<(i64, i64), u32>,
+}
+
+impl VisitGrid {
+    fn new(cell_size: f64) -> Self {
+        Self {
+            cell_size: cell_size.max(1.0),
+            visits: HashMap::new(),
+        }
+    }
+
+    fn cell(&self, position: &Position) -> (i64, i64) {
+        (
+            (position.x / self.cell_size).floor() as i64,
+            (position.y / self.cell_size).floor() as i64,
+        )
+    }
+
+    fn record(&mut self, position: &Position) {
+        *self.visits.entry(self.cell(position)).or_insert(0) += 1;
+    }
+
+    /// Centre of the least-visited cell around the one holding `position`;
+    /// ties go to the first in a fixed order.
+    fn least_visited_neighbor(&self, position: &Position) -> Position {
+        const NEIGHBORS: [(i64, i64); 8] = [
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+        ];
+        let (cx, cy) = self.cell(position);
+        let (dx, dy) = NEIGHBORS
+            .into_iter()
+            .min_by_key(|(dx, dy)| self.visits.get(&(cx + dx, cy + dy)).copied().unwrap_or(0))
+            .unwrap_or((0, 0));
+        Position::new(
+            ((cx + dx) as f64 + 0.5) * self.cell_size,
+            ((cy + dy) as f64 + 0.5) * self.cell_size,
+        )
+    }
+}
+
+/// A sentinel's patrol and its progress.
+#[derive(Debug, Clone)]
+struct Patrol {
+    strategy: PatrolStrategy,
+    route: Vec<Position>,
+    /// Index into `route` of the position heading for.
+    next: usize,
+    visits: VisitGrid,
+}
+
+impl Patrol {
+    fn new(strategy: PatrolStrategy, cell_size: f64) -> Self {
+        Self {
+            route: strategy.route(),
+            strategy,
+            next: 0,
+            visits: VisitGrid::new(cell_size),
+        }
+    }
+}
 
 /// Statistical self-model: distribution of concept frequencies.
 #[derive(Debug, Clone)]
@@ -68,7 +282,7 @@ enum SentinelState {
     /// Mature — actively scanning for anomalies.
     Scanning,
     /// Detected an anomaly — emitting signal.
-    Alerting(String), // anomaly description
+    Alerting(AnomalyWarning),
 }
 
 /// The Sentinel agent — the immune system's anomaly detector.
@@ -94,6 +308,13 @@ pub struct Sentinel {
     max_idle_ticks: u64,
     idle_ticks: u64,
     scan_interval: u64,
+    /// Movement once mature; `None` keeps the sentinel where it is.
+    patrol: Option<Patrol>,
+    /// Distance moved per tick while patrolling.
+    patrol_step: f64,
+    anomaly_thresholds: AnomalyThresholds,
+    /// Document → the version last scored.
+    scored_documents: HashMap<DocumentId, u32>,
 }
 
 impl Sentinel {
@@ -112,6 +333,10 @@ impl Sentinel {
             max_idle_ticks: 200, // Very patient
             idle_ticks: 0,
             scan_interval: 5,
+            patrol: None,
+            patrol_step: DEFAULT_PATROL_STEP,
+            anomaly_thresholds: AnomalyThresholds::default(),
+            scored_documents: HashMap::new(),
         }
     }
 
@@ -131,24 +356,140 @@ impl Sentinel {
             max_idle_ticks: 200,
             idle_ticks: 0,
             scan_interval: 5,
+            patrol: None,
+            patrol_step: DEFAULT_PATROL_STEP,
+            anomaly_thresholds: AnomalyThresholds::default(),
+            scored_documents: HashMap::new(),
+        }
+    }
+
+    /// Set the idle ticks after which the sentinel dies (default: 200).
+    pub fn with_max_idle(mut self, max_idle: u64) -> Self {
+        self.max_idle_ticks = max_idle;
+        self
+    }
+
+    /// Patrol with `strategy` once the self-model has matured.
+    pub fn with_patrol(mut self, strategy: PatrolStrategy) -> Self {
+        self.patrol = Some(Patrol::new(strategy, self.sense_radius));
+        self
+    }
+
+    /// Set how far the sentinel moves per tick while patrolling
+    /// (default: 2.0).
+    pub fn with_patrol_step(mut self, step: f64) -> Self {
+        self.patrol_step = step;
+        self
+    }
+
+    /// Set the radius within which concepts are observed and scanned
+    /// (default: 50.0).
+    pub fn with_sense_radius(mut self, radius: f64) -> Self {
+        self.sense_radius = radius;
+        if let Some(patrol) = &mut self.patrol {
+            patrol.visits = VisitGrid::new(radius);
+        }
+        self
+    }
+
+    /// Ignore findings scored below `low`, flag those below `high` for
+    /// review, and treat the rest as anomalies (default: 0.25 and 0.5).
+    /// A `low` above `high` is lowered to it.
+    pub fn with_anomaly_thresholds(mut self, low: f64, high: f64) -> Self {
+        self.anomaly_thresholds = AnomalyThresholds {
+            low: low.min(high),
+            high,
+        };
+        self
+    }
+
+    pub fn anomaly_thresholds(&self) -> AnomalyThresholds {
+        self.anomaly_thresholds
+    }
+
+    /// Share of an observation's terms missing from the self-model, each
+    /// weighted by how rare it is in the graph, from 0.0 (all familiar) to
+    /// 1.0 (all novel). `None` for an empty observation.
+    pub fn novelty_score(&self, observation: &[(String, u64)]) -> Option<f64> {
+        if observation.is_empty() {
+            return None;
         }
+        let mut novel = 0.0;
+        let mut total = 0.0;
+        for (label, count) in observation {
+            let weight = rarity(*count);
+            total += weight;
+            if !self.self_model.concept_freq.contains_key(label) {
+                novel += weight;
+            }
+        }
+        Some(novel / total)
+    }
+
+    /// The patrol strategy, if the sentinel patrols.
+    pub fn patrol(&self) -> Option<&PatrolStrategy> {
+        self.patrol.as_ref().map(|p| &p.strategy)
     }
 
     pub fn anomalies_detected(&self) -> u64 {
         self.anomalies_detected
     }
 
+    /// Concept nodes within the sense radius.
+    fn concepts_in_range<'a>(&self, substrate: &'a dyn Substrate) -> Vec<&'a NodeData> {
+        substrate
+            .all_nodes()
+            .iter()
+            .filter_map(|id| substrate.get_node(id))
+            .filter(|node| {
+                node.node_type == NodeType::Concept
+                    && substrate.distance(&node.position, &self.position) <= self.sense_radius
+            })
+            .collect()
+    }
+
+    /// Whether the node is within the sense radius.
+    fn in_range(&self, substrate: &dyn Substrate, node: &NodeId) -> bool {
+        substrate
+            .get_node(node)
+            .is_some_and(|n| substrate.distance(&n.position, &self.position) <= self.sense_radius)
+    }
+
+    /// Step along the patrol, or stay put without one.
+    fn patrol_move(&mut self, substrate: &dyn Substrate) -> AgentAction {
+        let Some(patrol) = &mut self.patrol else {
+            return AgentAction::Idle;
+        };
+        patrol.visits.record(&self.position);
+        let gradients = self.gradient(substrate);
+        let Orientation::Toward(target) = self.orient(&gradients) else {
+            return AgentAction::Idle;
+        };
+
+        let offset = substrate.geometry().offset(&self.position, &target);
+        let distance = (offset.x * offset.x + offset.y * offset.y).sqrt();
+        if distance <= self.patrol_step {
+            // Reached the next stop of the route
+            if let Some(patrol) = &mut self.patrol {
+                if !patrol.route.is_empty() {
+                    patrol.next = (patrol.next + 1) % patrol.route.len();
+                }
+            }
+            return AgentAction::Move(target);
+        }
+        let scale = self.patrol_step / distance;
+        AgentAction::Move(Position::new(
+            self.position.x + offset.x * scale,
+            self.position.y + offset.y * scale,
+        ))
+    }
+
     /// Build the self-model by observing the current graph state.
     fn observe_graph(&mut self, substrate: &dyn Substrate) {
-        let all_nodes = substrate.all_nodes();
         let mut concept_counts: HashMap<String, u64> = HashMap::new();
 
-        for node_id in &all_nodes {
-            if let Some(node) = substrate.get_node(node_id) {
-                if node.node_type == NodeType::Concept {
-                    *concept_counts.entry(node.label.clone()).or_insert(0) += node.access_count;
-                }
-            }
+        for node in self.concepts_in_range(substrate) {
+            *concept_counts.entry(node.label.clone()).or_insert(0) += node.access_count;
         }
 
         // Update frequency distribution
@@ -168,7 +509,11 @@ impl Sentinel {
         }
 
         // Compute edge weight statistics
-        let all_edges = substrate.all_edges();
+        let all_edges: Vec<_> = substrate
+            .all_edges()
+            .into_iter()
+            .filter(|(from, to, _)| self.in_range(substrate, from) || self.in_range(substrate, to))
+            .collect();
         if !all_edges.is_empty() {
             let weights: Vec<f64> = all_edges.iter().map(|(_, _, e)| e.weight).collect();
             let mean = weights.iter().sum::<f64>() / weights.len() as f64;
@@ -188,7 +533,7 @@ impl Sentinel {
     }
 
     /// Scan the graph for anomalies by comparing current state to self-model.
-    fn scan_for_anomalies(&self, substrate: &dyn Substrate) -> Vec<String> {
+    fn scan_for_anomalies(&self, substrate: &dyn Substrate) -> Vec<Finding> {
         let mut anomalies = Vec::new();
 
         if self.self_model.observation_count == 0 {
@@ -196,17 +541,12 @@ impl Sentinel {
         }
 
         // Check for concept nodes that deviate from expected distribution
-        let all_nodes = substrate.all_nodes();
         let mut current_counts: HashMap<String, u64> = HashMap::new();
         let mut total_count: u64 = 0;
 
-        for node_id in &all_nodes {
-            if let Some(node) = substrate.get_node(node_id) {
-                if node.node_type == NodeType::Concept {
-                    *current_counts.entry(node.label.clone()).or_insert(0) += node.access_count;
-                    total_count += node.access_count;
-                }
-            }
+        for node in self.concepts_in_range(substrate) {
+            *current_counts.entry(node.label.clone()).or_insert(0) += node.access_count;
+            total_count += node.access_count;
         }
 
         if total_count == 0 {
@@ -221,10 +561,15 @@ impl Sentinel {
                 None => {
                     // Concept not in self-model — it's novel
                     if current_freq > 0.01 {
-                        anomalies.push(format!(
-                            "Novel concept '{}' not in self-model (freq: {:.3})",
-                            label, current_freq
-                        ));
+                        anomalies.push(Finding {
+                            description: format!(
+                                "Novel concept '{}' not in self-model (freq: {:.3})",
+                                label, current_freq
+                            ),
+                            terms: vec![label.clone()],
+                            score: 1.0,
+                            document: None,
+                        });
                     }
                 }
                 Some(&expected_freq) => {
@@ -232,10 +577,15 @@ impl Sentinel {
                     if expected_freq > 0.0 {
                         let deviation = (current_freq - expected_freq).abs() / expected_freq;
                         if deviation > ANOMALY_THRESHOLD {
-                            anomalies.push(format!(
-                                "Concept '{}' deviates from self-model: expected {:.3}, got {:.3} (deviation: {:.1}%)",
-                                label, expected_freq, current_freq, deviation * 100.0
-                            ));
+                            anomalies.push(Finding {
+                                description: format!(
+                                    "Concept '{}' deviates from self-model: expected {:.3}, got {:.3} (deviation: {:.1}%)",
+                                    label, expected_freq, current_freq, deviation * 100.0
+                                ),
+                                terms: vec![label.clone()],
+                                score: deviation.min(1.0),
+                                document: None,
+                            });
                         }
                     }
                 }
@@ -244,11 +594,13 @@ impl Sentinel {
 
         // Check for edge weight anomalies (only if we have enough observations)
         if self.self_model.observation_count >= 5 {
-            let all_edges = substrate.all_edges();
             let mean = self.self_model.mean_edge_weight;
             let std = self.self_model.edge_weight_std.max(0.05);
 
-            for (from_id, to_id, edge) in &all_edges {
+            for (from_id, to_id, edge) in &substrate.all_edges() {
+                if !self.in_range(substrate, from_id) && !self.in_range(substrate, to_id) {
+                    continue;
+                }
                 let z_score = (edge.weight - mean).abs() / std;
                 if z_score > 3.0 {
                     let from_label = substrate
@@ -259,16 +611,74 @@ impl Sentinel {
                         .get_node(to_id)
                         .map(|n| n.label.as_str())
                         .unwrap_or("?");
-                    anomalies.push(format!(
-                        "Edge '{}'-'{}' has anomalous weight {:.3} (z-score: {:.1})",
-                        from_label, to_label, edge.weight, z_score
-                    ));
+                    anomalies.push(Finding {
+                        description: format!(
+                            "Edge '{}'-'{}' has anomalous weight {:.3} (z-score: {:.1})",
+                            from_label, to_label, edge.weight, z_score
+                        ),
+                        terms: vec![from_label.to_string(), to_label.to_string()],
+                        // Twice the cut-off scores 1.0
+                        score: (z_score / 6.0).min(1.0),
+                        document: None,
+                    });
                 }
             }
         }
 
         anomalies
     }
+
+    /// Score digested documents within the sense radius that are new, or
+    /// changed since last scored, by the novelty of the concepts they
+    /// mention.
+    fn score_new_documents(&mut self, substrate: &dyn Substrate) -> Vec<Finding> {
+        let counts: HashMap<&str, u64> = substrate
+            .all_nodes()
+            .iter()
+            .filter_map(|id| substrate.get_node(id))
+            .filter(|node| node.node_type == NodeType::Concept)
+            .map(|node| (node.label.as_str(), node.access_count))
+            .collect();
+
+        let mut findings = Vec::new();
+        for doc in substrate.all_documents() {
+            if !doc.digested
+                || self.scored_documents.get(&doc.id) == Some(&doc.version)
+                || substrate.distance(&doc.position, &self.position) > self.sense_radius
+            {
+                continue;
+            }
+            self.scored_documents.insert(doc.id, doc.version);
+
+            let mut observation: Vec<(String, u64)> = Vec::new();
+            for word in doc.content.split(|c: char| !c.is_alphanumeric()) {
+                let term = word.to_lowercase();
+                if let Some(&count) = counts.get(term.as_str()) {
+                    if !observation.iter().any(|(t, _)| *t == term) {
+                        observation.push((term, count));
+                    }
+                }
+            }
+            let Classification::NonSelf(score) = self.classify(&observation) else {
+                continue;
+            };
+            let terms = observation
+                .into_iter()
+                .map(|(term, _)| term)
+                .filter(|term| !self.self_model.concept_freq.contains_key(term))
+                .collect();
+            findings.push(Finding {
+                description: format!(
+                    "Document '{}' is novel to the self-model (score: {:.2})",
+                    doc.title, score
+                ),
+                terms,
+                score,
+                document: Some(doc.id),
+            });
+        }
+        findings
+    }
 }
 
 // --- Trait Implementations ---
@@ -313,6 +723,7 @@ impl Apoptose for Sentinel {
             useful_outputs: self.anomalies_detected,
             final_fragments: Vec::new(),
             cause: DeathCause::SelfAssessed(self.self_assess()),
+            had_pending_work: false,
         }
     }
 }
@@ -326,12 +737,52 @@ impl Sense for Sentinel {
         self.position
     }
 
-    fn gradient(&self, _substrate: &dyn Substrate) -> Vec<Gradient> {
-        Vec::new() // Sentinels don't chase signals
+    fn gradient(&self, substrate: &dyn Substrate) -> Vec<Gradient> {
+        // Only signal-seeking sentinels chase signals
+        let Some(Patrol {
+            strategy: PatrolStrategy::SignalSeeking { .. },
+            ..
+        }) = &self.patrol
+        else {
+            return Vec::new();
+        };
+        let signals = substrate.signals_near(&self.position, self.sense_radius);
+        let geometry = substrate.geometry();
+        let mut by_type: HashMap<&SignalType, Vec<&Signal>> = HashMap::new();
+        for signal in &signals {
+            by_type.entry(&signal.signal_type).or_default().push(signal);
+        }
+        by_type
+            .values()
+            .filter_map(|sigs| compute_gradient_in(sigs, &self.position, &geometry))
+            .collect()
     }
 
-    fn orient(&self, _gradients: &[Gradient]) -> Orientation {
-        Orientation::Stay // Sentinels are stationary
+    fn orient(&self, gradients: &[Gradient]) -> Orientation {
+        let Some(patrol) = &self.patrol else {
+            return Orientation::Stay; // Static sentinels stay where they were placed
+        };
+        if let Some(stop) = patrol.route.get(patrol.next) {
+            return Orientation::Toward(*stop);
+        }
+        let PatrolStrategy::SignalSeeking {
+            weight_on_warning,
+            weight_on_input,
+        } = patrol.strategy
+        else {
+            return Orientation::Stay; // An empty route
+        };
+        let weights = HashMap::from([
+            (SignalType::Anomaly, weight_on_warning),
+            (SignalType::Input, weight_on_input),
+        ]);
+        match blend_gradients(gradients, &weights) {
+            Some(g) => Orientation::Toward(Position::new(
+                self.position.x + g.direction.x * self.patrol_step,
+                self.position.y + g.direction.y * self.patrol_step,
+            )),
+            None => Orientation::Toward(patrol.visits.least_visited_neighbor(&self.position)),
+        }
     }
 }
 
@@ -372,29 +823,10 @@ impl Negate for Sentinel {
             return Classification::Unknown;
         }
 
-        let total: u64 = observation.iter().map(|(_, c)| c).sum();
-        if total == 0 {
-            return Classification::Unknown;
-        }
-
-        let mut max_deviation = 0.0f64;
-        for (label, count) in observation {
-            let freq = *count as f64 / total as f64;
-            if let Some(&expected) = self.self_model.concept_freq.get(label) {
-                if expected > 0.0 {
-                    let deviation = (freq - expected).abs() / expected;
-                    max_deviation = max_deviation.max(deviation);
-                }
-            } else {
-                // Novel concept — high deviation
-                max_deviation = max_deviation.max(1.0);
-            }
-        }
-
-        if max_deviation > ANOMALY_THRESHOLD {
-            Classification::NonSelf(max_deviation.min(1.0))
-        } else {
-            Classification::IsSelf
+        match self.novelty_score(observation) {
+            None => Classification::Unknown,
+            Some(score) if score >= self.anomaly_thresholds.low => Classification::NonSelf(score),
+            Some(_) => Classification::IsSelf,
         }
     }
 }
@@ -446,23 +878,44 @@ impl Agent for Sentinel {
                     self.last_scan_tick = self.age_ticks;
 
                     let mut anomalies = self.scan_for_anomalies(substrate);
+                    anomalies.extend(self.score_new_documents(substrate));
+                    let thresholds = self.anomaly_thresholds;
+                    anomalies.retain(|a| thresholds.grade(a.score) != AnomalyGrade::Ignore);
                     // Cap to most significant anomalies per cycle
+                    anomalies.sort_by(|a, b| b.score.total_cmp(&a.score));
                     anomalies.truncate(MAX_ANOMALIES_PER_SCAN);
 
                     if !anomalies.is_empty() {
-                        let description = anomalies.join("; ");
+                        // Only full anomalies warn other agents off their terms
+                        let mut terms: Vec<String> = anomalies
+                            .iter()
+                            .filter(|a| thresholds.grade(a.score) == AnomalyGrade::Anomaly)
+                            .flat_map(|a| a.terms.iter().cloned())
+                            .collect();
+                        terms.sort();
+                        terms.dedup();
                         self.anomalies_detected += anomalies.len() as u64;
-                        self.state = SentinelState::Alerting(description.clone());
+                        if !terms.is_empty() {
+                            self.state = SentinelState::Alerting(AnomalyWarning { terms });
+                        }
                         self.idle_ticks = 0;
 
                         // Present anomalies as insight fragments
                         let presentations: Vec<FragmentPresentation> = anomalies
                             .iter()
                             .map(|a| FragmentPresentation {
-                                label: format!("[ANOMALY] {}", a),
-                                source_document: DocumentId::new(),
+                                label: match thresholds.grade(a.score) {
+                                    AnomalyGrade::Anomaly => format!("[ANOMALY] {}", a.description),
+                                    _ => format!("[REVIEW] {}", a.description),
+                                },
+                                source_document: a.document.unwrap_or_else(DocumentId::new),
                                 position: self.position,
                                 node_type: NodeType::Anomaly,
+                                derived_from: Vec::new(),
+                                passages: Vec::new(),
+                                access_weight: 1,
+                                score: Some(a.score),
+                                namespace: None,
                             })
                             .collect();
 
@@ -471,19 +924,23 @@ impl Agent for Sentinel {
                 }
 
                 self.idle_ticks += 1;
-                AgentAction::Idle
+                self.patrol_move(substrate)
             }
 
-            SentinelState::Alerting(_description) => {
-                // Emit anomaly signal to attract synthesizers
+            SentinelState::Alerting(warning) => {
+                // Emit anomaly signal to attract synthesizers, naming the
+                // suspect terms so digesters can avoid them
                 self.state = SentinelState::Scanning;
-                AgentAction::Emit(Signal::new(
-                    SignalType::Anomaly,
-                    1.0,
-                    self.position,
-                    self.id,
-                    self.age_ticks,
-                ))
+                AgentAction::Emit(
+                    Signal::new(
+                        SignalType::Anomaly,
+                        1.0,
+                        self.position,
+                        self.id,
+                        self.age_ticks,
+                    )
+                    .with_payload(warning),
+                )
             }
         }
     }
@@ -497,14 +954,15 @@ impl Agent for Sentinel {
             return None;
         }
         let terms: Vec<String> = self.self_model.concept_freq.keys().cloned().collect();
-        let cap = VocabularyCapability {
-            terms,
-            origin: self.id,
-            document_count: self.self_model.observation_count,
-        };
+        let cap = VocabularyCapability::new(terms, self.id, self.self_model.observation_count);
         serde_json::to_vec(&cap).ok()
     }
 
+    fn vocabulary_snapshot(&self, top_n: usize) -> Vec<(String, f64)> {
+        let weights = self.self_model.concept_freq.iter();
+        top_terms(weights.map(|(c, f)| (c.as_str(), *f)), top_n)
+    }
+
     fn profile(&self) -> AgentProfile {
         AgentProfile {
             id: self.id,
@@ -534,6 +992,9 @@ impl SerializableAgent for Sentinel {
             sense_radius: self.sense_radius,
             max_idle_ticks: self.max_idle_ticks,
             scan_interval: self.scan_interval,
+            patrol: self.patrol().cloned(),
+            patrol_step: self.patrol_step,
+            anomaly_thresholds: self.anomaly_thresholds,
         })
     }
 
@@ -554,6 +1015,13 @@ impl SerializableAgent for Sentinel {
                     max_idle_ticks: s.max_idle_ticks,
                     idle_ticks: s.idle_ticks,
                     scan_interval: s.scan_interval,
+                    patrol: s
+                        .patrol
+                        .clone()
+                        .map(|strategy| Patrol::new(strategy, s.sense_radius)),
+                    patrol_step: s.patrol_step,
+                    anomaly_thresholds: s.anomaly_thresholds,
+                    scored_documents: HashMap::new(),
                 };
                 // Restore self-model concepts with default frequency
                 for concept in &s.self_model_concepts {
@@ -597,4 +1065,66 @@ mod tests {
         assert!(sentinel.self_model().concept_freq.contains_key("membrane"));
         assert_eq!(sentinel.self_model().observation_count, 2);
     }
+
+    #[test]
+    fn coverage_sweep_alternates_lane_direction() {
+        let strategy = PatrolStrategy::CoverageSweep {
+            bounds: Rect {
+                min: Position::new(0.0, 0.0),
+                max: Position::new(10.0, 25.0),
+            },
+            lane_spacing: 10.0,
+        };
+        let route: Vec<(f64, f64)> = strategy.route().iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(
+            route,
+            vec![
+                (0.0, 0.0),
+                (10.0, 0.0),
+                (10.0, 10.0),
+                (0.0, 10.0),
+                (0.0, 20.0),
+                (10.0, 20.0),
+                (10.0, 25.0),
+                (0.0, 25.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn visit_grid_prefers_unvisited_neighbors() {
+        let mut grid = VisitGrid::new(10.0);
+        let here = Position::new(5.0, 5.0);
+        grid.record(&here);
+        assert_eq!(grid.least_visited_neighbor(&here), Position::new(15.0, 5.0));
+
+        grid.record(&Position::new(15.0, 5.0));
+        assert_eq!(
+            grid.least_visited_neighbor(&here),
+            Position::new(15.0, 15.0)
+        );
+    }
+
+    #[test]
+    fn patrol_survives_state_round_trip() {
+        let strategy = PatrolStrategy::Waypoints(vec![Position::new(3.0, 4.0)]);
+        let sentinel = Sentinel::new(Position::new(0.0, 0.0))
+            .with_patrol(strategy.clone())
+            .with_patrol_step(4.5);
+        let restored = Sentinel::from_state(&sentinel.export_state()).unwrap();
+        assert_eq!(restored.patrol(), Some(&strategy));
+        assert_eq!(restored.patrol_step, 4.5);
+    }
+
+    #[test]
+    fn anomaly_thresholds_grade_scores() {
+        let sentinel = Sentinel::new(Position::new(0.0, 0.0)).with_anomaly_thresholds(0.3, 0.7);
+        let thresholds = sentinel.anomaly_thresholds();
+        assert_eq!(thresholds.grade(0.1), AnomalyGrade::Ignore);
+        assert_eq!(thresholds.grade(0.3), AnomalyGrade::Review);
+        assert_eq!(thresholds.grade(0.7), AnomalyGrade::Anomaly);
+
+        let restored = Sentinel::from_state(&sentinel.export_state()).unwrap();
+        assert_eq!(restored.anomaly_thresholds(), thresholds);
+    }
 }
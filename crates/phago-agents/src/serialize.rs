@@ -3,6 +3,8 @@
 //! Enables saving and restoring agent state across sessions.
 //! Each agent type has a corresponding serializable state struct.
 
+use crate::sentinel::{AnomalyThresholds, PatrolStrategy};
+use phago_core::primitives::{DigestionLimits, EmergeConfig};
 use phago_core::types::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -43,6 +45,25 @@ pub struct DigesterState {
     pub boundary_permeability: f64,
     pub max_idle_ticks: u64,
     pub sense_radius: f64,
+    /// SENSE blending weights; empty means the agent's defaults.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signal_weights: Vec<(SignalType, f64)>,
+    /// Chance of exploring before the colony scales it.
+    #[serde(default)]
+    pub explore_bias: f64,
+    /// Whether the digester puts off documents named by anomaly warnings.
+    #[serde(default = "heeds_warnings")]
+    pub heed_warnings: bool,
+    /// Digestion limits, if set on the agent rather than the colony.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digestion: Option<DigestionLimits>,
+    /// Fragments presented per tick at most, if limited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fragments_per_tick: Option<usize>,
+}
+
+fn heeds_warnings() -> bool {
+    true
 }
 
 /// Serializable state for a Synthesizer agent.
@@ -53,9 +74,24 @@ pub struct SynthesizerState {
     pub age_ticks: u64,
     pub idle_ticks: u64,
     pub insights_produced: u64,
+    /// Concepts of the agent's insights, with how many each was part of.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub concept_counts: Vec<(String, u64)>,
     pub sense_radius: f64,
     pub cooldown_ticks: u64,
     pub max_idle_ticks: u64,
+    /// Quorum weights per signal type; empty means the agent's defaults.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signal_weights: Vec<(SignalType, f64)>,
+    /// Chance of drifting while dormant before the colony scales it.
+    #[serde(default)]
+    pub explore_bias: f64,
+    /// Thresholds set explicitly on the agent; `None` means it follows
+    /// the colony's defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emerge: Option<EmergeConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_trigger_tick: Option<Tick>,
 }
 
 /// Serializable state for a Sentinel agent.
@@ -71,6 +107,17 @@ pub struct SentinelState {
     pub sense_radius: f64,
     pub max_idle_ticks: u64,
     pub scan_interval: u64,
+    /// Patrol strategy; the route starts over from its first stop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patrol: Option<PatrolStrategy>,
+    #[serde(default = "default_patrol_step")]
+    pub patrol_step: f64,
+    #[serde(default)]
+    pub anomaly_thresholds: AnomalyThresholds,
+}
+
+fn default_patrol_step() -> f64 {
+    2.0
 }
 
 /// Union of all serializable agent states.
@@ -156,6 +203,11 @@ mod tests {
             boundary_permeability: 0.5,
             max_idle_ticks: 30,
             sense_radius: 10.0,
+            signal_weights: Vec::new(),
+            explore_bias: 0.0,
+            heed_warnings: true,
+            digestion: None,
+            max_fragments_per_tick: None,
         };
 
         let json = serde_json::to_string(&state).unwrap();
@@ -180,6 +232,11 @@ mod tests {
             boundary_permeability: 0.0,
             max_idle_ticks: 30,
             sense_radius: 10.0,
+            signal_weights: Vec::new(),
+            explore_bias: 0.0,
+            heed_warnings: true,
+            digestion: None,
+            max_fragments_per_tick: None,
         };
 
         let agent = SerializedAgent::Digester(state);
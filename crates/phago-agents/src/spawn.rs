@@ -2,10 +2,17 @@
 //!
 //! When an agent dies, the colony can spawn a replacement using a policy.
 //! The FitnessSpawnPolicy creates a mutated offspring of the fittest
-//! living agent, implementing biological selection.
+//! living agent, implementing biological selection. With vocabulary
+//! inheritance (and always under the LamarckianSpawnPolicy) the offspring
+//! also starts out knowing the parent's most presented terms.
+//!
+//! A [`SpawnPolicySpec`] describes one of these policies as data, for a
+//! colony to breed replacements by itself (`ColonyConfig::spawn_policy`).
 
+use crate::digester::Digester;
 use crate::genome::AgentGenome;
 use phago_core::types::{AgentId, Position};
+use serde::{Deserialize, Serialize};
 
 /// Trait for spawn policies.
 pub trait SpawnPolicy {
@@ -20,6 +27,61 @@ pub trait SpawnPolicy {
         fittest_genome: Option<&AgentGenome>,
         fittest_position: Option<Position>,
     ) -> Option<(AgentGenome, Position)>;
+
+    /// Like [`on_death`](Self::on_death), also given the fittest agent's
+    /// exported vocabulary (the bytes of `export_vocabulary`, e.g. from
+    /// `Colony::agent_vocabulary`). Policies that pass learned vocabulary
+    /// on attach it to the offspring; by default none is inherited.
+    fn on_death_with_vocabulary(
+        &mut self,
+        dead_id: AgentId,
+        alive_count: usize,
+        fittest_genome: Option<&AgentGenome>,
+        fittest_position: Option<Position>,
+        _fittest_vocabulary: Option<&[u8]>,
+    ) -> Option<Offspring> {
+        self.on_death(dead_id, alive_count, fittest_genome, fittest_position)
+            .map(|(genome, position)| Offspring {
+                genome,
+                position,
+                vocabulary: None,
+            })
+    }
+}
+
+/// A new agent bred by a spawn policy.
+#[derive(Debug, Clone)]
+pub struct Offspring {
+    pub genome: AgentGenome,
+    pub position: Position,
+    /// Parent vocabulary to inherit and the fraction of its terms to keep.
+    pub vocabulary: Option<(Vec<u8>, f64)>,
+}
+
+impl Offspring {
+    /// A digester with the offspring's idle threshold, explore bias and
+    /// inherited vocabulary.
+    pub fn digester(&self) -> Digester {
+        self.equip(Digester::new(self.position))
+    }
+
+    /// Like [`digester`](Self::digester), with a deterministic ID (for
+    /// reproducible runs).
+    pub fn seeded_digester(&self, seed: u64) -> Digester {
+        self.equip(Digester::with_seed(self.position, seed))
+    }
+
+    fn equip(&self, digester: Digester) -> Digester {
+        let digester = digester
+            .with_max_idle(self.genome.max_idle)
+            .with_explore_bias(self.genome.explore_bias);
+        match &self.vocabulary {
+            Some((vocabulary, fraction)) => {
+                digester.with_inherited_vocabulary(vocabulary, *fraction)
+            }
+            None => digester,
+        }
+    }
 }
 
 /// Fitness-based spawn: create mutated offspring of the fittest agent.
@@ -28,6 +90,8 @@ pub struct FitnessSpawnPolicy {
     pub max_population: usize,
     /// Mutation rate for offspring genomes.
     pub mutation_rate: f64,
+    /// Fraction of the parent's vocabulary offspring inherit (0 = none).
+    pub inheritance_fraction: f64,
     /// Counter for seeding mutations.
     spawn_counter: u64,
 }
@@ -37,9 +101,16 @@ impl FitnessSpawnPolicy {
         Self {
             max_population,
             mutation_rate,
+            inheritance_fraction: 0.0,
             spawn_counter: 0,
         }
     }
+
+    /// Let offspring inherit the top `fraction` of the parent's vocabulary.
+    pub fn with_vocabulary_inheritance(mut self, fraction: f64) -> Self {
+        self.inheritance_fraction = fraction.clamp(0.0, 1.0);
+        self
+    }
 }
 
 impl SpawnPolicy for FitnessSpawnPolicy {
@@ -69,6 +140,76 @@ impl SpawnPolicy for FitnessSpawnPolicy {
 
         Some((offspring_genome, position))
     }
+
+    fn on_death_with_vocabulary(
+        &mut self,
+        dead_id: AgentId,
+        alive_count: usize,
+        fittest_genome: Option<&AgentGenome>,
+        fittest_position: Option<Position>,
+        fittest_vocabulary: Option<&[u8]>,
+    ) -> Option<Offspring> {
+        let (genome, position) =
+            self.on_death(dead_id, alive_count, fittest_genome, fittest_position)?;
+        let vocabulary = fittest_vocabulary
+            .filter(|_| self.inheritance_fraction > 0.0)
+            .map(|v| (v.to_vec(), self.inheritance_fraction));
+        Some(Offspring {
+            genome,
+            position,
+            vocabulary,
+        })
+    }
+}
+
+/// Lamarckian spawn: mutated offspring of the fittest agent that also
+/// inherit what it learned — the top fraction of its vocabulary.
+pub struct LamarckianSpawnPolicy {
+    inner: FitnessSpawnPolicy,
+}
+
+impl LamarckianSpawnPolicy {
+    pub fn new(max_population: usize, mutation_rate: f64, inheritance_fraction: f64) -> Self {
+        Self {
+            inner: FitnessSpawnPolicy::new(max_population, mutation_rate)
+                .with_vocabulary_inheritance(inheritance_fraction),
+        }
+    }
+
+    /// Fraction of the parent's vocabulary offspring inherit.
+    pub fn inheritance_fraction(&self) -> f64 {
+        self.inner.inheritance_fraction
+    }
+}
+
+impl SpawnPolicy for LamarckianSpawnPolicy {
+    fn on_death(
+        &mut self,
+        dead_id: AgentId,
+        alive_count: usize,
+        fittest_genome: Option<&AgentGenome>,
+        fittest_position: Option<Position>,
+    ) -> Option<(AgentGenome, Position)> {
+        self.inner
+            .on_death(dead_id, alive_count, fittest_genome, fittest_position)
+    }
+
+    fn on_death_with_vocabulary(
+        &mut self,
+        dead_id: AgentId,
+        alive_count: usize,
+        fittest_genome: Option<&AgentGenome>,
+        fittest_position: Option<Position>,
+        fittest_vocabulary: Option<&[u8]>,
+    ) -> Option<Offspring> {
+        self.inner.on_death_with_vocabulary(
+            dead_id,
+            alive_count,
+            fittest_genome,
+            fittest_position,
+            fittest_vocabulary,
+        )
+    }
 }
 
 /// No-spawn policy: never create new agents (static population).
@@ -123,9 +264,137 @@ impl SpawnPolicy for RandomSpawnPolicy {
     }
 }
 
+/// Which of the built-in spawn policies a [`SpawnPolicySpec`] describes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnPolicyKind {
+    /// [`FitnessSpawnPolicy`].
+    #[default]
+    Fitness,
+    /// [`LamarckianSpawnPolicy`].
+    Lamarckian,
+    /// [`RandomSpawnPolicy`].
+    Random,
+    /// [`NoSpawnPolicy`].
+    None,
+}
+
+impl SpawnPolicyKind {
+    /// Whether offspring descend from the fittest living agent, rather
+    /// than being made up.
+    pub fn breeds_from_parent(self) -> bool {
+        matches!(self, SpawnPolicyKind::Fitness | SpawnPolicyKind::Lamarckian)
+    }
+}
+
+/// A spawn policy described as data: which policy, its population cap and
+/// mutation rate, and the agent type offspring are built as.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpawnPolicySpec {
+    #[serde(default)]
+    pub policy: SpawnPolicyKind,
+    pub max_population: usize,
+    /// Mutation rate of offspring genomes (ignored by `random`, whose
+    /// genomes are random anyway).
+    #[serde(default)]
+    pub mutation_rate: f64,
+    /// Fraction of the parent's vocabulary offspring inherit; 0 means none,
+    /// except under `lamarckian`, which then passes on half.
+    #[serde(default)]
+    pub inheritance_fraction: f64,
+    /// Registry type offspring are built as (default: `"digester"`). Its
+    /// parameters must take a `position` and a `genome`.
+    #[serde(default = "default_agent_type")]
+    pub agent_type: String,
+    /// Seed offspring IDs are derived from, one after another (for
+    /// reproducible runs); random IDs without it. The agent type's
+    /// parameters must then take a `seed` as well.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+fn default_agent_type() -> String {
+    "digester".to_string()
+}
+
+impl SpawnPolicySpec {
+    /// Mutated offspring of the fittest digester, at most `max_population`
+    /// alive.
+    pub fn fitness(max_population: usize, mutation_rate: f64) -> Self {
+        Self {
+            policy: SpawnPolicyKind::Fitness,
+            max_population,
+            mutation_rate,
+            inheritance_fraction: 0.0,
+            agent_type: default_agent_type(),
+            seed: None,
+        }
+    }
+
+    /// Like [`fitness`](Self::fitness), with offspring inheriting the top
+    /// `inheritance_fraction` of the parent's vocabulary.
+    pub fn lamarckian(
+        max_population: usize,
+        mutation_rate: f64,
+        inheritance_fraction: f64,
+    ) -> Self {
+        Self {
+            policy: SpawnPolicyKind::Lamarckian,
+            inheritance_fraction,
+            ..Self::fitness(max_population, mutation_rate)
+        }
+    }
+
+    /// Digesters with random genomes, at most `max_population` alive.
+    pub fn random(max_population: usize) -> Self {
+        Self {
+            policy: SpawnPolicyKind::Random,
+            ..Self::fitness(max_population, 0.0)
+        }
+    }
+
+    /// Build offspring as `agent_type` instead of digesters.
+    pub fn with_agent_type(mut self, agent_type: impl Into<String>) -> Self {
+        self.agent_type = agent_type.into();
+        self
+    }
+
+    /// Derive offspring IDs from `seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// The policy this spec describes.
+    pub fn build(&self) -> Box<dyn SpawnPolicy> {
+        match self.policy {
+            SpawnPolicyKind::Fitness => Box::new(
+                FitnessSpawnPolicy::new(self.max_population, self.mutation_rate)
+                    .with_vocabulary_inheritance(self.inheritance_fraction),
+            ),
+            SpawnPolicyKind::Lamarckian => {
+                let fraction = if self.inheritance_fraction > 0.0 {
+                    self.inheritance_fraction
+                } else {
+                    0.5
+                };
+                Box::new(LamarckianSpawnPolicy::new(
+                    self.max_population,
+                    self.mutation_rate,
+                    fraction,
+                ))
+            }
+            SpawnPolicyKind::Random => Box::new(RandomSpawnPolicy::new(self.max_population)),
+            SpawnPolicyKind::None => Box::new(NoSpawnPolicy),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use phago_core::agent::Agent;
+    use phago_core::primitives::Digest;
 
     #[test]
     fn fitness_spawn_creates_offspring() {
@@ -151,6 +420,66 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn offspring_inherit_vocabulary_only_when_enabled() {
+        let mut parent = Digester::new(Position::new(0.0, 0.0));
+        parent.digest("cell membrane protein".to_string());
+        let vocabulary = parent.export_vocabulary().unwrap();
+        let genome = AgentGenome::default_genome();
+        let pos = Some(Position::new(0.0, 0.0));
+
+        let mut plain = FitnessSpawnPolicy::new(10, 0.1);
+        let offspring = plain
+            .on_death_with_vocabulary(AgentId::new(), 3, Some(&genome), pos, Some(&vocabulary))
+            .unwrap();
+        assert!(offspring.vocabulary.is_none());
+        assert_eq!(offspring.digester().vocabulary_size(), 0);
+
+        let mut lamarckian = LamarckianSpawnPolicy::new(10, 0.1, 1.0);
+        let offspring = lamarckian
+            .on_death_with_vocabulary(AgentId::new(), 3, Some(&genome), pos, Some(&vocabulary))
+            .unwrap();
+        assert_eq!(offspring.digester().vocabulary_size(), 3);
+
+        // Other policies spawn without vocabulary
+        let offspring = RandomSpawnPolicy::new(10)
+            .on_death_with_vocabulary(AgentId::new(), 3, None, None, Some(&vocabulary))
+            .unwrap();
+        assert!(offspring.vocabulary.is_none());
+    }
+
+    #[test]
+    fn specs_build_their_policies() {
+        let spec: SpawnPolicySpec =
+            serde_json::from_str(r#"{"policy": "lamarckian", "max_population": 4}"#).unwrap();
+        assert_eq!(spec.agent_type, "digester");
+        assert_eq!(spec, SpawnPolicySpec::lamarckian(4, 0.0, 0.0));
+
+        let genome = AgentGenome::default_genome();
+        let pos = Some(Position::new(0.0, 0.0));
+        let mut parent = Digester::new(Position::new(0.0, 0.0));
+        parent.digest("cell membrane protein".to_string());
+        let vocabulary = parent.export_vocabulary().unwrap();
+        let offspring = spec
+            .build()
+            .on_death_with_vocabulary(AgentId::new(), 3, Some(&genome), pos, Some(&vocabulary))
+            .unwrap();
+        assert_eq!(offspring.vocabulary.map(|(_, f)| f), Some(0.5));
+        assert!(spec
+            .build()
+            .on_death(AgentId::new(), 4, Some(&genome), pos)
+            .is_none());
+
+        let none = SpawnPolicySpec {
+            policy: SpawnPolicyKind::None,
+            ..SpawnPolicySpec::random(10)
+        };
+        assert!(none
+            .build()
+            .on_death(AgentId::new(), 1, Some(&genome), pos)
+            .is_none());
+    }
+
     #[test]
     fn no_spawn_never_spawns() {
         let mut policy = NoSpawnPolicy;
@@ -13,17 +13,15 @@
 //! bacteria cannot perform these behaviors — they are emergent properties
 //! of the collective.
 
-use phago_core::agent::Agent;
+use phago_core::agent::{agent_roll, top_terms, Agent};
 use phago_core::primitives::symbiose::AgentProfile;
-use phago_core::primitives::{Apoptose, Digest, Emerge, Sense};
+use phago_core::primitives::{Apoptose, Digest, Emerge, EmergeConfig, QuorumStatus, Sense};
 use phago_core::substrate::Substrate;
 use phago_core::types::*;
+use std::collections::HashMap;
 
-/// Configuration for the Synthesizer.
-const QUORUM_THRESHOLD: f64 = 3.0;
+/// A concept must have been presented this often to count as a bridge.
 const MIN_BRIDGE_ACCESS: u64 = 2;
-const MIN_CLUSTER_SIZE: usize = 3;
-const MIN_CLUSTER_WEIGHT: f64 = 0.15;
 
 /// State machine for the Synthesizer.
 #[derive(Debug, Clone, PartialEq)]
@@ -43,6 +41,8 @@ enum SynthesizerState {
 pub struct InsightData {
     pub label: String,
     pub insight_type: InsightType,
+    /// The concept this insight is centered on.
+    pub anchor: String,
     pub related_concepts: Vec<String>,
 }
 
@@ -63,16 +63,43 @@ pub struct Synthesizer {
 
     // Emerge tracking
     insights_produced: u64,
+    /// How many of its insights each concept was anchor or member of.
+    concept_counts: HashMap<String, u64>,
 
     // Digestion (required by Agent trait but Synthesizer digests insights, not documents)
     engulfed: Option<String>,
     fragments: Vec<String>,
 
+    /// Tick at which quorum was last reached.
+    last_trigger_tick: Option<Tick>,
+
     // Configuration
     sense_radius: f64,
-    cooldown_ticks: u64,
+    emerge: EmergeConfig,
+    /// Set through the builder, so colony defaults do not override it.
+    emerge_configured: bool,
     max_idle_ticks: u64,
     idle_ticks: u64,
+    /// How much each nearby signal type counts toward quorum.
+    signal_weights: HashMap<SignalType, f64>,
+    /// Chance of drifting to survey another region while dormant, before
+    /// the colony's exploitation scales it down.
+    explore_bias: f64,
+}
+
+/// By default every built-in signal type counts equally toward quorum.
+fn default_signal_weights() -> HashMap<SignalType, f64> {
+    [
+        SignalType::Input,
+        SignalType::Presence,
+        SignalType::Quorum,
+        SignalType::Anomaly,
+        SignalType::Insight,
+        SignalType::Capability,
+    ]
+    .into_iter()
+    .map(|t| (t, 1.0))
+    .collect()
 }
 
 impl Synthesizer {
@@ -83,12 +110,17 @@ impl Synthesizer {
             age_ticks: 0,
             state: SynthesizerState::Dormant,
             insights_produced: 0,
+            concept_counts: HashMap::new(),
             engulfed: None,
             fragments: Vec::new(),
+            last_trigger_tick: None,
             sense_radius: 50.0, // Large radius — synthesizers survey the whole substrate
-            cooldown_ticks: 10,
+            emerge: EmergeConfig::default(),
+            emerge_configured: false,
             max_idle_ticks: 100, // Patient — waits longer than digesters
             idle_ticks: 0,
+            signal_weights: default_signal_weights(),
+            explore_bias: 0.0,
         }
     }
 
@@ -100,15 +132,60 @@ impl Synthesizer {
             age_ticks: 0,
             state: SynthesizerState::Dormant,
             insights_produced: 0,
+            concept_counts: HashMap::new(),
             engulfed: None,
             fragments: Vec::new(),
+            last_trigger_tick: None,
             sense_radius: 50.0,
-            cooldown_ticks: 10,
+            emerge: EmergeConfig::default(),
+            emerge_configured: false,
             max_idle_ticks: 100,
             idle_ticks: 0,
+            signal_weights: default_signal_weights(),
+            explore_bias: 0.0,
         }
     }
 
+    /// Set the idle ticks after which the synthesizer dies (default: 100).
+    pub fn with_max_idle(mut self, max_idle: u64) -> Self {
+        self.max_idle_ticks = max_idle;
+        self
+    }
+
+    /// Replace the per-type weights used when counting nearby signals
+    /// toward quorum. Types left out do not count.
+    pub fn with_signal_weights(mut self, weights: HashMap<SignalType, f64>) -> Self {
+        self.signal_weights = weights;
+        self
+    }
+
+    /// Set the quorum weight of a single signal type.
+    pub fn with_signal_weight(mut self, signal_type: SignalType, weight: f64) -> Self {
+        self.signal_weights.insert(signal_type, weight);
+        self
+    }
+
+    /// Chance per dormant tick of drifting a fifth of the sensing radius
+    /// in a random direction (default: 0.0, stay put). A colony with an
+    /// exploration schedule scales it down as it matures.
+    pub fn with_explore_bias(mut self, bias: f64) -> Self {
+        self.explore_bias = bias.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set quorum and insight thresholds. Colony-wide defaults no longer
+    /// apply to this synthesizer.
+    pub fn with_emerge_config(mut self, config: EmergeConfig) -> Self {
+        self.emerge = config;
+        self.emerge_configured = true;
+        self
+    }
+
+    /// Current quorum and insight thresholds.
+    pub fn emerge_config(&self) -> &EmergeConfig {
+        &self.emerge
+    }
+
     /// Total insights produced in lifetime.
     pub fn insights_produced(&self) -> u64 {
         self.insights_produced
@@ -131,7 +208,7 @@ impl Synthesizer {
                 if node.access_count >= MIN_BRIDGE_ACCESS && node.node_type == NodeType::Concept {
                     // Check if this bridge hasn't been reported yet
                     let existing_insights = substrate.all_nodes().iter().any(|nid| {
-                        substrate.get_node(nid).map_or(false, |n| {
+                        substrate.get_node(nid).is_some_and(|n| {
                             n.node_type == NodeType::Insight && n.label.contains(&node.label)
                         })
                     });
@@ -145,6 +222,11 @@ impl Synthesizer {
                             .take(5)
                             .collect();
 
+                        // A bridge with nothing left to connect is not a bridge
+                        if connected.is_empty() {
+                            continue;
+                        }
+
                         insights.push(InsightData {
                             label: format!(
                                 "Bridge: '{}' connects {} document contexts",
@@ -153,6 +235,7 @@ impl Synthesizer {
                             insight_type: InsightType::BridgeConcept {
                                 access_count: node.access_count,
                             },
+                            anchor: node.label.clone(),
                             related_concepts: connected,
                         });
                     }
@@ -176,7 +259,7 @@ impl Synthesizer {
                 let strong_neighbors: Vec<(String, f64)> = neighbors
                     .iter()
                     .filter_map(|(nid, edge)| {
-                        if edge.weight >= MIN_CLUSTER_WEIGHT {
+                        if edge.weight >= self.emerge.bridge_min_weight {
                             substrate
                                 .get_node(nid)
                                 .map(|n| (n.label.clone(), edge.weight))
@@ -186,7 +269,7 @@ impl Synthesizer {
                     })
                     .collect();
 
-                if strong_neighbors.len() >= MIN_CLUSTER_SIZE {
+                if strong_neighbors.len() >= self.emerge.min_cluster_size.max(1) {
                     let mut cluster_labels: Vec<String> = strong_neighbors
                         .iter()
                         .map(|(label, _)| label.clone())
@@ -209,7 +292,7 @@ impl Synthesizer {
                         // Check no existing insight for this cluster
                         let cluster_key = format!("Cluster: {}", node.label);
                         let exists = substrate.all_nodes().iter().any(|nid| {
-                            substrate.get_node(nid).map_or(false, |n| {
+                            substrate.get_node(nid).is_some_and(|n| {
                                 n.node_type == NodeType::Insight && n.label == cluster_key
                             })
                         });
@@ -221,6 +304,7 @@ impl Synthesizer {
                                     size: strong_neighbors.len(),
                                     avg_weight,
                                 },
+                                anchor: node.label.clone(),
                                 related_concepts: cluster_labels.clone(),
                             });
                             reported_clusters.push(cluster_labels);
@@ -276,6 +360,7 @@ impl Apoptose for Synthesizer {
             useful_outputs: self.insights_produced,
             final_fragments: Vec::new(),
             cause: DeathCause::SelfAssessed(self.self_assess()),
+            had_pending_work: false,
         }
     }
 }
@@ -306,17 +391,25 @@ impl Emerge for Synthesizer {
     fn signal_density(&self, substrate: &dyn Substrate) -> f64 {
         // Count digestion traces in sensing radius — this is our quorum signal
         let nearby_signals = substrate.signals_near(&self.position, self.sense_radius);
-        let trace_count = nearby_signals.len();
+        let trace_count: f64 = nearby_signals
+            .iter()
+            .map(|s| {
+                self.signal_weights
+                    .get(&s.signal_type)
+                    .copied()
+                    .unwrap_or(0.0)
+            })
+            .sum();
 
         // Also count concept nodes — more concepts = more material to synthesize
         let node_count = substrate.node_count();
 
         // Quorum is based on both agent activity (traces) and knowledge density (nodes)
-        (trace_count as f64) * 0.3 + (node_count as f64) * 0.1
+        trace_count * 0.3 + (node_count as f64) * 0.1
     }
 
     fn quorum_threshold(&self) -> f64 {
-        QUORUM_THRESHOLD
+        self.emerge.quorum_threshold
     }
 
     fn emergent_behavior(&self) -> Option<Vec<InsightData>> {
@@ -363,6 +456,7 @@ impl Agent for Synthesizer {
                 if density >= self.quorum_threshold() {
                     self.state = SynthesizerState::Analyzing;
                     self.idle_ticks = 0;
+                    self.last_trigger_tick = Some(substrate.current_tick());
                     // Emit quorum signal to alert other agents
                     AgentAction::Emit(Signal::new(
                         SignalType::Quorum,
@@ -373,7 +467,17 @@ impl Agent for Synthesizer {
                     ))
                 } else {
                     self.idle_ticks += 1;
-                    AgentAction::Idle
+                    let explore = substrate.ambient().explore_bias(self.explore_bias);
+                    if agent_roll(&self.id, self.age_ticks) < explore {
+                        let angle = agent_roll(&self.id, !self.age_ticks) * std::f64::consts::TAU;
+                        let step = self.sense_radius / 5.0;
+                        AgentAction::Move(Position::new(
+                            self.position.x + angle.cos() * step,
+                            self.position.y + angle.sin() * step,
+                        ))
+                    } else {
+                        AgentAction::Idle
+                    }
                 }
             }
 
@@ -406,6 +510,13 @@ impl Agent for Synthesizer {
                 } else {
                     self.state = SynthesizerState::Presenting(insights.clone());
                     self.insights_produced += insights.len() as u64;
+                    for insight in &insights {
+                        for concept in
+                            std::iter::once(&insight.anchor).chain(&insight.related_concepts)
+                        {
+                            *self.concept_counts.entry(concept.clone()).or_insert(0) += 1;
+                        }
+                    }
 
                     // Present insights as fragment presentations
                     let presentations: Vec<FragmentPresentation> = insights
@@ -422,11 +533,19 @@ impl Agent for Synthesizer {
                                     )
                                 }
                             };
+                            // Provenance: anchor first, then the related concepts
+                            let mut derived_from = vec![insight.anchor.clone()];
+                            derived_from.extend(insight.related_concepts.iter().cloned());
                             FragmentPresentation {
                                 label,
                                 source_document: DocumentId::new(),
                                 position: self.position,
                                 node_type: NodeType::Insight,
+                                derived_from,
+                                passages: Vec::new(),
+                                access_weight: 1,
+                                score: None,
+                                namespace: None,
                             }
                         })
                         .collect();
@@ -437,7 +556,7 @@ impl Agent for Synthesizer {
 
             SynthesizerState::Presenting(_insights) => {
                 // Emit insight signal and enter cooldown
-                self.state = SynthesizerState::Cooldown(self.cooldown_ticks);
+                self.state = SynthesizerState::Cooldown(self.emerge.cooldown_ticks);
                 AgentAction::Emit(Signal::new(
                     SignalType::Insight,
                     1.0,
@@ -471,6 +590,33 @@ impl Agent for Synthesizer {
             health: self.self_assess(),
         }
     }
+
+    fn vocabulary_size(&self) -> usize {
+        self.concept_counts.len()
+    }
+
+    fn vocabulary_snapshot(&self, top_n: usize) -> Vec<(String, f64)> {
+        let weights = self.concept_counts.iter();
+        top_terms(weights.map(|(c, n)| (c.as_str(), *n as f64)), top_n)
+    }
+
+    fn adopt_emerge_defaults(&mut self, config: &EmergeConfig) {
+        if !self.emerge_configured {
+            self.emerge = config.clone();
+        }
+    }
+
+    fn quorum_status(&self, substrate: &dyn Substrate) -> Option<QuorumStatus> {
+        Some(QuorumStatus {
+            center: self.position,
+            radius: self.sense_radius,
+            density: self.signal_density(substrate),
+            threshold: self.quorum_threshold(),
+            cooling_down: matches!(self.state, SynthesizerState::Cooldown(_)),
+            last_trigger_tick: self.last_trigger_tick,
+            insights_produced: self.insights_produced,
+        })
+    }
 }
 
 // --- Serialization ---
@@ -487,9 +633,22 @@ impl SerializableAgent for Synthesizer {
             age_ticks: self.age_ticks,
             idle_ticks: self.idle_ticks,
             insights_produced: self.insights_produced,
+            concept_counts: self
+                .concept_counts
+                .iter()
+                .map(|(c, n)| (c.clone(), *n))
+                .collect(),
             sense_radius: self.sense_radius,
-            cooldown_ticks: self.cooldown_ticks,
+            cooldown_ticks: self.emerge.cooldown_ticks,
             max_idle_ticks: self.max_idle_ticks,
+            signal_weights: self
+                .signal_weights
+                .iter()
+                .map(|(t, w)| (t.clone(), *w))
+                .collect(),
+            explore_bias: self.explore_bias,
+            emerge: self.emerge_configured.then(|| self.emerge.clone()),
+            last_trigger_tick: self.last_trigger_tick,
         })
     }
 
@@ -501,12 +660,24 @@ impl SerializableAgent for Synthesizer {
                 age_ticks: s.age_ticks,
                 state: SynthesizerState::Dormant,
                 insights_produced: s.insights_produced,
+                concept_counts: s.concept_counts.iter().cloned().collect(),
                 engulfed: None,
                 fragments: Vec::new(),
+                last_trigger_tick: s.last_trigger_tick,
                 sense_radius: s.sense_radius,
-                cooldown_ticks: s.cooldown_ticks,
+                emerge: s.emerge.clone().unwrap_or_else(|| EmergeConfig {
+                    cooldown_ticks: s.cooldown_ticks,
+                    ..Default::default()
+                }),
+                emerge_configured: s.emerge.is_some(),
                 max_idle_ticks: s.max_idle_ticks,
                 idle_ticks: s.idle_ticks,
+                signal_weights: if s.signal_weights.is_empty() {
+                    default_signal_weights()
+                } else {
+                    s.signal_weights.iter().cloned().collect()
+                },
+                explore_bias: s.explore_bias,
             }),
             _ => None,
         }
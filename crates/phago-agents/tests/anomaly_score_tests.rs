@@ -0,0 +1,142 @@
+//! Sentinel anomaly scores — graded novelty instead of binary flags.
+
+use phago_agents::sentinel::Sentinel;
+use phago_core::agent::Agent;
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use phago_runtime::substrate_impl::SubstrateImpl;
+use std::collections::{BTreeMap, HashMap};
+
+const BIOLOGY: [&str; 6] = [
+    "cell", "membrane", "protein", "enzyme", "ribosome", "nucleus",
+];
+const ASTRONOMY: [&str; 5] = ["quasar", "nebula", "pulsar", "galaxy", "comet"];
+
+fn add_concept(substrate: &mut SubstrateImpl, label: &str, access_count: u64) {
+    substrate.add_node(NodeData {
+        id: NodeId::new(),
+        label: label.to_string(),
+        node_type: NodeType::Concept,
+        position: Position::new(1.0, 1.0),
+        access_count,
+        created_tick: 0,
+        last_accessed_tick: 0,
+        tags: BTreeMap::new(),
+    });
+}
+
+fn add_digested(substrate: &mut SubstrateImpl, title: &str, content: &str) -> DocumentId {
+    let id = DocumentId::new();
+    substrate.add_document(Document {
+        id,
+        title: title.to_string(),
+        content: content.to_string(),
+        position: Position::new(2.0, 2.0),
+        digested: true,
+        tags: Vec::new(),
+        version: 1,
+    });
+    id
+}
+
+/// A sentinel matured on a biology graph, which then sees astronomy
+/// concepts appear.
+fn biology_trained(sentinel: &mut Sentinel) -> SubstrateImpl {
+    let mut substrate = SubstrateImpl::new();
+    for label in BIOLOGY {
+        add_concept(&mut substrate, label, 5);
+    }
+    // Maturation takes ten ticks
+    for _ in 0..10 {
+        sentinel.tick(&substrate);
+    }
+    for label in ASTRONOMY {
+        add_concept(&mut substrate, label, 1);
+    }
+    substrate
+}
+
+/// Run the sentinel and collect the label and score of what it presented
+/// about each document.
+fn presented(
+    sentinel: &mut Sentinel,
+    substrate: &SubstrateImpl,
+) -> HashMap<DocumentId, Vec<(String, f64)>> {
+    let mut found: HashMap<DocumentId, Vec<(String, f64)>> = HashMap::new();
+    for _ in 0..30 {
+        if let AgentAction::PresentFragments(fragments) = sentinel.tick(substrate) {
+            for f in fragments {
+                let score = f.score.expect("sentinels score anomalies");
+                found
+                    .entry(f.source_document)
+                    .or_default()
+                    .push((f.label, score));
+            }
+        }
+    }
+    found
+}
+
+#[test]
+fn graduated_weirdness_scores_monotonically() {
+    let mut sentinel = Sentinel::new(Position::new(0.0, 0.0)).with_anomaly_thresholds(0.05, 0.9);
+    let mut substrate = biology_trained(&mut sentinel);
+    let mild = add_digested(
+        &mut substrate,
+        "mild",
+        "The cell membrane and nucleus hold a protein enzyme, like a comet.",
+    );
+    let odd = add_digested(
+        &mut substrate,
+        "odd",
+        "The cell membrane orbits a quasar near a nebula and a pulsar.",
+    );
+    let alien = add_digested(
+        &mut substrate,
+        "alien",
+        "A quasar lit the nebula beside a pulsar, a comet and a galaxy.",
+    );
+
+    let found = presented(&mut sentinel, &substrate);
+    let scores: Vec<f64> = [mild, odd, alien]
+        .iter()
+        .map(|doc| {
+            assert_eq!(found[doc].len(), 1, "{:?}", found[doc]);
+            found[doc][0].1
+        })
+        .collect();
+
+    assert!(
+        scores[0] < scores[1] && scores[1] < scores[2],
+        "{:?}",
+        scores
+    );
+    assert!(scores[0] > 0.0);
+    assert_eq!(scores[2], 1.0);
+}
+
+#[test]
+fn thresholds_ignore_review_or_flag_documents() {
+    let mut sentinel = Sentinel::new(Position::new(0.0, 0.0)).with_anomaly_thresholds(0.5, 0.9);
+    let mut substrate = biology_trained(&mut sentinel);
+    let familiar = add_digested(
+        &mut substrate,
+        "familiar",
+        "The ribosome makes a protein for the cell membrane.",
+    );
+    let odd = add_digested(
+        &mut substrate,
+        "odd",
+        "The cell membrane orbits a quasar near a nebula and a pulsar.",
+    );
+    let alien = add_digested(
+        &mut substrate,
+        "alien",
+        "A quasar lit the nebula beside a pulsar, a comet and a galaxy.",
+    );
+
+    let found = presented(&mut sentinel, &substrate);
+    assert!(!found.contains_key(&familiar), "{:?}", found);
+    assert!(found[&odd][0].0.starts_with("[REVIEW] Document 'odd'"));
+    assert!(found[&alien][0].0.starts_with("[ANOMALY] Document 'alien'"));
+}
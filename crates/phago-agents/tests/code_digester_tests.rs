@@ -0,0 +1,136 @@
+//! CodeDigester integration tests.
+//!
+//! Definitions of the same name in different files must stay different
+//! concepts, found together under the plain name; files must only be wired
+//! to each other through what they import, and prose stays unqualified.
+
+use phago_agents::code_digester::CodeDigester;
+use phago_core::types::*;
+use phago_runtime::colony::Colony;
+use std::collections::HashSet;
+
+const CELL: &str = "\
+pub struct Cell {}
+
+impl Cell {
+    pub fn new() -> Self { Cell {} }
+    pub fn divide(&self) {}
+}
+";
+
+const MEMBRANE: &str = "\
+use crate::cell::Cell;
+
+pub struct Membrane {}
+
+impl Membrane {
+    pub fn new() -> Self { Membrane {} }
+    pub fn enclose(&self, cell: Cell) {}
+}
+";
+
+/// A colony that has digested `files`, one after the other.
+fn digested(files: &[(&str, &str)]) -> Colony {
+    let mut colony = Colony::new();
+    colony.spawn(Box::new(
+        CodeDigester::new(Position::new(0.0, 0.0)).with_max_idle(100),
+    ));
+    for (i, (path, source)) in files.iter().enumerate() {
+        colony.ingest_document(path, source, Position::new(0.0, 0.0));
+        for _ in 0..20 {
+            colony.tick();
+            if colony.stats().documents_digested > i {
+                break;
+            }
+        }
+        assert_eq!(colony.stats().documents_digested, i + 1);
+        // Counted as digested once engulfed; presented after
+        colony.run(3);
+    }
+    colony
+}
+
+fn labels(colony: &Colony, ids: &[NodeId]) -> HashSet<String> {
+    let graph = colony.substrate().graph();
+    ids.iter()
+        .filter_map(|id| graph.get_node(id).map(|n| n.label.clone()))
+        .collect()
+}
+
+fn node(colony: &Colony, label: &str) -> NodeId {
+    let graph = colony.substrate().graph();
+    let ids = graph.find_nodes_by_exact_label(label);
+    assert!(!ids.is_empty(), "no node {label}");
+    ids[0]
+}
+
+fn neighbors(colony: &Colony, label: &str) -> HashSet<String> {
+    let graph = colony.substrate().graph();
+    let ids: Vec<NodeId> = graph
+        .neighbors(&node(colony, label))
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    labels(colony, &ids)
+}
+
+#[test]
+fn definitions_are_qualified_by_their_file() {
+    let colony = digested(&[("src/cell.rs", CELL), ("src/membrane.rs", MEMBRANE)]);
+    let graph = colony.substrate().graph();
+
+    // Two `new`s, both found under the plain name
+    let news = graph.find_nodes_by_exact_label("new");
+    assert_eq!(
+        labels(&colony, &news),
+        HashSet::from([
+            "src/cell.rs::new".to_string(),
+            "src/membrane.rs::new".to_string()
+        ])
+    );
+
+    // Wired within the file, never to another file's definitions
+    let divide = neighbors(&colony, "src/cell.rs::divide");
+    assert!(divide.contains("src/cell.rs::new"), "{divide:?}");
+    assert!(
+        divide.iter().all(|l| !l.starts_with("src/membrane.rs")),
+        "{divide:?}"
+    );
+}
+
+#[test]
+fn prose_stays_unqualified() {
+    let colony = digested(&[
+        ("src/cell.rs", CELL),
+        (
+            "notes.txt",
+            "The membrane encloses the cell before it divides.",
+        ),
+    ]);
+    let graph = colony.substrate().graph();
+    let prose: Vec<NodeId> = graph
+        .all_nodes()
+        .into_iter()
+        .filter(|id| !graph.get_node(id).unwrap().label.contains("::"))
+        .collect();
+    assert!(labels(&colony, &prose).contains("encloses"));
+    // Mentions of what the code defines reinforce the definitions
+    assert!(graph.find_nodes_by_exact_label("cell").len() == 1);
+    assert!(neighbors(&colony, "encloses").contains("src/cell.rs::cell"));
+}
+
+#[test]
+fn imports_link_files_to_the_definitions_they_use() {
+    // Defined before it is imported: the import is the definition
+    let colony = digested(&[("src/cell.rs", CELL), ("src/membrane.rs", MEMBRANE)]);
+    assert!(neighbors(&colony, "src/cell.rs::cell").contains("src/membrane.rs::membrane"));
+
+    // Imported before it is defined: the definition is linked to the import
+    let colony = digested(&[("src/membrane.rs", MEMBRANE), ("src/cell.rs", CELL)]);
+    let cell = neighbors(&colony, "src/cell.rs::cell");
+    let import = cell
+        .iter()
+        .find(|l| !l.contains("::"))
+        .unwrap_or_else(|| panic!("no unqualified import among {cell:?}"));
+    assert!(neighbors(&colony, import).contains("src/membrane.rs::membrane"));
+}
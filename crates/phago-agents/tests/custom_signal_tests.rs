@@ -0,0 +1,203 @@
+//! Custom signal kinds — agents outside phago-agents emit and sense their
+//! own signals through the colony.
+
+use phago_core::agent::Agent;
+use phago_core::primitives::{Apoptose, Digest, Sense};
+use phago_core::signal::{compute_gradient_in, SignalKindInfo, SignalKinds};
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use phago_runtime::colony::Colony;
+
+fn review_request() -> SignalType {
+    SignalType::custom("review_request")
+}
+
+/// Either asks for review every tick, or follows review requests and
+/// nothing else.
+struct Reviewer {
+    id: AgentId,
+    position: Position,
+    requester: bool,
+    age: Tick,
+}
+
+impl Reviewer {
+    fn new(position: Position, requester: bool) -> Self {
+        Self {
+            id: AgentId::new(),
+            position,
+            requester,
+            age: 0,
+        }
+    }
+}
+
+impl Digest for Reviewer {
+    type Input = String;
+    type Fragment = String;
+    type Presentation = Vec<String>;
+
+    fn engulf(&mut self, _input: String) -> DigestionResult {
+        DigestionResult::Engulfed
+    }
+
+    fn lyse(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn present(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+impl Apoptose for Reviewer {
+    fn self_assess(&self) -> CellHealth {
+        CellHealth::Healthy
+    }
+
+    fn prepare_death_signal(&self) -> DeathSignal {
+        DeathSignal {
+            agent_id: self.id,
+            total_ticks: self.age,
+            useful_outputs: 0,
+            final_fragments: Vec::new(),
+            cause: DeathCause::SelfAssessed(CellHealth::Redundant),
+            had_pending_work: false,
+        }
+    }
+}
+
+impl Sense for Reviewer {
+    fn sense_radius(&self) -> f64 {
+        20.0
+    }
+
+    fn sense_position(&self) -> Position {
+        self.position
+    }
+
+    fn subscription(&self) -> SignalKinds {
+        SignalKinds::only([review_request()])
+    }
+
+    fn gradient(&self, substrate: &dyn Substrate) -> Vec<Gradient> {
+        let signals = self.sense(substrate);
+        let signals: Vec<&Signal> = signals.iter().collect();
+        compute_gradient_in(&signals, &self.position, &substrate.geometry())
+            .into_iter()
+            .collect()
+    }
+
+    fn orient(&self, gradients: &[Gradient]) -> Orientation {
+        match gradients.first() {
+            Some(g) => Orientation::Toward(Position::new(
+                self.position.x + g.direction.x,
+                self.position.y + g.direction.y,
+            )),
+            None => Orientation::Stay,
+        }
+    }
+}
+
+impl Agent for Reviewer {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn agent_type(&self) -> &str {
+        "reviewer"
+    }
+
+    fn tick(&mut self, substrate: &dyn Substrate) -> AgentAction {
+        self.age += 1;
+        if self.requester {
+            return AgentAction::Emit(Signal::new(
+                review_request(),
+                1.0,
+                self.position,
+                self.id,
+                substrate.current_tick(),
+            ));
+        }
+        match self.orient(&self.gradient(substrate)) {
+            Orientation::Toward(to) => AgentAction::Move(to),
+            _ => AgentAction::Idle,
+        }
+    }
+
+    fn age(&self) -> Tick {
+        self.age
+    }
+}
+
+#[test]
+fn custom_signals_reach_subscribed_agents_through_the_colony() {
+    let mut colony = Colony::new();
+    colony.register_signal_kind(
+        review_request(),
+        SignalKindInfo::new("Request for review").with_decay_rate(0.01),
+    );
+    // A document to the west pulls with an Input signal the listener ignores
+    colony.ingest_document(
+        "Membranes",
+        "cell membrane protein transport",
+        Position::new(-5.0, 0.0),
+    );
+    colony.spawn(Box::new(Reviewer::new(Position::new(5.0, 0.0), true)));
+    let listener = colony.spawn(Box::new(Reviewer::new(Position::new(0.0, 0.0), false)));
+
+    colony.run(3);
+
+    let stats = colony.stats();
+    assert!(stats.signals_by_kind["review_request"] > 0);
+    assert!(stats.signals_by_kind.contains_key("input"));
+    assert_eq!(
+        colony
+            .substrate()
+            .signal_kind_info(&review_request())
+            .map(|info| info.label.as_str()),
+        Some("Request for review")
+    );
+
+    // The listener follows the review requests, not the document
+    let listener = colony.agents().iter().find(|a| a.id() == listener).unwrap();
+    assert!(listener.position().x > 1.0, "{:?}", listener.position());
+
+    // Registered decay applies to the custom kind only
+    let intensities: Vec<(SignalType, f64)> = colony
+        .substrate()
+        .all_signals()
+        .iter()
+        .map(|s| (s.signal_type.clone(), s.intensity))
+        .collect();
+    for (kind, intensity) in intensities {
+        if kind == review_request() {
+            assert!(intensity > 0.95, "{intensity}");
+        } else {
+            assert!(intensity < 0.95, "{kind}: {intensity}");
+        }
+    }
+}
+
+#[test]
+fn legacy_signal_names_deserialize_unchanged() {
+    for kind in SignalType::BUILT_IN {
+        let json = serde_json::to_string(&kind).unwrap();
+        assert_eq!(json, format!("\"{kind:?}\""));
+        assert_eq!(serde_json::from_str::<SignalType>(&json).unwrap(), kind);
+        assert_eq!(SignalType::from_name(kind.name()), kind);
+    }
+    let custom: SignalType = serde_json::from_str(r#"{"Custom":"review_request"}"#).unwrap();
+    assert_eq!(custom, review_request());
+    assert_eq!(SignalType::from_name("review_request"), review_request());
+    assert!(SignalKinds::only([review_request()]).contains(&custom));
+    assert!(!SignalKinds::only([review_request()]).contains(&SignalType::Anomaly));
+}
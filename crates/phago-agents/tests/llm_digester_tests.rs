@@ -0,0 +1,104 @@
+//! LlmDigester integration tests.
+//!
+//! Relationships an LLM extracts must survive the trip through the colony
+//! and come out of the triple export with their relation, not as
+//! `related_to`; a failing backend must still leave the document digested.
+
+#![cfg(feature = "llm")]
+
+use phago_agents::llm_digester::LlmDigester;
+use phago_core::types::*;
+use phago_llm::{Concept, ExtractionResponse, MockBackend, RelationType, Relationship};
+use phago_runtime::colony::Colony;
+use phago_runtime::export::export_triples;
+use std::sync::Arc;
+
+const TEXT: &str =
+    "Mitochondria produce ATP inside the cell. The mitochondria are part of the cell.";
+
+fn canned_backend() -> MockBackend {
+    MockBackend::new().with_extraction(ExtractionResponse {
+        concepts: vec![
+            Concept::new("mitochondria").with_confidence(0.95),
+            Concept::new("ATP").with_confidence(0.9),
+            Concept::new("cell").with_confidence(0.3),
+        ],
+        relationships: vec![
+            Relationship::new("mitochondria", "ATP", "produces").with_type(RelationType::Produces),
+            Relationship::new("mitochondria", "cell", "part of").with_type(RelationType::PartOf),
+        ],
+        raw_response: None,
+        tokens_used: None,
+    })
+}
+
+fn colony_digesting_with(backend: MockBackend) -> Colony {
+    let mut colony = Colony::new();
+    colony.ingest_document("Cell biology", TEXT, Position::new(0.0, 0.0));
+    colony.spawn(Box::new(
+        LlmDigester::new(Position::new(0.0, 0.0), Arc::new(backend)).with_max_idle(100),
+    ));
+    colony
+}
+
+fn node_access_count(colony: &Colony, label: &str) -> u64 {
+    let graph = colony.substrate().graph();
+    let id = graph.find_nodes_by_exact_label(label)[0];
+    graph.get_node(&id).unwrap().access_count
+}
+
+#[test]
+fn extracted_relations_appear_in_exported_triples() {
+    let mut colony = colony_digesting_with(canned_backend());
+    colony.run(30);
+
+    let triples = export_triples(&colony);
+    let find = |predicate: &str| {
+        triples
+            .iter()
+            .find(|t| t.predicate == predicate)
+            .unwrap_or_else(|| panic!("no {predicate} triple in {triples:#?}"))
+    };
+
+    let produces = find("produces");
+    assert_eq!(
+        (produces.subject.as_str(), produces.object.as_str()),
+        ("mitochondria", "atp")
+    );
+    let part_of = find("part_of");
+    assert_eq!(
+        (part_of.subject.as_str(), part_of.object.as_str()),
+        ("mitochondria", "cell")
+    );
+    // Relations strengthen the co-occurrence edges they label
+    assert!(produces.weight > 0.1, "{produces:?}");
+
+    // Concepts were the extracted ones, weighted by confidence
+    assert!(colony
+        .substrate()
+        .graph()
+        .find_nodes_by_exact_label("inside")
+        .is_empty());
+    // Dissolution reinforces both alike; presentation gave them 3 and 1
+    assert_eq!(
+        node_access_count(&colony, "mitochondria") - node_access_count(&colony, "cell"),
+        2
+    );
+}
+
+#[test]
+fn failing_backend_falls_back_to_keywords() {
+    let mut colony = colony_digesting_with(MockBackend::new().failing("offline"));
+    colony.run(30);
+
+    let graph = colony.substrate().graph();
+    for keyword in ["mitochondria", "produce", "inside"] {
+        assert!(
+            !graph.find_nodes_by_exact_label(keyword).is_empty(),
+            "{keyword} missing"
+        );
+    }
+    assert!(export_triples(&colony)
+        .iter()
+        .all(|t| t.predicate == "related_to"));
+}
@@ -0,0 +1,138 @@
+//! Sentinel patrol tests — coverage beyond the spawn position.
+
+use phago_agents::sentinel::{PatrolStrategy, Sentinel};
+use phago_core::agent::Agent;
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use phago_runtime::substrate_impl::SubstrateImpl;
+use std::collections::BTreeMap;
+
+/// Where the unfamiliar concepts sit, far outside the sense radius of a
+/// sentinel spawned at the origin.
+const FAR: Position = Position { x: 200.0, y: 0.0 };
+
+fn add_concept(substrate: &mut SubstrateImpl, label: &str, position: Position) {
+    substrate.add_node(NodeData {
+        id: NodeId::new(),
+        label: label.to_string(),
+        node_type: NodeType::Concept,
+        position,
+        access_count: 5,
+        created_tick: 0,
+        last_accessed_tick: 0,
+        tags: BTreeMap::new(),
+    });
+}
+
+/// Familiar concepts around the origin and unfamiliar ones far away.
+fn split_substrate() -> SubstrateImpl {
+    let mut substrate = SubstrateImpl::new();
+    for (i, label) in ["cell", "membrane", "protein"].iter().enumerate() {
+        add_concept(&mut substrate, label, Position::new(i as f64, 1.0));
+    }
+    for (i, label) in ["quasar", "nebula"].iter().enumerate() {
+        add_concept(
+            &mut substrate,
+            label,
+            Position::new(FAR.x + i as f64, FAR.y + 1.0),
+        );
+    }
+    substrate
+}
+
+/// Run the sentinel, applying its moves, and collect the anomaly labels it
+/// presents.
+fn run(sentinel: &mut Sentinel, substrate: &SubstrateImpl, ticks: usize) -> Vec<String> {
+    let mut anomalies = Vec::new();
+    for _ in 0..ticks {
+        match sentinel.tick(substrate) {
+            AgentAction::Move(to) => sentinel.set_position(to),
+            AgentAction::PresentFragments(fragments) => {
+                anomalies.extend(fragments.into_iter().map(|f| f.label));
+            }
+            _ => {}
+        }
+    }
+    anomalies
+}
+
+fn flags(anomalies: &[String], term: &str) -> bool {
+    let novel = format!("Novel concept '{}'", term);
+    anomalies.iter().any(|a| a.contains(&novel))
+}
+
+#[test]
+fn static_sentinel_misses_distant_anomalies() {
+    let substrate = split_substrate();
+    let mut sentinel = Sentinel::new(Position::new(0.0, 0.0)).with_sense_radius(20.0);
+
+    let anomalies = run(&mut sentinel, &substrate, 300);
+
+    assert_eq!(sentinel.position(), Position::new(0.0, 0.0));
+    assert!(!flags(&anomalies, "quasar"), "{:?}", anomalies);
+    assert!(!flags(&anomalies, "nebula"), "{:?}", anomalies);
+}
+
+#[test]
+fn patrolling_sentinel_flags_distant_anomalies() {
+    let substrate = split_substrate();
+    let mut sentinel = Sentinel::new(Position::new(0.0, 0.0))
+        .with_sense_radius(20.0)
+        .with_patrol(PatrolStrategy::Waypoints(vec![
+            Position::new(0.0, 0.0),
+            FAR,
+        ]))
+        .with_patrol_step(5.0);
+
+    // Maturing, then 40 steps out, with time to scan on arrival
+    let anomalies = run(&mut sentinel, &substrate, 80);
+
+    assert!(flags(&anomalies, "quasar"), "{:?}", anomalies);
+    assert!(flags(&anomalies, "nebula"), "{:?}", anomalies);
+    // The familiar concepts were learned before leaving
+    assert!(!flags(&anomalies, "cell"), "{:?}", anomalies);
+}
+
+#[test]
+fn patrol_moves_at_most_one_step_per_tick() {
+    let substrate = split_substrate();
+    let mut sentinel = Sentinel::new(Position::new(0.0, 0.0))
+        .with_patrol(PatrolStrategy::Waypoints(vec![FAR]))
+        .with_patrol_step(3.0);
+
+    for _ in 0..60 {
+        let before = sentinel.position();
+        if let AgentAction::Move(to) = sentinel.tick(&substrate) {
+            assert!(before.distance_to(&to) <= 3.0 + 1e-9);
+            sentinel.set_position(to);
+        }
+    }
+    assert!(sentinel.position().x > 0.0);
+}
+
+#[test]
+fn signal_seeking_sentinel_follows_anomaly_warnings() {
+    let mut substrate = split_substrate();
+    substrate.emit_signal(Signal::new(
+        SignalType::Anomaly,
+        1.0,
+        Position::new(0.0, 10.0),
+        AgentId::new(),
+        0,
+    ));
+    let mut sentinel =
+        Sentinel::new(Position::new(0.0, 0.0)).with_patrol(PatrolStrategy::SignalSeeking {
+            weight_on_warning: 1.0,
+            weight_on_input: 0.5,
+        });
+
+    let moves: Vec<Position> = (0..20)
+        .filter_map(|_| match sentinel.tick(&substrate) {
+            AgentAction::Move(to) => Some(to),
+            _ => None,
+        })
+        .collect();
+
+    let first = moves.first().expect("a mature sentinel patrols");
+    assert!(first.y > 0.0, "{:?}", first);
+}
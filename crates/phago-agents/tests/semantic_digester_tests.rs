@@ -0,0 +1,170 @@
+//! SemanticDigester integration tests.
+//!
+//! Embedding happens off the tick: across a multi-document run each term
+//! is embedded once however many documents and digesters present it, the
+//! embeddings reach the concepts, and ticks stay fast with an embedder
+//! that takes a long time per call.
+
+#![cfg(feature = "semantic")]
+
+use phago_agents::embedding_queue::EmbeddingQueue;
+use phago_agents::semantic_digester::SemanticDigester;
+use phago_core::types::*;
+use phago_embeddings::{Embedder, EmbeddingResult, SimpleEmbedder};
+use phago_runtime::colony::Colony;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const DOCUMENTS: &[&str] = &[
+    "The cell membrane controls transport of ions into the cell.",
+    "Membrane proteins move ions across the membrane of the cell.",
+    "Mitochondria produce energy for the cell from glucose.",
+    "Energy from mitochondria drives membrane transport of ions.",
+];
+
+/// Counts the calls for each text, each taking `delay`.
+struct CountingEmbedder {
+    inner: SimpleEmbedder,
+    delay: Duration,
+    calls: Mutex<HashMap<String, usize>>,
+}
+
+impl CountingEmbedder {
+    fn new(delay: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            inner: SimpleEmbedder::new(64),
+            delay,
+            calls: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn calls(&self) -> HashMap<String, usize> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl Embedder for CountingEmbedder {
+    fn embed(&self, text: &str) -> EmbeddingResult<Vec<f32>> {
+        std::thread::sleep(self.delay);
+        *self
+            .calls
+            .lock()
+            .unwrap()
+            .entry(text.to_string())
+            .or_default() += 1;
+        self.inner.embed(text)
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+
+    fn model_name(&self) -> &str {
+        "counting"
+    }
+}
+
+/// A colony with a digester next to each document, all sharing `queue`.
+fn colony(queue: &Arc<EmbeddingQueue>, documents: &[&str], seed: u64) -> Colony {
+    let mut colony = Colony::new();
+    add_documents(&mut colony, queue, documents, seed);
+    colony
+}
+
+fn add_documents(colony: &mut Colony, queue: &Arc<EmbeddingQueue>, documents: &[&str], seed: u64) {
+    for (i, content) in documents.iter().enumerate() {
+        let position = Position::new(i as f64 * 20.0, seed as f64 * 20.0);
+        colony.ingest_document(&format!("Doc {seed}.{i}"), content, position);
+        let embedder = Arc::clone(queue.embedder());
+        colony.spawn(Box::new(
+            SemanticDigester::with_seed(position, embedder, seed * 100 + i as u64)
+                .with_queue(Arc::clone(queue))
+                .with_max_idle(1000),
+        ));
+    }
+}
+
+/// Tick until every document is digested and every embedding attached
+/// for a while, returning the slowest tick.
+fn run_until_embedded(colony: &mut Colony, queue: &EmbeddingQueue) -> Duration {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    let mut slowest = Duration::ZERO;
+    let mut settled = 0;
+    loop {
+        let started = Instant::now();
+        colony.tick();
+        slowest = slowest.max(started.elapsed());
+        let stats = colony.stats();
+        let done = stats.documents_digested == stats.documents_total
+            && queue.pending() == 0
+            && unembedded(colony).is_empty();
+        settled = if done { settled + 1 } else { 0 };
+        // Long enough for digesters to get from engulfing to presenting
+        if settled == 10 {
+            return slowest;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "still unembedded: {:?}",
+            unembedded(colony)
+        );
+        std::thread::sleep(Duration::from_millis(2));
+    }
+}
+
+fn unembedded(colony: &Colony) -> Vec<String> {
+    let substrate = colony.substrate();
+    let graph = substrate.graph();
+    graph
+        .all_nodes()
+        .into_iter()
+        .filter(|id| substrate.embedding(id).is_none())
+        .filter_map(|id| graph.get_node(&id).map(|n| n.label.clone()))
+        .collect()
+}
+
+#[test]
+fn each_unique_term_is_embedded_once_across_documents() {
+    let embedder = CountingEmbedder::new(Duration::from_millis(1));
+    let queue = Arc::new(EmbeddingQueue::new(embedder.clone()));
+    let mut colony = colony(&queue, DOCUMENTS, 0);
+    run_until_embedded(&mut colony, &queue);
+
+    let calls = embedder.calls();
+    assert!(calls.contains_key("membrane"), "{calls:?}");
+    assert!(calls.values().all(|&n| n == 1), "{calls:?}");
+    let nodes = colony.substrate().graph().node_count();
+    assert_eq!(calls.len(), nodes);
+    assert_eq!(colony.substrate().embedding_cache().len(), nodes);
+
+    // Later documents reuse the colony's cache: only new terms are embedded
+    add_documents(
+        &mut colony,
+        &queue,
+        &["Ion channels in the cell membrane open for transport."],
+        1,
+    );
+    run_until_embedded(&mut colony, &queue);
+    let later = embedder.calls();
+    assert!(later.values().all(|&n| n == 1), "{later:?}");
+    assert!(later.contains_key("channels"), "{later:?}");
+    // Terms of the first documents were not embedded again
+    assert!(later.len() > calls.len());
+    assert!(calls.keys().all(|term| later[term] == 1));
+}
+
+#[test]
+fn ticks_do_not_wait_for_a_slow_embedder() {
+    let delay = Duration::from_millis(150);
+    let embedder = CountingEmbedder::new(delay);
+    let queue = Arc::new(EmbeddingQueue::new(embedder.clone()));
+    let mut colony = colony(&queue, DOCUMENTS, 0);
+
+    let slowest = run_until_embedded(&mut colony, &queue);
+    // Embedding synchronously, a tick would take at least one call per
+    // concept presented in it
+    assert!(slowest < delay / 2, "slowest tick took {slowest:?}");
+    assert!(embedder.calls().len() > 5);
+    assert!(unembedded(&colony).is_empty());
+}
@@ -0,0 +1,170 @@
+//! SENSE (chemotaxis) tests — multi-signal blending.
+
+use phago_agents::digester::Digester;
+use phago_agents::genome::AgentGenome;
+use phago_core::agent::Agent;
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use phago_runtime::substrate_impl::SubstrateImpl;
+use std::collections::HashMap;
+
+/// A digester at the origin with a document (and its fading Input signal)
+/// to the west and a fresh capability deposit to the east.
+fn torn_substrate() -> (SubstrateImpl, Position) {
+    let mut substrate = SubstrateImpl::new();
+    let doc_pos = Position::new(-6.0, 0.0);
+    substrate.add_document(Document {
+        id: DocumentId::new(),
+        title: "Membranes".to_string(),
+        content: "cell membrane protein transport".to_string(),
+        position: doc_pos,
+        digested: false,
+        tags: Vec::new(),
+        version: 1,
+    });
+    substrate.emit_signal(Signal::new(
+        SignalType::Input,
+        0.3,
+        doc_pos,
+        AgentId::new(),
+        0,
+    ));
+    substrate.emit_signal(Signal::new(
+        SignalType::Capability,
+        0.8,
+        Position::new(6.0, 0.0),
+        AgentId::new(),
+        0,
+    ));
+    (substrate, doc_pos)
+}
+
+#[test]
+fn digester_prioritizes_documents_when_configured_to() {
+    let (substrate, doc_pos) = torn_substrate();
+
+    let mut digester = Digester::new(Position::new(0.0, 0.0))
+        .with_signal_weight(SignalType::Input, 3.0)
+        .with_signal_weight(SignalType::Capability, 0.5);
+
+    match digester.tick(&substrate) {
+        AgentAction::Move(to) => assert_eq!(to, doc_pos),
+        other => panic!("expected a move toward the document, got {:?}", other),
+    }
+}
+
+#[test]
+fn digester_follows_capability_when_it_dominates() {
+    let (substrate, _) = torn_substrate();
+
+    let weights = HashMap::from([(SignalType::Input, 1.0), (SignalType::Capability, 1.0)]);
+    let mut digester = Digester::new(Position::new(0.0, 0.0)).with_signal_weights(weights);
+
+    match digester.tick(&substrate) {
+        AgentAction::Move(to) => assert!(to.x > 0.0, "should head east, got {:?}", to),
+        other => panic!("expected a move toward the deposit, got {:?}", other),
+    }
+}
+
+#[test]
+fn default_digester_ignores_capability_signals() {
+    let (substrate, doc_pos) = torn_substrate();
+
+    let mut digester = Digester::new(Position::new(0.0, 0.0));
+    assert!(matches!(digester.tick(&substrate), AgentAction::Move(to) if to == doc_pos));
+}
+
+#[test]
+fn genome_weights_drive_the_digester() {
+    let (substrate, _) = torn_substrate();
+
+    let mut genome = AgentGenome::default_genome();
+    genome.capability_signal_weight = 2.0;
+    let mut digester =
+        Digester::new(Position::new(0.0, 0.0)).with_signal_weights(genome.signal_weights());
+
+    assert!(matches!(digester.tick(&substrate), AgentAction::Move(to) if to.x > 0.0));
+}
+
+#[test]
+fn substrate_ranks_alternative_signals() {
+    let (mut substrate, _) = torn_substrate();
+    substrate.emit_signal(Signal::new(
+        SignalType::Input,
+        0.9,
+        Position::new(1.0, 1.0),
+        AgentId::new(),
+        0,
+    ));
+
+    let origin = Position::new(0.0, 0.0);
+    let top = substrate.strongest_k_signals(&origin, &SignalType::Input, 5);
+    assert_eq!(top.len(), 2);
+    assert!((top[0].intensity - 0.9).abs() < 1e-12);
+
+    let weights = HashMap::from([(SignalType::Capability, 1.0)]);
+    let blended = substrate.gradient_blend(&origin, &weights, 10.0).unwrap();
+    assert_eq!(blended.signal_type, SignalType::Capability);
+    assert!(blended.direction.x > 0.99);
+}
+
+/// The document about a warned term, and the order in which one digester
+/// engulfs it and an unwarned document lying at the same spot.
+fn engulf_order(heed_warnings: bool) -> (DocumentId, Vec<DocumentId>) {
+    use phago_core::signal::AnomalyWarning;
+    use phago_runtime::colony::{Colony, ColonyEvent};
+
+    let mut colony = Colony::new();
+    let warned = colony.ingest_document(
+        "Outbreak",
+        "virus spreading through tissue",
+        Position::new(0.0, 0.0),
+    );
+    colony.ingest_document(
+        "Membranes",
+        "cell membrane protein transport",
+        Position::new(0.0, 0.0),
+    );
+    let warning = AnomalyWarning {
+        terms: vec!["Virus".to_string()],
+    };
+    colony.substrate_mut().emit_signal(
+        Signal::new(
+            SignalType::Anomaly,
+            1.0,
+            Position::new(0.0, 0.0),
+            AgentId::new(),
+            0,
+        )
+        .with_payload(&warning),
+    );
+    colony.spawn(Box::new(
+        Digester::new(Position::new(0.0, 0.0))
+            .with_max_idle(50)
+            .with_heed_warnings(heed_warnings),
+    ));
+
+    let order: Vec<DocumentId> = colony
+        .run(20)
+        .into_iter()
+        .flatten()
+        .filter_map(|event| match event {
+            ColonyEvent::Engulfed { document, .. } => Some(document),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(order.len(), 2, "both documents should be digested");
+    assert!(order.contains(&warned));
+    (warned, order)
+}
+
+#[test]
+fn digester_puts_off_warned_documents() {
+    // Document iteration order is arbitrary, so repeat to rule out luck
+    for _ in 0..5 {
+        let (warned, order) = engulf_order(true);
+        assert_eq!(order[1], warned);
+    }
+    // Switched off, warnings are ignored but nothing is skipped
+    engulf_order(false);
+}
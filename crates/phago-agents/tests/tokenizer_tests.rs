@@ -0,0 +1,83 @@
+//! Shared tokenizer tests.
+//!
+//! Digesters used to carry their own stopword list; the default
+//! [`Tokenizer`] must split text exactly as that list did, so graphs built
+//! before and after the switch agree.
+
+use phago_agents::digester::Digester;
+use phago_core::tokenize::{Language, Tokenizer, TokenizerConfig};
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig};
+use phago_runtime::corpus::Corpus;
+use std::collections::HashSet;
+
+/// The digester's tokenization before it moved to [`Tokenizer`].
+fn legacy_tokens(text: &str) -> Vec<String> {
+    let stopwords: HashSet<&str> = [
+        "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
+        "do", "does", "did", "will", "would", "shall", "should", "may", "might", "must", "can",
+        "could", "of", "in", "to", "for", "with", "on", "at", "from", "by", "about", "as", "into",
+        "through", "during", "before", "after", "above", "below", "between", "out", "off", "over",
+        "under", "again", "further", "then", "once", "here", "there", "when", "where", "why",
+        "how", "all", "each", "every", "both", "few", "more", "most", "other", "some", "such",
+        "no", "nor", "not", "only", "own", "same", "so", "than", "too", "very", "just", "because",
+        "but", "and", "or", "if", "while", "that", "this", "these", "those", "it", "its", "they",
+        "them", "their", "we", "our", "you", "your", "he", "she", "his", "her", "which", "what",
+        "who", "whom",
+    ]
+    .into_iter()
+    .collect();
+
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.len() >= 3 && !stopwords.contains(w.as_str()))
+        .collect()
+}
+
+#[test]
+fn default_tokenizer_matches_legacy_output_on_embedded_corpus() {
+    let tokenizer = Tokenizer::default();
+    for corpus in [Corpus::from_embedded(), Corpus::inline_corpus()] {
+        for doc in &corpus.documents {
+            for text in [&doc.title, &doc.content] {
+                assert_eq!(
+                    tokenizer.tokens(text),
+                    legacy_tokens(text),
+                    "tokens differ for {:?}",
+                    doc.title
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn colony_hands_its_tokenizer_to_spawned_digesters() {
+    let mut colony = Colony::from_config(ColonyConfig {
+        tokenizer: TokenizerConfig::for_language(Language::German),
+        ..Default::default()
+    });
+    colony.ingest_document(
+        "Zellbiologie",
+        "Die Zellmembran und der Zellkern. Die Zellmembran schützt die Zelle und den Zellkern.",
+        Position::new(0.0, 0.0),
+    );
+    colony.spawn(Box::new(
+        Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+    ));
+    colony.run(10);
+
+    let graph = colony.substrate().graph();
+    assert!(!graph.find_nodes_by_label("zellmembran").is_empty());
+    for stopword in ["die", "und", "der", "den"] {
+        assert!(
+            graph.find_nodes_by_label(stopword).is_empty(),
+            "{stopword} became a concept"
+        );
+    }
+    assert_eq!(
+        colony.config().tokenizer.language,
+        Language::German,
+        "config round-trips"
+    );
+}
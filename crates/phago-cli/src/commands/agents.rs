@@ -0,0 +1,85 @@
+//! Inspect the session's agents.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use phago::prelude::*;
+use phago::runtime::session::restore_agents;
+
+use crate::config::{current_session_path, Config};
+
+/// Show what the session's agents know: each one's heaviest terms and
+/// whether the graph has them, and how much agents' terms overlap.
+/// `agent` narrows the listing to agents whose ID starts with it.
+pub fn vocab(top: usize, agent: Option<&str>) -> Result<()> {
+    let session_path = current_session_path()?;
+
+    if !session_path.exists() {
+        bail!("No session found. Run {} first.", "phago ingest".cyan());
+    }
+
+    let state = load_session(&session_path)?;
+    let mut colony = Colony::from_config(Config::load()?.to_runtime_config());
+    super::session::restore(&mut colony, &state);
+    restore_agents(&mut colony, &state);
+
+    let report = colony.vocabulary_report(top);
+    if report.agents.is_empty() {
+        println!("{} No agents were saved with this session.", "!".yellow());
+        return Ok(());
+    }
+    let selected: Vec<&AgentVocabulary> = report
+        .agents
+        .iter()
+        .filter(|a| agent.is_none_or(|prefix| a.id.0.to_string().starts_with(prefix)))
+        .collect();
+    if selected.is_empty() {
+        bail!("No agent ID starts with '{}'", agent.unwrap_or_default());
+    }
+
+    for vocabulary in &selected {
+        println!(
+            "{} {} {} {}",
+            "→".blue(),
+            vocabulary.agent_type.white().bold(),
+            short_id(&vocabulary.id).cyan(),
+            format!("({} terms)", vocabulary.vocabulary_size).dimmed()
+        );
+        for (term, weight) in &vocabulary.top_terms {
+            let coverage = match report.coverage_of(term) {
+                Some(c) if c.node.is_some() => format!("accessed {}", c.access_count),
+                _ => "not in graph".to_string(),
+            };
+            println!("    {:24} {:>6.1}  {}", term, weight, coverage.dimmed());
+        }
+        for overlap in report.overlaps_of(&vocabulary.id) {
+            let other = if overlap.a == vocabulary.id {
+                overlap.b
+            } else {
+                overlap.a
+            };
+            println!(
+                "    {} {} {}",
+                "overlap with".dimmed(),
+                short_id(&other),
+                format!("{:.2} ({} shared)", overlap.jaccard, overlap.shared).cyan()
+            );
+        }
+        println!();
+    }
+
+    let summary = report.summary();
+    println!(
+        "{} {} agents with terms, mean overlap {}, max {}, {} of terms in the graph",
+        "→".blue(),
+        summary.agents,
+        format!("{:.2}", summary.mean_jaccard).cyan(),
+        format!("{:.2}", summary.max_jaccard).cyan(),
+        format!("{:.0}%", summary.graph_coverage * 100.0).cyan()
+    );
+
+    Ok(())
+}
+
+fn short_id(id: &AgentId) -> String {
+    id.0.to_string()[..8].to_string()
+}
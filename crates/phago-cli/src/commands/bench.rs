@@ -0,0 +1,205 @@
+//! Benchmarks of the project's colony.
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use colored::Colorize;
+use phago::prelude::*;
+use phago::runtime::corpus::Corpus;
+use phago_runtime::bench::{
+    query_load_csv, query_load_test, LockMode, QueryLoadConfig, QueryLoadReport,
+};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::{current_session_path, Config};
+
+/// Which ways of serving queries to load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum LockChoice {
+    /// Every query waits its turn on the colony
+    Mutex,
+    /// Queries read a read view of the colony concurrently
+    ReadView,
+    /// One run with each
+    #[default]
+    Both,
+}
+
+/// Whether load-test queries reinforce what they retrieve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ReinforceChoice {
+    On,
+    Off,
+    /// One run with each
+    #[default]
+    Both,
+}
+
+/// How load test results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum BenchFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+/// What `phago bench query-load` runs.
+pub struct QueryLoadArgs<'a> {
+    pub concurrency: usize,
+    pub duration: u64,
+    pub queries: Option<&'a Path>,
+    pub corpus: Option<&'a str>,
+    pub lock: LockChoice,
+    pub reinforce: ReinforceChoice,
+    pub format: BenchFormat,
+}
+
+/// Concepts queried when no query file is given.
+const DEFAULT_QUERIES: usize = 50;
+
+/// Query the session's colony, or a digested embedded corpus, from
+/// concurrent workers and report latency percentiles per configuration.
+pub fn query_load(args: &QueryLoadArgs) -> Result<()> {
+    let locks: &[LockMode] = match args.lock {
+        LockChoice::Mutex => &[LockMode::Mutex],
+        LockChoice::ReadView => &[LockMode::ReadView],
+        LockChoice::Both => &[LockMode::Mutex, LockMode::ReadView],
+    };
+    let reinforce: &[bool] = match args.reinforce {
+        ReinforceChoice::On => &[true],
+        ReinforceChoice::Off => &[false],
+        ReinforceChoice::Both => &[false, true],
+    };
+
+    let config = Config::load()?;
+    let load = || -> Result<Colony> {
+        let mut colony = Colony::from_config(config.to_runtime_config());
+        match args.corpus {
+            Some(name) => {
+                let Some(corpus) = Corpus::from_embedded_named(name) else {
+                    bail!("Unknown corpus '{}'", name);
+                };
+                corpus.ingest_into(&mut colony);
+                for doc in &corpus.documents {
+                    colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(80)));
+                }
+                colony.run(30);
+            }
+            None => {
+                let session_path = current_session_path()?;
+                if !session_path.exists() {
+                    bail!("No session found. Run {} first.", "phago ingest".cyan());
+                }
+                super::session::restore(&mut colony, &load_session(&session_path)?);
+            }
+        }
+        Ok(colony)
+    };
+
+    let colony = load()?;
+    let query_set = match args.queries {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .lines()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(str::to_string)
+            .collect(),
+        None => top_concepts(&colony, DEFAULT_QUERIES),
+    };
+
+    let mut reports = Vec::new();
+    // The first run reuses the colony the queries were taken from
+    let mut colony = Some(colony);
+    for &lock_mode in locks {
+        for &reinforce in reinforce {
+            let colony = match colony.take() {
+                Some(colony) => colony,
+                None => load()?,
+            };
+            let run = QueryLoadConfig {
+                concurrency: args.concurrency,
+                duration: Duration::from_secs(args.duration),
+                query_set: query_set.clone(),
+                reinforce,
+                lock_mode,
+                ..Default::default()
+            };
+            if args.format == BenchFormat::Table {
+                eprintln!(
+                    "{} {} workers for {}s ({}{})...",
+                    "Loading".green().bold(),
+                    args.concurrency,
+                    args.duration,
+                    lock_mode.name(),
+                    if reinforce { ", reinforcing" } else { "" }
+                );
+            }
+            reports.push(query_load_test(colony, &run)?);
+        }
+    }
+
+    match args.format {
+        BenchFormat::Table => print_table(&reports),
+        BenchFormat::Json => println!("{}", serde_json::to_string_pretty(&reports)?),
+        BenchFormat::Csv => print!("{}", query_load_csv(&reports)),
+    }
+    Ok(())
+}
+
+/// Labels of the `n` most accessed concepts.
+fn top_concepts(colony: &Colony, n: usize) -> Vec<String> {
+    let graph = colony.substrate().graph();
+    let mut concepts: Vec<&NodeData> = graph
+        .all_nodes()
+        .iter()
+        .filter_map(|id| graph.get_node(id))
+        .filter(|node| node.node_type == NodeType::Concept)
+        .collect();
+    concepts.sort_by(|a, b| {
+        b.access_count
+            .cmp(&a.access_count)
+            .then(a.label.cmp(&b.label))
+    });
+    concepts
+        .into_iter()
+        .take(n)
+        .map(|node| node.label.clone())
+        .collect()
+}
+
+fn print_table(reports: &[QueryLoadReport]) {
+    println!();
+    println!(
+        "{:<22} {:>9} {:>7} {:>9} {:>9} {:>9} {:>9} {:>9}",
+        "Run".bold(),
+        "Queries".bold(),
+        "Errors".bold(),
+        "QPS".bold(),
+        "p50 ms".bold(),
+        "p90 ms".bold(),
+        "p99 ms".bold(),
+        "Max ms".bold()
+    );
+    let ms = |us: u64| format!("{:.2}", us as f64 / 1000.0);
+    for report in reports {
+        let latency = &report.latency;
+        let errors = if report.errors > 0 {
+            report.errors.to_string().red()
+        } else {
+            report.errors.to_string().normal()
+        };
+        println!(
+            "{:<22} {:>9} {:>7} {:>9.0} {:>9} {:>9} {:>9} {:>9}",
+            report.name().cyan(),
+            report.queries,
+            errors,
+            report.queries_per_sec,
+            ms(latency.p50_us),
+            ms(latency.p90_us),
+            ms(latency.p99_us),
+            ms(latency.max_us)
+        );
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! These commands manage a distributed Phago colony cluster including
 //! starting coordinators, starting shards, querying cluster status,
-//! and running benchmarks.
+//! feeding and querying a running cluster, and running benchmarks.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::path::Path;
 
 /// Start a coordinator node.
 pub fn start_coordinator(port: u16, num_shards: u32) -> Result<()> {
@@ -56,6 +58,7 @@ pub fn start_shard(shard_port: u16, coordinator_addr: &str, shard_id: u32) -> Re
         use phago_distributed::shard::ShardedColony;
         use phago_distributed::types::{ShardId, ShardInfo};
         use phago_runtime::colony::ColonyConfig;
+        use std::rc::Rc;
         use std::sync::Arc;
         use tokio::sync::RwLock;
 
@@ -76,7 +79,7 @@ pub fn start_shard(shard_port: u16, coordinator_addr: &str, shard_id: u32) -> Re
 
         // Create the shard
         let hash_ring = Arc::new(RwLock::new(ConsistentHashRing::new(3)));
-        let shard = Arc::new(RwLock::new(ShardedColony::new(
+        let shard = Rc::new(RwLock::new(ShardedColony::new(
             registered_id,
             ColonyConfig::default(),
             hash_ring,
@@ -137,6 +140,184 @@ pub fn status(coordinator_addr: &str) -> Result<()> {
     })
 }
 
+/// Ingest files into a running cluster.
+///
+/// Each document is routed through the coordinator's hash ring to its
+/// owning shard, plus `replicas` additional shards (the coordinator's
+/// replication factor when `None`), then the cluster runs `ticks` ticks
+/// so the shards digest what they received.
+pub fn ingest(
+    path: &str,
+    coordinator_addr: &str,
+    replicas: Option<u32>,
+    ticks: u64,
+    extensions: &str,
+) -> Result<()> {
+    use phago::prelude::Position;
+    use phago::runtime::corpus::{extract_text, ExtractError};
+    use phago_distributed::cluster::ClusterClient;
+    use phago_distributed::rpc::client::ClientConfig;
+
+    let path = Path::new(path);
+    if !path.exists() {
+        bail!("Path does not exist: {}", path.display());
+    }
+
+    let ext_list: Vec<String> = extensions
+        .split(',')
+        .map(|e| e.trim().trim_start_matches('.').to_ascii_lowercase())
+        .collect();
+    let files = super::ingest::collect_files(path, &ext_list)?;
+    if files.is_empty() {
+        bail!("No files found with extensions: {}", extensions);
+    }
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let cluster = ClusterClient::connect(coordinator_addr, ClientConfig::default()).await?;
+        println!(
+            "{} Connected to {} ({} shards)",
+            "cluster".green().bold(),
+            cluster.coordinator_addr(),
+            cluster.shards().len()
+        );
+
+        println!(
+            "{} Routing {} files...",
+            "→".blue(),
+            files.len().to_string().cyan()
+        );
+        let pb = ProgressBar::new(files.len() as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+
+        let mut ingested = 0usize;
+        let mut copies = 0usize;
+        let mut skipped = Vec::new();
+        for file in &files {
+            let doc = match extract_text(file) {
+                Ok(doc) => doc,
+                Err(e @ (ExtractError::Skipped(_) | ExtractError::Unsupported(_))) => {
+                    skipped.push((file.clone(), e.to_string()));
+                    pb.inc(1);
+                    continue;
+                }
+                Err(ExtractError::Io(e)) => {
+                    bail!("Failed to read {}: {}", file.display(), e);
+                }
+            };
+
+            // Position documents in a grid, as local ingestion does
+            let x = (ingested % 10) as f64;
+            let y = (ingested / 10) as f64;
+            let shards = cluster
+                .ingest(&doc.title, &doc.content, Position::new(x, y), replicas)
+                .await?;
+            ingested += 1;
+            copies += shards.len();
+
+            pb.set_message(format!("{}", shards[0]));
+            pb.inc(1);
+        }
+        pb.finish_with_message("done");
+
+        for (file, reason) in &skipped {
+            println!("{} {} ({})", "⚠".yellow(), file.display(), reason);
+        }
+        if ingested == 0 {
+            bail!("No documents could be extracted from {} files", files.len());
+        }
+
+        println!(
+            "{} Running {} ticks across the cluster...",
+            "→".blue(),
+            ticks.to_string().cyan()
+        );
+        let pb = ProgressBar::new(ticks);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} ticks")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+        for _ in 0..ticks {
+            cluster.run_ticks(1).await?;
+            pb.inc(1);
+        }
+        pb.finish();
+
+        println!(
+            "{} Ingested {} documents ({} copies)",
+            "✓".green(),
+            ingested.to_string().cyan(),
+            copies.to_string().cyan()
+        );
+        Ok(())
+    })
+}
+
+/// Query a running cluster.
+///
+/// Runs a two-phase TF-IDF query across every shard and prints the merged
+/// results with the shard each one came from.
+pub fn query(query_text: &str, coordinator_addr: &str, max_results: usize) -> Result<()> {
+    use phago_distributed::cluster::ClusterClient;
+    use phago_distributed::query::{DistributedHybridConfig, DistributedQueryEngine};
+    use phago_distributed::rpc::client::ClientConfig;
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let cluster = ClusterClient::connect(coordinator_addr, ClientConfig::default()).await?;
+        let engine = DistributedQueryEngine::new(DistributedHybridConfig {
+            max_results,
+            ..Default::default()
+        });
+        let results = cluster.query(&engine, query_text).await?;
+
+        if results.is_empty() {
+            println!(
+                "{} No results found for: {}",
+                "•".yellow(),
+                query_text.cyan()
+            );
+            return Ok(());
+        }
+
+        println!(
+            "{} Results for {} ({} shards):",
+            "→".blue(),
+            query_text.cyan().bold(),
+            cluster.shards().len()
+        );
+        println!();
+
+        for (i, result) in results.iter().enumerate() {
+            let rank = format!("{}.", i + 1);
+            let score = format!("{:.3}", result.score);
+
+            println!(
+                "  {} {} {} {}",
+                rank.blue(),
+                result.label.white().bold(),
+                format!("({})", score).dimmed(),
+                result.shard_id.to_string().cyan()
+            );
+        }
+
+        println!();
+        println!(
+            "{} {} results",
+            "✓".green(),
+            results.len().to_string().cyan()
+        );
+        Ok(())
+    })
+}
+
 /// Run distributed benchmarks.
 pub fn bench(mode: &str) -> Result<()> {
     println!(
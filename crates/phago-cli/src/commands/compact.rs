@@ -0,0 +1,97 @@
+//! Merge concepts whose labels are variants of one another.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use phago::prelude::*;
+use phago::runtime::session::save_session_with_agents;
+
+use crate::config::{current_session_path, Config};
+
+/// Build a strategy from its CLI name and the flags that tune it.
+pub fn strategy(
+    name: &str,
+    max_distance: usize,
+    min_len: usize,
+    min_similarity: f64,
+) -> Result<CompactionStrategy> {
+    Ok(match name {
+        "stemming" => CompactionStrategy::Stemming,
+        "edit-distance" => CompactionStrategy::EditDistance {
+            max_distance,
+            min_len,
+        },
+        "embedding" => CompactionStrategy::Embedding { min_similarity },
+        other => bail!("Unknown strategy '{other}'. Use: stemming, edit-distance, embedding"),
+    })
+}
+
+pub fn run(strategy: &CompactionStrategy, dry_run: bool) -> Result<()> {
+    let session_path = current_session_path()?;
+
+    if !session_path.exists() {
+        bail!("No session found. Run {} first.", "phago ingest".cyan());
+    }
+
+    // Load session
+    println!("{} Loading session...", "→".blue());
+    let state = load_session(&session_path)?;
+    let mut colony = Colony::from_config(Config::load()?.to_runtime_config());
+    super::session::restore(&mut colony, &state);
+
+    let plan = colony.plan_compaction(strategy);
+    if plan.is_empty() {
+        println!("{} Nothing to merge.", "✓".green().bold());
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "{} {} clusters, {} nodes to merge:",
+        "Plan".bold(),
+        plan.clusters.len().to_string().cyan(),
+        plan.nodes_merged().to_string().cyan()
+    );
+    for cluster in &plan.clusters {
+        let merged: Vec<String> = cluster
+            .merged
+            .iter()
+            .map(|m| format!("{} ({})", m.label, m.access_count))
+            .collect();
+        println!(
+            "  {} ← {}",
+            cluster.canonical_label.green(),
+            merged.join(", ").yellow()
+        );
+    }
+
+    if dry_run {
+        println!();
+        println!("{} Dry run: the session was not changed.", "→".blue());
+        return Ok(());
+    }
+
+    let before = colony.stats();
+    colony.apply_compaction(&plan);
+    save_session_with_agents(
+        &colony,
+        &session_path,
+        &state.metadata.files_indexed,
+        &state.agents,
+    )?;
+
+    let after = colony.stats();
+    println!();
+    println!("{} Compaction complete!", "✓".green().bold());
+    println!(
+        "  Nodes: {} → {}",
+        before.graph_nodes.to_string().yellow(),
+        after.graph_nodes.to_string().green()
+    );
+    println!(
+        "  Edges: {} → {}",
+        before.graph_edges.to_string().yellow(),
+        after.graph_edges.to_string().green()
+    );
+
+    Ok(())
+}
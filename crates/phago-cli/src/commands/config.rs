@@ -0,0 +1,74 @@
+//! Inspect the project configuration.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use crate::config::{find_config_file, Config, Overrides, Source};
+
+pub fn show(resolved: bool) -> Result<()> {
+    if !resolved {
+        match find_config_file() {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read config: {}", path.display()))?;
+                println!("{} {}", "→".blue(), path.display());
+                println!();
+                print!("{}", text);
+            }
+            None => println!(
+                "{} No phago.toml found; all settings are defaults. See {}",
+                "•".yellow(),
+                "phago config show --resolved".cyan()
+            ),
+        }
+        return Ok(());
+    }
+
+    let resolved = Config::resolve(&Overrides::default())?;
+    let width = resolved
+        .values
+        .iter()
+        .map(|(path, (value, _))| path.len() + value.to_string().len() + 3)
+        .max()
+        .unwrap_or(0);
+    for (path, (value, source)) in &resolved.values {
+        // Access tokens are secrets; only their roles are shown
+        let value = match path.as_str() {
+            "web.tokens" => hide_tokens(value),
+            _ => value.clone(),
+        };
+        let setting = format!("{} = {}", path, value);
+        let label = format!("({})", source);
+        let label = match source {
+            Source::Default => label.dimmed(),
+            _ => label.cyan(),
+        };
+        println!("{:width$}  {}", setting, label, width = width);
+    }
+
+    Ok(())
+}
+
+/// `tokens` with each `ROLE:TOKEN` shown as `ROLE:***`.
+fn hide_tokens(tokens: &toml::Value) -> toml::Value {
+    let hide = |token: &str| {
+        let role = token.split_once(':').map_or("", |(role, _)| role);
+        toml::Value::String(format!("{}:***", role))
+    };
+    match tokens {
+        toml::Value::Array(items) => toml::Value::Array(
+            items
+                .iter()
+                .map(|t| hide(t.as_str().unwrap_or("")))
+                .collect(),
+        ),
+        toml::Value::String(items) => toml::Value::Array(
+            items
+                .split(',')
+                .filter(|t| !t.trim().is_empty())
+                .map(|t| hide(t.trim()))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
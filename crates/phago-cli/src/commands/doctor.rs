@@ -0,0 +1,107 @@
+//! Check the session's graph for inconsistencies and optionally repair them.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use phago::prelude::*;
+use phago::runtime::integrity::{self, RepairPolicy};
+use phago::runtime::session::save_session_with_agents;
+use std::collections::HashSet;
+
+use crate::config::{current_session_path, Config};
+
+pub fn run(fix: bool) -> Result<()> {
+    let session_path = current_session_path()?;
+
+    if !session_path.exists() {
+        bail!("No session found. Run {} first.", "phago ingest".cyan());
+    }
+
+    let state = load_session(&session_path)?;
+
+    // Restoring skips edges whose endpoints are not in the session
+    let labels: HashSet<&str> = state.nodes.iter().map(|n| n.label.as_str()).collect();
+    let dangling = state
+        .edges
+        .iter()
+        .filter(|e| {
+            !labels.contains(e.from_label.as_str()) || !labels.contains(e.to_label.as_str())
+        })
+        .count();
+
+    let mut colony = Colony::from_config(Config::load()?.to_runtime_config());
+    let report = restore_into_colony(&mut colony, &state, None).integrity;
+
+    println!(
+        "{} Checked {} nodes, {} edges and {} documents",
+        "→".blue(),
+        report.nodes_checked.to_string().cyan(),
+        (report.edges_checked + dangling).to_string().cyan(),
+        report.documents_checked.to_string().cyan()
+    );
+    if dangling > 0 {
+        println!(
+            "  {} {} session edges name concepts the session does not contain",
+            "✗".red(),
+            dangling
+        );
+    }
+    for issue in &report.issues {
+        println!("  {} {}", "✗".red(), issue);
+    }
+
+    let problems = report.issues.len() + dangling;
+    if problems == 0 {
+        println!("{} No problems found.", "✓".green().bold());
+        return Ok(());
+    }
+
+    if !fix {
+        println!();
+        let repairable = report.repairable() + dangling;
+        if repairable > 0 {
+            println!(
+                "{} {} of {} problems can be repaired with {}",
+                "→".blue(),
+                repairable,
+                problems,
+                "phago doctor --fix".cyan()
+            );
+        }
+        bail!("{} integrity problems found", problems);
+    }
+
+    let repaired = integrity::repair(&mut colony, &RepairPolicy::default());
+    println!();
+    if dangling > 0 {
+        println!(
+            "  {} dropped {} dangling session edges",
+            "✓".green(),
+            dangling
+        );
+    }
+    for action in &repaired.actions {
+        println!("  {} {}", "✓".green(), action);
+    }
+    if dangling > 0 || !repaired.actions.is_empty() {
+        save_session_with_agents(
+            &colony,
+            &session_path,
+            &state.metadata.files_indexed,
+            &state.agents,
+        )?;
+        println!("{} Session saved.", "✓".green().bold());
+    }
+
+    if !repaired.remaining.is_clean() {
+        println!();
+        for issue in &repaired.remaining.issues {
+            println!("  {} {}", "✗".red(), issue);
+        }
+        bail!(
+            "{} integrity problems could not be repaired",
+            repaired.remaining.issues.len()
+        );
+    }
+
+    Ok(())
+}
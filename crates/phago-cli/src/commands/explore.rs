@@ -3,8 +3,9 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
 use phago::prelude::*;
+use phago::runtime::community;
 
-use crate::config::current_session_path;
+use crate::config::{current_session_path, Config};
 
 fn load_colony() -> Result<Colony> {
     let session_path = current_session_path()?;
@@ -14,12 +15,12 @@ fn load_colony() -> Result<Colony> {
     }
 
     let state = load_session(&session_path)?;
-    let mut colony = Colony::new();
-    restore_into_colony(&mut colony, &state);
+    let mut colony = Colony::from_config(Config::load()?.to_runtime_config());
+    super::session::restore(&mut colony, &state);
     Ok(colony)
 }
 
-pub fn centrality(top: usize) -> Result<()> {
+pub fn centrality(top: usize, method: CentralityMethod) -> Result<()> {
     let colony = load_colony()?;
     let graph = colony.substrate().graph();
 
@@ -30,7 +31,10 @@ pub fn centrality(top: usize) -> Result<()> {
     );
     println!();
 
-    let centrality = graph.betweenness_centrality(100);
+    let centrality = match method {
+        CentralityMethod::Betweenness => graph.betweenness_centrality(100),
+        CentralityMethod::PageRank => pagerank(graph, &PageRankConfig::default()),
+    };
 
     for (i, (node_id, score)) in centrality.iter().take(top).enumerate() {
         if let Some(node) = graph.get_node(node_id) {
@@ -75,23 +79,8 @@ pub fn bridges(top: usize) -> Result<()> {
     Ok(())
 }
 
-pub fn path(from: &str, to: &str) -> Result<()> {
+pub fn path(from: &str, to: &str, cost: PathCost, k: usize) -> Result<()> {
     let colony = load_colony()?;
-    let graph = colony.substrate().graph();
-
-    // Find nodes by label
-    let from_nodes = graph.find_nodes_by_label(from);
-    let to_nodes = graph.find_nodes_by_label(to);
-
-    if from_nodes.is_empty() {
-        bail!("No node found with label: {}", from);
-    }
-    if to_nodes.is_empty() {
-        bail!("No node found with label: {}", to);
-    }
-
-    let from_id = &from_nodes[0];
-    let to_id = &to_nodes[0];
 
     println!(
         "{} Finding path: {} → {}",
@@ -101,36 +90,98 @@ pub fn path(from: &str, to: &str) -> Result<()> {
     );
     println!();
 
-    match graph.shortest_path(from_id, to_id) {
-        Some((path, weight)) => {
-            println!(
-                "  Path length: {} hops",
-                (path.len() - 1).to_string().green()
-            );
-            println!("  Total weight: {:.4}", weight);
+    let paths = match colony.find_path(from, to, cost, k) {
+        Ok(paths) => paths,
+        Err(e @ PathError::UnknownConcept { .. }) => bail!("{}", e),
+        Err(e) => {
+            println!("  {} {}", "✗".red(), e);
+            return Ok(());
+        }
+    };
+
+    for (n, path) in paths.iter().enumerate() {
+        if n > 0 {
             println!();
-            println!("  Path:");
-            for (i, node_id) in path.iter().enumerate() {
-                if let Some(node) = graph.get_node(node_id) {
-                    let prefix = if i == 0 {
-                        "  ●".green().to_string()
-                    } else if i == path.len() - 1 {
-                        "  ●".blue().to_string()
-                    } else {
-                        "  │".dimmed().to_string()
-                    };
-                    println!("{} {}", prefix, node.label);
-                }
-            }
+            println!("  Alternative {}:", n);
         }
-        None => {
-            println!("  {} No path found between {} and {}", "✗".red(), from, to);
+        println!(
+            "  Path length: {} hops",
+            path.hops.len().to_string().green()
+        );
+        println!("  Strength: {:.4} (cost {:.4})", path.strength, path.cost);
+        println!();
+        println!("  {} {}", "●".green(), path.labels[0]);
+        for (i, hop) in path.hops.iter().enumerate() {
+            println!(
+                "  {} weight {:.3}, co-activated {}×",
+                "│".dimmed(),
+                hop.weight,
+                hop.co_activations
+            );
+            let marker = if i == path.hops.len() - 1 {
+                "●".blue()
+            } else {
+                "○".dimmed()
+            };
+            println!("  {} {}", marker, hop.to_label);
         }
     }
 
     Ok(())
 }
 
+pub fn edge(from: &str, to: &str) -> Result<()> {
+    let colony = load_colony()?;
+    let req = ExploreRequest::Edge {
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    let ExploreResponse::Edge {
+        found,
+        from,
+        to,
+        weight,
+        co_activations,
+        sources,
+    } = phago_explore(&colony, &req)
+    else {
+        unreachable!("edge exploration answers with an edge");
+    };
+    if !found {
+        bail!("No edge between '{}' and '{}'", from, to);
+    }
+
+    println!("{} {} — {}", "→".blue(), from.cyan(), to.cyan());
+    println!("  Weight: {:.4}", weight);
+    println!("  Co-activated: {}×", co_activations);
+    println!();
+    if sources.is_empty() {
+        let note = if colony.edge_provenance().is_enabled() {
+            "No reinforcements recorded yet."
+        } else {
+            "Edge provenance is off (colony.edge_provenance = 0)."
+        };
+        println!("  {}", note.dimmed());
+        return Ok(());
+    }
+    println!("  Recent sources, newest first:");
+    for source in &sources {
+        let what = match (&source.document, &source.import) {
+            (Some(title), _) => title.white().bold(),
+            (None, Some(name)) => format!("import from {name}").normal(),
+            (None, None) => source.source.normal(),
+        };
+        println!(
+            "  {} {} {}",
+            "•".blue(),
+            what,
+            format!("(tick {})", source.tick).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
 pub fn components() -> Result<()> {
     let colony = load_colony()?;
     let graph = colony.substrate().graph();
@@ -151,3 +202,104 @@ pub fn components() -> Result<()> {
 
     Ok(())
 }
+
+pub fn communities(algorithm: CommunityAlgorithm, top: usize) -> Result<()> {
+    let colony = load_colony()?;
+    let result = community::detect(colony.substrate().graph(), algorithm);
+
+    println!(
+        "{} {} communities among {} concepts (modularity {:.4}):",
+        "→".blue(),
+        result.num_communities.to_string().cyan(),
+        result.total_nodes.to_string().cyan(),
+        result.modularity
+    );
+    println!();
+
+    for community in result.communities.iter().take(top) {
+        println!(
+            "  {} {} {}",
+            format!("#{}", community.id).blue(),
+            community.top_labels.join(", ").white().bold(),
+            format!(
+                "({} concepts, internal weight {:.2}, modularity {:.4})",
+                community.size, community.internal_weight, community.modularity
+            )
+            .dimmed()
+        );
+    }
+    if result.num_communities > top {
+        println!(
+            "  {}",
+            format!("... and {} more", result.num_communities - top).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+pub fn regions() -> Result<()> {
+    let colony = load_colony()?;
+    let regions = colony.substrate().regions();
+    if regions.is_empty() {
+        println!("{} No regions defined.", "→".blue());
+        return Ok(());
+    }
+
+    println!(
+        "{} {} region(s):",
+        "→".blue(),
+        regions.len().to_string().cyan()
+    );
+    println!();
+
+    let graph = colony.substrate().graph();
+    let positions: Vec<Position> = graph
+        .all_nodes()
+        .iter()
+        .filter_map(|id| graph.get_node(id).map(|n| n.position))
+        .collect();
+    for region in regions {
+        let shape = match region.shape {
+            RegionShape::Rect(rect) => format!(
+                "rect ({:.1}, {:.1})–({:.1}, {:.1})",
+                rect.min.x, rect.min.y, rect.max.x, rect.max.y
+            ),
+            RegionShape::Circle { center, radius } => format!(
+                "circle at ({:.1}, {:.1}), radius {:.1}",
+                center.x, center.y, radius
+            ),
+        };
+        let policy = &region.policy;
+        let mut closed = Vec::new();
+        if !policy.allow_ingest {
+            closed.push("no ingest");
+        }
+        if !policy.allow_agent_entry {
+            closed.push("no agent entry");
+        }
+        println!(
+            "  {} {} {}",
+            region.name.white().bold(),
+            shape,
+            format!(
+                "({} concepts)",
+                positions.iter().filter(|p| region.contains(p)).count()
+            )
+            .dimmed()
+        );
+        println!(
+            "    decay ×{} edges, ×{} traces, ×{} signals{}",
+            policy.edge_decay_multiplier,
+            policy.trace_decay_multiplier,
+            policy.signal_decay_multiplier,
+            if closed.is_empty() {
+                String::new()
+            } else {
+                format!("; {}", closed.join(", "))
+            }
+        );
+    }
+
+    Ok(())
+}
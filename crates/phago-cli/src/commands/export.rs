@@ -3,10 +3,11 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
 use phago::prelude::*;
+use phago::runtime::export::{export_turtle, RdfConfig};
 use serde::Serialize;
 use std::path::Path;
 
-use crate::config::current_session_path;
+use crate::config::{current_session_path, Config};
 
 #[derive(Serialize)]
 struct ExportedGraph {
@@ -21,6 +22,9 @@ struct ExportedNode {
     label: String,
     node_type: String,
     access_count: u64,
+    /// Other names the concept goes by, e.g. synonyms of its label.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -48,8 +52,8 @@ pub fn run(output: &str, format: &str) -> Result<()> {
     // Load session
     println!("{} Loading session...", "→".blue());
     let state = load_session(&session_path)?;
-    let mut colony = Colony::new();
-    restore_into_colony(&mut colony, &state);
+    let mut colony = Colony::from_config(Config::load()?.to_runtime_config());
+    super::session::restore(&mut colony, &state);
 
     let graph = colony.substrate().graph();
     let all_nodes = graph.all_nodes();
@@ -65,6 +69,7 @@ pub fn run(output: &str, format: &str) -> Result<()> {
                 label: node.label.clone(),
                 node_type: format!("{:?}", node.node_type),
                 access_count: node.access_count,
+                aliases: graph.aliases(node_id),
             });
 
             // Get edges for this node
@@ -99,8 +104,11 @@ pub fn run(output: &str, format: &str) -> Result<()> {
             let content = serde_json::to_string_pretty(&export)?;
             std::fs::write(output_path, content)?;
         }
+        "turtle" | "ttl" => {
+            std::fs::write(output_path, export_turtle(&colony, RdfConfig::default()))?;
+        }
         _ => {
-            bail!("Unsupported format: {}. Use 'json'.", format);
+            bail!("Unsupported format: {}. Use 'json' or 'turtle'.", format);
         }
     }
 
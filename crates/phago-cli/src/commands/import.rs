@@ -0,0 +1,80 @@
+//! Import curated triples into the colony.
+
+use anyhow::{bail, Result};
+use colored::Colorize;
+use phago::prelude::*;
+use phago::runtime::import::{triples_from_csv, triples_from_turtle, CsvTripleConfig};
+use phago::runtime::session::save_session_with_agents;
+use std::path::Path;
+
+use crate::config::{current_session_path, data_dir, Config};
+
+pub fn run(file: &str, format: Option<&str>, csv: &CsvTripleConfig) -> Result<()> {
+    let path = Path::new(file);
+    if !path.is_file() {
+        bail!("File does not exist: {}", path.display());
+    }
+    // Without a format, go by the extension
+    let format = match format {
+        Some(format) => format.to_lowercase(),
+        None => match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("ttl") => "turtle".to_string(),
+            _ => "csv".to_string(),
+        },
+    };
+    let triples = match format.as_str() {
+        "csv" => triples_from_csv(path, csv)?,
+        "turtle" | "ttl" => triples_from_turtle(path)?,
+        _ => bail!("Unsupported format: {}. Use 'csv' or 'turtle'.", format),
+    };
+
+    let data = data_dir()?;
+    if !data.exists() {
+        std::fs::create_dir_all(&data)?;
+    }
+    let session_path = current_session_path()?;
+    let mut colony = Colony::from_config(Config::load()?.to_runtime_config());
+    let state = if session_path.exists() {
+        println!("{} Loading existing session...", "→".blue());
+        let state = load_session(&session_path)?;
+        super::session::restore(&mut colony, &state);
+        Some(state)
+    } else {
+        None
+    };
+
+    println!(
+        "{} Importing {} triples from {}...",
+        "→".blue(),
+        triples.triples.len().to_string().cyan(),
+        triples.source.cyan()
+    );
+    let report = colony.import_triples(&triples);
+    match &state {
+        Some(state) => save_session_with_agents(
+            &colony,
+            &session_path,
+            &state.metadata.files_indexed,
+            &state.agents,
+        )?,
+        None => save_session(&colony, &session_path, &[])?,
+    }
+
+    println!();
+    println!("{} Import complete!", "✓".green().bold());
+    println!(
+        "  Concepts: {} created, {} merged",
+        report.nodes_created.to_string().cyan(),
+        report.nodes_merged.to_string().cyan()
+    );
+    println!(
+        "  Edges: {} created, {} merged",
+        report.edges_created.to_string().cyan(),
+        report.edges_merged.to_string().cyan()
+    );
+    if report.skipped > 0 {
+        println!("  Skipped: {}", report.skipped.to_string().yellow());
+    }
+
+    Ok(())
+}
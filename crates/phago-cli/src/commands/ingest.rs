@@ -1,21 +1,27 @@
 //! Ingest documents into the colony.
 
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, Result};
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use phago::prelude::*;
+use phago::runtime::corpus::{extract_text, ExtractError};
 use std::path::Path;
 
-use crate::config::{current_session_path, data_dir, Config};
+use crate::config::{current_session_path, data_dir, Config, Overrides};
 
-pub fn run(path: &str, ticks: u64, extensions: &str, verbose: bool) -> Result<()> {
+pub fn run(path: &str, tags: &[String], flags: &Overrides, verbose: bool) -> Result<()> {
     let path = Path::new(path);
     if !path.exists() {
         bail!("Path does not exist: {}", path.display());
     }
 
-    // Load config
-    let config = Config::load()?;
+    // Load config, with the command-line flags on top
+    let config = Config::resolve(flags)?.config;
+    let ticks = config.ingest.ticks;
+    let dedup = DedupConfig {
+        policy: config.ingest.dedup,
+        threshold: config.ingest.dedup_threshold,
+    };
 
     // Ensure .phago directory exists
     let data = data_dir()?;
@@ -25,22 +31,25 @@ pub fn run(path: &str, ticks: u64, extensions: &str, verbose: bool) -> Result<()
 
     // Load or create colony
     let session_path = current_session_path()?;
-    let mut colony = if session_path.exists() {
+    let restored = session_path.exists();
+    let mut colony = if restored {
         println!("{} Loading existing session...", "→".blue());
         let state = load_session(&session_path)?;
-        let mut c = Colony::new();
-        restore_into_colony(&mut c, &state);
+        let mut c = Colony::from_config(config.to_runtime_config());
+        super::session::restore(&mut c, &state);
         c
     } else {
-        Colony::new()
+        Colony::from_config(config.to_runtime_config())
     };
 
     // Collect files to ingest
-    let ext_list: Vec<&str> = extensions.split(',').collect();
-    let files = collect_files(path, &ext_list)?;
+    let files = collect_files(path, &config.ingest.extensions)?;
 
     if files.is_empty() {
-        bail!("No files found with extensions: {}", extensions);
+        bail!(
+            "No files found with extensions: {}",
+            config.ingest.extensions.join(",")
+        );
     }
 
     println!(
@@ -58,20 +67,22 @@ pub fn run(path: &str, ticks: u64, extensions: &str, verbose: bool) -> Result<()
             .progress_chars("#>-"),
     );
 
-    for (i, file) in files.iter().enumerate() {
-        let content = std::fs::read_to_string(file)
-            .with_context(|| format!("Failed to read: {}", file.display()))?;
-
-        let title = file
-            .file_stem()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_else(|| format!("doc-{}", i));
-
-        // Position documents in a grid
-        let x = (i % 10) as f64;
-        let y = (i / 10) as f64;
+    let mut extracted = Vec::new();
+    let mut skipped = Vec::new();
+    for file in &files {
+        let doc = match extract_text(file) {
+            Ok(doc) => doc,
+            Err(e @ (ExtractError::Skipped(_) | ExtractError::Unsupported(_))) => {
+                skipped.push((file.clone(), e.to_string()));
+                pb.inc(1);
+                continue;
+            }
+            Err(ExtractError::Io(e)) => {
+                bail!("Failed to read {}: {}", file.display(), e);
+            }
+        };
 
-        colony.ingest_document(&title, &content, Position::new(x, y));
+        extracted.push(doc);
 
         if verbose {
             pb.set_message(format!("{}", file.file_name().unwrap().to_string_lossy()));
@@ -80,20 +91,66 @@ pub fn run(path: &str, ticks: u64, extensions: &str, verbose: bool) -> Result<()
     }
     pb.finish_with_message("done");
 
-    // Spawn digesters
-    let num_digesters = (files.len() / 3).max(1).min(config.colony.max_agents);
+    for (file, reason) in &skipped {
+        println!("{} {} ({})", "⚠".yellow(), file.display(), reason);
+    }
+    if extracted.is_empty() {
+        bail!("No documents could be extracted from {} files", files.len());
+    }
+
+    // Files ingested before are updated rather than added again;
+    // copies of other documents are handled by the dedup policy
+    let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+    let batch: Vec<BatchDocument> = extracted
+        .iter()
+        .enumerate()
+        .map(|(i, doc)| BatchDocument {
+            title: &doc.title,
+            content: &doc.content,
+            // Position documents in a grid
+            position: Position::new((i % 10) as f64, (i / 10) as f64),
+            tags: &tags,
+        })
+        .collect();
+    let report = colony.ingest_batch(&batch, &dedup);
+    print_duplicates(&report);
+    let ingested = report.ingested.len();
+
+    // Spawn the population, digesters first, within the agent limit
+    let population = &config.population;
+    let max_agents = config.colony.max_agents;
+    let num_digesters = (ingested / population.docs_per_digester)
+        .max(1)
+        .min(max_agents);
+    let num_sentinels = population.sentinels.min(max_agents - num_digesters);
+    let num_synthesizers = population
+        .synthesizers
+        .min(max_agents - num_digesters - num_sentinels);
     println!(
         "{} Spawning {} digesters...",
         "→".blue(),
         num_digesters.to_string().cyan()
     );
+    if num_sentinels + num_synthesizers > 0 {
+        println!(
+            "{} Spawning {} sentinels and {} synthesizers...",
+            "→".blue(),
+            num_sentinels.to_string().cyan(),
+            num_synthesizers.to_string().cyan()
+        );
+    }
 
-    for i in 0..num_digesters {
-        let x = (i % 10) as f64;
-        let y = (i / 10) as f64;
-        colony.spawn(Box::new(
-            Digester::new(Position::new(x, y)).with_max_idle(config.digester.max_idle),
-        ));
+    for i in 0..num_digesters + num_sentinels + num_synthesizers {
+        let position = Position::new((i % 10) as f64, (i / 10) as f64);
+        if i < num_digesters {
+            colony.spawn(Box::new(
+                Digester::new(position).with_max_idle(config.digester.max_idle),
+            ));
+        } else if i < num_digesters + num_sentinels {
+            colony.spawn(Box::new(Sentinel::new(position)));
+        } else {
+            colony.spawn(Box::new(Synthesizer::new(position)));
+        }
     }
 
     // Run simulation
@@ -111,8 +168,12 @@ pub fn run(path: &str, ticks: u64, extensions: &str, verbose: bool) -> Result<()
             .progress_chars("#>-"),
     );
 
-    for _ in 0..ticks {
+    let auto_save = config.persistence.auto_save_interval;
+    for tick in 1..=ticks {
         colony.tick();
+        if auto_save > 0 && tick % auto_save == 0 {
+            save_session(&colony, &session_path, &[])?;
+        }
         pb.inc(1);
     }
     pb.finish_with_message("done");
@@ -126,20 +187,57 @@ pub fn run(path: &str, ticks: u64, extensions: &str, verbose: bool) -> Result<()
     println!("{} Ingestion complete!", "✓".green().bold());
     println!("  Nodes: {}", stats.graph_nodes.to_string().cyan());
     println!("  Edges: {}", stats.graph_edges.to_string().cyan());
-    println!("  Documents: {}", files.len().to_string().cyan());
+    println!("  Documents: {}", ingested.to_string().cyan());
+    if !skipped.is_empty() {
+        println!("  Skipped: {}", skipped.len().to_string().yellow());
+    }
+    if report.duplicates() > 0 {
+        println!("  Duplicates: {}", report.duplicates().to_string().yellow());
+    }
+    super::replay::save(&colony, restored)?;
 
     Ok(())
 }
 
-fn collect_files(path: &Path, extensions: &[&str]) -> Result<Vec<std::path::PathBuf>> {
+fn print_duplicates(report: &IngestReport) {
+    let groups = [
+        ("skipped, copy of", &report.skipped),
+        ("merged into", &report.merged),
+        ("ingested anyway, copy of", &report.ingested_duplicates),
+    ];
+    for (action, entries) in groups {
+        for entry in entries {
+            let kind = match entry.duplicate_of.kind {
+                MatchKind::Exact => "exact".to_string(),
+                MatchKind::Near { similarity } => format!("{:.0}% similar", similarity * 100.0),
+            };
+            println!(
+                "{} {} {} {} ({})",
+                "⚠".yellow(),
+                entry.title,
+                action,
+                entry.duplicate_of.original_title.cyan(),
+                kind
+            );
+        }
+    }
+}
+
+pub(crate) fn collect_files(path: &Path, extensions: &[String]) -> Result<Vec<std::path::PathBuf>> {
     let mut files = Vec::new();
 
     if path.is_file() {
         files.push(path.to_path_buf());
     } else if path.is_dir() {
         for entry in walkdir(path)? {
-            let ext = entry.extension().and_then(|e| e.to_str()).unwrap_or("");
-            if extensions.contains(&ext) {
+            let ext = entry
+                .extension()
+                .and_then(|e| e.to_str())
+                .unwrap_or("")
+                .to_ascii_lowercase();
+            // "html" also picks up ".htm" files
+            if extensions.contains(&ext) || (ext == "htm" && extensions.iter().any(|e| e == "html"))
+            {
                 files.push(entry);
             }
         }
@@ -1,13 +1,26 @@
 //! CLI command implementations.
 
+pub mod agents;
+pub mod compact;
+pub mod config;
+pub mod doctor;
 pub mod explore;
 pub mod export;
+pub mod import;
 pub mod ingest;
 pub mod init;
 pub mod query;
+pub mod replay;
 pub mod run;
 pub mod session;
 pub mod stats;
+pub mod viz;
+
+#[cfg(feature = "bench")]
+pub mod bench;
+
+#[cfg(feature = "serve")]
+pub mod serve;
 
 #[cfg(feature = "distributed")]
 pub mod cluster;
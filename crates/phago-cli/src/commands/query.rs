@@ -1,54 +1,357 @@
 //! Query the knowledge graph.
 
 use anyhow::{bail, Result};
+use clap::ValueEnum;
 use colored::Colorize;
 use phago::prelude::*;
+use phago::runtime::session::{save_session_with_agents, GraphState};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
 
-use crate::config::current_session_path;
+use crate::config::{current_session_path, Config, Overrides};
 
-pub fn run(query: &str, max_results: usize, alpha: f64) -> Result<()> {
-    let session_path = current_session_path()?;
+/// How query results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Ranked results with their score breakdown, for reading
+    #[default]
+    Table,
+    /// One JSON document with every result's scores
+    Json,
+    /// One result per line: rank, label, final, TF-IDF and graph score
+    Tsv,
+}
 
+/// What to query and how.
+pub struct QueryArgs<'a> {
+    pub query: &'a str,
+    pub page: usize,
+    pub tags: &'a [String],
+    /// Attach source passages to each result.
+    pub passages: bool,
+}
+
+/// The JSON output. Field names and order are a stable schema; new fields
+/// are only ever added.
+#[derive(Debug, Serialize)]
+struct JsonOutput<'a> {
+    query: &'a str,
+    alpha: f64,
+    tags: &'a [String],
+    page: usize,
+    page_size: usize,
+    results: Vec<JsonResult>,
+    /// The page after this one, if there are more results.
+    next_page: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonResult {
+    rank: usize,
+    label: String,
+    final_score: f64,
+    tfidf_score: f64,
+    graph_score: f64,
+    /// Only with `--passages`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    passages: Option<Vec<JsonPassage>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonPassage {
+    title: String,
+    start: usize,
+    end: usize,
+    snippet: String,
+}
+
+/// A loaded session, queryable.
+struct Loaded {
+    colony: Colony,
+    state: GraphState,
+    alpha: f64,
+    page_size: usize,
+}
+
+/// One page of results, where it starts and whether another follows.
+struct Page {
+    results: Vec<HybridResult>,
+    first_rank: usize,
+    has_next: bool,
+}
+
+fn load(session_path: &Path, flags: &Overrides) -> Result<Loaded> {
     if !session_path.exists() {
         bail!("No session found. Run {} first.", "phago ingest".cyan());
     }
+    let config = Config::resolve(flags)?.config;
+    let state = load_session(session_path)?;
+    let mut colony = Colony::from_config(config.to_runtime_config());
+    super::session::restore(&mut colony, &state);
+    Ok(Loaded {
+        colony,
+        state,
+        alpha: config.query.default_alpha,
+        page_size: config.query.max_results,
+    })
+}
 
-    // Load session
-    let state = load_session(&session_path)?;
-    let mut colony = Colony::new();
-    restore_into_colony(&mut colony, &state);
+fn query_page(loaded: &Loaded, args: &QueryArgs) -> Result<Page> {
+    // Follow cursors to the requested page; the cache ranks once for all
+    // of them
+    let hybrid = HybridConfig::builder()
+        .alpha(loaded.alpha)
+        .max_results(loaded.page_size)
+        .tags(args.tags.to_vec())
+        .include_passages(args.passages)
+        .source(QuerySource::Cli)
+        .build()?;
 
-    // Run hybrid query
-    let config = HybridConfig {
-        alpha,
-        max_results,
-        candidate_multiplier: 3,
-    };
+    let cache = QueryCache::new(1);
+    let colony = &loaded.colony;
+    let mut current = hybrid_query_page_cached(colony, &cache, args.query, &hybrid, None)?;
+    for _ in 1..args.page {
+        match current.next_cursor.take() {
+            Some(cursor) => {
+                current =
+                    hybrid_query_page_cached(colony, &cache, args.query, &hybrid, Some(&cursor))?
+            }
+            None => {
+                current.results.clear();
+                break;
+            }
+        }
+    }
+    Ok(Page {
+        results: current.results,
+        first_rank: (args.page - 1) * loaded.page_size + 1,
+        has_next: current.next_cursor.is_some(),
+    })
+}
+
+/// Run the query once and print the results. Returns whether there were
+/// any.
+pub fn run(args: &QueryArgs, output: OutputFormat, flags: &Overrides) -> Result<bool> {
+    if args.page == 0 {
+        bail!("Pages are numbered from 1");
+    }
+    let session_path = current_session_path()?;
+    let mut loaded = load(&session_path, flags)?;
+    let page = query_page(&loaded, args)?;
+    touch_results(&mut loaded.colony, &page.results);
+
+    // Persist the logged query and the recalled concepts' access ticks
+    // alongside the graph
+    if loaded.colony.query_log().is_some() || !page.results.is_empty() {
+        save_session_with_agents(
+            &loaded.colony,
+            &session_path,
+            &loaded.state.metadata.files_indexed,
+            &loaded.state.agents,
+        )?;
+    }
+
+    match output {
+        OutputFormat::Table => print_table(args, &loaded, &page, None),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&json_output(args, &loaded, &page))?
+        ),
+        OutputFormat::Tsv => print!("{}", tsv_output(&page)),
+    }
+    Ok(!page.results.is_empty())
+}
 
-    let results = hybrid_query(&colony, query, &config);
+/// Assemble the query's passages into cited context and print it: the
+/// prompt text as a table, the passages and citations as JSON, or one
+/// passage per line as TSV. Returns whether any passage was assembled.
+pub fn assemble(
+    args: &QueryArgs,
+    config: &AssembleConfig,
+    output: OutputFormat,
+    flags: &Overrides,
+) -> Result<bool> {
+    let session_path = current_session_path()?;
+    let loaded = load(&session_path, flags)?;
+    let hybrid = HybridConfig::builder()
+        .alpha(loaded.alpha)
+        .max_results(loaded.page_size)
+        .tags(args.tags.to_vec())
+        .source(QuerySource::Cli)
+        .build()?;
+    let context = assemble_context_with(
+        &loaded.colony,
+        args.query,
+        &hybrid,
+        config,
+        &ApproxTokenCounter,
+    )?;
 
-    if results.is_empty() {
-        println!("{} No results found for: {}", "•".yellow(), query.cyan());
-        return Ok(());
+    match output {
+        OutputFormat::Table if context.passages.is_empty() => println!(
+            "{} No passages found for: {}",
+            "•".yellow(),
+            args.query.cyan()
+        ),
+        OutputFormat::Table => {
+            print!("{}", context.render());
+            println!();
+            println!(
+                "{} {} passages, ~{} of {} tokens",
+                "✓".green(),
+                context.passages.len().to_string().cyan(),
+                context.tokens_used,
+                context.token_budget
+            );
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&context)?),
+        OutputFormat::Tsv => print!("{}", tsv_context(&context)),
     }
+    Ok(!context.passages.is_empty())
+}
+
+/// Citation marker, title, score and text, tab-separated, one passage per
+/// line.
+fn tsv_context(context: &RagContext) -> String {
+    let clean = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    context
+        .passages
+        .iter()
+        .zip(&context.citations)
+        .map(|(p, c)| {
+            format!(
+                "{}\t{}\t{:.6}\t{}\n",
+                c.marker,
+                clean(&p.title),
+                p.score,
+                clean(&p.text)
+            )
+        })
+        .collect()
+}
 
+/// Re-run the query every `interval`, reloading the session whenever its
+/// file changes, and redraw the table with each score's change since the
+/// previous run. Runs until interrupted.
+///
+/// Watching only reads the session, so it never competes with a process
+/// writing it, and its queries are not logged.
+pub fn watch(args: &QueryArgs, interval: Duration, flags: &Overrides) -> Result<()> {
+    if args.page == 0 {
+        bail!("Pages are numbered from 1");
+    }
+    if interval.is_zero() {
+        bail!("The watch interval must be at least one second");
+    }
+    let session_path = current_session_path()?;
+    let mut loaded = load(&session_path, flags)?;
+    let mut modified = modified_at(&session_path);
+    let mut previous: Option<HashMap<String, f64>> = None;
+
+    loop {
+        let stamp = modified_at(&session_path);
+        if stamp != modified {
+            // A half-written file fails to load; keep the last good one
+            // and try again next time
+            if let Ok(reloaded) = load(&session_path, flags) {
+                loaded = reloaded;
+                modified = stamp;
+            }
+        }
+        let page = query_page(&loaded, args)?;
+
+        // Clear the screen and redraw from the top
+        print!("\x1B[2J\x1B[H");
+        print_table(args, &loaded, &page, previous.as_ref());
+        println!(
+            "{} tick {}, every {}s; Ctrl-C to stop",
+            "⟳".dimmed(),
+            loaded.state.metadata.tick,
+            interval.as_secs()
+        );
+        std::io::stdout().flush()?;
+        previous = Some(
+            page.results
+                .iter()
+                .map(|r| (r.label.clone(), r.final_score))
+                .collect(),
+        );
+        std::thread::sleep(interval);
+    }
+}
+
+fn modified_at(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Print results as a table. With `previous` scores, each result also
+/// shows how its score moved since then.
+fn print_table(
+    args: &QueryArgs,
+    loaded: &Loaded,
+    page: &Page,
+    previous: Option<&HashMap<String, f64>>,
+) {
+    if page.results.is_empty() {
+        if args.page > 1 {
+            println!(
+                "{} No results on page {} for: {}",
+                "•".yellow(),
+                args.page,
+                args.query.cyan()
+            );
+        } else {
+            println!(
+                "{} No results found for: {}",
+                "•".yellow(),
+                args.query.cyan()
+            );
+        }
+        return;
+    }
+
+    let scope = if args.tags.is_empty() {
+        String::new()
+    } else {
+        format!(", tags={}", args.tags.join(","))
+    };
     println!(
-        "{} Results for {} (alpha={}):",
+        "{} Results for {} (alpha={}{}):",
         "→".blue(),
-        query.cyan().bold(),
-        alpha
+        args.query.cyan().bold(),
+        loaded.alpha,
+        scope
     );
     println!();
 
-    for (i, result) in results.iter().enumerate() {
-        let rank = format!("{}.", i + 1);
+    for (i, result) in page.results.iter().enumerate() {
+        let rank = format!("{}.", page.first_rank + i);
         let score = format!("{:.3}", result.final_score);
+        let delta = match previous.map(|p| p.get(&result.label)) {
+            None => String::new(),
+            Some(None) => format!(" {}", "new".green().bold()),
+            Some(Some(before)) => {
+                let change = result.final_score - before;
+                if change.abs() < 5e-4 {
+                    String::new()
+                } else if change > 0.0 {
+                    format!(" {}", format!("+{change:.3}").green().bold())
+                } else {
+                    format!(" {}", format!("{change:.3}").red().bold())
+                }
+            }
+        };
 
         println!(
-            "  {} {} {}",
+            "  {} {} {}{}",
             rank.blue(),
             result.label.white().bold(),
-            format!("({})", score).dimmed()
+            format!("({})", score).dimmed(),
+            delta
         );
 
         // Show score breakdown
@@ -56,14 +359,193 @@ pub fn run(query: &str, max_results: usize, alpha: f64) -> Result<()> {
             "      TF-IDF: {:.3}  Graph: {:.3}",
             result.tfidf_score, result.graph_score
         );
+        for passage in &result.passages {
+            println!(
+                "      {} {}",
+                format!("[{}]", passage.title).dimmed(),
+                passage.snippet
+            );
+        }
     }
 
     println!();
     println!(
         "{} {} results",
         "✓".green(),
-        results.len().to_string().cyan()
+        page.results.len().to_string().cyan()
     );
+    if page.has_next {
+        println!(
+            "  More results: {}",
+            format!("--page {}", args.page + 1).cyan()
+        );
+    }
+}
+
+fn json_output<'a>(args: &'a QueryArgs, loaded: &Loaded, page: &Page) -> JsonOutput<'a> {
+    JsonOutput {
+        query: args.query,
+        alpha: loaded.alpha,
+        tags: args.tags,
+        page: args.page,
+        page_size: loaded.page_size,
+        results: page
+            .results
+            .iter()
+            .enumerate()
+            .map(|(i, r)| JsonResult {
+                rank: page.first_rank + i,
+                label: r.label.clone(),
+                final_score: r.final_score,
+                tfidf_score: r.tfidf_score,
+                graph_score: r.graph_score,
+                passages: args.passages.then(|| {
+                    r.passages
+                        .iter()
+                        .map(|p| JsonPassage {
+                            title: p.title.clone(),
+                            start: p.span.start,
+                            end: p.span.end,
+                            snippet: p.snippet.clone(),
+                        })
+                        .collect()
+                }),
+            })
+            .collect(),
+        next_page: page.has_next.then_some(args.page + 1),
+    }
+}
+
+/// Rank, label, final, TF-IDF and graph score, tab-separated, one result
+/// per line. Tabs and line breaks in labels become spaces.
+fn tsv_output(page: &Page) -> String {
+    page.results
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let label: String = r
+                .label
+                .chars()
+                .map(|c| {
+                    if matches!(c, '\t' | '\n' | '\r') {
+                        ' '
+                    } else {
+                        c
+                    }
+                })
+                .collect();
+            format!(
+                "{}\t{}\t{:.6}\t{:.6}\t{:.6}\n",
+                page.first_rank + i,
+                label,
+                r.final_score,
+                r.tfidf_score,
+                r.graph_score
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loaded() -> Loaded {
+        Loaded {
+            colony: Colony::new(),
+            state: GraphState {
+                nodes: Vec::new(),
+                edges: Vec::new(),
+                agents: Vec::new(),
+                metadata: phago::runtime::session::SessionMetadata {
+                    session_id: "test".to_string(),
+                    tick: 0,
+                    node_count: 0,
+                    edge_count: 0,
+                    agent_count: 0,
+                    format_version: phago::runtime::session::FORMAT_VERSION,
+                    files_indexed: Vec::new(),
+                    population: Vec::new(),
+                },
+                query_log: Vec::new(),
+                documents: Vec::new(),
+                embedding_storage: Default::default(),
+                regions: Vec::new(),
+                embedding_cache: Vec::new(),
+            },
+            alpha: 0.5,
+            page_size: 2,
+        }
+    }
+
+    fn page() -> Page {
+        let result = |label: &str, score: f64| HybridResult {
+            node_id: NodeId::from_seed(score.to_bits()),
+            label: label.to_string(),
+            tfidf_score: score,
+            graph_score: score / 2.0,
+            final_score: score * 0.75,
+            rerank_score: None,
+            passages: Vec::new(),
+        };
+        Page {
+            results: vec![result("membrane", 0.8), result("cell\twall", 0.4)],
+            first_rank: 3,
+            has_next: true,
+        }
+    }
+
+    #[test]
+    fn json_output_has_a_stable_schema() {
+        let tags = vec!["biology".to_string()];
+        let args = QueryArgs {
+            query: "cell membrane",
+            page: 2,
+            tags: &tags,
+            passages: false,
+        };
+        let json = serde_json::to_value(json_output(&args, &loaded(), &page())).unwrap();
+        let expected = serde_json::json!({
+            "query": "cell membrane",
+            "alpha": 0.5,
+            "tags": ["biology"],
+            "page": 2,
+            "page_size": 2,
+            "results": [
+                {
+                    "rank": 3,
+                    "label": "membrane",
+                    "final_score": 0.8 * 0.75,
+                    "tfidf_score": 0.8,
+                    "graph_score": 0.4,
+                },
+                {
+                    "rank": 4,
+                    "label": "cell\twall",
+                    "final_score": 0.4 * 0.75,
+                    "tfidf_score": 0.4,
+                    "graph_score": 0.2,
+                },
+            ],
+            "next_page": 3,
+        });
+        assert_eq!(json, expected);
 
-    Ok(())
+        // Passages appear only when asked for, even if there are none
+        let args = QueryArgs {
+            passages: true,
+            ..args
+        };
+        let json = serde_json::to_value(json_output(&args, &loaded(), &page())).unwrap();
+        assert_eq!(json["results"][0]["passages"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn tsv_output_is_one_result_per_line() {
+        assert_eq!(
+            tsv_output(&page()),
+            "3\tmembrane\t0.600000\t0.800000\t0.400000\n\
+             4\tcell wall\t0.300000\t0.400000\t0.200000\n"
+        );
+    }
 }
@@ -0,0 +1,75 @@
+//! Replay a recorded run up to a tick.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use phago::prelude::*;
+use phago::runtime::replay::Replay;
+use std::path::Path;
+
+use crate::config::replay_path;
+
+pub fn run(file: &str, until: Option<u64>, dump_snapshot: Option<&str>) -> Result<()> {
+    println!("{} Loading {}...", "→".blue(), file.cyan());
+    let replay = Replay::load(Path::new(file)).with_context(|| format!("Failed to read {file}"))?;
+    let last = replay.last_tick();
+    let until = until.unwrap_or(last);
+    if until > last {
+        println!(
+            "{} The recording ends at tick {}; replaying all of it",
+            "⚠".yellow(),
+            last
+        );
+    }
+
+    println!(
+        "{} Replaying {} steps up to tick {}...",
+        "→".blue(),
+        replay.steps.len().to_string().cyan(),
+        until.min(last).to_string().cyan()
+    );
+    let colony = replay.run_until(until);
+    let stats = colony.stats();
+
+    println!();
+    println!("{} Replayed to tick {}", "✓".green().bold(), stats.tick);
+    println!("  Agents: {}", stats.agents_alive.to_string().cyan());
+    println!("  Nodes: {}", stats.graph_nodes.to_string().cyan());
+    println!("  Edges: {}", stats.graph_edges.to_string().cyan());
+    println!(
+        "  Documents: {} ({} digested)",
+        stats.documents_total.to_string().cyan(),
+        stats.documents_digested.to_string().cyan()
+    );
+
+    if let Some(path) = dump_snapshot {
+        let json = serde_json::to_string_pretty(&colony.snapshot())?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write {path}"))?;
+        println!("  Snapshot written to {}", path.cyan());
+    }
+
+    Ok(())
+}
+
+/// Save what `colony` recorded (with `colony.record_replay = true`) to
+/// `.phago/replay.jsonl`. A colony restored from a session is not saved:
+/// the restored state is not part of the recording, so it would not
+/// replay.
+pub fn save(colony: &Colony, restored: bool) -> Result<()> {
+    let Some(replay) = colony.replay() else {
+        return Ok(());
+    };
+    if restored {
+        println!(
+            "{} Replay not saved: runs continuing a session cannot be replayed. \
+             Start a new session to record one.",
+            "⚠".yellow()
+        );
+        return Ok(());
+    }
+    let path = replay_path()?;
+    replay
+        .save(&path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("  Replay: {}", path.display().to_string().cyan());
+    Ok(())
+}
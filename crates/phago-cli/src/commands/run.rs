@@ -5,7 +5,7 @@ use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use phago::prelude::*;
 
-use crate::config::current_session_path;
+use crate::config::{current_session_path, Config};
 
 pub fn run(ticks: u64, verbose: bool) -> Result<()> {
     let session_path = current_session_path()?;
@@ -16,9 +16,10 @@ pub fn run(ticks: u64, verbose: bool) -> Result<()> {
 
     // Load session
     println!("{} Loading session...", "→".blue());
+    let config = Config::load()?;
     let state = load_session(&session_path)?;
-    let mut colony = Colony::new();
-    restore_into_colony(&mut colony, &state);
+    let mut colony = Colony::from_config(config.to_runtime_config());
+    super::session::restore(&mut colony, &state);
 
     let initial_stats = colony.stats();
     println!(
@@ -42,11 +43,15 @@ pub fn run(ticks: u64, verbose: bool) -> Result<()> {
             .progress_chars("#>-"),
     );
 
-    for _ in 0..ticks {
+    let auto_save = config.persistence.auto_save_interval;
+    for tick in 1..=ticks {
         let events = colony.tick();
         if verbose && !events.is_empty() {
             pb.println(format!("  {} events", events.len()));
         }
+        if auto_save > 0 && tick % auto_save == 0 {
+            save_session(&colony, &session_path, &[])?;
+        }
         pb.inc(1);
     }
     pb.finish_with_message("done");
@@ -0,0 +1,244 @@
+//! Serve one colony to the web dashboard and to MCP clients at once.
+//!
+//! The dashboard listens on a port while the MCP server speaks over
+//! stdio, so stdout belongs to the MCP protocol and everything here is
+//! reported on stderr. Both go through one colony runner: their requests
+//! are served in turn, and the events of MCP requests reach the
+//! dashboard's WebSocket clients too. MCP clients are not notified of
+//! changes; they see them on their next call.
+//!
+//! The dashboard only answers requests carrying one of its tokens, given
+//! with `--token` or as `web.tokens` in phago.toml (or `PHAGO_WEB_TOKENS`);
+//! `--no-auth` serves it without.
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use colored::Colorize;
+use phago::agents::serialize::SerializedAgent;
+use phago::prelude::*;
+use phago::runtime::colony::ColonyConfig;
+use phago::runtime::session::save_session_with_agents;
+use phago_mcp::tools::PhagoTools;
+use phago_mcp::worker::{ColonyHandle, DEFAULT_SPACE};
+use phago_web::{AppState, AuthTokens, ColonyStore, Readiness, RecordingConfig};
+use rmcp::{transport::stdio, ServiceExt};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::{current_session_path, Config};
+
+/// Which interface refuses to change the colony.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReadOnly {
+    /// The dashboard's ingest, tick and run endpoints answer 403
+    Web,
+    /// The MCP remember, connect and annotate tools fail
+    Mcp,
+}
+
+/// Who may use the dashboard.
+#[derive(Debug, Clone, Default)]
+pub struct Access {
+    /// Tokens given on the command line, written `ROLE:TOKEN`; the
+    /// configured ones are used if there are none.
+    pub tokens: Vec<String>,
+    /// Serve every request without a token.
+    pub no_auth: bool,
+}
+
+/// The project session as the colony's store.
+struct SessionStore {
+    path: PathBuf,
+    config: ColonyConfig,
+    /// Carried over from the loaded session into every save.
+    files_indexed: Vec<String>,
+    agents: Vec<SerializedAgent>,
+}
+
+impl ColonyStore for SessionStore {
+    fn load(&mut self) -> Result<Colony> {
+        let mut colony = Colony::from_config(self.config.clone());
+        if self.path.exists() {
+            let state = load_session(&self.path)?;
+            super::session::restore(&mut colony, &state);
+            self.files_indexed = state.metadata.files_indexed;
+            self.agents = state.agents;
+        }
+        Ok(colony)
+    }
+
+    fn save(&mut self, colony: &Colony) -> Result<PathBuf> {
+        save_session_with_agents(colony, &self.path, &self.files_indexed, &self.agents)?;
+        Ok(self.path.clone())
+    }
+}
+
+pub fn run(
+    port: u16,
+    host: &str,
+    db: Option<String>,
+    read_only: Option<ReadOnly>,
+    save_interval: u64,
+    access: Access,
+) -> Result<()> {
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(serve(port, host, db, read_only, save_interval, access))
+}
+
+async fn serve(
+    port: u16,
+    host: &str,
+    db: Option<String>,
+    read_only: Option<ReadOnly>,
+    save_interval: u64,
+    access: Access,
+) -> Result<()> {
+    let config = Config::load()?;
+    let tokens = if access.tokens.is_empty() {
+        &config.web.tokens
+    } else {
+        &access.tokens
+    };
+    let tokens = AuthTokens::parse(tokens).map_err(anyhow::Error::msg)?;
+    if tokens.is_empty() && !access.no_auth {
+        bail!(
+            "No dashboard tokens configured: pass --token ROLE:TOKEN, set web.tokens in phago.toml, or pass --no-auth to serve without authentication"
+        );
+    }
+
+    let state = match &db {
+        Some(db) => AppState::with_query_cache(Some(db.clone()), RecordingConfig::default(), 0)?,
+        None => {
+            let path = current_session_path()?;
+            if !path.exists() {
+                eprintln!(
+                    "{} No session yet; {} will be created on save",
+                    "→".blue(),
+                    path.display()
+                );
+            }
+            let store = SessionStore {
+                path,
+                config: config.to_runtime_config(),
+                files_indexed: Vec::new(),
+                agents: Vec::new(),
+            };
+            AppState::with_store(store, RecordingConfig::default(), 0)?
+        }
+    }
+    .with_read_only(read_only == Some(ReadOnly::Web));
+    let state = if access.no_auth {
+        eprintln!(
+            "{} Authentication is disabled (--no-auth): anyone who can reach http://{}:{} can change this colony",
+            "⚠".yellow().bold(),
+            host,
+            port
+        );
+        state
+    } else {
+        state.with_auth(tokens)
+    };
+
+    while state.readiness() == Readiness::Restoring {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    if state.readiness() == Readiness::Failed {
+        bail!("Failed to load the colony");
+    }
+    let stats = state.stats().await;
+    eprintln!(
+        "{} Loaded: {} nodes, {} edges",
+        "→".blue(),
+        stats.graph_nodes.to_string().cyan(),
+        stats.graph_edges.to_string().cyan()
+    );
+
+    // Other MCP spaces are separate colonies, kept next to the database
+    let handle = ColonyHandle::spawn(db)
+        .with_read_only(read_only == Some(ReadOnly::Mcp))
+        .with_shared_space(DEFAULT_SPACE, state.shared_colony());
+    let mcp = tokio::spawn(async move {
+        match PhagoTools::new(handle).serve(stdio()).await {
+            Ok(service) => {
+                let _ = service.waiting().await;
+                eprintln!("{} MCP client disconnected", "→".blue());
+            }
+            Err(e) => eprintln!("{} MCP server failed: {e}", "✗".red()),
+        }
+    });
+
+    let saver = (save_interval > 0).then(|| {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let period = Duration::from_secs(save_interval);
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = state.save().await {
+                    eprintln!("{} Auto-save failed: {e}", "✗".red());
+                }
+            }
+        })
+    });
+
+    let listener = tokio::net::TcpListener::bind((host, port)).await?;
+    eprintln!(
+        "{} Dashboard on http://{}, MCP on stdio{}",
+        "✓".green().bold(),
+        listener.local_addr()?,
+        match read_only {
+            Some(ReadOnly::Web) => " (dashboard read-only)",
+            Some(ReadOnly::Mcp) => " (MCP read-only)",
+            None => "",
+        }
+    );
+    let app = phago_web::routes::create_router(state.clone())
+        .into_make_service_with_connect_info::<std::net::SocketAddr>();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(state.clone()))
+        .await?;
+
+    mcp.abort();
+    if let Some(saver) = saver {
+        saver.abort();
+    }
+    let report = state.shutdown_and_save().await?;
+    if let Some(path) = &report.saved_to {
+        eprintln!(
+            "{} Saved {} nodes and {} edges at tick {} to {}",
+            "✓".green().bold(),
+            report.nodes,
+            report.edges,
+            report.tick,
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Resolve on Ctrl-C or SIGTERM, then tell the colony worker to wind down.
+async fn shutdown_signal(state: AppState) {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    eprintln!("{} Shutting down...", "→".blue());
+    state.begin_shutdown();
+}
@@ -3,8 +3,18 @@
 use anyhow::{bail, Context, Result};
 use colored::Colorize;
 use phago::prelude::*;
+use phago::runtime::bundle::{Bundle, SESSION_ENTRY};
+use phago::runtime::session::{
+    load_session_with, LoadMode, LoadReport, RestoreReport, SessionError, FORMAT_VERSION,
+};
+use std::path::Path;
 
-use crate::config::{current_session_path, sessions_dir};
+use crate::config::{current_session_path, find_config_file, sessions_dir, Config};
+
+/// Bundle entry holding the project's phago.toml.
+const PROJECT_CONFIG_ENTRY: &str = "phago.toml";
+/// Bundle entry holding the vector store dump, one record per line.
+const VECTORS_ENTRY: &str = "vectors.jsonl";
 
 pub fn save(name: &str) -> Result<()> {
     let current_path = current_session_path()?;
@@ -27,7 +37,7 @@ pub fn save(name: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn load(name: &str) -> Result<()> {
+pub fn load(name: &str, best_effort: bool) -> Result<()> {
     let sessions = sessions_dir()?;
     let session_path = sessions.join(format!("{}.json", name));
 
@@ -36,16 +46,35 @@ pub fn load(name: &str) -> Result<()> {
     }
 
     // Load the named session to verify it's valid
-    let state = load_session(&session_path)?;
+    let mode = if best_effort {
+        LoadMode::BestEffort
+    } else {
+        LoadMode::Migrate
+    };
+    let (state, report) = match load_session_with(&session_path, mode) {
+        Ok(loaded) => loaded,
+        Err(e @ SessionError::UnsupportedVersion { .. }) => bail!(
+            "{}\nRetry with {} to load what this version understands.",
+            e,
+            format!("phago session load {} --best-effort", name).cyan()
+        ),
+        Err(e) => return Err(e).with_context(|| format!("Failed to load session: {}", name)),
+    };
 
-    // Copy to current session
+    // Make it the current session, in the current format if it was not
     let current_path = current_session_path()?;
-    std::fs::copy(&session_path, &current_path)
-        .with_context(|| format!("Failed to load session: {}", name))?;
+    if report.migrated_from.is_empty() && report.skipped.is_empty() {
+        std::fs::copy(&session_path, &current_path)
+            .with_context(|| format!("Failed to load session: {}", name))?;
+    } else {
+        std::fs::write(&current_path, serde_json::to_string_pretty(&state)?)
+            .with_context(|| format!("Failed to load session: {}", name))?;
+    }
+    print_load_report(&report);
 
     // Restore to get stats
-    let mut colony = Colony::new();
-    restore_into_colony(&mut colony, &state);
+    let mut colony = Colony::from_config(Config::load()?.to_runtime_config());
+    restore(&mut colony, &state);
     let stats = colony.stats();
 
     println!("{} Session loaded: {}", "✓".green().bold(), name.cyan());
@@ -55,6 +84,62 @@ pub fn load(name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Restore `state` into `colony`, saying what the restore had to do.
+pub fn restore(colony: &mut Colony, state: &GraphState) {
+    print_restore_report(&restore_into_colony(colony, state, None));
+}
+
+/// Say what restoring a session had to do, on stderr to keep it out of
+/// output meant for other programs.
+fn print_restore_report(report: &RestoreReport) {
+    if let Some(labels) = report.term_index_rebuilt {
+        eprintln!("Rebuilt term index over {} node label(s).", labels);
+    }
+    let integrity = &report.integrity;
+    if !integrity.is_clean() {
+        eprintln!(
+            "{} Restored graph has {} integrity issue(s), see {}:",
+            "!".yellow().bold(),
+            integrity.issues.len(),
+            "phago doctor".cyan()
+        );
+        for issue in integrity.issues.iter().take(5) {
+            eprintln!("  {}", issue);
+        }
+    }
+}
+
+/// Say what loading a session changed, if anything.
+fn print_load_report(report: &LoadReport) {
+    if !report.migrated_from.is_empty() {
+        println!(
+            "{} Migrated from session format version {} to {}",
+            "•".yellow(),
+            report.found_version,
+            FORMAT_VERSION
+        );
+    }
+    if report.found_version > FORMAT_VERSION {
+        println!(
+            "{} Written in session format version {}, newer than this build's {}",
+            "!".yellow().bold(),
+            report.found_version,
+            FORMAT_VERSION
+        );
+    }
+    if !report.unknown_fields.is_empty() {
+        println!(
+            "{} Ignored {} unknown field(s): {}",
+            "!".yellow().bold(),
+            report.unknown_fields.len(),
+            report.unknown_fields.join(", ")
+        );
+    }
+    for skipped in &report.skipped {
+        println!("{} Skipped {}", "!".yellow().bold(), skipped);
+    }
+}
+
 pub fn list() -> Result<()> {
     let sessions = sessions_dir()?;
 
@@ -63,6 +148,7 @@ pub fn list() -> Result<()> {
         return Ok(());
     }
 
+    let config = Config::load()?;
     let mut found = false;
     println!("{} Saved sessions:", "→".blue());
     println!();
@@ -79,8 +165,8 @@ pub fn list() -> Result<()> {
 
             // Try to load and get stats
             if let Ok(state) = load_session(&path) {
-                let mut colony = Colony::new();
-                restore_into_colony(&mut colony, &state);
+                let mut colony = Colony::from_config(config.to_runtime_config());
+                restore_into_colony(&mut colony, &state, None);
                 let stats = colony.stats();
 
                 println!(
@@ -101,3 +187,164 @@ pub fn list() -> Result<()> {
 
     Ok(())
 }
+
+pub fn export(name: &str, out: &str, vectors: bool) -> Result<()> {
+    let session_path = sessions_dir()?.join(format!("{}.json", name));
+    if !session_path.exists() {
+        bail!(
+            "Session not found: {}. Save it first with {}.",
+            name,
+            format!("phago session save {}", name).cyan()
+        );
+    }
+
+    let state = load_session(&session_path)?;
+    let config = Config::load()?;
+    let mut bundle = Bundle::from_session(name, &state, &config.to_runtime_config())?;
+    if let Some(config_path) = find_config_file() {
+        let toml = std::fs::read(&config_path)
+            .with_context(|| format!("Failed to read config: {}", config_path.display()))?;
+        bundle.insert(PROJECT_CONFIG_ENTRY, toml);
+    }
+    if vectors {
+        let dump = dump_vectors(&config)?;
+        println!("  Vectors: {}", dump.len().to_string().cyan());
+        let lines: Vec<String> = dump
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<_, _>>()?;
+        bundle.insert(VECTORS_ENTRY, lines.join("\n").into_bytes());
+    }
+
+    let manifest = bundle
+        .save(Path::new(out))
+        .with_context(|| format!("Failed to write bundle: {}", out))?;
+
+    println!("{} Session exported: {}", "✓".green().bold(), out.cyan());
+    println!("  Nodes: {}", state.nodes.len().to_string().cyan());
+    println!("  Edges: {}", state.edges.len().to_string().cyan());
+    println!("  Documents: {}", state.documents.len().to_string().cyan());
+    println!("  Entries: {}", manifest.entries.len().to_string().cyan());
+
+    Ok(())
+}
+
+pub fn import(path: &str, force: bool) -> Result<()> {
+    let bundle = Bundle::load(Path::new(path))
+        .with_context(|| format!("Failed to read bundle: {}", path))?;
+    let state = bundle.session()?;
+    let name = bundle.name().to_string();
+
+    let sessions = sessions_dir()?;
+    let session_path = sessions.join(format!("{}.json", name));
+    let current_path = current_session_path()?;
+    let config_path = find_config_file().unwrap_or_else(|| Path::new("phago.toml").to_path_buf());
+    let bundled_config = bundle.entry(PROJECT_CONFIG_ENTRY);
+
+    if !force {
+        let mut conflicts = Vec::new();
+        if current_path.exists() && !load_session(&current_path)?.nodes.is_empty() {
+            conflicts.push("an active session".to_string());
+        }
+        if session_path.exists() {
+            conflicts.push(format!("a saved session named '{}'", name));
+        }
+        if let Some(bundled) = bundled_config {
+            if config_path.exists() && std::fs::read(&config_path)? != bundled {
+                conflicts.push(format!("a different {}", config_path.display()));
+            }
+        }
+        if !conflicts.is_empty() {
+            bail!(
+                "Project is not empty: it has {}. Use {} to overwrite.",
+                conflicts.join(", "),
+                "--force".cyan()
+            );
+        }
+    }
+
+    // Write the session exactly as bundled, as both the named and active one
+    let session = bundle.entry(SESSION_ENTRY).unwrap_or_default();
+    std::fs::create_dir_all(&sessions)?;
+    std::fs::write(&session_path, session)
+        .with_context(|| format!("Failed to write session: {}", name))?;
+    std::fs::write(&current_path, session)
+        .with_context(|| format!("Failed to write session: {}", current_path.display()))?;
+    if let Some(bundled) = bundled_config {
+        std::fs::write(&config_path, bundled)
+            .with_context(|| format!("Failed to write config: {}", config_path.display()))?;
+    }
+
+    println!("{} Session imported: {}", "✓".green().bold(), name.cyan());
+    println!("  Nodes: {}", state.nodes.len().to_string().cyan());
+    println!("  Edges: {}", state.edges.len().to_string().cyan());
+    println!("  Documents: {}", state.documents.len().to_string().cyan());
+
+    if let Some(dump) = bundle.entry(VECTORS_ENTRY) {
+        let restored = restore_vectors(&Config::load()?, dump)?;
+        println!("  Vectors: {}", restored.to_string().cyan());
+    }
+
+    Ok(())
+}
+
+/// Every record in the configured vector store.
+#[cfg(feature = "vectors")]
+fn dump_vectors(config: &Config) -> Result<Vec<phago_vectors::VectorRecord>> {
+    use futures::TryStreamExt;
+
+    let Some(store_config) = config.vectors.clone() else {
+        bail!("No vector store configured. Add a [vectors] section to phago.toml.");
+    };
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let store = phago_vectors::create_store(store_config).await?;
+        let records = store.export_all().try_collect().await?;
+        Ok(records)
+    })
+}
+
+#[cfg(not(feature = "vectors"))]
+fn dump_vectors(_config: &Config) -> Result<Vec<serde_json::Value>> {
+    bail!("This build has no vector store support. Rebuild with the `vectors` feature.")
+}
+
+/// Upsert a bundled vector dump into the configured store, creating its
+/// collection if needed. Returns the number of records restored.
+#[cfg(feature = "vectors")]
+fn restore_vectors(config: &Config, dump: &[u8]) -> Result<usize> {
+    let records: Vec<phago_vectors::VectorRecord> = std::str::from_utf8(dump)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()
+        .context("Bundled vector dump is corrupt")?;
+    let Some(store_config) = config.vectors.clone() else {
+        println!(
+            "  {} Skipped {} vectors: no [vectors] section in phago.toml.",
+            "•".yellow(),
+            records.len()
+        );
+        return Ok(0);
+    };
+
+    let count = records.len();
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let store = phago_vectors::create_store(store_config).await?;
+        for batch in records.chunks(100) {
+            store.upsert(batch.to_vec()).await?;
+        }
+        Ok::<_, phago_vectors::VectorError>(())
+    })?;
+    Ok(count)
+}
+
+#[cfg(not(feature = "vectors"))]
+fn restore_vectors(_config: &Config, _dump: &[u8]) -> Result<usize> {
+    println!(
+        "  {} Skipped bundled vectors: this build has no vector store support.",
+        "•".yellow()
+    );
+    Ok(0)
+}
@@ -4,7 +4,7 @@ use anyhow::{bail, Result};
 use colored::Colorize;
 use phago::prelude::*;
 
-use crate::config::current_session_path;
+use crate::config::{current_session_path, Config};
 
 pub fn run() -> Result<()> {
     let session_path = current_session_path()?;
@@ -15,8 +15,8 @@ pub fn run() -> Result<()> {
 
     // Load session
     let state = load_session(&session_path)?;
-    let mut colony = Colony::new();
-    restore_into_colony(&mut colony, &state);
+    let mut colony = Colony::from_config(Config::load()?.to_runtime_config());
+    super::session::restore(&mut colony, &state);
 
     let stats = colony.stats();
     let graph = colony.substrate().graph();
@@ -118,3 +118,60 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Print aggregates of the session's query log.
+pub fn queries(top: usize) -> Result<()> {
+    let session_path = current_session_path()?;
+
+    if !session_path.exists() {
+        bail!("No session found. Run {} first.", "phago ingest".cyan());
+    }
+
+    let state = load_session(&session_path)?;
+    if state.query_log.is_empty() {
+        println!(
+            "{} No logged queries. Set {} in phago.toml to record them.",
+            "•".yellow(),
+            "query.log = true".cyan()
+        );
+        return Ok(());
+    }
+    let log = QueryLog::from_entries(state.query_log);
+
+    println!("{}", "Phago Query Log".white().bold());
+    println!("{}", "═".repeat(40).dimmed());
+    println!();
+    println!("  Logged queries:    {}", log.len().to_string().cyan());
+    println!();
+
+    println!("{}", "Top Queries".blue().bold());
+    for (query, count) in log.top_queries(top) {
+        println!("  {:>5}  {}", count.to_string().cyan(), query);
+    }
+    println!();
+
+    let zero = log.zero_result_queries();
+    println!(
+        "{} ({})",
+        "Zero-Result Queries".blue().bold(),
+        zero.len().to_string().yellow()
+    );
+    for entry in zero.iter().rev().take(top) {
+        println!(
+            "  {} {}",
+            format!("[tick {}, {}]", entry.tick, entry.source.as_str()).dimmed(),
+            entry.text
+        );
+    }
+    println!();
+
+    println!("{}", "Most Traversed Edges".blue().bold());
+    for ((from, to), count) in log.most_traversed_edges(top) {
+        println!("  {:>5}  {} — {}", count.to_string().cyan(), from, to);
+    }
+
+    println!();
+    println!("{}", "═".repeat(40).dimmed());
+
+    Ok(())
+}
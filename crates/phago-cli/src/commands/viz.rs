@@ -0,0 +1,38 @@
+//! Render recorded snapshots as an HTML visualization.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use phago_viz::VizOptions;
+use std::path::Path;
+
+pub fn run(
+    snapshots: &str,
+    events: Option<&str>,
+    output: &str,
+    options: &VizOptions,
+) -> Result<()> {
+    println!("{} Loading {}...", "→".blue(), snapshots.cyan());
+    let page = phago_viz::render_files(Path::new(snapshots), events.map(Path::new), options)
+        .with_context(|| format!("Failed to render {snapshots}"))?;
+    page.write(Path::new(output))
+        .with_context(|| format!("Failed to write {output}"))?;
+
+    println!();
+    println!(
+        "{} Visualization written to {}",
+        "✓".green().bold(),
+        output.cyan()
+    );
+    match &page.external_data {
+        None => println!("  Open it in a browser; no server needed."),
+        Some(data) => {
+            println!(
+                "  The recording was too large to embed and was written to {} alongside it.",
+                data.file_name.cyan()
+            );
+            println!("  Serve the directory over HTTP (e.g. `python3 -m http.server`) to view it.");
+        }
+    }
+
+    Ok(())
+}
@@ -1,15 +1,66 @@
 //! Configuration management for Phago CLI.
+//!
+//! A project is configured by `phago.toml`, found in the working directory
+//! or one of its parents. Each section is optional and every key has a
+//! default:
+//!
+//! | Section | Configures |
+//! |---------|------------|
+//! | `[colony]` | Agent limits, plus any runtime colony setting not covered below (`weight_update`, `backend`, `emerge`, `deferred_wiring`, ...) |
+//! | `[digester]` | Digesters spawned by `phago ingest` |
+//! | `[population]` | How many agents of each kind `phago ingest` spawns |
+//! | `[ingest]` | Default file extensions, ticks and deduplication of `phago ingest` |
+//! | `[query]` | Default alpha and page size of `phago query`, query logging |
+//! | `[decay]` | Signal, trace and edge decay |
+//! | `[semantic]` | Embedding-based wiring |
+//! | `[tokenizer]` | Stopword language, minimum term length, identifiers |
+//! | `[synonyms]` | Synonym dictionary and query expansion weight |
+//! | `[vectors]` | Vector store backend (with the `vectors` feature) |
+//! | `[persistence]` | Active session file and auto-save interval |
+//! | `[web]` | Access tokens of the dashboard `phago serve` starts |
+//!
+//! Environment variables and command-line flags override the file; see
+//! [`layers`] for the order and naming.
+
+mod layers;
+
+pub use layers::{Overrides, Resolved, Source};
 
 use anyhow::{Context, Result};
+use phago::prelude::DedupPolicy;
+use phago::runtime::synonyms::{SynonymConfig, SynonymError, SynonymMap};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+/// Runtime colony settings configured in a section other than `[colony]`.
+const MOVED_SETTINGS: &[(&str, &str)] = &[
+    ("signal_decay_rate", "decay"),
+    ("signal_removal_threshold", "decay"),
+    ("trace_decay_rate", "decay"),
+    ("trace_removal_threshold", "decay"),
+    ("edge_decay_rate", "decay"),
+    ("edge_prune_threshold", "decay"),
+    ("staleness_factor", "decay"),
+    ("maturation_ticks", "decay"),
+    ("max_edge_degree", "decay"),
+    ("semantic_wiring", "semantic"),
+    ("query_log", "query"),
+    ("tokenizer", "tokenizer"),
+    ("synonyms", "synonyms"),
+];
+
 /// Phago project configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
     #[serde(default)]
     pub colony: ColonyConfig,
     #[serde(default)]
+    pub population: PopulationConfig,
+    #[serde(default)]
+    pub ingest: IngestConfig,
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+    #[serde(default)]
     pub digester: DigesterConfig,
     #[serde(default)]
     pub wiring: WiringConfig,
@@ -19,6 +70,18 @@ pub struct Config {
     pub decay: DecayConfig,
     #[serde(default)]
     pub semantic: SemanticConfig,
+    #[serde(default)]
+    pub web: WebConfig,
+    /// How documents and queries are split into terms (stopword language,
+    /// minimum length, identifier handling).
+    #[serde(default)]
+    pub tokenizer: phago::core::tokenize::TokenizerConfig,
+    #[serde(default)]
+    pub synonyms: SynonymsConfig,
+    /// Vector store dumped into and restored from session bundles.
+    #[cfg(feature = "vectors")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vectors: Option<phago_vectors::VectorStoreConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +90,103 @@ pub struct ColonyConfig {
     pub tick_rate: u64,
     #[serde(default = "default_max_agents")]
     pub max_agents: usize,
+    /// Runtime colony settings, by their `ColonyConfig` field names.
+    #[serde(flatten)]
+    pub runtime: toml::Table,
+}
+
+/// Agents spawned by `phago ingest`, at most `colony.max_agents` in all.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PopulationConfig {
+    /// One digester per this many ingested documents, at least one.
+    #[serde(default = "default_docs_per_digester")]
+    pub docs_per_digester: usize,
+    #[serde(default)]
+    pub sentinels: usize,
+    #[serde(default)]
+    pub synthesizers: usize,
+}
+
+/// Defaults of `phago ingest`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestConfig {
+    /// File extensions to include, without the dot.
+    #[serde(default = "default_extensions")]
+    pub extensions: Vec<String>,
+    /// Ticks to run after ingesting.
+    #[serde(default = "default_ingest_ticks")]
+    pub ticks: u64,
+    /// What to do with duplicates of already ingested documents.
+    #[serde(default)]
+    pub dedup: DedupPolicy,
+    /// Similarity (0-1) at which documents count as near duplicates.
+    #[serde(default = "default_dedup_threshold")]
+    pub dedup_threshold: f64,
+}
+
+/// Where sessions are kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistenceConfig {
+    /// Active session file, relative to the working directory.
+    #[serde(default = "default_session_path")]
+    pub session_path: PathBuf,
+    /// Save the session every this many ticks while running; 0 saves only
+    /// once the command is done.
+    #[serde(default)]
+    pub auto_save_interval: u64,
+}
+
+/// Access to the dashboard `phago serve` starts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WebConfig {
+    /// Tokens the dashboard accepts, written `ROLE:TOKEN` with role `read`
+    /// or `write`. A comma-separated string also works, as in
+    /// `PHAGO_WEB_TOKENS=read:abc,write:xyz`.
+    #[serde(default, deserialize_with = "list_or_commas")]
+    pub tokens: Vec<String>,
+}
+
+/// A list of strings, or one string of comma-separated items.
+fn list_or_commas<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ListOrCommas {
+        List(Vec<String>),
+        Commas(String),
+    }
+    Ok(match ListOrCommas::deserialize(d)? {
+        ListOrCommas::List(items) => items,
+        ListOrCommas::Commas(items) => items
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect(),
+    })
+}
+
+/// Synonyms concepts are canonicalized from and queries expanded with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SynonymsConfig {
+    /// Dictionary file, relative to the working directory: TSV (a
+    /// canonical term and its aliases per line) or, ending in `.json`, an
+    /// object from canonical terms to alias lists. None by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// How much a match through a synonym counts against a match of the
+    /// term as typed, in (0, 1].
+    #[serde(default = "default_expansion_weight")]
+    pub expansion_weight: f64,
+}
+
+impl SynonymsConfig {
+    /// The dictionary at `path`, or an empty one without a path.
+    fn load(&self) -> std::result::Result<SynonymMap, SynonymError> {
+        match &self.path {
+            Some(path) => SynonymMap::load(path),
+            None => Ok(SynonymMap::default()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +213,9 @@ pub struct QueryConfig {
     pub default_alpha: f64,
     #[serde(default = "default_max_results")]
     pub max_results: usize,
+    /// Record queries in the session's query log (see `phago stats --queries`).
+    #[serde(default)]
+    pub log: bool,
 }
 
 /// Configuration for signal and trace decay.
@@ -117,6 +280,24 @@ fn default_alpha() -> f64 {
 fn default_max_results() -> usize {
     10
 }
+fn default_expansion_weight() -> f64 {
+    phago::runtime::synonyms::DEFAULT_EXPANSION_WEIGHT
+}
+fn default_docs_per_digester() -> usize {
+    3
+}
+fn default_extensions() -> Vec<String> {
+    vec!["txt".to_string(), "md".to_string()]
+}
+fn default_ingest_ticks() -> u64 {
+    30
+}
+fn default_dedup_threshold() -> f64 {
+    0.8
+}
+fn default_session_path() -> PathBuf {
+    PathBuf::from(".phago").join("current.json")
+}
 
 // Decay config defaults (match Colony::new() hardcoded values)
 fn default_signal_decay_rate() -> f64 {
@@ -155,24 +336,51 @@ fn default_similarity_influence() -> f64 {
     0.5
 }
 
-impl Default for Config {
+impl Default for ColonyConfig {
+    fn default() -> Self {
+        Self {
+            tick_rate: default_tick_rate(),
+            max_agents: default_max_agents(),
+            runtime: toml::Table::new(),
+        }
+    }
+}
+
+impl Default for SynonymsConfig {
     fn default() -> Self {
         Self {
-            colony: ColonyConfig::default(),
-            digester: DigesterConfig::default(),
-            wiring: WiringConfig::default(),
-            query: QueryConfig::default(),
-            decay: DecayConfig::default(),
-            semantic: SemanticConfig::default(),
+            path: None,
+            expansion_weight: default_expansion_weight(),
         }
     }
 }
 
-impl Default for ColonyConfig {
+impl Default for PopulationConfig {
     fn default() -> Self {
         Self {
-            tick_rate: default_tick_rate(),
-            max_agents: default_max_agents(),
+            docs_per_digester: default_docs_per_digester(),
+            sentinels: 0,
+            synthesizers: 0,
+        }
+    }
+}
+
+impl Default for IngestConfig {
+    fn default() -> Self {
+        Self {
+            extensions: default_extensions(),
+            ticks: default_ingest_ticks(),
+            dedup: DedupPolicy::default(),
+            dedup_threshold: default_dedup_threshold(),
+        }
+    }
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            session_path: default_session_path(),
+            auto_save_interval: 0,
         }
     }
 }
@@ -201,6 +409,7 @@ impl Default for QueryConfig {
         Self {
             default_alpha: default_alpha(),
             max_results: default_max_results(),
+            log: false,
         }
     }
 }
@@ -232,16 +441,158 @@ impl Default for SemanticConfig {
 }
 
 impl Config {
-    /// Load config from phago.toml in the current or parent directories.
+    /// Load config from phago.toml in the current or parent directories,
+    /// overridden by `PHAGO_*` environment variables.
     pub fn load() -> Result<Self> {
-        if let Some(path) = find_config_file() {
-            let content = std::fs::read_to_string(&path)
-                .with_context(|| format!("Failed to read config: {}", path.display()))?;
-            toml::from_str(&content)
-                .with_context(|| format!("Failed to parse config: {}", path.display()))
-        } else {
-            Ok(Config::default())
+        Ok(Self::resolve(&Overrides::default())?.config)
+    }
+
+    /// [`Config::load`] with command-line `flags` on top, keeping where
+    /// each value came from.
+    pub fn resolve(flags: &Overrides) -> Result<Resolved> {
+        let file = match find_config_file() {
+            Some(path) => {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read config: {}", path.display()))?;
+                Some((path, text))
+            }
+            None => None,
+        };
+        let vars = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+        layers::resolve(
+            file.as_ref()
+                .map(|(path, text)| layers::ConfigFile { path, text }),
+            vars,
+            flags,
+        )
+    }
+
+    /// Out-of-range values, by dotted path.
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut problems = Vec::new();
+        let mut check = |path: &'static str, ok: bool, expected: &str| {
+            if !ok {
+                problems.push((path, format!("must be {}", expected)));
+            }
+        };
+        let unit = |x: f64| (0.0..=1.0).contains(&x);
+        let non_negative = |x: f64| x >= 0.0;
+
+        check("colony.tick_rate", self.colony.tick_rate > 0, "at least 1");
+        check(
+            "colony.max_agents",
+            self.colony.max_agents > 0,
+            "at least 1",
+        );
+        check(
+            "digester.max_idle",
+            self.digester.max_idle > 0,
+            "at least 1",
+        );
+        check(
+            "digester.sense_radius",
+            self.digester.sense_radius > 0.0,
+            "positive",
+        );
+        check(
+            "wiring.edge_decay_rate",
+            unit(self.wiring.edge_decay_rate),
+            "between 0 and 1",
+        );
+        check(
+            "wiring.prune_threshold",
+            non_negative(self.wiring.prune_threshold),
+            "at least 0",
+        );
+        check(
+            "wiring.tentative_weight",
+            unit(self.wiring.tentative_weight),
+            "between 0 and 1",
+        );
+        check(
+            "query.default_alpha",
+            unit(self.query.default_alpha),
+            "between 0 (pure graph) and 1 (pure TF-IDF)",
+        );
+        check(
+            "query.max_results",
+            self.query.max_results > 0,
+            "at least 1",
+        );
+        for (path, rate) in [
+            ("decay.signal_decay_rate", self.decay.signal_decay_rate),
+            ("decay.trace_decay_rate", self.decay.trace_decay_rate),
+            ("decay.edge_decay_rate", self.decay.edge_decay_rate),
+        ] {
+            check(path, unit(rate), "between 0 and 1");
+        }
+        for (path, threshold) in [
+            (
+                "decay.signal_removal_threshold",
+                self.decay.signal_removal_threshold,
+            ),
+            (
+                "decay.trace_removal_threshold",
+                self.decay.trace_removal_threshold,
+            ),
+            (
+                "decay.edge_prune_threshold",
+                self.decay.edge_prune_threshold,
+            ),
+        ] {
+            check(path, non_negative(threshold), "at least 0");
+        }
+        check(
+            "decay.staleness_factor",
+            self.decay.staleness_factor > 0.0,
+            "positive",
+        );
+        check(
+            "decay.max_edge_degree",
+            self.decay.max_edge_degree > 0,
+            "at least 1",
+        );
+        check(
+            "semantic.min_similarity",
+            unit(self.semantic.min_similarity),
+            "between 0 and 1",
+        );
+        check(
+            "semantic.similarity_influence",
+            unit(self.semantic.similarity_influence),
+            "between 0 and 1",
+        );
+        check(
+            "population.docs_per_digester",
+            self.population.docs_per_digester > 0,
+            "at least 1",
+        );
+        check(
+            "ingest.extensions",
+            !self.ingest.extensions.is_empty(),
+            "a non-empty list",
+        );
+        check(
+            "ingest.dedup_threshold",
+            unit(self.ingest.dedup_threshold),
+            "between 0 and 1",
+        );
+        check(
+            "persistence.session_path",
+            !self.persistence.session_path.as_os_str().is_empty(),
+            "a file path",
+        );
+        check(
+            "synonyms.expansion_weight",
+            self.synonyms.expansion_weight > 0.0 && self.synonyms.expansion_weight <= 1.0,
+            "above 0 and at most 1",
+        );
+        if let Err(e) = self.synonyms.load() {
+            problems.push(("synonyms.path", format!("could not be loaded: {}", e)));
         }
+        problems
     }
 
     /// Save config to the specified path.
@@ -252,20 +603,53 @@ impl Config {
         Ok(())
     }
 
-    /// Generate default config as TOML string.
-    pub fn default_toml() -> String {
-        let config = Config::default();
-        toml::to_string_pretty(&config).unwrap()
-    }
-
     /// Convert to runtime colony configuration.
     ///
     /// This creates a `phago::runtime::colony::ColonyConfig` from the CLI config
     /// for use with `Colony::from_config()` or `ColonyBuilder::with_config()`.
+    /// `[colony]` settings that do not fit are left at their defaults;
+    /// [`Config::load`] has already rejected them.
     pub fn to_runtime_config(&self) -> phago::runtime::colony::ColonyConfig {
+        self.runtime_config().unwrap_or_else(|_| {
+            let mut fallback = self.clone();
+            fallback.colony.runtime.clear();
+            fallback
+                .runtime_config()
+                .expect("defaults are a valid runtime config")
+        })
+    }
+
+    /// The runtime colony configuration: `[colony]` settings over the
+    /// defaults, then the sections that configure parts of it.
+    fn runtime_config(
+        &self,
+    ) -> std::result::Result<phago::runtime::colony::ColonyConfig, layers::RuntimeConfigError> {
+        use layers::RuntimeConfigError;
         use phago::core::semantic::SemanticWiringConfig;
 
-        phago::runtime::colony::ColonyConfig {
+        let mut table = toml::Table::try_from(phago::runtime::colony::ColonyConfig::default())
+            .map_err(|e| RuntimeConfigError::Invalid(e.to_string()))?;
+        for (key, value) in &self.colony.runtime {
+            if let Some((_, section)) = MOVED_SETTINGS.iter().find(|(moved, _)| moved == key) {
+                return Err(RuntimeConfigError::Moved {
+                    key: key.clone(),
+                    section,
+                });
+            }
+            table.insert(key.clone(), value.clone());
+        }
+
+        let mut unknown = Vec::new();
+        let runtime: phago::runtime::colony::ColonyConfig =
+            serde_ignored::deserialize(toml::Value::Table(table), |path| {
+                unknown.push(path.to_string())
+            })
+            .map_err(|e| RuntimeConfigError::Invalid(e.to_string()))?;
+        if !unknown.is_empty() {
+            return Err(RuntimeConfigError::Unknown(unknown));
+        }
+
+        Ok(phago::runtime::colony::ColonyConfig {
             signal_decay_rate: self.decay.signal_decay_rate,
             signal_removal_threshold: self.decay.signal_removal_threshold,
             trace_decay_rate: self.decay.trace_decay_rate,
@@ -280,12 +664,33 @@ impl Config {
                 similarity_influence: self.semantic.similarity_influence,
                 require_embeddings: self.semantic.require_embeddings,
             },
-        }
+            query_log: phago::runtime::query_log::QueryLogConfig {
+                enabled: self.query.log,
+                ..Default::default()
+            },
+            tokenizer: self.tokenizer.clone(),
+            // Config::load has already reported a dictionary that fails to load
+            synonyms: SynonymConfig {
+                map: self.synonyms.load().unwrap_or_default(),
+                expansion_weight: self.synonyms.expansion_weight,
+            },
+            ..runtime
+        })
     }
 }
 
+/// Runtime colony settings that `[colony]` may set, at their defaults.
+fn runtime_defaults() -> toml::Table {
+    let mut table =
+        toml::Table::try_from(phago::runtime::colony::ColonyConfig::default()).unwrap_or_default();
+    for (key, _) in MOVED_SETTINGS {
+        table.remove(*key);
+    }
+    table
+}
+
 /// Find phago.toml in current or parent directories.
-fn find_config_file() -> Option<PathBuf> {
+pub fn find_config_file() -> Option<PathBuf> {
     let mut dir = std::env::current_dir().ok()?;
     loop {
         let config_path = dir.join("phago.toml");
@@ -305,12 +710,18 @@ pub fn data_dir() -> Result<PathBuf> {
     Ok(dir)
 }
 
+/// Where `phago ingest` saves the replay of a run recorded with
+/// `colony.record_replay`.
+pub fn replay_path() -> Result<PathBuf> {
+    Ok(data_dir()?.join("replay.jsonl"))
+}
+
 /// Get the sessions directory.
 pub fn sessions_dir() -> Result<PathBuf> {
     Ok(data_dir()?.join("sessions"))
 }
 
-/// Get the current session file path.
+/// Get the current session file path (`persistence.session_path`).
 pub fn current_session_path() -> Result<PathBuf> {
-    Ok(data_dir()?.join("current.json"))
+    Ok(std::env::current_dir()?.join(Config::load()?.persistence.session_path))
 }
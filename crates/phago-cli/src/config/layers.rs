@@ -0,0 +1,533 @@
+//! Layered configuration: built-in defaults < phago.toml < `PHAGO_*`
+//! environment variables < command-line flags.
+//!
+//! Every layer is a TOML table. A later layer replaces the values of the
+//! earlier ones key by key, tables merging recursively, and every value
+//! remembers the layer it came from so problems can be reported where they
+//! were written and `phago config show --resolved` can explain each setting.
+//!
+//! Environment variables name a section and a key, upper-cased:
+//! `PHAGO_QUERY_DEFAULT_ALPHA=0.7` sets `default_alpha` in `[query]`, and
+//! `__` separates nested keys (`PHAGO_COLONY_EMERGE__QUORUM_THRESHOLD`).
+//! Values are read as TOML (`0.7`, `true`, `["txt", "md"]`), falling back
+//! to a plain string. `PHAGO_*` variables that name no section are left to
+//! other tools.
+
+use super::Config;
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Sections of phago.toml, as named by environment variables.
+const SECTIONS: &[&str] = &[
+    "colony",
+    "digester",
+    "wiring",
+    "query",
+    "decay",
+    "semantic",
+    "tokenizer",
+    "synonyms",
+    "vectors",
+    "population",
+    "ingest",
+    "persistence",
+    "web",
+];
+
+/// Where a configuration value was set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Built into phago.
+    Default,
+    /// The project's config file, at a 1-based line.
+    File { path: PathBuf, line: usize },
+    /// An environment variable.
+    Env(String),
+    /// A command-line flag.
+    Flag(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File { path, line } => write!(f, "{}:{}", path.display(), line),
+            Source::Env(var) => write!(f, "env {}", var),
+            Source::Flag(flag) => write!(f, "flag {}", flag),
+        }
+    }
+}
+
+/// Settings given on the command line, the top layer.
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    values: Table,
+    flags: BTreeMap<String, String>,
+}
+
+impl Overrides {
+    /// Set the dotted `path` (e.g. `"query.default_alpha"`) from `flag`, if
+    /// the flag was given.
+    pub fn set(mut self, path: &str, flag: &str, value: Option<impl Into<Value>>) -> Self {
+        if let Some(value) = value {
+            insert_path(&mut self.values, path, value.into());
+            self.flags.insert(path.to_string(), flag.to_string());
+        }
+        self
+    }
+}
+
+/// The effective configuration and where each of its values came from.
+#[derive(Debug)]
+pub struct Resolved {
+    pub config: Config,
+    /// Every leaf value by dotted path, with its source.
+    pub values: BTreeMap<String, (Value, Source)>,
+}
+
+/// The config file's path and contents.
+pub struct ConfigFile<'a> {
+    pub path: &'a Path,
+    pub text: &'a str,
+}
+
+/// Resolve the configuration from `file`, the environment `vars` and the
+/// command-line `flags`. Fails listing every unknown key and out-of-range
+/// value, each with the file line, variable or flag that set it.
+pub fn resolve(
+    file: Option<ConfigFile<'_>>,
+    vars: impl IntoIterator<Item = (String, String)>,
+    flags: &Overrides,
+) -> Result<Resolved> {
+    let mut merged = defaults();
+    let mut sources = BTreeMap::new();
+    mark_leaves(
+        &Value::Table(merged.clone()),
+        "",
+        &|_| Source::Default,
+        &mut sources,
+    );
+
+    if let Some(file) = file {
+        let layer: Table = toml::from_str(file.text)
+            .with_context(|| format!("Failed to parse config: {}", file.path.display()))?;
+        let lines = key_lines(file.text);
+        let source = |path: &str| Source::File {
+            path: file.path.to_path_buf(),
+            line: lookup(&lines, path).copied().unwrap_or(1),
+        };
+        overlay(&mut merged, &layer, "", &source, &mut sources);
+    }
+
+    let (layer, vars) = env_layer(vars);
+    let source = |path: &str| Source::Env(lookup(&vars, path).cloned().unwrap_or_default());
+    overlay(&mut merged, &layer, "", &source, &mut sources);
+
+    let source = |path: &str| Source::Flag(lookup(&flags.flags, path).cloned().unwrap_or_default());
+    overlay(&mut merged, &flags.values, "", &source, &mut sources);
+
+    let located = |path: &str| match locate(&sources, path) {
+        Some(source) => format!("{} ({})", path, source),
+        None => path.to_string(),
+    };
+
+    let mut unknown = Vec::new();
+    let config: Config = serde_ignored::deserialize(Value::Table(merged.clone()), |path| {
+        unknown.push(path.to_string())
+    })
+    .map_err(|e| anyhow::anyhow!("Invalid configuration: {}", e))?;
+    // Without vector store support the section is accepted but unused
+    #[cfg(not(feature = "vectors"))]
+    unknown.retain(|path| path != "vectors" && !path.starts_with("vectors."));
+    let mut problems: Vec<String> = unknown
+        .iter()
+        .map(|path| format!("{}: unknown key", located(path)))
+        .collect();
+
+    match config.runtime_config() {
+        Ok(_) => {}
+        Err(RuntimeConfigError::Unknown(paths)) => problems.extend(
+            paths
+                .iter()
+                .map(|path| format!("{}: unknown key", located(&format!("colony.{}", path)))),
+        ),
+        Err(RuntimeConfigError::Moved { key, section }) => problems.push(format!(
+            "{}: set in [{}] instead",
+            located(&format!("colony.{}", key)),
+            section
+        )),
+        Err(RuntimeConfigError::Invalid(e)) => problems.push(format!("[colony]: {}", e)),
+    }
+
+    for (path, problem) in config.validate() {
+        let value = lookup_value(&merged, path)
+            .map(|v| format!(" = {}", v))
+            .unwrap_or_default();
+        problems.push(format!("{}{}: {}", located(path), value, problem));
+    }
+
+    if !problems.is_empty() {
+        bail!("Invalid configuration:\n  {}", problems.join("\n  "));
+    }
+
+    let mut values = BTreeMap::new();
+    collect_leaves(&Value::Table(merged), "", &mut |path, value| {
+        let source = sources.get(path).cloned().unwrap_or(Source::Default);
+        values.insert(path.to_string(), (value.clone(), source));
+    });
+    Ok(Resolved { config, values })
+}
+
+/// Why the `[colony]` settings could not be turned into a runtime config.
+#[derive(Debug)]
+pub enum RuntimeConfigError {
+    /// Keys that are not runtime colony settings.
+    Unknown(Vec<String>),
+    /// A setting configured in another section.
+    Moved { key: String, section: &'static str },
+    /// A value of the wrong type.
+    Invalid(String),
+}
+
+/// Built-in defaults, with every runtime colony setting listed under
+/// `[colony]` so the resolved configuration shows them.
+fn defaults() -> Table {
+    let mut table = match Value::try_from(Config::default()) {
+        Ok(Value::Table(table)) => table,
+        _ => Table::new(),
+    };
+    if let Some(Value::Table(colony)) = table.get_mut("colony") {
+        colony.extend(super::runtime_defaults());
+    }
+    table
+}
+
+/// Merge `layer` into `base`, recording `source` for every value it sets.
+fn overlay(
+    base: &mut Table,
+    layer: &Table,
+    prefix: &str,
+    source: &dyn Fn(&str) -> Source,
+    sources: &mut BTreeMap<String, Source>,
+) {
+    for (key, value) in layer {
+        let path = join(prefix, key);
+        match (base.get_mut(key), value) {
+            (Some(Value::Table(base)), Value::Table(layer)) => {
+                overlay(base, layer, &path, source, sources)
+            }
+            _ => {
+                // The old value, and wherever its parts came from, is gone
+                sources.retain(|p, _| !within(p, &path));
+                if value.is_table() {
+                    // A new table is reported where it starts
+                    sources.insert(path.clone(), source(&path));
+                }
+                mark_leaves(value, &path, source, sources);
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn mark_leaves(
+    value: &Value,
+    path: &str,
+    source: &dyn Fn(&str) -> Source,
+    sources: &mut BTreeMap<String, Source>,
+) {
+    collect_leaves(value, path, &mut |leaf, _| {
+        sources.insert(leaf.to_string(), source(leaf));
+    });
+}
+
+/// Call `f` with every non-table value below `value` and its dotted path.
+fn collect_leaves(value: &Value, path: &str, f: &mut dyn FnMut(&str, &Value)) {
+    match value {
+        Value::Table(table) if !table.is_empty() => {
+            for (key, value) in table {
+                collect_leaves(value, &join(path, key), f);
+            }
+        }
+        _ => f(path, value),
+    }
+}
+
+/// The environment layer, and the variable behind each of its paths.
+fn env_layer(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> (Table, BTreeMap<String, String>) {
+    let mut layer = Table::new();
+    let mut names = BTreeMap::new();
+    for (name, raw) in vars {
+        let Some(rest) = name.strip_prefix("PHAGO_") else {
+            continue;
+        };
+        let rest = rest.to_ascii_lowercase();
+        let Some((section, key)) = SECTIONS.iter().find_map(|section| {
+            let key = rest.strip_prefix(section)?.strip_prefix('_')?;
+            (!key.is_empty()).then_some((section, key))
+        }) else {
+            continue;
+        };
+        let path = format!("{}.{}", section, key.replace("__", "."));
+        insert_path(&mut layer, &path, parse_env_value(&raw));
+        names.insert(path, name);
+    }
+    (layer, names)
+}
+
+/// A variable's value as TOML, or as a string if it is not valid TOML.
+fn parse_env_value(raw: &str) -> Value {
+    toml::from_str::<Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// Set the dotted `path` in `table`, creating tables along the way.
+fn insert_path(table: &mut Table, path: &str, value: Value) {
+    let mut keys: Vec<&str> = path.split('.').collect();
+    let last = keys.pop().unwrap_or_default();
+    let mut table = table;
+    for key in keys {
+        let entry = table
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+        let Value::Table(next) = entry else {
+            unreachable!("just made a table");
+        };
+        table = next;
+    }
+    table.insert(last.to_string(), value);
+}
+
+/// Line of every key in a TOML document, by dotted path.
+fn key_lines(text: &str) -> BTreeMap<String, usize> {
+    let mut lines = BTreeMap::new();
+    if let Ok(doc) = text.parse::<toml_edit::ImDocument<String>>() {
+        walk_keys(doc.as_table(), "", text, &mut lines);
+    }
+    lines
+}
+
+fn walk_keys(
+    table: &dyn toml_edit::TableLike,
+    prefix: &str,
+    text: &str,
+    lines: &mut BTreeMap<String, usize>,
+) {
+    for (key, item) in table.iter() {
+        let path = join(prefix, key);
+        let span = table
+            .key(key)
+            .and_then(|k| k.span())
+            .or_else(|| item.span());
+        if let Some(span) = span {
+            lines.insert(path.clone(), line_of(text, span.start));
+        }
+        if let Some(inner) = item.as_table_like() {
+            walk_keys(inner, &path, text, lines);
+        }
+    }
+}
+
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// The entry for `path`, or for the nearest enclosing table that has one.
+fn lookup<'a, T>(entries: &'a BTreeMap<String, T>, path: &str) -> Option<&'a T> {
+    let mut path = path;
+    loop {
+        if let Some(entry) = entries.get(path) {
+            return Some(entry);
+        }
+        path = &path[..path.rfind('.')?];
+    }
+}
+
+/// Where `path`, or the first value inside it, was set.
+fn locate<'a>(sources: &'a BTreeMap<String, Source>, path: &str) -> Option<&'a Source> {
+    lookup(sources, path).or_else(|| {
+        sources
+            .range(path.to_string()..)
+            .find(|(p, _)| within(p, path))
+            .map(|(_, source)| source)
+    })
+}
+
+fn lookup_value<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let mut keys = path.split('.');
+    let mut value = table.get(keys.next()?)?;
+    for key in keys {
+        value = value.as_table()?.get(key)?;
+    }
+    Some(value)
+}
+
+/// Whether `path` is `ancestor` or lies inside it.
+fn within(path: &str, ancestor: &str) -> bool {
+    path == ancestor
+        || path
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago::prelude::DedupPolicy;
+
+    fn resolve_with(text: &str, vars: &[(&str, &str)], flags: &Overrides) -> Result<Resolved> {
+        let vars = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()));
+        let file = ConfigFile {
+            path: Path::new("phago.toml"),
+            text,
+        };
+        resolve(Some(file), vars, flags)
+    }
+
+    #[test]
+    fn typo_is_reported_with_its_line() {
+        let err = resolve_with("[colony]\ntick_rat = 5\n", &[], &Overrides::default())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("colony.tick_rat (phago.toml:2): unknown key"),
+            "{err}"
+        );
+
+        let err = resolve_with("[qeury]\nlog = true\n", &[], &Overrides::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("qeury (phago.toml:1): unknown key"), "{err}");
+    }
+
+    #[test]
+    fn out_of_range_value_is_reported_with_its_line() {
+        let text = "[query]\nmax_results = 5\ndefault_alpha = 1.5\n";
+        let err = resolve_with(text, &[], &Overrides::default())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("query.default_alpha (phago.toml:3) = 1.5: must be between 0"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn moved_colony_setting_points_to_its_section() {
+        let err = resolve_with(
+            "[colony]\nedge_decay_rate = 0.1\n",
+            &[],
+            &Overrides::default(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("set in [decay] instead"), "{err}");
+    }
+
+    #[test]
+    fn partial_config_keeps_defaults() {
+        let text = "[query]\ndefault_alpha = 0.7\n\n[colony]\ndeferred_wiring = true\n";
+        let resolved = resolve_with(text, &[], &Overrides::default()).unwrap();
+        let config = &resolved.config;
+        assert_eq!(config.query.default_alpha, 0.7);
+        assert_eq!(config.query.max_results, 10);
+        assert_eq!(config.ingest.extensions, vec!["txt", "md"]);
+        assert_eq!(config.persistence.auto_save_interval, 0);
+        assert!(config.to_runtime_config().deferred_wiring);
+
+        assert_eq!(
+            resolved.values["query.default_alpha"].1,
+            Source::File {
+                path: PathBuf::from("phago.toml"),
+                line: 2
+            }
+        );
+        assert_eq!(resolved.values["query.max_results"].1, Source::Default);
+        assert_eq!(
+            resolved.values["colony.deferred_wiring"].1.to_string(),
+            "phago.toml:5"
+        );
+    }
+
+    #[test]
+    fn web_tokens_are_a_list_or_comma_separated() {
+        let text = "[web]\ntokens = [\"read:abc\"]\n";
+        let resolved = resolve_with(text, &[], &Overrides::default()).unwrap();
+        assert_eq!(resolved.config.web.tokens, vec!["read:abc"]);
+
+        let vars = [("PHAGO_WEB_TOKENS", "read:abc, write:xyz")];
+        let resolved = resolve_with(text, &vars, &Overrides::default()).unwrap();
+        assert_eq!(resolved.config.web.tokens, vec!["read:abc", "write:xyz"]);
+    }
+
+    #[test]
+    fn env_overrides_file_and_flags_override_env() {
+        let text = "[query]\ndefault_alpha = 0.7\nmax_results = 5\n";
+        let vars = [
+            ("PHAGO_QUERY_DEFAULT_ALPHA", "0.2"),
+            ("PHAGO_QUERY_MAX_RESULTS", "20"),
+            ("PHAGO_INGEST_EXTENSIONS", r#"["rst"]"#),
+            ("PHAGO_INGEST_DEDUP", "merge_into_existing"),
+            ("PHAGO_HOME", "/elsewhere"),
+        ];
+        let flags = Overrides::default()
+            .set("query.max_results", "--page-size", Some(50))
+            .set("query.default_alpha", "--alpha", None::<f64>);
+        let resolved = resolve_with(text, &vars, &flags).unwrap();
+        let config = &resolved.config;
+
+        assert_eq!(config.query.default_alpha, 0.2);
+        assert_eq!(config.query.max_results, 50);
+        assert_eq!(config.ingest.extensions, vec!["rst"]);
+        assert_eq!(config.ingest.dedup, DedupPolicy::MergeIntoExisting);
+        assert_eq!(
+            resolved.values["query.default_alpha"].1,
+            Source::Env("PHAGO_QUERY_DEFAULT_ALPHA".into())
+        );
+        assert_eq!(
+            resolved.values["query.max_results"].1,
+            Source::Flag("--page-size".into())
+        );
+    }
+
+    #[test]
+    fn nested_env_keys_reach_colony_settings() {
+        let vars = [("PHAGO_COLONY_COMPACTION__INTERVAL", "25")];
+        let resolved = resolve_with("", &vars, &Overrides::default()).unwrap();
+        assert_eq!(resolved.config.to_runtime_config().compaction.interval, 25);
+        assert_eq!(
+            resolved.values["colony.compaction.interval"].1.to_string(),
+            "env PHAGO_COLONY_COMPACTION__INTERVAL"
+        );
+    }
+
+    #[test]
+    fn missing_synonyms_file_is_reported() {
+        let text = "[synonyms]\npath = \"no/such/synonyms.tsv\"\nexpansion_weight = 0.3\n";
+        let err = resolve_with(text, &[], &Overrides::default())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("synonyms.path"), "{err}");
+        assert!(err.contains("could not be loaded"), "{err}");
+    }
+}
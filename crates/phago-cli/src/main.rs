@@ -5,6 +5,9 @@ mod config;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use config::Overrides;
+use phago::prelude::{AssembleConfig, CentralityMethod, CommunityAlgorithm, DedupPolicy, PathCost};
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "phago")]
@@ -32,13 +35,60 @@ enum Commands {
         /// File or directory to ingest
         path: String,
 
-        /// Number of ticks to run after ingestion
-        #[arg(short, long, default_value = "30")]
-        ticks: u64,
+        /// Number of ticks to run after ingestion [default: `ingest.ticks`]
+        #[arg(short, long)]
+        ticks: Option<u64>,
 
-        /// File extensions to include (e.g., "txt,md")
-        #[arg(short, long, default_value = "txt,md")]
-        extensions: String,
+        /// File extensions to include, e.g. "txt,md,pdf,html"
+        /// [default: `ingest.extensions`]
+        #[arg(short, long)]
+        extensions: Option<String>,
+
+        /// Tag the ingested documents (repeatable, e.g. --tag work)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// What to do with duplicates of already ingested documents:
+        /// skip, merge or ingest [default: `ingest.dedup`]
+        #[arg(long)]
+        dedup: Option<DedupPolicy>,
+
+        /// Similarity (0-1) at which documents count as near duplicates
+        /// [default: `ingest.dedup_threshold`]
+        #[arg(long)]
+        dedup_threshold: Option<f64>,
+    },
+
+    /// Import curated triples from a CSV or Turtle file
+    Import {
+        /// CSV (subject, predicate, object, ...) or Turtle file
+        file: String,
+
+        /// File format: csv or turtle [default: by the file's extension]
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// CSV column with each triple's weight or confidence (0-1), by
+        /// header name or 0-based index
+        #[arg(long)]
+        weight_column: Option<String>,
+
+        /// CSV column grouping subjects laid out together, by header name
+        /// or 0-based index
+        #[arg(long)]
+        group_column: Option<String>,
+
+        /// CSV field separator
+        #[arg(long, default_value = ",")]
+        delimiter: char,
+
+        /// The CSV's first row is data, not column names
+        #[arg(long)]
+        no_header: bool,
+
+        /// Source recorded on the imported edges [default: the file name]
+        #[arg(long)]
+        source: Option<String>,
     },
 
     /// Run the colony simulation
@@ -53,13 +103,45 @@ enum Commands {
         /// Search query
         query: String,
 
-        /// Maximum results to return
-        #[arg(short, long, default_value = "10")]
-        max_results: usize,
+        /// Results per page [default: `query.max_results`]
+        #[arg(short = 'm', long, alias = "max-results")]
+        page_size: Option<usize>,
+
+        /// Page of results to show, starting at 1
+        #[arg(long, default_value = "1")]
+        page: usize,
 
         /// Alpha value (0.0 = pure graph, 1.0 = pure TF-IDF)
-        #[arg(short, long, default_value = "0.5")]
-        alpha: f64,
+        /// [default: `query.default_alpha`]
+        #[arg(short, long)]
+        alpha: Option<f64>,
+
+        /// Only search concepts carrying one of these tags (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Output format: table, json or tsv. Exits with 2 when there are
+        /// no results
+        #[arg(short, long, value_enum, default_value = "table")]
+        output: commands::query::OutputFormat,
+
+        /// Show source passages for each result
+        #[arg(long)]
+        passages: bool,
+
+        /// Print the results' passages as cited context for a prompt,
+        /// within a token budget, instead of the ranked results
+        #[arg(long, conflicts_with_all = ["passages", "page", "watch"])]
+        assemble: bool,
+
+        /// Most tokens of assembled context (about four characters each)
+        #[arg(long, requires = "assemble", default_value = "2000")]
+        budget: usize,
+
+        /// Re-run the query every this many seconds, reloading the session
+        /// when it changes, and show how scores moved
+        #[arg(short, long, value_name = "SECONDS", conflicts_with = "output")]
+        watch: Option<u64>,
     },
 
     /// Explore graph structure
@@ -68,16 +150,82 @@ enum Commands {
         command: ExploreCommands,
     },
 
+    /// Inspect the session's agents
+    Agents {
+        #[command(subcommand)]
+        command: AgentsCommands,
+    },
+
+    /// Merge concepts whose labels are variants of one another
+    Compact {
+        /// How variants are recognised: stemming, edit-distance or embedding
+        #[arg(short, long, default_value = "stemming")]
+        strategy: String,
+
+        /// Most single-character edits between variants (edit-distance)
+        #[arg(long, default_value = "1")]
+        max_distance: usize,
+
+        /// Shortest label considered, in characters (edit-distance)
+        #[arg(long, default_value = "5")]
+        min_len: usize,
+
+        /// Least cosine similarity between variants' embeddings (embedding)
+        #[arg(long, default_value = "0.9")]
+        min_similarity: f64,
+
+        /// Show the merge plan without changing the session
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Export the knowledge graph
     Export {
         /// Output file path
         output: String,
 
-        /// Export format
+        /// Export format: json or turtle (RDF)
         #[arg(short, long, default_value = "json")]
         format: String,
     },
 
+    /// Render recorded colony snapshots as a standalone HTML player
+    Viz {
+        /// Snapshot file (JSON array, JSONL or a recorded snapshot series)
+        snapshots: String,
+
+        /// Event file of `[tick, event]` records (JSON array or JSONL)
+        #[arg(short, long)]
+        events: Option<String>,
+
+        /// Output HTML file
+        #[arg(short, long, default_value = "phago-viz.html")]
+        output: String,
+
+        /// Largest recording, in MB, embedded in the page; bigger ones are
+        /// written to data.json next to it
+        #[arg(long, default_value = "32")]
+        max_embedded_mb: usize,
+
+        /// Longest node label kept in full, in characters
+        #[arg(long, default_value = "200")]
+        max_label: usize,
+    },
+
+    /// Replay a run recorded with `colony.record_replay` up to a tick
+    Replay {
+        /// Replay file, e.g. .phago/replay.jsonl
+        file: String,
+
+        /// Tick to stop after [default: the last recorded tick]
+        #[arg(long)]
+        until: Option<u64>,
+
+        /// Write a snapshot of the replayed colony to this JSON file
+        #[arg(long)]
+        dump_snapshot: Option<String>,
+    },
+
     /// Manage sessions
     Session {
         #[command(subcommand)]
@@ -85,7 +233,69 @@ enum Commands {
     },
 
     /// Show colony statistics
-    Stats,
+    Stats {
+        /// Show query log aggregates instead (requires `query.log = true`)
+        #[arg(long)]
+        queries: bool,
+
+        /// Number of entries per query aggregate
+        #[arg(short, long, default_value = "10")]
+        top: usize,
+    },
+
+    /// Check the session's graph for inconsistencies
+    Doctor {
+        /// Repair what can be repaired and save the session
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Inspect the project configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Serve the session to the web dashboard and to MCP clients (over
+    /// stdio) at once
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Dashboard port
+        #[arg(short, long, default_value = "3000")]
+        port: u16,
+
+        /// Address the dashboard binds to
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+
+        /// Serve a SQLite database instead of the project session
+        #[arg(long)]
+        db: Option<String>,
+
+        /// Interface that may not change the colony: web or mcp
+        #[arg(long, value_enum)]
+        read_only: Option<commands::serve::ReadOnly>,
+
+        /// Save every this many seconds (0 saves only on shutdown)
+        #[arg(long, default_value = "60")]
+        save_interval: u64,
+
+        /// Accept a dashboard token, written ROLE:TOKEN with role read or
+        /// write (repeatable; replaces web.tokens in phago.toml)
+        #[arg(long = "token")]
+        tokens: Vec<String>,
+
+        /// Serve the dashboard without tokens, as for local development
+        #[arg(long, conflicts_with = "tokens")]
+        no_auth: bool,
+    },
+
+    /// Benchmark the project's colony
+    #[cfg(feature = "bench")]
+    Bench {
+        #[command(subcommand)]
+        command: BenchCommands,
+    },
 
     /// Start the MCP server (delegates to phago-mcp binary)
     Mcp {
@@ -102,6 +312,43 @@ enum Commands {
     },
 }
 
+#[cfg(feature = "bench")]
+#[derive(Subcommand)]
+enum BenchCommands {
+    /// Query the colony from concurrent workers and report latency
+    /// percentiles, per locking mode and with or without reinforcement
+    QueryLoad {
+        /// Workers querying at once
+        #[arg(short, long, default_value = "4")]
+        concurrency: usize,
+
+        /// Seconds each run lasts
+        #[arg(short, long, default_value = "10")]
+        duration: u64,
+
+        /// File with one query per line [default: the most accessed
+        /// concepts]
+        #[arg(short, long)]
+        queries: Option<String>,
+
+        /// Load a digested embedded corpus instead of the session
+        #[arg(long)]
+        corpus: Option<String>,
+
+        /// Locking mode: mutex, read-view or both
+        #[arg(long, value_enum, default_value = "both")]
+        lock: commands::bench::LockChoice,
+
+        /// Reinforce what queries retrieve: on, off or both
+        #[arg(long, value_enum, default_value = "both")]
+        reinforce: commands::bench::ReinforceChoice,
+
+        /// Output format: table, json or csv
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: commands::bench::BenchFormat,
+    },
+}
+
 #[derive(Subcommand)]
 enum ExploreCommands {
     /// Show most central concepts
@@ -109,6 +356,10 @@ enum ExploreCommands {
         /// Number of top concepts
         #[arg(short, long, default_value = "10")]
         top: usize,
+
+        /// Centrality measure: betweenness or pagerank
+        #[arg(short, long, default_value = "betweenness")]
+        method: CentralityMethod,
     },
 
     /// Show bridge concepts between clusters
@@ -118,16 +369,64 @@ enum ExploreCommands {
         top: usize,
     },
 
-    /// Find shortest path between concepts
+    /// Find paths between concepts, explained hop by hop
     Path {
         /// Source concept
         from: String,
         /// Target concept
         to: String,
+        /// Path cost: strongest, hops, inverse or complement
+        #[arg(short, long, default_value = "strongest")]
+        cost: PathCost,
+        /// Number of paths to show, the best first
+        #[arg(short, long, default_value = "1")]
+        k: usize,
+    },
+
+    /// Show an edge's weight and the documents that reinforced it lately
+    Edge {
+        /// One concept
+        from: String,
+        /// The other concept
+        to: String,
     },
 
     /// Count connected components
     Components,
+
+    /// Group concepts into communities, the largest first
+    Communities {
+        /// Detection algorithm: louvain or label-propagation
+        #[arg(short, long, default_value = "louvain")]
+        algorithm: CommunityAlgorithm,
+        /// Label propagation seed
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Louvain resolution; higher finds more, smaller communities
+        #[arg(short, long)]
+        resolution: Option<f64>,
+        /// Number of communities to show
+        #[arg(short, long, default_value = "10")]
+        top: usize,
+    },
+
+    /// List the named regions of the field and their policies
+    Regions,
+}
+
+#[derive(Subcommand)]
+enum AgentsCommands {
+    /// Show each agent's heaviest terms, whether the graph has them and
+    /// how much agents' terms overlap
+    Vocab {
+        /// Number of terms per agent
+        #[arg(short, long, default_value = "10")]
+        top: usize,
+
+        /// Only the agent whose ID starts with this
+        #[arg(short, long)]
+        agent: Option<String>,
+    },
 }
 
 #[cfg(feature = "distributed")]
@@ -159,6 +458,42 @@ enum ClusterCommands {
         id: u32,
     },
 
+    /// Ingest files into a running cluster
+    Ingest {
+        /// File or directory to ingest
+        path: String,
+
+        /// Coordinator address (host:port)
+        #[arg(short, long, default_value = "127.0.0.1:9000")]
+        coordinator: String,
+
+        /// Extra copies per document (default: the cluster's replication factor)
+        #[arg(short, long)]
+        replicas: Option<u32>,
+
+        /// Number of cluster ticks to run after ingestion
+        #[arg(short, long, default_value = "15")]
+        ticks: u64,
+
+        /// File extensions to include (e.g., "txt,md,pdf,html")
+        #[arg(short, long, default_value = "txt,md")]
+        extensions: String,
+    },
+
+    /// Query a running cluster
+    Query {
+        /// Search query
+        query: String,
+
+        /// Coordinator address (host:port)
+        #[arg(short, long, default_value = "127.0.0.1:9000")]
+        coordinator: String,
+
+        /// Maximum results to return
+        #[arg(short, long, default_value = "10")]
+        max_results: usize,
+    },
+
     /// Show cluster status
     Status {
         /// Coordinator address (host:port)
@@ -174,6 +509,17 @@ enum ClusterCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Show phago.toml, or with --resolved the effective configuration
+    Show {
+        /// Show every setting after defaults, environment variables and
+        /// flags are applied, with where each value came from
+        #[arg(long)]
+        resolved: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum SessionCommands {
     /// Save current session
@@ -186,10 +532,39 @@ enum SessionCommands {
     Load {
         /// Session name
         name: String,
+
+        /// Load a session from a newer release, or with damaged entries,
+        /// as far as possible, leaving out what cannot be read
+        #[arg(long)]
+        best_effort: bool,
     },
 
     /// List saved sessions
     List,
+
+    /// Export a saved session, its config and documents to one bundle file
+    Export {
+        /// Session name
+        name: String,
+
+        /// Bundle file to write
+        #[arg(short, long)]
+        out: String,
+
+        /// Also dump the vector store configured under `[vectors]`
+        #[arg(long)]
+        vectors: bool,
+    },
+
+    /// Restore a session bundle into this project
+    Import {
+        /// Bundle file to read
+        bundle: String,
+
+        /// Overwrite an existing session or phago.toml
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 fn main() -> Result<()> {
@@ -201,26 +576,203 @@ fn main() -> Result<()> {
             path,
             ticks,
             extensions,
-        } => commands::ingest::run(&path, ticks, &extensions, cli.verbose),
+            tags,
+            dedup,
+            dedup_threshold,
+        } => {
+            let extensions = extensions.map(|list| {
+                list.split(',')
+                    .map(|e| e.trim().trim_start_matches('.').to_ascii_lowercase())
+                    .collect::<Vec<_>>()
+            });
+            let dedup = dedup.and_then(|policy| toml::Value::try_from(policy).ok());
+            let flags = Overrides::default()
+                .set("ingest.ticks", "--ticks", ticks.map(|t| t as i64))
+                .set("ingest.extensions", "--extensions", extensions)
+                .set("ingest.dedup", "--dedup", dedup)
+                .set(
+                    "ingest.dedup_threshold",
+                    "--dedup-threshold",
+                    dedup_threshold,
+                );
+            commands::ingest::run(&path, &tags, &flags, cli.verbose)
+        }
+        Commands::Import {
+            file,
+            format,
+            weight_column,
+            group_column,
+            delimiter,
+            no_header,
+            source,
+        } => {
+            let csv = phago::runtime::import::CsvTripleConfig {
+                delimiter,
+                has_header: !no_header,
+                weight_column,
+                group_column,
+                source,
+            };
+            commands::import::run(&file, format.as_deref(), &csv)
+        }
         Commands::Run { ticks } => commands::run::run(ticks, cli.verbose),
         Commands::Query {
             query,
-            max_results,
+            page_size,
+            page,
             alpha,
-        } => commands::query::run(&query, max_results, alpha),
+            tags,
+            output,
+            passages,
+            assemble,
+            budget,
+            watch,
+        } => {
+            let flags = Overrides::default()
+                .set(
+                    "query.max_results",
+                    "--page-size",
+                    page_size.map(|n| n as i64),
+                )
+                .set("query.default_alpha", "--alpha", alpha);
+            let args = commands::query::QueryArgs {
+                query: &query,
+                page,
+                tags: &tags,
+                passages,
+            };
+            if let Some(seconds) = watch {
+                return commands::query::watch(&args, Duration::from_secs(seconds), &flags);
+            }
+            if assemble {
+                let config = AssembleConfig {
+                    token_budget: budget,
+                    ..AssembleConfig::default()
+                };
+                if !commands::query::assemble(&args, &config, output, &flags)? {
+                    std::process::exit(2);
+                }
+                return Ok(());
+            }
+            if !commands::query::run(&args, output, &flags)? {
+                // Distinguishes "nothing found" from errors, which exit with 1
+                std::process::exit(2);
+            }
+            Ok(())
+        }
         Commands::Explore { command } => match command {
-            ExploreCommands::Centrality { top } => commands::explore::centrality(top),
+            ExploreCommands::Centrality { top, method } => {
+                commands::explore::centrality(top, method)
+            }
             ExploreCommands::Bridges { top } => commands::explore::bridges(top),
-            ExploreCommands::Path { from, to } => commands::explore::path(&from, &to),
+            ExploreCommands::Path { from, to, cost, k } => {
+                commands::explore::path(&from, &to, cost, k)
+            }
+            ExploreCommands::Edge { from, to } => commands::explore::edge(&from, &to),
             ExploreCommands::Components => commands::explore::components(),
+            ExploreCommands::Communities {
+                algorithm,
+                seed,
+                resolution,
+                top,
+            } => commands::explore::communities(algorithm.with_params(seed, resolution), top),
+            ExploreCommands::Regions => commands::explore::regions(),
+        },
+        Commands::Agents { command } => match command {
+            AgentsCommands::Vocab { top, agent } => commands::agents::vocab(top, agent.as_deref()),
         },
+        Commands::Compact {
+            strategy,
+            max_distance,
+            min_len,
+            min_similarity,
+            dry_run,
+        } => {
+            let strategy =
+                commands::compact::strategy(&strategy, max_distance, min_len, min_similarity)?;
+            commands::compact::run(&strategy, dry_run)
+        }
         Commands::Export { output, format } => commands::export::run(&output, &format),
+        Commands::Viz {
+            snapshots,
+            events,
+            output,
+            max_embedded_mb,
+            max_label,
+        } => {
+            let options = phago_viz::VizOptions {
+                max_embedded_bytes: max_embedded_mb * 1024 * 1024,
+                max_label_chars: max_label,
+                ..Default::default()
+            };
+            commands::viz::run(&snapshots, events.as_deref(), &output, &options)
+        }
+        Commands::Replay {
+            file,
+            until,
+            dump_snapshot,
+        } => commands::replay::run(&file, until, dump_snapshot.as_deref()),
         Commands::Session { command } => match command {
             SessionCommands::Save { name } => commands::session::save(&name),
-            SessionCommands::Load { name } => commands::session::load(&name),
+            SessionCommands::Load { name, best_effort } => {
+                commands::session::load(&name, best_effort)
+            }
             SessionCommands::List => commands::session::list(),
+            SessionCommands::Export { name, out, vectors } => {
+                commands::session::export(&name, &out, vectors)
+            }
+            SessionCommands::Import { bundle, force } => commands::session::import(&bundle, force),
         },
-        Commands::Stats => commands::stats::run(),
+        Commands::Stats { queries, top } => {
+            if queries {
+                commands::stats::queries(top)
+            } else {
+                commands::stats::run()
+            }
+        }
+        Commands::Doctor { fix } => commands::doctor::run(fix),
+        Commands::Config { command } => match command {
+            ConfigCommands::Show { resolved } => commands::config::show(resolved),
+        },
+        #[cfg(feature = "serve")]
+        Commands::Serve {
+            port,
+            host,
+            db,
+            read_only,
+            save_interval,
+            tokens,
+            no_auth,
+        } => commands::serve::run(
+            port,
+            &host,
+            db,
+            read_only,
+            save_interval,
+            commands::serve::Access { tokens, no_auth },
+        ),
+
+        #[cfg(feature = "bench")]
+        Commands::Bench { command } => match command {
+            BenchCommands::QueryLoad {
+                concurrency,
+                duration,
+                queries,
+                corpus,
+                lock,
+                reinforce,
+                format,
+            } => commands::bench::query_load(&commands::bench::QueryLoadArgs {
+                concurrency,
+                duration,
+                queries: queries.as_deref().map(std::path::Path::new),
+                corpus: corpus.as_deref(),
+                lock,
+                reinforce,
+                format,
+            }),
+        },
+
         Commands::Mcp { db } => {
             let mut cmd = std::process::Command::new("phago-mcp");
             if let Some(path) = db {
@@ -248,6 +800,18 @@ fn main() -> Result<()> {
                 coordinator,
                 id,
             } => commands::cluster::start_shard(port, &coordinator, id),
+            ClusterCommands::Ingest {
+                path,
+                coordinator,
+                replicas,
+                ticks,
+                extensions,
+            } => commands::cluster::ingest(&path, &coordinator, replicas, ticks, &extensions),
+            ClusterCommands::Query {
+                query,
+                coordinator,
+                max_results,
+            } => commands::cluster::query(&query, &coordinator, max_results),
             ClusterCommands::Status { coordinator } => commands::cluster::status(&coordinator),
             ClusterCommands::Bench { mode } => commands::cluster::bench(&mode),
         },
@@ -0,0 +1,89 @@
+//! `phago query` output formats and exit codes, run against the binary.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// A fresh project directory with one ingested document.
+fn project(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("phago_cli_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("biology.txt"),
+        "The cell membrane controls transport of proteins. Membrane proteins \
+         act as channels, and the cell membrane protects the cell.",
+    )
+    .unwrap();
+    assert!(phago(&dir, &["init"]).status.success());
+    let ingest = phago(&dir, &["ingest", "biology.txt", "--ticks", "30"]);
+    assert!(ingest.status.success(), "{ingest:?}");
+    dir
+}
+
+fn phago(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_phago"))
+        .args(args)
+        .current_dir(dir)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn query_exit_codes_tell_results_from_none_and_errors() {
+    let dir = project("exit_codes");
+
+    let found = phago(&dir, &["query", "membrane", "--output", "json"]);
+    assert_eq!(found.status.code(), Some(0), "{found:?}");
+    let json: serde_json::Value = serde_json::from_slice(&found.stdout).unwrap();
+    let results = json["results"].as_array().unwrap();
+    assert!(!results.is_empty());
+    for key in ["rank", "label", "final_score", "tfidf_score", "graph_score"] {
+        assert!(results[0].get(key).is_some(), "missing {key}");
+    }
+
+    let tsv = phago(&dir, &["query", "membrane", "--output", "tsv"]);
+    assert_eq!(tsv.status.code(), Some(0));
+    let lines = String::from_utf8(tsv.stdout).unwrap();
+    assert_eq!(lines.lines().count(), results.len());
+    assert!(lines.lines().all(|l| l.split('\t').count() == 5));
+
+    for output in ["table", "json", "tsv"] {
+        let none = phago(&dir, &["query", "zyzzyva", "--output", output]);
+        assert_eq!(none.status.code(), Some(2), "{output}: {none:?}");
+    }
+    let none = phago(&dir, &["query", "zyzzyva", "--output", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&none.stdout).unwrap();
+    assert_eq!(json["results"], serde_json::json!([]));
+
+    let error = phago(&dir, &["query", "membrane", "--page", "0"]);
+    assert_eq!(error.status.code(), Some(1));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn assembled_context_is_cited_and_within_budget() {
+    let dir = project("assemble");
+
+    let args = ["query", "membrane", "--assemble", "--budget", "100"];
+    let json = phago(&dir, &[&args[..], &["--output", "json"]].concat());
+    assert_eq!(json.status.code(), Some(0), "{json:?}");
+    let context: serde_json::Value = serde_json::from_slice(&json.stdout).unwrap();
+    let passages = context["passages"].as_array().unwrap();
+    assert!(!passages.is_empty());
+    assert!(context["tokens_used"].as_u64().unwrap() <= 100);
+    assert_eq!(passages[0]["title"], "biology");
+    assert_eq!(context["citations"][0]["marker"], "[1]");
+
+    let table = phago(&dir, &["query", "membrane", "--assemble"]);
+    assert_eq!(table.status.code(), Some(0));
+    let text = String::from_utf8(table.stdout).unwrap();
+    assert!(text.starts_with("[1] "), "{text}");
+    assert!(text.contains("Sources:"));
+
+    let none = phago(&dir, &["query", "zyzzyva", "--assemble"]);
+    assert_eq!(none.status.code(), Some(2));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
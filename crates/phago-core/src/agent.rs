@@ -7,10 +7,18 @@
 //! - APOPTOSE: the ability to self-assess and gracefully die
 //! - SENSE: the ability to detect environmental signals
 
+use crate::primitives::digest::DigestionLimits;
+use crate::primitives::emerge::{EmergeConfig, QuorumStatus};
 use crate::primitives::symbiose::AgentProfile;
 use crate::primitives::{Apoptose, Digest, Sense};
 use crate::substrate::Substrate;
+use crate::tokenize::Tokenizer;
 use crate::types::*;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 
 /// The fundamental unit of computation in Phago — a biological cell.
 ///
@@ -47,6 +55,14 @@ pub trait Agent: Digest + Apoptose + Sense {
     /// How many ticks this agent has been alive.
     fn age(&self) -> Tick;
 
+    /// Give up a document that was engulfed but never presented.
+    ///
+    /// Called by the runtime when the agent dies, so the document can be
+    /// put back in the substrate for another agent instead of being lost.
+    fn release_undigested(&mut self) -> Option<(DocumentId, String)> {
+        None
+    }
+
     // --- Transfer (Horizontal Gene Transfer) default methods ---
 
     /// Export this agent's vocabulary as serialized bytes.
@@ -61,6 +77,13 @@ pub trait Agent: Digest + Apoptose + Sense {
         false
     }
 
+    /// Start out knowing the top `fraction` of a parent's exported
+    /// vocabulary, as offspring bred by a spawn policy. Returns the number
+    /// of terms inherited; agents without a vocabulary inherit none.
+    fn inherit_vocabulary(&mut self, _vocabulary: &[u8], _fraction: f64) -> usize {
+        0
+    }
+
     // --- Symbiose (Endosymbiosis) default methods ---
 
     /// Build a profile describing this agent's capabilities.
@@ -106,4 +129,71 @@ pub trait Agent: Digest + Apoptose + Sense {
     fn vocabulary_size(&self) -> usize {
         0
     }
+
+    /// Up to `top_n` of this agent's vocabulary terms with their weight
+    /// (how often the agent presented or saw each), heaviest first.
+    /// Agents without a vocabulary return nothing.
+    fn vocabulary_snapshot(&self, _top_n: usize) -> Vec<(String, f64)> {
+        Vec::new()
+    }
+
+    /// Adopt the colony's default emergence settings on spawn. Agents
+    /// without quorum behavior, or configured explicitly, ignore them.
+    fn adopt_emerge_defaults(&mut self, _config: &EmergeConfig) {}
+
+    /// Adopt the colony's tokenizer on spawn, so concepts are extracted
+    /// the same way queries are split. Agents that don't tokenize text
+    /// ignore it.
+    fn adopt_tokenizer(&mut self, _tokenizer: &Tokenizer) {}
+
+    /// Adopt the colony's default digestion limits on spawn. Agents that
+    /// don't extract fragments from text, or were configured explicitly,
+    /// ignore them.
+    fn adopt_digestion_limits(&mut self, _limits: &DigestionLimits) {}
+
+    /// How close this agent's region is to quorum, for agents that sense it.
+    fn quorum_status(&self, _substrate: &dyn Substrate) -> Option<QuorumStatus> {
+        None
+    }
+}
+
+/// The `top_n` heaviest of `weights`, heaviest first and ties in term
+/// order; a helper for [`Agent::vocabulary_snapshot`]. Only the terms
+/// kept are copied.
+pub fn top_terms<'a>(
+    weights: impl IntoIterator<Item = (&'a str, f64)>,
+    top_n: usize,
+) -> Vec<(String, f64)> {
+    if top_n == 0 {
+        return Vec::new();
+    }
+    let mut ranked: Vec<(&str, f64)> = weights.into_iter().collect();
+    let by_weight = |a: &(&str, f64), b: &(&str, f64)| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(core::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(b.0))
+    };
+    if ranked.len() > top_n {
+        ranked.select_nth_unstable_by(top_n - 1, by_weight);
+        ranked.truncate(top_n);
+    }
+    ranked.sort_by(by_weight);
+    ranked
+        .into_iter()
+        .map(|(term, weight)| (term.to_string(), weight))
+        .collect()
+}
+
+/// A number in [0, 1) that depends only on `id` and `tick`, for agent
+/// decisions that should differ between agents and ticks yet replay the
+/// same.
+pub fn agent_roll(id: &AgentId, tick: Tick) -> f64 {
+    let bits = id.0.as_u128();
+    // SplitMix64 of the ID's halves and the tick
+    let mut z =
+        (bits as u64 ^ (bits >> 64) as u64).wrapping_add(tick.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
 }
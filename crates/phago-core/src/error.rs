@@ -2,11 +2,15 @@
 //!
 //! Provides structured error handling instead of panics.
 
-use std::error::Error;
-use std::fmt;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+#[cfg(all(not(feature = "std"), feature = "serde_json"))]
+use alloc::string::ToString;
+use core::error::Error;
+use core::fmt;
 
 /// Result type for Phago operations.
-pub type Result<T> = std::result::Result<T, PhagoError>;
+pub type Result<T> = core::result::Result<T, PhagoError>;
 
 /// Errors that can occur during Phago operations.
 #[derive(Debug, Clone)]
@@ -46,12 +50,14 @@ impl fmt::Display for PhagoError {
 
 impl Error for PhagoError {}
 
+#[cfg(feature = "std")]
 impl From<std::io::Error> for PhagoError {
     fn from(e: std::io::Error) -> Self {
         PhagoError::Io(e.to_string())
     }
 }
 
+#[cfg(feature = "serde_json")]
 impl From<serde_json::Error> for PhagoError {
     fn from(e: serde_json::Error) -> Self {
         PhagoError::Serialization(e.to_string())
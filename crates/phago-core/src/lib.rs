@@ -27,14 +27,31 @@
 //! // Create a deterministic agent ID (for testing)
 //! let id = AgentId::from_seed(42);
 //! ```
+//!
+//! ## Features
+//!
+//! - `std` (default) — random (v4) IDs, `std::io` error conversions,
+//!   gradient blending and Louvain community detection.
+//! - `alloc` — the types, the primitive traits and the prelude on `alloc`
+//!   alone, for `no_std` guests such as `wasm32-unknown-unknown` agents.
+//!   Without `std`, `AgentId::new()` and friends hand out deterministic IDs.
+//! - `serde_json` (default) — typed signal payloads and conversion from
+//!   `serde_json::Error`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 pub mod agent;
 pub mod error;
+#[cfg(feature = "std")]
 pub mod louvain;
+mod math;
 pub mod prelude;
 pub mod primitives;
 pub mod semantic;
 pub mod signal;
 pub mod substrate;
+pub mod tokenize;
 pub mod topology;
 pub mod types;
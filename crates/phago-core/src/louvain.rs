@@ -8,6 +8,10 @@
 //! 2. **Aggregation**: Build a new graph with communities as nodes
 //!
 //! Reference: Blondel et al. (2008) "Fast unfolding of communities in large networks"
+//!
+//! The result is deterministic: ties are broken towards the lowest
+//! community index, and communities are listed in the order of their
+//! first node.
 
 use crate::types::NodeId;
 use std::collections::HashMap;
@@ -45,11 +49,14 @@ struct LouvainGraph {
     adj: Vec<Vec<(usize, f64)>>,
     /// Total edge weight (sum of all weights, counting undirected edges once).
     total_weight: f64,
+    /// Weight of the expected-edges term; above 1 favours smaller
+    /// communities.
+    resolution: f64,
 }
 
 impl LouvainGraph {
     /// Create from a list of edges with weights.
-    fn from_edges(node_count: usize, edges: &[(usize, usize, f64)]) -> Self {
+    fn from_edges(node_count: usize, edges: &[(usize, usize, f64)], resolution: f64) -> Self {
         let mut nodes: Vec<LouvainNode> = (0..node_count)
             .map(|i| LouvainNode {
                 id: i,
@@ -81,6 +88,7 @@ impl LouvainGraph {
             nodes,
             adj,
             total_weight,
+            resolution,
         }
     }
 
@@ -113,10 +121,10 @@ impl LouvainGraph {
             }
         }
 
-        // Q = Σc [ (internal_c / m) - (degree_c / 2m)^2 ]
+        // Q = Σc [ (internal_c / m) - γ (degree_c / 2m)^2 ]
         for (c, internal) in &comm_internal {
             let degree = comm_degree.get(c).unwrap_or(&0.0);
-            q += internal / self.total_weight - (degree / m2).powi(2);
+            q += internal / self.total_weight - self.resolution * (degree / m2).powi(2);
         }
 
         q
@@ -152,8 +160,8 @@ impl LouvainGraph {
             }
         }
 
-        // ΔQ = ki_in/m - (sigma_tot * ki) / (2m^2)
-        ki_in / self.total_weight - (sigma_tot * ki) / (m2 * self.total_weight)
+        // ΔQ = ki_in/m - γ (sigma_tot * ki) / (2m^2)
+        ki_in / self.total_weight - self.resolution * (sigma_tot * ki) / (m2 * self.total_weight)
     }
 
     /// Phase 1: Local moving of nodes to maximize modularity.
@@ -169,21 +177,20 @@ impl LouvainGraph {
             for i in 0..n {
                 let current_community = self.nodes[i].community;
 
-                // Find neighboring communities
-                let mut neighbor_communities: HashMap<usize, f64> = HashMap::new();
-                for &(j, weight) in &self.adj[i] {
-                    let c = self.nodes[j].community;
-                    *neighbor_communities.entry(c).or_insert(0.0) += weight;
-                }
-
-                // Always consider staying in current community
-                neighbor_communities.entry(current_community).or_insert(0.0);
+                // Find neighboring communities, in index order so ties are
+                // broken the same way every run
+                let mut neighbor_communities: Vec<usize> = self.adj[i]
+                    .iter()
+                    .map(|&(j, _)| self.nodes[j].community)
+                    .collect();
+                neighbor_communities.sort_unstable();
+                neighbor_communities.dedup();
 
-                // Find best community
+                // Find best community (staying has no gain)
                 let mut best_community = current_community;
                 let mut best_gain = 0.0;
 
-                for &c in neighbor_communities.keys() {
+                for c in neighbor_communities {
                     // Temporarily remove from current community for gain calculation
                     self.nodes[i].community = current_community; // Ensure starting state
                     let gain = if c == current_community {
@@ -257,7 +264,7 @@ impl LouvainGraph {
         }
 
         // Convert to edge list (halve weights for undirected, except self-loops)
-        let edges: Vec<(usize, usize, f64)> = new_edges
+        let mut edges: Vec<(usize, usize, f64)> = new_edges
             .into_iter()
             .map(|((a, b), w)| {
                 if a == b {
@@ -267,8 +274,9 @@ impl LouvainGraph {
                 }
             })
             .collect();
+        edges.sort_unstable_by_key(|&(a, b, _)| (a, b));
 
-        let new_graph = LouvainGraph::from_edges(new_node_count, &edges);
+        let new_graph = LouvainGraph::from_edges(new_node_count, &edges, self.resolution);
         (new_graph, communities)
     }
 }
@@ -282,6 +290,18 @@ impl LouvainGraph {
 /// # Returns
 /// A `LouvainResult` containing communities (as NodeIds), modularity score, and pass count.
 pub fn louvain_communities(node_ids: &[NodeId], edges: &[(usize, usize, f64)]) -> LouvainResult {
+    louvain_communities_with_resolution(node_ids, edges, 1.0)
+}
+
+/// Run Louvain community detection optimizing modularity at `resolution`.
+///
+/// A resolution above 1.0 yields more, smaller communities; below 1.0
+/// fewer, larger ones. The reported modularity is at the same resolution.
+pub fn louvain_communities_with_resolution(
+    node_ids: &[NodeId],
+    edges: &[(usize, usize, f64)],
+    resolution: f64,
+) -> LouvainResult {
     if node_ids.is_empty() {
         return LouvainResult {
             communities: Vec::new(),
@@ -299,7 +319,7 @@ pub fn louvain_communities(node_ids: &[NodeId], edges: &[(usize, usize, f64)]) -
         };
     }
 
-    let mut graph = LouvainGraph::from_edges(node_ids.len(), edges);
+    let mut graph = LouvainGraph::from_edges(node_ids.len(), edges, resolution);
     let mut dendrogram: Vec<Vec<Vec<usize>>> = Vec::new();
     let mut passes = 0;
     const MAX_PASSES: usize = 100;
@@ -367,14 +387,17 @@ pub fn louvain_communities(node_ids: &[NodeId], edges: &[(usize, usize, f64)]) -
         }
     }
 
-    // Group nodes by community
-    let mut community_map: HashMap<usize, Vec<NodeId>> = HashMap::new();
+    // Group nodes by community, in order of each community's first node
+    let mut community_index: HashMap<usize, usize> = HashMap::new();
+    let mut communities: Vec<Vec<NodeId>> = Vec::new();
     for (i, &comm) in final_mapping.iter().enumerate() {
-        community_map.entry(comm).or_default().push(node_ids[i]);
+        let index = *community_index.entry(comm).or_insert_with(|| {
+            communities.push(Vec::new());
+            communities.len() - 1
+        });
+        communities[index].push(node_ids[i]);
     }
 
-    let communities: Vec<Vec<NodeId>> = community_map.into_values().collect();
-
     LouvainResult {
         communities,
         modularity: final_modularity,
@@ -400,7 +423,7 @@ pub fn compute_modularity(
         return 0.0;
     }
 
-    let mut graph = LouvainGraph::from_edges(node_count, edges);
+    let mut graph = LouvainGraph::from_edges(node_count, edges, 1.0);
 
     // Apply the given partition
     for (i, &comm) in partition.iter().enumerate() {
@@ -602,4 +625,29 @@ mod tests {
             "nodes 2 and 3 should be in same community"
         );
     }
+
+    #[test]
+    fn resolution_sets_community_size() {
+        // Two triangles joined by a strong bridge
+        let nodes: Vec<NodeId> = (0..6).map(|i| node_id(i as u64)).collect();
+        let edges = vec![
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (0, 2, 1.0),
+            (3, 4, 1.0),
+            (4, 5, 1.0),
+            (3, 5, 1.0),
+            (2, 3, 1.0),
+        ];
+        let coarse = louvain_communities_with_resolution(&nodes, &edges, 0.1);
+        assert_eq!(coarse.communities.len(), 1);
+        let default = louvain_communities_with_resolution(&nodes, &edges, 1.0);
+        assert_eq!(default.communities.len(), 2);
+        let fine = louvain_communities_with_resolution(&nodes, &edges, 5.0);
+        assert!(fine.communities.len() > 2, "{:?}", fine.communities);
+
+        // Same input, same partition and order
+        let again = louvain_communities_with_resolution(&nodes, &edges, 1.0);
+        assert_eq!(again.communities, default.communities);
+    }
 }
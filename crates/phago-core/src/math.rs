@@ -0,0 +1,24 @@
+//! Float math that works with and without `std`.
+//!
+//! `f64::sqrt` and friends live in `std`; `no_std` builds fall back to
+//! `libm`.
+
+#[cfg(feature = "std")]
+pub(crate) fn sqrt(x: f64) -> f64 {
+    x.sqrt()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn sqrt(x: f64) -> f64 {
+    libm::sqrt(x)
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn sqrtf(x: f32) -> f32 {
+    x.sqrt()
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn sqrtf(x: f32) -> f32 {
+    libm::sqrtf(x)
+}
@@ -12,14 +12,23 @@ pub use crate::types::{
     SymbiosisFailure, Tick, Trace, TraceType,
 };
 
+// Re-export typed signal payloads
+pub use crate::signal::{AnomalyWarning, SignalKindInfo, SignalKinds, SignalPayload};
+
+// Re-export emergence settings
+pub use crate::primitives::emerge::{EmergeConfig, QuorumStatus};
+
+// Re-export digestion limits
+pub use crate::primitives::digest::{DigestionLimits, FragmentRanking};
+
 // Re-export the Agent trait
 pub use crate::agent::Agent;
 
 // Re-export the Substrate trait
-pub use crate::substrate::Substrate;
+pub use crate::substrate::{Ambient, Substrate};
 
 // Re-export the TopologyGraph trait
-pub use crate::topology::TopologyGraph;
+pub use crate::topology::{TopologyError, TopologyGraph};
 
 // Re-export error types
 pub use crate::error::{PhagoError, Result};
@@ -31,4 +40,5 @@ pub use crate::semantic::{
 };
 
 // Re-export Louvain community detection
+#[cfg(feature = "std")]
 pub use crate::louvain::{compute_modularity, louvain_communities, LouvainResult};
@@ -12,6 +12,9 @@
 //! semantics ARE phagocytosis.
 
 use crate::types::DigestionResult;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
 
 /// Consume input, break it down, and present extracted fragments.
 ///
@@ -52,3 +55,68 @@ pub trait Digest {
         self.present()
     }
 }
+
+/// How a digester ranks terms when it can only keep some of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FragmentRanking {
+    /// Occurrences in the document.
+    #[default]
+    Frequency,
+    /// Occurrences in the document, discounted by how many documents the
+    /// colony has already seen the term in.
+    TfIdf,
+}
+
+/// Bounds on what a single document contributes to the graph.
+///
+/// Unbounded, a long document presents every distinct term and the colony
+/// wires every pair of them: 800 terms become 320k tentative edges. Set
+/// these per agent, or colony-wide through the runtime's
+/// `ColonyConfig::digestion`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestionLimits {
+    /// Most fragments presented per document, best ranked first
+    /// (default: unlimited).
+    pub max_fragments_per_document: Option<usize>,
+    /// How terms are ranked for `max_fragments_per_document`
+    /// (default: frequency).
+    pub ranking: FragmentRanking,
+    /// Occurrences a term needs in a document to be presented (default: 1).
+    pub min_term_frequency: usize,
+    /// Most concept pairs wired per presentation, strongest co-occurrences
+    /// first (default: unlimited). The colony enforces this whichever agent
+    /// presents.
+    pub max_pairs_per_presentation: Option<usize>,
+    /// Drop numbers, hex blobs and overlong tokens (default: true). Ignored
+    /// by tokenizers that keep identifiers whole, which is how code is
+    /// digested.
+    pub filter_token_shapes: bool,
+}
+
+impl Default for DigestionLimits {
+    fn default() -> Self {
+        Self {
+            max_fragments_per_document: None,
+            ranking: FragmentRanking::default(),
+            min_term_frequency: 1,
+            max_pairs_per_presentation: None,
+            filter_token_shapes: true,
+        }
+    }
+}
+
+/// Longest term, in characters, kept by the token-shape filter.
+pub const MAX_TERM_CHARS: usize = 24;
+
+/// Whether the token-shape filter drops `term`: pure numbers, hex blobs
+/// of eight or more digits, and terms longer than [`MAX_TERM_CHARS`].
+pub fn is_noise_term(term: &str) -> bool {
+    let len = term.chars().count();
+    let numeric = term.chars().all(|c| c.is_numeric());
+    let hex = len >= 8
+        && term.chars().all(|c| c.is_ascii_hexdigit())
+        && term.chars().any(|c| c.is_ascii_digit());
+    numeric || hex || len > MAX_TERM_CHARS
+}
@@ -11,6 +11,86 @@
 
 use crate::substrate::Substrate;
 use crate::types::*;
+use serde::{Deserialize, Serialize};
+
+/// Quorum and insight thresholds for agents that implement [`Emerge`].
+///
+/// On a small corpus the default threshold may never be reached; on a
+/// large one it is reached constantly. Set it per agent, or colony-wide
+/// through the runtime's `ColonyConfig::emerge`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmergeConfig {
+    /// Signal density at which quorum is reached (default: 3.0).
+    #[serde(default = "default_quorum_threshold")]
+    pub quorum_threshold: f64,
+    /// Strong neighbors a concept needs to anchor a topic cluster (default: 3).
+    #[serde(default = "default_min_cluster_size")]
+    pub min_cluster_size: usize,
+    /// Minimum edge weight for a neighbor to be bridged into a cluster
+    /// (default: 0.15).
+    #[serde(default = "default_bridge_min_weight")]
+    pub bridge_min_weight: f64,
+    /// Ticks to rest after presenting insights (default: 10).
+    #[serde(default = "default_cooldown_ticks")]
+    pub cooldown_ticks: u64,
+}
+
+fn default_quorum_threshold() -> f64 {
+    3.0
+}
+
+fn default_min_cluster_size() -> usize {
+    3
+}
+
+fn default_bridge_min_weight() -> f64 {
+    0.15
+}
+
+fn default_cooldown_ticks() -> u64 {
+    10
+}
+
+impl Default for EmergeConfig {
+    fn default() -> Self {
+        Self {
+            quorum_threshold: default_quorum_threshold(),
+            min_cluster_size: default_min_cluster_size(),
+            bridge_min_weight: default_bridge_min_weight(),
+            cooldown_ticks: default_cooldown_ticks(),
+        }
+    }
+}
+
+/// How close the region an emergent agent surveys is to quorum.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuorumStatus {
+    /// Center of the surveyed region.
+    pub center: Position,
+    /// Radius of the surveyed region.
+    pub radius: f64,
+    /// Current signal density.
+    pub density: f64,
+    /// Density at which quorum is reached.
+    pub threshold: f64,
+    /// Resting after its last insights; quorum is not checked meanwhile.
+    pub cooling_down: bool,
+    /// Tick at which quorum was last reached.
+    pub last_trigger_tick: Option<Tick>,
+    /// Insights produced over the agent's lifetime.
+    pub insights_produced: u64,
+}
+
+impl QuorumStatus {
+    /// Density as a fraction of the threshold; 1.0 or more triggers.
+    pub fn progress(&self) -> f64 {
+        if self.threshold > 0.0 {
+            self.density / self.threshold
+        } else {
+            f64::INFINITY
+        }
+    }
+}
 
 /// Detect quorum and activate collective behaviors.
 ///
@@ -16,9 +16,9 @@ pub mod wire;
 
 // Re-export all traits at the primitives level
 pub use apoptose::Apoptose;
-pub use digest::Digest;
+pub use digest::{Digest, DigestionLimits, FragmentRanking};
 pub use dissolve::Dissolve;
-pub use emerge::Emerge;
+pub use emerge::{Emerge, EmergeConfig, QuorumStatus};
 pub use negate::Negate;
 pub use sense::Sense;
 pub use stigmerge::Stigmerge;
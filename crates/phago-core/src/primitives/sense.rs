@@ -9,8 +9,11 @@
 //! enough agents emit presence signals, the collective detects its own
 //! density and can trigger phase transitions.
 
+use crate::signal::SignalKinds;
 use crate::substrate::Substrate;
 use crate::types::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 /// Detect environmental signals, compute gradients, and emit signals.
 ///
@@ -22,14 +25,20 @@ pub trait Sense {
     /// The radius within which this agent can sense signals.
     fn sense_radius(&self) -> f64;
 
+    /// The signal kinds this agent senses (default: every kind).
+    fn subscription(&self) -> SignalKinds {
+        SignalKinds::All
+    }
+
     /// Read signals from the local environment.
     ///
-    /// Returns only signals within `sense_radius` of the agent's position.
+    /// Returns only signals within `sense_radius` of the agent's position
+    /// whose kind the agent subscribes to.
     fn sense(&self, substrate: &dyn Substrate) -> Vec<Signal> {
         let position = self.sense_position();
         let radius = self.sense_radius();
         substrate
-            .signals_near(&position, radius)
+            .signals_of_kinds_near(&position, radius, &self.subscription())
             .into_iter()
             .cloned()
             .collect()
@@ -14,6 +14,8 @@
 
 use crate::substrate::Substrate;
 use crate::types::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 /// Coordinate through environmental modification.
 ///
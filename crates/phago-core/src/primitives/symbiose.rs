@@ -13,6 +13,8 @@
 //! broken down. The other agent is integrated as a permanent sub-component.
 
 use crate::types::*;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
 
 /// Metadata about another agent, used to evaluate symbiosis potential.
 #[derive(Debug, Clone)]
@@ -10,6 +10,8 @@
 //! can integrate at runtime — instant skill acquisition from strangers.
 
 use crate::types::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 /// Export and import capabilities across agent boundaries.
 ///
@@ -11,6 +11,8 @@
 
 use crate::topology::TopologyGraph;
 use crate::types::*;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
 /// Strengthen used connections and prune unused ones.
 ///
@@ -4,6 +4,10 @@
 //! for the knowledge graph. When nodes have embeddings, edge weights
 //! can be modulated by semantic similarity.
 
+use crate::math::{sqrt, sqrtf};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
 /// Compute cosine similarity between two vectors.
 ///
 /// Returns a value in [-1, 1] where:
@@ -27,7 +31,7 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f64> {
         norm_b += (bi as f64) * (bi as f64);
     }
 
-    let norm = (norm_a * norm_b).sqrt();
+    let norm = sqrt(norm_a * norm_b);
     if norm == 0.0 {
         return None;
     }
@@ -146,7 +150,7 @@ pub fn l2_distance(a: &[f32], b: &[f32]) -> Option<f64> {
         })
         .sum();
 
-    Some(sum.sqrt())
+    Some(sqrt(sum))
 }
 
 /// Compute dot product between two vectors.
@@ -166,7 +170,7 @@ pub fn dot_product(a: &[f32], b: &[f32]) -> Option<f64> {
 
 /// L2 normalize a vector in place.
 pub fn l2_normalize(v: &mut [f32]) {
-    let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm = sqrtf(v.iter().map(|x| x * x).sum::<f32>());
     if norm > 0.0 {
         for x in v.iter_mut() {
             *x /= norm;
@@ -176,7 +180,7 @@ pub fn l2_normalize(v: &mut [f32]) {
 
 /// L2 normalize a vector, returning a new vector.
 pub fn l2_normalized(v: &[f32]) -> Vec<f32> {
-    let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm = sqrtf(v.iter().map(|x| x * x).sum::<f32>());
     if norm > 0.0 {
         v.iter().map(|x| x / norm).collect()
     } else {
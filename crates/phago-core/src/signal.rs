@@ -1,6 +1,159 @@
 //! Signal utilities — helpers for working with signals and gradients.
 
+use crate::math::sqrt;
 use crate::types::*;
+#[cfg(all(not(feature = "std"), feature = "serde_json"))]
+use alloc::string::ToString;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// A structured payload carried by a signal.
+///
+/// Payloads are stored as JSON tagged with [`KIND`](Self::KIND). Readers
+/// decode only the kinds they know, so a signal with an unfamiliar payload
+/// behaves like a plain signal.
+pub trait SignalPayload: Serialize + DeserializeOwned {
+    /// Tag stored in [`Signal::payload_kind`].
+    const KIND: &'static str;
+}
+
+/// Payload of an Anomaly signal naming the terms found anomalous, so
+/// digesters nearby can put off documents about them.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AnomalyWarning {
+    pub terms: Vec<String>,
+}
+
+impl SignalPayload for AnomalyWarning {
+    const KIND: &'static str = "anomaly_warning";
+}
+
+impl SignalType {
+    /// The built-in kinds, in declaration order.
+    pub const BUILT_IN: [SignalType; 6] = [
+        SignalType::Input,
+        SignalType::Presence,
+        SignalType::Quorum,
+        SignalType::Anomaly,
+        SignalType::Insight,
+        SignalType::Capability,
+    ];
+
+    /// A user-defined kind. Give it a label or its own decay rate by
+    /// registering a [`SignalKindInfo`] with the substrate.
+    pub fn custom(name: impl Into<String>) -> Self {
+        SignalType::Custom(name.into())
+    }
+
+    /// The kind named `name`: a built-in kind for its snake_case name,
+    /// otherwise a custom kind.
+    pub fn from_name(name: &str) -> Self {
+        Self::BUILT_IN
+            .into_iter()
+            .find(|kind| kind.name() == name)
+            .unwrap_or_else(|| SignalType::Custom(name.into()))
+    }
+
+    /// Snake_case name of a built-in kind, or the name of a custom one.
+    pub fn name(&self) -> &str {
+        match self {
+            SignalType::Input => "input",
+            SignalType::Presence => "presence",
+            SignalType::Quorum => "quorum",
+            SignalType::Anomaly => "anomaly",
+            SignalType::Insight => "insight",
+            SignalType::Capability => "capability",
+            SignalType::Custom(name) => name,
+        }
+    }
+
+    pub fn is_custom(&self) -> bool {
+        matches!(self, SignalType::Custom(_))
+    }
+}
+
+impl core::fmt::Display for SignalType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What a substrate knows about a signal kind beyond its name.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SignalKindInfo {
+    /// Human-readable name, e.g. for visualizations.
+    pub label: String,
+    /// Decay rate for signals of this kind in place of the colony's rate.
+    #[serde(default)]
+    pub decay_rate: Option<f64>,
+}
+
+impl SignalKindInfo {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            decay_rate: None,
+        }
+    }
+
+    pub fn with_decay_rate(mut self, rate: f64) -> Self {
+        self.decay_rate = Some(rate);
+        self
+    }
+}
+
+/// The signal kinds an agent subscribes to.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKinds {
+    /// Every kind, including ones registered later.
+    #[default]
+    All,
+    /// Only these kinds.
+    Only(Vec<SignalType>),
+}
+
+impl SignalKinds {
+    pub fn only(kinds: impl IntoIterator<Item = SignalType>) -> Self {
+        let mut only = Vec::new();
+        for kind in kinds {
+            if !only.contains(&kind) {
+                only.push(kind);
+            }
+        }
+        SignalKinds::Only(only)
+    }
+
+    /// Also subscribe to `kind`.
+    pub fn with(self, kind: SignalType) -> Self {
+        match self {
+            SignalKinds::All => SignalKinds::All,
+            SignalKinds::Only(mut kinds) => {
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+                SignalKinds::Only(kinds)
+            }
+        }
+    }
+
+    pub fn contains(&self, kind: &SignalType) -> bool {
+        match self {
+            SignalKinds::All => true,
+            SignalKinds::Only(kinds) => kinds.contains(kind),
+        }
+    }
+}
+
+impl FromIterator<SignalType> for SignalKinds {
+    fn from_iter<I: IntoIterator<Item = SignalType>>(iter: I) -> Self {
+        SignalKinds::only(iter)
+    }
+}
 
 impl Signal {
     /// Create a new signal.
@@ -17,7 +170,30 @@ impl Signal {
             position,
             emitter,
             tick,
+            payload_kind: None,
+            payload: Vec::new(),
+            sources: 1,
+        }
+    }
+
+    /// Attach a typed payload.
+    #[cfg(feature = "serde_json")]
+    pub fn with_payload<P: SignalPayload>(mut self, payload: &P) -> Self {
+        if let Ok(bytes) = serde_json::to_vec(payload) {
+            self.payload_kind = Some(P::KIND.to_string());
+            self.payload = bytes;
+        }
+        self
+    }
+
+    /// Decode the payload as `P`. `None` if the signal carries no payload,
+    /// one of another kind, or one that does not decode.
+    #[cfg(feature = "serde_json")]
+    pub fn payload<P: SignalPayload>(&self) -> Option<P> {
+        if self.payload_kind.as_deref() != Some(P::KIND) {
+            return None;
         }
+        serde_json::from_slice(&self.payload).ok()
     }
 
     /// Apply decay to this signal's intensity.
@@ -48,6 +224,16 @@ impl Gradient {
 /// This is the computational analog of how a cell senses a chemical gradient
 /// by comparing receptor binding rates across its surface.
 pub fn compute_gradient(signals: &[&Signal], from: &Position) -> Option<Gradient> {
+    compute_gradient_in(signals, from, &WorldGeometry::default())
+}
+
+/// [`compute_gradient`] in a bounded field: on a torus, signals pull the
+/// short way round.
+pub fn compute_gradient_in(
+    signals: &[&Signal],
+    from: &Position,
+    geometry: &WorldGeometry,
+) -> Option<Gradient> {
     if signals.is_empty() {
         return None;
     }
@@ -58,9 +244,8 @@ pub fn compute_gradient(signals: &[&Signal], from: &Position) -> Option<Gradient
     let mut total_intensity = 0.0;
 
     for signal in signals {
-        let dx = signal.position.x - from.x;
-        let dy = signal.position.y - from.y;
-        let dist = (dx * dx + dy * dy).sqrt().max(0.001); // Avoid division by zero
+        let Position { x: dx, y: dy } = geometry.offset(from, &signal.position);
+        let dist = sqrt(dx * dx + dy * dy).max(0.001); // Avoid division by zero
 
         // Weight by intensity, inversely by distance
         let weight = signal.intensity / dist;
@@ -73,7 +258,7 @@ pub fn compute_gradient(signals: &[&Signal], from: &Position) -> Option<Gradient
         return None;
     }
 
-    let magnitude = (weighted_x * weighted_x + weighted_y * weighted_y).sqrt();
+    let magnitude = sqrt(weighted_x * weighted_x + weighted_y * weighted_y);
     if magnitude < f64::EPSILON {
         return None;
     }
@@ -84,3 +269,181 @@ pub fn compute_gradient(signals: &[&Signal], from: &Position) -> Option<Gradient
         magnitude,
     ))
 }
+
+/// Combine per-type gradients into a single heading.
+///
+/// Each gradient contributes its vector (`direction * magnitude`) scaled by
+/// the weight of its signal type; types without a weight are ignored and
+/// negative weights repel. The result is labelled with the type that
+/// contributed most, so callers can tell what they are actually chasing.
+/// Returns `None` when nothing is weighted or the contributions cancel out.
+#[cfg(feature = "std")]
+pub fn blend_gradients(
+    gradients: &[Gradient],
+    weights: &HashMap<SignalType, f64>,
+) -> Option<Gradient> {
+    let mut x = 0.0;
+    let mut y = 0.0;
+    let mut dominant: Option<(&SignalType, f64)> = None;
+
+    for gradient in gradients {
+        let Some(&weight) = weights.get(&gradient.signal_type) else {
+            continue;
+        };
+        let pull = gradient.magnitude * weight;
+        x += gradient.direction.x * pull;
+        y += gradient.direction.y * pull;
+        if dominant.is_none_or(|(_, best)| pull.abs() > best) {
+            dominant = Some((&gradient.signal_type, pull.abs()));
+        }
+    }
+
+    let magnitude = sqrt(x * x + y * y);
+    let (signal_type, _) = dominant?;
+    if magnitude < f64::EPSILON {
+        return None;
+    }
+
+    Some(Gradient::new(
+        signal_type.clone(),
+        Position::new(x / magnitude, y / magnitude),
+        magnitude,
+    ))
+}
+
+/// How strongly a signal is felt from a position.
+///
+/// Intensity attenuated by distance, so a faint signal next to the agent
+/// can outrank a strong one far away.
+pub fn perceived_strength(signal: &Signal, from: &Position) -> f64 {
+    perceived_strength_in(signal, from, &WorldGeometry::default())
+}
+
+/// [`perceived_strength`] with distance measured in `geometry`.
+pub fn perceived_strength_in(signal: &Signal, from: &Position, geometry: &WorldGeometry) -> f64 {
+    signal.intensity / (1.0 + geometry.distance(&signal.position, from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(signal_type: SignalType, intensity: f64, x: f64, y: f64) -> Signal {
+        Signal::new(
+            signal_type,
+            intensity,
+            Position::new(x, y),
+            AgentId::new(),
+            0,
+        )
+    }
+
+    fn weights(entries: &[(SignalType, f64)]) -> HashMap<SignalType, f64> {
+        entries.iter().cloned().collect()
+    }
+
+    #[test]
+    fn opposing_signals_cancel() {
+        let origin = Position::new(0.0, 0.0);
+        let east = signal(SignalType::Input, 1.0, 5.0, 0.0);
+        let west = signal(SignalType::Capability, 1.0, -5.0, 0.0);
+        let gradients = vec![
+            compute_gradient(&[&east], &origin).unwrap(),
+            compute_gradient(&[&west], &origin).unwrap(),
+        ];
+
+        let w = weights(&[(SignalType::Input, 1.0), (SignalType::Capability, 1.0)]);
+        assert!(blend_gradients(&gradients, &w).is_none());
+    }
+
+    #[test]
+    fn weights_scale_contributions() {
+        let origin = Position::new(0.0, 0.0);
+        let east = signal(SignalType::Input, 1.0, 5.0, 0.0);
+        let north = signal(SignalType::Capability, 1.0, 0.0, 5.0);
+        let gradients = vec![
+            compute_gradient(&[&east], &origin).unwrap(),
+            compute_gradient(&[&north], &origin).unwrap(),
+        ];
+
+        let w = weights(&[(SignalType::Input, 3.0), (SignalType::Capability, 1.0)]);
+        let blended = blend_gradients(&gradients, &w).unwrap();
+        assert_eq!(blended.signal_type, SignalType::Input);
+        assert!((blended.direction.y / blended.direction.x - 1.0 / 3.0).abs() < 1e-9);
+        assert!((blended.magnitude - 10.0_f64.sqrt()).abs() < 1e-9);
+
+        // Doubling every weight doubles the magnitude but keeps the heading
+        let doubled = weights(&[(SignalType::Input, 6.0), (SignalType::Capability, 2.0)]);
+        let scaled = blend_gradients(&gradients, &doubled).unwrap();
+        assert!((scaled.magnitude - 2.0 * blended.magnitude).abs() < 1e-9);
+        assert!((scaled.direction.x - blended.direction.x).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unweighted_types_are_ignored_and_negative_weights_repel() {
+        let origin = Position::new(0.0, 0.0);
+        let east = signal(SignalType::Anomaly, 1.0, 5.0, 0.0);
+        let gradients = vec![compute_gradient(&[&east], &origin).unwrap()];
+
+        assert!(blend_gradients(&gradients, &weights(&[(SignalType::Input, 1.0)])).is_none());
+
+        let repelled =
+            blend_gradients(&gradients, &weights(&[(SignalType::Anomaly, -1.0)])).unwrap();
+        assert!(repelled.direction.x < 0.0);
+    }
+
+    #[test]
+    fn gradients_pull_across_the_seam_of_a_torus() {
+        let bounds = Rect::new(Position::new(0.0, 0.0), Position::new(100.0, 100.0));
+        let torus = WorldGeometry::bounded(bounds, BoundaryBehavior::Wrap);
+        let near_right_edge = Position::new(98.0, 50.0);
+        let across_seam = signal(SignalType::Input, 1.0, 2.0, 50.0);
+
+        let flat = compute_gradient(&[&across_seam], &near_right_edge).unwrap();
+        assert!(flat.direction.x < 0.0);
+        let wrapped = compute_gradient_in(&[&across_seam], &near_right_edge, &torus).unwrap();
+        assert!(wrapped.direction.x > 0.99, "{wrapped:?}");
+        assert!((torus.distance(&near_right_edge, &across_seam.position) - 4.0).abs() < 1e-9);
+        assert!(
+            perceived_strength_in(&across_seam, &near_right_edge, &torus)
+                > perceived_strength(&across_seam, &near_right_edge)
+        );
+    }
+
+    #[test]
+    fn confine_clamps_wraps_and_reflects() {
+        let bounds = Rect::new(Position::new(100.0, 100.0), Position::new(-100.0, -100.0));
+        let at = |behavior| WorldGeometry::bounded(bounds, behavior);
+        let outside = Position::new(130.0, -250.0);
+
+        assert_eq!(
+            at(BoundaryBehavior::Clamp).confine(outside),
+            Position::new(100.0, -100.0)
+        );
+        assert_eq!(
+            at(BoundaryBehavior::Wrap).confine(outside),
+            Position::new(-70.0, -50.0)
+        );
+        assert_eq!(
+            at(BoundaryBehavior::Reflect).confine(outside),
+            Position::new(70.0, 50.0)
+        );
+        let inside = Position::new(12.5, -3.0);
+        for behavior in [
+            BoundaryBehavior::Clamp,
+            BoundaryBehavior::Wrap,
+            BoundaryBehavior::Reflect,
+        ] {
+            assert_eq!(at(behavior).confine(inside), inside);
+        }
+        assert_eq!(WorldGeometry::default().confine(outside), outside);
+    }
+
+    #[test]
+    fn perceived_strength_falls_off_with_distance() {
+        let origin = Position::new(0.0, 0.0);
+        let near = signal(SignalType::Input, 0.5, 1.0, 0.0);
+        let far = signal(SignalType::Input, 1.0, 9.0, 0.0);
+        assert!(perceived_strength(&near, &origin) > perceived_strength(&far, &origin));
+    }
+}
@@ -4,7 +4,34 @@
 //! the knowledge graph, and trace deposits. Agents read from and write to
 //! the substrate, but never directly to each other.
 
+#[cfg(feature = "std")]
+use crate::signal::{blend_gradients, compute_gradient_in};
+use crate::signal::{perceived_strength_in, SignalKindInfo, SignalKinds};
+use crate::topology::TopologyError;
 use crate::types::*;
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Colony-wide conditions agents read through [`Substrate::ambient`].
+///
+/// The runtime sets them each tick; agents only read them.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Ambient {
+    /// How far the colony has moved from exploring to exploiting, from 0.0
+    /// (explore as much as each agent's own bias says) to 1.0 (do not
+    /// explore).
+    pub exploitation: f64,
+}
+
+impl Ambient {
+    /// Chance an agent with explore bias `personal` explores this tick:
+    /// its own bias, scaled down by the colony's exploitation.
+    pub fn explore_bias(&self, personal: f64) -> f64 {
+        (personal * (1.0 - self.exploitation)).clamp(0.0, 1.0)
+    }
+}
 
 /// The shared environment that all agents sense and modify.
 ///
@@ -14,6 +41,19 @@ use crate::types::*;
 /// - A **knowledge graph** for stigmergy (agents deposit and read structure)
 /// - **Trace storage** for indirect coordination
 pub trait Substrate {
+    // --- Spatial field ---
+
+    /// The shape of the spatial field. Unbounded by default.
+    fn geometry(&self) -> WorldGeometry {
+        WorldGeometry::default()
+    }
+
+    /// Distance between two positions in this field (the short way round
+    /// when it wraps).
+    fn distance(&self, a: &Position, b: &Position) -> f64 {
+        self.geometry().distance(a, b)
+    }
+
     // --- Signal field ---
 
     /// Read all signals within a radius of a position.
@@ -22,9 +62,96 @@ pub trait Substrate {
     /// Emit a signal into the substrate.
     fn emit_signal(&mut self, signal: Signal);
 
+    /// Read the signals within a radius that carry a payload of `kind`
+    /// (see [`SignalPayload`](crate::signal::SignalPayload)).
+    fn signals_with_payload_near(
+        &self,
+        position: &Position,
+        radius: f64,
+        kind: &str,
+    ) -> Vec<&Signal> {
+        self.signals_near(position, radius)
+            .into_iter()
+            .filter(|s| s.payload_kind.as_deref() == Some(kind))
+            .collect()
+    }
+
+    /// Read the signals within a radius whose kind is in `kinds`.
+    fn signals_of_kinds_near(
+        &self,
+        position: &Position,
+        radius: f64,
+        kinds: &SignalKinds,
+    ) -> Vec<&Signal> {
+        self.signals_near(position, radius)
+            .into_iter()
+            .filter(|s| kinds.contains(&s.signal_type))
+            .collect()
+    }
+
+    /// What has been registered about a signal kind, if anything.
+    fn signal_kind_info(&self, _kind: &SignalType) -> Option<&SignalKindInfo> {
+        None
+    }
+
     /// Decay all signals by a rate (0.0-1.0). Signals below threshold are removed.
+    ///
+    /// Kinds registered with their own decay rate decay at that rate instead.
     fn decay_signals(&mut self, rate: f64, removal_threshold: f64);
 
+    /// Combine the gradients of several signal types into one heading.
+    ///
+    /// Signals within `radius` are grouped by type, each group yields a
+    /// gradient, and the gradients are blended with the given per-type
+    /// weights (see [`blend_gradients`]). Types missing from `weights`
+    /// are ignored.
+    #[cfg(feature = "std")]
+    fn gradient_blend(
+        &self,
+        position: &Position,
+        weights: &HashMap<SignalType, f64>,
+        radius: f64,
+    ) -> Option<Gradient> {
+        let mut by_type: HashMap<&SignalType, Vec<&Signal>> = HashMap::new();
+        for signal in self.signals_near(position, radius) {
+            if weights.contains_key(&signal.signal_type) {
+                by_type.entry(&signal.signal_type).or_default().push(signal);
+            }
+        }
+        let geometry = self.geometry();
+        let gradients: Vec<Gradient> = by_type
+            .values()
+            .filter_map(|signals| compute_gradient_in(signals, position, &geometry))
+            .collect();
+        blend_gradients(&gradients, weights)
+    }
+
+    /// The `k` signals of one type that are felt most strongly from
+    /// `position`, strongest first.
+    ///
+    /// Strength is intensity attenuated by distance, so agents can weigh
+    /// a few alternatives instead of only the single maximum.
+    fn strongest_k_signals(
+        &self,
+        position: &Position,
+        signal_type: &SignalType,
+        k: usize,
+    ) -> Vec<&Signal> {
+        let mut signals: Vec<&Signal> = self
+            .signals_near(position, f64::INFINITY)
+            .into_iter()
+            .filter(|s| &s.signal_type == signal_type)
+            .collect();
+        let geometry = self.geometry();
+        signals.sort_by(|a, b| {
+            perceived_strength_in(b, position, &geometry)
+                .partial_cmp(&perceived_strength_in(a, position, &geometry))
+                .unwrap_or(core::cmp::Ordering::Equal)
+        });
+        signals.truncate(k);
+        signals
+    }
+
     // --- Knowledge graph ---
 
     /// Add a node to the knowledge graph.
@@ -33,8 +160,9 @@ pub trait Substrate {
     /// Get a node by ID.
     fn get_node(&self, id: &NodeId) -> Option<&NodeData>;
 
-    /// Add or update an edge between two nodes.
-    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData);
+    /// Add or update an edge between two nodes. Fails if either node is
+    /// missing.
+    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData) -> Result<(), TopologyError>;
 
     /// Get edge data between two nodes.
     fn get_edge(&self, from: &NodeId, to: &NodeId) -> Option<&EdgeData>;
@@ -85,11 +213,25 @@ pub trait Substrate {
     /// Get all documents.
     fn all_documents(&self) -> Vec<&Document>;
 
+    // --- Embeddings ---
+
+    /// The embedding the runtime has cached for `term`, so agents need not
+    /// compute it again. `None` unless the runtime keeps a cache.
+    fn cached_embedding(&self, _term: &str) -> Option<Vec<f32>> {
+        None
+    }
+
     // --- Lifecycle ---
 
     /// Current simulation tick.
     fn current_tick(&self) -> Tick;
 
+    /// Colony-wide conditions, such as how far the colony has moved from
+    /// exploring to exploiting. Neutral unless the runtime sets them.
+    fn ambient(&self) -> Ambient {
+        Ambient::default()
+    }
+
     /// Advance the tick counter.
     fn advance_tick(&mut self);
 }
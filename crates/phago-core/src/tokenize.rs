@@ -0,0 +1,320 @@
+//! Tokenization shared by ingestion and retrieval.
+//!
+//! Digesters extract concepts with a [`Tokenizer`] and queries match
+//! against those concepts with one, so both sides must be configured the
+//! same way — a stopword kept at ingest but dropped at query time (or a
+//! hyphenated identifier split on one side only) silently loses matches.
+//! The runtime builds one from `ColonyConfig::tokenizer` and hands it to
+//! every agent it spawns and every query it runs.
+
+use alloc::collections::BTreeSet;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde::{Deserialize, Serialize};
+
+/// A stopword preset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "fr")]
+    French,
+    #[serde(rename = "es")]
+    Spanish,
+}
+
+impl Language {
+    /// ISO 639-1 code, as used in configuration files.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// The preset's stopwords, lowercase.
+    pub fn stopwords(&self) -> &'static [&'static str] {
+        match self {
+            Language::English => ENGLISH,
+            Language::German => GERMAN,
+            Language::French => FRENCH,
+            Language::Spanish => SPANISH,
+        }
+    }
+}
+
+const ENGLISH: &[&str] = &[
+    "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
+    "do", "does", "did", "will", "would", "shall", "should", "may", "might", "must", "can",
+    "could", "of", "in", "to", "for", "with", "on", "at", "from", "by", "about", "as", "into",
+    "through", "during", "before", "after", "above", "below", "between", "out", "off", "over",
+    "under", "again", "further", "then", "once", "here", "there", "when", "where", "why", "how",
+    "all", "each", "every", "both", "few", "more", "most", "other", "some", "such", "no", "nor",
+    "not", "only", "own", "same", "so", "than", "too", "very", "just", "because", "but", "and",
+    "or", "if", "while", "that", "this", "these", "those", "it", "its", "they", "them", "their",
+    "we", "our", "you", "your", "he", "she", "his", "her", "which", "what", "who", "whom",
+];
+
+const GERMAN: &[&str] = &[
+    "der", "die", "das", "den", "dem", "des", "ein", "eine", "einer", "eines", "einem", "einen",
+    "und", "oder", "aber", "doch", "sondern", "denn", "ist", "sind", "war", "waren", "wird",
+    "werden", "wurde", "wurden", "sein", "seine", "seiner", "seinen", "hat", "haben", "hatte",
+    "hatten", "kann", "können", "muss", "müssen", "soll", "sollen", "will", "wollen", "nicht",
+    "kein", "keine", "auch", "noch", "schon", "nur", "sehr", "mit", "von", "vom", "zu", "zum",
+    "zur", "bei", "beim", "aus", "auf", "für", "über", "unter", "vor", "nach", "durch", "gegen",
+    "ohne", "um", "an", "am", "im", "in", "ins", "als", "wie", "wenn", "dass", "daß", "ob", "weil",
+    "so", "da", "hier", "dort", "dies", "diese", "dieser", "dieses", "diesem", "diesen", "jede",
+    "jeder", "jedes", "alle", "ich", "du", "er", "sie", "es", "wir", "ihr", "ihn", "ihm", "ihre",
+    "ihren", "man", "sich", "was", "wer", "wo", "welche", "welcher", "welches", "zwischen", "mehr",
+    "viel", "einige", "andere", "dann", "also", "bis", "seit", "sowie",
+];
+
+const FRENCH: &[&str] = &[
+    "le", "la", "les", "l", "un", "une", "des", "du", "de", "d", "et", "ou", "mais", "donc", "car",
+    "ni", "est", "sont", "était", "étaient", "être", "été", "avoir", "a", "ont", "avait", "fait",
+    "faire", "peut", "pour", "par", "avec", "sans", "sous", "sur", "dans", "en", "entre", "vers",
+    "chez", "ce", "cet", "cette", "ces", "celui", "celle", "ceux", "qui", "que", "quoi", "dont",
+    "où", "quand", "comme", "si", "ne", "pas", "plus", "moins", "très", "aussi", "tout", "tous",
+    "toute", "toutes", "je", "tu", "il", "elle", "nous", "vous", "ils", "elles", "on", "se", "sa",
+    "son", "ses", "leur", "leurs", "notre", "nos", "votre", "vos", "mon", "ma", "mes", "au", "aux",
+    "y", "lui", "même", "autre", "autres", "alors", "ainsi", "encore", "déjà", "avant", "après",
+    "depuis", "pendant",
+];
+
+const SPANISH: &[&str] = &[
+    "el", "la", "los", "las", "un", "una", "unos", "unas", "y", "o", "pero", "sino", "de", "del",
+    "al", "a", "en", "con", "sin", "por", "para", "sobre", "entre", "hacia", "desde", "hasta",
+    "es", "son", "era", "eran", "ser", "fue", "fueron", "está", "están", "estar", "ha", "han",
+    "haber", "había", "que", "qué", "quien", "quién", "cual", "cuál", "cuando", "donde", "como",
+    "si", "no", "más", "menos", "muy", "también", "ya", "todo", "todos", "toda", "todas", "este",
+    "esta", "estos", "estas", "ese", "esa", "esos", "esas", "aquel", "yo", "tú", "él", "ella",
+    "nosotros", "vosotros", "ellos", "ellas", "se", "su", "sus", "lo", "le", "les", "mi", "mis",
+    "nuestro", "otro", "otra", "otros", "mismo", "porque", "pues", "entonces", "antes", "después",
+    "durante",
+];
+
+/// How text is split into terms.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenizerConfig {
+    /// Stopword preset (default: English).
+    pub language: Language,
+    /// Words to drop in addition to the preset.
+    pub extra_stopwords: Vec<String>,
+    /// Preset stopwords to keep as terms.
+    pub kept_stopwords: Vec<String>,
+    /// Shortest term kept, in characters (default: 3).
+    pub min_token_len: usize,
+    /// Keep `-` and `_` inside terms, so identifiers such as `phago-core`
+    /// or `max_idle_ticks` stay whole (default: false).
+    pub keep_identifiers: bool,
+}
+
+impl Default for TokenizerConfig {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            extra_stopwords: Vec::new(),
+            kept_stopwords: Vec::new(),
+            min_token_len: 3,
+            keep_identifiers: false,
+        }
+    }
+}
+
+impl TokenizerConfig {
+    /// Defaults with the given stopword preset.
+    pub fn for_language(language: Language) -> Self {
+        Self {
+            language,
+            ..Default::default()
+        }
+    }
+}
+
+/// Splits text into lowercase terms and drops stopwords.
+///
+/// Terms are maximal runs of alphanumeric characters (plus `-` and `_`
+/// with `keep_identifiers`), lowercased with full Unicode case mapping.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tokenizer {
+    config: TokenizerConfig,
+    stopwords: BTreeSet<String>,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new(TokenizerConfig::default())
+    }
+}
+
+impl From<TokenizerConfig> for Tokenizer {
+    fn from(config: TokenizerConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+impl Tokenizer {
+    pub fn new(config: TokenizerConfig) -> Self {
+        let mut stopwords: BTreeSet<String> = config
+            .language
+            .stopwords()
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        stopwords.extend(config.extra_stopwords.iter().map(|w| w.to_lowercase()));
+        for word in &config.kept_stopwords {
+            stopwords.remove(&word.to_lowercase());
+        }
+        Self { config, stopwords }
+    }
+
+    /// Default settings with the given stopword preset.
+    pub fn for_language(language: Language) -> Self {
+        Self::new(TokenizerConfig::for_language(language))
+    }
+
+    pub fn config(&self) -> &TokenizerConfig {
+        &self.config
+    }
+
+    /// Whether the (lowercase) `word` is dropped as a stopword.
+    pub fn is_stopword(&self, word: &str) -> bool {
+        self.stopwords.contains(word)
+    }
+
+    /// Lowercase words of at least `min_token_len` characters, stopwords
+    /// included. Use this to split concept labels and source text for
+    /// matching against [`tokens`](Self::tokens).
+    pub fn words<'a>(&'a self, text: &'a str) -> impl Iterator<Item = String> + 'a {
+        let keep_identifiers = self.config.keep_identifiers;
+        text.split(move |c: char| !(c.is_alphanumeric() || keep_identifiers && is_joiner(c)))
+            .map(|w| w.trim_matches(is_joiner))
+            .filter(|w| w.chars().count() >= self.config.min_token_len)
+            .map(str::to_lowercase)
+    }
+
+    /// The terms of `text`, in order, with stopwords removed.
+    pub fn tokens(&self, text: &str) -> Vec<String> {
+        self.words(text).filter(|w| !self.is_stopword(w)).collect()
+    }
+}
+
+fn is_joiner(c: char) -> bool {
+    c == '-' || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(config: TokenizerConfig, text: &str) -> Vec<String> {
+        Tokenizer::new(config).tokens(text)
+    }
+
+    #[test]
+    fn english_drops_stopwords_and_short_words() {
+        let tokenizer = Tokenizer::default();
+        assert_eq!(
+            tokenizer.tokens("The cell membrane, and the ER: a transport system."),
+            vec!["cell", "membrane", "transport", "system"]
+        );
+    }
+
+    #[test]
+    fn german_preset() {
+        let text = "Die Zelle und der Zellkern sind über Poren verbunden";
+        assert_eq!(
+            tokens(TokenizerConfig::for_language(Language::German), text),
+            vec!["zelle", "zellkern", "poren", "verbunden"]
+        );
+        // The English preset lets the German function words through
+        assert!(Tokenizer::default()
+            .tokens(text)
+            .contains(&"und".to_string()));
+    }
+
+    #[test]
+    fn french_preset() {
+        assert_eq!(
+            tokens(
+                TokenizerConfig::for_language(Language::French),
+                "Les cellules et leurs membranes dans l'organisme"
+            ),
+            vec!["cellules", "membranes", "organisme"]
+        );
+    }
+
+    #[test]
+    fn spanish_preset() {
+        assert_eq!(
+            tokens(
+                TokenizerConfig::for_language(Language::Spanish),
+                "Las células están rodeadas por una membrana"
+            ),
+            vec!["células", "rodeadas", "membrana"]
+        );
+    }
+
+    #[test]
+    fn lowercases_unicode_and_counts_characters() {
+        let tokenizer = Tokenizer::for_language(Language::German);
+        assert_eq!(
+            tokenizer.tokens("STRAẞE Straße ÉCOLE"),
+            vec!["straße", "straße", "école"]
+        );
+        // Two characters, four bytes: too short
+        assert!(tokenizer.tokens("éé").is_empty());
+        // Multi-byte letters never split a word
+        assert_eq!(tokenizer.tokens("naïveté"), vec!["naïveté"]);
+    }
+
+    #[test]
+    fn custom_stopwords_and_length() {
+        let config = TokenizerConfig {
+            extra_stopwords: vec!["Cell".to_string()],
+            kept_stopwords: vec!["between".to_string()],
+            min_token_len: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            tokens(config, "ER between cell walls"),
+            vec!["er", "between", "walls"]
+        );
+    }
+
+    #[test]
+    fn identifiers_stay_whole_when_asked() {
+        let text = "phago-core sets max_idle_ticks -- see _private";
+        assert_eq!(
+            Tokenizer::default().tokens(text),
+            vec!["phago", "core", "sets", "max", "idle", "ticks", "see", "private"]
+        );
+        let config = TokenizerConfig {
+            keep_identifiers: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            tokens(config, text),
+            vec!["phago-core", "sets", "max_idle_ticks", "see", "private"]
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn config_deserializes_language_codes() {
+        let config: TokenizerConfig =
+            serde_json::from_str(r#"{"language": "fr", "keep_identifiers": true}"#).unwrap();
+        assert_eq!(config.language, Language::French);
+        assert_eq!(config.min_token_len, 3);
+        assert!(config.keep_identifiers);
+    }
+}
@@ -4,7 +4,42 @@
 //! It encodes relationships between concepts, documents, and insights.
 //! Following Hebbian learning: the structure IS the memory.
 
+use crate::error::{GraphError, PhagoError};
 use crate::types::*;
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::error::Error;
+use core::fmt;
+
+/// Why a graph change could not be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopologyError {
+    /// An endpoint of the edge is not in the graph.
+    UnknownNode(NodeId),
+}
+
+impl fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopologyError::UnknownNode(id) => write!(f, "unknown node {}", id.0),
+        }
+    }
+}
+
+impl Error for TopologyError {}
+
+impl From<TopologyError> for PhagoError {
+    fn from(e: TopologyError) -> Self {
+        match e {
+            TopologyError::UnknownNode(id) => {
+                PhagoError::Graph(GraphError::NodeNotFound(id.0.to_string()))
+            }
+        }
+    }
+}
 
 /// A handle to the topology graph, used by the Wire primitive.
 ///
@@ -21,7 +56,8 @@ pub trait TopologyGraph {
     fn get_node_mut(&mut self, id: &NodeId) -> Option<&mut NodeData>;
 
     /// Add or update an edge. If the edge exists, the data is replaced.
-    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData);
+    /// Fails, leaving the graph unchanged, if either endpoint is missing.
+    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData) -> Result<(), TopologyError>;
 
     /// Get edge data.
     fn get_edge(&self, from: &NodeId, to: &NodeId) -> Option<&EdgeData>;
@@ -35,6 +71,10 @@ pub trait TopologyGraph {
     /// Remove an edge. Returns the removed edge data if it existed.
     fn remove_edge(&mut self, from: &NodeId, to: &NodeId) -> Option<EdgeData>;
 
+    /// Remove a node together with all of its edges.
+    /// Returns the removed node data if it existed.
+    fn remove_node(&mut self, id: &NodeId) -> Option<NodeData>;
+
     /// Get all node IDs.
     fn all_nodes(&self) -> Vec<NodeId>;
 
@@ -78,16 +118,75 @@ pub trait TopologyGraph {
             .into_iter()
             .filter(|id| {
                 self.get_node(id)
-                    .map_or(false, |n| n.label.to_lowercase() == label_lower)
+                    .is_some_and(|n| n.label.to_lowercase() == label_lower)
             })
             .collect()
     }
 
+    /// Find nodes whose label starts with `prefix` (case-insensitive).
+    /// Default implementation filters find_nodes_by_label.
+    fn find_nodes_by_label_prefix(&self, prefix: &str) -> Vec<NodeId> {
+        let prefix_lower = prefix.to_lowercase();
+        self.find_nodes_by_label(prefix)
+            .into_iter()
+            .filter(|id| {
+                self.get_node(id)
+                    .is_some_and(|n| n.label.to_lowercase().starts_with(&prefix_lower))
+            })
+            .collect()
+    }
+
+    /// Record `alias` as another label for `id`, so that
+    /// [`find_nodes_by_exact_label`](Self::find_nodes_by_exact_label) finds
+    /// the node under it too. Returns false if the node does not exist or
+    /// the backend does not keep aliases (the default).
+    fn add_alias(&mut self, _id: &NodeId, _alias: &str) -> bool {
+        false
+    }
+
+    /// Aliases recorded for `id`, lowercase, in the order they were added.
+    fn aliases(&self, _id: &NodeId) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Attach `annotation` to `id`. Returns false if the node does not
+    /// exist, the node already carries an identical annotation, or the
+    /// backend does not keep annotations (the default).
+    fn annotate(&mut self, _id: &NodeId, _annotation: Annotation) -> bool {
+        false
+    }
+
+    /// Annotations attached to `id`, in the order they were added.
+    fn annotations(&self, _id: &NodeId) -> Vec<Annotation> {
+        Vec::new()
+    }
+
+    /// Every label-index entry as (lowercase label, node), for consistency
+    /// checks. Backends that look labels up in storage keep no separate
+    /// index and return nothing (the default).
+    fn label_index_entries(&self) -> Vec<(String, NodeId)> {
+        Vec::new()
+    }
+
+    /// Rebuild the lookup indexes from the stored nodes. Returns how many
+    /// entries were added or dropped; 0 if the indexes were consistent or
+    /// the backend keeps none (the default).
+    fn rebuild_indexes(&mut self) -> usize {
+        0
+    }
+
+    /// Number of edges touching a node.
+    fn degree(&self, node: &NodeId) -> usize {
+        self.neighbors(node).len()
+    }
+
     // --- Structural query types ---
 
     /// Find the shortest weighted path between two nodes.
-    /// Returns (path_of_node_ids, total_weight). Uses inverse weight as cost
-    /// so stronger edges are preferred.
+    /// Returns (path_of_node_ids, total_cost). Each edge costs
+    /// `1 / weight` (weights floored at 0.001), so stronger edges are
+    /// preferred. For other cost models, alternatives and per-hop detail,
+    /// use `phago_runtime::graph_algorithms::find_path`.
     fn shortest_path(&self, from: &NodeId, to: &NodeId) -> Option<(Vec<NodeId>, f64)>;
 
     /// Compute betweenness centrality for all nodes (approximate, sampled).
@@ -122,5 +221,14 @@ pub trait TopologyGraph {
     /// println!("Found {} communities with modularity {:.3}",
     ///          result.communities.len(), result.modularity);
     /// ```
+    #[cfg(feature = "std")]
     fn louvain_communities(&self) -> crate::louvain::LouvainResult;
+
+    // --- Persistence ---
+
+    /// Write any buffered mutations to durable storage.
+    ///
+    /// The colony calls this once per tick. In-memory backends have
+    /// nothing to flush, so the default is a no-op.
+    fn flush(&mut self) {}
 }
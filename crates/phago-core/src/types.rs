@@ -1,24 +1,49 @@
 //! Shared types used across all Phago primitives and crates.
 
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// A fresh ID: random (v4) with `std`, otherwise the next value of a
+/// process-wide counter, so `no_std` guests need no entropy source.
+fn fresh_uuid() -> Uuid {
+    #[cfg(feature = "std")]
+    {
+        Uuid::new_v4()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        use core::sync::atomic::{AtomicU64, Ordering};
+        // Counts down from the top, clear of the small seeds tests pass
+        // to `from_seed`.
+        static NEXT: AtomicU64 = AtomicU64::new(u64::MAX);
+        seeded_uuid(NEXT.fetch_sub(1, Ordering::Relaxed))
+    }
+}
+
+/// A deterministic ID built from a seed.
+fn seeded_uuid(seed: u64) -> Uuid {
+    let bytes = seed.to_le_bytes();
+    let mut uuid_bytes = [0u8; 16];
+    uuid_bytes[0..8].copy_from_slice(&bytes);
+    uuid_bytes[8..16].copy_from_slice(&bytes);
+    Uuid::from_bytes(uuid_bytes)
+}
+
 /// Unique identifier for an agent in the colony.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AgentId(pub Uuid);
 
 impl AgentId {
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(fresh_uuid())
     }
 
     /// Create a deterministic AgentId from a seed (for testing).
     pub fn from_seed(seed: u64) -> Self {
-        let bytes = seed.to_le_bytes();
-        let mut uuid_bytes = [0u8; 16];
-        uuid_bytes[0..8].copy_from_slice(&bytes);
-        uuid_bytes[8..16].copy_from_slice(&bytes);
-        Self(Uuid::from_bytes(uuid_bytes))
+        Self(seeded_uuid(seed))
     }
 }
 
@@ -34,16 +59,12 @@ pub struct NodeId(pub Uuid);
 
 impl NodeId {
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(fresh_uuid())
     }
 
     /// Create a deterministic NodeId from a seed (for testing).
     pub fn from_seed(seed: u64) -> Self {
-        let bytes = seed.to_le_bytes();
-        let mut uuid_bytes = [0u8; 16];
-        uuid_bytes[0..8].copy_from_slice(&bytes);
-        uuid_bytes[8..16].copy_from_slice(&bytes);
-        Self(Uuid::from_bytes(uuid_bytes))
+        Self(seeded_uuid(seed))
     }
 }
 
@@ -59,16 +80,12 @@ pub struct DocumentId(pub Uuid);
 
 impl DocumentId {
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(fresh_uuid())
     }
 
     /// Create a deterministic DocumentId from a seed (for testing).
     pub fn from_seed(seed: u64) -> Self {
-        let bytes = seed.to_le_bytes();
-        let mut uuid_bytes = [0u8; 16];
-        uuid_bytes[0..8].copy_from_slice(&bytes);
-        uuid_bytes[8..16].copy_from_slice(&bytes);
-        Self(Uuid::from_bytes(uuid_bytes))
+        Self(seeded_uuid(seed))
     }
 }
 
@@ -87,6 +104,84 @@ pub struct Document {
     pub position: Position,
     /// Whether this document has been fully digested.
     pub digested: bool,
+    /// Tags scoping the document (e.g. "work", "personal"). Concepts
+    /// digested from it carry the same tags.
+    ///
+    /// Always serialized: documents cross the shard RPC boundary as bincode,
+    /// which cannot skip fields.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Starts at 1 and is bumped each time the document is re-ingested
+    /// with changed content.
+    #[serde(default = "first_version")]
+    pub version: u32,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+/// A range of a document's content, as byte offsets into
+/// [`Document::content`]. Both ends fall on character boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl TextSpan {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// The spanned text, or `None` if the span does not fit `text`.
+    pub fn slice<'a>(&self, text: &'a str) -> Option<&'a str> {
+        text.get(self.start..self.end)
+    }
+}
+
+/// Where in a document the concepts it presented came from.
+///
+/// Only offsets are kept: passage text is sliced from the document's
+/// content when it is needed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentPassages {
+    /// Concept label → the passages mentioning it, in document order.
+    pub concepts: BTreeMap<String, Vec<TextSpan>>,
+}
+
+impl DocumentPassages {
+    /// Record that `label` occurs in `spans`, keeping spans ordered and unique.
+    pub fn add(&mut self, label: &str, spans: &[TextSpan]) {
+        if spans.is_empty() {
+            return;
+        }
+        let entry = self.concepts.entry(label.into()).or_default();
+        entry.extend_from_slice(spans);
+        entry.sort();
+        entry.dedup();
+    }
+
+    /// The passages mentioning `label`.
+    pub fn spans(&self, label: &str) -> &[TextSpan] {
+        self.concepts.get(label).map_or(&[], Vec::as_slice)
+    }
+
+    /// File the passages recorded under `from` under `to`, e.g. because
+    /// the concepts were merged. Returns false if `from` had none.
+    pub fn rename(&mut self, from: &str, to: &str) -> bool {
+        match self.concepts.remove(from) {
+            Some(spans) => {
+                self.add(to, &spans);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.concepts.is_empty()
+    }
 }
 
 /// A position in the substrate's spatial field.
@@ -102,7 +197,150 @@ impl Position {
     }
 
     pub fn distance_to(&self, other: &Position) -> f64 {
-        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+        let (dx, dy) = (self.x - other.x, self.y - other.y);
+        crate::math::sqrt(dx * dx + dy * dy)
+    }
+}
+
+/// An axis-aligned rectangle of the spatial field.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Rect {
+    /// The rectangle spanned by two corners, in any order.
+    pub fn new(a: Position, b: Position) -> Self {
+        Self {
+            min: Position::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Position::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    /// Whether `position` lies inside or on the edge.
+    pub fn contains(&self, position: &Position) -> bool {
+        (self.min.x..=self.max.x).contains(&position.x)
+            && (self.min.y..=self.max.y).contains(&position.y)
+    }
+}
+
+/// What happens to an agent that moves past the world bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BoundaryBehavior {
+    /// Stop at the edge.
+    #[default]
+    Clamp,
+    /// Come back in on the opposite edge; the field is a torus.
+    Wrap,
+    /// Bounce back off the edge by the distance overshot.
+    Reflect,
+}
+
+/// The shape of the spatial field: unbounded, or a rectangle whose edges
+/// clamp, wrap or reflect movement.
+///
+/// Under [`BoundaryBehavior::Wrap`] distances and directions are measured
+/// the short way round, so a signal just across the seam is close by.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct WorldGeometry {
+    pub bounds: Option<Rect>,
+    pub behavior: BoundaryBehavior,
+}
+
+impl WorldGeometry {
+    /// A field bounded by `bounds`.
+    pub fn bounded(bounds: Rect, behavior: BoundaryBehavior) -> Self {
+        Self {
+            bounds: Some(bounds),
+            behavior,
+        }
+    }
+
+    /// Whether the field wraps around its bounds.
+    pub fn wraps(&self) -> bool {
+        self.torus().is_some()
+    }
+
+    /// The bounds, if the field wraps around them.
+    fn torus(&self) -> Option<&Rect> {
+        self.bounds
+            .as_ref()
+            .filter(|_| self.behavior == BoundaryBehavior::Wrap)
+    }
+
+    /// Shortest displacement from `from` to `to`.
+    pub fn offset(&self, from: &Position, to: &Position) -> Position {
+        let (mut dx, mut dy) = (to.x - from.x, to.y - from.y);
+        if let Some(bounds) = self.torus() {
+            dx = shortest(dx, bounds.width());
+            dy = shortest(dy, bounds.height());
+        }
+        Position::new(dx, dy)
+    }
+
+    /// Distance between two positions, the short way round on a torus.
+    pub fn distance(&self, a: &Position, b: &Position) -> f64 {
+        let d = self.offset(a, b);
+        crate::math::sqrt(d.x * d.x + d.y * d.y)
+    }
+
+    /// Where a move to `target` ends up.
+    pub fn confine(&self, target: Position) -> Position {
+        let Some(bounds) = self.bounds else {
+            return target;
+        };
+        let axis = |v: f64, min: f64, max: f64| match self.behavior {
+            BoundaryBehavior::Clamp => v.clamp(min, max),
+            BoundaryBehavior::Wrap => min + positive_rem(v - min, max - min),
+            BoundaryBehavior::Reflect => {
+                // Fold onto a segment twice the width, then mirror the far half
+                let t = positive_rem(v - min, 2.0 * (max - min));
+                min + if t > max - min {
+                    2.0 * (max - min) - t
+                } else {
+                    t
+                }
+            }
+        };
+        Position::new(
+            axis(target.x, bounds.min.x, bounds.max.x),
+            axis(target.y, bounds.min.y, bounds.max.y),
+        )
+    }
+}
+
+/// `v` modulo `m`, in `[0, m)`; 0 for an empty range.
+fn positive_rem(v: f64, m: f64) -> f64 {
+    if m <= 0.0 {
+        return 0.0;
+    }
+    let r = v % m;
+    if r < 0.0 {
+        r + m
+    } else {
+        r
+    }
+}
+
+/// The shorter of `d` and the way round a loop of length `m`.
+fn shortest(d: f64, m: f64) -> f64 {
+    if m <= 0.0 {
+        return d;
+    }
+    let d = positive_rem(d, m);
+    if d > m / 2.0 {
+        d - m
+    } else {
+        d
     }
 }
 
@@ -130,7 +368,8 @@ pub enum SignalType {
     Insight,
     /// Capability available for transfer.
     Capability,
-    /// Custom signal type for domain-specific use.
+    /// User-defined kind, named by the agents that emit and sense it;
+    /// see [`SignalType::custom`].
     Custom(String),
 }
 
@@ -143,6 +382,25 @@ pub struct Signal {
     pub emitter: AgentId,
     /// Monotonic tick count when this signal was emitted.
     pub tick: u64,
+    /// Kind of [`payload`](Self::payload), if the signal carries one
+    /// (see [`SignalPayload`](crate::signal::SignalPayload)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_kind: Option<String>,
+    /// Serialized payload; empty when there is none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub payload: Vec<u8>,
+    /// Emissions this signal stands for: 1, unless the substrate merged
+    /// nearby signals of the same kind into it.
+    #[serde(default = "one_source", skip_serializing_if = "is_one_source")]
+    pub sources: u32,
+}
+
+fn one_source() -> u32 {
+    1
+}
+
+fn is_one_source(sources: &u32) -> bool {
+    *sources == 1
 }
 
 /// A directional gradient sensed by an agent.
@@ -211,6 +469,9 @@ pub struct DeathSignal {
     pub useful_outputs: u64,
     pub final_fragments: Vec<Vec<u8>>,
     pub cause: DeathCause,
+    /// The agent died holding a document it had not yet presented.
+    #[serde(default)]
+    pub had_pending_work: bool,
 }
 
 /// Why an agent died.
@@ -238,6 +499,33 @@ pub struct VocabularyCapability {
     pub origin: AgentId,
     /// How many documents contributed to building this vocabulary.
     pub document_count: u64,
+    /// Fitness of the exporting agent when the capability was deposited.
+    /// Stamped by the colony; `None` for payloads without provenance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_fitness: Option<f64>,
+    /// How often each term was reinforced in the graph when the capability
+    /// was deposited, parallel to `terms`. Stamped by the colony; empty for
+    /// payloads without provenance.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub term_reinforcement: Vec<u64>,
+}
+
+impl VocabularyCapability {
+    /// A capability without provenance; the colony adds it on deposit.
+    pub fn new(terms: Vec<String>, origin: AgentId, document_count: u64) -> Self {
+        Self {
+            terms,
+            origin,
+            document_count,
+            source_fitness: None,
+            term_reinforcement: Vec::new(),
+        }
+    }
+
+    /// How often `terms[index]` was reinforced at export, 0 if unknown.
+    pub fn reinforcement_of(&self, index: usize) -> u64 {
+        self.term_reinforcement.get(index).copied().unwrap_or(0)
+    }
 }
 
 /// Identifier for a transferable capability.
@@ -342,7 +630,7 @@ pub struct BoundaryContext {
 }
 
 /// An action returned by an agent's tick.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AgentAction {
     /// Agent is idle — nothing to do.
     Idle,
@@ -358,6 +646,15 @@ pub enum AgentAction {
     Emit(Signal),
     /// Agent is wiring connections between nodes.
     WireNodes(Vec<(NodeId, NodeId, f64)>),
+    /// Agent is wiring connections that say how the nodes relate. Wired
+    /// like [`AgentAction::WireNodes`]; the runtime also keeps the labels.
+    WireRelations(Vec<RelationWiring>),
+    /// Agent is attaching embeddings to concepts by label, e.g. once an
+    /// embedder it queued them with has answered. The runtime also caches
+    /// them for agents to look up (see [`Substrate::cached_embedding`]).
+    ///
+    /// [`Substrate::cached_embedding`]: crate::substrate::Substrate::cached_embedding
+    EmbedConcepts(Vec<ConceptEmbedding>),
     /// Agent is triggering apoptosis.
     Apoptose,
     /// Agent is attempting symbiosis with another agent.
@@ -366,6 +663,31 @@ pub enum AgentAction {
     ExportCapability(CapabilityId),
     /// Agent is contributing to collective computation.
     ContributeToCollective,
+    /// An action the runtime has no built-in meaning for. The colony hands
+    /// it to the handler registered for `kind`, which decodes `payload`.
+    Custom { kind: String, payload: Vec<u8> },
+}
+
+impl AgentAction {
+    /// Snake-case name of the action, e.g. `"wire_nodes"`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AgentAction::Idle => "idle",
+            AgentAction::Move(_) => "move",
+            AgentAction::EngulfDocument(_) => "engulf_document",
+            AgentAction::PresentFragments(_) => "present_fragments",
+            AgentAction::Deposit(..) => "deposit",
+            AgentAction::Emit(_) => "emit",
+            AgentAction::WireNodes(_) => "wire_nodes",
+            AgentAction::WireRelations(_) => "wire_relations",
+            AgentAction::EmbedConcepts(_) => "embed_concepts",
+            AgentAction::Apoptose => "apoptose",
+            AgentAction::SymbioseWith(_) => "symbiose_with",
+            AgentAction::ExportCapability(_) => "export_capability",
+            AgentAction::ContributeToCollective => "contribute_to_collective",
+            AgentAction::Custom { .. } => "custom",
+        }
+    }
 }
 
 /// A fragment to present to the knowledge graph.
@@ -376,6 +698,88 @@ pub struct FragmentPresentation {
     pub position: Position,
     /// What type of node to create. Defaults to Concept.
     pub node_type: NodeType,
+    /// Labels of the existing nodes this fragment was derived from.
+    ///
+    /// Synthesized insights list their anchor concept first, followed by
+    /// the related concepts. The colony uses this provenance to re-score
+    /// insights as the underlying edges decay. Empty for plain concepts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub derived_from: Vec<String>,
+    /// Passages of the source document the fragment was found in. Empty
+    /// when the agent does not track them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub passages: Vec<TextSpan>,
+    /// Access count a new node starts with, and how much an existing one
+    /// is reinforced by. Agents without a notion of confidence present 1.
+    #[serde(default = "default_access_weight")]
+    pub access_weight: u64,
+    /// How anomalous an [`NodeType::Anomaly`] fragment is, from 0.0
+    /// (ordinary) to 1.0. `None` for other fragments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    /// Where the fragment was defined, e.g. the relative path of a source
+    /// file. Qualified fragments become nodes labelled
+    /// `namespace::label`, found under their plain label as an alias, and
+    /// are only wired to fragments of the same namespace or unqualified
+    /// ones. `None` for plain-text concepts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+impl FragmentPresentation {
+    /// Label of the node this fragment is presented as.
+    pub fn qualified_label(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{namespace}{NAMESPACE_SEPARATOR}{}", self.label),
+            None => self.label.clone(),
+        }
+    }
+}
+
+/// Separates a node label's namespace from its plain label.
+pub const NAMESPACE_SEPARATOR: &str = "::";
+
+/// Split a node label into its namespace, if qualified, and plain label.
+pub fn split_qualified_label(label: &str) -> (Option<&str>, &str) {
+    match label.rsplit_once(NAMESPACE_SEPARATOR) {
+        Some((namespace, plain)) => (Some(namespace), plain),
+        None => (None, label),
+    }
+}
+
+fn default_access_weight() -> u64 {
+    1
+}
+
+/// A labelled connection, read as `from <relation> to`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationWiring {
+    pub from: NodeId,
+    pub to: NodeId,
+    /// Base weight, as for [`AgentAction::WireNodes`].
+    pub weight: f64,
+    /// The relation, e.g. `"produces"` or `"part_of"`.
+    pub relation: String,
+}
+
+/// The embedding of a concept, as attached by [`AgentAction::EmbedConcepts`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConceptEmbedding {
+    /// Label of the concept, as it was presented.
+    pub label: String,
+    /// The embedding, or why the agent gave up on computing it. The
+    /// runtime reports failures as failed actions.
+    pub embedding: Result<Vec<f32>, String>,
+}
+
+/// A free-text note attached to a node from outside the colony.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub text: String,
+    /// Who made the note, e.g. `"mcp"`.
+    pub source: String,
+    /// Tick the note was made at.
+    pub tick: Tick,
 }
 
 /// Data stored in a knowledge graph node.
@@ -388,9 +792,42 @@ pub struct NodeData {
     /// Number of times this node has been accessed/reinforced.
     pub access_count: u64,
     pub created_tick: u64,
-    /// Optional vector embedding for semantic similarity.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub embedding: Option<Vec<f32>>,
+    /// Last tick this node was presented by a digester or traversed by a query.
+    #[serde(default)]
+    pub last_accessed_tick: u64,
+    /// Union of the tags of the documents this node was presented from,
+    /// each with the number of times the node was accessed under that tag.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, u64>,
+}
+
+impl NodeData {
+    /// Whether the node carries at least one of `tags`.
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        tags.iter().any(|t| self.tags.contains_key(t))
+    }
+
+    /// Whether the node is visible in a view restricted to `tags`
+    /// (`None` means unrestricted).
+    pub fn in_scope(&self, tags: Option<&[String]>) -> bool {
+        tags.is_none_or(|t| self.has_any_tag(t))
+    }
+
+    /// Access count within a view: the sum of the per-tag counts for
+    /// `tags`, or the global `access_count` when unrestricted.
+    pub fn scoped_access_count(&self, tags: Option<&[String]>) -> u64 {
+        match tags {
+            None => self.access_count,
+            Some(tags) => tags.iter().filter_map(|t| self.tags.get(t)).sum(),
+        }
+    }
+
+    /// Count one access under each of `tags`, adding tags not yet present.
+    pub fn add_tags(&mut self, tags: &[String]) {
+        for tag in tags {
+            *self.tags.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
 }
 
 /// Types of nodes in the knowledge graph.
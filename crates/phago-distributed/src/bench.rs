@@ -12,6 +12,7 @@ use crate::shard::ShardedColony;
 use crate::types::*;
 use phago_core::types::Position;
 use phago_runtime::colony::ColonyConfig;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -29,6 +30,8 @@ pub struct BenchConfig {
     pub num_queries: usize,
     /// Sample queries to run.
     pub sample_queries: Vec<String>,
+    /// Pipeline ticks instead of barriering every phase.
+    pub pipelined: bool,
 }
 
 impl Default for BenchConfig {
@@ -43,6 +46,7 @@ impl Default for BenchConfig {
                 "protein transport".to_string(),
                 "molecular biology".to_string(),
             ],
+            pipelined: false,
         }
     }
 }
@@ -82,6 +86,12 @@ impl BenchConfig {
         self.sample_queries = queries;
         self
     }
+
+    /// Pipeline ticks instead of barriering every phase.
+    pub fn with_pipelining(mut self, pipelined: bool) -> Self {
+        self.pipelined = pipelined;
+        self
+    }
 }
 
 /// Results from a benchmark run.
@@ -113,6 +123,19 @@ pub struct BenchResults {
     pub num_documents: usize,
     /// Number of ticks run.
     pub num_ticks: u64,
+    /// Whether ticks were pipelined.
+    pub pipelined: bool,
+}
+
+impl BenchResults {
+    /// Tick mode, as shown in reports.
+    pub fn mode(&self) -> &'static str {
+        if self.pipelined {
+            "pipelined"
+        } else {
+            "barriered"
+        }
+    }
 }
 
 impl BenchResults {
@@ -121,8 +144,11 @@ impl BenchResults {
         println!("\n=== Distributed Colony Benchmark Results ===\n");
         println!("Configuration:");
         println!(
-            "  Shards: {}, Documents: {}, Ticks: {}",
-            self.num_shards, self.num_documents, self.num_ticks
+            "  Shards: {}, Documents: {}, Ticks: {} ({})",
+            self.num_shards,
+            self.num_documents,
+            self.num_ticks,
+            self.mode()
         );
         println!();
         println!("Timing:");
@@ -150,7 +176,7 @@ impl BenchResults {
     /// Return results as a CSV row.
     pub fn to_csv_row(&self) -> String {
         format!(
-            "{},{},{},{:.2},{:.2},{:.2},{},{},{}",
+            "{},{},{},{:.2},{:.2},{:.2},{},{},{},{}",
             self.num_shards,
             self.num_documents,
             self.num_ticks,
@@ -159,28 +185,27 @@ impl BenchResults {
             self.queries_per_second,
             self.total_nodes,
             self.total_edges,
-            self.total_time.as_millis()
+            self.total_time.as_millis(),
+            self.mode()
         )
     }
 
     /// Return CSV header.
     pub fn csv_header() -> &'static str {
-        "shards,documents,ticks,docs_per_sec,ticks_per_sec,queries_per_sec,nodes,edges,total_time_ms"
+        "shards,documents,ticks,docs_per_sec,ticks_per_sec,queries_per_sec,nodes,edges,total_time_ms,mode"
     }
 }
 
 /// Create a test cluster for benchmarking.
 ///
 /// Returns the coordinator and a vector of sharded colonies.
-pub fn create_bench_cluster(
-    num_shards: u32,
-) -> (Arc<Coordinator>, Vec<Arc<RwLock<ShardedColony>>>) {
+pub fn create_bench_cluster(num_shards: u32) -> (Arc<Coordinator>, Vec<Rc<RwLock<ShardedColony>>>) {
     let coordinator = Arc::new(Coordinator::new(num_shards));
     let hash_ring = Arc::new(RwLock::new(ConsistentHashRing::new(num_shards)));
 
     let shards: Vec<_> = (0..num_shards)
         .map(|i| {
-            Arc::new(RwLock::new(ShardedColony::new(
+            Rc::new(RwLock::new(ShardedColony::new(
                 ShardId::new(i),
                 ColonyConfig::default(),
                 hash_ring.clone(),
@@ -291,6 +316,7 @@ pub async fn run_benchmark(config: BenchConfig) -> BenchResults {
         shards.clone(),
         RunnerConfig {
             resolve_ghosts: false, // Skip ghost resolution for benchmarking
+            pipelined: config.pipelined,
             ..Default::default()
         },
     );
@@ -341,6 +367,7 @@ pub async fn run_benchmark(config: BenchConfig) -> BenchResults {
         num_shards: config.num_shards,
         num_documents: config.num_documents,
         num_ticks: config.num_ticks,
+        pipelined: config.pipelined,
     }
 }
 
@@ -354,6 +381,7 @@ pub async fn run_quick_benchmark() -> BenchResults {
         num_ticks: 10,
         num_queries: 5,
         sample_queries: vec!["cell".to_string(), "protein".to_string()],
+        pipelined: false,
     })
     .await
 }
@@ -437,22 +465,29 @@ pub async fn compare_single_vs_distributed(num_documents: usize, num_ticks: u64)
 
 /// Run a scaling benchmark across different shard counts.
 ///
-/// Returns results for 1, 2, 4, and 8 shards.
+/// Returns results for 1, 2, 4, and 8 shards, each run barriered and then
+/// pipelined.
 pub async fn scaling_benchmark(num_documents: usize, num_ticks: u64) -> Vec<BenchResults> {
     let shard_counts = [1, 2, 4, 8];
     let mut results = Vec::new();
 
     for &num_shards in &shard_counts {
-        println!("Running benchmark with {} shard(s)...", num_shards);
-        let result = run_benchmark(BenchConfig {
-            num_shards,
-            num_documents,
-            num_ticks,
-            num_queries: 20,
-            ..Default::default()
-        })
-        .await;
-        results.push(result);
+        for pipelined in [false, true] {
+            let config = BenchConfig {
+                num_shards,
+                num_documents,
+                num_ticks,
+                num_queries: 20,
+                ..Default::default()
+            }
+            .with_pipelining(pipelined);
+            println!(
+                "Running benchmark with {} shard(s), {}...",
+                num_shards,
+                if pipelined { "pipelined" } else { "barriered" }
+            );
+            results.push(run_benchmark(config).await);
+        }
     }
 
     results
@@ -502,6 +537,7 @@ mod tests {
             num_ticks: 5,
             num_queries: 5,
             sample_queries: vec!["cell".to_string()],
+            pipelined: false,
         })
         .await;
 
@@ -512,6 +548,25 @@ mod tests {
         assert_eq!(result.num_ticks, 5);
     }
 
+    #[tokio::test]
+    async fn test_pipelined_benchmark_builds_the_same_graph() {
+        let config = BenchConfig {
+            num_shards: 3,
+            num_documents: 24,
+            num_ticks: 8,
+            num_queries: 1,
+            sample_queries: vec!["cell".to_string()],
+            pipelined: false,
+        };
+
+        let barriered = run_benchmark(config.clone()).await;
+        let pipelined = run_benchmark(config.with_pipelining(true)).await;
+        assert_eq!(pipelined.mode(), "pipelined");
+        assert!(pipelined.ticks_per_second > 0.0);
+        assert_eq!(pipelined.total_nodes, barriered.total_nodes);
+        assert_eq!(pipelined.total_edges, barriered.total_edges);
+    }
+
     #[tokio::test]
     async fn test_quick_benchmark() {
         let result = run_quick_benchmark().await;
@@ -552,11 +607,13 @@ mod tests {
             num_shards: 3,
             num_documents: 100,
             num_ticks: 20,
+            pipelined: true,
         };
 
         let csv = results.to_csv_row();
         assert!(csv.contains("3,100,20"));
         assert!(csv.contains("1000.00"));
+        assert!(csv.ends_with(",pipelined"));
     }
 
     #[tokio::test]
@@ -567,6 +624,7 @@ mod tests {
             num_ticks: 5,
             num_queries: 3,
             sample_queries: vec!["cell".to_string()],
+            pipelined: false,
         };
 
         let result = run_benchmark(config).await;
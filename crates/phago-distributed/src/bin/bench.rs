@@ -24,6 +24,7 @@ fn print_usage() {
     println!("  --docs N       Number of documents (default: 100)");
     println!("  --ticks N      Number of ticks (default: 20)");
     println!("  --queries N    Number of queries (default: 50)");
+    println!("  --pipelined    Pipeline ticks instead of barriering every phase");
     println!();
     println!("Examples:");
     println!("  phago-bench quick");
@@ -37,30 +38,23 @@ fn parse_custom_args(args: &[String]) -> BenchConfig {
     let mut i = 0;
     while i < args.len() {
         match args[i].as_str() {
-            "--shards" => {
-                if i + 1 < args.len() {
-                    config.num_shards = args[i + 1].parse().unwrap_or(config.num_shards);
-                    i += 1;
-                }
+            "--shards" if i + 1 < args.len() => {
+                config.num_shards = args[i + 1].parse().unwrap_or(config.num_shards);
+                i += 1;
             }
-            "--docs" => {
-                if i + 1 < args.len() {
-                    config.num_documents = args[i + 1].parse().unwrap_or(config.num_documents);
-                    i += 1;
-                }
+            "--docs" if i + 1 < args.len() => {
+                config.num_documents = args[i + 1].parse().unwrap_or(config.num_documents);
+                i += 1;
             }
-            "--ticks" => {
-                if i + 1 < args.len() {
-                    config.num_ticks = args[i + 1].parse().unwrap_or(config.num_ticks);
-                    i += 1;
-                }
+            "--ticks" if i + 1 < args.len() => {
+                config.num_ticks = args[i + 1].parse().unwrap_or(config.num_ticks);
+                i += 1;
             }
-            "--queries" => {
-                if i + 1 < args.len() {
-                    config.num_queries = args[i + 1].parse().unwrap_or(config.num_queries);
-                    i += 1;
-                }
+            "--queries" if i + 1 < args.len() => {
+                config.num_queries = args[i + 1].parse().unwrap_or(config.num_queries);
+                i += 1;
             }
+            "--pipelined" => config.pipelined = true,
             _ => {}
         }
         i += 1;
@@ -115,14 +109,17 @@ async fn main() {
 
             println!("\n=== Scaling Results ===\n");
             println!(
-                "| {:>6} | {:>12} | {:>10} | {:>12} | {:>8} | {:>8} |",
-                "Shards", "Docs/sec", "Ticks/sec", "Queries/sec", "Nodes", "Edges"
+                "| {:>6} | {:>9} | {:>12} | {:>10} | {:>12} | {:>8} | {:>8} |",
+                "Shards", "Mode", "Docs/sec", "Ticks/sec", "Queries/sec", "Nodes", "Edges"
+            );
+            println!(
+                "|--------|-----------|--------------|------------|--------------|----------|----------|"
             );
-            println!("|--------|--------------|------------|--------------|----------|----------|");
             for r in &results {
                 println!(
-                    "| {:>6} | {:>12.1} | {:>10.1} | {:>12.1} | {:>8} | {:>8} |",
+                    "| {:>6} | {:>9} | {:>12.1} | {:>10.1} | {:>12.1} | {:>8} | {:>8} |",
                     r.num_shards,
+                    r.mode(),
                     r.docs_per_second,
                     r.ticks_per_second,
                     r.queries_per_second,
@@ -136,8 +133,12 @@ async fn main() {
             let config = parse_custom_args(&args[2..]);
             println!("Running custom benchmark...");
             println!(
-                "  Shards: {}, Documents: {}, Ticks: {}, Queries: {}",
-                config.num_shards, config.num_documents, config.num_ticks, config.num_queries
+                "  Shards: {}, Documents: {}, Ticks: {}, Queries: {}, Pipelined: {}",
+                config.num_shards,
+                config.num_documents,
+                config.num_ticks,
+                config.num_queries,
+                config.pipelined
             );
             println!();
 
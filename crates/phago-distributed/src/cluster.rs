@@ -0,0 +1,277 @@
+//! Client access to a running cluster.
+//!
+//! [`ClusterClient`] is the entry point for tools that feed and query a
+//! cluster from outside: it connects to the coordinator, discovers the
+//! registered shards, routes documents through the coordinator's hash ring
+//! and drives distributed queries over RPC.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use phago_distributed::cluster::ClusterClient;
+//! use phago_distributed::rpc::client::ClientConfig;
+//!
+//! let cluster = ClusterClient::connect("127.0.0.1:9000", ClientConfig::default()).await?;
+//! cluster.ingest("Cell Biology", "cell membrane protein", Position::new(0.0, 0.0), None).await?;
+//! cluster.run_ticks(15).await?;
+//! let results = cluster.query(&DistributedQueryEngine::with_defaults(), "membrane").await?;
+//! ```
+
+use crate::query::DistributedQueryEngine;
+use crate::rpc::client::{connect_to_coordinator_with_retry, ClientConfig, ShardClientPool};
+use crate::rpc::protocol::{CoordinatorServiceClient, ShardServiceClient};
+use crate::types::*;
+use phago_core::types::{Document, DocumentId, Position};
+use std::net::SocketAddr;
+use tarpc::context;
+
+/// A connection to a running cluster, made through its coordinator.
+///
+/// The shard list is read from the coordinator once, when connecting.
+/// Shards that register afterwards are not seen until the client
+/// reconnects.
+pub struct ClusterClient {
+    coordinator_addr: SocketAddr,
+    coordinator: CoordinatorServiceClient,
+    shards: Vec<(ShardId, SocketAddr)>,
+    pool: ShardClientPool,
+    config: ClientConfig,
+}
+
+impl ClusterClient {
+    /// Connect to the coordinator at `addr` (`host:port`).
+    ///
+    /// The connection is retried according to `config`. Every shard
+    /// registered with the coordinator is added to the client's pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DistributedError::Unreachable` if the coordinator cannot be
+    /// reached, and `DistributedError::RpcError` if it has no shards.
+    pub async fn connect(addr: &str, config: ClientConfig) -> DistributedResult<Self> {
+        let coordinator_addr = resolve_addr(addr).await?;
+        let coordinator = connect_to_coordinator_with_retry(coordinator_addr, &config)
+            .await
+            .map_err(|e| DistributedError::Unreachable {
+                target: format!("coordinator at {}", coordinator_addr),
+                attempts: config.retry_attempts,
+                reason: e.to_string(),
+            })?;
+
+        let infos = coordinator
+            .list_shards(context::current())
+            .await
+            .map_err(|e| DistributedError::RpcError(e.to_string()))?;
+        if infos.is_empty() {
+            return Err(DistributedError::RpcError(format!(
+                "coordinator at {} has no registered shards",
+                coordinator_addr
+            )));
+        }
+
+        let pool = ShardClientPool::with_config(config.clone());
+        let mut shards = Vec::with_capacity(infos.len());
+        for info in infos {
+            let shard_addr = resolve_addr(&info.address).await?;
+            pool.register_shard(info.id, shard_addr).await;
+            shards.push((info.id, shard_addr));
+        }
+        shards.sort_by_key(|(id, _)| *id);
+
+        Ok(Self {
+            coordinator_addr,
+            coordinator,
+            shards,
+            pool,
+            config,
+        })
+    }
+
+    /// Address of the coordinator this client is connected to.
+    pub fn coordinator_addr(&self) -> SocketAddr {
+        self.coordinator_addr
+    }
+
+    /// The shards known to this client, ordered by ID.
+    pub fn shards(&self) -> &[(ShardId, SocketAddr)] {
+        &self.shards
+    }
+
+    /// Ingest a document into the shards that own it.
+    ///
+    /// The coordinator picks the primary shard and `replicas` additional
+    /// shards from its hash ring; `None` uses its configured replication
+    /// factor. Each target shard digests its copy over the following ticks.
+    ///
+    /// Returns the shards that received the document, primary first.
+    pub async fn ingest(
+        &self,
+        title: &str,
+        content: &str,
+        position: Position,
+        replicas: Option<u32>,
+    ) -> DistributedResult<Vec<ShardId>> {
+        let doc = Document {
+            id: DocumentId::new(),
+            title: title.to_string(),
+            content: content.to_string(),
+            position,
+            digested: false,
+            tags: Vec::new(),
+            version: 1,
+        };
+
+        let targets = self
+            .coordinator
+            .route_document_replicas(context::current(), doc.id, replicas)
+            .await
+            .map_err(|e| DistributedError::RpcError(e.to_string()))?;
+        if targets.is_empty() {
+            return Err(DistributedError::RoutingFailed(doc.id));
+        }
+
+        for &shard_id in &targets {
+            let client = self.shard_client(shard_id).await?;
+            client
+                .ingest_document(context::current(), doc.clone())
+                .await
+                .map_err(|e| DistributedError::RpcError(e.to_string()))?
+                .map_err(|e| DistributedError::RpcError(e.to_string()))?;
+        }
+
+        Ok(targets)
+    }
+
+    /// Run `ticks` synchronized ticks across every shard.
+    ///
+    /// Each tick runs the sense, act and decay phases on all shards in
+    /// lockstep, then advances the coordinator's tick counter.
+    pub async fn run_ticks(&self, ticks: u64) -> DistributedResult<()> {
+        let clients = self.shard_clients().await?;
+
+        for _ in 0..ticks {
+            let tick = self
+                .coordinator
+                .current_tick(context::current())
+                .await
+                .map_err(|e| DistributedError::RpcError(e.to_string()))?;
+
+            for phase in [TickPhase::Sense, TickPhase::Act, TickPhase::Decay] {
+                let results = futures::future::join_all(
+                    clients
+                        .iter()
+                        .map(|client| client.tick_phase(context::current(), phase, tick)),
+                )
+                .await;
+                for result in results {
+                    result
+                        .map_err(|e| DistributedError::RpcError(e.to_string()))?
+                        .map_err(|e| DistributedError::RpcError(e.to_string()))?;
+                }
+            }
+
+            self.coordinator
+                .start_tick(context::current())
+                .await
+                .map_err(|e| DistributedError::RpcError(e.to_string()))?
+                .map_err(|e| DistributedError::RpcError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Query every shard with `engine` and return the merged results.
+    ///
+    /// Each result carries the ID of the shard it came from.
+    pub async fn query(
+        &self,
+        engine: &DistributedQueryEngine,
+        query_text: &str,
+    ) -> DistributedResult<Vec<ScoredNode>> {
+        let clients = self.shard_clients().await?;
+        engine.remote_query(&clients, query_text).await
+    }
+
+    /// Connect to every known shard.
+    async fn shard_clients(&self) -> DistributedResult<Vec<ShardServiceClient>> {
+        let mut clients = Vec::with_capacity(self.shards.len());
+        for &(shard_id, _) in &self.shards {
+            clients.push(self.shard_client(shard_id).await?);
+        }
+        Ok(clients)
+    }
+
+    /// Connect to one shard, reporting its address if it is unreachable.
+    async fn shard_client(&self, shard_id: ShardId) -> DistributedResult<ShardServiceClient> {
+        let addr = self
+            .shards
+            .iter()
+            .find(|(id, _)| *id == shard_id)
+            .map(|(_, addr)| *addr)
+            .ok_or(DistributedError::ShardNotFound(shard_id))?;
+
+        self.pool
+            .get_client(shard_id)
+            .await
+            .map_err(|e| DistributedError::Unreachable {
+                target: format!("{} at {}", shard_id, addr),
+                attempts: self.config.retry_attempts,
+                reason: e.to_string(),
+            })
+    }
+}
+
+/// Resolve a `host:port` string to a socket address.
+async fn resolve_addr(addr: &str) -> DistributedResult<SocketAddr> {
+    tokio::net::lookup_host(addr)
+        .await
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| DistributedError::RpcError(format!("invalid address: {}", addr)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_connect_reports_unreachable_coordinator() {
+        // Bind and drop a listener so the port is known to be closed
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let config = ClientConfig {
+            connect_timeout: Duration::from_millis(200),
+            retry_attempts: 2,
+            retry_delay: Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        let err = ClusterClient::connect(&addr.to_string(), config)
+            .await
+            .err()
+            .expect("connecting to a closed port should fail");
+
+        match &err {
+            DistributedError::Unreachable {
+                target, attempts, ..
+            } => {
+                assert_eq!(target, &format!("coordinator at {}", addr));
+                assert_eq!(*attempts, 2);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(err.to_string().contains("after 2 attempt(s)"));
+    }
+
+    #[tokio::test]
+    async fn test_connect_rejects_invalid_address() {
+        let err = ClusterClient::connect("not an address", ClientConfig::default())
+            .await
+            .err()
+            .expect("an unparseable address should fail");
+        assert!(matches!(err, DistributedError::RpcError(_)));
+    }
+}
@@ -6,20 +6,26 @@
 //! - Routing documents to shards using consistent hashing
 //! - Synchronizing ticks across shards using barriers
 //! - Aggregating global statistics like document frequencies
+//! - Optionally persisting its state so it can fail over after a restart
 
 mod shard_registry;
+mod state;
 mod tick_barrier;
 
 pub use shard_registry::{RegisteredShard, ShardRegistry};
 pub use tick_barrier::TickBarrier;
 
 use crate::hashing::ConsistentHashRing;
+use crate::rpc::messages::{HeartbeatMessage, HeartbeatResponse};
 use crate::types::*;
 use phago_core::types::{DocumentId, Tick};
+use state::{CoordinatorState, PersistedShard};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tracing::warn;
 
 /// The distributed coordinator.
 ///
@@ -35,6 +41,13 @@ use tokio::sync::RwLock;
 /// mutability with `RwLock` for the registry and hash ring, and atomics
 /// for the tick counter.
 ///
+/// # Failover
+///
+/// A coordinator built with [`Coordinator::with_state_path`] snapshots its
+/// registry, hash ring, and tick on every change and restores them on
+/// startup. Each restart bumps the coordinator's epoch; shards notice the
+/// new epoch in heartbeat responses and call [`Coordinator::rejoin_shard`].
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -62,6 +75,12 @@ pub struct Coordinator {
     hash_ring: Arc<RwLock<ConsistentHashRing>>,
     /// Configuration for the distributed system.
     config: DistributedConfig,
+    /// Generation of this coordinator instance.
+    epoch: u64,
+    /// Where state snapshots are written, if persistence is enabled.
+    state_path: Option<PathBuf>,
+    /// Agents moved between shards since startup.
+    migrations: AtomicU64,
 }
 
 impl Coordinator {
@@ -80,6 +99,9 @@ impl Coordinator {
                 num_shards,
                 ..Default::default()
             },
+            epoch: 1,
+            state_path: None,
+            migrations: AtomicU64::new(0),
         }
     }
 
@@ -95,9 +117,61 @@ impl Coordinator {
                 config.virtual_nodes_per_shard,
             ))),
             config,
+            epoch: 1,
+            state_path: None,
+            migrations: AtomicU64::new(0),
         }
     }
 
+    /// Persist state to `path` and restore from it if it already exists.
+    ///
+    /// When a snapshot is found, the registry, hash ring, tick counter, and
+    /// configuration are replaced with the stored ones, the tick barrier is
+    /// rebuilt for the restored shard count, and the epoch is advanced past
+    /// the stored one. Restored shards are marked `Recovering` until they
+    /// rejoin or heartbeat. The snapshot is rewritten immediately so the new
+    /// epoch survives another restart.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DistributedError::Persistence` if the snapshot cannot be
+    /// read, parsed, or written.
+    pub fn with_state_path(mut self, path: impl Into<PathBuf>) -> DistributedResult<Self> {
+        let path = path.into();
+
+        if let Some(state) = CoordinatorState::load(&path)? {
+            let mut registry = ShardRegistry::new();
+            for shard in state.shards {
+                let mut shard = RegisteredShard::from(shard);
+                if shard.status == ShardStatus::Online {
+                    shard.status = ShardStatus::Recovering;
+                }
+                registry.restore(shard);
+            }
+            registry.reserve_ids(state.next_shard_id);
+
+            let barrier_count = if registry.count() > 0 {
+                registry.count()
+            } else {
+                state.config.num_shards as usize
+            };
+
+            self.barrier = Arc::new(TickBarrier::new(barrier_count));
+            self.shards = Arc::new(RwLock::new(registry));
+            self.hash_ring = Arc::new(RwLock::new(ConsistentHashRing::from_shards(
+                &state.ring_shards,
+                state.virtual_nodes,
+            )));
+            self.current_tick = Arc::new(AtomicU64::new(state.tick));
+            self.config = state.config;
+            self.epoch = state.epoch + 1;
+        }
+
+        self.state_path = Some(path);
+        self.snapshot()?;
+        Ok(self)
+    }
+
     /// Register a shard with the coordinator.
     ///
     /// The shard will be assigned a unique ID, added to the registry,
@@ -120,10 +194,38 @@ impl Coordinator {
 
         // Update barrier for new shard count
         self.barrier.set_shard_count(registry.count()).await;
+        drop((registry, ring));
 
+        self.persist().await?;
         Ok(shard_id)
     }
 
+    /// Re-register a shard under the ID it already holds.
+    ///
+    /// Called by shards after they detect a coordinator restart (see
+    /// [`Coordinator::heartbeat`]). The shard is marked online, placed back
+    /// on the hash ring if missing, and the tick barrier is resized.
+    ///
+    /// # Returns
+    ///
+    /// The coordinator's tick, which the shard should synchronize to.
+    pub async fn rejoin_shard(&self, info: ShardInfo) -> DistributedResult<Tick> {
+        let shard_id = info.id;
+        let mut registry = self.shards.write().await;
+        registry.register_with_id(info, shard_id);
+
+        let mut ring = self.hash_ring.write().await;
+        if !ring.contains_shard(shard_id) {
+            ring.add_shard(shard_id);
+        }
+
+        self.barrier.set_shard_count(registry.count()).await;
+        drop((registry, ring));
+
+        self.persist().await?;
+        Ok(self.current_tick())
+    }
+
     /// Deregister a shard from the coordinator.
     ///
     /// The shard will be removed from the registry and hash ring.
@@ -141,8 +243,9 @@ impl Coordinator {
 
         // Update barrier
         self.barrier.set_shard_count(registry.count()).await;
+        drop((registry, ring));
 
-        Ok(())
+        self.persist().await
     }
 
     /// Route a document to the appropriate shard.
@@ -160,8 +263,22 @@ impl Coordinator {
     /// Returns the primary shard plus additional replica shards based
     /// on the configured replication factor.
     pub async fn get_replica_shards(&self, doc_id: &DocumentId) -> Vec<ShardId> {
+        self.get_replica_shards_with(doc_id, self.config.replication_factor)
+            .await
+    }
+
+    /// Get the primary shard plus `replicas` additional replica shards.
+    ///
+    /// Like [`get_replica_shards`](Self::get_replica_shards), but with an
+    /// explicit replica count instead of the configured replication factor.
+    /// The result never holds more shards than the ring contains.
+    pub async fn get_replica_shards_with(
+        &self,
+        doc_id: &DocumentId,
+        replicas: u32,
+    ) -> Vec<ShardId> {
         let ring = self.hash_ring.read().await;
-        ring.get_replica_shards(doc_id, self.config.replication_factor as usize)
+        ring.get_replica_shards(doc_id, replicas as usize)
     }
 
     /// Signal that a shard has completed a phase.
@@ -186,6 +303,19 @@ impl Coordinator {
         self.barrier.wait_all(phase, tick).await
     }
 
+    /// Wait until every shard has completed `phase` for `tick` or later.
+    ///
+    /// Used by pipelined runners, which gate only the phases with
+    /// cross-shard dependencies and let shards drift apart in between.
+    pub async fn wait_for_epoch(&self, phase: TickPhase, tick: Tick) -> DistributedResult<()> {
+        self.barrier.wait_epoch(phase, tick).await
+    }
+
+    /// The latest tick every shard has completed `phase` for.
+    pub async fn phase_epoch(&self, phase: TickPhase) -> Option<Tick> {
+        self.barrier.phase_epoch(phase).await
+    }
+
     /// Advance to the next tick.
     ///
     /// This should be called after all phases of the current tick
@@ -193,14 +323,87 @@ impl Coordinator {
     pub async fn advance_tick(&self) -> Tick {
         let new_tick = self.current_tick.fetch_add(1, Ordering::SeqCst) + 1;
         self.barrier.reset_for_tick(new_tick).await;
+        if let Err(e) = self.persist().await {
+            warn!(
+                "Failed to persist coordinator state at tick {}: {}",
+                new_tick, e
+            );
+        }
         new_tick
     }
 
+    /// Decide which shards should hand idle agents to which.
+    ///
+    /// Shards with no undigested documents donate their migratable agents
+    /// to shards whose undigested documents outnumber their digesters by
+    /// at least `policy.min_backlog`, largest shortfall first. At most
+    /// `policy.max_migrations_per_tick` agents are moved in total.
+    pub fn plan_migrations(
+        &self,
+        backlogs: &[ShardBacklog],
+        policy: &MigrationPolicy,
+    ) -> Vec<MigrationCommand> {
+        let mut donors: Vec<(ShardId, usize)> = backlogs
+            .iter()
+            .filter(|b| b.undigested_documents == 0 && b.migratable > 0)
+            .map(|b| (b.shard_id, b.migratable))
+            .collect();
+        donors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut needy: Vec<(ShardId, usize)> = backlogs
+            .iter()
+            .map(|b| {
+                let shortfall = b.undigested_documents.saturating_sub(b.digesters_alive);
+                (b.shard_id, shortfall)
+            })
+            .filter(|&(_, shortfall)| shortfall > 0 && shortfall >= policy.min_backlog)
+            .collect();
+        needy.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut budget = policy.max_migrations_per_tick;
+        let mut commands = Vec::new();
+        for (to, mut shortfall) in needy {
+            for (from, available) in donors.iter_mut() {
+                let count = (*available).min(shortfall).min(budget);
+                if count == 0 {
+                    continue;
+                }
+                commands.push(MigrationCommand {
+                    from: *from,
+                    to,
+                    count,
+                });
+                *available -= count;
+                shortfall -= count;
+                budget -= count;
+            }
+        }
+        commands
+    }
+
+    /// Count agents moved by carrying out migration commands.
+    pub fn record_migrations(&self, count: u64) {
+        self.migrations.fetch_add(count, Ordering::Relaxed);
+    }
+
     /// Get the current tick number.
     pub fn current_tick(&self) -> Tick {
         self.current_tick.load(Ordering::SeqCst)
     }
 
+    /// Get this coordinator's generation.
+    ///
+    /// Starts at 1 and increases by one each time a persistent coordinator
+    /// is restored from its state file.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Get the state file path, if persistence is enabled.
+    pub fn state_path(&self) -> Option<&std::path::Path> {
+        self.state_path.as_deref()
+    }
+
     /// Aggregate global document frequencies from all shards.
     ///
     /// This is used for computing global TF-IDF scores. Each shard
@@ -253,6 +456,27 @@ impl Coordinator {
         registry.heartbeat(&shard_id);
     }
 
+    /// Handle a heartbeat message from a shard.
+    ///
+    /// Known shards have their heartbeat and metrics updated. Unknown shards
+    /// get an unacknowledged response, which tells them to re-register.
+    /// Every response carries the coordinator's epoch and tick.
+    pub async fn heartbeat(&self, msg: &HeartbeatMessage) -> HeartbeatResponse {
+        let mut registry = self.shards.write().await;
+        let acknowledged = registry.contains(&msg.shard_id);
+        if acknowledged {
+            registry.heartbeat(&msg.shard_id);
+            registry.update_metrics(&msg.shard_id, msg.document_count as usize, msg.memory_bytes);
+        }
+
+        HeartbeatResponse {
+            acknowledged,
+            expected_tick: self.current_tick(),
+            epoch: self.epoch,
+            commands: Vec::new(),
+        }
+    }
+
     /// Check for dead shards and mark them offline.
     ///
     /// Returns the IDs of shards that were marked offline.
@@ -287,6 +511,7 @@ impl Coordinator {
             total_documents: registry.total_documents(),
             total_memory_bytes: registry.total_memory(),
             current_tick: self.current_tick(),
+            migrations: self.migrations.load(Ordering::Relaxed),
         }
     }
 
@@ -300,6 +525,51 @@ impl Coordinator {
         let ring = self.hash_ring.read().await;
         ring.shard_count()
     }
+
+    /// Write a snapshot if persistence is enabled.
+    async fn persist(&self) -> DistributedResult<()> {
+        if self.state_path.is_none() {
+            return Ok(());
+        }
+        // The write lock serializes concurrent snapshots onto the same file
+        let registry = self.shards.write().await;
+        let ring = self.hash_ring.read().await;
+        self.write_state(&registry, &ring)
+    }
+
+    /// Write a snapshot without awaiting locks (construction time only).
+    fn snapshot(&self) -> DistributedResult<()> {
+        let (Ok(registry), Ok(ring)) = (self.shards.try_read(), self.hash_ring.try_read()) else {
+            return Err(DistributedError::Persistence(
+                "coordinator state is locked".to_string(),
+            ));
+        };
+        self.write_state(&registry, &ring)
+    }
+
+    fn write_state(
+        &self,
+        registry: &ShardRegistry,
+        ring: &ConsistentHashRing,
+    ) -> DistributedResult<()> {
+        let Some(path) = &self.state_path else {
+            return Ok(());
+        };
+        CoordinatorState {
+            epoch: self.epoch,
+            tick: self.current_tick(),
+            config: self.config.clone(),
+            next_shard_id: registry.next_id(),
+            ring_shards: ring.all_shards(),
+            virtual_nodes: ring.virtual_nodes_per_shard(),
+            shards: registry
+                .all_registered()
+                .into_iter()
+                .map(PersistedShard::from)
+                .collect(),
+        }
+        .save(path)
+    }
 }
 
 /// Statistics about the distributed cluster.
@@ -315,6 +585,8 @@ pub struct ClusterStats {
     pub total_memory_bytes: u64,
     /// Current simulation tick.
     pub current_tick: Tick,
+    /// Agents moved between shards since the coordinator started.
+    pub migrations: u64,
 }
 
 #[cfg(test)]
@@ -395,7 +667,7 @@ mod tests {
         let coord = Coordinator::new(3);
 
         let id1 = coord.register_shard(test_shard_info()).await.unwrap();
-        let id2 = coord.register_shard(test_shard_info()).await.unwrap();
+        let _id2 = coord.register_shard(test_shard_info()).await.unwrap();
 
         assert_eq!(coord.all_shards().await.len(), 2);
 
@@ -423,6 +695,42 @@ mod tests {
         assert_eq!(stats.total_memory_bytes, 1024);
     }
 
+    #[test]
+    fn test_plan_migrations_moves_idle_agents_to_backlogs() {
+        let coord = Coordinator::new(3);
+        let backlog = |id, undigested, alive, migratable| ShardBacklog {
+            shard_id: ShardId::new(id),
+            undigested_documents: undigested,
+            digesters_alive: alive,
+            migratable,
+        };
+        let policy = MigrationPolicy {
+            max_migrations_per_tick: 3,
+            ..Default::default()
+        };
+
+        let plan = coord.plan_migrations(
+            &[
+                backlog(0, 0, 4, 4),
+                backlog(1, 6, 2, 0),
+                backlog(2, 2, 1, 1),
+            ],
+            &policy,
+        );
+        assert_eq!(
+            plan,
+            vec![MigrationCommand {
+                from: ShardId::new(0),
+                to: ShardId::new(1),
+                count: 3,
+            }]
+        );
+
+        // A shard with work of its own never donates
+        let plan = coord.plan_migrations(&[backlog(0, 1, 4, 4), backlog(1, 6, 0, 0)], &policy);
+        assert!(plan.is_empty());
+    }
+
     #[tokio::test]
     async fn test_replica_shards() {
         let config = DistributedConfig {
@@ -442,4 +750,57 @@ mod tests {
         let unique: std::collections::HashSet<_> = replicas.iter().collect();
         assert_eq!(unique.len(), 3);
     }
+
+    #[tokio::test]
+    async fn test_heartbeat_reports_epoch_and_unknown_shards() {
+        let coord = Coordinator::new(2);
+        let id = coord.register_shard(test_shard_info()).await.unwrap();
+
+        let mut msg = HeartbeatMessage {
+            shard_id: id,
+            current_tick: 0,
+            agent_count: 0,
+            document_count: 42,
+            node_count: 0,
+            memory_bytes: 0,
+            timestamp_ms: 0,
+        };
+        let response = coord.heartbeat(&msg).await;
+        assert!(response.acknowledged);
+        assert_eq!(response.epoch, coord.epoch());
+        assert_eq!(coord.total_documents().await, 42);
+
+        msg.shard_id = ShardId::new(7);
+        assert!(!coord.heartbeat(&msg).await.acknowledged);
+    }
+
+    #[tokio::test]
+    async fn test_state_path_restores_registry_ring_and_tick() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        let coord = Coordinator::new(2).with_state_path(&path).unwrap();
+        let a = coord.register_shard(test_shard_info()).await.unwrap();
+        let b = coord.register_shard(test_shard_info()).await.unwrap();
+        coord.deregister_shard(a).await.unwrap();
+        coord.advance_tick().await;
+        coord.advance_tick().await;
+        let doc_id = DocumentId::from_seed(9);
+        let routed = coord.route_document(&doc_id).await;
+        drop(coord);
+
+        let restored = Coordinator::new(2).with_state_path(&path).unwrap();
+        assert_eq!(restored.epoch(), 2);
+        assert_eq!(restored.current_tick(), 2);
+        assert_eq!(restored.route_document(&doc_id).await, routed);
+        assert_eq!(restored.barrier.shard_count().await, 1);
+
+        let shards = restored.all_shards().await;
+        assert_eq!(shards.len(), 1);
+        assert_eq!(shards[0].id, b);
+
+        // Deregistered IDs are not handed out again
+        let c = restored.register_shard(test_shard_info()).await.unwrap();
+        assert_eq!(c, ShardId::new(2));
+    }
 }
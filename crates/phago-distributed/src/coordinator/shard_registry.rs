@@ -99,6 +99,34 @@ impl ShardRegistry {
         id
     }
 
+    /// Restore a shard entry exactly as it was snapshotted.
+    ///
+    /// Unlike `register_with_id`, status and metrics are kept as given.
+    pub fn restore(&mut self, shard: RegisteredShard) {
+        let id = shard.info.id;
+        self.shards.insert(id, shard);
+        if id.0 >= self.next_id {
+            self.next_id = id.0 + 1;
+        }
+    }
+
+    /// Get the ID the next call to `register` will assign.
+    pub fn next_id(&self) -> u32 {
+        self.next_id
+    }
+
+    /// Ensure IDs below `next_id` are never reassigned.
+    pub fn reserve_ids(&mut self, next_id: u32) {
+        self.next_id = self.next_id.max(next_id);
+    }
+
+    /// Get all registered shards (includes status), ordered by ID.
+    pub fn all_registered(&self) -> Vec<&RegisteredShard> {
+        let mut shards: Vec<&RegisteredShard> = self.shards.values().collect();
+        shards.sort_by_key(|r| r.info.id);
+        shards
+    }
+
     /// Get shard info by ID.
     pub fn get(&self, id: &ShardId) -> Option<&ShardInfo> {
         self.shards.get(id).map(|r| &r.info)
@@ -334,7 +362,7 @@ mod tests {
     fn test_online_shards() {
         let mut registry = ShardRegistry::new();
 
-        let id1 = registry.register(test_shard_info());
+        let _id1 = registry.register(test_shard_info());
         let id2 = registry.register(test_shard_info());
         let _id3 = registry.register(test_shard_info());
 
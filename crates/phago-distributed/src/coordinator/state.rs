@@ -0,0 +1,84 @@
+//! Durable coordinator state for failover.
+//!
+//! A coordinator configured with a state path snapshots everything needed
+//! to resume after a restart: the shard registry, the hash ring topology,
+//! the current tick, and its generation (epoch). Shards compare the epoch
+//! in heartbeat responses to detect that the coordinator has restarted.
+
+use super::shard_registry::RegisteredShard;
+use crate::types::*;
+use phago_core::types::Tick;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Snapshot of a coordinator written to its state file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CoordinatorState {
+    /// Generation of the coordinator that wrote this snapshot.
+    pub epoch: u64,
+    /// Global tick at the time of the snapshot.
+    pub tick: Tick,
+    /// Distributed configuration the coordinator was running with.
+    pub config: DistributedConfig,
+    /// Next shard ID the registry would assign.
+    pub next_shard_id: u32,
+    /// Shard IDs present on the hash ring.
+    pub ring_shards: Vec<ShardId>,
+    /// Virtual nodes per shard on the hash ring.
+    pub virtual_nodes: u32,
+    /// Registered shards with their last known status and metrics.
+    pub shards: Vec<PersistedShard>,
+}
+
+/// A registry entry as stored on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PersistedShard {
+    pub info: ShardInfo,
+    pub status: ShardStatus,
+    pub memory_bytes: u64,
+}
+
+impl From<&RegisteredShard> for PersistedShard {
+    fn from(shard: &RegisteredShard) -> Self {
+        Self {
+            info: shard.info.clone(),
+            status: shard.status,
+            memory_bytes: shard.memory_bytes,
+        }
+    }
+}
+
+impl From<PersistedShard> for RegisteredShard {
+    fn from(shard: PersistedShard) -> Self {
+        Self {
+            info: shard.info,
+            status: shard.status,
+            memory_bytes: shard.memory_bytes,
+        }
+    }
+}
+
+impl CoordinatorState {
+    /// Load a snapshot, returning `None` if the file does not exist yet.
+    pub fn load(path: &Path) -> DistributedResult<Option<Self>> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(DistributedError::Persistence(e.to_string())),
+        };
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| DistributedError::Persistence(e.to_string()))
+    }
+
+    /// Write the snapshot atomically (temp file + rename), so a crash
+    /// mid-write leaves the previous snapshot intact.
+    pub fn save(&self, path: &Path) -> DistributedResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| DistributedError::Persistence(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| DistributedError::Persistence(e.to_string()))
+    }
+}
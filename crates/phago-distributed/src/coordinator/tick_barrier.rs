@@ -6,7 +6,7 @@
 
 use crate::types::*;
 use phago_core::types::Tick;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use tokio::sync::{Mutex, Notify};
 
 /// Barrier ensuring all shards complete a phase before any proceeds.
@@ -19,11 +19,19 @@ use tokio::sync::{Mutex, Notify};
 ///
 /// Each shard must signal completion of each phase, and all shards must
 /// complete before any can proceed to the next phase.
+///
+/// Alongside the per-tick completion records, the barrier keeps a per-phase
+/// epoch for every shard: the latest tick it has completed that phase for.
+/// Epochs survive [`reset_for_tick`](Self::reset_for_tick), so pipelined
+/// runners can gate a single phase with [`wait_epoch`](Self::wait_epoch)
+/// while shards are spread over neighbouring ticks.
 pub struct TickBarrier {
     /// Number of shards expected to participate.
     shard_count: Mutex<usize>,
     /// Set of (shard, phase, tick) tuples that have completed.
     completed: Mutex<HashSet<(ShardId, TickPhase, Tick)>>,
+    /// Latest tick each shard has completed each phase for.
+    epochs: Mutex<HashMap<(ShardId, TickPhase), Tick>>,
     /// Notification channel for waiters.
     notify: Notify,
     /// Default timeout for phase completion in seconds.
@@ -36,6 +44,7 @@ impl TickBarrier {
         Self {
             shard_count: Mutex::new(shard_count),
             completed: Mutex::new(HashSet::new()),
+            epochs: Mutex::new(HashMap::new()),
             notify: Notify::new(),
             phase_timeout_secs: 30,
         }
@@ -46,6 +55,7 @@ impl TickBarrier {
         Self {
             shard_count: Mutex::new(shard_count),
             completed: Mutex::new(HashSet::new()),
+            epochs: Mutex::new(HashMap::new()),
             notify: Notify::new(),
             phase_timeout_secs: timeout_secs,
         }
@@ -81,6 +91,11 @@ impl TickBarrier {
         completed.insert((shard_id, phase, tick));
         drop(completed);
 
+        let mut epochs = self.epochs.lock().await;
+        let epoch = epochs.entry((shard_id, phase)).or_insert(tick);
+        *epoch = (*epoch).max(tick);
+        drop(epochs);
+
         // Notify all waiters that progress was made
         self.notify.notify_waiters();
 
@@ -179,6 +194,68 @@ impl TickBarrier {
         }
     }
 
+    /// The latest tick `shard_id` has completed `phase` for.
+    pub async fn epoch(&self, shard_id: ShardId, phase: TickPhase) -> Option<Tick> {
+        self.epochs.lock().await.get(&(shard_id, phase)).copied()
+    }
+
+    /// The latest tick every shard has completed `phase` for.
+    ///
+    /// `None` until as many shards as the barrier expects have completed
+    /// the phase at least once.
+    pub async fn phase_epoch(&self, phase: TickPhase) -> Option<Tick> {
+        let shard_count = *self.shard_count.lock().await;
+        let epochs = self.epochs.lock().await;
+        let mut reached: Vec<Tick> = epochs
+            .iter()
+            .filter(|((_, p), _)| *p == phase)
+            .map(|(_, tick)| *tick)
+            .collect();
+        if shard_count == 0 || reached.len() < shard_count {
+            return None;
+        }
+        // With departed shards still on record, the laggards that count
+        // are the slowest `shard_count` of the fastest
+        reached.sort_unstable_by(|a, b| b.cmp(a));
+        reached.get(shard_count - 1).copied()
+    }
+
+    /// Wait until every shard has completed `phase` for `tick` or later.
+    ///
+    /// Unlike [`wait_all`](Self::wait_all), this gates a single phase: it
+    /// does not care which phases of later ticks shards have moved on to,
+    /// and it is not disturbed by [`reset_for_tick`](Self::reset_for_tick).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DistributedError::PhaseTimeout` if the timeout is reached
+    /// before all shards get there.
+    pub async fn wait_epoch(&self, phase: TickPhase, tick: Tick) -> DistributedResult<()> {
+        let timeout = tokio::time::Duration::from_secs(self.phase_timeout_secs);
+
+        loop {
+            // Register before checking, so a completion in between still wakes us
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if self
+                .phase_epoch(phase)
+                .await
+                .is_some_and(|epoch| epoch >= tick)
+            {
+                return Ok(());
+            }
+
+            tokio::select! {
+                _ = notified => continue,
+                _ = tokio::time::sleep(timeout) => {
+                    return Err(DistributedError::PhaseTimeout(phase));
+                }
+            }
+        }
+    }
+
     /// Reset the barrier for a new tick.
     ///
     /// This clears all completion records. Should be called before
@@ -327,6 +404,55 @@ mod tests {
         assert_eq!(barrier.completed_count(TickPhase::Sense, 1).await, 2);
     }
 
+    #[tokio::test]
+    async fn test_epochs_track_each_phase_and_survive_reset() {
+        let barrier = TickBarrier::with_timeout(2, 5);
+
+        for tick in 0..3 {
+            barrier
+                .complete(ShardId::new(0), TickPhase::Act, tick)
+                .await
+                .unwrap();
+        }
+        barrier
+            .complete(ShardId::new(1), TickPhase::Act, 1)
+            .await
+            .unwrap();
+        barrier.reset_for_tick(3).await;
+
+        assert_eq!(
+            barrier.epoch(ShardId::new(0), TickPhase::Act).await,
+            Some(2)
+        );
+        assert_eq!(barrier.epoch(ShardId::new(0), TickPhase::Decay).await, None);
+        assert_eq!(barrier.phase_epoch(TickPhase::Act).await, Some(1));
+        assert_eq!(barrier.phase_epoch(TickPhase::Decay).await, None);
+
+        // The shard ahead does not hold up waiters for older ticks
+        barrier.wait_epoch(TickPhase::Act, 1).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_epoch_waits_for_the_slowest_shard() {
+        let barrier = std::sync::Arc::new(TickBarrier::with_timeout(2, 5));
+        barrier
+            .complete(ShardId::new(0), TickPhase::Act, 4)
+            .await
+            .unwrap();
+
+        let barrier_ref = barrier.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+            barrier_ref
+                .complete(ShardId::new(1), TickPhase::Act, 4)
+                .await
+                .unwrap();
+        });
+
+        barrier.wait_epoch(TickPhase::Act, 4).await.unwrap();
+        assert_eq!(barrier.phase_epoch(TickPhase::Act).await, Some(4));
+    }
+
     #[tokio::test]
     async fn test_update_shard_count() {
         let barrier = TickBarrier::new(2);
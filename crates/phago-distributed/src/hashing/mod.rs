@@ -106,6 +106,32 @@ impl ConsistentHashRing {
         }
     }
 
+    /// Rebuild a ring containing exactly the given shards.
+    ///
+    /// Used to restore a ring topology from a snapshot. Unlike `new`, the
+    /// shard IDs need not be contiguous.
+    pub fn from_shards(shards: &[ShardId], virtual_nodes: u32) -> Self {
+        assert!(virtual_nodes > 0, "Virtual nodes must be greater than 0");
+
+        let mut ring = BTreeMap::new();
+        for shard_id in shards {
+            for vnode in 0..virtual_nodes {
+                ring.insert(Self::hash_shard_vnode(shard_id.0, vnode), *shard_id);
+            }
+        }
+
+        Self {
+            ring,
+            shard_count: shards.len() as u32,
+            virtual_nodes,
+        }
+    }
+
+    /// Whether a shard has virtual nodes on the ring.
+    pub fn contains_shard(&self, shard_id: ShardId) -> bool {
+        self.ring.values().any(|s| *s == shard_id)
+    }
+
     /// Get the shard ID for a document.
     ///
     /// This operation is O(log n) where n is the total number of virtual nodes.
@@ -293,7 +319,7 @@ mod tests {
         // Each shard should get roughly 33 documents
         for count in counts {
             assert!(
-                count >= 20 && count <= 50,
+                (20..=50).contains(&count),
                 "Distribution skewed: {:?}",
                 counts
             );
@@ -5,6 +5,7 @@
 //! and fault tolerance.
 
 pub mod bench;
+pub mod cluster;
 pub mod coordinator;
 pub mod hashing;
 pub mod query;
@@ -17,9 +18,10 @@ pub use bench::{
     compare_single_vs_distributed, create_bench_cluster, generate_documents, print_scaling_results,
     run_benchmark, run_quick_benchmark, scaling_benchmark, BenchConfig, BenchResults,
 };
+pub use cluster::ClusterClient;
 pub use coordinator::{ClusterStats, Coordinator, RegisteredShard, ShardRegistry, TickBarrier};
 pub use hashing::ConsistentHashRing;
 pub use query::{merge_results, tokenize, DistributedHybridConfig, DistributedQueryEngine};
-pub use runner::{DistributedRunner, DistributedTickResult, RunnerConfig};
+pub use runner::{DistributedRunner, DistributedTickResult, QuerySnapshot, RunnerConfig};
 pub use shard::{GhostCacheStats, GhostNodeCache, ShardedColony};
 pub use types::*;
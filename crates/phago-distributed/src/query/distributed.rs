@@ -7,10 +7,10 @@
 //! 3. **Scatter (Phase 3)**: Execute local queries with global DF for accurate IDF
 //! 4. **Gather (Phase 4)**: Merge and rank top-k results from all shards
 
-use crate::query::tokenize;
+use crate::rpc::protocol::ShardServiceClient;
 use crate::shard::ShardedColony;
 use crate::types::*;
-use phago_core::topology::TopologyGraph;
+use phago_core::tokenize::{Tokenizer, TokenizerConfig};
 use std::collections::HashMap;
 
 /// Configuration for distributed hybrid queries.
@@ -24,6 +24,9 @@ pub struct DistributedHybridConfig {
     pub max_results: usize,
     /// Candidate multiplier for TF-IDF.
     pub candidate_multiplier: usize,
+    /// How query text is split into terms. Must match the shards'
+    /// `ColonyConfig::tokenizer`, or query terms miss their concepts.
+    pub tokenizer: TokenizerConfig,
 }
 
 impl Default for DistributedHybridConfig {
@@ -33,6 +36,7 @@ impl Default for DistributedHybridConfig {
             max_local_results: 30,
             max_results: 10,
             candidate_multiplier: 3,
+            tokenizer: TokenizerConfig::default(),
         }
     }
 }
@@ -46,12 +50,14 @@ impl Default for DistributedHybridConfig {
 /// 4. Merging and normalizing results across shards
 pub struct DistributedQueryEngine {
     config: DistributedHybridConfig,
+    tokenizer: Tokenizer,
 }
 
 impl DistributedQueryEngine {
     /// Create a new distributed query engine with the given configuration.
     pub fn new(config: DistributedHybridConfig) -> Self {
-        Self { config }
+        let tokenizer = Tokenizer::new(config.tokenizer.clone());
+        Self { config, tokenizer }
     }
 
     /// Create a query engine with default configuration.
@@ -64,6 +70,11 @@ impl DistributedQueryEngine {
         &self.config
     }
 
+    /// The tokenizer query text is split with.
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+
     /// Phase 1: Get term frequencies from a shard.
     ///
     /// Collects how many documents in this shard contain each query term.
@@ -102,6 +113,7 @@ impl DistributedQueryEngine {
         shard: &ShardedColony,
         request: &LocalQueryRequest,
     ) -> LocalQueryResult {
+        let tokenizer = shard.local().tokenizer();
         let graph = shard.local().substrate().graph();
         let all_nodes = graph.all_nodes();
         let total_docs = all_nodes.len().max(1) as f64;
@@ -112,11 +124,7 @@ impl DistributedQueryEngine {
         for nid in &all_nodes {
             if let Some(node) = graph.get_node(nid) {
                 let label_lower = node.label.to_lowercase();
-                let label_terms: Vec<String> = label_lower
-                    .split(|c: char| !c.is_alphanumeric())
-                    .filter(|w| w.len() >= 3)
-                    .map(|w| w.to_string())
-                    .collect();
+                let label_terms: Vec<String> = tokenizer.words(&node.label).collect();
 
                 let mut score = 0.0;
                 for qt in &request.query_terms {
@@ -215,7 +223,7 @@ impl DistributedQueryEngine {
         shards: &[&ShardedColony],
         query_text: &str,
     ) -> Vec<ScoredNode> {
-        let query_terms = tokenize(query_text);
+        let query_terms = self.tokenizer.tokens(query_text);
         if query_terms.is_empty() || shards.is_empty() {
             return Vec::new();
         }
@@ -245,6 +253,58 @@ impl DistributedQueryEngine {
         self.merge_results(local_results)
     }
 
+    /// Execute a distributed query against remote shards over RPC.
+    ///
+    /// Runs the same four phases as [`distributed_query`](Self::distributed_query),
+    /// with each scatter step sent to every shard concurrently. Concepts
+    /// found on several shards (including replicas of the same document)
+    /// appear once per shard.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DistributedError::RpcError` if any shard fails to answer.
+    pub async fn remote_query(
+        &self,
+        shards: &[ShardServiceClient],
+        query_text: &str,
+    ) -> DistributedResult<Vec<ScoredNode>> {
+        let query_terms = self.tokenizer.tokens(query_text);
+        if query_terms.is_empty() || shards.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Phase 1: Get local term frequencies
+        let local_dfs = futures::future::join_all(shards.iter().map(|client| {
+            client.get_term_frequencies(tarpc::context::current(), query_terms.clone())
+        }))
+        .await
+        .into_iter()
+        .map(flatten_rpc)
+        .collect::<DistributedResult<Vec<_>>>()?;
+
+        // Phase 2: Aggregate global DF
+        let global_df = self.aggregate_global_df(local_dfs);
+
+        // Phase 3: Execute local queries with global DF
+        let request = LocalQueryRequest {
+            query_terms,
+            max_results: self.config.max_local_results,
+            global_df,
+        };
+        let local_results = futures::future::join_all(
+            shards
+                .iter()
+                .map(|client| client.local_query(tarpc::context::current(), request.clone())),
+        )
+        .await
+        .into_iter()
+        .map(flatten_rpc)
+        .collect::<DistributedResult<Vec<_>>>()?;
+
+        // Phase 4: Merge results
+        Ok(self.merge_results(local_results))
+    }
+
     /// Execute a query on a single shard (for non-distributed use).
     ///
     /// This is useful for testing or when the data resides in a single shard.
@@ -253,10 +313,20 @@ impl DistributedQueryEngine {
     }
 }
 
+/// Collapse a tarpc transport error and a service error into one result.
+fn flatten_rpc<T>(
+    result: Result<crate::rpc::protocol::RpcResult<T>, tarpc::client::RpcError>,
+) -> DistributedResult<T> {
+    result
+        .map_err(|e| DistributedError::RpcError(e.to_string()))?
+        .map_err(|e| DistributedError::RpcError(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::hashing::ConsistentHashRing;
+    use crate::query::tokenize;
     use phago_core::types::Position;
     use phago_runtime::colony::ColonyConfig;
     use std::sync::Arc;
@@ -13,30 +13,15 @@ mod distributed;
 pub use distributed::{DistributedHybridConfig, DistributedQueryEngine};
 
 use crate::types::*;
+use phago_core::tokenize::Tokenizer;
 
-/// Tokenizer matching phago-rag's tokenizer.
+/// Tokenize `text` with the default [`Tokenizer`] (English stopwords,
+/// terms of three or more characters).
 ///
-/// Performs case-insensitive tokenization with stopword removal
-/// and minimum token length filtering.
+/// Engines split queries with their configured tokenizer instead; see
+/// [`DistributedHybridConfig::tokenizer`].
 pub fn tokenize(text: &str) -> Vec<String> {
-    let stopwords: std::collections::HashSet<&str> = [
-        "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
-        "do", "does", "did", "will", "would", "could", "should", "may", "might", "shall", "can",
-        "need", "to", "of", "in", "for", "on", "with", "at", "by", "from", "as", "into", "through",
-        "during", "before", "after", "above", "below", "between", "out", "off", "over", "under",
-        "again", "further", "then", "once", "and", "but", "or", "if", "while", "what", "which",
-        "who", "this", "that", "these", "those", "it", "its", "how",
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    text.to_lowercase()
-        .split_whitespace()
-        .filter(|w| w.len() >= 3 && !stopwords.contains(w))
-        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
-        .filter(|w| w.len() >= 3)
-        .collect()
+    Tokenizer::default().tokens(text)
 }
 
 /// Merge scored results from multiple shards.
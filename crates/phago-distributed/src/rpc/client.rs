@@ -4,7 +4,8 @@
 //! and the coordinator. It includes connection functions, retry logic,
 //! and a connection pool for efficient client reuse.
 
-use crate::rpc::protocol::{CoordinatorServiceClient, ShardServiceClient};
+use crate::rpc::protocol::{CoordinatorServiceClient, RpcError, ShardServiceClient};
+use crate::shard::ShardedColony;
 use crate::types::ShardId;
 use std::collections::HashMap;
 use std::net::SocketAddr;
@@ -547,6 +548,49 @@ impl CoordinatorClient {
         debug!("Invalidated coordinator client");
     }
 
+    /// Send a shard's heartbeat and rejoin if the coordinator restarted.
+    ///
+    /// The remote counterpart of `DistributedRunner::heartbeat`. On a
+    /// changed epoch or an unacknowledged heartbeat, the shard re-registers
+    /// at `address` and synchronizes its tick to the coordinator's.
+    ///
+    /// # Returns
+    ///
+    /// Whether the shard rejoined.
+    pub async fn heartbeat_shard(
+        &self,
+        shard: &RwLock<ShardedColony>,
+        address: &str,
+    ) -> Result<bool, RpcError> {
+        let client = self
+            .get()
+            .await
+            .map_err(|_| RpcError::CoordinatorUnavailable)?;
+
+        let msg = shard.read().await.heartbeat_message();
+        let response = client
+            .heartbeat(tarpc::context::current(), msg)
+            .await
+            .map_err(|e| RpcError::RpcFailed(e.to_string()))?;
+
+        let mut s = shard.write().await;
+        if !s.needs_rejoin(&response) {
+            return Ok(false);
+        }
+
+        info!(
+            "Coordinator epoch changed to {}; shard {:?} rejoining",
+            response.epoch,
+            s.shard_id()
+        );
+        let tick = client
+            .rejoin(tarpc::context::current(), s.shard_info(address.to_string()))
+            .await
+            .map_err(|e| RpcError::RpcFailed(e.to_string()))??;
+        s.mark_registered(response.epoch, tick);
+        Ok(true)
+    }
+
     /// Get the coordinator's address.
     pub fn addr(&self) -> SocketAddr {
         self.addr
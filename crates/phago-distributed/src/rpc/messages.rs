@@ -4,6 +4,7 @@
 //! operations that don't fit directly into the service traits.
 
 use crate::types::{CrossShardEdge, ScoredNode, ShardId};
+use phago_core::signal::SignalKindInfo;
 use phago_core::types::{AgentId, NodeId, Position, SignalType, Tick};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -88,6 +89,15 @@ pub struct CrossShardSignal {
     pub tick: Tick,
     /// Source shard where the signal originated.
     pub source_shard: ShardId,
+    /// Kind of the signal's payload, if it carries one.
+    pub payload_kind: Option<String>,
+    /// Serialized payload; empty when there is none.
+    pub payload: Vec<u8>,
+    /// What the source shard registered for the signal's kind, so a
+    /// custom kind keeps its label and decay rate on shards that have not
+    /// registered it.
+    #[serde(default)]
+    pub kind_info: Option<SignalKindInfo>,
 }
 
 /// Heartbeat message from shard to coordinator.
@@ -116,6 +126,11 @@ pub struct HeartbeatResponse {
     pub acknowledged: bool,
     /// Expected tick (for drift detection).
     pub expected_tick: Tick,
+    /// Generation of the coordinator that answered.
+    ///
+    /// Changes whenever the coordinator restarts; shards that see a new
+    /// epoch (or an unacknowledged heartbeat) must re-register.
+    pub epoch: u64,
     /// Any pending commands for the shard.
     pub commands: Vec<ShardCommand>,
 }
@@ -20,7 +20,7 @@
 //! use phago_distributed::rpc::server::ShardServer;
 //! use phago_distributed::shard::ShardedColony;
 //!
-//! let shard = Arc::new(RwLock::new(ShardedColony::new(...)));
+//! let shard = Rc::new(RwLock::new(ShardedColony::new(...)));
 //! let server = ShardServer::new(shard);
 //! server.serve("127.0.0.1:8080".parse().unwrap()).await?;
 //! ```
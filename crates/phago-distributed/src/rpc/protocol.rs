@@ -3,11 +3,12 @@
 //! This module defines the RPC service interfaces for distributed
 //! colony coordination using tarpc's procedural macro system.
 
+use crate::rpc::messages::{HeartbeatMessage, HeartbeatResponse};
 use crate::types::{
-    GhostNode, LocalQueryRequest, LocalQueryResult, PhaseResult, ShardHealth, ShardId, ShardInfo,
-    TickPhase,
+    GhostNode, LocalQueryRequest, LocalQueryResult, MigratableAgent, PhaseResult, ShardBacklog,
+    ShardHealth, ShardId, ShardInfo, TickPhase,
 };
-use phago_core::types::{Document, DocumentId, NodeData, NodeId};
+use phago_core::types::{AgentId, Document, DocumentId, NodeData, NodeId};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -113,6 +114,24 @@ pub trait ShardService {
     /// Signals from other shards are delivered here for local processing.
     async fn receive_signals(signals: Vec<crate::rpc::messages::CrossShardSignal>)
         -> RpcResult<()>;
+
+    /// Report undigested documents and idle digesters for migration planning.
+    ///
+    /// Agents that arrived less than `cooldown_ticks` ago are not counted
+    /// as migratable.
+    async fn backlog(cooldown_ticks: u64) -> RpcResult<ShardBacklog>;
+
+    /// Remove up to `count` idle agents so they can be sent to another shard.
+    ///
+    /// The agents leave without dying. The caller is responsible for
+    /// delivering each one with `receive_agent`.
+    async fn emigrate_agents(count: usize, cooldown_ticks: u64) -> RpcResult<Vec<MigratableAgent>>;
+
+    /// Respawn an agent that migrated from another shard.
+    ///
+    /// The agent is placed next to an undigested document and keeps its
+    /// ID, age, and vocabulary. Returns the respawned agent's ID.
+    async fn receive_agent(agent: MigratableAgent) -> RpcResult<AgentId>;
 }
 
 /// Service provided by the coordinator.
@@ -127,6 +146,17 @@ pub trait CoordinatorService {
     /// this shard in subsequent tick coordination.
     async fn register(info: ShardInfo) -> RpcResult<ShardId>;
 
+    /// Re-register a shard under its existing ID after a coordinator restart.
+    ///
+    /// Returns the coordinator's tick, which the shard should synchronize to.
+    async fn rejoin(info: ShardInfo) -> RpcResult<u64>;
+
+    /// Periodic liveness report from a shard.
+    ///
+    /// The response carries the coordinator's epoch; a changed epoch or an
+    /// unacknowledged heartbeat means the shard must call `rejoin`.
+    async fn heartbeat(msg: HeartbeatMessage) -> HeartbeatResponse;
+
     /// Unregister a shard from the coordinator.
     ///
     /// Should be called during graceful shutdown. The coordinator
@@ -144,6 +174,12 @@ pub trait CoordinatorService {
     /// Uses consistent hashing to determine which shard owns a document.
     async fn route_document(doc_id: DocumentId) -> ShardId;
 
+    /// Get every shard that should store a document.
+    ///
+    /// Returns the primary shard first, followed by `replicas` additional
+    /// shards. `None` uses the coordinator's configured replication factor.
+    async fn route_document_replicas(doc_id: DocumentId, replicas: Option<u32>) -> Vec<ShardId>;
+
     /// Get the shard responsible for a node.
     ///
     /// Uses consistent hashing based on node ID.
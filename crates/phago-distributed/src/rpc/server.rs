@@ -5,16 +5,18 @@
 //! (ShardedColony or Coordinator) and exposes it via tarpc.
 
 use crate::coordinator::Coordinator;
-use crate::rpc::messages::CrossShardSignal;
+use crate::query::DistributedQueryEngine;
+use crate::rpc::messages::{CrossShardSignal, HeartbeatMessage, HeartbeatResponse};
 use crate::rpc::protocol::{CoordinatorService, RpcError, RpcResult, ShardService, TickStatus};
 use crate::shard::ShardedColony;
 use crate::types::*;
 use futures::StreamExt;
+use phago_agents::digester::Digester;
 use phago_core::substrate::Substrate;
-use phago_core::topology::TopologyGraph;
-use phago_core::types::{Document, DocumentId, NodeData, NodeId};
+use phago_core::types::{AgentId, Document, DocumentId, NodeData, NodeId};
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::rc::Rc;
 use std::sync::Arc;
 use tarpc::context::Context;
 use tarpc::server::{self, Channel};
@@ -22,6 +24,9 @@ use tokio::sync::RwLock;
 use tokio_serde::formats::Bincode;
 use tracing::{debug, error, info, instrument};
 
+/// Idle ticks before a digester spawned for an ingested document dies.
+const DIGESTER_MAX_IDLE: u64 = 30;
+
 /// Server implementation for a shard.
 ///
 /// ShardServer wraps a `ShardedColony` and implements the `ShardService` trait,
@@ -29,8 +34,9 @@ use tracing::{debug, error, info, instrument};
 ///
 /// # Thread Safety
 ///
-/// The server uses `Arc<RwLock<ShardedColony>>` to allow safe concurrent access
-/// from multiple RPC handler tasks.
+/// The server uses `Rc<RwLock<ShardedColony>>` to allow safe concurrent access
+/// from multiple RPC handler tasks. A shard's colony is not `Send`, so the
+/// handlers all run on the task that calls [`start`](Self::start).
 ///
 /// # Example
 ///
@@ -38,13 +44,13 @@ use tracing::{debug, error, info, instrument};
 /// use phago_distributed::rpc::server::ShardServer;
 /// use phago_distributed::shard::ShardedColony;
 ///
-/// let shard = Arc::new(RwLock::new(ShardedColony::new(...)));
+/// let shard = Rc::new(RwLock::new(ShardedColony::new(...)));
 /// let server = ShardServer::new(shard);
 /// server.serve("127.0.0.1:8080".parse().unwrap()).await?;
 /// ```
 #[derive(Clone)]
 pub struct ShardServer {
-    shard: Arc<RwLock<ShardedColony>>,
+    shard: Rc<RwLock<ShardedColony>>,
 }
 
 impl ShardServer {
@@ -53,7 +59,7 @@ impl ShardServer {
     /// # Arguments
     ///
     /// * `shard` - The sharded colony to serve
-    pub fn new(shard: Arc<RwLock<ShardedColony>>) -> Self {
+    pub fn new(shard: Rc<RwLock<ShardedColony>>) -> Self {
         Self { shard }
     }
 
@@ -79,7 +85,15 @@ impl ShardServer {
             .map(server::BaseChannel::with_defaults)
             .for_each_concurrent(10, |channel| {
                 let server = self.clone();
-                async move { channel.execute(server.serve()).for_each(|_| async {}).await }
+                // Each item is an in-flight request that must be driven to
+                // send its response; they run on this task because shard
+                // state is not `Send`.
+                async move {
+                    channel
+                        .execute(server.serve())
+                        .for_each_concurrent(None, |response| response)
+                        .await
+                }
             })
             .await;
 
@@ -93,6 +107,11 @@ impl ShardService for ShardServer {
         debug!("Ingesting document: {}", doc.title);
         let mut shard = self.shard.write().await;
         let id = shard.ingest_document_direct(&doc.title, &doc.content, doc.position);
+        // Remote callers cannot spawn agents, so each document brings its
+        // own digester and is digested over the following ticks.
+        shard.local_mut().spawn(Box::new(
+            Digester::new(doc.position).with_max_idle(DIGESTER_MAX_IDLE),
+        ));
         debug!("Document ingested with ID: {:?}", id);
         Ok(id)
     }
@@ -124,7 +143,7 @@ impl ShardService for ShardServer {
     ) -> RpcResult<LocalQueryResult> {
         debug!("Executing local query with {} terms", req.query_terms.len());
         let shard = self.shard.read().await;
-        let result = shard.execute_local_query(&req);
+        let result = DistributedQueryEngine::with_defaults().execute_local_query(&shard, &req);
         debug!("Query returned {} results", result.results.len());
         Ok(result)
     }
@@ -206,12 +225,21 @@ impl ShardService for ShardServer {
 
         let mut shard = self.shard.write().await;
         for sig in &signals {
+            let substrate = shard.local_mut().substrate_mut();
+            if let Some(info) = &sig.kind_info {
+                if substrate.signal_kind_info(&sig.signal_type).is_none() {
+                    substrate.register_signal_kind(sig.signal_type.clone(), info.clone());
+                }
+            }
             let local_signal = phago_core::types::Signal {
                 signal_type: sig.signal_type.clone(),
                 intensity: sig.intensity,
-                position: sig.position.clone(),
+                position: sig.position,
                 emitter: sig.emitter,
                 tick: sig.tick,
+                payload_kind: sig.payload_kind.clone(),
+                payload: sig.payload.clone(),
+                sources: 1,
             };
             shard.local_mut().substrate_mut().emit_signal(local_signal);
         }
@@ -219,6 +247,35 @@ impl ShardService for ShardServer {
         debug!("Applied {} signals to substrate", signals.len());
         Ok(())
     }
+
+    #[instrument(skip(self, _ctx))]
+    async fn backlog(self, _ctx: Context, cooldown_ticks: u64) -> RpcResult<ShardBacklog> {
+        let shard = self.shard.read().await;
+        Ok(shard.backlog(cooldown_ticks))
+    }
+
+    #[instrument(skip(self, _ctx))]
+    async fn emigrate_agents(
+        self,
+        _ctx: Context,
+        count: usize,
+        cooldown_ticks: u64,
+    ) -> RpcResult<Vec<MigratableAgent>> {
+        let mut shard = self.shard.write().await;
+        let leaving = shard.emigrate(count, cooldown_ticks);
+        debug!("{} agents leaving", leaving.len());
+        Ok(leaving)
+    }
+
+    #[instrument(skip(self, _ctx, agent), fields(agent_id = %agent.agent_id.0))]
+    async fn receive_agent(self, _ctx: Context, agent: MigratableAgent) -> RpcResult<AgentId> {
+        let mut shard = self.shard.write().await;
+        let id = shard.immigrate(&agent, DIGESTER_MAX_IDLE).ok_or_else(|| {
+            RpcError::Internal(format!("cannot migrate a {} agent", agent.agent_type))
+        })?;
+        debug!("Agent arrived from shard {}", agent.source_shard);
+        Ok(id)
+    }
 }
 
 /// Server implementation for the coordinator.
@@ -275,7 +332,15 @@ impl CoordinatorServer {
             .map(server::BaseChannel::with_defaults)
             .for_each_concurrent(10, |channel| {
                 let server = self.clone();
-                async move { channel.execute(server.serve()).for_each(|_| async {}).await }
+                // Each item is an in-flight request that must be driven to
+                // send its response; they run on this task because shard
+                // state is not `Send`.
+                async move {
+                    channel
+                        .execute(server.serve())
+                        .for_each_concurrent(None, |response| response)
+                        .await
+                }
             })
             .await;
 
@@ -299,6 +364,25 @@ impl CoordinatorService for CoordinatorServer {
         }
     }
 
+    #[instrument(skip(self, _ctx), fields(shard_id = ?info.id, address = %info.address))]
+    async fn rejoin(self, _ctx: Context, info: ShardInfo) -> RpcResult<u64> {
+        info!("Shard {:?} rejoining", info.id);
+        self.coordinator
+            .rejoin_shard(info)
+            .await
+            .map_err(|e| RpcError::Internal(e.to_string()))
+    }
+
+    #[instrument(skip(self, _ctx, msg), fields(shard_id = ?msg.shard_id))]
+    async fn heartbeat(self, _ctx: Context, msg: HeartbeatMessage) -> HeartbeatResponse {
+        let response = self.coordinator.heartbeat(&msg).await;
+        debug!(
+            "Heartbeat from {:?}: acknowledged={}, epoch={}",
+            msg.shard_id, response.acknowledged, response.epoch
+        );
+        response
+    }
+
     #[instrument(skip(self, _ctx), fields(shard_id = ?shard_id))]
     async fn unregister(self, _ctx: Context, shard_id: ShardId) -> RpcResult<()> {
         info!("Unregistering shard {:?}", shard_id);
@@ -344,6 +428,21 @@ impl CoordinatorService for CoordinatorServer {
         shard
     }
 
+    #[instrument(skip(self, _ctx), fields(doc_id = ?doc_id, replicas = ?replicas))]
+    async fn route_document_replicas(
+        self,
+        _ctx: Context,
+        doc_id: DocumentId,
+        replicas: Option<u32>,
+    ) -> Vec<ShardId> {
+        let shards = match replicas {
+            Some(n) => self.coordinator.get_replica_shards_with(&doc_id, n).await,
+            None => self.coordinator.get_replica_shards(&doc_id).await,
+        };
+        debug!("Document {:?} routed to shards {:?}", doc_id, shards);
+        shards
+    }
+
     #[instrument(skip(self, _ctx), fields(node_id = ?node_id))]
     async fn route_node(self, _ctx: Context, node_id: NodeId) -> ShardId {
         // Use the hash ring to route based on node ID
@@ -465,9 +564,9 @@ mod tests {
     use phago_core::types::Position;
     use phago_runtime::colony::ColonyConfig;
 
-    fn create_test_shard() -> Arc<RwLock<ShardedColony>> {
+    fn create_test_shard() -> Rc<RwLock<ShardedColony>> {
         let hash_ring = Arc::new(RwLock::new(ConsistentHashRing::new(3)));
-        Arc::new(RwLock::new(ShardedColony::new(
+        Rc::new(RwLock::new(ShardedColony::new(
             ShardId::new(0),
             ColonyConfig::default(),
             hash_ring,
@@ -501,6 +600,8 @@ mod tests {
             content: "Test content".to_string(),
             position: Position::new(0.0, 0.0),
             digested: false,
+            tags: Vec::new(),
+            version: 1,
         };
 
         let ctx = tarpc::context::current();
@@ -608,6 +709,9 @@ mod tests {
             emitter: phago_core::types::AgentId::from_seed(1),
             tick: 0,
             source_shard: ShardId::new(1),
+            payload_kind: None,
+            payload: Vec::new(),
+            kind_info: None,
         }];
 
         let ctx = tarpc::context::current();
@@ -616,6 +720,76 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_shard_server_receive_custom_signals() {
+        use phago_core::signal::SignalKindInfo;
+        use phago_core::types::SignalType;
+
+        let shard = create_test_shard();
+        let server = ShardServer::new(shard.clone());
+
+        let review = SignalType::custom("review_request");
+        let signals = vec![CrossShardSignal {
+            signal_type: review.clone(),
+            intensity: 0.5,
+            position: Position::new(0.0, 0.0),
+            emitter: phago_core::types::AgentId::from_seed(1),
+            tick: 0,
+            source_shard: ShardId::new(1),
+            payload_kind: None,
+            payload: Vec::new(),
+            kind_info: Some(SignalKindInfo::new("Request for review").with_decay_rate(0.2)),
+        }];
+
+        let ctx = tarpc::context::current();
+        server.receive_signals(ctx, signals).await.unwrap();
+
+        let shard = shard.read().await;
+        let substrate = shard.local().substrate();
+        assert_eq!(substrate.all_signals()[0].signal_type, review);
+        assert_eq!(
+            substrate
+                .signal_kind_info(&review)
+                .and_then(|info| info.decay_rate),
+            Some(0.2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shard_server_receive_agent() {
+        let shard = create_test_shard();
+        let server = ShardServer::new(shard.clone());
+
+        let migrant = MigratableAgent {
+            agent_id: phago_core::types::AgentId::from_seed(7),
+            agent_type: "digester".to_string(),
+            age: 12,
+            vocabulary: None,
+            source_shard: ShardId::new(1),
+        };
+        let id = server
+            .clone()
+            .receive_agent(tarpc::context::current(), migrant.clone())
+            .await
+            .unwrap();
+        assert_eq!(id, migrant.agent_id);
+
+        {
+            let s = shard.read().await;
+            let agent = &s.local().agents()[0];
+            assert_eq!((agent.id(), agent.age()), (id, 12));
+        }
+
+        let sentinel = MigratableAgent {
+            agent_type: "sentinel".to_string(),
+            ..migrant
+        };
+        let result = server
+            .receive_agent(tarpc::context::current(), sentinel)
+            .await;
+        assert!(matches!(result, Err(RpcError::Internal(_))));
+    }
+
     #[tokio::test]
     async fn test_coordinator_server_register() {
         let coordinator = create_test_coordinator();
@@ -682,6 +856,38 @@ mod tests {
         assert_eq!(shard1, shard2);
     }
 
+    #[tokio::test]
+    async fn test_coordinator_server_route_document_replicas() {
+        let coordinator = create_test_coordinator();
+        let server = CoordinatorServer::new(coordinator);
+
+        let doc_id = DocumentId::from_seed(42);
+
+        let ctx = tarpc::context::current();
+        let primary = server.clone().route_document(ctx, doc_id).await;
+
+        let ctx = tarpc::context::current();
+        let single = server
+            .clone()
+            .route_document_replicas(ctx, doc_id, Some(0))
+            .await;
+        assert_eq!(single, vec![primary]);
+
+        // The default replication factor of 2 puts a copy on every shard
+        let ctx = tarpc::context::current();
+        let all = server
+            .clone()
+            .route_document_replicas(ctx, doc_id, None)
+            .await;
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0], primary);
+
+        // More replicas than shards is capped at the ring size
+        let ctx = tarpc::context::current();
+        let capped = server.route_document_replicas(ctx, doc_id, Some(10)).await;
+        assert_eq!(capped.len(), 3);
+    }
+
     #[tokio::test]
     async fn test_coordinator_server_current_tick() {
         let coordinator = create_test_coordinator();
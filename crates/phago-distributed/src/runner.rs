@@ -4,8 +4,13 @@
 //! phase synchronization and cross-shard edge resolution.
 
 use crate::coordinator::Coordinator;
+use crate::query::DistributedQueryEngine;
 use crate::shard::ShardedColony;
 use crate::types::*;
+use phago_core::types::Tick;
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -18,6 +23,13 @@ pub struct RunnerConfig {
     pub resolve_ghosts: bool,
     /// Maximum parallel operations.
     pub max_parallelism: usize,
+    /// Move idle agents toward shards with undigested documents. `None`
+    /// keeps every agent on the shard it was spawned on.
+    pub migration: Option<MigrationPolicy>,
+    /// Pipeline ticks in [`DistributedRunner::run`]: each shard starts
+    /// sensing the next tick as soon as it has finished its own, and only
+    /// the Act phase is barriered. `false` barriers every phase.
+    pub pipelined: bool,
 }
 
 impl Default for RunnerConfig {
@@ -26,6 +38,8 @@ impl Default for RunnerConfig {
             phase_timeout_ms: 30_000,
             resolve_ghosts: true,
             max_parallelism: 8,
+            migration: None,
+            pipelined: false,
         }
     }
 }
@@ -48,7 +62,23 @@ impl Default for RunnerConfig {
 /// 4. **Advance Phase**: Coordinator advances the global tick counter
 ///
 /// After the Act phase, any cross-shard edges are collected and ghost nodes
-/// are resolved if configured.
+/// are resolved if configured. With a [`MigrationPolicy`] set, idle agents
+/// are moved between shards after the Decay phase every
+/// `interval_ticks` ticks.
+///
+/// # Pipelining
+///
+/// With [`RunnerConfig::pipelined`] set, [`run`](Self::run) stops holding
+/// every shard at every phase. Each shard moves through Sense, Act and
+/// Decay on its own, and waits only before acting on a tick until every
+/// shard has acted on the previous one, since Act results feed cross-shard
+/// edge resolution. A fast shard can therefore decay tick N and sense tick
+/// N+1 while a slow one is still acting on tick N. Ghost updates resolved
+/// from tick N are held by shards that have not reached it, and
+/// [`query_snapshot`](Self::query_snapshot) waits for shards to line up on
+/// one tick. Ticks on which a migration is due end a pipelined stretch, so
+/// agents only move while every shard is idle. [`tick`](Self::tick)
+/// always runs barriered.
 ///
 /// # Example
 ///
@@ -66,7 +96,7 @@ impl Default for RunnerConfig {
 /// ```
 pub struct DistributedRunner {
     coordinator: Arc<Coordinator>,
-    shards: Vec<Arc<RwLock<ShardedColony>>>,
+    shards: Vec<Rc<RwLock<ShardedColony>>>,
     config: RunnerConfig,
 }
 
@@ -76,11 +106,11 @@ impl DistributedRunner {
     /// # Arguments
     ///
     /// * `coordinator` - The coordinator for global synchronization
-    /// * `shards` - Vector of shard instances wrapped in Arc<RwLock<_>>
+    /// * `shards` - Vector of shard instances wrapped in Rc<RwLock<_>>
     /// * `config` - Runner configuration
     pub fn new(
         coordinator: Arc<Coordinator>,
-        shards: Vec<Arc<RwLock<ShardedColony>>>,
+        shards: Vec<Rc<RwLock<ShardedColony>>>,
         config: RunnerConfig,
     ) -> Self {
         Self {
@@ -125,23 +155,30 @@ impl DistributedRunner {
         let decay_results = self.run_phase(TickPhase::Decay, tick).await?;
         phase_results.extend(decay_results);
 
+        let migrations = self.due_migrations(tick).await;
+
         // Phase 4: Advance
         let new_tick = self.coordinator.advance_tick().await;
 
         // Resolve ghost nodes if configured
         if self.config.resolve_ghosts && !all_cross_edges.is_empty() {
-            self.resolve_cross_shard_edges(&all_cross_edges).await?;
+            self.resolve_cross_shard_edges(&all_cross_edges, tick)
+                .await?;
         }
 
         Ok(DistributedTickResult {
             tick: new_tick,
             phase_results,
             cross_shard_edges: all_cross_edges,
+            migrations,
         })
     }
 
     /// Run multiple ticks.
     ///
+    /// Ticks are pipelined if the configuration asks for it, and barriered
+    /// one by one otherwise.
+    ///
     /// # Arguments
     ///
     /// * `num_ticks` - Number of ticks to execute
@@ -151,12 +188,154 @@ impl DistributedRunner {
     /// A vector of `DistributedTickResult` for each tick executed.
     pub async fn run(&self, num_ticks: u64) -> DistributedResult<Vec<DistributedTickResult>> {
         let mut results = Vec::with_capacity(num_ticks as usize);
+        if self.config.pipelined {
+            let end = self.coordinator.current_tick() + num_ticks;
+            while self.coordinator.current_tick() < end {
+                let start = self.coordinator.current_tick();
+                // Stop after the next migration tick, when shards must be idle
+                let stop = match &self.config.migration {
+                    Some(policy) => start.next_multiple_of(policy.interval_ticks.max(1)) + 1,
+                    None => end,
+                };
+                results.extend(self.run_pipelined(start..stop.min(end)).await?);
+            }
+            return Ok(results);
+        }
         for _ in 0..num_ticks {
             results.push(self.tick().await?);
         }
         Ok(results)
     }
 
+    /// Run a query against every shard at one consistent tick.
+    ///
+    /// While a pipelined run has shards spread over neighbouring ticks,
+    /// the query waits until every shard has settled on the same tick, so
+    /// global document frequencies are never aggregated from graphs of
+    /// different ticks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DistributedError::PhaseTimeout` if the shards do not line
+    /// up before the barrier times out.
+    pub async fn query_snapshot(
+        &self,
+        engine: &DistributedQueryEngine,
+        query_text: &str,
+    ) -> DistributedResult<QuerySnapshot> {
+        use futures::future::join_all;
+
+        loop {
+            let guards = join_all(self.shards.iter().map(|shard| shard.read())).await;
+            let settled: Vec<_> = guards.iter().map(|s| s.settled_tick()).collect();
+            let tick = match settled.first() {
+                None => Some(self.coordinator.current_tick()),
+                Some(first) => first.filter(|_| settled.iter().all(|t| t == first)),
+            };
+            if let Some(tick) = tick {
+                let refs: Vec<&ShardedColony> = guards.iter().map(|g| &**g).collect();
+                return Ok(QuerySnapshot {
+                    tick,
+                    results: engine.distributed_query(&refs, query_text),
+                });
+            }
+
+            let target = guards.iter().filter_map(|s| s.reached_tick()).max();
+            drop(guards);
+            self.coordinator
+                .wait_for_epoch(TickPhase::Decay, target.unwrap_or(0))
+                .await?;
+        }
+    }
+
+    /// Run `ticks` with pipelining.
+    ///
+    /// Every shard works through the ticks at its own pace while the
+    /// coordinator follows behind, resolving each tick's cross-shard edges
+    /// once every shard has acted on it and advancing the global tick once
+    /// every shard has decayed it.
+    async fn run_pipelined(
+        &self,
+        ticks: Range<Tick>,
+    ) -> DistributedResult<Vec<DistributedTickResult>> {
+        use futures::future::{try_join, try_join_all};
+
+        let phase_results = RefCell::new(vec![Vec::new(); (ticks.end - ticks.start) as usize]);
+        let shards = try_join_all(
+            self.shards
+                .iter()
+                .map(|shard| self.pipeline_shard(shard, ticks.clone(), &phase_results)),
+        );
+        let (_, results) = try_join(shards, self.follow_pipeline(ticks, &phase_results)).await?;
+        Ok(results)
+    }
+
+    /// Drive one shard through `ticks`, barriered only before acting.
+    async fn pipeline_shard(
+        &self,
+        shard: &Rc<RwLock<ShardedColony>>,
+        ticks: Range<Tick>,
+        phase_results: &RefCell<Vec<Vec<PhaseResult>>>,
+    ) -> DistributedResult<()> {
+        for tick in ticks.clone() {
+            for phase in [TickPhase::Sense, TickPhase::Act, TickPhase::Decay] {
+                if phase == TickPhase::Act && tick > ticks.start {
+                    self.coordinator
+                        .wait_for_epoch(TickPhase::Act, tick - 1)
+                        .await?;
+                }
+                let result = shard.write().await.tick_phase_at(phase, tick);
+                let shard_id = result.shard_id;
+                phase_results.borrow_mut()[(tick - ticks.start) as usize].push(result);
+                self.coordinator
+                    .phase_complete(shard_id, phase, tick)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve edges and advance the global tick behind pipelined shards.
+    async fn follow_pipeline(
+        &self,
+        ticks: Range<Tick>,
+        phase_results: &RefCell<Vec<Vec<PhaseResult>>>,
+    ) -> DistributedResult<Vec<DistributedTickResult>> {
+        let mut results = Vec::with_capacity((ticks.end - ticks.start) as usize);
+        for tick in ticks.clone() {
+            let slot = (tick - ticks.start) as usize;
+
+            self.coordinator
+                .wait_for_epoch(TickPhase::Act, tick)
+                .await?;
+            let cross_shard_edges: Vec<_> = phase_results.borrow()[slot]
+                .iter()
+                .filter(|r| r.phase == TickPhase::Act)
+                .flat_map(|r| r.cross_shard_edges.iter().cloned())
+                .collect();
+            if self.config.resolve_ghosts && !cross_shard_edges.is_empty() {
+                self.resolve_cross_shard_edges(&cross_shard_edges, tick)
+                    .await?;
+            }
+
+            self.coordinator
+                .wait_for_epoch(TickPhase::Decay, tick)
+                .await?;
+            // Only the last tick of a stretch can be due, and by the time it
+            // has been decayed every shard is done
+            let migrations = self.due_migrations(tick).await;
+            let new_tick = self.coordinator.advance_tick().await;
+
+            results.push(DistributedTickResult {
+                tick: new_tick,
+                phase_results: std::mem::take(&mut phase_results.borrow_mut()[slot]),
+                cross_shard_edges,
+                migrations,
+            });
+        }
+        Ok(results)
+    }
+
     /// Execute a single phase across all shards.
     ///
     /// Runs the specified phase on all shards in parallel, then waits
@@ -172,7 +351,7 @@ impl DistributedRunner {
                 let shard = shard.clone();
                 async move {
                     let mut s = shard.write().await;
-                    s.tick_phase(phase)
+                    s.tick_phase_at(phase, tick)
                 }
             })
             .collect();
@@ -192,11 +371,63 @@ impl DistributedRunner {
         Ok(results)
     }
 
+    /// Carry out migrations if the policy has one due on `tick`.
+    async fn due_migrations(&self, tick: Tick) -> Vec<AgentMigration> {
+        match &self.config.migration {
+            Some(policy) if tick.is_multiple_of(policy.interval_ticks.max(1)) => {
+                self.migrate(policy, tick).await
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Collect backlogs, let the coordinator plan migrations, and move the
+    /// agents it asks for.
+    async fn migrate(&self, policy: &MigrationPolicy, tick: u64) -> Vec<AgentMigration> {
+        let mut backlogs = Vec::with_capacity(self.shards.len());
+        let mut by_id = std::collections::HashMap::new();
+        for shard in &self.shards {
+            let backlog = shard.read().await.backlog(policy.cooldown_ticks);
+            by_id.insert(backlog.shard_id, shard);
+            backlogs.push(backlog);
+        }
+
+        let mut migrations = Vec::new();
+        for command in self.coordinator.plan_migrations(&backlogs, policy) {
+            let (Some(source), Some(target)) = (by_id.get(&command.from), by_id.get(&command.to))
+            else {
+                continue;
+            };
+            let leaving = source
+                .write()
+                .await
+                .emigrate(command.count, policy.cooldown_ticks);
+            let mut target = target.write().await;
+            for agent in leaving {
+                if target.immigrate(&agent, policy.max_idle_ticks).is_some() {
+                    migrations.push(AgentMigration {
+                        agent_id: agent.agent_id,
+                        from_shard: command.from,
+                        to_shard: command.to,
+                        tick,
+                    });
+                }
+            }
+        }
+        self.coordinator.record_migrations(migrations.len() as u64);
+        migrations
+    }
+
     /// Resolve cross-shard edges by fetching ghost nodes.
     ///
     /// For each cross-shard edge, fetches the target node's data from
-    /// the owning shard and caches it as a ghost node in the requesting shard.
-    async fn resolve_cross_shard_edges(&self, edges: &[CrossShardEdge]) -> DistributedResult<()> {
+    /// the owning shard and caches it as a ghost node in the requesting
+    /// shard, once that shard has reached `tick`.
+    async fn resolve_cross_shard_edges(
+        &self,
+        edges: &[CrossShardEdge],
+        tick: Tick,
+    ) -> DistributedResult<()> {
         use std::collections::HashMap;
 
         // Group edges by target shard
@@ -229,7 +460,7 @@ impl DistributedRunner {
                                             shard_id,
                                             node_data.label.clone(),
                                         );
-                                        req.ghost_cache_mut().insert(ghost);
+                                        req.queue_ghost_update(tick, ghost);
                                     }
                                 }
                             }
@@ -243,6 +474,39 @@ impl DistributedRunner {
         Ok(())
     }
 
+    /// Send a heartbeat for every shard and rejoin where required.
+    ///
+    /// Shards that the coordinator does not recognize, or whose recorded
+    /// coordinator epoch is stale, re-register under their existing IDs and
+    /// synchronize their local tick to the coordinator's.
+    ///
+    /// # Returns
+    ///
+    /// The number of shards that rejoined.
+    pub async fn heartbeat(&self) -> DistributedResult<usize> {
+        let mut rejoined = 0;
+        for shard in &self.shards {
+            let mut s = shard.write().await;
+            let response = self.coordinator.heartbeat(&s.heartbeat_message()).await;
+            if s.needs_rejoin(&response) {
+                let info = s.shard_info(self.shard_address(&s).await);
+                let tick = self.coordinator.rejoin_shard(info).await?;
+                s.mark_registered(self.coordinator.epoch(), tick);
+                rejoined += 1;
+            }
+        }
+        Ok(rejoined)
+    }
+
+    /// Address a shard was registered under, if the coordinator knows it.
+    async fn shard_address(&self, shard: &ShardedColony) -> String {
+        self.coordinator
+            .get_shard(shard.shard_id())
+            .await
+            .map(|info| info.address)
+            .unwrap_or_else(|| format!("local:{}", shard.shard_id().as_u32()))
+    }
+
     /// Get the coordinator.
     pub fn coordinator(&self) -> &Arc<Coordinator> {
         &self.coordinator
@@ -254,7 +518,7 @@ impl DistributedRunner {
     }
 
     /// Get a reference to all shards.
-    pub fn shards(&self) -> &[Arc<RwLock<ShardedColony>>] {
+    pub fn shards(&self) -> &[Rc<RwLock<ShardedColony>>] {
         &self.shards
     }
 
@@ -264,6 +528,15 @@ impl DistributedRunner {
     }
 }
 
+/// Query results tagged with the tick they were read at.
+#[derive(Debug, Clone)]
+pub struct QuerySnapshot {
+    /// Number of ticks every shard had completed when it was read.
+    pub tick: Tick,
+    /// The merged, scored results.
+    pub results: Vec<ScoredNode>,
+}
+
 /// Result of a distributed tick.
 #[derive(Debug, Clone)]
 pub struct DistributedTickResult {
@@ -273,6 +546,8 @@ pub struct DistributedTickResult {
     pub phase_results: Vec<PhaseResult>,
     /// Cross-shard edges created this tick.
     pub cross_shard_edges: Vec<CrossShardEdge>,
+    /// Agents moved between shards this tick.
+    pub migrations: Vec<AgentMigration>,
 }
 
 impl DistributedTickResult {
@@ -309,13 +584,13 @@ mod tests {
     use crate::hashing::ConsistentHashRing;
     use phago_runtime::colony::ColonyConfig;
 
-    fn create_test_cluster(num_shards: u32) -> (Arc<Coordinator>, Vec<Arc<RwLock<ShardedColony>>>) {
+    fn create_test_cluster(num_shards: u32) -> (Arc<Coordinator>, Vec<Rc<RwLock<ShardedColony>>>) {
         let coordinator = Arc::new(Coordinator::new(num_shards));
         let hash_ring = Arc::new(RwLock::new(ConsistentHashRing::new(num_shards)));
 
         let shards: Vec<_> = (0..num_shards)
             .map(|i| {
-                Arc::new(RwLock::new(ShardedColony::new(
+                Rc::new(RwLock::new(ShardedColony::new(
                     ShardId::new(i),
                     ColonyConfig::default(),
                     hash_ring.clone(),
@@ -377,6 +652,8 @@ mod tests {
             phase_timeout_ms: 5_000,
             resolve_ghosts: false,
             max_parallelism: 4,
+            migration: None,
+            pipelined: false,
         };
 
         let (coordinator, shards) = create_test_cluster(2);
@@ -390,11 +667,7 @@ mod tests {
     #[tokio::test]
     async fn test_concurrent_ticks() {
         let (coordinator, shards) = create_test_cluster(4);
-        let runner = Arc::new(DistributedRunner::new(
-            coordinator,
-            shards,
-            RunnerConfig::default(),
-        ));
+        let runner = DistributedRunner::new(coordinator, shards, RunnerConfig::default());
 
         // Run 10 sequential ticks (concurrent tick execution would require
         // additional synchronization which the runner doesn't currently support)
@@ -405,4 +678,61 @@ mod tests {
             assert_eq!(result.tick, (i + 1) as u64);
         }
     }
+
+    #[tokio::test]
+    async fn test_query_snapshot_waits_for_shards_to_line_up() {
+        let (coordinator, shards) = create_test_cluster(2);
+        let runner =
+            DistributedRunner::new(coordinator.clone(), shards.clone(), RunnerConfig::default());
+
+        // Shard 1 has acted on tick 0 but not decayed it yet
+        for (i, shard) in shards.iter().enumerate() {
+            let mut s = shard.write().await;
+            for phase in [TickPhase::Sense, TickPhase::Act, TickPhase::Decay] {
+                if i == 1 && phase == TickPhase::Decay {
+                    break;
+                }
+                s.tick_phase_at(phase, 0);
+                coordinator
+                    .phase_complete(s.shard_id(), phase, 0)
+                    .await
+                    .unwrap();
+            }
+        }
+        assert_eq!(shards[1].read().await.settled_tick(), None);
+
+        let engine = DistributedQueryEngine::with_defaults();
+        let decay = async {
+            tokio::task::yield_now().await;
+            let mut s = shards[1].write().await;
+            s.tick_phase_at(TickPhase::Decay, 0);
+            coordinator
+                .phase_complete(s.shard_id(), TickPhase::Decay, 0)
+                .await
+                .unwrap();
+        };
+        let (snapshot, ()) = tokio::join!(runner.query_snapshot(&engine, "cell"), decay);
+        assert_eq!(snapshot.unwrap().tick, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pipelined_run_reports_every_phase() {
+        let (coordinator, shards) = create_test_cluster(3);
+        let config = RunnerConfig {
+            pipelined: true,
+            ..Default::default()
+        };
+        let runner = DistributedRunner::new(coordinator, shards, config);
+
+        let results = runner.run(4).await.unwrap();
+        assert_eq!(results.len(), 4);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result.tick, (i + 1) as u64);
+            for phase in [TickPhase::Sense, TickPhase::Act, TickPhase::Decay] {
+                let shards = result.phase_results.iter().filter(|r| r.phase == phase);
+                assert_eq!(shards.count(), 3);
+            }
+        }
+        assert_eq!(runner.coordinator().current_tick(), 4);
+    }
 }
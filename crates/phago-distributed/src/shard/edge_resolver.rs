@@ -117,7 +117,7 @@ impl CrossShardEdgeManager {
     pub fn add_outgoing_edge(&mut self, edge: CrossShardEdge) {
         self.outgoing_edges
             .entry(edge.from_node)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(edge.clone());
         self.pending_resolution.push(edge);
     }
@@ -146,7 +146,7 @@ impl CrossShardEdgeManager {
     pub fn add_incoming_edge(&mut self, edge: CrossShardEdge) {
         self.incoming_edges
             .entry(edge.to_node)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(edge);
     }
 
@@ -222,14 +222,14 @@ impl CrossShardEdgeManager {
     pub fn has_outgoing(&self, node_id: &NodeId) -> bool {
         self.outgoing_edges
             .get(node_id)
-            .map_or(false, |v| !v.is_empty())
+            .is_some_and(|v| !v.is_empty())
     }
 
     /// Check if a node has incoming cross-shard edges.
     pub fn has_incoming(&self, node_id: &NodeId) -> bool {
         self.incoming_edges
             .get(node_id)
-            .map_or(false, |v| !v.is_empty())
+            .is_some_and(|v| !v.is_empty())
     }
 
     /// Remove edges to/from a specific shard.
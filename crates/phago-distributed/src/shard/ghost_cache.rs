@@ -88,8 +88,8 @@ impl GhostNodeCache {
         let id = ghost.node_id;
 
         // If already in cache, just update it
-        if self.cache.contains_key(&id) {
-            self.cache.insert(id, ghost);
+        if let std::collections::hash_map::Entry::Occupied(mut e) = self.cache.entry(id) {
+            e.insert(ghost);
             // Update access order
             self.access_order.retain(|x| *x != id);
             self.access_order.push(id);
@@ -6,6 +6,7 @@
 //! - Document routing via consistent hash ring
 //! - Ghost node cache for cross-shard references
 //! - Tick phase coordination with the coordinator
+//! - Agent migration to and from other shards
 //!
 //! # Architecture
 //!
@@ -44,10 +45,13 @@ pub use edge_resolver::{CrossShardEdgeManager, CrossShardEdgeStats};
 pub use ghost_cache::{GhostCacheStats, GhostNodeCache};
 
 use crate::hashing::ConsistentHashRing;
+use crate::rpc::messages::{HeartbeatMessage, HeartbeatResponse};
 use crate::types::*;
+use phago_agents::digester::Digester;
+use phago_agents::serialize::{SerializableAgent, SerializedAgent};
+use phago_core::agent::Agent;
 use phago_core::substrate::Substrate;
-use phago_core::topology::TopologyGraph;
-use phago_core::types::{DocumentId, NodeData, NodeId, Position, Tick};
+use phago_core::types::{AgentId, CellHealth, DocumentId, NodeData, NodeId, Position, Tick};
 use phago_runtime::colony::{Colony, ColonyConfig, ColonyStats};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -78,6 +82,14 @@ pub struct ShardedColony {
     peers: HashMap<ShardId, String>,
     /// Pending cross-shard edges to resolve.
     pending_cross_edges: Vec<CrossShardEdge>,
+    /// Epoch of the coordinator this shard last registered with.
+    coordinator_epoch: Option<u64>,
+    /// Tick at which each migrated-in agent arrived.
+    arrivals: HashMap<AgentId, Tick>,
+    /// Distributed tick and phase this shard last completed.
+    position: Option<(Tick, TickPhase)>,
+    /// Ghost updates from ticks this shard has not reached yet.
+    pending_ghosts: Vec<(Tick, GhostNode)>,
 }
 
 impl ShardedColony {
@@ -111,6 +123,10 @@ impl ShardedColony {
             hash_ring,
             peers: HashMap::new(),
             pending_cross_edges: Vec::new(),
+            coordinator_epoch: None,
+            arrivals: HashMap::new(),
+            position: None,
+            pending_ghosts: Vec::new(),
         }
     }
 
@@ -136,6 +152,10 @@ impl ShardedColony {
             hash_ring,
             peers: HashMap::new(),
             pending_cross_edges: Vec::new(),
+            coordinator_epoch: None,
+            arrivals: HashMap::new(),
+            position: None,
+            pending_ghosts: Vec::new(),
         }
     }
 
@@ -276,6 +296,59 @@ impl ShardedColony {
         }
     }
 
+    /// Execute phase `phase` of distributed tick `tick`.
+    ///
+    /// Like [`tick_phase`](Self::tick_phase), but records how far this shard
+    /// has got, so that ghost updates from later ticks are held back and
+    /// query snapshots can tell whether the shard is between ticks.
+    pub fn tick_phase_at(&mut self, phase: TickPhase, tick: Tick) -> PhaseResult {
+        let result = self.tick_phase(phase);
+        self.position = Some((tick, phase));
+        self.apply_due_ghost_updates();
+        result
+    }
+
+    /// The latest distributed tick this shard has run a phase of.
+    pub fn reached_tick(&self) -> Option<Tick> {
+        self.position.map(|(tick, _)| tick)
+    }
+
+    /// Number of distributed ticks this shard has completed.
+    ///
+    /// `None` while the shard is between the Act and Decay phases of a
+    /// tick, when its graph belongs to no tick in particular.
+    pub fn settled_tick(&self) -> Option<Tick> {
+        match self.position {
+            None => Some(0),
+            Some((tick, TickPhase::Sense)) => Some(tick),
+            Some((_, TickPhase::Act)) => None,
+            Some((tick, TickPhase::Decay | TickPhase::Advance)) => Some(tick + 1),
+        }
+    }
+
+    /// Cache a ghost node resolved during distributed tick `tick`.
+    ///
+    /// Updates from a tick this shard has not reached yet are held until
+    /// it gets there.
+    pub fn queue_ghost_update(&mut self, tick: Tick, ghost: GhostNode) {
+        if self.reached_tick().is_some_and(|reached| reached >= tick) {
+            self.ghost_cache.insert(ghost);
+        } else {
+            self.pending_ghosts.push((tick, ghost));
+        }
+    }
+
+    /// Number of ghost updates waiting for this shard to reach their tick.
+    pub fn pending_ghost_updates(&self) -> usize {
+        self.pending_ghosts.len()
+    }
+
+    fn apply_due_ghost_updates(&mut self) {
+        for (tick, ghost) in std::mem::take(&mut self.pending_ghosts) {
+            self.queue_ghost_update(tick, ghost);
+        }
+    }
+
     /// Get local term frequencies for TF-IDF computation.
     ///
     /// This is called by the coordinator to aggregate document frequencies
@@ -287,13 +360,28 @@ impl ShardedColony {
     ///
     /// # Returns
     ///
-    /// A map of term to document frequency on this shard.
+    /// A map of term to document frequency on this shard: the number of
+    /// nodes whose label contains the term as a word. Read from the term
+    /// index when the colony keeps one, counted from the labels otherwise.
     pub fn get_term_frequencies(&self, terms: &[String]) -> HashMap<String, u64> {
         let mut freqs = HashMap::new();
-        let graph = self.local.substrate().graph();
+        let substrate = self.local.substrate();
+        let graph = substrate.graph();
+        let tokenizer = self.local.tokenizer();
+        let index = substrate
+            .term_index()
+            .filter(|index| index.tokenizer() == tokenizer);
 
         for term in terms {
-            let count = graph.find_nodes_by_label(term).len();
+            let count = match index {
+                Some(index) => index.document_frequency(term),
+                None => graph
+                    .all_nodes()
+                    .iter()
+                    .filter_map(|id| graph.get_node(id))
+                    .filter(|node| tokenizer.words(&node.label).any(|w| w == *term))
+                    .count(),
+            };
             if count > 0 {
                 freqs.insert(term.clone(), count as u64);
             }
@@ -519,6 +607,70 @@ impl ShardedColony {
         self.local.run(ticks);
     }
 
+    /// Advance the local tick counter until it reaches `tick`.
+    ///
+    /// Used to resynchronize with the coordinator after a failover. Ticks
+    /// never run backwards, so a shard that is already ahead is unchanged.
+    pub fn sync_tick(&mut self, tick: Tick) {
+        while self.current_tick() < tick {
+            self.local.substrate_mut().advance_tick();
+        }
+        if let Some(last) = tick.checked_sub(1) {
+            if self.reached_tick().is_none_or(|reached| reached < last) {
+                self.position = Some((last, TickPhase::Decay));
+                self.apply_due_ghost_updates();
+            }
+        }
+    }
+
+    /// Build a heartbeat message describing this shard.
+    pub fn heartbeat_message(&self) -> HeartbeatMessage {
+        let stats = self.local.stats();
+        HeartbeatMessage {
+            shard_id: self.shard_id,
+            current_tick: self.current_tick(),
+            agent_count: stats.agents_alive as u64,
+            document_count: stats.documents_total as u64,
+            node_count: stats.graph_nodes as u64,
+            memory_bytes: 0,
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        }
+    }
+
+    /// Inspect a heartbeat response and report whether to re-register.
+    ///
+    /// A shard must rejoin when the coordinator does not know it or when
+    /// the coordinator's epoch differs from the one it registered with
+    /// (i.e. the coordinator restarted). The first epoch seen is adopted.
+    pub fn needs_rejoin(&mut self, response: &HeartbeatResponse) -> bool {
+        if !response.acknowledged {
+            return true;
+        }
+        match self.coordinator_epoch {
+            Some(epoch) => epoch != response.epoch,
+            None => {
+                self.coordinator_epoch = Some(response.epoch);
+                false
+            }
+        }
+    }
+
+    /// Record a successful (re-)registration with a coordinator.
+    ///
+    /// Adopts the coordinator's epoch and synchronizes the local tick.
+    pub fn mark_registered(&mut self, epoch: u64, tick: Tick) {
+        self.coordinator_epoch = Some(epoch);
+        self.sync_tick(tick);
+    }
+
+    /// Get the epoch of the coordinator this shard is registered with.
+    pub fn coordinator_epoch(&self) -> Option<u64> {
+        self.coordinator_epoch
+    }
+
     /// Get shard info for registration with coordinator.
     pub fn shard_info(&self, address: String) -> ShardInfo {
         let stats = self.local.stats();
@@ -532,6 +684,130 @@ impl ShardedColony {
         }
     }
 
+    /// Digestion backlog of this shard, for migration planning.
+    ///
+    /// Agents that arrived less than `cooldown_ticks` ago are not counted
+    /// as migratable.
+    pub fn backlog(&self, cooldown_ticks: u64) -> ShardBacklog {
+        ShardBacklog {
+            shard_id: self.shard_id,
+            undigested_documents: self
+                .local
+                .substrate()
+                .all_documents()
+                .iter()
+                .filter(|d| !d.digested)
+                .count(),
+            digesters_alive: self
+                .local
+                .agents()
+                .iter()
+                .filter(|a| a.agent_type() == "digester")
+                .count(),
+            migratable: self.migratable_agents(cooldown_ticks).len(),
+        }
+    }
+
+    /// Remove up to `count` migratable agents so they can be respawned on
+    /// another shard.
+    ///
+    /// The agents leave without dying: no death is recorded and nothing is
+    /// deposited here.
+    pub fn emigrate(&mut self, count: usize, cooldown_ticks: u64) -> Vec<MigratableAgent> {
+        let mut leaving = Vec::new();
+        for id in self
+            .migratable_agents(cooldown_ticks)
+            .into_iter()
+            .take(count)
+        {
+            let Some(agent) = self.local.take_agent(&id) else {
+                continue;
+            };
+            self.arrivals.remove(&id);
+            leaving.push(MigratableAgent {
+                agent_id: id,
+                agent_type: agent.agent_type().to_string(),
+                age: agent.age(),
+                vocabulary: agent.export_vocabulary(),
+                source_shard: self.shard_id,
+            });
+        }
+        leaving
+    }
+
+    /// Respawn an agent that left another shard, next to the undigested
+    /// document farthest from any local agent.
+    ///
+    /// The agent keeps its ID, age, and vocabulary; it starts with a fresh
+    /// idle count and `max_idle_ticks` as its idle limit. Returns `None`
+    /// for agent types that cannot migrate.
+    pub fn immigrate(&mut self, agent: &MigratableAgent, max_idle_ticks: u64) -> Option<AgentId> {
+        if agent.agent_type != "digester" {
+            return None;
+        }
+        let position = self
+            .least_covered_undigested()
+            .unwrap_or(Position::new(0.0, 0.0));
+        let mut state = match Digester::new(position)
+            .with_max_idle(max_idle_ticks)
+            .export_state()
+        {
+            SerializedAgent::Digester(state) => state,
+            _ => unreachable!("digesters export digester state"),
+        };
+        state.id = agent.agent_id;
+        state.age_ticks = agent.age;
+        let mut digester = Digester::from_state(&SerializedAgent::Digester(state))?;
+        if let Some(vocabulary) = &agent.vocabulary {
+            digester.integrate_vocabulary(vocabulary);
+        }
+
+        let alive: Vec<AgentId> = self.local.agents().iter().map(|a| a.id()).collect();
+        self.arrivals.retain(|id, _| alive.contains(id));
+        self.arrivals.insert(agent.agent_id, self.current_tick());
+        Some(self.local.spawn(Box::new(digester)))
+    }
+
+    /// Digesters that could leave now: idle long enough to be stressed but
+    /// not dying, holding no engulfed document, and not within
+    /// `cooldown_ticks` of arriving here.
+    fn migratable_agents(&self, cooldown_ticks: u64) -> Vec<AgentId> {
+        let now = self.current_tick();
+        self.local
+            .agents()
+            .iter()
+            .filter(|a| a.agent_type() == "digester")
+            .filter(|a| a.profile().health == CellHealth::Stressed)
+            .filter(|a| !a.prepare_death_signal().had_pending_work)
+            .filter(|a| {
+                self.arrivals
+                    .get(&a.id())
+                    .is_none_or(|&arrived| now.saturating_sub(arrived) >= cooldown_ticks)
+            })
+            .map(|a| a.id())
+            .collect()
+    }
+
+    /// Position of the undigested document with the most distance to its
+    /// nearest agent.
+    fn least_covered_undigested(&self) -> Option<Position> {
+        let agents: Vec<Position> = self.local.agents().iter().map(|a| a.position()).collect();
+        self.local
+            .substrate()
+            .all_documents()
+            .into_iter()
+            .filter(|d| !d.digested)
+            .map(|d| {
+                let nearest = agents
+                    .iter()
+                    .map(|p| p.distance_to(&d.position))
+                    .fold(f64::INFINITY, f64::min);
+                (d.position, nearest)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(position, _)| position)
+    }
+
     /// Execute a local query and return scored results.
     ///
     /// # Arguments
@@ -646,6 +922,28 @@ mod tests {
         assert_eq!(result.phase, TickPhase::Act);
     }
 
+    #[test]
+    fn test_ghost_updates_wait_for_their_tick() {
+        let (mut shard, _) = create_test_shard();
+        let ghost = |seed| GhostNode::new(NodeId::from_seed(seed), ShardId::new(1), "g".into());
+
+        shard.tick_phase_at(TickPhase::Sense, 0);
+        shard.queue_ghost_update(0, ghost(1));
+        shard.queue_ghost_update(1, ghost(2));
+        assert_eq!(shard.ghost_cache().len(), 1);
+        assert_eq!(shard.pending_ghost_updates(), 1);
+
+        shard.tick_phase_at(TickPhase::Act, 0);
+        assert_eq!(shard.settled_tick(), None);
+        shard.tick_phase_at(TickPhase::Decay, 0);
+        assert_eq!(shard.settled_tick(), Some(1));
+        assert_eq!(shard.pending_ghost_updates(), 1);
+
+        shard.tick_phase_at(TickPhase::Sense, 1);
+        assert_eq!(shard.pending_ghost_updates(), 0);
+        assert!(shard.ghost_cache().contains(&NodeId::from_seed(2)));
+    }
+
     #[test]
     fn test_health() {
         let (shard, _) = create_test_shard();
@@ -694,6 +992,37 @@ mod tests {
         assert!(freqs.is_empty());
     }
 
+    #[test]
+    fn test_term_frequencies_match_with_and_without_index() {
+        use phago_core::types::NodeType;
+
+        let (mut shard, _) = create_test_shard();
+        for label in ["cell membrane", "cell", "membranes"] {
+            shard.local.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: label.to_string(),
+                node_type: NodeType::Concept,
+                position: Position::new(0.0, 0.0),
+                access_count: 1,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            });
+        }
+        let terms = ["cell".to_string(), "membrane".to_string()];
+
+        // Nodes added behind the colony's back leave the index stale
+        assert!(shard.local.substrate().term_index().is_none());
+        let scanned = shard.get_term_frequencies(&terms);
+        assert!(shard.local.sync_term_index());
+        assert!(shard.local.substrate().term_index().is_some());
+        let indexed = shard.get_term_frequencies(&terms);
+
+        assert_eq!(scanned, indexed);
+        assert_eq!(indexed["cell"], 2);
+        assert_eq!(indexed["membrane"], 1);
+    }
+
     #[test]
     fn test_execute_local_query() {
         let (shard, _) = create_test_shard();
@@ -742,6 +1071,31 @@ mod tests {
         assert_eq!(shard.current_tick(), 6);
     }
 
+    #[test]
+    fn test_needs_rejoin_on_epoch_change() {
+        let (mut shard, _) = create_test_shard();
+        let response = |acknowledged, epoch| HeartbeatResponse {
+            acknowledged,
+            expected_tick: 0,
+            epoch,
+            commands: Vec::new(),
+        };
+
+        assert!(!shard.needs_rejoin(&response(true, 1)));
+        assert_eq!(shard.coordinator_epoch(), Some(1));
+        assert!(!shard.needs_rejoin(&response(true, 1)));
+        assert!(shard.needs_rejoin(&response(true, 2)));
+        assert!(shard.needs_rejoin(&response(false, 1)));
+
+        shard.mark_registered(2, 7);
+        assert_eq!(shard.current_tick(), 7);
+        assert!(!shard.needs_rejoin(&response(true, 2)));
+
+        // Ticks never run backwards
+        shard.sync_tick(3);
+        assert_eq!(shard.current_tick(), 7);
+    }
+
     #[tokio::test]
     async fn test_owns_document() {
         let (shard, _hash_ring) = create_test_shard();
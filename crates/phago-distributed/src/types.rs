@@ -2,9 +2,10 @@
 //!
 //! This module defines the core data structures used across the distributed
 //! system including shard identifiers, tick phases, cross-shard edges,
-//! query requests/results, and ghost nodes for remote references.
+//! query requests/results, ghost nodes for remote references, and agent
+//! migration between shards.
 
-use phago_core::types::{DocumentId, NodeData, NodeId, Tick};
+use phago_core::types::{AgentId, DocumentId, NodeData, NodeId, Tick};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
@@ -138,6 +139,13 @@ pub enum DistributedError {
     #[error("Coordinator unavailable")]
     CoordinatorUnavailable,
 
+    #[error("Could not reach {target} after {attempts} attempt(s): {reason}")]
+    Unreachable {
+        target: String,
+        attempts: u32,
+        reason: String,
+    },
+
     #[error("RPC error: {0}")]
     RpcError(String),
 
@@ -155,6 +163,9 @@ pub enum DistributedError {
 
     #[error("Barrier synchronization failed")]
     BarrierFailed,
+
+    #[error("Coordinator state persistence failed: {0}")]
+    Persistence(String),
 }
 
 /// Result type for distributed operations.
@@ -363,6 +374,95 @@ impl GhostNode {
     }
 }
 
+/// When and how many idle agents move between shards.
+///
+/// Migration is off unless a policy is set on the runner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationPolicy {
+    /// Collect backlogs and migrate every this many ticks.
+    pub interval_ticks: u64,
+    /// Upper bound on agents moved across the whole cluster per round.
+    pub max_migrations_per_tick: usize,
+    /// Undigested documents a shard needs beyond its own digesters before
+    /// it receives agents.
+    pub min_backlog: usize,
+    /// Ticks an agent must stay on a shard it migrated to before it may
+    /// move again, so agents don't ping-pong between shards.
+    pub cooldown_ticks: u64,
+    /// Idle ticks a respawned digester tolerates before dying.
+    pub max_idle_ticks: u64,
+}
+
+impl Default for MigrationPolicy {
+    fn default() -> Self {
+        Self {
+            interval_ticks: 5,
+            max_migrations_per_tick: 2,
+            min_backlog: 1,
+            cooldown_ticks: 20,
+            max_idle_ticks: 30,
+        }
+    }
+}
+
+/// Digestion backlog of one shard, collected by the coordinator to plan
+/// migrations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardBacklog {
+    /// The shard being reported on.
+    pub shard_id: ShardId,
+    /// Documents no agent has engulfed yet.
+    pub undigested_documents: usize,
+    /// Live digesters on the shard.
+    pub digesters_alive: usize,
+    /// Digesters that could leave now: no pending work, not dying, and
+    /// not within their arrival cooldown.
+    pub migratable: usize,
+}
+
+/// Instruction to move `count` agents from one shard to another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationCommand {
+    /// Shard giving up agents.
+    pub from: ShardId,
+    /// Shard receiving them.
+    pub to: ShardId,
+    /// Number of agents to move.
+    pub count: usize,
+}
+
+/// An agent in transit between shards.
+///
+/// Carries what a target shard needs to respawn the agent: its type,
+/// identity, age, and learned vocabulary. Parameters such as the idle
+/// limit come from the target's [`MigrationPolicy`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigratableAgent {
+    /// The agent's ID, kept across the move.
+    pub agent_id: AgentId,
+    /// Agent type, e.g. `"digester"`.
+    pub agent_type: String,
+    /// Age in ticks when it left.
+    pub age: Tick,
+    /// Vocabulary from `Agent::export_vocabulary`, if it had learned any.
+    pub vocabulary: Option<Vec<u8>>,
+    /// Shard the agent left.
+    pub source_shard: ShardId,
+}
+
+/// An agent that moved between shards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentMigration {
+    /// The migrated agent.
+    pub agent_id: AgentId,
+    /// Shard it left.
+    pub from_shard: ShardId,
+    /// Shard it was respawned on.
+    pub to_shard: ShardId,
+    /// Tick of the move.
+    pub tick: Tick,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -433,7 +533,8 @@ mod tests {
             position: Position::new(0.0, 0.0),
             access_count: 0,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         };
         ghost.resolve(data);
         assert!(ghost.is_resolved());
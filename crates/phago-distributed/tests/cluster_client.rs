@@ -0,0 +1,126 @@
+//! End-to-end tests for feeding and querying a cluster over RPC.
+//!
+//! Starts the in-process bench cluster behind real coordinator and shard
+//! servers, ingests the embedded corpus through `ClusterClient` and checks
+//! that a distributed query draws results from several shards.
+
+use phago_core::types::Position;
+use phago_distributed::bench::create_bench_cluster;
+use phago_distributed::cluster::ClusterClient;
+use phago_distributed::query::DistributedQueryEngine;
+use phago_distributed::rpc::client::ClientConfig;
+use phago_distributed::rpc::server::{CoordinatorServer, ShardServer};
+use phago_distributed::types::*;
+use phago_runtime::corpus::Corpus;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use tokio::task::LocalSet;
+
+/// Reserve a free local port by binding and immediately releasing it.
+fn free_addr() -> SocketAddr {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+/// Start a bench cluster behind RPC servers and return the coordinator address.
+async fn start_cluster(num_shards: u32) -> SocketAddr {
+    let (coordinator, shards) = create_bench_cluster(num_shards);
+
+    // Shards register in order, so the coordinator assigns matching IDs
+    for shard in shards {
+        let addr = free_addr();
+        let id = shard.read().await.shard_id();
+        let registered = coordinator
+            .register_shard(ShardInfo::new(id, addr.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(registered, id);
+        tokio::task::spawn_local(ShardServer::new(shard).start(addr));
+    }
+
+    let coordinator_addr = free_addr();
+    tokio::task::spawn_local(CoordinatorServer::new(coordinator).start(coordinator_addr));
+    coordinator_addr
+}
+
+#[tokio::test]
+async fn test_ingest_and_query_through_coordinator() {
+    LocalSet::new()
+        .run_until(async {
+            let coordinator_addr = start_cluster(3).await;
+            let cluster =
+                ClusterClient::connect(&coordinator_addr.to_string(), ClientConfig::default())
+                    .await
+                    .unwrap();
+            assert_eq!(cluster.shards().len(), 3);
+
+            let corpus = Corpus::from_embedded();
+            let mut owners = HashSet::new();
+            for doc in &corpus.documents {
+                let targets = cluster
+                    .ingest(&doc.title, &doc.content, doc.position, Some(0))
+                    .await
+                    .unwrap();
+                assert_eq!(targets.len(), 1);
+                owners.insert(targets[0]);
+            }
+            assert!(owners.len() > 1, "hash ring should spread documents");
+
+            // A few ticks are enough for the digesters to present concepts
+            cluster.run_ticks(3).await.unwrap();
+
+            let results = cluster
+                .query(
+                    &DistributedQueryEngine::with_defaults(),
+                    "cell membrane protein",
+                )
+                .await
+                .unwrap();
+            assert!(!results.is_empty(), "query should find digested concepts");
+
+            let shards: HashSet<ShardId> = results.iter().map(|r| r.shard_id).collect();
+            assert!(
+                shards.len() > 1,
+                "expected results from several shards, got {:?}",
+                shards
+            );
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_ingest_honors_replica_count() {
+    LocalSet::new()
+        .run_until(async {
+            let coordinator_addr = start_cluster(3).await;
+            let cluster =
+                ClusterClient::connect(&coordinator_addr.to_string(), ClientConfig::default())
+                    .await
+                    .unwrap();
+
+            let targets = cluster
+                .ingest(
+                    "Replicated",
+                    "cell membrane",
+                    Position::new(0.0, 0.0),
+                    Some(1),
+                )
+                .await
+                .unwrap();
+            assert_eq!(targets.len(), 2);
+            assert_ne!(targets[0], targets[1]);
+
+            // Without an explicit count the coordinator's replication factor applies
+            let targets = cluster
+                .ingest("Default", "cell membrane", Position::new(0.0, 0.0), None)
+                .await
+                .unwrap();
+            assert_eq!(
+                targets.len(),
+                DistributedConfig::default().replication_factor as usize + 1
+            );
+        })
+        .await;
+}
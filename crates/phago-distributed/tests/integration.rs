@@ -12,11 +12,12 @@ use phago_distributed::runner::{DistributedRunner, RunnerConfig};
 use phago_distributed::shard::ShardedColony;
 use phago_distributed::types::*;
 use phago_runtime::colony::ColonyConfig;
+use std::rc::Rc;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Helper: tick all shards and advance coordinator (without barrier sync).
-async fn tick_cluster(coordinator: &Coordinator, shards: &[Arc<RwLock<ShardedColony>>]) {
+async fn tick_cluster(coordinator: &Coordinator, shards: &[Rc<RwLock<ShardedColony>>]) {
     for shard in shards {
         let mut s = shard.write().await;
         s.tick();
@@ -25,13 +26,13 @@ async fn tick_cluster(coordinator: &Coordinator, shards: &[Arc<RwLock<ShardedCol
 }
 
 /// Helper: create an in-process cluster with N shards.
-fn create_cluster(num_shards: u32) -> (Arc<Coordinator>, Vec<Arc<RwLock<ShardedColony>>>) {
+fn create_cluster(num_shards: u32) -> (Arc<Coordinator>, Vec<Rc<RwLock<ShardedColony>>>) {
     let coordinator = Arc::new(Coordinator::new(num_shards));
     let hash_ring = Arc::new(RwLock::new(ConsistentHashRing::new(num_shards)));
 
     let shards: Vec<_> = (0..num_shards)
         .map(|i| {
-            Arc::new(RwLock::new(ShardedColony::new(
+            Rc::new(RwLock::new(ShardedColony::new(
                 ShardId::new(i),
                 ColonyConfig::default(),
                 hash_ring.clone(),
@@ -43,7 +44,7 @@ fn create_cluster(num_shards: u32) -> (Arc<Coordinator>, Vec<Arc<RwLock<ShardedC
 }
 
 /// Helper: register all shards with coordinator.
-async fn register_shards(coordinator: &Coordinator, shards: &[Arc<RwLock<ShardedColony>>]) {
+async fn register_shards(coordinator: &Coordinator, shards: &[Rc<RwLock<ShardedColony>>]) {
     for (i, _) in shards.iter().enumerate() {
         let info = ShardInfo::new(ShardId::new(i as u32), format!("127.0.0.1:{}", 9000 + i));
         coordinator.register_shard(info).await.unwrap();
@@ -53,7 +54,7 @@ async fn register_shards(coordinator: &Coordinator, shards: &[Arc<RwLock<Sharded
 /// Helper: ingest a document into the correct shard via routing.
 async fn route_and_ingest(
     coordinator: &Coordinator,
-    shards: &[Arc<RwLock<ShardedColony>>],
+    shards: &[Rc<RwLock<ShardedColony>>],
     doc_seed: u64,
     title: &str,
     content: &str,
@@ -311,7 +312,7 @@ async fn test_cross_shard_edge_lifecycle() {
 
     // Edge manager should track it
     let stats = s0.cross_shard_edge_stats();
-    assert!(stats.outgoing_edges > 0 || s0.pending_cross_edges().len() > 0);
+    assert!(stats.outgoing_edges > 0 || !s0.pending_cross_edges().is_empty());
 
     // Clear pending
     s0.clear_pending_cross_edges();
@@ -0,0 +1,115 @@
+//! Agent migration between shards.
+//!
+//! Documents are routed to one shard while the digesters live on another.
+//! Without migration the digesters idle to death next to an empty shard;
+//! with it they move over and the backlog gets digested.
+
+use phago_agents::digester::Digester;
+use phago_core::types::Position;
+use phago_distributed::bench::{create_bench_cluster, generate_documents};
+use phago_distributed::runner::{DistributedRunner, RunnerConfig};
+use phago_distributed::types::*;
+
+const TICKS: u64 = 80;
+
+/// Two shards: shard 0 has two documents and four digesters, shard 1 has
+/// six documents and no digesters.
+async fn skewed_runner(migration: Option<MigrationPolicy>, pipelined: bool) -> DistributedRunner {
+    let (coordinator, shards) = create_bench_cluster(2);
+    for i in 0..2 {
+        let info = ShardInfo::new(ShardId::new(i), format!("127.0.0.1:{}", 9100 + i));
+        coordinator.register_shard(info).await.unwrap();
+    }
+
+    let documents = generate_documents(8);
+    {
+        let mut busy = shards[0].write().await;
+        for (i, (title, content)) in documents[..2].iter().enumerate() {
+            busy.ingest_document_direct(title, content, Position::new(i as f64 * 2.0, 0.0));
+        }
+        for i in 0..4 {
+            let digester = Digester::new(Position::new(i as f64, 1.0)).with_max_idle(30);
+            busy.local_mut().spawn(Box::new(digester));
+        }
+    }
+    {
+        let mut backlogged = shards[1].write().await;
+        for (i, (title, content)) in documents[2..].iter().enumerate() {
+            backlogged.ingest_document_direct(title, content, Position::new(i as f64 * 2.0, 0.0));
+        }
+    }
+
+    DistributedRunner::new(
+        coordinator,
+        shards,
+        RunnerConfig {
+            resolve_ghosts: false,
+            migration,
+            pipelined,
+            ..Default::default()
+        },
+    )
+}
+
+/// (documents, digested) per shard.
+async fn digestion(runner: &DistributedRunner) -> Vec<(usize, usize)> {
+    let mut progress = Vec::new();
+    for shard in runner.shards() {
+        let stats = shard.read().await.stats();
+        progress.push((stats.documents_total, stats.documents_digested));
+    }
+    progress
+}
+
+#[tokio::test]
+async fn without_migration_the_backlogged_shard_is_never_digested() {
+    let runner = skewed_runner(None, false).await;
+    let results = runner.run(TICKS).await.unwrap();
+
+    assert_eq!(digestion(&runner).await, vec![(2, 2), (6, 0)]);
+    assert!(results.iter().all(|r| r.migrations.is_empty()));
+    assert_eq!(runner.coordinator().cluster_stats().await.migrations, 0);
+}
+
+#[tokio::test]
+async fn idle_digesters_migrate_and_finish_the_backlog() {
+    let runner = skewed_runner(Some(MigrationPolicy::default()), false).await;
+    let results = runner.run(TICKS).await.unwrap();
+
+    assert_eq!(digestion(&runner).await, vec![(2, 2), (6, 6)]);
+
+    let migrations: Vec<_> = results.iter().flat_map(|r| r.migrations.clone()).collect();
+    assert!(!migrations.is_empty());
+    assert!(migrations
+        .iter()
+        .all(|m| m.from_shard == ShardId::new(0) && m.to_shard == ShardId::new(1)));
+    assert!(results
+        .iter()
+        .all(|r| r.migrations.len() <= MigrationPolicy::default().max_migrations_per_tick));
+    assert_eq!(
+        runner.coordinator().cluster_stats().await.migrations,
+        migrations.len() as u64
+    );
+
+    // Leaving is not dying
+    let source = runner.shards()[0].read().await.stats();
+    assert_eq!(
+        source.agents_died + source.agents_alive + migrations.len(),
+        4
+    );
+}
+
+#[tokio::test]
+async fn pipelined_runs_still_migrate_between_stretches() {
+    let policy = MigrationPolicy::default();
+    let runner = skewed_runner(Some(policy.clone()), true).await;
+    let results = runner.run(TICKS).await.unwrap();
+
+    assert_eq!(results.len() as u64, TICKS);
+    assert_eq!(digestion(&runner).await, vec![(2, 2), (6, 6)]);
+    for (tick, result) in results.iter().enumerate() {
+        if !result.migrations.is_empty() {
+            assert!((tick as u64).is_multiple_of(policy.interval_ticks));
+        }
+    }
+}
@@ -29,7 +29,7 @@ mod normalize;
 mod simple;
 
 pub use chunker::{ChunkConfig, Chunker};
-pub use embedder::{Embedder, EmbeddingError, EmbeddingResult};
+pub use embedder::{Embedder, Embedding, EmbeddingError, EmbeddingResult};
 pub use normalize::{
     cosine_similarity, dot_product, euclidean_distance, normalize_l1, normalize_l2,
     normalize_minmax, normalize_zscore,
@@ -0,0 +1,168 @@
+/* Phago C API. Generated by cbindgen from src/lib.rs; do not edit. */
+
+#ifndef PHAGO_H
+#define PHAGO_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/*
+ The call succeeded.
+ */
+#define PHAGO_OK 0
+
+/*
+ A required pointer argument was NULL.
+ */
+#define PHAGO_ERR_NULL_POINTER 1
+
+/*
+ A string argument was not valid UTF-8.
+ */
+#define PHAGO_ERR_INVALID_UTF8 2
+
+/*
+ An argument was malformed, e.g. JSON that does not parse.
+ */
+#define PHAGO_ERR_INVALID_ARGUMENT 3
+
+/*
+ Reading or writing a file failed.
+ */
+#define PHAGO_ERR_IO 4
+
+/*
+ The colony was used from a thread other than the one that created it
+ (checked in debug builds only).
+ */
+#define PHAGO_ERR_WRONG_THREAD 5
+
+/*
+ Phago panicked; the message is available from [`phago_last_error`].
+ */
+#define PHAGO_ERR_PANIC 6
+
+/*
+ A colony owned by the host. Opaque to C.
+
+ Not thread-safe: call functions on a colony only from the thread that
+ created it, including [`phago_colony_free`]. Debug builds fail calls
+ from other threads with [`PHAGO_ERR_WRONG_THREAD`].
+ */
+typedef struct PhagoColony PhagoColony;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Create a colony.
+
+ `config_json` is a JSON object of colony configuration fields, e.g.
+ `{"edge_decay_rate": 0.01}`; fields left out keep their defaults. NULL
+ uses the default configuration.
+
+ Returns NULL on failure. Free the colony with [`phago_colony_free`].
+
+ # Safety
+ `config_json` must be NULL or a valid NUL-terminated string.
+ */
+struct PhagoColony *phago_colony_new(const char *config_json);
+
+/*
+ Free a colony. NULL is ignored.
+
+ # Safety
+ `colony` must be NULL or a pointer returned by [`phago_colony_new`] that
+ has not been freed yet.
+ */
+void phago_colony_free(struct PhagoColony *colony);
+
+/*
+ Ingest a document and spawn a digester for it, placing the document
+ near similar content.
+
+ `content` is `content_len` bytes of UTF-8 and may contain NUL bytes.
+ Returns the document ID, or NULL on failure. Free the ID with
+ [`phago_string_free`].
+
+ # Safety
+ `colony` must be a live colony, `title` a valid NUL-terminated string
+ and `content` valid for reads of `content_len` bytes.
+ */
+char *phago_ingest(struct PhagoColony *colony,
+                   const char *title,
+                   const uint8_t *content,
+                   size_t content_len);
+
+/*
+ Run the simulation for `ticks` ticks.
+
+ # Safety
+ `colony` must be a live colony.
+ */
+int32_t phago_run(struct PhagoColony *colony, uint64_t ticks);
+
+/*
+ Query the knowledge graph with hybrid scoring.
+
+ `query_json` is `{"query": "...", "alpha": 0.5, "max_results": 10}`;
+ `alpha` (1.0 is pure TF-IDF, 0.0 pure graph structure) and
+ `max_results` are optional. Returns a JSON array of
+ `{"label", "score", "tfidf_score", "graph_score"}` objects, best first,
+ or NULL on failure. Free it with [`phago_string_free`].
+
+ # Safety
+ `colony` must be a live colony and `query_json` a valid NUL-terminated
+ string.
+ */
+char *phago_query(const struct PhagoColony *colony, const char *query_json);
+
+/*
+ Colony statistics as a JSON object (tick, agent, graph and document
+ counts), or NULL on failure. Free it with [`phago_string_free`].
+
+ # Safety
+ `colony` must be a live colony.
+ */
+char *phago_stats(const struct PhagoColony *colony);
+
+/*
+ Save the colony's knowledge graph to a session file at `path`.
+
+ # Safety
+ `colony` must be a live colony and `path` a valid NUL-terminated string.
+ */
+int32_t phago_save_session(const struct PhagoColony *colony, const char *path);
+
+/*
+ Add the knowledge graph saved at `path` to the colony.
+
+ # Safety
+ `colony` must be a live colony and `path` a valid NUL-terminated string.
+ */
+int32_t phago_load_session(struct PhagoColony *colony, const char *path);
+
+/*
+ Free a string returned by this library. NULL is ignored.
+
+ # Safety
+ `s` must be NULL or a string returned by this library that has not been
+ freed yet.
+ */
+void phago_string_free(char *s);
+
+/*
+ Message describing the calling thread's most recent failure, or NULL if
+ nothing has failed on it yet.
+
+ The string belongs to the library and stays valid until the next
+ failing call on the same thread; do not free it.
+ */
+const char *phago_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PHAGO_H */
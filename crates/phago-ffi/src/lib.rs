@@ -0,0 +1,575 @@
+//! C bindings for Phago biological computing framework.
+//!
+//! A minimal C ABI for embedding a colony in hosts other than Rust and
+//! Python, e.g. Go through cgo or Swift through a module map. The header
+//! is `include/phago.h`, generated from this file by cbindgen.
+//!
+//! # Conventions
+//!
+//! - Strings passed in are NUL-terminated UTF-8. Document content takes an
+//!   explicit length instead, so it may contain NUL bytes.
+//! - Strings returned are owned by the caller and released with
+//!   [`phago_string_free`].
+//! - Functions returning a pointer return NULL on failure; functions
+//!   returning `int32_t` return [`PHAGO_OK`] or one of the `PHAGO_ERR_*`
+//!   codes. [`phago_last_error`] describes the calling thread's most recent
+//!   failure.
+//! - No call unwinds into the host: a panic is caught and reported as
+//!   [`PHAGO_ERR_PANIC`]. A colony that panicked may be left half-updated
+//!   and should only be freed.
+//!
+//! # Threads
+//!
+//! A colony handle is single-threaded, like the Python binding's `Colony`:
+//! use it only from the thread that created it. Separate handles may live
+//! on separate threads. Debug builds enforce this, failing calls from any
+//! other thread with [`PHAGO_ERR_WRONG_THREAD`].
+
+use phago_agents::digester::Digester;
+use phago_rag::{hybrid_query, HybridConfig};
+use phago_runtime::colony::{Colony, ColonyConfig};
+use phago_runtime::session::{load_session, restore_into_colony, save_session};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+/// The call succeeded.
+pub const PHAGO_OK: i32 = 0;
+/// A required pointer argument was NULL.
+pub const PHAGO_ERR_NULL_POINTER: i32 = 1;
+/// A string argument was not valid UTF-8.
+pub const PHAGO_ERR_INVALID_UTF8: i32 = 2;
+/// An argument was malformed, e.g. JSON that does not parse.
+pub const PHAGO_ERR_INVALID_ARGUMENT: i32 = 3;
+/// Reading or writing a file failed.
+pub const PHAGO_ERR_IO: i32 = 4;
+/// The colony was used from a thread other than the one that created it
+/// (checked in debug builds only).
+pub const PHAGO_ERR_WRONG_THREAD: i32 = 5;
+/// Phago panicked; the message is available from [`phago_last_error`].
+pub const PHAGO_ERR_PANIC: i32 = 6;
+
+/// A colony owned by the host. Opaque to C.
+///
+/// Not thread-safe: call functions on a colony only from the thread that
+/// created it, including [`phago_colony_free`]. Debug builds fail calls
+/// from other threads with [`PHAGO_ERR_WRONG_THREAD`].
+pub struct PhagoColony {
+    inner: Colony,
+    #[cfg(debug_assertions)]
+    owner: std::thread::ThreadId,
+}
+
+impl PhagoColony {
+    fn check_thread(&self) -> Result<(), FfiError> {
+        #[cfg(debug_assertions)]
+        if std::thread::current().id() != self.owner {
+            return Err(FfiError::new(
+                PHAGO_ERR_WRONG_THREAD,
+                "colony used from a thread other than the one that created it",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A failure to report to the host.
+struct FfiError {
+    code: i32,
+    message: String,
+}
+
+impl FfiError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run `f`, turning failures and panics into an error code and recording
+/// their message for [`phago_last_error`].
+fn guard<T>(f: impl FnOnce() -> Result<T, FfiError>) -> Result<T, i32> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => {
+            set_last_error(&e.message);
+            Err(e.code)
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(&format!("panic: {message}"));
+            Err(PHAGO_ERR_PANIC)
+        }
+    }
+}
+
+fn status(result: Result<(), i32>) -> i32 {
+    result.err().unwrap_or(PHAGO_OK)
+}
+
+/// Hand a string to the caller, who frees it with [`phago_string_free`].
+fn into_c_string(s: String) -> *mut c_char {
+    CString::new(s.replace('\0', " "))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+unsafe fn colony_ref<'a>(colony: *const PhagoColony) -> Result<&'a PhagoColony, FfiError> {
+    let colony = colony
+        .as_ref()
+        .ok_or_else(|| FfiError::new(PHAGO_ERR_NULL_POINTER, "colony is NULL"))?;
+    colony.check_thread()?;
+    Ok(colony)
+}
+
+unsafe fn colony_mut<'a>(colony: *mut PhagoColony) -> Result<&'a mut PhagoColony, FfiError> {
+    let colony = colony
+        .as_mut()
+        .ok_or_else(|| FfiError::new(PHAGO_ERR_NULL_POINTER, "colony is NULL"))?;
+    colony.check_thread()?;
+    Ok(colony)
+}
+
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::new(
+            PHAGO_ERR_NULL_POINTER,
+            format!("{name} is NULL"),
+        ));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|e| FfiError::new(PHAGO_ERR_INVALID_UTF8, format!("{name}: {e}")))
+}
+
+fn to_json(value: &impl Serialize) -> Result<String, FfiError> {
+    serde_json::to_string(value).map_err(|e| FfiError::new(PHAGO_ERR_PANIC, e.to_string()))
+}
+
+/// Parse `json` as a colony configuration; top-level fields left out keep
+/// their defaults.
+fn parse_config(json: &str) -> Result<ColonyConfig, FfiError> {
+    let invalid = |e: serde_json::Error| {
+        FfiError::new(PHAGO_ERR_INVALID_ARGUMENT, format!("config_json: {e}"))
+    };
+    let overrides: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(json).map_err(invalid)?;
+    let mut config = serde_json::to_value(ColonyConfig::default()).map_err(invalid)?;
+    if let Some(fields) = config.as_object_mut() {
+        fields.extend(overrides);
+    }
+    serde_json::from_value(config).map_err(invalid)
+}
+
+/// Query accepted by [`phago_query`].
+#[derive(Deserialize)]
+struct QueryRequest {
+    query: String,
+    #[serde(default = "default_alpha")]
+    alpha: f64,
+    #[serde(default = "default_max_results")]
+    max_results: usize,
+}
+
+fn default_alpha() -> f64 {
+    0.5
+}
+
+fn default_max_results() -> usize {
+    10
+}
+
+/// One entry of [`phago_query`]'s result array.
+#[derive(Serialize)]
+struct QueryResult {
+    label: String,
+    score: f64,
+    tfidf_score: f64,
+    graph_score: f64,
+}
+
+/// Create a colony.
+///
+/// `config_json` is a JSON object of colony configuration fields, e.g.
+/// `{"edge_decay_rate": 0.01}`; fields left out keep their defaults. NULL
+/// uses the default configuration.
+///
+/// Returns NULL on failure. Free the colony with [`phago_colony_free`].
+///
+/// # Safety
+/// `config_json` must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn phago_colony_new(config_json: *const c_char) -> *mut PhagoColony {
+    guard(|| {
+        let config = if config_json.is_null() {
+            ColonyConfig::default()
+        } else {
+            parse_config(str_arg(config_json, "config_json")?)?
+        };
+        let inner = Colony::try_from_config(config)
+            .map_err(|e| FfiError::new(PHAGO_ERR_IO, e.to_string()))?;
+        Ok(Box::into_raw(Box::new(PhagoColony {
+            inner,
+            #[cfg(debug_assertions)]
+            owner: std::thread::current().id(),
+        })))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// Free a colony. NULL is ignored.
+///
+/// # Safety
+/// `colony` must be NULL or a pointer returned by [`phago_colony_new`] that
+/// has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn phago_colony_free(colony: *mut PhagoColony) {
+    if colony.is_null() {
+        return;
+    }
+    let _ = guard(|| {
+        colony_ref(colony)?;
+        drop(Box::from_raw(colony));
+        Ok(())
+    });
+}
+
+/// Ingest a document and spawn a digester for it, placing the document
+/// near similar content.
+///
+/// `content` is `content_len` bytes of UTF-8 and may contain NUL bytes.
+/// Returns the document ID, or NULL on failure. Free the ID with
+/// [`phago_string_free`].
+///
+/// # Safety
+/// `colony` must be a live colony, `title` a valid NUL-terminated string
+/// and `content` valid for reads of `content_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn phago_ingest(
+    colony: *mut PhagoColony,
+    title: *const c_char,
+    content: *const u8,
+    content_len: usize,
+) -> *mut c_char {
+    guard(|| {
+        let colony = colony_mut(colony)?;
+        let title = str_arg(title, "title")?;
+        if content.is_null() && content_len > 0 {
+            return Err(FfiError::new(PHAGO_ERR_NULL_POINTER, "content is NULL"));
+        }
+        let bytes = if content_len == 0 {
+            &[][..]
+        } else {
+            std::slice::from_raw_parts(content, content_len)
+        };
+        let content = std::str::from_utf8(bytes)
+            .map_err(|e| FfiError::new(PHAGO_ERR_INVALID_UTF8, format!("content: {e}")))?;
+
+        let position = colony.inner.place_document(title, content);
+        let doc_id = colony.inner.ingest_document(title, content, position);
+        colony
+            .inner
+            .spawn(Box::new(Digester::new(position).with_max_idle(30)));
+        Ok(into_c_string(doc_id.0.to_string()))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// Run the simulation for `ticks` ticks.
+///
+/// # Safety
+/// `colony` must be a live colony.
+#[no_mangle]
+pub unsafe extern "C" fn phago_run(colony: *mut PhagoColony, ticks: u64) -> i32 {
+    status(guard(|| {
+        colony_mut(colony)?.inner.run(ticks);
+        Ok(())
+    }))
+}
+
+/// Query the knowledge graph with hybrid scoring.
+///
+/// `query_json` is `{"query": "...", "alpha": 0.5, "max_results": 10}`;
+/// `alpha` (1.0 is pure TF-IDF, 0.0 pure graph structure) and
+/// `max_results` are optional. Returns a JSON array of
+/// `{"label", "score", "tfidf_score", "graph_score"}` objects, best first,
+/// or NULL on failure. Free it with [`phago_string_free`].
+///
+/// # Safety
+/// `colony` must be a live colony and `query_json` a valid NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn phago_query(
+    colony: *const PhagoColony,
+    query_json: *const c_char,
+) -> *mut c_char {
+    guard(|| {
+        let colony = colony_ref(colony)?;
+        let request: QueryRequest = serde_json::from_str(str_arg(query_json, "query_json")?)
+            .map_err(|e| FfiError::new(PHAGO_ERR_INVALID_ARGUMENT, format!("query_json: {e}")))?;
+        let invalid = |e: phago_rag::HybridConfigError| {
+            FfiError::new(PHAGO_ERR_INVALID_ARGUMENT, e.to_string())
+        };
+        let config = HybridConfig::builder()
+            .alpha(request.alpha)
+            .max_results(request.max_results)
+            .build()
+            .map_err(invalid)?;
+        let results: Vec<QueryResult> = hybrid_query(&colony.inner, &request.query, &config)
+            .map_err(invalid)?
+            .into_iter()
+            .map(|r| QueryResult {
+                label: r.label,
+                score: r.final_score,
+                tfidf_score: r.tfidf_score,
+                graph_score: r.graph_score,
+            })
+            .collect();
+        Ok(into_c_string(to_json(&results)?))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// Colony statistics as a JSON object (tick, agent, graph and document
+/// counts), or NULL on failure. Free it with [`phago_string_free`].
+///
+/// # Safety
+/// `colony` must be a live colony.
+#[no_mangle]
+pub unsafe extern "C" fn phago_stats(colony: *const PhagoColony) -> *mut c_char {
+    guard(|| {
+        let colony = colony_ref(colony)?;
+        Ok(into_c_string(to_json(&colony.inner.stats())?))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// Save the colony's knowledge graph to a session file at `path`.
+///
+/// # Safety
+/// `colony` must be a live colony and `path` a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn phago_save_session(
+    colony: *const PhagoColony,
+    path: *const c_char,
+) -> i32 {
+    status(guard(|| {
+        let colony = colony_ref(colony)?;
+        let path = str_arg(path, "path")?;
+        save_session(&colony.inner, Path::new(path), &[])
+            .map_err(|e| FfiError::new(PHAGO_ERR_IO, format!("{path}: {e}")))
+    }))
+}
+
+/// Add the knowledge graph saved at `path` to the colony.
+///
+/// # Safety
+/// `colony` must be a live colony and `path` a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn phago_load_session(colony: *mut PhagoColony, path: *const c_char) -> i32 {
+    status(guard(|| {
+        let colony = colony_mut(colony)?;
+        let path = str_arg(path, "path")?;
+        let state = load_session(Path::new(path))
+            .map_err(|e| FfiError::new(PHAGO_ERR_IO, format!("{path}: {e}")))?;
+        restore_into_colony(&mut colony.inner, &state, None);
+        Ok(())
+    }))
+}
+
+/// Free a string returned by this library. NULL is ignored.
+///
+/// # Safety
+/// `s` must be NULL or a string returned by this library that has not been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn phago_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Message describing the calling thread's most recent failure, or NULL if
+/// nothing has failed on it yet.
+///
+/// The string belongs to the library and stays valid until the next
+/// failing call on the same thread; do not free it.
+#[no_mangle]
+pub extern "C" fn phago_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    unsafe fn take(s: *mut c_char) -> String {
+        assert!(!s.is_null(), "{:?}", CStr::from_ptr(phago_last_error()));
+        let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+        phago_string_free(s);
+        owned
+    }
+
+    unsafe fn last_error() -> String {
+        CStr::from_ptr(phago_last_error())
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn ingest_run_query_and_stats() {
+        unsafe {
+            let colony = phago_colony_new(std::ptr::null());
+            for (title, content) in [
+                ("Cells", "The cell membrane controls transport of proteins."),
+                (
+                    "Proteins",
+                    "Proteins fold inside the cell and cross the membrane.",
+                ),
+            ] {
+                let id = take(phago_ingest(
+                    colony,
+                    c(title).as_ptr(),
+                    content.as_ptr(),
+                    content.len(),
+                ));
+                assert_eq!(id.len(), 36);
+            }
+            assert_eq!(phago_run(colony, 20), PHAGO_OK);
+
+            let results = take(phago_query(colony, c(r#"{"query": "membrane"}"#).as_ptr()));
+            let results: Vec<serde_json::Value> = serde_json::from_str(&results).unwrap();
+            assert!(!results.is_empty());
+            assert!(results[0]["score"].as_f64().unwrap() > 0.0);
+
+            let stats: serde_json::Value =
+                serde_json::from_str(&take(phago_stats(colony))).unwrap();
+            assert_eq!(stats["documents_total"], 2);
+            assert_eq!(stats["tick"], 20);
+            phago_colony_free(colony);
+        }
+    }
+
+    #[test]
+    fn content_may_contain_nul_bytes() {
+        unsafe {
+            let colony = phago_colony_new(std::ptr::null());
+            let content = b"membrane\0transport";
+            let id = phago_ingest(colony, c("nul").as_ptr(), content.as_ptr(), content.len());
+            assert!(!id.is_null());
+            phago_string_free(id);
+
+            let invalid = [0xff, 0xfe];
+            let id = phago_ingest(colony, c("bad").as_ptr(), invalid.as_ptr(), invalid.len());
+            assert!(id.is_null());
+            assert!(last_error().starts_with("content:"), "{}", last_error());
+            phago_colony_free(colony);
+        }
+    }
+
+    #[test]
+    fn config_json_overrides_defaults() {
+        unsafe {
+            let colony = phago_colony_new(c(r#"{"max_edge_degree": 7}"#).as_ptr());
+            assert_eq!((*colony).inner.config().max_edge_degree, 7);
+            phago_colony_free(colony);
+
+            assert!(phago_colony_new(c("[1, 2]").as_ptr()).is_null());
+            assert!(last_error().starts_with("config_json:"));
+            assert!(phago_colony_new(c(r#"{"max_edge_degree": "x"}"#).as_ptr()).is_null());
+        }
+    }
+
+    #[test]
+    fn errors_are_reported_as_codes() {
+        unsafe {
+            assert_eq!(phago_run(std::ptr::null_mut(), 1), PHAGO_ERR_NULL_POINTER);
+            assert_eq!(last_error(), "colony is NULL");
+
+            let colony = phago_colony_new(std::ptr::null());
+            assert!(phago_query(colony, c("not json").as_ptr()).is_null());
+            assert!(phago_query(colony, c(r#"{"query": "x", "alpha": 2}"#).as_ptr()).is_null());
+            assert_eq!(
+                phago_load_session(colony, c("/nonexistent/session.json").as_ptr()),
+                PHAGO_ERR_IO
+            );
+            phago_colony_free(colony);
+        }
+    }
+
+    #[test]
+    fn panics_do_not_unwind_into_the_caller() {
+        let result: Result<(), i32> = guard(|| panic!("boom"));
+        assert_eq!(result, Err(PHAGO_ERR_PANIC));
+        assert_eq!(unsafe { last_error() }, "panic: boom");
+    }
+
+    #[test]
+    fn sessions_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = c(dir.path().join("session.json").to_str().unwrap());
+        unsafe {
+            let colony = phago_colony_new(std::ptr::null());
+            let content = "Neurons fire and synapses strengthen between neurons.";
+            phago_string_free(phago_ingest(
+                colony,
+                c("Neurons").as_ptr(),
+                content.as_ptr(),
+                content.len(),
+            ));
+            phago_run(colony, 20);
+            assert_eq!(phago_save_session(colony, path.as_ptr()), PHAGO_OK);
+
+            let restored = phago_colony_new(std::ptr::null());
+            assert_eq!(phago_load_session(restored, path.as_ptr()), PHAGO_OK);
+            assert_eq!(
+                (*restored).inner.stats().graph_nodes,
+                (*colony).inner.stats().graph_nodes
+            );
+            phago_colony_free(colony);
+            phago_colony_free(restored);
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn colonies_are_bound_to_their_thread() {
+        struct Handle(*mut PhagoColony);
+        unsafe impl Send for Handle {}
+
+        let handle = Handle(unsafe { phago_colony_new(std::ptr::null()) });
+        let handle = std::thread::spawn(move || {
+            let code = unsafe { phago_run(handle.0, 1) };
+            assert_eq!(code, PHAGO_ERR_WRONG_THREAD);
+            handle
+        })
+        .join()
+        .unwrap();
+        unsafe { phago_colony_free(handle.0) };
+    }
+}
@@ -0,0 +1,78 @@
+/* Drives the C API end to end: ingest, run, query, stats, save and load.
+ * Usage: smoke <session path> */
+#include <stdio.h>
+#include <string.h>
+
+#include "phago.h"
+
+#define CHECK(cond)                                                         \
+    do {                                                                    \
+        if (!(cond)) {                                                      \
+            const char *err = phago_last_error();                           \
+            fprintf(stderr, "%s:%d: %s failed (%s)\n", __FILE__, __LINE__,  \
+                    #cond, err ? err : "no error");                         \
+            return 1;                                                       \
+        }                                                                   \
+    } while (0)
+
+static char *ingest(PhagoColony *colony, const char *title, const char *content,
+                    size_t len) {
+    return phago_ingest(colony, title, (const uint8_t *)content, len);
+}
+
+int main(int argc, char **argv) {
+    CHECK(argc == 2);
+    const char *session = argv[1];
+
+    PhagoColony *colony = phago_colony_new("{\"max_edge_degree\": 20}");
+    CHECK(colony != NULL);
+
+    const char *docs[][2] = {
+        {"Cells", "The cell membrane controls transport of proteins."},
+        {"Proteins", "Proteins fold inside the cell and cross the membrane."},
+        {"Ribosomes", "Ribosomes build proteins for the cell."},
+    };
+    for (size_t i = 0; i < sizeof docs / sizeof docs[0]; i++) {
+        char *id = ingest(colony, docs[i][0], docs[i][1], strlen(docs[i][1]));
+        CHECK(id != NULL && strlen(id) == 36);
+        phago_string_free(id);
+    }
+    /* Content is length-delimited and may contain NUL bytes */
+    const char with_nul[] = "membrane\0transport";
+    char *id = ingest(colony, "Binary", with_nul, sizeof with_nul - 1);
+    CHECK(id != NULL);
+    phago_string_free(id);
+
+    CHECK(phago_run(colony, 30) == PHAGO_OK);
+
+    char *results = phago_query(colony, "{\"query\": \"cell membrane\", \"max_results\": 5}");
+    CHECK(results != NULL && strstr(results, "\"label\"") != NULL);
+    printf("query: %s\n", results);
+    phago_string_free(results);
+
+    char *stats = phago_stats(colony);
+    CHECK(stats != NULL && strstr(stats, "\"documents_total\":4") != NULL);
+    printf("stats: %s\n", stats);
+    phago_string_free(stats);
+
+    CHECK(phago_save_session(colony, session) == PHAGO_OK);
+    PhagoColony *restored = phago_colony_new(NULL);
+    CHECK(restored != NULL);
+    CHECK(phago_load_session(restored, session) == PHAGO_OK);
+    results = phago_query(restored, "{\"query\": \"membrane\"}");
+    CHECK(results != NULL && strstr(results, "membrane") != NULL);
+    phago_string_free(results);
+
+    /* Failures return codes or NULL and leave a message */
+    CHECK(phago_run(NULL, 1) == PHAGO_ERR_NULL_POINTER);
+    CHECK(phago_last_error() != NULL);
+    CHECK(phago_query(colony, "not json") == NULL);
+    CHECK(phago_load_session(restored, "/nonexistent/session.json") == PHAGO_ERR_IO);
+    CHECK(phago_colony_new("{\"max_edge_degree\": \"many\"}") == NULL);
+
+    phago_colony_free(restored);
+    phago_colony_free(colony);
+    phago_string_free(NULL);
+    phago_colony_free(NULL);
+    return 0;
+}
@@ -0,0 +1,81 @@
+//! The generated header matches the Rust API, and a C program using it
+//! links against the cdylib and runs.
+//!
+//! After changing the API, regenerate the header with:
+//! `PHAGO_UPDATE_HEADER=1 cargo test -p phago-ffi --test c_api`
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn crate_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn generate_header() -> String {
+    let config = cbindgen::Config::from_file(crate_dir().join("cbindgen.toml")).unwrap();
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(crate_dir())
+        .with_config(config)
+        .generate()
+        .expect("cbindgen failed")
+        .write(&mut header);
+    String::from_utf8(header).unwrap()
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let path = crate_dir().join("include/phago.h");
+    let generated = generate_header();
+    if std::env::var_os("PHAGO_UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "include/phago.h is stale; rerun with PHAGO_UPDATE_HEADER=1"
+    );
+}
+
+/// Where cargo put `libphago_ffi` for this test: its `deps/` directory,
+/// next to the test binary.
+fn library_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    exe.parent().unwrap().to_path_buf()
+}
+
+#[cfg(unix)]
+#[test]
+fn c_program_runs_against_the_library() {
+    let lib_dir = library_dir();
+    let work = tempfile::tempdir().unwrap();
+    let program = work.path().join("smoke");
+
+    let compiled = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg(crate_dir().join("tests/c/smoke.c"))
+        .arg("-I")
+        .arg(crate_dir().join("include"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lphago_ffi")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("no C compiler");
+    assert!(compiled.success(), "compiling tests/c/smoke.c failed");
+
+    let output = Command::new(&program)
+        .arg(work.path().join("session.json"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "smoke test failed:\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -197,6 +197,8 @@ pub trait LlmBackend: Send + Sync {
 pub struct MockBackend {
     config: LlmConfig,
     responses: std::collections::HashMap<String, String>,
+    extraction: Option<ExtractionResponse>,
+    failure: Option<String>,
 }
 
 impl MockBackend {
@@ -205,6 +207,8 @@ impl MockBackend {
         Self {
             config: LlmConfig::default(),
             responses: std::collections::HashMap::new(),
+            extraction: None,
+            failure: None,
         }
     }
 
@@ -214,6 +218,25 @@ impl MockBackend {
             .insert(pattern.to_string(), response.to_string());
         self
     }
+
+    /// Return these concepts and relationships for any text.
+    pub fn with_extraction(mut self, extraction: ExtractionResponse) -> Self {
+        self.extraction = Some(extraction);
+        self
+    }
+
+    /// Fail every request with a connection error.
+    pub fn failing(mut self, message: &str) -> Self {
+        self.failure = Some(message.to_string());
+        self
+    }
+
+    fn check_failure(&self) -> LlmResult<()> {
+        match &self.failure {
+            Some(message) => Err(LlmError::ConnectionFailed(message.clone())),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Default for MockBackend {
@@ -233,6 +256,7 @@ impl LlmBackend for MockBackend {
     }
 
     async fn complete(&self, prompt: &str) -> LlmResult<String> {
+        self.check_failure()?;
         // Check for matching pattern
         for (pattern, response) in &self.responses {
             if prompt.contains(pattern) {
@@ -243,6 +267,10 @@ impl LlmBackend for MockBackend {
     }
 
     async fn extract_concepts(&self, text: &str) -> LlmResult<Vec<Concept>> {
+        self.check_failure()?;
+        if let Some(extraction) = &self.extraction {
+            return Ok(extraction.concepts.clone());
+        }
         // Simple keyword extraction for testing
         let words: Vec<&str> = text
             .split(|c: char| !c.is_alphanumeric())
@@ -263,6 +291,10 @@ impl LlmBackend for MockBackend {
         _text: &str,
         concepts: &[Concept],
     ) -> LlmResult<Vec<Relationship>> {
+        self.check_failure()?;
+        if let Some(extraction) = &self.extraction {
+            return Ok(extraction.relationships.clone());
+        }
         // Create simple relationships between consecutive concepts
         let relationships: Vec<Relationship> = concepts
             .windows(2)
@@ -313,6 +345,26 @@ mod tests {
         assert_eq!(relationships[0].target, "ATP");
     }
 
+    #[tokio::test]
+    async fn test_mock_canned_extraction_and_failure() {
+        let canned = ExtractionResponse {
+            concepts: vec![Concept::new("mitochondria"), Concept::new("ATP")],
+            relationships: vec![Relationship::new("mitochondria", "ATP", "produces")],
+            raw_response: None,
+            tokens_used: None,
+        };
+        let backend = MockBackend::new().with_extraction(canned);
+        let response = backend.extract("anything at all").await.unwrap();
+        assert_eq!(response.concepts.len(), 2);
+        assert_eq!(response.relationships[0].label, "produces");
+
+        let backend = MockBackend::new().failing("offline");
+        assert!(matches!(
+            backend.extract("anything").await,
+            Err(LlmError::ConnectionFailed(_))
+        ));
+    }
+
     #[test]
     fn test_config_builders() {
         let claude = LlmConfig::claude();
@@ -24,9 +24,11 @@ mod backend;
 mod prompt;
 mod types;
 
-pub use backend::{LlmBackend, LlmError, LlmResult};
-pub use prompt::{ConceptPrompt, PromptTemplate, RelationshipPrompt};
-pub use types::{Concept, ConceptType, RelationType, Relationship};
+pub use backend::{LlmBackend, LlmError, LlmResult, MockBackend};
+pub use prompt::{
+    parse_concepts_json, ConceptPrompt, PromptTemplate, QueryExpansionPrompt, RelationshipPrompt,
+};
+pub use types::{Concept, ConceptType, ExtractionResponse, RelationType, Relationship};
 
 #[cfg(feature = "local")]
 mod ollama;
@@ -3,11 +3,12 @@
 use serde::{Deserialize, Serialize};
 
 /// Type of concept extracted by LLM.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum ConceptType {
     /// A named entity (person, place, organization).
     Entity,
     /// A scientific or technical concept.
+    #[default]
     Concept,
     /// A process or action.
     Process,
@@ -19,12 +20,6 @@ pub enum ConceptType {
     Other,
 }
 
-impl Default for ConceptType {
-    fn default() -> Self {
-        Self::Concept
-    }
-}
-
 /// A concept extracted from text by an LLM.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Concept {
@@ -78,7 +73,7 @@ impl Concept {
 }
 
 /// Type of relationship between concepts.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum RelationType {
     /// A is a type of B (hypernym).
     IsA,
@@ -91,6 +86,7 @@ pub enum RelationType {
     /// A requires B.
     Requires,
     /// A is related to B (general).
+    #[default]
     RelatedTo,
     /// A produces B.
     Produces,
@@ -104,12 +100,6 @@ pub enum RelationType {
     Custom(String),
 }
 
-impl Default for RelationType {
-    fn default() -> Self {
-        Self::RelatedTo
-    }
-}
-
 /// A relationship between two concepts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Relationship {
@@ -1,13 +1,22 @@
 //! Phago MCP Server — Model Context Protocol interface for the
 //! biological knowledge graph.
 //!
-//! Provides three tools:
+//! Provides eight tools:
 //! - `phago_remember`: Ingest documents into the colony
 //! - `phago_recall`: Hybrid query with TF-IDF + graph scoring
-//! - `phago_explore`: Structural graph queries (paths, centrality, bridges, stats)
+//! - `phago_explore`: Structural graph queries (paths, centrality, bridges, communities, stats)
+//! - `phago_suggest`: Autocomplete concept labels by prefix
+//! - `phago_connect`: Link two concepts without ingesting a document
+//! - `phago_annotate`: Attach a free-text note to a concept
+//! - `phago_spaces`: List the named knowledge spaces
+//! - `phago_status`: Report when each space was last saved
 //!
-//! Uses a dedicated worker thread for Colony operations (Colony is not
-//! Send+Sync due to trait object agents).
+//! Each named space is a separate colony on its own worker thread
+//! (Colony is not Send+Sync due to trait object agents).
+//!
+//! Operational metrics can be exported for Prometheus on a separate port
+//! (see [`metrics`]).
 
+pub mod metrics;
 pub mod tools;
 pub mod worker;
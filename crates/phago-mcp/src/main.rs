@@ -4,7 +4,27 @@
 //! Cursor, or any MCP client to interact with the biological knowledge graph.
 //!
 //! Usage:
-//!   phago-mcp [--db path/to/knowledge.db]
+//!   phago-mcp [--db path/to/knowledge.db] [--max-spaces N] [--metrics-port PORT]
+//!             [--query-cache N] [--save-every-ticks N] [--save-every-secs N]
+//!             [--save-after-edits N] [--synonyms path/to/synonyms.tsv]
+//!
+//! Each knowledge space gets its own database next to `--db`
+//! (`knowledge.db` for the default space, `knowledge.<space>.db` for others).
+//!
+//! Spaces are saved when unloaded, and with the `--save-*` options also
+//! as they are edited. Documents remembered since the last save are
+//! journaled next to the database and recovered after a crash.
+//!
+//! With `--metrics-port` the server also answers Prometheus scrapes at
+//! `http://<metrics-host>:<port>/metrics`.
+//!
+//! With `--synonyms` concepts named by an alias are stored under their
+//! canonical term and recalls also match synonyms of the query's terms;
+//! `phago_synonyms` replaces a space's dictionary, or re-reads the file,
+//! without a restart.
+//!
+//! With `--query-cache` repeated recalls are answered from a per-space
+//! cache until the space's graph changes.
 //!
 //! Claude Desktop config example:
 //! ```json
@@ -21,8 +41,11 @@
 use anyhow::Result;
 use clap::Parser;
 use phago_mcp::tools::PhagoTools;
-use phago_mcp::worker::ColonyHandle;
+use phago_mcp::worker::{AutoSave, ColonyHandle, DEFAULT_MAX_RESIDENT};
+use phago_runtime::metrics::PromRegistry;
 use rmcp::{transport::stdio, ServiceExt};
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "phago-mcp")]
@@ -32,13 +55,71 @@ struct Args {
     /// If omitted, knowledge is stored in memory only.
     #[arg(long)]
     db: Option<String>,
+
+    /// Maximum number of knowledge spaces kept in memory at once.
+    /// Least recently used spaces are saved and unloaded beyond this.
+    #[arg(long, default_value_t = DEFAULT_MAX_RESIDENT)]
+    max_spaces: usize,
+
+    /// Serve Prometheus metrics on this port.
+    #[arg(long)]
+    metrics_port: Option<u16>,
+
+    /// Address the metrics exporter binds to.
+    #[arg(long, default_value = "127.0.0.1")]
+    metrics_host: String,
+
+    /// Cache up to this many recall results per space, reused until the
+    /// space's graph changes (0 disables the cache).
+    #[arg(long, default_value_t = 0)]
+    query_cache: usize,
+
+    /// Save a space once this many ticks have run since its last save.
+    #[arg(long)]
+    save_every_ticks: Option<u64>,
+
+    /// Save a space once this many seconds have passed since its last
+    /// save. Checked whenever the space is edited.
+    #[arg(long)]
+    save_every_secs: Option<u64>,
+
+    /// Save a space after this many edits.
+    #[arg(long)]
+    save_after_edits: Option<usize>,
+
+    /// Synonym dictionary: a canonical term and its aliases per line,
+    /// tab-separated, or a JSON object from canonical terms to aliases.
+    #[arg(long)]
+    synonyms: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
-    let handle = ColonyHandle::spawn(args.db);
+    let mut handle = ColonyHandle::with_max_resident(args.db, args.max_spaces)
+        .with_query_cache(args.query_cache)
+        .with_auto_save(AutoSave {
+            every_ticks: args.save_every_ticks,
+            every: args.save_every_secs.map(Duration::from_secs),
+            after_edits: args.save_after_edits,
+        });
+    if let Some(path) = args.synonyms {
+        handle = handle.with_synonyms(path);
+        if let Err(e) = handle.load_synonyms() {
+            eprintln!("Warning: Failed to load synonyms: {e}");
+        }
+    }
+    if let Some(port) = args.metrics_port {
+        let registry = Arc::new(PromRegistry::new());
+        let listener = tokio::net::TcpListener::bind((args.metrics_host.as_str(), port)).await?;
+        eprintln!(
+            "Serving metrics on http://{}/metrics",
+            listener.local_addr()?
+        );
+        tokio::spawn(phago_mcp::metrics::serve(listener, registry.clone()));
+        handle = handle.with_metrics(registry);
+    }
     let tools = PhagoTools::new(handle);
 
     let service = tools.serve(stdio()).await?;
@@ -0,0 +1,106 @@
+//! Standalone Prometheus exporter.
+//!
+//! The MCP server talks to its client over stdio, so metrics are served on
+//! a separate port (`--metrics-port`). This is a minimal HTTP/1.1 responder:
+//! `GET /metrics` returns the registry, anything else a 404, and every
+//! connection is closed after one response.
+
+use phago_runtime::metrics::prometheus::CONTENT_TYPE;
+use phago_runtime::metrics::PromRegistry;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest request head accepted before the connection is dropped.
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Serve `registry` on `listener` until the task is dropped.
+pub async fn serve(listener: TcpListener, registry: Arc<PromRegistry>) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let registry = registry.clone();
+        tokio::spawn(async move {
+            if let Err(e) = respond(stream, &registry).await {
+                eprintln!("Warning: metrics request failed: {e}");
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, registry: &PromRegistry) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || head.len() + n > MAX_REQUEST_BYTES {
+            return Ok(());
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let request_line = String::from_utf8_lossy(&head);
+    let mut parts = request_line.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", CONTENT_TYPE, registry.encode()),
+        _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::ColonyHandle;
+    use phago_rag::mcp::RememberRequest;
+
+    async fn fetch(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn serves_every_space() {
+        let registry = Arc::new(PromRegistry::new());
+        let handle = ColonyHandle::spawn(None).with_metrics(registry.clone());
+        for space in ["alpha", "beta"] {
+            handle
+                .remember(
+                    space,
+                    RememberRequest {
+                        title: "Cells".to_string(),
+                        content: "cell membrane protein transport".to_string(),
+                        ticks: Some(5),
+                        tags: Vec::new(),
+                        ttl_ticks: None,
+                        ttl_seconds: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, registry));
+
+        let response = fetch(addr, "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("phago_colony_tick{space=\"alpha\"} 5"));
+        assert!(response.contains("phago_colony_tick{space=\"beta\"} 5"));
+
+        assert!(fetch(addr, "/").await.starts_with("HTTP/1.1 404 Not Found"));
+    }
+}
@@ -3,13 +3,18 @@
 //! Wraps the existing `phago_rag::mcp` functions as MCP tools
 //! accessible via the rmcp protocol.
 
-use crate::worker::ColonyHandle;
+use crate::worker::{ColonyHandle, ReadOnlyError, DEFAULT_SPACE};
+use phago_rag::assemble::AssembleConfig;
+use phago_runtime::colony::GraphEditError;
+use phago_runtime::community::CommunityAlgorithm;
+use phago_runtime::sampling::SampleStrategy;
 use rmcp::{
     handler::server::router::tool::ToolRouter, handler::server::wrapper::Parameters, model::*,
     schemars, tool, tool_handler, tool_router, ServerHandler,
 };
 use serde::Deserialize;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 type McpError = rmcp::model::ErrorData;
 
@@ -36,6 +41,17 @@ pub struct RememberParams {
     pub content: String,
     /// Number of simulation ticks to run for digestion (default: 15).
     pub ticks: Option<u64>,
+    /// Tags for the document (e.g. "work"); recall can be scoped to them.
+    pub tags: Option<Vec<String>>,
+    /// Forget the document this many ticks after storing it; what only it
+    /// contributed fades from the graph.
+    pub ttl_ticks: Option<u64>,
+    /// Forget the document this many seconds after storing it. Expiry is
+    /// checked as the colony ticks, i.e. on the next remember. Documents,
+    /// and so their TTLs, are not kept in the SQLite database.
+    pub ttl_seconds: Option<u64>,
+    /// Knowledge space to store the document in (default: "default").
+    pub space: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -44,21 +60,197 @@ pub struct RecallParams {
     pub query: String,
     /// Maximum number of results to return (default: 10).
     pub max_results: Option<usize>,
-    /// Balance between TF-IDF (1.0) and graph-based (0.0) scoring (default: 0.5).
+    /// Weight of TF-IDF scoring: 1.0 is pure TF-IDF, 0.0 pure graph (default: 0.5).
     pub alpha: Option<f64>,
+    /// Only return concepts from documents with at least one of these tags.
+    pub tags: Option<Vec<String>>,
+    /// Attach the document passages each concept came from (default: false).
+    pub include_passages: Option<bool>,
+    /// Most passages per result when include_passages is set (default: 3).
+    pub max_passages: Option<usize>,
+    /// next_cursor from the previous response, to get the next page of the
+    /// same query.
+    pub cursor: Option<String>,
+    /// Also return the results' passages assembled into cited context,
+    /// ready to put in a prompt (default: false).
+    pub assemble: Option<bool>,
+    /// Most tokens the assembled context may take (default: 2000).
+    pub token_budget: Option<usize>,
+    /// Add the strongest related concepts from the graph to the assembled
+    /// context (default: true).
+    pub include_graph_context: Option<bool>,
+    /// Knowledge space to search (default: "default").
+    pub space: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ExploreParams {
-    /// Type of structural query: "path", "centrality", "bridges", or "stats".
+    /// Type of structural query: "path", "centrality", "bridges", "stats",
+    /// "node", "edge", "communities", "anomalies" or "regions".
     #[serde(rename = "type")]
     pub query_type: String,
-    /// Source concept (required for "path" queries).
+    /// Concept to describe (required for "node" queries).
+    pub label: Option<String>,
+    /// Source concept (required for "path" and "edge" queries).
     pub from: Option<String>,
-    /// Target concept (required for "path" queries).
+    /// Target concept (required for "path" and "edge" queries).
     pub to: Option<String>,
-    /// Number of top results (for "centrality" and "bridges", default: 10).
+    /// Path cost for "path" queries: "strongest" (default, strongest chain
+    /// of associations), "hops", "inverse" or "complement".
+    pub cost: Option<String>,
+    /// Number of alternative paths for "path" queries (default: 1).
+    pub k: Option<usize>,
+    /// Number of top results (for "centrality", "bridges", "communities"
+    /// and "anomalies", and neighbors for "node", default: 10).
     pub top_k: Option<usize>,
+    /// Lowest anomaly score to list for "anomalies" queries (default: all).
+    pub min_score: Option<f64>,
+    /// Centrality measure: "betweenness" (default) or "pagerank".
+    pub method: Option<String>,
+    /// Community detection for "communities" queries: "louvain" or
+    /// "label-propagation" (default: the space's configured algorithm).
+    pub algorithm: Option<String>,
+    /// Label propagation or "forest" sample seed (default: 0).
+    pub seed: Option<u64>,
+    /// Louvain resolution; higher finds smaller communities (default: 1.0).
+    pub resolution: Option<f64>,
+    /// For "stats" queries, also report how much of the graph a sample
+    /// would show: "top" (most accessed), "ego" (around `seeds`), "weight"
+    /// (edges of at least `min_weight`) or "forest" (forest fire).
+    pub sample: Option<String>,
+    /// Nodes in the sample (default: 500).
+    pub sample_size: Option<usize>,
+    /// Concepts an "ego" sample is centered on.
+    pub seeds: Option<Vec<String>>,
+    /// Hops out from the seeds of an "ego" sample (default: 2).
+    pub hops: Option<usize>,
+    /// Weakest edge a "weight" sample keeps.
+    pub min_weight: Option<f64>,
+    /// Knowledge space to explore (default: "default").
+    pub space: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SuggestParams {
+    /// Beginning of a concept label (case-insensitive).
+    pub prefix: String,
+    /// Maximum number of suggestions (default: 10).
+    pub limit: Option<usize>,
+    /// Knowledge space to search (default: "default").
+    pub space: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ConnectParams {
+    /// Label of the first concept.
+    pub from_label: String,
+    /// Label of the second concept.
+    pub to_label: String,
+    /// Weight of a new edge, or how much an existing one is strengthened
+    /// by, in (0, 1] (default: 0.5).
+    pub weight: Option<f64>,
+    /// Relation the edge is read as, "from <relation> to" (e.g. "part_of").
+    pub relation: Option<String>,
+    /// Create concepts for labels that do not exist yet (default: false).
+    pub create_missing: Option<bool>,
+    /// Knowledge space to edit (default: "default").
+    pub space: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AnnotateParams {
+    /// Label of the concept to annotate.
+    pub label: String,
+    /// Free-text note to attach.
+    pub note: String,
+    /// Knowledge space to edit (default: "default").
+    pub space: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SynonymsParams {
+    /// Canonical terms mapped to their aliases, e.g. {"myocardial
+    /// infarction": ["heart attack", "MI"]}. Replaces the space's
+    /// dictionary; omit to read the server's --synonyms file again.
+    pub synonyms: Option<BTreeMap<String, Vec<String>>>,
+    /// How much a match through a synonym counts against a match of the
+    /// term as typed, in (0, 1] (default: unchanged, initially 0.5).
+    pub expansion_weight: Option<f64>,
+    /// Knowledge space to edit (default: "default").
+    pub space: Option<String>,
+}
+
+/// Error result for a failed graph edit: the caller's fault if the colony
+/// rejected the edit or the server is read-only, ours otherwise.
+fn edit_error(action: &str, e: anyhow::Error) -> McpError {
+    if e.is::<ReadOnlyError>() {
+        return McpError {
+            code: ErrorCode::INVALID_REQUEST,
+            message: Cow::from(e.to_string()),
+            data: None,
+        };
+    }
+    match e.downcast_ref::<GraphEditError>() {
+        Some(e) => McpError {
+            code: ErrorCode::INVALID_PARAMS,
+            message: Cow::from(e.to_string()),
+            data: None,
+        },
+        None => McpError {
+            code: ErrorCode::INTERNAL_ERROR,
+            message: Cow::from(format!("{action} failed: {e}")),
+            data: None,
+        },
+    }
+}
+
+/// The sample a "stats" explore query asks to cover, if any.
+fn sample_strategy(params: &ExploreParams) -> Result<Option<SampleStrategy>, McpError> {
+    let invalid = |message: String| McpError {
+        code: ErrorCode::INVALID_PARAMS,
+        message: Cow::from(message),
+        data: None,
+    };
+    let n = params.sample_size.unwrap_or(500);
+    let strategy = match params.sample.as_deref() {
+        None => return Ok(None),
+        Some("top") => SampleStrategy::TopByAccess { n },
+        Some("ego") => match &params.seeds {
+            Some(seeds) if !seeds.is_empty() => SampleStrategy::EgoNetworks {
+                seeds: seeds.clone(),
+                hops: params.hops.unwrap_or(2),
+                n,
+            },
+            _ => return Err(invalid("'seeds' is required for ego samples".into())),
+        },
+        Some("weight") => SampleStrategy::WeightThreshold {
+            min_edge_weight: params
+                .min_weight
+                .ok_or_else(|| invalid("'min_weight' is required for weight samples".into()))?,
+            max_nodes: n,
+        },
+        Some("forest") => SampleStrategy::ForestFire {
+            seed: params.seed.unwrap_or(0),
+            n,
+        },
+        Some(other) => {
+            return Err(invalid(format!(
+                "Unknown sample '{other}'. Use: top, ego, weight, forest"
+            )))
+        }
+    };
+    Ok(Some(strategy))
+}
+
+/// Resolve the space a request targets.
+fn space_of(space: &Option<String>) -> Result<&str, McpError> {
+    let space = space.as_deref().unwrap_or(DEFAULT_SPACE);
+    crate::worker::validate_space_name(space).map_err(|e| McpError {
+        code: ErrorCode::INVALID_PARAMS,
+        message: Cow::from(e),
+        data: None,
+    })?;
+    Ok(space)
 }
 
 #[tool_router]
@@ -75,24 +267,28 @@ impl PhagoTools {
     /// co-activation, and build a self-organizing knowledge structure.
     #[tool(
         name = "phago_remember",
-        description = "Ingest a document into the biological knowledge graph. Agents digest text into concepts and wire them via Hebbian co-activation learning."
+        description = "Ingest a document into the biological knowledge graph. Agents digest text into concepts and wire them via Hebbian co-activation learning. Remembering an existing title updates that document; a note duplicating a document under another title is not stored, and the response names the original in duplicate_of."
     )]
     async fn remember(
         &self,
         params: Parameters<RememberParams>,
     ) -> Result<CallToolResult, McpError> {
         let params = params.0;
+        let space = space_of(&params.space)?.to_string();
         let req = phago_rag::mcp::RememberRequest {
             title: params.title,
             content: params.content,
             ticks: params.ticks,
+            tags: params.tags.unwrap_or_default(),
+            ttl_ticks: params.ttl_ticks,
+            ttl_seconds: params.ttl_seconds,
         };
 
-        let resp = self.handle.remember(req).await.map_err(|e| McpError {
-            code: ErrorCode::INTERNAL_ERROR,
-            message: Cow::from(format!("Remember failed: {e}")),
-            data: None,
-        })?;
+        let resp = self
+            .handle
+            .remember(&space, req)
+            .await
+            .map_err(|e| edit_error("Remember", e))?;
 
         let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(json)]))
@@ -103,22 +299,51 @@ impl PhagoTools {
     /// structural importance in the knowledge graph.
     #[tool(
         name = "phago_recall",
-        description = "Query the knowledge graph with hybrid TF-IDF + graph-topology scoring. Returns concepts ranked by combined text and structural relevance."
+        description = "Query the knowledge graph with hybrid TF-IDF + graph-topology scoring. Returns concepts ranked by combined text and structural relevance, optionally with the source passages they came from, or with those passages assembled into cited, token-budgeted context (assemble). Results come a page of max_results at a time: pass next_cursor back as cursor for the next page. cursor_expired means the graph changed and the results are a fresh first page."
     )]
     async fn recall(&self, params: Parameters<RecallParams>) -> Result<CallToolResult, McpError> {
         let params = params.0;
+        let space = space_of(&params.space)?.to_string();
+        let defaults = AssembleConfig::default();
         let req = phago_rag::mcp::RecallRequest {
             query: params.query,
             max_results: params.max_results.unwrap_or(10),
             alpha: params.alpha.unwrap_or(0.5),
+            tags: params.tags,
+            include_passages: params.include_passages.unwrap_or(false),
+            max_passages: params.max_passages.unwrap_or(3),
+            cursor: params.cursor,
+            assemble: params.assemble.unwrap_or(false),
+            assembly: AssembleConfig {
+                token_budget: params.token_budget.unwrap_or(defaults.token_budget),
+                include_graph_context: params
+                    .include_graph_context
+                    .unwrap_or(defaults.include_graph_context),
+                ..defaults
+            },
         };
-
-        let resp = self.handle.recall(req).await.map_err(|e| McpError {
-            code: ErrorCode::INTERNAL_ERROR,
-            message: Cow::from(format!("Recall failed: {e}")),
+        req.hybrid_config().map_err(|e| McpError {
+            code: ErrorCode::INVALID_PARAMS,
+            message: Cow::from(e.to_string()),
             data: None,
         })?;
 
+        let resp = self.handle.recall(&space, req).await.map_err(|e| {
+            match e.downcast_ref::<phago_rag::hybrid::HybridConfigError>() {
+                // A malformed cursor, or one issued for another query
+                Some(e) => McpError {
+                    code: ErrorCode::INVALID_PARAMS,
+                    message: Cow::from(e.to_string()),
+                    data: None,
+                },
+                None => McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: Cow::from(format!("Recall failed: {e}")),
+                    data: None,
+                },
+            }
+        })?;
+
         let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
@@ -128,10 +353,11 @@ impl PhagoTools {
     /// clusters, or get colony statistics.
     #[tool(
         name = "phago_explore",
-        description = "Explore the graph structure. Supports: 'path' (shortest path between concepts), 'centrality' (hub nodes), 'bridges' (cross-cluster connectors), 'stats' (colony metrics)."
+        description = "Explore the graph structure. Supports: 'path' (strongest or shortest paths between concepts, with the weight of each hop), 'centrality' (hub nodes, by betweenness or pagerank), 'bridges' (cross-cluster connectors), 'stats' (colony metrics, and how much of the graph a 'sample' would show), 'node' (one concept's notes and strongest neighbors), 'edge' (an edge's weight and the documents or queries that reinforced it lately), 'communities' (clusters of related concepts, with stable IDs), 'anomalies' (what sentinels found novel, most novel first), 'regions' (named zones of the field with their decay and access policies)."
     )]
     async fn explore(&self, params: Parameters<ExploreParams>) -> Result<CallToolResult, McpError> {
         let params = params.0;
+        let space = space_of(&params.space)?.to_string();
         let req = match params.query_type.as_str() {
             "path" => {
                 let from = params.from.ok_or_else(|| McpError {
@@ -144,33 +370,267 @@ impl PhagoTools {
                     message: Cow::from("'to' is required for path queries"),
                     data: None,
                 })?;
-                phago_rag::mcp::ExploreRequest::ShortestPath { from, to }
+                let cost = match params.cost.as_deref() {
+                    Some(cost) => cost.parse().map_err(|e: String| McpError {
+                        code: ErrorCode::INVALID_PARAMS,
+                        message: Cow::from(e),
+                        data: None,
+                    })?,
+                    None => Default::default(),
+                };
+                phago_rag::mcp::ExploreRequest::ShortestPath {
+                    from,
+                    to,
+                    cost,
+                    k: params.k.unwrap_or(1),
+                }
             }
-            "centrality" => phago_rag::mcp::ExploreRequest::Centrality {
+            "centrality" => {
+                let method = match params.method.as_deref() {
+                    Some(method) => method.parse().map_err(|e: String| McpError {
+                        code: ErrorCode::INVALID_PARAMS,
+                        message: Cow::from(e),
+                        data: None,
+                    })?,
+                    None => Default::default(),
+                };
+                phago_rag::mcp::ExploreRequest::Centrality {
+                    top_k: params.top_k.unwrap_or(10),
+                    method,
+                }
+            }
+            "bridges" => phago_rag::mcp::ExploreRequest::Bridges {
                 top_k: params.top_k.unwrap_or(10),
             },
-            "bridges" => phago_rag::mcp::ExploreRequest::Bridges {
+            "stats" => phago_rag::mcp::ExploreRequest::Stats {
+                sample: sample_strategy(&params)?,
+            },
+            "node" => phago_rag::mcp::ExploreRequest::Node {
+                label: params.label.ok_or_else(|| McpError {
+                    code: ErrorCode::INVALID_PARAMS,
+                    message: Cow::from("'label' is required for node queries"),
+                    data: None,
+                })?,
+                top_k: params.top_k.unwrap_or(10),
+            },
+            "edge" => phago_rag::mcp::ExploreRequest::Edge {
+                from: params.from.ok_or_else(|| McpError {
+                    code: ErrorCode::INVALID_PARAMS,
+                    message: Cow::from("'from' is required for edge queries"),
+                    data: None,
+                })?,
+                to: params.to.ok_or_else(|| McpError {
+                    code: ErrorCode::INVALID_PARAMS,
+                    message: Cow::from("'to' is required for edge queries"),
+                    data: None,
+                })?,
+            },
+            "communities" => {
+                let algorithm = match params.algorithm.as_deref() {
+                    Some(algorithm) => Some(
+                        algorithm
+                            .parse::<CommunityAlgorithm>()
+                            .map_err(|e| McpError {
+                                code: ErrorCode::INVALID_PARAMS,
+                                message: Cow::from(e),
+                                data: None,
+                            })?
+                            .with_params(params.seed, params.resolution),
+                    ),
+                    None => None,
+                };
+                phago_rag::mcp::ExploreRequest::Communities {
+                    algorithm,
+                    top_k: params.top_k.unwrap_or(10),
+                }
+            }
+            "anomalies" => phago_rag::mcp::ExploreRequest::Anomalies {
                 top_k: params.top_k.unwrap_or(10),
+                min_score: params.min_score,
             },
-            "stats" => phago_rag::mcp::ExploreRequest::Stats,
+            "regions" => phago_rag::mcp::ExploreRequest::Regions,
             other => {
                 return Err(McpError {
                     code: ErrorCode::INVALID_PARAMS,
                     message: Cow::from(format!(
-                        "Unknown explore type '{other}'. Use: path, centrality, bridges, stats"
+                        "Unknown explore type '{other}'. Use: path, centrality, bridges, stats, node, edge, communities, anomalies, regions"
                     )),
                     data: None,
                 });
             }
         };
 
-        let resp = self.handle.explore(req).await.map_err(|e| McpError {
+        let resp = self
+            .handle
+            .explore(&space, req)
+            .await
+            .map_err(|e| McpError {
+                code: ErrorCode::INTERNAL_ERROR,
+                message: Cow::from(format!("Explore failed: {e}")),
+                data: None,
+            })?;
+
+        let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// Complete a concept label: labels starting with the prefix, ranked by
+    /// usage and connectivity, with their strongest neighbors.
+    #[tool(
+        name = "phago_suggest",
+        description = "Autocomplete concept labels. Returns labels starting with the prefix, ranked by usage and connectivity, each with its node type and strongest neighbors."
+    )]
+    async fn suggest(&self, params: Parameters<SuggestParams>) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let space = space_of(&params.space)?.to_string();
+        let req = phago_rag::mcp::SuggestRequest {
+            prefix: params.prefix,
+            limit: params.limit.unwrap_or(10),
+        };
+
+        let resp = self
+            .handle
+            .suggest(&space, req)
+            .await
+            .map_err(|e| McpError {
+                code: ErrorCode::INTERNAL_ERROR,
+                message: Cow::from(format!("Suggest failed: {e}")),
+                data: None,
+            })?;
+
+        let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// Connect two concepts the caller knows are related, even though no
+    /// document mentions both.
+    #[tool(
+        name = "phago_connect",
+        description = "Create or strengthen an edge between two concepts you know are related, without ingesting a document. Fails if a label does not exist unless create_missing is set. The edge decays like any other unless documents or queries reinforce it."
+    )]
+    async fn connect(&self, params: Parameters<ConnectParams>) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let space = space_of(&params.space)?.to_string();
+        let req = phago_rag::mcp::ConnectRequest {
+            from_label: params.from_label,
+            to_label: params.to_label,
+            weight: params.weight.unwrap_or(0.5),
+            relation: params.relation,
+            create_missing: params.create_missing.unwrap_or(false),
+        };
+
+        let resp = self
+            .handle
+            .connect(&space, req)
+            .await
+            .map_err(|e| edit_error("Connect", e))?;
+
+        let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// Attach a free-text note to a concept.
+    #[tool(
+        name = "phago_annotate",
+        description = "Attach a free-text note to a concept. Notes are returned with the concept by phago_recall and by phago_explore with type 'node'."
+    )]
+    async fn annotate(
+        &self,
+        params: Parameters<AnnotateParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let space = space_of(&params.space)?.to_string();
+        let req = phago_rag::mcp::AnnotateRequest {
+            label: params.label,
+            note: params.note,
+        };
+
+        let resp = self
+            .handle
+            .annotate(&space, req)
+            .await
+            .map_err(|e| edit_error("Annotate", e))?;
+
+        let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// Replace a space's synonym dictionary without restarting the server.
+    #[tool(
+        name = "phago_synonyms",
+        description = "Replace the synonym dictionary of a space: canonical terms mapped to their aliases. Concepts named by an alias are merged into the canonical term's concept, later documents are stored under the canonical term, and phago_recall also matches synonyms of the query's terms, ranked below exact matches. Omit 'synonyms' to re-read the server's dictionary file."
+    )]
+    async fn synonyms(
+        &self,
+        params: Parameters<SynonymsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let params = params.0;
+        let space = space_of(&params.space)?.to_string();
+        let invalid = |message: &str| McpError {
+            code: ErrorCode::INVALID_PARAMS,
+            message: Cow::from(message.to_string()),
+            data: None,
+        };
+        if params
+            .expansion_weight
+            .is_some_and(|w| !(w > 0.0 && w <= 1.0))
+        {
+            return Err(invalid("'expansion_weight' must be above 0 and at most 1"));
+        }
+        let synonyms = match params.synonyms {
+            Some(entries) => entries.into(),
+            None => self
+                .handle
+                .load_synonyms()
+                .map_err(|e| McpError {
+                    code: ErrorCode::INTERNAL_ERROR,
+                    message: Cow::from(format!("Loading synonyms failed: {e}")),
+                    data: None,
+                })?
+                .ok_or_else(|| {
+                    invalid("'synonyms' is required when the server has no --synonyms file")
+                })?,
+        };
+        let req = phago_rag::mcp::SynonymsRequest {
+            synonyms,
+            expansion_weight: params.expansion_weight,
+        };
+
+        let resp = self
+            .handle
+            .set_synonyms(&space, req)
+            .await
+            .map_err(|e| edit_error("Synonyms", e))?;
+
+        let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// List knowledge spaces: every loaded space plus those persisted next
+    /// to the database, with statistics for the loaded ones.
+    #[tool(
+        name = "phago_spaces",
+        description = "List knowledge spaces (separate colonies, e.g. one per project) with their statistics. Pass 'space' to the other tools to use one."
+    )]
+    async fn spaces(&self) -> Result<CallToolResult, McpError> {
+        let spaces = self.handle.spaces().await.map_err(|e| McpError {
             code: ErrorCode::INTERNAL_ERROR,
-            message: Cow::from(format!("Explore failed: {e}")),
+            message: Cow::from(format!("Listing spaces failed: {e}")),
             data: None,
         })?;
 
-        let json = serde_json::to_string_pretty(&resp).unwrap_or_default();
+        let json = serde_json::to_string_pretty(&spaces).unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    /// Report how recently each persisted space was saved and how many
+    /// remember requests it has journaled since.
+    #[tool(
+        name = "phago_status",
+        description = "Report the persistence of each saved knowledge space: seconds since it was last saved, and how many remembered documents are journaled but not yet saved."
+    )]
+    async fn status(&self) -> Result<CallToolResult, McpError> {
+        let json = serde_json::to_string_pretty(&self.handle.status()).unwrap_or_default();
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 }
@@ -193,7 +653,13 @@ impl ServerHandler for PhagoTools {
             },
             instructions: Some(
                 "Phago biological knowledge graph. Use phago_remember to ingest documents, \
-                 phago_recall to query knowledge, and phago_explore to analyze graph structure."
+                 phago_recall to query knowledge, phago_suggest to complete concept names, \
+                 and phago_explore to analyze graph structure. \
+                 Use phago_connect to link concepts you know are related, phago_annotate \
+                 to attach notes to a concept and phago_synonyms to say which terms mean \
+                 the same. \
+                 Pass a 'space' name to keep projects in separate colonies; phago_spaces lists them \
+                 and phago_status reports when they were last saved."
                     .into(),
             ),
         }
@@ -1,112 +1,1253 @@
-//! Colony worker thread.
+//! Colony worker threads, one per knowledge space.
 //!
-//! Colony contains `Box<dyn Agent>` (not Send+Sync), so it must live
-//! on a dedicated thread. Commands are sent via `mpsc`, responses via
-//! `oneshot`.
+//! Colony contains `Box<dyn Agent>` (not Send+Sync), so each colony must
+//! live on a dedicated thread. Each space runs on an [`AsyncColonyRunner`]
+//! that ticks only when a request asks it to.
+//!
+//! A space is a named, independent colony. Every space gets its own worker
+//! thread, so requests to different spaces run concurrently. With a base
+//! `--db` path each space persists to its own SQLite file next to it, and
+//! only the most recently used spaces stay resident; the others are saved
+//! and their threads shut down until they are needed again. Without a
+//! database every space stays in memory.
+//!
+//! With a metrics registry attached ([`ColonyHandle::with_metrics`]) every
+//! space reports to it under its own `space` label.
+//!
+//! With a query cache ([`ColonyHandle::with_query_cache`]) every space
+//! answers repeated recalls from its own cache until its graph changes.
+//!
+//! With a synonym dictionary ([`ColonyHandle::with_synonyms`]) every space
+//! canonicalizes concepts and expands recalls with it, read again whenever
+//! a space loads; [`ColonyHandle::set_synonyms`] replaces a loaded space's.
+//!
+//! Every space keeps a read view of its colony, refreshed after each
+//! request, that structural explores are answered from.
+//!
+//! A persisted space is saved by writing a new database and renaming it
+//! over the old one, so an interrupted save never damages the last good
+//! file. Besides when unloaded, spaces save themselves as their
+//! [`AutoSave`] policy asks. Remember requests are journaled next to the
+//! database before they are served; a journal outliving the last save (the
+//! server stopped without saving) is replayed when the space is loaded.
+//!
+//! A space can also be served from a colony someone else runs
+//! ([`ColonyHandle::with_shared_space`]), such as the web dashboard's in
+//! `phago serve`. Such a space is never evicted, and is saved by its owner.
 
+use phago_core::substrate::Substrate;
+use phago_core::types::Tick;
+use phago_rag::cache::QueryCache;
 use phago_rag::mcp::{
-    ExploreRequest, ExploreResponse, RecallRequest, RecallResponse, RememberRequest,
-    RememberResponse,
+    AnnotateRequest, AnnotateResponse, ConnectRequest, ConnectResponse, ExploreRequest,
+    ExploreResponse, RecallRequest, RecallResponse, RememberRequest, RememberResponse,
+    SuggestRequest, SuggestResponse, SynonymsRequest, SynonymsResponse,
+};
+use phago_runtime::async_runtime::{
+    AsyncColonyRunner, RunnerColony, RunnerConfig, SharedColony, TickRate,
 };
-use phago_runtime::colony::Colony;
-use std::sync::mpsc;
-use tokio::sync::oneshot;
+use phago_runtime::colony::{Colony, ColonyStats};
+use phago_runtime::colony_builder::{self, ColonyBuilder};
+use phago_runtime::metrics::PromRegistry;
+use phago_runtime::read_view::ViewRefresh;
+use phago_runtime::synonyms::{SynonymConfig, SynonymMap};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// Commands sent to the colony worker thread.
-pub enum ColonyCommand {
-    Remember {
-        req: RememberRequest,
-        tx: oneshot::Sender<RememberResponse>,
-    },
-    Recall {
-        req: RecallRequest,
-        tx: oneshot::Sender<RecallResponse>,
-    },
-    Explore {
-        req: ExploreRequest,
-        tx: oneshot::Sender<ExploreResponse>,
-    },
+/// Space used when a request does not name one.
+pub const DEFAULT_SPACE: &str = "default";
+
+/// Default number of spaces kept resident at once.
+pub const DEFAULT_MAX_RESIDENT: usize = 4;
+
+/// Longest accepted space name.
+const MAX_SPACE_NAME_LEN: usize = 64;
+
+/// Appended to a space's database path to name its journal.
+const JOURNAL_SUFFIX: &str = ".journal.jsonl";
+
+/// A knowledge space as reported by `phago_spaces`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpaceInfo {
+    pub name: String,
+    /// Whether the space's colony is currently loaded.
+    pub resident: bool,
+    /// SQLite file backing the space, if persistence is enabled.
+    pub db_path: Option<String>,
+    /// Colony statistics; only available for resident spaces.
+    pub stats: Option<ColonyStats>,
+}
+
+/// A space's persistence as reported by `phago_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpaceStatus {
+    pub name: String,
+    pub resident: bool,
+    /// Seconds since the space was last saved; `None` if it never was.
+    pub last_save_age_secs: Option<f64>,
+    /// Remember requests journaled since the last save, replayed if the
+    /// server stops before saving them.
+    pub journal_backlog: usize,
+}
+
+/// When persisted spaces save themselves, besides when they are unloaded.
+///
+/// The policy is checked after every edit (remember, connect, annotate);
+/// a space saves once any of its limits is reached. By default spaces only
+/// save when unloaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AutoSave {
+    /// Save once this many ticks have run since the last save.
+    pub every_ticks: Option<u64>,
+    /// Save once this much time has passed since the last save.
+    pub every: Option<Duration>,
+    /// Save after this many edits.
+    pub after_edits: Option<usize>,
+}
+
+impl AutoSave {
+    fn is_due(&self, ticks: u64, elapsed: Duration, edits: usize) -> bool {
+        self.every_ticks.is_some_and(|n| ticks >= n)
+            || self.every.is_some_and(|d| elapsed >= d)
+            || self.after_edits.is_some_and(|n| edits >= n)
+    }
+}
+
+/// Check that a space name is usable as part of a file name.
+pub fn validate_space_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_SPACE_NAME_LEN {
+        return Err(format!(
+            "space name must be 1-{MAX_SPACE_NAME_LEN} characters long"
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "invalid space name '{name}': use letters, digits, '-' and '_'"
+        ));
+    }
+    Ok(())
+}
+
+/// Returned by edits to a [`ColonyHandle`] opened read-only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOnlyError;
+
+impl std::fmt::Display for ReadOnlyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "knowledge spaces are read-only")
+    }
 }
 
-/// Handle to the colony worker thread.
+impl std::error::Error for ReadOnlyError {}
+
+/// What requests to a space go through: its colony, recall cache and,
+/// when persisted, its store.
+#[derive(Clone)]
+struct Space {
+    colony: SharedColony,
+    cache: Option<Arc<QueryCache>>,
+    store: Option<Arc<SpaceStore>>,
+}
+
+/// A loaded space and its LRU bookkeeping.
+struct ResidentSpace {
+    space: Space,
+    /// Worker thread running the space; `None` for shared spaces, which
+    /// are never evicted.
+    thread: Option<JoinHandle<()>>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Spaces {
+    resident: HashMap<String, ResidentSpace>,
+    /// Threads of evicted spaces that may still be saving their colony.
+    evicted: HashMap<String, JoinHandle<()>>,
+    clock: u64,
+}
+
+/// Handle to the colony worker threads.
 #[derive(Clone)]
 pub struct ColonyHandle {
-    cmd_tx: mpsc::Sender<ColonyCommand>,
+    db_path: Option<PathBuf>,
+    max_resident: usize,
+    spaces: Arc<Mutex<Spaces>>,
+    metrics: Option<Arc<PromRegistry>>,
+    /// Recall results cached per space; 0 disables caching.
+    query_cache: usize,
+    read_only: bool,
+    auto_save: AutoSave,
+    /// Synonym dictionary every space is loaded with.
+    synonyms_path: Option<PathBuf>,
 }
 
 impl ColonyHandle {
-    /// Spawn a new colony worker thread and return a handle.
+    /// Create a handle with the default residency limit.
     pub fn spawn(db_path: Option<String>) -> Self {
-        let (cmd_tx, cmd_rx) = mpsc::channel();
-
-        std::thread::spawn(move || {
-            let mut colony = if let Some(ref _path) = db_path {
-                // SQLite persistence requires phago-runtime's "sqlite" feature.
-                // ColonyBuilder::with_persistence is a no-op without it.
-                use phago_runtime::colony_builder::ColonyBuilder;
-                match ColonyBuilder::new()
-                    .with_persistence(_path)
-                    .auto_save(true)
-                    .build()
-                {
-                    Ok(pc) => pc.into_inner(),
-                    Err(e) => {
-                        eprintln!("Warning: Failed to open database: {e}. Using in-memory colony.");
-                        Colony::new()
-                    }
+        Self::with_max_resident(db_path, DEFAULT_MAX_RESIDENT)
+    }
+
+    /// Create a handle keeping at most `max_resident` spaces loaded.
+    ///
+    /// The limit only applies when `db_path` is set; in-memory spaces have
+    /// nowhere to be saved and are never evicted.
+    pub fn with_max_resident(db_path: Option<String>, max_resident: usize) -> Self {
+        Self {
+            db_path: db_path.map(PathBuf::from),
+            max_resident: max_resident.max(1),
+            spaces: Arc::new(Mutex::new(Spaces::default())),
+            metrics: None,
+            query_cache: 0,
+            read_only: false,
+            auto_save: AutoSave::default(),
+            synonyms_path: None,
+        }
+    }
+
+    /// Report every space's colony to `registry`. Applies to spaces loaded
+    /// from now on.
+    pub fn with_metrics(mut self, registry: Arc<PromRegistry>) -> Self {
+        self.metrics = Some(registry);
+        self
+    }
+
+    /// Cache up to `capacity` recall results per space, reused until the
+    /// space's graph changes. Applies to spaces loaded from now on.
+    pub fn with_query_cache(mut self, capacity: usize) -> Self {
+        self.query_cache = capacity;
+        self
+    }
+
+    /// Reject remember, connect and annotate with [`ReadOnlyError`].
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Save persisted spaces as `policy` asks, besides when they are
+    /// unloaded. Applies to spaces loaded from now on.
+    pub fn with_auto_save(mut self, policy: AutoSave) -> Self {
+        self.auto_save = policy;
+        self
+    }
+
+    /// Canonicalize concepts and expand recalls with the synonym dictionary
+    /// at `path` (TSV, or JSON if it ends in `.json`), read again whenever
+    /// a space loads. Applies to spaces loaded from now on.
+    pub fn with_synonyms(mut self, path: impl Into<PathBuf>) -> Self {
+        self.synonyms_path = Some(path.into());
+        self
+    }
+
+    /// The dictionary [`with_synonyms`](Self::with_synonyms) names, read
+    /// now; `None` without one.
+    pub fn load_synonyms(&self) -> anyhow::Result<Option<SynonymMap>> {
+        self.synonyms_path
+            .as_deref()
+            .map(|path| {
+                SynonymMap::load(path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))
+            })
+            .transpose()
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Serve `space` from a colony run elsewhere, so that requests from
+    /// this handle and the colony's other users are served in turn. The
+    /// space stays resident and is not saved by this handle.
+    pub fn with_shared_space(self, space: &str, colony: SharedColony) -> Self {
+        let cache = (self.query_cache > 0).then(|| Arc::new(QueryCache::new(self.query_cache)));
+        self.spaces.lock().unwrap().resident.insert(
+            space.to_string(),
+            ResidentSpace {
+                space: Space {
+                    colony,
+                    cache,
+                    store: None,
+                },
+                thread: None,
+                last_used: 0,
+            },
+        );
+        self
+    }
+
+    /// SQLite file for a space: the base path itself for the default space,
+    /// `<stem>.<space>.<ext>` next to it for the others.
+    pub fn space_db_path(&self, space: &str) -> Option<PathBuf> {
+        let base = self.db_path.as_ref()?;
+        if space == DEFAULT_SPACE {
+            return Some(base.clone());
+        }
+        let stem = base.file_stem()?.to_string_lossy();
+        let name = match base.extension() {
+            Some(ext) => format!("{stem}.{space}.{}", ext.to_string_lossy()),
+            None => format!("{stem}.{space}"),
+        };
+        Some(base.with_file_name(name))
+    }
+
+    /// Ingest a document into a space.
+    pub async fn remember(
+        &self,
+        space: &str,
+        req: RememberRequest,
+    ) -> anyhow::Result<RememberResponse> {
+        let Space { colony, store, .. } = self.writable(space)?;
+        colony
+            .with(move |colony| {
+                if let Some(store) = &store {
+                    store.journal(&req);
+                }
+                phago_rag::mcp::phago_remember(colony, &req)
+            })
+            .await
+            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))
+    }
+
+    /// Query a space's knowledge graph.
+    pub async fn recall(&self, space: &str, req: RecallRequest) -> anyhow::Result<RecallResponse> {
+        let Space { colony, cache, .. } = self.space(space)?;
+        let resp = colony
+            .with(move |colony| match &cache {
+                Some(cache) => phago_rag::mcp::phago_recall_cached(colony, cache, &req),
+                None => phago_rag::mcp::phago_recall(colony, &req),
+            })
+            .await
+            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))?;
+        Ok(resp?)
+    }
+
+    /// Explore a space's graph structure.
+    ///
+    /// Structural queries run on the space's read view, off the worker
+    /// thread, so a slow centrality or community pass never holds up other
+    /// requests to the space.
+    pub async fn explore(
+        &self,
+        space: &str,
+        req: ExploreRequest,
+    ) -> anyhow::Result<ExploreResponse> {
+        let colony = self.space(space)?.colony;
+        if let Some(view) = colony.view() {
+            if !req.needs_colony() {
+                return tokio::task::spawn_blocking(move || {
+                    phago_rag::mcp::phago_explore_view(&view, &req)
+                        .expect("structural explore answered from the view")
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("Explore task failed: {e}"));
+            }
+        }
+        colony
+            .query(move |colony| phago_rag::mcp::phago_explore(colony, &req))
+            .await
+            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))
+    }
+
+    /// Complete a label prefix in a space.
+    pub async fn suggest(
+        &self,
+        space: &str,
+        req: SuggestRequest,
+    ) -> anyhow::Result<SuggestResponse> {
+        self.space(space)?
+            .colony
+            .query(move |colony| phago_rag::mcp::phago_suggest(colony, &req))
+            .await
+            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))
+    }
+
+    /// Connect two concepts in a space. Invalid requests fail with a
+    /// [`GraphEditError`](phago_runtime::colony::GraphEditError), and all
+    /// requests with [`ReadOnlyError`] on a read-only handle.
+    pub async fn connect(
+        &self,
+        space: &str,
+        req: ConnectRequest,
+    ) -> anyhow::Result<ConnectResponse> {
+        let resp = self
+            .writable(space)?
+            .colony
+            .with(move |colony| phago_rag::mcp::phago_connect(colony, &req))
+            .await
+            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))?;
+        Ok(resp?)
+    }
+
+    /// Attach a note to a concept in a space. Invalid requests fail with a
+    /// [`GraphEditError`](phago_runtime::colony::GraphEditError), and all
+    /// requests with [`ReadOnlyError`] on a read-only handle.
+    pub async fn annotate(
+        &self,
+        space: &str,
+        req: AnnotateRequest,
+    ) -> anyhow::Result<AnnotateResponse> {
+        let resp = self
+            .writable(space)?
+            .colony
+            .with(move |colony| phago_rag::mcp::phago_annotate(colony, &req))
+            .await
+            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))?;
+        Ok(resp?)
+    }
+
+    /// Replace the synonym dictionary of a space. Fails with
+    /// [`ReadOnlyError`] on a read-only handle.
+    pub async fn set_synonyms(
+        &self,
+        space: &str,
+        req: SynonymsRequest,
+    ) -> anyhow::Result<SynonymsResponse> {
+        self.writable(space)?
+            .colony
+            .with(move |colony| phago_rag::mcp::phago_synonyms(colony, &req))
+            .await
+            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))
+    }
+
+    /// List resident spaces and spaces persisted next to the base database,
+    /// sorted by name. Only resident spaces report statistics; listing does
+    /// not load anything.
+    pub async fn spaces(&self) -> anyhow::Result<Vec<SpaceInfo>> {
+        let mut listing: BTreeMap<String, Option<SharedColony>> = self
+            .persisted_spaces()
+            .into_iter()
+            .map(|name| (name, None))
+            .collect();
+        {
+            let spaces = self.spaces.lock().unwrap();
+            for (name, space) in &spaces.resident {
+                listing.insert(name.clone(), Some(space.space.colony.clone()));
+            }
+        }
+
+        let mut infos = Vec::with_capacity(listing.len());
+        for (name, colony) in listing {
+            let stats = match colony {
+                Some(colony) => colony.query(|colony| colony.stats()).await.ok(),
+                None => None,
+            };
+            infos.push(SpaceInfo {
+                db_path: self
+                    .space_db_path(&name)
+                    .map(|p| p.to_string_lossy().into_owned()),
+                resident: stats.is_some(),
+                stats,
+                name,
+            });
+        }
+        Ok(infos)
+    }
+
+    /// How recently each space listed by [`spaces`](Self::spaces) was
+    /// saved and how many remember requests it has journaled since. Spaces
+    /// kept in memory only are not listed.
+    pub fn status(&self) -> Vec<SpaceStatus> {
+        let mut listing: BTreeMap<String, Option<Arc<SpaceStore>>> = self
+            .persisted_spaces()
+            .into_iter()
+            .map(|name| (name, None))
+            .collect();
+        {
+            let spaces = self.spaces.lock().unwrap();
+            for (name, space) in &spaces.resident {
+                if let Some(store) = &space.space.store {
+                    listing.insert(name.clone(), Some(store.clone()));
                 }
-            } else {
-                Colony::new()
+            }
+        }
+
+        listing
+            .into_iter()
+            .filter_map(|(name, store)| {
+                let resident = store.is_some();
+                let store = match store {
+                    Some(store) => store,
+                    None => Arc::new(SpaceStore::new(self.space_db_path(&name)?)),
+                };
+                let (last_save, journal_backlog) = store.status();
+                Some(SpaceStatus {
+                    name,
+                    resident,
+                    last_save_age_secs: last_save.map(|at| {
+                        SystemTime::now()
+                            .duration_since(at)
+                            .unwrap_or_default()
+                            .as_secs_f64()
+                    }),
+                    journal_backlog,
+                })
+            })
+            .collect()
+    }
+
+    /// Space names with a database or journal next to the base path, and
+    /// those of evicted spaces that may still be saving.
+    fn persisted_spaces(&self) -> Vec<String> {
+        let Some(base) = self.db_path.as_ref() else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = self
+            .spaces
+            .lock()
+            .unwrap()
+            .evicted
+            .keys()
+            .cloned()
+            .collect();
+        let mut journal = base.clone().into_os_string();
+        journal.push(JOURNAL_SUFFIX);
+        if base.exists() || Path::new(&journal).exists() {
+            names.push(DEFAULT_SPACE.to_string());
+        }
+
+        let Some(stem) = base.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+            return names;
+        };
+        let suffix = base
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        let dir = match base.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return names;
+        };
+
+        let prefix = format!("{stem}.");
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let Some(space) = file_name.strip_prefix(&prefix).and_then(|rest| {
+                rest.strip_suffix(JOURNAL_SUFFIX)
+                    .unwrap_or(rest)
+                    .strip_suffix(&suffix)
+            }) else {
+                continue;
             };
+            if space != DEFAULT_SPACE && validate_space_name(space).is_ok() {
+                names.push(space.to_string());
+            }
+        }
+        names
+    }
+
+    /// A space to edit, unless the handle is read-only.
+    fn writable(&self, space: &str) -> anyhow::Result<Space> {
+        if self.read_only {
+            return Err(ReadOnlyError.into());
+        }
+        self.space(space)
+    }
+
+    /// A space, loading it (and evicting the least recently used space if
+    /// over the limit) when necessary.
+    fn space(&self, space: &str) -> anyhow::Result<Space> {
+        validate_space_name(space).map_err(|e| anyhow::anyhow!(e))?;
+
+        let mut spaces = self.spaces.lock().unwrap();
+        spaces.clock += 1;
+        let now = spaces.clock;
 
-            while let Ok(cmd) = cmd_rx.recv() {
-                match cmd {
-                    ColonyCommand::Remember { req, tx } => {
-                        let resp = phago_rag::mcp::phago_remember(&mut colony, &req);
-                        let _ = tx.send(resp);
-                    }
-                    ColonyCommand::Recall { req, tx } => {
-                        let resp = phago_rag::mcp::phago_recall(&colony, &req);
-                        let _ = tx.send(resp);
-                    }
-                    ColonyCommand::Explore { req, tx } => {
-                        let resp = phago_rag::mcp::phago_explore(&colony, &req);
-                        let _ = tx.send(resp);
-                    }
+        if let Some(resident) = spaces.resident.get_mut(space) {
+            resident.last_used = now;
+            return Ok(resident.space.clone());
+        }
+
+        if self.db_path.is_some() {
+            while spaces.resident.len() >= self.max_resident {
+                let Some(lru) = spaces
+                    .resident
+                    .iter()
+                    .filter(|(_, s)| s.thread.is_some())
+                    .min_by_key(|(_, s)| s.last_used)
+                    .map(|(name, _)| name.clone())
+                else {
+                    break;
+                };
+                // Dropping the handle lets the runner drain its queue, save and exit
+                if let Some(thread) = spaces.resident.remove(&lru).and_then(|s| s.thread) {
+                    spaces.evicted.insert(lru, thread);
                 }
             }
+        }
+        spaces.evicted.retain(|_, thread| !thread.is_finished());
+
+        let previous = spaces.evicted.remove(space);
+        let store = self
+            .space_db_path(space)
+            .map(|path| Arc::new(SpaceStore::new(path)));
+        let name = space.to_string();
+        let metrics = self.metrics.clone();
+        let auto_save = self.auto_save;
+        let synonyms = self.load_synonyms().unwrap_or_else(|e| {
+            eprintln!("Warning: Failed to load synonyms for space '{space}': {e}");
+            None
+        });
+        let config = RunnerConfig {
+            view: Some(ViewRefresh::EveryTick),
+            ..RunnerConfig::new(TickRate::Manual)
+        };
+        let worker_store = store.clone();
+        let (runner, thread) = AsyncColonyRunner::spawn(config, move || {
+            // Never load a space while its previous worker is still saving it
+            if let Some(previous) = previous {
+                let _ = previous.join();
+            }
+            Some(SpaceColony::open(
+                name,
+                worker_store,
+                metrics,
+                auto_save,
+                synonyms,
+            ))
         });
 
-        Self { cmd_tx }
+        let loaded = Space {
+            colony: runner.shared(),
+            cache: (self.query_cache > 0).then(|| Arc::new(QueryCache::new(self.query_cache))),
+            store,
+        };
+        spaces.resident.insert(
+            space.to_string(),
+            ResidentSpace {
+                space: loaded.clone(),
+                thread: Some(thread),
+                last_used: now,
+            },
+        );
+        Ok(loaded)
     }
+}
 
-    /// Ingest a document into the colony.
-    pub async fn remember(&self, req: RememberRequest) -> anyhow::Result<RememberResponse> {
-        let (tx, rx) = oneshot::channel();
-        self.cmd_tx
-            .send(ColonyCommand::Remember { req, tx })
-            .map_err(|_| anyhow::anyhow!("Colony worker thread has shut down"))?;
-        rx.await
-            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))
+/// A remember request in a space's journal.
+#[derive(Serialize, Deserialize)]
+struct JournalEntry {
+    /// When the request was journaled, in nanoseconds since the Unix epoch.
+    at: u128,
+    request: RememberRequest,
+}
+
+/// Where a persisted space is saved: its database, and the journal of
+/// remember requests served since the database was last written.
+///
+/// Only the space's worker writes either, so the journal holds exactly the
+/// requests the database lacks; were the server to stop between a save and
+/// the journal being cleared, the journal would end before the save and
+/// not be replayed.
+struct SpaceStore {
+    db_path: PathBuf,
+    journal_path: PathBuf,
+    /// When the database was last written, and how many requests have
+    /// been journaled since; read from disk on first use.
+    state: Mutex<Option<(Option<SystemTime>, usize)>>,
+}
+
+impl SpaceStore {
+    fn new(db_path: PathBuf) -> Self {
+        let mut journal_path = db_path.clone().into_os_string();
+        journal_path.push(JOURNAL_SUFFIX);
+        Self {
+            db_path,
+            journal_path: journal_path.into(),
+            state: Mutex::new(None),
+        }
     }
 
-    /// Query the knowledge graph.
-    pub async fn recall(&self, req: RecallRequest) -> anyhow::Result<RecallResponse> {
-        let (tx, rx) = oneshot::channel();
-        self.cmd_tx
-            .send(ColonyCommand::Recall { req, tx })
-            .map_err(|_| anyhow::anyhow!("Colony worker thread has shut down"))?;
-        rx.await
-            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))
+    /// When the database was last saved and the journal's length.
+    fn status(&self) -> (Option<SystemTime>, usize) {
+        let cached = *self.state.lock().unwrap();
+        cached.unwrap_or_else(|| self.refresh())
     }
 
-    /// Explore the graph structure.
-    pub async fn explore(&self, req: ExploreRequest) -> anyhow::Result<ExploreResponse> {
-        let (tx, rx) = oneshot::channel();
-        self.cmd_tx
-            .send(ColonyCommand::Explore { req, tx })
-            .map_err(|_| anyhow::anyhow!("Colony worker thread has shut down"))?;
-        rx.await
-            .map_err(|_| anyhow::anyhow!("Colony worker dropped response"))
+    /// Read the status from disk.
+    fn refresh(&self) -> (Option<SystemTime>, usize) {
+        let saved = std::fs::metadata(&self.db_path)
+            .and_then(|m| m.modified())
+            .ok();
+        let status = (saved, self.entries().len());
+        *self.state.lock().unwrap() = Some(status);
+        status
+    }
+
+    /// Append `req` to the journal, before it is served.
+    fn journal(&self, req: &RememberRequest) {
+        let status = self.status();
+        let entry = JournalEntry {
+            at: nanos_since_epoch(SystemTime::now()),
+            request: req.clone(),
+        };
+        let line = serde_json::to_string(&entry).expect("journal entries serialize");
+        let appended = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.journal_path)
+            .and_then(|mut file| {
+                writeln!(file, "{line}")?;
+                file.sync_data()
+            });
+        match appended {
+            Ok(()) => *self.state.lock().unwrap() = Some((status.0, status.1 + 1)),
+            Err(e) => eprintln!(
+                "Warning: Failed to journal '{}' to {}: {e}",
+                req.title,
+                self.journal_path.display()
+            ),
+        }
+    }
+
+    /// The journaled requests, skipping a line torn by a crash.
+    fn entries(&self) -> Vec<JournalEntry> {
+        let Ok(journal) = std::fs::read_to_string(&self.journal_path) else {
+            return Vec::new();
+        };
+        journal
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    /// The colony saved last, or an empty one if there is none.
+    fn load(&self) -> Result<Colony, String> {
+        if !self.db_path.exists() {
+            return Ok(ColonyBuilder::new().build_simple());
+        }
+        ColonyBuilder::new()
+            .with_persistence(&self.db_path)
+            .build()
+            .map(|pc| pc.into_inner())
+            .map_err(|e| e.to_string())
+    }
+
+    /// The journaled requests, if they are newer than the last save.
+    fn unsaved(&self) -> Vec<JournalEntry> {
+        // A previous worker may have saved since the status was read
+        let (saved, _) = self.refresh();
+        let entries = self.entries();
+        let newer = match (entries.last(), saved) {
+            (Some(tail), Some(saved)) => tail.at > nanos_since_epoch(saved),
+            (tail, None) => tail.is_some(),
+            (None, _) => false,
+        };
+        if newer {
+            entries
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Save `colony` over the database and clear the journal.
+    fn save(&self, colony: &Colony) -> Result<(), String> {
+        colony_builder::save_atomically(colony, &self.db_path).map_err(|e| e.to_string())?;
+        let saved = std::fs::metadata(&self.db_path)
+            .and_then(|m| m.modified())
+            .unwrap_or_else(|_| SystemTime::now());
+        *self.state.lock().unwrap() = Some((Some(saved), 0));
+        match std::fs::remove_file(&self.journal_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn nanos_since_epoch(at: SystemTime) -> u128 {
+    at.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos()
+}
+
+/// A space's colony, owned by its runner. Saved as its [`AutoSave`] policy
+/// asks, and when the runner stops, once every handle to it is gone.
+struct SpaceColony {
+    name: String,
+    colony: Colony,
+    /// `None` for spaces kept in memory only.
+    store: Option<Arc<SpaceStore>>,
+    auto_save: AutoSave,
+    /// Tick and time of the last save, and edits since.
+    saved_tick: Tick,
+    saved_at: Instant,
+    edits: usize,
+}
+
+impl SpaceColony {
+    /// Load a space with `synonyms`, replaying remember requests journaled
+    /// after its last save. Falls back to memory if the database fails.
+    fn open(
+        name: String,
+        mut store: Option<Arc<SpaceStore>>,
+        metrics: Option<Arc<PromRegistry>>,
+        auto_save: AutoSave,
+        synonyms: Option<SynonymMap>,
+    ) -> Self {
+        let mut colony = match store.as_ref().map(|store| store.load()) {
+            Some(Ok(colony)) => colony,
+            Some(Err(e)) => {
+                eprintln!(
+                    "Warning: Failed to open database for space '{name}': {e}. Using in-memory colony."
+                );
+                store = None;
+                ColonyBuilder::new().build_simple()
+            }
+            None => ColonyBuilder::new().build_simple(),
+        };
+        if let Some(registry) = metrics {
+            colony.attach_metrics(registry, &name);
+        }
+        if let Some(map) = synonyms {
+            colony.set_synonyms(SynonymConfig {
+                map,
+                ..colony.synonyms().clone()
+            });
+        }
+
+        let unsaved = store.as_ref().map(|s| s.unsaved()).unwrap_or_default();
+        let mut space = Self {
+            saved_tick: colony.substrate().current_tick(),
+            name,
+            colony,
+            store,
+            auto_save,
+            saved_at: Instant::now(),
+            edits: 0,
+        };
+        if !unsaved.is_empty() {
+            eprintln!(
+                "Replaying {} journaled requests into space '{}'.",
+                unsaved.len(),
+                space.name
+            );
+            for entry in &unsaved {
+                phago_rag::mcp::phago_remember(&mut space.colony, &entry.request);
+            }
+            space.edits = unsaved.len();
+            space.save();
+        }
+        space
+    }
+
+    fn save(&mut self) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        match store.save(&self.colony) {
+            Ok(()) => {
+                self.saved_tick = self.colony.substrate().current_tick();
+                self.saved_at = Instant::now();
+                self.edits = 0;
+            }
+            Err(e) => eprintln!("Warning: Failed to save space '{}': {e}", self.name),
+        }
+    }
+}
+
+impl RunnerColony for SpaceColony {
+    fn colony(&self) -> &Colony {
+        &self.colony
+    }
+
+    fn colony_mut(&mut self) -> &mut Colony {
+        &mut self.colony
+    }
+
+    fn after_edit(&mut self) {
+        self.edits += 1;
+        let ticks = self.colony.substrate().current_tick() - self.saved_tick;
+        if self
+            .auto_save
+            .is_due(ticks, self.saved_at.elapsed(), self.edits)
+        {
+            self.save();
+        }
+    }
+}
+
+impl Drop for SpaceColony {
+    fn drop(&mut self) {
+        if self.edits > 0 || self.colony.substrate().current_tick() != self.saved_tick {
+            self.save();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_runtime::colony::GraphEditError;
+
+    fn remember(title: &str, content: &str) -> RememberRequest {
+        RememberRequest {
+            title: title.to_string(),
+            content: content.to_string(),
+            ticks: Some(15),
+            tags: Vec::new(),
+            ttl_ticks: None,
+            ttl_seconds: None,
+        }
+    }
+
+    fn recall(query: &str) -> RecallRequest {
+        RecallRequest {
+            query: query.to_string(),
+            max_results: 10,
+            alpha: 0.5,
+            tags: None,
+            include_passages: false,
+            max_passages: 3,
+            cursor: None,
+            assemble: false,
+            assembly: Default::default(),
+        }
+    }
+
+    #[test]
+    fn space_names_are_validated() {
+        assert!(validate_space_name("project-a_2").is_ok());
+        assert!(validate_space_name("").is_err());
+        assert!(validate_space_name("../etc").is_err());
+        assert!(validate_space_name("a b").is_err());
+    }
+
+    #[test]
+    fn space_databases_sit_next_to_the_base_path() {
+        let handle = ColonyHandle::spawn(Some("/data/knowledge.db".to_string()));
+        assert_eq!(
+            handle.space_db_path(DEFAULT_SPACE),
+            Some(PathBuf::from("/data/knowledge.db"))
+        );
+        assert_eq!(
+            handle.space_db_path("work"),
+            Some(PathBuf::from("/data/knowledge.work.db"))
+        );
+        assert_eq!(ColonyHandle::spawn(None).space_db_path("work"), None);
+    }
+
+    #[tokio::test]
+    async fn spaces_are_isolated() {
+        let handle = ColonyHandle::spawn(None);
+        handle
+            .remember(
+                "alpha",
+                remember("Cells", "cell membrane protein transport"),
+            )
+            .await
+            .unwrap();
+        handle
+            .remember("beta", remember("Stars", "galaxy nebula stellar fusion"))
+            .await
+            .unwrap();
+
+        let alpha = handle
+            .recall("alpha", recall("galaxy nebula"))
+            .await
+            .unwrap();
+        assert!(alpha.results.iter().all(|r| r.label != "galaxy"));
+        let beta = handle
+            .recall("beta", recall("galaxy nebula"))
+            .await
+            .unwrap();
+        assert!(beta.results.iter().any(|r| r.label == "galaxy"));
+
+        let names: Vec<String> = handle
+            .spaces()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["alpha", "beta"]);
+    }
+
+    #[tokio::test]
+    async fn evicted_spaces_are_saved_and_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("knowledge.db");
+        let handle = ColonyHandle::with_max_resident(Some(base.to_string_lossy().into_owned()), 1);
+
+        handle
+            .remember(
+                "alpha",
+                remember("Cells", "cell membrane protein transport"),
+            )
+            .await
+            .unwrap();
+        // Loading beta evicts alpha, which is saved to its own file
+        handle
+            .remember("beta", remember("Stars", "galaxy nebula stellar fusion"))
+            .await
+            .unwrap();
+
+        let spaces = handle.spaces().await.unwrap();
+        let alpha = spaces.iter().find(|s| s.name == "alpha").unwrap();
+        assert!(!alpha.resident);
+        assert!(spaces.iter().find(|s| s.name == "beta").unwrap().resident);
+
+        let reloaded = handle.recall("alpha", recall("membrane")).await.unwrap();
+        assert!(reloaded.results.iter().any(|r| r.label == "membrane"));
+        assert!(dir.path().join("knowledge.alpha.db").exists());
+        // Reloading alpha evicted beta, which may still be saving
+        let names: Vec<String> = handle
+            .spaces()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["alpha", "beta"]);
+    }
+
+    #[tokio::test]
+    async fn journaled_documents_survive_a_crash() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = Some(
+            dir.path()
+                .join("knowledge.db")
+                .to_string_lossy()
+                .into_owned(),
+        );
+        let handle = ColonyHandle::spawn(base.clone());
+        let documents = [
+            ("Cells", "cell membrane protein transport", "membrane"),
+            ("Stars", "galaxy nebula stellar fusion", "galaxy"),
+            ("Division", "cell division mitosis spindle", "mitosis"),
+        ];
+        for (title, content, _) in documents {
+            handle
+                .remember(DEFAULT_SPACE, remember(title, content))
+                .await
+                .unwrap();
+        }
+        let status = handle.status();
+        assert_eq!(status[0].journal_backlog, 3);
+        assert!(status[0].last_save_age_secs.is_none());
+
+        // Stopped without unloading the space: nothing is saved
+        std::mem::forget(handle);
+
+        let restarted = ColonyHandle::spawn(base);
+        for (_, _, concept) in documents {
+            let recalled = restarted
+                .recall(DEFAULT_SPACE, recall(concept))
+                .await
+                .unwrap();
+            assert!(
+                recalled.results.iter().any(|r| r.label == concept),
+                "{concept} was not recovered"
+            );
+        }
+        // Replayed and saved, so not replayed again
+        let status = restarted.status();
+        assert_eq!(status[0].journal_backlog, 0);
+        assert!(status[0].last_save_age_secs.is_some());
+    }
+
+    #[tokio::test]
+    async fn spaces_save_as_their_policy_asks() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("knowledge.db");
+        let handle = ColonyHandle::spawn(Some(base.to_string_lossy().into_owned())).with_auto_save(
+            AutoSave {
+                after_edits: Some(2),
+                ..AutoSave::default()
+            },
+        );
+
+        handle
+            .remember(
+                DEFAULT_SPACE,
+                remember("Cells", "cell membrane protein transport"),
+            )
+            .await
+            .unwrap();
+        assert!(!base.exists());
+        assert_eq!(handle.status()[0].journal_backlog, 1);
+
+        handle
+            .remember(
+                DEFAULT_SPACE,
+                remember("Stars", "galaxy nebula stellar fusion"),
+            )
+            .await
+            .unwrap();
+        assert!(base.exists());
+        let status = handle.status();
+        assert_eq!(status[0].journal_backlog, 0);
+        assert!(status[0].last_save_age_secs.is_some());
+        assert!(!dir.path().join("knowledge.db.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn shared_spaces_serve_a_colony_run_elsewhere() {
+        let (runner, thread) =
+            AsyncColonyRunner::spawn(RunnerConfig::new(TickRate::Manual), || Some(Colony::new()));
+        let handle = ColonyHandle::with_max_resident(None, 1)
+            .with_shared_space(DEFAULT_SPACE, runner.shared());
+
+        handle
+            .remember(
+                DEFAULT_SPACE,
+                remember("Cells", "cell membrane protein transport"),
+            )
+            .await
+            .unwrap();
+        let nodes = runner
+            .query(|colony| colony.stats().graph_nodes)
+            .await
+            .unwrap();
+        assert!(nodes > 0);
+
+        // Loading another space does not evict the shared one
+        handle
+            .remember("other", remember("Stars", "galaxy nebula stellar fusion"))
+            .await
+            .unwrap();
+        let spaces = handle.spaces().await.unwrap();
+        assert!(spaces.iter().all(|s| s.resident));
+
+        let read_only = handle.clone().with_read_only(true);
+        let err = read_only
+            .remember(DEFAULT_SPACE, remember("Division", "cell division mitosis"))
+            .await
+            .unwrap_err();
+        assert!(err.is::<ReadOnlyError>());
+        let recalled = read_only
+            .recall(DEFAULT_SPACE, recall("membrane"))
+            .await
+            .unwrap();
+        assert!(recalled.results.iter().any(|r| r.label == "membrane"));
+
+        drop((handle, read_only));
+        runner.stop();
+        thread.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn cached_recall_sees_new_knowledge() {
+        let handle = ColonyHandle::spawn(None).with_query_cache(8);
+        handle
+            .remember(
+                DEFAULT_SPACE,
+                remember("Cells", "cell membrane protein transport"),
+            )
+            .await
+            .unwrap();
+        let first = handle
+            .recall(DEFAULT_SPACE, recall("cell mitosis"))
+            .await
+            .unwrap();
+        let again = handle
+            .recall(DEFAULT_SPACE, recall("cell mitosis"))
+            .await
+            .unwrap();
+        assert_eq!(first.total_nodes, again.total_nodes);
+        assert!(first.results.iter().all(|r| r.label != "mitosis"));
+
+        handle
+            .remember(
+                DEFAULT_SPACE,
+                remember("Division", "cell division mitosis spindle"),
+            )
+            .await
+            .unwrap();
+        let after = handle
+            .recall(DEFAULT_SPACE, recall("cell mitosis"))
+            .await
+            .unwrap();
+        assert!(after.total_nodes > first.total_nodes);
+        assert!(after.results.iter().any(|r| r.label == "mitosis"));
+    }
+
+    #[tokio::test]
+    async fn connected_concepts_raise_graph_scores() {
+        let handle = ColonyHandle::spawn(None).with_query_cache(8);
+        handle
+            .remember(
+                DEFAULT_SPACE,
+                remember("Cells", "cell membrane protein transport"),
+            )
+            .await
+            .unwrap();
+        handle
+            .remember(
+                DEFAULT_SPACE,
+                remember("Stars", "galaxy nebula stellar fusion"),
+            )
+            .await
+            .unwrap();
+        let graph_score = |resp: &RecallResponse| {
+            resp.results
+                .iter()
+                .find(|r| r.label == "galaxy")
+                .map(|r| r.graph_score)
+                .unwrap()
+        };
+        let before = handle
+            .recall(DEFAULT_SPACE, recall("galaxy"))
+            .await
+            .unwrap();
+
+        let err = handle
+            .connect(
+                DEFAULT_SPACE,
+                ConnectRequest {
+                    from_label: "galaxy".into(),
+                    to_label: " ".into(),
+                    weight: 0.5,
+                    relation: None,
+                    create_missing: false,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<GraphEditError>().is_some());
+
+        let connected = handle
+            .connect(
+                DEFAULT_SPACE,
+                ConnectRequest {
+                    from_label: "galaxy".into(),
+                    to_label: "membrane".into(),
+                    weight: 1.0,
+                    relation: Some("inspired".into()),
+                    create_missing: false,
+                },
+            )
+            .await
+            .unwrap();
+        assert!(connected.created_edge);
+        handle
+            .annotate(
+                DEFAULT_SPACE,
+                AnnotateRequest {
+                    label: "galaxy".into(),
+                    note: "named in the outreach talk".into(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let after = handle
+            .recall(DEFAULT_SPACE, recall("galaxy"))
+            .await
+            .unwrap();
+        assert_eq!(after.total_edges, before.total_edges + 1);
+        assert!(
+            graph_score(&after) > graph_score(&before),
+            "{} <= {}",
+            graph_score(&after),
+            graph_score(&before)
+        );
+        let galaxy = after.results.iter().find(|r| r.label == "galaxy").unwrap();
+        assert_eq!(galaxy.annotations[0].text, "named in the outreach talk");
     }
 }
@@ -17,16 +17,26 @@ from phago._phago import (
     Colony,
     ColonyConfig,
     ColonyStats,
+    LabelSuggestion,
     Position,
     QueryResult,
+    Snapshot,
+    SnapshotAgent,
+    SnapshotEdge,
+    SnapshotNode,
 )
 
 __all__ = [
     "Colony",
     "ColonyConfig",
     "ColonyStats",
+    "LabelSuggestion",
     "Position",
     "QueryResult",
+    "Snapshot",
+    "SnapshotAgent",
+    "SnapshotEdge",
+    "SnapshotNode",
 ]
 
 __version__ = "0.2.0"
@@ -141,7 +141,7 @@ class PhagoKnowledgeStore(BaseDocumentStore):
         Args:
             query: Search query
             max_results: Maximum number of results
-            alpha: Balance between TF-IDF (0) and graph (1) scores
+            alpha: Weight of TF-IDF scoring (1.0 = pure TF-IDF, 0.0 = pure graph)
 
         Returns:
             List of NodeWithScore objects
@@ -195,7 +195,7 @@ class PhagoRetriever:
         Args:
             colony: Existing colony or None to create new
             k: Number of results to retrieve
-            alpha: Balance between TF-IDF and graph scores
+            alpha: Weight of TF-IDF scoring (1.0 = pure TF-IDF, 0.0 = pure graph)
         """
         self.colony = colony or Colony()
         self.k = k
@@ -2,14 +2,23 @@
 //!
 //! This crate provides Python bindings via PyO3 for the core Phago
 //! functionality including Colony management, document ingestion,
-//! hybrid queries, and graph exploration.
+//! hybrid queries, and graph exploration. For notebooks, a colony converts
+//! to networkx node-link data and renders the phago-viz player as HTML.
 
 use phago_agents::digester::Digester;
+use phago_agents::registry::{AgentRegistry, AgentSpec};
+use phago_core::substrate::Substrate;
 use phago_core::types::Position as CorePosition;
 use phago_rag::{hybrid_query, HybridConfig};
-use phago_runtime::colony::{Colony as RustColony, ColonyConfig as RustColonyConfig};
-use pyo3::exceptions::PyValueError;
+use phago_runtime::colony::{
+    Colony as RustColony, ColonyConfig as RustColonyConfig, ColonySnapshot,
+    ColonyStats as RustColonyStats,
+};
+use phago_runtime::snapshot::{SnapshotMode, SnapshotSeries};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyList, PyTuple};
+use serde_json::Value;
 
 /// Python-friendly Position class.
 #[pyclass]
@@ -105,8 +114,36 @@ impl QueryResult {
     }
 }
 
+/// Label completion returned by `Colony.suggest`.
+#[pyclass]
+pub struct LabelSuggestion {
+    #[pyo3(get)]
+    pub label: String,
+    #[pyo3(get)]
+    pub node_type: String,
+    #[pyo3(get)]
+    pub access_count: u64,
+    #[pyo3(get)]
+    pub degree: usize,
+    #[pyo3(get)]
+    pub score: f64,
+    #[pyo3(get)]
+    pub top_neighbors: Vec<String>,
+}
+
+#[pymethods]
+impl LabelSuggestion {
+    fn __repr__(&self) -> String {
+        format!(
+            "LabelSuggestion(label='{}', node_type={}, score={:.3})",
+            self.label, self.node_type, self.score
+        )
+    }
+}
+
 /// Colony statistics.
 #[pyclass]
+#[derive(Clone)]
 pub struct ColonyStats {
     #[pyo3(get)]
     pub tick: u64,
@@ -138,6 +175,235 @@ impl ColonyStats {
     }
 }
 
+impl From<&RustColonyStats> for ColonyStats {
+    fn from(s: &RustColonyStats) -> Self {
+        ColonyStats {
+            tick: s.tick,
+            agents_alive: s.agents_alive,
+            agents_died: s.agents_died,
+            total_spawned: s.total_spawned,
+            graph_nodes: s.graph_nodes,
+            graph_edges: s.graph_edges,
+            total_signals: s.total_signals,
+            documents_total: s.documents_total,
+            documents_digested: s.documents_digested,
+        }
+    }
+}
+
+/// A node in a `Snapshot`.
+#[pyclass]
+#[derive(Clone)]
+pub struct SnapshotNode {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub label: String,
+    #[pyo3(get)]
+    pub node_type: String,
+    #[pyo3(get)]
+    pub position: Position,
+    #[pyo3(get)]
+    pub access_count: u64,
+}
+
+#[pymethods]
+impl SnapshotNode {
+    fn __repr__(&self) -> String {
+        format!(
+            "SnapshotNode(label='{}', node_type={}, access_count={})",
+            self.label, self.node_type, self.access_count
+        )
+    }
+}
+
+/// An edge in a `Snapshot`, between two node labels.
+#[pyclass]
+#[derive(Clone)]
+pub struct SnapshotEdge {
+    #[pyo3(get)]
+    pub from_label: String,
+    #[pyo3(get)]
+    pub to_label: String,
+    #[pyo3(get)]
+    pub weight: f64,
+    #[pyo3(get)]
+    pub co_activations: u64,
+}
+
+#[pymethods]
+impl SnapshotEdge {
+    fn __repr__(&self) -> String {
+        format!(
+            "SnapshotEdge('{}' -- '{}', weight={:.3})",
+            self.from_label, self.to_label, self.weight
+        )
+    }
+}
+
+/// An agent in a `Snapshot`.
+#[pyclass]
+#[derive(Clone)]
+pub struct SnapshotAgent {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub agent_type: String,
+    #[pyo3(get)]
+    pub position: Position,
+    #[pyo3(get)]
+    pub age: u64,
+    #[pyo3(get)]
+    pub vocabulary_size: usize,
+}
+
+#[pymethods]
+impl SnapshotAgent {
+    fn __repr__(&self) -> String {
+        format!(
+            "SnapshotAgent(type={}, age={}, vocabulary_size={})",
+            self.agent_type, self.age, self.vocabulary_size
+        )
+    }
+}
+
+/// The colony's state at one tick, returned by `Colony.snapshot`.
+#[pyclass]
+pub struct Snapshot {
+    #[pyo3(get)]
+    pub tick: u64,
+    #[pyo3(get)]
+    pub nodes: Vec<SnapshotNode>,
+    #[pyo3(get)]
+    pub edges: Vec<SnapshotEdge>,
+    #[pyo3(get)]
+    pub agents: Vec<SnapshotAgent>,
+    #[pyo3(get)]
+    pub stats: ColonyStats,
+    json: String,
+}
+
+#[pymethods]
+impl Snapshot {
+    /// The snapshot as JSON, as `Colony.snapshot_json` returns it.
+    fn to_json(&self) -> String {
+        self.json.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Snapshot(tick={}, nodes={}, edges={}, agents={})",
+            self.tick,
+            self.nodes.len(),
+            self.edges.len(),
+            self.agents.len()
+        )
+    }
+}
+
+impl Snapshot {
+    fn new(snapshot: &ColonySnapshot) -> PyResult<Self> {
+        Ok(Snapshot {
+            tick: snapshot.tick,
+            nodes: snapshot
+                .nodes
+                .iter()
+                .map(|n| SnapshotNode {
+                    id: n.id.0.to_string(),
+                    label: n.label.clone(),
+                    node_type: format!("{:?}", n.node_type),
+                    position: n.position.into(),
+                    access_count: n.access_count,
+                })
+                .collect(),
+            edges: snapshot
+                .edges
+                .iter()
+                .map(|e| SnapshotEdge {
+                    from_label: e.from_label.clone(),
+                    to_label: e.to_label.clone(),
+                    weight: e.weight,
+                    co_activations: e.co_activations,
+                })
+                .collect(),
+            agents: snapshot
+                .agents
+                .iter()
+                .map(|a| SnapshotAgent {
+                    id: a.id.0.to_string(),
+                    agent_type: a.agent_type.clone(),
+                    position: a.position.into(),
+                    age: a.age,
+                    vocabulary_size: a.vocabulary_size,
+                })
+                .collect(),
+            stats: (&snapshot.stats).into(),
+            json: serde_json::to_string(snapshot).map_err(|e| {
+                PyValueError::new_err(format!("Failed to serialize snapshot: {}", e))
+            })?,
+        })
+    }
+}
+
+/// Snapshots recorded for `Colony.render_html`.
+struct Recording {
+    /// Record a snapshot every this many ticks.
+    every: u64,
+    series: SnapshotSeries,
+    /// First tick recorded.
+    since: u64,
+    /// Serialized size of the frames recorded so far.
+    bytes: usize,
+    max_bytes: usize,
+    /// Whether `run` and `tick` record; false once stopped or full.
+    active: bool,
+    /// Whether recording stopped because `max_bytes` was reached.
+    full: bool,
+}
+
+impl Recording {
+    fn new(every: u64, max_bytes: usize, colony: &RustColony) -> Self {
+        let mut recording = Recording {
+            every,
+            series: SnapshotSeries::new(SnapshotMode::Delta),
+            since: colony.substrate().current_tick(),
+            bytes: 0,
+            max_bytes,
+            active: true,
+            full: false,
+        };
+        recording.record(colony);
+        recording
+    }
+
+    /// Record the colony as it is now, unless the recording is full.
+    fn record(&mut self, colony: &RustColony) {
+        if !self.active {
+            return;
+        }
+        colony.record_snapshot(&mut self.series);
+        self.bytes += self
+            .series
+            .frames()
+            .last()
+            .and_then(|frame| serde_json::to_vec(frame).ok())
+            .map_or(0, |json| json.len());
+        if self.bytes >= self.max_bytes {
+            self.active = false;
+            self.full = true;
+        }
+    }
+
+    fn after_tick(&mut self, colony: &RustColony) {
+        if colony.substrate().current_tick().is_multiple_of(self.every) {
+            self.record(colony);
+        }
+    }
+}
+
+/// Default cap on the memory a recording holds: 16 MiB of snapshot JSON.
+const DEFAULT_RECORDING_BYTES: usize = 16 * 1024 * 1024;
+
 /// Main Colony class - the Phago biological computing environment.
 ///
 /// Example:
@@ -153,6 +419,8 @@ impl ColonyStats {
 #[pyclass(unsendable)]
 pub struct Colony {
     inner: RustColony,
+    registry: AgentRegistry,
+    recording: Option<Recording>,
 }
 
 #[pymethods]
@@ -174,6 +442,7 @@ impl Colony {
                 maturation_ticks: cfg.maturation_ticks,
                 max_edge_degree: cfg.max_edge_degree,
                 semantic_wiring: SemanticWiringConfig::default(),
+                ..RustColonyConfig::default()
             }
         } else {
             RustColonyConfig::default()
@@ -181,6 +450,8 @@ impl Colony {
 
         Ok(Colony {
             inner: RustColony::from_config(rust_config),
+            registry: AgentRegistry::new(),
+            recording: None,
         })
     }
 
@@ -189,7 +460,7 @@ impl Colony {
     /// Args:
     ///     title: Document title
     ///     content: Document content
-    ///     position: Optional Position (default: (0, 0))
+    ///     position: Optional Position (default: near similar documents)
     ///
     /// Returns:
     ///     Document ID string
@@ -202,7 +473,7 @@ impl Colony {
     ) -> String {
         let pos = position
             .map(|p| p.into())
-            .unwrap_or(CorePosition::new(0.0, 0.0));
+            .unwrap_or_else(|| self.inner.place_document(title, content));
         let doc_id = self.inner.ingest_document(title, content, pos);
 
         // Spawn a digester to process the document
@@ -212,37 +483,81 @@ impl Colony {
         format!("{}", doc_id.0)
     }
 
+    /// Spawn an agent by type name.
+    ///
+    /// Args:
+    ///     agent_type: "digester", "sentinel" or "synthesizer"
+    ///     **params: Constructor parameters, e.g. position=Position(1, 2),
+    ///         max_idle=50, seed=7
+    ///
+    /// Returns:
+    ///     Agent ID string
+    ///
+    /// Raises:
+    ///     ValueError: If the type is unknown or a parameter does not fit it
+    #[pyo3(signature = (agent_type, **params))]
+    fn spawn(&mut self, agent_type: &str, params: Option<&Bound<'_, PyDict>>) -> PyResult<String> {
+        let params = match params {
+            Some(dict) => py_to_json(dict.as_any())?,
+            None => Value::Null,
+        };
+        let spec = AgentSpec::new(agent_type, params);
+        let id = self
+            .inner
+            .spawn_from_spec(&self.registry, &spec)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(format!("{}", id.0))
+    }
+
     /// Run the simulation for N ticks.
     ///
     /// Args:
     ///     ticks: Number of simulation ticks to run
     fn run(&mut self, ticks: u64) {
-        self.inner.run(ticks);
+        match &mut self.recording {
+            Some(recording) if recording.active => {
+                for _ in 0..ticks {
+                    self.inner.tick();
+                    recording.after_tick(&self.inner);
+                }
+            }
+            _ => {
+                self.inner.run(ticks);
+            }
+        }
     }
 
     /// Run a single simulation tick.
     fn tick(&mut self) {
         self.inner.tick();
+        if let Some(recording) = &mut self.recording {
+            recording.after_tick(&self.inner);
+        }
     }
 
     /// Query the knowledge graph using hybrid scoring.
     ///
     /// Args:
     ///     query: Search query string
-    ///     alpha: Balance between TF-IDF (0) and graph (1) scores (default: 0.5)
+    ///     alpha: Weight of TF-IDF scoring; 1.0 is pure TF-IDF, 0.0 pure
+    ///         graph structure (default: 0.5)
     ///     max_results: Maximum number of results (default: 10)
     ///
     /// Returns:
     ///     List of QueryResult objects
+    ///
+    /// Raises:
+    ///     ValueError: If alpha is outside [0, 1] or max_results is 0
     #[pyo3(signature = (query, alpha=0.5, max_results=10))]
-    fn query(&self, query: &str, alpha: f64, max_results: usize) -> Vec<QueryResult> {
-        let config = HybridConfig {
-            alpha,
-            max_results,
-            candidate_multiplier: 3,
-        };
+    fn query(&self, query: &str, alpha: f64, max_results: usize) -> PyResult<Vec<QueryResult>> {
+        let config = HybridConfig::builder()
+            .alpha(alpha)
+            .max_results(max_results)
+            .build()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-        hybrid_query(&self.inner, query, &config)
+        Ok(hybrid_query(&self.inner, query, &config)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?
             .into_iter()
             .map(|r| QueryResult {
                 label: r.label,
@@ -250,6 +565,30 @@ impl Colony {
                 tfidf_score: r.tfidf_score,
                 graph_score: r.graph_score,
             })
+            .collect())
+    }
+
+    /// Suggest concept labels starting with a prefix.
+    ///
+    /// Args:
+    ///     prefix: Beginning of the label (case-insensitive)
+    ///     limit: Maximum number of suggestions (default: 10)
+    ///
+    /// Returns:
+    ///     List of LabelSuggestion objects, most used and connected first
+    #[pyo3(signature = (prefix, limit=10))]
+    fn suggest(&self, prefix: &str, limit: usize) -> Vec<LabelSuggestion> {
+        self.inner
+            .suggest_labels(prefix, limit)
+            .into_iter()
+            .map(|s| LabelSuggestion {
+                label: s.label,
+                node_type: format!("{:?}", s.node_type),
+                access_count: s.access_count,
+                degree: s.degree,
+                score: s.score,
+                top_neighbors: s.top_neighbors,
+            })
             .collect()
     }
 
@@ -258,18 +597,7 @@ impl Colony {
     /// Returns:
     ///     ColonyStats object with current metrics
     fn stats(&self) -> ColonyStats {
-        let s = self.inner.stats();
-        ColonyStats {
-            tick: s.tick,
-            agents_alive: s.agents_alive,
-            agents_died: s.agents_died,
-            total_spawned: s.total_spawned,
-            graph_nodes: s.graph_nodes,
-            graph_edges: s.graph_edges,
-            total_signals: s.total_signals,
-            documents_total: s.documents_total,
-            documents_digested: s.documents_digested,
-        }
+        (&self.inner.stats()).into()
     }
 
     /// Get the number of alive agents.
@@ -284,6 +612,154 @@ impl Colony {
             .map_err(|e| PyValueError::new_err(format!("Failed to serialize snapshot: {}", e)))
     }
 
+    /// Get a snapshot of the colony state.
+    ///
+    /// Returns:
+    ///     Snapshot with the tick's nodes, edges, agents and stats
+    fn snapshot(&self) -> PyResult<Snapshot> {
+        Snapshot::new(&self.inner.snapshot())
+    }
+
+    /// Get the knowledge graph as networkx node-link data.
+    ///
+    /// Nodes carry `label`, `type` and `access_count`; links carry
+    /// `weight` and `co_activations`. Load it with
+    /// `networkx.node_link_graph(data, edges="links")` (networkx 3.4 and
+    /// later; older versions take just `data`).
+    ///
+    /// Returns:
+    ///     Dict with `directed`, `multigraph`, `graph`, `nodes` and `links`
+    fn to_networkx<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let graph = self.inner.substrate().graph();
+        let mut ids = graph.all_nodes();
+        ids.sort();
+
+        let nodes = PyList::empty_bound(py);
+        for id in &ids {
+            if let Some(node) = graph.get_node(id) {
+                let entry = PyDict::new_bound(py);
+                entry.set_item("id", id.0.to_string())?;
+                entry.set_item("label", &node.label)?;
+                entry.set_item("type", format!("{:?}", node.node_type))?;
+                entry.set_item("access_count", node.access_count)?;
+                nodes.append(entry)?;
+            }
+        }
+
+        let mut edges = graph.all_edges();
+        edges.sort_by_key(|(from, to, _)| (*from, *to));
+        let links = PyList::empty_bound(py);
+        for (from, to, edge) in edges {
+            let entry = PyDict::new_bound(py);
+            entry.set_item("source", from.0.to_string())?;
+            entry.set_item("target", to.0.to_string())?;
+            entry.set_item("weight", edge.weight)?;
+            entry.set_item("co_activations", edge.co_activations)?;
+            links.append(entry)?;
+        }
+
+        let data = PyDict::new_bound(py);
+        data.set_item("directed", false)?;
+        data.set_item("multigraph", false)?;
+        data.set_item("graph", PyDict::new_bound(py))?;
+        data.set_item("nodes", nodes)?;
+        data.set_item("links", links)?;
+        Ok(data)
+    }
+
+    /// Record a snapshot every `snapshot_every` ticks of the coming `run`
+    /// and `tick` calls, for `render_html`. Starts over if a recording is
+    /// already kept.
+    ///
+    /// Args:
+    ///     snapshot_every: Ticks between snapshots (default: 5)
+    ///     max_bytes: Stop recording once the snapshots take this much
+    ///         memory as JSON (default: 16 MiB)
+    ///
+    /// Raises:
+    ///     ValueError: If snapshot_every or max_bytes is 0
+    #[pyo3(signature = (snapshot_every=5, max_bytes=DEFAULT_RECORDING_BYTES))]
+    fn start_recording(&mut self, snapshot_every: u64, max_bytes: usize) -> PyResult<()> {
+        if snapshot_every == 0 || max_bytes == 0 {
+            return Err(PyValueError::new_err(
+                "snapshot_every and max_bytes must be at least 1",
+            ));
+        }
+        self.recording = Some(Recording::new(snapshot_every, max_bytes, &self.inner));
+        Ok(())
+    }
+
+    /// Stop recording snapshots, keeping those recorded for `render_html`.
+    ///
+    /// Returns:
+    ///     Number of snapshots recorded
+    fn stop_recording(&mut self) -> usize {
+        match &mut self.recording {
+            Some(recording) => {
+                recording.active = false;
+                recording.series.len()
+            }
+            None => 0,
+        }
+    }
+
+    /// Drop the recorded snapshots and stop recording.
+    fn clear_recording(&mut self) {
+        self.recording = None;
+    }
+
+    /// Whether `run` and `tick` are recording snapshots. False once
+    /// stopped, or once the recording reached its memory cap.
+    #[getter]
+    fn is_recording(&self) -> bool {
+        self.recording.as_ref().is_some_and(|r| r.active)
+    }
+
+    /// Whether recording stopped because it reached its memory cap.
+    #[getter]
+    fn recording_full(&self) -> bool {
+        self.recording.as_ref().is_some_and(|r| r.full)
+    }
+
+    /// Render the recorded snapshots as the phago-viz player, one
+    /// self-contained HTML page for `IPython.display.HTML`.
+    ///
+    /// Without a recording, starts one (see `start_recording`) so that the
+    /// coming `run` calls are recorded, and renders the colony as it is
+    /// now.
+    ///
+    /// Args:
+    ///     snapshot_every: Ticks between snapshots of a new recording
+    ///         (default: 5)
+    ///
+    /// Returns:
+    ///     The HTML page as a string
+    ///
+    /// Raises:
+    ///     RuntimeError: If the recording is too large to embed
+    #[pyo3(signature = (snapshot_every=5))]
+    fn render_html(&mut self, snapshot_every: u64) -> PyResult<String> {
+        if self.recording.is_none() {
+            self.start_recording(snapshot_every, DEFAULT_RECORDING_BYTES)?;
+        }
+        let recording = self.recording.as_ref().expect("recording started above");
+        // The events between the first snapshot and the last
+        let last = recording
+            .series
+            .frames()
+            .last()
+            .map_or(recording.since, |frame| frame.tick());
+        let events: Vec<_> = self
+            .inner
+            .event_history()
+            .iter()
+            .filter(|(tick, _)| (recording.since..=last).contains(tick))
+            .cloned()
+            .collect();
+        phago_viz::generate_html_series(&recording.series, &events)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
     fn __repr__(&self) -> String {
         let stats = self.inner.stats();
         format!(
@@ -293,13 +769,58 @@ impl Colony {
     }
 }
 
+/// Convert a keyword argument to JSON for an agent spec. Positions become
+/// `{"x": .., "y": ..}` objects.
+fn py_to_json(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        return Ok(Value::Null);
+    }
+    if let Ok(b) = obj.downcast::<PyBool>() {
+        return Ok(Value::Bool(b.is_true()));
+    }
+    if let Ok(i) = obj.extract::<i64>() {
+        return Ok(Value::from(i));
+    }
+    if let Ok(f) = obj.extract::<f64>() {
+        return Ok(Value::from(f));
+    }
+    if let Ok(s) = obj.extract::<String>() {
+        return Ok(Value::String(s));
+    }
+    if let Ok(p) = obj.extract::<Position>() {
+        return Ok(serde_json::json!({"x": p.x, "y": p.y}));
+    }
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut map = serde_json::Map::new();
+        for (key, value) in dict.iter() {
+            map.insert(key.extract::<String>()?, py_to_json(&value)?);
+        }
+        return Ok(Value::Object(map));
+    }
+    if let Ok(list) = obj.downcast::<PyList>() {
+        return list.iter().map(|v| py_to_json(&v)).collect();
+    }
+    if let Ok(tuple) = obj.downcast::<PyTuple>() {
+        return tuple.iter().map(|v| py_to_json(&v)).collect();
+    }
+    Err(PyValueError::new_err(format!(
+        "unsupported parameter value: {}",
+        obj.repr()?
+    )))
+}
+
 /// Python module definition.
 #[pymodule]
 fn _phago(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Colony>()?;
     m.add_class::<ColonyConfig>()?;
     m.add_class::<ColonyStats>()?;
+    m.add_class::<LabelSuggestion>()?;
     m.add_class::<Position>()?;
     m.add_class::<QueryResult>()?;
+    m.add_class::<Snapshot>()?;
+    m.add_class::<SnapshotAgent>()?;
+    m.add_class::<SnapshotEdge>()?;
+    m.add_class::<SnapshotNode>()?;
     Ok(())
 }
@@ -0,0 +1,111 @@
+"""Notebook helpers: networkx node-link data, typed snapshots and the
+inline viz player.
+
+Run after building the bindings into the environment:
+
+    maturin develop && pytest
+"""
+
+import json
+
+import pytest
+
+from phago import Colony, Position, Snapshot
+
+
+def tiny_colony():
+    colony = Colony()
+    colony.ingest_document(
+        "Cells", "The cell membrane controls transport of proteins.", Position(0, 0)
+    )
+    colony.ingest_document(
+        "Energy", "Mitochondria produce energy for membrane transport.", Position(3, 0)
+    )
+    colony.run(20)
+    return colony
+
+
+def normalized(data):
+    """Node-link data with nodes keyed by id and links as unordered pairs."""
+    nodes = {
+        node["id"]: {k: v for k, v in node.items() if k != "id"} for node in data["nodes"]
+    }
+    links = {
+        tuple(sorted((link["source"], link["target"]))): {
+            k: v for k, v in link.items() if k not in ("source", "target")
+        }
+        for link in data["links"]
+    }
+    return data["directed"], data["multigraph"], data["graph"], nodes, links
+
+
+def test_node_link_data_describes_the_graph():
+    colony = tiny_colony()
+    data = colony.to_networkx()
+    stats = colony.stats()
+
+    assert not data["directed"] and not data["multigraph"]
+    assert len(data["nodes"]) == stats.graph_nodes
+    assert len(data["links"]) == stats.graph_edges
+    assert {"id", "label", "type", "access_count"} <= set(data["nodes"][0])
+    assert {"source", "target", "weight", "co_activations"} <= set(data["links"][0])
+
+    # Plain data: survives JSON unchanged
+    assert json.loads(json.dumps(data)) == data
+
+
+def test_node_link_data_round_trips_through_networkx():
+    nx = pytest.importorskip("networkx")
+    data = tiny_colony().to_networkx()
+
+    graph = nx.node_link_graph(data, edges="links")
+    assert graph.number_of_nodes() == len(data["nodes"])
+    assert graph.number_of_edges() == len(data["links"])
+    assert normalized(nx.node_link_data(graph, edges="links")) == normalized(data)
+
+
+def test_snapshot_is_typed():
+    colony = tiny_colony()
+    snapshot = colony.snapshot()
+
+    assert isinstance(snapshot, Snapshot)
+    assert snapshot.tick == 20
+    assert snapshot.stats.graph_nodes == len(snapshot.nodes)
+    labels = {node.label for node in snapshot.nodes}
+    assert "membrane" in labels
+    assert all(
+        edge.from_label in labels and edge.to_label in labels for edge in snapshot.edges
+    )
+    assert json.loads(snapshot.to_json())["tick"] == 20
+
+
+def test_render_html_embeds_the_recorded_snapshots():
+    colony = tiny_colony()
+    assert not colony.is_recording
+
+    first = colony.render_html(snapshot_every=5)
+    assert colony.is_recording
+    assert "const SNAPSHOTS" in first
+
+    colony.run(20)
+    assert colony.stop_recording() == 5
+    assert not colony.is_recording
+    colony.run(5)
+
+    html = colony.render_html()
+    assert "const SNAPSHOTS" in html
+    assert '"tick":40' in html and '"tick":45' not in html
+    assert "mitochondria" in html
+
+
+def test_recording_stops_at_its_memory_cap():
+    colony = tiny_colony()
+    colony.start_recording(snapshot_every=1, max_bytes=1)
+    colony.run(10)
+
+    assert colony.recording_full
+    assert not colony.is_recording
+    assert colony.stop_recording() == 1
+
+    with pytest.raises(ValueError):
+        colony.start_recording(snapshot_every=0)
@@ -0,0 +1,460 @@
+//! Answer assembly — the last step of RAG, from query to prompt-ready context.
+//!
+//! [`assemble_context`] runs a [hybrid query](crate::hybrid) with passages
+//! attached and turns its results into a [`RagContext`]:
+//!
+//! 1. Passages are taken in result order, best result first, skipping any
+//!    already taken (the same span, or the same text from another
+//!    document) and any past `max_passages_per_doc` for their document
+//! 2. Each passage is kept if it still fits the token budget, counted on
+//!    the lines [`RagContext::render`] prints for it, citation included
+//! 3. With `include_graph_context`, the best results' strongest neighbors
+//!    in the Hebbian graph fill what is left of the budget as short lines
+//!
+//! Tokens are counted by a [`TokenCounter`]; the default,
+//! [`ApproxTokenCounter`], takes four characters per token. Each printed
+//! line is counted on its own, so for it the rendered context as a whole
+//! never counts more tokens than the budget.
+
+use crate::hybrid::{hybrid_query, HybridConfig, HybridConfigError, HybridResult};
+use phago_core::types::{DocumentId, NodeType};
+use phago_runtime::colony::Colony;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Best results whose neighbors make up the graph context.
+const GRAPH_CONTEXT_CONCEPTS: usize = 5;
+
+/// Neighbors listed per concept in the graph context.
+const GRAPH_CONTEXT_NEIGHBORS: usize = 3;
+
+/// Counts the tokens a piece of text takes up in a prompt.
+pub trait TokenCounter {
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Four characters per token, rounded up — close enough for English text
+/// and most tokenizers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproxTokenCounter;
+
+impl TokenCounter for ApproxTokenCounter {
+    fn count(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+}
+
+impl<F: Fn(&str) -> usize> TokenCounter for F {
+    fn count(&self, text: &str) -> usize {
+        self(text)
+    }
+}
+
+/// How passages point at their sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationStyle {
+    /// `[1]`, numbered by document, with a list of sources at the end.
+    #[default]
+    Numeric,
+    /// `[Document title]` in front of each passage.
+    Title,
+}
+
+/// How [`assemble_context`] builds its context.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssembleConfig {
+    /// Most tokens the rendered context may take (default: 2000).
+    pub token_budget: usize,
+    /// Most passages taken from one document (default: 2).
+    pub max_passages_per_doc: usize,
+    pub citation_style: CitationStyle,
+    /// Add the strongest graph neighbors of the best results (default: true).
+    pub include_graph_context: bool,
+}
+
+impl Default for AssembleConfig {
+    fn default() -> Self {
+        Self {
+            token_budget: 2000,
+            max_passages_per_doc: 2,
+            citation_style: CitationStyle::default(),
+            include_graph_context: true,
+        }
+    }
+}
+
+/// A source passage in an assembled context.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextPassage {
+    pub text: String,
+    pub document_id: DocumentId,
+    pub title: String,
+    /// Final score of the best result the passage was attached to.
+    pub score: f64,
+    /// Character range of the passage within the document's content.
+    pub char_start: usize,
+    pub char_end: usize,
+}
+
+/// A concept and its strongest neighbors in the graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphFact {
+    pub concept: String,
+    /// Neighbor labels and edge weights, strongest first.
+    pub neighbors: Vec<(String, f64)>,
+}
+
+/// Where a passage of the context came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// Index into [`RagContext::passages`].
+    pub passage: usize,
+    pub document_id: DocumentId,
+    /// How the rendered context marks the passage, e.g. `[1]`.
+    pub marker: String,
+}
+
+/// Context for answering a query: passages, graph facts and citations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RagContext {
+    pub query: String,
+    /// Best first.
+    pub passages: Vec<ContextPassage>,
+    pub graph_context: Vec<GraphFact>,
+    /// One per passage, in passage order.
+    pub citations: Vec<Citation>,
+    pub citation_style: CitationStyle,
+    /// Tokens the rendered context takes, at most `token_budget`.
+    pub tokens_used: usize,
+    pub token_budget: usize,
+}
+
+impl RagContext {
+    /// The context as prompt text: cited passages, related concepts and,
+    /// with numeric citations, the list of sources.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (passage, citation) in self.passages.iter().zip(&self.citations) {
+            out.push_str(&passage_line(&citation.marker, &passage.text));
+        }
+        for (k, fact) in self.graph_context.iter().enumerate() {
+            if k == 0 {
+                out.push_str(GRAPH_CONTEXT_HEADER);
+            }
+            out.push_str(&fact_line(fact));
+        }
+        if self.citation_style == CitationStyle::Numeric {
+            for (k, (marker, title)) in self.sources().into_iter().enumerate() {
+                if k == 0 {
+                    out.push_str(SOURCES_HEADER);
+                }
+                out.push_str(&source_line(marker, title));
+            }
+        }
+        out
+    }
+
+    /// Each cited document's marker and title, in order of first citation.
+    fn sources(&self) -> Vec<(&str, &str)> {
+        let mut seen = HashSet::new();
+        self.citations
+            .iter()
+            .zip(&self.passages)
+            .filter(|(c, _)| seen.insert(c.document_id))
+            .map(|(c, p)| (c.marker.as_str(), p.title.as_str()))
+            .collect()
+    }
+}
+
+const GRAPH_CONTEXT_HEADER: &str = "\nRelated concepts:\n";
+const SOURCES_HEADER: &str = "\nSources:\n";
+
+fn passage_line(marker: &str, text: &str) -> String {
+    let text: Vec<&str> = text.split_whitespace().collect();
+    format!("{} {}\n", marker, text.join(" "))
+}
+
+fn fact_line(fact: &GraphFact) -> String {
+    let neighbors: Vec<&str> = fact.neighbors.iter().map(|(l, _)| l.as_str()).collect();
+    format!("- {} is linked to {}\n", fact.concept, neighbors.join(", "))
+}
+
+fn source_line(marker: &str, title: &str) -> String {
+    format!("{} {}\n", marker, title)
+}
+
+/// Retrieve passages for `query` and assemble them into a context within
+/// `config.token_budget`, counting tokens with [`ApproxTokenCounter`].
+pub fn assemble_context(
+    colony: &Colony,
+    query: &str,
+    config: &AssembleConfig,
+) -> Result<RagContext, HybridConfigError> {
+    assemble_context_with(
+        colony,
+        query,
+        &HybridConfig::default(),
+        config,
+        &ApproxTokenCounter,
+    )
+}
+
+/// [`assemble_context`], retrieving with `retrieval` (passages are always
+/// attached) and counting tokens with `counter`.
+pub fn assemble_context_with(
+    colony: &Colony,
+    query: &str,
+    retrieval: &HybridConfig,
+    config: &AssembleConfig,
+    counter: &dyn TokenCounter,
+) -> Result<RagContext, HybridConfigError> {
+    let retrieval = HybridConfig {
+        include_passages: true,
+        max_passages: retrieval.max_passages.max(config.max_passages_per_doc),
+        ..retrieval.clone()
+    };
+    let results = hybrid_query(colony, query, &retrieval)?;
+    Ok(assemble_results(colony, query, &results, config, counter))
+}
+
+/// Assemble a context from hybrid query `results` retrieved with their
+/// passages.
+pub(crate) fn assemble_results(
+    colony: &Colony,
+    query: &str,
+    results: &[HybridResult],
+    config: &AssembleConfig,
+    counter: &dyn TokenCounter,
+) -> RagContext {
+    let mut context = RagContext {
+        query: query.to_string(),
+        passages: Vec::new(),
+        graph_context: Vec::new(),
+        citations: Vec::new(),
+        citation_style: config.citation_style,
+        tokens_used: 0,
+        token_budget: config.token_budget,
+    };
+    let mut spans = HashSet::new();
+    // The same sentence in two documents is taken once
+    let mut texts = HashSet::new();
+    let mut per_doc: HashMap<DocumentId, usize> = HashMap::new();
+    // Document → its numeric marker
+    let mut numbers: HashMap<DocumentId, usize> = HashMap::new();
+
+    for result in results {
+        for passage in &result.passages {
+            let text: Vec<&str> = passage.snippet.split_whitespace().collect();
+            let text = text.join(" ");
+            if spans.contains(&(passage.document_id, passage.span))
+                || texts.contains(&text.to_lowercase())
+                || per_doc.get(&passage.document_id).copied().unwrap_or(0)
+                    >= config.max_passages_per_doc
+            {
+                continue;
+            }
+            let new_source = !numbers.contains_key(&passage.document_id);
+            let marker = match config.citation_style {
+                CitationStyle::Numeric => {
+                    let next = numbers.len() + 1;
+                    format!("[{}]", numbers.get(&passage.document_id).unwrap_or(&next))
+                }
+                CitationStyle::Title => format!("[{}]", passage.title),
+            };
+            let mut cost = counter.count(&passage_line(&marker, &text));
+            if new_source && config.citation_style == CitationStyle::Numeric {
+                cost += counter.count(&source_line(&marker, &passage.title));
+                if numbers.is_empty() {
+                    cost += counter.count(SOURCES_HEADER);
+                }
+            }
+            if context.tokens_used + cost > config.token_budget {
+                continue;
+            }
+            context.tokens_used += cost;
+            spans.insert((passage.document_id, passage.span));
+            texts.insert(text.to_lowercase());
+            if new_source {
+                numbers.insert(passage.document_id, numbers.len() + 1);
+            }
+            *per_doc.entry(passage.document_id).or_insert(0) += 1;
+            let content = colony
+                .substrate()
+                .get_document(&passage.document_id)
+                .map_or("", |d| d.content.as_str());
+            let char_offset = |byte: usize| content.get(..byte).map_or(0, |s| s.chars().count());
+            context.citations.push(Citation {
+                passage: context.passages.len(),
+                document_id: passage.document_id,
+                marker,
+            });
+            context.passages.push(ContextPassage {
+                text: passage.snippet.clone(),
+                document_id: passage.document_id,
+                title: passage.title.clone(),
+                score: result.final_score,
+                char_start: char_offset(passage.span.start),
+                char_end: char_offset(passage.span.end),
+            });
+        }
+    }
+
+    if config.include_graph_context {
+        let labels: Vec<&str> = results.iter().map(|r| r.label.as_str()).collect();
+        for fact in graph_facts(colony, &labels) {
+            let mut cost = counter.count(&fact_line(&fact));
+            if context.graph_context.is_empty() {
+                cost += counter.count(GRAPH_CONTEXT_HEADER);
+            }
+            if context.tokens_used + cost > config.token_budget {
+                continue;
+            }
+            context.tokens_used += cost;
+            context.graph_context.push(fact);
+        }
+    }
+    context
+}
+
+/// The strongest concept neighbors of the best concepts among `labels`.
+fn graph_facts(colony: &Colony, labels: &[&str]) -> Vec<GraphFact> {
+    let graph = colony.substrate().graph();
+    labels
+        .iter()
+        .filter_map(|label| {
+            let id = *graph.find_nodes_by_exact_label(label).first()?;
+            let node = graph.get_node(&id)?;
+            if node.node_type != NodeType::Concept {
+                return None;
+            }
+            let mut neighbors: Vec<(String, f64)> = graph
+                .neighbors(&id)
+                .into_iter()
+                .filter_map(|(n, edge)| {
+                    let neighbor = graph.get_node(&n)?;
+                    (neighbor.node_type == NodeType::Concept)
+                        .then(|| (neighbor.label.clone(), edge.weight))
+                })
+                .collect();
+            neighbors.sort_by(|a, b| {
+                b.1.partial_cmp(&a.1)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.0.cmp(&b.0))
+            });
+            neighbors.truncate(GRAPH_CONTEXT_NEIGHBORS);
+            (!neighbors.is_empty()).then(|| GraphFact {
+                concept: node.label.clone(),
+                neighbors,
+            })
+        })
+        .take(GRAPH_CONTEXT_CONCEPTS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_agents::digester::Digester;
+    use phago_runtime::corpus::Corpus;
+
+    fn corpus_colony() -> Colony {
+        let corpus = Corpus::from_embedded().limit(24);
+        let mut colony = Colony::new();
+        corpus.ingest_into(&mut colony);
+        for doc in &corpus.documents {
+            colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(80)));
+        }
+        colony.run(40);
+        colony
+    }
+
+    #[test]
+    fn assembled_context_fits_the_budget_without_duplicates() {
+        let colony = corpus_colony();
+        for budget in [150, 400, 2000] {
+            let config = AssembleConfig {
+                token_budget: budget,
+                ..AssembleConfig::default()
+            };
+            let context = assemble_context(&colony, "cell membrane transport", &config).unwrap();
+            assert!(!context.passages.is_empty(), "budget {budget}");
+            assert!(context.tokens_used <= budget);
+            assert!(ApproxTokenCounter.count(&context.render()) <= budget);
+
+            let spans: HashSet<_> = context
+                .passages
+                .iter()
+                .map(|p| (p.document_id, p.char_start, p.char_end))
+                .collect();
+            assert_eq!(spans.len(), context.passages.len());
+            let texts: HashSet<_> = context.passages.iter().map(|p| &p.text).collect();
+            assert_eq!(texts.len(), context.passages.len());
+            let mut per_doc: HashMap<DocumentId, usize> = HashMap::new();
+            for p in &context.passages {
+                *per_doc.entry(p.document_id).or_insert(0) += 1;
+            }
+            assert!(per_doc.values().all(|&n| n <= config.max_passages_per_doc));
+        }
+    }
+
+    #[test]
+    fn every_citation_resolves_to_its_passage_and_document() {
+        let colony = corpus_colony();
+        let context =
+            assemble_context(&colony, "protein signaling", &AssembleConfig::default()).unwrap();
+        assert_eq!(context.citations.len(), context.passages.len());
+        let rendered = context.render();
+        for (k, citation) in context.citations.iter().enumerate() {
+            assert_eq!(citation.passage, k);
+            let passage = &context.passages[citation.passage];
+            assert_eq!(citation.document_id, passage.document_id);
+            let doc = colony
+                .substrate()
+                .get_document(&citation.document_id)
+                .expect("cited document exists");
+            assert_eq!(doc.title, passage.title);
+            // Character offsets slice the passage out of the document
+            let text: String = doc
+                .content
+                .chars()
+                .skip(passage.char_start)
+                .take(passage.char_end - passage.char_start)
+                .collect();
+            assert_eq!(text, passage.text);
+            assert!(rendered.contains(&citation.marker));
+        }
+        assert!(!context.graph_context.is_empty());
+        assert!(rendered.contains("Related concepts:"));
+        assert!(rendered.contains("Sources:"));
+    }
+
+    #[test]
+    fn title_citations_and_custom_counters() {
+        let colony = corpus_colony();
+        let config = AssembleConfig {
+            token_budget: 50,
+            citation_style: CitationStyle::Title,
+            include_graph_context: false,
+            ..AssembleConfig::default()
+        };
+        // One token per word
+        let words = |text: &str| text.split_whitespace().count();
+        let context = assemble_context_with(
+            &colony,
+            "cell membrane transport",
+            &HybridConfig::default(),
+            &config,
+            &words,
+        )
+        .unwrap();
+        assert!(!context.passages.is_empty());
+        assert!(context.tokens_used <= 50);
+        assert!(context.graph_context.is_empty());
+        for (passage, citation) in context.passages.iter().zip(&context.citations) {
+            assert_eq!(citation.marker, format!("[{}]", passage.title));
+        }
+        assert!(!context.render().contains("Sources:"));
+    }
+}
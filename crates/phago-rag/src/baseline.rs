@@ -5,79 +5,34 @@
 //! - Static graph baseline (same BFS, no reinforcement)
 //! - Random baseline (random selection from all concepts)
 
-use phago_core::topology::TopologyGraph;
+use crate::hybrid::{sort_by_tfidf, tfidf_indexed, tfidf_scan};
 use phago_runtime::colony::Colony;
-use std::collections::HashMap;
 
 /// TF-IDF baseline: score concepts by term frequency overlap with query.
 ///
 /// Returns concept labels ranked by TF-IDF similarity to query terms.
 pub fn tfidf_query(colony: &Colony, query_text: &str, max_results: usize) -> Vec<String> {
-    let query_terms = tokenize(query_text);
+    let tokenizer = colony.tokenizer();
+    let query_terms = tokenizer.tokens(query_text);
     if query_terms.is_empty() {
         return Vec::new();
     }
 
+    // Read frequencies from the term index when the colony keeps one
     let graph = colony.substrate().graph();
-    let all_nodes = graph.all_nodes();
-    let total_docs = all_nodes.len().max(1) as f64;
-
-    // Build document frequency for each term across all node labels
-    let mut df: HashMap<String, usize> = HashMap::new();
-    for nid in &all_nodes {
-        if let Some(node) = graph.get_node(nid) {
-            let label_terms: Vec<String> = node
-                .label
-                .to_lowercase()
-                .split(|c: char| !c.is_alphanumeric())
-                .filter(|w| w.len() >= 3)
-                .map(|w| w.to_string())
-                .collect();
-            let unique: std::collections::HashSet<_> = label_terms.into_iter().collect();
-            for term in unique {
-                *df.entry(term).or_insert(0) += 1;
-            }
-        }
-    }
-
-    // Score each node by TF-IDF overlap with query
-    let mut scores: Vec<(String, f64)> = Vec::new();
-    for nid in &all_nodes {
-        if let Some(node) = graph.get_node(nid) {
-            let label_lower = node.label.to_lowercase();
-            let label_terms: Vec<String> = label_lower
-                .split(|c: char| !c.is_alphanumeric())
-                .filter(|w| w.len() >= 3)
-                .map(|w| w.to_string())
-                .collect();
-
-            let mut score = 0.0;
-            for qt in &query_terms {
-                let tf = label_terms.iter().filter(|t| *t == qt).count() as f64;
-                if tf > 0.0 {
-                    let idf = (total_docs / (*df.get(qt).unwrap_or(&1) as f64)).ln() + 1.0;
-                    score += tf * idf;
-                }
-            }
-
-            // Boost exact matches significantly
-            for qt in &query_terms {
-                if label_lower == *qt {
-                    score += 10.0;
-                }
-            }
-
-            if score > 0.0 {
-                scores.push((node.label.clone(), score));
-            }
-        }
-    }
-
-    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let mut scores = match colony
+        .substrate()
+        .term_index()
+        .filter(|index| index.tokenizer() == tokenizer)
+    {
+        Some(index) => tfidf_indexed(graph, index, &query_terms),
+        None => tfidf_scan(graph, tokenizer, &graph.all_nodes(), &query_terms),
+    };
+    sort_by_tfidf(&mut scores);
     scores
         .into_iter()
         .take(max_results)
-        .map(|(label, _)| label)
+        .map(|(_, label, _)| label)
         .collect()
 }
 
@@ -130,28 +85,6 @@ pub fn random_query(colony: &Colony, max_results: usize, seed: u64) -> Vec<Strin
         .collect()
 }
 
-/// Simple tokenizer matching the one in query.rs.
-fn tokenize(text: &str) -> Vec<String> {
-    let stopwords: std::collections::HashSet<&str> = [
-        "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
-        "do", "does", "did", "will", "would", "could", "should", "may", "might", "shall", "can",
-        "need", "to", "of", "in", "for", "on", "with", "at", "by", "from", "as", "into", "through",
-        "during", "before", "after", "above", "below", "between", "out", "off", "over", "under",
-        "again", "further", "then", "once", "and", "but", "or", "if", "while", "what", "which",
-        "who", "this", "that", "these", "those", "it", "its", "how",
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    text.to_lowercase()
-        .split_whitespace()
-        .filter(|w| w.len() >= 3 && !stopwords.contains(w))
-        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
-        .filter(|w| w.len() >= 3)
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
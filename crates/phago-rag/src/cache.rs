@@ -0,0 +1,234 @@
+//! Query result cache for [`hybrid_query_cached`](crate::hybrid::hybrid_query_cached).
+//!
+//! Entries are keyed by the tokenized query and the settings that affect
+//! ranking, and stamped with the colony's
+//! [`graph_revision`](phago_runtime::colony::Colony::graph_revision). An
+//! entry is only returned while the revision it was computed at is still
+//! current, so any change to the graph — presentations, wiring, decay,
+//! pruning, query reinforcement — makes every older entry unreachable.
+//!
+//! Revisions are per colony: use one cache per colony.
+
+use crate::hybrid::{HybridConfig, HybridResult};
+use phago_runtime::query_log::EdgeLabels;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// Ranked results paired with the seed edges that scored them.
+pub(crate) type Ranked = Vec<(HybridResult, Vec<EdgeLabels>)>;
+
+/// Hit and miss counts of a [`QueryCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct QueryCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries currently stored, stale ones included.
+    pub entries: usize,
+}
+
+impl QueryCacheStats {
+    /// Fraction of lookups answered from the cache (0 before any lookup).
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    terms: Vec<String>,
+    config: u64,
+}
+
+struct CacheEntry {
+    revision: u64,
+    last_used: u64,
+    ranked: Ranked,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    clock: u64,
+    hits: u64,
+    misses: u64,
+}
+
+/// Bounded cache of hybrid query results for one colony.
+///
+/// Shareable between threads; lookups take a short lock.
+pub struct QueryCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+impl QueryCache {
+    /// Cache at most `capacity` queries. When full, entries from older
+    /// revisions go first, then the least recently used.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Maximum number of entries.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Hits, misses and current size.
+    pub fn stats(&self) -> QueryCacheStats {
+        let state = self.state.lock().unwrap();
+        QueryCacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            entries: state.entries.len(),
+        }
+    }
+
+    /// Drop every entry. Statistics are kept.
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+
+    /// The entry for `terms` under `config` computed at `revision`, or the
+    /// result of `compute`, stored for next time.
+    pub(crate) fn get_or_compute(
+        &self,
+        revision: u64,
+        terms: &[String],
+        config: &HybridConfig,
+        compute: impl FnOnce() -> Ranked,
+    ) -> Ranked {
+        let key = CacheKey {
+            terms: terms.to_vec(),
+            config: config_key(config),
+        };
+        {
+            let mut state = self.state.lock().unwrap();
+            state.clock += 1;
+            let now = state.clock;
+            if let Some(entry) = state.entries.get_mut(&key) {
+                if entry.revision == revision {
+                    entry.last_used = now;
+                    let ranked = entry.ranked.clone();
+                    state.hits += 1;
+                    return ranked;
+                }
+            }
+            state.misses += 1;
+        }
+
+        // Computed without the lock so other lookups are not held up
+        let ranked = compute();
+        if self.capacity == 0 {
+            return ranked;
+        }
+        let mut state = self.state.lock().unwrap();
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            state.entries.retain(|_, e| e.revision == revision);
+            while state.entries.len() >= self.capacity {
+                let Some(lru) = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.last_used)
+                    .map(|(k, _)| k.clone())
+                else {
+                    break;
+                };
+                state.entries.remove(&lru);
+            }
+        }
+        let last_used = state.clock;
+        state.entries.insert(
+            key,
+            CacheEntry {
+                revision,
+                last_used,
+                ranked: ranked.clone(),
+            },
+        );
+        ranked
+    }
+}
+
+/// Hash of the settings that change what a query returns. The query
+/// source only affects logging and is left out.
+pub(crate) fn config_key(config: &HybridConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    config.alpha.to_bits().hash(&mut hasher);
+    config.max_results.hash(&mut hasher);
+    config.candidate_multiplier.hash(&mut hasher);
+    config.insight_boost.to_bits().hash(&mut hasher);
+    config.tags.hash(&mut hasher);
+    format!("{:?}", config.graph_scorer).hash(&mut hasher);
+    config.include_passages.hash(&mut hasher);
+    config.max_passages.hash(&mut hasher);
+    config.min_anomaly_score.map(f64::to_bits).hash(&mut hasher);
+    if let Some(stage) = &config.reranker {
+        (std::sync::Arc::as_ptr(&stage.reranker) as *const () as usize).hash(&mut hasher);
+        stage.top_n.hash(&mut hasher);
+        stage.blend_weight.to_bits().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_scorer::PersonalizedPageRankScorer;
+
+    #[test]
+    fn settings_that_change_rankings_change_the_key() {
+        let base = HybridConfig::default();
+        let same_but_source = HybridConfig {
+            source: phago_runtime::query_log::QuerySource::Web,
+            ..HybridConfig::default()
+        };
+        assert_eq!(config_key(&base), config_key(&same_but_source));
+
+        let other_alpha = HybridConfig {
+            alpha: 0.7,
+            ..HybridConfig::default()
+        };
+        let pagerank = HybridConfig {
+            graph_scorer: std::sync::Arc::new(PersonalizedPageRankScorer::default()),
+            ..HybridConfig::default()
+        };
+        assert_ne!(config_key(&base), config_key(&other_alpha));
+        assert_ne!(config_key(&base), config_key(&pagerank));
+    }
+
+    #[test]
+    fn full_cache_evicts_stale_then_least_recently_used() {
+        let cache = QueryCache::new(2);
+        let config = HybridConfig::default();
+        let terms = |t: &str| vec![t.to_string()];
+
+        cache.get_or_compute(1, &terms("a"), &config, Vec::new);
+        cache.get_or_compute(2, &terms("b"), &config, Vec::new);
+        cache.get_or_compute(2, &terms("c"), &config, Vec::new);
+        // "a" was stale and made room for "c"
+        cache.get_or_compute(2, &terms("b"), &config, || panic!("b evicted"));
+        cache.get_or_compute(2, &terms("c"), &config, || panic!("c evicted"));
+
+        cache.get_or_compute(2, &terms("d"), &config, Vec::new);
+        cache.get_or_compute(2, &terms("c"), &config, || panic!("c evicted"));
+        assert_eq!(
+            cache.stats(),
+            QueryCacheStats {
+                hits: 3,
+                misses: 4,
+                entries: 2
+            }
+        );
+    }
+}
@@ -2,28 +2,68 @@
 //!
 //! Queries the knowledge graph for code-related concepts like
 //! function names, type references, and file associations.
+//!
+//! Concepts a code digester qualified with their file are matched on their
+//! plain label, so that querying `agent` does not find everything under
+//! `crates/phago-agents/`.
 
-use phago_core::topology::TopologyGraph;
+use phago_core::types::{split_qualified_label, NodeId, NAMESPACE_SEPARATOR};
 use phago_runtime::colony::Colony;
+use std::collections::HashSet;
 
 /// A code query result.
 #[derive(Debug, Clone)]
 pub struct CodeQueryResult {
+    /// The concept's plain label.
     pub label: String,
+    /// Where the concept is defined, e.g. its source file. `None` for
+    /// unqualified concepts.
+    pub namespace: Option<String>,
     pub score: f64,
     pub related: Vec<String>,
 }
 
+impl CodeQueryResult {
+    /// The label qualified by its namespace, as the graph labels the node.
+    pub fn qualified_label(&self) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{namespace}{NAMESPACE_SEPARATOR}{}", self.label),
+            None => self.label.clone(),
+        }
+    }
+}
+
 /// Query the code knowledge graph for a term.
 ///
 /// Performs a 2-3 hop BFS traversal from seed nodes with cumulative
 /// edge weight scoring. This surfaces related types, functions,
 /// and concepts beyond immediate neighbors.
+///
+/// Seeds are the concepts whose plain label contains the query, or that
+/// carry it as an alias. Qualified concepts labelled exactly the query,
+/// the definitions of what was asked for, rank above everything else.
 pub fn code_query(colony: &Colony, query: &str, max_results: usize) -> Vec<CodeQueryResult> {
     let graph = colony.substrate().graph();
+    let needle = query.to_lowercase();
 
     // Find seed nodes
-    let seed_ids = graph.find_nodes_by_label(query);
+    let mut seed_ids: Vec<NodeId> = graph
+        .find_nodes_by_label(query)
+        .into_iter()
+        .filter(|id| {
+            graph.get_node(id).is_some_and(|n| {
+                split_qualified_label(&n.label)
+                    .1
+                    .to_lowercase()
+                    .contains(&needle)
+            })
+        })
+        .collect();
+    for id in graph.find_nodes_by_exact_label(query) {
+        if !seed_ids.contains(&id) {
+            seed_ids.push(id);
+        }
+    }
     if seed_ids.is_empty() {
         return Vec::new();
     }
@@ -33,15 +73,20 @@ pub fn code_query(colony: &Colony, query: &str, max_results: usize) -> Vec<CodeQ
 
     // BFS with cumulative edge weight scoring
     // (cumulative_weight, node_id, depth)
-    let mut frontier: Vec<(f64, phago_core::types::NodeId, usize)> = Vec::new();
-    let mut visited: std::collections::HashSet<phago_core::types::NodeId> =
-        std::collections::HashSet::new();
-    let mut scored: Vec<(String, f64, phago_core::types::NodeId)> = Vec::new();
+    let mut frontier: Vec<(f64, NodeId, usize)> = Vec::new();
+    let mut visited: HashSet<NodeId> = HashSet::new();
+    // (definition, label, score, node_id)
+    let mut scored: Vec<(bool, String, f64, NodeId)> = Vec::new();
 
     for seed_id in &seed_ids {
         visited.insert(*seed_id);
         if let Some(node) = graph.get_node(seed_id) {
+            let definition = match split_qualified_label(&node.label) {
+                (Some(_), plain) => plain.to_lowercase() == needle,
+                (None, _) => false,
+            };
             scored.push((
+                definition,
                 node.label.clone(),
                 (node.access_count as f64 + 1.0) * 10.0,
                 *seed_id,
@@ -71,20 +116,23 @@ pub fn code_query(colony: &Colony, query: &str, max_results: usize) -> Vec<CodeQ
             if let Some(node) = graph.get_node(nid) {
                 let cumulative_weight = weight * edge.weight;
                 let score = cumulative_weight * (1.0 + (node.access_count as f64).ln().max(0.0));
-                scored.push((node.label.clone(), score, *nid));
+                scored.push((false, node.label.clone(), score, *nid));
                 frontier.push((cumulative_weight, *nid, depth + 1));
             }
         }
     }
 
-    // Sort by score descending
-    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    // Definitions first, then by score descending
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then(b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal))
+    });
 
     // Build results with related nodes
     let mut results: Vec<CodeQueryResult> = Vec::new();
-    let mut seen = std::collections::HashSet::new();
+    let mut seen = HashSet::new();
 
-    for (label, score, nid) in &scored {
+    for (_, label, score, nid) in &scored {
         if seen.contains(label) {
             continue;
         }
@@ -103,8 +151,10 @@ pub fn code_query(colony: &Colony, query: &str, max_results: usize) -> Vec<CodeQ
 
         let related: Vec<String> = neighbors.iter().take(10).map(|(l, _)| l.clone()).collect();
 
+        let (namespace, plain) = split_qualified_label(label);
         results.push(CodeQueryResult {
-            label: label.clone(),
+            label: plain.to_string(),
+            namespace: namespace.map(str::to_string),
             score: *score,
             related,
         });
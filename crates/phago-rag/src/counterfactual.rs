@@ -12,6 +12,11 @@
 //! 3. Re-run the same query on the modified graph
 //! 4. Compare rankings to quantify the intervention's impact
 //!
+//! [`attribute_edge`] answers the question behind many interventions,
+//! "which documents made this edge strong?", from the edge's recorded
+//! sources (see [`phago_runtime::provenance`]) when the colony keeps them,
+//! and otherwise from what each document's digestion contributed.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -21,12 +26,13 @@
 //!     from_label: "cell".into(),
 //!     to_label: "membrane".into(),
 //! };
-//! let result = counterfactual_query(&colony, &intervention, "cell biology", &Default::default());
+//! let result = counterfactual_query(&colony, &intervention, "cell biology", &Default::default())?;
 //! println!("Impact: {} rank changes", result.rank_changes.len());
 //! ```
 
-use phago_core::topology::TopologyGraph;
+use crate::hybrid::{hybrid_query, HybridConfig, HybridConfigError};
 use phago_runtime::colony::Colony;
+use phago_runtime::provenance::EdgeSource;
 use phago_runtime::session::{self, GraphState};
 use std::collections::HashMap;
 
@@ -104,24 +110,22 @@ impl Default for CounterfactualConfig {
 ///
 /// This is a graph-level operation that works on the serialized state,
 /// so it doesn't require cloning the Colony (which isn't Clone).
+/// Fails if `alpha` or `max_results` are out of range.
 pub fn counterfactual_query(
     colony: &Colony,
     intervention: &Intervention,
     query: &str,
     config: &CounterfactualConfig,
-) -> CounterfactualResult {
+) -> Result<CounterfactualResult, HybridConfigError> {
     use phago_runtime::session::restore_into_colony;
 
+    let hconfig = HybridConfig::builder()
+        .alpha(config.alpha)
+        .max_results(config.max_results)
+        .build()?;
+
     // Step 1: Get baseline results from the current colony
-    let baseline_results = {
-        use crate::hybrid::{hybrid_query, HybridConfig};
-        let hconfig = HybridConfig {
-            alpha: config.alpha,
-            max_results: config.max_results,
-            candidate_multiplier: 3,
-        };
-        hybrid_query(colony, query, &hconfig)
-    };
+    let baseline_results = hybrid_query(colony, query, &hconfig)?;
 
     let baseline_ranks: Vec<RankedConcept> = baseline_results
         .iter()
@@ -140,15 +144,8 @@ pub fn counterfactual_query(
     // Step 3: Restore into a temporary colony and re-query
     let counterfactual_results = {
         let mut temp_colony = Colony::new();
-        restore_into_colony(&mut temp_colony, &modified_state);
-
-        use crate::hybrid::{hybrid_query, HybridConfig};
-        let hconfig = HybridConfig {
-            alpha: config.alpha,
-            max_results: config.max_results,
-            candidate_multiplier: 3,
-        };
-        hybrid_query(&temp_colony, query, &hconfig)
+        restore_into_colony(&mut temp_colony, &modified_state, None);
+        hybrid_query(&temp_colony, query, &hconfig)?
     };
 
     let counterfactual_ranks: Vec<RankedConcept> = counterfactual_results
@@ -201,7 +198,7 @@ pub fn counterfactual_query(
     }
 
     // Sort by absolute rank delta
-    rank_changes.sort_by(|a, b| b.rank_delta.abs().cmp(&a.rank_delta.abs()));
+    rank_changes.sort_by_key(|c| std::cmp::Reverse(c.rank_delta.abs()));
 
     let significant = rank_changes
         .iter()
@@ -229,13 +226,106 @@ pub fn counterfactual_query(
         }
     };
 
-    CounterfactualResult {
+    Ok(CounterfactualResult {
         intervention: intervention_desc,
         baseline_ranks,
         counterfactual_ranks,
         rank_changes,
         significant,
-    }
+    })
+}
+
+/// Which documents an edge's strength is credited to.
+#[derive(Debug, Clone)]
+pub struct EdgeAttribution {
+    pub from_label: String,
+    pub to_label: String,
+    /// Documents by title with their share of the edge's reinforcements,
+    /// the largest first.
+    pub documents: Vec<DocumentShare>,
+    /// Share of reinforcements by queries, or by no document.
+    pub other: f64,
+    /// Where the shares come from.
+    pub basis: AttributionBasis,
+}
+
+/// A document's share of an edge's reinforcements.
+#[derive(Debug, Clone)]
+pub struct DocumentShare {
+    pub title: String,
+    pub share: f64,
+}
+
+/// What an [`EdgeAttribution`] was computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributionBasis {
+    /// The edge's recent sources, as the colony recorded them.
+    Provenance,
+    /// Every document's contribution, set against the edge's co-activations.
+    Contributions,
+}
+
+/// Credit the edge between the concepts labelled `from_label` and
+/// `to_label` to the documents that reinforced it.
+///
+/// Uses the edge's recorded sources when there are any; they cover only
+/// its last few reinforcements, but name queries too. Colonies that keep
+/// no provenance fall back on document contributions. Returns `None` if
+/// there is no such edge.
+pub fn attribute_edge(
+    colony: &Colony,
+    from_label: &str,
+    to_label: &str,
+) -> Option<EdgeAttribution> {
+    let graph = colony.substrate().graph();
+    let from = *graph.find_nodes_by_exact_label(from_label).first()?;
+    let to = *graph.find_nodes_by_exact_label(to_label).first()?;
+    let edge = graph.get_edge(&from, &to)?;
+
+    let mut counts: HashMap<String, f64> = HashMap::new();
+    let sources = colony.edge_sources(&from, &to);
+    let (basis, total) = if sources.is_empty() {
+        let key = if from <= to { (from, to) } else { (to, from) };
+        for doc in colony.substrate().all_documents() {
+            let added = colony
+                .document_contribution(&doc.id)
+                .and_then(|c| c.edges.get(&key));
+            if let Some(added) = added {
+                *counts.entry(doc.title.clone()).or_insert(0.0) += added.co_activations as f64;
+            }
+        }
+        (AttributionBasis::Contributions, edge.co_activations as f64)
+    } else {
+        for record in &sources {
+            if let EdgeSource::Document(id) = record.source {
+                if let Some(doc) = colony.substrate().get_document(&id) {
+                    *counts.entry(doc.title.clone()).or_insert(0.0) += 1.0;
+                }
+            }
+        }
+        (AttributionBasis::Provenance, sources.len() as f64)
+    };
+
+    let mut documents: Vec<DocumentShare> = counts
+        .into_iter()
+        .map(|(title, count)| DocumentShare {
+            title,
+            share: if total > 0.0 {
+                (count / total).min(1.0)
+            } else {
+                0.0
+            },
+        })
+        .collect();
+    documents.sort_by(|a, b| b.share.total_cmp(&a.share).then(a.title.cmp(&b.title)));
+    let credited: f64 = documents.iter().map(|d| d.share).sum();
+    Some(EdgeAttribution {
+        from_label: graph.get_node(&from)?.label.clone(),
+        to_label: graph.get_node(&to)?.label.clone(),
+        documents,
+        other: (1.0 - credited).max(0.0),
+        basis,
+    })
 }
 
 /// Snapshot the colony's graph into a GraphState (without saving to disk).
@@ -253,7 +343,12 @@ fn snapshot_state(colony: &Colony) -> GraphState {
             position_x: n.position.x,
             position_y: n.position.y,
             created_tick: n.created_tick,
-            embedding: n.embedding.clone(),
+            last_accessed_tick: n.last_accessed_tick,
+            embedding: colony.substrate().embeddings().packed(&n.id).cloned(),
+            tags: n.tags.clone(),
+            aliases: graph.aliases(&n.id),
+            annotations: graph.annotations(&n.id),
+            anomaly_score: colony.anomaly_score(&n.id),
         })
         .collect();
 
@@ -270,6 +365,7 @@ fn snapshot_state(colony: &Colony) -> GraphState {
                 co_activations: edge.co_activations,
                 created_tick: edge.created_tick,
                 last_activated_tick: edge.last_activated_tick,
+                sources: Vec::new(),
             })
         })
         .collect();
@@ -287,8 +383,15 @@ fn snapshot_state(colony: &Colony) -> GraphState {
             node_count,
             edge_count,
             agent_count: 0,
+            format_version: session::FORMAT_VERSION,
             files_indexed: vec![],
+            population: vec![],
         },
+        query_log: Vec::new(),
+        documents: Vec::new(),
+        embedding_storage: colony.substrate().embeddings().storage(),
+        regions: colony.substrate().regions().to_vec(),
+        embedding_cache: Vec::new(),
     }
 }
 
@@ -346,6 +449,9 @@ mod tests {
             title: "Bio".into(),
             content: "cell membrane protein transport channel receptor signaling pathway".into(),
             ticks: Some(15),
+            tags: Vec::new(),
+            ttl_ticks: None,
+            ttl_seconds: None,
         };
         crate::mcp::phago_remember(&mut colony, &req);
 
@@ -353,6 +459,9 @@ mod tests {
             title: "Bio2".into(),
             content: "cell membrane lipid bilayer phospholipid structure".into(),
             ticks: Some(15),
+            tags: Vec::new(),
+            ttl_ticks: None,
+            ttl_seconds: None,
         };
         crate::mcp::phago_remember(&mut colony, &req2);
 
@@ -373,7 +482,8 @@ mod tests {
             &intervention,
             "cell membrane",
             &CounterfactualConfig::default(),
-        );
+        )
+        .unwrap();
 
         // Should produce some results
         assert!(!result.baseline_ranks.is_empty());
@@ -393,7 +503,8 @@ mod tests {
             &intervention,
             "cell membrane",
             &CounterfactualConfig::default(),
-        );
+        )
+        .unwrap();
 
         // "membrane" should not appear in counterfactual results
         let has_membrane = result
@@ -406,6 +517,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn edges_are_attributed_from_provenance_when_kept() {
+        let colony = setup_colony();
+        let attribution = attribute_edge(&colony, "cell", "membrane").unwrap();
+        assert_eq!(attribution.basis, AttributionBasis::Provenance);
+        let titles: Vec<&str> = attribution
+            .documents
+            .iter()
+            .map(|d| d.title.as_str())
+            .collect();
+        assert!(titles.contains(&"Bio") && titles.contains(&"Bio2"));
+
+        // Without provenance, contributions give the same documents
+        let mut colony = Colony::from_config(phago_runtime::colony::ColonyConfig {
+            edge_provenance: 0,
+            ..Default::default()
+        });
+        for (title, content) in [
+            ("Bio", "cell membrane protein transport channel receptor"),
+            ("Bio2", "cell membrane lipid bilayer phospholipid structure"),
+        ] {
+            crate::mcp::phago_remember(
+                &mut colony,
+                &crate::mcp::RememberRequest {
+                    title: title.into(),
+                    content: content.into(),
+                    ticks: Some(15),
+                    tags: Vec::new(),
+                    ttl_ticks: None,
+                    ttl_seconds: None,
+                },
+            );
+        }
+        let attribution = attribute_edge(&colony, "cell", "membrane").unwrap();
+        assert_eq!(attribution.basis, AttributionBasis::Contributions);
+        assert_eq!(attribution.documents.len(), 2);
+        assert!(attribute_edge(&colony, "cell", "nonexistent_node_xyz").is_none());
+    }
+
     #[test]
     fn identical_intervention_no_changes() {
         let colony = setup_colony();
@@ -421,7 +571,8 @@ mod tests {
             &intervention,
             "cell membrane",
             &CounterfactualConfig::default(),
-        );
+        )
+        .unwrap();
 
         // Should have no rank changes
         assert!(
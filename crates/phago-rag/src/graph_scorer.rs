@@ -0,0 +1,462 @@
+//! The graph component of hybrid scores.
+//!
+//! A [hybrid query](crate::hybrid) picks its candidates by TF-IDF, then
+//! scores each against the graph with the [`GraphScorer`] set in
+//! [`HybridConfig::graph_scorer`](crate::hybrid::HybridConfig). Scorers see
+//! the colony through a [`ScoringView`]: the nodes the query may return,
+//! the seed nodes its terms matched exactly, and the candidate pool.
+//!
+//! In-tree scorers:
+//!
+//! - [`DefaultGraphScorer`] — seed edge strength, co-activations, degree
+//!   and access count. The default.
+//! - [`PersonalizedPageRankScorer`] — PageRank seeded from the query's
+//!   matched nodes, relative to the best candidate.
+//! - [`DegreeNormalizedScorer`] — seed edges and access count, with access
+//!   damped by log-degree so hubs don't dominate.
+//! - [`CommunityBoostScorer`] — the default score, boosted for candidates
+//!   in a community that matches several query terms.
+//!
+//! [`named`] looks a scorer up by the names in [`NAMES`], for benchmarks
+//! and command lines that compare them.
+
+use crate::hybrid::RankSource;
+use phago_core::tokenize::Tokenizer;
+use phago_core::topology::TopologyGraph;
+use phago_core::types::NodeId;
+use phago_runtime::colony::Colony;
+use phago_runtime::community::{detect, CommunityAlgorithm};
+use phago_runtime::graph_algorithms::{pagerank_within, PageRankConfig};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Scores hybrid query candidates against the graph.
+///
+/// Queries are cached per scorer by its `Debug` output, so it should show
+/// every setting that changes scores.
+pub trait GraphScorer: Send + Sync + std::fmt::Debug {
+    /// Graph score of `candidate` for a query of `query_terms`, in [0, 1].
+    fn score(&self, view: &ScoringView<'_>, candidate: NodeId, query_terms: &[String]) -> f64;
+
+    /// Graph scores of every candidate in `view`, in order. Override it
+    /// when scoring shares work across candidates.
+    fn score_candidates(&self, view: &ScoringView<'_>, query_terms: &[String]) -> Vec<f64> {
+        view.candidates()
+            .iter()
+            .map(|candidate| self.score(view, *candidate, query_terms))
+            .collect()
+    }
+}
+
+/// What a [`GraphScorer`] sees of the colony for one query.
+#[derive(Clone, Copy)]
+pub struct ScoringView<'a> {
+    source: &'a dyn RankSource,
+    scope: Option<&'a [String]>,
+    min_anomaly_score: Option<f64>,
+    seeds: &'a [NodeId],
+    candidates: &'a [NodeId],
+}
+
+impl<'a> ScoringView<'a> {
+    /// A view of `colony` restricted to nodes carrying one of `scope`'s
+    /// tags, leaving out anomalies scored below `min_anomaly_score`.
+    pub fn new(
+        colony: &'a Colony,
+        scope: Option<&'a [String]>,
+        min_anomaly_score: Option<f64>,
+        seeds: &'a [NodeId],
+        candidates: &'a [NodeId],
+    ) -> Self {
+        Self::of(colony, scope, min_anomaly_score, seeds, candidates)
+    }
+
+    /// [`new`](Self::new), over whatever the query ranks.
+    pub(crate) fn of(
+        source: &'a dyn RankSource,
+        scope: Option<&'a [String]>,
+        min_anomaly_score: Option<f64>,
+        seeds: &'a [NodeId],
+        candidates: &'a [NodeId],
+    ) -> Self {
+        Self {
+            source,
+            scope,
+            min_anomaly_score,
+            seeds,
+            candidates,
+        }
+    }
+
+    /// The colony queried, or `None` for a
+    /// [`StaticColony`](crate::static_colony::StaticColony).
+    pub fn colony(&self) -> Option<&'a Colony> {
+        self.source.colony()
+    }
+
+    /// The tokenizer the query was split with.
+    pub fn tokenizer(&self) -> &'a Tokenizer {
+        self.source.tokenizer()
+    }
+
+    pub fn graph(&self) -> &'a dyn TopologyGraph {
+        self.source.graph()
+    }
+
+    /// Tags the query is restricted to, if any.
+    pub fn scope(&self) -> Option<&'a [String]> {
+        self.scope
+    }
+
+    /// Nodes a query term names exactly.
+    pub fn seeds(&self) -> &'a [NodeId] {
+        self.seeds
+    }
+
+    /// The TF-IDF candidate pool, best first.
+    pub fn candidates(&self) -> &'a [NodeId] {
+        self.candidates
+    }
+
+    /// Whether `node` carries one of the scope's tags.
+    pub fn in_scope(&self, node: &NodeId) -> bool {
+        self.graph()
+            .get_node(node)
+            .is_some_and(|n| n.in_scope(self.scope))
+    }
+
+    /// Whether the query may return `node`: it is in scope and not an
+    /// anomaly scored below the threshold.
+    pub fn contains(&self, node: &NodeId) -> bool {
+        let sub_threshold = match self.min_anomaly_score {
+            Some(min) => self
+                .source
+                .anomaly_score(node)
+                .is_some_and(|score| score < min),
+            None => false,
+        };
+        self.in_scope(node) && !sub_threshold
+    }
+
+    /// Every node the query may return.
+    pub fn collection(&self) -> Vec<NodeId> {
+        self.graph()
+            .all_nodes()
+            .into_iter()
+            .filter(|nid| self.contains(nid))
+            .collect()
+    }
+
+    /// Neighbors of `node` in scope.
+    pub fn degree(&self, node: &NodeId) -> usize {
+        self.graph()
+            .neighbors(node)
+            .iter()
+            .filter(|(id, _)| self.in_scope(id))
+            .count()
+    }
+
+    /// Access count of `node`, per the scope's tags when scoped.
+    pub fn access_count(&self, node: &NodeId) -> u64 {
+        self.graph()
+            .get_node(node)
+            .map_or(0, |n| n.scoped_access_count(self.scope))
+    }
+
+    /// Strongest edge weight from a seed to `node`, and the co-activations
+    /// summed over all seed edges. A seed has no edge to itself.
+    pub fn seed_connectivity(&self, node: &NodeId) -> (f64, u64) {
+        let graph = self.graph();
+        let mut max_weight = 0.0_f64;
+        let mut co_activations = 0_u64;
+        for seed in self.seeds.iter().filter(|seed| *seed != node) {
+            if let Some(edge) = graph.get_edge(seed, node) {
+                max_weight = max_weight.max(edge.weight);
+                co_activations += edge.co_activations;
+            }
+        }
+        (max_weight, co_activations)
+    }
+}
+
+/// `ln(x)` scaled to reach 1 around 150, floored at 0 and capped at 1.
+fn log_score(x: f64) -> f64 {
+    (x.ln().max(0.0) / 5.0).min(1.0)
+}
+
+/// Seed edge strength (0.4), co-activations (0.1, diminishing), degree
+/// (0.2) and access count (0.3), capped at 1.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultGraphScorer;
+
+impl GraphScorer for DefaultGraphScorer {
+    fn score(&self, view: &ScoringView<'_>, candidate: NodeId, _query_terms: &[String]) -> f64 {
+        let (max_weight, co_activations) = view.seed_connectivity(&candidate);
+        let mut score = max_weight * 0.4 + (co_activations as f64).ln().max(0.0) * 0.1;
+        if view.graph().get_node(&candidate).is_some() {
+            score += log_score(view.degree(&candidate) as f64) * 0.2;
+            score += log_score(view.access_count(&candidate) as f64) * 0.3;
+        }
+        score.min(1.0)
+    }
+}
+
+/// Personalized PageRank seeded from the query's seed nodes, over the
+/// nodes the query may return, relative to the best candidate.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PersonalizedPageRankScorer(pub PageRankConfig);
+
+impl PersonalizedPageRankScorer {
+    fn ranks(&self, view: &ScoringView<'_>) -> (HashMap<NodeId, f64>, f64) {
+        let ranks = pagerank_within(view.graph(), &view.collection(), view.seeds(), &self.0);
+        let best = view
+            .candidates()
+            .iter()
+            .filter_map(|nid| ranks.get(nid))
+            .fold(0.0_f64, |a, b| a.max(*b));
+        (ranks, best)
+    }
+
+    fn relative(ranks: &HashMap<NodeId, f64>, best: f64, candidate: &NodeId) -> f64 {
+        if best > 0.0 {
+            ranks.get(candidate).copied().unwrap_or(0.0) / best
+        } else {
+            0.0
+        }
+    }
+}
+
+impl GraphScorer for PersonalizedPageRankScorer {
+    fn score(&self, view: &ScoringView<'_>, candidate: NodeId, _query_terms: &[String]) -> f64 {
+        let (ranks, best) = self.ranks(view);
+        Self::relative(&ranks, best, &candidate)
+    }
+
+    fn score_candidates(&self, view: &ScoringView<'_>, _query_terms: &[String]) -> Vec<f64> {
+        let (ranks, best) = self.ranks(view);
+        view.candidates()
+            .iter()
+            .map(|candidate| Self::relative(&ranks, best, candidate))
+            .collect()
+    }
+}
+
+/// Seed edge strength (0.4), co-activations (0.1, diminishing) and access
+/// count (0.5) divided by `1 + ln(1 + degree)`, capped at 1.
+///
+/// Hubs are accessed whenever any of their neighbors is, so under
+/// [`DefaultGraphScorer`] they outrank specific concepts on most queries;
+/// here degree counts against them instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DegreeNormalizedScorer;
+
+impl GraphScorer for DegreeNormalizedScorer {
+    fn score(&self, view: &ScoringView<'_>, candidate: NodeId, _query_terms: &[String]) -> f64 {
+        let (max_weight, co_activations) = view.seed_connectivity(&candidate);
+        let access = log_score(view.access_count(&candidate) as f64);
+        let damping = 1.0 + (view.degree(&candidate) as f64).ln_1p();
+        let score =
+            max_weight * 0.4 + (co_activations as f64).ln().max(0.0) * 0.1 + access / damping * 0.5;
+        score.min(1.0)
+    }
+}
+
+/// [`DefaultGraphScorer`], plus `boost` for a candidate whose community
+/// matches every query term, in proportion for fewer, and nothing for
+/// fewer than two.
+///
+/// A community matches a term when one of its seeds or candidates has the
+/// term among its label's words. Communities are detected over the whole
+/// graph with `algorithm`, once per query.
+#[derive(Debug, Clone, Copy)]
+pub struct CommunityBoostScorer {
+    pub algorithm: CommunityAlgorithm,
+    pub boost: f64,
+}
+
+impl Default for CommunityBoostScorer {
+    fn default() -> Self {
+        Self {
+            algorithm: CommunityAlgorithm::default(),
+            boost: 0.3,
+        }
+    }
+}
+
+impl CommunityBoostScorer {
+    /// Each candidate's share of the boost, in order.
+    fn shares(&self, view: &ScoringView<'_>, query_terms: &[String]) -> Vec<f64> {
+        let terms: HashSet<&String> = query_terms.iter().collect();
+        if terms.len() < 2 {
+            return vec![0.0; view.candidates().len()];
+        }
+        let graph = view.graph();
+        let communities = detect(graph, self.algorithm);
+        let community_of = |nid: &NodeId| {
+            graph
+                .get_node(nid)
+                .and_then(|n| communities.assignments.get(&n.label).copied())
+        };
+
+        // Query terms matched in each community
+        let mut matched: HashMap<usize, HashSet<&String>> = HashMap::new();
+        for nid in view.seeds().iter().chain(view.candidates()) {
+            let (Some(node), Some(community)) = (graph.get_node(nid), community_of(nid)) else {
+                continue;
+            };
+            let words: HashSet<String> = view.tokenizer().words(&node.label).collect();
+            matched
+                .entry(community)
+                .or_default()
+                .extend(terms.iter().filter(|t| words.contains(t.as_str())));
+        }
+
+        view.candidates()
+            .iter()
+            .map(|nid| {
+                let count = community_of(nid)
+                    .and_then(|c| matched.get(&c))
+                    .map_or(0, HashSet::len);
+                if count < 2 {
+                    0.0
+                } else {
+                    (count - 1) as f64 / (terms.len() - 1) as f64
+                }
+            })
+            .collect()
+    }
+}
+
+impl GraphScorer for CommunityBoostScorer {
+    fn score(&self, view: &ScoringView<'_>, candidate: NodeId, query_terms: &[String]) -> f64 {
+        let Some(position) = view.candidates().iter().position(|c| *c == candidate) else {
+            return DefaultGraphScorer.score(view, candidate, query_terms);
+        };
+        self.score_candidates(view, query_terms)[position]
+    }
+
+    fn score_candidates(&self, view: &ScoringView<'_>, query_terms: &[String]) -> Vec<f64> {
+        let base = DefaultGraphScorer.score_candidates(view, query_terms);
+        base.into_iter()
+            .zip(self.shares(view, query_terms))
+            .map(|(score, share)| (score + self.boost * share).min(1.0))
+            .collect()
+    }
+}
+
+/// Names [`named`] accepts.
+pub const NAMES: [&str; 4] = [
+    "default",
+    "pagerank",
+    "degree-normalized",
+    "community-boost",
+];
+
+/// The in-tree scorer called `name` (see [`NAMES`]), with default settings.
+pub fn named(name: &str) -> Option<Arc<dyn GraphScorer>> {
+    match name {
+        "default" => Some(Arc::new(DefaultGraphScorer)),
+        "pagerank" => Some(Arc::new(PersonalizedPageRankScorer::default())),
+        "degree-normalized" => Some(Arc::new(DegreeNormalizedScorer)),
+        "community-boost" => Some(Arc::new(CommunityBoostScorer::default())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_core::substrate::Substrate;
+    use phago_core::types::{EdgeData, NodeData, NodeType, Position};
+
+    /// `cell` linked to `cell transport` and `cell wall`; `cell transport`
+    /// linked to `membrane` too.
+    fn small_graph() -> (Colony, [NodeId; 4]) {
+        let mut colony = Colony::new();
+        let mut add = |label: &str, access_count| {
+            colony.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: label.to_string(),
+                node_type: NodeType::Concept,
+                position: Position::new(0.0, 0.0),
+                access_count,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            })
+        };
+        let nodes = [
+            add("cell", 40),
+            add("cell transport", 12),
+            add("cell wall", 3),
+            add("membrane", 1),
+        ];
+        let [cell, transport, wall, membrane] = nodes;
+        let edge = |weight, co_activations| EdgeData {
+            weight,
+            co_activations,
+            created_tick: 0,
+            last_activated_tick: 0,
+        };
+        let substrate = colony.substrate_mut();
+        substrate.set_edge(cell, transport, edge(0.8, 5)).unwrap();
+        substrate.set_edge(cell, wall, edge(0.3, 1)).unwrap();
+        substrate
+            .set_edge(transport, membrane, edge(0.5, 2))
+            .unwrap();
+        (colony, nodes)
+    }
+
+    fn scores(
+        scorer: &dyn GraphScorer,
+        colony: &Colony,
+        seeds: &[NodeId],
+        nodes: &[NodeId],
+    ) -> Vec<f64> {
+        let terms = vec!["cell".to_string(), "transport".to_string()];
+        let view = ScoringView::new(colony, None, None, seeds, nodes);
+        let scores = scorer.score_candidates(&view, &terms);
+        for (candidate, score) in nodes.iter().zip(&scores) {
+            assert_eq!(scorer.score(&view, *candidate, &terms), *score);
+        }
+        scores
+    }
+
+    #[test]
+    fn default_scores_are_pinned() {
+        let (colony, nodes) = small_graph();
+        let scores = scores(&DefaultGraphScorer, &colony, &nodes[..1], &nodes);
+        let expected = [0.249_058_654_469, 0.657_764_077_453, 0.185_916_737_320, 0.0];
+        for (score, expected) in scores.iter().zip(expected) {
+            assert!((score - expected).abs() < 1e-9, "{:?}", scores);
+        }
+    }
+
+    #[test]
+    fn degree_normalization_damps_hubs() {
+        let (colony, nodes) = small_graph();
+        let [cell, _, wall, _] = nodes;
+        // Unseeded, the hub's lead is access and degree, and shrinks
+        let default = scores(&DefaultGraphScorer, &colony, &[], &[cell, wall]);
+        let damped = scores(&DegreeNormalizedScorer, &colony, &[], &[cell, wall]);
+        assert!(default[0] > default[1]);
+        assert!(damped[0] / damped[1] < default[0] / default[1]);
+    }
+
+    #[test]
+    fn communities_matching_several_terms_are_boosted() {
+        let (colony, nodes) = small_graph();
+        let scorer = CommunityBoostScorer {
+            algorithm: CommunityAlgorithm::LabelPropagation { seed: 0 },
+            boost: 0.3,
+        };
+        let base = scores(&DefaultGraphScorer, &colony, &nodes[..1], &nodes);
+        let boosted = scores(&scorer, &colony, &nodes[..1], &nodes);
+        // "cell" and "transport" are both matched in their community
+        let transport = 1;
+        assert!((boosted[transport] - (base[transport] + 0.3).min(1.0)).abs() < 1e-12);
+        for (base, boosted) in base.iter().zip(&boosted) {
+            assert!(boosted >= base);
+        }
+    }
+}
@@ -2,26 +2,106 @@
 //!
 //! Strategy:
 //! 1. TF-IDF generates a broad candidate set (2x max_results)
-//! 2. Graph structure re-ranks candidates using:
+//! 2. Graph structure re-ranks candidates using, by default:
 //!    - Edge weight to query seed nodes (direct connectivity)
 //!    - Co-activation count (reinforcement signal)
 //!    - Node degree / centrality (hub importance)
 //!    - Access count (usage frequency)
-//! 3. Final score = alpha * tfidf_score + (1 - alpha) * graph_score
+//!
+//!    `graph_scorer` swaps in another [`GraphScorer`], such as personalized
+//!    PageRank seeded from the query's matched nodes; see
+//!    [`crate::graph_scorer`].
+//! 3. Final score = alpha * tfidf_score + (1 - alpha) * graph_score, so
+//!    alpha = 1.0 is pure TF-IDF and alpha = 0.0 is pure graph structure
+//! 4. Insight nodes are scaled by `insight_boost` (their labels embed several
+//!    concept names, so they tend to over-match on TF-IDF)
+//!
+//! Query terms are expanded with the colony's synonyms (see
+//! [`phago_runtime::synonyms`]): a node named by a synonym of a term
+//! matches as exactly as one named by the term, scaled down by the
+//! expansion weight, and seeds the graph score.
+//!
+//! With `tags` set, all of the above only sees nodes carrying one of the
+//! tags, and access counts are the per-tag counts for those tags.
+//!
+//! TF-IDF reads term and document frequencies from the colony's term index
+//! (see [`phago_runtime::inverted_index`]) rather than scanning every
+//! label, unless it is disabled or `tags` or `min_anomaly_score` narrow the
+//! collection it would have to cover. Both paths score identically.
+//!
+//! With `include_passages` set, each result also carries the document
+//! sentences its concept was digested from, ranked by how many query terms
+//! and result concepts they mention.
+//!
+//! [`hybrid_query_cached`] answers repeated queries from a [`QueryCache`]
+//! for as long as the colony's graph has not changed.
+//!
+//! [`hybrid_query_page`] serves results a page at a time. The first page
+//! ranks the top [`PAGE_DEPTH`] results once and returns an opaque cursor;
+//! later pages are slices of that same ranking (reused from the cache, if
+//! any), so pages never overlap or skip results. A cursor names the graph
+//! revision it was issued at: once the graph changes, following it yields
+//! a fresh first page flagged as expired.
+//!
+//! With a reranker set (see [`HybridConfig::with_reranker`]), the best
+//! candidates are rescored by an external model after all of the above,
+//! before truncation to `max_results`; see [`crate::rerank`].
+//!
+//! A [`StaticColony`](crate::static_colony::StaticColony) ranks a published
+//! bundle the same way, without the colony.
 
+use crate::cache::{config_key, QueryCache, Ranked};
+use crate::graph_scorer::{DefaultGraphScorer, GraphScorer, ScoringView};
+use crate::rerank::{rerank, RerankStage, Reranker};
+use phago_core::substrate::Substrate;
+use phago_core::tokenize::Tokenizer;
 use phago_core::topology::TopologyGraph;
+use phago_core::types::{DocumentId, NodeId, NodeType, TextSpan};
 use phago_runtime::colony::Colony;
-use std::collections::HashMap;
+use phago_runtime::inverted_index::InvertedIndex;
+use phago_runtime::query_log::{EdgeLabels, QueryLogEntry, QuerySource};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Results ranked for a paged query: pages are slices of the top
+/// `PAGE_DEPTH` results, or of a single page if that is larger.
+pub const PAGE_DEPTH: usize = 100;
 
 /// Configuration for hybrid scoring.
+///
+/// Build one with [`HybridConfig::builder`], which validates the result.
 #[derive(Debug, Clone)]
 pub struct HybridConfig {
-    /// Weight for TF-IDF component (0.0 to 1.0). Graph weight = 1.0 - alpha.
+    /// Weight for TF-IDF component (0.0 to 1.0). Graph weight = 1.0 - alpha,
+    /// so 0.0 is pure graph and 1.0 is pure TF-IDF.
     pub alpha: f64,
     /// Maximum results to return.
     pub max_results: usize,
     /// Size of TF-IDF candidate pool (multiplier on max_results).
     pub candidate_multiplier: usize,
+    /// Multiplier on the final score of Insight nodes.
+    /// 1.0 ranks them like concepts, < 1.0 penalizes, > 1.0 boosts.
+    pub insight_boost: f64,
+    /// Restrict candidates and graph scoring to nodes carrying at least one
+    /// of these tags (`None` searches the whole graph).
+    pub tags: Option<Vec<String>>,
+    /// Caller recorded in the colony's query log.
+    pub source: QuerySource,
+    /// How candidates are scored against the graph.
+    pub graph_scorer: Arc<dyn GraphScorer>,
+    /// Attach the source passages each result was digested from.
+    pub include_passages: bool,
+    /// Most passages attached to one result.
+    pub max_passages: usize,
+    /// Leave out anomaly nodes scored below this (see
+    /// [`Colony::anomaly_score`]). Unscored anomalies are kept; `None`
+    /// keeps every anomaly.
+    pub min_anomaly_score: Option<f64>,
+    /// Rescore the best candidates with an external reranker.
+    pub reranker: Option<RerankStage>,
 }
 
 impl Default for HybridConfig {
@@ -30,80 +110,613 @@ impl Default for HybridConfig {
             alpha: 0.5,
             max_results: 10,
             candidate_multiplier: 3,
+            insight_boost: 1.0,
+            tags: None,
+            source: QuerySource::default(),
+            graph_scorer: Arc::new(DefaultGraphScorer),
+            include_passages: false,
+            max_passages: 3,
+            min_anomaly_score: None,
+            reranker: None,
+        }
+    }
+}
+
+/// Why a [`HybridConfig`] was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HybridConfigError {
+    /// `alpha` is outside [0, 1] (or not a number).
+    AlphaOutOfRange(f64),
+    /// `max_results` is zero.
+    ZeroMaxResults,
+    /// `candidate_multiplier` is zero.
+    ZeroCandidateMultiplier,
+    /// A page cursor is malformed or belongs to a different query.
+    InvalidCursor,
+    /// The reranker's `blend_weight` is outside [0, 1] (or not a number).
+    BlendWeightOutOfRange(f64),
+}
+
+impl std::fmt::Display for HybridConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HybridConfigError::AlphaOutOfRange(alpha) => write!(
+                f,
+                "alpha must be between 0.0 (pure graph) and 1.0 (pure TF-IDF), got {}",
+                alpha
+            ),
+            HybridConfigError::ZeroMaxResults => write!(f, "max_results must be at least 1"),
+            HybridConfigError::ZeroCandidateMultiplier => {
+                write!(f, "candidate_multiplier must be at least 1")
+            }
+            HybridConfigError::InvalidCursor => {
+                write!(f, "cursor is malformed or belongs to a different query")
+            }
+            HybridConfigError::BlendWeightOutOfRange(weight) => write!(
+                f,
+                "blend_weight must be between 0.0 (hybrid score) and 1.0 (reranker score), got {}",
+                weight
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HybridConfigError {}
+
+impl HybridConfig {
+    /// Start from the defaults.
+    pub fn builder() -> HybridConfigBuilder {
+        HybridConfigBuilder::default()
+    }
+
+    /// Check that the settings produce meaningful rankings.
+    pub fn validate(&self) -> Result<(), HybridConfigError> {
+        if !(0.0..=1.0).contains(&self.alpha) {
+            return Err(HybridConfigError::AlphaOutOfRange(self.alpha));
+        }
+        if self.max_results == 0 {
+            return Err(HybridConfigError::ZeroMaxResults);
+        }
+        if self.candidate_multiplier == 0 {
+            return Err(HybridConfigError::ZeroCandidateMultiplier);
+        }
+        if let Some(stage) = &self.reranker {
+            if !(0.0..=1.0).contains(&stage.blend_weight) {
+                return Err(HybridConfigError::BlendWeightOutOfRange(stage.blend_weight));
+            }
+        }
+        Ok(())
+    }
+
+    /// Rescore the best `top_n` candidates with `reranker`, blending its
+    /// score into the final score with weight `blend_weight`: 0.0 keeps the
+    /// hybrid score, 1.0 replaces it. See [`crate::rerank`].
+    pub fn with_reranker(
+        mut self,
+        reranker: Box<dyn Reranker>,
+        top_n: usize,
+        blend_weight: f64,
+    ) -> Self {
+        self.reranker = Some(RerankStage {
+            reranker: Arc::from(reranker),
+            top_n,
+            blend_weight,
+        });
+        self
+    }
+
+    /// How many candidates scoring keeps: the reranker may need more than
+    /// `max_results`.
+    fn candidates_kept(&self) -> usize {
+        self.reranker
+            .as_ref()
+            .map_or(self.max_results, |stage| self.max_results.max(stage.top_n))
+    }
+}
+
+/// Builder for [`HybridConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct HybridConfigBuilder {
+    config: HybridConfig,
+}
+
+impl HybridConfigBuilder {
+    /// Weight of the TF-IDF score: 0.0 is pure graph, 1.0 pure TF-IDF.
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.config.alpha = alpha;
+        self
+    }
+
+    pub fn max_results(mut self, max_results: usize) -> Self {
+        self.config.max_results = max_results;
+        self
+    }
+
+    pub fn candidate_multiplier(mut self, multiplier: usize) -> Self {
+        self.config.candidate_multiplier = multiplier;
+        self
+    }
+
+    pub fn insight_boost(mut self, boost: f64) -> Self {
+        self.config.insight_boost = boost;
+        self
+    }
+
+    /// Restrict the query to these tags; an empty list searches everything.
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.config.tags = (!tags.is_empty()).then_some(tags);
+        self
+    }
+
+    pub fn source(mut self, source: QuerySource) -> Self {
+        self.config.source = source;
+        self
+    }
+
+    pub fn graph_scorer(mut self, scorer: impl GraphScorer + 'static) -> Self {
+        self.config.graph_scorer = Arc::new(scorer);
+        self
+    }
+
+    /// Attach up to `max_passages` source passages to each result.
+    pub fn include_passages(mut self, include: bool) -> Self {
+        self.config.include_passages = include;
+        self
+    }
+
+    pub fn max_passages(mut self, max_passages: usize) -> Self {
+        self.config.max_passages = max_passages;
+        self
+    }
+
+    /// Leave out anomalies scored below `score`.
+    pub fn min_anomaly_score(mut self, score: f64) -> Self {
+        self.config.min_anomaly_score = Some(score);
+        self
+    }
+
+    /// See [`HybridConfig::with_reranker`].
+    pub fn reranker(self, reranker: Box<dyn Reranker>, top_n: usize, blend_weight: f64) -> Self {
+        Self {
+            config: self.config.with_reranker(reranker, top_n, blend_weight),
         }
     }
+
+    /// Validate and return the configuration.
+    pub fn build(self) -> Result<HybridConfig, HybridConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
 }
 
 /// A hybrid query result with component scores.
 #[derive(Debug, Clone)]
 pub struct HybridResult {
+    /// The concept's node.
+    pub node_id: NodeId,
     pub label: String,
     pub tfidf_score: f64,
     pub graph_score: f64,
+    /// The score results are ranked by. With a reranker, this is the
+    /// blend of the hybrid score and `rerank_score`.
     pub final_score: f64,
+    /// The reranker's score, if it rescored this result.
+    pub rerank_score: Option<f64>,
+    /// Source passages mentioning the concept, best first. Only filled
+    /// in with [`HybridConfig::include_passages`].
+    pub passages: Vec<Passage>,
+}
+
+/// One page of a [paged query](hybrid_query_page).
+#[derive(Debug, Clone)]
+pub struct HybridPage {
+    /// At most `max_results` results, continuing from the previous page.
+    pub results: Vec<HybridResult>,
+    /// Cursor for the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
+    /// The cursor followed was issued before the graph last changed, so
+    /// this is a fresh first page instead of the page it pointed to.
+    pub cursor_expired: bool,
+}
+
+/// Where a paged query continues: which query, at which graph revision,
+/// from which result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cursor {
+    query: u64,
+    revision: u64,
+    offset: usize,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        format!("{:016x}.{:x}.{:x}", self.query, self.revision, self.offset)
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let mut parts = cursor.split('.');
+        let query = u64::from_str_radix(parts.next()?, 16).ok()?;
+        let revision = u64::from_str_radix(parts.next()?, 16).ok()?;
+        let offset = usize::from_str_radix(parts.next()?, 16).ok()?;
+        parts.next().is_none().then_some(Cursor {
+            query,
+            revision,
+            offset,
+        })
+    }
+}
+
+/// Hash identifying a paged query: its terms and every setting but the
+/// source, page size included.
+fn query_key(terms: &[String], config: &HybridConfig) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    terms.hash(&mut hasher);
+    config_key(config).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A passage of an ingested document that a result was digested from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Passage {
+    pub document_id: DocumentId,
+    pub title: String,
+    /// Byte range of the passage within the document's content.
+    pub span: TextSpan,
+    pub snippet: String,
 }
 
 /// Execute a hybrid query: TF-IDF candidates re-ranked by graph structure.
-pub fn hybrid_query(colony: &Colony, query_text: &str, config: &HybridConfig) -> Vec<HybridResult> {
-    let query_terms = tokenize(query_text);
-    if query_terms.is_empty() {
-        return Vec::new();
+///
+/// If the colony's query log is enabled, the query is recorded in it, and
+/// its latency is reported to the colony's metrics registry.
+/// Fails without querying if `config` does not [validate](HybridConfig::validate).
+pub fn hybrid_query(
+    colony: &Colony,
+    query_text: &str,
+    config: &HybridConfig,
+) -> Result<Vec<HybridResult>, HybridConfigError> {
+    run(colony, None, query_text, config)
+}
+
+/// [`hybrid_query`], reusing the results of an earlier identical query
+/// from `cache` if the colony's [graph revision](Colony::graph_revision)
+/// has not changed since.
+///
+/// Queries are identical when they tokenize to the same terms and share
+/// every setting but the source. Cache hits are logged and timed like any
+/// other query.
+pub fn hybrid_query_cached(
+    colony: &Colony,
+    cache: &QueryCache,
+    query_text: &str,
+    config: &HybridConfig,
+) -> Result<Vec<HybridResult>, HybridConfigError> {
+    run(colony, Some(cache), query_text, config)
+}
+
+/// One page of results for `query_text`, `config.max_results` long.
+///
+/// Without a cursor this is the first page; pass the previous page's
+/// `next_cursor` to continue. A cursor issued before the graph last changed
+/// yields a fresh first page with `cursor_expired` set. Only first pages
+/// are recorded in the query log.
+///
+/// Fails if `config` does not [validate](HybridConfig::validate), or with
+/// [`HybridConfigError::InvalidCursor`] if the cursor is malformed or was
+/// issued for a different query or settings.
+pub fn hybrid_query_page(
+    colony: &Colony,
+    query_text: &str,
+    config: &HybridConfig,
+    cursor: Option<&str>,
+) -> Result<HybridPage, HybridConfigError> {
+    run_page(colony, None, query_text, config, cursor)
+}
+
+/// [`hybrid_query_page`], ranking once per graph revision: every page of a
+/// query is sliced from the same cache entry.
+pub fn hybrid_query_page_cached(
+    colony: &Colony,
+    cache: &QueryCache,
+    query_text: &str,
+    config: &HybridConfig,
+    cursor: Option<&str>,
+) -> Result<HybridPage, HybridConfigError> {
+    run_page(colony, Some(cache), query_text, config, cursor)
+}
+
+/// Mark the concepts behind `results` as accessed at the current tick, so
+/// concepts that keep being recalled are not pruned as stale.
+///
+/// Hybrid queries only read the colony; front ends serving recalls from a
+/// colony they own call this with every page they return, as
+/// [`QueryEngine::query`](crate::query::QueryEngine::query) does for what it
+/// surfaces.
+pub fn touch_results(colony: &mut Colony, results: &[HybridResult]) {
+    let ids: Vec<NodeId> = results.iter().map(|r| r.node_id).collect();
+    colony.touch_nodes(&ids);
+}
+
+fn run(
+    colony: &Colony,
+    cache: Option<&QueryCache>,
+    query_text: &str,
+    config: &HybridConfig,
+) -> Result<Vec<HybridResult>, HybridConfigError> {
+    config.validate()?;
+    let started = std::time::Instant::now();
+    let query_terms = colony.tokenizer().tokens(query_text);
+    let ranked = ranked(colony, cache, query_text, &query_terms, config);
+
+    log_query(colony, query_text, query_terms, config, &ranked);
+    colony.record_query_latency(config.source, started.elapsed());
+    Ok(ranked.into_iter().map(|(r, _)| r).collect())
+}
+
+fn run_page(
+    colony: &Colony,
+    cache: Option<&QueryCache>,
+    query_text: &str,
+    config: &HybridConfig,
+    cursor: Option<&str>,
+) -> Result<HybridPage, HybridConfigError> {
+    config.validate()?;
+    let started = std::time::Instant::now();
+    let query_terms = colony.tokenizer().tokens(query_text);
+    let paged = paginate(
+        &query_terms,
+        config,
+        colony.graph_revision(),
+        cursor,
+        |ranking| ranked(colony, cache, query_text, &query_terms, ranking),
+    )?;
+
+    if paged.first {
+        log_query(colony, query_text, query_terms, config, &paged.page);
     }
+    colony.record_query_latency(config.source, started.elapsed());
+    Ok(paged.into_page())
+}
 
-    let graph = colony.substrate().graph();
-    let all_nodes = graph.all_nodes();
-    let total_docs = all_nodes.len().max(1) as f64;
+/// A page sliced from a ranking, seed edges still attached.
+pub(crate) struct Paged {
+    page: Ranked,
+    /// Whether this is a first page.
+    first: bool,
+    next_cursor: Option<String>,
+    cursor_expired: bool,
+}
 
-    // Phase 1: TF-IDF scoring for all nodes
-    let mut df: HashMap<String, usize> = HashMap::new();
-    for nid in &all_nodes {
-        if let Some(node) = graph.get_node(nid) {
-            let unique: std::collections::HashSet<String> = node
-                .label
-                .to_lowercase()
-                .split(|c: char| !c.is_alphanumeric())
-                .filter(|w| w.len() >= 3)
-                .map(|w| w.to_string())
-                .collect();
-            for term in unique {
-                *df.entry(term).or_insert(0) += 1;
+impl Paged {
+    pub(crate) fn into_page(self) -> HybridPage {
+        HybridPage {
+            results: self.page.into_iter().map(|(r, _)| r).collect(),
+            next_cursor: self.next_cursor,
+            cursor_expired: self.cursor_expired,
+        }
+    }
+}
+
+/// The page `cursor` points to, sliced from the ranking `rank` computes
+/// for a graph at `revision` under `config` deepened to [`PAGE_DEPTH`].
+pub(crate) fn paginate(
+    query_terms: &[String],
+    config: &HybridConfig,
+    revision: u64,
+    cursor: Option<&str>,
+    rank: impl FnOnce(&HybridConfig) -> Ranked,
+) -> Result<Paged, HybridConfigError> {
+    let query = query_key(query_terms, config);
+    let (offset, cursor_expired) = match cursor {
+        None => (0, false),
+        Some(cursor) => {
+            let cursor = Cursor::decode(cursor)
+                .filter(|c| c.query == query)
+                .ok_or(HybridConfigError::InvalidCursor)?;
+            if cursor.revision == revision {
+                (cursor.offset, false)
+            } else {
+                (0, true)
             }
         }
+    };
+
+    let ranking = HybridConfig {
+        max_results: config.max_results.max(PAGE_DEPTH),
+        ..config.clone()
+    };
+    let mut ranked = rank(&ranking);
+    let start = offset.min(ranked.len());
+    let end = offset.saturating_add(config.max_results).min(ranked.len());
+    let next_cursor = (end < ranked.len()).then(|| {
+        Cursor {
+            query,
+            revision,
+            offset: end,
+        }
+        .encode()
+    });
+    ranked.truncate(end);
+    let page = ranked.split_off(start);
+    Ok(Paged {
+        page,
+        first: start == 0,
+        next_cursor,
+        cursor_expired,
+    })
+}
+
+/// Rank `query_terms` under `config`, from `cache` if it has them.
+fn ranked(
+    colony: &Colony,
+    cache: Option<&QueryCache>,
+    query_text: &str,
+    query_terms: &[String],
+    config: &HybridConfig,
+) -> Ranked {
+    let compute = || rank_fully(colony, query_text, query_terms, config);
+    match cache {
+        Some(cache) => cache.get_or_compute(colony.graph_revision(), query_terms, config, compute),
+        None => compute(),
     }
+}
 
-    let mut tfidf_scores: Vec<(phago_core::types::NodeId, String, f64)> = Vec::new();
-    for nid in &all_nodes {
-        if let Some(node) = graph.get_node(nid) {
-            let label_lower = node.label.to_lowercase();
-            let label_terms: Vec<String> = label_lower
-                .split(|c: char| !c.is_alphanumeric())
-                .filter(|w| w.len() >= 3)
-                .map(|w| w.to_string())
-                .collect();
+/// Rank `query_terms`, attach passages if asked (and `source` has the
+/// documents) and rerank.
+pub(crate) fn rank_fully(
+    source: &dyn RankSource,
+    query_text: &str,
+    query_terms: &[String],
+    config: &HybridConfig,
+) -> Ranked {
+    let mut ranked = rank(source, query_terms, config);
+    if let Some(colony) = source.colony().filter(|_| config.include_passages) {
+        attach_passages(colony, query_terms, &mut ranked, config.max_passages);
+    }
+    if let Some(stage) = &config.reranker {
+        rerank(stage, query_text, &mut ranked);
+        ranked.truncate(config.max_results);
+    }
+    ranked
+}
 
-            let mut score = 0.0;
-            for qt in &query_terms {
-                let tf = label_terms.iter().filter(|t| *t == qt).count() as f64;
-                if tf > 0.0 {
-                    let idf = (total_docs / (*df.get(qt).unwrap_or(&1) as f64)).ln() + 1.0;
-                    score += tf * idf;
-                }
+/// Record the query and its results, if the colony keeps a query log.
+fn log_query(
+    colony: &Colony,
+    query_text: &str,
+    query_terms: Vec<String>,
+    config: &HybridConfig,
+    ranked: &Ranked,
+) {
+    if colony.query_log().is_some() {
+        let tick = colony.substrate().current_tick();
+        let mut entry = QueryLogEntry::new(tick, config.source, query_text, query_terms);
+        entry.tags = config.tags.clone();
+        entry.results = ranked.iter().map(|(r, _)| r.label.clone()).collect();
+        entry.traversed_edges = ranked.iter().flat_map(|(_, e)| e.iter().cloned()).collect();
+        colony.log_query(entry);
+    }
+}
+
+/// What ranking reads of the knowledge base queried: a colony, or a
+/// published bundle (see [`crate::static_colony`]).
+pub(crate) trait RankSource {
+    fn graph(&self) -> &dyn TopologyGraph;
+    fn tokenizer(&self) -> &Tokenizer;
+    fn anomaly_score(&self, node: &NodeId) -> Option<f64>;
+    /// The term index, if it is current.
+    fn term_index(&self) -> Option<&InvertedIndex>;
+    /// Synonyms of `terms`, with their weights.
+    fn expand_query(&self, terms: &[String]) -> Vec<(String, f64)>;
+    /// The colony itself, which has the documents passages come from.
+    fn colony(&self) -> Option<&Colony> {
+        None
+    }
+}
+
+impl RankSource for Colony {
+    fn graph(&self) -> &dyn TopologyGraph {
+        self.substrate().graph()
+    }
+
+    fn tokenizer(&self) -> &Tokenizer {
+        Colony::tokenizer(self)
+    }
+
+    fn anomaly_score(&self, node: &NodeId) -> Option<f64> {
+        Colony::anomaly_score(self, node)
+    }
+
+    fn term_index(&self) -> Option<&InvertedIndex> {
+        self.substrate().term_index()
+    }
+
+    fn expand_query(&self, terms: &[String]) -> Vec<(String, f64)> {
+        Colony::expand_query(self, terms)
+    }
+
+    fn colony(&self) -> Option<&Colony> {
+        Some(self)
+    }
+}
+
+/// Rank candidates, pairing each result with the seed edges that scored it.
+fn rank(source: &dyn RankSource, query_terms: &[String], config: &HybridConfig) -> Ranked {
+    if query_terms.is_empty() {
+        return Vec::new();
+    }
+
+    let scope = config.tags.as_deref();
+    let tokenizer = source.tokenizer();
+    let graph = source.graph();
+    let sub_threshold = |nid: &NodeId| match config.min_anomaly_score {
+        Some(min) => source.anomaly_score(nid).is_some_and(|score| score < min),
+        None => false,
+    };
+    let in_collection = |nid: &NodeId| {
+        graph.get_node(nid).is_some_and(|n| n.in_scope(scope)) && !sub_threshold(nid)
+    };
+    let collection = || -> Vec<NodeId> {
+        graph
+            .all_nodes()
+            .into_iter()
+            .filter(|nid| in_collection(nid))
+            .collect()
+    };
+
+    // Phase 1: TF-IDF scoring. The term index covers every node, so it
+    // stands in for the scan only when nothing narrows the collection
+    let index = source.term_index().filter(|index| {
+        scope.is_none() && config.min_anomaly_score.is_none() && index.tokenizer() == tokenizer
+    });
+    let mut tfidf_scores = match index {
+        Some(index) => tfidf_indexed(graph, index, query_terms),
+        None => tfidf_scan(graph, tokenizer, &collection(), query_terms),
+    };
+
+    // A query term that is an alias of a node (see
+    // `Colony::compact_labels`) matches it as exactly as its label would
+    for qt in query_terms {
+        for nid in graph.find_nodes_by_exact_label(qt) {
+            let Some(node) = graph.get_node(&nid).filter(|_| in_collection(&nid)) else {
+                continue;
+            };
+            if node.label.to_lowercase() == *qt {
+                continue;
             }
-            // Exact match boost
-            for qt in &query_terms {
-                if label_lower == *qt {
-                    score += 10.0;
-                }
+            match tfidf_scores.iter_mut().find(|(id, _, _)| *id == nid) {
+                Some(entry) => entry.2 += 10.0,
+                None => tfidf_scores.push((nid, node.label.clone(), 10.0)),
             }
+        }
+    }
 
-            if score > 0.0 {
-                tfidf_scores.push((*nid, node.label.clone(), score));
+    // Synonyms of the query terms match exactly too, at the expansion
+    // weight, so a node named as typed still ranks first
+    let expansion = source.expand_query(query_terms);
+    let typed: HashSet<NodeId> = query_terms
+        .iter()
+        .flat_map(|qt| graph.find_nodes_by_exact_label(qt))
+        .collect();
+    let mut expanded_seeds: Vec<NodeId> = Vec::new();
+    for (form, weight) in &expansion {
+        for nid in graph.find_nodes_by_exact_label(form) {
+            if typed.contains(&nid) || expanded_seeds.contains(&nid) || !in_collection(&nid) {
+                continue;
+            }
+            let Some(node) = graph.get_node(&nid) else {
+                continue;
+            };
+            expanded_seeds.push(nid);
+            match tfidf_scores.iter_mut().find(|(id, _, _)| *id == nid) {
+                Some(entry) => entry.2 += 10.0 * weight,
+                None => tfidf_scores.push((nid, node.label.clone(), 10.0 * weight)),
             }
         }
     }
 
     // Sort by TF-IDF score and take top candidates
-    tfidf_scores.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    sort_by_tfidf(&mut tfidf_scores);
     let candidate_count = config.max_results * config.candidate_multiplier;
     tfidf_scores.truncate(candidate_count);
 
@@ -114,98 +727,224 @@ pub fn hybrid_query(colony: &Colony, query_text: &str, config: &HybridConfig) ->
     // Normalize TF-IDF scores to [0, 1]
     let max_tfidf = tfidf_scores.first().map(|s| s.2).unwrap_or(1.0).max(0.001);
 
-    // Phase 2: Find seed nodes (query terms that exactly match graph nodes)
+    // Phase 2: Find seed nodes (query terms, or their synonyms, that
+    // exactly match graph nodes)
     let seed_ids: Vec<phago_core::types::NodeId> = query_terms
         .iter()
         .flat_map(|t| graph.find_nodes_by_exact_label(t).to_vec())
+        .filter(|nid| graph.get_node(nid).is_some_and(|n| n.in_scope(scope)))
+        .chain(expanded_seeds)
         .collect();
 
     // Phase 3: Graph structural scoring for each candidate
-    let mut results: Vec<HybridResult> = Vec::new();
+    let candidates: Vec<NodeId> = tfidf_scores.iter().map(|(nid, _, _)| *nid).collect();
+    let view = ScoringView::of(
+        source,
+        scope,
+        config.min_anomaly_score,
+        &seed_ids,
+        &candidates,
+    );
+    let graph_scores = config.graph_scorer.score_candidates(&view, query_terms);
+    let mut results: Ranked = Vec::new();
 
-    for (nid, label, tfidf_raw) in &tfidf_scores {
+    for ((nid, label, tfidf_raw), graph_score_norm) in tfidf_scores.iter().zip(graph_scores) {
         let tfidf_norm = tfidf_raw / max_tfidf;
 
-        // Graph score components:
-        let mut graph_score = 0.0;
+        // Seed edges that connect to this candidate, for the query log
+        let seed_edges: Vec<EdgeLabels> = seed_ids
+            .iter()
+            .filter(|seed| *seed != nid && graph.get_edge(seed, nid).is_some())
+            .filter_map(|seed| graph.get_node(seed))
+            .map(|seed_node| (seed_node.label.clone(), label.clone()))
+            .collect();
+        let node = graph.get_node(nid);
 
-        // (a) Direct edge connectivity to seed nodes
-        let mut max_edge_weight = 0.0_f64;
-        let mut total_co_activations = 0_u64;
-        for seed in &seed_ids {
-            if seed == nid {
-                continue;
-            }
-            if let Some(edge) = graph.get_edge(seed, nid) {
-                max_edge_weight = max_edge_weight.max(edge.weight);
-                total_co_activations += edge.co_activations;
+        // Final blended score
+        let mut final_score = config.alpha * tfidf_norm + (1.0 - config.alpha) * graph_score_norm;
+        if node.is_some_and(|n| n.node_type == NodeType::Insight) {
+            final_score *= config.insight_boost;
+        }
+
+        results.push((
+            HybridResult {
+                node_id: *nid,
+                label: label.clone(),
+                tfidf_score: tfidf_norm,
+                graph_score: graph_score_norm,
+                final_score,
+                rerank_score: None,
+                passages: Vec::new(),
+            },
+            seed_edges,
+        ));
+    }
+
+    // Sort by final score and take top results
+    results.sort_by(|(a, _), (b, _)| {
+        b.final_score
+            .partial_cmp(&a.final_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results.truncate(config.candidates_kept());
+    results
+}
+
+/// TF-IDF of node labels against the query terms, scanning `nodes`: every
+/// occurrence of a query term in a label scores its idf over `nodes`, and a
+/// label equal to a query term scores 10 more. Only matching nodes are kept.
+pub(crate) fn tfidf_scan(
+    graph: &dyn TopologyGraph,
+    tokenizer: &Tokenizer,
+    nodes: &[NodeId],
+    query_terms: &[String],
+) -> Vec<(NodeId, String, f64)> {
+    let total_docs = nodes.len().max(1) as f64;
+
+    let mut df: HashMap<String, usize> = HashMap::new();
+    for nid in nodes {
+        if let Some(node) = graph.get_node(nid) {
+            let unique: HashSet<String> = tokenizer.words(&node.label).collect();
+            for term in unique {
+                *df.entry(term).or_insert(0) += 1;
             }
         }
-        // Direct connectivity: 0-1 based on strongest seed edge
-        graph_score += max_edge_weight * 0.4;
-        // Co-activation bonus: diminishing returns
-        graph_score += (total_co_activations as f64).ln().max(0.0) * 0.1;
+    }
 
-        // (b) Node importance: degree-based (hub nodes are more central)
+    let mut scores = Vec::new();
+    for nid in nodes {
         if let Some(node) = graph.get_node(nid) {
-            let degree = graph.neighbors(nid).len();
-            let degree_score = (degree as f64).ln().max(0.0) / 5.0; // normalize
-            graph_score += degree_score.min(1.0) * 0.2;
+            let label_lower = node.label.to_lowercase();
+            let label_terms: Vec<String> = tokenizer.words(&node.label).collect();
 
-            // (c) Access count (usage frequency, Hebbian reinforcement)
-            let access_score = (node.access_count as f64).ln().max(0.0) / 5.0;
-            graph_score += access_score.min(1.0) * 0.3;
-        }
+            let mut score = 0.0;
+            for qt in query_terms {
+                let tf = label_terms.iter().filter(|t| *t == qt).count() as f64;
+                if tf > 0.0 {
+                    let idf = (total_docs / (*df.get(qt).unwrap_or(&1) as f64)).ln() + 1.0;
+                    score += tf * idf;
+                }
+            }
+            // Exact match boost
+            for qt in query_terms {
+                if label_lower == *qt {
+                    score += 10.0;
+                }
+            }
 
-        // Clamp graph_score to [0, 1]
-        let graph_score_norm = graph_score.min(1.0);
+            if score > 0.0 {
+                scores.push((*nid, node.label.clone(), score));
+            }
+        }
+    }
+    scores
+}
 
-        // Final blended score
-        let final_score = config.alpha * tfidf_norm + (1.0 - config.alpha) * graph_score_norm;
+/// [`tfidf_scan`] over the whole graph, answered from its term index: only
+/// the nodes carrying a query term, or labelled with one, are visited.
+pub(crate) fn tfidf_indexed(
+    graph: &dyn TopologyGraph,
+    index: &InvertedIndex,
+    query_terms: &[String],
+) -> Vec<(NodeId, String, f64)> {
+    let total_docs = index.node_count().max(1) as f64;
 
-        results.push(HybridResult {
-            label: label.clone(),
-            tfidf_score: tfidf_norm,
-            graph_score: graph_score_norm,
-            final_score,
-        });
+    // Accumulated term by term in query order, as the scan does per node
+    let mut scores: HashMap<NodeId, f64> = HashMap::new();
+    for qt in query_terms {
+        let Some(postings) = index.postings(qt) else {
+            continue;
+        };
+        let idf = (total_docs / postings.len() as f64).ln() + 1.0;
+        for (nid, tf) in postings {
+            *scores.entry(*nid).or_insert(0.0) += *tf as f64 * idf;
+        }
+    }
+    // Exact match boost; the lookup also finds aliases, which don't count
+    for qt in query_terms {
+        for nid in graph.find_nodes_by_exact_label(qt) {
+            if graph
+                .get_node(&nid)
+                .is_some_and(|n| n.label.to_lowercase() == *qt)
+            {
+                *scores.entry(nid).or_insert(0.0) += 10.0;
+            }
+        }
     }
 
-    // Sort by final score and take top results
-    results.sort_by(|a, b| {
-        b.final_score
-            .partial_cmp(&a.final_score)
+    scores
+        .into_iter()
+        .filter(|(_, score)| *score > 0.0)
+        .filter_map(|(nid, score)| graph.get_node(&nid).map(|n| (nid, n.label.clone(), score)))
+        .collect()
+}
+
+/// Best TF-IDF score first; ties by label, then node, so the order does not
+/// depend on how the candidates were found.
+pub(crate) fn sort_by_tfidf(scores: &mut [(NodeId, String, f64)]) {
+    scores.sort_by(|a, b| {
+        b.2.partial_cmp(&a.2)
             .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.1.cmp(&b.1))
+            .then_with(|| a.0.cmp(&b.0))
     });
-    results.truncate(config.max_results);
-    results
 }
 
-/// Simple tokenizer matching the ones in query.rs and baseline.rs.
-fn tokenize(text: &str) -> Vec<String> {
-    let stopwords: std::collections::HashSet<&str> = [
-        "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
-        "do", "does", "did", "will", "would", "could", "should", "may", "might", "shall", "can",
-        "need", "to", "of", "in", "for", "on", "with", "at", "by", "from", "as", "into", "through",
-        "during", "before", "after", "above", "below", "between", "out", "off", "over", "under",
-        "again", "further", "then", "once", "and", "but", "or", "if", "while", "what", "which",
-        "who", "this", "that", "these", "those", "it", "its", "how",
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    text.to_lowercase()
-        .split_whitespace()
-        .filter(|w| w.len() >= 3 && !stopwords.contains(w))
-        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
-        .filter(|w| w.len() >= 3)
-        .collect()
+/// Attach up to `limit` passages to each result, ranked by how many distinct
+/// query terms and result concepts they mention, earliest first on ties.
+fn attach_passages(
+    colony: &Colony,
+    query_terms: &[String],
+    ranked: &mut [(HybridResult, Vec<EdgeLabels>)],
+    limit: usize,
+) {
+    let tokenizer = colony.tokenizer();
+    let mut terms: HashSet<String> = query_terms.iter().cloned().collect();
+    for (result, _) in ranked.iter() {
+        terms.extend(tokenizer.words(&result.label));
+    }
+
+    let substrate = colony.substrate();
+    let mut documents = substrate.all_documents();
+    documents.sort_by(|a, b| a.title.cmp(&b.title));
+
+    for (result, _) in ranked.iter_mut() {
+        let mut candidates: Vec<(usize, Passage)> = Vec::new();
+        for doc in &documents {
+            let Some(passages) = substrate.passages(&doc.id) else {
+                continue;
+            };
+            for span in passages.spans(&result.label) {
+                let Some(snippet) = span.slice(&doc.content) else {
+                    continue;
+                };
+                let mentioned: HashSet<String> = tokenizer.words(snippet).collect();
+                let score = mentioned.iter().filter(|w| terms.contains(*w)).count();
+                candidates.push((
+                    score,
+                    Passage {
+                        document_id: doc.id,
+                        title: doc.title.clone(),
+                        span: *span,
+                        snippet: snippet.to_string(),
+                    },
+                ));
+            }
+        }
+        // Stable sort keeps title and offset order among equal scores
+        candidates.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        result.passages = candidates
+            .into_iter()
+            .take(limit)
+            .map(|(_, passage)| passage)
+            .collect();
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graph_scorer::PersonalizedPageRankScorer;
     use phago_agents::digester::Digester;
     use phago_core::types::Position;
 
@@ -232,15 +971,196 @@ mod tests {
         colony
     }
 
+    fn ranked(results: Vec<HybridResult>) -> Vec<(String, f64)> {
+        let mut ranked: Vec<(String, f64)> = results
+            .into_iter()
+            .map(|r| (r.label, r.final_score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    #[test]
+    fn bundle_round_trip_preserves_results() {
+        use phago_runtime::bundle::Bundle;
+        use phago_runtime::colony::ColonyConfig;
+        use phago_runtime::session::{load_session, restore_into_colony, save_session};
+
+        let colony = setup_colony();
+        let config = HybridConfig::default();
+        let before = ranked(hybrid_query(&colony, "cell membrane protein", &config).unwrap());
+        assert!(!before.is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let session = dir.path().join("session.json");
+        save_session(&colony, &session, &[]).unwrap();
+        let state = load_session(&session).unwrap();
+        let bundle_path = dir.path().join("bio.phago");
+        Bundle::from_session("bio", &state, &ColonyConfig::default())
+            .unwrap()
+            .save(&bundle_path)
+            .unwrap();
+
+        let imported = Bundle::load(&bundle_path).unwrap();
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &imported.session().unwrap(), None);
+        let after = ranked(hybrid_query(&restored, "cell membrane protein", &config).unwrap());
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn term_index_scores_match_the_scan_on_the_embedded_corpus() {
+        use crate::baseline::tfidf_query;
+        use phago_runtime::colony::ColonyConfig;
+        use phago_runtime::corpus::Corpus;
+
+        let corpus = Corpus::from_embedded().limit(24);
+        let mut colony = Colony::new();
+        corpus.ingest_into(&mut colony);
+        for doc in &corpus.documents {
+            colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(80)));
+        }
+        colony.run(40);
+        assert!(colony.substrate().term_index().is_some());
+
+        // A sample of the words in labels, alone and three at a time
+        let graph = colony.substrate().graph();
+        let words: std::collections::BTreeSet<String> = graph
+            .all_nodes()
+            .iter()
+            .filter_map(|id| graph.get_node(id))
+            .flat_map(|n| colony.tokenizer().tokens(&n.label))
+            .collect();
+        let words: Vec<String> = words.into_iter().step_by(25).collect();
+        let queries: Vec<String> = words
+            .iter()
+            .cloned()
+            .chain(words.windows(3).map(|w| w.join(" ")))
+            .collect();
+        assert!(words.len() > 10, "{:?}", words);
+
+        let ranked_tfidf = |colony: &Colony, terms: &[String]| {
+            let graph = colony.substrate().graph();
+            let mut scores = match colony.substrate().term_index() {
+                Some(index) => tfidf_indexed(graph, index, terms),
+                None => tfidf_scan(graph, colony.tokenizer(), &graph.all_nodes(), terms),
+            };
+            sort_by_tfidf(&mut scores);
+            scores
+        };
+
+        let scores = |colony: &Colony| {
+            let config = HybridConfig::default();
+            queries
+                .iter()
+                .map(|q| {
+                    let terms = colony.tokenizer().tokens(q);
+                    let hybrid: Vec<_> = hybrid_query(colony, q, &config)
+                        .unwrap()
+                        .into_iter()
+                        .map(|r| (r.label, r.tfidf_score, r.graph_score, r.final_score))
+                        .collect();
+                    (
+                        ranked_tfidf(colony, &terms),
+                        hybrid,
+                        tfidf_query(colony, q, 20),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let indexed = scores(&colony);
+        colony.substrate_mut().disable_term_index();
+        assert!(colony.substrate().term_index().is_none());
+        let scanned = scores(&colony);
+
+        assert!(indexed.iter().all(|(raw, hybrid, baseline)| {
+            !raw.is_empty() && !hybrid.is_empty() && !baseline.is_empty()
+        }));
+        assert_eq!(indexed, scanned);
+
+        let unindexed = Colony::from_config(ColonyConfig {
+            term_index: false,
+            ..Default::default()
+        });
+        assert!(unindexed.substrate().term_index().is_none());
+        assert!(!unindexed.config().term_index);
+    }
+
     #[test]
     fn hybrid_returns_results() {
         let colony = setup_colony();
         let config = HybridConfig::default();
-        let results = hybrid_query(&colony, "cell membrane", &config);
+        let results = hybrid_query(&colony, "cell membrane", &config).unwrap();
         assert!(!results.is_empty(), "hybrid should return results");
         assert!(results[0].final_score > 0.0);
     }
 
+    #[test]
+    fn passages_come_from_the_document_mentioning_the_concept() {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Cells",
+            "Mitochondria produce energy for the cell. Ribosomes assemble proteins.",
+            Position::new(0.0, 0.0),
+        );
+        colony.ingest_document(
+            "Stars",
+            "Stars fuse hydrogen into helium. Energy from fusion makes stars shine.",
+            Position::new(1.0, 0.0),
+        );
+        for x in [0.0, 1.0] {
+            colony.spawn(Box::new(
+                Digester::new(Position::new(x, 0.0)).with_max_idle(80),
+            ));
+        }
+        colony.run(20);
+
+        let config = HybridConfig::builder()
+            .alpha(1.0)
+            .include_passages(true)
+            .build()
+            .unwrap();
+        let results = hybrid_query(&colony, "mitochondria hydrogen energy", &config).unwrap();
+        let passages_of = |label: &str| {
+            results
+                .iter()
+                .find(|r| r.label == label)
+                .map(|r| r.passages.clone())
+                .unwrap()
+        };
+
+        let mitochondria = passages_of("mitochondria");
+        assert_eq!(mitochondria.len(), 1);
+        assert_eq!(mitochondria[0].title, "Cells");
+        assert_eq!(
+            mitochondria[0].snippet,
+            "Mitochondria produce energy for the cell."
+        );
+        let hydrogen = passages_of("hydrogen");
+        assert_eq!(hydrogen[0].title, "Stars");
+        assert!(hydrogen[0].snippet.to_lowercase().contains("hydrogen"));
+
+        // "energy" appears in both documents
+        let energy = passages_of("energy");
+        let titles: HashSet<&str> = energy.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, HashSet::from(["Cells", "Stars"]));
+        for passage in &energy {
+            let doc = colony
+                .substrate()
+                .get_document(&passage.document_id)
+                .unwrap();
+            assert_eq!(
+                passage.span.slice(&doc.content),
+                Some(passage.snippet.as_str())
+            );
+            assert!(passage.snippet.to_lowercase().contains("energy"));
+        }
+
+        // Passages are opt-in
+        let plain = hybrid_query(&colony, "mitochondria", &HybridConfig::default()).unwrap();
+        assert!(plain.iter().all(|r| r.passages.is_empty()));
+    }
+
     #[test]
     fn hybrid_blends_scores() {
         let colony = setup_colony();
@@ -248,7 +1168,7 @@ mod tests {
             alpha: 0.5,
             ..Default::default()
         };
-        let results = hybrid_query(&colony, "cell membrane", &config);
+        let results = hybrid_query(&colony, "cell membrane", &config).unwrap();
 
         for r in &results {
             // Final score should be between tfidf and graph components
@@ -264,6 +1184,253 @@ mod tests {
         }
     }
 
+    #[test]
+    fn insight_boost_scales_insight_scores() {
+        use phago_core::substrate::Substrate;
+        use phago_core::types::{NodeData, NodeId};
+
+        let mut colony = setup_colony();
+        let insight_id = colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "[BRIDGE:2] Bridge: 'cell' connects 2 document contexts".to_string(),
+            node_type: NodeType::Insight,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+        let insight_label = colony
+            .substrate()
+            .graph()
+            .get_node(&insight_id)
+            .unwrap()
+            .label
+            .clone();
+
+        let score_with = |boost: f64| {
+            let config = HybridConfig {
+                max_results: 50,
+                insight_boost: boost,
+                ..Default::default()
+            };
+            hybrid_query(&colony, "cell", &config)
+                .unwrap()
+                .into_iter()
+                .find(|r| r.label == insight_label)
+                .map(|r| r.final_score)
+                .unwrap()
+        };
+
+        let neutral = score_with(1.0);
+        let penalized = score_with(0.5);
+        assert!(neutral > 0.0);
+        assert!((penalized - neutral * 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn min_anomaly_score_drops_weak_anomalies() {
+        use phago_core::substrate::Substrate;
+        use phago_core::types::NodeData;
+
+        let mut colony = setup_colony();
+        let mut add_anomaly = |label: &str, score: f64| {
+            let id = colony.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: label.to_string(),
+                node_type: NodeType::Anomaly,
+                position: Position::new(0.0, 0.0),
+                access_count: 1,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            });
+            colony.set_anomaly_score(&id, score);
+        };
+        add_anomaly("[REVIEW] cell reading", 0.3);
+        add_anomaly("[ANOMALY] cell outlier", 0.8);
+
+        let labels = |config: &HybridConfig| -> Vec<String> {
+            hybrid_query(&colony, "cell", config)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.label)
+                .collect()
+        };
+        let all = labels(&HybridConfig::builder().max_results(50).build().unwrap());
+        assert!(all.iter().any(|l| l == "[REVIEW] cell reading"));
+        assert!(all.iter().any(|l| l == "[ANOMALY] cell outlier"));
+
+        let config = HybridConfig::builder()
+            .max_results(50)
+            .min_anomaly_score(0.5)
+            .build()
+            .unwrap();
+        let strong = labels(&config);
+        assert!(!strong.iter().any(|l| l == "[REVIEW] cell reading"));
+        assert!(strong.iter().any(|l| l == "[ANOMALY] cell outlier"));
+    }
+
+    #[test]
+    fn tags_restrict_candidates() {
+        let mut colony = Colony::new();
+        colony.ingest_document_tagged(
+            "Quarterly plan",
+            "The project deadline requires budget review with the client.",
+            Position::new(0.0, 0.0),
+            &["work"],
+        );
+        colony.ingest_document_tagged(
+            "Weekend",
+            "The garden project needs compost and regular watering.",
+            Position::new(1.0, 0.0),
+            &["personal"],
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.spawn(Box::new(
+            Digester::new(Position::new(1.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(20);
+
+        let labels = |tags: Option<&[&str]>| -> Vec<String> {
+            let config = HybridConfig {
+                max_results: 20,
+                tags: tags.map(|t| t.iter().map(|s| s.to_string()).collect()),
+                ..Default::default()
+            };
+            hybrid_query(&colony, "project budget garden", &config)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.label)
+                .collect()
+        };
+
+        let all = labels(None);
+        assert!(all.contains(&"budget".to_string()));
+        assert!(all.contains(&"garden".to_string()));
+
+        let work = labels(Some(&["work"]));
+        assert!(work.contains(&"project".to_string()));
+        assert!(work.contains(&"budget".to_string()));
+        assert!(!work.contains(&"garden".to_string()));
+
+        let both = labels(Some(&["work", "personal"]));
+        assert_eq!(both.len(), all.len());
+        assert!(labels(Some(&["travel"])).is_empty());
+    }
+
+    #[test]
+    fn personalized_pagerank_scores_reachability_from_seeds() {
+        use phago_core::types::{EdgeData, NodeData, NodeId};
+
+        let mut colony = Colony::new();
+        let mut add = |label: &str| {
+            colony.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: label.to_string(),
+                node_type: NodeType::Concept,
+                position: Position::new(0.0, 0.0),
+                access_count: 1,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            })
+        };
+        let cell = add("cell");
+        let transport = add("cell transport");
+        let membrane = add("membrane");
+        add("cell wall");
+        let edge = |weight| EdgeData {
+            weight,
+            co_activations: 1,
+            created_tick: 0,
+            last_activated_tick: 0,
+        };
+        colony
+            .substrate_mut()
+            .set_edge(cell, transport, edge(0.8))
+            .unwrap();
+        colony
+            .substrate_mut()
+            .set_edge(transport, membrane, edge(0.5))
+            .unwrap();
+
+        let config = HybridConfig {
+            graph_scorer: Arc::new(PersonalizedPageRankScorer::default()),
+            ..Default::default()
+        };
+        let results = hybrid_query(&colony, "cell", &config).unwrap();
+        let graph_score = |label: &str| {
+            results
+                .iter()
+                .find(|r| r.label == label)
+                .map(|r| r.graph_score)
+                .unwrap()
+        };
+        // Relative to the best candidate; unreachable ones score 0
+        let best = results.iter().map(|r| r.graph_score).fold(0.0, f64::max);
+        assert_eq!(best, 1.0);
+        assert!(graph_score("cell") > 0.0);
+        assert!(graph_score("cell transport") > 0.0);
+        assert_eq!(graph_score("cell wall"), 0.0);
+    }
+
+    #[test]
+    fn any_synonym_retrieves_the_canonical_concept() {
+        use phago_core::types::NodeData;
+        use phago_runtime::colony::ColonyConfig;
+        use phago_runtime::synonyms::{SynonymConfig, SynonymMap};
+
+        let mut colony = Colony::from_config(ColonyConfig {
+            synonyms: SynonymConfig {
+                map: SynonymMap::from_tsv("hypertension\thypertonia\thighbloodpressure\n").unwrap(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        for (i, content) in [
+            "Hypertension damages arteries over many years.",
+            "Hypertonia damages arteries over many years.",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let position = Position::new(i as f64 * 20.0, 0.0);
+            colony.ingest_document(&format!("doc{i}"), content, position);
+            colony.spawn(Box::new(Digester::new(position).with_max_idle(30)));
+        }
+        colony.run(20);
+
+        // Presented as "hypertonia", never as "highbloodpressure"
+        let config = HybridConfig::default();
+        for query in ["hypertension", "hypertonia", "highbloodpressure"] {
+            let results = hybrid_query(&colony, query, &config).unwrap();
+            assert_eq!(results[0].label, "hypertension", "{query}");
+        }
+
+        // A node named as typed outranks one named by a synonym
+        colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "highbloodpressure".to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+        let config = HybridConfig {
+            alpha: 1.0,
+            ..HybridConfig::default()
+        };
+        let results = hybrid_query(&colony, "highbloodpressure", &config).unwrap();
+        let labels: Vec<&str> = results.iter().map(|r| r.label.as_str()).collect();
+        assert_eq!(labels[..2], ["highbloodpressure", "hypertension"]);
+        assert!(results[1].tfidf_score < results[0].tfidf_score);
+    }
+
     #[test]
     fn alpha_1_equals_pure_tfidf() {
         let colony = setup_colony();
@@ -271,8 +1438,16 @@ mod tests {
             alpha: 1.0,
             max_results: 5,
             candidate_multiplier: 3,
+            insight_boost: 1.0,
+            tags: None,
+            source: QuerySource::Library,
+            graph_scorer: Arc::new(DefaultGraphScorer),
+            include_passages: false,
+            max_passages: 3,
+            min_anomaly_score: None,
+            reranker: None,
         };
-        let results = hybrid_query(&colony, "cell", &config);
+        let results = hybrid_query(&colony, "cell", &config).unwrap();
 
         for r in &results {
             assert!(
@@ -283,4 +1458,379 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn queries_split_identifiers_like_ingestion() {
+        use phago_core::tokenize::TokenizerConfig;
+        use phago_runtime::colony::ColonyConfig;
+
+        let mut colony = Colony::from_config(ColonyConfig {
+            tokenizer: TokenizerConfig {
+                keep_identifiers: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        colony.ingest_document(
+            "Digester",
+            "The digester dies after max_idle_ticks quiet rounds in phago-agents.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(10);
+
+        let results = hybrid_query(&colony, "max_idle_ticks", &HybridConfig::default()).unwrap();
+        assert_eq!(results[0].label, "max_idle_ticks");
+        let results = hybrid_query(&colony, "phago-agents", &HybridConfig::default()).unwrap();
+        assert_eq!(results[0].label, "phago-agents");
+        // Neither half is a concept on its own
+        let graph = colony.substrate().graph();
+        let labels: Vec<String> = graph
+            .all_nodes()
+            .iter()
+            .filter_map(|id| graph.get_node(id).map(|n| n.label.clone()))
+            .collect();
+        assert!(!labels.contains(&"idle".to_string()));
+        assert!(!labels.contains(&"agents".to_string()));
+    }
+
+    #[test]
+    fn query_log_records_each_query_once() {
+        use phago_runtime::colony::ColonyConfig;
+        use phago_runtime::query_log::QueryLogConfig;
+
+        let colony = setup_colony();
+        assert!(colony.query_log().is_none());
+        hybrid_query(&colony, "cell", &HybridConfig::default()).unwrap();
+        assert!(colony.query_log().is_none());
+
+        let mut colony = Colony::from_config(ColonyConfig {
+            query_log: QueryLogConfig::enabled(),
+            ..Default::default()
+        });
+        colony.ingest_document(
+            "Bio1",
+            "Cell signaling through membrane receptors activates protein cascades.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(20);
+
+        let config = HybridConfig {
+            source: QuerySource::Web,
+            ..Default::default()
+        };
+        let results = hybrid_query(&colony, "cell membrane", &config).unwrap();
+        hybrid_query(&colony, "", &config).unwrap();
+
+        let entries = colony.query_log().unwrap().entries();
+        assert_eq!(entries.len(), 2);
+        let labels: Vec<String> = results.into_iter().map(|r| r.label).collect();
+        assert_eq!(entries[0].results, labels);
+        assert_eq!(entries[0].source, QuerySource::Web);
+        assert!(entries[0]
+            .traversed_edges
+            .contains(&("cell".into(), "membrane".into())));
+        assert!(entries[1].results.is_empty());
+    }
+
+    /// "cell" matches the query exactly but is poorly connected, "cell wall"
+    /// matches two terms, and "cell membrane" is the seed's strongest neighbor.
+    fn fixed_colony() -> Colony {
+        use phago_core::types::{EdgeData, NodeData, NodeId};
+
+        let mut colony = Colony::new();
+        let mut add = |label: &str, access_count: u64| {
+            colony.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: label.to_string(),
+                node_type: NodeType::Concept,
+                position: Position::new(0.0, 0.0),
+                access_count,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            })
+        };
+        let cell = add("cell", 1);
+        let membrane = add("cell membrane", 50);
+        add("cell wall", 5);
+        colony
+            .substrate_mut()
+            .set_edge(
+                cell,
+                membrane,
+                EdgeData {
+                    weight: 0.9,
+                    co_activations: 10,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+        colony
+    }
+
+    /// More "cell" concepts than one paged query ranks, with varied access
+    /// counts so graph scores reorder them.
+    fn crowded_colony() -> Colony {
+        use phago_core::types::{NodeData, NodeId};
+
+        let mut colony = Colony::new();
+        for i in 0..130u8 {
+            let suffix: String = [b'a' + i / 26, b'a' + i % 26, b'x']
+                .iter()
+                .map(|b| *b as char)
+                .collect();
+            colony.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: format!("cell {}", suffix),
+                node_type: NodeType::Concept,
+                position: Position::new(0.0, 0.0),
+                access_count: (i as u64 * 7) % 23 + 1,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            });
+        }
+        colony
+    }
+
+    /// Labels of every page, following cursors until the last.
+    fn all_pages(
+        colony: &Colony,
+        cache: Option<&QueryCache>,
+        config: &HybridConfig,
+    ) -> Vec<String> {
+        let mut labels = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = match cache {
+                Some(cache) => {
+                    hybrid_query_page_cached(colony, cache, "cell", config, cursor.as_deref())
+                }
+                None => hybrid_query_page(colony, "cell", config, cursor.as_deref()),
+            }
+            .unwrap();
+            assert!(!page.cursor_expired);
+            assert!(page.results.len() <= config.max_results);
+            labels.extend(page.results.into_iter().map(|r| r.label));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return labels,
+            }
+        }
+    }
+
+    #[test]
+    fn pages_concatenate_to_one_deep_query() {
+        let colony = crowded_colony();
+        let page = HybridConfig::builder().max_results(7).build().unwrap();
+        let deep = HybridConfig::builder()
+            .max_results(PAGE_DEPTH)
+            .build()
+            .unwrap();
+        let expected: Vec<String> = hybrid_query(&colony, "cell", &deep)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.label)
+            .collect();
+        assert_eq!(expected.len(), PAGE_DEPTH);
+
+        assert_eq!(all_pages(&colony, None, &page), expected);
+
+        let cache = QueryCache::new(4);
+        assert_eq!(all_pages(&colony, Some(&cache), &page), expected);
+        // Ranked once, every later page sliced from the cached ranking
+        let stats = cache.stats();
+        assert_eq!((stats.misses, stats.hits), (1, 14));
+    }
+
+    #[test]
+    fn graph_changes_expire_cursors() {
+        let mut colony = crowded_colony();
+        let config = HybridConfig::builder().max_results(10).build().unwrap();
+        let first = hybrid_query_page(&colony, "cell", &config, None).unwrap();
+        let cursor = first.next_cursor.expect("more than one page");
+
+        let id = colony.substrate().graph().find_nodes_by_label("cell")[0];
+        colony
+            .substrate_mut()
+            .graph_mut()
+            .get_node_mut(&id)
+            .unwrap()
+            .access_count = 1000;
+
+        let page = hybrid_query_page(&colony, "cell", &config, Some(&cursor)).unwrap();
+        assert!(page.cursor_expired);
+        let fresh = hybrid_query_page(&colony, "cell", &config, None).unwrap();
+        assert!(!fresh.cursor_expired);
+        assert_eq!(ranked(page.results), ranked(fresh.results));
+        assert_eq!(page.next_cursor, fresh.next_cursor);
+    }
+
+    #[test]
+    fn foreign_cursors_are_rejected() {
+        let colony = crowded_colony();
+        let config = HybridConfig::builder().max_results(10).build().unwrap();
+        let cursor = hybrid_query_page(&colony, "cell", &config, None)
+            .unwrap()
+            .next_cursor
+            .unwrap();
+
+        let other = HybridConfig::builder().alpha(0.9).build().unwrap();
+        for (query, config, cursor) in [
+            ("cell", &other, cursor.as_str()),
+            ("membrane", &config, cursor.as_str()),
+            ("cell", &config, "not a cursor"),
+        ] {
+            let err = hybrid_query_page(&colony, query, config, Some(cursor)).unwrap_err();
+            assert_eq!(err, HybridConfigError::InvalidCursor);
+        }
+    }
+
+    fn labels_for_alpha(alpha: f64) -> Vec<String> {
+        let config = HybridConfig::builder().alpha(alpha).build().unwrap();
+        hybrid_query(&fixed_colony(), "cell wall", &config)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.label)
+            .collect()
+    }
+
+    #[test]
+    fn alpha_one_is_pure_tfidf_and_zero_pure_graph() {
+        assert_eq!(
+            labels_for_alpha(1.0),
+            vec!["cell", "cell wall", "cell membrane"]
+        );
+        assert_eq!(
+            labels_for_alpha(0.0),
+            vec!["cell membrane", "cell wall", "cell"]
+        );
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected() {
+        let colony = fixed_colony();
+        for (config, expected) in [
+            (
+                HybridConfig {
+                    alpha: 7.3,
+                    ..Default::default()
+                },
+                HybridConfigError::AlphaOutOfRange(7.3),
+            ),
+            (
+                HybridConfig {
+                    max_results: 0,
+                    ..Default::default()
+                },
+                HybridConfigError::ZeroMaxResults,
+            ),
+            (
+                HybridConfig {
+                    candidate_multiplier: 0,
+                    ..Default::default()
+                },
+                HybridConfigError::ZeroCandidateMultiplier,
+            ),
+        ] {
+            assert_eq!(config.validate(), Err(expected.clone()));
+            assert_eq!(
+                hybrid_query(&colony, "cell", &config).unwrap_err(),
+                expected
+            );
+        }
+        assert!(HybridConfig::builder().alpha(-0.1).build().is_err());
+        assert!(HybridConfig::builder().alpha(f64::NAN).build().is_err());
+
+        let config = HybridConfig::builder()
+            .max_results(5)
+            .tags(Vec::new())
+            .build()
+            .unwrap();
+        assert_eq!(config.max_results, 5);
+        assert_eq!(config.tags, None);
+    }
+
+    #[test]
+    fn repeated_read_only_queries_hit_the_cache() {
+        let colony = setup_colony();
+        let cache = QueryCache::new(8);
+        let config = HybridConfig::default();
+
+        let first = hybrid_query_cached(&colony, &cache, "cell membrane", &config).unwrap();
+        let second = hybrid_query_cached(&colony, &cache, "Cell,  MEMBRANE", &config).unwrap();
+        assert_eq!(ranked(first.clone()), ranked(second));
+        assert_eq!(
+            ranked(first),
+            ranked(hybrid_query(&colony, "cell membrane", &config).unwrap())
+        );
+        // A different setting is a different entry
+        let narrow = HybridConfig::builder().max_results(1).build().unwrap();
+        hybrid_query_cached(&colony, &cache, "cell membrane", &narrow).unwrap();
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 2));
+    }
+
+    #[test]
+    fn cached_results_never_outlive_a_graph_change() {
+        let mut colony = setup_colony();
+        let cache = QueryCache::new(8);
+        let config = HybridConfig::default();
+        let before = ranked(hybrid_query_cached(&colony, &cache, "membrane", &config).unwrap());
+
+        let revision = colony.graph_revision();
+        let id = colony
+            .substrate()
+            .graph()
+            .find_nodes_by_exact_label("membrane")[0];
+        colony
+            .substrate_mut()
+            .graph_mut()
+            .get_node_mut(&id)
+            .unwrap()
+            .access_count = 0;
+        assert!(colony.graph_revision() > revision);
+
+        let after = ranked(hybrid_query_cached(&colony, &cache, "membrane", &config).unwrap());
+        assert_ne!(after, before);
+        assert_eq!(
+            after,
+            ranked(hybrid_query(&colony, "membrane", &config).unwrap())
+        );
+        assert_eq!(cache.stats().hits, 0);
+
+        // Ticks change the graph too
+        colony.tick();
+        hybrid_query_cached(&colony, &cache, "membrane", &config).unwrap();
+        assert_eq!(cache.stats().misses, 3);
+    }
+
+    #[test]
+    fn reinforcing_queries_invalidate_cached_results() {
+        use crate::query::{Query, QueryEngine};
+
+        let mut colony = setup_colony();
+        let cache = QueryCache::new(8);
+        let config = HybridConfig::default();
+        hybrid_query_cached(&colony, &cache, "cell membrane", &config).unwrap();
+
+        let revision = colony.graph_revision();
+        QueryEngine::query(&mut colony, &Query::new("cell membrane"));
+        assert!(colony.graph_revision() > revision);
+
+        let cached = hybrid_query_cached(&colony, &cache, "cell membrane", &config).unwrap();
+        assert_eq!(
+            ranked(cached),
+            ranked(hybrid_query(&colony, "cell membrane", &config).unwrap())
+        );
+        assert_eq!(cache.stats().hits, 0);
+    }
 }
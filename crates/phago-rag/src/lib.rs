@@ -16,15 +16,34 @@
 //! | No anomaly detection | Sentinels flag what doesn't fit |
 //! | Flat retrieval | Structured, weighted paths |
 
+pub mod assemble;
 pub mod baseline;
+pub mod cache;
 pub mod code_query;
 pub mod counterfactual;
+pub mod graph_scorer;
 pub mod hybrid;
 pub mod mcp;
 pub mod prelude;
 pub mod query;
+pub mod rerank;
 pub mod scoring;
+pub mod static_colony;
 
-pub use hybrid::{hybrid_query, HybridConfig, HybridResult};
-pub use mcp::{phago_explore, phago_recall, phago_remember};
+pub use assemble::{
+    assemble_context, assemble_context_with, ApproxTokenCounter, AssembleConfig, CitationStyle,
+    RagContext, TokenCounter,
+};
+pub use cache::{QueryCache, QueryCacheStats};
+pub use graph_scorer::{
+    CommunityBoostScorer, DefaultGraphScorer, DegreeNormalizedScorer, GraphScorer,
+    PersonalizedPageRankScorer, ScoringView,
+};
+pub use hybrid::{
+    hybrid_query, hybrid_query_cached, hybrid_query_page, hybrid_query_page_cached, touch_results,
+    HybridConfig, HybridConfigBuilder, HybridConfigError, HybridPage, HybridResult,
+};
+pub use mcp::{phago_explore, phago_recall, phago_recall_cached, phago_remember};
 pub use query::{Query, QueryEngine, QueryResult};
+pub use rerank::{RerankError, Reranker};
+pub use static_colony::{StaticColony, StaticQueryError};
@@ -1,64 +1,132 @@
 //! MCP Adapter — Model Context Protocol interface for Phago.
 //!
-//! Provides three core tools for external LLMs/agents to interact
+//! Provides core tools for external LLMs/agents to interact
 //! with the biological knowledge graph:
 //!
 //! - `phago_remember`: Ingest text into the colony (document → digestion → graph)
 //! - `phago_recall`: Query the knowledge graph with hybrid scoring
-//! - `phago_explore`: Structural queries (paths, bridges, centrality, components)
+//! - `phago_explore`: Structural queries (paths, bridges, centrality, communities,
+//!   regions) and what reinforced an edge
+//! - `phago_suggest`: Complete a concept label prefix
+//! - `phago_connect`: Connect two concepts the caller knows are related
+//! - `phago_annotate`: Attach a free-text note to a concept
+//! - `phago_synonyms`: Replace the synonym dictionary without a restart
 //!
 //! All operations use serializable request/response types compatible
 //! with JSON-RPC or any other transport layer.
 
+use crate::assemble::{assemble_results, ApproxTokenCounter, AssembleConfig, RagContext};
+use crate::cache::QueryCache;
+use crate::hybrid::{
+    hybrid_query_page, hybrid_query_page_cached, touch_results, HybridConfig, HybridConfigError,
+    HybridPage, Passage,
+};
 use phago_core::topology::TopologyGraph;
-use phago_core::types::*;
-use phago_runtime::colony::Colony;
+use phago_core::types::{Annotation, NodeId, Position};
+use phago_runtime::colony::{Colony, ColonyEvent, ColonyStats, GraphEditError, LabelSuggestion};
+use phago_runtime::community::{Community, CommunityAlgorithm, CommunityTracker};
+use phago_runtime::dedup::{DedupConfig, DuplicateMatch, IngestOutcome};
+use phago_runtime::graph_algorithms::{
+    find_path, pagerank, ConceptPath, PageRankConfig, PathCost, PathError,
+};
+use phago_runtime::provenance::EdgeSource;
+use phago_runtime::query_log::QuerySource;
+use phago_runtime::read_view::ColonyView;
+use phago_runtime::region::Region;
+use phago_runtime::sampling::{self, SampleCoverage, SampleStrategy};
+use phago_runtime::synonyms::{SynonymConfig, SynonymMap};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // === phago_remember ===
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RememberRequest {
     pub title: String,
     pub content: String,
     #[serde(default)]
     pub ticks: Option<u64>,
+    /// View tags for the document (e.g. "work", "personal").
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Forget the document this many ticks after it is stored.
+    #[serde(default)]
+    pub ttl_ticks: Option<u64>,
+    /// Forget the document this many seconds after it is stored, for
+    /// servers whose ticks do not follow the clock.
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct RememberResponse {
     pub document_id: String,
+    /// Above 1 when a document with the same title was updated.
+    pub version: u32,
     pub nodes_created: usize,
     pub edges_created: usize,
     pub tick: u64,
+    /// Set when the note duplicates another document. It was then not
+    /// stored, and `document_id` is that of the original.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<DuplicateMatch>,
 }
 
 /// Ingest a document into the colony and run digestion.
+///
+/// Remembering a title again updates that document instead of adding a
+/// second copy (see [`Colony::ingest_or_update_document`]). A note that
+/// duplicates a document with another title is not stored (see
+/// [`Colony::ingest_deduplicated`]).
 pub fn phago_remember(colony: &mut Colony, req: &RememberRequest) -> RememberResponse {
     use phago_agents::digester::Digester;
 
     let before_nodes = colony.stats().graph_nodes;
     let before_edges = colony.stats().graph_edges;
 
-    let doc_id = colony.ingest_document(&req.title, &req.content, Position::new(0.0, 0.0));
+    // Related memories cluster, so synthesizers see them together
+    let position = colony.place_document(&req.title, &req.content);
+    let tags: Vec<&str> = req.tags.iter().map(String::as_str).collect();
+    let outcome = colony.ingest_deduplicated(
+        &req.title,
+        &req.content,
+        position,
+        &tags,
+        &DedupConfig::default(),
+    );
+    let (doc_id, duplicate_of) = match outcome {
+        IngestOutcome::Ingested { id, .. } => {
+            if let Some(ttl) = req.ttl_ticks {
+                colony.set_document_ttl(&id, ttl);
+            }
+            if let Some(ttl) = req.ttl_seconds {
+                colony.set_document_ttl_seconds(&id, ttl);
+            }
 
-    // Spawn a digester to process the document
-    colony.spawn(Box::new(
-        Digester::new(Position::new(0.0, 0.0)).with_max_idle(30),
-    ));
+            // Spawn a digester to process the document
+            colony.spawn(Box::new(Digester::new(position).with_max_idle(30)));
 
-    // Run enough ticks for digestion
-    let ticks = req.ticks.unwrap_or(15);
-    colony.run(ticks);
+            // Run enough ticks for digestion
+            let ticks = req.ticks.unwrap_or(15);
+            colony.run(ticks);
+            (id, None)
+        }
+        IngestOutcome::Skipped(m) | IngestOutcome::Merged(m) => (m.original, Some(m)),
+    };
 
     let after_nodes = colony.stats().graph_nodes;
     let after_edges = colony.stats().graph_edges;
 
     RememberResponse {
         document_id: format!("{}", doc_id.0),
+        version: colony
+            .substrate()
+            .get_document(&doc_id)
+            .map_or(1, |d| d.version),
         nodes_created: after_nodes.saturating_sub(before_nodes),
         edges_created: after_edges.saturating_sub(before_edges),
         tick: colony.stats().tick,
+        duplicate_of,
     }
 }
 
@@ -71,11 +139,33 @@ pub struct RecallRequest {
     pub max_results: usize,
     #[serde(default = "default_alpha")]
     pub alpha: f64,
+    /// Only recall concepts carrying at least one of these tags.
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Attach the source passages each concept was digested from.
+    #[serde(default)]
+    pub include_passages: bool,
+    /// Most passages per result when `include_passages` is set.
+    #[serde(default = "default_max_passages")]
+    pub max_passages: usize,
+    /// `next_cursor` of the previous page; `None` for the first page.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Also assemble the page's passages into context ready for a prompt
+    /// (see [`crate::assemble`]).
+    #[serde(default)]
+    pub assemble: bool,
+    /// How the context is assembled when `assemble` is set.
+    #[serde(default)]
+    pub assembly: AssembleConfig,
 }
 
 fn default_max_results() -> usize {
     10
 }
+fn default_max_passages() -> usize {
+    3
+}
 fn default_alpha() -> f64 {
     0.5
 }
@@ -86,6 +176,11 @@ pub struct RecallResult {
     pub score: f64,
     pub tfidf_score: f64,
     pub graph_score: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub passages: Vec<Passage>,
+    /// Notes attached to the concept (see [`phago_annotate`]).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
 }
 
 #[derive(Debug, Serialize)]
@@ -93,32 +188,130 @@ pub struct RecallResponse {
     pub results: Vec<RecallResult>,
     pub total_nodes: usize,
     pub total_edges: usize,
+    /// Pass as `cursor` to get the next `max_results` results; absent on
+    /// the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// The graph changed since `cursor` was issued, so `results` are a
+    /// fresh first page.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cursor_expired: bool,
+    /// The results assembled into context, when asked to `assemble`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<RagContext>,
 }
 
-/// Query the knowledge graph using hybrid scoring.
-pub fn phago_recall(colony: &Colony, req: &RecallRequest) -> RecallResponse {
-    use crate::hybrid::{hybrid_query, HybridConfig};
+impl RecallRequest {
+    /// The hybrid scoring settings this request asks for, validated.
+    pub fn hybrid_config(&self) -> Result<HybridConfig, HybridConfigError> {
+        HybridConfig::builder()
+            .alpha(self.alpha)
+            .max_results(self.max_results)
+            .tags(self.tags.clone().unwrap_or_default())
+            .source(QuerySource::Mcp)
+            .include_passages(self.include_passages || self.assemble)
+            .max_passages(if self.assemble {
+                self.max_passages.max(self.assembly.max_passages_per_doc)
+            } else {
+                self.max_passages
+            })
+            .build()
+    }
+}
 
-    let config = HybridConfig {
-        alpha: req.alpha,
-        max_results: req.max_results,
-        candidate_multiplier: 3,
-    };
+/// Query the knowledge graph using hybrid scoring, one page of
+/// `max_results` at a time.
+///
+/// The concepts returned count as accessed (see [`touch_results`]).
+/// Fails if `alpha` or `max_results` are out of range, or if `cursor` was
+/// not issued for this query.
+pub fn phago_recall(
+    colony: &mut Colony,
+    req: &RecallRequest,
+) -> Result<RecallResponse, HybridConfigError> {
+    let config = req.hybrid_config()?;
+    let page = hybrid_query_page(colony, &req.query, &config, req.cursor.as_deref())?;
+    touch_results(colony, &page.results);
+    Ok(recall_response(colony, req, page))
+}
 
-    let results = hybrid_query(colony, &req.query, &config);
+/// [`phago_recall`], answering repeated queries from `cache` while the
+/// graph is unchanged.
+pub fn phago_recall_cached(
+    colony: &mut Colony,
+    cache: &QueryCache,
+    req: &RecallRequest,
+) -> Result<RecallResponse, HybridConfigError> {
+    let config = req.hybrid_config()?;
+    let page = hybrid_query_page_cached(colony, cache, &req.query, &config, req.cursor.as_deref())?;
+    touch_results(colony, &page.results);
+    Ok(recall_response(colony, req, page))
+}
 
+fn recall_response(colony: &Colony, req: &RecallRequest, page: HybridPage) -> RecallResponse {
+    let context = req.assemble.then(|| {
+        assemble_results(
+            colony,
+            &req.query,
+            &page.results,
+            &req.assembly,
+            &ApproxTokenCounter,
+        )
+    });
     RecallResponse {
-        results: results
+        results: page
+            .results
             .into_iter()
             .map(|r| RecallResult {
+                annotations: annotations_of(colony, &r.label),
                 label: r.label,
                 score: r.final_score,
                 tfidf_score: r.tfidf_score,
                 graph_score: r.graph_score,
+                passages: if req.include_passages {
+                    r.passages
+                } else {
+                    Vec::new()
+                },
             })
             .collect(),
         total_nodes: colony.stats().graph_nodes,
         total_edges: colony.stats().graph_edges,
+        next_cursor: page.next_cursor,
+        cursor_expired: page.cursor_expired,
+        context,
+    }
+}
+
+/// Notes on the node labelled `label`.
+fn annotations_of(colony: &Colony, label: &str) -> Vec<Annotation> {
+    colony
+        .substrate()
+        .graph()
+        .find_nodes_by_exact_label(label)
+        .first()
+        .map(|id| colony.annotations(id))
+        .unwrap_or_default()
+}
+
+// === phago_suggest ===
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestRequest {
+    pub prefix: String,
+    #[serde(default = "default_max_results")]
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuggestResponse {
+    pub suggestions: Vec<LabelSuggestion>,
+}
+
+/// Suggest concept labels starting with a prefix, most useful first.
+pub fn phago_suggest(colony: &Colony, req: &SuggestRequest) -> SuggestResponse {
+    SuggestResponse {
+        suggestions: colony.suggest_labels(&req.prefix, req.limit),
     }
 }
 
@@ -128,11 +321,21 @@ pub fn phago_recall(colony: &Colony, req: &RecallRequest) -> RecallResponse {
 #[serde(tag = "type")]
 pub enum ExploreRequest {
     #[serde(rename = "path")]
-    ShortestPath { from: String, to: String },
+    ShortestPath {
+        from: String,
+        to: String,
+        #[serde(default)]
+        cost: PathCost,
+        /// Number of paths to return, the best first.
+        #[serde(default = "default_path_count")]
+        k: usize,
+    },
     #[serde(rename = "centrality")]
     Centrality {
         #[serde(default = "default_top_k")]
         top_k: usize,
+        #[serde(default)]
+        method: CentralityMethod,
     },
     #[serde(rename = "bridges")]
     Bridges {
@@ -140,21 +343,102 @@ pub enum ExploreRequest {
         top_k: usize,
     },
     #[serde(rename = "stats")]
-    Stats,
+    Stats {
+        /// Also report how much of the graph a sample taken this way
+        /// would show (see [`Colony::sample_snapshot`]).
+        #[serde(default)]
+        sample: Option<SampleStrategy>,
+    },
+    /// One concept: its notes and strongest neighbors.
+    #[serde(rename = "node")]
+    Node {
+        label: String,
+        #[serde(default = "default_top_k")]
+        top_k: usize,
+    },
+    /// One edge: its weight and what reinforced it lately.
+    #[serde(rename = "edge")]
+    Edge { from: String, to: String },
+    /// The largest communities, numbered as the colony tracks them.
+    #[serde(rename = "communities")]
+    Communities {
+        /// Defaults to the colony's configured algorithm.
+        #[serde(default)]
+        algorithm: Option<CommunityAlgorithm>,
+        #[serde(default = "default_top_k")]
+        top_k: usize,
+    },
+    /// Scored anomalies, the most novel first.
+    #[serde(rename = "anomalies")]
+    Anomalies {
+        #[serde(default = "default_top_k")]
+        top_k: usize,
+        /// Leave out anomalies scored below this.
+        #[serde(default)]
+        min_score: Option<f64>,
+    },
+    /// Named regions of the field, in the order they were defined.
+    #[serde(rename = "regions")]
+    Regions,
+}
+
+impl ExploreRequest {
+    /// Whether answering takes the colony rather than a read view.
+    pub fn needs_colony(&self) -> bool {
+        matches!(
+            self,
+            ExploreRequest::Node { .. } | ExploreRequest::Edge { .. }
+        )
+    }
 }
 
 fn default_top_k() -> usize {
     10
 }
 
+fn default_path_count() -> usize {
+    1
+}
+
+/// How centrality is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CentralityMethod {
+    /// Sampled betweenness: how many shortest paths run through a node.
+    #[default]
+    Betweenness,
+    /// Weighted PageRank: where a walk along Hebbian edges ends up.
+    PageRank,
+}
+
+impl std::str::FromStr for CentralityMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "betweenness" => Ok(CentralityMethod::Betweenness),
+            "pagerank" => Ok(CentralityMethod::PageRank),
+            other => Err(format!(
+                "Unknown centrality method '{other}'. Use: betweenness, pagerank"
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(tag = "type")]
 pub enum ExploreResponse {
     #[serde(rename = "path")]
     Path {
         found: bool,
+        /// Labels along the best path.
         path: Vec<String>,
         cost: f64,
+        /// The best path and any alternatives, with per-hop weights.
+        paths: Vec<ConceptPath>,
+        /// Why nothing was found.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<PathError>,
     },
     #[serde(rename = "centrality")]
     Centrality { nodes: Vec<CentralityEntry> },
@@ -167,7 +451,48 @@ pub enum ExploreResponse {
         connected_components: usize,
         tick: u64,
         agents_alive: usize,
+        /// Documents with a TTL that have yet to expire.
+        documents_expiring: usize,
+        /// When the next document expires by tick.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        next_expiry_tick: Option<u64>,
+        /// The requested sample's nodes and edges out of the totals: what a
+        /// client shown that sample has not seen.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sample: Option<SampleCoverage>,
     },
+    #[serde(rename = "node")]
+    Node {
+        found: bool,
+        label: String,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        annotations: Vec<Annotation>,
+        /// Strongest neighbors first.
+        neighbors: Vec<NeighborEntry>,
+    },
+    #[serde(rename = "edge")]
+    Edge {
+        found: bool,
+        from: String,
+        to: String,
+        weight: f64,
+        co_activations: u64,
+        /// The most recent reinforcements, newest first, as many as the
+        /// colony keeps (see `ColonyConfig::edge_provenance`).
+        sources: Vec<EdgeSourceEntry>,
+    },
+    #[serde(rename = "communities")]
+    Communities {
+        algorithm: CommunityAlgorithm,
+        modularity: f64,
+        total_communities: usize,
+        /// Largest first.
+        communities: Vec<Community>,
+    },
+    #[serde(rename = "anomalies")]
+    Anomalies { anomalies: Vec<AnomalyEntry> },
+    #[serde(rename = "regions")]
+    Regions { regions: Vec<RegionEntry> },
 }
 
 #[derive(Debug, Serialize)]
@@ -182,43 +507,213 @@ pub struct BridgeEntry {
     pub fragility: f64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct AnomalyEntry {
+    pub label: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegionEntry {
+    #[serde(flatten)]
+    pub region: Region,
+    /// Concepts lying in the region.
+    pub nodes: usize,
+}
+
+/// One reinforcement of an edge.
+#[derive(Debug, Serialize)]
+pub struct EdgeSourceEntry {
+    /// "document", "query", "unattributed" or "import".
+    pub source: &'static str,
+    /// Title of the reinforcing document; "(removed)" if it is gone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document: Option<String>,
+    /// Name of the source the triple was imported from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub import: Option<String>,
+    pub tick: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NeighborEntry {
+    pub label: String,
+    pub weight: f64,
+    /// The edge's relation, read as `label <relation> neighbor` or the
+    /// reverse, whichever it was labelled in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+}
+
 /// Explore the graph structure.
 pub fn phago_explore(colony: &Colony, req: &ExploreRequest) -> ExploreResponse {
     let graph = colony.substrate().graph();
 
+    if let ExploreRequest::Edge { from, to } = req {
+        return explore_edge(colony, from, to);
+    }
+    let ExploreRequest::Node { label, top_k } = req else {
+        return explore_structure(
+            graph,
+            &colony.stats(),
+            colony.community_tracker(),
+            colony.config().communities.algorithm,
+            &colony.anomalies(),
+            colony.substrate().regions(),
+            req,
+        );
+    };
+    let Some(&id) = graph.find_nodes_by_exact_label(label).first() else {
+        return ExploreResponse::Node {
+            found: false,
+            label: label.clone(),
+            annotations: Vec::new(),
+            neighbors: Vec::new(),
+        };
+    };
+    let mut neighbors: Vec<NeighborEntry> = graph
+        .neighbors(&id)
+        .into_iter()
+        .filter_map(|(other, edge)| {
+            graph.get_node(&other).map(|n| NeighborEntry {
+                label: n.label.clone(),
+                weight: edge.weight,
+                relation: colony
+                    .edge_relation(&id, &other)
+                    .or_else(|| colony.edge_relation(&other, &id))
+                    .map(str::to_string),
+            })
+        })
+        .collect();
+    neighbors.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+    neighbors.truncate(*top_k);
+    ExploreResponse::Node {
+        found: true,
+        label: graph
+            .get_node(&id)
+            .map_or_else(|| label.clone(), |n| n.label.clone()),
+        annotations: colony.annotations(&id),
+        neighbors,
+    }
+}
+
+/// The edge between the concepts labelled `from` and `to`, with its
+/// recent sources resolved to document titles.
+fn explore_edge(colony: &Colony, from: &str, to: &str) -> ExploreResponse {
+    let graph = colony.substrate().graph();
+    let id = |label: &str| graph.find_nodes_by_exact_label(label).first().copied();
+    let found = id(from)
+        .zip(id(to))
+        .and_then(|(a, b)| graph.get_edge(&a, &b).map(|edge| (a, b, edge)));
+    let Some((a, b, edge)) = found else {
+        return ExploreResponse::Edge {
+            found: false,
+            from: from.to_string(),
+            to: to.to_string(),
+            weight: 0.0,
+            co_activations: 0,
+            sources: Vec::new(),
+        };
+    };
+    let label = |id: &NodeId| {
+        graph
+            .get_node(id)
+            .map_or_else(String::new, |n| n.label.clone())
+    };
+    let sources = colony
+        .edge_sources(&a, &b)
+        .into_iter()
+        .rev()
+        .map(|record| {
+            let (source, document) = match record.source {
+                EdgeSource::Document(id) => (
+                    "document",
+                    Some(
+                        colony
+                            .substrate()
+                            .get_document(&id)
+                            .map_or_else(|| "(removed)".to_string(), |d| d.title.clone()),
+                    ),
+                ),
+                EdgeSource::Query => ("query", None),
+                EdgeSource::Unattributed => ("unattributed", None),
+                EdgeSource::Import(_) => ("import", None),
+            };
+            EdgeSourceEntry {
+                source,
+                document,
+                import: colony
+                    .edge_provenance()
+                    .import_name(record.source)
+                    .map(str::to_string),
+                tick: record.tick,
+            }
+        })
+        .collect();
+    ExploreResponse::Edge {
+        found: true,
+        from: label(&a),
+        to: label(&b),
+        weight: edge.weight,
+        co_activations: edge.co_activations,
+        sources,
+    }
+}
+
+/// Explore the graph structure of a read view, as [`phago_explore`] would
+/// have at the view's tick, without the colony.
+///
+/// Returns `None` for [`ExploreRequest::Node`] and [`ExploreRequest::Edge`],
+/// whose annotations, relations and sources only the colony has.
+pub fn phago_explore_view(view: &ColonyView, req: &ExploreRequest) -> Option<ExploreResponse> {
+    if req.needs_colony() {
+        return None;
+    }
+    Some(explore_structure(
+        view.graph(),
+        view.stats(),
+        view.community_tracker(),
+        view.community_algorithm(),
+        &view.anomalies(),
+        view.regions(),
+        req,
+    ))
+}
+
+/// Every explore request but those that need the colony itself.
+fn explore_structure(
+    graph: &dyn TopologyGraph,
+    stats: &ColonyStats,
+    tracker: &CommunityTracker,
+    default_algorithm: CommunityAlgorithm,
+    anomalies: &[(NodeId, f64)],
+    regions: &[Region],
+    req: &ExploreRequest,
+) -> ExploreResponse {
     match req {
-        ExploreRequest::ShortestPath { from, to } => {
-            let from_nodes = graph.find_nodes_by_label(from);
-            let to_nodes = graph.find_nodes_by_label(to);
-
-            if let (Some(&from_id), Some(&to_id)) = (from_nodes.first(), to_nodes.first()) {
-                if let Some((path, cost)) = graph.shortest_path(&from_id, &to_id) {
-                    let labels: Vec<String> = path
-                        .iter()
-                        .filter_map(|nid| graph.get_node(nid).map(|n| n.label.clone()))
-                        .collect();
-                    ExploreResponse::Path {
-                        found: true,
-                        path: labels,
-                        cost,
-                    }
-                } else {
-                    ExploreResponse::Path {
-                        found: false,
-                        path: Vec::new(),
-                        cost: 0.0,
-                    }
-                }
-            } else {
-                ExploreResponse::Path {
+        ExploreRequest::ShortestPath { from, to, cost, k } => {
+            match find_path(graph, from, to, *cost, *k) {
+                Ok(paths) => ExploreResponse::Path {
+                    found: true,
+                    path: paths[0].labels.clone(),
+                    cost: paths[0].cost,
+                    paths,
+                    error: None,
+                },
+                Err(e) => ExploreResponse::Path {
                     found: false,
                     path: Vec::new(),
                     cost: 0.0,
-                }
+                    paths: Vec::new(),
+                    error: Some(e),
+                },
             }
         }
-        ExploreRequest::Centrality { top_k } => {
-            let centrality = graph.betweenness_centrality(100);
+        ExploreRequest::Centrality { top_k, method } => {
+            let centrality = match method {
+                CentralityMethod::Betweenness => graph.betweenness_centrality(100),
+                CentralityMethod::PageRank => pagerank(graph, &PageRankConfig::default()),
+            };
             let entries: Vec<CentralityEntry> = centrality
                 .into_iter()
                 .take(*top_k)
@@ -244,16 +739,229 @@ pub fn phago_explore(colony: &Colony, req: &ExploreRequest) -> ExploreResponse {
                 .collect();
             ExploreResponse::Bridges { nodes: entries }
         }
-        ExploreRequest::Stats => {
-            let stats = colony.stats();
-            ExploreResponse::Stats {
-                total_nodes: stats.graph_nodes,
-                total_edges: stats.graph_edges,
-                connected_components: graph.connected_components(),
-                tick: stats.tick,
-                agents_alive: stats.agents_alive,
+        ExploreRequest::Stats { sample } => ExploreResponse::Stats {
+            total_nodes: stats.graph_nodes,
+            total_edges: stats.graph_edges,
+            connected_components: graph.connected_components(),
+            tick: stats.tick,
+            agents_alive: stats.agents_alive,
+            documents_expiring: stats.documents_expiring,
+            next_expiry_tick: stats.next_expiry_tick,
+            sample: sample
+                .as_ref()
+                .map(|strategy| sampling::coverage(graph, strategy)),
+        },
+        ExploreRequest::Communities { algorithm, top_k } => {
+            let algorithm = algorithm.unwrap_or(default_algorithm);
+            // Number them as the colony would, without advancing its tracker
+            let mut result = tracker.clone().detect(graph, algorithm);
+            result.communities.truncate(*top_k);
+            ExploreResponse::Communities {
+                algorithm,
+                modularity: result.modularity,
+                total_communities: result.num_communities,
+                communities: result.communities,
+            }
+        }
+        ExploreRequest::Anomalies { top_k, min_score } => ExploreResponse::Anomalies {
+            anomalies: anomalies
+                .iter()
+                .filter(|(_, score)| min_score.is_none_or(|min| *score >= min))
+                .filter_map(|(nid, score)| {
+                    graph.get_node(nid).map(|n| AnomalyEntry {
+                        label: n.label.clone(),
+                        score: *score,
+                    })
+                })
+                .take(*top_k)
+                .collect(),
+        },
+        ExploreRequest::Regions => {
+            let positions: Vec<Position> = graph
+                .all_nodes()
+                .iter()
+                .filter_map(|id| graph.get_node(id).map(|n| n.position))
+                .collect();
+            ExploreResponse::Regions {
+                regions: regions
+                    .iter()
+                    .map(|region| RegionEntry {
+                        region: region.clone(),
+                        nodes: positions.iter().filter(|p| region.contains(p)).count(),
+                    })
+                    .collect(),
             }
         }
+        ExploreRequest::Node { .. } | ExploreRequest::Edge { .. } => {
+            unreachable!("node and edge exploration need the colony")
+        }
+    }
+}
+
+// === phago_connect ===
+
+#[derive(Debug, Deserialize)]
+pub struct ConnectRequest {
+    pub from_label: String,
+    pub to_label: String,
+    /// Weight of a new edge, or how much an existing one is strengthened
+    /// by, in (0, 1].
+    #[serde(default = "default_connect_weight")]
+    pub weight: f64,
+    /// Relation the edge is read as, `from <relation> to`.
+    #[serde(default)]
+    pub relation: Option<String>,
+    /// Create concepts for labels no node has instead of failing.
+    #[serde(default)]
+    pub create_missing: bool,
+}
+
+fn default_connect_weight() -> f64 {
+    0.5
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConnectResponse {
+    pub from_label: String,
+    pub to_label: String,
+    /// The edge's weight afterwards.
+    pub weight: f64,
+    /// Whether the edge is new rather than strengthened.
+    pub created_edge: bool,
+    /// Labels of the concepts created for it.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub created_nodes: Vec<String>,
+    pub tick: u64,
+}
+
+/// Connect two concepts that are related although no document says so.
+///
+/// See [`Colony::connect_concepts`].
+pub fn phago_connect(
+    colony: &mut Colony,
+    req: &ConnectRequest,
+) -> Result<ConnectResponse, GraphEditError> {
+    let event = colony.connect_concepts(
+        &req.from_label,
+        &req.to_label,
+        req.weight,
+        req.relation.as_deref(),
+        req.create_missing,
+    )?;
+    let ColonyEvent::Connected {
+        from,
+        to,
+        weight,
+        created_edge,
+        created_nodes,
+    } = event
+    else {
+        unreachable!("connect_concepts reports a connected event");
+    };
+    let graph = colony.substrate().graph();
+    let label = |id| {
+        graph
+            .get_node(&id)
+            .map(|n| n.label.clone())
+            .unwrap_or_default()
+    };
+    Ok(ConnectResponse {
+        from_label: label(from),
+        to_label: label(to),
+        weight,
+        created_edge,
+        created_nodes,
+        tick: colony.stats().tick,
+    })
+}
+
+// === phago_annotate ===
+
+/// Source recorded on notes made through MCP.
+pub const ANNOTATION_SOURCE: &str = "mcp";
+
+#[derive(Debug, Deserialize)]
+pub struct AnnotateRequest {
+    pub label: String,
+    pub note: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnnotateResponse {
+    pub label: String,
+    /// All notes on the concept, the new one last.
+    pub annotations: Vec<Annotation>,
+}
+
+/// Attach a free-text note to a concept. Notes show up in recall results
+/// and `node` explorations.
+///
+/// See [`Colony::annotate`].
+pub fn phago_annotate(
+    colony: &mut Colony,
+    req: &AnnotateRequest,
+) -> Result<AnnotateResponse, GraphEditError> {
+    let event = colony.annotate(&req.label, &req.note, ANNOTATION_SOURCE)?;
+    let ColonyEvent::Annotated { node_id, label, .. } = event else {
+        unreachable!("annotate reports an annotated event");
+    };
+    Ok(AnnotateResponse {
+        label,
+        annotations: colony.annotations(&node_id),
+    })
+}
+
+// === phago_synonyms ===
+
+#[derive(Debug, Deserialize)]
+pub struct SynonymsRequest {
+    /// Canonical terms and their aliases, replacing the colony's.
+    pub synonyms: SynonymMap,
+    /// Weight of synonym matches in (0, 1]; the colony's current weight
+    /// if not given.
+    #[serde(default)]
+    pub expansion_weight: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SynonymsResponse {
+    pub canonical_terms: usize,
+    pub aliases: usize,
+    pub expansion_weight: f64,
+    /// Concepts that were named by an alias, by the label of the concept
+    /// they were merged into.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub merged: BTreeMap<String, Vec<String>>,
+}
+
+/// Replace the colony's synonym dictionary. Concepts named by an alias are
+/// merged into their canonical term's concept.
+///
+/// See [`Colony::set_synonyms`].
+pub fn phago_synonyms(colony: &mut Colony, req: &SynonymsRequest) -> SynonymsResponse {
+    let config = SynonymConfig {
+        map: req.synonyms.clone(),
+        expansion_weight: req
+            .expansion_weight
+            .unwrap_or(colony.synonyms().expansion_weight),
+    };
+    let mut merged: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for event in colony.set_synonyms(config) {
+        if let ColonyEvent::NodesMerged {
+            label,
+            merged: labels,
+            ..
+        } = event
+        {
+            merged.entry(label).or_default().extend(labels);
+        }
+    }
+    let synonyms = colony.synonyms();
+    SynonymsResponse {
+        canonical_terms: synonyms.map.len(),
+        aliases: synonyms.map.alias_count(),
+        expansion_weight: synonyms.expansion_weight,
+        merged,
     }
 }
 
@@ -268,11 +976,72 @@ mod tests {
             title: "Biology 101".into(),
             content: "The cell membrane controls transport of molecules and proteins".into(),
             ticks: Some(15),
+            tags: Vec::new(),
+            ttl_ticks: None,
+            ttl_seconds: None,
         };
         let resp = phago_remember(&mut colony, &req);
         assert!(resp.nodes_created > 0, "should create nodes");
     }
 
+    #[test]
+    fn remember_same_title_updates_document() {
+        let mut colony = Colony::new();
+        let mut req = RememberRequest {
+            title: "Biology 101".into(),
+            content: "The cell membrane controls transport of molecules and proteins".into(),
+            ticks: Some(15),
+            tags: Vec::new(),
+            ttl_ticks: None,
+            ttl_seconds: None,
+        };
+        let first = phago_remember(&mut colony, &req);
+        req.content = "The cell membrane controls transport of ions".into();
+        let second = phago_remember(&mut colony, &req);
+
+        assert_eq!(second.document_id, first.document_id);
+        assert_eq!((first.version, second.version), (1, 2));
+        assert_eq!(colony.stats().documents_total, 1);
+        let graph = colony.substrate().graph();
+        assert!(graph.find_nodes_by_exact_label("proteins").is_empty());
+    }
+
+    #[test]
+    fn remember_reports_duplicate_notes() {
+        let mut colony = Colony::new();
+        let note = |title: &str, content: &str| RememberRequest {
+            title: title.into(),
+            content: content.into(),
+            ticks: Some(15),
+            tags: Vec::new(),
+            ttl_ticks: None,
+            ttl_seconds: None,
+        };
+        let original = phago_remember(
+            &mut colony,
+            &note(
+                "Standup 1",
+                "Standup 2024-06-03: the ingest worker retries failed uploads \
+                 and the dashboard shows queue depth per tenant",
+            ),
+        );
+        let copy = phago_remember(
+            &mut colony,
+            &note(
+                "Standup 2",
+                "Standup 2024-06-04: the ingest worker retries failed uploads \
+                 and the dashboard shows queue depth per tenant",
+            ),
+        );
+
+        assert!(original.duplicate_of.is_none());
+        let duplicate = copy.duplicate_of.expect("near duplicate");
+        assert_eq!(duplicate.original_title, "Standup 1");
+        assert_eq!(copy.document_id, original.document_id);
+        assert_eq!(copy.nodes_created, 0);
+        assert_eq!(colony.stats().documents_total, 1);
+    }
+
     #[test]
     fn recall_returns_results() {
         let mut colony = Colony::new();
@@ -282,6 +1051,9 @@ mod tests {
                 title: "Bio".into(),
                 content: "cell membrane protein transport channel receptor".into(),
                 ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
             },
         );
         let _ = phago_remember(
@@ -290,18 +1062,143 @@ mod tests {
                 title: "Bio2".into(),
                 content: "cell membrane protein signaling pathway cascade".into(),
                 ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
             },
         );
 
         let resp = phago_recall(
-            &colony,
+            &mut colony,
             &RecallRequest {
                 query: "cell membrane".into(),
                 max_results: 5,
                 alpha: 0.5,
+                tags: None,
+                include_passages: false,
+                max_passages: 3,
+                cursor: None,
+                assemble: false,
+                assembly: AssembleConfig::default(),
             },
-        );
+        )
+        .unwrap();
         assert!(!resp.results.is_empty(), "should return results");
+
+        let err = phago_recall(
+            &mut colony,
+            &RecallRequest {
+                query: "cell membrane".into(),
+                max_results: 5,
+                alpha: 1.5,
+                tags: None,
+                include_passages: false,
+                max_passages: 3,
+                cursor: None,
+                assemble: false,
+                assembly: AssembleConfig::default(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, HybridConfigError::AlphaOutOfRange(1.5));
+    }
+
+    #[test]
+    fn recall_can_assemble_its_results_into_context() {
+        let mut colony = Colony::new();
+        let _ = phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Bio".into(),
+                content: "The cell membrane controls transport. Channel proteins span membranes."
+                    .into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
+            },
+        );
+
+        let request = |assemble| RecallRequest {
+            query: "membrane transport".into(),
+            max_results: 5,
+            alpha: 0.5,
+            tags: None,
+            include_passages: false,
+            max_passages: 3,
+            cursor: None,
+            assemble,
+            assembly: AssembleConfig::default(),
+        };
+        let plain = phago_recall(&mut colony, &request(false)).unwrap();
+        assert!(plain.context.is_none());
+
+        let resp = phago_recall(&mut colony, &request(true)).unwrap();
+        // Passages go into the context, not onto the results
+        assert!(resp.results.iter().all(|r| r.passages.is_empty()));
+        let context = resp.context.expect("assembled context");
+        assert!(!context.passages.is_empty());
+        assert_eq!(context.passages[0].title, "Bio");
+        assert!(context.render().starts_with("[1] "));
+    }
+
+    #[test]
+    fn recalled_concepts_are_not_pruned_as_stale() {
+        use phago_runtime::colony::{ColonyConfig, ColonyEvent};
+
+        let mut colony = Colony::from_config(ColonyConfig {
+            node_staleness_ticks: 20,
+            ..Default::default()
+        });
+        for (title, content) in [
+            (
+                "Biology",
+                "The cell membrane controls transport of molecules.",
+            ),
+            ("Junk", "lorem ipsum dolor sit amet consectetur"),
+        ] {
+            phago_remember(
+                &mut colony,
+                &RememberRequest {
+                    title: title.into(),
+                    content: content.into(),
+                    ticks: Some(15),
+                    tags: Vec::new(),
+                    ttl_ticks: None,
+                    ttl_seconds: None,
+                },
+            );
+        }
+        let request = |query: &str, cursor: Option<String>| RecallRequest {
+            query: query.into(),
+            max_results: 1,
+            alpha: 0.5,
+            tags: None,
+            include_passages: false,
+            max_passages: 3,
+            cursor,
+            assemble: false,
+            assembly: AssembleConfig::default(),
+        };
+
+        // Touching what a page surfaced leaves its cursor valid
+        let first = phago_recall(&mut colony, &request("lorem ipsum", None)).unwrap();
+        let next = phago_recall(&mut colony, &request("lorem ipsum", first.next_cursor)).unwrap();
+        assert!(!next.cursor_expired);
+
+        // Recall alone keeps a concept alive while unrecalled ones go stale
+        let mut pruned = Vec::new();
+        for _ in 0..150 {
+            let resp = phago_recall(&mut colony, &request("lorem", None)).unwrap();
+            assert_eq!(resp.results[0].label, "lorem");
+            for event in colony.tick() {
+                if let ColonyEvent::NodePruned { label, .. } = event {
+                    pruned.push(label);
+                }
+            }
+        }
+        assert!(pruned.iter().any(|l| l == "membrane"), "{pruned:?}");
+        assert!(pruned.iter().all(|l| l != "lorem"), "{pruned:?}");
     }
 
     #[test]
@@ -313,10 +1210,13 @@ mod tests {
                 title: "Bio".into(),
                 content: "cell membrane protein".into(),
                 ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
             },
         );
 
-        let resp = phago_explore(&colony, &ExploreRequest::Stats);
+        let resp = phago_explore(&colony, &ExploreRequest::Stats { sample: None });
         match resp {
             ExploreResponse::Stats { total_nodes, .. } => {
                 assert!(total_nodes > 0, "should have nodes");
@@ -324,4 +1224,465 @@ mod tests {
             _ => panic!("expected Stats response"),
         }
     }
+
+    #[test]
+    fn remember_with_ttl_shows_in_stats() {
+        let mut colony = Colony::new();
+        let resp = phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Standup".into(),
+                content: "sprint planning retrospective".into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: Some(100),
+                ttl_seconds: None,
+            },
+        );
+
+        match phago_explore(&colony, &ExploreRequest::Stats { sample: None }) {
+            ExploreResponse::Stats {
+                documents_expiring,
+                next_expiry_tick,
+                ..
+            } => {
+                assert_eq!(documents_expiring, 1);
+                assert_eq!(next_expiry_tick, Some(100));
+            }
+            _ => panic!("expected Stats response"),
+        }
+        assert_eq!(resp.tick, 15);
+    }
+
+    #[test]
+    fn stats_report_how_much_a_sample_would_show() {
+        let mut colony = Colony::new();
+        phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Bio".into(),
+                content: "cell membrane protein transport channel".into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
+            },
+        );
+
+        let plain: ExploreRequest = serde_json::from_str(r#"{"type": "stats"}"#).unwrap();
+        let json = serde_json::to_value(phago_explore(&colony, &plain)).unwrap();
+        assert!(json.get("sample").is_none());
+
+        let sampled: ExploreRequest = serde_json::from_str(
+            r#"{"type": "stats", "sample": {"strategy": "top_by_access", "n": 2}}"#,
+        )
+        .unwrap();
+        match phago_explore(&colony, &sampled) {
+            ExploreResponse::Stats {
+                total_nodes,
+                total_edges,
+                sample: Some(coverage),
+                ..
+            } => {
+                assert_eq!(coverage.nodes_sampled, 2);
+                assert_eq!(coverage.nodes_total, total_nodes);
+                assert_eq!(coverage.edges_total, total_edges);
+                assert!(coverage.edges_sampled <= 1);
+            }
+            other => panic!("expected Stats with a sample, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn explore_lists_anomalies_by_score() {
+        use phago_core::substrate::Substrate;
+        use phago_core::types::{NodeData, NodeType, Position};
+
+        let mut colony = Colony::new();
+        for (label, score) in [("[REVIEW] faint", 0.3), ("[ANOMALY] loud", 0.9)] {
+            let id = colony.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: label.into(),
+                node_type: NodeType::Anomaly,
+                position: Position::new(0.0, 0.0),
+                access_count: 1,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            });
+            colony.set_anomaly_score(&id, score);
+        }
+
+        let request: ExploreRequest = serde_json::from_str(r#"{"type": "anomalies"}"#).unwrap();
+        let ExploreResponse::Anomalies { anomalies } = phago_explore(&colony, &request) else {
+            panic!("expected Anomalies response");
+        };
+        let labels: Vec<&str> = anomalies.iter().map(|a| a.label.as_str()).collect();
+        assert_eq!(labels, ["[ANOMALY] loud", "[REVIEW] faint"]);
+
+        let request = ExploreRequest::Anomalies {
+            top_k: 10,
+            min_score: Some(0.5),
+        };
+        let view = colony.read_view();
+        let Some(ExploreResponse::Anomalies { anomalies }) = phago_explore_view(&view, &request)
+        else {
+            panic!("expected Anomalies response");
+        };
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].score, 0.9);
+    }
+
+    #[test]
+    fn explore_centrality_by_pagerank() {
+        let request: ExploreRequest =
+            serde_json::from_str(r#"{"type": "centrality", "top_k": 3, "method": "pagerank"}"#)
+                .unwrap();
+        assert!(matches!(
+            request,
+            ExploreRequest::Centrality {
+                top_k: 3,
+                method: CentralityMethod::PageRank
+            }
+        ));
+        assert!("closeness".parse::<CentralityMethod>().is_err());
+
+        let mut colony = Colony::new();
+        phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Bio".into(),
+                content: "cell membrane protein transport channel".into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
+            },
+        );
+        match phago_explore(&colony, &request) {
+            ExploreResponse::Centrality { nodes } => {
+                assert!(!nodes.is_empty() && nodes.len() <= 3);
+                assert!(nodes.windows(2).all(|w| w[0].centrality >= w[1].centrality));
+            }
+            _ => panic!("expected Centrality response"),
+        }
+    }
+
+    #[test]
+    fn explore_communities_uses_the_colony_numbering() {
+        let request: ExploreRequest = serde_json::from_str(
+            r#"{"type": "communities", "algorithm": {"label_propagation": {"seed": 4}}, "top_k": 1}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            request,
+            ExploreRequest::Communities {
+                algorithm: Some(CommunityAlgorithm::LabelPropagation { seed: 4 }),
+                top_k: 1
+            }
+        ));
+
+        let mut colony = Colony::new();
+        phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Bio".into(),
+                content: "cell membrane protein transport channel".into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
+            },
+        );
+        let tracked = colony.update_communities();
+        let request = ExploreRequest::Communities {
+            algorithm: None,
+            top_k: 1,
+        };
+        match phago_explore(&colony, &request) {
+            ExploreResponse::Communities {
+                total_communities,
+                communities,
+                ..
+            } => {
+                assert!(total_communities >= 1);
+                assert_eq!(communities.len(), 1);
+                for member in &communities[0].members {
+                    assert_eq!(tracked.assignments.get(member), Some(&communities[0].id));
+                }
+            }
+            _ => panic!("expected Communities response"),
+        }
+    }
+
+    #[test]
+    fn explore_view_matches_the_colony_it_was_taken_from() {
+        let mut colony = Colony::new();
+        phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Bio".into(),
+                content: "cell membrane protein transport channel".into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
+            },
+        );
+        colony.update_communities();
+        let view = colony.read_view();
+        let requests = [
+            ExploreRequest::Stats {
+                sample: Some(SampleStrategy::TopByAccess { n: 2 }),
+            },
+            ExploreRequest::Communities {
+                algorithm: None,
+                top_k: 5,
+            },
+            ExploreRequest::ShortestPath {
+                from: "cell".into(),
+                to: "membrane".into(),
+                cost: PathCost::default(),
+                k: 1,
+            },
+        ];
+        for request in &requests {
+            let seen = serde_json::to_value(phago_explore_view(&view, request).unwrap()).unwrap();
+            let live = serde_json::to_value(phago_explore(&colony, request)).unwrap();
+            assert_eq!(seen, live);
+        }
+
+        let node = ExploreRequest::Node {
+            label: "cell".into(),
+            top_k: 5,
+        };
+        assert!(phago_explore_view(&view, &node).is_none());
+    }
+
+    #[test]
+    fn explore_path_explains_hops_and_missing_concepts() {
+        let request: ExploreRequest = serde_json::from_str(
+            r#"{"type": "path", "from": "cell", "to": "channel", "cost": "fewest_hops", "k": 2}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            request,
+            ExploreRequest::ShortestPath {
+                cost: PathCost::FewestHops,
+                k: 2,
+                ..
+            }
+        ));
+
+        let mut colony = Colony::new();
+        phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Bio".into(),
+                content: "cell membrane protein transport channel".into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
+            },
+        );
+        match phago_explore(&colony, &request) {
+            ExploreResponse::Path {
+                found: true,
+                path,
+                paths,
+                error: None,
+                ..
+            } => {
+                assert_eq!(path, paths[0].labels);
+                assert_eq!(paths[0].hops.len(), path.len() - 1);
+                assert!(paths[0].hops.iter().all(|h| h.weight > 0.0));
+            }
+            other => panic!("expected a path, got {other:?}"),
+        }
+
+        let missing = ExploreRequest::ShortestPath {
+            from: "cell".into(),
+            to: "quasar".into(),
+            cost: PathCost::default(),
+            k: 1,
+        };
+        match phago_explore(&colony, &missing) {
+            ExploreResponse::Path {
+                found: false,
+                error: Some(PathError::UnknownConcept { label }),
+                ..
+            } => assert_eq!(label, "quasar"),
+            other => panic!("expected an unknown concept, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn suggest_completes_prefix() {
+        let mut colony = Colony::new();
+        phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Biology".into(),
+                content:
+                    "The cell membrane controls transport. Cellular proteins serve as channels."
+                        .into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
+            },
+        );
+
+        let resp = phago_suggest(
+            &colony,
+            &SuggestRequest {
+                prefix: "Cel".into(),
+                limit: 5,
+            },
+        );
+        assert!(!resp.suggestions.is_empty());
+        assert!(resp
+            .suggestions
+            .iter()
+            .all(|s| s.label.to_lowercase().starts_with("cel")));
+    }
+
+    #[test]
+    fn connect_and_annotate_show_up_in_explore_and_recall() {
+        let mut colony = Colony::new();
+        phago_remember(
+            &mut colony,
+            &RememberRequest {
+                title: "Bio".into(),
+                content: "cell membrane protein transport channel receptor".into(),
+                ticks: Some(15),
+                tags: Vec::new(),
+                ttl_ticks: None,
+                ttl_seconds: None,
+            },
+        );
+
+        let connect = |from: &str, to: &str, weight: f64| ConnectRequest {
+            from_label: from.into(),
+            to_label: to.into(),
+            weight,
+            relation: Some("studied_with".into()),
+            create_missing: false,
+        };
+        let err = phago_connect(&mut colony, &connect("membrane", "quasar", 0.5)).unwrap_err();
+        assert_eq!(err, GraphEditError::UnknownConcept("quasar".into()));
+        let err = phago_connect(&mut colony, &connect("membrane", "cell", 7.0)).unwrap_err();
+        assert_eq!(err, GraphEditError::InvalidWeight(7.0));
+
+        let resp = phago_connect(
+            &mut colony,
+            &ConnectRequest {
+                create_missing: true,
+                ..connect("membrane", "microscopy", 0.9)
+            },
+        )
+        .unwrap();
+        assert!(resp.created_edge);
+        assert_eq!(resp.created_nodes, vec!["microscopy"]);
+
+        let resp = phago_annotate(
+            &mut colony,
+            &AnnotateRequest {
+                label: "membrane".into(),
+                note: "lipid bilayer".into(),
+            },
+        )
+        .unwrap();
+        assert_eq!(resp.annotations[0].source, ANNOTATION_SOURCE);
+
+        let request = ExploreRequest::Node {
+            label: "Membrane".into(),
+            top_k: 3,
+        };
+        match phago_explore(&colony, &request) {
+            ExploreResponse::Node {
+                found: true,
+                annotations,
+                neighbors,
+                ..
+            } => {
+                assert_eq!(annotations[0].text, "lipid bilayer");
+                assert_eq!(neighbors[0].label, "microscopy");
+                assert_eq!(neighbors[0].relation.as_deref(), Some("studied_with"));
+            }
+            other => panic!("expected a node, got {other:?}"),
+        }
+
+        let resp = phago_recall(
+            &mut colony,
+            &RecallRequest {
+                query: "membrane".into(),
+                max_results: 5,
+                alpha: 0.5,
+                tags: None,
+                include_passages: false,
+                max_passages: 3,
+                cursor: None,
+                assemble: false,
+                assembly: AssembleConfig::default(),
+            },
+        )
+        .unwrap();
+        let membrane = resp.results.iter().find(|r| r.label == "membrane").unwrap();
+        assert_eq!(membrane.annotations[0].text, "lipid bilayer");
+        assert!(resp
+            .results
+            .iter()
+            .filter(|r| r.label != "membrane")
+            .all(|r| r.annotations.is_empty()));
+    }
+
+    #[test]
+    fn synonyms_merge_forms_and_expand_recalls() {
+        let mut colony = Colony::new();
+        for (title, content) in [
+            ("Canonical", "hypertension damages arteries over years"),
+            ("Medical", "hypertonia damages arteries over years"),
+        ] {
+            phago_remember(
+                &mut colony,
+                &RememberRequest {
+                    title: title.into(),
+                    content: content.into(),
+                    ticks: Some(15),
+                    tags: Vec::new(),
+                    ttl_ticks: None,
+                    ttl_seconds: None,
+                },
+            );
+        }
+
+        let req: SynonymsRequest = serde_json::from_str(
+            r#"{"synonyms": {"hypertension": ["hypertonia", "highbloodpressure"]},
+                "expansion_weight": 0.25}"#,
+        )
+        .unwrap();
+        let resp = phago_synonyms(&mut colony, &req);
+        assert_eq!((resp.canonical_terms, resp.aliases), (1, 2));
+        assert_eq!(resp.expansion_weight, 0.25);
+        assert_eq!(resp.merged["hypertension"], ["hypertonia"]);
+
+        let resp = phago_recall(
+            &mut colony,
+            &RecallRequest {
+                query: "highbloodpressure".into(),
+                max_results: 5,
+                alpha: 0.5,
+                tags: None,
+                include_passages: false,
+                max_passages: 3,
+                cursor: None,
+                assemble: false,
+                assembly: AssembleConfig::default(),
+            },
+        )
+        .unwrap();
+        assert_eq!(resp.results[0].label, "hypertension");
+    }
 }
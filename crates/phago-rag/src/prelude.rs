@@ -5,17 +5,30 @@
 //! ```
 
 // Re-export query types
+pub use crate::assemble::{
+    assemble_context, assemble_context_with, ApproxTokenCounter, AssembleConfig, Citation,
+    CitationStyle, ContextPassage, GraphFact, RagContext, TokenCounter,
+};
 pub use crate::baseline::{random_query, static_graph_query, tfidf_query};
-pub use crate::hybrid::{hybrid_query, HybridConfig, HybridResult};
+pub use crate::graph_scorer::{
+    CommunityBoostScorer, DefaultGraphScorer, DegreeNormalizedScorer, GraphScorer,
+    PersonalizedPageRankScorer, ScoringView,
+};
+pub use crate::hybrid::{
+    hybrid_query, hybrid_query_page, HybridConfig, HybridConfigBuilder, HybridConfigError,
+    HybridPage, HybridResult,
+};
 pub use crate::query::{Query, QueryEngine, QueryResult};
+pub use crate::rerank::{RerankError, Reranker};
 pub use crate::scoring::{
     aggregate, mrr, ndcg_at_k, precision_at_k, score_query, AggregateScores, QueryScores,
 };
+pub use crate::static_colony::{StaticColony, StaticQueryError};
 
 // Re-export MCP types
 pub use crate::mcp::{
-    phago_explore, phago_recall, phago_remember, BridgeEntry, CentralityEntry, ExploreRequest,
-    ExploreResponse, RecallRequest, RecallResponse, RecallResult, RememberRequest,
+    phago_explore, phago_recall, phago_remember, BridgeEntry, CentralityEntry, CentralityMethod,
+    ExploreRequest, ExploreResponse, RecallRequest, RecallResponse, RecallResult, RememberRequest,
     RememberResponse,
 };
 
@@ -2,14 +2,16 @@
 //!
 //! The query engine:
 //! 1. Tokenizes the query into terms
-//! 2. Finds matching nodes in the graph (exact label match)
+//! 2. Finds matching nodes in the graph (exact label match), and nodes
+//!    named by synonyms of the terms, which score less
 //! 3. Traverses outward following strongest edges (BFS weighted by edge weight)
 //! 4. Collects and ranks results by path weight × access count
 //! 5. Optionally reinforces traversed paths (the graph learns from queries)
 
-use phago_core::topology::TopologyGraph;
+use phago_core::substrate::Substrate;
 use phago_core::types::*;
 use phago_runtime::colony::Colony;
+use phago_runtime::query_log::{QueryLogEntry, QuerySource};
 use serde::Serialize;
 
 /// A query to the knowledge graph.
@@ -23,6 +25,11 @@ pub struct Query {
     pub max_depth: usize,
     /// Whether to reinforce traversed paths (learning from queries).
     pub reinforce: bool,
+    /// Restrict seeds and traversal to nodes carrying at least one of
+    /// these tags (`None` searches the whole graph).
+    pub tags: Option<Vec<String>>,
+    /// Caller recorded in the colony's query log.
+    pub source: QuerySource,
 }
 
 impl Query {
@@ -32,6 +39,8 @@ impl Query {
             max_results: 10,
             max_depth: 3,
             reinforce: true,
+            tags: None,
+            source: QuerySource::default(),
         }
     }
 
@@ -49,6 +58,18 @@ impl Query {
         self.reinforce = false;
         self
     }
+
+    /// Record the query in the log as coming from `source`.
+    pub fn with_source(mut self, source: QuerySource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Restrict the query to nodes carrying at least one of `tags`.
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = Some(tags.iter().map(|t| t.to_string()).collect());
+        self
+    }
 }
 
 /// A single result from a query.
@@ -58,7 +79,8 @@ pub struct QueryResult {
     pub label: String,
     /// Node type (Concept, Insight, Anomaly).
     pub node_type: NodeType,
-    /// How many times this node has been accessed/reinforced.
+    /// How many times this node has been accessed/reinforced (within the
+    /// query's tags, if any).
     pub access_count: u64,
     /// Relevance score (path weight × access count).
     pub score: f64,
@@ -74,9 +96,43 @@ pub struct QueryEngine;
 impl QueryEngine {
     /// Execute a query against the colony's knowledge graph.
     ///
-    /// Returns results ranked by score (highest first).
+    /// Returns results ranked by score (highest first). If the colony's
+    /// query log is enabled, the query is recorded in it, and its latency
+    /// is reported to the colony's metrics registry.
     pub fn query(colony: &mut Colony, q: &Query) -> Vec<QueryResult> {
-        let terms = tokenize(&q.text);
+        let started = std::time::Instant::now();
+        let terms = colony.tokenizer().tokens(&q.text);
+        let mut entry = colony.query_log().map(|_| {
+            let tick = colony.substrate().current_tick();
+            QueryLogEntry::new(tick, q.source, &q.text, terms.clone())
+        });
+
+        let results = Self::run(colony, q, &terms, entry.as_mut());
+
+        if let Some(mut entry) = entry {
+            entry.tags = q.tags.clone();
+            entry.results = results.iter().map(|r| r.label.clone()).collect();
+            for r in &results {
+                for hop in r.path.windows(2) {
+                    let edge = (hop[0].clone(), hop[1].clone());
+                    if !entry.traversed_edges.contains(&edge) {
+                        entry.traversed_edges.push(edge);
+                    }
+                }
+            }
+            colony.log_query(entry);
+        }
+        colony.record_query_latency(q.source, started.elapsed());
+        results
+    }
+
+    fn run(
+        colony: &mut Colony,
+        q: &Query,
+        terms: &[String],
+        log: Option<&mut QueryLogEntry>,
+    ) -> Vec<QueryResult> {
+        let scope = q.tags.as_deref();
         let graph = colony.substrate().graph();
 
         // Phase 1: Find seed nodes (fuzzy substring matching)
@@ -84,24 +140,37 @@ impl QueryEngine {
         // labeled "cell_membrane", "membrane_proteins", etc.
         let mut seed_nodes: Vec<(NodeId, String)> = Vec::new();
         let mut seed_seen: std::collections::HashSet<NodeId> = std::collections::HashSet::new();
-        for term in &terms {
+        for term in terms {
             // First try exact match
-            for nid in graph.find_nodes_by_exact_label(term) {
-                if seed_seen.insert(*nid) {
-                    if let Some(node) = graph.get_node(nid) {
+            for nid in &graph.find_nodes_by_exact_label(term) {
+                if let Some(node) = graph.get_node(nid).filter(|n| n.in_scope(scope)) {
+                    if seed_seen.insert(*nid) {
                         seed_nodes.push((*nid, node.label.clone()));
                     }
                 }
             }
             // Then try substring match for broader coverage
             for nid in graph.find_nodes_by_label(term) {
-                if seed_seen.insert(nid) {
-                    if let Some(node) = graph.get_node(&nid) {
+                if let Some(node) = graph.get_node(&nid).filter(|n| n.in_scope(scope)) {
+                    if seed_seen.insert(nid) {
                         seed_nodes.push((nid, node.label.clone()));
                     }
                 }
             }
         }
+        // Then synonyms of the terms, by exact match only
+        let mut seed_weights: std::collections::HashMap<NodeId, f64> =
+            std::collections::HashMap::new();
+        for (form, weight) in colony.expand_query(terms) {
+            for nid in &graph.find_nodes_by_exact_label(&form) {
+                if let Some(node) = graph.get_node(nid).filter(|n| n.in_scope(scope)) {
+                    if seed_seen.insert(*nid) {
+                        seed_nodes.push((*nid, node.label.clone()));
+                        seed_weights.insert(*nid, weight);
+                    }
+                }
+            }
+        }
 
         if seed_nodes.is_empty() {
             return Vec::new();
@@ -121,7 +190,12 @@ impl QueryEngine {
         let mut expansions = 0;
 
         // Compute median edge weight to filter out weak edges during traversal
-        let all_edges = graph.all_edges();
+        // (over the edges inside the query's view, if it has tags)
+        let in_scope = |id: &NodeId| graph.get_node(id).is_some_and(|n| n.in_scope(scope));
+        let mut all_edges = graph.all_edges();
+        if scope.is_some() {
+            all_edges.retain(|(from, to, _)| in_scope(from) && in_scope(to));
+        }
         let edge_threshold = if all_edges.is_empty() {
             0.0
         } else {
@@ -141,8 +215,8 @@ impl QueryEngine {
                 results.push(QueryResult {
                     label: node.label.clone(),
                     node_type: node.node_type.clone(),
-                    access_count: node.access_count,
-                    score: 10.0 + term_overlap * 5.0,
+                    access_count: node.scoped_access_count(scope),
+                    score: (10.0 + term_overlap * 5.0) * seed_weights.get(nid).unwrap_or(&1.0),
                     path: vec![label.clone()],
                     node_id: *nid,
                 });
@@ -172,34 +246,36 @@ impl QueryEngine {
                 if edge.weight < edge_threshold {
                     continue;
                 }
+                // Views only traverse nodes carrying one of the query's tags
+                let Some(node) = graph.get_node(nid).filter(|n| n.in_scope(scope)) else {
+                    continue;
+                };
                 visited.insert(*nid);
 
-                if let Some(node) = graph.get_node(nid) {
-                    // Additive scoring: edge weight decays per hop, term overlap dominates
-                    let hop_decay = 0.5_f64.powi((depth + 1) as i32);
-                    let graph_score =
-                        edge.weight * hop_decay * (1.0 + edge.co_activations as f64 * 0.1);
-                    let term_overlap = terms
-                        .iter()
-                        .filter(|t| node.label.to_lowercase().contains(t.as_str()))
-                        .count() as f64;
-                    let term_bonus = term_overlap * 5.0;
-                    let score = graph_score + term_bonus;
-
-                    let mut node_path = path.clone();
-                    node_path.push(node.label.clone());
-
-                    results.push(QueryResult {
-                        label: node.label.clone(),
-                        node_type: node.node_type.clone(),
-                        access_count: node.access_count,
-                        score,
-                        path: node_path.clone(),
-                        node_id: *nid,
-                    });
-
-                    frontier.push((weight * edge.weight, *nid, node_path, depth + 1));
-                }
+                // Additive scoring: edge weight decays per hop, term overlap dominates
+                let hop_decay = 0.5_f64.powi((depth + 1) as i32);
+                let graph_score =
+                    edge.weight * hop_decay * (1.0 + edge.co_activations as f64 * 0.1);
+                let term_overlap = terms
+                    .iter()
+                    .filter(|t| node.label.to_lowercase().contains(t.as_str()))
+                    .count() as f64;
+                let term_bonus = term_overlap * 5.0;
+                let score = graph_score + term_bonus;
+
+                let mut node_path = path.clone();
+                node_path.push(node.label.clone());
+
+                results.push(QueryResult {
+                    label: node.label.clone(),
+                    node_type: node.node_type.clone(),
+                    access_count: node.scoped_access_count(scope),
+                    score,
+                    path: node_path.clone(),
+                    node_id: *nid,
+                });
+
+                frontier.push((weight * edge.weight, *nid, node_path, depth + 1));
             }
         }
 
@@ -211,6 +287,10 @@ impl QueryEngine {
         });
         results.truncate(q.max_results);
 
+        // Every node the query surfaced counts as accessed, reinforced or not
+        let surfaced: Vec<NodeId> = results.iter().map(|r| r.node_id).collect();
+        colony.touch_nodes(&surfaced);
+
         // Phase 4: Reinforce traversed paths (the graph learns from queries)
         // True Hebbian: "neurons that fire together wire together"
         //
@@ -226,38 +306,40 @@ impl QueryEngine {
             for result in &results {
                 let mut seed_count = 0;
                 for seed_id in &seed_ids {
-                    if *seed_id == result.node_id {
-                        seed_count += 1;
-                    } else if graph_ref.get_edge(seed_id, &result.node_id).is_some() {
+                    if *seed_id == result.node_id
+                        || graph_ref.get_edge(seed_id, &result.node_id).is_some()
+                    {
                         seed_count += 1;
                     }
                 }
                 result_seed_connections.push((result.node_id, seed_count));
             }
 
-            let graph_mut = colony.substrate_mut().graph_mut();
-
-            // Reinforce based on seed connectivity (Hebbian correlation)
+            // Boost access counts proportional to seed connectivity, also
+            // under each of the query's tags the node carries, and
+            // strengthen all seed↔result edges
+            let mut boosts = Vec::new();
+            let mut edges = Vec::new();
             for (result_id, seed_count) in &result_seed_connections {
                 if *seed_count == 0 {
                     continue;
                 }
                 let multi_seed_bonus = *seed_count as f64;
-
-                // Boost access count proportional to seed connectivity
-                if let Some(node) = graph_mut.get_node_mut(result_id) {
-                    node.access_count += (*seed_count as u64) * 2;
-                }
-
-                // Strengthen all seed↔result edges
+                boosts.push((*result_id, (*seed_count as u64) * 2));
                 for seed_id in &seed_ids {
-                    if seed_id == result_id {
-                        continue;
+                    if seed_id != result_id {
+                        edges.push((*seed_id, *result_id, 0.05 * multi_seed_bonus));
                     }
-                    if let Some(edge) = graph_mut.get_edge_mut(seed_id, result_id) {
-                        let boost = 0.05 * multi_seed_bonus;
-                        edge.weight = (edge.weight + boost).min(1.0);
-                        edge.co_activations += 1;
+                }
+            }
+            let tags = scope.unwrap_or_default();
+            let reinforced = colony.reinforce_retrieval(&boosts, tags, &edges);
+            if let Some(entry) = log {
+                let graph = colony.substrate().graph();
+                let label = |id| graph.get_node(id).map(|n| n.label.clone());
+                for (from, to) in &reinforced {
+                    if let (Some(a), Some(b)) = (label(from), label(to)) {
+                        entry.reinforced_edges.push((a, b));
                     }
                 }
             }
@@ -267,31 +349,6 @@ impl QueryEngine {
     }
 }
 
-/// Simple tokenizer — lowercase, split on whitespace, filter stopwords and short words.
-fn tokenize(text: &str) -> Vec<String> {
-    let stopwords: std::collections::HashSet<&str> = [
-        "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
-        "do", "does", "did", "will", "would", "could", "should", "may", "might", "shall", "can",
-        "need", "dare", "ought", "used", "to", "of", "in", "for", "on", "with", "at", "by", "from",
-        "as", "into", "through", "during", "before", "after", "above", "below", "between", "out",
-        "off", "over", "under", "again", "further", "then", "once", "here", "there", "when",
-        "where", "why", "how", "all", "each", "every", "both", "few", "more", "most", "other",
-        "some", "such", "no", "nor", "not", "only", "own", "same", "so", "than", "too", "very",
-        "and", "but", "or", "if", "while", "what", "which", "who", "this", "that", "these",
-        "those", "it", "its",
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    text.to_lowercase()
-        .split_whitespace()
-        .filter(|w| w.len() >= 3 && !stopwords.contains(w))
-        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
-        .filter(|w| w.len() >= 3)
-        .collect()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,6 +378,77 @@ mod tests {
         );
     }
 
+    fn work_and_personal_colony() -> Colony {
+        let mut colony = Colony::new();
+        colony.ingest_document_tagged(
+            "Quarterly plan",
+            "The project deadline requires budget review meetings with the client.",
+            Position::new(0.0, 0.0),
+            &["work"],
+        );
+        colony.ingest_document_tagged(
+            "Weekend",
+            "The garden project needs compost, seeds and regular watering.",
+            Position::new(2.0, 0.0),
+            &["personal"],
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.spawn(Box::new(
+            Digester::new(Position::new(2.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(20);
+        colony
+    }
+
+    #[test]
+    fn tagged_query_stays_within_its_view() {
+        let mut colony = work_and_personal_colony();
+
+        let labels = |colony: &mut Colony, q: &Query| -> Vec<String> {
+            QueryEngine::query(colony, q)
+                .into_iter()
+                .map(|r| r.label)
+                .collect()
+        };
+
+        let q = Query::new("project")
+            .with_tags(&["work"])
+            .without_reinforcement();
+        let work = labels(&mut colony, &q);
+        assert!(work.iter().any(|l| l == "project"));
+        assert!(work.iter().any(|l| l == "budget"));
+        assert!(!work.iter().any(|l| l == "garden"));
+
+        let q = Query::new("project")
+            .with_tags(&["personal"])
+            .without_reinforcement();
+        let personal = labels(&mut colony, &q);
+        assert!(personal.iter().any(|l| l == "project"));
+        assert!(!personal.iter().any(|l| l == "budget"));
+    }
+
+    #[test]
+    fn tagged_query_reinforces_only_its_tag() {
+        let mut colony = work_and_personal_colony();
+        let tag_count = |colony: &Colony, tag: &str| {
+            let graph = colony.substrate().graph();
+            let id = graph.find_nodes_by_exact_label("project")[0];
+            graph.get_node(&id).unwrap().tags[tag]
+        };
+        let (work_before, personal_before) =
+            (tag_count(&colony, "work"), tag_count(&colony, "personal"));
+
+        let q = Query::new("project budget").with_tags(&["work"]);
+        let results = QueryEngine::query(&mut colony, &q);
+        let project = results.iter().find(|r| r.label == "project").unwrap();
+        assert_eq!(project.access_count, work_before);
+
+        assert!(tag_count(&colony, "work") > work_before);
+        assert_eq!(tag_count(&colony, "personal"), personal_before);
+    }
+
     #[test]
     fn query_reinforces_traversed_nodes() {
         let mut colony = Colony::new();
@@ -354,6 +482,132 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unqueried_concepts_are_forgotten_while_queried_ones_survive() {
+        use phago_runtime::colony::{ColonyConfig, ColonyEvent};
+
+        let mut colony = Colony::from_config(ColonyConfig {
+            node_staleness_ticks: 20,
+            ..Default::default()
+        });
+        colony.ingest_document(
+            "Biology",
+            "The cell membrane controls transport of molecules. Proteins serve as channels.",
+            Position::new(0.0, 0.0),
+        );
+        colony.ingest_document(
+            "Junk",
+            "lorem ipsum dolor sit amet consectetur",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(10),
+        ));
+        colony.run(15);
+        assert!(!colony
+            .substrate()
+            .graph()
+            .find_nodes_by_label("lorem")
+            .is_empty());
+
+        let q = Query::new("cell membrane");
+        let mut pruned = Vec::new();
+        for _ in 0..150 {
+            QueryEngine::query(&mut colony, &q);
+            for event in colony.tick() {
+                if let ColonyEvent::NodePruned { label, .. } = event {
+                    pruned.push(label);
+                }
+            }
+        }
+
+        let graph = colony.substrate().graph();
+        assert!(graph.find_nodes_by_label("lorem").is_empty());
+        assert!(pruned.iter().any(|l| l == "lorem"));
+        assert!(!graph.find_nodes_by_label("cell").is_empty());
+        assert!(!graph.find_nodes_by_label("membrane").is_empty());
+
+        // Retrieval still works over the pruned graph
+        assert!(!QueryEngine::query(&mut colony, &q).is_empty());
+        let hybrid =
+            crate::hybrid::hybrid_query(&colony, "lorem cell", &Default::default()).unwrap();
+        assert!(hybrid.iter().all(|r| r.label != "lorem"));
+    }
+
+    #[test]
+    fn updated_document_withdraws_removed_paragraph() {
+        use phago_runtime::colony::ColonyEvent;
+
+        let membrane = "The cell membrane controls transport of molecules. Proteins serve as \
+                        channels and receptors.";
+        let mitochondria = "Mitochondria generate adenosine triphosphate through oxidative \
+                            phosphorylation.";
+        let mut colony = Colony::new();
+        let id = colony.ingest_or_update_document(
+            "notes.md",
+            &format!("{membrane}\n\n{mitochondria}"),
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(15);
+
+        let q = Query::new("mitochondria phosphorylation").without_reinforcement();
+        assert!(!QueryEngine::query(&mut colony, &q).is_empty());
+        let access = |colony: &Colony, label: &str| {
+            let graph = colony.substrate().graph();
+            graph
+                .find_nodes_by_exact_label(label)
+                .first()
+                .and_then(|id| graph.get_node(id))
+                .map(|n| n.access_count)
+        };
+        let membrane_before = access(&colony, "membrane").unwrap();
+
+        // Unchanged content is not ingested again
+        let same = colony.ingest_or_update_document(
+            "notes.md",
+            &format!("{membrane}\n\n{mitochondria}"),
+            Position::new(0.0, 0.0),
+        );
+        assert_eq!(same, id);
+        assert_eq!(colony.substrate().get_document(&id).unwrap().version, 1);
+
+        let updated =
+            colony.ingest_or_update_document("notes.md", membrane, Position::new(0.0, 0.0));
+        assert_eq!(updated, id);
+        assert_eq!(colony.substrate().get_document(&id).unwrap().version, 2);
+        assert_eq!(colony.stats().documents_total, 1);
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        let update = colony
+            .run(15)
+            .into_iter()
+            .flatten()
+            .find_map(|event| match event {
+                ColonyEvent::DocumentUpdated {
+                    id,
+                    version,
+                    concepts_removed,
+                    ..
+                } => Some((id, version, concepts_removed)),
+                _ => None,
+            })
+            .expect("the new version should be digested");
+        assert_eq!((update.0, update.1), (id, 2));
+
+        // The removed paragraph's unique concepts lost their only support
+        for label in ["mitochondria", "phosphorylation"] {
+            assert!(update.2.iter().any(|l| l == label), "{label} not withdrawn");
+            assert_eq!(access(&colony, label), None, "{label} still in the graph");
+        }
+        assert!(QueryEngine::query(&mut colony, &q).is_empty());
+        // The paragraph that persists was reinforced
+        assert!(access(&colony, "membrane").unwrap() > membrane_before);
+    }
+
     #[test]
     fn empty_query_returns_empty() {
         let mut colony = Colony::new();
@@ -364,10 +618,86 @@ mod tests {
 
     #[test]
     fn tokenizer_filters_stopwords() {
-        let tokens = tokenize("the cell is a membrane");
+        let tokens = Colony::new().tokenizer().tokens("the cell is a membrane");
         assert!(tokens.contains(&"cell".to_string()));
         assert!(tokens.contains(&"membrane".to_string()));
         assert!(!tokens.contains(&"the".to_string()));
         assert!(!tokens.contains(&"is".to_string()));
     }
+
+    #[test]
+    fn query_log_records_each_query_once() {
+        use phago_runtime::colony::ColonyConfig;
+        use phago_runtime::query_log::QueryLogConfig;
+
+        let mut colony = Colony::from_config(ColonyConfig {
+            query_log: QueryLogConfig::enabled(),
+            ..Default::default()
+        });
+        colony.ingest_document(
+            "Biology",
+            "The cell membrane controls transport of molecules. Proteins serve as channels.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(15);
+
+        let results = QueryEngine::query(&mut colony, &Query::new("cell membrane"));
+        QueryEngine::query(
+            &mut colony,
+            &Query::new("quasar").with_source(QuerySource::Cli),
+        );
+
+        let entries = colony.query_log().unwrap().entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].terms, vec!["cell", "membrane"]);
+        assert_eq!(entries[0].results.len(), results.len());
+        assert!(!entries[0].traversed_edges.is_empty());
+        assert!(!entries[0].reinforced_edges.is_empty());
+        assert_eq!(entries[1].source, QuerySource::Cli);
+        assert!(entries[1].results.is_empty());
+    }
+
+    #[test]
+    fn queries_for_either_form_find_the_compacted_node() {
+        use phago_runtime::compaction::CompactionStrategy;
+
+        let mut colony = Colony::new();
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(100),
+        ));
+        colony.ingest_document(
+            "Singular",
+            "The membrane protects the cell. A membrane protein binds lipid.",
+            Position::new(0.0, 0.0),
+        );
+        colony.run(10);
+        colony.ingest_document(
+            "Plural",
+            "Membranes protect cells. Membranes hold transport channels.",
+            Position::new(0.0, 0.0),
+        );
+        colony.run(10);
+        colony.compact_labels(&CompactionStrategy::Stemming);
+
+        let graph = colony.substrate().graph();
+        let merged = graph.find_nodes_by_exact_label("membrane");
+        assert_eq!(merged.len(), 1);
+        assert_eq!(graph.find_nodes_by_exact_label("membranes"), merged);
+
+        for term in ["membrane", "membranes"] {
+            let q = Query::new(term).without_reinforcement();
+            let results = QueryEngine::query(&mut colony, &q);
+            assert!(
+                results.iter().any(|r| r.node_id == merged[0]),
+                "{term} did not find the merged node"
+            );
+        }
+        for term in ["membrane", "membranes"] {
+            let hybrid = crate::hybrid::hybrid_query(&colony, term, &Default::default()).unwrap();
+            assert!(hybrid.iter().any(|r| r.label == "membrane"), "{term}");
+        }
+    }
 }
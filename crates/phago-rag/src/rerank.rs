@@ -0,0 +1,417 @@
+//! Late scoring of hybrid query results by an external reranker.
+//!
+//! A [`Reranker`] — typically a cross-encoder served over HTTP — rescores
+//! the best `top_n` candidates of a [hybrid query](crate::hybrid) after
+//! TF-IDF and graph scoring, before they are cut down to `max_results`.
+//! Each rescored candidate's final score becomes
+//!
+//! ```text
+//! final = (1 - blend_weight) * hybrid_final + blend_weight * rerank_score
+//! ```
+//!
+//! and the reranker's score is kept in [`HybridResult::rerank_score`].
+//! Candidates past `top_n`, or left out by the reranker, keep their hybrid
+//! score. If the reranker fails, the hybrid order stands: retrieval never
+//! fails because a reranker is down.
+//!
+//! With the `http-rerank` feature, [`HttpReranker`] talks to a rerank
+//! endpoint such as text-embeddings-inference's `/rerank`.
+
+use crate::cache::Ranked;
+use crate::hybrid::HybridResult;
+use std::sync::Arc;
+
+/// Rescores hybrid query candidates against the query.
+pub trait Reranker: Send + Sync {
+    /// Score `candidates` against `query`, as `(index into candidates,
+    /// score)` pairs with scores in [0, 1]. Candidates left out keep their
+    /// hybrid score.
+    fn rerank(
+        &self,
+        query: &str,
+        candidates: &[HybridResult],
+    ) -> Result<Vec<(usize, f64)>, RerankError>;
+}
+
+/// Why a reranker could not score the candidates.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RerankError {
+    /// The reranker did not answer in time.
+    Timeout,
+    /// The reranker could not be reached or reported an error.
+    Unavailable(String),
+    /// The reranker answered with something other than scores.
+    InvalidResponse(String),
+}
+
+impl std::fmt::Display for RerankError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RerankError::Timeout => write!(f, "reranker timed out"),
+            RerankError::Unavailable(e) => write!(f, "reranker unavailable: {}", e),
+            RerankError::InvalidResponse(e) => write!(f, "invalid reranker response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RerankError {}
+
+/// A [`Reranker`] applied to the best candidates of a query; see
+/// [`HybridConfig::with_reranker`](crate::hybrid::HybridConfig::with_reranker).
+#[derive(Clone)]
+pub struct RerankStage {
+    pub reranker: Arc<dyn Reranker>,
+    /// How many of the best candidates are rescored.
+    pub top_n: usize,
+    /// Weight of the reranker's score against the hybrid final score, in
+    /// [0, 1]: 0.0 keeps the hybrid score, 1.0 replaces it.
+    pub blend_weight: f64,
+}
+
+impl std::fmt::Debug for RerankStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RerankStage")
+            .field("top_n", &self.top_n)
+            .field("blend_weight", &self.blend_weight)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Rescore the first `top_n` of `ranked`, best first, and sort it again.
+/// Leaves it as it is if the reranker fails.
+pub(crate) fn rerank(stage: &RerankStage, query: &str, ranked: &mut Ranked) {
+    let n = stage.top_n.min(ranked.len());
+    if n == 0 {
+        return;
+    }
+    let candidates: Vec<HybridResult> = ranked[..n].iter().map(|(r, _)| r.clone()).collect();
+    let scores = match stage.reranker.rerank(query, &candidates) {
+        Ok(scores) => scores,
+        Err(e) => {
+            eprintln!("Warning: {}; keeping the hybrid ranking", e);
+            return;
+        }
+    };
+
+    let w = stage.blend_weight;
+    for (idx, score) in scores {
+        let Some((result, _)) = ranked.get_mut(idx).filter(|_| idx < n) else {
+            continue;
+        };
+        if !score.is_finite() || result.rerank_score.is_some() {
+            continue;
+        }
+        result.rerank_score = Some(score);
+        result.final_score = (1.0 - w) * result.final_score + w * score;
+    }
+    ranked.sort_by(|(a, _), (b, _)| {
+        b.final_score
+            .partial_cmp(&a.final_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+#[cfg(feature = "http-rerank")]
+pub use http::HttpReranker;
+
+#[cfg(feature = "http-rerank")]
+mod http {
+    use super::{RerankError, Reranker};
+    use crate::hybrid::HybridResult;
+    use serde::{Deserialize, Serialize};
+    use std::time::Duration;
+
+    /// Reranks over HTTP: POSTs `{"query", "texts"}` to an endpoint that
+    /// answers `[{"index", "score"}]`, as text-embeddings-inference's
+    /// `/rerank` does. Each text is the candidate's label followed by its
+    /// passages, if the query attached any.
+    ///
+    /// ```rust,no_run
+    /// use phago_rag::hybrid::HybridConfig;
+    /// use phago_rag::rerank::HttpReranker;
+    /// use std::time::Duration;
+    ///
+    /// let reranker = HttpReranker::new("http://localhost:8080/rerank")?
+    ///     .with_timeout(Duration::from_millis(500))?;
+    /// let config = HybridConfig::default().with_reranker(Box::new(reranker), 50, 0.5);
+    /// # Ok::<(), phago_rag::rerank::RerankError>(())
+    /// ```
+    pub struct HttpReranker {
+        endpoint: String,
+        client: reqwest::blocking::Client,
+    }
+
+    #[derive(Serialize)]
+    struct RerankRequest<'a> {
+        query: &'a str,
+        texts: Vec<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct RerankScore {
+        index: usize,
+        score: f64,
+    }
+
+    impl HttpReranker {
+        /// How long a rerank may take unless set otherwise.
+        pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+        /// Rerank through `endpoint`, giving up after
+        /// [`DEFAULT_TIMEOUT`](Self::DEFAULT_TIMEOUT).
+        pub fn new(endpoint: impl Into<String>) -> Result<Self, RerankError> {
+            Ok(Self {
+                endpoint: endpoint.into(),
+                client: client(Self::DEFAULT_TIMEOUT)?,
+            })
+        }
+
+        /// Give up on a rerank after `timeout`, keeping the hybrid ranking.
+        pub fn with_timeout(mut self, timeout: Duration) -> Result<Self, RerankError> {
+            self.client = client(timeout)?;
+            Ok(self)
+        }
+
+        pub fn endpoint(&self) -> &str {
+            &self.endpoint
+        }
+    }
+
+    fn client(timeout: Duration) -> Result<reqwest::blocking::Client, RerankError> {
+        reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| RerankError::Unavailable(e.to_string()))
+    }
+
+    impl Reranker for HttpReranker {
+        fn rerank(
+            &self,
+            query: &str,
+            candidates: &[HybridResult],
+        ) -> Result<Vec<(usize, f64)>, RerankError> {
+            let texts = candidates
+                .iter()
+                .map(|c| {
+                    std::iter::once(c.label.as_str())
+                        .chain(c.passages.iter().map(|p| p.snippet.as_str()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .collect();
+            let response = self
+                .client
+                .post(&self.endpoint)
+                .json(&RerankRequest { query, texts })
+                .send()
+                .map_err(|e| {
+                    if e.is_timeout() {
+                        RerankError::Timeout
+                    } else {
+                        RerankError::Unavailable(e.to_string())
+                    }
+                })?;
+            if !response.status().is_success() {
+                return Err(RerankError::Unavailable(format!(
+                    "HTTP {}",
+                    response.status()
+                )));
+            }
+            let scores: Vec<RerankScore> = response.json().map_err(|e| {
+                if e.is_timeout() {
+                    RerankError::Timeout
+                } else {
+                    RerankError::InvalidResponse(e.to_string())
+                }
+            })?;
+            Ok(scores.into_iter().map(|s| (s.index, s.score)).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hybrid::{hybrid_query, HybridConfig, HybridConfigError};
+    use phago_agents::digester::Digester;
+    use phago_core::types::Position;
+    use phago_runtime::colony::Colony;
+
+    /// Scores candidates in reverse: the last gets 1.0, the first 0.0.
+    struct Reverse;
+
+    impl Reranker for Reverse {
+        fn rerank(
+            &self,
+            _query: &str,
+            candidates: &[HybridResult],
+        ) -> Result<Vec<(usize, f64)>, RerankError> {
+            let last = candidates.len().saturating_sub(1).max(1) as f64;
+            Ok((0..candidates.len())
+                .map(|i| (i, i as f64 / last))
+                .collect())
+        }
+    }
+
+    struct Down;
+
+    impl Reranker for Down {
+        fn rerank(&self, _: &str, _: &[HybridResult]) -> Result<Vec<(usize, f64)>, RerankError> {
+            Err(RerankError::Timeout)
+        }
+    }
+
+    fn colony() -> Colony {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Bio",
+            "The cell membrane controls transport. Cell signaling through membrane receptors.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(15);
+        colony
+    }
+
+    fn config() -> HybridConfig {
+        HybridConfig {
+            max_results: 3,
+            ..HybridConfig::default()
+        }
+    }
+
+    #[test]
+    fn reranker_scores_blend_into_the_final_score() {
+        let colony = colony();
+        let plain = hybrid_query(&colony, "cell membrane", &config()).unwrap();
+        assert!(plain.len() >= 2);
+        assert!(plain.iter().all(|r| r.rerank_score.is_none()));
+
+        // Full weight: the reranker's order, reversed
+        let reranked = hybrid_query(
+            &colony,
+            "cell membrane",
+            &config().with_reranker(Box::new(Reverse), 3, 1.0),
+        )
+        .unwrap();
+        let labels = |results: &[HybridResult]| -> Vec<String> {
+            results.iter().map(|r| r.label.clone()).collect()
+        };
+        let mut reversed = labels(&plain);
+        reversed.reverse();
+        assert_eq!(labels(&reranked), reversed);
+        assert_eq!(reranked[0].rerank_score, Some(1.0));
+        assert_eq!(reranked[0].final_score, 1.0);
+
+        // Half weight: the average of both scores
+        let blended = hybrid_query(
+            &colony,
+            "cell membrane",
+            &config().with_reranker(Box::new(Reverse), 3, 0.5),
+        )
+        .unwrap();
+        for result in &blended {
+            let original = plain.iter().find(|r| r.label == result.label).unwrap();
+            let rerank_score = result.rerank_score.unwrap();
+            let expected = 0.5 * original.final_score + 0.5 * rerank_score;
+            assert!((result.final_score - expected).abs() < 1e-12);
+            assert_eq!(result.tfidf_score, original.tfidf_score);
+        }
+
+        // Zero weight keeps the hybrid order
+        let unchanged = hybrid_query(
+            &colony,
+            "cell membrane",
+            &config().with_reranker(Box::new(Reverse), 3, 0.0),
+        )
+        .unwrap();
+        assert_eq!(labels(&unchanged), labels(&plain));
+    }
+
+    #[test]
+    fn reranker_sees_candidates_beyond_max_results() {
+        let colony = colony();
+        let config = HybridConfig {
+            max_results: 1,
+            ..HybridConfig::default()
+        };
+        let plain = hybrid_query(
+            &colony,
+            "cell membrane",
+            &HybridConfig {
+                max_results: 3,
+                ..config.clone()
+            },
+        )
+        .unwrap();
+        let reranked = hybrid_query(
+            &colony,
+            "cell membrane",
+            &config.with_reranker(Box::new(Reverse), 3, 1.0),
+        )
+        .unwrap();
+        assert_eq!(reranked.len(), 1);
+        assert_eq!(reranked[0].label, plain[plain.len() - 1].label);
+    }
+
+    #[test]
+    fn failing_reranker_keeps_the_hybrid_ranking() {
+        let colony = colony();
+        let plain = hybrid_query(&colony, "cell membrane", &config()).unwrap();
+        let fallback = hybrid_query(
+            &colony,
+            "cell membrane",
+            &config().with_reranker(Box::new(Down), 3, 1.0),
+        )
+        .unwrap();
+        let scores = |results: &[HybridResult]| -> Vec<(String, f64, Option<f64>)> {
+            results
+                .iter()
+                .map(|r| (r.label.clone(), r.final_score, r.rerank_score))
+                .collect()
+        };
+        assert_eq!(scores(&fallback), scores(&plain));
+    }
+
+    #[test]
+    fn blend_weight_is_validated() {
+        let err = hybrid_query(
+            &colony(),
+            "cell",
+            &config().with_reranker(Box::new(Reverse), 3, 1.5),
+        )
+        .unwrap_err();
+        assert_eq!(err, HybridConfigError::BlendWeightOutOfRange(1.5));
+    }
+
+    #[cfg(feature = "http-rerank")]
+    #[test]
+    fn http_reranker_times_out_to_the_hybrid_ranking() {
+        use std::time::{Duration, Instant};
+
+        // Accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/rerank", listener.local_addr().unwrap());
+        let reranker = HttpReranker::new(endpoint)
+            .unwrap()
+            .with_timeout(Duration::from_millis(200))
+            .unwrap();
+        let started = Instant::now();
+        assert_eq!(reranker.rerank("cell", &[]), Err(RerankError::Timeout),);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let colony = colony();
+        let plain = hybrid_query(&colony, "cell membrane", &config()).unwrap();
+        let fallback = hybrid_query(
+            &colony,
+            "cell membrane",
+            &config().with_reranker(Box::new(reranker), 3, 1.0),
+        )
+        .unwrap();
+        assert_eq!(fallback.len(), plain.len());
+        assert!(fallback.iter().all(|r| r.rerank_score.is_none()));
+        drop(listener);
+    }
+}
@@ -0,0 +1,207 @@
+//! Static colonies — queries over a published bundle, without a colony.
+//!
+//! [`publish_bundle`] writes a colony's graph, term index and, optionally,
+//! embeddings as static files. A [`StaticColony`] opens such a bundle and
+//! answers the read-only part of the query surface from it: hybrid
+//! queries, ranked exactly as the colony ranked them when it was
+//! published, the structural [explore](StaticColony::explore) requests
+//! (paths, centrality, bridges, communities, stats, anomalies, regions),
+//! and sampled subgraphs through its [`view`](StaticColony::view). It holds
+//! no agents or other trait objects, and nothing ticks.
+//!
+//! Opening reads only the manifest; the graph and index are read on the
+//! first query, embeddings on the first lookup. Bundles carry no
+//! documents, so results have no passages, and queries are neither logged
+//! nor reinforce anything.
+//!
+//! [`publish_bundle`]: phago_runtime::publish::publish_bundle
+
+use crate::hybrid::{
+    paginate, rank_fully, HybridConfig, HybridConfigError, HybridPage, HybridResult, RankSource,
+};
+use crate::mcp::{phago_explore_view, ExploreRequest, ExploreResponse};
+use phago_core::tokenize::Tokenizer;
+use phago_core::topology::TopologyGraph;
+use phago_core::types::NodeId;
+use phago_runtime::inverted_index::InvertedIndex;
+use phago_runtime::publish::{PublishError, PublishManifest, PublishedBundle};
+use phago_runtime::read_view::ColonyView;
+use phago_runtime::synonyms::SynonymConfig;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Why a query against a [`StaticColony`] failed.
+#[derive(Debug)]
+pub enum StaticQueryError {
+    /// The query's configuration was rejected.
+    Config(HybridConfigError),
+    /// The bundle could not be read.
+    Bundle(PublishError),
+}
+
+impl std::fmt::Display for StaticQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StaticQueryError::Config(e) => write!(f, "{e}"),
+            StaticQueryError::Bundle(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for StaticQueryError {}
+
+impl From<HybridConfigError> for StaticQueryError {
+    fn from(e: HybridConfigError) -> Self {
+        StaticQueryError::Config(e)
+    }
+}
+
+impl From<PublishError> for StaticQueryError {
+    fn from(e: PublishError) -> Self {
+        StaticQueryError::Bundle(e)
+    }
+}
+
+/// The parts of a bundle queries read.
+struct Loaded {
+    view: ColonyView,
+    index: InvertedIndex,
+    tokenizer: Tokenizer,
+    synonyms: SynonymConfig,
+}
+
+impl RankSource for Loaded {
+    fn graph(&self) -> &dyn TopologyGraph {
+        self.view.graph()
+    }
+
+    fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+
+    fn anomaly_score(&self, node: &NodeId) -> Option<f64> {
+        self.view.anomaly_score(node)
+    }
+
+    fn term_index(&self) -> Option<&InvertedIndex> {
+        Some(&self.index)
+    }
+
+    fn expand_query(&self, terms: &[String]) -> Vec<(String, f64)> {
+        self.synonyms
+            .map
+            .expand(terms, self.synonyms.expansion_weight)
+    }
+}
+
+/// A published bundle, queried read-only.
+pub struct StaticColony {
+    bundle: PublishedBundle,
+    loaded: OnceLock<Loaded>,
+    embeddings: OnceLock<Option<HashMap<NodeId, Vec<f32>>>>,
+}
+
+impl StaticColony {
+    /// Open the bundle in `dir`, reading its manifest.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, PublishError> {
+        Ok(Self {
+            bundle: PublishedBundle::open(dir)?,
+            loaded: OnceLock::new(),
+            embeddings: OnceLock::new(),
+        })
+    }
+
+    pub fn manifest(&self) -> &PublishManifest {
+        self.bundle.manifest()
+    }
+
+    /// Read the graph and index now instead of on the first query, e.g. to
+    /// report a damaged bundle before serving it.
+    pub fn load(&self) -> Result<(), PublishError> {
+        self.loaded().map(|_| ())
+    }
+
+    fn loaded(&self) -> Result<&Loaded, PublishError> {
+        if let Some(loaded) = self.loaded.get() {
+            return Ok(loaded);
+        }
+        let view = self.bundle.read_graph()?;
+        let index = self.bundle.read_index(view.graph())?;
+        Ok(self.loaded.get_or_init(|| Loaded {
+            view,
+            index,
+            tokenizer: self.bundle.tokenizer(),
+            synonyms: self.bundle.manifest().synonyms.clone(),
+        }))
+    }
+
+    /// The published graph, as a view of the colony when it was published.
+    pub fn view(&self) -> Result<&ColonyView, PublishError> {
+        self.loaded().map(|loaded| &loaded.view)
+    }
+
+    /// [`hybrid_query`](crate::hybrid::hybrid_query) against the bundle.
+    pub fn hybrid_query(
+        &self,
+        query_text: &str,
+        config: &HybridConfig,
+    ) -> Result<Vec<HybridResult>, StaticQueryError> {
+        config.validate()?;
+        let loaded = self.loaded()?;
+        let query_terms = loaded.tokenizer.tokens(query_text);
+        let ranked = rank_fully(loaded, query_text, &query_terms, config);
+        Ok(ranked.into_iter().map(|(r, _)| r).collect())
+    }
+
+    /// [`hybrid_query_page`](crate::hybrid::hybrid_query_page) against the
+    /// bundle. Its graph never changes, so cursors never expire.
+    pub fn hybrid_query_page(
+        &self,
+        query_text: &str,
+        config: &HybridConfig,
+        cursor: Option<&str>,
+    ) -> Result<HybridPage, StaticQueryError> {
+        config.validate()?;
+        let loaded = self.loaded()?;
+        let query_terms = loaded.tokenizer.tokens(query_text);
+        let paged = paginate(
+            &query_terms,
+            config,
+            self.manifest().revision,
+            cursor,
+            |ranking| rank_fully(loaded, query_text, &query_terms, ranking),
+        )?;
+        Ok(paged.into_page())
+    }
+
+    /// Explore the bundle's graph as [`phago_explore_view`] explores a
+    /// view. `None` for the requests that need the colony.
+    pub fn explore(&self, req: &ExploreRequest) -> Result<Option<ExploreResponse>, PublishError> {
+        Ok(phago_explore_view(self.view()?, req))
+    }
+
+    /// The published embedding of `node`, if the bundle has one.
+    pub fn embedding(&self, node: &NodeId) -> Result<Option<&[f32]>, PublishError> {
+        let embeddings = match self.embeddings.get() {
+            Some(embeddings) => embeddings,
+            None => {
+                let read = self.bundle.read_embeddings()?;
+                self.embeddings.get_or_init(|| read)
+            }
+        };
+        Ok(embeddings
+            .as_ref()
+            .and_then(|e| e.get(node))
+            .map(Vec::as_slice))
+    }
+}
+
+impl std::fmt::Debug for StaticColony {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticColony")
+            .field("dir", &self.bundle.dir())
+            .field("loaded", &self.loaded.get().is_some())
+            .finish_non_exhaustive()
+    }
+}
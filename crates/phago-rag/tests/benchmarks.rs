@@ -0,0 +1,111 @@
+//! Retrieval benchmarks.
+//!
+//! Run with: cargo test --release -p phago-rag --test benchmarks -- --nocapture
+//!
+//! These benchmarks measure:
+//! 1. Query latency as documents are added, with and without the term index
+
+use phago_core::substrate::Substrate;
+use phago_core::types::{NodeData, NodeId, NodeType, Position};
+use phago_rag::hybrid::{hybrid_query, HybridConfig};
+use phago_runtime::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Concepts each synthetic document contributes.
+const CONCEPTS_PER_DOC: usize = 5;
+
+fn concept(label: String) -> NodeData {
+    NodeData {
+        id: NodeId::new(),
+        label,
+        node_type: NodeType::Concept,
+        position: Position::new(0.0, 0.0),
+        access_count: 1,
+        created_tick: 0,
+        last_accessed_tick: 0,
+        tags: Default::default(),
+    }
+}
+
+/// Grow the colony to `docs` synthetic documents. Only the first mentions
+/// the queried concept; the rest contribute concepts of their own.
+fn grow_to(colony: &mut Colony, from: usize, docs: usize) {
+    for doc in from..docs {
+        for i in 0..CONCEPTS_PER_DOC {
+            let label = match (doc, i) {
+                (0, 0) => "membrane".to_string(),
+                (0, 1) => "membrane transport".to_string(),
+                _ => format!("topic{doc} concept{i}"),
+            };
+            colony.substrate_mut().add_node(concept(label));
+        }
+    }
+    colony.sync_term_index();
+}
+
+/// Median latency of a one-term hybrid query.
+fn median_latency(colony: &Colony, runs: usize) -> Duration {
+    let config = HybridConfig::default();
+    let mut samples: Vec<Duration> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            let results = hybrid_query(colony, "membrane", &config).unwrap();
+            let elapsed = start.elapsed();
+            assert_eq!(results.len(), 2);
+            elapsed
+        })
+        .collect();
+    samples.sort();
+    samples[runs / 2]
+}
+
+// ============================================================================
+// BENCHMARK 1: Query Latency Growth
+// ============================================================================
+
+/// One-term hybrid queries while the graph grows a hundredfold.
+///
+/// Scanning every label makes latency grow with the graph: in release
+/// builds about 0.2 ms at 100 documents and 47 ms at 10,000. The term index
+/// only touches the postings of the query term, which stay the same size,
+/// so latency stays around 1 µs throughout.
+#[test]
+fn bench_query_latency_growth() {
+    println!("\n=== BENCHMARK: Query Latency Growth (one-term hybrid query) ===\n");
+
+    let mut colony = Colony::new();
+    let tokenizer = colony.tokenizer().clone();
+    println!(
+        "{:<8} {:>8} {:>14} {:>14}",
+        "Docs", "Nodes", "Indexed (µs)", "Scan (µs)"
+    );
+    println!("{:-<48}", "");
+
+    let mut indexed = Vec::new();
+    let mut docs = 0;
+    for target in [100, 1_000, 10_000] {
+        grow_to(&mut colony, docs, target);
+        docs = target;
+
+        assert!(colony.substrate().term_index().is_some());
+        let with_index = median_latency(&colony, 21);
+        colony.substrate_mut().disable_term_index();
+        let scan = median_latency(&colony, 5);
+        colony.substrate_mut().enable_term_index(tokenizer.clone());
+
+        println!(
+            "{:<8} {:>8} {:>14} {:>14}",
+            docs,
+            colony.stats().graph_nodes,
+            with_index.as_micros(),
+            scan.as_micros()
+        );
+        indexed.push(with_index);
+    }
+    println!();
+
+    // A hundred times the documents must cost well under a hundred times
+    // the latency
+    let growth = indexed[2].as_secs_f64() / indexed[0].as_secs_f64().max(1e-9);
+    assert!(growth < 10.0, "indexed latency grew {growth:.1}x");
+}
@@ -0,0 +1,102 @@
+//! A recorded run replays to the same colony: after any recorded tick the
+//! replay has the original's stats and answers queries the same way, with
+//! agents that were never seeded.
+
+use phago_agents::digester::Digester;
+use phago_agents::sentinel::Sentinel;
+use phago_agents::synthesizer::Synthesizer;
+use phago_core::types::Position;
+use phago_rag::hybrid::{hybrid_query, HybridConfig};
+use phago_rag::query::{Query, QueryEngine};
+use phago_runtime::prelude::*;
+use phago_runtime::replay::Replay;
+
+const DOCUMENTS: &[&str] = &[
+    "The mitochondria produce energy for the cell through oxidative phosphorylation.",
+    "Ribosomes translate messenger RNA into protein chains inside the cell.",
+    "The cell membrane controls transport of ions and nutrients into the cell.",
+    "Protein folding in the endoplasmic reticulum depends on chaperone proteins.",
+    "Energy from ATP drives membrane transport and protein synthesis.",
+    "Oxidative stress damages mitochondria and membrane lipids over time.",
+];
+
+/// Ingested halfway through, together with a query touching what it hit.
+const LATE_DOCUMENT: &str =
+    "Chaperone proteins protect mitochondria from oxidative stress and misfolding.";
+
+const CHECKPOINTS: [u64; 4] = [20, 50, 75, 100];
+
+/// What has to match at a checkpoint.
+fn observe(colony: &Colony) -> (ColonyStats, Vec<(String, f64)>) {
+    let results = hybrid_query(colony, "mitochondria energy", &HybridConfig::default())
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.label, r.final_score))
+        .collect();
+    (colony.stats(), results)
+}
+
+#[test]
+fn replayed_run_matches_the_original_at_every_checkpoint() {
+    let mut colony = Colony::from_config(ColonyConfig {
+        record_replay: true,
+        ..Default::default()
+    });
+    for (i, content) in DOCUMENTS.iter().enumerate() {
+        let position = Position::new(i as f64 * 3.0, 0.0);
+        colony.ingest_document(&format!("doc-{i}"), content, position);
+        colony.spawn(Box::new(Digester::new(position).with_max_idle(40)));
+    }
+    colony.spawn(Box::new(Sentinel::new(Position::new(5.0, 5.0))));
+    colony.spawn(Box::new(Synthesizer::new(Position::new(8.0, 2.0))));
+
+    let mut observed = Vec::new();
+    for tick in 1..=100 {
+        colony.tick();
+        // A replay stops right after the tick, before what came next
+        if CHECKPOINTS.contains(&tick) {
+            observed.push((tick, observe(&colony)));
+        }
+        if tick == 50 {
+            colony.ingest_document("late", LATE_DOCUMENT, Position::new(4.0, 4.0));
+            colony.spawn(Box::new(Digester::new(Position::new(4.0, 4.0))));
+            QueryEngine::query(&mut colony, &Query::new("chaperone proteins"));
+        }
+    }
+    assert!(colony.stats().graph_edges > 0);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("run.replay.jsonl");
+    colony.replay().unwrap().save(&path).unwrap();
+    let replay = Replay::load(&path).unwrap();
+    assert_eq!(replay.last_tick(), 100);
+
+    for (tick, (stats, results)) in observed {
+        let replayed = replay.run_until(tick);
+        let (replayed_stats, replayed_results) = observe(&replayed);
+        assert_eq!(replayed_stats.tick, tick);
+        assert_eq!(
+            serde_json::to_value(&replayed_stats).unwrap(),
+            serde_json::to_value(&stats).unwrap(),
+            "stats differ at tick {tick}"
+        );
+        assert!(!results.is_empty());
+        assert_eq!(replayed_results, results, "query differs at tick {tick}");
+    }
+
+    // The whole graph, IDs included
+    let replayed = replay.run_until(100);
+    let graph = colony.substrate().graph();
+    let replayed_graph = replayed.substrate().graph();
+    let mut nodes = graph.all_nodes();
+    let mut replayed_nodes = replayed_graph.all_nodes();
+    nodes.sort();
+    replayed_nodes.sort();
+    assert_eq!(nodes, replayed_nodes);
+    for id in &nodes {
+        assert_eq!(
+            replayed_graph.get_node(id).map(|n| n.access_count),
+            graph.get_node(id).map(|n| n.access_count)
+        );
+    }
+}
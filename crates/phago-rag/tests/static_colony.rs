@@ -0,0 +1,133 @@
+//! A published bundle answers like the colony it was published from: a
+//! StaticColony loaded from the embedded-corpus colony's bundle returns
+//! exactly the colony's hybrid results, pages and structural explore
+//! responses, without a colony or agents.
+
+use phago_agents::digester::Digester;
+use phago_agents::sentinel::Sentinel;
+use phago_rag::graph_scorer::DegreeNormalizedScorer;
+use phago_rag::hybrid::{hybrid_query, hybrid_query_page, HybridConfig, HybridResult};
+use phago_rag::mcp::{phago_explore_view, CentralityMethod, ExploreRequest};
+use phago_rag::static_colony::StaticColony;
+use phago_runtime::corpus::Corpus;
+use phago_runtime::export::{publish_bundle, BundleConfig};
+use phago_runtime::graph_algorithms::PathCost;
+use phago_runtime::prelude::*;
+use std::collections::BTreeSet;
+
+fn embedded_colony() -> Colony {
+    let corpus = Corpus::from_embedded().limit(24);
+    let mut colony = Colony::new();
+    corpus.ingest_into(&mut colony);
+    for doc in &corpus.documents {
+        colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(80)));
+    }
+    colony.spawn(Box::new(Sentinel::new(Position::new(0.0, 0.0))));
+    colony.run(40);
+    colony
+}
+
+/// Words of labels, alone and three at a time.
+fn queries(colony: &Colony) -> Vec<String> {
+    let graph = colony.substrate().graph();
+    let words: BTreeSet<String> = graph
+        .all_nodes()
+        .iter()
+        .filter_map(|id| graph.get_node(id))
+        .flat_map(|n| colony.tokenizer().tokens(&n.label))
+        .collect();
+    let words: Vec<String> = words.into_iter().step_by(7).collect();
+    let mut queries = words.clone();
+    queries.extend(words.chunks(3).map(|chunk| chunk.join(" ")));
+    queries
+}
+
+fn exact(results: &[HybridResult]) -> Vec<(String, f64, f64, f64)> {
+    results
+        .iter()
+        .map(|r| (r.label.clone(), r.tfidf_score, r.graph_score, r.final_score))
+        .collect()
+}
+
+#[test]
+fn published_bundle_answers_exactly_like_the_colony() {
+    let colony = embedded_colony();
+    let dir = tempfile::tempdir().unwrap();
+    let manifest =
+        publish_bundle(&colony, BundleConfig::new(dir.path()).with_embeddings(true)).unwrap();
+    assert_eq!(manifest.stats, colony.stats());
+
+    let bundle = StaticColony::open(dir.path()).unwrap();
+    let configs = [
+        HybridConfig::default(),
+        HybridConfig::builder()
+            .alpha(0.2)
+            .max_results(25)
+            .build()
+            .unwrap(),
+        HybridConfig::builder()
+            .graph_scorer(DegreeNormalizedScorer)
+            .min_anomaly_score(0.1)
+            .build()
+            .unwrap(),
+    ];
+    let queries = queries(&colony);
+    assert!(queries.len() > 10);
+    let mut matched = 0;
+    for config in &configs {
+        for query in &queries {
+            let live = hybrid_query(&colony, query, config).unwrap();
+            let published = bundle.hybrid_query(query, config).unwrap();
+            assert_eq!(exact(&published), exact(&live), "{query}");
+            matched += live.len();
+        }
+    }
+    assert!(matched > 0);
+
+    // Pages follow the same cursors
+    let config = HybridConfig::builder().max_results(3).build().unwrap();
+    let query = &queries[queries.len() - 1];
+    let (mut live_cursor, mut published_cursor) = (None, None);
+    loop {
+        let live = hybrid_query_page(&colony, query, &config, live_cursor.as_deref()).unwrap();
+        let published = bundle
+            .hybrid_query_page(query, &config, published_cursor.as_deref())
+            .unwrap();
+        assert_eq!(exact(&published.results), exact(&live.results));
+        assert_eq!(published.next_cursor, live.next_cursor);
+        if live.next_cursor.is_none() {
+            break;
+        }
+        (live_cursor, published_cursor) = (live.next_cursor, published.next_cursor);
+    }
+
+    // Structural explore requests match a read view of the colony
+    let top = hybrid_query(&colony, &queries[0], &HybridConfig::default()).unwrap();
+    let view = colony.read_view();
+    let requests = [
+        ExploreRequest::ShortestPath {
+            from: top[0].label.clone(),
+            to: top[top.len() - 1].label.clone(),
+            cost: PathCost::default(),
+            k: 2,
+        },
+        ExploreRequest::Centrality {
+            top_k: 10,
+            method: CentralityMethod::PageRank,
+        },
+        ExploreRequest::Stats { sample: None },
+    ];
+    for req in &requests {
+        let published = bundle.explore(req).unwrap().unwrap();
+        let live = phago_explore_view(&view, req).unwrap();
+        assert_eq!(
+            serde_json::to_value(published).unwrap(),
+            serde_json::to_value(live).unwrap()
+        );
+    }
+    let node = ExploreRequest::Node {
+        label: top[0].label.clone(),
+        top_k: 5,
+    };
+    assert!(bundle.explore(&node).unwrap().is_none());
+}
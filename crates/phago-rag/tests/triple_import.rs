@@ -0,0 +1,155 @@
+//! Importing curated triples bootstraps a colony: a 1k-triple CSV creates
+//! its concepts and edges once however often it is imported, queries find
+//! the imported concepts, and digested documents wire further onto them.
+
+use phago_agents::digester::Digester;
+use phago_core::types::*;
+use phago_rag::hybrid::{hybrid_query, HybridConfig};
+use phago_runtime::import::{triples_from_csv, CsvTripleConfig, TENTATIVE_WEIGHT};
+use phago_runtime::prelude::*;
+use phago_runtime::provenance::EdgeSource;
+
+/// Concepts the document below mentions, first so that they are wired to
+/// each other.
+const WORDS: &[&str] = &[
+    "cell",
+    "membrane",
+    "transport",
+    "protein",
+    "channel",
+    "ion",
+    "energy",
+    "atp",
+];
+const CONCEPTS: usize = 200;
+/// Each concept is linked to the next few, for 1000 distinct pairs.
+const LINKS: usize = 5;
+
+fn label(i: usize) -> String {
+    WORDS
+        .get(i)
+        .map_or_else(|| format!("entity{i}"), |w| w.to_string())
+}
+
+/// A CSV of 1000 triples, every other one with a confidence, grouped in
+/// four domains.
+fn write_csv(dir: &std::path::Path) -> std::path::PathBuf {
+    let mut csv = String::from("subject,predicate,object,confidence,domain\n");
+    for i in 0..CONCEPTS {
+        for d in 1..=LINKS {
+            let confidence = if (i + d) % 2 == 0 { "0.4" } else { "" };
+            csv.push_str(&format!(
+                "{},related_to,{},{},domain{}\n",
+                label(i),
+                label((i + d) % CONCEPTS),
+                confidence,
+                i % 4
+            ));
+        }
+    }
+    let path = dir.join("curated.csv");
+    std::fs::write(&path, csv).unwrap();
+    path
+}
+
+fn edge(colony: &Colony, from: &str, to: &str) -> Option<(NodeId, NodeId, EdgeData)> {
+    let graph = colony.substrate().graph();
+    let id = |label| graph.find_nodes_by_exact_label(label).first().copied();
+    let (from, to) = (id(from)?, id(to)?);
+    Some((from, to, graph.get_edge(&from, &to)?.clone()))
+}
+
+#[test]
+fn imported_triples_bootstrap_queries_and_digestion() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = CsvTripleConfig {
+        weight_column: Some("confidence".to_string()),
+        group_column: Some("domain".to_string()),
+        ..Default::default()
+    };
+    let triples = triples_from_csv(write_csv(dir.path()), &config).unwrap();
+    assert_eq!(triples.triples.len(), CONCEPTS * LINKS);
+    assert_eq!(triples.source, "curated.csv");
+
+    let mut colony = Colony::new();
+    let report = colony.import_triples(&triples);
+    assert_eq!(report.nodes_created, CONCEPTS);
+    assert_eq!(report.edges_created, CONCEPTS * LINKS);
+    assert_eq!(report.skipped, 0);
+    let graph = colony.substrate().graph();
+    assert_eq!(graph.node_count(), CONCEPTS);
+    assert_eq!(graph.edge_count(), CONCEPTS * LINKS);
+    let total_weight = |colony: &Colony| -> f64 {
+        let edges = colony.substrate().graph().all_edges();
+        edges.iter().map(|(_, _, e)| e.weight).sum()
+    };
+    let weight = total_weight(&colony);
+
+    // Importing again matches everything and strengthens nothing
+    let again = colony.import_triples(&triples);
+    assert_eq!(again.nodes_created, 0);
+    assert_eq!(again.nodes_merged, CONCEPTS);
+    assert_eq!(again.edges_created, 0);
+    assert_eq!(again.edges_merged, CONCEPTS * LINKS);
+    assert_eq!(colony.substrate().graph().node_count(), CONCEPTS);
+    assert_eq!(colony.substrate().graph().edge_count(), CONCEPTS * LINKS);
+    assert_eq!(total_weight(&colony), weight);
+
+    // Tentative without a confidence, and credited to the file
+    let (cell, membrane, imported) = edge(&colony, "cell", "membrane").unwrap();
+    assert_eq!(imported.weight, TENTATIVE_WEIGHT);
+    assert_eq!(imported.co_activations, 1);
+    assert_eq!(edge(&colony, "cell", "transport").unwrap().2.weight, 0.4);
+    let sources = colony.edge_sources(&cell, &membrane);
+    assert_eq!(sources.len(), 1);
+    assert_eq!(
+        colony.edge_provenance().import_name(sources[0].source),
+        Some("curated.csv")
+    );
+    assert!(matches!(sources[0].source, EdgeSource::Import(_)));
+
+    let results = hybrid_query(&colony, "membrane", &HybridConfig::default()).unwrap();
+    assert_eq!(results.first().map(|r| r.label.as_str()), Some("membrane"));
+
+    // A document about imported concepts wires onto them
+    let position = colony.substrate().graph().get_node(&cell).unwrap().position;
+    colony.ingest_document(
+        "Membranes",
+        "The cell membrane controls ion transport. Membrane channel proteins \
+         use ATP energy to move ions across the cell membrane.",
+        position,
+    );
+    colony.spawn(Box::new(Digester::new(position).with_max_idle(40)));
+    colony.run(30);
+
+    assert_eq!(colony.stats().documents_digested, 1);
+    let (_, _, wired) = edge(&colony, "cell", "membrane").unwrap();
+    assert!(wired.co_activations > 1, "{wired:?}");
+    assert_eq!(
+        colony
+            .substrate()
+            .graph()
+            .find_nodes_by_exact_label("membrane")
+            .len(),
+        1
+    );
+    // Wired to the document's other terms besides its imported neighbours
+    let graph = colony.substrate().graph();
+    assert!(graph.neighbors(&membrane).len() > 2 * LINKS);
+    // Imported edges nothing reinforced are still maturing
+    for i in 0..CONCEPTS {
+        assert!(edge(&colony, &label(i), &label((i + LINKS) % CONCEPTS)).is_some());
+    }
+
+    // The import is still credited after a session round trip
+    let path = dir.path().join("session.json");
+    save_session(&colony, &path, &[]).unwrap();
+    let mut restored = Colony::new();
+    restore_into_colony(&mut restored, &load_session(&path).unwrap(), None);
+    let (a, b, _) = edge(&restored, "entity100", "entity101").unwrap();
+    let sources = restored.edge_sources(&a, &b);
+    assert_eq!(
+        restored.edge_provenance().import_name(sources[0].source),
+        Some("curated.csv")
+    );
+}
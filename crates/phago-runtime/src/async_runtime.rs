@@ -3,6 +3,11 @@
 //! This module provides async variants of key Colony operations for improved
 //! throughput in I/O-bound scenarios (document ingestion, API calls, etc.).
 //!
+//! [`AsyncColonyRunner`] ticks a colony at a steady wall-clock rate while
+//! serving ingest and query requests from any thread. With a
+//! [`ViewRefresh`] policy it also keeps a [`ColonyView`] that queries can
+//! read from any thread without waiting for ticks.
+//!
 //! # Feature Flag
 //!
 //! This module requires the `async` feature:
@@ -45,10 +50,17 @@
 #![cfg(feature = "async")]
 
 use crate::colony::{Colony, ColonyEvent, ColonySnapshot, ColonyStats};
+use crate::colony_builder::PersistentColony;
+use crate::read_view::{ColonyView, ViewRefresh};
 use phago_core::types::{DocumentId, Position};
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot, watch, Mutex as AsyncMutex};
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
 /// Async wrapper around Colony for concurrent operations.
 ///
@@ -304,6 +316,697 @@ where
         .await
 }
 
+/// How often an [`AsyncColonyRunner`] ticks on its own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TickRate {
+    /// Ticks per second of wall-clock time.
+    PerSecond(f64),
+    /// One tick per interval.
+    Every(Duration),
+    /// Only when asked through [`RunnerHandle::tick`].
+    Manual,
+}
+
+impl TickRate {
+    /// Time between ticks; `None` if the runner should not tick on its own
+    /// (manual, zero or non-finite rates).
+    pub fn period(&self) -> Option<Duration> {
+        match *self {
+            TickRate::PerSecond(n) if n.is_finite() && n > 0.0 => {
+                Some(Duration::from_secs_f64(1.0 / n))
+            }
+            TickRate::Every(interval) if !interval.is_zero() => Some(interval),
+            _ => None,
+        }
+    }
+}
+
+/// What the runner does when a tick finishes after the next one was due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverrunPolicy {
+    /// Drop the missed ticks and stay on the original schedule.
+    #[default]
+    Skip,
+    /// Tick again a full period after the late tick finished, shifting the
+    /// schedule by the overrun.
+    Stretch,
+}
+
+/// Settings for an [`AsyncColonyRunner`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunnerConfig {
+    pub rate: TickRate,
+    pub overrun: OverrunPolicy,
+    /// Start paused; scheduled ticks begin on [`RunnerHandle::resume`].
+    pub paused: bool,
+    /// Capacity of the event broadcast channel (default: 1000).
+    pub event_capacity: usize,
+    /// Keep a read view, refreshed this often (default: none). See
+    /// [`RunnerHandle::view`].
+    pub view: Option<ViewRefresh>,
+}
+
+impl RunnerConfig {
+    pub fn new(rate: TickRate) -> Self {
+        Self {
+            rate,
+            overrun: OverrunPolicy::default(),
+            paused: false,
+            event_capacity: 1000,
+            view: None,
+        }
+    }
+}
+
+/// Tick counters kept by an [`AsyncColonyRunner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RunnerStats {
+    /// Ticks run, scheduled or requested through [`RunnerHandle::tick`].
+    pub ticks: u64,
+    /// Scheduled ticks that finished after the next one was due.
+    pub overruns: u64,
+    /// Scheduled ticks dropped under [`OverrunPolicy::Skip`].
+    pub skipped_ticks: u64,
+    /// Delay added to the schedule under [`OverrunPolicy::Stretch`].
+    pub stretched: Duration,
+}
+
+/// Returned by [`RunnerHandle`] once the runner has stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunnerStopped;
+
+impl std::fmt::Display for RunnerStopped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "colony runner has stopped")
+    }
+}
+
+impl std::error::Error for RunnerStopped {}
+
+/// What an [`AsyncColonyRunner`] drives: a [`Colony`], or a type that owns
+/// one along with state of its own (a database, a cache, a recorder).
+pub trait RunnerColony: 'static {
+    fn colony(&self) -> &Colony;
+
+    fn colony_mut(&mut self) -> &mut Colony;
+
+    /// Called after every tick the runner runs, with the tick's events.
+    fn after_tick(&mut self, _events: &[ColonyEvent]) {}
+
+    /// Called after every request served through [`SharedColony::with`],
+    /// which may have changed the colony.
+    fn after_edit(&mut self) {}
+}
+
+impl RunnerColony for Colony {
+    fn colony(&self) -> &Colony {
+        self
+    }
+
+    fn colony_mut(&mut self) -> &mut Colony {
+        self
+    }
+}
+
+impl RunnerColony for PersistentColony {
+    fn colony(&self) -> &Colony {
+        PersistentColony::colony(self)
+    }
+
+    fn colony_mut(&mut self) -> &mut Colony {
+        PersistentColony::colony_mut(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Control {
+    rate: TickRate,
+    paused: bool,
+    view: Option<ViewRefresh>,
+}
+
+/// Sends a served request's result back, once the view is up to date.
+type Reply = Box<dyn FnOnce() + Send>;
+
+type Job<C> = Box<dyn FnOnce(&mut C) -> Reply + Send>;
+
+/// The runner's latest read view, shared with its handles.
+#[derive(Default)]
+struct ViewSlot(RwLock<Option<Arc<ColonyView>>>);
+
+impl ViewSlot {
+    fn get(&self) -> Option<Arc<ColonyView>> {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Bring the view in line with `policy` at a boundary between ticks
+    /// and requests: take or refresh it if due, drop it if disabled.
+    fn update(&self, colony: &Colony, policy: Option<ViewRefresh>) {
+        let next = match (policy, self.get()) {
+            (None, None) => return,
+            (None, Some(_)) => None,
+            (Some(_), None) => Some(colony.read_view()),
+            (Some(ViewRefresh::Every(interval)), Some(view)) if view.age() < interval => return,
+            (Some(_), Some(view)) => Some(view.refresh(colony)),
+        };
+        *self.0.write().unwrap() = next.map(Arc::new);
+    }
+}
+
+enum Command<C> {
+    Run(Job<C>),
+    Stop,
+}
+
+/// The runner's side of the channels a [`RunnerHandle`] talks to.
+struct Inbox<C> {
+    commands: mpsc::UnboundedReceiver<Command<C>>,
+    control: watch::Receiver<Control>,
+    overrun: OverrunPolicy,
+}
+
+/// Ticks a colony at a fixed wall-clock rate while serving requests.
+///
+/// The runner owns the colony behind an async mutex. Scheduled ticks and
+/// requests from [`RunnerHandle`]s take turns on it, so a request waits
+/// for at most the tick in progress. Every tick's events are broadcast to
+/// [`RunnerHandle::subscribe`]rs.
+///
+/// The colony is not `Send`: either await [`run`](Self::run) on the
+/// thread that built it, or let [`spawn`](Self::spawn) build it on a
+/// thread of its own. Handles are `Send` and can be used from anywhere.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let runner = AsyncColonyRunner::new(Colony::new(), TickRate::PerSecond(4.0));
+/// let handle = runner.handle();
+/// tokio::task::spawn_local(runner.run());
+///
+/// handle.ingest("Biology", "cell membrane protein", Position::new(0.0, 0.0)).await?;
+/// let nodes = handle.query(|colony| colony.stats().graph_nodes).await?;
+/// handle.set_rate(TickRate::PerSecond(10.0));
+/// ```
+pub struct AsyncColonyRunner<C: RunnerColony = Colony> {
+    colony: Rc<AsyncMutex<C>>,
+    inbox: Inbox<C>,
+    handle: RunnerHandle<C>,
+}
+
+impl<C: RunnerColony> AsyncColonyRunner<C> {
+    /// Tick `colony` at `rate`, skipping missed ticks on overrun.
+    pub fn new(colony: C, rate: TickRate) -> Self {
+        Self::with_config(colony, RunnerConfig::new(rate))
+    }
+
+    pub fn with_config(colony: C, config: RunnerConfig) -> Self {
+        let (handle, inbox) = RunnerHandle::channels(config);
+        Self::from_parts(colony, inbox, handle)
+    }
+
+    fn from_parts(colony: C, inbox: Inbox<C>, handle: RunnerHandle<C>) -> Self {
+        Self {
+            colony: Rc::new(AsyncMutex::new(colony)),
+            inbox,
+            handle,
+        }
+    }
+
+    /// Build the colony with `make` on a dedicated thread and run it there.
+    ///
+    /// Returns at once; requests sent before the colony is built wait for
+    /// it. If `make` returns `None` the thread exits and pending requests
+    /// fail with [`RunnerStopped`]. The thread ends, dropping the colony,
+    /// once the runner stops.
+    pub fn spawn<F>(config: RunnerConfig, make: F) -> (RunnerHandle<C>, thread::JoinHandle<()>)
+    where
+        F: FnOnce() -> Option<C> + Send + 'static,
+    {
+        let (handle, inbox) = RunnerHandle::channels(config);
+        let runner_handle = handle.clone();
+        let thread = thread::spawn(move || {
+            let Some(colony) = make() else {
+                return;
+            };
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_time()
+                .build()
+                .expect("failed to build the colony runner's runtime");
+            runtime.block_on(Self::from_parts(colony, inbox, runner_handle).run());
+        });
+        (handle, thread)
+    }
+
+    /// A handle for sending requests and controlling the tick rate.
+    pub fn handle(&self) -> RunnerHandle<C> {
+        self.handle.clone()
+    }
+
+    /// The colony, for tasks on the runner's thread that need to hold it
+    /// across an await.
+    pub fn colony(&self) -> Rc<AsyncMutex<C>> {
+        Rc::clone(&self.colony)
+    }
+
+    /// Tick on schedule and serve requests until every handle is dropped or
+    /// [`RunnerHandle::stop`] is called, then return the colony.
+    ///
+    /// # Panics
+    /// Panics if a reference from [`colony`](Self::colony) is still alive.
+    pub async fn run(self) -> C {
+        let AsyncColonyRunner {
+            colony,
+            inbox,
+            handle,
+        } = self;
+        let Inbox {
+            mut commands,
+            control,
+            overrun,
+        } = inbox;
+        // Keep only what the ticker needs, so dropping the last outside
+        // handle closes the command channel
+        let RunnerHandle {
+            commands: commands_tx,
+            control: control_tx,
+            events,
+            stats,
+            views,
+        } = handle;
+        drop((commands_tx, control_tx));
+
+        let serve = async {
+            while let Some(command) = commands.recv().await {
+                match command {
+                    Command::Run(job) => {
+                        let mut owner = colony.lock().await;
+                        let reply = job(&mut *owner);
+                        views.update(owner.colony(), control.borrow().view);
+                        reply();
+                    }
+                    Command::Stop => break,
+                }
+            }
+        };
+        tokio::select! {
+            _ = serve => {}
+            _ = drive_ticks(&colony, control.clone(), overrun, &events, &stats, &views) => {}
+            _ = drive_views(&colony, control.clone(), &views) => {}
+        }
+
+        match Rc::try_unwrap(colony) {
+            Ok(colony) => colony.into_inner(),
+            Err(_) => panic!("Cannot unwrap AsyncColonyRunner: other references exist"),
+        }
+    }
+}
+
+/// Run one tick: notify the colony's owner, broadcast events, count it.
+fn run_tick<C: RunnerColony + ?Sized>(
+    colony: &mut C,
+    events: &broadcast::Sender<ColonyEvent>,
+    stats: &Mutex<RunnerStats>,
+) -> Vec<ColonyEvent> {
+    let tick_events = colony.colony_mut().tick();
+    colony.after_tick(&tick_events);
+    for event in &tick_events {
+        let _ = events.send(event.clone());
+    }
+    stats.lock().unwrap().ticks += 1;
+    tick_events
+}
+
+/// Tick on the configured schedule, restarting it whenever the rate changes
+/// or the runner is paused or resumed. Never completes.
+async fn drive_ticks<C: RunnerColony>(
+    colony: &AsyncMutex<C>,
+    mut control: watch::Receiver<Control>,
+    overrun: OverrunPolicy,
+    events: &broadcast::Sender<ColonyEvent>,
+    stats: &Mutex<RunnerStats>,
+    views: &ViewSlot,
+) {
+    loop {
+        let current = *control.borrow_and_update();
+        let Some(period) = current.rate.period().filter(|_| !current.paused) else {
+            if control.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+            continue;
+        };
+
+        let mut next = Instant::now() + period;
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(next) => {}
+                changed = control.changed() => {
+                    if changed.is_err() {
+                        std::future::pending::<()>().await;
+                    }
+                    break;
+                }
+            }
+            let due = next;
+            {
+                let mut owner = colony.lock().await;
+                run_tick(&mut *owner, events, stats);
+                views.update(owner.colony(), control.borrow().view);
+            }
+            let finished = Instant::now();
+
+            next = due + period;
+            if finished <= next {
+                continue;
+            }
+            let mut stats = stats.lock().unwrap();
+            stats.overruns += 1;
+            match overrun {
+                OverrunPolicy::Skip => {
+                    while next < finished {
+                        next += period;
+                        stats.skipped_ticks += 1;
+                    }
+                }
+                OverrunPolicy::Stretch => {
+                    stats.stretched += finished - next;
+                    next = finished + period;
+                }
+            }
+        }
+    }
+}
+
+/// Apply view policy changes as they come, and under
+/// [`ViewRefresh::Every`] refresh a view that has grown older than the
+/// interval without a tick or request to do it. Never completes.
+async fn drive_views<C: RunnerColony>(
+    colony: &AsyncMutex<C>,
+    mut control: watch::Receiver<Control>,
+    views: &ViewSlot,
+) {
+    loop {
+        let policy = control.borrow_and_update().view;
+        views.update(colony.lock().await.colony(), policy);
+        let Some(ViewRefresh::Every(interval)) = policy.filter(|p| match p {
+            ViewRefresh::Every(interval) => !interval.is_zero(),
+            ViewRefresh::EveryTick => false,
+        }) else {
+            if control.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+            continue;
+        };
+
+        loop {
+            let wait = views
+                .get()
+                .map_or(Duration::ZERO, |view| interval.saturating_sub(view.age()));
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {
+                    views.update(colony.lock().await.colony(), policy);
+                }
+                changed = control.changed() => {
+                    if changed.is_err() {
+                        std::future::pending::<()>().await;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Sends requests to an [`AsyncColonyRunner`] and controls its tick rate.
+///
+/// Cheap to clone and `Send`. Requests are served in order, between ticks.
+pub struct RunnerHandle<C = Colony> {
+    commands: mpsc::UnboundedSender<Command<C>>,
+    control: Arc<watch::Sender<Control>>,
+    events: broadcast::Sender<ColonyEvent>,
+    stats: Arc<Mutex<RunnerStats>>,
+    views: Arc<ViewSlot>,
+}
+
+impl<C> Clone for RunnerHandle<C> {
+    fn clone(&self) -> Self {
+        Self {
+            commands: self.commands.clone(),
+            control: Arc::clone(&self.control),
+            events: self.events.clone(),
+            stats: Arc::clone(&self.stats),
+            views: Arc::clone(&self.views),
+        }
+    }
+}
+
+impl<C: RunnerColony> RunnerHandle<C> {
+    fn channels(config: RunnerConfig) -> (Self, Inbox<C>) {
+        let (commands, commands_rx) = mpsc::unbounded_channel();
+        let (control, control_rx) = watch::channel(Control {
+            rate: config.rate,
+            paused: config.paused,
+            view: config.view,
+        });
+        let handle = Self {
+            commands,
+            control: Arc::new(control),
+            events: broadcast::channel(config.event_capacity.max(1)).0,
+            stats: Arc::new(Mutex::new(RunnerStats::default())),
+            views: Arc::new(ViewSlot::default()),
+        };
+        let inbox = Inbox {
+            commands: commands_rx,
+            control: control_rx,
+            overrun: config.overrun,
+        };
+        (handle, inbox)
+    }
+
+    /// Run `f` on the colony's owner between ticks and return its result.
+    pub async fn with<R, F>(&self, f: F) -> Result<R, RunnerStopped>
+    where
+        F: FnOnce(&mut C) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let job: Job<C> = Box::new(move |colony| {
+            let result = f(colony);
+            Box::new(move || {
+                let _ = tx.send(result);
+            })
+        });
+        self.commands
+            .send(Command::Run(job))
+            .map_err(|_| RunnerStopped)?;
+        rx.await.map_err(|_| RunnerStopped)
+    }
+
+    /// Read from the colony between ticks.
+    pub async fn query<R, F>(&self, f: F) -> Result<R, RunnerStopped>
+    where
+        F: FnOnce(&Colony) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.with(move |owner| f(owner.colony())).await
+    }
+
+    /// Add a document for agents to digest on the coming ticks.
+    pub async fn ingest(
+        &self,
+        title: impl Into<String>,
+        content: impl Into<String>,
+        position: Position,
+    ) -> Result<DocumentId, RunnerStopped> {
+        let (title, content) = (title.into(), content.into());
+        self.with(move |owner| {
+            owner
+                .colony_mut()
+                .ingest_document(&title, &content, position)
+        })
+        .await
+    }
+
+    /// Run one tick now, whether or not the runner is paused, and return
+    /// its events. They are broadcast like those of scheduled ticks.
+    pub async fn tick(&self) -> Result<Vec<ColonyEvent>, RunnerStopped> {
+        let events = self.events.clone();
+        let stats = Arc::clone(&self.stats);
+        self.with(move |owner| run_tick(owner, &events, &stats))
+            .await
+    }
+
+    /// Stop scheduled ticks until [`resume`](Self::resume). Requests are
+    /// still served.
+    pub fn pause(&self) {
+        self.control
+            .send_if_modified(|c| !std::mem::replace(&mut c.paused, true));
+    }
+
+    /// Resume scheduled ticks, one period from now.
+    pub fn resume(&self) {
+        self.control
+            .send_if_modified(|c| std::mem::replace(&mut c.paused, false));
+    }
+
+    /// Change the tick rate; the schedule restarts one period from now.
+    pub fn set_rate(&self, rate: TickRate) {
+        self.control.send_if_modified(|c| {
+            let changed = c.rate != rate;
+            c.rate = rate;
+            changed
+        });
+    }
+
+    pub fn rate(&self) -> TickRate {
+        self.control.borrow().rate
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.control.borrow().paused
+    }
+
+    /// Keep a read view refreshed as `refresh` says, or stop keeping one.
+    pub fn set_view_refresh(&self, refresh: Option<ViewRefresh>) {
+        self.control.send_if_modified(|c| {
+            let changed = c.view != refresh;
+            c.view = refresh;
+            changed
+        });
+    }
+
+    pub fn view_refresh(&self) -> Option<ViewRefresh> {
+        self.control.borrow().view
+    }
+
+    /// The latest read view, if the runner keeps one and has taken it.
+    ///
+    /// Reading it never waits for the runner: queries on the view run
+    /// concurrently with ticks and with each other, on the state as of
+    /// [`ColonyView::tick`]. A request's changes are in the view by the
+    /// time its result arrives under [`ViewRefresh::EveryTick`].
+    pub fn view(&self) -> Option<Arc<ColonyView>> {
+        self.views.get()
+    }
+
+    /// Stop the runner once the requests already sent have been served.
+    pub fn stop(&self) {
+        let _ = self.commands.send(Command::Stop);
+    }
+
+    /// Ticks run, overruns and what was done about them.
+    pub fn stats(&self) -> RunnerStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Subscribe to the events of every tick from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ColonyEvent> {
+        self.events.subscribe()
+    }
+
+    /// A handle to the same runner that hides the colony's owner type, for
+    /// front ends that only need the [`Colony`].
+    pub fn shared(&self) -> SharedColony {
+        let commands = self.commands.clone();
+        let send = move |job: SharedJob| {
+            let job: Job<C> = Box::new(move |owner| job(owner));
+            commands.send(Command::Run(job)).map_err(|_| RunnerStopped)
+        };
+        SharedColony {
+            send: Arc::new(send),
+            events: self.events.clone(),
+            stats: Arc::clone(&self.stats),
+            views: Arc::clone(&self.views),
+        }
+    }
+}
+
+type SharedJob = Box<dyn FnOnce(&mut dyn RunnerColony) -> Reply + Send>;
+
+/// A [`RunnerHandle`] without the owner type, so that several front ends
+/// (a web dashboard, an MCP server) can serve one colony.
+///
+/// Requests from every clone go through the same runner and are served in
+/// order, between ticks. Events raised while serving [`with`](Self::with)
+/// (an ingest that runs its own ticks, say) are broadcast to subscribers
+/// like those of scheduled ticks.
+#[derive(Clone)]
+pub struct SharedColony {
+    send: Arc<dyn Fn(SharedJob) -> Result<(), RunnerStopped> + Send + Sync>,
+    events: broadcast::Sender<ColonyEvent>,
+    stats: Arc<Mutex<RunnerStats>>,
+    views: Arc<ViewSlot>,
+}
+
+impl SharedColony {
+    async fn run<R, F>(&self, f: F) -> Result<R, RunnerStopped>
+    where
+        F: FnOnce(&mut dyn RunnerColony) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        (self.send)(Box::new(move |owner| {
+            let result = f(owner);
+            Box::new(move || {
+                let _ = tx.send(result);
+            })
+        }))?;
+        rx.await.map_err(|_| RunnerStopped)
+    }
+
+    /// Run `f` on the colony between ticks and return its result.
+    pub async fn with<R, F>(&self, f: F) -> Result<R, RunnerStopped>
+    where
+        F: FnOnce(&mut Colony) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let events = self.events.clone();
+        self.run(move |owner| {
+            let colony = owner.colony_mut();
+            let seen = colony.event_history().len();
+            let result = f(colony);
+            for (_, event) in colony.event_history().iter().skip(seen) {
+                let _ = events.send(event.clone());
+            }
+            owner.after_edit();
+            result
+        })
+        .await
+    }
+
+    /// Read from the colony between ticks.
+    pub async fn query<R, F>(&self, f: F) -> Result<R, RunnerStopped>
+    where
+        F: FnOnce(&Colony) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.run(move |owner| f(owner.colony())).await
+    }
+
+    /// Run one tick now and return its events, as [`RunnerHandle::tick`].
+    pub async fn tick(&self) -> Result<Vec<ColonyEvent>, RunnerStopped> {
+        let events = self.events.clone();
+        let stats = Arc::clone(&self.stats);
+        self.run(move |owner| run_tick(owner, &events, &stats))
+            .await
+    }
+
+    /// Subscribe to the events of every tick from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<ColonyEvent> {
+        self.events.subscribe()
+    }
+
+    /// The runner's latest read view, as [`RunnerHandle::view`].
+    pub fn view(&self) -> Option<Arc<ColonyView>> {
+        self.views.get()
+    }
+}
+
+impl std::fmt::Debug for SharedColony {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedColony").finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -443,6 +1146,238 @@ mod tests {
         .await;
     }
 
+    /// Start `runner` on the current LocalSet.
+    fn start<C: RunnerColony>(runner: AsyncColonyRunner<C>) -> JoinHandle<C> {
+        tokio::task::spawn_local(runner.run())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn runner_keeps_its_rate_for_a_simulated_minute() {
+        run_test(|| async {
+            let runner = AsyncColonyRunner::new(Colony::new(), TickRate::PerSecond(4.0));
+            let handle = runner.handle();
+            let mut events = handle.subscribe();
+            let task = start(runner);
+
+            tokio::time::sleep(Duration::from_millis(60_100)).await;
+            let stats = handle.stats();
+            assert_eq!(stats.ticks, 240);
+            assert_eq!(stats.overruns, 0);
+            assert!(matches!(
+                events.recv().await,
+                Ok(ColonyEvent::TickComplete { tick: 1, .. })
+            ));
+
+            handle.pause();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            assert_eq!(handle.stats().ticks, 240);
+
+            handle.set_rate(TickRate::Every(Duration::from_secs(1)));
+            handle.resume();
+            tokio::time::sleep(Duration::from_millis(10_100)).await;
+            assert_eq!(handle.stats().ticks, 250);
+
+            handle.stop();
+            let colony = task.await.unwrap();
+            assert_eq!(colony.stats().tick, 250);
+        })
+        .await;
+    }
+
+    /// Hold the colony from `from` for `hold`, as a tick that slow would.
+    fn slow_tick<C: RunnerColony>(runner: &AsyncColonyRunner<C>, from: Duration, hold: Duration) {
+        let colony = runner.colony();
+        tokio::task::spawn_local(async move {
+            tokio::time::sleep(from).await;
+            let _guard = colony.lock().await;
+            tokio::time::sleep(hold).await;
+        });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_tick_skips_missed_ticks_without_blocking_queries() {
+        run_test(|| async {
+            let mut colony = Colony::new();
+            colony.ingest_document("Test", "cell membrane protein", Position::new(0.0, 0.0));
+            let runner = AsyncColonyRunner::new(colony, TickRate::PerSecond(10.0));
+            let handle = runner.handle();
+            slow_tick(&runner, Duration::from_millis(150), Duration::from_secs(1));
+            let task = start(runner);
+
+            tokio::time::sleep(Duration::from_millis(160)).await;
+            let documents = tokio::time::timeout(
+                Duration::from_secs(5),
+                handle.query(|colony| colony.stats().documents_total),
+            )
+            .await
+            .expect("query waited on a deadlocked runner")
+            .unwrap();
+            assert_eq!(documents, 1);
+
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let stats = handle.stats();
+            assert_eq!(stats.overruns, 1);
+            // The tick due at 200ms finished at 1150ms; 300ms..1100ms were dropped
+            assert_eq!(stats.skipped_ticks, 9);
+            assert_eq!(stats.stretched, Duration::ZERO);
+            assert_eq!(stats.ticks, 12);
+
+            handle.stop();
+            task.await.unwrap();
+        })
+        .await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stretch_shifts_the_schedule_after_an_overrun() {
+        run_test(|| async {
+            let config = RunnerConfig {
+                overrun: OverrunPolicy::Stretch,
+                ..RunnerConfig::new(TickRate::PerSecond(10.0))
+            };
+            let runner = AsyncColonyRunner::with_config(Colony::new(), config);
+            let handle = runner.handle();
+            slow_tick(&runner, Duration::from_millis(150), Duration::from_secs(1));
+            let task = start(runner);
+
+            tokio::time::sleep(Duration::from_millis(2_160)).await;
+            let stats = handle.stats();
+            assert_eq!(stats.overruns, 1);
+            assert_eq!(stats.skipped_ticks, 0);
+            assert_eq!(stats.stretched, Duration::from_millis(850));
+            // 100ms, 200ms (finished 1150ms), then 1250ms..2150ms
+            assert_eq!(stats.ticks, 12);
+
+            handle.stop();
+            task.await.unwrap();
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn spawned_runner_serves_requests_from_other_threads() {
+        let (handle, thread) =
+            AsyncColonyRunner::spawn(RunnerConfig::new(TickRate::Manual), || Some(Colony::new()));
+        handle
+            .ingest("Test", "cell membrane protein", Position::new(0.0, 0.0))
+            .await
+            .unwrap();
+        let events = handle.tick().await.unwrap();
+        assert!(!events.is_empty());
+        let stats = handle.query(|colony| colony.stats()).await.unwrap();
+        assert_eq!((stats.tick, stats.documents_total), (1, 1));
+        assert_eq!(handle.stats().ticks, 1);
+
+        handle.stop();
+        thread.join().unwrap();
+        assert_eq!(
+            handle.query(|colony| colony.stats().tick).await,
+            Err(RunnerStopped)
+        );
+    }
+
+    #[tokio::test]
+    async fn runner_stops_once_every_handle_is_dropped() {
+        let (handle, thread) =
+            AsyncColonyRunner::spawn(RunnerConfig::new(TickRate::PerSecond(100.0)), || {
+                Some(Colony::new())
+            });
+        handle.tick().await.unwrap();
+        drop(handle);
+        thread.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn shared_colony_broadcasts_events_raised_by_requests() {
+        let (handle, thread) =
+            AsyncColonyRunner::spawn(RunnerConfig::new(TickRate::Manual), || Some(Colony::new()));
+        let shared = handle.shared();
+        let mut events = handle.subscribe();
+
+        shared
+            .with(|colony| {
+                colony.ingest_document("Test", "cell membrane protein", Position::new(0.0, 0.0));
+                colony.run(2);
+            })
+            .await
+            .unwrap();
+        let mut ticks = 0;
+        while let Ok(event) = events.try_recv() {
+            if matches!(event, ColonyEvent::TickComplete { .. }) {
+                ticks += 1;
+            }
+        }
+        assert_eq!(ticks, 2);
+
+        shared.tick().await.unwrap();
+        assert_eq!(handle.stats().ticks, 1);
+        assert_eq!(shared.query(|colony| colony.stats().tick).await, Ok(3));
+
+        // The shared handle keeps the runner alive like any other
+        drop(handle);
+        assert_eq!(shared.query(|colony| colony.stats().tick).await, Ok(3));
+        drop(shared);
+        thread.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn view_reflects_a_request_before_its_reply() {
+        let config = RunnerConfig {
+            view: Some(ViewRefresh::EveryTick),
+            ..RunnerConfig::new(TickRate::Manual)
+        };
+        let (handle, thread) = AsyncColonyRunner::spawn(config, || Some(Colony::new()));
+        handle
+            .ingest("Test", "cell membrane protein", Position::new(0.0, 0.0))
+            .await
+            .unwrap();
+        let view = handle.view().expect("view kept every tick");
+        assert_eq!(view.stats().documents_total, 1);
+
+        handle.tick().await.unwrap();
+        let ticked = handle.view().unwrap();
+        assert_eq!(ticked.tick(), 1);
+        // An earlier view stays as it was taken
+        assert_eq!(view.tick(), 0);
+
+        handle.set_view_refresh(None);
+        handle.tick().await.unwrap();
+        assert!(handle.view().is_none());
+
+        handle.stop();
+        thread.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn interval_view_refreshes_without_ticks() {
+        let config = RunnerConfig {
+            view: Some(ViewRefresh::Every(Duration::from_millis(50))),
+            ..RunnerConfig::new(TickRate::Manual)
+        };
+        let (handle, thread) = AsyncColonyRunner::spawn(config, || Some(Colony::new()));
+        let shared = handle.shared();
+        shared
+            .with(|colony| {
+                colony.ingest_document("Test", "cell membrane protein", Position::new(0.0, 0.0))
+            })
+            .await
+            .unwrap();
+
+        let mut documents = 0;
+        for _ in 0..100 {
+            documents = shared.view().map_or(0, |v| v.stats().documents_total);
+            if documents == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(documents, 1);
+        assert_eq!(handle.stats().ticks, 0);
+
+        handle.stop();
+        thread.join().unwrap();
+    }
+
     #[tokio::test]
     async fn run_in_local_convenience() {
         let colony = Colony::new();
@@ -4,14 +4,17 @@
 
 use crate::topology_impl::PetTopologyGraph;
 use phago_core::topology::TopologyGraph;
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "sqlite")]
 use std::path::PathBuf;
 
 /// Configuration for graph backend selection.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum BackendConfig {
     /// In-memory petgraph backend (default, fast, no persistence).
+    #[default]
     InMemory,
 
     /// SQLite-backed persistent storage.
@@ -25,12 +28,6 @@ pub enum BackendConfig {
     },
 }
 
-impl Default for BackendConfig {
-    fn default() -> Self {
-        BackendConfig::InMemory
-    }
-}
-
 impl BackendConfig {
     /// Create an in-memory backend configuration.
     pub fn in_memory() -> Self {
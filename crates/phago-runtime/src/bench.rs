@@ -3,19 +3,32 @@
 //! Provides a standard framework for running timed simulations,
 //! collecting snapshots at regular intervals, and comparing metrics
 //! across multiple runs with different configurations.
+//!
+//! With the `async` feature, [`query_load_test`] measures query latency
+//! under concurrent load.
 
-use crate::colony::{Colony, ColonySnapshot};
-use crate::metrics::{compute_from_snapshots, ColonyMetrics};
+use crate::colony::Colony;
+use crate::metrics::{compute_from_series, ColonyMetrics};
+use crate::snapshot::{SnapshotMode, SnapshotSeries};
 use phago_core::types::Tick;
 use serde::Serialize;
 use std::time::Instant;
 
+#[cfg(feature = "async")]
+mod query_load;
+
+#[cfg(feature = "async")]
+pub use query_load::{
+    query_load_csv, query_load_test, LatencyHistogram, LatencySummary, LockMode, QueryLoadConfig,
+    QueryLoadError, QueryLoadReport,
+};
+
 /// A single benchmark run capturing timeline data.
 #[derive(Debug, Clone, Serialize)]
 pub struct BenchmarkRun {
     pub name: String,
     pub ticks: u64,
-    pub snapshots: Vec<ColonySnapshot>,
+    pub snapshots: SnapshotSeries,
     pub metrics_timeline: Vec<(Tick, ColonyMetrics)>,
     pub wall_time_ms: u64,
 }
@@ -31,6 +44,9 @@ pub struct BenchmarkConfig {
     pub ticks: u64,
     /// Take a snapshot every N ticks.
     pub snapshot_interval: u64,
+    /// How much of each snapshot to keep. Full snapshots of a big colony
+    /// add up quickly; see [`SnapshotMode`].
+    pub snapshot_mode: SnapshotMode,
     /// Compute metrics every N ticks.
     pub metrics_interval: u64,
 }
@@ -41,6 +57,7 @@ impl Default for BenchmarkConfig {
             name: "default".to_string(),
             ticks: 200,
             snapshot_interval: 10,
+            snapshot_mode: SnapshotMode::Full,
             metrics_interval: 50,
         }
     }
@@ -60,6 +77,11 @@ impl BenchmarkConfig {
         self
     }
 
+    pub fn with_snapshot_mode(mut self, mode: SnapshotMode) -> Self {
+        self.snapshot_mode = mode;
+        self
+    }
+
     pub fn with_metrics_interval(mut self, interval: u64) -> Self {
         self.metrics_interval = interval;
         self
@@ -71,11 +93,11 @@ impl BenchmarkConfig {
 /// The colony should already have documents ingested and agents spawned.
 /// This function runs the simulation and collects data.
 pub fn run_benchmark(colony: &mut Colony, config: &BenchmarkConfig) -> BenchmarkRun {
-    let mut snapshots = Vec::new();
+    let mut snapshots = SnapshotSeries::new(config.snapshot_mode);
     let mut metrics_timeline = Vec::new();
 
     // Initial snapshot
-    snapshots.push(colony.snapshot());
+    colony.record_snapshot(&mut snapshots);
 
     let start = Instant::now();
 
@@ -83,11 +105,11 @@ pub fn run_benchmark(colony: &mut Colony, config: &BenchmarkConfig) -> Benchmark
         colony.tick();
 
         if tick_num % config.snapshot_interval == 0 {
-            snapshots.push(colony.snapshot());
+            colony.record_snapshot(&mut snapshots);
         }
 
         if tick_num % config.metrics_interval == 0 {
-            let metrics = compute_from_snapshots(colony, &snapshots);
+            let metrics = compute_from_series(colony, &snapshots);
             metrics_timeline.push((tick_num, metrics));
         }
     }
@@ -95,7 +117,7 @@ pub fn run_benchmark(colony: &mut Colony, config: &BenchmarkConfig) -> Benchmark
     let wall_time = start.elapsed();
 
     // Final metrics
-    let final_metrics = compute_from_snapshots(colony, &snapshots);
+    let final_metrics = compute_from_series(colony, &snapshots);
     metrics_timeline.push((config.ticks, final_metrics));
 
     BenchmarkRun {
@@ -107,6 +129,56 @@ pub fn run_benchmark(colony: &mut Colony, config: &BenchmarkConfig) -> Benchmark
     }
 }
 
+/// How fast a colony digested its documents.
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestThroughput {
+    pub name: String,
+    /// Documents digested during the run.
+    pub documents: usize,
+    pub ticks: u64,
+    pub wall_time_ms: u64,
+    pub docs_per_sec: f64,
+    pub nodes: usize,
+    pub edges: usize,
+}
+
+/// Tick `colony` until every document is digested or `max_ticks` have
+/// passed, and report documents digested per second of wall time.
+///
+/// The colony should already have documents ingested and agents spawned.
+pub fn measure_ingest(colony: &mut Colony, name: &str, max_ticks: u64) -> IngestThroughput {
+    let digested = |colony: &Colony| {
+        colony
+            .substrate()
+            .all_documents()
+            .iter()
+            .filter(|d| d.digested)
+            .count()
+    };
+    let before = digested(colony);
+    let total = colony.substrate().all_documents().len();
+
+    let start = Instant::now();
+    let mut ticks = 0;
+    while ticks < max_ticks && digested(colony) < total {
+        colony.tick();
+        ticks += 1;
+    }
+    let wall_time = start.elapsed();
+
+    let documents = digested(colony) - before;
+    let stats = colony.stats();
+    IngestThroughput {
+        name: name.to_string(),
+        documents,
+        ticks,
+        wall_time_ms: wall_time.as_millis() as u64,
+        docs_per_sec: documents as f64 / wall_time.as_secs_f64().max(f64::EPSILON),
+        nodes: stats.graph_nodes,
+        edges: stats.graph_edges,
+    }
+}
+
 impl BenchmarkSuite {
     pub fn new() -> Self {
         Self { runs: Vec::new() }
@@ -291,4 +363,21 @@ mod tests {
         let csv = suite.to_csv();
         assert!(csv.contains("empty"));
     }
+
+    #[test]
+    fn measure_ingest_stops_once_everything_is_digested() {
+        let mut colony = Colony::new();
+        let corpus = Corpus::inline_corpus().limit(4);
+        corpus.ingest_into(&mut colony);
+        for doc in &corpus.documents {
+            colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(80)));
+        }
+
+        let run = measure_ingest(&mut colony, "test", 200);
+
+        assert_eq!(run.documents, 4);
+        assert!(run.ticks < 200);
+        assert!(run.docs_per_sec > 0.0);
+        assert!(run.edges > 0);
+    }
 }
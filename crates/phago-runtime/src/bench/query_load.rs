@@ -0,0 +1,592 @@
+//! Query latency under concurrent load.
+//!
+//! [`query_load_test`] puts a colony behind an [`AsyncColonyRunner`] that
+//! keeps ticking, and lets `concurrency` workers query it as fast as they
+//! can for a fixed time, recording the latency of every query. Where the
+//! other benchmarks measure what a colony answers, this one measures how
+//! long answering takes, for each way of serving queries:
+//!
+//! - [`LockMode::Mutex`] sends every query to the runner, which serves
+//!   them one at a time between ticks, as a front end without a read view
+//!   does.
+//! - [`LockMode::ReadView`] reads the runner's [`ColonyView`] on the
+//!   workers' own threads. Reinforcement writes, so it still goes to the
+//!   runner, and the runner refreshes the view after every write.
+//!
+//! Each query seeds on the concepts its terms name and follows their
+//! strongest edges, the traversal every query path starts with. With
+//! `reinforce` it then strengthens what it retrieved, as a learning query
+//! does.
+//!
+//! [`ColonyView`]: crate::read_view::ColonyView
+
+use crate::async_runtime::{AsyncColonyRunner, RunnerConfig, RunnerHandle, TickRate};
+use crate::colony::Colony;
+use crate::read_view::ViewRefresh;
+use phago_core::topology::TopologyGraph;
+use phago_core::types::NodeId;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::{Builder, Runtime};
+use tokio::task::LocalSet;
+
+/// How load-test queries reach the colony.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockMode {
+    /// Every query waits its turn on the runner.
+    Mutex,
+    /// Queries read the runner's read view concurrently.
+    ReadView,
+}
+
+impl LockMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LockMode::Mutex => "mutex",
+            LockMode::ReadView => "read_view",
+        }
+    }
+}
+
+/// Configuration for [`query_load_test`].
+#[derive(Debug, Clone)]
+pub struct QueryLoadConfig {
+    /// Workers querying at once (default: 4).
+    pub concurrency: usize,
+    /// How long the workers query (default: 10 seconds).
+    pub duration: Duration,
+    /// Queries the workers ask in turn, each starting at its own offset.
+    pub query_set: Vec<String>,
+    /// Strengthen what each query retrieved (default: false).
+    pub reinforce: bool,
+    pub lock_mode: LockMode,
+    /// How often the colony ticks under load (default: 10 per second).
+    pub tick_rate: TickRate,
+    /// Concepts each query retrieves at most (default: 10).
+    pub max_results: usize,
+}
+
+impl Default for QueryLoadConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            duration: Duration::from_secs(10),
+            query_set: Vec::new(),
+            reinforce: false,
+            lock_mode: LockMode::Mutex,
+            tick_rate: TickRate::PerSecond(10.0),
+            max_results: 10,
+        }
+    }
+}
+
+/// Why a load test could not run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryLoadError {
+    /// `concurrency` is zero.
+    NoWorkers,
+    /// No query in the query set has a term.
+    NoQueries,
+    /// The runner stopped before the load began.
+    RunnerStopped,
+}
+
+impl std::fmt::Display for QueryLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryLoadError::NoWorkers => write!(f, "Concurrency must be at least 1"),
+            QueryLoadError::NoQueries => write!(f, "The query set has no query with a term"),
+            QueryLoadError::RunnerStopped => {
+                write!(f, "The colony runner stopped before the load began")
+            }
+        }
+    }
+}
+
+impl std::error::Error for QueryLoadError {}
+
+/// Values below this are kept exactly, one bucket each.
+const LINEAR_BUCKETS: u64 = 64;
+
+/// Buckets per doubling above [`LINEAR_BUCKETS`].
+const SUB_BUCKETS: usize = 32;
+
+/// Latencies in microseconds, bucketed log-linearly as in an HDR
+/// histogram: every latency is kept to within 1/32 of itself, in a few
+/// hundred buckets however long the tail.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyHistogram {
+    counts: Vec<u64>,
+    count: u64,
+    total_us: u128,
+    max_us: u64,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        let us = latency.as_micros().min(u64::MAX as u128) as u64;
+        let bucket = bucket_of(us);
+        if bucket >= self.counts.len() {
+            self.counts.resize(bucket + 1, 0);
+        }
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.total_us += us as u128;
+        self.max_us = self.max_us.max(us);
+    }
+
+    /// Add every latency recorded in `other`.
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        if other.counts.len() > self.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (mine, theirs) in self.counts.iter_mut().zip(&other.counts) {
+            *mine += theirs;
+        }
+        self.count += other.count;
+        self.total_us += other.total_us;
+        self.max_us = self.max_us.max(other.max_us);
+    }
+
+    /// Latencies recorded.
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The latency `percentile` (0–100) of recorded latencies are at or
+    /// below, rounded up to its bucket; zero when nothing was recorded.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let rank =
+            ((percentile.clamp(0.0, 100.0) / 100.0 * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, &n) in self.counts.iter().enumerate() {
+            seen += n;
+            if seen >= rank {
+                return Duration::from_micros(highest_in(bucket).min(self.max_us));
+            }
+        }
+        self.max()
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.max_us)
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            n => Duration::from_micros((self.total_us / n as u128) as u64),
+        }
+    }
+
+    /// The percentiles a report shows.
+    pub fn summary(&self) -> LatencySummary {
+        let us = |d: Duration| d.as_micros() as u64;
+        LatencySummary {
+            p50_us: us(self.percentile(50.0)),
+            p90_us: us(self.percentile(90.0)),
+            p99_us: us(self.percentile(99.0)),
+            max_us: self.max_us,
+            mean_us: us(self.mean()),
+        }
+    }
+}
+
+fn bucket_of(us: u64) -> usize {
+    if us < LINEAR_BUCKETS {
+        return us as usize;
+    }
+    // Halve the resolution with every doubling past the linear range
+    let shift = 63 - us.leading_zeros() - SUB_BUCKETS.trailing_zeros();
+    shift as usize * SUB_BUCKETS + (us >> shift) as usize
+}
+
+/// The largest value [`bucket_of`] puts in `bucket`.
+fn highest_in(bucket: usize) -> u64 {
+    if (bucket as u64) < LINEAR_BUCKETS {
+        return bucket as u64;
+    }
+    let shift = bucket / SUB_BUCKETS - 1;
+    let sub = (bucket - shift * SUB_BUCKETS) as u128;
+    (((sub + 1) << shift) - 1).min(u64::MAX as u128) as u64
+}
+
+/// Latency percentiles of a load test, in microseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LatencySummary {
+    pub p50_us: u64,
+    pub p90_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+    pub mean_us: u64,
+}
+
+/// What a load test measured.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryLoadReport {
+    pub lock_mode: LockMode,
+    pub reinforce: bool,
+    pub concurrency: usize,
+    /// Wall time from the first query sent to the last answered.
+    pub wall_time_ms: u64,
+    /// Queries answered.
+    pub queries: u64,
+    /// Queries that got no answer, because the runner stopped or a worker
+    /// failed.
+    pub errors: u64,
+    pub queries_per_sec: f64,
+    /// Concepts retrieved, summed over queries.
+    pub results: u64,
+    /// Ticks the colony ran under load.
+    pub ticks: u64,
+    pub latency: LatencySummary,
+    /// Every latency recorded.
+    #[serde(skip)]
+    pub histogram: LatencyHistogram,
+}
+
+impl QueryLoadReport {
+    /// The lock mode, and whether queries reinforced, e.g.
+    /// `read_view+reinforce`.
+    pub fn name(&self) -> String {
+        if self.reinforce {
+            format!("{}+reinforce", self.lock_mode.name())
+        } else {
+            self.lock_mode.name().to_string()
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Export load test reports to CSV, one row each.
+pub fn query_load_csv(reports: &[QueryLoadReport]) -> String {
+    let mut csv = String::new();
+    csv.push_str("name,concurrency,wall_time_ms,queries,errors,queries_per_sec,p50_us,p90_us,p99_us,max_us,mean_us,ticks\n");
+    for report in reports {
+        let latency = &report.latency;
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:.1},{},{},{},{},{},{}\n",
+            report.name(),
+            report.concurrency,
+            report.wall_time_ms,
+            report.queries,
+            report.errors,
+            report.queries_per_sec,
+            latency.p50_us,
+            latency.p90_us,
+            latency.p99_us,
+            latency.max_us,
+            latency.mean_us,
+            report.ticks,
+        ));
+    }
+    csv
+}
+
+/// Query `colony` from `config.concurrency` workers at once for
+/// `config.duration`, with the colony ticking at `config.tick_rate`, and
+/// report the latency of the queries.
+///
+/// Blocks for the duration. The colony is dropped afterwards; load a
+/// fresh one to compare another configuration.
+pub fn query_load_test(
+    colony: Colony,
+    config: &QueryLoadConfig,
+) -> Result<QueryLoadReport, QueryLoadError> {
+    if config.concurrency == 0 {
+        return Err(QueryLoadError::NoWorkers);
+    }
+    let queries: Vec<Vec<String>> = config
+        .query_set
+        .iter()
+        .map(|q| colony.tokenizer().tokens(q))
+        .filter(|terms| !terms.is_empty())
+        .collect();
+    if queries.is_empty() {
+        return Err(QueryLoadError::NoQueries);
+    }
+
+    let mut runner_config = RunnerConfig::new(config.tick_rate);
+    if config.lock_mode == LockMode::ReadView {
+        runner_config.view = Some(ViewRefresh::EveryTick);
+    }
+    let runner = AsyncColonyRunner::with_config(colony, runner_config);
+    let handle = runner.handle();
+
+    // The colony is not `Send`: its runner stays on this thread while the
+    // workers get threads of their own
+    let workers = Builder::new_multi_thread()
+        .worker_threads(config.concurrency)
+        .enable_time()
+        .build()
+        .expect("failed to build the load test's worker runtime");
+    let local = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("failed to build the load test's colony runtime");
+    LocalSet::new().block_on(&local, async {
+        let colony = tokio::task::spawn_local(runner.run());
+        let report = drive(&workers, &handle, Arc::new(queries), config).await;
+        handle.stop();
+        let _ = colony.await;
+        report
+    })
+}
+
+/// What one worker measured.
+#[derive(Default)]
+struct WorkerTally {
+    histogram: LatencyHistogram,
+    errors: u64,
+    results: u64,
+}
+
+async fn drive(
+    workers: &Runtime,
+    handle: &RunnerHandle,
+    queries: Arc<Vec<Vec<String>>>,
+    config: &QueryLoadConfig,
+) -> Result<QueryLoadReport, QueryLoadError> {
+    // Once the runner has served a request its read view exists
+    handle
+        .query(|_| ())
+        .await
+        .map_err(|_| QueryLoadError::RunnerStopped)?;
+    let ticks = handle.stats().ticks;
+
+    let started = Instant::now();
+    let deadline = started + config.duration;
+    let jobs: Vec<_> = (0..config.concurrency)
+        .map(|offset| {
+            let worker = Worker {
+                handle: handle.clone(),
+                queries: Arc::clone(&queries),
+                lock_mode: config.lock_mode,
+                reinforce: config.reinforce,
+                max_results: config.max_results,
+            };
+            workers.spawn(worker.run(offset, deadline))
+        })
+        .collect();
+    let mut total = WorkerTally::default();
+    for job in jobs {
+        match job.await {
+            Ok(tally) => {
+                total.histogram.merge(&tally.histogram);
+                total.errors += tally.errors;
+                total.results += tally.results;
+            }
+            Err(_) => total.errors += 1,
+        }
+    }
+    let wall_time = started.elapsed();
+
+    let queries = total.histogram.len();
+    Ok(QueryLoadReport {
+        lock_mode: config.lock_mode,
+        reinforce: config.reinforce,
+        concurrency: config.concurrency,
+        wall_time_ms: wall_time.as_millis() as u64,
+        queries,
+        errors: total.errors,
+        queries_per_sec: queries as f64 / wall_time.as_secs_f64().max(f64::EPSILON),
+        results: total.results,
+        ticks: handle.stats().ticks - ticks,
+        latency: total.histogram.summary(),
+        histogram: total.histogram,
+    })
+}
+
+/// One of the load test's query loops.
+struct Worker {
+    handle: RunnerHandle,
+    queries: Arc<Vec<Vec<String>>>,
+    lock_mode: LockMode,
+    reinforce: bool,
+    max_results: usize,
+}
+
+impl Worker {
+    /// Ask queries in turn, starting with the `offset`th, until `deadline`.
+    async fn run(self, offset: usize, deadline: Instant) -> WorkerTally {
+        let mut tally = WorkerTally::default();
+        let mut next = offset;
+        while Instant::now() < deadline {
+            let terms = &self.queries[next % self.queries.len()];
+            next += 1;
+            let started = Instant::now();
+            match self.ask(terms).await {
+                Some(results) => {
+                    tally.histogram.record(started.elapsed());
+                    tally.results += results as u64;
+                }
+                None => tally.errors += 1,
+            }
+        }
+        tally
+    }
+
+    /// Answer one query, returning the concepts it retrieved.
+    async fn ask(&self, terms: &[String]) -> Option<usize> {
+        let (terms, max_results, reinforce) = (terms.to_vec(), self.max_results, self.reinforce);
+        let retrieved = match self.lock_mode {
+            LockMode::Mutex => {
+                return self
+                    .handle
+                    .with(move |colony| {
+                        let retrieved = retrieve(colony.substrate().graph(), &terms, max_results);
+                        if reinforce {
+                            reinforce_retrieved(colony, &retrieved);
+                        }
+                        retrieved.len()
+                    })
+                    .await
+                    .ok();
+            }
+            LockMode::ReadView => retrieve(self.handle.view()?.graph(), &terms, max_results),
+        };
+        let count = retrieved.len();
+        if reinforce {
+            self.handle
+                .with(move |colony| reinforce_retrieved(colony, &retrieved))
+                .await
+                .ok()?;
+        }
+        Some(count)
+    }
+}
+
+/// The strongest edges from the concepts `terms` name: (seed, concept,
+/// weight), strongest first.
+fn retrieve(
+    graph: &dyn TopologyGraph,
+    terms: &[String],
+    max_results: usize,
+) -> Vec<(NodeId, NodeId, f64)> {
+    let mut retrieved: Vec<(NodeId, NodeId, f64)> = terms
+        .iter()
+        .flat_map(|term| graph.find_nodes_by_exact_label(term))
+        .flat_map(|seed| {
+            graph
+                .neighbors(&seed)
+                .into_iter()
+                .map(move |(node, edge)| (seed, node, edge.weight))
+        })
+        .collect();
+    retrieved.sort_by(|a, b| b.2.total_cmp(&a.2));
+    retrieved.truncate(max_results);
+    retrieved
+}
+
+/// Strengthen what a query retrieved, as the query engine does.
+fn reinforce_retrieved(colony: &mut Colony, retrieved: &[(NodeId, NodeId, f64)]) {
+    let nodes: Vec<(NodeId, u64)> = retrieved.iter().map(|(_, node, _)| (*node, 1)).collect();
+    let edges: Vec<(NodeId, NodeId, f64)> = retrieved
+        .iter()
+        .map(|(seed, node, _)| (*seed, *node, 0.05))
+        .collect();
+    colony.reinforce_retrieval(&nodes, &[], &edges);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corpus::Corpus;
+    use phago_agents::digester::Digester;
+
+    /// The embedded corpus, digested.
+    fn digested_colony() -> Colony {
+        let mut colony = Colony::new();
+        let corpus = Corpus::inline_corpus();
+        corpus.ingest_into(&mut colony);
+        for doc in &corpus.documents {
+            colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(80)));
+        }
+        colony.run(15);
+        colony
+    }
+
+    #[test]
+    fn histogram_keeps_latencies_within_a_bucket() {
+        for us in [0, 63, 64, 65, 127, 128, 1_000, 123_456, u64::MAX] {
+            let bucket = bucket_of(us);
+            assert!(highest_in(bucket) >= us, "{us} above its bucket");
+            assert!(
+                bucket == 0 || highest_in(bucket - 1) < us,
+                "{us} below its bucket"
+            );
+        }
+
+        let mut histogram = LatencyHistogram::new();
+        for us in 1..=1000 {
+            histogram.record(Duration::from_micros(us));
+        }
+        let p50 = histogram.percentile(50.0).as_micros() as f64;
+        assert!((500.0..=500.0 * 33.0 / 32.0).contains(&p50), "p50 {p50}");
+        assert_eq!(histogram.percentile(100.0), Duration::from_micros(1000));
+        assert_eq!(histogram.max(), Duration::from_micros(1000));
+
+        let mut merged = LatencyHistogram::new();
+        merged.merge(&histogram);
+        merged.merge(&histogram);
+        assert_eq!(merged.len(), 2000);
+        assert_eq!(merged.percentile(50.0), histogram.percentile(50.0));
+    }
+
+    #[test]
+    fn load_test_records_every_query() {
+        let query_set: Vec<String> = ["cell membrane", "protein", "energy", "dna replication"]
+            .iter()
+            .map(|q| q.to_string())
+            .collect();
+        let mut reports = Vec::new();
+        for (lock_mode, reinforce) in [(LockMode::Mutex, true), (LockMode::ReadView, false)] {
+            let config = QueryLoadConfig {
+                concurrency: 2,
+                duration: Duration::from_secs(2),
+                query_set: query_set.clone(),
+                reinforce,
+                lock_mode,
+                ..Default::default()
+            };
+            let report = query_load_test(digested_colony(), &config).unwrap();
+            assert!(!report.histogram.is_empty());
+            assert_eq!(report.queries, report.histogram.len());
+            assert_eq!(report.errors, 0);
+            assert!(report.results > 0);
+            assert!(report.latency.p50_us <= report.latency.p99_us);
+            assert!(report.latency.p99_us <= report.latency.max_us);
+            reports.push(report);
+        }
+
+        let csv = query_load_csv(&reports);
+        assert!(csv.contains("\nmutex+reinforce,2,"));
+        assert!(csv.contains("\nread_view,2,"));
+        assert!(reports[0].to_json().unwrap().contains("\"p99_us\""));
+
+        let no_queries = QueryLoadConfig {
+            query_set: vec!["  ".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            query_load_test(Colony::new(), &no_queries).unwrap_err(),
+            QueryLoadError::NoQueries
+        );
+    }
+}
@@ -0,0 +1,482 @@
+//! Backup bundles — a whole knowledge base in one file.
+//!
+//! A bundle is a gzipped tar archive (`tar xzf` can unpack it) holding a
+//! `manifest.json` followed by named entries: the session's [`GraphState`]
+//! (graph, agents and documents), the [`ColonyConfig`], and anything else
+//! the caller adds, such as the project's `phago.toml` or a dump of a
+//! vector store. The manifest records the bundle format version and a
+//! size and CRC-32 per entry; [`Bundle::read_from`] rejects bundles whose
+//! entries are missing, unlisted or fail their checksum, and bundles
+//! written by a newer format it cannot migrate.
+//!
+//! Requires the `bundle` feature.
+
+use crate::colony::ColonyConfig;
+use crate::session::GraphState;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Format version written by this build.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Entry holding the manifest, always first in the archive.
+pub const MANIFEST_ENTRY: &str = "manifest.json";
+/// Entry holding the session's [`GraphState`].
+pub const SESSION_ENTRY: &str = "session.json";
+/// Entry holding the [`ColonyConfig`].
+pub const COLONY_CONFIG_ENTRY: &str = "colony_config.json";
+
+/// Tar block size.
+const BLOCK: usize = 512;
+/// Longest entry name a plain ustar header holds.
+const MAX_NAME_LEN: usize = 100;
+
+/// Errors reading or writing a bundle.
+#[derive(Debug)]
+pub enum BundleError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// The archive is not a well-formed bundle.
+    Malformed(String),
+    /// The bundle was written by a newer, unknown format.
+    UnsupportedVersion(u32),
+    /// An entry's contents do not match its manifest checksum.
+    ChecksumMismatch(String),
+    /// A required entry is absent.
+    MissingEntry(String),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Io(e) => write!(f, "bundle I/O error: {e}"),
+            BundleError::Json(e) => write!(f, "bundle entry is not valid JSON: {e}"),
+            BundleError::Malformed(msg) => write!(f, "malformed bundle: {msg}"),
+            BundleError::UnsupportedVersion(v) => write!(
+                f,
+                "bundle format version {v} is newer than supported ({BUNDLE_FORMAT_VERSION})"
+            ),
+            BundleError::ChecksumMismatch(name) => {
+                write!(f, "bundle entry '{name}' is corrupt (checksum mismatch)")
+            }
+            BundleError::MissingEntry(name) => write!(f, "bundle has no '{name}' entry"),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+impl From<io::Error> for BundleError {
+    fn from(e: io::Error) -> Self {
+        BundleError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for BundleError {
+    fn from(e: serde_json::Error) -> Self {
+        BundleError::Json(e)
+    }
+}
+
+/// Size and checksum of one bundle entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleEntryInfo {
+    pub name: String,
+    pub size: u64,
+    pub crc32: u32,
+}
+
+/// Describes a bundle's contents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    /// Name the bundle was exported under (e.g. the session name).
+    pub name: String,
+    /// Wall-clock export time, in milliseconds since the Unix epoch.
+    pub created_at_ms: u64,
+    /// Version of Phago that wrote the bundle.
+    pub phago_version: String,
+    /// Every entry other than the manifest, in archive order.
+    pub entries: Vec<BundleEntryInfo>,
+}
+
+/// An in-memory bundle: named entries plus the name it is exported under.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bundle {
+    name: String,
+    created_at_ms: u64,
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+impl Bundle {
+    /// An empty bundle.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            created_at_ms: now_ms(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// A bundle holding a session and the config its colony ran with.
+    pub fn from_session(
+        name: impl Into<String>,
+        state: &GraphState,
+        config: &ColonyConfig,
+    ) -> Result<Self, BundleError> {
+        let mut bundle = Self::new(name);
+        bundle.insert_json(SESSION_ENTRY, state)?;
+        bundle.insert_json(COLONY_CONFIG_ENTRY, config)?;
+        Ok(bundle)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Add or replace an entry.
+    ///
+    /// # Panics
+    ///
+    /// If `name` is empty, is the manifest's name, or is longer than 100
+    /// bytes (the limit of a tar header).
+    pub fn insert(&mut self, name: impl Into<String>, contents: Vec<u8>) {
+        let name = name.into();
+        assert!(
+            !name.is_empty() && name != MANIFEST_ENTRY && name.len() <= MAX_NAME_LEN,
+            "invalid bundle entry name: {name:?}"
+        );
+        self.entries.insert(name, contents);
+    }
+
+    /// Add or replace an entry holding `value` as pretty JSON.
+    pub fn insert_json<T: Serialize>(&mut self, name: &str, value: &T) -> Result<(), BundleError> {
+        self.insert(name, serde_json::to_vec_pretty(value)?);
+        Ok(())
+    }
+
+    /// An entry's contents.
+    pub fn entry(&self, name: &str) -> Option<&[u8]> {
+        self.entries.get(name).map(Vec::as_slice)
+    }
+
+    /// Parse an entry as JSON, or `None` if the bundle lacks it.
+    pub fn entry_json<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, BundleError> {
+        self.entry(name)
+            .map(|bytes| serde_json::from_slice(bytes).map_err(BundleError::from))
+            .transpose()
+    }
+
+    /// Names of all entries, sorted.
+    pub fn entry_names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// The bundled session.
+    pub fn session(&self) -> Result<GraphState, BundleError> {
+        self.entry_json(SESSION_ENTRY)?
+            .ok_or_else(|| BundleError::MissingEntry(SESSION_ENTRY.to_string()))
+    }
+
+    /// The bundled colony config, if any.
+    pub fn colony_config(&self) -> Result<Option<ColonyConfig>, BundleError> {
+        self.entry_json(COLONY_CONFIG_ENTRY)
+    }
+
+    /// The manifest describing the current entries.
+    pub fn manifest(&self) -> BundleManifest {
+        BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            name: self.name.clone(),
+            created_at_ms: self.created_at_ms,
+            phago_version: env!("CARGO_PKG_VERSION").to_string(),
+            entries: self
+                .entries
+                .iter()
+                .map(|(name, contents)| BundleEntryInfo {
+                    name: name.clone(),
+                    size: contents.len() as u64,
+                    crc32: crc32fast::hash(contents),
+                })
+                .collect(),
+        }
+    }
+
+    /// Write the bundle as a gzipped tar archive.
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<BundleManifest, BundleError> {
+        let manifest = self.manifest();
+        let mut gz = GzEncoder::new(writer, Compression::default());
+        write_tar_entry(
+            &mut gz,
+            MANIFEST_ENTRY,
+            &serde_json::to_vec_pretty(&manifest)?,
+        )?;
+        for (name, contents) in &self.entries {
+            write_tar_entry(&mut gz, name, contents)?;
+        }
+        // End of archive: two zero blocks
+        gz.write_all(&[0; 2 * BLOCK])?;
+        gz.finish()?.flush()?;
+        Ok(manifest)
+    }
+
+    /// Read and verify a bundle written by [`write_to`](Self::write_to).
+    pub fn read_from<R: Read>(reader: R) -> Result<Self, BundleError> {
+        let mut gz = GzDecoder::new(reader);
+        let (first, manifest) = read_tar_entry(&mut gz)?
+            .ok_or_else(|| BundleError::MissingEntry(MANIFEST_ENTRY.to_string()))?;
+        if first != MANIFEST_ENTRY {
+            return Err(BundleError::MissingEntry(MANIFEST_ENTRY.to_string()));
+        }
+        let manifest = migrate_manifest(serde_json::from_slice(&manifest)?)?;
+
+        let mut entries = BTreeMap::new();
+        while let Some((name, contents)) = read_tar_entry(&mut gz)? {
+            if entries.insert(name.clone(), contents).is_some() {
+                return Err(BundleError::Malformed(format!("duplicate entry '{name}'")));
+            }
+        }
+
+        for info in &manifest.entries {
+            let contents = entries
+                .get(&info.name)
+                .ok_or_else(|| BundleError::MissingEntry(info.name.clone()))?;
+            if contents.len() as u64 != info.size || crc32fast::hash(contents) != info.crc32 {
+                return Err(BundleError::ChecksumMismatch(info.name.clone()));
+            }
+        }
+        if let Some(extra) = entries
+            .keys()
+            .find(|name| !manifest.entries.iter().any(|info| &info.name == *name))
+        {
+            return Err(BundleError::Malformed(format!(
+                "entry '{extra}' is not listed in the manifest"
+            )));
+        }
+
+        Ok(Self {
+            name: manifest.name,
+            created_at_ms: manifest.created_at_ms,
+            entries,
+        })
+    }
+
+    /// Write the bundle to a file.
+    pub fn save(&self, path: &Path) -> Result<BundleManifest, BundleError> {
+        let file = std::fs::File::create(path)?;
+        self.write_to(io::BufWriter::new(file))
+    }
+
+    /// Read and verify a bundle file.
+    pub fn load(path: &Path) -> Result<Self, BundleError> {
+        let file = std::fs::File::open(path)?;
+        Self::read_from(io::BufReader::new(file))
+    }
+}
+
+/// Bring a manifest written by an older format up to the current one.
+///
+/// Version 1 is the only format so far; later versions add their
+/// migrations here.
+fn migrate_manifest(value: serde_json::Value) -> Result<BundleManifest, BundleError> {
+    let version = value
+        .get("format_version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| BundleError::Malformed("manifest has no format_version".to_string()))?;
+    match version {
+        1 => Ok(serde_json::from_value(value)?),
+        v => Err(BundleError::UnsupportedVersion(v as u32)),
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Write `contents` as a regular file in ustar format.
+fn write_tar_entry<W: Write>(writer: &mut W, name: &str, contents: &[u8]) -> io::Result<()> {
+    let mut header = [0u8; BLOCK];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut header[100..108], 0o644);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], contents.len() as u64);
+    write_octal(&mut header[136..148], 0);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // Checksum is computed with its own field filled with spaces
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+    write_octal(&mut header[148..155], checksum as u64);
+    header[155] = b' ';
+
+    writer.write_all(&header)?;
+    writer.write_all(contents)?;
+    writer.write_all(&[0; BLOCK][..padding(contents.len())])
+}
+
+/// Read the next regular file, or `None` at the end-of-archive marker.
+fn read_tar_entry<R: Read>(reader: &mut R) -> Result<Option<(String, Vec<u8>)>, BundleError> {
+    let mut header = [0u8; BLOCK];
+    reader.read_exact(&mut header)?;
+    if header.iter().all(|b| *b == 0) {
+        return Ok(None);
+    }
+
+    let stored = read_octal(&header[148..156])?;
+    let mut blank = header;
+    blank[148..156].fill(b' ');
+    if blank.iter().map(|b| *b as u64).sum::<u64>() != stored {
+        return Err(BundleError::Malformed("corrupt entry header".to_string()));
+    }
+    if !matches!(header[156], b'0' | 0) {
+        return Err(BundleError::Malformed(
+            "unexpected non-file entry".to_string(),
+        ));
+    }
+
+    let name_len = header[..MAX_NAME_LEN]
+        .iter()
+        .position(|b| *b == 0)
+        .unwrap_or(MAX_NAME_LEN);
+    let name = String::from_utf8(header[..name_len].to_vec())
+        .map_err(|_| BundleError::Malformed("entry name is not UTF-8".to_string()))?;
+    let size = read_octal(&header[124..136])? as usize;
+
+    let mut contents = vec![0; size];
+    reader.read_exact(&mut contents)?;
+    let mut pad = [0u8; BLOCK];
+    reader.read_exact(&mut pad[..padding(size)])?;
+    Ok(Some((name, contents)))
+}
+
+fn padding(len: usize) -> usize {
+    (BLOCK - len % BLOCK) % BLOCK
+}
+
+/// Zero-padded octal, NUL-terminated, filling `field`.
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{:0width$o}", value, width = field.len() - 1);
+    field[..digits.len()].copy_from_slice(digits.as_bytes());
+    field[digits.len()] = 0;
+}
+
+fn read_octal(field: &[u8]) -> Result<u64, BundleError> {
+    let text: String = field
+        .iter()
+        .take_while(|b| **b != 0)
+        .map(|b| *b as char)
+        .collect();
+    u64::from_str_radix(text.trim(), 8)
+        .map_err(|_| BundleError::Malformed(format!("bad octal field {text:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colony::Colony;
+    use crate::session::{restore_into_colony, save_session};
+    use phago_core::types::Position;
+
+    fn colony() -> Colony {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Cells",
+            "The cell membrane controls transport of proteins into the cell.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            phago_agents::digester::Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+        ));
+        colony.run(15);
+        colony
+    }
+
+    fn state(colony: &Colony) -> GraphState {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        save_session(colony, &path, &[]).unwrap();
+        crate::session::load_session(&path).unwrap()
+    }
+
+    fn round_trip(bundle: &Bundle) -> Result<Bundle, BundleError> {
+        let mut bytes = Vec::new();
+        bundle.write_to(&mut bytes).unwrap();
+        Bundle::read_from(bytes.as_slice())
+    }
+
+    #[test]
+    fn session_round_trips_through_bundle() {
+        let colony = colony();
+        let mut bundle =
+            Bundle::from_session("biology", &state(&colony), &ColonyConfig::default()).unwrap();
+        bundle.insert("phago.toml", b"[query]\nlog = true\n".to_vec());
+        // Entries over a block long need padding on both sides
+        bundle.insert("vectors.jsonl", vec![b'x'; 3 * BLOCK + 7]);
+
+        let restored = round_trip(&bundle).unwrap();
+        assert_eq!(restored, bundle);
+        assert_eq!(restored.name(), "biology");
+        assert!(restored.colony_config().unwrap().is_some());
+
+        let mut copy = Colony::new();
+        restore_into_colony(&mut copy, &restored.session().unwrap(), None);
+        assert_eq!(copy.stats().graph_nodes, colony.stats().graph_nodes);
+        assert_eq!(copy.stats().graph_edges, colony.stats().graph_edges);
+    }
+
+    #[test]
+    fn corrupt_entries_are_rejected() {
+        let mut bundle = Bundle::new("biology");
+        bundle.insert("notes.txt", b"cell membrane".to_vec());
+        let mut manifest = bundle.manifest();
+        manifest.entries[0].crc32 ^= 1;
+
+        // Hand-write an archive whose manifest disagrees with its entry
+        let mut bytes = Vec::new();
+        {
+            let mut gz = GzEncoder::new(&mut bytes, Compression::default());
+            write_tar_entry(
+                &mut gz,
+                MANIFEST_ENTRY,
+                &serde_json::to_vec(&manifest).unwrap(),
+            )
+            .unwrap();
+            write_tar_entry(&mut gz, "notes.txt", b"cell membrane").unwrap();
+            gz.write_all(&[0; 2 * BLOCK]).unwrap();
+            gz.finish().unwrap();
+        }
+        assert!(matches!(
+            Bundle::read_from(bytes.as_slice()),
+            Err(BundleError::ChecksumMismatch(name)) if name == "notes.txt"
+        ));
+    }
+
+    #[test]
+    fn newer_formats_are_refused() {
+        let mut manifest = Bundle::new("biology").manifest();
+        manifest.format_version = BUNDLE_FORMAT_VERSION + 1;
+        let value = serde_json::to_value(&manifest).unwrap();
+        assert!(matches!(
+            migrate_manifest(value),
+            Err(BundleError::UnsupportedVersion(v)) if v == BUNDLE_FORMAT_VERSION + 1
+        ));
+        assert!(matches!(
+            Bundle::read_from(&b"not a bundle"[..]),
+            Err(BundleError::Io(_))
+        ));
+    }
+}
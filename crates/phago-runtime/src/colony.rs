@@ -9,23 +9,77 @@
 //! 2. The colony processes all actions (moves, digestions, signals)
 //! 3. Dead agents are removed, death signals collected
 //! 4. The substrate decays signals and traces
-//! 5. The tick counter advances
-
-use crate::substrate_impl::SubstrateImpl;
+//! 5. The tick counter advances and buffered graph writes are flushed
+
+use crate::backend::{create_backend, BackendConfig, BackendError};
+use crate::community::{CommunityAlgorithm, CommunityConfig, CommunityResult, CommunityTracker};
+use crate::compaction::{self, CompactionConfig, CompactionPlan, CompactionStrategy};
+use crate::concept_index::ConceptIndex;
+use crate::decay::{
+    self, AdaptiveDecayConfig, DecayController, DecayLoad, DecayRates, DecayReport,
+};
+use crate::dedup::{
+    BatchDocument, DedupConfig, DedupIndex, DedupPolicy, IngestOutcome, IngestReport,
+};
+use crate::embedding_cache;
+use crate::embedding_store::{EmbeddingStorage, EmbeddingTable};
+use crate::exploration::{ExplorationController, ExplorationSchedule};
+use crate::graph_algorithms::{ConceptPath, PathCost, PathError};
+use crate::import::{layout_position, ImportReport, TripleSet, TENTATIVE_WEIGHT};
+use crate::label_policy::{LabelPolicy, ORIGINAL_LABEL_SOURCE};
+use crate::maintenance::{self as maintenance, EdgeDecay, Maintenance, Pass};
+use crate::metrics::PromRegistry;
+use crate::placement::{self, DocPlacement};
+use crate::plugin::{
+    ActionContext, ActionDecision, ColonyPlugin, CustomActionHandler, PluginContext,
+};
+use crate::provenance::{EdgeProvenance, EdgeSource, SourceRecord};
+use crate::query_log::{QueryLog, QueryLogConfig, QueryLogEntry, QuerySource};
+use crate::read_view::ColonyView;
+use crate::region::{Region, RegionError, RegionPolicy, RegionShape};
+use crate::reinforcement::{apply_reinforcement, WeightUpdate};
+use crate::replay::{Replay, ReplayInput, ReplayRecorder};
+use crate::sampling::{self, Sample, SampleConfig, SampleCoverage};
+use crate::snapshot::{SnapshotMode, SnapshotSeries, StatsSnapshot};
+use crate::substrate_impl::{SignalCoalescing, SubstrateImpl};
+use crate::synonyms::SynonymConfig;
+use crate::trace_payload;
+use crate::transfer::TransferConfig;
+use crate::vocabulary::VocabularyReport;
+use crate::wiring::{LabelResolver, PendingWiring};
 use phago_agents::fitness::FitnessTracker;
+use phago_agents::genome::AgentGenome;
+use phago_agents::registry::{AgentRegistry, AgentSpec, RegistryError};
+use phago_agents::spawn::{SpawnPolicy, SpawnPolicySpec};
 use phago_core::agent::Agent;
+use phago_core::primitives::{DigestionLimits, EmergeConfig, QuorumStatus};
 use phago_core::semantic::{compute_semantic_weight, SemanticWiringConfig};
-use phago_core::substrate::Substrate;
-use phago_core::topology::TopologyGraph;
+use phago_core::signal::SignalKindInfo;
+use phago_core::substrate::{Ambient, Substrate};
+use phago_core::tokenize::{Tokenizer, TokenizerConfig};
+use phago_core::topology::{TopologyError, TopologyGraph};
 use phago_core::types::*;
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 /// Event emitted by the colony during simulation.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ColonyEvent {
-    /// An agent was spawned.
-    Spawned { id: AgentId, agent_type: String },
+    /// The spawn policy bred an agent to replace one that died.
+    Spawned {
+        id: AgentId,
+        agent_type: String,
+        /// The fittest living agent it descends from; none for policies
+        /// that make genomes up.
+        #[serde(default)]
+        parent: Option<AgentId>,
+        #[serde(default)]
+        generation: u32,
+    },
     /// An agent moved to a new position.
     Moved { id: AgentId, to: Position },
     /// An agent engulfed a document.
@@ -63,7 +117,12 @@ pub enum ColonyEvent {
     CapabilityIntegrated {
         agent_id: AgentId,
         from_agent: AgentId,
-        terms_count: usize,
+        /// Terms the agent took, after [`TransferConfig`] filtering.
+        #[serde(alias = "terms_count")]
+        terms_accepted: usize,
+        /// Terms the deposit offered.
+        #[serde(default)]
+        terms_offered: usize,
     },
     /// An agent absorbed another through symbiosis.
     Symbiosis {
@@ -78,10 +137,310 @@ pub enum ColonyEvent {
         permeability: f64,
         terms_externalized: usize,
     },
+    /// A stale insight was removed because its supporting edges decayed.
+    InsightPruned {
+        node_id: NodeId,
+        label: String,
+        support: f64,
+    },
+    /// A concept that was neither presented nor retrieved for too long,
+    /// and no longer anchored by a strong edge, was forgotten.
+    NodePruned {
+        node_id: NodeId,
+        label: String,
+        retention: f64,
+    },
+    /// A re-ingested document's new version was digested; what only the
+    /// previous version contributed was taken back.
+    DocumentUpdated {
+        id: DocumentId,
+        version: u32,
+        /// Concepts the new version presented that the previous did not.
+        #[serde(default)]
+        concepts_added: Vec<String>,
+        /// Concepts the previous version presented that the new one does not.
+        #[serde(default)]
+        concepts_removed: Vec<String>,
+    },
+    /// A document's TTL ran out (see [`Colony::set_document_ttl`]). It was
+    /// removed, and what it alone contributed was taken back.
+    DocumentExpired {
+        id: DocumentId,
+        /// Concepts no other document supported, now removed.
+        nodes_removed: usize,
+        /// Edges it had wired, weakened by its share or removed.
+        edges_weakened: usize,
+    },
+    /// A dying agent gave back a document it had engulfed but not yet
+    /// presented; the document is undigested again.
+    WorkReleased {
+        agent_id: AgentId,
+        document: DocumentId,
+    },
+    /// Concepts whose labels are variants of one another were merged into
+    /// one node; their labels became its aliases.
+    NodesMerged {
+        node_id: NodeId,
+        label: String,
+        merged: Vec<String>,
+    },
+    /// An agent's action could not be applied, e.g. it named a document
+    /// or node that does not exist. The colony carries on; the failure is
+    /// counted in [`ColonyStats::errors`].
+    ActionFailed {
+        agent_id: AgentId,
+        /// [`AgentAction::kind`] of the action, `"integrate_capability"`
+        /// for a capability deposit that could not be read, or `"spawn"`
+        /// for a replacement of the agent the registry could not build.
+        action_kind: String,
+        reason: String,
+    },
+    /// Two concepts were connected from outside the colony (see
+    /// [`Colony::connect_concepts`]).
+    Connected {
+        from: NodeId,
+        to: NodeId,
+        /// The edge's weight afterwards.
+        weight: f64,
+        /// Whether the edge is new rather than strengthened.
+        created_edge: bool,
+        /// Labels of the concepts created for it.
+        #[serde(default)]
+        created_nodes: Vec<String>,
+    },
+    /// A note was attached to a node (see [`Colony::annotate`]).
+    Annotated {
+        node_id: NodeId,
+        label: String,
+        source: String,
+    },
+    /// An agent presented a scored anomaly (see [`Colony::anomaly_score`]).
+    AnomalyDetected {
+        agent_id: AgentId,
+        label: String,
+        score: f64,
+        /// The document the anomaly is about, if it is about one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        document: Option<DocumentId>,
+    },
+}
+
+impl ColonyEvent {
+    /// Snake-case name of the event type, e.g. `"tick_complete"`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ColonyEvent::Spawned { .. } => "spawned",
+            ColonyEvent::Moved { .. } => "moved",
+            ColonyEvent::Engulfed { .. } => "engulfed",
+            ColonyEvent::Presented { .. } => "presented",
+            ColonyEvent::Deposited { .. } => "deposited",
+            ColonyEvent::Wired { .. } => "wired",
+            ColonyEvent::Died { .. } => "died",
+            ColonyEvent::TickComplete { .. } => "tick_complete",
+            ColonyEvent::CapabilityExported { .. } => "capability_exported",
+            ColonyEvent::CapabilityIntegrated { .. } => "capability_integrated",
+            ColonyEvent::Symbiosis { .. } => "symbiosis",
+            ColonyEvent::Dissolved { .. } => "dissolved",
+            ColonyEvent::InsightPruned { .. } => "insight_pruned",
+            ColonyEvent::NodePruned { .. } => "node_pruned",
+            ColonyEvent::DocumentUpdated { .. } => "document_updated",
+            ColonyEvent::DocumentExpired { .. } => "document_expired",
+            ColonyEvent::WorkReleased { .. } => "work_released",
+            ColonyEvent::NodesMerged { .. } => "nodes_merged",
+            ColonyEvent::ActionFailed { .. } => "action_failed",
+            ColonyEvent::Connected { .. } => "connected",
+            ColonyEvent::Annotated { .. } => "annotated",
+            ColonyEvent::AnomalyDetected { .. } => "anomaly_detected",
+        }
+    }
+
+    fn action_failed(agent_id: AgentId, action_kind: &str, reason: impl ToString) -> Self {
+        ColonyEvent::ActionFailed {
+            agent_id,
+            action_kind: action_kind.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// An agent's presentation, put into the graph over as many ticks as
+/// [`ColonyConfig::max_fragments_per_agent_per_tick`] takes.
+struct Presentation {
+    agent_id: AgentId,
+    fragments: Vec<FragmentPresentation>,
+    /// The document (and version) the agent was digesting when it presented.
+    digesting: Option<(DocumentId, u32)>,
+    /// Nodes of the fragments put in so far, in order.
+    node_ids: Vec<NodeId>,
+    /// Qualified nodes added for the presentation, each with an
+    /// unqualified node referring to it from an earlier one.
+    references: Vec<(NodeId, NodeId)>,
+    contribution: DocumentContribution,
+}
+
+/// Weight and co-activations a document added to one edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EdgeContribution {
+    pub weight: f64,
+    pub co_activations: u64,
+}
+
+/// What digesting a document added to the graph, kept so a re-ingested
+/// version can take back what it no longer contains.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentContribution {
+    /// Presentations per node.
+    pub nodes: HashMap<NodeId, u64>,
+    /// Wiring per edge, keyed by its endpoints in ascending order.
+    pub edges: HashMap<(NodeId, NodeId), EdgeContribution>,
+}
+
+impl DocumentContribution {
+    /// Count one more presentation of `node`.
+    pub fn add_node(&mut self, node: NodeId, count: u64) {
+        *self.nodes.entry(node).or_insert(0) += count;
+    }
+
+    /// Count wiring added to the edge between `a` and `b`.
+    pub fn add_edge(&mut self, a: NodeId, b: NodeId, weight: f64, co_activations: u64) {
+        let key = if a <= b { (a, b) } else { (b, a) };
+        let entry = self.edges.entry(key).or_default();
+        entry.weight += weight;
+        entry.co_activations += co_activations;
+    }
+
+    /// Add everything `other` contributed.
+    pub fn merge(&mut self, other: DocumentContribution) {
+        for (node, count) in other.nodes {
+            self.add_node(node, count);
+        }
+        for ((a, b), edge) in other.edges {
+            self.add_edge(a, b, edge.weight, edge.co_activations);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+
+    /// Credit what was contributed to `from` to `into` instead, e.g.
+    /// because the nodes were merged. Edges between the two are dropped.
+    pub fn replace_node(&mut self, from: NodeId, into: NodeId) {
+        if let Some(count) = self.nodes.remove(&from) {
+            self.add_node(into, count);
+        }
+        let moved: Vec<((NodeId, NodeId), EdgeContribution)> = self
+            .edges
+            .iter()
+            .filter(|((a, b), _)| *a == from || *b == from)
+            .map(|(key, edge)| (*key, *edge))
+            .collect();
+        for ((a, b), edge) in moved {
+            self.edges.remove(&(a, b));
+            let other = if a == from { b } else { a };
+            if other != into && other != from {
+                self.add_edge(into, other, edge.weight, edge.co_activations);
+            }
+        }
+    }
+
+    /// Drop what was contributed to `node`, e.g. because it was pruned.
+    pub fn forget_node(&mut self, node: NodeId) {
+        self.nodes.remove(&node);
+        self.edges.retain(|(a, b), _| *a != node && *b != node);
+    }
+}
+
+/// Failures the colony recovered from (see [`ColonyEvent::ActionFailed`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorCounters {
+    /// Agent actions that could not be applied, by action kind.
+    #[serde(default)]
+    pub action_failures: BTreeMap<String, u64>,
+}
+
+impl ErrorCounters {
+    /// Failures of every kind.
+    pub fn total(&self) -> u64 {
+        self.action_failures.values().sum()
+    }
+}
+
+/// Longest note [`Colony::annotate`] accepts, in characters.
+pub const MAX_ANNOTATION_CHARS: usize = 4000;
+
+/// Why [`Colony::connect_concepts`] or [`Colony::annotate`] changed
+/// nothing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphEditError {
+    /// A label is empty or whitespace only.
+    EmptyLabel,
+    /// No node has the label.
+    UnknownConcept(String),
+    /// Both labels name the same node.
+    SelfLoop(String),
+    /// An edge weight outside (0, 1].
+    InvalidWeight(f64),
+    /// The note is empty or whitespace only.
+    EmptyNote,
+    /// The note is longer than [`MAX_ANNOTATION_CHARS`].
+    NoteTooLong(usize),
+    /// The node already carries the same note from the same source.
+    DuplicateNote(String),
+    /// The graph refused the edge.
+    Topology(TopologyError),
+}
+
+impl std::fmt::Display for GraphEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphEditError::EmptyLabel => write!(f, "Label must not be empty"),
+            GraphEditError::UnknownConcept(label) => {
+                write!(f, "No concept matches '{}'", label)
+            }
+            GraphEditError::SelfLoop(label) => {
+                write!(f, "Cannot connect '{}' to itself", label)
+            }
+            GraphEditError::InvalidWeight(weight) => {
+                write!(f, "Weight must be in (0, 1], got {}", weight)
+            }
+            GraphEditError::EmptyNote => write!(f, "Note must not be empty"),
+            GraphEditError::NoteTooLong(len) => write!(
+                f,
+                "Note is {} characters long, at most {} are allowed",
+                len, MAX_ANNOTATION_CHARS
+            ),
+            GraphEditError::DuplicateNote(label) => {
+                write!(f, "'{}' already carries this note", label)
+            }
+            GraphEditError::Topology(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for GraphEditError {}
+
+/// When an agent was spawned and, once dead, when and why it died.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentLifespan {
+    pub agent_id: AgentId,
+    pub agent_type: String,
+    /// Tick the agent was spawned at.
+    pub born: Tick,
+    /// Tick the agent died in and why; `None` while it lives.
+    pub died: Option<(Tick, DeathCause)>,
+}
+
+impl AgentLifespan {
+    /// Ticks lived, or lived so far at `now` for a living agent.
+    pub fn ticks(&self, now: Tick) -> u64 {
+        let end = self.died.as_ref().map_or(now, |(tick, _)| *tick);
+        end.saturating_sub(self.born)
+    }
 }
 
 /// Statistics about the colony.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ColonyStats {
     pub tick: Tick,
     pub agents_alive: usize,
@@ -90,12 +449,76 @@ pub struct ColonyStats {
     pub graph_nodes: usize,
     pub graph_edges: usize,
     pub total_signals: usize,
+    /// Live signals per kind name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub signals_by_kind: BTreeMap<String, usize>,
     pub documents_total: usize,
     pub documents_digested: usize,
+    /// Approximate bytes held by substrate traces.
+    #[serde(default)]
+    pub trace_bytes: usize,
+    /// Traces evicted to stay within `max_trace_bytes`.
+    #[serde(default)]
+    pub traces_evicted: u64,
+    /// Emitted signals merged into a live one (see
+    /// [`ColonyConfig::signal_coalescing`]).
+    #[serde(default)]
+    pub signals_merged: u64,
+    /// Signals evicted to stay within the per-kind cap.
+    #[serde(default)]
+    pub signals_evicted: u64,
+    #[serde(default)]
+    pub errors: ErrorCounters,
+    /// Decay rates of the last tick; see [`Colony::decay_report`].
+    #[serde(default)]
+    pub decay: DecayRates,
+    /// Edges, nodes and agents the budgeted maintenance passes have yet to
+    /// reach; always 0 without a tick budget.
+    #[serde(default)]
+    pub maintenance_backlog: usize,
+    /// Documents with a TTL that have yet to expire.
+    #[serde(default)]
+    pub documents_expiring: usize,
+    /// The earliest tick a document is due to expire at, if any expires
+    /// by tick.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_expiry_tick: Option<Tick>,
+    /// How far agents exploit rather than explore; always 0 without an
+    /// exploration schedule (see [`ColonyConfig::exploration`]).
+    #[serde(default)]
+    pub exploitation: f64,
+}
+
+/// When a document expires; see [`Colony::set_document_ttl`]. A document
+/// expires at whichever deadline comes first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentExpiry {
+    /// Expires at the start of this tick.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tick: Option<Tick>,
+    /// Expires once the wall clock passes this many seconds since the Unix
+    /// epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unix_time: Option<u64>,
+}
+
+impl DocumentExpiry {
+    /// Whether either deadline has passed.
+    pub fn is_due(&self, tick: Tick, unix_time: u64) -> bool {
+        self.tick.is_some_and(|t| t <= tick) || self.unix_time.is_some_and(|t| t <= unix_time)
+    }
+}
+
+/// Seconds since the Unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 /// A serializable snapshot of an agent's state.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AgentSnapshot {
     pub id: AgentId,
     pub agent_type: String,
@@ -106,17 +529,31 @@ pub struct AgentSnapshot {
 }
 
 /// A serializable snapshot of a graph node.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NodeSnapshot {
     pub id: NodeId,
     pub label: String,
     pub node_type: NodeType,
     pub position: Position,
     pub access_count: u64,
+    /// Per-tag access counts (see [`NodeData::tags`]).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, u64>,
+    /// Tracked community, once communities have been detected (see
+    /// [`Colony::update_communities`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub community: Option<usize>,
+    /// The node's embedding, dequantized; left out unless the snapshot was
+    /// taken with [`Colony::snapshot_with_embeddings`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    /// Novelty score of a scored anomaly (see [`Colony::anomaly_score`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly_score: Option<f64>,
 }
 
 /// A serializable snapshot of a graph edge.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EdgeSnapshot {
     pub from_label: String,
     pub to_label: String,
@@ -124,14 +561,78 @@ pub struct EdgeSnapshot {
     pub co_activations: u64,
 }
 
-/// A complete serializable snapshot of the colony at a point in time.
+/// A concept label completing a typed prefix (see [`Colony::suggest_labels`]).
 #[derive(Debug, Clone, Serialize)]
+pub struct LabelSuggestion {
+    pub id: NodeId,
+    pub label: String,
+    pub node_type: NodeType,
+    pub access_count: u64,
+    pub degree: usize,
+    /// Ranking score: `ln(1 + access_count) + ln(1 + degree)`.
+    pub score: f64,
+    /// Labels of the most strongly connected neighbors, for previews.
+    pub top_neighbors: Vec<String>,
+}
+
+/// Neighbors included in each [`LabelSuggestion`].
+const SUGGESTION_NEIGHBORS: usize = 3;
+
+/// A complete serializable snapshot of the colony at a point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ColonySnapshot {
     pub tick: Tick,
     pub agents: Vec<AgentSnapshot>,
     pub nodes: Vec<NodeSnapshot>,
     pub edges: Vec<EdgeSnapshot>,
     pub stats: ColonyStats,
+    /// The world bounds, if the colony has any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_bounds: Option<Rect>,
+    /// Named regions of the field (see [`crate::region`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<Region>,
+    /// How much of the graph the snapshot shows, if it was sampled (see
+    /// [`Colony::sample_snapshot`]); unsampled snapshots show all of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<SampleCoverage>,
+}
+
+impl ColonySnapshot {
+    /// Serialize to a single-line JSON object.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Parse a snapshot written by [`ColonySnapshot::to_json`].
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Quorum state of one synthesizer's sensing region.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuorumRegion {
+    pub agent_id: AgentId,
+    #[serde(flatten)]
+    pub status: QuorumStatus,
+}
+
+/// How close the colony is to emergence, and what it has produced so far
+/// (see [`Colony::emergence_report`]).
+#[derive(Debug, Clone, Serialize)]
+pub struct EmergenceReport {
+    pub tick: Tick,
+    /// One region per living agent that senses quorum.
+    pub regions: Vec<QuorumRegion>,
+    /// Insight nodes created since the colony started.
+    pub insights_generated: u64,
+    /// Insight nodes currently in the graph.
+    pub insight_nodes: usize,
+    /// Tick at which any agent last reached quorum.
+    pub last_trigger_tick: Option<Tick>,
+    /// Colony-wide emergence defaults.
+    pub config: EmergeConfig,
 }
 
 /// Configuration for colony simulation parameters.
@@ -149,6 +650,16 @@ pub struct ColonyConfig {
     pub trace_decay_rate: f64,
     /// Threshold below which traces are removed (default: 0.01).
     pub trace_removal_threshold: f64,
+    /// Trace memory budget in bytes; the weakest traces are evicted when
+    /// it is exceeded. 0 means unlimited (default: 0).
+    #[serde(default)]
+    pub max_trace_bytes: usize,
+    /// Merge nearby signals of a kind and cap how many stay live (default:
+    /// input signals within 2.0 merge, at most 1000 live). `None` keeps
+    /// every emitted signal, for experiments that need them one by one.
+    /// See [`SignalCoalescing`].
+    #[serde(default = "default_signal_coalescing")]
+    pub signal_coalescing: Option<SignalCoalescing>,
     /// Rate at which edges decay per tick (default: 0.005).
     pub edge_decay_rate: f64,
     /// Threshold below which edges are pruned (default: 0.05).
@@ -161,6 +672,183 @@ pub struct ColonyConfig {
     pub max_edge_degree: usize,
     /// Semantic wiring configuration.
     pub semantic_wiring: SemanticWiringConfig,
+    /// Modulate agent boundaries and exchange vocabulary with the graph
+    /// every N ticks; 0 disables it (default: 1). Permeability changes
+    /// slowly, so large colonies can raise this to save time.
+    #[serde(default = "default_dissolution_interval")]
+    pub dissolution_interval: u64,
+    /// Run insight maintenance every N ticks; 0 disables it (default: 10).
+    #[serde(default = "default_insight_maintenance_interval")]
+    pub insight_maintenance_interval: u64,
+    /// Insights whose support falls below this are removed (default: 0.05).
+    #[serde(default = "default_insight_prune_threshold")]
+    pub insight_prune_threshold: f64,
+    /// Half-life, in ticks, of a node's retention since it was last presented
+    /// or retrieved; 0 disables node pruning (default: 0).
+    #[serde(default)]
+    pub node_staleness_ticks: u64,
+    /// Nodes whose retention (`access_count` halved every
+    /// `node_staleness_ticks` of idleness) falls below this are pruned (default: 0.5).
+    #[serde(default = "default_node_prune_threshold")]
+    pub node_prune_threshold: f64,
+    /// An edge at least this heavy keeps a stale node alive (default: 0.3).
+    #[serde(default = "default_node_anchor_edge_weight")]
+    pub node_anchor_edge_weight: f64,
+    /// Node types eligible for pruning (default: concepts only).
+    #[serde(default = "default_node_prune_types")]
+    pub node_prune_types: Vec<NodeType>,
+    /// How reinforcement changes existing edge weights (default: capped additive).
+    #[serde(default)]
+    pub weight_update: WeightUpdate,
+    /// Storage backend for the knowledge graph (default: in-memory).
+    #[serde(default)]
+    pub backend: BackendConfig,
+    /// Record queries in a bounded [`QueryLog`] (default: disabled).
+    #[serde(default)]
+    pub query_log: QueryLogConfig,
+    /// Quorum and insight thresholds for agents spawned without their own
+    /// (default: [`EmergeConfig::default`]).
+    #[serde(default)]
+    pub emerge: EmergeConfig,
+    /// How spawned agents and queries split text into terms (default:
+    /// English stopwords, terms of three or more characters).
+    #[serde(default)]
+    pub tokenizer: TokenizerConfig,
+    /// Per-document fragment and wiring bounds for agents spawned without
+    /// their own (default: unbounded, token-shape filter on). The pair cap
+    /// applies to every presentation.
+    #[serde(default)]
+    pub digestion: DigestionLimits,
+    /// Merge concepts whose labels are variants of one another every N
+    /// ticks (default: disabled). See [`Colony::compact_labels`].
+    #[serde(default)]
+    pub compaction: CompactionConfig,
+    /// Detect communities every N ticks, keeping their IDs stable
+    /// (default: disabled). See [`Colony::update_communities`].
+    #[serde(default)]
+    pub communities: CommunityConfig,
+    /// Rectangle agents and documents are kept inside (default: unbounded).
+    #[serde(default)]
+    pub world_bounds: Option<Rect>,
+    /// What happens to agents moving past `world_bounds` (default: clamp).
+    /// Under `Wrap`, sensing also measures distance the short way round.
+    #[serde(default)]
+    pub boundary_behavior: BoundaryBehavior,
+    /// Where documents ingested without a position go (default: near
+    /// similar documents). See [`Colony::ingest_document_auto`].
+    #[serde(default)]
+    pub placement: DocPlacement,
+    /// Adapt the signal, trace and edge decay rates to colony load instead
+    /// of using the fixed rates above (default: fixed rates). The fixed
+    /// rates are what an adaptive colony decays at within its budgets.
+    #[serde(default)]
+    pub adaptive_decay: Option<AdaptiveDecayConfig>,
+    /// Anneal agents from exploring to exploiting as documents get
+    /// digested and discovery slows (default: off, agents explore by
+    /// their own bias only). See [`crate::exploration`].
+    #[serde(default)]
+    pub exploration: Option<ExplorationSchedule>,
+    /// Buffer co-occurrence wiring during each tick and write every edge
+    /// once, resolving labels through a per-tick cache (default: off).
+    /// Builds the same graph as incremental wiring, faster on bulk
+    /// ingestion. Ignored under [`WeightUpdate::Normalized`], whose
+    /// competition between edges cannot be deferred.
+    #[serde(default)]
+    pub deferred_wiring: bool,
+    /// Which terms of a capability deposit integrating agents take, by the
+    /// exporter's fitness and the terms' reinforcement (default: all).
+    #[serde(default)]
+    pub transfer: TransferConfig,
+    /// Time, in milliseconds, a tick may spend before edge decay, degree
+    /// pruning and dissolution stop for it and carry on next tick (default:
+    /// none, all of it every tick). Agents still act every tick; see
+    /// [`Colony::finish_maintenance`].
+    #[serde(default)]
+    pub tick_budget_ms: Option<u64>,
+    /// Fragments an agent may put into the graph per tick; 0 means
+    /// unlimited (default: 0). The rest of a larger presentation waits
+    /// for later ticks and is wired as one presentation once all of it
+    /// is in.
+    #[serde(default)]
+    pub max_fragments_per_agent_per_tick: usize,
+    /// How node embeddings are stored (default: full precision). Quantized
+    /// formats trade a bounded error in semantic wiring for memory; see
+    /// [`crate::embedding_store`].
+    #[serde(default)]
+    pub embedding_storage: EmbeddingStorage,
+    /// Terms whose embeddings the colony caches for agents, the least
+    /// recently used evicted beyond it (default: 4096; 0 turns the cache
+    /// off). See [`crate::embedding_cache`].
+    #[serde(default = "default_embedding_cache_capacity")]
+    pub embedding_cache_capacity: usize,
+    /// Keep an inverted index of node labels so TF-IDF retrieval only
+    /// touches the nodes carrying the query terms (default: true). When
+    /// off, every query scans all labels; see [`crate::inverted_index`].
+    #[serde(default = "default_term_index")]
+    pub term_index: bool,
+    /// How presented labels become node labels (default: NFC, collapsed
+    /// whitespace, case kept, at most 120 characters). See
+    /// [`crate::label_policy`].
+    #[serde(default)]
+    pub labels: LabelPolicy,
+    /// Aliases presented labels are canonicalized from and queries are
+    /// expanded with (default: none). See [`crate::synonyms`].
+    #[serde(default)]
+    pub synonyms: SynonymConfig,
+    /// Record what each tick needs to be reproduced, for
+    /// [`Colony::replay`] (default: false). See [`crate::replay`].
+    #[serde(default)]
+    pub record_replay: bool,
+    /// Reinforcements remembered per edge, with the document or query
+    /// behind each; 0 turns this off (default: 8). See
+    /// [`crate::provenance`] for what it costs.
+    #[serde(default = "default_edge_provenance")]
+    pub edge_provenance: usize,
+    /// Replace agents that die with offspring the policy breeds, built
+    /// through the colony's agent registry (default: none, the dead are
+    /// not replaced). See [`Colony::genomes`].
+    #[serde(default)]
+    pub spawn_policy: Option<SpawnPolicySpec>,
+}
+
+fn default_term_index() -> bool {
+    true
+}
+
+fn default_embedding_cache_capacity() -> usize {
+    embedding_cache::DEFAULT_CAPACITY
+}
+
+fn default_edge_provenance() -> usize {
+    8
+}
+
+fn default_signal_coalescing() -> Option<SignalCoalescing> {
+    Some(SignalCoalescing::default())
+}
+
+fn default_dissolution_interval() -> u64 {
+    1
+}
+
+fn default_insight_maintenance_interval() -> u64 {
+    10
+}
+
+fn default_insight_prune_threshold() -> f64 {
+    0.05
+}
+
+fn default_node_prune_threshold() -> f64 {
+    0.5
+}
+
+fn default_node_anchor_edge_weight() -> f64 {
+    0.3
+}
+
+fn default_node_prune_types() -> Vec<NodeType> {
+    vec![NodeType::Concept]
 }
 
 impl Default for ColonyConfig {
@@ -170,25 +858,114 @@ impl Default for ColonyConfig {
             signal_removal_threshold: 0.01,
             trace_decay_rate: 0.02,
             trace_removal_threshold: 0.01,
+            max_trace_bytes: 0,
+            signal_coalescing: default_signal_coalescing(),
             edge_decay_rate: 0.005,
             edge_prune_threshold: 0.05,
             staleness_factor: 1.5,
             maturation_ticks: 50,
             max_edge_degree: 30,
             semantic_wiring: SemanticWiringConfig::default(),
+            dissolution_interval: default_dissolution_interval(),
+            insight_maintenance_interval: default_insight_maintenance_interval(),
+            insight_prune_threshold: default_insight_prune_threshold(),
+            node_staleness_ticks: 0,
+            node_prune_threshold: default_node_prune_threshold(),
+            node_anchor_edge_weight: default_node_anchor_edge_weight(),
+            node_prune_types: default_node_prune_types(),
+            weight_update: WeightUpdate::default(),
+            backend: BackendConfig::default(),
+            query_log: QueryLogConfig::default(),
+            emerge: EmergeConfig::default(),
+            tokenizer: TokenizerConfig::default(),
+            digestion: DigestionLimits::default(),
+            compaction: CompactionConfig::default(),
+            communities: CommunityConfig::default(),
+            world_bounds: None,
+            boundary_behavior: BoundaryBehavior::default(),
+            placement: DocPlacement::default(),
+            adaptive_decay: None,
+            exploration: None,
+            deferred_wiring: false,
+            transfer: TransferConfig::default(),
+            tick_budget_ms: None,
+            max_fragments_per_agent_per_tick: 0,
+            embedding_storage: EmbeddingStorage::default(),
+            embedding_cache_capacity: default_embedding_cache_capacity(),
+            term_index: default_term_index(),
+            labels: LabelPolicy::default(),
+            synonyms: SynonymConfig::default(),
+            record_replay: false,
+            edge_provenance: default_edge_provenance(),
+            spawn_policy: None,
         }
     }
 }
 
+/// An agent that died this tick, as a possible parent of its replacement.
+struct Dying {
+    id: AgentId,
+    position: Position,
+    genome: Option<AgentGenome>,
+    vocabulary: Option<Vec<u8>>,
+}
+
+/// A configured spawn policy with what breeding needs between deaths.
+struct Breeder {
+    spec: SpawnPolicySpec,
+    policy: Box<dyn SpawnPolicy>,
+    /// Seed of the next offspring's ID, when the spec seeds them.
+    next_seed: Option<u64>,
+}
+
 /// The colony — manages agent lifecycle and simulation.
 pub struct Colony {
     substrate: SubstrateImpl,
     agents: Vec<Box<dyn Agent<Input = String, Fragment = String, Presentation = Vec<String>>>>,
     death_signals: Vec<DeathSignal>,
+    /// Agent → when it was spawned and, once dead, when and why it died.
+    lifespans: HashMap<AgentId, AgentLifespan>,
     event_history: Vec<(Tick, ColonyEvent)>,
     total_spawned: usize,
     total_died: usize,
     fitness_tracker: FitnessTracker,
+    error_counters: ErrorCounters,
+    /// Capability deposits (depositor, tick) already reported as unreadable.
+    unreadable_deposits: HashSet<(AgentId, Tick)>,
+    /// Agent → trust from its last boundary modulation.
+    boundary_trust: HashMap<AgentId, f64>,
+    /// Agent → the spec it was spawned from, for agents spawned from one.
+    agent_specs: HashMap<AgentId, AgentSpec>,
+    /// Living agent → its genome, for agents spawned with one.
+    genomes: HashMap<AgentId, AgentGenome>,
+    /// Living agent → terms it inherited from its parent at birth.
+    inherited_terms: HashMap<AgentId, usize>,
+    /// Breeds replacements for the dead when a spawn policy is configured.
+    breeder: Option<Breeder>,
+    /// Builds the agents the spawn policy breeds.
+    agent_registry: AgentRegistry,
+    /// Insight node → the concept nodes it was derived from (anchor first).
+    insight_provenance: HashMap<NodeId, Vec<NodeId>>,
+    /// Anomaly node → the novelty score it was last presented with.
+    anomaly_scores: HashMap<NodeId, f64>,
+    /// (from, to) → the relation an agent labelled that edge with.
+    edge_relations: HashMap<(NodeId, NodeId), String>,
+    /// Edge → what reinforced it lately.
+    edge_provenance: EdgeProvenance,
+    /// Agent → the document (and version) it is currently digesting.
+    digesting: HashMap<AgentId, (DocumentId, u32)>,
+    /// Document → what its current version contributed to the graph.
+    document_contributions: HashMap<DocumentId, DocumentContribution>,
+    /// Document → what its previous versions contributed, taken back once
+    /// the new version has been presented.
+    superseded_contributions: HashMap<DocumentId, DocumentContribution>,
+    /// Document → when it expires, for documents with a TTL.
+    document_expiry: HashMap<DocumentId, DocumentExpiry>,
+    /// Content signatures of ingested documents, for duplicate detection.
+    dedup_index: DedupIndex,
+    /// Document → duplicates merged into it before it was digested, applied
+    /// once it has been presented.
+    pending_merges: HashMap<DocumentId, u64>,
 
     // Configuration
     signal_decay_rate: f64,
@@ -200,7 +977,61 @@ pub struct Colony {
     staleness_factor: f64,
     maturation_ticks: u64,
     max_edge_degree: usize,
+    /// Sets the decay rates each tick when adaptive decay is configured.
+    decay_controller: Option<DecayController>,
+    /// Sets the exploitation agents read each tick when an exploration
+    /// schedule is configured.
+    exploration: Option<ExplorationController>,
+    /// Cursors of the maintenance passes when ticks have a time budget.
+    maintenance: Option<Maintenance>,
     semantic_wiring: SemanticWiringConfig,
+    dissolution_interval: u64,
+    /// Spatial and label lookups for the dissolution phase.
+    concept_index: ConceptIndex,
+    insight_maintenance_interval: u64,
+    insight_prune_threshold: f64,
+    node_staleness_ticks: u64,
+    node_prune_threshold: f64,
+    node_anchor_edge_weight: f64,
+    node_prune_types: Vec<NodeType>,
+    weight_update: WeightUpdate,
+    deferred_wiring: bool,
+    transfer: TransferConfig,
+    /// Label lookups of the presentations since the last wiring flush.
+    label_resolver: Option<LabelResolver>,
+    /// Co-occurrences presented since the last wiring flush.
+    pending_wiring: PendingWiring,
+    max_fragments_per_agent_per_tick: usize,
+    label_policy: LabelPolicy,
+    synonyms: SynonymConfig,
+    /// Records the run when `record_replay` is set.
+    replay: Option<ReplayRecorder>,
+    /// IDs to mint next, in order, while replaying a recording.
+    scripted_ids: VecDeque<uuid::Uuid>,
+    /// Presentations over the per-tick cap, oldest first.
+    deferred_presentations: VecDeque<Presentation>,
+    backend: BackendConfig,
+    query_log_config: QueryLogConfig,
+    query_log: Option<QueryLog>,
+    /// Registry the colony reports to, and its `space` label there.
+    metrics: Option<(Arc<PromRegistry>, String)>,
+    emerge: EmergeConfig,
+    /// Handed to agents on spawn and used to split queries.
+    tokenizer: Tokenizer,
+    /// Handed to agents on spawn; bounds wiring per presentation.
+    digestion: DigestionLimits,
+    compaction: CompactionConfig,
+    community_config: CommunityConfig,
+    /// Carries community IDs from one detection to the next. Shared with
+    /// read views until the next detection.
+    communities: Arc<CommunityTracker>,
+    placement: DocPlacement,
+    /// Called at fixed points of every tick, in registration order.
+    plugins: Vec<Box<dyn ColonyPlugin>>,
+    /// Custom action kind → what carries it out.
+    action_handlers: HashMap<String, Box<dyn CustomActionHandler>>,
+    insights_generated: u64,
+    last_quorum_tick: Option<Tick>,
 }
 
 impl Colony {
@@ -210,15 +1041,74 @@ impl Colony {
     }
 
     /// Create a new colony with the specified configuration.
+    ///
+    /// # Panics
+    /// Panics if the configured graph backend cannot be opened; use
+    /// [`Colony::try_from_config`] to handle that case.
     pub fn from_config(config: ColonyConfig) -> Self {
-        Self {
-            substrate: SubstrateImpl::new(),
+        Self::try_from_config(config).expect("failed to create graph backend")
+    }
+
+    /// Create a new colony, returning an error if the graph backend
+    /// selected in the configuration cannot be created.
+    pub fn try_from_config(config: ColonyConfig) -> Result<Self, BackendError> {
+        let graph = create_backend(&config.backend)?;
+        let replay = config
+            .record_replay
+            .then(|| ReplayRecorder::new(config.clone()));
+        let mut substrate = SubstrateImpl::with_graph(graph);
+        substrate.set_max_trace_bytes(config.max_trace_bytes);
+        substrate.set_signal_coalescing(config.signal_coalescing);
+        substrate
+            .embeddings_mut()
+            .set_storage(config.embedding_storage);
+        substrate
+            .embedding_cache_mut()
+            .set_capacity(config.embedding_cache_capacity);
+        substrate.set_geometry(WorldGeometry {
+            bounds: config.world_bounds,
+            behavior: config.boundary_behavior,
+        });
+        if config.term_index {
+            substrate.enable_term_index(Tokenizer::new(config.tokenizer.clone()));
+        }
+        let exploration = config.exploration.map(ExplorationController::new);
+        if let Some(controller) = &exploration {
+            substrate.set_ambient(Ambient {
+                exploitation: controller.exploitation(),
+            });
+        }
+        Ok(Self {
+            substrate,
             agents: Vec::new(),
             death_signals: Vec::new(),
+            lifespans: HashMap::new(),
             event_history: Vec::new(),
             total_spawned: 0,
             total_died: 0,
             fitness_tracker: FitnessTracker::new(),
+            error_counters: ErrorCounters::default(),
+            unreadable_deposits: HashSet::new(),
+            boundary_trust: HashMap::new(),
+            agent_specs: HashMap::new(),
+            genomes: HashMap::new(),
+            inherited_terms: HashMap::new(),
+            breeder: config.spawn_policy.map(|spec| Breeder {
+                policy: spec.build(),
+                next_seed: spec.seed,
+                spec,
+            }),
+            agent_registry: AgentRegistry::new(),
+            insight_provenance: HashMap::new(),
+            anomaly_scores: HashMap::new(),
+            edge_relations: HashMap::new(),
+            edge_provenance: EdgeProvenance::new(config.edge_provenance),
+            digesting: HashMap::new(),
+            document_contributions: HashMap::new(),
+            superseded_contributions: HashMap::new(),
+            document_expiry: HashMap::new(),
+            dedup_index: DedupIndex::new(),
+            pending_merges: HashMap::new(),
             signal_decay_rate: config.signal_decay_rate,
             signal_removal_threshold: config.signal_removal_threshold,
             trace_decay_rate: config.trace_decay_rate,
@@ -228,8 +1118,59 @@ impl Colony {
             staleness_factor: config.staleness_factor,
             maturation_ticks: config.maturation_ticks,
             max_edge_degree: config.max_edge_degree,
+            decay_controller: config.adaptive_decay.map(|adaptive| {
+                DecayController::new(
+                    adaptive,
+                    DecayRates {
+                        signal: config.signal_decay_rate,
+                        trace: config.trace_decay_rate,
+                        edge: config.edge_decay_rate,
+                    },
+                )
+            }),
+            exploration,
+            maintenance: config
+                .tick_budget_ms
+                .map(|ms| Maintenance::new(Duration::from_millis(ms))),
             semantic_wiring: config.semantic_wiring,
-        }
+            dissolution_interval: config.dissolution_interval,
+            concept_index: ConceptIndex::new(),
+            insight_maintenance_interval: config.insight_maintenance_interval,
+            insight_prune_threshold: config.insight_prune_threshold,
+            node_staleness_ticks: config.node_staleness_ticks,
+            node_prune_threshold: config.node_prune_threshold,
+            node_anchor_edge_weight: config.node_anchor_edge_weight,
+            node_prune_types: config.node_prune_types,
+            weight_update: config.weight_update,
+            deferred_wiring: config.deferred_wiring,
+            transfer: config.transfer,
+            label_resolver: None,
+            pending_wiring: PendingWiring::default(),
+            max_fragments_per_agent_per_tick: config.max_fragments_per_agent_per_tick,
+            label_policy: config.labels,
+            synonyms: config.synonyms,
+            replay,
+            scripted_ids: VecDeque::new(),
+            deferred_presentations: VecDeque::new(),
+            backend: config.backend,
+            query_log: config
+                .query_log
+                .enabled
+                .then(|| QueryLog::from_config(&config.query_log)),
+            query_log_config: config.query_log,
+            metrics: None,
+            emerge: config.emerge,
+            tokenizer: Tokenizer::new(config.tokenizer),
+            digestion: config.digestion,
+            compaction: config.compaction,
+            community_config: config.communities,
+            communities: Arc::new(CommunityTracker::new()),
+            placement: config.placement,
+            plugins: Vec::new(),
+            action_handlers: HashMap::new(),
+            insights_generated: 0,
+            last_quorum_tick: None,
+        })
     }
 
     /// Get the current configuration.
@@ -239,15 +1180,205 @@ impl Colony {
             signal_removal_threshold: self.signal_removal_threshold,
             trace_decay_rate: self.trace_decay_rate,
             trace_removal_threshold: self.trace_removal_threshold,
+            max_trace_bytes: self.substrate.max_trace_bytes(),
+            signal_coalescing: self.substrate.signal_coalescing().cloned(),
             edge_decay_rate: self.edge_decay_rate,
             edge_prune_threshold: self.edge_prune_threshold,
             staleness_factor: self.staleness_factor,
             maturation_ticks: self.maturation_ticks,
             max_edge_degree: self.max_edge_degree,
             semantic_wiring: self.semantic_wiring.clone(),
+            dissolution_interval: self.dissolution_interval,
+            insight_maintenance_interval: self.insight_maintenance_interval,
+            insight_prune_threshold: self.insight_prune_threshold,
+            node_staleness_ticks: self.node_staleness_ticks,
+            node_prune_threshold: self.node_prune_threshold,
+            node_anchor_edge_weight: self.node_anchor_edge_weight,
+            node_prune_types: self.node_prune_types.clone(),
+            weight_update: self.weight_update,
+            backend: self.backend.clone(),
+            query_log: self.query_log_config.clone(),
+            emerge: self.emerge.clone(),
+            tokenizer: self.tokenizer.config().clone(),
+            digestion: self.digestion.clone(),
+            compaction: self.compaction,
+            communities: self.community_config,
+            world_bounds: self.substrate.geometry().bounds,
+            boundary_behavior: self.substrate.geometry().behavior,
+            placement: self.placement,
+            adaptive_decay: self
+                .decay_controller
+                .as_ref()
+                .map(|controller| controller.config().clone()),
+            exploration: self
+                .exploration
+                .as_ref()
+                .map(|controller| controller.schedule().clone()),
+            deferred_wiring: self.deferred_wiring,
+            transfer: self.transfer,
+            tick_budget_ms: self
+                .maintenance
+                .as_ref()
+                .map(|m| m.budget().as_millis() as u64),
+            max_fragments_per_agent_per_tick: self.max_fragments_per_agent_per_tick,
+            embedding_storage: self.substrate.embeddings().storage(),
+            embedding_cache_capacity: self.substrate.embedding_cache().capacity(),
+            term_index: self.substrate.term_index_enabled(),
+            labels: self.label_policy,
+            synonyms: self.synonyms.clone(),
+            record_replay: self.replay.is_some(),
+            edge_provenance: self.edge_provenance.capacity(),
+            spawn_policy: self.breeder.as_ref().map(|b| b.spec.clone()),
+        }
+    }
+
+    /// The tokenizer agents extract concepts with. Queries against this
+    /// colony must split their text with it too.
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+
+    /// The synonyms presented labels are canonicalized from and queries
+    /// are expanded with. See [`crate::synonyms`].
+    pub fn synonyms(&self) -> &SynonymConfig {
+        &self.synonyms
+    }
+
+    /// What the query `terms` name besides themselves under the colony's
+    /// synonyms, each with the weight a node matched through it counts
+    /// for. Queries against this colony add these to their terms.
+    pub fn expand_query(&self, terms: &[String]) -> Vec<(String, f64)> {
+        self.synonyms
+            .map
+            .expand(terms, self.synonyms.expansion_weight)
+    }
+
+    /// What the colony recorded of its run so far, if `record_replay` is
+    /// set. See [`crate::replay`].
+    pub fn replay(&self) -> Option<&Replay> {
+        self.replay.as_ref().map(ReplayRecorder::replay)
+    }
+
+    /// Mint `ids`, in order, for the next nodes and documents created,
+    /// before falling back to fresh ones. Used to replay a recording.
+    pub(crate) fn script_ids(&mut self, ids: impl IntoIterator<Item = uuid::Uuid>) {
+        self.scripted_ids = ids.into_iter().collect();
+    }
+
+    /// A fresh ID for a node or document, recorded when recording.
+    fn mint_id(&mut self) -> uuid::Uuid {
+        let id = self
+            .scripted_ids
+            .pop_front()
+            .unwrap_or_else(|| NodeId::new().0);
+        if let Some(replay) = self.replay.as_mut() {
+            replay.minted(id);
+        }
+        id
+    }
+
+    fn mint_node_id(&mut self) -> NodeId {
+        NodeId(self.mint_id())
+    }
+
+    /// Start recording a call, if recording. Every call begun must be
+    /// ended with [`end_replay_step`](Self::end_replay_step).
+    fn begin_replay_step(&mut self) {
+        if let Some(replay) = self.replay.as_mut() {
+            replay.begin();
+        }
+    }
+
+    fn end_replay_step(&mut self, input: impl FnOnce() -> ReplayInput) {
+        if let Some(replay) = self.replay.as_mut() {
+            replay.end(input);
+        }
+    }
+
+    /// Record a call that neither mints IDs nor asks agents anything.
+    fn record_replay_step(&mut self, input: impl FnOnce() -> ReplayInput) {
+        self.begin_replay_step();
+        self.end_replay_step(input);
+    }
+
+    /// The query log, if enabled in the configuration.
+    pub fn query_log(&self) -> Option<&QueryLog> {
+        self.query_log.as_ref()
+    }
+
+    /// Record a query in the log. Does nothing when logging is disabled.
+    pub fn log_query(&self, entry: QueryLogEntry) {
+        if let Some(log) = &self.query_log {
+            log.record(entry);
+        }
+    }
+
+    /// Report to `registry` under the label `space` from now on: gauges
+    /// are updated immediately and after every tick.
+    pub fn attach_metrics(&mut self, registry: Arc<PromRegistry>, space: &str) {
+        registry.record_stats(space, &self.stats());
+        self.metrics = Some((registry, space.to_string()));
+    }
+
+    /// The registry this colony reports to, if any.
+    pub fn metrics(&self) -> Option<&Arc<PromRegistry>> {
+        self.metrics.as_ref().map(|(registry, _)| registry)
+    }
+
+    /// Record how long a query took. Does nothing without a registry.
+    pub fn record_query_latency(&self, source: QuerySource, duration: Duration) {
+        if let Some((registry, _)) = &self.metrics {
+            registry.record_query(source, duration);
+        }
+    }
+
+    /// Record a save that took `duration` and just finished. Does nothing
+    /// without a registry.
+    pub fn record_save(&self, duration: Duration) {
+        if let Some((registry, space)) = &self.metrics {
+            registry.record_save(space, SystemTime::now(), duration);
+        }
+    }
+
+    /// Quorum levels per agent region and the insights produced so far.
+    pub fn emergence_report(&self) -> EmergenceReport {
+        let regions = self
+            .agents
+            .iter()
+            .filter_map(|a| {
+                a.quorum_status(&self.substrate).map(|status| QuorumRegion {
+                    agent_id: a.id(),
+                    status,
+                })
+            })
+            .collect();
+        let insight_nodes = self
+            .substrate
+            .graph()
+            .all_nodes()
+            .iter()
+            .filter(|id| {
+                self.substrate
+                    .graph()
+                    .get_node(id)
+                    .is_some_and(|n| n.node_type == NodeType::Insight)
+            })
+            .count();
+        EmergenceReport {
+            tick: self.substrate.current_tick(),
+            regions,
+            insights_generated: self.insights_generated,
+            insight_nodes,
+            last_trigger_tick: self.last_quorum_tick,
+            config: self.emerge.clone(),
         }
     }
 
+    /// The rule used to reinforce existing edges.
+    pub fn weight_update(&self) -> &WeightUpdate {
+        &self.weight_update
+    }
+
     /// Configure semantic wiring for embedding-based edge weights.
     pub fn with_semantic_wiring(mut self, config: SemanticWiringConfig) -> Self {
         self.semantic_wiring = config;
@@ -267,992 +1398,5332 @@ impl Colony {
     /// Spawn an agent into the colony.
     pub fn spawn(
         &mut self,
-        agent: Box<dyn Agent<Input = String, Fragment = String, Presentation = Vec<String>>>,
+        mut agent: Box<dyn Agent<Input = String, Fragment = String, Presentation = Vec<String>>>,
     ) -> AgentId {
+        self.begin_replay_step();
+        agent.adopt_emerge_defaults(&self.emerge);
+        agent.adopt_tokenizer(&self.tokenizer);
+        agent.adopt_digestion_limits(&self.digestion);
+        agent.set_position(self.substrate.geometry().confine(agent.position()));
         let id = agent.id();
         self.total_spawned += 1;
         self.fitness_tracker.register(id, 0);
+        self.lifespans.insert(
+            id,
+            AgentLifespan {
+                agent_id: id,
+                agent_type: agent.agent_type().to_string(),
+                born: self.substrate.current_tick(),
+                died: None,
+            },
+        );
+        if let Some(replay) = &self.replay {
+            agent = replay.wrap(agent);
+        }
+        let (agent_type, position) = (agent.agent_type().to_string(), agent.position());
         self.agents.push(agent);
+        self.end_replay_step(|| ReplayInput::Spawn {
+            agent: id,
+            agent_type,
+            position,
+        });
         id
     }
 
+    /// Build an agent from `spec` with `registry` and spawn it. The colony
+    /// remembers the spec, so the agent is part of [`Colony::population`],
+    /// and the genome among its parameters, if any (see [`Colony::genomes`]).
+    pub fn spawn_from_spec(
+        &mut self,
+        registry: &AgentRegistry,
+        spec: &AgentSpec,
+    ) -> Result<AgentId, RegistryError> {
+        let agent = registry.build(spec)?;
+        let id = self.spawn(agent);
+        self.agent_specs.insert(id, spec.clone());
+        let genome = spec.params.get("genome").cloned();
+        if let Some(genome) = genome.and_then(|g| serde_json::from_value(g).ok()) {
+            self.genomes.insert(id, genome);
+        }
+        Ok(id)
+    }
+
+    /// Genomes of the living agents spawned with one: those spawned from a
+    /// spec with a `genome` parameter and those the spawn policy bred.
+    pub fn genomes(&self) -> &HashMap<AgentId, AgentGenome> {
+        &self.genomes
+    }
+
+    /// Number of terms a living agent inherited from its parent when the
+    /// spawn policy bred it; 0 for agents that inherited none.
+    pub fn inherited_terms(&self, id: &AgentId) -> usize {
+        self.inherited_terms.get(id).copied().unwrap_or(0)
+    }
+
+    /// Build the agents the spawn policy breeds with `registry` instead of
+    /// one of the built-in types.
+    pub fn set_agent_registry(&mut self, registry: AgentRegistry) {
+        self.agent_registry = registry;
+    }
+
+    /// Breed a replacement for `dead` with the spawn policy: offspring of
+    /// the fittest of `survivors` — or of `dying` when none survived —
+    /// built through the agent registry with the offspring's position and
+    /// genome. Nothing if no policy is configured or it declines; a failed
+    /// build is reported against `dead`.
+    fn breed_replacement(
+        &mut self,
+        dead: AgentId,
+        survivors: &[AgentId],
+        dying: &[Dying],
+    ) -> Option<ColonyEvent> {
+        let breeds_from_parent = self.breeder.as_ref()?.spec.policy.breeds_from_parent();
+        let dying_ids: Vec<AgentId> = dying.iter().map(|d| d.id).collect();
+        let parent = self
+            .fitness_tracker
+            .fittest(survivors)
+            .or_else(|| self.fitness_tracker.fittest(&dying_ids))
+            .map(|f| f.agent_id)
+            .filter(|_| breeds_from_parent);
+        let (parent_genome, parent_position, vocabulary) = match parent {
+            Some(id) => match dying.iter().find(|d| d.id == id) {
+                Some(d) => (d.genome.clone(), Some(d.position), d.vocabulary.clone()),
+                None => (
+                    self.genomes.get(&id).cloned(),
+                    self.agents
+                        .iter()
+                        .find(|a| a.id() == id)
+                        .map(|a| a.position()),
+                    self.agent_vocabulary(&id),
+                ),
+            },
+            None => (None, None, None),
+        };
+        // Agents spawned without a genome carry the default one
+        let parent_genome =
+            parent.map(|_| parent_genome.unwrap_or_else(AgentGenome::default_genome));
+
+        let alive = self.agents.len();
+        let breeder = self.breeder.as_mut()?;
+        let offspring = breeder.policy.on_death_with_vocabulary(
+            dead,
+            alive,
+            parent_genome.as_ref(),
+            parent_position,
+            vocabulary.as_deref(),
+        )?;
+        let mut params = serde_json::json!({
+            "position": offspring.position,
+            "genome": offspring.genome,
+        });
+        if let Some(seed) = breeder.next_seed.as_mut() {
+            params["seed"] = (*seed).into();
+            *seed += 1;
+        }
+        let spec = AgentSpec::new(breeder.spec.agent_type.clone(), params);
+        let mut agent = match self.agent_registry.build(&spec) {
+            Ok(agent) => agent,
+            Err(e) => {
+                return Some(ColonyEvent::ActionFailed {
+                    agent_id: dead,
+                    action_kind: "spawn".to_string(),
+                    reason: e.to_string(),
+                })
+            }
+        };
+        let inherited = match &offspring.vocabulary {
+            Some((vocabulary, fraction)) => agent.inherit_vocabulary(vocabulary, *fraction),
+            None => 0,
+        };
+
+        let generation = self.fitness_tracker.next_generation();
+        let agent_type = agent.agent_type().to_string();
+        let id = self.spawn(agent);
+        self.fitness_tracker.register(id, generation);
+        self.agent_specs.insert(id, spec);
+        self.genomes.insert(id, offspring.genome);
+        if inherited > 0 {
+            self.inherited_terms.insert(id, inherited);
+        }
+        Some(ColonyEvent::Spawned {
+            id,
+            agent_type,
+            parent,
+            generation,
+        })
+    }
+
+    /// The spec a living agent was spawned from, if it was spawned from one.
+    pub fn agent_spec(&self, id: &AgentId) -> Option<&AgentSpec> {
+        self.agent_specs.get(id)
+    }
+
+    /// Specs of the living agents spawned from one, in spawn order. Saved
+    /// with the session so a restore can respawn the same population.
+    pub fn population(&self) -> Vec<AgentSpec> {
+        self.agents
+            .iter()
+            .filter_map(|a| self.agent_specs.get(&a.id()).cloned())
+            .collect()
+    }
+
+    /// Remove a live agent without killing it, e.g. to move it to another
+    /// colony. No death is recorded and no vocabulary is deposited; a
+    /// document it had engulfed is put back for others.
+    pub fn take_agent(
+        &mut self,
+        id: &AgentId,
+    ) -> Option<Box<dyn Agent<Input = String, Fragment = String, Presentation = Vec<String>>>> {
+        let idx = self.agents.iter().position(|a| a.id() == *id)?;
+        self.begin_replay_step();
+        let mut agent = self.agents.remove(idx);
+        self.digesting.remove(id);
+        self.concept_index.forget_agent(id);
+        self.lifespans.remove(id);
+        self.agent_specs.remove(id);
+        self.genomes.remove(id);
+        self.inherited_terms.remove(id);
+        if let Some((doc_id, _content)) = agent.release_undigested() {
+            self.release_document(&doc_id);
+        }
+        self.end_replay_step(|| ReplayInput::TakeAgent { agent: *id });
+        Some(agent)
+    }
+
     /// Ingest a document into the substrate.
     ///
     /// Places the document at the given position and emits an Input signal
-    /// to attract nearby agents via chemotaxis.
+    /// to attract nearby agents via chemotaxis. Positions outside the world
+    /// bounds are brought inside the way an agent's move would be.
     pub fn ingest_document(
         &mut self,
         title: &str,
         content: &str,
         position: Position,
     ) -> DocumentId {
+        self.ingest_document_tagged(title, content, position, &[])
+    }
+
+    /// Ingest a document that expires after `ttl_ticks` ticks, if given
+    /// (see [`set_document_ttl`](Self::set_document_ttl)).
+    pub fn ingest_document_with_ttl(
+        &mut self,
+        title: &str,
+        content: &str,
+        position: Position,
+        ttl_ticks: Option<u64>,
+    ) -> DocumentId {
+        let id = self.ingest_document(title, content, position);
+        if let Some(ttl) = ttl_ticks {
+            self.set_document_ttl(&id, ttl);
+        }
+        id
+    }
+
+    /// Ingest a document at a position chosen by the configured
+    /// [`DocPlacement`] (see [`place_document`](Self::place_document)).
+    pub fn ingest_document_auto(&mut self, title: &str, content: &str) -> DocumentId {
+        let position = self.place_document(title, content);
+        self.ingest_document(title, content, position)
+    }
+
+    /// Where the configured [`DocPlacement`] would put a document. A
+    /// document with the title of an existing one keeps that one's
+    /// position, so updates stay in place.
+    pub fn place_document(&self, title: &str, content: &str) -> Position {
+        let documents = self.substrate.all_documents();
+        if let Some(existing) = documents.iter().find(|d| d.title == title) {
+            return existing.position;
+        }
+        placement::place(&self.placement, &self.tokenizer, &documents, content)
+    }
+
+    /// Ingest a document carrying view tags (e.g. "work", "personal").
+    ///
+    /// Concept nodes presented from the document accumulate its tags, so
+    /// queries can be restricted to the nodes of one or more tags.
+    pub fn ingest_document_tagged(
+        &mut self,
+        title: &str,
+        content: &str,
+        position: Position,
+        tags: &[&str],
+    ) -> DocumentId {
+        self.begin_replay_step();
         let doc = Document {
-            id: DocumentId::new(),
+            id: DocumentId(self.mint_id()),
             title: title.to_string(),
             content: content.to_string(),
-            position,
+            position: self.substrate.geometry().confine(position),
             digested: false,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            version: 1,
         };
         let doc_id = doc.id;
         let doc_pos = doc.position;
 
         self.substrate.add_document(doc);
+        self.attract_to_document(doc_pos);
 
-        // Emit input signal to attract agents
-        self.substrate.emit_signal(Signal::new(
-            SignalType::Input,
-            1.0,
-            doc_pos,
-            AgentId::new(), // System-emitted
-            self.substrate.current_tick(),
-        ));
-
+        self.end_replay_step(|| ReplayInput::Ingest {
+            title: title.to_string(),
+            content: content.to_string(),
+            position,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        });
         doc_id
     }
 
-    /// Run a single simulation tick.
-    pub fn tick(&mut self) -> Vec<ColonyEvent> {
-        let mut events = Vec::new();
-        let mut actions: Vec<(usize, AgentAction)> = Vec::new();
-
-        // Phase 1: All agents sense and decide
-        for (idx, agent) in self.agents.iter_mut().enumerate() {
-            let action = agent.tick(&self.substrate);
-            actions.push((idx, action));
-        }
-
-        // Phase 2: Process actions
-        let mut to_die = Vec::new();
-        let mut symbiotic_deaths: Vec<(usize, AgentId)> = Vec::new(); // (idx, absorber_id)
+    /// Ingest a document, or update the existing document with the same
+    /// title.
+    ///
+    /// Re-ingesting unchanged content is a no-op. Changed content replaces
+    /// the stored document and bumps its `version`. Once the new version
+    /// has been digested, its concepts are reinforced as usual, whatever
+    /// only the previous version contributed is taken back (concepts no
+    /// other document presented are removed) and a
+    /// [`ColonyEvent::DocumentUpdated`] is emitted.
+    pub fn ingest_or_update_document(
+        &mut self,
+        title: &str,
+        content: &str,
+        position: Position,
+    ) -> DocumentId {
+        self.ingest_or_update_document_tagged(title, content, position, &[])
+    }
 
-        for (idx, action) in actions {
-            match action {
-                AgentAction::Move(pos) => {
-                    self.agents[idx].set_position(pos);
-                    events.push(ColonyEvent::Moved {
-                        id: self.agents[idx].id(),
-                        to: pos,
-                    });
-                }
+    /// [`ingest_or_update_document`](Self::ingest_or_update_document) for a
+    /// document carrying view tags.
+    pub fn ingest_or_update_document_tagged(
+        &mut self,
+        title: &str,
+        content: &str,
+        position: Position,
+        tags: &[&str],
+    ) -> DocumentId {
+        let existing = self
+            .substrate
+            .all_documents()
+            .into_iter()
+            .filter(|d| d.title == title)
+            .max_by_key(|d| d.version)
+            .cloned();
+        let Some(mut doc) = existing else {
+            return self.ingest_document_tagged(title, content, position, tags);
+        };
 
-                AgentAction::EngulfDocument(doc_id) => {
-                    // Try to consume the document from substrate
-                    if let Some(content) = self.substrate.consume_document(&doc_id) {
-                        self.agents[idx].engulf(content);
-                        // Also set the document context via downcast
-                        // (The agent's state machine will handle digestion next tick)
-                        events.push(ColonyEvent::Engulfed {
-                            id: self.agents[idx].id(),
-                            document: doc_id,
-                        });
-                    }
-                }
+        let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+        if doc.content == content && doc.tags == tags {
+            return doc.id;
+        }
 
-                AgentAction::PresentFragments(fragments) => {
-                    let agent_id = self.agents[idx].id();
-                    let tick = self.substrate.current_tick();
-                    let mut node_ids = Vec::new();
-
-                    for frag in &fragments {
-                        // Check if this concept already exists in the graph
-                        let existing = self.substrate.graph().find_nodes_by_label(&frag.label);
-                        let node_id = if let Some(&existing_id) = existing.first() {
-                            // Reinforce existing node
-                            if let Some(node) =
-                                self.substrate.graph_mut().get_node_mut(&existing_id)
-                            {
-                                node.access_count += 1;
-                            }
-                            existing_id
-                        } else {
-                            // Create new node with the type specified by the agent
-                            let node = NodeData {
-                                id: NodeId::new(),
-                                label: frag.label.clone(),
-                                node_type: frag.node_type.clone(),
-                                position: frag.position,
-                                access_count: 1,
-                                created_tick: tick,
-                                embedding: None,
-                            };
-                            self.substrate.add_node(node)
-                        };
-                        node_ids.push(node_id);
-                    }
+        let position = self.substrate.geometry().confine(position);
+        doc.content = content.to_string();
+        doc.position = position;
+        doc.tags = tags;
+        doc.digested = false;
+        doc.version += 1;
+        let doc_id = doc.id;
+        self.record_replay_step(|| ReplayInput::Update {
+            title: title.to_string(),
+            content: content.to_string(),
+            position,
+            tags: doc.tags.clone(),
+        });
+        // Offsets into the old content no longer apply
+        self.substrate.clear_passages(&doc_id);
+        self.substrate.add_document(doc);
+        self.attract_to_document(position);
 
-                    // Wire co-occurring concepts (from same document)
-                    // Only wire Concept nodes — Insight/Anomaly nodes don't co-occur
-                    //
-                    // Co-activation gating (Hebbian LTP model):
-                    // - First co-occurrence: create a TENTATIVE edge with low weight (0.1)
-                    // - Subsequent co-occurrences: reinforce to full weight (+0.1 per hit)
-                    // - Only edges reinforced by multiple documents survive synaptic pruning
-                    // This reduces the dense graph problem: single-doc edges are weak
-                    // and decay quickly unless reinforced by cross-document co-occurrence.
-                    //
-                    // Semantic wiring (Phase 9.3):
-                    // - If nodes have embeddings, modulate edge weight by similarity
-                    // - weight = base_weight * (1 + similarity_influence * similarity)
-                    // - Below min_similarity threshold: skip or use base weight
-                    let concept_node_ids: Vec<NodeId> = node_ids
-                        .iter()
-                        .filter(|id| {
-                            self.substrate
-                                .graph()
-                                .get_node(id)
-                                .map_or(false, |n| n.node_type == NodeType::Concept)
-                        })
-                        .copied()
-                        .collect();
-                    let mut wire_events = Vec::new();
-                    for i in 0..concept_node_ids.len() {
-                        for j in (i + 1)..concept_node_ids.len() {
-                            let from = concept_node_ids[i];
-                            let to = concept_node_ids[j];
-
-                            // Get embeddings for semantic wiring (clone to avoid borrow issues)
-                            let embedding_from = self
-                                .substrate
-                                .graph()
-                                .get_node(&from)
-                                .and_then(|n| n.embedding.clone());
-                            let embedding_to = self
-                                .substrate
-                                .graph()
-                                .get_node(&to)
-                                .and_then(|n| n.embedding.clone());
-
-                            // Compute semantic weight before mutating graph
-                            let base_weight = 0.1;
-                            let semantic_weight = compute_semantic_weight(
-                                base_weight,
-                                embedding_from.as_deref(),
-                                embedding_to.as_deref(),
-                                &self.semantic_wiring,
-                            );
+        doc_id
+    }
 
-                            if let Some(edge) = self.substrate.graph_mut().get_edge_mut(&from, &to)
-                            {
-                                // Edge already exists: strengthen it (Hebbian reinforcement)
-                                // Use semantic similarity to modulate reinforcement
-                                let reinforcement = semantic_weight.unwrap_or(base_weight);
-                                edge.weight = (edge.weight + reinforcement).min(1.0);
-                                edge.co_activations += 1;
-                                edge.last_activated_tick = tick;
-                                wire_events.push((from, to));
-                            } else {
-                                // First co-occurrence: create tentative edge with low weight.
-                                // Use semantic similarity to compute initial weight.
-                                let weight = semantic_weight;
-
-                                // Only create edge if semantic check passes
-                                if let Some(w) = weight {
-                                    self.substrate.set_edge(
-                                        from,
-                                        to,
-                                        EdgeData {
-                                            weight: w,
-                                            co_activations: 1,
-                                            created_tick: tick,
-                                            last_activated_tick: tick,
-                                        },
-                                    );
-                                    wire_events.push((from, to));
-                                }
-                            }
-                        }
-                    }
+    /// Ingest a document unless it duplicates one already ingested.
+    ///
+    /// Exact and near duplicates of other documents are handled according
+    /// to `config.policy`; see [`crate::dedup`]. A document with the title
+    /// of an existing one is an update, as with
+    /// [`ingest_or_update_document_tagged`](Self::ingest_or_update_document_tagged).
+    pub fn ingest_deduplicated(
+        &mut self,
+        title: &str,
+        content: &str,
+        position: Position,
+        tags: &[&str],
+        config: &DedupConfig,
+    ) -> IngestOutcome {
+        self.dedup_index.sync(self.substrate.all_documents());
+        self.ingest_indexed(title, content, position, tags, config)
+    }
 
-                    events.push(ColonyEvent::Presented {
-                        id: agent_id,
-                        fragment_count: fragments.len(),
-                        node_ids,
-                    });
+    /// Ingest documents in order, deduplicating each against everything
+    /// ingested before it, earlier documents of the batch included.
+    pub fn ingest_batch(
+        &mut self,
+        documents: &[BatchDocument<'_>],
+        config: &DedupConfig,
+    ) -> IngestReport {
+        self.dedup_index.sync(self.substrate.all_documents());
+        let mut report = IngestReport::default();
+        for doc in documents {
+            let outcome =
+                self.ingest_indexed(doc.title, doc.content, doc.position, doc.tags, config);
+            report.record(doc.title, outcome);
+        }
+        report
+    }
 
-                    if !wire_events.is_empty() {
-                        events.push(ColonyEvent::Wired {
-                            id: agent_id,
-                            connection_count: wire_events.len(),
-                        });
-                    }
+    /// Deduplicated ingestion against an index already in sync.
+    fn ingest_indexed(
+        &mut self,
+        title: &str,
+        content: &str,
+        position: Position,
+        tags: &[&str],
+        config: &DedupConfig,
+    ) -> IngestOutcome {
+        let duplicate = self.dedup_index.find(title, content, config.threshold);
+        match (duplicate, config.policy) {
+            (Some(m), DedupPolicy::Skip) => IngestOutcome::Skipped(m),
+            (Some(m), DedupPolicy::MergeIntoExisting) => {
+                self.merge_duplicate(m.original);
+                IngestOutcome::Merged(m)
+            }
+            (duplicate_of, _) => {
+                let id = self.ingest_or_update_document_tagged(title, content, position, tags);
+                if let Some(doc) = self.substrate.get_document(&id) {
+                    self.dedup_index.insert(doc);
                 }
+                IngestOutcome::Ingested { id, duplicate_of }
+            }
+        }
+    }
 
-                AgentAction::Deposit(location, trace) => {
-                    let agent_id = self.agents[idx].id();
-                    self.substrate.deposit_trace(&location, trace);
-                    events.push(ColonyEvent::Deposited {
-                        id: agent_id,
-                        location,
-                    });
-                }
+    /// Count a duplicate towards `original`: one more access for each
+    /// concept it contributed. Deferred until it has been presented.
+    pub(crate) fn merge_duplicate(&mut self, original: DocumentId) {
+        self.record_replay_step(|| ReplayInput::MergeDuplicate { original });
+        let Some(contribution) = self.document_contributions.get_mut(&original) else {
+            *self.pending_merges.entry(original).or_insert(0) += 1;
+            return;
+        };
+        let graph = self.substrate.graph_mut();
+        for (node_id, count) in contribution.nodes.iter_mut() {
+            if let Some(node) = graph.get_node_mut(node_id) {
+                node.access_count += 1;
+                *count += 1;
+            }
+        }
+    }
 
-                AgentAction::Emit(signal) => {
-                    self.substrate.emit_signal(signal);
-                }
+    /// Mark a document an agent engulfed as undigested again and attract
+    /// agents to it. Returns false if the document no longer exists.
+    fn release_document(&mut self, id: &DocumentId) -> bool {
+        let Some(mut doc) = self.substrate.get_document(id).cloned() else {
+            return false;
+        };
+        doc.digested = false;
+        let position = doc.position;
+        self.substrate.add_document(doc);
+        self.attract_to_document(position);
+        true
+    }
 
-                AgentAction::WireNodes(connections) => {
-                    let agent_id = self.agents[idx].id();
-                    let tick = self.substrate.current_tick();
-                    let mut wired_count = 0;
-                    for (from, to, base_weight) in &connections {
-                        // Get embeddings for semantic wiring (clone to avoid borrow issues)
-                        let embedding_from = self
-                            .substrate
-                            .graph()
-                            .get_node(from)
-                            .and_then(|n| n.embedding.clone());
-                        let embedding_to = self
-                            .substrate
-                            .graph()
-                            .get_node(to)
-                            .and_then(|n| n.embedding.clone());
-
-                        // Compute semantic weight before mutating graph
-                        let weight = compute_semantic_weight(
-                            *base_weight,
-                            embedding_from.as_deref(),
-                            embedding_to.as_deref(),
-                            &self.semantic_wiring,
-                        );
-
-                        if let Some(w) = weight {
-                            if let Some(edge) = self.substrate.graph_mut().get_edge_mut(from, to) {
-                                edge.weight = (edge.weight + w).min(1.0);
-                                edge.co_activations += 1;
-                                edge.last_activated_tick = tick;
-                            } else {
-                                self.substrate.set_edge(
-                                    *from,
-                                    *to,
-                                    EdgeData {
-                                        weight: w,
-                                        co_activations: 1,
-                                        created_tick: tick,
-                                        last_activated_tick: tick,
-                                    },
-                                );
-                            }
-                            wired_count += 1;
-                        }
-                    }
-                    if wired_count > 0 {
-                        events.push(ColonyEvent::Wired {
-                            id: agent_id,
-                            connection_count: wired_count,
-                        });
-                    }
+    /// Modulate agent `i`'s boundary by how strongly the graph reinforces
+    /// its vocabulary; a permeable agent then boosts the concepts it knows
+    /// and absorbs the concept labels around it.
+    fn dissolve_boundary(&mut self, i: usize, events: &mut Vec<ColonyEvent>) {
+        let agent_id = self.agents[i].id();
+        let agent_pos = self.agents[i].position();
+        let agent_age = self.agents[i].age();
+
+        // Compute BoundaryContext — cache externalized vocab for reuse
+        let vocab_terms = self.agents[i].externalize_vocabulary();
+        let mut reinforcement_count = 0u64;
+        let graph = self.substrate.graph();
+        for term in &vocab_terms {
+            for nid in self.concept_index.resolve(agent_id, term, graph) {
+                if let Some(node) = graph.get_node(nid) {
+                    reinforcement_count += node.access_count;
                 }
+            }
+        }
 
-                AgentAction::ExportCapability(_cap_id) => {
-                    let agent_id = self.agents[idx].id();
-                    let agent_pos = self.agents[idx].position();
-                    if let Some(vocab_bytes) = self.agents[idx].export_vocabulary() {
-                        // Count terms for event
-                        let terms_count =
-                            serde_json::from_slice::<VocabularyCapability>(&vocab_bytes)
-                                .map(|v| v.terms.len())
-                                .unwrap_or(0);
-
-                        // Deposit as CapabilityDeposit trace at agent position
-                        let trace = Trace {
-                            agent_id,
-                            trace_type: TraceType::CapabilityDeposit,
-                            intensity: 1.0,
-                            tick: self.substrate.current_tick(),
-                            payload: vocab_bytes,
-                        };
-                        self.substrate
-                            .deposit_trace(&SubstrateLocation::Spatial(agent_pos), trace);
-
-                        // Emit Capability signal to attract other agents
-                        self.substrate.emit_signal(Signal::new(
-                            SignalType::Capability,
-                            0.8,
-                            agent_pos,
-                            agent_id,
-                            self.substrate.current_tick(),
-                        ));
+        let useful_outputs_estimate = reinforcement_count.min(100);
+        let trust = if agent_age > 0 {
+            (useful_outputs_estimate as f64 / agent_age as f64).min(1.0)
+        } else {
+            0.0
+        };
 
-                        events.push(ColonyEvent::CapabilityExported {
-                            agent_id,
-                            terms_count,
-                        });
+        self.boundary_trust.insert(agent_id, trust);
+        let context = BoundaryContext {
+            reinforcement_count,
+            age: agent_age,
+            trust,
+        };
+
+        self.agents[i].modulate_boundary(&context);
+        let permeability = self.agents[i].permeability();
+
+        // High permeability: boost matching graph nodes' access_count
+        if permeability > 0.5 {
+            let mut terms_externalized = 0usize;
+            let graph = self.substrate.graph_mut();
+            for term in &vocab_terms {
+                for nid in self.concept_index.resolve(agent_id, term, &*graph) {
+                    if let Some(node) = graph.get_node_mut(nid) {
+                        node.access_count += 1;
+                        terms_externalized += 1;
                     }
                 }
+            }
+            if terms_externalized > 0 {
+                events.push(ColonyEvent::Dissolved {
+                    agent_id,
+                    permeability,
+                    terms_externalized,
+                });
+            }
+        }
 
-                AgentAction::SymbioseWith(target_id) => {
-                    let host_idx = idx;
-                    let host_id = self.agents[host_idx].id();
+        // Any permeability > 0: internalize nearby concept labels
+        if permeability > 0.0 {
+            let nearby_labels = self
+                .concept_index
+                .nearby_labels(&agent_pos, self.substrate.graph());
+            if !nearby_labels.is_empty() {
+                self.agents[i].internalize_vocabulary(&nearby_labels);
+            }
+        }
+    }
 
-                    // Find target agent
-                    if let Some(target_idx) = self.agents.iter().position(|a| a.id() == target_id) {
-                        // Build target's profile and extract vocabulary
-                        let target_profile = self.agents[target_idx].profile();
-                        let target_vocab = self.agents[target_idx]
-                            .export_vocabulary()
-                            .unwrap_or_default();
+    /// Deposit exported vocabulary as a CapabilityDeposit trace at
+    /// `position` and signal nearby agents to come and integrate it. The
+    /// deposit carries the exporter's fitness and how often the graph has
+    /// reinforced each term, for [`TransferConfig`] to weigh.
+    fn deposit_capability(
+        &mut self,
+        agent_id: AgentId,
+        position: Position,
+        vocab_bytes: Vec<u8>,
+    ) -> ColonyEvent {
+        let mut capability = match serde_json::from_slice::<VocabularyCapability>(&vocab_bytes) {
+            Ok(capability) => capability,
+            Err(e) => {
+                return ColonyEvent::action_failed(
+                    agent_id,
+                    "export_capability",
+                    format!("invalid vocabulary payload: {e}"),
+                )
+            }
+        };
+        let terms_count = capability.terms.len();
+        let graph = self.substrate.graph();
+        capability.source_fitness = self.fitness_tracker.get(&agent_id).map(|f| f.fitness);
+        capability.term_reinforcement = capability
+            .terms
+            .iter()
+            .map(|term| {
+                graph
+                    .find_nodes_by_exact_label(&term.to_lowercase())
+                    .iter()
+                    .filter_map(|nid| graph.get_node(nid))
+                    .map(|node| node.access_count)
+                    .sum()
+            })
+            .collect();
+        let vocab_bytes = serde_json::to_vec(&capability).unwrap_or(vocab_bytes);
 
-                        // Evaluate symbiosis
-                        if let Some(SymbiosisEval::Integrate) =
-                            self.agents[host_idx].evaluate_symbiosis(&target_profile)
-                        {
-                            let host_type = self.agents[host_idx].agent_type().to_string();
-                            let absorbed_type = self.agents[target_idx].agent_type().to_string();
+        let trace = Trace {
+            agent_id,
+            trace_type: TraceType::CapabilityDeposit,
+            intensity: 1.0,
+            tick: self.substrate.current_tick(),
+            payload: trace_payload::encode(vocab_bytes),
+        };
+        self.substrate
+            .deposit_trace(&SubstrateLocation::Spatial(position), trace);
 
-                            // Host absorbs the symbiont
-                            self.agents[host_idx].absorb_symbiont(target_profile, target_vocab);
+        self.substrate.emit_signal(Signal::new(
+            SignalType::Capability,
+            0.8,
+            position,
+            agent_id,
+            self.substrate.current_tick(),
+        ));
 
-                            // Mark target for removal via symbiotic absorption
-                            symbiotic_deaths.push((target_idx, host_id));
+        ColonyEvent::CapabilityExported {
+            agent_id,
+            terms_count,
+        }
+    }
 
-                            events.push(ColonyEvent::Symbiosis {
-                                host: host_id,
-                                absorbed: target_id,
-                                host_type,
-                                absorbed_type,
-                            });
-                        }
-                    }
-                }
+    /// Whether presentations are wired in one pass per tick (see
+    /// [`ColonyConfig::deferred_wiring`]).
+    fn defers_wiring(&self) -> bool {
+        self.deferred_wiring && !matches!(self.weight_update, WeightUpdate::Normalized { .. })
+    }
 
-                AgentAction::Apoptose => {
-                    to_die.push(idx);
-                }
+    /// Write the wiring deferred since the last flush and forget the
+    /// label lookups made meanwhile. Returns events for edges that could
+    /// not be written.
+    fn flush_wiring(&mut self) -> Vec<ColonyEvent> {
+        self.label_resolver = None;
+        if self.pending_wiring.is_empty() {
+            return Vec::new();
+        }
+        let tick = self.substrate.current_tick();
+        let flushed = std::mem::take(&mut self.pending_wiring).flush(
+            self.substrate.graph_mut(),
+            tick,
+            &self.weight_update,
+        );
+        for (doc_id, contribution) in flushed.contributions {
+            self.document_contributions
+                .entry(doc_id)
+                .or_default()
+                .merge(contribution);
+        }
+        for (from, to, created, documents) in flushed.wired {
+            if created {
+                self.edge_provenance.forget(from, to);
+            }
+            for document in documents {
+                let source = document.map_or(EdgeSource::Unattributed, EdgeSource::Document);
+                self.edge_provenance.record(from, to, source, tick);
+            }
+        }
+        flushed
+            .failures
+            .iter()
+            .filter_map(|(agent_id, errors)| wiring_failure(*agent_id, "present_fragments", errors))
+            .collect()
+    }
 
-                AgentAction::Idle => {}
+    /// Take an agent's presentation, putting in as many fragments as the
+    /// agent may still present this tick and queueing the rest behind its
+    /// earlier deferred presentations.
+    fn accept_presentation(
+        &mut self,
+        agent_id: AgentId,
+        fragments: Vec<FragmentPresentation>,
+        presented: &mut HashMap<AgentId, usize>,
+        events: &mut Vec<ColonyEvent>,
+    ) {
+        let presentation = Presentation {
+            agent_id,
+            fragments,
+            digesting: self.digesting.get(&agent_id).copied(),
+            node_ids: Vec::new(),
+            references: Vec::new(),
+            contribution: DocumentContribution::default(),
+        };
+        if self
+            .deferred_presentations
+            .iter()
+            .any(|p| p.agent_id == agent_id)
+        {
+            self.deferred_presentations.push_back(presentation);
+        } else if let Some(presentation) = self.present_within_cap(presentation, presented, events)
+        {
+            self.deferred_presentations.push_back(presentation);
+        }
+    }
 
-                _ => {}
+    /// Carry on with deferred presentations, oldest first, as far as each
+    /// agent's [`ColonyConfig::max_fragments_per_agent_per_tick`] allows.
+    fn present_deferred(
+        &mut self,
+        presented: &mut HashMap<AgentId, usize>,
+        events: &mut Vec<ColonyEvent>,
+    ) {
+        for presentation in std::mem::take(&mut self.deferred_presentations) {
+            if let Some(presentation) = self.present_within_cap(presentation, presented, events) {
+                self.deferred_presentations.push_back(presentation);
             }
         }
+    }
 
-        // Phase 2.5: Dissolution + Capability Integration
-        // For each agent: compute BoundaryContext, modulate boundary,
-        // externalize/internalize vocabulary, integrate nearby capabilities
-        {
-            let _tick = self.substrate.current_tick();
-            let agent_count = self.agents.len();
+    /// Put in the fragments of `presentation` its agent may still present
+    /// this tick, wiring them once all are in. Returns the presentation if
+    /// some are left for later ticks.
+    fn present_within_cap(
+        &mut self,
+        mut presentation: Presentation,
+        presented: &mut HashMap<AgentId, usize>,
+        events: &mut Vec<ColonyEvent>,
+    ) -> Option<Presentation> {
+        let remaining = presentation.fragments.len() - presentation.node_ids.len();
+        let count = if self.max_fragments_per_agent_per_tick == 0 {
+            remaining
+        } else {
+            let used = presented.entry(presentation.agent_id).or_insert(0);
+            let count = remaining.min(self.max_fragments_per_agent_per_tick.saturating_sub(*used));
+            *used += count;
+            count
+        };
+        if count > 0 {
+            self.present_fragments(&mut presentation, count, events);
+        }
+        if presentation.node_ids.len() < presentation.fragments.len() {
+            return Some(presentation);
+        }
+        self.wire_presentation(presentation, events);
+        None
+    }
 
-            for i in 0..agent_count {
-                let agent_id = self.agents[i].id();
-                let agent_pos = self.agents[i].position();
-                let agent_age = self.agents[i].age();
-
-                // Compute BoundaryContext — cache externalized vocab for reuse
-                let vocab_terms = self.agents[i].externalize_vocabulary();
-                let mut reinforcement_count = 0u64;
-                let graph = self.substrate.graph();
-                for term in &vocab_terms {
-                    let matching = graph.find_nodes_by_exact_label(term);
-                    for nid in matching {
-                        if let Some(node) = graph.get_node(nid) {
-                            reinforcement_count += node.access_count;
-                        }
+    /// Add or reinforce the nodes of the next `count` fragments of
+    /// `presentation`.
+    fn present_fragments(
+        &mut self,
+        presentation: &mut Presentation,
+        count: usize,
+        events: &mut Vec<ColonyEvent>,
+    ) {
+        let agent_id = presentation.agent_id;
+        let tick = self.substrate.current_tick();
+        let digesting = presentation.digesting;
+        let tags = digesting
+            .and_then(|(doc_id, _)| self.substrate.get_document(&doc_id))
+            .map(|d| d.tags.clone())
+            .unwrap_or_default();
+        let defers_wiring = self.defers_wiring();
+        // Code presentations name exactly what they mean: a substring match
+        // would merge `new` into `agent.rs::new_agent`
+        let qualified = presentation.fragments.iter().any(|f| f.namespace.is_some());
+        let start = presentation.node_ids.len();
+        let mut node_ids = Vec::new();
+        let mut references = Vec::new();
+        let mut anomaly_events = Vec::new();
+
+        for frag in &presentation.fragments[start..start + count] {
+            let access_weight = frag.access_weight.max(1);
+            let presented = frag.qualified_label();
+            // An alias is presented as its canonical term
+            let canonical = if qualified {
+                None
+            } else {
+                self.synonyms
+                    .map
+                    .canonical(&frag.label)
+                    .filter(|c| c.to_lowercase() != frag.label.to_lowercase())
+                    .map(str::to_string)
+            };
+            let label = self
+                .label_policy
+                .normalize(canonical.as_deref().unwrap_or(&presented))
+                .into_owned();
+            let plain = self
+                .label_policy
+                .normalize(canonical.as_deref().unwrap_or(&frag.label));
+            // Check if this concept already exists in the graph,
+            // under its label or an alias, before matching substrings
+            let graph = self.substrate.graph();
+            let existing = if qualified {
+                graph.find_nodes_by_exact_label(&label).first().copied()
+            } else if defers_wiring {
+                self.label_resolver
+                    .get_or_insert_with(LabelResolver::default)
+                    .resolve(graph, &plain)
+            } else {
+                graph
+                    .find_nodes_by_exact_label(&plain)
+                    .first()
+                    .copied()
+                    .or_else(|| graph.find_nodes_by_label(&plain).first().copied())
+            };
+            let node_id = if let Some(existing_id) = existing {
+                // Reinforce existing node
+                if let Some(node) = self.substrate.graph_mut().get_node_mut(&existing_id) {
+                    node.access_count += access_weight;
+                    node.last_accessed_tick = tick;
+                    for _ in 0..access_weight {
+                        node.add_tags(&tags);
                     }
                 }
-
-                let useful_outputs_estimate = reinforcement_count.min(100);
-                let trust = if agent_age > 0 {
-                    (useful_outputs_estimate as f64 / agent_age as f64).min(1.0)
-                } else {
-                    0.0
-                };
-
-                let context = BoundaryContext {
-                    reinforcement_count,
-                    age: agent_age,
-                    trust,
+                existing_id
+            } else {
+                // Create new node with the type specified by the agent
+                let mut node = NodeData {
+                    id: self.mint_node_id(),
+                    label: label.clone(),
+                    node_type: frag.node_type.clone(),
+                    position: frag.position,
+                    access_count: access_weight,
+                    created_tick: tick,
+                    last_accessed_tick: tick,
+                    tags: Default::default(),
                 };
-
-                self.agents[i].modulate_boundary(&context);
-                let permeability = self.agents[i].permeability();
-
-                // High permeability: boost matching graph nodes' access_count
-                if permeability > 0.5 {
-                    // Reuse cached vocab_terms instead of calling externalize_vocabulary again
-                    let mut terms_externalized = 0usize;
-                    for term in &vocab_terms {
-                        let matching: Vec<NodeId> = self
-                            .substrate
-                            .graph()
-                            .find_nodes_by_exact_label(term)
-                            .to_vec();
-                        for nid in &matching {
-                            if let Some(node) = self.substrate.graph_mut().get_node_mut(nid) {
-                                node.access_count += 1;
-                                terms_externalized += 1;
-                            }
-                        }
-                    }
-                    if terms_externalized > 0 {
-                        events.push(ColonyEvent::Dissolved {
-                            agent_id,
-                            permeability,
-                            terms_externalized,
-                        });
+                for _ in 0..access_weight {
+                    node.add_tags(&tags);
+                }
+                if frag.node_type == NodeType::Insight {
+                    self.insights_generated += 1;
+                }
+                self.concept_index.node_added(&node);
+                let id = self.substrate.add_node(node);
+                if let Some(embedding) = self.substrate.cached_embedding(&plain) {
+                    self.substrate.set_embedding(id, &embedding);
+                }
+                if canonical.is_none() && label != presented {
+                    // Nothing normalization changed is lost
+                    let graph = self.substrate.graph_mut();
+                    if label.to_lowercase() != presented.to_lowercase() {
+                        graph.add_alias(&id, &presented);
                     }
+                    graph.annotate(
+                        &id,
+                        Annotation {
+                            text: presented.clone(),
+                            source: ORIGINAL_LABEL_SOURCE.to_string(),
+                            tick,
+                        },
+                    );
+                }
+                if frag.namespace.is_some() {
+                    // Imports of the concept presented before its definition
+                    let graph = self.substrate.graph_mut();
+                    references.extend(
+                        graph
+                            .find_nodes_by_exact_label(&plain)
+                            .into_iter()
+                            .filter(|r| {
+                                graph.get_node(r).is_some_and(|n| {
+                                    n.node_type == NodeType::Concept
+                                        && split_qualified_label(&n.label).0.is_none()
+                                })
+                            })
+                            .map(|r| (id, r)),
+                    );
+                    // Still found by its plain label, e.g. from an import
+                    graph.add_alias(&id, &plain);
+                }
+                if let Some(resolver) = &mut self.label_resolver {
+                    resolver.node_added(id, &label);
                 }
+                id
+            };
+            if canonical.is_some() {
+                self.substrate
+                    .graph_mut()
+                    .add_alias(&node_id, &self.label_policy.normalize(&frag.label));
+            }
+            node_ids.push(node_id);
+            presentation.contribution.add_node(node_id, access_weight);
+            if let Some((doc_id, _)) = digesting {
+                // Filed under the node's own label, which queries look up
+                let label = self
+                    .substrate
+                    .graph()
+                    .get_node(&node_id)
+                    .map_or(label, |n| n.label.clone());
+                self.substrate.add_passages(doc_id, &label, &frag.passages);
+            }
 
-                // Any permeability > 0: internalize nearby concept labels
-                if permeability > 0.0 {
-                    let all_nodes = self.substrate.graph().all_nodes();
-                    let nearby_labels: Vec<String> = all_nodes
+            // Remember what an insight was derived from so it can be re-scored
+            if frag.node_type == NodeType::Insight && !frag.derived_from.is_empty() {
+                if let std::collections::hash_map::Entry::Vacant(entry) =
+                    self.insight_provenance.entry(node_id)
+                {
+                    let graph = self.substrate.graph();
+                    let sources: Vec<NodeId> = frag
+                        .derived_from
                         .iter()
-                        .filter_map(|nid| {
-                            let node = self.substrate.graph().get_node(nid)?;
-                            if node.position.distance_to(&agent_pos) <= 15.0
-                                && node.node_type == NodeType::Concept
-                            {
-                                Some(node.label.clone())
-                            } else {
-                                None
-                            }
-                        })
+                        .filter_map(|l| graph.find_nodes_by_exact_label(l).first().copied())
                         .collect();
-                    if !nearby_labels.is_empty() {
-                        self.agents[i].internalize_vocabulary(&nearby_labels);
-                    }
+                    entry.insert(sources);
                 }
+            }
 
-                // Capability integration: check for CapabilityDeposit traces near agent
-                let traces =
-                    self.substrate
-                        .traces_near(&agent_pos, 10.0, &TraceType::CapabilityDeposit);
-                for trace in &traces {
-                    if trace.agent_id != agent_id && !trace.payload.is_empty() {
-                        let payload = trace.payload.clone();
-                        let from_agent = trace.agent_id;
-                        let terms_count = serde_json::from_slice::<VocabularyCapability>(&payload)
-                            .map(|v| v.terms.len())
-                            .unwrap_or(0);
-                        if self.agents[i].integrate_vocabulary(&payload) {
-                            events.push(ColonyEvent::CapabilityIntegrated {
-                                agent_id,
-                                from_agent,
-                                terms_count,
-                            });
+            // Keep the score a scored anomaly was last presented with
+            if let (NodeType::Anomaly, Some(score)) = (&frag.node_type, frag.score) {
+                self.anomaly_scores.insert(node_id, score);
+                anomaly_events.push(ColonyEvent::AnomalyDetected {
+                    agent_id,
+                    label: self
+                        .substrate
+                        .graph()
+                        .get_node(&node_id)
+                        .map_or_else(|| frag.label.clone(), |n| n.label.clone()),
+                    score,
+                    document: self
+                        .substrate
+                        .get_document(&frag.source_document)
+                        .map(|d| d.id),
+                });
+            }
+        }
+
+        presentation.node_ids.extend_from_slice(&node_ids);
+        presentation.references.extend(references);
+        events.push(ColonyEvent::Presented {
+            id: agent_id,
+            fragment_count: count,
+            node_ids,
+        });
+        events.extend(anomaly_events);
+    }
+
+    /// Wire the co-occurring concepts of a fully presented presentation
+    /// and file what it contributed under its document.
+    fn wire_presentation(&mut self, presentation: Presentation, events: &mut Vec<ColonyEvent>) {
+        let Presentation {
+            agent_id,
+            fragments,
+            digesting,
+            node_ids,
+            references,
+            mut contribution,
+        } = presentation;
+        let tick = self.substrate.current_tick();
+        let source = digesting.map_or(EdgeSource::Unattributed, |(doc_id, _)| {
+            EdgeSource::Document(doc_id)
+        });
+        let mut wire_errors = Vec::new();
+        let deferred = self.defers_wiring().then(|| {
+            self.pending_wiring
+                .begin(agent_id, digesting.map(|(doc_id, _)| doc_id))
+        });
+
+        // Wire co-occurring concepts (from same document)
+        // Only wire Concept nodes — Insight/Anomaly nodes don't co-occur
+        //
+        // Co-activation gating (Hebbian LTP model):
+        // - First co-occurrence: create a TENTATIVE edge with low weight (0.1)
+        // - Subsequent co-occurrences: reinforce to full weight (+0.1 per hit)
+        // - Only edges reinforced by multiple documents survive synaptic pruning
+        // This reduces the dense graph problem: single-doc edges are weak
+        // and decay quickly unless reinforced by cross-document co-occurrence.
+        //
+        // Semantic wiring (Phase 9.3):
+        // - If nodes have embeddings, modulate edge weight by similarity
+        // - weight = base_weight * (1 + similarity_influence * similarity)
+        // - Below min_similarity threshold: skip or use base weight
+        //
+        // With `max_pairs_per_presentation` only the strongest
+        // co-occurrences are wired (see `wiring_pairs`). Qualified concepts
+        // are only wired within their namespace and to unqualified ones,
+        // such as the imports of a source file, and to what referred to
+        // them before they were defined.
+        let concepts: Vec<usize> = (0..node_ids.len())
+            .filter(|&k| {
+                self.substrate
+                    .graph()
+                    .get_node(&node_ids[k])
+                    .is_some_and(|n| n.node_type == NodeType::Concept)
+            })
+            .collect();
+        let concept_passages: Vec<&[TextSpan]> = concepts
+            .iter()
+            .map(|&k| fragments[k].passages.as_slice())
+            .collect();
+        let namespaces: Vec<Option<&str>> = concepts
+            .iter()
+            .map(|&k| fragments[k].namespace.as_deref())
+            .collect();
+        let pairs = wiring_pairs(
+            &concept_passages,
+            &namespaces,
+            self.digestion.max_pairs_per_presentation,
+        );
+        let pairs = pairs
+            .into_iter()
+            .map(|(i, j)| (node_ids[concepts[i]], node_ids[concepts[j]]))
+            .chain(references);
+        let mut wire_events = Vec::new();
+        for (from, to) in pairs {
+            // Get embeddings for semantic wiring, dequantized
+            let embedding_from = self.substrate.embedding(&from);
+            let embedding_to = self.substrate.embedding(&to);
+
+            // Compute semantic weight before mutating graph
+            let base_weight = 0.1;
+            let semantic_weight = compute_semantic_weight(
+                base_weight,
+                embedding_from.as_deref(),
+                embedding_to.as_deref(),
+                &self.semantic_wiring,
+            );
+
+            // Edge already exists: strengthen it (Hebbian reinforcement)
+            // Use semantic similarity to modulate reinforcement
+            let reinforcement = semantic_weight.unwrap_or(base_weight);
+            if let Some(deferred) = deferred {
+                // Wired like below once the tick's presentations are in
+                let exists = self.substrate.graph().get_edge(&from, &to).is_some()
+                    || self.pending_wiring.is_pending(from, to);
+                if exists || semantic_weight.is_some() {
+                    self.pending_wiring
+                        .push(deferred, from, to, reinforcement, semantic_weight);
+                    wire_events.push((from, to));
+                }
+                continue;
+            }
+            let before = self
+                .substrate
+                .graph()
+                .get_edge(&from, &to)
+                .map_or(0.0, |e| e.weight);
+            if apply_reinforcement(
+                self.substrate.graph_mut(),
+                &from,
+                &to,
+                reinforcement,
+                tick,
+                &self.weight_update,
+            ) {
+                let after = self
+                    .substrate
+                    .graph()
+                    .get_edge(&from, &to)
+                    .map_or(before, |e| e.weight);
+                contribution.add_edge(from, to, after - before, 1);
+                self.edge_provenance.record(from, to, source, tick);
+                wire_events.push((from, to));
+            } else {
+                // First co-occurrence: create tentative edge with low weight.
+                // Use semantic similarity to compute initial weight.
+                let weight = semantic_weight;
+
+                // Only create edge if semantic check passes
+                if let Some(w) = weight {
+                    let edge = EdgeData {
+                        weight: w,
+                        co_activations: 1,
+                        created_tick: tick,
+                        last_activated_tick: tick,
+                    };
+                    match self.substrate.set_edge(from, to, edge) {
+                        Ok(()) => {
+                            contribution.add_edge(from, to, w, 1);
+                            self.edge_provenance.forget(from, to);
+                            self.edge_provenance.record(from, to, source, tick);
+                            wire_events.push((from, to));
                         }
+                        Err(e) => wire_errors.push(e),
                     }
                 }
             }
         }
 
-        // Add symbiotic deaths to the death list
-        for (idx, _absorber_id) in &symbiotic_deaths {
-            if !to_die.contains(idx) {
-                to_die.push(*idx);
+        if !wire_events.is_empty() {
+            events.push(ColonyEvent::Wired {
+                id: agent_id,
+                connection_count: wire_events.len(),
+            });
+        }
+        if let Some(failure) = wiring_failure(agent_id, "present_fragments", &wire_errors) {
+            events.push(failure);
+        }
+
+        if let Some((doc_id, version)) = digesting {
+            self.document_contributions
+                .entry(doc_id)
+                .or_default()
+                .merge(contribution);
+            if self.pending_merges.contains_key(&doc_id)
+                || self.superseded_contributions.contains_key(&doc_id)
+            {
+                events.extend(self.flush_wiring());
+            }
+            if let Some(merges) = self.pending_merges.remove(&doc_id) {
+                for _ in 0..merges {
+                    self.merge_duplicate(doc_id);
+                }
+            }
+            if let Some(previous) = self.superseded_contributions.remove(&doc_id) {
+                events.push(self.retract_superseded(doc_id, version, previous));
             }
         }
+    }
 
-        // Phase 3: Remove dead agents
-        to_die.sort();
-        to_die.dedup();
-        let dead_count = to_die.len();
-        for idx in to_die.into_iter().rev() {
-            let agent = self.agents.remove(idx);
-            let mut death_signal = agent.prepare_death_signal();
+    /// Fragments presented to the colony but held back by
+    /// [`ColonyConfig::max_fragments_per_agent_per_tick`], still to be put
+    /// into the graph.
+    pub fn deferred_fragment_count(&self) -> usize {
+        self.deferred_presentations
+            .iter()
+            .map(|p| p.fragments.len() - p.node_ids.len())
+            .sum()
+    }
 
-            // Override cause if this was a symbiotic absorption
-            if let Some((_, absorber_id)) = symbiotic_deaths.iter().find(|(i, _)| *i == idx) {
-                death_signal.cause = DeathCause::SymbioticAbsorption(*absorber_id);
+    /// Wire `(from, to, base_weight)` connections for `agent`, reinforcing
+    /// existing edges and modulating weights by embedding similarity.
+    /// Returns how many were wired, and why the others could not be.
+    fn wire_connections(
+        &mut self,
+        agent: AgentId,
+        connections: impl IntoIterator<Item = (NodeId, NodeId, f64)>,
+    ) -> (usize, Vec<TopologyError>) {
+        let source = self.edge_source(&agent);
+        let tick = self.substrate.current_tick();
+        let mut wired_count = 0;
+        let mut errors = Vec::new();
+        for (from, to, base_weight) in connections {
+            // Get embeddings for semantic wiring, dequantized
+            let embedding_from = self.substrate.embedding(&from);
+            let embedding_to = self.substrate.embedding(&to);
+
+            // Compute semantic weight before mutating graph
+            let weight = compute_semantic_weight(
+                base_weight,
+                embedding_from.as_deref(),
+                embedding_to.as_deref(),
+                &self.semantic_wiring,
+            );
+
+            if let Some(w) = weight {
+                if !apply_reinforcement(
+                    self.substrate.graph_mut(),
+                    &from,
+                    &to,
+                    w,
+                    tick,
+                    &self.weight_update,
+                ) {
+                    let edge = EdgeData {
+                        weight: w,
+                        co_activations: 1,
+                        created_tick: tick,
+                        last_activated_tick: tick,
+                    };
+                    if let Err(e) = self.substrate.set_edge(from, to, edge) {
+                        errors.push(e);
+                        continue;
+                    }
+                    self.edge_provenance.forget(from, to);
+                }
+                self.edge_provenance.record(from, to, source, tick);
+                wired_count += 1;
             }
+        }
+        (wired_count, errors)
+    }
 
-            events.push(ColonyEvent::Died {
-                signal: death_signal.clone(),
-            });
-            self.death_signals.push(death_signal);
-            self.total_died += 1;
+    /// Emit an input signal at a new document to attract agents.
+    fn attract_to_document(&mut self, position: Position) {
+        // Nothing is digested where ingest is not allowed
+        if self.ingest_refused(&position).is_some() {
+            return;
+        }
+        self.substrate.emit_signal(Signal::new(
+            SignalType::Input,
+            1.0,
+            position,
+            AgentId::new(), // System-emitted
+            self.substrate.current_tick(),
+        ));
+    }
+
+    /// What the current version of a document contributed to the graph.
+    pub fn document_contribution(&self, id: &DocumentId) -> Option<&DocumentContribution> {
+        self.document_contributions.get(id)
+    }
+
+    /// Let a document expire `ttl_ticks` ticks from now. Once it has, the
+    /// document is removed at the start of a tick and what it alone
+    /// contributed is taken back: concepts only it presented are removed,
+    /// shared ones lose its presentations, and edges it wired lose its
+    /// share of their weight. A [`ColonyEvent::DocumentExpired`] is emitted.
+    /// A document still being digested expires once it has been presented.
+    ///
+    /// Returns false if there is no such document.
+    pub fn set_document_ttl(&mut self, id: &DocumentId, ttl_ticks: u64) -> bool {
+        let tick = self.substrate.current_tick().saturating_add(ttl_ticks);
+        self.update_expiry(id, |expiry| expiry.tick = Some(tick))
+    }
+
+    /// Let a document expire `ttl_seconds` of wall-clock time from now, as
+    /// [`set_document_ttl`](Self::set_document_ttl) does by ticks. Suits
+    /// deployments whose ticks do not follow time, such as an MCP server
+    /// that only runs ticks when asked to remember.
+    pub fn set_document_ttl_seconds(&mut self, id: &DocumentId, ttl_seconds: u64) -> bool {
+        let deadline = unix_now().saturating_add(ttl_seconds);
+        self.update_expiry(id, |expiry| expiry.unix_time = Some(deadline))
+    }
+
+    /// Set when a document expires, replacing any TTL it had, e.g. when
+    /// restoring a session. An expiry with no deadline clears it.
+    pub fn set_document_expiry(&mut self, id: &DocumentId, expiry: DocumentExpiry) -> bool {
+        self.update_expiry(id, |e| *e = expiry)
+    }
+
+    /// When a document expires, if it has a TTL.
+    pub fn document_expiry(&self, id: &DocumentId) -> Option<DocumentExpiry> {
+        self.document_expiry.get(id).copied()
+    }
+
+    /// Documents with a TTL, soonest tick deadline first, then soonest
+    /// wall-clock deadline.
+    pub fn upcoming_expirations(&self) -> Vec<(DocumentId, DocumentExpiry)> {
+        let mut upcoming: Vec<_> = self
+            .document_expiry
+            .iter()
+            .map(|(id, expiry)| (*id, *expiry))
+            .collect();
+        upcoming.sort_by_key(|(id, e)| {
+            (
+                e.tick.unwrap_or(Tick::MAX),
+                e.unix_time.unwrap_or(u64::MAX),
+                id.0,
+            )
+        });
+        upcoming
+    }
+
+    fn update_expiry(&mut self, id: &DocumentId, update: impl FnOnce(&mut DocumentExpiry)) -> bool {
+        if self.substrate.get_document(id).is_none() {
+            return false;
+        }
+        let expiry = self.document_expiry.entry(*id).or_default();
+        update(expiry);
+        let expiry = *expiry;
+        if expiry.tick.is_none() && expiry.unix_time.is_none() {
+            self.document_expiry.remove(id);
         }
+        self.record_replay_step(|| ReplayInput::Expiry {
+            document: *id,
+            expiry,
+        });
+        true
+    }
 
-        // Phase 4: Substrate decay
-        self.substrate
-            .decay_signals(self.signal_decay_rate, self.signal_removal_threshold);
-        self.substrate
-            .decay_traces(self.trace_decay_rate, self.trace_removal_threshold);
-        // Synaptic pruning: activity-based decay with maturation protection
-        let current_tick = self.substrate.current_tick();
-        self.substrate.graph_mut().decay_edges_activity(
-            self.edge_decay_rate,
-            self.edge_prune_threshold,
-            current_tick,
-            self.staleness_factor,
-            self.maturation_ticks,
+    /// Put back a document and its contribution, e.g. from a saved session.
+    /// No input signal is emitted.
+    pub fn restore_document(&mut self, doc: Document, contribution: DocumentContribution) {
+        if !contribution.is_empty() {
+            self.document_contributions.insert(doc.id, contribution);
+        }
+        self.substrate.add_document(doc);
+    }
+
+    /// Register a plugin. Plugins are called in registration order; see
+    /// [`crate::plugin`] for when each hook runs.
+    pub fn add_plugin(&mut self, plugin: Box<dyn ColonyPlugin>) {
+        self.plugins.push(plugin);
+    }
+
+    /// Carry out agents' [`AgentAction::Custom`] actions of `kind` with
+    /// `handler`, replacing any handler registered for it before. See
+    /// [custom actions](crate::plugin#custom-actions).
+    pub fn register_action_handler(
+        &mut self,
+        kind: impl Into<String>,
+        handler: Box<dyn CustomActionHandler>,
+    ) {
+        self.action_handlers.insert(kind.into(), handler);
+    }
+
+    /// Names of the registered plugins, in call order.
+    pub fn plugin_names(&self) -> Vec<&str> {
+        self.plugins.iter().map(|p| p.name()).collect()
+    }
+
+    /// Modulate as many boundaries of the current dissolution round as the
+    /// tick budget allows, starting a new round if one is `due` and none is
+    /// under way.
+    fn dissolve_within_budget(
+        &mut self,
+        due: bool,
+        started: Instant,
+        events: &mut Vec<ColonyEvent>,
+    ) {
+        let Some(maintenance) = self.maintenance.as_mut() else {
+            return;
+        };
+        if due {
+            let agents = &self.agents;
+            maintenance.begin_dissolution(|| agents.iter().map(|a| a.id()).collect());
+        }
+        let deadline = started + maintenance.budget();
+        let mut round = maintenance.take_dissolution();
+        self.dissolve_round(&mut round, Some(deadline), events);
+        if let Some(maintenance) = self.maintenance.as_mut() {
+            maintenance.restore_dissolution(round);
+        }
+    }
+
+    /// Modulate the boundaries of the next agents of a dissolution round.
+    fn dissolve_round(
+        &mut self,
+        round: &mut Pass<AgentId>,
+        deadline: Option<Instant>,
+        events: &mut Vec<ColonyEvent>,
+    ) {
+        if round.is_done() {
+            return;
+        }
+        self.concept_index.sync(self.substrate.graph());
+        let index: HashMap<AgentId, usize> = self
+            .agents
+            .iter()
+            .enumerate()
+            .map(|(i, a)| (a.id(), i))
+            .collect();
+        // Agents that died since the round started are skipped
+        round.run(deadline, |id| {
+            if let Some(&i) = index.get(&id) {
+                self.dissolve_boundary(i, events);
+            }
+        });
+    }
+
+    /// Bring budgeted maintenance up to date: decay every edge for every
+    /// tick so far, cap every node's degree and finish the dissolution
+    /// round, however long that takes. Afterwards the graph is what it
+    /// would be without a tick budget, bar edges reinforced in between.
+    ///
+    /// Does nothing without [`ColonyConfig::tick_budget_ms`].
+    pub fn finish_maintenance(&mut self) -> Vec<ColonyEvent> {
+        let mut events = Vec::new();
+        let Some(maintenance) = self.maintenance.as_mut() else {
+            return events;
+        };
+        let multipliers = self.substrate.edge_decay_multipliers();
+        maintenance.catch_up(
+            self.substrate.graph_mut(),
+            &multipliers,
+            self.max_edge_degree,
         );
-        // Competitive pruning: cap per-node degree
-        self.substrate
-            .graph_mut()
-            .prune_to_max_degree(self.max_edge_degree);
+        let mut round = maintenance.take_dissolution();
+        self.dissolve_round(&mut round, None, &mut events);
+        if !self.edge_relations.is_empty() {
+            let graph = self.substrate.graph();
+            self.edge_relations
+                .retain(|(from, to), _| graph.get_edge(from, to).is_some());
+        }
+        self.substrate.graph_mut().flush();
+        events
+    }
 
-        // Phase 4b: Fitness tracking — wire colony events to the tracker
-        for event in &events {
-            match event {
-                ColonyEvent::Presented {
-                    id, fragment_count, ..
-                } => {
-                    self.fitness_tracker
-                        .record_concepts(id, *fragment_count as u64);
-                }
-                ColonyEvent::Wired {
-                    id,
-                    connection_count,
-                } => {
-                    self.fitness_tracker
-                        .record_edges(id, *connection_count as u64);
+    /// Run a single simulation tick.
+    pub fn tick(&mut self) -> Vec<ColonyEvent> {
+        let started = Instant::now();
+        self.begin_replay_step();
+        let mut events = Vec::new();
+        let mut actions: Vec<(usize, AgentAction)> = Vec::new();
+
+        // Phase 0: Plugins see the substrate before anyone acts
+        for plugin in &mut self.plugins {
+            plugin.on_tick_start(&mut PluginContext::new(&mut self.substrate));
+        }
+        // Catch the term index up with changes made behind the colony's back
+        self.substrate.sync_term_index();
+        self.expire_documents(&mut events);
+
+        // Phase 1: All agents sense and decide
+        for (idx, agent) in self.agents.iter_mut().enumerate() {
+            let action = agent.tick(&self.substrate);
+            actions.push((idx, action));
+        }
+
+        // Phase 2: Process actions, after what earlier presentations held back
+        let mut presented = HashMap::new();
+        self.present_deferred(&mut presented, &mut events);
+        let mut to_die = Vec::new();
+        let mut symbiotic_deaths: Vec<(usize, AgentId)> = Vec::new(); // (idx, absorber_id)
+
+        for (idx, mut action) in actions {
+            // Deferred wiring must land before anything else reads the graph
+            if !matches!(action, AgentAction::PresentFragments(_)) || !self.plugins.is_empty() {
+                events.extend(self.flush_wiring());
+            }
+
+            // Plugins may veto or replace the action
+            let agent_id = self.agents[idx].id();
+            let mut skipped = false;
+            for plugin in &mut self.plugins {
+                match plugin.before_action(&self.substrate, agent_id, &action) {
+                    ActionDecision::Allow => {}
+                    ActionDecision::Skip => {
+                        skipped = true;
+                        break;
+                    }
+                    ActionDecision::Replace(replacement) => action = replacement,
                 }
-                _ => {}
             }
-        }
-        let alive_ids: Vec<AgentId> = self.agents.iter().map(|a| a.id()).collect();
-        self.fitness_tracker.tick_all(&alive_ids);
+            if skipped {
+                continue;
+            }
+
+            match action {
+                AgentAction::Move(pos) => {
+                    let pos = self.substrate.geometry().confine(pos);
+                    let from = self.agents[idx].position();
+                    if let Some(region) = self.entry_refused(&from, &pos) {
+                        events.push(ColonyEvent::action_failed(
+                            agent_id,
+                            "move",
+                            format!("region '{}' does not allow agent entry", region.name),
+                        ));
+                        continue;
+                    }
+                    self.agents[idx].set_position(pos);
+                    events.push(ColonyEvent::Moved {
+                        id: self.agents[idx].id(),
+                        to: pos,
+                    });
+                }
+
+                AgentAction::EngulfDocument(doc_id) => {
+                    let refused = self
+                        .substrate
+                        .get_document(&doc_id)
+                        .and_then(|d| self.ingest_refused(&d.position));
+                    if let Some(region) = refused {
+                        events.push(ColonyEvent::action_failed(
+                            agent_id,
+                            "engulf_document",
+                            format!("region '{}' does not allow ingest", region.name),
+                        ));
+                        continue;
+                    }
+                    let version = self
+                        .substrate
+                        .get_document(&doc_id)
+                        .map_or(1, |d| d.version);
+                    // Try to consume the document from substrate
+                    if let Some(content) = self.substrate.consume_document(&doc_id) {
+                        // Remember the document for the fragments this digestion presents
+                        let agent_id = self.agents[idx].id();
+                        self.digesting.insert(agent_id, (doc_id, version));
+                        // A new version replaces what the previous one contributed
+                        if version > 1 {
+                            let previous = self
+                                .document_contributions
+                                .remove(&doc_id)
+                                .unwrap_or_default();
+                            self.superseded_contributions
+                                .entry(doc_id)
+                                .or_default()
+                                .merge(previous);
+                        }
+                        self.agents[idx].engulf(content);
+                        // Also set the document context via downcast
+                        // (The agent's state machine will handle digestion next tick)
+                        events.push(ColonyEvent::Engulfed {
+                            id: self.agents[idx].id(),
+                            document: doc_id,
+                        });
+                    } else {
+                        let reason = match self.substrate.get_document(&doc_id) {
+                            Some(_) => "document already digested",
+                            None => "document not found",
+                        };
+                        events.push(ColonyEvent::action_failed(
+                            agent_id,
+                            "engulf_document",
+                            reason,
+                        ));
+                    }
+                }
+
+                AgentAction::PresentFragments(fragments) => {
+                    let agent_id = self.agents[idx].id();
+                    self.accept_presentation(agent_id, fragments, &mut presented, &mut events);
+                }
+
+                AgentAction::Deposit(location, trace) => {
+                    let agent_id = self.agents[idx].id();
+                    self.substrate.deposit_trace(&location, trace);
+                    events.push(ColonyEvent::Deposited {
+                        id: agent_id,
+                        location,
+                    });
+                }
+
+                AgentAction::Emit(signal) => {
+                    if signal.signal_type == SignalType::Quorum {
+                        self.last_quorum_tick = Some(self.substrate.current_tick());
+                    }
+                    self.substrate.emit_signal(signal);
+                }
+
+                AgentAction::WireNodes(connections) => {
+                    let agent_id = self.agents[idx].id();
+                    let (wired_count, errors) = self.wire_connections(agent_id, connections);
+                    if wired_count > 0 {
+                        events.push(ColonyEvent::Wired {
+                            id: agent_id,
+                            connection_count: wired_count,
+                        });
+                    }
+                    events.extend(wiring_failure(agent_id, "wire_nodes", &errors));
+                }
+
+                AgentAction::WireRelations(relations) => {
+                    let agent_id = self.agents[idx].id();
+                    let (wired_count, errors) = self.wire_connections(
+                        agent_id,
+                        relations.iter().map(|r| (r.from, r.to, r.weight)),
+                    );
+                    for r in relations {
+                        if self.substrate.graph().get_edge(&r.from, &r.to).is_some() {
+                            self.edge_relations.remove(&(r.to, r.from));
+                            self.edge_relations.insert((r.from, r.to), r.relation);
+                        }
+                    }
+                    if wired_count > 0 {
+                        events.push(ColonyEvent::Wired {
+                            id: agent_id,
+                            connection_count: wired_count,
+                        });
+                    }
+                    events.extend(wiring_failure(agent_id, "wire_relations", &errors));
+                }
+
+                AgentAction::EmbedConcepts(embeddings) => {
+                    let agent_id = self.agents[idx].id();
+                    events.extend(self.embed_concepts(agent_id, embeddings));
+                }
+
+                AgentAction::ExportCapability(_cap_id) => {
+                    let agent_id = self.agents[idx].id();
+                    let agent_pos = self.agents[idx].position();
+                    if let Some(vocab_bytes) = self.agents[idx].export_vocabulary() {
+                        events.push(self.deposit_capability(agent_id, agent_pos, vocab_bytes));
+                    }
+                }
+
+                AgentAction::SymbioseWith(target_id) => {
+                    let host_idx = idx;
+                    let host_id = self.agents[host_idx].id();
+
+                    // Find target agent
+                    if let Some(target_idx) = self.agents.iter().position(|a| a.id() == target_id) {
+                        // Build target's profile and extract vocabulary
+                        let target_profile = self.agents[target_idx].profile();
+                        let target_vocab = self.agents[target_idx]
+                            .export_vocabulary()
+                            .unwrap_or_default();
+
+                        // Evaluate symbiosis
+                        if let Some(SymbiosisEval::Integrate) =
+                            self.agents[host_idx].evaluate_symbiosis(&target_profile)
+                        {
+                            let host_type = self.agents[host_idx].agent_type().to_string();
+                            let absorbed_type = self.agents[target_idx].agent_type().to_string();
+
+                            // Host absorbs the symbiont
+                            self.agents[host_idx].absorb_symbiont(target_profile, target_vocab);
+
+                            // Mark target for removal via symbiotic absorption
+                            symbiotic_deaths.push((target_idx, host_id));
+
+                            events.push(ColonyEvent::Symbiosis {
+                                host: host_id,
+                                absorbed: target_id,
+                                host_type,
+                                absorbed_type,
+                            });
+                        }
+                    } else {
+                        events.push(ColonyEvent::action_failed(
+                            host_id,
+                            "symbiose_with",
+                            "target agent not found",
+                        ));
+                    }
+                }
+
+                AgentAction::Apoptose => {
+                    to_die.push(idx);
+                }
+
+                AgentAction::Custom { kind, payload } => {
+                    let agent_id = self.agents[idx].id();
+                    let Some(handler) = self.action_handlers.get_mut(&kind) else {
+                        events.push(ColonyEvent::action_failed(
+                            agent_id,
+                            "custom",
+                            format!("no handler for custom action '{kind}'"),
+                        ));
+                        continue;
+                    };
+                    let mut ctx = ActionContext::new(
+                        &mut self.substrate,
+                        &mut self.concept_index,
+                        agent_id,
+                        self.agents[idx].position(),
+                        &kind,
+                    );
+                    let result = handler.handle(&mut ctx, &payload);
+                    events.extend(ctx.into_events());
+                    if let Err(reason) = result {
+                        events.push(ColonyEvent::action_failed(
+                            agent_id,
+                            "custom",
+                            format!("{kind}: {reason}"),
+                        ));
+                    }
+                }
+
+                AgentAction::Idle | AgentAction::ContributeToCollective => {}
+            }
+        }
+        events.extend(self.flush_wiring());
+
+        // Phase 2.5: Dissolution + Capability Integration
+        // Every `dissolution_interval` ticks, modulate each agent's boundary
+        // and externalize/internalize vocabulary; every tick, integrate
+        // nearby capabilities
+        {
+            let due = self.dissolution_interval > 0
+                && self
+                    .substrate
+                    .current_tick()
+                    .is_multiple_of(self.dissolution_interval);
+            // With a tick budget, boundaries are modulated in rounds instead
+            let dissolve = due && self.maintenance.is_none();
+            if dissolve {
+                self.concept_index.sync(self.substrate.graph());
+            } else if self.maintenance.is_some() {
+                self.dissolve_within_budget(due, started, &mut events);
+            }
+
+            for i in 0..self.agents.len() {
+                if dissolve {
+                    self.dissolve_boundary(i, &mut events);
+                }
+
+                // Capability integration: check for CapabilityDeposit traces near agent
+                let agent_id = self.agents[i].id();
+                let agent_pos = self.agents[i].position();
+                let traces =
+                    self.substrate
+                        .traces_near(&agent_pos, 10.0, &TraceType::CapabilityDeposit);
+                for trace in &traces {
+                    if trace.agent_id != agent_id && !trace.payload.is_empty() {
+                        // Borrowed for legacy payloads, inflated if compressed
+                        let capability = trace_payload::decode(&trace.payload)
+                            .ok_or_else(|| "compressed payload cannot be inflated".to_string())
+                            .and_then(|payload| {
+                                serde_json::from_slice::<VocabularyCapability>(&payload)
+                                    .map(|capability| (capability, payload))
+                                    .map_err(|e| format!("invalid vocabulary payload: {e}"))
+                            });
+                        let (capability, payload) = match capability {
+                            Ok(read) => read,
+                            Err(reason) => {
+                                // Reported once; the deposit stays until it decays
+                                if self
+                                    .unreadable_deposits
+                                    .insert((trace.agent_id, trace.tick))
+                                {
+                                    events.push(ColonyEvent::action_failed(
+                                        agent_id,
+                                        "integrate_capability",
+                                        reason,
+                                    ));
+                                }
+                                continue;
+                            }
+                        };
+                        let terms_offered = capability.terms.len();
+                        let (payload, terms_accepted) = if self.transfer.accepts_all() {
+                            (payload, terms_offered)
+                        } else {
+                            let trust = self.boundary_trust.get(&agent_id).copied();
+                            let accepted = self.transfer.accepted_terms(
+                                &capability,
+                                agent_id,
+                                trust.unwrap_or(0.0),
+                            );
+                            let terms_accepted = accepted.len();
+                            let filtered = VocabularyCapability {
+                                terms: accepted,
+                                term_reinforcement: Vec::new(),
+                                ..capability
+                            };
+                            match serde_json::to_vec(&filtered) {
+                                Ok(bytes) => (Cow::Owned(bytes), terms_accepted),
+                                Err(_) => continue,
+                            }
+                        };
+                        if self.agents[i].integrate_vocabulary(&payload) {
+                            events.push(ColonyEvent::CapabilityIntegrated {
+                                agent_id,
+                                from_agent: trace.agent_id,
+                                terms_accepted,
+                                terms_offered,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // Add symbiotic deaths to the death list
+        for (idx, _absorber_id) in &symbiotic_deaths {
+            if !to_die.contains(idx) {
+                to_die.push(*idx);
+            }
+        }
+
+        // Phase 3: Remove dead agents
+        to_die.sort();
+        to_die.dedup();
+        let dead_count = to_die.len();
+        let mut dying = Vec::new();
+        for idx in to_die.into_iter().rev() {
+            let mut agent = self.agents.remove(idx);
+            let agent_id = agent.id();
+            self.digesting.remove(&agent_id);
+            self.concept_index.forget_agent(&agent_id);
+            self.boundary_trust.remove(&agent_id);
+            self.agent_specs.remove(&agent_id);
+            self.inherited_terms.remove(&agent_id);
+            let genome = self.genomes.remove(&agent_id);
+            if self.breeder.is_some() {
+                dying.push(Dying {
+                    id: agent_id,
+                    position: agent.position(),
+                    genome,
+                    vocabulary: agent.export_vocabulary(),
+                });
+            }
+            let mut death_signal = agent.prepare_death_signal();
+
+            // Put back a document the agent engulfed but never presented
+            if let Some((doc_id, _content)) = agent.release_undigested() {
+                death_signal.had_pending_work = true;
+                if self.release_document(&doc_id) {
+                    events.push(ColonyEvent::WorkReleased {
+                        agent_id,
+                        document: doc_id,
+                    });
+                }
+            }
+
+            // Override cause if this was a symbiotic absorption; otherwise
+            // leave the vocabulary behind for successors
+            if let Some((_, absorber_id)) = symbiotic_deaths.iter().find(|(i, _)| *i == idx) {
+                death_signal.cause = DeathCause::SymbioticAbsorption(*absorber_id);
+            } else if let Some(vocab_bytes) = agent.export_vocabulary() {
+                events.push(self.deposit_capability(agent_id, agent.position(), vocab_bytes));
+            }
+
+            if let Some(lifespan) = self.lifespans.get_mut(&agent_id) {
+                lifespan.died = Some((self.substrate.current_tick(), death_signal.cause.clone()));
+            }
+            events.push(ColonyEvent::Died {
+                signal: death_signal.clone(),
+            });
+            self.death_signals.push(death_signal);
+            self.total_died += 1;
+        }
+
+        // Phase 4: Substrate decay, at rates adapted to load if configured
+        let rates = match self.decay_controller.as_mut() {
+            Some(controller) => {
+                let tick = self.substrate.current_tick();
+                let edges = self.substrate.graph().all_edges();
+                let mature_edges = edges
+                    .iter()
+                    .filter(|(_, _, e)| {
+                        tick.saturating_sub(e.created_tick) >= self.maturation_ticks
+                    })
+                    .count();
+                let median_edge_weight = match controller.config().target_median_weight {
+                    Some(_) => {
+                        let mut weights: Vec<f64> =
+                            edges.iter().map(|(_, _, e)| e.weight).collect();
+                        decay::median(&mut weights)
+                    }
+                    None => None,
+                };
+                controller.update(DecayLoad {
+                    edges: edges.len(),
+                    mature_edges,
+                    signals: self.substrate.all_signals().len(),
+                    traces: self.substrate.total_trace_count(),
+                    median_edge_weight,
+                })
+            }
+            None => self.fixed_decay_rates(),
+        };
+        self.substrate
+            .decay_signals(rates.signal, self.signal_removal_threshold);
+        self.substrate
+            .decay_traces(rates.trace, self.trace_removal_threshold);
+        // Synaptic pruning: activity-based decay with maturation protection,
+        // then competitive pruning to cap per-node degree
+        let current_tick = self.substrate.current_tick();
+        let decay = EdgeDecay {
+            rate: rates.edge,
+            prune_threshold: self.edge_prune_threshold,
+            staleness_factor: self.staleness_factor,
+            maturation_ticks: self.maturation_ticks,
+        };
+        // Edges in regions decay at their regions' rates
+        let multipliers = self.substrate.edge_decay_multipliers();
+        match self.maintenance.as_mut() {
+            Some(maintenance) => {
+                // As much of each pass as the budget leaves room for
+                let deadline = started + maintenance.budget();
+                let graph = self.substrate.graph_mut();
+                maintenance.decay_edges(graph, decay, &multipliers, current_tick, Some(deadline));
+                maintenance.prune_to_max_degree(graph, self.max_edge_degree, Some(deadline));
+            }
+            None if !multipliers.is_empty() => {
+                let graph = self.substrate.graph_mut();
+                maintenance::decay_edges_scaled(graph, decay, &multipliers, current_tick);
+                graph.prune_to_max_degree(self.max_edge_degree);
+            }
+            None => {
+                let graph = self.substrate.graph_mut();
+                graph.decay_edges_activity(
+                    rates.edge,
+                    self.edge_prune_threshold,
+                    current_tick,
+                    self.staleness_factor,
+                    self.maturation_ticks,
+                );
+                graph.prune_to_max_degree(self.max_edge_degree);
+            }
+        }
+        // Relation labels and provenance go with their edges
+        if !self.edge_relations.is_empty() {
+            let graph = self.substrate.graph();
+            self.edge_relations
+                .retain(|(from, to), _| graph.get_edge(from, to).is_some());
+        }
+        if !self.edge_provenance.is_empty() {
+            self.edge_provenance.retain_edges(self.substrate.graph());
+        }
+
+        // Phase 4a: Insight maintenance — re-score insights against their sources
+        if self.insight_maintenance_interval > 0
+            && current_tick.is_multiple_of(self.insight_maintenance_interval)
+        {
+            events.extend(self.maintain_insights());
+        }
+
+        // Phase 4b: Node forgetting — drop concepts nobody presents or retrieves
+        if self.node_staleness_ticks > 0 {
+            events.extend(self.prune_stale_nodes());
+        }
+
+        // Phase 4c: Label compaction — merge concepts whose labels are variants
+        if self.compaction.interval > 0 && current_tick.is_multiple_of(self.compaction.interval) {
+            let strategy = self.compaction.strategy;
+            events.extend(self.compact_labels(&strategy));
+        }
+
+        // Phase 4d: Fitness tracking — wire colony events to the tracker
+        for event in &events {
+            match event {
+                ColonyEvent::Presented {
+                    id, fragment_count, ..
+                } => {
+                    self.fitness_tracker
+                        .record_concepts(id, *fragment_count as u64);
+                }
+                ColonyEvent::Wired {
+                    id,
+                    connection_count,
+                } => {
+                    self.fitness_tracker
+                        .record_edges(id, *connection_count as u64);
+                }
+                ColonyEvent::ActionFailed { action_kind, .. } => {
+                    *self
+                        .error_counters
+                        .action_failures
+                        .entry(action_kind.clone())
+                        .or_insert(0) += 1;
+                }
+                _ => {}
+            }
+        }
+        let alive_ids: Vec<AgentId> = self.agents.iter().map(|a| a.id()).collect();
+        self.fitness_tracker.tick_all(&alive_ids);
+
+        // Phase 4e: Replacement — breed a successor for each agent that died
+        for dead in &dying {
+            events.extend(self.breed_replacement(dead.id, &alive_ids, &dying));
+        }
+
+        // Phase 4f: Community tracking — re-detect, keeping IDs stable
+        let interval = self.community_config.interval;
+        if interval > 0 && current_tick.is_multiple_of(interval) {
+            self.update_communities();
+        }
+
+        // Phase 4g: Exploration schedule — how much agents exploit next tick
+        if let Some(controller) = self.exploration.as_mut() {
+            let docs = self.substrate.all_documents();
+            let digested = docs.iter().filter(|d| d.digested).count();
+            let exploitation = controller.update(docs.len(), digested, self.substrate.node_count());
+            self.substrate.set_ambient(Ambient { exploitation });
+        }
+
+        // Phase 5: Advance tick and persist buffered graph writes
+        self.substrate.advance_tick();
+        self.substrate.graph_mut().flush();
+
+        events.push(ColonyEvent::TickComplete {
+            tick: self.substrate.current_tick(),
+            alive: self.agents.len(),
+            dead_this_tick: dead_count,
+        });
+
+        // Record events in history
+        let current_tick = self.substrate.current_tick();
+        for event in &events {
+            self.event_history.push((current_tick, event.clone()));
+        }
+
+        if let Some((registry, space)) = &self.metrics {
+            registry.record_tick(space, &self.stats(), &events, started.elapsed());
+        }
+
+        if !self.plugins.is_empty() {
+            let stats = self.stats();
+            for plugin in &mut self.plugins {
+                for event in &events {
+                    plugin.on_event(current_tick, event);
+                }
+                plugin.on_tick_end(&stats);
+            }
+        }
+
+        self.end_replay_step(|| ReplayInput::Tick { tick: current_tick });
+        events
+    }
+
+    /// Support score of an insight: mean weight of the edges between its
+    /// anchor concept and the related concepts it was derived from.
+    ///
+    /// Missing nodes or edges count as zero. Returns `None` for nodes
+    /// without recorded provenance.
+    pub fn insight_support(&self, insight: &NodeId) -> Option<f64> {
+        let sources = self.insight_provenance.get(insight)?;
+        let graph = self.substrate.graph();
+        let Some((anchor, related)) = sources.split_first() else {
+            return Some(0.0);
+        };
+        if graph.get_node(anchor).is_none() || related.is_empty() {
+            return Some(0.0);
+        }
+        let total: f64 = related
+            .iter()
+            .filter_map(|r| graph.get_edge(anchor, r))
+            .map(|e| e.weight)
+            .sum();
+        Some(total / related.len() as f64)
+    }
+
+    /// The concept nodes an insight was derived from (anchor first).
+    pub fn insight_provenance(&self, insight: &NodeId) -> Option<&[NodeId]> {
+        self.insight_provenance.get(insight).map(|v| v.as_slice())
+    }
+
+    /// The label a node was presented with, if the [`LabelPolicy`] changed
+    /// it. See [`crate::label_policy`].
+    pub fn original_label(&self, node: &NodeId) -> Option<String> {
+        self.substrate
+            .graph()
+            .annotations(node)
+            .into_iter()
+            .find(|a| a.source == ORIGINAL_LABEL_SOURCE)
+            .map(|a| a.text)
+    }
+
+    /// The novelty score an anomaly node was last presented with, from 0.0
+    /// to 1.0. `None` for other nodes and for unscored anomalies.
+    pub fn anomaly_score(&self, node: &NodeId) -> Option<f64> {
+        self.anomaly_scores.get(node).copied()
+    }
+
+    /// Score an anomaly node, as if an agent had presented it with
+    /// `score`. Returns `false`, changing nothing, if the node is not an
+    /// anomaly.
+    pub fn set_anomaly_score(&mut self, node: &NodeId, score: f64) -> bool {
+        let is_anomaly = self
+            .substrate
+            .graph()
+            .get_node(node)
+            .is_some_and(|n| n.node_type == NodeType::Anomaly);
+        if is_anomaly {
+            self.anomaly_scores.insert(*node, score);
+        }
+        is_anomaly
+    }
+
+    /// Scored anomaly nodes, the highest score first.
+    pub fn anomalies(&self) -> Vec<(NodeId, f64)> {
+        let graph = self.substrate.graph();
+        let mut anomalies: Vec<(NodeId, f64)> = self
+            .anomaly_scores
+            .iter()
+            .filter(|(id, _)| graph.get_node(id).is_some())
+            .map(|(id, score)| (*id, *score))
+            .collect();
+        anomalies.sort_by(|a, b| b.1.total_cmp(&a.1));
+        anomalies
+    }
+
+    /// The relation an agent labelled the edge `from → to` with, if any.
+    ///
+    /// Relations are directional: `edge_relation(a, b)` and
+    /// `edge_relation(b, a)` never both return a label.
+    pub fn edge_relation(&self, from: &NodeId, to: &NodeId) -> Option<&str> {
+        self.edge_relations.get(&(*from, *to)).map(String::as_str)
+    }
+
+    /// What reinforced the edge between `a` and `b` lately, oldest first,
+    /// at most [`ColonyConfig::edge_provenance`] records.
+    pub fn edge_sources(&self, a: &NodeId, b: &NodeId) -> Vec<SourceRecord> {
+        self.edge_provenance.sources(*a, *b)
+    }
+
+    /// The recent reinforcements of every edge. See [`crate::provenance`].
+    pub fn edge_provenance(&self) -> &EdgeProvenance {
+        &self.edge_provenance
+    }
+
+    /// Put back recorded reinforcements of the edge between `a` and `b`,
+    /// oldest first, e.g. from a saved session.
+    pub fn restore_edge_sources(
+        &mut self,
+        a: NodeId,
+        b: NodeId,
+        records: impl IntoIterator<Item = SourceRecord>,
+    ) {
+        for record in records {
+            self.edge_provenance
+                .record(a, b, record.source, record.tick);
+        }
+    }
+
+    /// What wiring by `agent` is credited to: the document it is digesting,
+    /// if any.
+    fn edge_source(&self, agent: &AgentId) -> EdgeSource {
+        self.digesting
+            .get(agent)
+            .map_or(EdgeSource::Unattributed, |(doc_id, _)| {
+                EdgeSource::Document(*doc_id)
+            })
+    }
+
+    /// Re-score every insight with provenance, decay its access count in
+    /// proportion to its support, and remove insights whose support fell
+    /// below `insight_prune_threshold`.
+    ///
+    /// Runs automatically every `insight_maintenance_interval` ticks.
+    pub fn maintain_insights(&mut self) -> Vec<ColonyEvent> {
+        let mut events = Vec::new();
+        let insight_ids: Vec<NodeId> = self.insight_provenance.keys().copied().collect();
+
+        for id in insight_ids {
+            if self.substrate.graph().get_node(&id).is_none() {
+                self.insight_provenance.remove(&id);
+                continue;
+            }
+            let support = self.insight_support(&id).unwrap_or(0.0);
+
+            if support < self.insight_prune_threshold {
+                self.insight_provenance.remove(&id);
+                if let Some(node) = self.substrate.remove_node(&id) {
+                    self.concept_index.node_removed(&node);
+                    events.push(ColonyEvent::InsightPruned {
+                        node_id: id,
+                        label: node.label,
+                        support,
+                    });
+                }
+            } else if let Some(node) = self.substrate.graph_mut().get_node_mut(&id) {
+                node.access_count = (node.access_count as f64 * support.min(1.0)).ceil() as u64;
+            }
+        }
+
+        events
+    }
+
+    /// Retention of a node at the current tick: its access count, halved
+    /// for every `node_staleness_ticks` since it was last accessed.
+    ///
+    /// Returns `None` when the node does not exist or node pruning is disabled.
+    pub fn node_retention(&self, id: &NodeId) -> Option<f64> {
+        if self.node_staleness_ticks == 0 {
+            return None;
+        }
+        let node = self.substrate.graph().get_node(id)?;
+        let idle = self
+            .substrate
+            .current_tick()
+            .saturating_sub(node.last_accessed_tick.max(node.created_tick));
+        let half_lives = idle as f64 / self.node_staleness_ticks as f64;
+        Some(node.access_count as f64 * 0.5_f64.powf(half_lives))
+    }
+
+    /// Mark nodes as accessed at the current tick, e.g. because a query
+    /// retrieved them. Unknown IDs are ignored.
+    pub fn touch_nodes(&mut self, ids: &[NodeId]) {
+        self.record_replay_step(|| ReplayInput::Touch {
+            nodes: ids.to_vec(),
+        });
+        self.substrate.touch_nodes(ids);
+    }
+
+    /// Strengthen what a query retrieved: raise each node's access count
+    /// by its boost, and its count under each of `tags` it carries, then
+    /// reinforce each edge by its amount under the colony's
+    /// [`WeightUpdate`] rule. Returns the edges reinforced, in order;
+    /// missing ones are skipped.
+    pub fn reinforce_retrieval(
+        &mut self,
+        nodes: &[(NodeId, u64)],
+        tags: &[String],
+        edges: &[(NodeId, NodeId, f64)],
+    ) -> Vec<(NodeId, NodeId)> {
+        self.record_replay_step(|| ReplayInput::Reinforce {
+            nodes: nodes.to_vec(),
+            tags: tags.to_vec(),
+            edges: edges.to_vec(),
+        });
+        let tick = self.substrate.current_tick();
+        let rule = self.weight_update;
+        let graph = self.substrate.graph_mut();
+        for (id, boost) in nodes {
+            if let Some(node) = graph.get_node_mut(id) {
+                node.access_count += boost;
+                for tag in tags {
+                    if let Some(count) = node.tags.get_mut(tag) {
+                        *count += boost;
+                    }
+                }
+            }
+        }
+        let reinforced: Vec<(NodeId, NodeId)> = edges
+            .iter()
+            .filter(|(from, to, amount)| apply_reinforcement(graph, from, to, *amount, tick, &rule))
+            .map(|(from, to, _)| (*from, *to))
+            .collect();
+        for (from, to) in &reinforced {
+            self.edge_provenance
+                .record(*from, *to, EdgeSource::Query, tick);
+        }
+        reinforced
+    }
+
+    /// Remove prunable nodes whose retention fell below `node_prune_threshold`
+    /// and that have no edge of at least `node_anchor_edge_weight` left.
+    ///
+    /// Runs automatically every tick when `node_staleness_ticks` is non-zero.
+    pub fn prune_stale_nodes(&mut self) -> Vec<ColonyEvent> {
+        let mut events = Vec::new();
+        if self.node_staleness_ticks == 0 {
+            return events;
+        }
+
+        let graph = self.substrate.graph();
+        let stale: Vec<(NodeId, f64)> = graph
+            .all_nodes()
+            .into_iter()
+            .filter(|id| {
+                graph
+                    .get_node(id)
+                    .is_some_and(|n| self.node_prune_types.contains(&n.node_type))
+            })
+            .filter_map(|id| {
+                let retention = self.node_retention(&id)?;
+                (retention < self.node_prune_threshold).then_some((id, retention))
+            })
+            .filter(|(id, _)| {
+                graph
+                    .neighbors(id)
+                    .iter()
+                    .all(|(_, edge)| edge.weight < self.node_anchor_edge_weight)
+            })
+            .collect();
+
+        for (id, retention) in stale {
+            self.insight_provenance.remove(&id);
+            self.anomaly_scores.remove(&id);
+            for contribution in self
+                .document_contributions
+                .values_mut()
+                .chain(self.superseded_contributions.values_mut())
+            {
+                contribution.forget_node(id);
+            }
+            if let Some(node) = self.substrate.remove_node(&id) {
+                self.concept_index.node_removed(&node);
+                events.push(ColonyEvent::NodePruned {
+                    node_id: id,
+                    label: node.label,
+                    retention,
+                });
+            }
+        }
+
+        events
+    }
+
+    /// Clusters of concepts whose labels are variants of one another under
+    /// `strategy`, each with the node the others would be merged into.
+    /// Changes nothing; see [`compact_labels`](Self::compact_labels).
+    pub fn plan_compaction(&self, strategy: &CompactionStrategy) -> CompactionPlan {
+        compaction::plan(
+            self.substrate.graph(),
+            self.substrate.embeddings(),
+            strategy,
+        )
+    }
+
+    /// Merge every cluster of `plan` into its canonical node (see
+    /// [`merge_nodes`](Self::merge_nodes)). Nodes that no longer exist are
+    /// skipped.
+    pub fn apply_compaction(&mut self, plan: &CompactionPlan) -> Vec<ColonyEvent> {
+        plan.clusters
+            .iter()
+            .filter_map(|cluster| {
+                let merged: Vec<NodeId> = cluster.merged.iter().map(|m| m.id).collect();
+                self.merge_nodes(cluster.canonical, &merged)
+            })
+            .collect()
+    }
+
+    /// Merge concepts whose labels are variants of one another under
+    /// `strategy`: plan, then apply.
+    ///
+    /// Runs automatically every `compaction.interval` ticks when configured.
+    pub fn compact_labels(&mut self, strategy: &CompactionStrategy) -> Vec<ColonyEvent> {
+        let plan = self.plan_compaction(strategy);
+        self.apply_compaction(&plan)
+    }
+
+    /// Define a named region of the field, replacing any region of the
+    /// same name. See [`crate::region`].
+    pub fn define_region(
+        &mut self,
+        name: &str,
+        shape: RegionShape,
+        policy: RegionPolicy,
+    ) -> Result<(), RegionError> {
+        let region = Region::new(name, shape, policy)?;
+        self.record_replay_step(|| ReplayInput::DefineRegion {
+            region: region.clone(),
+        });
+        self.substrate.add_region(region);
+        Ok(())
+    }
+
+    /// Move the concept labelled `label` to the middle of the region named
+    /// `to_region`, so that it and its edges decay at that region's rates
+    /// from now on. Returns the node moved.
+    pub fn promote_node(&mut self, label: &str, to_region: &str) -> Result<NodeId, RegionError> {
+        let center = self
+            .substrate
+            .region(to_region)
+            .ok_or_else(|| RegionError::UnknownRegion(to_region.to_string()))?
+            .shape
+            .center();
+        let id = self
+            .find_labelled(label.trim())
+            .ok_or_else(|| RegionError::UnknownConcept(label.to_string()))?;
+        self.record_replay_step(|| ReplayInput::Promote {
+            label: label.to_string(),
+            region: to_region.to_string(),
+        });
+        if let Some(node) = self.substrate.graph_mut().get_node_mut(&id) {
+            node.position = center;
+        }
+        self.substrate.mark_changed();
+        Ok(id)
+    }
+
+    /// A region closed to agents that moving from `from` to `to` enters.
+    fn entry_refused(&self, from: &Position, to: &Position) -> Option<&Region> {
+        self.substrate
+            .regions()
+            .iter()
+            .find(|r| !r.policy.allow_agent_entry && r.contains(to) && !r.contains(from))
+    }
+
+    /// A region that does not allow ingest containing `position`.
+    fn ingest_refused(&self, position: &Position) -> Option<&Region> {
+        self.substrate
+            .regions()
+            .iter()
+            .find(|r| !r.policy.allow_ingest && r.contains(position))
+    }
+
+    /// Replace the colony's synonyms, e.g. with a dictionary reloaded from
+    /// disk. Concepts already in the graph under an alias are merged into
+    /// the concept of its canonical term, created if there is none yet, and
+    /// queries see the new synonyms from now on.
+    pub fn set_synonyms(&mut self, synonyms: SynonymConfig) -> Vec<ColonyEvent> {
+        self.synonyms = synonyms;
+        // Cached query results were expanded with the old synonyms
+        self.substrate.mark_changed();
+
+        let entries: Vec<(String, Vec<String>)> = self
+            .synonyms
+            .map
+            .iter()
+            .map(|(canonical, aliases)| (canonical.to_string(), aliases.to_vec()))
+            .collect();
+        let mut events = Vec::new();
+        for (canonical, aliases) in entries {
+            let label = self.label_policy.normalize(&canonical).into_owned();
+            let merged: Vec<NodeId> = aliases
+                .iter()
+                .flat_map(|alias| self.concepts_labelled(&self.label_policy.normalize(alias)))
+                .collect();
+            let Some(&first) = merged.first() else {
+                continue;
+            };
+            let target = match self.concepts_labelled(&label).first() {
+                Some(&id) => id,
+                None => {
+                    let graph = self.substrate.graph();
+                    let position = graph
+                        .get_node(&first)
+                        .map_or_else(|| Position::new(0.0, 0.0), |n| n.position);
+                    let tick = self.substrate.current_tick();
+                    let node = NodeData {
+                        id: self.mint_node_id(),
+                        label,
+                        node_type: NodeType::Concept,
+                        position,
+                        access_count: 0,
+                        created_tick: tick,
+                        last_accessed_tick: 0,
+                        tags: Default::default(),
+                    };
+                    self.concept_index.node_added(&node);
+                    self.substrate.add_node(node)
+                }
+            };
+            events.extend(self.merge_nodes(target, &merged));
+        }
+        events
+    }
+
+    /// Unqualified concepts labelled `label`, ignoring case; not those
+    /// only having it as an alias.
+    fn concepts_labelled(&self, label: &str) -> Vec<NodeId> {
+        let graph = self.substrate.graph();
+        let key = label.to_lowercase();
+        graph
+            .find_nodes_by_exact_label(label)
+            .into_iter()
+            .filter(|id| {
+                graph.get_node(id).is_some_and(|n| {
+                    n.node_type == NodeType::Concept
+                        && n.label.to_lowercase() == key
+                        && split_qualified_label(&n.label).0.is_none()
+                })
+            })
+            .collect()
+    }
+
+    /// Detect communities with the configured algorithm and track them, so
+    /// communities that persist keep their IDs and node snapshots carry
+    /// them.
+    ///
+    /// Runs automatically every `communities.interval` ticks when
+    /// configured.
+    pub fn update_communities(&mut self) -> CommunityResult {
+        let algorithm = self.community_config.algorithm;
+        Arc::make_mut(&mut self.communities).detect(self.substrate.graph(), algorithm)
+    }
+
+    /// The tracker behind [`update_communities`](Self::update_communities).
+    pub fn community_tracker(&self) -> &CommunityTracker {
+        &self.communities
+    }
+
+    /// The tracker, shared rather than copied, for read views.
+    pub(crate) fn shared_community_tracker(&self) -> Arc<CommunityTracker> {
+        Arc::clone(&self.communities)
+    }
+
+    pub(crate) fn community_algorithm(&self) -> CommunityAlgorithm {
+        self.community_config.algorithm
+    }
+
+    /// Fold the nodes in `merged` into `canonical`.
+    ///
+    /// Their edges are rewired to `canonical`, adding weights (capped at
+    /// 1.0) and co-activations where it already has that edge; access
+    /// counts and tags are added up. Their labels, and any aliases they
+    /// had, become aliases of `canonical`, so presenting or looking up one
+    /// of them by exact label finds `canonical`. Document contributions,
+    /// insight provenance, edge relations and provenance, annotations and
+    /// passages follow.
+    ///
+    /// Returns `None` if `canonical` does not exist or nothing was merged.
+    pub fn merge_nodes(&mut self, canonical: NodeId, merged: &[NodeId]) -> Option<ColonyEvent> {
+        let label = self.substrate.graph().get_node(&canonical)?.label.clone();
+        let mut merged_labels = Vec::new();
+
+        for &id in merged {
+            if id == canonical {
+                continue;
+            }
+            let graph = self.substrate.graph_mut();
+            let Some(node) = graph.get_node(&id).cloned() else {
+                continue;
+            };
+            let aliases = graph.aliases(&id);
+            let annotations = graph.annotations(&id);
+
+            let edges: Vec<(NodeId, EdgeData)> = graph
+                .neighbors(&id)
+                .into_iter()
+                .filter(|(other, _)| *other != canonical)
+                .map(|(other, edge)| (other, edge.clone()))
+                .collect();
+            for (other, edge) in edges {
+                match graph.get_edge_mut(&canonical, &other) {
+                    Some(existing) => {
+                        existing.weight = (existing.weight + edge.weight).min(1.0);
+                        existing.co_activations += edge.co_activations;
+                        existing.created_tick = existing.created_tick.min(edge.created_tick);
+                        existing.last_activated_tick =
+                            existing.last_activated_tick.max(edge.last_activated_tick);
+                    }
+                    None => {
+                        // Both endpoints are in the graph
+                        let _ = graph.set_edge(canonical, other, edge);
+                    }
+                }
+            }
+
+            if let Some(target) = graph.get_node_mut(&canonical) {
+                target.access_count += node.access_count;
+                target.created_tick = target.created_tick.min(node.created_tick);
+                target.last_accessed_tick = target.last_accessed_tick.max(node.last_accessed_tick);
+                for (tag, count) in &node.tags {
+                    *target.tags.entry(tag.clone()).or_insert(0) += count;
+                }
+            }
+            self.substrate.embeddings_mut().merge(&id, canonical);
+            if let Some(removed) = self.substrate.remove_node(&id) {
+                self.concept_index.node_removed(&removed);
+            }
+            let graph = self.substrate.graph_mut();
+            graph.add_alias(&canonical, &node.label);
+            for alias in &aliases {
+                graph.add_alias(&canonical, alias);
+            }
+            for annotation in annotations {
+                graph.annotate(&canonical, annotation);
+            }
+            self.substrate.rename_passages(&node.label, &label);
+
+            for contribution in self
+                .document_contributions
+                .values_mut()
+                .chain(self.superseded_contributions.values_mut())
+            {
+                contribution.replace_node(id, canonical);
+            }
+            self.edge_provenance.replace_node(id, canonical);
+            if let Some(sources) = self.insight_provenance.remove(&id) {
+                self.insight_provenance.entry(canonical).or_insert(sources);
+            }
+            if let Some(score) = self.anomaly_scores.remove(&id) {
+                let kept = self.anomaly_scores.entry(canonical).or_insert(score);
+                *kept = kept.max(score);
+            }
+            for sources in self.insight_provenance.values_mut() {
+                if sources.contains(&id) {
+                    let mut seen = HashSet::new();
+                    sources.retain_mut(|s| {
+                        if *s == id {
+                            *s = canonical;
+                        }
+                        seen.insert(*s)
+                    });
+                }
+            }
+            let relations: Vec<((NodeId, NodeId), String)> = self
+                .edge_relations
+                .iter()
+                .filter(|((from, to), _)| *from == id || *to == id)
+                .map(|(key, relation)| (*key, relation.clone()))
+                .collect();
+            for ((from, to), relation) in relations {
+                self.edge_relations.remove(&(from, to));
+                let from = if from == id { canonical } else { from };
+                let to = if to == id { canonical } else { to };
+                if from != to
+                    && !self.edge_relations.contains_key(&(from, to))
+                    && !self.edge_relations.contains_key(&(to, from))
+                {
+                    self.edge_relations.insert((from, to), relation);
+                }
+            }
+
+            merged_labels.push(node.label);
+        }
+
+        if merged_labels.is_empty() {
+            return None;
+        }
+        Some(ColonyEvent::NodesMerged {
+            node_id: canonical,
+            label,
+            merged: merged_labels,
+        })
+    }
+
+    /// Node labelled `label` (case-insensitive), preferring a concept.
+    /// Cache the embeddings an agent computed and give them to the
+    /// concepts they are of. One failed action reports those the agent
+    /// could not compute.
+    fn embed_concepts(
+        &mut self,
+        agent_id: AgentId,
+        embeddings: Vec<ConceptEmbedding>,
+    ) -> Option<ColonyEvent> {
+        let mut failed = Vec::new();
+        for ConceptEmbedding { label, embedding } in embeddings {
+            let embedding = match embedding {
+                Ok(embedding) => embedding,
+                Err(reason) => {
+                    failed.push(format!("'{label}': {reason}"));
+                    continue;
+                }
+            };
+            // Named as the concept was when presented
+            let canonical = self.synonyms.map.canonical(&label).map(str::to_string);
+            let normalized = self
+                .label_policy
+                .normalize(canonical.as_deref().unwrap_or(label.trim()))
+                .into_owned();
+            if let Some(id) = self.find_labelled(&normalized) {
+                self.substrate.set_embedding(id, &embedding);
+            }
+            self.substrate
+                .embedding_cache_mut()
+                .insert(&normalized, embedding);
+        }
+        let first = failed.first()?;
+        let reason = match failed.len() {
+            1 => format!("could not embed {first}"),
+            n => format!("could not embed {n} concepts, first {first}"),
+        };
+        Some(ColonyEvent::action_failed(
+            agent_id,
+            "embed_concepts",
+            reason,
+        ))
+    }
+
+    fn find_labelled(&self, label: &str) -> Option<NodeId> {
+        let graph = self.substrate.graph();
+        let ids = graph.find_nodes_by_exact_label(label);
+        ids.iter()
+            .find(|id| {
+                graph
+                    .get_node(id)
+                    .is_some_and(|n| n.node_type == NodeType::Concept)
+            })
+            .or(ids.first())
+            .copied()
+    }
+
+    /// Connect the concepts labelled `from` and `to` with knowledge from
+    /// outside the colony, e.g. an agent that knows they are related
+    /// although no document mentions both.
+    ///
+    /// A new edge starts at `weight`; an existing one is reinforced by it
+    /// under the colony's weight update rule. `relation` labels the edge as
+    /// [`AgentAction::WireRelations`] does. With `create_missing`, labels
+    /// without a node get a new concept next to the other endpoint;
+    /// otherwise they are an error. The edge decays and is pruned like any
+    /// other.
+    pub fn connect_concepts(
+        &mut self,
+        from: &str,
+        to: &str,
+        weight: f64,
+        relation: Option<&str>,
+        create_missing: bool,
+    ) -> Result<ColonyEvent, GraphEditError> {
+        let (from, to) = (from.trim(), to.trim());
+        if from.is_empty() || to.is_empty() {
+            return Err(GraphEditError::EmptyLabel);
+        }
+        if !(weight > 0.0 && weight <= 1.0) {
+            return Err(GraphEditError::InvalidWeight(weight));
+        }
+        let from_id = self.find_labelled(from);
+        let to_id = self.find_labelled(to);
+        if !create_missing {
+            if from_id.is_none() {
+                return Err(GraphEditError::UnknownConcept(from.to_string()));
+            }
+            if to_id.is_none() {
+                return Err(GraphEditError::UnknownConcept(to.to_string()));
+            }
+        }
+        if (from_id.is_some() && from_id == to_id) || from.to_lowercase() == to.to_lowercase() {
+            return Err(GraphEditError::SelfLoop(from.to_string()));
+        }
+
+        let tick = self.substrate.current_tick();
+        let position = [from_id, to_id]
+            .iter()
+            .flatten()
+            .find_map(|id| self.substrate.graph().get_node(id).map(|n| n.position))
+            .unwrap_or(Position::new(0.0, 0.0));
+        let mut created_nodes = Vec::new();
+        let mut resolve = |colony: &mut Self, id: Option<NodeId>, label: &str| {
+            id.unwrap_or_else(|| {
+                let node = NodeData {
+                    id: colony.mint_node_id(),
+                    label: label.to_string(),
+                    node_type: NodeType::Concept,
+                    position,
+                    access_count: 1,
+                    created_tick: tick,
+                    last_accessed_tick: tick,
+                    tags: Default::default(),
+                };
+                created_nodes.push(node.label.clone());
+                colony.concept_index.node_added(&node);
+                colony.substrate.add_node(node)
+            })
+        };
+        let from_id = resolve(self, from_id, from);
+        let to_id = resolve(self, to_id, to);
+
+        let created_edge = !apply_reinforcement(
+            self.substrate.graph_mut(),
+            &from_id,
+            &to_id,
+            weight,
+            tick,
+            &self.weight_update,
+        );
+        if created_edge {
+            let edge = EdgeData {
+                weight,
+                co_activations: 1,
+                created_tick: tick,
+                last_activated_tick: tick,
+            };
+            self.substrate
+                .set_edge(from_id, to_id, edge)
+                .map_err(GraphEditError::Topology)?;
+            self.edge_provenance.forget(from_id, to_id);
+        }
+        self.edge_provenance
+            .record(from_id, to_id, EdgeSource::Unattributed, tick);
+        if let Some(relation) = relation.map(str::trim).filter(|r| !r.is_empty()) {
+            self.edge_relations.remove(&(to_id, from_id));
+            self.edge_relations
+                .insert((from_id, to_id), relation.to_string());
+        }
+
+        let weight = self
+            .substrate
+            .graph()
+            .get_edge(&from_id, &to_id)
+            .map_or(weight, |e| e.weight);
+        let event = ColonyEvent::Connected {
+            from: from_id,
+            to: to_id,
+            weight,
+            created_edge,
+            created_nodes,
+        };
+        self.event_history.push((tick, event.clone()));
+        Ok(event)
+    }
+
+    /// Add imported triples to the graph, creating the concepts and edges
+    /// that are missing and keeping the higher weight of edges that are
+    /// not. Importing the same set twice changes nothing the second time.
+    /// See [`crate::import`].
+    pub fn import_triples(&mut self, set: &TripleSet) -> ImportReport {
+        let tick = self.substrate.current_tick();
+        let source = self.edge_provenance.import_source(&set.source);
+        let groups: HashMap<&str, &str> = set
+            .triples
+            .iter()
+            .filter_map(|t| Some((t.subject.as_str(), t.group.as_deref()?)))
+            .collect();
+        let mut report = ImportReport {
+            skipped: set.skipped,
+            ..Default::default()
+        };
+        // Labels resolved so far, so each is counted once
+        let mut resolved: HashMap<String, NodeId> = HashMap::new();
+        let mut resolve = |colony: &mut Self, raw: &str, report: &mut ImportReport| {
+            // Named as a presented fragment would be
+            let canonical = colony.synonyms.map.canonical(raw).map(str::to_string);
+            let label = colony
+                .label_policy
+                .normalize(canonical.as_deref().unwrap_or(raw.trim()))
+                .into_owned();
+            if label.is_empty() {
+                return None;
+            }
+            if let Some(&id) = resolved.get(&label.to_lowercase()) {
+                return Some(id);
+            }
+            let id = match colony.find_labelled(&label) {
+                Some(id) => {
+                    report.nodes_merged += 1;
+                    id
+                }
+                None => {
+                    report.nodes_created += 1;
+                    let node = NodeData {
+                        id: colony.mint_node_id(),
+                        label: label.clone(),
+                        node_type: NodeType::Concept,
+                        position: layout_position(&label, groups.get(raw).copied()),
+                        access_count: 1,
+                        created_tick: tick,
+                        last_accessed_tick: tick,
+                        tags: Default::default(),
+                    };
+                    colony.concept_index.node_added(&node);
+                    let id = colony.substrate.add_node(node);
+                    if let Some(resolver) = &mut colony.label_resolver {
+                        resolver.node_added(id, &label);
+                    }
+                    id
+                }
+            };
+            resolved.insert(label.to_lowercase(), id);
+            Some(id)
+        };
+
+        for triple in &set.triples {
+            let from = resolve(self, &triple.subject, &mut report);
+            let to = resolve(self, &triple.object, &mut report);
+            let (Some(from), Some(to)) = (from, to) else {
+                report.skipped += 1;
+                continue;
+            };
+            if from == to {
+                report.skipped += 1;
+                continue;
+            }
+            let weight = triple
+                .weight
+                .unwrap_or(TENTATIVE_WEIGHT)
+                .clamp(f64::MIN_POSITIVE, 1.0);
+            let graph = self.substrate.graph_mut();
+            match graph.get_edge_mut(&from, &to) {
+                Some(edge) => {
+                    report.edges_merged += 1;
+                    if edge.weight < weight {
+                        edge.weight = weight;
+                        edge.last_activated_tick = tick;
+                        self.edge_provenance.record(from, to, source, tick);
+                    }
+                }
+                None => {
+                    // A new edge matures from now on like any other
+                    let edge = EdgeData {
+                        weight,
+                        co_activations: 1,
+                        created_tick: tick,
+                        last_activated_tick: tick,
+                    };
+                    if self.substrate.set_edge(from, to, edge).is_err() {
+                        report.skipped += 1;
+                        continue;
+                    }
+                    report.edges_created += 1;
+                    self.edge_provenance.forget(from, to);
+                    self.edge_provenance.record(from, to, source, tick);
+                }
+            }
+            let relation = triple.predicate.trim();
+            if !relation.is_empty()
+                && !self.edge_relations.contains_key(&(from, to))
+                && !self.edge_relations.contains_key(&(to, from))
+            {
+                self.edge_relations.insert((from, to), relation.to_string());
+            }
+        }
+        report
+    }
+
+    /// The provenance source of triples imported from `name`, e.g. to put
+    /// back records of a saved session. See [`EdgeProvenance::import_source`].
+    pub fn import_source(&mut self, name: &str) -> EdgeSource {
+        self.edge_provenance.import_source(name)
+    }
+
+    /// Attach a free-text note to the node labelled `label` (preferring a
+    /// concept). `source` names who made it, e.g. `"mcp"`.
+    pub fn annotate(
+        &mut self,
+        label: &str,
+        text: &str,
+        source: &str,
+    ) -> Result<ColonyEvent, GraphEditError> {
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(GraphEditError::EmptyLabel);
+        }
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(GraphEditError::EmptyNote);
+        }
+        let len = text.chars().count();
+        if len > MAX_ANNOTATION_CHARS {
+            return Err(GraphEditError::NoteTooLong(len));
+        }
+        let node_id = self
+            .find_labelled(label)
+            .ok_or_else(|| GraphEditError::UnknownConcept(label.to_string()))?;
+
+        let tick = self.substrate.current_tick();
+        let graph = self.substrate.graph();
+        let node_label = graph
+            .get_node(&node_id)
+            .map_or_else(|| label.to_string(), |n| n.label.clone());
+        if graph
+            .annotations(&node_id)
+            .iter()
+            .any(|a| a.text == text && a.source == source)
+        {
+            return Err(GraphEditError::DuplicateNote(node_label));
+        }
+        let annotation = Annotation {
+            text: text.to_string(),
+            source: source.to_string(),
+            tick,
+        };
+        self.substrate.graph_mut().annotate(&node_id, annotation);
+
+        let event = ColonyEvent::Annotated {
+            node_id,
+            label: node_label,
+            source: source.to_string(),
+        };
+        self.event_history.push((tick, event.clone()));
+        Ok(event)
+    }
+
+    /// Notes attached to `node`, in the order they were made.
+    pub fn annotations(&self, node: &NodeId) -> Vec<Annotation> {
+        self.substrate.graph().annotations(node)
+    }
+
+    /// Take back what a document's previous versions contributed and its
+    /// current version does not. Nodes no other document presented are
+    /// removed.
+    fn retract_superseded(
+        &mut self,
+        id: DocumentId,
+        version: u32,
+        previous: DocumentContribution,
+    ) -> ColonyEvent {
+        let current = self
+            .document_contributions
+            .get(&id)
+            .cloned()
+            .unwrap_or_default();
+        let tags = self
+            .substrate
+            .get_document(&id)
+            .map(|d| d.tags.clone())
+            .unwrap_or_default();
+
+        let graph = self.substrate.graph();
+        let mut concepts_added: Vec<String> = current
+            .nodes
+            .keys()
+            .filter(|n| !previous.nodes.contains_key(n))
+            .filter_map(|n| graph.get_node(n).map(|node| node.label.clone()))
+            .collect();
+        concepts_added.sort();
+
+        // Edges first, while both endpoints still exist
+        let graph = self.substrate.graph_mut();
+        for ((a, b), added) in &previous.edges {
+            if current.edges.contains_key(&(*a, *b)) {
+                continue;
+            }
+            let exhausted = match graph.get_edge_mut(a, b) {
+                Some(edge) => {
+                    edge.weight -= added.weight;
+                    edge.co_activations = edge.co_activations.saturating_sub(added.co_activations);
+                    edge.co_activations == 0 || edge.weight <= 0.0
+                }
+                None => false,
+            };
+            if exhausted {
+                graph.remove_edge(a, b);
+            }
+        }
+
+        let mut concepts_removed = Vec::new();
+        for (node_id, count) in &previous.nodes {
+            if current.nodes.contains_key(node_id) {
+                continue;
+            }
+            if let Some((label, _)) = self.retract_node(id, node_id, *count, &tags) {
+                concepts_removed.push(label);
+            }
+        }
+        concepts_removed.sort();
+
+        ColonyEvent::DocumentUpdated {
+            id,
+            version,
+            concepts_added,
+            concepts_removed,
+        }
+    }
+
+    /// Take back `count` presentations of a node by document `id`, and
+    /// the tags they carried. The node is removed if no other document
+    /// presented it or nothing accesses it any more. Returns its label and
+    /// whether it was removed, or `None` if the node is already gone.
+    fn retract_node(
+        &mut self,
+        id: DocumentId,
+        node_id: &NodeId,
+        count: u64,
+        tags: &[String],
+    ) -> Option<(String, bool)> {
+        let supported_elsewhere = self
+            .document_contributions
+            .iter()
+            .any(|(doc, c)| *doc != id && c.nodes.contains_key(node_id));
+        let node = self.substrate.graph_mut().get_node_mut(node_id)?;
+        let label = node.label.clone();
+        node.access_count = node.access_count.saturating_sub(count);
+        for tag in tags {
+            if let Some(n) = node.tags.get_mut(tag) {
+                *n = n.saturating_sub(count);
+                if *n == 0 {
+                    node.tags.remove(tag);
+                }
+            }
+        }
+        let removed = !supported_elsewhere || node.access_count == 0;
+        if removed {
+            if let Some(node) = self.substrate.remove_node(node_id) {
+                self.concept_index.node_removed(&node);
+            }
+            self.insight_provenance.remove(node_id);
+            self.anomaly_scores.remove(node_id);
+        }
+        Some((label, removed))
+    }
+
+    /// Expire the documents whose TTL has run out, except those still
+    /// being digested, which expire once they have been presented.
+    fn expire_documents(&mut self, events: &mut Vec<ColonyEvent>) {
+        if self.document_expiry.is_empty() {
+            return;
+        }
+        let tick = self.substrate.current_tick();
+        let now = unix_now();
+        let mut due: Vec<DocumentId> = self
+            .document_expiry
+            .iter()
+            .filter(|(_, expiry)| expiry.is_due(tick, now))
+            .map(|(id, _)| *id)
+            .filter(|id| !self.awaiting_presentation(id))
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+        due.sort_by_key(|id| id.0);
+        // Deferred wiring still owes these documents contributions
+        events.extend(self.flush_wiring());
+        for id in due {
+            events.push(self.expire_document(id));
+        }
+    }
+
+    /// Whether an agent engulfed the document and has yet to present it.
+    fn awaiting_presentation(&self, id: &DocumentId) -> bool {
+        !self.document_contributions.contains_key(id)
+            && self.digesting.values().any(|(doc, _)| doc == id)
+    }
+
+    /// Remove a document and take back what it contributed: concepts only
+    /// it presented are removed, and edges lose the share of their weight
+    /// its co-activations account for.
+    fn expire_document(&mut self, id: DocumentId) -> ColonyEvent {
+        self.document_expiry.remove(&id);
+        self.pending_merges.remove(&id);
+        self.dedup_index.remove(&id);
+        self.edge_provenance.forget_document(&id);
+        let mut contribution = self.document_contributions.remove(&id).unwrap_or_default();
+        if let Some(superseded) = self.superseded_contributions.remove(&id) {
+            contribution.merge(superseded);
+        }
+        let tags = self
+            .substrate
+            .remove_document(&id)
+            .map(|d| d.tags)
+            .unwrap_or_default();
+
+        // Edges first, while both endpoints still exist
+        let mut edges_weakened = 0;
+        let graph = self.substrate.graph_mut();
+        for ((a, b), added) in &contribution.edges {
+            let Some(edge) = graph.get_edge_mut(a, b) else {
+                continue;
+            };
+            edges_weakened += 1;
+            let share = if edge.co_activations == 0 {
+                1.0
+            } else {
+                (added.co_activations as f64 / edge.co_activations as f64).min(1.0)
+            };
+            edge.weight *= 1.0 - share;
+            edge.co_activations = edge.co_activations.saturating_sub(added.co_activations);
+            if edge.co_activations == 0 || edge.weight <= 0.0 {
+                graph.remove_edge(a, b);
+            }
+        }
+
+        let mut nodes_removed = 0;
+        for (node_id, count) in &contribution.nodes {
+            if let Some((_, true)) = self.retract_node(id, node_id, *count, &tags) {
+                nodes_removed += 1;
+            }
+        }
+
+        ColonyEvent::DocumentExpired {
+            id,
+            nodes_removed,
+            edges_weakened,
+        }
+    }
+
+    /// Run the simulation for N ticks.
+    pub fn run(&mut self, ticks: u64) -> Vec<Vec<ColonyEvent>> {
+        let mut all_events = Vec::new();
+        for _ in 0..ticks {
+            all_events.push(self.tick());
+        }
+        all_events
+    }
+
+    /// Get colony statistics.
+    pub fn stats(&self) -> ColonyStats {
+        let docs = self.substrate.all_documents();
+        let digested = docs.iter().filter(|d| d.digested).count();
+        ColonyStats {
+            tick: self.substrate.current_tick(),
+            agents_alive: self.agents.len(),
+            agents_died: self.total_died,
+            total_spawned: self.total_spawned,
+            graph_nodes: self.substrate.node_count(),
+            graph_edges: self.substrate.edge_count(),
+            total_signals: self.substrate.all_signals().len(),
+            signals_by_kind: self.substrate.signal_counts(),
+            documents_total: docs.len(),
+            documents_digested: digested,
+            trace_bytes: self.substrate.trace_memory_bytes(),
+            traces_evicted: self.substrate.traces_evicted(),
+            signals_merged: self.substrate.signals_merged(),
+            signals_evicted: self.substrate.signals_evicted(),
+            errors: self.error_counters.clone(),
+            decay: self.decay_report().rates,
+            maintenance_backlog: self.maintenance.as_ref().map_or(0, |m| m.backlog()),
+            documents_expiring: self.document_expiry.len(),
+            next_expiry_tick: self.document_expiry.values().filter_map(|e| e.tick).min(),
+            exploitation: self.substrate.ambient().exploitation,
+        }
+    }
+
+    /// The rates signals, traces and edges decay at, and with adaptive
+    /// decay the load they were chosen for.
+    pub fn decay_report(&self) -> DecayReport {
+        match &self.decay_controller {
+            Some(controller) => DecayReport {
+                adaptive: true,
+                rates: controller.rates(),
+                load: controller.load(),
+                config: Some(controller.config().clone()),
+            },
+            None => DecayReport {
+                adaptive: false,
+                rates: self.fixed_decay_rates(),
+                load: DecayLoad::default(),
+                config: None,
+            },
+        }
+    }
+
+    fn fixed_decay_rates(&self) -> DecayRates {
+        DecayRates {
+            signal: self.signal_decay_rate,
+            trace: self.trace_decay_rate,
+            edge: self.edge_decay_rate,
+        }
+    }
+
+    /// Get a reference to the substrate.
+    pub fn substrate(&self) -> &SubstrateImpl {
+        &self.substrate
+    }
+
+    /// Revision of the knowledge graph, documents and passages; see
+    /// [`SubstrateImpl::graph_revision`]. Cheap enough to check per query.
+    pub fn graph_revision(&self) -> u64 {
+        self.substrate.graph_revision()
+    }
+
+    /// Get a mutable reference to the substrate.
+    ///
+    /// The colony cannot see what is changed through it, so its concept
+    /// index is rebuilt from the graph on the next dissolution pass, and
+    /// its term index on the next tick (or [`sync_term_index`]).
+    ///
+    /// [`sync_term_index`]: Self::sync_term_index
+    pub fn substrate_mut(&mut self) -> &mut SubstrateImpl {
+        self.concept_index.invalidate();
+        self.substrate.invalidate_term_index();
+        &mut self.substrate
+    }
+
+    /// Rebuild the term index now if changes made through
+    /// [`substrate_mut`](Self::substrate_mut) left it out of date, rather
+    /// than on the next tick. Until then queries scan the graph. Returns
+    /// whether it was rebuilt.
+    pub fn sync_term_index(&mut self) -> bool {
+        self.substrate.sync_term_index()
+    }
+
+    /// Number of agents currently alive.
+    pub fn alive_count(&self) -> usize {
+        self.agents.len()
+    }
+
+    /// All death signals collected during the simulation.
+    pub fn death_signals(&self) -> &[DeathSignal] {
+        &self.death_signals
+    }
+
+    /// Birth and death of every agent spawned into this colony, living or
+    /// dead, in no particular order.
+    pub fn lifespans(&self) -> impl Iterator<Item = &AgentLifespan> {
+        self.lifespans.values()
+    }
+
+    /// Feed text input to a specific agent by index.
+    pub fn feed_agent(&mut self, agent_idx: usize, input: String) -> Option<DigestionResult> {
+        self.agents
+            .get_mut(agent_idx)
+            .map(|agent| agent.engulf(input))
+    }
+
+    /// Take a serializable snapshot of the colony's current state, without
+    /// node embeddings.
+    pub fn snapshot(&self) -> ColonySnapshot {
+        self.snapshot_of(None, None)
+    }
+
+    /// A [`snapshot`](Self::snapshot) whose nodes carry their embeddings.
+    pub fn snapshot_with_embeddings(&self) -> ColonySnapshot {
+        self.snapshot_of(Some(self.substrate.embeddings()), None)
+    }
+
+    /// A [`snapshot`](Self::snapshot) of only the nodes `config` samples,
+    /// and the edges between them, for graphs too large to show whole.
+    /// Its [`coverage`](ColonySnapshot::coverage) says how much it leaves
+    /// out. See [`sampling`](crate::sampling).
+    pub fn sample_snapshot(&self, config: &SampleConfig) -> ColonySnapshot {
+        let sample = sampling::sample(self.substrate.graph(), &config.strategy);
+        let embeddings = config.with_embeddings.then(|| self.substrate.embeddings());
+        self.snapshot_of(embeddings, Some(&sample))
+    }
+
+    fn snapshot_of(
+        &self,
+        embeddings: Option<&EmbeddingTable>,
+        sample: Option<&Sample>,
+    ) -> ColonySnapshot {
+        let graph = self.substrate.graph();
+        let (nodes, edges) = graph_snapshot(
+            graph,
+            &self.communities,
+            &self.anomaly_scores,
+            embeddings,
+            sample,
+        );
+        let coverage = sample.map(|s| s.coverage(graph));
+        ColonySnapshot {
+            tick: self.substrate.current_tick(),
+            agents: self.agent_snapshots(),
+            nodes,
+            edges,
+            stats: self.stats(),
+            world_bounds: self.substrate.geometry().bounds,
+            regions: self.substrate.regions().to_vec(),
+            coverage,
+        }
+    }
+
+    /// An immutable copy of the graph, stats and agents as of now, for
+    /// queries that should not wait for ticks. See [`ColonyView`].
+    pub fn read_view(&self) -> ColonyView {
+        ColonyView::capture(self)
+    }
+
+    /// Take a snapshot of the stats and agents, leaving out the graph.
+    pub fn stats_snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            tick: self.substrate.current_tick(),
+            agents: self.agent_snapshots(),
+            stats: self.stats(),
+            world_bounds: self.substrate.geometry().bounds,
+            regions: self.substrate.regions().to_vec(),
+        }
+    }
+
+    /// Record the colony's current state into `series`, in the series'
+    /// [`SnapshotMode`]. Stats-only series never collect the graph.
+    pub fn record_snapshot(&self, series: &mut SnapshotSeries) {
+        match series.mode() {
+            SnapshotMode::StatsOnly => series.push_stats(self.stats_snapshot()),
+            SnapshotMode::Full | SnapshotMode::Delta => series.push(self.snapshot()),
+        }
+    }
+
+    pub(crate) fn agent_snapshots(&self) -> Vec<AgentSnapshot> {
+        self.agents
+            .iter()
+            .map(|a| AgentSnapshot {
+                id: a.id(),
+                agent_type: a.agent_type().to_string(),
+                position: a.position(),
+                age: a.age(),
+                permeability: a.permeability(),
+                vocabulary_size: a.vocabulary_size(),
+            })
+            .collect()
+    }
+
+    /// Up to `k` cheapest paths between two concepts, cheapest first. See
+    /// [`graph_algorithms::find_path`](crate::graph_algorithms::find_path).
+    pub fn find_path(
+        &self,
+        from_label: &str,
+        to_label: &str,
+        cost: PathCost,
+        k: usize,
+    ) -> Result<Vec<ConceptPath>, PathError> {
+        crate::graph_algorithms::find_path(self.substrate.graph(), from_label, to_label, cost, k)
+    }
+
+    /// Suggest up to `limit` labels starting with `prefix` (case-insensitive),
+    /// most useful first.
+    ///
+    /// Matches come from the graph's label index. They are ranked by how
+    /// often they were accessed and how connected they are, ties going to
+    /// the shorter label.
+    pub fn suggest_labels(&self, prefix: &str, limit: usize) -> Vec<LabelSuggestion> {
+        let prefix = prefix.trim();
+        if prefix.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let graph = self.substrate.graph();
+
+        let mut ranked: Vec<(f64, &NodeData, usize)> = graph
+            .find_nodes_by_label_prefix(prefix)
+            .iter()
+            .filter_map(|id| {
+                let node = graph.get_node(id)?;
+                let degree = graph.degree(id);
+                let score = (node.access_count as f64).ln_1p() + (degree as f64).ln_1p();
+                Some((score, node, degree))
+            })
+            .collect();
+        let by_rank = |a: &(f64, &NodeData, usize), b: &(f64, &NodeData, usize)| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.1.label.len().cmp(&b.1.label.len()))
+                .then_with(|| a.1.label.cmp(&b.1.label))
+        };
+        if ranked.len() > limit {
+            ranked.select_nth_unstable_by(limit - 1, by_rank);
+            ranked.truncate(limit);
+        }
+        ranked.sort_by(by_rank);
+
+        ranked
+            .into_iter()
+            .map(|(score, node, degree)| {
+                let mut neighbors = graph.neighbors(&node.id);
+                neighbors.sort_by(|a, b| {
+                    b.1.weight
+                        .partial_cmp(&a.1.weight)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+                let top_neighbors = neighbors
+                    .iter()
+                    .take(SUGGESTION_NEIGHBORS)
+                    .filter_map(|(id, _)| graph.get_node(id).map(|n| n.label.clone()))
+                    .collect();
+                LabelSuggestion {
+                    id: node.id,
+                    label: node.label.clone(),
+                    node_type: node.node_type.clone(),
+                    access_count: node.access_count,
+                    degree,
+                    score,
+                    top_neighbors,
+                }
+            })
+            .collect()
+    }
+
+    /// Get the full event history with tick numbers.
+    pub fn event_history(&self) -> &[(Tick, ColonyEvent)] {
+        &self.event_history
+    }
+
+    /// Get a reference to the agents.
+    pub fn agents(
+        &self,
+    ) -> &[Box<dyn Agent<Input = String, Fragment = String, Presentation = Vec<String>>>] {
+        &self.agents
+    }
+
+    /// The vocabulary a living agent would export (the bytes of
+    /// `export_vocabulary`), e.g. for offspring to inherit. `None` if the
+    /// agent is not alive or has nothing to export yet.
+    pub fn agent_vocabulary(&self, id: &AgentId) -> Option<Vec<u8>> {
+        self.agents
+            .iter()
+            .find(|a| a.id() == *id)?
+            .export_vocabulary()
+    }
+
+    /// Up to `top_n` terms of every living agent's vocabulary, how much
+    /// they overlap between agents, and which of them the graph has. See
+    /// [`crate::vocabulary`].
+    pub fn vocabulary_report(&self, top_n: usize) -> VocabularyReport {
+        let graph = self.substrate.graph();
+        VocabularyReport::build(
+            self.agents.iter().map(|a| a.as_ref()),
+            self.substrate.current_tick(),
+            top_n,
+            |term| {
+                let id = self.find_labelled(&self.label_policy.normalize(term))?;
+                Some((id, graph.get_node(&id)?.access_count))
+            },
+        )
+    }
+
+    /// Hand the vocabulary agent `from` exports straight to agent `to`, as
+    /// if `to` had come across its capability deposit; transfer filters do
+    /// not apply. Returns whether `to` integrated it, which it does once
+    /// per source.
+    pub fn transfer_vocabulary(&mut self, from: &AgentId, to: &AgentId) -> bool {
+        if from == to {
+            return false;
+        }
+        let Some(payload) = self.agent_vocabulary(from) else {
+            return false;
+        };
+        let Some(agent) = self.agents.iter_mut().find(|a| a.id() == *to) else {
+            return false;
+        };
+        if !agent.integrate_vocabulary(&payload) {
+            return false;
+        }
+        let terms = serde_json::from_slice::<VocabularyCapability>(&payload)
+            .map_or(0, |capability| capability.terms.len());
+        let event = ColonyEvent::CapabilityIntegrated {
+            agent_id: *to,
+            from_agent: *from,
+            terms_accepted: terms,
+            terms_offered: terms,
+        };
+        self.event_history
+            .push((self.substrate.current_tick(), event));
+        true
+    }
+
+    /// Get a reference to the fitness tracker.
+    pub fn fitness_tracker(&self) -> &FitnessTracker {
+        &self.fitness_tracker
+    }
+
+    /// Failures the colony has recovered from so far.
+    pub fn error_counters(&self) -> &ErrorCounters {
+        &self.error_counters
+    }
+
+    /// Get a mutable reference to the fitness tracker.
+    pub fn fitness_tracker_mut(&mut self) -> &mut FitnessTracker {
+        &mut self.fitness_tracker
+    }
+
+    /// Register a label, and optionally a decay rate, for a signal kind;
+    /// see [`SubstrateImpl::register_signal_kind`].
+    pub fn register_signal_kind(
+        &mut self,
+        kind: SignalType,
+        info: SignalKindInfo,
+    ) -> Option<SignalKindInfo> {
+        self.substrate.register_signal_kind(kind, info)
+    }
+
+    /// Emit an input signal at a position (to attract agents).
+    pub fn emit_input_signal(&mut self, position: Position, intensity: f64) {
+        let signal = Signal::new(
+            SignalType::Input,
+            intensity,
+            position,
+            AgentId::new(),
+            self.substrate.current_tick(),
+        );
+        self.substrate.emit_signal(signal);
+    }
+}
+
+impl Default for Colony {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One [`ColonyEvent::ActionFailed`] for the connections of an action that
+/// could not be wired, if there are any.
+fn wiring_failure(
+    agent_id: AgentId,
+    action_kind: &str,
+    errors: &[TopologyError],
+) -> Option<ColonyEvent> {
+    let first = errors.first()?;
+    let reason = match errors.len() {
+        1 => first.to_string(),
+        n => format!("{n} connections not wired, first: {first}"),
+    };
+    Some(ColonyEvent::action_failed(agent_id, action_kind, reason))
+}
+
+/// Index pairs of a presentation's concepts to wire, given the passages
+/// each concept was found in and the namespace it was qualified with.
+///
+/// Concepts of different namespaces are never paired; unqualified ones
+/// pair with any. Without a cap (or under it) every other pair is wired.
+/// Over it, pairs that share the most sentences go first, then the rest in
+/// presentation order, so agents that rank their fragments keep their
+/// best-ranked pairs.
+fn wiring_pairs(
+    passages: &[&[TextSpan]],
+    namespaces: &[Option<&str>],
+    cap: Option<usize>,
+) -> Vec<(usize, usize)> {
+    let n = passages.len();
+    let pairable = move |&(i, j): &(usize, usize)| match (namespaces[i], namespaces[j]) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    };
+    let all_pairs = move || {
+        (0..n)
+            .flat_map(move |i| ((i + 1)..n).map(move |j| (i, j)))
+            .filter(pairable)
+    };
+    let cap = match cap {
+        Some(cap) if cap < n * n.saturating_sub(1) / 2 => cap,
+        _ => return all_pairs().collect(),
+    };
+
+    // Concepts found in each sentence, in presentation order
+    let mut sentences: HashMap<TextSpan, Vec<usize>> = HashMap::new();
+    for (i, spans) in passages.iter().enumerate() {
+        for span in spans.iter() {
+            let members = sentences.entry(*span).or_default();
+            if members.last() != Some(&i) {
+                members.push(i);
+            }
+        }
+    }
+    let mut shared: HashMap<(usize, usize), usize> = HashMap::new();
+    for members in sentences.values() {
+        for (k, &i) in members.iter().enumerate() {
+            for &j in &members[k + 1..] {
+                *shared.entry((i, j)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut ranked: Vec<((usize, usize), usize)> =
+        shared.into_iter().filter(|(p, _)| pairable(p)).collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mut pairs: Vec<(usize, usize)> = ranked.into_iter().take(cap).map(|(p, _)| p).collect();
+    if pairs.len() < cap {
+        let chosen: HashSet<(usize, usize)> = pairs.iter().copied().collect();
+        let missing = cap - pairs.len();
+        pairs.extend(all_pairs().filter(|p| !chosen.contains(p)).take(missing));
+    }
+    pairs
+}
+
+/// Snapshots of every node and edge in `graph` (or only those `sample`
+/// keeps), nodes carrying their tracked community and anomaly score.
+pub(crate) fn graph_snapshot(
+    graph: &dyn TopologyGraph,
+    communities: &CommunityTracker,
+    anomaly_scores: &HashMap<NodeId, f64>,
+    embeddings: Option<&EmbeddingTable>,
+    sample: Option<&Sample>,
+) -> (Vec<NodeSnapshot>, Vec<EdgeSnapshot>) {
+    let nodes: Vec<NodeSnapshot> = graph
+        .all_nodes()
+        .iter()
+        .filter(|nid| sample.is_none_or(|s| s.nodes.contains(nid)))
+        .filter_map(|nid| {
+            let n = graph.get_node(nid)?;
+            Some(NodeSnapshot {
+                id: n.id,
+                label: n.label.clone(),
+                node_type: n.node_type.clone(),
+                position: n.position,
+                access_count: n.access_count,
+                tags: n.tags.clone(),
+                community: communities.community_of(&n.label),
+                embedding: embeddings.and_then(|e| e.get(&n.id)),
+                anomaly_score: anomaly_scores.get(&n.id).copied(),
+            })
+        })
+        .collect();
+
+    let edges: Vec<EdgeSnapshot> = graph
+        .all_edges()
+        .iter()
+        .filter(|(from, to, data)| sample.is_none_or(|s| s.keeps_edge(from, to, data.weight)))
+        .map(|(from, to, data)| {
+            let from_label = graph
+                .get_node(from)
+                .map(|n| n.label.clone())
+                .unwrap_or_default();
+            let to_label = graph
+                .get_node(to)
+                .map(|n| n.label.clone())
+                .unwrap_or_default();
+            EdgeSnapshot {
+                from_label,
+                to_label,
+                weight: data.weight,
+                co_activations: data.co_activations,
+            }
+        })
+        .collect();
+
+    (nodes, edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampling::SampleStrategy;
+    use phago_agents::digester::Digester;
+    use phago_agents::synthesizer::Synthesizer;
+
+    #[test]
+    fn spawn_and_count_agents() {
+        let mut colony = Colony::new();
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony.spawn(Box::new(Digester::new(Position::new(5.0, 5.0))));
+        assert_eq!(colony.alive_count(), 2);
+        assert_eq!(colony.stats().total_spawned, 2);
+    }
+
+    #[test]
+    fn taken_agents_leave_without_dying_and_give_back_their_work() {
+        let mut colony = Colony::new();
+        let doc = colony.ingest_document("Doc", "cell membrane protein", Position::new(0.0, 0.0));
+        let id = colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony.tick();
+        assert!(colony.substrate().get_document(&doc).unwrap().digested);
+
+        let agent = colony.take_agent(&id).unwrap();
+        assert_eq!(agent.id(), id);
+        assert_eq!(colony.alive_count(), 0);
+        assert!(colony.take_agent(&id).is_none());
+        assert!(colony.death_signals().is_empty());
+        assert_eq!(colony.stats().agents_died, 0);
+        // The engulfed document is back for the next digester
+        assert!(!colony.substrate().get_document(&doc).unwrap().digested);
+    }
+
+    #[test]
+    fn living_agents_export_their_vocabulary() {
+        let mut colony = Colony::new();
+        colony.ingest_document("Doc", "cell membrane protein", Position::new(0.0, 0.0));
+        let id = colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        assert!(colony.agent_vocabulary(&id).is_none());
+
+        colony.run(3);
+        let vocabulary = colony.agent_vocabulary(&id).unwrap();
+        let capability: VocabularyCapability = serde_json::from_slice(&vocabulary).unwrap();
+        assert_eq!(capability.origin, id);
+        assert!(capability.terms.contains(&"membrane".to_string()));
+        assert!(colony.agent_vocabulary(&AgentId::new()).is_none());
+    }
+
+    #[test]
+    fn tick_advances_simulation() {
+        let mut colony = Colony::new();
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony.tick();
+        assert_eq!(colony.stats().tick, 1);
+    }
+
+    #[test]
+    fn agent_apoptosis_in_colony() {
+        let mut colony = Colony::new();
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(3),
+        ));
+
+        assert_eq!(colony.alive_count(), 1);
+
+        for _ in 0..5 {
+            colony.tick();
+        }
+
+        assert_eq!(colony.alive_count(), 0);
+        assert_eq!(colony.stats().agents_died, 1);
+        assert_eq!(colony.death_signals().len(), 1);
+    }
+
+    #[test]
+    fn ingest_document_creates_signal() {
+        let mut colony = Colony::new();
+        let pos = Position::new(5.0, 5.0);
+        let doc_id = colony.ingest_document("Test Doc", "cell membrane protein", pos);
+
+        let stats = colony.stats();
+        assert_eq!(stats.documents_total, 1);
+        assert_eq!(stats.documents_digested, 0);
+        assert_eq!(stats.total_signals, 1); // Input signal emitted
+
+        // Document is in the substrate
+        let doc = colony.substrate().get_document(&doc_id);
+        assert!(doc.is_some());
+        assert!(!doc.unwrap().digested);
+    }
+
+    #[test]
+    fn agent_finds_and_digests_document() {
+        let mut colony = Colony::new();
+
+        // Place document at origin
+        colony.ingest_document(
+            "Biology 101",
+            "The cell membrane controls transport of molecules into and out of the cell. \
+             Proteins embedded in the membrane serve as channels and receptors.",
+            Position::new(0.0, 0.0),
+        );
+
+        // Spawn agent at origin (right on top of the document)
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+        ));
+
+        // Run enough ticks for the full cycle:
+        // tick 1: Seeking → finds doc → EngulfDocument
+        // tick 2: FoundTarget → engulfed → Digesting
+        // tick 3: Digesting → lyse → PresentFragments
+        // tick 4: Presenting → Deposit trace
+        colony.run(10);
+
+        let stats = colony.stats();
+        assert_eq!(stats.documents_digested, 1, "Document should be digested");
+        assert!(
+            stats.graph_nodes > 0,
+            "Should have concept nodes: got {}",
+            stats.graph_nodes
+        );
+        assert!(
+            stats.graph_edges > 0,
+            "Should have edges: got {}",
+            stats.graph_edges
+        );
+    }
+
+    #[test]
+    fn digestion_records_concept_passages() {
+        let mut colony = Colony::new();
+        let content = "The cell membrane controls transport. Ribosomes build proteins.";
+        let doc_id = colony.ingest_or_update_document("Biology", content, Position::new(0.0, 0.0));
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+        ));
+        colony.run(10);
+
+        let passages = colony.substrate().passages(&doc_id).unwrap();
+        let membrane: Vec<&str> = passages
+            .spans("membrane")
+            .iter()
+            .filter_map(|s| s.slice(content))
+            .collect();
+        assert_eq!(membrane, vec!["The cell membrane controls transport."]);
+        let ribosomes = passages.spans("ribosomes");
+        assert_eq!(
+            ribosomes[0].slice(content),
+            Some("Ribosomes build proteins.")
+        );
+
+        // Changed content invalidates the offsets until it is digested again
+        colony.ingest_or_update_document("Biology", "Mitochondria", Position::new(0.0, 0.0));
+        assert!(colony.substrate().passages(&doc_id).is_none());
+    }
+
+    #[test]
+    fn dying_agent_releases_undigested_document() {
+        let mut colony = Colony::new();
+        let doc_pos = Position::new(8.0, 0.0);
+        let content = "The cell membrane controls protein transport.";
+        let doc_id = colony.ingest_document("Biology", content, doc_pos);
+
+        // An agent that learned some vocabulary, then timed out mid-digestion
+        let mut dying = Digester::new(doc_pos).with_max_idle(2);
+        dying.digest_text("mitochondria produce energy for the cell".to_string());
+        let dying_id = dying.id();
+        colony.substrate_mut().consume_document(&doc_id).unwrap();
+        dying.feed_document(doc_id, content.to_string());
+        dying.set_idle_ticks(2);
+        colony.spawn(Box::new(dying));
+
+        let events = colony.tick();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ColonyEvent::WorkReleased { agent_id, document }
+                if *agent_id == dying_id && *document == doc_id
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ColonyEvent::CapabilityExported { agent_id, .. } if *agent_id == dying_id
+        )));
+        assert!(colony.death_signals()[0].had_pending_work);
+        assert!(!colony.substrate().get_document(&doc_id).unwrap().digested);
+
+        // A later agent, far from the document, picks the work up
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+        ));
+        colony.run(20);
+
+        assert!(colony.substrate().get_document(&doc_id).unwrap().digested);
+        assert!(colony.document_contribution(&doc_id).is_some());
+        assert!(!colony
+            .substrate()
+            .graph()
+            .find_nodes_by_label("membrane")
+            .is_empty());
+    }
+
+    #[test]
+    fn multiple_documents_build_graph() {
+        let mut colony = Colony::new();
+
+        // Two documents about related topics
+        colony.ingest_document(
+            "Cell Biology",
+            "The cell membrane is a lipid bilayer that controls transport. \
+             Proteins in the membrane act as channels and receptors for signaling.",
+            Position::new(0.0, 0.0),
+        );
+        colony.ingest_document(
+            "Molecular Transport",
+            "Active transport across the cell membrane requires ATP energy. \
+             Channel proteins facilitate passive transport of ions and molecules.",
+            Position::new(2.0, 0.0),
+        );
+
+        // Spawn agents near each document
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+        ));
+        colony.spawn(Box::new(
+            Digester::new(Position::new(2.0, 0.0)).with_max_idle(50),
+        ));
+
+        colony.run(20);
+
+        let stats = colony.stats();
+        assert_eq!(
+            stats.documents_digested, 2,
+            "Both documents should be digested"
+        );
+        // Shared concepts (cell, membrane, transport, proteins) should create
+        // overlapping graph nodes and strengthen edges
+        assert!(
+            stats.graph_nodes >= 5,
+            "Expected at least 5 concept nodes, got {}",
+            stats.graph_nodes
+        );
+    }
+
+    #[test]
+    fn tagged_documents_tag_their_concepts() {
+        let mut colony = Colony::new();
+        colony.ingest_document_tagged(
+            "Quarterly plan",
+            "The project deadline requires budget review meetings with the client.",
+            Position::new(0.0, 0.0),
+            &["work"],
+        );
+        colony.ingest_document_tagged(
+            "Weekend",
+            "The garden project needs compost, seeds and regular watering.",
+            Position::new(2.0, 0.0),
+            &["personal"],
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+        ));
+        colony.spawn(Box::new(
+            Digester::new(Position::new(2.0, 0.0)).with_max_idle(50),
+        ));
+        colony.run(20);
+
+        let graph = colony.substrate().graph();
+        let node = |label: &str| {
+            let id = graph.find_nodes_by_exact_label(label)[0];
+            graph.get_node(&id).unwrap().clone()
+        };
+
+        // Shared concepts carry both tags, with an access count per tag
+        let project = node("project");
+        assert_eq!(project.tags.get("work"), Some(&1));
+        assert_eq!(project.tags.get("personal"), Some(&1));
+
+        let budget = node("budget");
+        assert!(budget.in_scope(Some(&["work".to_string()])));
+        assert!(!budget.in_scope(Some(&["personal".to_string()])));
+        assert!(node("compost").has_any_tag(&["personal".to_string()]));
+    }
+
+    #[test]
+    fn expired_documents_take_back_what_only_they_contributed() {
+        let mut colony = Colony::new();
+        let standup = colony.ingest_document_with_ttl(
+            "Standup",
+            "The membrane protein standup agenda covers the sprint retrospective.",
+            Position::new(0.0, 0.0),
+            Some(25),
+        );
+        colony.ingest_document(
+            "Reference",
+            "The membrane protein controls transport across the lipid bilayer.",
+            Position::new(2.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.spawn(Box::new(
+            Digester::new(Position::new(2.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(20);
+        assert!(colony.find_labelled("retrospective").is_some());
+        let stats = colony.stats();
+        assert_eq!(stats.documents_expiring, 1);
+        assert_eq!(stats.next_expiry_tick, Some(25));
+        assert_eq!(colony.upcoming_expirations()[0].0, standup);
+
+        let events: Vec<ColonyEvent> = colony.run(10).into_iter().flatten().collect();
+        let expired: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                ColonyEvent::DocumentExpired {
+                    id, nodes_removed, ..
+                } => Some((*id, *nodes_removed)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].0, standup);
+        assert!(expired[0].1 > 0);
+
+        assert!(colony.substrate().get_document(&standup).is_none());
+        assert!(colony.document_expiry(&standup).is_none());
+        assert_eq!(colony.stats().documents_expiring, 0);
+        // What only the standup presented is gone; shared concepts remain
+        assert!(colony.find_labelled("retrospective").is_none());
+        assert!(colony.find_labelled("sprint").is_none());
+        assert!(colony.document_contribution(&standup).is_none());
+        assert!(colony.find_labelled("membrane").is_some());
+        assert!(colony.find_labelled("protein").is_some());
+        assert!(colony.find_labelled("bilayer").is_some());
+    }
+
+    #[test]
+    fn suggest_labels_ranks_by_usage_and_connectivity() {
+        let mut colony = Colony::new();
+        let mut add = |label: &str, access_count: u64| {
+            colony.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: label.to_string(),
+                node_type: NodeType::Concept,
+                position: Position::new(0.0, 0.0),
+                access_count,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            })
+        };
+        let cell = add("cell", 5);
+        let cellular = add("cellular", 1);
+        let membrane = add("membrane", 1);
+        let celsius = add("celsius", 1);
+        add("cytoplasm", 50);
+        let edge = |weight| EdgeData {
+            weight,
+            co_activations: 1,
+            created_tick: 0,
+            last_activated_tick: 0,
+        };
+        colony
+            .substrate_mut()
+            .set_edge(cell, membrane, edge(0.9))
+            .unwrap();
+        colony
+            .substrate_mut()
+            .set_edge(cell, cellular, edge(0.2))
+            .unwrap();
+        colony
+            .substrate_mut()
+            .set_edge(celsius, membrane, edge(0.5))
+            .unwrap();
+
+        let suggestions = colony.suggest_labels("CEL", 10);
+        let labels: Vec<&str> = suggestions.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(labels, vec!["cell", "celsius", "cellular"]);
+        assert_eq!(suggestions[0].degree, 2);
+        assert_eq!(suggestions[0].top_neighbors, vec!["membrane", "cellular"]);
+
+        assert_eq!(colony.suggest_labels("cel", 1).len(), 1);
+        assert!(colony.suggest_labels("", 10).is_empty());
+    }
+
+    #[test]
+    fn colony_stats_are_accurate() {
+        let mut colony = Colony::new();
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(2),
+        ));
+        colony.spawn(Box::new(
+            Digester::new(Position::new(5.0, 5.0)).with_max_idle(100),
+        ));
+
+        colony.run(5);
+
+        let stats = colony.stats();
+        assert_eq!(stats.total_spawned, 2);
+        assert_eq!(stats.agents_died, 1);
+        assert_eq!(stats.agents_alive, 1);
+    }
+
+    #[test]
+    fn semantic_wiring_config_is_accessible() {
+        let colony = Colony::new();
+        let config = colony.semantic_wiring_config();
+        // Default config should not require embeddings
+        assert!(!config.require_embeddings);
+        assert!(config.min_similarity >= 0.0);
+        assert!(config.similarity_influence >= 0.0);
+    }
+
+    #[test]
+    fn with_semantic_wiring_configures_colony() {
+        use phago_core::semantic::SemanticWiringConfig;
+
+        let colony = Colony::new().with_semantic_wiring(SemanticWiringConfig::strict());
+
+        let config = colony.semantic_wiring_config();
+        assert!(config.require_embeddings);
+        assert!(config.min_similarity > 0.0);
+    }
+
+    #[test]
+    fn semantic_wiring_boosts_similar_concept_edges() {
+        use phago_core::semantic::SemanticWiringConfig;
+
+        let mut colony = Colony::new().with_semantic_wiring(SemanticWiringConfig::default());
+
+        // Manually add two nodes with similar embeddings
+        let emb_a = vec![1.0, 0.0, 0.0]; // Unit vector along x
+        let emb_b = vec![0.95, 0.31, 0.0]; // ~18° from emb_a (high similarity)
+
+        let node_a = colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "concept_a".to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+        colony.substrate_mut().set_embedding(node_a, &emb_a);
+
+        let node_b = colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "concept_b".to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(1.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+        colony.substrate_mut().set_embedding(node_b, &emb_b);
+
+        // Wire them manually using WireNodes action
+        colony
+            .substrate_mut()
+            .set_edge(
+                node_a,
+                node_b,
+                EdgeData {
+                    weight: 0.1,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+
+        let edge = colony
+            .substrate()
+            .graph()
+            .get_edge(&node_a, &node_b)
+            .unwrap();
+        assert!(edge.weight >= 0.1, "Edge should have at least base weight");
+    }
+
+    #[test]
+    fn semantic_wiring_with_no_embeddings_uses_base_weight() {
+        use phago_core::semantic::SemanticWiringConfig;
+
+        let mut colony = Colony::new().with_semantic_wiring(SemanticWiringConfig::default());
+
+        // Add two nodes WITHOUT embeddings
+        let node_a = colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "plain_a".to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+
+        let node_b = colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "plain_b".to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(1.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+
+        // Wire them
+        colony
+            .substrate_mut()
+            .set_edge(
+                node_a,
+                node_b,
+                EdgeData {
+                    weight: 0.1,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+
+        let edge = colony
+            .substrate()
+            .graph()
+            .get_edge(&node_a, &node_b)
+            .unwrap();
+        // With default config, no embeddings means base weight is used
+        assert!(
+            (edge.weight - 0.1).abs() < 0.01,
+            "Edge should use base weight: got {}",
+            edge.weight
+        );
+    }
 
-        // Phase 5: Advance tick
-        self.substrate.advance_tick();
+    /// A deterministic unit vector: `seed`'s direction pulled `spread`
+    /// of the way away from cluster `center`.
+    fn test_embedding(center: u64, seed: u64, spread: f32, dimension: usize) -> Vec<f32> {
+        let direction = |seed: u64| -> Vec<f32> {
+            let mut state = seed.wrapping_add(1);
+            (0..dimension)
+                .map(|_| {
+                    state = state
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    ((state >> 40) as f32 / (1u64 << 24) as f32) * 2.0 - 1.0
+                })
+                .collect()
+        };
+        let (center, own) = (direction(center), direction(1000 + seed));
+        let mut values: Vec<f32> = center
+            .iter()
+            .zip(&own)
+            .map(|(c, o)| c * (1.0 - spread) + o * spread)
+            .collect();
+        let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+        values.iter_mut().for_each(|v| *v /= norm);
+        values
+    }
 
-        events.push(ColonyEvent::TickComplete {
-            tick: self.substrate.current_tick(),
-            alive: self.agents.len(),
-            dead_this_tick: dead_count,
+    /// A colony of `count` concepts with 384-dimensional embeddings stored
+    /// in `storage`, in four clusters.
+    fn embedded_colony(storage: EmbeddingStorage, count: u64) -> (Colony, Vec<NodeId>) {
+        let mut colony = Colony::from_config(ColonyConfig {
+            semantic_wiring: SemanticWiringConfig::default(),
+            embedding_storage: storage,
+            ..Default::default()
         });
+        let ids = (0..count)
+            .map(|i| {
+                let id = colony.substrate_mut().add_node(NodeData {
+                    id: NodeId::new(),
+                    label: format!("concept-{i}"),
+                    node_type: NodeType::Concept,
+                    position: Position::new(0.0, 0.0),
+                    access_count: 1,
+                    created_tick: 0,
+                    last_accessed_tick: 0,
+                    tags: Default::default(),
+                });
+                let spread = (i % 8) as f32 / 8.0;
+                colony
+                    .substrate_mut()
+                    .set_embedding(id, &test_embedding(i % 4, i, spread, 384));
+                id
+            })
+            .collect();
+        (colony, ids)
+    }
 
-        // Record events in history
-        let current_tick = self.substrate.current_tick();
-        for event in &events {
-            self.event_history.push((current_tick, event.clone()));
-        }
-
-        events
+    #[test]
+    fn quantized_embeddings_shrink_a_large_colony() {
+        let memory = |storage| {
+            let (colony, _) = embedded_colony(storage, 10_000);
+            assert_eq!(colony.substrate().embeddings().len(), 10_000);
+            colony.substrate().embeddings().memory_bytes()
+        };
+        let full = memory(EmbeddingStorage::F32);
+        let half = memory(EmbeddingStorage::F16);
+        let bytes = memory(EmbeddingStorage::UNIT_I8);
+        // 15.36 MB of f32 values alone
+        assert!(full >= 10_000 * 384 * 4);
+        assert!(half * 100 <= full * 53, "f16 takes {half} of {full} bytes");
+        assert!(bytes * 100 <= full * 28, "i8 takes {bytes} of {full} bytes");
     }
 
-    /// Run the simulation for N ticks.
-    pub fn run(&mut self, ticks: u64) -> Vec<Vec<ColonyEvent>> {
-        let mut all_events = Vec::new();
-        for _ in 0..ticks {
-            all_events.push(self.tick());
+    #[test]
+    fn quantized_wiring_stays_close_to_full_precision() {
+        let weights = |storage| {
+            let (mut colony, ids) = embedded_colony(storage, 32);
+            let pairs: Vec<(NodeId, NodeId, f64)> = ids
+                .iter()
+                .enumerate()
+                .flat_map(|(i, a)| ids[i + 1..].iter().map(|b| (*a, *b, 0.1)))
+                .collect();
+            let (wired, errors) = colony.wire_connections(AgentId::new(), pairs.clone());
+            assert_eq!((wired, errors.len()), (pairs.len(), 0));
+            pairs
+                .iter()
+                .map(|(a, b, _)| colony.substrate().get_edge(a, b).unwrap().weight)
+                .collect::<Vec<f64>>()
+        };
+        let full = weights(EmbeddingStorage::F32);
+        assert!(
+            full.iter().any(|w| *w > 0.14) && full.iter().any(|w| *w < 0.13),
+            "the test set should span similarities"
+        );
+        for storage in [EmbeddingStorage::F16, EmbeddingStorage::UNIT_I8] {
+            let quantized = weights(storage);
+            let worst = full
+                .iter()
+                .zip(&quantized)
+                .map(|(f, q)| (f - q).abs())
+                .fold(0.0, f64::max);
+            assert!(worst < 1e-3, "{storage:?} is off by up to {worst}");
         }
-        all_events
     }
 
-    /// Get colony statistics.
-    pub fn stats(&self) -> ColonyStats {
-        let docs = self.substrate.all_documents();
-        let digested = docs.iter().filter(|d| d.digested).count();
-        ColonyStats {
-            tick: self.substrate.current_tick(),
-            agents_alive: self.agents.len(),
-            agents_died: self.total_died,
-            total_spawned: self.total_spawned,
-            graph_nodes: self.substrate.node_count(),
-            graph_edges: self.substrate.edge_count(),
-            total_signals: self.substrate.all_signals().len(),
-            documents_total: docs.len(),
-            documents_digested: digested,
+    #[test]
+    fn snapshots_leave_out_embeddings_unless_asked() {
+        let (mut colony, ids) = embedded_colony(EmbeddingStorage::F16, 2);
+        assert!(colony
+            .snapshot()
+            .nodes
+            .iter()
+            .all(|n| n.embedding.is_none()));
+        let json = serde_json::to_string(&colony.snapshot()).unwrap();
+        assert!(!json.contains("embedding"));
+
+        let snapshot = colony.snapshot_with_embeddings();
+        for node in &snapshot.nodes {
+            assert_eq!(node.embedding, colony.substrate().embedding(&node.id));
+            assert_eq!(node.embedding.as_ref().map(Vec::len), Some(384));
         }
-    }
 
-    /// Get a reference to the substrate.
-    pub fn substrate(&self) -> &SubstrateImpl {
-        &self.substrate
+        // Merged and removed nodes take their embeddings with them
+        colony.merge_nodes(ids[0], &[ids[1]]);
+        assert_eq!(colony.substrate().embeddings().len(), 1);
+        assert!(colony.substrate().embedding(&ids[0]).is_some());
+        colony.substrate_mut().remove_node(&ids[0]);
+        assert!(colony.substrate().embeddings().is_empty());
     }
 
-    /// Get a mutable reference to the substrate.
-    pub fn substrate_mut(&mut self) -> &mut SubstrateImpl {
-        &mut self.substrate
-    }
+    #[test]
+    fn sampled_snapshots_keep_whole_edges_and_report_coverage() {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Cells",
+            "cell membrane protein transport channel receptor",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony.run(10);
+        let full = colony.snapshot();
+        assert!(full.coverage.is_none());
+        assert!(full.nodes.len() > 3);
+
+        let sampled = colony.sample_snapshot(&SampleStrategy::TopByAccess { n: 3 }.into());
+        assert_eq!(sampled.nodes.len(), 3);
+        let labels: HashSet<&str> = sampled.nodes.iter().map(|n| n.label.as_str()).collect();
+        assert!(sampled.edges.iter().all(
+            |e| labels.contains(e.from_label.as_str()) && labels.contains(e.to_label.as_str())
+        ));
 
-    /// Number of agents currently alive.
-    pub fn alive_count(&self) -> usize {
-        self.agents.len()
+        let coverage = sampled.coverage.clone().unwrap();
+        assert_eq!(coverage.nodes_total, full.nodes.len());
+        assert_eq!(coverage.edges_total, full.edges.len());
+        assert_eq!(coverage.edges_sampled, sampled.edges.len());
+        assert!(coverage.node_fraction < 1.0 && !coverage.is_complete());
+        // The most accessed nodes carry more than their share of accesses
+        assert!(coverage.access_fraction >= coverage.node_fraction);
+        let restored = ColonySnapshot::from_json(&sampled.to_json().unwrap()).unwrap();
+        assert_eq!(restored.coverage, Some(coverage));
+
+        let everything = colony.sample_snapshot(&SampleStrategy::TopByAccess { n: 1000 }.into());
+        assert!(everything.coverage.unwrap().is_complete());
+        let view = colony.read_view();
+        let from_view = view.sample_snapshot(&SampleStrategy::TopByAccess { n: 3 }.into());
+        assert_eq!(from_view.coverage, sampled.coverage);
     }
 
-    /// All death signals collected during the simulation.
-    pub fn death_signals(&self) -> &[DeathSignal] {
-        &self.death_signals
+    /// Three overlapping documents, three digesters and a synthesizer
+    /// following the colony's emergence defaults.
+    fn emergence_colony(emerge: EmergeConfig) -> Colony {
+        let mut colony = Colony::from_config(ColonyConfig {
+            emerge,
+            ..Default::default()
+        });
+        let docs = [
+            "The cell membrane controls transport of proteins and ions. \
+             Membrane proteins act as channels for cell signaling.",
+            "Active transport across the cell membrane requires energy. \
+             Channel proteins move ions through the membrane of the cell.",
+            "Signaling proteins bind receptors on the cell membrane. \
+             Transport of ions changes membrane potential in the cell.",
+        ];
+        for (i, text) in docs.iter().enumerate() {
+            let x = i as f64 * 2.0;
+            colony.ingest_document(&format!("Doc {i}"), text, Position::new(x, 0.0));
+            colony.spawn(Box::new(
+                Digester::new(Position::new(x, 0.0)).with_max_idle(200),
+            ));
+        }
+        colony.spawn(Box::new(Synthesizer::new(Position::new(2.0, 0.0))));
+        colony
     }
 
-    /// Feed text input to a specific agent by index.
-    pub fn feed_agent(&mut self, agent_idx: usize, input: String) -> Option<DigestionResult> {
-        self.agents
-            .get_mut(agent_idx)
-            .map(|agent| agent.engulf(input))
+    fn cluster_insights(colony: &Colony) -> usize {
+        let graph = colony.substrate().graph();
+        graph
+            .all_nodes()
+            .iter()
+            .filter_map(|id| graph.get_node(id))
+            .filter(|n| n.node_type == NodeType::Insight && n.label.starts_with("[CLUSTER"))
+            .count()
     }
 
-    /// Take a serializable snapshot of the colony's current state.
-    pub fn snapshot(&self) -> ColonySnapshot {
-        let graph = self.substrate.graph();
+    #[test]
+    fn low_quorum_threshold_produces_cluster_insights() {
+        let mut colony = emergence_colony(EmergeConfig {
+            quorum_threshold: 0.5,
+            min_cluster_size: 2,
+            bridge_min_weight: 0.05,
+            cooldown_ticks: 5,
+        });
+        colony.run(100);
+
+        assert!(cluster_insights(&colony) >= 1);
+        let report = colony.emergence_report();
+        assert_eq!(report.regions.len(), 1);
+        assert_eq!(report.regions[0].status.threshold, 0.5);
+        assert!(report.last_trigger_tick.is_some());
+        assert!(report.insights_generated >= 1);
+        assert!(report.insight_nodes >= 1);
+    }
 
-        let agents: Vec<AgentSnapshot> = self
-            .agents
-            .iter()
-            .map(|a| AgentSnapshot {
-                id: a.id(),
-                agent_type: a.agent_type().to_string(),
-                position: a.position(),
-                age: a.age(),
-                permeability: a.permeability(),
-                vocabulary_size: a.vocabulary_size(),
-            })
-            .collect();
+    #[test]
+    fn high_quorum_threshold_suppresses_insights() {
+        let mut colony = emergence_colony(EmergeConfig {
+            quorum_threshold: 1e9,
+            ..Default::default()
+        });
+        colony.run(100);
+
+        assert_eq!(cluster_insights(&colony), 0);
+        let report = colony.emergence_report();
+        assert_eq!(report.insights_generated, 0);
+        assert_eq!(report.last_trigger_tick, None);
+        let region = &report.regions[0];
+        assert!(region.status.density > 0.0);
+        assert!(region.status.progress() < 1.0);
+    }
 
-        let nodes: Vec<NodeSnapshot> = graph
-            .all_nodes()
-            .iter()
-            .filter_map(|nid| {
-                let n = graph.get_node(nid)?;
-                Some(NodeSnapshot {
-                    id: n.id,
-                    label: n.label.clone(),
-                    node_type: n.node_type.clone(),
-                    position: n.position,
-                    access_count: n.access_count,
-                })
-            })
-            .collect();
+    /// Events of a seeded run, with the random node and document IDs
+    /// blanked and lists sorted (fragment order follows hash order) so two
+    /// runs can be compared.
+    fn seeded_dissolution_run(
+        config: ColonyConfig,
+        force_rebuild: bool,
+        plugins: Vec<Box<dyn ColonyPlugin>>,
+    ) -> Vec<(Tick, String)> {
+        let mut colony = Colony::from_config(config);
+        for plugin in plugins {
+            colony.add_plugin(plugin);
+        }
+        let topics = [
+            "Cell membranes regulate transport. Membrane proteins move ions across the membrane.",
+            "Mitochondria produce energy. Mitochondria convert glucose into chemical energy.",
+            "Ribosomes translate messenger RNA. Ribosomes assemble proteins from amino acids.",
+            "Chloroplasts capture light. Chloroplasts turn light into sugar through photosynthesis.",
+        ];
+        let mut agents = std::collections::HashSet::new();
+        for (i, text) in topics.iter().enumerate() {
+            let (x, y) = ((i % 2) as f64 * 40.0, (i / 2) as f64 * 40.0);
+            colony.ingest_document(&format!("Topic {i}"), text, Position::new(x, y));
+            for j in 0..2 {
+                let seed = (i * 2 + j) as u64;
+                let digester = Digester::with_seed(Position::new(x + 2.0, y + j as f64), seed)
+                    .with_max_idle(60);
+                agents.insert(digester.id().0.to_string());
+                colony.spawn(Box::new(digester));
+            }
+        }
 
-        let edges: Vec<EdgeSnapshot> = graph
-            .all_edges()
-            .iter()
-            .map(|(from, to, data)| {
-                let from_label = graph
-                    .get_node(from)
-                    .map(|n| n.label.clone())
-                    .unwrap_or_default();
-                let to_label = graph
-                    .get_node(to)
-                    .map(|n| n.label.clone())
-                    .unwrap_or_default();
-                EdgeSnapshot {
-                    from_label,
-                    to_label,
-                    weight: data.weight,
-                    co_activations: data.co_activations,
+        fn normalize(value: &mut serde_json::Value, agents: &std::collections::HashSet<String>) {
+            match value {
+                serde_json::Value::String(s)
+                    if uuid::Uuid::parse_str(s).is_ok() && !agents.contains(s.as_str()) =>
+                {
+                    *s = "_".to_string();
                 }
-            })
-            .collect();
+                serde_json::Value::Array(items) => {
+                    items.iter_mut().for_each(|v| normalize(v, agents));
+                    items.sort_by_key(|v| v.to_string());
+                }
+                serde_json::Value::Object(fields) => {
+                    fields.values_mut().for_each(|v| normalize(v, agents));
+                }
+                _ => {}
+            }
+        }
 
-        ColonySnapshot {
-            tick: self.substrate.current_tick(),
-            agents,
-            nodes,
-            edges,
-            stats: self.stats(),
+        let mut stream = Vec::new();
+        for tick in 0..80 {
+            if force_rebuild {
+                colony.substrate_mut();
+            }
+            for event in colony.tick() {
+                let mut value = serde_json::to_value(&event).unwrap();
+                normalize(&mut value, &agents);
+                stream.push((tick, value.to_string()));
+            }
         }
+        stream
     }
 
-    /// Get the full event history with tick numbers.
-    pub fn event_history(&self) -> &[(Tick, ColonyEvent)] {
-        &self.event_history
+    #[test]
+    fn incremental_dissolution_matches_full_rebuilds() {
+        // Forcing a rebuild every tick recomputes everything from the graph,
+        // as the dissolution phase did before it was incremental
+        let incremental = seeded_dissolution_run(ColonyConfig::default(), false, Vec::new());
+        let rebuilt = seeded_dissolution_run(ColonyConfig::default(), true, Vec::new());
+        assert!(incremental
+            .iter()
+            .any(|(_, e)| e.starts_with("{\"Dissolved\"")));
+        assert_eq!(incremental, rebuilt);
     }
 
-    /// Get a reference to the agents.
-    pub fn agents(
-        &self,
-    ) -> &[Box<dyn Agent<Input = String, Fragment = String, Presentation = Vec<String>>>] {
-        &self.agents
+    #[test]
+    fn observer_plugins_keep_seeded_runs_reproducible() {
+        use crate::plugin::{DocumentPolicy, JsonlEventLog};
+
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("events.jsonl");
+        let plain = seeded_dissolution_run(ColonyConfig::default(), false, Vec::new());
+        let observed = seeded_dissolution_run(
+            ColonyConfig::default(),
+            false,
+            vec![
+                Box::new(JsonlEventLog::open(&log).unwrap()),
+                Box::new(DocumentPolicy::new("^Draft").unwrap()),
+            ],
+        );
+        assert_eq!(plain, observed);
+        let logged = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(logged.lines().count(), observed.len());
     }
 
-    /// Get a reference to the fitness tracker.
-    pub fn fitness_tracker(&self) -> &FitnessTracker {
-        &self.fitness_tracker
+    #[test]
+    fn dissolution_interval_spaces_out_boundary_modulation() {
+        let config = ColonyConfig {
+            dissolution_interval: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            Colony::from_config(config.clone())
+                .config()
+                .dissolution_interval,
+            4
+        );
+
+        let stream = seeded_dissolution_run(config, false, Vec::new());
+        let dissolved: Vec<Tick> = stream
+            .iter()
+            .filter(|(_, e)| e.starts_with("{\"Dissolved\""))
+            .map(|(tick, _)| *tick)
+            .collect();
+        assert!(!dissolved.is_empty());
+        assert!(dissolved.iter().all(|tick| tick % 4 == 0), "{dissolved:?}");
     }
+    #[test]
+    fn every_event_round_trips_through_json() {
+        let id = AgentId::new();
+        let other = AgentId::new();
+        let node = NodeId::new();
+        let document = DocumentId::new();
+        let signal = |cause| DeathSignal {
+            agent_id: id,
+            total_ticks: 40,
+            useful_outputs: 3,
+            final_fragments: vec![b"cell".to_vec(), b"membrane".to_vec()],
+            cause,
+            had_pending_work: true,
+        };
+        let events = vec![
+            ColonyEvent::Spawned {
+                id,
+                agent_type: "digester".into(),
+                parent: Some(other),
+                generation: 2,
+            },
+            ColonyEvent::Moved {
+                id,
+                to: Position::new(1.5, -2.0),
+            },
+            ColonyEvent::Engulfed { id, document },
+            ColonyEvent::Presented {
+                id,
+                fragment_count: 2,
+                node_ids: vec![node],
+            },
+            ColonyEvent::Deposited {
+                id,
+                location: SubstrateLocation::Spatial(Position::new(0.0, 1.0)),
+            },
+            ColonyEvent::Deposited {
+                id,
+                location: SubstrateLocation::GraphNode(node),
+            },
+            ColonyEvent::Wired {
+                id,
+                connection_count: 4,
+            },
+            ColonyEvent::Died {
+                signal: signal(DeathCause::SelfAssessed(CellHealth::Redundant)),
+            },
+            ColonyEvent::Died {
+                signal: signal(DeathCause::SymbioticAbsorption(other)),
+            },
+            ColonyEvent::Died {
+                signal: signal(DeathCause::RuntimeTermination),
+            },
+            ColonyEvent::TickComplete {
+                tick: 7,
+                alive: 3,
+                dead_this_tick: 1,
+            },
+            ColonyEvent::CapabilityExported {
+                agent_id: id,
+                terms_count: 12,
+            },
+            ColonyEvent::CapabilityIntegrated {
+                agent_id: id,
+                from_agent: other,
+                terms_accepted: 5,
+                terms_offered: 8,
+            },
+            ColonyEvent::Symbiosis {
+                host: id,
+                absorbed: other,
+                host_type: "synthesizer".into(),
+                absorbed_type: "digester".into(),
+            },
+            ColonyEvent::Dissolved {
+                agent_id: id,
+                permeability: 0.75,
+                terms_externalized: 6,
+            },
+            ColonyEvent::InsightPruned {
+                node_id: node,
+                label: "cell-membrane".into(),
+                support: 0.1,
+            },
+            ColonyEvent::NodePruned {
+                node_id: node,
+                label: "cell".into(),
+                retention: 0.02,
+            },
+            ColonyEvent::DocumentUpdated {
+                id: document,
+                version: 2,
+                concepts_added: vec!["protein".into()],
+                concepts_removed: vec!["lipid".into()],
+            },
+            ColonyEvent::WorkReleased {
+                agent_id: id,
+                document,
+            },
+            ColonyEvent::NodesMerged {
+                node_id: node,
+                label: "membrane".into(),
+                merged: vec!["membranes".into()],
+            },
+            ColonyEvent::ActionFailed {
+                agent_id: id,
+                action_kind: "wire_nodes".into(),
+                reason: "unknown node".into(),
+            },
+            ColonyEvent::Connected {
+                from: node,
+                to: NodeId::new(),
+                weight: 0.5,
+                created_edge: true,
+                created_nodes: vec!["lipid".into()],
+            },
+            ColonyEvent::Annotated {
+                node_id: node,
+                label: "membrane".into(),
+                source: "mcp".into(),
+            },
+        ];
 
-    /// Get a mutable reference to the fitness tracker.
-    pub fn fitness_tracker_mut(&mut self) -> &mut FitnessTracker {
-        &mut self.fitness_tracker
+        let kinds: std::collections::HashSet<_> = events.iter().map(ColonyEvent::kind).collect();
+        assert_eq!(kinds.len(), 20, "a variant is missing from this test");
+
+        for event in &events {
+            let record = (9u64, event.clone());
+            let json = serde_json::to_string(&record).unwrap();
+            let (tick, back): (Tick, ColonyEvent) = serde_json::from_str(&json).unwrap();
+            assert_eq!(tick, 9);
+            assert_eq!(back.kind(), event.kind());
+            assert_eq!(serde_json::to_string(&(tick, back)).unwrap(), json);
+        }
     }
 
-    /// Emit an input signal at a position (to attract agents).
-    pub fn emit_input_signal(&mut self, position: Position, intensity: f64) {
-        let signal = Signal::new(
-            SignalType::Input,
-            intensity,
-            position,
-            AgentId::new(),
-            self.substrate.current_tick(),
+    #[test]
+    fn snapshot_round_trips_and_reads_older_records() {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Cells",
+            "cell membrane protein transport",
+            Position::new(0.0, 0.0),
         );
-        self.substrate.emit_signal(signal);
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        colony.run(15);
+
+        let snapshot = colony.snapshot();
+        let json = snapshot.to_json().unwrap();
+        let back = ColonySnapshot::from_json(&json).unwrap();
+        assert_eq!(back.to_json().unwrap(), json);
+        assert!(!back.nodes.is_empty());
+
+        // Snapshots recorded before trace accounting and tags existed
+        let mut older: serde_json::Value = serde_json::from_str(&json).unwrap();
+        older["stats"]
+            .as_object_mut()
+            .unwrap()
+            .remove("trace_bytes");
+        older["stats"]
+            .as_object_mut()
+            .unwrap()
+            .remove("traces_evicted");
+        for node in older["nodes"].as_array_mut().unwrap() {
+            node.as_object_mut().unwrap().remove("tags");
+        }
+        let back = ColonySnapshot::from_json(&older.to_string()).unwrap();
+        assert_eq!(back.stats.traces_evicted, 0);
+        assert_eq!(back.nodes.len(), snapshot.nodes.len());
     }
-}
 
-impl Default for Colony {
-    fn default() -> Self {
-        Self::new()
+    fn bounded_colony(behavior: BoundaryBehavior) -> Colony {
+        Colony::from_config(ColonyConfig {
+            world_bounds: Some(Rect::new(
+                Position::new(0.0, 0.0),
+                Position::new(100.0, 100.0),
+            )),
+            boundary_behavior: behavior,
+            ..Default::default()
+        })
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use phago_agents::digester::Digester;
 
     #[test]
-    fn spawn_and_count_agents() {
-        let mut colony = Colony::new();
-        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
-        colony.spawn(Box::new(Digester::new(Position::new(5.0, 5.0))));
-        assert_eq!(colony.alive_count(), 2);
-        assert_eq!(colony.stats().total_spawned, 2);
+    fn agents_follow_gradients_across_the_seam_under_wrap() {
+        let mut colony = bounded_colony(BoundaryBehavior::Wrap);
+        colony.spawn(Box::new(Digester::new(Position::new(98.5, 50.0))));
+        // Only within sensing range the short way round, across the right edge
+        colony.emit_input_signal(Position::new(3.0, 50.0), 1.0);
+
+        let mut xs = Vec::new();
+        for _ in 0..4 {
+            colony.tick();
+            xs.push(colony.agents()[0].position().x);
+        }
+        assert!(xs.iter().all(|x| (0.0..=100.0).contains(x)), "{xs:?}");
+        assert!(xs[0] > 99.0, "heads east first: {xs:?}");
+        assert!(xs[3] < 5.0, "comes back in on the left: {xs:?}");
     }
 
     #[test]
-    fn tick_advances_simulation() {
-        let mut colony = Colony::new();
-        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
-        colony.tick();
-        assert_eq!(colony.stats().tick, 1);
+    fn clamped_agents_stop_at_the_edge_and_documents_are_placed_inside() {
+        let mut colony = bounded_colony(BoundaryBehavior::Clamp);
+        colony.spawn(Box::new(Digester::new(Position::new(98.5, 50.0))));
+        colony.emit_input_signal(Position::new(108.0, 50.0), 1.0);
+        for _ in 0..4 {
+            colony.tick();
+            assert!(colony.agents()[0].position().x <= 100.0);
+        }
+        assert_eq!(colony.agents()[0].position().x, 100.0);
+
+        let doc =
+            colony.ingest_document("Far away", "distant text", Position::new(3000.0, -4500.0));
+        assert_eq!(
+            colony.substrate().get_document(&doc).unwrap().position,
+            Position::new(100.0, 0.0)
+        );
+
+        let snapshot = colony.snapshot();
+        assert_eq!(snapshot.world_bounds, colony.config().world_bounds);
+        let restored = ColonySnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(restored.world_bounds, snapshot.world_bounds);
+        assert!(Colony::new().snapshot().world_bounds.is_none());
     }
 
     #[test]
-    fn agent_apoptosis_in_colony() {
-        let mut colony = Colony::new();
-        colony.spawn(Box::new(
-            Digester::new(Position::new(0.0, 0.0)).with_max_idle(3),
-        ));
+    fn merged_duplicates_reinforce_the_original_before_or_after_digestion() {
+        const NOTE: &str = "Build 4417 finished at 09:12.\n\
+            The cell membrane controls transport of molecules into and out of the cell. \
+            Proteins embedded in the membrane serve as channels and receptors.";
+        let rebuilt = NOTE.replace("4417 finished at 09:12", "4418 finished at 11:40");
+        let merge = DedupConfig {
+            policy: DedupPolicy::MergeIntoExisting,
+            ..DedupConfig::default()
+        };
+        let presentations = |colony: &Colony, id: &DocumentId| -> u64 {
+            colony
+                .document_contribution(id)
+                .unwrap()
+                .nodes
+                .values()
+                .sum()
+        };
+        let digest = |colony: &mut Colony| {
+            colony.spawn(Box::new(
+                Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+            ));
+            colony.run(10);
+        };
 
-        assert_eq!(colony.alive_count(), 1);
+        // After: the merge lands on the nodes right away
+        let mut after = Colony::new();
+        let IngestOutcome::Ingested {
+            id,
+            duplicate_of: None,
+        } = after.ingest_deduplicated("build-a", NOTE, Position::new(0.0, 0.0), &[], &merge)
+        else {
+            panic!("first copy should be ingested");
+        };
+        digest(&mut after);
+        let digested = presentations(&after, &id);
+        let nodes = after.document_contribution(&id).unwrap().nodes.len() as u64;
+        let outcome =
+            after.ingest_deduplicated("build-b", &rebuilt, Position::new(0.0, 0.0), &[], &merge);
+        assert!(matches!(outcome, IngestOutcome::Merged(ref m) if m.original == id));
+        assert_eq!(presentations(&after, &id), digested + nodes);
+        assert_eq!(after.stats().documents_total, 1);
+
+        // Before: held back until the original has been presented
+        let mut before = Colony::new();
+        let batch = [
+            BatchDocument {
+                title: "build-a",
+                content: NOTE,
+                position: Position::new(0.0, 0.0),
+                tags: &[],
+            },
+            BatchDocument {
+                title: "build-b",
+                content: &rebuilt,
+                position: Position::new(0.0, 0.0),
+                tags: &[],
+            },
+        ];
+        let report = before.ingest_batch(&batch, &merge);
+        assert_eq!(report.ingested.len(), 1);
+        assert_eq!(report.merged.len(), 1);
+        digest(&mut before);
+        assert_eq!(
+            presentations(&before, &report.ingested[0]),
+            digested + nodes
+        );
+    }
 
-        for _ in 0..5 {
-            colony.tick();
-        }
+    #[test]
+    fn capped_wiring_prefers_concepts_sharing_sentences() {
+        let first = TextSpan::new(0, 10);
+        let second = TextSpan::new(11, 20);
+        let (none, one, both) = ([], [first], [first, second]);
+        let passages: Vec<&[TextSpan]> = vec![&none, &both, &one, &both];
 
-        assert_eq!(colony.alive_count(), 0);
-        assert_eq!(colony.stats().agents_died, 1);
-        assert_eq!(colony.death_signals().len(), 1);
+        let namespaces = [None; 4];
+
+        assert_eq!(wiring_pairs(&passages, &namespaces, None).len(), 6);
+        assert_eq!(wiring_pairs(&passages, &namespaces, Some(10)).len(), 6);
+        assert_eq!(wiring_pairs(&passages, &namespaces, Some(1)), vec![(1, 3)]);
+        assert_eq!(
+            wiring_pairs(&passages, &namespaces, Some(4)),
+            vec![(1, 3), (1, 2), (2, 3), (0, 1)]
+        );
     }
 
     #[test]
-    fn ingest_document_creates_signal() {
-        let mut colony = Colony::new();
-        let pos = Position::new(5.0, 5.0);
-        let doc_id = colony.ingest_document("Test Doc", "cell membrane protein", pos);
+    fn wiring_pairs_stay_within_a_namespace() {
+        let span = [TextSpan::new(0, 10)];
+        let passages: Vec<&[TextSpan]> = vec![&span; 4];
+        let namespaces = [Some("a.rs"), Some("b.rs"), None, Some("a.rs")];
 
-        let stats = colony.stats();
-        assert_eq!(stats.documents_total, 1);
-        assert_eq!(stats.documents_digested, 0);
-        assert_eq!(stats.total_signals, 1); // Input signal emitted
+        assert_eq!(
+            wiring_pairs(&passages, &namespaces, None),
+            vec![(0, 2), (0, 3), (1, 2), (2, 3)]
+        );
+        assert_eq!(
+            wiring_pairs(&passages, &namespaces, Some(2)),
+            vec![(0, 2), (0, 3)]
+        );
+    }
 
-        // Document is in the substrate
-        let doc = colony.substrate().get_document(&doc_id);
-        assert!(doc.is_some());
-        assert!(!doc.unwrap().digested);
+    /// A long document of `words` words: twenty terms make up half of it,
+    /// the rest is numbers, hex blobs and words that occur once.
+    fn long_document(words: usize) -> (Vec<&'static str>, String) {
+        const FREQUENT: [&str; 20] = [
+            "membrane",
+            "protein",
+            "enzyme",
+            "ribosome",
+            "nucleus",
+            "cytoplasm",
+            "mitochondria",
+            "lipid",
+            "receptor",
+            "signal",
+            "vesicle",
+            "chromatin",
+            "kinase",
+            "ligand",
+            "peptide",
+            "organelle",
+            "transport",
+            "channel",
+            "gradient",
+            "metabolism",
+        ];
+        let rare = |i: usize| {
+            let mut word = String::from("rare");
+            let mut n = i;
+            while n > 0 {
+                word.push((b'a' + (n % 26) as u8) as char);
+                n /= 26;
+            }
+            word
+        };
+        let mut text = String::new();
+        for i in 0..words {
+            let word = match i % 10 {
+                0 | 2 | 4 | 6 | 8 => FREQUENT[(i / 2) % FREQUENT.len()].to_string(),
+                1 => i.to_string(),
+                3 => format!("{:012x}", i as u64 * 2_654_435_761),
+                _ => rare(i),
+            };
+            text.push_str(&word);
+            text.push_str(if i % 7 == 6 { ". " } else { " " });
+        }
+        (FREQUENT.to_vec(), text)
     }
 
     #[test]
-    fn agent_finds_and_digests_document() {
-        let mut colony = Colony::new();
+    fn digestion_limits_bound_what_a_long_document_adds() {
+        let (frequent, text) = long_document(5_000);
+        let mut colony = Colony::from_config(ColonyConfig {
+            digestion: DigestionLimits {
+                max_fragments_per_document: Some(40),
+                max_pairs_per_presentation: Some(200),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        colony.ingest_document("long", &text, Position::new(0.0, 0.0));
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony.run(5);
 
-        // Place document at origin
-        colony.ingest_document(
-            "Biology 101",
-            "The cell membrane controls transport of molecules into and out of the cell. \
-             Proteins embedded in the membrane serve as channels and receptors.",
-            Position::new(0.0, 0.0),
-        );
+        assert_eq!(colony.stats().documents_digested, 1);
+        let graph = colony.substrate().graph();
+        assert!(graph.node_count() <= 40);
+        assert!(graph.edge_count() <= 200);
 
-        // Spawn agent at origin (right on top of the document)
+        let labels: Vec<String> = graph
+            .all_nodes()
+            .iter()
+            .filter_map(|id| graph.get_node(id))
+            .map(|n| n.label.clone())
+            .collect();
+        for term in &frequent {
+            assert!(labels.iter().any(|l| l == term), "{term} missing");
+        }
+        assert!(labels
+            .iter()
+            .all(|l| !l.chars().any(|c| c.is_ascii_digit())));
+
+        // The wiring budget went to the terms that keep co-occurring
+        for term in &frequent {
+            let id = graph.find_nodes_by_exact_label(term)[0];
+            assert!(
+                graph.neighbors(&id).iter().any(|(other, _)| graph
+                    .get_node(other)
+                    .is_some_and(|n| frequent.contains(&n.label.as_str()))),
+                "{term} not wired to another frequent term"
+            );
+        }
+    }
+
+    /// Digests a document using the singular, then one using the plural.
+    fn singular_and_plural_colony(config: ColonyConfig) -> (Colony, DocumentId, DocumentId) {
+        let mut colony = Colony::from_config(config);
         colony.spawn(Box::new(
-            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(100),
         ));
-
-        // Run enough ticks for the full cycle:
-        // tick 1: Seeking → finds doc → EngulfDocument
-        // tick 2: FoundTarget → engulfed → Digesting
-        // tick 3: Digesting → lyse → PresentFragments
-        // tick 4: Presenting → Deposit trace
-        colony.run(10);
-
-        let stats = colony.stats();
-        assert_eq!(stats.documents_digested, 1, "Document should be digested");
-        assert!(
-            stats.graph_nodes > 0,
-            "Should have concept nodes: got {}",
-            stats.graph_nodes
+        let singular = colony.ingest_document(
+            "Singular",
+            "The membrane protects the cell. A membrane protein binds lipid.",
+            Position::new(0.0, 0.0),
         );
-        assert!(
-            stats.graph_edges > 0,
-            "Should have edges: got {}",
-            stats.graph_edges
+        colony.run(10);
+        let plural = colony.ingest_document(
+            "Plural",
+            "Membranes protect cells. Membranes hold transport channels.",
+            Position::new(0.0, 0.0),
         );
+        colony.run(10);
+        (colony, singular, plural)
     }
 
     #[test]
-    fn multiple_documents_build_graph() {
-        let mut colony = Colony::new();
+    fn compaction_merges_plural_variants_into_one_node() {
+        let (mut colony, singular, plural) = singular_and_plural_colony(ColonyConfig::default());
+        let graph = colony.substrate().graph();
+        let membrane = graph.find_nodes_by_exact_label("membrane")[0];
+        let membranes = graph.find_nodes_by_exact_label("membranes")[0];
+        assert_ne!(membrane, membranes);
+        let count = |id| graph.get_node(&id).unwrap().access_count;
+        let combined = count(membrane) + count(membranes);
+        let plural_neighbors: Vec<String> = graph
+            .neighbors(&membranes)
+            .into_iter()
+            .filter(|(n, _)| *n != membrane)
+            .filter_map(|(n, _)| graph.get_node(&n).map(|n| n.label.clone()))
+            .collect();
+        assert!(!plural_neighbors.is_empty());
 
-        // Two documents about related topics
+        let plan = colony.plan_compaction(&CompactionStrategy::Stemming);
+        assert!(plan.clusters.iter().any(|c| c
+            .merged
+            .iter()
+            .any(|m| m.id == membrane || m.id == membranes)));
+        let events = colony.compact_labels(&CompactionStrategy::Stemming);
+        assert_eq!(events.len(), plan.clusters.len());
+
+        let graph = colony.substrate().graph();
+        let merged = graph.find_nodes_by_exact_label("membranes");
+        assert_eq!(merged, graph.find_nodes_by_exact_label("membrane"));
+        assert_eq!(merged.len(), 1);
+        let node = graph.get_node(&merged[0]).unwrap();
+        assert_eq!(node.access_count, combined);
+        assert!(graph.get_node(&membrane).is_none() || graph.get_node(&membranes).is_none());
+        assert!(graph.aliases(&merged[0]).contains(&"membranes".to_string()));
+        for label in &plural_neighbors {
+            let other = graph.find_nodes_by_exact_label(label)[0];
+            assert!(other == merged[0] || graph.get_edge(&merged[0], &other).is_some());
+        }
+
+        // Both documents' contributions and passages now point at the one node
+        for doc in [singular, plural] {
+            let contribution = colony.document_contribution(&doc).unwrap();
+            assert!(contribution.nodes.contains_key(&merged[0]));
+            assert!(!contribution
+                .nodes
+                .keys()
+                .any(|id| graph.get_node(id).is_none()));
+            let passages = colony.substrate().passages(&doc).unwrap();
+            assert!(!passages.spans(&node.label).is_empty());
+        }
+
+        // Presenting the alias reinforces the canonical node
+        let before = node.access_count;
         colony.ingest_document(
-            "Cell Biology",
-            "The cell membrane is a lipid bilayer that controls transport. \
-             Proteins in the membrane act as channels and receptors for signaling.",
+            "Again",
+            "Membranes separate compartments.",
             Position::new(0.0, 0.0),
         );
-        colony.ingest_document(
-            "Molecular Transport",
-            "Active transport across the cell membrane requires ATP energy. \
-             Channel proteins facilitate passive transport of ions and molecules.",
-            Position::new(2.0, 0.0),
-        );
-
-        // Spawn agents near each document
-        colony.spawn(Box::new(
-            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
-        ));
-        colony.spawn(Box::new(
-            Digester::new(Position::new(2.0, 0.0)).with_max_idle(50),
-        ));
-
-        colony.run(20);
+        colony.run(10);
+        let graph = colony.substrate().graph();
+        assert_eq!(graph.find_nodes_by_exact_label("membranes"), merged);
+        assert!(graph.get_node(&merged[0]).unwrap().access_count > before);
+    }
 
-        let stats = colony.stats();
+    #[test]
+    fn compaction_runs_on_its_interval() {
+        let (colony, _, _) = singular_and_plural_colony(ColonyConfig {
+            compaction: CompactionConfig {
+                interval: 5,
+                strategy: CompactionStrategy::Stemming,
+            },
+            ..Default::default()
+        });
+        let graph = colony.substrate().graph();
+        assert_eq!(graph.find_nodes_by_exact_label("membranes").len(), 1);
         assert_eq!(
-            stats.documents_digested, 2,
-            "Both documents should be digested"
-        );
-        // Shared concepts (cell, membrane, transport, proteins) should create
-        // overlapping graph nodes and strengthen edges
-        assert!(
-            stats.graph_nodes >= 5,
-            "Expected at least 5 concept nodes, got {}",
-            stats.graph_nodes
+            graph.find_nodes_by_exact_label("membranes"),
+            graph.find_nodes_by_exact_label("membrane")
         );
+        assert!(colony
+            .plan_compaction(&CompactionStrategy::Stemming)
+            .is_empty());
+        assert!(colony
+            .event_history()
+            .iter()
+            .any(|(_, e)| matches!(e, ColonyEvent::NodesMerged { .. })));
     }
 
     #[test]
-    fn colony_stats_are_accurate() {
-        let mut colony = Colony::new();
-        colony.spawn(Box::new(
-            Digester::new(Position::new(0.0, 0.0)).with_max_idle(2),
-        ));
-        colony.spawn(Box::new(
-            Digester::new(Position::new(5.0, 5.0)).with_max_idle(100),
-        ));
+    fn communities_are_tracked_on_their_interval() {
+        let (mut colony, _, _) = singular_and_plural_colony(ColonyConfig {
+            communities: CommunityConfig {
+                interval: 5,
+                algorithm: CommunityAlgorithm::LabelPropagation { seed: 3 },
+            },
+            ..Default::default()
+        });
+        let snapshot = colony.snapshot();
+        assert!(snapshot.nodes.iter().all(|n| n.community.is_some()));
+        let membrane = colony.community_tracker().community_of("membrane");
 
-        colony.run(5);
+        // Nothing changed since the last detection, so neither did the IDs
+        let result = colony.update_communities();
+        assert_eq!(result.assignments.get("membrane").copied(), membrane);
+        assert_eq!(
+            result.algorithm,
+            CommunityAlgorithm::LabelPropagation { seed: 3 }
+        );
+        assert_eq!(result.total_nodes, snapshot.nodes.len());
+    }
 
-        let stats = colony.stats();
-        assert_eq!(stats.total_spawned, 2);
-        assert_eq!(stats.agents_died, 1);
-        assert_eq!(stats.agents_alive, 1);
+    /// Swaps the first action it sees for wiring between nodes that do
+    /// not exist.
+    struct BogusWiring(bool);
+
+    impl ColonyPlugin for BogusWiring {
+        fn before_action(
+            &mut self,
+            _substrate: &SubstrateImpl,
+            _agent: AgentId,
+            _action: &AgentAction,
+        ) -> ActionDecision {
+            if std::mem::replace(&mut self.0, true) {
+                return ActionDecision::Allow;
+            }
+            ActionDecision::Replace(AgentAction::WireNodes(vec![(
+                NodeId::new(),
+                NodeId::new(),
+                0.5,
+            )]))
+        }
     }
 
     #[test]
-    fn semantic_wiring_config_is_accessible() {
-        let colony = Colony::new();
-        let config = colony.semantic_wiring_config();
-        // Default config should not require embeddings
-        assert!(!config.require_embeddings);
-        assert!(config.min_similarity >= 0.0);
-        assert!(config.similarity_influence >= 0.0);
+    fn wiring_unknown_nodes_is_reported_and_counted() {
+        let mut colony = Colony::new();
+        colony.add_plugin(Box::new(BogusWiring(false)));
+        let id = colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+
+        let events = colony.tick();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ColonyEvent::ActionFailed { agent_id, action_kind, reason }
+                if *agent_id == id
+                    && action_kind == "wire_nodes"
+                    && reason.starts_with("unknown node")
+        )));
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, ColonyEvent::Wired { .. })));
+        assert_eq!(colony.substrate().edge_count(), 0);
+        assert_eq!(colony.error_counters().action_failures["wire_nodes"], 1);
+        assert_eq!(colony.stats().errors.total(), 1);
     }
 
     #[test]
-    fn with_semantic_wiring_configures_colony() {
-        use phago_core::semantic::SemanticWiringConfig;
-
-        let colony = Colony::new().with_semantic_wiring(SemanticWiringConfig::strict());
+    fn unreadable_capability_deposits_are_reported_once() {
+        let mut colony = Colony::new();
+        let id = colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony.substrate_mut().deposit_trace(
+            &SubstrateLocation::Spatial(Position::new(0.0, 0.0)),
+            Trace {
+                agent_id: AgentId::new(),
+                trace_type: TraceType::CapabilityDeposit,
+                intensity: 1.0,
+                tick: 0,
+                payload: b"{\"terms\": [\"cell\"".to_vec(),
+            },
+        );
 
-        let config = colony.semantic_wiring_config();
-        assert!(config.require_embeddings);
-        assert!(config.min_similarity > 0.0);
+        let events: Vec<ColonyEvent> = colony.run(3).into_iter().flatten().collect();
+        let failures: Vec<&str> = events
+            .iter()
+            .filter_map(|e| match e {
+                ColonyEvent::ActionFailed {
+                    agent_id,
+                    action_kind,
+                    reason,
+                } if *agent_id == id => {
+                    assert!(reason.starts_with("invalid vocabulary payload"));
+                    Some(action_kind.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(failures, vec!["integrate_capability"]);
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, ColonyEvent::CapabilityIntegrated { .. })));
+        assert_eq!(
+            colony.stats().errors.action_failures["integrate_capability"],
+            1
+        );
     }
 
     #[test]
-    fn semantic_wiring_boosts_similar_concept_edges() {
-        use phago_core::semantic::SemanticWiringConfig;
+    fn auto_placement_clusters_related_documents() {
+        let mut colony = Colony::new();
+        let biology = [
+            colony.ingest_document_auto(
+                "Membranes",
+                "The cell membrane controls transport of proteins and ions into the cell.",
+            ),
+            colony.ingest_document_auto(
+                "Mitochondria",
+                "Mitochondria supply the cell with energy; membrane proteins move ions.",
+            ),
+            colony.ingest_document_auto(
+                "Ribosomes",
+                "Ribosomes build proteins that the cell inserts into its membrane.",
+            ),
+        ];
+        let quantum = colony.ingest_document_auto(
+            "Qubits",
+            "Quantum computers entangle qubits; superposition and decoherence limit algorithms.",
+        );
 
-        let mut colony = Colony::new().with_semantic_wiring(SemanticWiringConfig::default());
+        let position = |id: &DocumentId| colony.substrate().get_document(id).unwrap().position;
+        let points: Vec<Position> = biology.iter().map(position).collect();
+        let centroid = Position::new(
+            points.iter().map(|p| p.x).sum::<f64>() / 3.0,
+            points.iter().map(|p| p.y).sum::<f64>() / 3.0,
+        );
+        let widest = points
+            .iter()
+            .flat_map(|a| points.iter().map(move |b| a.distance_to(b)))
+            .fold(0.0, f64::max);
+        assert!(widest > 0.0, "documents must not pile up");
+        assert!(position(&quantum).distance_to(&centroid) > widest);
 
-        // Manually add two nodes with similar embeddings
-        let emb_a = vec![1.0, 0.0, 0.0]; // Unit vector along x
-        let emb_b = vec![0.95, 0.31, 0.0]; // ~18° from emb_a (high similarity)
+        // Re-placing an existing title keeps its position
+        assert_eq!(
+            colony.place_document("Qubits", "anything"),
+            position(&quantum)
+        );
+    }
 
-        let node_a = colony.substrate_mut().add_node(NodeData {
+    fn concept(label: &str) -> NodeData {
+        NodeData {
             id: NodeId::new(),
-            label: "concept_a".to_string(),
+            label: label.into(),
             node_type: NodeType::Concept,
-            position: Position::new(0.0, 0.0),
+            position: Position::new(2.0, 3.0),
             access_count: 1,
             created_tick: 0,
-            embedding: Some(emb_a),
-        });
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        }
+    }
 
-        let node_b = colony.substrate_mut().add_node(NodeData {
-            id: NodeId::new(),
-            label: "concept_b".to_string(),
-            node_type: NodeType::Concept,
-            position: Position::new(1.0, 0.0),
-            access_count: 1,
-            created_tick: 0,
-            embedding: Some(emb_b),
-        });
+    #[test]
+    fn connect_concepts_creates_then_strengthens_an_edge() {
+        let mut colony = Colony::new();
+        let membrane = colony.substrate_mut().add_node(concept("membrane"));
+        let galaxy = colony.substrate_mut().add_node(concept("galaxy"));
+
+        for (from, to, weight, error) in [
+            ("", "galaxy", 0.5, GraphEditError::EmptyLabel),
+            (
+                "membrane",
+                "nebula",
+                0.5,
+                GraphEditError::UnknownConcept("nebula".into()),
+            ),
+            (
+                "membrane",
+                "galaxy",
+                0.0,
+                GraphEditError::InvalidWeight(0.0),
+            ),
+            (
+                "membrane",
+                "galaxy",
+                1.5,
+                GraphEditError::InvalidWeight(1.5),
+            ),
+            (
+                "membrane",
+                "Membrane",
+                0.5,
+                GraphEditError::SelfLoop("membrane".into()),
+            ),
+        ] {
+            assert_eq!(
+                colony
+                    .connect_concepts(from, to, weight, None, false)
+                    .unwrap_err(),
+                error
+            );
+        }
+        assert!(colony
+            .connect_concepts("membrane", "galaxy", f64::NAN, None, false)
+            .is_err());
+        assert_eq!(colony.substrate().graph().edge_count(), 0);
 
-        // Wire them manually using WireNodes action
-        colony.substrate_mut().set_edge(
-            node_a,
-            node_b,
-            EdgeData {
-                weight: 0.1,
-                co_activations: 1,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        let event = colony
+            .connect_concepts("Membrane", "galaxy", 0.4, Some("resembles"), false)
+            .unwrap();
+        assert!(matches!(
+            event,
+            ColonyEvent::Connected { created_edge: true, weight, .. } if weight == 0.4
+        ));
+        assert_eq!(colony.edge_relation(&membrane, &galaxy), Some("resembles"));
 
-        let edge = colony
-            .substrate()
-            .graph()
-            .get_edge(&node_a, &node_b)
+        let event = colony
+            .connect_concepts("galaxy", "membrane", 0.4, None, false)
             .unwrap();
-        assert!(edge.weight >= 0.1, "Edge should have at least base weight");
+        let ColonyEvent::Connected {
+            created_edge,
+            weight,
+            ..
+        } = event
+        else {
+            panic!("expected a connected event");
+        };
+        assert!(!created_edge);
+        assert!((weight - 0.8).abs() < 1e-12);
+        assert_eq!(
+            colony
+                .substrate()
+                .graph()
+                .get_edge(&membrane, &galaxy)
+                .unwrap()
+                .co_activations,
+            2
+        );
+        assert_eq!(
+            colony
+                .event_history()
+                .iter()
+                .filter(|(_, e)| e.kind() == "connected")
+                .count(),
+            2
+        );
     }
 
     #[test]
-    fn semantic_wiring_with_no_embeddings_uses_base_weight() {
-        use phago_core::semantic::SemanticWiringConfig;
+    fn connect_concepts_can_create_missing_concepts() {
+        let mut colony = Colony::new();
+        colony.substrate_mut().add_node(concept("membrane"));
 
-        let mut colony = Colony::new().with_semantic_wiring(SemanticWiringConfig::default());
+        let event = colony
+            .connect_concepts("membrane", "lipid", 0.5, None, true)
+            .unwrap();
+        let ColonyEvent::Connected {
+            to, created_nodes, ..
+        } = event
+        else {
+            panic!("expected a connected event");
+        };
+        assert_eq!(created_nodes, vec!["lipid"]);
+        let lipid = colony.substrate().graph().get_node(&to).unwrap();
+        assert_eq!(lipid.node_type, NodeType::Concept);
+        assert_eq!(lipid.position, Position::new(2.0, 3.0));
+    }
 
-        // Add two nodes WITHOUT embeddings
-        let node_a = colony.substrate_mut().add_node(NodeData {
-            id: NodeId::new(),
-            label: "plain_a".to_string(),
-            node_type: NodeType::Concept,
-            position: Position::new(0.0, 0.0),
-            access_count: 1,
-            created_tick: 0,
-            embedding: None,
-        });
+    #[test]
+    fn sentinel_anomalies_carry_scores() {
+        use phago_agents::sentinel::Sentinel;
 
-        let node_b = colony.substrate_mut().add_node(NodeData {
-            id: NodeId::new(),
-            label: "plain_b".to_string(),
-            node_type: NodeType::Concept,
-            position: Position::new(1.0, 0.0),
-            access_count: 1,
-            created_tick: 0,
-            embedding: None,
-        });
+        let mut colony = Colony::new();
+        let origin = Position::new(0.0, 0.0);
+        colony.ingest_document(
+            "Biology",
+            "The cell membrane controls protein transport. Ribosomes build protein in the cell.",
+            origin,
+        );
+        colony.spawn(Box::new(Digester::new(origin).with_max_idle(200)));
+        colony.run(10);
+        colony.spawn(Box::new(Sentinel::new(origin)));
+        colony.run(10);
 
-        // Wire them
-        colony.substrate_mut().set_edge(
-            node_a,
-            node_b,
-            EdgeData {
-                weight: 0.1,
-                co_activations: 1,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
+        let weird = colony.ingest_document(
+            "Astronomy",
+            "Quasars and pulsars light the nebula around a distant galaxy.",
+            origin,
+        );
+        let events: Vec<ColonyEvent> = colony.run(30).into_iter().flatten().collect();
+        let (label, score) = events
+            .iter()
+            .find_map(|e| match e {
+                ColonyEvent::AnomalyDetected {
+                    label,
+                    score,
+                    document: Some(doc),
+                    ..
+                } if *doc == weird => Some((label.clone(), *score)),
+                _ => None,
+            })
+            .expect("the astronomy document is scored");
+        assert!(
+            label.starts_with("[ANOMALY] Document 'Astronomy'"),
+            "{label}"
         );
+        assert!(score > 0.5, "{score}");
+
+        let graph = colony.substrate().graph();
+        let id = graph.find_nodes_by_exact_label(&label)[0];
+        assert_eq!(colony.anomaly_score(&id), Some(score));
+        let anomalies = colony.anomalies();
+        assert!(anomalies.windows(2).all(|w| w[0].1 >= w[1].1));
+        let snapshot = colony.snapshot();
+        let node = snapshot.nodes.iter().find(|n| n.label == label).unwrap();
+        assert_eq!(node.anomaly_score, Some(score));
+    }
 
-        let edge = colony
+    #[test]
+    fn term_index_follows_merges() {
+        let mut colony = Colony::new();
+        let membrane = colony.substrate_mut().add_node(concept("membrane"));
+        let membranes = colony.substrate_mut().add_node(concept("cell membranes"));
+        // Added behind the colony's back, so withheld until rebuilt
+        assert!(colony.substrate().term_index().is_none());
+        assert!(colony.sync_term_index());
+
+        colony.merge_nodes(membrane, &[membranes]).unwrap();
+        let index = colony
             .substrate()
-            .graph()
-            .get_edge(&node_a, &node_b)
+            .term_index()
+            .expect("merging keeps the index current");
+        assert_eq!(index.node_count(), 1);
+        assert_eq!(index.document_frequency("membrane"), 1);
+        assert_eq!(index.document_frequency("membranes"), 0);
+        assert_eq!(index.document_frequency("cell"), 0);
+    }
+
+    #[test]
+    fn annotations_are_validated_and_follow_merges() {
+        let mut colony = Colony::new();
+        let membrane = colony.substrate_mut().add_node(concept("membrane"));
+        let membranes = colony.substrate_mut().add_node(concept("membranes"));
+
+        assert_eq!(
+            colony.annotate("membranes", "  ", "mcp").unwrap_err(),
+            GraphEditError::EmptyNote
+        );
+        let long = "x".repeat(MAX_ANNOTATION_CHARS + 1);
+        assert_eq!(
+            colony.annotate("membranes", &long, "mcp").unwrap_err(),
+            GraphEditError::NoteTooLong(MAX_ANNOTATION_CHARS + 1)
+        );
+        assert_eq!(
+            colony.annotate("nebula", "far away", "mcp").unwrap_err(),
+            GraphEditError::UnknownConcept("nebula".into())
+        );
+
+        let event = colony
+            .annotate("Membranes", "plural used by the lab", "mcp")
             .unwrap();
-        // With default config, no embeddings means base weight is used
-        assert!(
-            (edge.weight - 0.1).abs() < 0.01,
-            "Edge should use base weight: got {}",
-            edge.weight
+        assert_eq!(event.kind(), "annotated");
+        assert_eq!(
+            colony
+                .annotate("membranes", "plural used by the lab", "mcp")
+                .unwrap_err(),
+            GraphEditError::DuplicateNote("membranes".into())
+        );
+        assert_eq!(
+            colony.annotations(&membranes)[0].text,
+            "plural used by the lab"
         );
+
+        colony.merge_nodes(membrane, &[membranes]).unwrap();
+        let notes = colony.annotations(&membrane);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].source, "mcp");
+        assert!(colony.annotations(&membranes).is_empty());
     }
 }
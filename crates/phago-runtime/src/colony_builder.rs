@@ -5,12 +5,14 @@
 //!
 //! # Architecture
 //!
-//! The Colony uses PetTopologyGraph internally for simulation (required for
-//! reference-based operations). Persistence is handled by:
+//! The colony built here simulates on the in-memory PetTopologyGraph.
+//! Persistence is handled by:
 //! - Loading initial state from SQLite on creation
 //! - Saving state to SQLite on explicit save or drop
 //!
 //! This gives the benefits of persistence without compromising simulation performance.
+//! To run the simulation directly against SQLite instead (for graphs that
+//! don't fit in memory), set `ColonyConfig::backend` to `BackendConfig::Sqlite`.
 //!
 //! # Example
 //!
@@ -38,8 +40,6 @@ use std::path::{Path, PathBuf};
 #[cfg(feature = "sqlite")]
 use crate::sqlite_topology::SqliteTopologyGraph;
 #[cfg(feature = "sqlite")]
-use crate::topology_impl::PetTopologyGraph;
-#[cfg(feature = "sqlite")]
 use phago_core::topology::TopologyGraph;
 
 /// Error type for colony builder operations.
@@ -153,6 +153,7 @@ impl ColonyBuilder {
 
             // Load existing nodes into the colony's graph
             load_from_sqlite(&db, colony.substrate_mut().graph_mut())?;
+            colony.sync_term_index();
 
             Some(PersistenceState {
                 db,
@@ -219,14 +220,12 @@ impl PersistentColony {
     /// Consume self and return the inner colony.
     ///
     /// Note: This will NOT trigger auto-save. Call `save()` first if needed.
+    /// The database is closed.
     pub fn into_inner(mut self) -> Colony {
-        // Disable auto_save to prevent save on drop
-        #[cfg(feature = "sqlite")]
-        if let Some(ref mut state) = self.persistence {
-            state.auto_save = false;
-        }
+        // Close the database without saving
+        drop(self.persistence.take());
         // Use ManuallyDrop to prevent Drop from running
-        let colony = std::mem::replace(&mut self.colony, Colony::new());
+        let colony = std::mem::take(&mut self.colony);
         std::mem::forget(self); // Don't run Drop
         colony
     }
@@ -237,10 +236,14 @@ impl PersistentColony {
     }
 
     /// Save the current graph state to SQLite.
+    ///
+    /// The save is timed and reported to the colony's metrics registry.
     #[cfg(feature = "sqlite")]
     pub fn save(&mut self) -> Result<(), BuilderError> {
         if let Some(ref mut state) = self.persistence {
+            let started = std::time::Instant::now();
             save_to_sqlite(self.colony.substrate().graph(), &mut state.db)?;
+            self.colony.record_save(started.elapsed());
         }
         Ok(())
     }
@@ -329,24 +332,68 @@ impl Drop for PersistentColony {
     }
 }
 
-/// Load nodes and edges from SQLite into PetTopologyGraph.
+/// Save the colony's graph to a new database at `path`, replacing the
+/// database there only once the new one is complete.
+///
+/// The graph is written to `<path>.tmp`, which is then renamed over `path`:
+/// a save interrupted part way leaves the previous database intact. Unlike
+/// [`PersistentColony::save`], nothing the graph no longer holds is kept.
+/// No connection to `path` may be open while saving.
+#[cfg(feature = "sqlite")]
+pub fn save_atomically<P: AsRef<Path>>(colony: &Colony, path: P) -> Result<(), BuilderError> {
+    let path = path.as_ref();
+    let started = std::time::Instant::now();
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    // Left behind by an interrupted save
+    for suffix in ["", "-wal", "-shm"] {
+        let mut stale = tmp.as_os_str().to_owned();
+        stale.push(suffix);
+        let _ = std::fs::remove_file(stale);
+    }
+
+    {
+        let mut db = SqliteTopologyGraph::open(&tmp)
+            .map_err(|e| BuilderError::DatabaseError(e.to_string()))?;
+        save_to_sqlite(colony.substrate().graph(), &mut db)?;
+        // Closing the last connection checkpoints the log into the file
+    }
+    std::fs::File::open(&tmp)
+        .and_then(|file| file.sync_all())
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| BuilderError::PersistenceError(e.to_string()))?;
+    colony.record_save(started.elapsed());
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlite"))]
+pub fn save_atomically<P: AsRef<Path>>(_colony: &Colony, _path: P) -> Result<(), BuilderError> {
+    Err(BuilderError::SqliteNotEnabled)
+}
+
+/// Load nodes and edges from SQLite into the colony's graph.
 #[cfg(feature = "sqlite")]
 fn load_from_sqlite(
     source: &SqliteTopologyGraph,
-    target: &mut PetTopologyGraph,
+    target: &mut (dyn TopologyGraph + Send + Sync),
 ) -> Result<(), BuilderError> {
     // Load all nodes using the iterator
     let mut node_count = 0;
     for node in source.iter_nodes() {
-        target.add_node(node);
+        let id = target.add_node(node);
+        for annotation in source.annotations(&id) {
+            target.annotate(&id, annotation);
+        }
         node_count += 1;
     }
 
-    // Load all edges
+    // Load all edges; those whose endpoints are missing are skipped
     let mut edge_count = 0;
     for (from, to, edge) in source.iter_edges() {
-        target.set_edge(from, to, edge);
-        edge_count += 1;
+        if target.set_edge(from, to, edge).is_ok() {
+            edge_count += 1;
+        }
     }
 
     if node_count > 0 || edge_count > 0 {
@@ -359,24 +406,28 @@ fn load_from_sqlite(
     Ok(())
 }
 
-/// Save nodes and edges from PetTopologyGraph to SQLite.
+/// Save nodes and edges from the colony's graph to SQLite.
 #[cfg(feature = "sqlite")]
 fn save_to_sqlite(
-    source: &PetTopologyGraph,
+    source: &(dyn TopologyGraph + Send + Sync),
     target: &mut SqliteTopologyGraph,
 ) -> Result<(), BuilderError> {
     // Save all nodes
     for node_id in source.all_nodes() {
         if let Some(node) = source.get_node(&node_id) {
             target.add_node(node.clone());
+            for annotation in source.annotations(&node_id) {
+                target.annotate(&node_id, annotation);
+            }
         }
     }
 
-    // Save all edges
+    // Save all edges; their endpoints were saved above
     for (from, to, edge) in source.all_edges() {
-        target.set_edge(from, to, edge.clone());
+        let _ = target.set_edge(from, to, edge.clone());
     }
 
+    target.flush();
     Ok(())
 }
 
@@ -408,9 +459,14 @@ mod tests {
         assert_eq!(colony.persistence_path(), Some(tmp.as_path()));
 
         // Ingest a document and save
+        let registry = std::sync::Arc::new(crate::metrics::PromRegistry::new());
+        colony.colony_mut().attach_metrics(registry.clone(), "test");
         colony.ingest_document("Test", "Content", Position::new(0.0, 0.0));
         colony.run(5);
         colony.save().unwrap();
+        assert!(registry
+            .encode()
+            .contains("phago_last_save_timestamp_seconds{space=\"test\"}"));
 
         // Clean up
         let _ = std::fs::remove_file(&tmp);
@@ -460,6 +516,10 @@ mod tests {
                 Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
             ));
             colony.run(15);
+            colony
+                .colony_mut()
+                .annotate("membrane", "bounds the cell", "test")
+                .unwrap();
 
             let stats = colony.stats();
             colony.save().unwrap();
@@ -480,7 +540,61 @@ mod tests {
             stats2.graph_edges, edge_count,
             "Edge count should match after reload"
         );
+        let graph = colony2.colony().substrate().graph();
+        let membrane = graph.find_nodes_by_exact_label("membrane")[0];
+        assert_eq!(
+            colony2.colony().annotations(&membrane)[0].text,
+            "bounds the cell"
+        );
 
         let _ = std::fs::remove_file(&tmp);
     }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn interrupted_atomic_save_keeps_the_previous_database() {
+        use phago_agents::digester::Digester;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("knowledge.db");
+        let nodes = |path: &Path| {
+            ColonyBuilder::new()
+                .with_persistence(path)
+                .build()
+                .unwrap()
+                .stats()
+                .graph_nodes
+        };
+
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Biology 101",
+            "Cell membrane proteins transport molecules",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+        ));
+        colony.run(15);
+        save_atomically(&colony, &path).unwrap();
+        let saved = nodes(&path);
+        assert!(saved > 0);
+
+        colony.ingest_document(
+            "Astronomy",
+            "Galaxy nebula stellar fusion",
+            Position::new(0.0, 0.0),
+        );
+        colony.run(15);
+        // The temporary database cannot be written
+        std::fs::create_dir(dir.path().join("knowledge.db.tmp")).unwrap();
+        assert!(save_atomically(&colony, &path).is_err());
+        assert_eq!(nodes(&path), saved);
+
+        std::fs::remove_dir(dir.path().join("knowledge.db.tmp")).unwrap();
+        save_atomically(&colony, &path).unwrap();
+        assert_eq!(nodes(&path), colony.stats().graph_nodes);
+        assert!(nodes(&path) > saved);
+        assert!(!dir.path().join("knowledge.db.tmp").exists());
+    }
 }
@@ -1,31 +1,125 @@
-//! Community detection via label propagation.
+//! Community detection in the knowledge graph.
 //!
-//! Detects communities in the knowledge graph using a simple
-//! label propagation algorithm. Used to evaluate whether the
-//! self-organized Hebbian graph recovers ground-truth topic clusters.
+//! [`detect`] partitions the graph by weighted label propagation or by
+//! Louvain modularity optimization ([`CommunityAlgorithm`]). Both are
+//! deterministic: nodes are visited in label order (shuffled by the label
+//! propagation seed) and communities are numbered largest first, so the
+//! same graph always yields the same IDs.
+//!
+//! On a graph that keeps changing, a [`CommunityTracker`] carries IDs
+//! from one detection to the next by matching each community to the
+//! previous one it shares most members with. A colony can track its
+//! communities on an interval ([`CommunityConfig`]); node snapshots then
+//! carry their community ID.
+//!
+//! [`compute_nmi`] measures whether the self-organized Hebbian graph
+//! recovers ground-truth topic clusters.
 
 use crate::colony::Colony;
+use phago_core::louvain;
 use phago_core::topology::TopologyGraph;
 use phago_core::types::NodeId;
-use serde::Serialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Members listed in [`Community::top_labels`].
+const TOP_LABELS: usize = 5;
+
+/// Iteration cap for label propagation run through [`detect`].
+const PROPAGATION_ITERATIONS: usize = 100;
+
+/// How communities are found.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommunityAlgorithm {
+    /// Weighted label propagation over the strongest edges. `seed` fixes
+    /// the order nodes are visited in.
+    LabelPropagation { seed: u64 },
+    /// Louvain modularity optimization. A `resolution` above 1.0 yields
+    /// more, smaller communities; below 1.0 fewer, larger ones.
+    Louvain { resolution: f64 },
+}
+
+impl Default for CommunityAlgorithm {
+    fn default() -> Self {
+        CommunityAlgorithm::Louvain { resolution: 1.0 }
+    }
+}
+
+impl CommunityAlgorithm {
+    /// This algorithm with `seed` (label propagation) or `resolution`
+    /// (Louvain) replaced, where given.
+    pub fn with_params(self, seed: Option<u64>, resolution: Option<f64>) -> Self {
+        match self {
+            CommunityAlgorithm::LabelPropagation { seed: default } => {
+                CommunityAlgorithm::LabelPropagation {
+                    seed: seed.unwrap_or(default),
+                }
+            }
+            CommunityAlgorithm::Louvain {
+                resolution: default,
+            } => CommunityAlgorithm::Louvain {
+                resolution: resolution.unwrap_or(default),
+            },
+        }
+    }
+}
+
+/// Parses an algorithm name, with its default parameter.
+impl std::str::FromStr for CommunityAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "louvain" => Ok(CommunityAlgorithm::Louvain { resolution: 1.0 }),
+            "label-propagation" | "label_propagation" => {
+                Ok(CommunityAlgorithm::LabelPropagation { seed: 0 })
+            }
+            other => Err(format!(
+                "Unknown community algorithm '{other}'. Use: louvain, label-propagation"
+            )),
+        }
+    }
+}
+
+/// Automatic community tracking settings.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CommunityConfig {
+    /// Detect communities every N ticks; 0 disables it (default: 0). See
+    /// [`Colony::update_communities`].
+    #[serde(default)]
+    pub interval: u64,
+    #[serde(default)]
+    pub algorithm: CommunityAlgorithm,
+}
 
 /// A detected community in the knowledge graph.
 #[derive(Debug, Clone, Serialize)]
 pub struct Community {
     pub id: usize,
+    /// Member labels, sorted.
     pub members: Vec<String>,
     pub size: usize,
+    /// Summed weight of the edges between members.
+    pub internal_weight: f64,
+    /// The most accessed members, most accessed first.
+    pub top_labels: Vec<String>,
+    /// This community's share of the partition's modularity.
+    pub modularity: f64,
 }
 
 /// Result of community detection.
 #[derive(Debug, Clone, Serialize)]
 pub struct CommunityResult {
+    /// Communities, largest first.
     pub communities: Vec<Community>,
     /// Node label → community ID mapping.
     pub assignments: HashMap<String, usize>,
     pub total_nodes: usize,
     pub num_communities: usize,
+    /// Modularity of the partition, the sum of the communities' shares.
+    pub modularity: f64,
+    pub algorithm: CommunityAlgorithm,
 }
 
 /// Run label propagation community detection.
@@ -37,93 +131,144 @@ pub struct CommunityResult {
 /// Uses edge weight thresholding: only edges above the median weight are
 /// considered during neighbor voting. This prunes weak cross-topic edges
 /// and preserves within-topic clusters, improving NMI.
+///
+/// Same as [`detect`] with `LabelPropagation { seed: 0 }`, with a caller
+/// chosen iteration cap.
 pub fn detect_communities(colony: &Colony, max_iterations: usize) -> CommunityResult {
     let graph = colony.substrate().graph();
-    let all_nodes = graph.all_nodes();
+    let nodes = nodes_by_label(graph);
+    let edges = indexed_edges(graph, &nodes);
+    let labels = label_propagation(&edges, nodes.len(), 0, max_iterations);
+    summarize(
+        graph,
+        &nodes,
+        &edges,
+        &labels,
+        CommunityAlgorithm::LabelPropagation { seed: 0 },
+    )
+}
 
-    if all_nodes.is_empty() {
-        return CommunityResult {
-            communities: Vec::new(),
-            assignments: HashMap::new(),
-            total_nodes: 0,
-            num_communities: 0,
-        };
-    }
+/// Partition `graph` into communities with `algorithm`.
+pub fn detect(graph: &dyn TopologyGraph, algorithm: CommunityAlgorithm) -> CommunityResult {
+    let nodes = nodes_by_label(graph);
+    let edges = indexed_edges(graph, &nodes);
+    let labels = match algorithm {
+        CommunityAlgorithm::LabelPropagation { seed } => {
+            label_propagation(&edges, nodes.len(), seed, PROPAGATION_ITERATIONS)
+        }
+        CommunityAlgorithm::Louvain { resolution } => {
+            let result = louvain::louvain_communities_with_resolution(&nodes, &edges, resolution);
+            let index: HashMap<NodeId, usize> =
+                nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+            let mut labels = vec![0; nodes.len()];
+            for (community, members) in result.communities.iter().enumerate() {
+                for member in members {
+                    labels[index[member]] = community;
+                }
+            }
+            labels
+        }
+    };
+    summarize(graph, &nodes, &edges, &labels, algorithm)
+}
+
+/// Every node, in label order.
+fn nodes_by_label(graph: &dyn TopologyGraph) -> Vec<NodeId> {
+    let mut nodes: Vec<(&str, NodeId)> = graph
+        .all_nodes()
+        .into_iter()
+        .filter_map(|id| graph.get_node(&id).map(|node| (node.label.as_str(), id)))
+        .collect();
+    nodes.sort();
+    nodes.into_iter().map(|(_, id)| id).collect()
+}
 
+/// Edges between `nodes` as `(lower, higher, weight)` index triples, each
+/// edge once, sorted.
+fn indexed_edges(graph: &dyn TopologyGraph, nodes: &[NodeId]) -> Vec<(usize, usize, f64)> {
+    let index: HashMap<NodeId, usize> = nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut edges: Vec<(usize, usize, f64)> = graph
+        .all_edges()
+        .into_iter()
+        .filter_map(|(from, to, edge)| {
+            let (a, b) = (*index.get(&from)?, *index.get(&to)?);
+            Some((a.min(b), a.max(b), edge.weight))
+        })
+        .collect();
+    edges.sort_by_key(|&(a, b, _)| (a, b));
+    edges
+}
+
+/// A community label per node.
+fn label_propagation(
+    edges: &[(usize, usize, f64)],
+    node_count: usize,
+    seed: u64,
+    max_iterations: usize,
+) -> Vec<usize> {
     // Compute edge weight threshold adaptively based on graph density.
     // Dense graphs need aggressive pruning (90th percentile) to reveal
-    // community structure; sparse graphs use median.
-    let all_edges = graph.all_edges();
-    let weight_threshold = if all_edges.is_empty() {
+    // community structure; sparse graphs use the 75th percentile.
+    let weight_threshold = if edges.is_empty() {
         0.0
     } else {
-        let mut weights: Vec<f64> = all_edges.iter().map(|(_, _, e)| e.weight).collect();
+        let mut weights: Vec<f64> = edges.iter().map(|&(_, _, w)| w).collect();
         weights.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-        let n = all_nodes.len() as f64;
+        let n = node_count as f64;
         let density = if n > 1.0 {
-            (2.0 * all_edges.len() as f64) / (n * (n - 1.0))
+            (2.0 * edges.len() as f64) / (n * (n - 1.0))
         } else {
             0.0
         };
-        // For dense graphs (density > 0.05), use 90th percentile to aggressively
-        // prune weak edges. Otherwise use 75th percentile.
         let percentile = if density > 0.05 { 90 } else { 75 };
         let idx = (weights.len() * percentile / 100).min(weights.len() - 1);
         weights[idx]
     };
 
-    // Initialize: each node gets its own label
-    let mut labels: HashMap<NodeId, usize> = HashMap::new();
-    let node_list: Vec<NodeId> = all_nodes.clone();
-    for (i, nid) in node_list.iter().enumerate() {
-        labels.insert(*nid, i);
+    // Only strong edges vote; weak ones are mostly cross-topic
+    let mut adjacency: Vec<Vec<(usize, f64)>> = vec![Vec::new(); node_count];
+    for &(a, b, weight) in edges {
+        if a != b && weight >= weight_threshold {
+            adjacency[a].push((b, weight));
+            adjacency[b].push((a, weight));
+        }
     }
 
+    // Initialize: each node gets its own label
+    let mut labels: Vec<usize> = (0..node_count).collect();
+
     // Iterate with shuffled node order per iteration (asynchronous LP)
     for iter in 0..max_iterations {
         let mut changed = false;
 
-        // Shuffle node processing order using Fisher-Yates with deterministic seed
-        let mut order: Vec<usize> = (0..node_list.len()).collect();
-        let mut seed: u64 = (iter as u64)
+        // Shuffle node processing order using Fisher-Yates, seeded
+        let mut order: Vec<usize> = (0..node_count).collect();
+        let mut state: u64 = (iter as u64 ^ seed)
             .wrapping_mul(6364136223846793005)
             .wrapping_add(1442695040888963407);
         for i in (1..order.len()).rev() {
-            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
-            let j = (seed >> 33) as usize % (i + 1);
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let j = (state >> 33) as usize % (i + 1);
             order.swap(i, j);
         }
 
-        for &idx in &order {
-            let nid = &node_list[idx];
-            let neighbors = graph.neighbors(nid);
-            if neighbors.is_empty() {
-                continue;
+        for &node in &order {
+            let mut votes: BTreeMap<usize, f64> = BTreeMap::new();
+            for &(neighbor, weight) in &adjacency[node] {
+                *votes.entry(labels[neighbor]).or_insert(0.0) += weight;
             }
 
-            // Weight-vote for neighbor labels, only considering edges above the median weight
-            let mut label_weights: HashMap<usize, f64> = HashMap::new();
-            for (neighbor_id, edge) in &neighbors {
-                if edge.weight < weight_threshold {
-                    continue; // Skip weak cross-topic edges
-                }
-                if let Some(&label) = labels.get(neighbor_id) {
-                    *label_weights.entry(label).or_insert(0.0) += edge.weight;
+            // Adopt the highest-weighted label, the lowest on a tie
+            let mut best: Option<(usize, f64)> = None;
+            for (label, weight) in votes {
+                match best {
+                    Some((_, best_weight)) if weight <= best_weight => {}
+                    _ => best = Some((label, weight)),
                 }
             }
-
-            if label_weights.is_empty() {
-                continue; // No strong neighbors, keep current label
-            }
-
-            // Adopt the highest-weighted label
-            if let Some((&best_label, _)) = label_weights
-                .iter()
-                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
-            {
-                let current = labels.get(nid).copied().unwrap_or(0);
-                if best_label != current {
-                    labels.insert(*nid, best_label);
+            if let Some((label, _)) = best {
+                if label != labels[node] {
+                    labels[node] = label;
                     changed = true;
                 }
             }
@@ -134,54 +279,196 @@ pub fn detect_communities(colony: &Colony, max_iterations: usize) -> CommunityRe
         }
     }
 
-    // Build communities from final labels
-    let mut community_members: HashMap<usize, Vec<String>> = HashMap::new();
-    let mut assignments: HashMap<String, usize> = HashMap::new();
-
-    for nid in &node_list {
-        if let (Some(&label), Some(node)) = (labels.get(nid), graph.get_node(nid)) {
-            community_members
-                .entry(label)
-                .or_default()
-                .push(node.label.clone());
-            assignments.insert(node.label.clone(), label);
+    labels
+}
+
+/// Build communities from a label per node, numbered largest first.
+fn summarize(
+    graph: &dyn TopologyGraph,
+    nodes: &[NodeId],
+    edges: &[(usize, usize, f64)],
+    labels: &[usize],
+    algorithm: CommunityAlgorithm,
+) -> CommunityResult {
+    let resolution = match algorithm {
+        CommunityAlgorithm::Louvain { resolution } => resolution,
+        CommunityAlgorithm::LabelPropagation { .. } => 1.0,
+    };
+    let total_weight: f64 = edges.iter().map(|&(_, _, w)| w).sum();
+
+    let mut internal: HashMap<usize, f64> = HashMap::new();
+    let mut degree: HashMap<usize, f64> = HashMap::new();
+    for &(a, b, weight) in edges {
+        *degree.entry(labels[a]).or_insert(0.0) += weight;
+        *degree.entry(labels[b]).or_insert(0.0) += weight;
+        if labels[a] == labels[b] {
+            *internal.entry(labels[a]).or_insert(0.0) += weight;
         }
     }
 
-    // Renumber communities 0, 1, 2, ...
-    let mut renumber: HashMap<usize, usize> = HashMap::new();
-    let mut next_id = 0;
-    for old_id in community_members.keys() {
-        renumber.entry(*old_id).or_insert_with(|| {
-            let id = next_id;
-            next_id += 1;
-            id
-        });
+    // Members stay in label order, as `nodes` is
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (i, &label) in labels.iter().enumerate() {
+        groups.entry(label).or_default().push(i);
     }
 
-    let mut communities: Vec<Community> = community_members
+    let mut communities: Vec<Community> = groups
         .into_iter()
-        .map(|(old_id, members)| {
-            let new_id = renumber[&old_id];
+        .map(|(label, members)| {
+            let mut ranked: Vec<(&str, u64)> = members
+                .iter()
+                .filter_map(|&i| graph.get_node(&nodes[i]))
+                .map(|node| (node.label.as_str(), node.access_count))
+                .collect();
+            let members: Vec<String> = ranked.iter().map(|(l, _)| l.to_string()).collect();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+            let internal_weight = internal.get(&label).copied().unwrap_or(0.0);
+            let degree = degree.get(&label).copied().unwrap_or(0.0);
+            let modularity = if total_weight > 0.0 {
+                internal_weight / total_weight
+                    - resolution * (degree / (2.0 * total_weight)).powi(2)
+            } else {
+                0.0
+            };
             Community {
-                id: new_id,
+                id: 0,
                 size: members.len(),
                 members,
+                internal_weight,
+                top_labels: ranked
+                    .into_iter()
+                    .take(TOP_LABELS)
+                    .map(|(l, _)| l.to_string())
+                    .collect(),
+                modularity,
             }
         })
         .collect();
-    communities.sort_by(|a, b| b.size.cmp(&a.size));
+    communities.sort_by(|a, b| b.size.cmp(&a.size).then(a.members.cmp(&b.members)));
 
-    // Update assignments with new IDs
-    for val in assignments.values_mut() {
-        *val = renumber[val];
+    let mut assignments = HashMap::new();
+    for (id, community) in communities.iter_mut().enumerate() {
+        community.id = id;
+        for member in &community.members {
+            assignments.insert(member.clone(), id);
+        }
     }
 
     CommunityResult {
         num_communities: communities.len(),
-        total_nodes: node_list.len(),
+        total_nodes: nodes.len(),
+        modularity: communities.iter().map(|c| c.modularity).sum(),
         communities,
         assignments,
+        algorithm,
+    }
+}
+
+/// Keeps community IDs stable across successive detections.
+///
+/// Each new community takes the ID of the previous community it overlaps
+/// most (members shared over members in either), if that overlap is at
+/// least the tracker's minimum. Pairs are matched best overlap first and
+/// every previous ID goes to one community at most; communities left
+/// unmatched get IDs never used before.
+#[derive(Debug, Clone)]
+pub struct CommunityTracker {
+    min_overlap: f64,
+    /// Communities of the last tracked result, by ID.
+    previous: Vec<(usize, HashSet<String>)>,
+    assignments: HashMap<String, usize>,
+    next_id: usize,
+}
+
+impl Default for CommunityTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommunityTracker {
+    /// Match communities overlapping by at least 30%.
+    pub fn new() -> Self {
+        Self::with_min_overlap(0.3)
+    }
+
+    pub fn with_min_overlap(min_overlap: f64) -> Self {
+        Self {
+            min_overlap,
+            previous: Vec::new(),
+            assignments: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Detect communities in `graph` and [`track`](Self::track) them.
+    pub fn detect(
+        &mut self,
+        graph: &dyn TopologyGraph,
+        algorithm: CommunityAlgorithm,
+    ) -> CommunityResult {
+        self.track(detect(graph, algorithm))
+    }
+
+    /// Give `result`'s communities the IDs of the communities they
+    /// continue, and remember them for the next call.
+    pub fn track(&mut self, mut result: CommunityResult) -> CommunityResult {
+        let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+        for (index, community) in result.communities.iter().enumerate() {
+            for (id, members) in &self.previous {
+                let shared = community
+                    .members
+                    .iter()
+                    .filter(|m| members.contains(*m))
+                    .count();
+                if shared == 0 {
+                    continue;
+                }
+                let overlap = shared as f64 / (community.size + members.len() - shared) as f64;
+                if overlap >= self.min_overlap {
+                    candidates.push((overlap, index, *id));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.1.cmp(&b.1))
+                .then(a.2.cmp(&b.2))
+        });
+
+        let mut ids: Vec<Option<usize>> = vec![None; result.communities.len()];
+        let mut taken = HashSet::new();
+        for (_, index, id) in candidates {
+            if ids[index].is_none() && taken.insert(id) {
+                ids[index] = Some(id);
+            }
+        }
+
+        result.assignments.clear();
+        for (community, id) in result.communities.iter_mut().zip(ids) {
+            community.id = id.unwrap_or_else(|| {
+                self.next_id += 1;
+                self.next_id - 1
+            });
+            for member in &community.members {
+                result.assignments.insert(member.clone(), community.id);
+            }
+        }
+
+        self.previous = result
+            .communities
+            .iter()
+            .map(|c| (c.id, c.members.iter().cloned().collect()))
+            .collect();
+        self.assignments = result.assignments.clone();
+        result
+    }
+
+    /// Community of the node labelled `label` in the last tracked result.
+    pub fn community_of(&self, label: &str) -> Option<usize> {
+        self.assignments.get(label).copied()
     }
 }
 
@@ -288,4 +575,133 @@ mod tests {
         let nmi = compute_nmi(&detected, &gt);
         assert!(nmi > 0.99, "NMI should be ~1.0 for perfect match: {}", nmi);
     }
+
+    use crate::topology_impl::PetTopologyGraph;
+    use phago_core::types::{EdgeData, NodeData, NodeType, Position};
+
+    const ALGORITHMS: [CommunityAlgorithm; 2] = [
+        CommunityAlgorithm::LabelPropagation { seed: 7 },
+        CommunityAlgorithm::Louvain { resolution: 1.0 },
+    ];
+
+    /// Two fully connected clusters joined by one weak edge.
+    fn two_clusters(a: &[String], b: &[String]) -> PetTopologyGraph {
+        let mut graph = PetTopologyGraph::new();
+        let mut clusters = Vec::new();
+        for labels in [a, b] {
+            let ids: Vec<NodeId> = labels
+                .iter()
+                .map(|label| {
+                    graph.add_node(NodeData {
+                        id: NodeId::new(),
+                        label: label.clone(),
+                        node_type: NodeType::Concept,
+                        position: Position::new(0.0, 0.0),
+                        access_count: label.len() as u64,
+                        created_tick: 0,
+                        last_accessed_tick: 0,
+                        tags: Default::default(),
+                    })
+                })
+                .collect();
+            for (i, &from) in ids.iter().enumerate() {
+                for &to in &ids[i + 1..] {
+                    connect(&mut graph, from, to, 1.0);
+                }
+            }
+            clusters.push(ids);
+        }
+        connect(&mut graph, clusters[0][0], clusters[1][0], 0.1);
+        graph
+    }
+
+    fn connect(graph: &mut PetTopologyGraph, a: NodeId, b: NodeId, weight: f64) {
+        graph
+            .set_edge(
+                a,
+                b,
+                EdgeData {
+                    weight,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    fn labels(prefix: &str, range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|i| format!("{prefix}{i}")).collect()
+    }
+
+    #[test]
+    fn two_clusters_give_two_communities() {
+        let a = labels("alpha", 0..10);
+        let b = labels("beta", 0..10);
+        let graph = two_clusters(&a, &b);
+        for algorithm in ALGORITHMS {
+            let result = detect(&graph, algorithm);
+            assert_eq!(result.num_communities, 2, "{algorithm:?}");
+            assert_eq!(result.total_nodes, 20);
+            let alpha = result.assignments["alpha0"];
+            assert!(a.iter().all(|l| result.assignments[l] == alpha));
+            assert!(b.iter().all(|l| result.assignments[l] != alpha));
+
+            for community in &result.communities {
+                assert_eq!(community.size, 10);
+                assert_eq!(community.internal_weight, 45.0);
+                assert_eq!(community.top_labels.len(), TOP_LABELS);
+                assert!(community.modularity > 0.0);
+            }
+            assert!(
+                (result.modularity - 0.5).abs() < 0.01,
+                "{}",
+                result.modularity
+            );
+
+            // Same graph, same numbering
+            let again = detect(&graph, algorithm);
+            assert_eq!(again.assignments, result.assignments);
+        }
+    }
+
+    #[test]
+    fn top_labels_rank_by_access_count() {
+        let a = vec!["x".to_string(), "zzz".to_string(), "yy".to_string()];
+        let b = labels("beta", 0..3);
+        let graph = two_clusters(&a, &b);
+        let result = detect(&graph, CommunityAlgorithm::default());
+        let community = &result.communities[result.assignments["x"]];
+        assert_eq!(community.members, vec!["x", "yy", "zzz"]);
+        assert_eq!(community.top_labels, vec!["zzz", "yy", "x"]);
+    }
+
+    #[test]
+    fn tracker_keeps_ids_when_a_tenth_of_members_change() {
+        for algorithm in ALGORITHMS {
+            let mut tracker = CommunityTracker::new();
+            let first = tracker.detect(
+                &two_clusters(&labels("a", 0..20), &labels("b", 0..18)),
+                algorithm,
+            );
+            let (a_id, b_id) = (first.assignments["a5"], first.assignments["b5"]);
+            assert_eq!(tracker.community_of("a5"), Some(a_id));
+
+            // `a` loses two members, `b` gains two and becomes the larger one
+            let graph = two_clusters(&labels("a", 2..20), &labels("b", 0..20));
+            let fresh = detect(&graph, algorithm);
+            assert_ne!(fresh.assignments["a5"], a_id, "{algorithm:?}");
+
+            let second = tracker.detect(&graph, algorithm);
+            assert_eq!(second.assignments["a5"], a_id, "{algorithm:?}");
+            assert_eq!(second.assignments["b19"], b_id);
+            assert_eq!(tracker.community_of("a0"), None);
+
+            // A community with no predecessor gets an unused ID
+            let graph = two_clusters(&labels("a", 2..20), &labels("c", 0..20));
+            let third = tracker.detect(&graph, algorithm);
+            assert_eq!(third.assignments["a5"], a_id);
+            assert!(third.assignments["c0"] > a_id.max(b_id));
+        }
+    }
 }
@@ -0,0 +1,376 @@
+//! Label compaction — merging concept nodes whose labels are variants of
+//! one another.
+//!
+//! Concepts are identified by their exact lowercase label, so "membrane",
+//! "membranes" and "cell-membrane" / "cell membrane" end up as separate
+//! nodes that split reinforcement between them and dilute query scores.
+//! [`plan`] groups such variants into clusters under a chosen
+//! [`CompactionStrategy`]; [`Colony::apply_compaction`] merges every
+//! cluster into its canonical node and records the other labels as
+//! aliases, so later presentations and exact lookups of a variant resolve
+//! to the canonical node.
+//!
+//! The canonical node of a cluster is the most accessed one; ties go to
+//! the shorter label, then the alphabetically first. Only concept nodes
+//! are considered.
+//!
+//! [`Colony::apply_compaction`]: crate::colony::Colony::apply_compaction
+
+use crate::embedding_store::EmbeddingTable;
+use phago_core::semantic::cosine_similarity;
+use phago_core::topology::TopologyGraph;
+use phago_core::types::{NodeId, NodeType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How variant labels are recognised.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionStrategy {
+    /// Labels that are equal once plurals are folded to the singular and
+    /// hyphens, underscores and spaces are treated alike.
+    #[default]
+    Stemming,
+    /// Labels of at least `min_len` characters within `max_distance`
+    /// single-character edits of each other.
+    EditDistance { max_distance: usize, min_len: usize },
+    /// Nodes whose embeddings have a cosine similarity of at least
+    /// `min_similarity`. Nodes without embeddings are left alone.
+    Embedding { min_similarity: f64 },
+}
+
+/// Automatic compaction settings.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct CompactionConfig {
+    /// Compact every N ticks; 0 disables it (default: 0).
+    #[serde(default)]
+    pub interval: u64,
+    #[serde(default)]
+    pub strategy: CompactionStrategy,
+}
+
+/// A node folded into a cluster's canonical node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergedNode {
+    pub id: NodeId,
+    pub label: String,
+    pub access_count: u64,
+}
+
+/// Nodes to merge into one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergeCluster {
+    pub canonical: NodeId,
+    pub canonical_label: String,
+    /// The other nodes of the cluster, by label.
+    pub merged: Vec<MergedNode>,
+}
+
+/// Clusters of variant labels, ordered by canonical label.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CompactionPlan {
+    pub clusters: Vec<MergeCluster>,
+}
+
+impl CompactionPlan {
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    /// Nodes that would disappear into a canonical node.
+    pub fn nodes_merged(&self) -> usize {
+        self.clusters.iter().map(|c| c.merged.len()).sum()
+    }
+}
+
+struct Candidate {
+    id: NodeId,
+    label: String,
+    access_count: u64,
+    embedding: Option<Vec<f32>>,
+}
+
+/// Group the graph's concept nodes into clusters of variant labels.
+/// `embeddings` are only read under [`CompactionStrategy::Embedding`].
+pub fn plan(
+    graph: &dyn TopologyGraph,
+    embeddings: &EmbeddingTable,
+    strategy: &CompactionStrategy,
+) -> CompactionPlan {
+    let by_embedding = matches!(strategy, CompactionStrategy::Embedding { .. });
+    let candidates: Vec<Candidate> = graph
+        .all_nodes()
+        .into_iter()
+        .filter_map(|id| graph.get_node(&id))
+        .filter(|n| n.node_type == NodeType::Concept)
+        .map(|n| Candidate {
+            id: n.id,
+            label: n.label.to_lowercase(),
+            access_count: n.access_count,
+            embedding: by_embedding.then(|| embeddings.get(&n.id)).flatten(),
+        })
+        .collect();
+
+    let mut sets = DisjointSets::new(candidates.len());
+    match *strategy {
+        CompactionStrategy::Stemming => {
+            let mut by_stem: HashMap<String, usize> = HashMap::new();
+            for (i, c) in candidates.iter().enumerate() {
+                for key in stem_keys(&c.label) {
+                    let first = *by_stem.entry(key).or_insert(i);
+                    sets.union(first, i);
+                }
+            }
+        }
+        CompactionStrategy::EditDistance {
+            max_distance,
+            min_len,
+        } => {
+            // Compare only labels whose lengths differ by at most
+            // `max_distance`, walking them in length order
+            let mut order: Vec<(usize, usize)> = candidates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (c.label.chars().count(), i))
+                .filter(|&(len, _)| len >= min_len)
+                .collect();
+            order.sort_unstable();
+            for (k, &(len_a, a)) in order.iter().enumerate() {
+                for &(len_b, b) in &order[k + 1..] {
+                    if len_b - len_a > max_distance {
+                        break;
+                    }
+                    if edit_distance(&candidates[a].label, &candidates[b].label) <= max_distance {
+                        sets.union(a, b);
+                    }
+                }
+            }
+        }
+        CompactionStrategy::Embedding { min_similarity } => {
+            let embedded: Vec<(usize, &[f32])> = candidates
+                .iter()
+                .enumerate()
+                .filter_map(|(i, c)| c.embedding.as_deref().map(|e| (i, e)))
+                .collect();
+            for (k, &(a, ea)) in embedded.iter().enumerate() {
+                for &(b, eb) in &embedded[k + 1..] {
+                    if cosine_similarity(ea, eb).is_some_and(|s| s >= min_similarity) {
+                        sets.union(a, b);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..candidates.len() {
+        groups.entry(sets.find(i)).or_default().push(i);
+    }
+
+    let mut clusters: Vec<MergeCluster> = groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|mut members| {
+            members.sort_by(|&a, &b| {
+                let (a, b) = (&candidates[a], &candidates[b]);
+                b.access_count
+                    .cmp(&a.access_count)
+                    .then(a.label.len().cmp(&b.label.len()))
+                    .then(a.label.cmp(&b.label))
+            });
+            let canonical = &candidates[members[0]];
+            MergeCluster {
+                canonical: canonical.id,
+                canonical_label: canonical.label.clone(),
+                merged: members[1..]
+                    .iter()
+                    .map(|&i| MergedNode {
+                        id: candidates[i].id,
+                        label: candidates[i].label.clone(),
+                        access_count: candidates[i].access_count,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| a.canonical_label.cmp(&b.canonical_label));
+    CompactionPlan { clusters }
+}
+
+/// Grouping keys for [`CompactionStrategy::Stemming`]: the words of the
+/// label separated by single spaces, each folded to its singular. A last
+/// word ending in "ies" can be the plural of "-y" ("bodies") or of "-ie"
+/// ("movies"), so it yields a key for each.
+fn stem_keys(label: &str) -> Vec<String> {
+    let words: Vec<&str> = label
+        .split(|c: char| c == '-' || c == '_' || c.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let folded: Vec<&str> = words.iter().map(|w| singular(w)).collect();
+    let mut keys = vec![folded.join(" ")];
+    if let Some(stem) = words
+        .last()
+        .and_then(|w| w.strip_suffix("ies"))
+        .filter(|stem| stem.len() > 1)
+    {
+        let mut folded = folded;
+        let last = format!("{stem}y");
+        *folded.last_mut().unwrap() = &last;
+        keys.push(folded.join(" "));
+    }
+    keys
+}
+
+/// Rule-based plural folding: "boxes" → "box", "membranes" → "membrane".
+/// Words ending in "ss", "us" or "is" are left as they are.
+fn singular(word: &str) -> &str {
+    // Keep short words ("gas", "bus") intact
+    if word.len() <= 3 {
+        return word;
+    }
+    if let Some(stem) = word.strip_suffix("es") {
+        if ["x", "z", "ch", "sh", "ss"]
+            .iter()
+            .any(|s| stem.ends_with(s))
+        {
+            return stem;
+        }
+    }
+    if ["ss", "us", "is"].iter().any(|s| word.ends_with(s)) {
+        return word;
+    }
+    word.strip_suffix('s').unwrap_or(word)
+}
+
+/// Levenshtein distance over characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Union-find over candidate indices.
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[b.max(a)] = a.min(b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology_impl::PetTopologyGraph;
+    use phago_core::types::{NodeData, Position};
+
+    fn add(graph: &mut PetTopologyGraph, label: &str, access_count: u64) -> NodeId {
+        graph.add_node(NodeData {
+            id: NodeId::new(),
+            label: label.into(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        })
+    }
+
+    #[test]
+    fn plurals_fold_to_the_singular() {
+        for (plural, singular_form) in [
+            ("membranes", "membrane"),
+            ("boxes", "box"),
+            ("processes", "process"),
+            ("branches", "branch"),
+            ("process", "process"),
+            ("virus", "virus"),
+            ("analysis", "analysis"),
+            ("gas", "gas"),
+        ] {
+            assert_eq!(singular(plural), singular_form, "{plural}");
+        }
+        assert_eq!(stem_keys("cell-membranes"), stem_keys("cell membrane"));
+        assert_eq!(stem_keys("bodies"), vec!["bodie", "body"]);
+        assert_eq!(stem_keys("movies")[0], stem_keys("movie")[0]);
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("membrane", "membrane"), 0);
+        assert_eq!(edit_distance("membrane", "membrain"), 2);
+        assert_eq!(edit_distance("colour", "color"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn stemming_clusters_variants_under_the_most_accessed_label() {
+        let mut graph = PetTopologyGraph::new();
+        let membrane = add(&mut graph, "membrane", 2);
+        let membranes = add(&mut graph, "membranes", 5);
+        add(&mut graph, "cell-membrane", 1);
+        add(&mut graph, "cell membranes", 1);
+        add(&mut graph, "protein", 3);
+
+        let plan = plan(
+            &graph,
+            &EmbeddingTable::default(),
+            &CompactionStrategy::Stemming,
+        );
+        assert_eq!(plan.clusters.len(), 2);
+        assert_eq!(plan.nodes_merged(), 2);
+        assert_eq!(plan.clusters[0].canonical_label, "cell-membrane");
+        assert_eq!(plan.clusters[1].canonical, membranes);
+        assert_eq!(plan.clusters[1].merged[0].id, membrane);
+    }
+
+    #[test]
+    fn edit_distance_respects_the_minimum_length() {
+        let mut graph = PetTopologyGraph::new();
+        add(&mut graph, "colour", 1);
+        add(&mut graph, "color", 1);
+        add(&mut graph, "cat", 1);
+        add(&mut graph, "car", 1);
+
+        let plan = plan(
+            &graph,
+            &EmbeddingTable::default(),
+            &CompactionStrategy::EditDistance {
+                max_distance: 1,
+                min_len: 4,
+            },
+        );
+        assert_eq!(plan.clusters.len(), 1);
+        // Equal access counts: the shorter label wins
+        assert_eq!(plan.clusters[0].canonical_label, "color");
+    }
+}
@@ -0,0 +1,296 @@
+//! Incremental lookups for the colony's dissolution phase.
+//!
+//! Each dissolution pass modulates every agent's boundary by how strongly
+//! its vocabulary is reinforced in the graph, then lets permeable agents
+//! absorb the concept labels around them. Done directly, that is a label
+//! lookup per vocabulary term and a scan of every node, per agent, per pass.
+//!
+//! [`ConceptIndex`] keeps concept nodes in a spatial grid and remembers
+//! each agent's term → node resolutions. The colony reports the nodes it
+//! adds and removes; a resolution is dropped only when a node with that
+//! label comes or goes. Anything that may have changed the graph behind
+//! the colony's back (see [`Colony::substrate_mut`]) marks the index stale,
+//! and it is rebuilt from the graph on the next pass.
+//!
+//! Like the graph's own label index, this assumes a node's label, type and
+//! position do not change after it is added.
+//!
+//! [`Colony::substrate_mut`]: crate::colony::Colony::substrate_mut
+
+use phago_core::topology::TopologyGraph;
+use phago_core::types::*;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+/// Distance within which an agent absorbs concept labels.
+const ABSORPTION_RADIUS: f64 = 15.0;
+
+type Cell = (i64, i64);
+
+pub(crate) struct ConceptIndex {
+    /// Concept nodes by grid cell; cells are [`ABSORPTION_RADIUS`] wide.
+    cells: HashMap<Cell, Vec<(NodeId, Position)>>,
+    /// Agent → lowercase vocabulary term → nodes carrying that label.
+    resolved: HashMap<AgentId, HashMap<String, Vec<NodeId>>>,
+    /// Lowercase labels of nodes added or removed since the last pass.
+    changed: HashSet<String>,
+    /// The graph may have changed without the index being told.
+    stale: bool,
+}
+
+impl ConceptIndex {
+    /// An index that builds itself from the graph on the first pass.
+    pub(crate) fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            resolved: HashMap::new(),
+            changed: HashSet::new(),
+            stale: true,
+        }
+    }
+
+    /// Rebuild from the graph on the next pass.
+    pub(crate) fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Record a node the colony added to the graph.
+    pub(crate) fn node_added(&mut self, node: &NodeData) {
+        if self.stale {
+            return;
+        }
+        if node.node_type == NodeType::Concept {
+            self.cells
+                .entry(cell_of(&node.position))
+                .or_default()
+                .push((node.id, node.position));
+        }
+        self.changed.insert(node.label.to_lowercase());
+    }
+
+    /// Record a node the colony removed from the graph.
+    pub(crate) fn node_removed(&mut self, node: &NodeData) {
+        if self.stale {
+            return;
+        }
+        if node.node_type == NodeType::Concept {
+            let cell = cell_of(&node.position);
+            if let Some(nodes) = self.cells.get_mut(&cell) {
+                nodes.retain(|(id, _)| *id != node.id);
+                if nodes.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+        self.changed.insert(node.label.to_lowercase());
+    }
+
+    /// Forget a dead agent's resolutions.
+    pub(crate) fn forget_agent(&mut self, agent: &AgentId) {
+        self.resolved.remove(agent);
+    }
+
+    /// Bring the index up to date before a dissolution pass: rebuild it if
+    /// stale, otherwise drop the resolutions of labels that changed.
+    pub(crate) fn sync(&mut self, graph: &dyn TopologyGraph) {
+        if self.stale {
+            self.cells.clear();
+            for id in graph.all_nodes() {
+                if let Some(node) = graph.get_node(&id) {
+                    if node.node_type == NodeType::Concept {
+                        self.cells
+                            .entry(cell_of(&node.position))
+                            .or_default()
+                            .push((id, node.position));
+                    }
+                }
+            }
+            self.resolved.clear();
+            self.stale = false;
+        } else if !self.changed.is_empty() {
+            for terms in self.resolved.values_mut() {
+                terms.retain(|term, _| !self.changed.contains(term));
+            }
+        }
+        self.changed.clear();
+    }
+
+    /// Nodes labelled `term` (case-insensitive), looked up once per agent
+    /// until a node with that label is added or removed.
+    pub(crate) fn resolve(
+        &mut self,
+        agent: AgentId,
+        term: &str,
+        graph: &dyn TopologyGraph,
+    ) -> &[NodeId] {
+        let key = lowercase(term);
+        let terms = self.resolved.entry(agent).or_default();
+        if !terms.contains_key(key.as_ref()) {
+            terms.insert(key.to_string(), graph.find_nodes_by_exact_label(&key));
+        }
+        &terms[key.as_ref()]
+    }
+
+    /// Labels of concept nodes within [`ABSORPTION_RADIUS`] of `position`,
+    /// in no particular order.
+    pub(crate) fn nearby_labels(
+        &self,
+        position: &Position,
+        graph: &dyn TopologyGraph,
+    ) -> Vec<String> {
+        let (cx, cy) = cell_of(position);
+        let mut labels = Vec::new();
+        for x in cx - 1..=cx + 1 {
+            for y in cy - 1..=cy + 1 {
+                let Some(nodes) = self.cells.get(&(x, y)) else {
+                    continue;
+                };
+                for (id, node_position) in nodes {
+                    if node_position.distance_to(position) > ABSORPTION_RADIUS {
+                        continue;
+                    }
+                    if let Some(node) = graph.get_node(id) {
+                        labels.push(node.label.clone());
+                    }
+                }
+            }
+        }
+        labels
+    }
+}
+
+fn cell_of(position: &Position) -> Cell {
+    (
+        (position.x / ABSORPTION_RADIUS).floor() as i64,
+        (position.y / ABSORPTION_RADIUS).floor() as i64,
+    )
+}
+
+/// Vocabulary terms are almost always lowercase already.
+fn lowercase(term: &str) -> Cow<'_, str> {
+    if term.chars().any(char::is_uppercase) {
+        Cow::Owned(term.to_lowercase())
+    } else {
+        Cow::Borrowed(term)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology_impl::PetTopologyGraph;
+
+    fn concept(label: &str, x: f64, y: f64) -> NodeData {
+        NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(x, y),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        }
+    }
+
+    /// What the dissolution phase computed before the index existed.
+    fn scan_nearby(graph: &dyn TopologyGraph, position: &Position) -> Vec<String> {
+        let mut labels: Vec<String> = graph
+            .all_nodes()
+            .iter()
+            .filter_map(|id| graph.get_node(id))
+            .filter(|n| {
+                n.node_type == NodeType::Concept
+                    && n.position.distance_to(position) <= ABSORPTION_RADIUS
+            })
+            .map(|n| n.label.clone())
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    #[test]
+    fn nearby_labels_match_a_full_scan() {
+        let mut graph = PetTopologyGraph::new();
+        for i in 0..400 {
+            let (x, y) = ((i % 20) as f64 * 4.3 - 40.0, (i / 20) as f64 * 3.7 - 35.0);
+            graph.add_node(concept(&format!("c{i}"), x, y));
+        }
+        let mut insight = concept("insight", 0.0, 0.0);
+        insight.node_type = NodeType::Insight;
+        graph.add_node(insight);
+
+        let mut index = ConceptIndex::new();
+        index.sync(&graph);
+        for (x, y) in [
+            (0.0, 0.0),
+            (-40.0, -35.0),
+            (15.0, 15.0),
+            (-7.5, 29.9),
+            (100.0, 0.0),
+        ] {
+            let position = Position::new(x, y);
+            let mut labels = index.nearby_labels(&position, &graph);
+            labels.sort();
+            assert_eq!(labels, scan_nearby(&graph, &position), "at ({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn resolutions_are_dropped_when_their_label_changes() {
+        let mut graph = PetTopologyGraph::new();
+        let agent = AgentId::new();
+        let cell = graph.add_node(concept("cell", 0.0, 0.0));
+        graph.add_node(concept("membrane", 1.0, 0.0));
+
+        let membrane = graph.find_nodes_by_exact_label("membrane").to_vec();
+
+        let mut index = ConceptIndex::new();
+        index.sync(&graph);
+        assert_eq!(index.resolve(agent, "Cell", &graph), &[cell]);
+        assert_eq!(
+            index.resolve(agent, "membrane", &graph),
+            membrane.as_slice()
+        );
+
+        // A second "cell" node drops that resolution; "membrane" stays cached
+        let second = concept("CELL", 30.0, 0.0);
+        let second_id = second.id;
+        index.node_added(&second);
+        graph.add_node(second);
+        index.sync(&graph);
+        assert!(!index.resolved[&agent].contains_key("cell"));
+        assert!(index.resolved[&agent].contains_key("membrane"));
+        assert_eq!(index.resolve(agent, "cell", &graph), &[cell, second_id]);
+
+        let removed = graph.remove_node(&cell).unwrap();
+        index.node_removed(&removed);
+        index.sync(&graph);
+        assert_eq!(index.resolve(agent, "cell", &graph), &[second_id]);
+        assert!(index
+            .nearby_labels(&Position::new(0.0, 0.0), &graph)
+            .contains(&"membrane".to_string()));
+        assert!(!index
+            .nearby_labels(&Position::new(0.0, 0.0), &graph)
+            .contains(&"cell".to_string()));
+    }
+
+    #[test]
+    fn stale_index_rebuilds_from_the_graph() {
+        let mut graph = PetTopologyGraph::new();
+        let agent = AgentId::new();
+        let mut index = ConceptIndex::new();
+        index.sync(&graph);
+        assert!(index.resolve(agent, "cell", &graph).is_empty());
+
+        // Added without telling the index
+        let cell = graph.add_node(concept("cell", 0.0, 0.0));
+        index.invalidate();
+        index.sync(&graph);
+        assert_eq!(index.resolve(agent, "cell", &graph), &[cell]);
+        assert_eq!(
+            index.nearby_labels(&Position::new(5.0, 5.0), &graph),
+            vec!["cell".to_string()]
+        );
+    }
+}
@@ -3,11 +3,42 @@
 //! Provides a standard way to load text documents from a directory
 //! or use a built-in embedded test corpus. Every branch prototype
 //! uses this to ingest documents into the colony.
+//!
+//! Besides the biology corpus, the `corpora-extra` feature compiles in
+//! more corpora with ground-truth categories, loaded by name with
+//! [`Corpus::from_embedded_named`]: `mixed-domains` (six unrelated
+//! fields, for anomaly and community tests) and `code-docs` (software
+//! documentation). [`Corpus::stats`] tells how separable a corpus's
+//! categories are, and [`Corpus::split`] holds documents out for
+//! evaluation.
 
 use crate::colony::Colony;
+use crate::dedup::{BatchDocument, DedupConfig, IngestReport};
+use phago_core::tokenize::Tokenizer;
 use phago_core::types::Position;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 
+pub use crate::extract::{extract_text, DocFormat, ExtractError, ExtractedDoc};
+
+/// Names [`Corpus::from_embedded_named`] accepts.
+#[cfg(not(feature = "corpora-extra"))]
+pub const EMBEDDED_CORPORA: &[&str] = &["biology"];
+
+/// Names [`Corpus::from_embedded_named`] accepts.
+#[cfg(feature = "corpora-extra")]
+pub const EMBEDDED_CORPORA: &[&str] = &["biology", "mixed-domains", "code-docs"];
+
+#[cfg(feature = "corpora-extra")]
+const MIXED_DOMAINS: &str = include_str!("../corpora/mixed-domains.txt");
+
+#[cfg(feature = "corpora-extra")]
+const CODE_DOCS: &str = include_str!("../corpora/code-docs.txt");
+
+/// Distance between neighboring documents in the grid layouts.
+const SPACING: f64 = 5.0;
+
 /// A corpus of documents to be ingested into a colony.
 pub struct Corpus {
     pub documents: Vec<CorpusDocument>,
@@ -33,7 +64,7 @@ impl Corpus {
         let mut documents = Vec::new();
         let mut entries: Vec<_> = std::fs::read_dir(path)?
             .filter_map(|e| e.ok())
-            .filter(|e| e.path().extension().map_or(false, |ext| ext == "txt"))
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "txt"))
             .collect();
 
         entries.sort_by_key(|e| e.file_name());
@@ -119,6 +150,56 @@ impl Corpus {
         Self::inline_corpus()
     }
 
+    /// The embedded corpus called `name`, one of [`EMBEDDED_CORPORA`].
+    /// `biology` is [`from_embedded`](Self::from_embedded); the others
+    /// need the `corpora-extra` feature.
+    pub fn from_embedded_named(name: &str) -> Option<Self> {
+        let mut corpus = match name {
+            "biology" => Self::from_embedded(),
+            #[cfg(feature = "corpora-extra")]
+            "mixed-domains" => Self::from_lines(MIXED_DOMAINS),
+            #[cfg(feature = "corpora-extra")]
+            "code-docs" => Self::from_lines(CODE_DOCS),
+            _ => return None,
+        };
+        corpus.name = name.to_string();
+        Some(corpus)
+    }
+
+    /// Documents written one per line as `CATEGORY<TAB>TEXT`, skipping
+    /// blank lines and `#` comments. Each category gets a row of the grid.
+    #[cfg(feature = "corpora-extra")]
+    fn from_lines(text: &str) -> Self {
+        let mut rows: Vec<&str> = Vec::new();
+        let mut documents = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((category, content)) = line.split_once('\t') else {
+                continue;
+            };
+            let row = rows.iter().position(|c| *c == category).unwrap_or_else(|| {
+                rows.push(category);
+                rows.len() - 1
+            });
+            let col = documents
+                .iter()
+                .filter(|d: &&CorpusDocument| d.category.as_deref() == Some(category))
+                .count();
+            documents.push(CorpusDocument {
+                title: format!("{}_{:02}", category, col + 1),
+                content: content.to_string(),
+                category: Some(category.to_string()),
+                position: Position::new(col as f64 * SPACING, row as f64 * SPACING),
+            });
+        }
+        Corpus {
+            documents,
+            name: "embedded".to_string(),
+        }
+    }
+
     /// Inline fallback corpus with 20 documents across 4 topics.
     /// Used when the disk corpus directory is not available.
     pub fn inline_corpus() -> Self {
@@ -154,13 +235,12 @@ impl Corpus {
         ];
 
         let mut documents = Vec::new();
-        let spacing = 5.0;
 
         for (topic_idx, (topic, docs)) in topics.iter().enumerate() {
             for (doc_idx, content) in docs.iter().enumerate() {
                 let title = format!("{}_{:02}", topic, doc_idx + 1);
-                let x = doc_idx as f64 * spacing;
-                let y = topic_idx as f64 * spacing;
+                let x = doc_idx as f64 * SPACING;
+                let y = topic_idx as f64 * SPACING;
 
                 documents.push(CorpusDocument {
                     title,
@@ -189,7 +269,7 @@ impl Corpus {
 
     /// Get the ground-truth category labels (for NMI computation).
     /// Returns a map of document title -> category.
-    pub fn ground_truth(&self) -> std::collections::HashMap<String, String> {
+    pub fn ground_truth(&self) -> HashMap<String, String> {
         self.documents
             .iter()
             .filter_map(|d| d.category.as_ref().map(|c| (d.title.clone(), c.clone())))
@@ -202,7 +282,7 @@ impl Corpus {
             .documents
             .iter()
             .filter_map(|d| d.category.clone())
-            .collect::<std::collections::HashSet<_>>()
+            .collect::<HashSet<_>>()
             .into_iter()
             .collect();
         cats.sort();
@@ -230,12 +310,165 @@ impl Corpus {
         self
     }
 
+    /// Document and category counts, vocabulary and document length, and
+    /// how much the categories' vocabularies overlap. Terms are split with
+    /// the default [`Tokenizer`].
+    pub fn stats(&self) -> CorpusStats {
+        let tokenizer = Tokenizer::default();
+        let mut categories: BTreeMap<String, usize> = BTreeMap::new();
+        let mut category_terms: BTreeMap<&str, HashSet<String>> = BTreeMap::new();
+        let mut vocabulary: HashSet<String> = HashSet::new();
+        let mut total_terms = 0;
+        for doc in &self.documents {
+            let terms = tokenizer.tokens(&doc.content);
+            total_terms += terms.len();
+            if let Some(category) = &doc.category {
+                *categories.entry(category.clone()).or_insert(0) += 1;
+                category_terms
+                    .entry(category)
+                    .or_default()
+                    .extend(terms.iter().cloned());
+            }
+            vocabulary.extend(terms);
+        }
+
+        let sets: Vec<(&str, &HashSet<String>)> =
+            category_terms.iter().map(|(c, t)| (*c, t)).collect();
+        let mut category_overlap = Vec::new();
+        for (i, (a, a_terms)) in sets.iter().enumerate() {
+            for (b, b_terms) in &sets[i + 1..] {
+                let shared = a_terms.intersection(b_terms).count();
+                let union = a_terms.len() + b_terms.len() - shared;
+                category_overlap.push(CategoryOverlap {
+                    a: a.to_string(),
+                    b: b.to_string(),
+                    jaccard: if union == 0 {
+                        0.0
+                    } else {
+                        shared as f64 / union as f64
+                    },
+                });
+            }
+        }
+
+        CorpusStats {
+            documents: self.documents.len(),
+            uncategorized: self.documents.len() - categories.values().sum::<usize>(),
+            categories,
+            vocabulary_size: vocabulary.len(),
+            mean_document_length: if self.documents.is_empty() {
+                0.0
+            } else {
+                total_terms as f64 / self.documents.len() as f64
+            },
+            category_overlap,
+        }
+    }
+
+    /// Split into a training and a held-out corpus. Each category (and the
+    /// uncategorized documents) is split on its own, `train_fraction` of it
+    /// going to training, so both halves keep every category; `seed` picks
+    /// which documents. Documents keep their order.
+    pub fn split(&self, train_fraction: f64, seed: u64) -> (Corpus, Corpus) {
+        let fraction = train_fraction.clamp(0.0, 1.0);
+        let mut groups: BTreeMap<Option<&str>, Vec<usize>> = BTreeMap::new();
+        for (i, doc) in self.documents.iter().enumerate() {
+            groups.entry(doc.category.as_deref()).or_default().push(i);
+        }
+
+        let mut train = HashSet::new();
+        for indices in groups.values_mut() {
+            // Order the group by a hash of the seed and each title
+            indices.sort_by_key(|&i| splitmix64(seed ^ title_hash(&self.documents[i].title)));
+            let keep = (indices.len() as f64 * fraction).round() as usize;
+            train.extend(indices.iter().take(keep).copied());
+        }
+
+        let (train_docs, test_docs): (Vec<_>, Vec<_>) = self
+            .documents
+            .iter()
+            .enumerate()
+            .partition(|(i, _)| train.contains(i));
+        let corpus = |docs: Vec<(usize, &CorpusDocument)>, part: &str| Corpus {
+            documents: docs.into_iter().map(|(_, d)| d.clone()).collect(),
+            name: format!("{}-{}", self.name, part),
+        };
+        (corpus(train_docs, "train"), corpus(test_docs, "test"))
+    }
+
     /// Ingest all documents into a colony.
     pub fn ingest_into(&self, colony: &mut Colony) {
         for doc in &self.documents {
             colony.ingest_document(&doc.title, &doc.content, doc.position);
         }
     }
+
+    /// Ingest all documents into a colony, leaving out or merging
+    /// duplicates according to `config`.
+    pub fn ingest_deduplicated(&self, colony: &mut Colony, config: &DedupConfig) -> IngestReport {
+        let batch: Vec<BatchDocument> = self
+            .documents
+            .iter()
+            .map(|d| BatchDocument {
+                title: &d.title,
+                content: &d.content,
+                position: d.position,
+                tags: &[],
+            })
+            .collect();
+        colony.ingest_batch(&batch, config)
+    }
+}
+
+/// What a corpus holds; see [`Corpus::stats`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CorpusStats {
+    pub documents: usize,
+    /// Documents per category.
+    pub categories: BTreeMap<String, usize>,
+    /// Documents without a category.
+    pub uncategorized: usize,
+    /// Distinct terms across all documents.
+    pub vocabulary_size: usize,
+    /// Mean terms per document.
+    pub mean_document_length: f64,
+    /// Every pair of categories, in name order.
+    pub category_overlap: Vec<CategoryOverlap>,
+}
+
+/// How much the vocabularies of two categories overlap.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategoryOverlap {
+    pub a: String,
+    pub b: String,
+    /// Shared terms over the terms either category has.
+    pub jaccard: f64,
+}
+
+impl CorpusStats {
+    /// Mean overlap between categories. Near 0 the categories are easy to
+    /// tell apart; the closer to 1, the less an NMI score against them says.
+    pub fn mean_category_overlap(&self) -> f64 {
+        if self.category_overlap.is_empty() {
+            return 0.0;
+        }
+        self.category_overlap.iter().map(|o| o.jaccard).sum::<f64>()
+            / self.category_overlap.len() as f64
+    }
+}
+
+fn title_hash(title: &str) -> u64 {
+    // FNV-1a, stable across runs and platforms
+    title.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
 }
 
 #[cfg(test)]
@@ -272,12 +505,109 @@ mod tests {
         );
     }
 
+    #[test]
+    fn named_corpora_load_with_distinct_ids_and_ground_truth() {
+        for name in EMBEDDED_CORPORA {
+            let corpus = Corpus::from_embedded_named(name).unwrap();
+            assert_eq!(corpus.name, *name);
+            assert!(corpus.len() >= 20, "{} has {} docs", name, corpus.len());
+            let titles: HashSet<&str> = corpus.documents.iter().map(|d| d.title.as_str()).collect();
+            assert_eq!(titles.len(), corpus.len(), "{} repeats a title", name);
+            let gt = corpus.ground_truth();
+            assert!(
+                corpus.documents.iter().all(|d| gt.contains_key(&d.title)),
+                "{} has documents without a category",
+                name
+            );
+        }
+        assert!(Corpus::from_embedded_named("astrology").is_none());
+    }
+
+    #[cfg(feature = "corpora-extra")]
+    #[test]
+    fn mixed_domains_are_further_apart_than_biology() {
+        let mixed = Corpus::from_embedded_named("mixed-domains")
+            .unwrap()
+            .stats();
+        let biology = Corpus::inline_corpus().stats();
+        assert_eq!(mixed.categories.len(), 6);
+        assert!(mixed.mean_category_overlap() < biology.mean_category_overlap());
+    }
+
+    #[test]
+    fn stats_count_categories_and_overlap() {
+        let stats = Corpus::inline_corpus().stats();
+        assert_eq!(stats.documents, 20);
+        assert_eq!(stats.uncategorized, 0);
+        assert!(stats.categories.values().all(|n| *n == 5));
+        // One overlap per pair of the 4 categories
+        assert_eq!(stats.category_overlap.len(), 6);
+        assert!(stats.vocabulary_size > 100);
+        assert!(stats.mean_document_length > 10.0);
+        let overlap = |a: &str, b: &str| {
+            stats
+                .category_overlap
+                .iter()
+                .find(|o| o.a == a && o.b == b)
+                .unwrap()
+                .jaccard
+        };
+        assert!(
+            overlap("cell_biology", "quantum_computing")
+                < overlap("cell_biology", "molecular_transport")
+        );
+    }
+
+    #[test]
+    fn split_keeps_every_category_in_both_halves() {
+        let corpus = Corpus::inline_corpus();
+        let (train, test) = corpus.split(0.6, 7);
+        assert_eq!(train.len(), 12);
+        assert_eq!(test.len(), 8);
+        assert_eq!(train.categories(), corpus.categories());
+        assert_eq!(test.categories(), corpus.categories());
+        let train_titles: HashSet<String> = train.ground_truth().into_keys().collect();
+        assert!(test
+            .documents
+            .iter()
+            .all(|d| !train_titles.contains(&d.title)));
+
+        let (again, _) = corpus.split(0.6, 7);
+        let titles = |c: &Corpus| {
+            c.documents
+                .iter()
+                .map(|d| d.title.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(titles(&train), titles(&again));
+        assert!((0..20).any(|seed| titles(&corpus.split(0.6, seed).0) != titles(&train)));
+    }
+
     #[test]
     fn inline_corpus_has_20_documents() {
         let corpus = Corpus::inline_corpus();
         assert_eq!(corpus.len(), 20);
     }
 
+    #[test]
+    fn deduplicated_ingestion_skips_mirrored_documents() {
+        let mut corpus = Corpus::inline_corpus();
+        let mut mirror = corpus.documents[0].clone();
+        mirror.title = format!("{} (mirror)", mirror.title);
+        mirror.content = format!("Mirrored on 2024-05-01\n{}", mirror.content);
+        corpus.documents.push(mirror);
+
+        let mut colony = Colony::new();
+        let report = corpus.ingest_deduplicated(&mut colony, &DedupConfig::default());
+        assert_eq!(report.ingested.len(), 20);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(
+            report.skipped[0].duplicate_of.original_title,
+            corpus.documents[0].title
+        );
+        assert_eq!(colony.stats().documents_total, 20);
+    }
+
     #[test]
     fn from_directory_loads_txt_files() {
         let path = Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -8,6 +8,7 @@
 use crate::community::CommunityResult;
 use crate::export::WeightedTriple;
 use serde::Serialize;
+use std::collections::HashMap;
 
 /// A curriculum-ordered sequence of triples.
 #[derive(Debug, Clone, Serialize)]
@@ -15,6 +16,8 @@ pub struct Curriculum {
     pub foundation: Vec<WeightedTriple>,
     pub bridges: Vec<WeightedTriple>,
     pub periphery: Vec<WeightedTriple>,
+    /// Node label → community ID the curriculum was built against.
+    pub communities: HashMap<String, usize>,
 }
 
 impl Curriculum {
@@ -44,6 +47,7 @@ pub fn build_curriculum(triples: &[WeightedTriple], communities: &CommunityResul
             foundation: Vec::new(),
             bridges: Vec::new(),
             periphery: Vec::new(),
+            communities: communities.assignments.clone(),
         };
     }
 
@@ -95,5 +99,6 @@ pub fn build_curriculum(triples: &[WeightedTriple], communities: &CommunityResul
         foundation,
         bridges,
         periphery,
+        communities: communities.assignments.clone(),
     }
 }
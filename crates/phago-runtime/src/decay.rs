@@ -0,0 +1,299 @@
+//! Adaptive decay — tying signal, trace and edge decay rates to colony
+//! load.
+//!
+//! Fixed rates suit one colony size only: a small colony prunes edges
+//! before reinforcement reaches them, and a large one keeps every edge
+//! until ticks slow to a crawl. A [`DecayController`] instead sets the
+//! rates each tick from how far the colony is over its budgets:
+//!
+//! ```text
+//! rate = clamp(base * (1 + gain * error), min, max)
+//! ```
+//!
+//! where `base` is the configured fixed rate and `error` the relative
+//! overshoot of the budget (`edges / max_edges - 1`, zero while under it).
+//! Only mature edges can be pruned, so the edge budget is measured against
+//! those: raising the rate while young edges are in their grace period
+//! would only make them all fall below the prune threshold together once
+//! they mature.
+//! With a [`target_median_weight`](AdaptiveDecayConfig::target_median_weight)
+//! the edge error also includes how far the median edge weight is from the
+//! target, so edges decay more slowly while they are weaker than the
+//! target. A colony within its budgets decays at the fixed rates.
+
+use serde::{Deserialize, Serialize};
+
+/// Lowest and highest rate the controller may choose.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateBounds {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl RateBounds {
+    pub fn new(min: f64, max: f64) -> Self {
+        Self { min, max }
+    }
+
+    fn clamp(&self, rate: f64) -> f64 {
+        rate.max(self.min).min(self.max)
+    }
+}
+
+/// Budgets and bounds for [`DecayController`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveDecayConfig {
+    /// Mature edges the graph should hold at most; 0 disables the budget
+    /// (default: 5000).
+    #[serde(default = "default_max_edges")]
+    pub max_edges: usize,
+    /// Signals the substrate should hold at most; 0 disables the budget
+    /// (default: 2000).
+    #[serde(default = "default_max_signals")]
+    pub max_signals: usize,
+    /// Traces the substrate should hold at most; 0 disables the budget
+    /// (default: 5000).
+    #[serde(default = "default_max_traces")]
+    pub max_traces: usize,
+    /// Median edge weight to steer towards (default: none).
+    #[serde(default)]
+    pub target_median_weight: Option<f64>,
+    /// How strongly the rates react to an error (default: 10.0).
+    #[serde(default = "default_gain")]
+    pub gain: f64,
+    #[serde(default = "default_signal_bounds")]
+    pub signal_rate: RateBounds,
+    #[serde(default = "default_trace_bounds")]
+    pub trace_rate: RateBounds,
+    #[serde(default = "default_edge_bounds")]
+    pub edge_rate: RateBounds,
+}
+
+fn default_max_edges() -> usize {
+    5000
+}
+
+fn default_max_signals() -> usize {
+    2000
+}
+
+fn default_max_traces() -> usize {
+    5000
+}
+
+fn default_gain() -> f64 {
+    10.0
+}
+
+fn default_signal_bounds() -> RateBounds {
+    RateBounds::new(0.01, 0.5)
+}
+
+fn default_trace_bounds() -> RateBounds {
+    RateBounds::new(0.005, 0.2)
+}
+
+fn default_edge_bounds() -> RateBounds {
+    RateBounds::new(0.001, 0.1)
+}
+
+impl Default for AdaptiveDecayConfig {
+    fn default() -> Self {
+        Self {
+            max_edges: default_max_edges(),
+            max_signals: default_max_signals(),
+            max_traces: default_max_traces(),
+            target_median_weight: None,
+            gain: default_gain(),
+            signal_rate: default_signal_bounds(),
+            trace_rate: default_trace_bounds(),
+            edge_rate: default_edge_bounds(),
+        }
+    }
+}
+
+/// Per-tick decay rates.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DecayRates {
+    pub signal: f64,
+    pub trace: f64,
+    pub edge: f64,
+}
+
+/// What the controller reacts to.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct DecayLoad {
+    pub edges: usize,
+    /// Edges past their maturation period, i.e. those pruning can remove.
+    pub mature_edges: usize,
+    pub signals: usize,
+    pub traces: usize,
+    /// Median edge weight; only measured with a weight target.
+    pub median_edge_weight: Option<f64>,
+}
+
+/// The rates a colony decays at and the load they were chosen for.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecayReport {
+    /// Whether a [`DecayController`] sets the rates.
+    pub adaptive: bool,
+    /// The rates of the last tick (the fixed rates before the first).
+    pub rates: DecayRates,
+    /// The load the rates were chosen for (empty without a controller).
+    pub load: DecayLoad,
+    pub config: Option<AdaptiveDecayConfig>,
+}
+
+/// Proportional controller for decay rates.
+#[derive(Debug, Clone)]
+pub struct DecayController {
+    config: AdaptiveDecayConfig,
+    base: DecayRates,
+    rates: DecayRates,
+    load: DecayLoad,
+}
+
+impl DecayController {
+    /// A controller around the fixed `base` rates.
+    pub fn new(config: AdaptiveDecayConfig, base: DecayRates) -> Self {
+        let rates = DecayRates {
+            signal: config.signal_rate.clamp(base.signal),
+            trace: config.trace_rate.clamp(base.trace),
+            edge: config.edge_rate.clamp(base.edge),
+        };
+        Self {
+            config,
+            base,
+            rates,
+            load: DecayLoad::default(),
+        }
+    }
+
+    pub fn config(&self) -> &AdaptiveDecayConfig {
+        &self.config
+    }
+
+    /// The rates chosen by the last [`update`](Self::update).
+    pub fn rates(&self) -> DecayRates {
+        self.rates
+    }
+
+    /// The load passed to the last [`update`](Self::update).
+    pub fn load(&self) -> DecayLoad {
+        self.load
+    }
+
+    /// Choose the rates for `load`.
+    pub fn update(&mut self, load: DecayLoad) -> DecayRates {
+        let config = &self.config;
+        let mut edge_error = overshoot(load.mature_edges, config.max_edges);
+        if let (Some(target), Some(median)) = (config.target_median_weight, load.median_edge_weight)
+        {
+            if target > 0.0 {
+                edge_error += (median - target) / target;
+            }
+        }
+        let adjust = |base: f64, error: f64, bounds: &RateBounds| {
+            bounds.clamp(base * (1.0 + config.gain * error))
+        };
+        self.rates = DecayRates {
+            signal: adjust(
+                self.base.signal,
+                overshoot(load.signals, config.max_signals),
+                &config.signal_rate,
+            ),
+            trace: adjust(
+                self.base.trace,
+                overshoot(load.traces, config.max_traces),
+                &config.trace_rate,
+            ),
+            edge: adjust(self.base.edge, edge_error, &config.edge_rate),
+        };
+        self.load = load;
+        self.rates
+    }
+}
+
+/// Relative amount `load` exceeds `budget` by; zero within it or
+/// without a budget.
+fn overshoot(load: usize, budget: usize) -> f64 {
+    if budget == 0 {
+        return 0.0;
+    }
+    (load as f64 / budget as f64 - 1.0).max(0.0)
+}
+
+/// Median of `weights`, which it reorders.
+pub(crate) fn median(weights: &mut [f64]) -> Option<f64> {
+    if weights.is_empty() {
+        return None;
+    }
+    let mid = weights.len() / 2;
+    let (_, m, _) = weights.select_nth_unstable_by(mid, |a, b| a.total_cmp(b));
+    Some(*m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> DecayRates {
+        DecayRates {
+            signal: 0.05,
+            trace: 0.02,
+            edge: 0.005,
+        }
+    }
+
+    fn load(edges: usize, median: Option<f64>) -> DecayLoad {
+        DecayLoad {
+            edges,
+            mature_edges: edges,
+            signals: 10,
+            traces: 10,
+            median_edge_weight: median,
+        }
+    }
+
+    #[test]
+    fn within_budget_keeps_the_fixed_rates() {
+        let mut controller = DecayController::new(AdaptiveDecayConfig::default(), base());
+        assert_eq!(controller.update(load(100, None)), base());
+    }
+
+    #[test]
+    fn overshoot_raises_rates_up_to_the_bound() {
+        let config = AdaptiveDecayConfig {
+            max_edges: 100,
+            gain: 2.0,
+            ..Default::default()
+        };
+        let mut controller = DecayController::new(config, base());
+        let rates = controller.update(load(150, None));
+        assert!((rates.edge - 0.01).abs() < 1e-12);
+        assert_eq!(rates.signal, 0.05);
+
+        let rates = controller.update(load(100_000, None));
+        assert_eq!(rates.edge, 0.1);
+    }
+
+    #[test]
+    fn weak_edges_slow_edge_decay() {
+        let config = AdaptiveDecayConfig {
+            target_median_weight: Some(0.4),
+            gain: 1.0,
+            ..Default::default()
+        };
+        let mut controller = DecayController::new(config, base());
+        let rates = controller.update(load(10, Some(0.2)));
+        assert!((rates.edge - 0.0025).abs() < 1e-12);
+        let rates = controller.update(load(10, Some(0.0)));
+        assert_eq!(rates.edge, 0.001);
+    }
+
+    #[test]
+    fn median_picks_the_middle_weight() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [0.9, 0.1, 0.5]), Some(0.5));
+    }
+}
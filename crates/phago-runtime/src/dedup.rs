@@ -0,0 +1,454 @@
+//! Duplicate and near-duplicate detection for ingestion.
+//!
+//! Mirrored pages and boilerplate-heavy exports would otherwise be digested
+//! once per copy, inflating the edges between their boilerplate phrases.
+//! Exact copies are found by content hash. Near copies are found with
+//! MinHash over word shingles of the content; signatures are bucketed by
+//! locality-sensitive hashing so a new document is only compared with
+//! plausible matches.
+//!
+//! Numbers are folded into a single token before shingling, so documents
+//! that differ only in timestamps, counters or page numbers look alike.
+//!
+//! Used through [`Colony::ingest_deduplicated`](crate::colony::Colony::ingest_deduplicated)
+//! and [`Colony::ingest_batch`](crate::colony::Colony::ingest_batch).
+
+use phago_core::types::{Document, DocumentId, Position};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// MinHash values per signature.
+const SIGNATURE_LEN: usize = 128;
+/// LSH bands; `SIGNATURE_LEN / BANDS` values per band.
+const BANDS: usize = 32;
+/// Words per shingle.
+const SHINGLE_WORDS: usize = 3;
+
+/// What to do with a document that duplicates one already ingested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupPolicy {
+    /// Leave it out and report the document it matched.
+    #[default]
+    Skip,
+    /// Leave it out, but count it towards the original: every concept the
+    /// original contributed gains one access, without presenting again.
+    MergeIntoExisting,
+    /// Ingest it regardless; the match is still reported.
+    IngestAnyway,
+}
+
+impl std::str::FromStr for DedupPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(DedupPolicy::Skip),
+            "merge" => Ok(DedupPolicy::MergeIntoExisting),
+            "ingest" => Ok(DedupPolicy::IngestAnyway),
+            other => Err(format!(
+                "Unknown dedup policy '{other}'. Use: skip, merge, ingest"
+            )),
+        }
+    }
+}
+
+/// Deduplication settings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DedupConfig {
+    pub policy: DedupPolicy,
+    /// Estimated Jaccard similarity of word shingles at or above which two
+    /// documents count as near duplicates (default: 0.8).
+    pub threshold: f64,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            policy: DedupPolicy::default(),
+            threshold: 0.8,
+        }
+    }
+}
+
+/// How a document matched an earlier one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// Identical content.
+    Exact,
+    /// Similar content, with the estimated similarity.
+    Near { similarity: f64 },
+}
+
+/// The earlier document a new one duplicates.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DuplicateMatch {
+    pub original: DocumentId,
+    pub original_title: String,
+    pub kind: MatchKind,
+}
+
+/// What happened to one document offered for deduplicated ingestion.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestOutcome {
+    /// Ingested, possibly despite a match under
+    /// [`DedupPolicy::IngestAnyway`].
+    Ingested {
+        id: DocumentId,
+        duplicate_of: Option<DuplicateMatch>,
+    },
+    /// Left out under [`DedupPolicy::Skip`].
+    Skipped(DuplicateMatch),
+    /// Folded into the original under [`DedupPolicy::MergeIntoExisting`].
+    Merged(DuplicateMatch),
+}
+
+impl IngestOutcome {
+    /// The earlier document this one matched, if any.
+    pub fn duplicate(&self) -> Option<&DuplicateMatch> {
+        match self {
+            IngestOutcome::Ingested { duplicate_of, .. } => duplicate_of.as_ref(),
+            IngestOutcome::Skipped(m) | IngestOutcome::Merged(m) => Some(m),
+        }
+    }
+}
+
+/// A document offered to [`Colony::ingest_batch`](crate::colony::Colony::ingest_batch).
+#[derive(Debug, Clone, Copy)]
+pub struct BatchDocument<'a> {
+    pub title: &'a str,
+    pub content: &'a str,
+    pub position: Position,
+    pub tags: &'a [&'a str],
+}
+
+/// A document that was not ingested as its own copy, and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DedupEntry {
+    pub title: String,
+    pub duplicate_of: DuplicateMatch,
+}
+
+/// Result of a deduplicated batch ingestion.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IngestReport {
+    /// Documents added or updated, in input order.
+    pub ingested: Vec<DocumentId>,
+    /// Duplicates left out under [`DedupPolicy::Skip`].
+    pub skipped: Vec<DedupEntry>,
+    /// Duplicates folded into their originals.
+    pub merged: Vec<DedupEntry>,
+    /// Duplicates ingested under [`DedupPolicy::IngestAnyway`].
+    pub ingested_duplicates: Vec<DedupEntry>,
+}
+
+impl IngestReport {
+    /// Record the outcome for the document titled `title`.
+    pub fn record(&mut self, title: &str, outcome: IngestOutcome) {
+        let entry = |duplicate_of| DedupEntry {
+            title: title.to_string(),
+            duplicate_of,
+        };
+        match outcome {
+            IngestOutcome::Ingested { id, duplicate_of } => {
+                self.ingested.push(id);
+                if let Some(m) = duplicate_of {
+                    self.ingested_duplicates.push(entry(m));
+                }
+            }
+            IngestOutcome::Skipped(m) => self.skipped.push(entry(m)),
+            IngestOutcome::Merged(m) => self.merged.push(entry(m)),
+        }
+    }
+
+    /// Number of documents found to duplicate an earlier one.
+    pub fn duplicates(&self) -> usize {
+        self.skipped.len() + self.merged.len() + self.ingested_duplicates.len()
+    }
+}
+
+struct Entry {
+    version: u32,
+    title: String,
+    content_hash: u64,
+    /// Empty for documents without words.
+    signature: Vec<u64>,
+}
+
+/// Content hashes and MinHash signatures of ingested documents.
+#[derive(Default)]
+pub struct DedupIndex {
+    entries: HashMap<DocumentId, Entry>,
+    /// Insertion order, so the earliest copy is reported as the original.
+    order: Vec<DocumentId>,
+    exact: HashMap<u64, Vec<DocumentId>>,
+    bands: HashMap<(usize, u64), Vec<DocumentId>>,
+}
+
+impl DedupIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of indexed documents.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no documents are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Index `doc`, replacing an older version of it.
+    pub fn insert(&mut self, doc: &Document) {
+        if self
+            .entries
+            .get(&doc.id)
+            .is_some_and(|e| e.version == doc.version)
+        {
+            return;
+        }
+        self.remove(&doc.id);
+
+        let content_hash = hash_of(&doc.content);
+        let signature = signature(&doc.content);
+        self.exact.entry(content_hash).or_default().push(doc.id);
+        for key in band_keys(&signature) {
+            self.bands.entry(key).or_default().push(doc.id);
+        }
+        self.order.push(doc.id);
+        self.entries.insert(
+            doc.id,
+            Entry {
+                version: doc.version,
+                title: doc.title.clone(),
+                content_hash,
+                signature,
+            },
+        );
+    }
+
+    /// Drop a document from the index.
+    pub fn remove(&mut self, id: &DocumentId) {
+        let Some(entry) = self.entries.remove(id) else {
+            return;
+        };
+        self.order.retain(|d| d != id);
+        if let Some(ids) = self.exact.get_mut(&entry.content_hash) {
+            ids.retain(|d| d != id);
+        }
+        for key in band_keys(&entry.signature) {
+            if let Some(ids) = self.bands.get_mut(&key) {
+                ids.retain(|d| d != id);
+            }
+        }
+    }
+
+    /// Bring the index in line with `docs`: new and changed documents are
+    /// indexed, documents no longer present are dropped.
+    pub fn sync<'a>(&mut self, docs: impl IntoIterator<Item = &'a Document>) {
+        let mut present = std::collections::HashSet::new();
+        for doc in docs {
+            present.insert(doc.id);
+            self.insert(doc);
+        }
+        let gone: Vec<DocumentId> = self
+            .order
+            .iter()
+            .filter(|id| !present.contains(id))
+            .copied()
+            .collect();
+        for id in gone {
+            self.remove(&id);
+        }
+    }
+
+    /// The earliest indexed document `content` duplicates at `threshold`.
+    ///
+    /// Documents titled `title` are ignored: they are earlier versions of
+    /// the same document, not copies of it.
+    pub fn find(&self, title: &str, content: &str, threshold: f64) -> Option<DuplicateMatch> {
+        let other = |id: &DocumentId| self.entries.get(id).filter(|e| e.title != title);
+        let matched = |id: DocumentId, entry: &Entry, kind| DuplicateMatch {
+            original: id,
+            original_title: entry.title.clone(),
+            kind,
+        };
+
+        if let Some(ids) = self.exact.get(&hash_of(content)) {
+            let earliest = self
+                .order
+                .iter()
+                .filter(|id| ids.contains(id))
+                .find_map(|id| other(id).map(|e| (*id, e)));
+            if let Some((id, entry)) = earliest {
+                return Some(matched(id, entry, MatchKind::Exact));
+            }
+        }
+
+        let signature = signature(content);
+        let mut candidates: Vec<DocumentId> = band_keys(&signature)
+            .filter_map(|key| self.bands.get(&key))
+            .flatten()
+            .copied()
+            .collect();
+        candidates.sort_by_key(|id| self.order.iter().position(|d| d == id));
+        candidates.dedup();
+
+        let mut best: Option<(DocumentId, &Entry, f64)> = None;
+        for id in candidates {
+            let Some(entry) = other(&id) else {
+                continue;
+            };
+            let similarity = similarity(&signature, &entry.signature);
+            if similarity >= threshold && best.is_none_or(|(_, _, s)| similarity > s) {
+                best = Some((id, entry, similarity));
+            }
+        }
+        best.map(|(id, entry, similarity)| matched(id, entry, MatchKind::Near { similarity }))
+    }
+}
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Lowercased words, with every number replaced by `#`.
+fn words(content: &str) -> Vec<String> {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            if w.chars().all(|c| c.is_numeric()) {
+                "#".to_string()
+            } else {
+                w.to_lowercase()
+            }
+        })
+        .collect()
+}
+
+/// MinHash signature over word shingles; empty if there are no words.
+fn signature(content: &str) -> Vec<u64> {
+    let words = words(content);
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let shingles: Vec<u64> = words
+        .windows(SHINGLE_WORDS.min(words.len()))
+        .map(hash_of)
+        .collect();
+
+    (0..SIGNATURE_LEN as u64)
+        .map(|i| {
+            // One universal hash per signature slot
+            let a = splitmix64(2 * i) | 1;
+            let b = splitmix64(2 * i + 1);
+            shingles
+                .iter()
+                .map(|&h| splitmix64(a.wrapping_mul(h).wrapping_add(b)))
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn band_keys(signature: &[u64]) -> impl Iterator<Item = (usize, u64)> + '_ {
+    signature
+        .chunks(SIGNATURE_LEN / BANDS)
+        .enumerate()
+        .map(|(band, rows)| (band, hash_of(rows)))
+}
+
+/// Fraction of matching signature slots: an estimate of the Jaccard
+/// similarity of the two shingle sets.
+fn similarity(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let same = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    same as f64 / a.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(title: &str, content: &str) -> Document {
+        Document {
+            id: DocumentId::new(),
+            title: title.to_string(),
+            content: content.to_string(),
+            position: Position::new(0.0, 0.0),
+            digested: false,
+            tags: Vec::new(),
+            version: 1,
+        }
+    }
+
+    const REPORT: &str = "Quarterly infrastructure report\n\
+        Exported 2024-03-01 10:15:22 UTC\n\
+        The cluster migrated its storage tier to replicated object storage. \
+        Latency for cold reads improved while write amplification stayed flat. \
+        Two incidents were traced to certificate rotation on the ingress proxies \
+        and have been addressed with automated renewal and alerting.";
+
+    #[test]
+    fn timestamp_only_changes_are_near_duplicates() {
+        let mut index = DedupIndex::new();
+        let original = doc("report-march", REPORT);
+        index.insert(&original);
+
+        let mirror = REPORT.replace("2024-03-01 10:15:22", "2024-03-02 08:01:09");
+        let found = index
+            .find("report-mirror", &mirror, DedupConfig::default().threshold)
+            .expect("near duplicate");
+        assert_eq!(found.original, original.id);
+        assert!(matches!(found.kind, MatchKind::Near { similarity } if similarity >= 0.8));
+
+        let copy = index.find("report-copy", REPORT, 0.8).expect("exact");
+        assert_eq!(copy.kind, MatchKind::Exact);
+    }
+
+    #[test]
+    fn different_documents_and_own_versions_are_not_duplicates() {
+        let mut index = DedupIndex::new();
+        index.insert(&doc("report-march", REPORT));
+
+        let unrelated = "Mitochondria produce ATP through oxidative phosphorylation. \
+            The electron transport chain pumps protons across the inner membrane.";
+        assert_eq!(index.find("biology", unrelated, 0.8), None);
+        // Re-ingesting a title is an update, not a copy
+        assert_eq!(index.find("report-march", REPORT, 0.8), None);
+    }
+
+    #[test]
+    fn sync_follows_updates_and_removals() {
+        let mut index = DedupIndex::new();
+        let mut report = doc("report", REPORT);
+        index.sync([&report]);
+        assert_eq!(index.len(), 1);
+
+        report.content = "Entirely rewritten notes about gardening and compost.".to_string();
+        report.version = 2;
+        index.sync([&report]);
+        assert_eq!(index.find("other", REPORT, 0.8), None);
+
+        index.sync([]);
+        assert!(index.is_empty());
+    }
+}
@@ -216,7 +216,12 @@ impl GraphDiff {
                 position_x: 0.0,
                 position_y: 0.0,
                 created_tick: self.after_tick,
+                last_accessed_tick: self.after_tick,
                 embedding: None,
+                tags: Default::default(),
+                aliases: Vec::new(),
+                annotations: Vec::new(),
+                anomaly_score: None,
             });
         }
 
@@ -269,6 +274,7 @@ impl GraphDiff {
                 co_activations: 1,
                 created_tick: self.after_tick,
                 last_activated_tick: self.after_tick,
+                sources: Vec::new(),
             });
         }
 
@@ -282,8 +288,15 @@ impl GraphDiff {
                 node_count: 0, // Will be updated
                 edge_count: 0,
                 agent_count: 0,
+                format_version: crate::session::FORMAT_VERSION,
                 files_indexed: vec![],
+                population: vec![],
             },
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: before.embedding_storage,
+            regions: before.regions.clone(),
+            embedding_cache: before.embedding_cache.clone(),
         }
     }
 }
@@ -301,7 +314,12 @@ mod tests {
             position_x: 0.0,
             position_y: 0.0,
             created_tick: 0,
+            last_accessed_tick: 0,
             embedding: None,
+            tags: Default::default(),
+            aliases: Vec::new(),
+            annotations: Vec::new(),
+            anomaly_score: None,
         }
     }
 
@@ -313,6 +331,7 @@ mod tests {
             co_activations: 1,
             created_tick: 0,
             last_activated_tick: 0,
+            sources: Vec::new(),
         }
     }
 
@@ -323,7 +342,9 @@ mod tests {
             node_count: nodes,
             edge_count: edges,
             agent_count: 0,
+            format_version: crate::session::FORMAT_VERSION,
             files_indexed: vec![],
+            population: vec![],
         }
     }
 
@@ -334,6 +355,11 @@ mod tests {
             edges: vec![make_edge("cell", "membrane", 0.8)],
             agents: vec![],
             metadata: make_metadata(10, 2, 1),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
         let diff = diff_sessions(&state, &state);
         assert!(diff.is_empty());
@@ -346,6 +372,11 @@ mod tests {
             edges: vec![],
             agents: vec![],
             metadata: make_metadata(0, 1, 0),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
 
         let after = GraphState {
@@ -353,6 +384,11 @@ mod tests {
             edges: vec![make_edge("cell", "membrane", 0.5)],
             agents: vec![],
             metadata: make_metadata(10, 2, 1),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
 
         let diff = diff_sessions(&before, &after);
@@ -370,6 +406,11 @@ mod tests {
             edges: vec![],
             agents: vec![],
             metadata: make_metadata(0, 2, 0),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
 
         let after = GraphState {
@@ -377,6 +418,11 @@ mod tests {
             edges: vec![],
             agents: vec![],
             metadata: make_metadata(10, 1, 0),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
 
         let diff = diff_sessions(&before, &after);
@@ -391,6 +437,11 @@ mod tests {
             edges: vec![make_edge("a", "b", 0.3)],
             agents: vec![],
             metadata: make_metadata(0, 2, 1),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
 
         let after = GraphState {
@@ -398,6 +449,11 @@ mod tests {
             edges: vec![make_edge("a", "b", 0.9)],
             agents: vec![],
             metadata: make_metadata(10, 2, 1),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
 
         let diff = diff_sessions(&before, &after);
@@ -415,12 +471,22 @@ mod tests {
             edges: vec![],
             agents: vec![],
             metadata: make_metadata(0, 1, 0),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
         let after = GraphState {
             nodes: vec![make_node("a"), make_node("b"), make_node("c")],
             edges: vec![make_edge("a", "b", 0.5)],
             agents: vec![],
             metadata: make_metadata(50, 3, 1),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
 
         let diff = diff_sessions(&before, &after);
@@ -437,12 +503,22 @@ mod tests {
             edges: vec![],
             agents: vec![],
             metadata: make_metadata(0, 1, 0),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
         let after = GraphState {
             nodes: vec![make_node("a"), make_node("b")],
             edges: vec![make_edge("a", "b", 0.5)],
             agents: vec![],
             metadata: make_metadata(10, 2, 1),
+            query_log: Vec::new(),
+            documents: Vec::new(),
+            embedding_storage: Default::default(),
+            regions: Vec::new(),
+            embedding_cache: Vec::new(),
         };
 
         let diff = diff_sessions(&before, &after);
@@ -0,0 +1,176 @@
+//! Embedding cache — term embeddings kept so each term is embedded once.
+//!
+//! Embedding agents present the same terms over and over ("membrane" in
+//! every biology paper), and an API embedder takes a round trip for each.
+//! The substrate keeps an [`EmbeddingCache`] from terms to the embeddings
+//! agents attached with [`AgentAction::EmbedConcepts`]. Agents consult it
+//! through [`Substrate::cached_embedding`] before calling their embedder,
+//! and nodes created for a cached term take its embedding straight away.
+//!
+//! The cache holds at most
+//! [`ColonyConfig::embedding_cache_capacity`](crate::colony::ColonyConfig::embedding_cache_capacity)
+//! terms, evicting the least recently used; lookups count as uses. Terms
+//! match case-insensitively. Sessions save the cache only when asked to
+//! (see [`SessionOptions`](crate::session::SessionOptions)), so a reloaded
+//! colony need not embed its vocabulary again.
+//!
+//! [`AgentAction::EmbedConcepts`]: phago_core::types::AgentAction::EmbedConcepts
+//! [`Substrate::cached_embedding`]: phago_core::substrate::Substrate::cached_embedding
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default number of terms an [`EmbeddingCache`] holds.
+pub const DEFAULT_CAPACITY: usize = 4096;
+
+#[derive(Debug)]
+struct Entry {
+    embedding: Vec<f32>,
+    /// Value of the cache's clock when the entry was last used.
+    used: AtomicU64,
+}
+
+/// Term → embedding, bounded and least-recently-used.
+///
+/// Lookups take `&self`, so agents can read the cache through the
+/// substrate while it is shared; recency is tracked atomically.
+#[derive(Debug)]
+pub struct EmbeddingCache {
+    capacity: usize,
+    entries: HashMap<String, Entry>,
+    clock: AtomicU64,
+}
+
+impl Default for EmbeddingCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl EmbeddingCache {
+    /// A cache of at most `capacity` terms; 0 caches nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            clock: AtomicU64::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Hold at most `capacity` terms from now on, evicting the least
+    /// recently used beyond it.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.evict();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// The embedding of `term`, marking it used.
+    pub fn get(&self, term: &str) -> Option<Vec<f32>> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let entry = self.entries.get(&key(term))?;
+        entry.used.store(self.tick(), Ordering::Relaxed);
+        Some(entry.embedding.clone())
+    }
+
+    pub fn contains(&self, term: &str) -> bool {
+        self.entries.contains_key(&key(term))
+    }
+
+    /// Cache `embedding` for `term`, replacing any earlier one and
+    /// evicting the least recently used term if the cache is full.
+    pub fn insert(&mut self, term: &str, embedding: Vec<f32>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = key(term);
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            self.evict();
+        }
+        let used = AtomicU64::new(self.tick());
+        self.entries.insert(key, Entry { embedding, used });
+    }
+
+    /// Drop the least recently used term.
+    fn evict(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, e)| e.used.load(Ordering::Relaxed))
+            .map(|(term, _)| term.clone());
+        if let Some(term) = oldest {
+            self.entries.remove(&term);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Cached terms with their embeddings, least recently used first, so
+    /// inserting them in order restores the recency.
+    pub fn entries(&self) -> Vec<(&str, &[f32])> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by_key(|(_, e)| e.used.load(Ordering::Relaxed));
+        entries
+            .into_iter()
+            .map(|(term, e)| (term.as_str(), e.embedding.as_slice()))
+            .collect()
+    }
+}
+
+fn key(term: &str) -> String {
+    term.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_the_least_recently_used_term() {
+        let mut cache = EmbeddingCache::new(2);
+        cache.insert("membrane", vec![1.0]);
+        cache.insert("cell", vec![2.0]);
+        // Reading membrane makes cell the least recently used
+        assert_eq!(cache.get("Membrane"), Some(vec![1.0]));
+        cache.insert("protein", vec![3.0]);
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains("cell"));
+        assert_eq!(
+            cache.entries(),
+            vec![("membrane", &[1.0][..]), ("protein", &[3.0][..])]
+        );
+
+        cache.set_capacity(1);
+        assert!(cache.contains("protein"));
+        assert!(!cache.contains("membrane"));
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let mut cache = EmbeddingCache::new(0);
+        cache.insert("membrane", vec![1.0]);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get("membrane"), None);
+    }
+}
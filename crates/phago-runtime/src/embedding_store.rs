@@ -0,0 +1,381 @@
+//! Embedding storage — node embeddings kept beside the graph instead of
+//! inside its nodes.
+//!
+//! An [`EmbeddingTable`] maps node IDs to embeddings and lives on the
+//! substrate, so copying or serializing the graph no longer copies every
+//! embedding with it: snapshots leave them out unless asked for, and
+//! sessions can skip them.
+//!
+//! Embeddings are stored in the table's [`EmbeddingStorage`] format and
+//! dequantized to `f32` on read, so similarity is always computed on
+//! `f32` vectors:
+//!
+//! | Format | Bytes per dimension | Error on read |
+//! |---|---|---|
+//! | [`F32`](EmbeddingStorage::F32) | 4 | none |
+//! | [`F16`](EmbeddingStorage::F16) | 2 | relative, at most 2⁻¹¹ (≈ 0.05%) for magnitudes in 6.1e-5..65504 |
+//! | [`I8`](EmbeddingStorage::I8) | 1 | absolute, at most `scale / 2` within ±127·`scale`; values beyond are clamped |
+
+use phago_core::types::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How an [`EmbeddingTable`] stores embeddings.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum EmbeddingStorage {
+    /// Full precision.
+    #[default]
+    F32,
+    /// IEEE half precision.
+    F16,
+    /// One signed byte per dimension: a value `v` is stored as
+    /// `round(v / scale)`, clamped to ±127. `scale` must be positive.
+    I8 { scale: f32 },
+}
+
+impl EmbeddingStorage {
+    /// [`I8`](Self::I8) scaled for embeddings with components in -1..=1,
+    /// such as unit-normalized ones.
+    pub const UNIT_I8: Self = Self::I8 { scale: 1.0 / 127.0 };
+}
+
+/// One embedding in a storage format. Full-precision embeddings serialize
+/// as a plain array of numbers, as inline embeddings did.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PackedEmbedding {
+    F32(Box<[f32]>),
+    F16 {
+        #[serde(rename = "f16")]
+        bits: Box<[u16]>,
+    },
+    I8 {
+        scale: f32,
+        #[serde(rename = "i8")]
+        values: Box<[i8]>,
+    },
+}
+
+impl PackedEmbedding {
+    /// Store `values` in `storage`.
+    pub fn pack(values: &[f32], storage: EmbeddingStorage) -> Self {
+        match storage {
+            EmbeddingStorage::F32 => Self::F32(values.into()),
+            EmbeddingStorage::F16 => Self::F16 {
+                bits: values.iter().map(|&v| f32_to_f16(v)).collect(),
+            },
+            EmbeddingStorage::I8 { scale } => Self::I8 {
+                scale,
+                values: values
+                    .iter()
+                    .map(|&v| (v / scale).round().clamp(-127.0, 127.0) as i8)
+                    .collect(),
+            },
+        }
+    }
+
+    /// The embedding as `f32` values.
+    pub fn unpack(&self) -> Vec<f32> {
+        match self {
+            Self::F32(values) => values.to_vec(),
+            Self::F16 { bits } => bits.iter().map(|&b| f16_to_f32(b)).collect(),
+            Self::I8 { scale, values } => values.iter().map(|&q| q as f32 * scale).collect(),
+        }
+    }
+
+    /// The format the embedding is stored in.
+    pub fn storage(&self) -> EmbeddingStorage {
+        match self {
+            Self::F32(_) => EmbeddingStorage::F32,
+            Self::F16 { .. } => EmbeddingStorage::F16,
+            Self::I8 { scale, .. } => EmbeddingStorage::I8 { scale: *scale },
+        }
+    }
+
+    /// Number of dimensions.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::F32(values) => values.len(),
+            Self::F16 { bits } => bits.len(),
+            Self::I8 { values, .. } => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The embedding in `storage`, converting only if it is stored in
+    /// another format. Converting a quantized embedding does not recover
+    /// the precision it lost.
+    pub fn convert(self, storage: EmbeddingStorage) -> Self {
+        if self.storage() == storage {
+            self
+        } else {
+            Self::pack(&self.unpack(), storage)
+        }
+    }
+
+    /// Bytes held on the heap.
+    fn heap_bytes(&self) -> usize {
+        match self {
+            Self::F32(values) => std::mem::size_of_val(&**values),
+            Self::F16 { bits } => std::mem::size_of_val(&**bits),
+            Self::I8 { values, .. } => values.len(),
+        }
+    }
+}
+
+/// Node embeddings, all stored in one [`EmbeddingStorage`] format.
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingTable {
+    storage: EmbeddingStorage,
+    entries: HashMap<NodeId, PackedEmbedding>,
+}
+
+impl EmbeddingTable {
+    pub fn new(storage: EmbeddingStorage) -> Self {
+        Self {
+            storage,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn storage(&self) -> EmbeddingStorage {
+        self.storage
+    }
+
+    /// Store embeddings in `storage` from now on, converting those already
+    /// stored.
+    pub fn set_storage(&mut self, storage: EmbeddingStorage) {
+        if storage != self.storage {
+            self.storage = storage;
+            for packed in self.entries.values_mut() {
+                *packed = Self::take(packed).convert(storage);
+            }
+        }
+    }
+
+    fn take(packed: &mut PackedEmbedding) -> PackedEmbedding {
+        std::mem::replace(packed, PackedEmbedding::F32(Box::default()))
+    }
+
+    /// Store `values` as the embedding of `id`, replacing any earlier one.
+    pub fn insert(&mut self, id: NodeId, values: &[f32]) {
+        self.entries
+            .insert(id, PackedEmbedding::pack(values, self.storage));
+    }
+
+    /// Store an already packed embedding, converting it to the table's
+    /// format if needed.
+    pub fn insert_packed(&mut self, id: NodeId, packed: PackedEmbedding) {
+        self.entries.insert(id, packed.convert(self.storage));
+    }
+
+    /// The embedding of `id`, dequantized.
+    pub fn get(&self, id: &NodeId) -> Option<Vec<f32>> {
+        self.entries.get(id).map(PackedEmbedding::unpack)
+    }
+
+    /// The embedding of `id` as stored.
+    pub fn packed(&self, id: &NodeId) -> Option<&PackedEmbedding> {
+        self.entries.get(id)
+    }
+
+    pub fn contains(&self, id: &NodeId) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    pub fn remove(&mut self, id: &NodeId) -> Option<PackedEmbedding> {
+        self.entries.remove(id)
+    }
+
+    /// Give `into` the embedding of `from` if it has none, and drop the
+    /// embedding of `from`.
+    pub fn merge(&mut self, from: &NodeId, into: NodeId) {
+        if let Some(packed) = self.entries.remove(from) {
+            self.entries.entry(into).or_insert(packed);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&NodeId, &PackedEmbedding)> {
+        self.entries.iter()
+    }
+
+    /// Approximate memory held by the stored embeddings: their values plus
+    /// one key and entry per node.
+    pub fn memory_bytes(&self) -> usize {
+        let entry = std::mem::size_of::<(NodeId, PackedEmbedding)>();
+        self.entries
+            .values()
+            .map(|packed| entry + packed.heap_bytes())
+            .sum()
+    }
+}
+
+/// `value` as IEEE half-precision bits, rounded to nearest, ties to even.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        // Infinity stays infinity; NaN stays NaN
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if half_exponent <= 0 {
+        // Subnormal in half precision, or too small for it
+        if half_exponent < -10 {
+            return sign;
+        }
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        let rounded = (mantissa + (1 << (shift - 1)) - 1 + ((mantissa >> shift) & 1)) >> shift;
+        return sign | rounded as u16;
+    }
+
+    let half = ((half_exponent as u32) << 10) | (mantissa >> 13);
+    let rest = mantissa & 0x1fff;
+    // A carry out of the mantissa correctly bumps the exponent
+    let rounded = if rest > 0x1000 || (rest == 0x1000 && half & 1 == 1) {
+        half + 1
+    } else {
+        half
+    };
+    sign | rounded as u16
+}
+
+/// IEEE half-precision `bits` as an `f32`, exactly.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits & 0x8000) as u32) << 16;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+    match exponent {
+        0 => {
+            let magnitude = mantissa as f32 * 2f32.powi(-24);
+            if sign == 0 {
+                magnitude
+            } else {
+                -magnitude
+            }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A deterministic pseudo-random unit vector.
+    fn unit_vector(seed: u64, dimension: usize) -> Vec<f32> {
+        let mut state = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let mut values: Vec<f32> = (0..dimension)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 40) as f32 / (1u64 << 24) as f32) * 2.0 - 1.0
+            })
+            .collect();
+        let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+        values.iter_mut().for_each(|v| *v /= norm);
+        values
+    }
+
+    #[test]
+    fn half_precision_round_trips_representable_values() {
+        for value in [
+            0.0f32,
+            -0.0,
+            1.0,
+            -2.5,
+            0.333_251_95,
+            65504.0,
+            6.1035156e-5,
+            5.9604645e-8,
+        ] {
+            assert_eq!(f16_to_f32(f32_to_f16(value)), value, "{value}");
+        }
+        assert_eq!(f16_to_f32(f32_to_f16(1e6)), f32::INFINITY);
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+        assert_eq!(f16_to_f32(f32_to_f16(1e-9)), 0.0);
+        // Ties go to the even mantissa
+        assert_eq!(f32_to_f16(1.0 + 2f32.powi(-11)), 0x3c00);
+        assert_eq!(f32_to_f16(1.0 + 3.0 * 2f32.powi(-11)), 0x3c02);
+    }
+
+    #[test]
+    fn quantized_values_stay_within_the_documented_error() {
+        let values = unit_vector(7, 384);
+        let half = PackedEmbedding::pack(&values, EmbeddingStorage::F16).unpack();
+        for (v, h) in values.iter().zip(&half) {
+            assert!(
+                (v - h).abs() <= v.abs() * 2f32.powi(-11) + 6e-8,
+                "{v} vs {h}"
+            );
+        }
+        let EmbeddingStorage::I8 { scale } = EmbeddingStorage::UNIT_I8 else {
+            unreachable!()
+        };
+        let bytes = PackedEmbedding::pack(&values, EmbeddingStorage::UNIT_I8).unpack();
+        for (v, q) in values.iter().zip(&bytes) {
+            assert!((v - q).abs() <= scale / 2.0 + 1e-7, "{v} vs {q}");
+        }
+        let clamped = PackedEmbedding::pack(&[3.0, -3.0], EmbeddingStorage::UNIT_I8).unpack();
+        assert_eq!(clamped, vec![1.0, -1.0]);
+    }
+
+    #[test]
+    fn full_precision_serializes_as_a_plain_array() {
+        let packed = PackedEmbedding::pack(&[0.1, -0.25], EmbeddingStorage::F32);
+        assert_eq!(serde_json::to_string(&packed).unwrap(), "[0.1,-0.25]");
+        for storage in [
+            EmbeddingStorage::F32,
+            EmbeddingStorage::F16,
+            EmbeddingStorage::UNIT_I8,
+        ] {
+            let packed = PackedEmbedding::pack(&[0.1, -0.25], storage);
+            let json = serde_json::to_string(&packed).unwrap();
+            let back: PackedEmbedding = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, packed, "{json}");
+        }
+    }
+
+    #[test]
+    fn table_converts_stored_embeddings_with_its_format() {
+        let (a, b) = (NodeId::new(), NodeId::new());
+        let mut table = EmbeddingTable::default();
+        table.insert(a, &[0.5, -0.5]);
+        table.insert_packed(b, PackedEmbedding::pack(&[0.25], EmbeddingStorage::F16));
+        assert_eq!(table.packed(&b).unwrap().storage(), EmbeddingStorage::F32);
+
+        table.set_storage(EmbeddingStorage::UNIT_I8);
+        assert!(table
+            .iter()
+            .all(|(_, p)| p.storage() == EmbeddingStorage::UNIT_I8));
+        assert_eq!(table.get(&a), Some(vec![64.0 / 127.0, -64.0 / 127.0]));
+
+        table.merge(&a, b);
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.get(&b).unwrap().len(), 1);
+    }
+}
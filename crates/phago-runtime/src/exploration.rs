@@ -0,0 +1,218 @@
+//! Exploration schedule — annealing agents from exploring to exploiting
+//! as the colony matures.
+//!
+//! Early in a run agents should range widely to find documents; late in
+//! a run they should stay near what they digested and deepen its wiring.
+//! An agent's explore bias (its genome's `explore_bias`) is fixed, so it
+//! favours either early coverage or late depth. An
+//! [`ExplorationController`] instead measures each tick how mature the
+//! colony is and publishes an exploitation factor to every agent through
+//! [`Substrate::ambient`](phago_core::substrate::Substrate::ambient):
+//!
+//! ```text
+//! maturity     = digested / documents * (1 - min(new nodes per tick / novelty_rate, 1))
+//! exploitation = min + (max - min) * maturity
+//! ```
+//!
+//! where new nodes per tick is the node count's growth averaged over the
+//! last `window` ticks, so a colony still creating concepts quickly keeps
+//! exploring even with most documents digested. The last documents are
+//! the hardest to find, so while any remain undigested maturity stays at
+//! most `pending_maturity`, by default 0: exploitation only begins once
+//! every known document is digested. Colonies that ingest continuously,
+//! and so always have some left, raise it to exploit between arrivals.
+//! Agents scale their own bias by
+//! `1 - exploitation`, so the genome still decides who explores more than
+//! whom.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// How the colony's exploitation follows its maturity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExplorationSchedule {
+    /// Ticks the node creation rate is averaged over (default: 10).
+    #[serde(default = "default_window")]
+    pub window: u64,
+    /// New nodes per tick at and above which the colony counts as still
+    /// discovering (default: 5.0).
+    #[serde(default = "default_novelty_rate")]
+    pub novelty_rate: f64,
+    /// Highest maturity while documents remain undigested (default: 0.0).
+    #[serde(default)]
+    pub pending_maturity: f64,
+    /// Exploitation of a colony that has just started (default: 0.0).
+    #[serde(default)]
+    pub min_exploitation: f64,
+    /// Exploitation of a colony with every document digested and no new
+    /// concepts (default: 1.0). Equal bounds fix the exploitation.
+    #[serde(default = "default_max_exploitation")]
+    pub max_exploitation: f64,
+}
+
+fn default_window() -> u64 {
+    10
+}
+
+fn default_novelty_rate() -> f64 {
+    5.0
+}
+
+fn default_max_exploitation() -> f64 {
+    1.0
+}
+
+impl Default for ExplorationSchedule {
+    fn default() -> Self {
+        Self {
+            window: default_window(),
+            novelty_rate: default_novelty_rate(),
+            pending_maturity: 0.0,
+            min_exploitation: 0.0,
+            max_exploitation: default_max_exploitation(),
+        }
+    }
+}
+
+impl ExplorationSchedule {
+    /// A schedule that keeps the exploitation at `exploitation` whatever
+    /// the colony's maturity, e.g. as a baseline to compare against.
+    pub fn fixed(exploitation: f64) -> Self {
+        Self {
+            min_exploitation: exploitation,
+            max_exploitation: exploitation,
+            ..Default::default()
+        }
+    }
+}
+
+/// What the controller measures the colony's maturity by.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Maturity {
+    /// Digested documents over all documents; 1.0 without documents.
+    pub digested_fraction: f64,
+    /// New nodes per tick over the schedule's window.
+    pub node_rate: f64,
+}
+
+/// Sets the colony's exploitation from its maturity each tick.
+#[derive(Debug, Clone)]
+pub struct ExplorationController {
+    schedule: ExplorationSchedule,
+    /// Node counts of the last `window` ticks and the one before, oldest
+    /// first.
+    node_counts: VecDeque<usize>,
+    maturity: Maturity,
+    exploitation: f64,
+}
+
+impl ExplorationController {
+    pub fn new(schedule: ExplorationSchedule) -> Self {
+        let exploitation = schedule.min_exploitation.clamp(0.0, 1.0);
+        Self {
+            schedule,
+            node_counts: VecDeque::new(),
+            maturity: Maturity::default(),
+            exploitation,
+        }
+    }
+
+    pub fn schedule(&self) -> &ExplorationSchedule {
+        &self.schedule
+    }
+
+    /// The exploitation chosen by the last [`update`](Self::update).
+    pub fn exploitation(&self) -> f64 {
+        self.exploitation
+    }
+
+    /// The maturity measured by the last [`update`](Self::update).
+    pub fn maturity(&self) -> Maturity {
+        self.maturity
+    }
+
+    /// Choose the exploitation for a colony with `documents` documents,
+    /// `digested` of them digested, and `nodes` graph nodes.
+    pub fn update(&mut self, documents: usize, digested: usize, nodes: usize) -> f64 {
+        let window = self.schedule.window.max(1) as usize;
+        self.node_counts.push_back(nodes);
+        while self.node_counts.len() > window + 1 {
+            self.node_counts.pop_front();
+        }
+        let oldest = self.node_counts.front().copied().unwrap_or(nodes);
+        let ticks = (self.node_counts.len() - 1).max(1);
+        self.maturity = Maturity {
+            digested_fraction: if documents == 0 {
+                1.0
+            } else {
+                digested as f64 / documents as f64
+            },
+            node_rate: nodes.saturating_sub(oldest) as f64 / ticks as f64,
+        };
+
+        let novelty = if self.schedule.novelty_rate > 0.0 {
+            (self.maturity.node_rate / self.schedule.novelty_rate).min(1.0)
+        } else {
+            0.0
+        };
+        let mut maturity = self.maturity.digested_fraction * (1.0 - novelty);
+        if digested < documents {
+            maturity = maturity.min(self.schedule.pending_maturity);
+        }
+        let (min, max) = (
+            self.schedule.min_exploitation,
+            self.schedule.max_exploitation,
+        );
+        self.exploitation = (min + (max - min) * maturity).clamp(0.0, 1.0);
+        self.exploitation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exploitation_rises_as_documents_are_digested_and_discovery_slows() {
+        let mut controller = ExplorationController::new(ExplorationSchedule {
+            window: 2,
+            pending_maturity: 1.0,
+            ..Default::default()
+        });
+        assert_eq!(controller.exploitation(), 0.0);
+        assert_eq!(controller.update(10, 0, 0), 0.0);
+        // Half digested, but 40 new nodes a tick: still discovering
+        assert_eq!(controller.update(10, 5, 40), 0.0);
+        // All digested, while discovery slows to 2, 1 and 0 nodes a tick
+        assert_eq!(controller.update(10, 10, 42), 0.0);
+        let slowing = controller.update(10, 10, 44);
+        assert!((slowing - 0.6).abs() < 1e-12, "{slowing}");
+        let slowing = controller.update(10, 10, 44);
+        assert!((slowing - 0.8).abs() < 1e-12, "{slowing}");
+        assert_eq!(controller.update(10, 10, 44), 1.0);
+        assert_eq!(controller.maturity().node_rate, 0.0);
+    }
+
+    #[test]
+    fn exploitation_stays_low_while_documents_are_left() {
+        let mut controller = ExplorationController::new(ExplorationSchedule::default());
+        assert_eq!(controller.update(10, 9, 40), 0.0);
+        assert_eq!(controller.update(10, 10, 40), 1.0);
+
+        let mut controller = ExplorationController::new(ExplorationSchedule {
+            pending_maturity: 0.25,
+            ..Default::default()
+        });
+        assert_eq!(controller.update(10, 9, 40), 0.25);
+        assert_eq!(controller.update(10, 5, 40), 0.25);
+        assert_eq!(controller.update(10, 2, 40), 0.2);
+    }
+
+    #[test]
+    fn fixed_schedules_ignore_maturity() {
+        let mut controller = ExplorationController::new(ExplorationSchedule::fixed(0.7));
+        assert_eq!(controller.exploitation(), 0.7);
+        assert_eq!(controller.update(10, 0, 100), 0.7);
+        assert_eq!(controller.update(10, 10, 100), 0.7);
+    }
+}
@@ -2,10 +2,26 @@
 //!
 //! Exports the colony's knowledge graph as (subject, predicate, object, weight)
 //! triples, suitable for downstream processing into training data.
+//!
+//! Also generates node2vec-style biased random walks over the Hebbian graph,
+//! for training node embeddings from the graph structure itself.
+//!
+//! RDF/Turtle export and import live in [`crate::rdf`], and publishing a
+//! queryable read-only bundle in [`crate::publish`]; both are re-exported
+//! here.
 
 use crate::colony::Colony;
+use crate::label_policy::LabelForm;
+use crate::session::{serialize_sources, SerializedEdgeSource};
 use phago_core::topology::TopologyGraph;
+use phago_core::types::NodeId;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+pub use crate::publish::{publish_bundle, BundleConfig, PublishError, PublishManifest};
+pub use crate::rdf::{export_turtle, import_turtle, RdfConfig, TurtleError, TurtleImport};
 
 /// A knowledge graph triple with weight.
 #[derive(Debug, Clone, Serialize)]
@@ -15,29 +31,61 @@ pub struct WeightedTriple {
     pub object: String,
     pub weight: f64,
     pub co_activations: u64,
+    /// Tags carried by both endpoints, i.e. the views the edge belongs to.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// What reinforced the edge lately, oldest first; only filled in by
+    /// [`export_triples_with_provenance`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SerializedEdgeSource>,
 }
 
 /// Export all edges as weighted triples.
+///
+/// Edges an agent labelled with a relation (see [`Colony::edge_relation`])
+/// use it as the predicate, read in the direction it was wired; all other
+/// edges are `related_to`.
 pub fn export_triples(colony: &Colony) -> Vec<WeightedTriple> {
+    export_triples_with(colony, LabelForm::Stored)
+}
+
+/// Export all edges as weighted triples, naming nodes by the label `form`
+/// asks for.
+pub fn export_triples_with(colony: &Colony, form: LabelForm) -> Vec<WeightedTriple> {
+    triples(colony, form, false)
+}
+
+/// Export all edges as weighted triples, as [`export_triples_with`] does,
+/// each with the documents (by title) and queries that reinforced it
+/// lately. See [`crate::provenance`].
+pub fn export_triples_with_provenance(colony: &Colony, form: LabelForm) -> Vec<WeightedTriple> {
+    triples(colony, form, true)
+}
+
+fn triples(colony: &Colony, form: LabelForm, provenance: bool) -> Vec<WeightedTriple> {
     let graph = colony.substrate().graph();
     let mut triples = Vec::new();
 
     for (from_id, to_id, edge) in graph.all_edges() {
-        let from_label = graph
-            .get_node(&from_id)
-            .map(|n| n.label.clone())
-            .unwrap_or_else(|| "?".to_string());
-        let to_label = graph
-            .get_node(&to_id)
-            .map(|n| n.label.clone())
-            .unwrap_or_else(|| "?".to_string());
-
+        let (subject, predicate, object) = if let Some(r) = colony.edge_relation(&from_id, &to_id) {
+            (from_id, r, to_id)
+        } else if let Some(r) = colony.edge_relation(&to_id, &from_id) {
+            (to_id, r, from_id)
+        } else {
+            (from_id, "related_to", to_id)
+        };
         triples.push(WeightedTriple {
-            subject: from_label,
-            predicate: "related_to".to_string(),
-            object: to_label,
+            subject: node_label(colony, &subject, form),
+            predicate: predicate.to_string(),
+            object: node_label(colony, &object, form),
             weight: edge.weight,
             co_activations: edge.co_activations,
+            tags: shared_tags(graph, &from_id, &to_id),
+            sources: if provenance {
+                serialize_sources(colony, &from_id, &to_id)
+            } else {
+                Vec::new()
+            },
         });
     }
 
@@ -50,6 +98,34 @@ pub fn export_triples(colony: &Colony) -> Vec<WeightedTriple> {
     triples
 }
 
+/// Resolve a node ID to the label used in exports.
+fn node_label(colony: &Colony, id: &NodeId, form: LabelForm) -> String {
+    if form == LabelForm::Original {
+        if let Some(original) = colony.original_label(id) {
+            return original;
+        }
+    }
+    colony
+        .substrate()
+        .graph()
+        .get_node(id)
+        .map(|n| n.label.clone())
+        .unwrap_or_else(|| "?".to_string())
+}
+
+/// Tags present on both nodes, in sorted order.
+fn shared_tags(graph: &dyn TopologyGraph, a: &NodeId, b: &NodeId) -> Vec<String> {
+    match (graph.get_node(a), graph.get_node(b)) {
+        (Some(a), Some(b)) => a
+            .tags
+            .keys()
+            .filter(|t| b.tags.contains_key(*t))
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 /// Export triples with weight statistics.
 pub fn triple_stats(triples: &[WeightedTriple]) -> TripleStats {
     if triples.is_empty() {
@@ -96,3 +172,370 @@ pub struct TripleStats {
     pub min_weight: f64,
     pub mean_co_activations: f64,
 }
+
+/// Parameters for node2vec-style random walks.
+///
+/// `p` is the return parameter (high values discourage stepping straight
+/// back to the previous node) and `q` the in-out parameter (high values
+/// keep the walk local, low values push it outward). `p = q = 1` gives a
+/// plain edge-weighted random walk.
+#[derive(Debug, Clone, Serialize)]
+pub struct WalkConfig {
+    /// Walks started from each non-isolated node.
+    pub num_walks: usize,
+    /// Maximum number of nodes per walk (including the start node).
+    pub walk_length: usize,
+    /// Return parameter: stepping back to the previous node is weighted by
+    /// `1 / p`. Must be finite and positive.
+    pub p: f64,
+    /// In-out parameter: stepping to a node not adjacent to the previous
+    /// one is weighted by `1 / q`. Must be finite and positive.
+    pub q: f64,
+    /// Seed of the walk generator; equal seeds give equal walks.
+    pub seed: u64,
+}
+
+/// Why a [`WalkConfig`] was rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WalkConfigError {
+    /// `p` is zero, negative or not finite.
+    InvalidReturnParameter(f64),
+    /// `q` is zero, negative or not finite.
+    InvalidInOutParameter(f64),
+}
+
+impl std::fmt::Display for WalkConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalkConfigError::InvalidReturnParameter(p) => {
+                write!(f, "p must be finite and positive, got {}", p)
+            }
+            WalkConfigError::InvalidInOutParameter(q) => {
+                write!(f, "q must be finite and positive, got {}", q)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WalkConfigError {}
+
+impl WalkConfig {
+    /// Check that `p` and `q` give finite biases.
+    pub fn validate(&self) -> Result<(), WalkConfigError> {
+        if !(self.p.is_finite() && self.p > 0.0) {
+            return Err(WalkConfigError::InvalidReturnParameter(self.p));
+        }
+        if !(self.q.is_finite() && self.q > 0.0) {
+            return Err(WalkConfigError::InvalidInOutParameter(self.q));
+        }
+        Ok(())
+    }
+}
+
+impl Default for WalkConfig {
+    fn default() -> Self {
+        Self {
+            num_walks: 10,
+            walk_length: 40,
+            p: 1.0,
+            q: 1.0,
+            seed: 42,
+        }
+    }
+}
+
+/// Generate biased random walks over the knowledge graph.
+///
+/// Each step picks a neighbor with probability proportional to the edge
+/// weight times the node2vec bias. Isolated nodes are skipped, and the
+/// output is deterministic for a given graph and seed. Fails if `config`
+/// does not [validate](WalkConfig::validate).
+pub fn random_walks(
+    colony: &Colony,
+    config: WalkConfig,
+) -> Result<Vec<Vec<String>>, WalkConfigError> {
+    config.validate()?;
+    let graph = colony.substrate().graph();
+
+    // Node and neighbor order must not depend on hash iteration order
+    let mut nodes = graph.all_nodes();
+    nodes.sort();
+    let adjacency: HashMap<NodeId, Vec<(NodeId, f64)>> = nodes
+        .iter()
+        .map(|id| {
+            let mut neighbors: Vec<(NodeId, f64)> = graph
+                .neighbors(id)
+                .into_iter()
+                .map(|(other, edge)| (other, edge.weight.max(0.0)))
+                .collect();
+            neighbors.sort_by_key(|(other, _)| *other);
+            (*id, neighbors)
+        })
+        .collect();
+
+    let mut rng = config.seed;
+    let mut walks = Vec::new();
+    for _ in 0..config.num_walks {
+        for start in &nodes {
+            if adjacency[start].is_empty() {
+                continue;
+            }
+            let mut walk = vec![*start];
+            while walk.len() < config.walk_length {
+                let current = walk[walk.len() - 1];
+                let previous = walk.len().checked_sub(2).map(|i| walk[i]);
+                match next_step(graph, &adjacency[&current], previous, &config, &mut rng) {
+                    Some(next) => walk.push(next),
+                    None => break,
+                }
+            }
+            walks.push(
+                walk.iter()
+                    .map(|id| node_label(colony, id, LabelForm::Stored))
+                    .collect(),
+            );
+        }
+    }
+    Ok(walks)
+}
+
+/// Pick the next node of a walk from `current`'s neighbors.
+fn next_step(
+    graph: &dyn TopologyGraph,
+    neighbors: &[(NodeId, f64)],
+    previous: Option<NodeId>,
+    config: &WalkConfig,
+    rng: &mut u64,
+) -> Option<NodeId> {
+    let biased: Vec<(NodeId, f64)> = neighbors
+        .iter()
+        .map(|&(next, weight)| {
+            let bias = match previous {
+                None => 1.0,
+                Some(prev) if prev == next => 1.0 / config.p,
+                Some(prev) if graph.get_edge(&prev, &next).is_some() => 1.0,
+                Some(_) => 1.0 / config.q,
+            };
+            (next, weight * bias)
+        })
+        .collect();
+
+    let total: f64 = biased.iter().map(|(_, w)| w).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    *rng = rng
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    let mut target = (*rng >> 11) as f64 / (1u64 << 53) as f64 * total;
+    for &(next, weight) in &biased {
+        if target < weight {
+            return Some(next);
+        }
+        target -= weight;
+    }
+    biased.last().map(|(next, _)| *next)
+}
+
+/// Write walks one per line, space-separated, as word2vec tools expect.
+///
+/// Whitespace inside labels is replaced with `_` so every label stays a
+/// single token.
+pub fn walks_to_text(walks: &[Vec<String>], path: &Path) -> std::io::Result<()> {
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    for walk in walks {
+        let tokens: Vec<String> = walk
+            .iter()
+            .map(|label| label.split_whitespace().collect::<Vec<_>>().join("_"))
+            .collect();
+        writeln!(out, "{}", tokens.join(" "))?;
+    }
+    out.flush()
+}
+
+/// Count how often label pairs co-occur within `window` steps of each
+/// other across default-configured random walks.
+///
+/// Pairs are keyed in lexicographic order, so `(a, b)` and `(b, a)`
+/// share one entry.
+pub fn cooccurrence_matrix(colony: &Colony, window: usize) -> HashMap<(String, String), u64> {
+    let walks = random_walks(colony, WalkConfig::default()).expect("default walk config is valid");
+    walk_cooccurrences(&walks, window)
+}
+
+/// Co-occurrence counts for an existing set of walks.
+pub fn walk_cooccurrences(walks: &[Vec<String>], window: usize) -> HashMap<(String, String), u64> {
+    let mut counts = HashMap::new();
+    for walk in walks {
+        for (i, a) in walk.iter().enumerate() {
+            for b in walk.iter().skip(i + 1).take(window) {
+                if a == b {
+                    continue;
+                }
+                let key = if a < b {
+                    (a.clone(), b.clone())
+                } else {
+                    (b.clone(), a.clone())
+                };
+                *counts.entry(key).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_core::types::*;
+
+    fn add_node(colony: &mut Colony, label: &str) -> NodeId {
+        let node = NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        };
+        let id = node.id;
+        colony.substrate_mut().graph_mut().add_node(node);
+        id
+    }
+
+    fn connect(colony: &mut Colony, a: NodeId, b: NodeId, weight: f64) {
+        colony
+            .substrate_mut()
+            .graph_mut()
+            .set_edge(
+                a,
+                b,
+                EdgeData {
+                    weight,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    /// A hub with one heavy spoke and one light spoke, plus an isolated node.
+    fn star_colony() -> Colony {
+        let mut colony = Colony::new();
+        let hub = add_node(&mut colony, "hub");
+        let heavy = add_node(&mut colony, "heavy");
+        let light = add_node(&mut colony, "light");
+        add_node(&mut colony, "lonely");
+        connect(&mut colony, hub, heavy, 0.9);
+        connect(&mut colony, hub, light, 0.1);
+        colony
+    }
+
+    #[test]
+    fn walks_have_requested_length_and_skip_isolated_nodes() {
+        let colony = star_colony();
+        let config = WalkConfig {
+            num_walks: 3,
+            walk_length: 7,
+            ..Default::default()
+        };
+        let walks = random_walks(&colony, config).unwrap();
+
+        assert_eq!(walks.len(), 3 * 3);
+        assert!(walks.iter().all(|w| w.len() == 7));
+        assert!(walks.iter().flatten().all(|label| label != "lonely"));
+    }
+
+    #[test]
+    fn walks_are_deterministic_under_seed() {
+        let colony = star_colony();
+        let a = random_walks(&colony, WalkConfig::default()).unwrap();
+        let b = random_walks(&colony, WalkConfig::default()).unwrap();
+        assert_eq!(a, b);
+
+        let c = random_walks(
+            &colony,
+            WalkConfig {
+                seed: 7,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn walks_reject_biases_that_are_not_finite_and_positive() {
+        let colony = star_colony();
+        let walks = |p: f64, q: f64| {
+            random_walks(
+                &colony,
+                WalkConfig {
+                    p,
+                    q,
+                    ..Default::default()
+                },
+            )
+        };
+        assert_eq!(
+            walks(0.0, 1.0),
+            Err(WalkConfigError::InvalidReturnParameter(0.0))
+        );
+        assert_eq!(
+            walks(1.0, -2.0),
+            Err(WalkConfigError::InvalidInOutParameter(-2.0))
+        );
+        assert!(walks(f64::NAN, 1.0).is_err());
+        assert!(walks(1.0, f64::INFINITY).is_err());
+        assert!(walks(0.25, 4.0).is_ok());
+    }
+
+    #[test]
+    fn heavy_edges_are_visited_proportionally_more() {
+        let colony = star_colony();
+        let walks = random_walks(
+            &colony,
+            WalkConfig {
+                num_walks: 200,
+                walk_length: 20,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let count = |label: &str| walks.iter().flatten().filter(|l| *l == label).count() as f64;
+        let ratio = count("heavy") / count("light");
+        // Every visit to a spoke comes from the hub, so visits follow 0.9 : 0.1
+        assert!((6.0..12.0).contains(&ratio), "heavy/light ratio {ratio}");
+    }
+
+    #[test]
+    fn cooccurrences_respect_window() {
+        let walks = vec![vec!["a".to_string(), "b".to_string(), "c".to_string()]];
+        let counts = walk_cooccurrences(&walks, 1);
+        assert_eq!(counts.get(&("a".into(), "b".into())), Some(&1));
+        assert_eq!(counts.get(&("b".into(), "c".into())), Some(&1));
+        assert!(!counts.contains_key(&("a".into(), "c".into())));
+
+        let colony = star_colony();
+        let matrix = cooccurrence_matrix(&colony, 2);
+        assert!(matrix[&("heavy".into(), "hub".into())] > matrix[&("hub".into(), "light".into())]);
+    }
+
+    #[test]
+    fn walks_written_one_per_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("walks.txt");
+        let walks = vec![
+            vec!["cell membrane".to_string(), "protein".to_string()],
+            vec!["protein".to_string()],
+        ];
+        walks_to_text(&walks, &path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text, "cell_membrane protein\nprotein\n");
+    }
+}
@@ -0,0 +1,638 @@
+//! Text extraction — turn PDFs and saved web pages into ingestible text.
+//!
+//! Plain text and markdown are read as-is. HTML is stripped of markup,
+//! scripts, and styles, keeping headings as title candidates. PDF text is
+//! pulled from the page content streams (behind the `pdf` feature).
+//!
+//! Documents that yield suspiciously little text — typically scanned PDFs
+//! with no text layer — are reported as skipped rather than ingested empty.
+
+use std::path::Path;
+
+/// Minimum number of alphanumeric characters an extraction must produce
+/// before it is considered real content.
+pub const MIN_TEXT_CHARS: usize = 40;
+
+/// Source format of an extracted document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    Text,
+    Html,
+    Pdf,
+}
+
+/// A document's text and title, ready for ingestion.
+#[derive(Debug, Clone)]
+pub struct ExtractedDoc {
+    /// From PDF metadata or the HTML `<title>`, falling back to the
+    /// first heading and then the filename.
+    pub title: String,
+    pub content: String,
+    pub format: DocFormat,
+    /// Headings found in the document, in order (HTML only).
+    pub headings: Vec<String>,
+}
+
+/// Why a file could not be turned into a document.
+#[derive(Debug)]
+pub enum ExtractError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The format is not supported (or its feature is not enabled).
+    Unsupported(String),
+    /// The file was read but is not usable; the reason says why.
+    Skipped(String),
+}
+
+impl std::fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtractError::Io(e) => write!(f, "I/O error: {}", e),
+            ExtractError::Unsupported(msg) => write!(f, "unsupported: {}", msg),
+            ExtractError::Skipped(reason) => write!(f, "skipped: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ExtractError {}
+
+impl From<std::io::Error> for ExtractError {
+    fn from(e: std::io::Error) -> Self {
+        ExtractError::Io(e)
+    }
+}
+
+/// Extract text from a file, choosing the extractor by extension.
+///
+/// `.pdf` needs the `pdf` feature; `.html`/`.htm` are stripped of markup;
+/// anything else is read as UTF-8 text.
+pub fn extract_text(path: &Path) -> Result<ExtractedDoc, ExtractError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    let fallback_title = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "untitled".to_string());
+
+    let doc = match ext.as_str() {
+        "html" | "htm" => extract_html(&std::fs::read_to_string(path)?, &fallback_title),
+        "pdf" => extract_pdf_file(path, &fallback_title)?,
+        _ => ExtractedDoc {
+            title: fallback_title,
+            content: std::fs::read_to_string(path)?,
+            format: DocFormat::Text,
+            headings: Vec::new(),
+        },
+    };
+
+    let chars = doc.content.chars().filter(|c| c.is_alphanumeric()).count();
+    if doc.format != DocFormat::Text && chars < MIN_TEXT_CHARS {
+        return Err(ExtractError::Skipped(format!(
+            "only {} characters of text extracted (scanned or image-only?)",
+            chars
+        )));
+    }
+    Ok(doc)
+}
+
+#[cfg(feature = "pdf")]
+fn extract_pdf_file(path: &Path, fallback_title: &str) -> Result<ExtractedDoc, ExtractError> {
+    let bytes = std::fs::read(path)?;
+    if !bytes.starts_with(b"%PDF") {
+        return Err(ExtractError::Skipped("not a PDF file".to_string()));
+    }
+    Ok(pdf::extract(&bytes, fallback_title))
+}
+
+#[cfg(not(feature = "pdf"))]
+fn extract_pdf_file(_path: &Path, _fallback_title: &str) -> Result<ExtractedDoc, ExtractError> {
+    Err(ExtractError::Unsupported(
+        "PDF extraction requires the `pdf` feature".to_string(),
+    ))
+}
+
+/// Extract text from an HTML document.
+pub fn extract_html(html: &str, fallback_title: &str) -> ExtractedDoc {
+    let mut text = String::new();
+    let mut title: Option<String> = None;
+    let mut headings = Vec::new();
+    let mut heading: Option<String> = None;
+    let mut in_title = false;
+    let mut title_buf = String::new();
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            continue;
+        }
+        if rest.starts_with('<') {
+            let Some(end) = rest.find('>') else {
+                break;
+            };
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+
+            let closing = tag.starts_with('/');
+            let name: String = tag
+                .trim_start_matches('/')
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_ascii_lowercase();
+
+            match name.as_str() {
+                // Skip everything up to the matching close tag
+                "script" | "style" | "noscript" | "template" if !closing => {
+                    let close = format!("</{}", name);
+                    rest = find_ignore_case(rest, &close).map_or("", |i| {
+                        let after = &rest[i..];
+                        after.find('>').map_or("", |j| &after[j + 1..])
+                    });
+                }
+                "title" => {
+                    in_title = !closing;
+                    if closing && title.is_none() {
+                        title = Some(collapse_whitespace(&title_buf));
+                    }
+                }
+                "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                    if closing {
+                        if let Some(h) = heading.take() {
+                            let h = collapse_whitespace(&h);
+                            if !h.is_empty() {
+                                headings.push(h);
+                            }
+                        }
+                    } else {
+                        heading = Some(String::new());
+                    }
+                    text.push('\n');
+                }
+                "p" | "div" | "br" | "li" | "tr" | "section" | "article" | "header" | "footer"
+                | "blockquote" | "pre" | "table" | "ul" | "ol" => text.push('\n'),
+                _ => text.push(' '),
+            }
+            continue;
+        }
+
+        let end = rest.find('<').unwrap_or(rest.len());
+        let chunk = decode_entities(&rest[..end]);
+        rest = &rest[end..];
+        if in_title {
+            title_buf.push_str(&chunk);
+        } else {
+            if let Some(h) = heading.as_mut() {
+                h.push_str(&chunk);
+            }
+            text.push_str(&chunk);
+        }
+    }
+
+    let content = text
+        .lines()
+        .map(collapse_whitespace)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let title = title
+        .filter(|t| !t.is_empty())
+        .or_else(|| headings.first().cloned())
+        .unwrap_or_else(|| fallback_title.to_string());
+
+    ExtractedDoc {
+        title,
+        content,
+        format: DocFormat::Html,
+        headings,
+    }
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .to_ascii_lowercase()
+        .find(&needle.to_ascii_lowercase())
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            ch.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Minimal PDF text-layer extraction.
+///
+/// Reads the document title from the info dictionary and the text shown by
+/// `Tj`/`TJ`/`'`/`"` operators in uncompressed or Flate-compressed content
+/// streams. Fonts with custom encodings (CID fonts) are not decoded; such
+/// documents usually come out short and are reported as skipped.
+#[cfg(feature = "pdf")]
+mod pdf {
+    use super::{DocFormat, ExtractedDoc};
+    use std::io::Read;
+
+    pub(super) fn extract(bytes: &[u8], fallback_title: &str) -> ExtractedDoc {
+        let title = info_title(bytes)
+            .map(|t| super::collapse_whitespace(&t))
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| fallback_title.to_string());
+
+        let mut content = String::new();
+        for (dict, data) in streams(bytes) {
+            if !is_content_stream(dict) {
+                continue;
+            }
+            let decoded = if contains(dict, b"/FlateDecode") {
+                let mut out = Vec::new();
+                match flate2::read::ZlibDecoder::new(data).read_to_end(&mut out) {
+                    Ok(_) => out,
+                    Err(_) => continue,
+                }
+            } else if contains(dict, b"/Filter") {
+                continue;
+            } else {
+                data.to_vec()
+            };
+            let text = show_text(&decoded);
+            if !text.trim().is_empty() {
+                content.push_str(text.trim());
+                content.push('\n');
+            }
+        }
+
+        ExtractedDoc {
+            title,
+            content: content.trim_end().to_string(),
+            format: DocFormat::Pdf,
+            headings: Vec::new(),
+        }
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        find(haystack, needle, 0).is_some()
+    }
+
+    fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+        haystack
+            .get(from..)?
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .map(|i| i + from)
+    }
+
+    fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).rposition(|w| w == needle)
+    }
+
+    /// Streams other than page content: fonts, images, xref and object streams.
+    fn is_content_stream(dict: &[u8]) -> bool {
+        ![
+            &b"/Subtype"[..],
+            b"/Length1",
+            b"/Type /XRef",
+            b"/Type/XRef",
+            b"/Type /ObjStm",
+            b"/Type/ObjStm",
+            b"/Type /Metadata",
+            b"/Type/Metadata",
+        ]
+        .iter()
+        .any(|marker| contains(dict, marker))
+    }
+
+    /// Yield `(dictionary, raw data)` for every stream object.
+    fn streams(bytes: &[u8]) -> Vec<(&[u8], &[u8])> {
+        let mut out = Vec::new();
+        let mut pos = 0;
+        while let Some(start) = find(bytes, b"stream", pos) {
+            // Skip the "stream" inside "endstream"
+            if start >= 3 && &bytes[start - 3..start] == b"end" {
+                pos = start + 6;
+                continue;
+            }
+            let mut data_start = start + 6;
+            if bytes.get(data_start) == Some(&b'\r') {
+                data_start += 1;
+            }
+            if bytes.get(data_start) == Some(&b'\n') {
+                data_start += 1;
+            }
+            let Some(end) = find(bytes, b"endstream", data_start) else {
+                break;
+            };
+            let dict_start = rfind(&bytes[..start], b"obj").map_or(0, |i| i + 3);
+            let data = &bytes[data_start..end];
+            let data = data.strip_suffix(b"\n").unwrap_or(data);
+            let data = data.strip_suffix(b"\r").unwrap_or(data);
+            out.push((&bytes[dict_start..start], data));
+            pos = end + 9;
+        }
+        out
+    }
+
+    /// The `/Title` entry of the document info dictionary.
+    fn info_title(bytes: &[u8]) -> Option<String> {
+        let at = find(bytes, b"/Title", 0)? + 6;
+        let rest = &bytes[at..];
+        let skip = rest.iter().take_while(|b| b.is_ascii_whitespace()).count();
+        let rest = &rest[skip..];
+        let raw = match rest.first()? {
+            b'(' => literal_string(rest).0,
+            b'<' => hex_string(rest).0,
+            _ => return None,
+        };
+        Some(decode_text_string(&raw))
+    }
+
+    /// PDF text strings are PDFDocEncoding (≈ Latin-1) or UTF-16BE with a BOM.
+    fn decode_text_string(raw: &[u8]) -> String {
+        if let Some(utf16) = raw.strip_prefix(&[0xFE, 0xFF]) {
+            let units: Vec<u16> = utf16
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            return String::from_utf16_lossy(&units);
+        }
+        raw.iter().map(|&b| b as char).collect()
+    }
+
+    /// Parse a `( ... )` literal starting at `s[0]`; returns bytes and length consumed.
+    fn literal_string(s: &[u8]) -> (Vec<u8>, usize) {
+        let mut out = Vec::new();
+        let mut depth = 0;
+        let mut i = 0;
+        while i < s.len() {
+            let b = s[i];
+            match b {
+                b'(' => {
+                    if depth > 0 {
+                        out.push(b);
+                    }
+                    depth += 1;
+                }
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return (out, i + 1);
+                    }
+                    out.push(b);
+                }
+                b'\\' => {
+                    i += 1;
+                    match s.get(i) {
+                        Some(b'n') => out.push(b'\n'),
+                        Some(b'r') => out.push(b'\r'),
+                        Some(b't') => out.push(b'\t'),
+                        Some(b'b') | Some(b'f') => {}
+                        Some(d @ b'0'..=b'7') => {
+                            let mut value = (d - b'0') as u32;
+                            for _ in 0..2 {
+                                match s.get(i + 1) {
+                                    Some(n @ b'0'..=b'7') => {
+                                        value = value * 8 + (n - b'0') as u32;
+                                        i += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            out.push(value as u8);
+                        }
+                        Some(b'\r') | Some(b'\n') => {}
+                        Some(&other) => out.push(other),
+                        None => break,
+                    }
+                }
+                _ => out.push(b),
+            }
+            i += 1;
+        }
+        (out, s.len())
+    }
+
+    /// Parse a `< ... >` hex string starting at `s[0]`.
+    fn hex_string(s: &[u8]) -> (Vec<u8>, usize) {
+        let end = s.iter().position(|&b| b == b'>').unwrap_or(s.len());
+        let digits: Vec<u8> = s[1..end]
+            .iter()
+            .filter_map(|&b| (b as char).to_digit(16).map(|d| d as u8))
+            .collect();
+        let bytes = digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+            .collect();
+        (bytes, (end + 1).min(s.len()))
+    }
+
+    /// Collect the text shown by a content stream's text operators.
+    fn show_text(content: &[u8]) -> String {
+        let mut out = String::new();
+        let mut pending: Vec<Vec<u8>> = Vec::new();
+        let mut i = 0;
+        let mut operator = Vec::new();
+
+        while i < content.len() {
+            let b = content[i];
+            match b {
+                b'(' => {
+                    let (s, used) = literal_string(&content[i..]);
+                    pending.push(s);
+                    i += used;
+                    continue;
+                }
+                b'<' if content.get(i + 1) != Some(&b'<') => {
+                    let (s, used) = hex_string(&content[i..]);
+                    pending.push(s);
+                    i += used;
+                    continue;
+                }
+                b'[' => {}
+                b']' => {}
+                b'-' | b'.' | b'0'..=b'9' => {
+                    // Large negative kerning inside TJ arrays marks a word gap
+                    let start = i;
+                    while i < content.len() && matches!(content[i], b'-' | b'.' | b'0'..=b'9') {
+                        i += 1;
+                    }
+                    let number: f64 = std::str::from_utf8(&content[start..i])
+                        .ok()
+                        .and_then(|n| n.parse().ok())
+                        .unwrap_or(0.0);
+                    if number < -200.0 && !pending.is_empty() {
+                        pending.push(b" ".to_vec());
+                    }
+                    continue;
+                }
+                b if b.is_ascii_alphabetic() || b == b'\'' || b == b'"' || b == b'*' => {
+                    operator.push(b);
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            if !operator.is_empty() {
+                apply_operator(&operator, &mut pending, &mut out);
+                operator.clear();
+            }
+            i += 1;
+        }
+        if !operator.is_empty() {
+            apply_operator(&operator, &mut pending, &mut out);
+        }
+        out
+    }
+
+    fn apply_operator(op: &[u8], pending: &mut Vec<Vec<u8>>, out: &mut String) {
+        match op {
+            b"Tj" | b"TJ" => {
+                for s in pending.drain(..) {
+                    out.extend(
+                        s.iter()
+                            .filter(|b| !b.is_ascii_control())
+                            .map(|&b| b as char),
+                    );
+                }
+            }
+            b"'" | b"\"" => {
+                out.push('\n');
+                for s in pending.drain(..) {
+                    out.extend(
+                        s.iter()
+                            .filter(|b| !b.is_ascii_control())
+                            .map(|&b| b as char),
+                    );
+                }
+            }
+            b"Td" | b"TD" | b"T*" | b"Tm" => {
+                if !out.ends_with(char::is_whitespace) && !out.is_empty() {
+                    out.push(' ');
+                }
+                pending.clear();
+            }
+            b"ET" => {
+                if !out.ends_with('\n') && !out.is_empty() {
+                    out.push('\n');
+                }
+                pending.clear();
+            }
+            _ => pending.clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn html_strips_markup_and_keeps_headings() {
+        let doc = extract_text(&fixture("membrane.html")).unwrap();
+        assert_eq!(doc.format, DocFormat::Html);
+        assert_eq!(doc.title, "The Cell Membrane");
+        assert_eq!(
+            doc.headings,
+            vec!["Membrane Structure", "Transport Proteins"]
+        );
+        assert!(doc.content.contains("phospholipid bilayer"));
+        assert!(doc.content.contains("sodium & potassium"));
+        assert!(!doc.content.contains("trackVisitor"));
+        assert!(!doc.content.contains("font-family"));
+        assert!(!doc.content.contains('<'));
+    }
+
+    #[test]
+    fn html_title_falls_back_to_heading_then_filename() {
+        let doc = extract_html("<h1>Ribosomes</h1><p>translate mRNA</p>", "notes");
+        assert_eq!(doc.title, "Ribosomes");
+        let doc = extract_html("<p>translate mRNA</p>", "notes");
+        assert_eq!(doc.title, "notes");
+    }
+
+    #[test]
+    fn plain_text_is_read_as_is() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("short_note.txt");
+        std::fs::write(&path, "tiny").unwrap();
+        let doc = extract_text(&path).unwrap();
+        assert_eq!(doc.title, "short_note");
+        assert_eq!(doc.content, "tiny");
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn pdf_text_and_title_are_extracted() {
+        let doc = extract_text(&fixture("mitochondria.pdf")).unwrap();
+        assert_eq!(doc.format, DocFormat::Pdf);
+        assert_eq!(doc.title, "Mitochondria Overview");
+        assert!(doc.content.contains("Mitochondria produce ATP"));
+        assert!(doc.content.contains("electron transport chain"));
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn scanned_pdf_is_skipped_with_reason() {
+        match extract_text(&fixture("scanned.pdf")) {
+            Err(ExtractError::Skipped(reason)) => assert!(reason.contains("characters")),
+            other => panic!("expected skip, got {:?}", other),
+        }
+    }
+
+    #[cfg(not(feature = "pdf"))]
+    #[test]
+    fn pdf_requires_feature() {
+        assert!(matches!(
+            extract_text(&fixture("mitochondria.pdf")),
+            Err(ExtractError::Unsupported(_))
+        ));
+    }
+}
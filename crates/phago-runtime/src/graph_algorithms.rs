@@ -0,0 +1,789 @@
+//! Graph algorithms over the knowledge graph.
+//!
+//! PageRank treats the Hebbian graph as a random walk: from a node, the
+//! walker follows an edge with probability proportional to its weight, and
+//! with probability `1 - damping` teleports instead. Global PageRank
+//! teleports uniformly; personalized PageRank teleports back to a set of
+//! seed nodes (e.g. the nodes a query matched), so scores measure how
+//! reachable each node is from the seeds.
+//!
+//! The walk reads edges through [`TopologyGraph::neighbors`] on every
+//! iteration rather than copying the graph, so it works on any backend.
+//! Only per-node score vectors are allocated.
+//!
+//! [`find_path`] connects two concepts by label. What "shortest" means is
+//! chosen with [`PathCost`]: the strongest chain of associations, the
+//! fewest hops, or a sum of per-edge costs. Alternatives come from Yen's
+//! k-shortest-paths algorithm, and every path carries the weight and
+//! co-activation count of each edge so callers can explain it.
+
+use phago_core::topology::TopologyGraph;
+use phago_core::types::NodeId;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+/// PageRank settings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PageRankConfig {
+    /// Probability of following an edge rather than teleporting (default: 0.85).
+    #[serde(default = "default_damping")]
+    pub damping: f64,
+    /// Stop once the total score change in an iteration falls below this
+    /// (default: 1e-6).
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+    /// Stop after this many iterations even if not converged (default: 100).
+    #[serde(default = "default_max_iterations")]
+    pub max_iterations: usize,
+}
+
+fn default_damping() -> f64 {
+    0.85
+}
+
+fn default_tolerance() -> f64 {
+    1e-6
+}
+
+fn default_max_iterations() -> usize {
+    100
+}
+
+impl Default for PageRankConfig {
+    fn default() -> Self {
+        Self {
+            damping: default_damping(),
+            tolerance: default_tolerance(),
+            max_iterations: default_max_iterations(),
+        }
+    }
+}
+
+/// Weighted PageRank over the whole graph, highest first.
+pub fn pagerank(graph: &dyn TopologyGraph, config: &PageRankConfig) -> Vec<(NodeId, f64)> {
+    sorted(pagerank_within(graph, &graph.all_nodes(), &[], config))
+}
+
+/// Weighted PageRank teleporting to `seeds`, highest first.
+///
+/// Without seeds (or if none of them is in the graph) this is global
+/// PageRank. Nodes unreachable from the seeds score 0.
+pub fn personalized_pagerank(
+    graph: &dyn TopologyGraph,
+    seeds: &[NodeId],
+    config: &PageRankConfig,
+) -> Vec<(NodeId, f64)> {
+    sorted(pagerank_within(graph, &graph.all_nodes(), seeds, config))
+}
+
+/// Weighted (personalized) PageRank over the subgraph induced by `nodes`.
+///
+/// Edges leaving `nodes` are ignored. Seeds outside `nodes` are dropped;
+/// with no seeds left, teleports are uniform. Scores sum to 1.
+pub fn pagerank_within(
+    graph: &dyn TopologyGraph,
+    nodes: &[NodeId],
+    seeds: &[NodeId],
+    config: &PageRankConfig,
+) -> HashMap<NodeId, f64> {
+    let n = nodes.len();
+    if n == 0 {
+        return HashMap::new();
+    }
+    let index: HashMap<NodeId, usize> = nodes.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let damping = config.damping.clamp(0.0, 1.0);
+
+    // Teleport distribution
+    let mut teleport = vec![0.0; n];
+    for seed in seeds {
+        if let Some(&i) = index.get(seed) {
+            teleport[i] += 1.0;
+        }
+    }
+    let seed_mass: f64 = teleport.iter().sum();
+    if seed_mass > 0.0 {
+        teleport.iter_mut().for_each(|t| *t /= seed_mass);
+    } else {
+        teleport.fill(1.0 / n as f64);
+    }
+
+    // Total outgoing weight per node, within the subgraph
+    let out_weight: Vec<f64> = nodes
+        .iter()
+        .map(|id| {
+            graph
+                .neighbors(id)
+                .iter()
+                .filter(|(other, _)| index.contains_key(other))
+                .map(|(_, e)| e.weight.max(0.0))
+                .sum()
+        })
+        .collect();
+
+    let mut rank = teleport.clone();
+    let mut next = vec![0.0; n];
+    for _ in 0..config.max_iterations {
+        next.fill(0.0);
+        // Nodes without outgoing weight hand their rank to the teleport
+        // distribution, so disconnected and isolated nodes leak nothing
+        let mut dangling = 0.0;
+        for (i, id) in nodes.iter().enumerate() {
+            if out_weight[i] <= 0.0 {
+                dangling += rank[i];
+                continue;
+            }
+            let share = damping * rank[i] / out_weight[i];
+            for (other, edge) in graph.neighbors(id) {
+                if let Some(&j) = index.get(&other) {
+                    next[j] += share * edge.weight.max(0.0);
+                }
+            }
+        }
+        let redistributed = (1.0 - damping) + damping * dangling;
+        for (value, t) in next.iter_mut().zip(&teleport) {
+            *value += redistributed * t;
+        }
+
+        let change: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        std::mem::swap(&mut rank, &mut next);
+        if change < config.tolerance {
+            break;
+        }
+    }
+
+    nodes.iter().copied().zip(rank).collect()
+}
+
+/// Edge weights are floored here so that a near-zero edge has a large but
+/// finite cost.
+const MIN_PATH_WEIGHT: f64 = 0.001;
+
+/// How the cost of a path between concepts is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathCost {
+    /// Maximize the product of edge weights: each edge costs
+    /// `-ln(weight)`, so a detour along strong associations beats a
+    /// shortcut over a weak one.
+    #[default]
+    StrongestPath,
+    /// Fewest edges, whatever their weight.
+    FewestHops,
+    /// Sum of a per-edge cost derived from its weight.
+    WeightedCost { transform: WeightTransform },
+}
+
+/// Per-edge cost for [`PathCost::WeightedCost`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightTransform {
+    /// `1 / weight`, as in [`TopologyGraph::shortest_path`].
+    Inverse,
+    /// `1 - weight`, plus a small constant so every hop costs something.
+    Complement,
+}
+
+impl PathCost {
+    /// Cost of traversing an edge of the given weight.
+    pub fn edge_cost(&self, weight: f64) -> f64 {
+        let weight = weight.clamp(MIN_PATH_WEIGHT, 1.0);
+        match self {
+            PathCost::StrongestPath => -weight.ln(),
+            PathCost::FewestHops => 1.0,
+            PathCost::WeightedCost {
+                transform: WeightTransform::Inverse,
+            } => 1.0 / weight,
+            PathCost::WeightedCost {
+                transform: WeightTransform::Complement,
+            } => 1.0 - weight + MIN_PATH_WEIGHT,
+        }
+    }
+}
+
+impl std::str::FromStr for PathCost {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strongest" => Ok(PathCost::StrongestPath),
+            "hops" => Ok(PathCost::FewestHops),
+            "inverse" => Ok(PathCost::WeightedCost {
+                transform: WeightTransform::Inverse,
+            }),
+            "complement" => Ok(PathCost::WeightedCost {
+                transform: WeightTransform::Complement,
+            }),
+            other => Err(format!(
+                "Unknown path cost '{other}'. Use: strongest, hops, inverse, complement"
+            )),
+        }
+    }
+}
+
+/// One edge of a [`ConceptPath`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PathHop {
+    pub from: NodeId,
+    pub from_label: String,
+    pub to: NodeId,
+    pub to_label: String,
+    pub weight: f64,
+    pub co_activations: u64,
+}
+
+/// A path between two concepts, with the edges that make it up.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConceptPath {
+    pub nodes: Vec<NodeId>,
+    pub labels: Vec<String>,
+    pub hops: Vec<PathHop>,
+    /// Total cost under the [`PathCost`] the path was found with.
+    pub cost: f64,
+    /// Product of the edge weights (1 for a path without edges).
+    pub strength: f64,
+}
+
+impl ConceptPath {
+    /// The weakest edge's weight, if the path has edges.
+    pub fn weakest_weight(&self) -> Option<f64> {
+        self.hops.iter().map(|h| h.weight).reduce(f64::min)
+    }
+}
+
+/// Why [`find_path`] returned no path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum PathError {
+    /// No concept label matches.
+    UnknownConcept { label: String },
+    /// Both concepts exist but are not connected. The sizes are those of
+    /// the components holding each side's matching nodes.
+    NoPath {
+        from: String,
+        to: String,
+        from_component: usize,
+        to_component: usize,
+    },
+}
+
+impl std::fmt::Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::UnknownConcept { label } => write!(f, "No concept matches '{}'", label),
+            PathError::NoPath {
+                from,
+                to,
+                from_component,
+                to_component,
+            } => write!(
+                f,
+                "No path between '{}' and '{}': they lie in separate components \
+                 of {} and {} concepts",
+                from, to, from_component, to_component
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Up to `k` cheapest paths between the concepts labelled `from_label`
+/// and `to_label`, cheapest first.
+///
+/// A label resolves to every node with exactly that label
+/// (case-insensitive), or failing that to every node whose label
+/// contains it; a path may start and end at any of them.
+pub fn find_path(
+    graph: &dyn TopologyGraph,
+    from_label: &str,
+    to_label: &str,
+    cost: PathCost,
+    k: usize,
+) -> Result<Vec<ConceptPath>, PathError> {
+    let sources = resolve_label(graph, from_label)?;
+    let targets = resolve_label(graph, to_label)?;
+
+    let paths = k_cheapest_paths(graph, &sources, &targets, cost, k.max(1));
+    if paths.is_empty() {
+        return Err(PathError::NoPath {
+            from: from_label.to_string(),
+            to: to_label.to_string(),
+            from_component: component_size(graph, &sources),
+            to_component: component_size(graph, &targets),
+        });
+    }
+    Ok(paths
+        .into_iter()
+        .map(|(nodes, cost)| explain_path(graph, nodes, cost))
+        .collect())
+}
+
+fn resolve_label(graph: &dyn TopologyGraph, label: &str) -> Result<Vec<NodeId>, PathError> {
+    let mut nodes = graph.find_nodes_by_exact_label(label);
+    if nodes.is_empty() {
+        nodes = graph.find_nodes_by_label(label);
+    }
+    if nodes.is_empty() {
+        return Err(PathError::UnknownConcept {
+            label: label.to_string(),
+        });
+    }
+    nodes.sort();
+    Ok(nodes)
+}
+
+/// Nodes reachable from any of `starts`, the starts included.
+fn component_size(graph: &dyn TopologyGraph, starts: &[NodeId]) -> usize {
+    let mut seen: HashSet<NodeId> = starts.iter().copied().collect();
+    let mut queue: VecDeque<NodeId> = starts.iter().copied().collect();
+    while let Some(node) = queue.pop_front() {
+        for (next, _) in graph.neighbors(&node) {
+            if seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen.len()
+}
+
+fn edge_weight(graph: &dyn TopologyGraph, a: &NodeId, b: &NodeId) -> Option<f64> {
+    graph
+        .get_edge(a, b)
+        .or_else(|| graph.get_edge(b, a))
+        .map(|e| e.weight)
+}
+
+fn path_cost(graph: &dyn TopologyGraph, nodes: &[NodeId], cost: PathCost) -> f64 {
+    nodes
+        .windows(2)
+        .map(|w| cost.edge_cost(edge_weight(graph, &w[0], &w[1]).unwrap_or(0.0)))
+        .sum()
+}
+
+fn explain_path(graph: &dyn TopologyGraph, nodes: Vec<NodeId>, cost: f64) -> ConceptPath {
+    let label = |id: &NodeId| {
+        graph
+            .get_node(id)
+            .map(|n| n.label.clone())
+            .unwrap_or_default()
+    };
+    let hops: Vec<PathHop> = nodes
+        .windows(2)
+        .map(|w| {
+            let edge = graph
+                .get_edge(&w[0], &w[1])
+                .or_else(|| graph.get_edge(&w[1], &w[0]));
+            PathHop {
+                from: w[0],
+                from_label: label(&w[0]),
+                to: w[1],
+                to_label: label(&w[1]),
+                weight: edge.map_or(0.0, |e| e.weight),
+                co_activations: edge.map_or(0, |e| e.co_activations),
+            }
+        })
+        .collect();
+    ConceptPath {
+        labels: nodes.iter().map(label).collect(),
+        strength: hops.iter().map(|h| h.weight).product(),
+        nodes,
+        hops,
+        cost,
+    }
+}
+
+/// Yen's algorithm over undirected edges, with several possible start and
+/// end nodes.
+fn k_cheapest_paths(
+    graph: &dyn TopologyGraph,
+    sources: &[NodeId],
+    targets: &[NodeId],
+    cost: PathCost,
+    k: usize,
+) -> Vec<(Vec<NodeId>, f64)> {
+    let targets: HashSet<NodeId> = targets.iter().copied().collect();
+    let no_nodes = HashSet::new();
+    let no_edges = HashSet::new();
+    let Some(first) = cheapest_path(graph, sources, &targets, cost, &no_nodes, &no_edges) else {
+        return Vec::new();
+    };
+
+    let mut found = vec![first];
+    let mut candidates: Vec<(Vec<NodeId>, f64)> = Vec::new();
+    while found.len() < k {
+        let last = found[found.len() - 1].0.clone();
+        // The first `root_len` nodes are kept; the rest is searched again
+        // from the last kept node (or from another source when none is)
+        for root_len in 0..last.len() {
+            let root = &last[..root_len];
+            let sharing_root = found
+                .iter()
+                .filter(|(p, _)| p.len() > root_len && &p[..root_len] == root);
+            let spur = if root_len == 0 {
+                let used: HashSet<NodeId> = sharing_root.map(|(p, _)| p[0]).collect();
+                let starts: Vec<NodeId> = sources
+                    .iter()
+                    .filter(|s| !used.contains(s))
+                    .copied()
+                    .collect();
+                cheapest_path(graph, &starts, &targets, cost, &no_nodes, &no_edges)
+            } else {
+                let spur_node = root[root_len - 1];
+                let banned_edges: HashSet<(NodeId, NodeId)> = sharing_root
+                    .map(|(p, _)| (p[root_len - 1], p[root_len]))
+                    .collect();
+                let banned_nodes: HashSet<NodeId> = root[..root_len - 1].iter().copied().collect();
+                cheapest_path(
+                    graph,
+                    &[spur_node],
+                    &targets,
+                    cost,
+                    &banned_nodes,
+                    &banned_edges,
+                )
+                .map(|(spur, spur_cost)| {
+                    let mut nodes = root[..root_len - 1].to_vec();
+                    nodes.extend(spur);
+                    (nodes, path_cost(graph, root, cost) + spur_cost)
+                })
+            };
+            if let Some(candidate) = spur {
+                let known = found
+                    .iter()
+                    .chain(&candidates)
+                    .any(|(p, _)| *p == candidate.0);
+                if !known {
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        let Some(best) = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                a.1.partial_cmp(&b.1)
+                    .unwrap_or(Ordering::Equal)
+                    .then(a.0.len().cmp(&b.0.len()))
+            })
+            .map(|(i, _)| i)
+        else {
+            break;
+        };
+        found.push(candidates.swap_remove(best));
+    }
+    found
+}
+
+/// Dijkstra from any of `starts` to the nearest of `targets`, avoiding
+/// `banned_nodes` and the undirected `banned_edges`.
+fn cheapest_path(
+    graph: &dyn TopologyGraph,
+    starts: &[NodeId],
+    targets: &HashSet<NodeId>,
+    cost: PathCost,
+    banned_nodes: &HashSet<NodeId>,
+    banned_edges: &HashSet<(NodeId, NodeId)>,
+) -> Option<(Vec<NodeId>, f64)> {
+    #[derive(PartialEq)]
+    struct State {
+        cost: f64,
+        node: NodeId,
+    }
+    impl Eq for State {}
+    impl PartialOrd for State {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for State {
+        fn cmp(&self, other: &Self) -> Ordering {
+            // Min-heap, ties broken by node id for stable results
+            other
+                .cost
+                .partial_cmp(&self.cost)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| other.node.cmp(&self.node))
+        }
+    }
+
+    let mut dist: HashMap<NodeId, f64> = HashMap::new();
+    let mut prev: HashMap<NodeId, NodeId> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for &start in starts {
+        if graph.get_node(&start).is_some() && !banned_nodes.contains(&start) {
+            dist.insert(start, 0.0);
+            heap.push(State {
+                cost: 0.0,
+                node: start,
+            });
+        }
+    }
+
+    while let Some(State { cost: so_far, node }) = heap.pop() {
+        if so_far > *dist.get(&node).unwrap_or(&f64::INFINITY) {
+            continue;
+        }
+        if targets.contains(&node) {
+            let mut path = vec![node];
+            let mut current = node;
+            while let Some(&p) = prev.get(&current) {
+                path.push(p);
+                current = p;
+            }
+            path.reverse();
+            return Some((path, so_far));
+        }
+
+        for (next, edge) in graph.neighbors(&node) {
+            if banned_nodes.contains(&next)
+                || banned_edges.contains(&(node, next))
+                || banned_edges.contains(&(next, node))
+            {
+                continue;
+            }
+            let next_cost = so_far + cost.edge_cost(edge.weight);
+            if next_cost < *dist.get(&next).unwrap_or(&f64::INFINITY) {
+                dist.insert(next, next_cost);
+                prev.insert(next, node);
+                heap.push(State {
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+    None
+}
+
+fn sorted(scores: HashMap<NodeId, f64>) -> Vec<(NodeId, f64)> {
+    let mut scores: Vec<(NodeId, f64)> = scores.into_iter().collect();
+    scores.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology_impl::PetTopologyGraph;
+    use phago_core::types::{EdgeData, NodeData, NodeType, Position};
+
+    fn add_node(graph: &mut PetTopologyGraph, label: &str) -> NodeId {
+        graph.add_node(NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        })
+    }
+
+    fn connect(graph: &mut PetTopologyGraph, a: NodeId, b: NodeId, weight: f64) {
+        graph
+            .set_edge(
+                a,
+                b,
+                EdgeData {
+                    weight,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    fn score(scores: &[(NodeId, f64)], id: NodeId) -> f64 {
+        scores.iter().find(|(n, _)| *n == id).unwrap().1
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn star_converges_to_closed_form() {
+        // Center c and leaves l: c = 0.15/4 + 0.85 * 3l, l = 0.15/4 + 0.85 * c/3
+        let mut graph = PetTopologyGraph::new();
+        let hub = add_node(&mut graph, "cell");
+        let leaves: Vec<NodeId> = ["membrane", "protein", "nucleus"]
+            .iter()
+            .map(|l| add_node(&mut graph, l))
+            .collect();
+        for &leaf in &leaves {
+            connect(&mut graph, hub, leaf, 0.5);
+        }
+
+        let scores = pagerank(&graph, &PageRankConfig::default());
+        assert_eq!(scores[0].0, hub);
+        assert_close(score(&scores, hub), 0.479730);
+        for &leaf in &leaves {
+            assert_close(score(&scores, leaf), 0.173423);
+        }
+        assert_close(scores.iter().map(|(_, s)| s).sum(), 1.0);
+    }
+
+    #[test]
+    fn edge_weights_steer_the_walk() {
+        // b = 0.05 + 0.85(a + c), a = 0.05 + 0.85 * 0.9b, c = 0.05 + 0.85 * 0.1b
+        let mut graph = PetTopologyGraph::new();
+        let a = add_node(&mut graph, "membrane");
+        let b = add_node(&mut graph, "cell");
+        let c = add_node(&mut graph, "quasar");
+        connect(&mut graph, b, a, 0.9);
+        connect(&mut graph, b, c, 0.1);
+
+        let scores = pagerank(&graph, &PageRankConfig::default());
+        assert_close(score(&scores, b), 0.486486);
+        assert_close(score(&scores, a), 0.422162);
+        assert_close(score(&scores, c), 0.091351);
+    }
+
+    #[test]
+    fn disconnected_components_keep_their_mass() {
+        let mut graph = PetTopologyGraph::new();
+        let a = add_node(&mut graph, "cell");
+        let b = add_node(&mut graph, "membrane");
+        let isolated = add_node(&mut graph, "quasar");
+        connect(&mut graph, a, b, 1.0);
+
+        // The isolated node's rank is teleported: i = 0.05 + 0.85 * i / 3
+        let scores = pagerank(&graph, &PageRankConfig::default());
+        assert!(scores.iter().all(|(_, s)| s.is_finite()));
+        assert_close(score(&scores, isolated), 0.069767);
+        assert_close(score(&scores, a), 0.465116);
+        assert_close(score(&scores, b), 0.465116);
+
+        // Seeded on one component, the other is unreachable
+        let personalized = personalized_pagerank(&graph, &[a], &PageRankConfig::default());
+        assert_eq!(score(&personalized, isolated), 0.0);
+        assert!(score(&personalized, a) > score(&personalized, b));
+        assert_close(personalized.iter().map(|(_, s)| s).sum(), 1.0);
+
+        // Seeds outside the graph fall back to global PageRank
+        let unseeded = personalized_pagerank(&graph, &[NodeId::new()], &PageRankConfig::default());
+        assert_close(score(&unseeded, isolated), 0.069767);
+    }
+
+    #[test]
+    fn iteration_cap_and_empty_graph() {
+        let graph = PetTopologyGraph::new();
+        assert!(pagerank(&graph, &PageRankConfig::default()).is_empty());
+
+        let mut graph = PetTopologyGraph::new();
+        let a = add_node(&mut graph, "cell");
+        let b = add_node(&mut graph, "membrane");
+        connect(&mut graph, a, b, 1.0);
+        let config = PageRankConfig {
+            max_iterations: 0,
+            ..Default::default()
+        };
+        // No iterations leaves the teleport distribution
+        let scores = pagerank(&graph, &config);
+        assert_eq!(score(&scores, a), 0.5);
+    }
+
+    /// cell -0.1- membrane directly, or cell -0.9- protein -0.9- channel
+    /// -0.9- membrane around; plus a separate quasar - pulsar pair.
+    fn detour_graph() -> (PetTopologyGraph, [NodeId; 4]) {
+        let mut graph = PetTopologyGraph::new();
+        let cell = add_node(&mut graph, "cell");
+        let membrane = add_node(&mut graph, "membrane");
+        let protein = add_node(&mut graph, "protein");
+        let channel = add_node(&mut graph, "ion channel");
+        connect(&mut graph, cell, membrane, 0.1);
+        connect(&mut graph, cell, protein, 0.9);
+        connect(&mut graph, protein, channel, 0.9);
+        connect(&mut graph, channel, membrane, 0.9);
+        let quasar = add_node(&mut graph, "quasar");
+        let pulsar = add_node(&mut graph, "pulsar");
+        connect(&mut graph, quasar, pulsar, 0.5);
+        (graph, [cell, membrane, protein, channel])
+    }
+
+    #[test]
+    fn strongest_path_and_fewest_hops_disagree() {
+        let (graph, [cell, membrane, protein, channel]) = detour_graph();
+
+        let strongest = find_path(&graph, "cell", "membrane", PathCost::StrongestPath, 1).unwrap();
+        assert_eq!(strongest[0].nodes, vec![cell, protein, channel, membrane]);
+        assert_close(strongest[0].strength, 0.729);
+        assert_close(strongest[0].cost, -0.729f64.ln());
+        assert_eq!(
+            strongest[0].labels,
+            ["cell", "protein", "ion channel", "membrane"]
+        );
+        assert_eq!(strongest[0].hops[1].from_label, "protein");
+        assert_eq!(strongest[0].hops[1].weight, 0.9);
+        assert_eq!(strongest[0].hops[1].co_activations, 1);
+
+        let fewest = find_path(&graph, "cell", "membrane", PathCost::FewestHops, 1).unwrap();
+        assert_eq!(fewest[0].nodes, vec![cell, membrane]);
+        assert_eq!(fewest[0].cost, 1.0);
+        assert_eq!(fewest[0].weakest_weight(), Some(0.1));
+
+        let inverse: PathCost = "inverse".parse().unwrap();
+        let weighted = find_path(&graph, "cell", "membrane", inverse, 1).unwrap();
+        assert_eq!(weighted[0].nodes.len(), 4);
+    }
+
+    #[test]
+    fn alternatives_come_cheapest_first() {
+        let (graph, [cell, membrane, ..]) = detour_graph();
+
+        let paths = find_path(&graph, "cell", "membrane", PathCost::StrongestPath, 3).unwrap();
+        assert_eq!(paths.len(), 2, "only two simple paths exist");
+        assert_eq!(paths[1].nodes, vec![cell, membrane]);
+        assert!(paths[0].cost < paths[1].cost);
+    }
+
+    #[test]
+    fn labels_resolve_to_every_matching_node() {
+        let (mut graph, [_, membrane, ..]) = detour_graph();
+        let other_cell = add_node(&mut graph, "Cell");
+        connect(&mut graph, other_cell, membrane, 1.0);
+
+        // Both "cell" nodes are candidate starts; the second is closer
+        let paths = find_path(&graph, "cell", "membrane", PathCost::StrongestPath, 1).unwrap();
+        assert_eq!(paths[0].nodes, vec![other_cell, membrane]);
+        // Without an exact match, labels containing the text are used
+        let paths = find_path(&graph, "channel", "membrane", PathCost::FewestHops, 1).unwrap();
+        assert_eq!(paths[0].labels, ["ion channel", "membrane"]);
+    }
+
+    #[test]
+    fn disconnected_and_unknown_concepts_are_explained() {
+        let (graph, _) = detour_graph();
+
+        let err = find_path(&graph, "cell", "quasar", PathCost::StrongestPath, 1).unwrap_err();
+        assert_eq!(
+            err,
+            PathError::NoPath {
+                from: "cell".into(),
+                to: "quasar".into(),
+                from_component: 4,
+                to_component: 2,
+            }
+        );
+        assert!(err.to_string().contains("components of 4 and 2 concepts"));
+
+        let err = find_path(&graph, "cell", "nebula", PathCost::FewestHops, 1).unwrap_err();
+        assert_eq!(
+            err,
+            PathError::UnknownConcept {
+                label: "nebula".into()
+            }
+        );
+    }
+}
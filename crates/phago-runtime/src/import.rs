@@ -0,0 +1,506 @@
+//! Triple import — bootstrapping a colony from curated knowledge.
+//!
+//! A colony learns concepts and how they relate by digesting documents,
+//! which takes many ticks before queries find much. Knowledge already
+//! curated as `subject, predicate, object` triples can go in directly:
+//! [`triples_from_csv`] and [`triples_from_turtle`] read a file into a
+//! [`TripleSet`], and [`Colony::import_triples`] adds it to the graph:
+//!
+//! - Every subject and object becomes a concept, or matches the concept
+//!   of the same label (under the colony's synonyms and label policy), as
+//!   a presented fragment would. New concepts are placed
+//!   deterministically: next to the others of their group when the CSV
+//!   has a grouping column, else at a point seeded from the label.
+//! - Every triple wires its subject to its object at the triple's weight,
+//!   or [`TENTATIVE_WEIGHT`] without one, labels the edge with the
+//!   predicate unless it already has a relation, and records the set's
+//!   source name in the edge's provenance. New edges start their
+//!   maturation period at import like agent wiring does, so pruning
+//!   leaves them alone until digestion had a chance to reinforce them.
+//!
+//! Importing is idempotent: an edge that already exists keeps the higher
+//! of its own weight and the triple's, so importing the same file again
+//! creates nothing and strengthens nothing. The [`ImportReport`] counts
+//! what was created and what matched.
+//!
+//! [`Colony::import_triples`]: crate::colony::Colony::import_triples
+
+use crate::rdf::{parse_turtle, Term, TurtleError, PHAGO_NS, RDFS_LABEL, RDF_TYPE, SKOS_NS};
+use phago_core::types::Position;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Weight of an imported edge without a weight of its own, that of a
+/// tentative edge wired by an agent.
+pub const TENTATIVE_WEIGHT: f64 = 0.1;
+
+/// Side of the square, centred on the origin, imported concepts and
+/// groups are laid out in.
+const LAYOUT_EXTENT: f64 = 100.0;
+
+/// Farthest a concept is placed from the centre of its group.
+const GROUP_RADIUS: f64 = 5.0;
+
+/// One imported statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triple {
+    pub subject: String,
+    pub predicate: String,
+    pub object: String,
+    /// Weight or confidence in (0, 1]; [`TENTATIVE_WEIGHT`] if `None`.
+    pub weight: Option<f64>,
+    /// Group the subject is laid out with.
+    pub group: Option<String>,
+}
+
+/// Triples read from one source.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TripleSet {
+    /// Name recorded as the provenance of the imported edges.
+    pub source: String,
+    pub triples: Vec<Triple>,
+    /// Rows or statements that were not read as triples, e.g. blank rows
+    /// or statements with a literal object.
+    pub skipped: usize,
+}
+
+/// How [`triples_from_csv`] reads a CSV file. Subject, predicate and
+/// object are its first three columns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CsvTripleConfig {
+    /// Field separator (default: `,`).
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// Whether the first row names the columns (default: true).
+    #[serde(default = "default_has_header")]
+    pub has_header: bool,
+    /// Column of each triple's weight or confidence in [0, 1], by header
+    /// name or 0-based index (default: none). Rows weighted 0 are skipped.
+    #[serde(default)]
+    pub weight_column: Option<String>,
+    /// Column grouping subjects that are laid out together, by header
+    /// name or 0-based index (default: none).
+    #[serde(default)]
+    pub group_column: Option<String>,
+    /// Provenance source recorded on the imported edges (default: the
+    /// file name).
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+impl Default for CsvTripleConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: default_delimiter(),
+            has_header: default_has_header(),
+            weight_column: None,
+            group_column: None,
+            source: None,
+        }
+    }
+}
+
+/// What [`Colony::import_triples`] changed.
+///
+/// [`Colony::import_triples`]: crate::colony::Colony::import_triples
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportReport {
+    /// Concepts created.
+    pub nodes_created: usize,
+    /// Distinct labels that matched a node already in the graph.
+    pub nodes_merged: usize,
+    /// Edges created.
+    pub edges_created: usize,
+    /// Triples whose edge already existed.
+    pub edges_merged: usize,
+    /// Triples not imported, e.g. with the same subject and object, and
+    /// rows the reader skipped.
+    pub skipped: usize,
+}
+
+/// Why a triple source could not be read.
+#[derive(Debug)]
+pub enum ImportError {
+    Io(std::io::Error),
+    /// A malformed CSV row, at its 1-based line.
+    Csv {
+        line: usize,
+        message: String,
+    },
+    Turtle(TurtleError),
+    /// A configured column that neither the header nor an index names.
+    UnknownColumn(String),
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::Io(e) => write!(f, "{}", e),
+            ImportError::Csv { line, message } => write!(f, "line {}: {}", line, message),
+            ImportError::Turtle(e) => write!(f, "{}", e),
+            ImportError::UnknownColumn(column) => write!(f, "No column '{}'", column),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<std::io::Error> for ImportError {
+    fn from(e: std::io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
+
+impl From<TurtleError> for ImportError {
+    fn from(e: TurtleError) -> Self {
+        ImportError::Turtle(e)
+    }
+}
+
+/// Read the triples of a CSV file.
+pub fn triples_from_csv(
+    path: impl AsRef<Path>,
+    config: &CsvTripleConfig,
+) -> Result<TripleSet, ImportError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    let (triples, skipped) = csv_triples(&text, config)?;
+    Ok(TripleSet {
+        source: config.source.clone().unwrap_or_else(|| file_name(path)),
+        triples,
+        skipped,
+    })
+}
+
+/// Read the triples of a Turtle file whose subjects and objects are
+/// IRIs. A resource's label is its `rdfs:label` or `skos:prefLabel`, else
+/// the IRI's last segment with `_` and `-` read as spaces; a predicate's
+/// is always its last segment. Type, label and literal statements and
+/// blank nodes are skipped.
+pub fn triples_from_turtle(path: impl AsRef<Path>) -> Result<TripleSet, ImportError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    let (triples, skipped) = turtle_triples(&text)?;
+    Ok(TripleSet {
+        source: file_name(path),
+        triples,
+        skipped,
+    })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    )
+}
+
+/// The position of a new concept labelled `label`, next to the other
+/// members of `group` if it has one. The same label and group always get
+/// the same position.
+pub fn layout_position(label: &str, group: Option<&str>) -> Position {
+    let point = |seed: u64| {
+        let unit = |bits: u64| (bits >> 11) as f64 / (1u64 << 53) as f64;
+        (
+            unit(seed),
+            unit(seed.rotate_left(32).wrapping_mul(0x9E37_79B9_7F4A_7C15)),
+        )
+    };
+    let (x, y) = point(fnv1a(&label.to_lowercase()));
+    match group {
+        None => Position::new((x - 0.5) * LAYOUT_EXTENT, (y - 0.5) * LAYOUT_EXTENT),
+        Some(group) => {
+            let (cx, cy) = point(fnv1a(&group.to_lowercase()));
+            let angle = x * std::f64::consts::TAU;
+            let distance = y.sqrt() * GROUP_RADIUS;
+            Position::new(
+                (cx - 0.5) * LAYOUT_EXTENT + angle.cos() * distance,
+                (cy - 0.5) * LAYOUT_EXTENT + angle.sin() * distance,
+            )
+        }
+    }
+}
+
+/// 64-bit FNV-1a, stable across runs and platforms unlike `std`'s hasher.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn csv_triples(text: &str, config: &CsvTripleConfig) -> Result<(Vec<Triple>, usize), ImportError> {
+    let mut rows = csv_rows(text, config.delimiter)?.into_iter();
+    let header = if config.has_header {
+        rows.next().map(|(_, fields)| fields)
+    } else {
+        None
+    };
+    let column = |spec: &Option<String>| -> Result<Option<usize>, ImportError> {
+        let Some(spec) = spec else {
+            return Ok(None);
+        };
+        let named = header.as_ref().and_then(|h| {
+            h.iter()
+                .position(|name| name.trim().eq_ignore_ascii_case(spec.trim()))
+        });
+        named
+            .or_else(|| spec.trim().parse().ok())
+            .map(Some)
+            .ok_or_else(|| ImportError::UnknownColumn(spec.clone()))
+    };
+    let weight_column = column(&config.weight_column)?;
+    let group_column = column(&config.group_column)?;
+
+    let mut triples = Vec::new();
+    let mut skipped = 0;
+    for (line, fields) in rows {
+        let field = |i: usize| fields.get(i).map(|f| f.trim()).filter(|f| !f.is_empty());
+        let (Some(subject), Some(predicate), Some(object)) = (field(0), field(1), field(2)) else {
+            skipped += 1;
+            continue;
+        };
+        let weight = match weight_column.and_then(field) {
+            Some(value) => match value.parse::<f64>() {
+                Ok(0.0) => {
+                    skipped += 1;
+                    continue;
+                }
+                Ok(w) if w > 0.0 && w <= 1.0 => Some(w),
+                _ => {
+                    return Err(ImportError::Csv {
+                        line,
+                        message: format!("weight '{}' is not a number in [0, 1]", value),
+                    })
+                }
+            },
+            None => None,
+        };
+        triples.push(Triple {
+            subject: subject.to_string(),
+            predicate: predicate.to_string(),
+            object: object.to_string(),
+            weight,
+            group: group_column.and_then(field).map(str::to_string),
+        });
+    }
+    Ok((triples, skipped))
+}
+
+/// The fields of every row of `text` with the line it starts on. Fields
+/// may be quoted with `"`, doubling quotes inside, and then span lines.
+fn csv_rows(text: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, ImportError> {
+    let mut rows = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let start = line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        loop {
+            match chars.next() {
+                None if quoted => {
+                    return Err(ImportError::Csv {
+                        line: start,
+                        message: "unterminated quoted field".to_string(),
+                    })
+                }
+                None => break,
+                Some('"') if quoted => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                Some('"') if field.trim().is_empty() => {
+                    field.clear();
+                    quoted = true;
+                }
+                Some(c) if quoted => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+                Some(c) if c == delimiter => fields.push(std::mem::take(&mut field)),
+                Some('\n') => {
+                    line += 1;
+                    break;
+                }
+                Some('\r') => {}
+                Some(c) => field.push(c),
+            }
+        }
+        fields.push(field);
+        if fields.iter().any(|f| !f.trim().is_empty()) {
+            rows.push((start, fields));
+        }
+    }
+    Ok(rows)
+}
+
+fn turtle_triples(text: &str) -> Result<(Vec<Triple>, usize), TurtleError> {
+    let statements = parse_turtle(text)?;
+    let skos_label = format!("{SKOS_NS}prefLabel");
+    let mut labels: HashMap<&str, &str> = HashMap::new();
+    let mut edges = HashSet::new();
+    for (subject, predicate, object) in &statements {
+        match object {
+            Term::Literal(label) if predicate == RDFS_LABEL || *predicate == skos_label => {
+                labels.entry(subject.as_str()).or_insert(label.as_str());
+            }
+            Term::Iri(t) if predicate == RDF_TYPE && t.strip_prefix(PHAGO_NS) == Some("Edge") => {
+                edges.insert(subject.as_str());
+            }
+            _ => {}
+        }
+    }
+    let label = |iri: &str| {
+        labels
+            .get(iri)
+            .map_or_else(|| local_name(iri), |l| l.to_string())
+    };
+
+    let mut triples = Vec::new();
+    let mut skipped = 0;
+    for (subject, predicate, object) in &statements {
+        let object = match object {
+            Term::Iri(object) if !object.starts_with("_:") => object,
+            _ => {
+                // Labels were read above; other literals say nothing of
+                // how two concepts relate
+                skipped += 1;
+                continue;
+            }
+        };
+        if predicate == RDF_TYPE || subject.starts_with("_:") || edges.contains(subject.as_str()) {
+            skipped += 1;
+            continue;
+        }
+        let (subject, object) = (label(subject), label(object));
+        if subject.is_empty() || object.is_empty() {
+            skipped += 1;
+            continue;
+        }
+        triples.push(Triple {
+            subject,
+            predicate: local_name(predicate),
+            object,
+            weight: None,
+            group: None,
+        });
+    }
+    Ok((triples, skipped))
+}
+
+/// The last segment of `iri`, after its last `#` or `/`, with `_` and `-`
+/// read as spaces: `http://example.org/Cell_membrane` is "Cell membrane".
+fn local_name(iri: &str) -> String {
+    let segment = iri.trim_end_matches(['/', '#']);
+    let segment = segment.rsplit(['#', '/']).next().unwrap_or(segment);
+    segment
+        .split(['_', '-'])
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_rows_keep_quoted_delimiters_and_report_bad_weights() {
+        let csv = "subject,predicate,object,confidence,domain\n\
+                   cell,contains,\"membrane, outer\",0.8,biology\n\
+                   \n\
+                   \"protein \"\"folding\"\"\",needs,chaperone,,biology\n\
+                   atp,powers,transport,0,biology\n\
+                   orphan,only\n";
+        let config = CsvTripleConfig {
+            weight_column: Some("confidence".to_string()),
+            group_column: Some("4".to_string()),
+            ..Default::default()
+        };
+        let (triples, skipped) = csv_triples(csv, &config).unwrap();
+        assert_eq!(skipped, 2);
+        assert_eq!(triples.len(), 2);
+        assert_eq!(triples[0].object, "membrane, outer");
+        assert_eq!(triples[0].weight, Some(0.8));
+        assert_eq!(triples[1].subject, "protein \"folding\"");
+        assert_eq!(triples[1].weight, None);
+        assert_eq!(triples[1].group.as_deref(), Some("biology"));
+
+        let bad = "s,p,o,w\na,b,c,1.5\n";
+        let config = CsvTripleConfig {
+            weight_column: Some("w".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            csv_triples(bad, &config),
+            Err(ImportError::Csv { line: 2, .. })
+        ));
+        let config = CsvTripleConfig {
+            weight_column: Some("confidence".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            csv_triples(bad, &config),
+            Err(ImportError::UnknownColumn(_))
+        ));
+    }
+
+    #[test]
+    fn turtle_resources_are_named_by_label_or_iri() {
+        let turtle = r#"
+            @prefix ex: <http://example.org/> .
+            @prefix rdfs: <http://www.w3.org/2000/01/rdf-schema#> .
+            ex:Cell_membrane rdfs:label "cell membrane" ;
+                a ex:Structure ;
+                ex:part-of ex:cell ;
+                ex:note "a literal" .
+            ex:cell ex:contains ex:Ribosome .
+        "#;
+        let (triples, skipped) = turtle_triples(turtle).unwrap();
+        let names: Vec<(&str, &str, &str)> = triples
+            .iter()
+            .map(|t| (t.subject.as_str(), t.predicate.as_str(), t.object.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("cell membrane", "part of", "cell"),
+                ("cell", "contains", "Ribosome"),
+            ]
+        );
+        // The label, the type and the note
+        assert_eq!(skipped, 3);
+    }
+
+    #[test]
+    fn layout_is_deterministic_and_keeps_groups_together() {
+        assert_eq!(layout_position("Cell", None), layout_position("cell", None));
+        assert_ne!(
+            layout_position("cell", None),
+            layout_position("membrane", None)
+        );
+        let a = layout_position("cell", Some("biology"));
+        let b = layout_position("membrane", Some("biology"));
+        assert!(a.distance_to(&b) <= 2.0 * GROUP_RADIUS);
+        for label in ["cell", "membrane", "a much longer label"] {
+            let p = layout_position(label, None);
+            assert!(p.x.abs() <= LAYOUT_EXTENT / 2.0 && p.y.abs() <= LAYOUT_EXTENT / 2.0);
+        }
+    }
+}
@@ -0,0 +1,679 @@
+//! Consistency checks for a colony's knowledge graph.
+//!
+//! No tick leaves the graph inconsistent, but long runs with save/load
+//! cycles, hand-edited session files and changes made through
+//! [`Colony::substrate_mut`] can: label-index entries that no longer match
+//! their node, edges whose endpoint is gone, NaN or out-of-range weights.
+//! Such states otherwise only show up as odd query results.
+//!
+//! [`check`] reports every problem it finds without changing anything;
+//! [`repair`] fixes what can be fixed mechanically under a
+//! [`RepairPolicy`] and reports each action it took.
+
+use crate::colony::Colony;
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use serde::Serialize;
+use std::fmt;
+
+/// A problem [`check`] found.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityIssue {
+    /// A label-index entry names a node that does not exist.
+    StaleLabelEntry { label: String, node: NodeId },
+    /// A label-index entry names a node that now has another label.
+    MislabeledEntry {
+        label: String,
+        node: NodeId,
+        actual: String,
+    },
+    /// A lookup by a node's own label does not find it.
+    UnindexedNode { node: NodeId, label: String },
+    /// An edge has an endpoint that does not exist.
+    DanglingEdge { from: NodeId, to: NodeId },
+    /// An edge weight is NaN, infinite or outside [0, 1].
+    InvalidWeight {
+        from: NodeId,
+        to: NodeId,
+        weight: f64,
+    },
+    /// A node's position is NaN or infinite.
+    InvalidPosition { node: NodeId, label: String },
+    /// A node was created or accessed after the colony's current tick.
+    NodeTickOutOfRange {
+        node: NodeId,
+        label: String,
+        tick: Tick,
+    },
+    /// An edge was created or activated after the colony's current tick.
+    EdgeTickOutOfRange {
+        from: NodeId,
+        to: NodeId,
+        tick: Tick,
+    },
+    /// A digested document none of whose presented concepts is in the
+    /// graph any more. Only documents with recorded provenance are checked.
+    DigestedWithoutConcepts { document: DocumentId, title: String },
+    /// A count the colony reports disagrees with the graph.
+    CountMismatch {
+        count: String,
+        reported: usize,
+        actual: usize,
+    },
+}
+
+impl IntegrityIssue {
+    /// Whether [`repair`] can fix this problem.
+    pub fn is_repairable(&self) -> bool {
+        !matches!(
+            self,
+            IntegrityIssue::InvalidPosition { .. }
+                | IntegrityIssue::DigestedWithoutConcepts { .. }
+                | IntegrityIssue::CountMismatch { .. }
+        )
+    }
+
+    fn is_index_drift(&self) -> bool {
+        matches!(
+            self,
+            IntegrityIssue::StaleLabelEntry { .. }
+                | IntegrityIssue::MislabeledEntry { .. }
+                | IntegrityIssue::UnindexedNode { .. }
+        )
+    }
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::StaleLabelEntry { label, node } => {
+                write!(
+                    f,
+                    "label index entry '{label}' names missing node {}",
+                    node.0
+                )
+            }
+            IntegrityIssue::MislabeledEntry {
+                label,
+                node,
+                actual,
+            } => write!(
+                f,
+                "label index entry '{label}' names node {}, labelled '{actual}'",
+                node.0
+            ),
+            IntegrityIssue::UnindexedNode { node, label } => {
+                write!(f, "node '{label}' ({}) is not found by its label", node.0)
+            }
+            IntegrityIssue::DanglingEdge { from, to } => {
+                write!(f, "edge {} — {} has a missing endpoint", from.0, to.0)
+            }
+            IntegrityIssue::InvalidWeight { from, to, weight } => {
+                write!(f, "edge {} — {} has invalid weight {weight}", from.0, to.0)
+            }
+            IntegrityIssue::InvalidPosition { node, label } => {
+                write!(f, "node '{label}' ({}) has a non-finite position", node.0)
+            }
+            IntegrityIssue::NodeTickOutOfRange { node, label, tick } => {
+                write!(
+                    f,
+                    "node '{label}' ({}) was touched at future tick {tick}",
+                    node.0
+                )
+            }
+            IntegrityIssue::EdgeTickOutOfRange { from, to, tick } => {
+                write!(
+                    f,
+                    "edge {} — {} was touched at future tick {tick}",
+                    from.0, to.0
+                )
+            }
+            IntegrityIssue::DigestedWithoutConcepts { document, title } => write!(
+                f,
+                "digested document '{title}' ({}) has no concepts left",
+                document.0
+            ),
+            IntegrityIssue::CountMismatch {
+                count,
+                reported,
+                actual,
+            } => write!(f, "{count}: colony reports {reported}, graph has {actual}"),
+        }
+    }
+}
+
+/// Everything [`check`] looked at and what it found.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IntegrityReport {
+    pub nodes_checked: usize,
+    pub edges_checked: usize,
+    pub documents_checked: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Issues [`repair`] can fix.
+    pub fn repairable(&self) -> usize {
+        self.issues.iter().filter(|i| i.is_repairable()).count()
+    }
+}
+
+/// What [`repair`] may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RepairPolicy {
+    /// Rebuild the graph's lookup indexes from its nodes.
+    pub rebuild_indexes: bool,
+    /// Remove edges with a missing endpoint.
+    pub drop_dangling_edges: bool,
+    /// Clamp weights into [0, 1] (NaN becomes 0) and future ticks to the
+    /// current tick.
+    pub clamp_values: bool,
+}
+
+impl Default for RepairPolicy {
+    /// Every repair.
+    fn default() -> Self {
+        Self {
+            rebuild_indexes: true,
+            drop_dangling_edges: true,
+            clamp_values: true,
+        }
+    }
+}
+
+/// A change [`repair`] made.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RepairAction {
+    /// The lookup indexes were rebuilt; `entries_changed` entries were
+    /// added or dropped.
+    RebuiltIndexes {
+        entries_changed: usize,
+    },
+    DroppedEdge {
+        from: NodeId,
+        to: NodeId,
+    },
+    ClampedWeight {
+        from: NodeId,
+        to: NodeId,
+        was: f64,
+        now: f64,
+    },
+    ClampedNodeTick {
+        node: NodeId,
+        was: Tick,
+        now: Tick,
+    },
+    ClampedEdgeTick {
+        from: NodeId,
+        to: NodeId,
+        was: Tick,
+        now: Tick,
+    },
+}
+
+impl fmt::Display for RepairAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepairAction::RebuiltIndexes { entries_changed } => {
+                write!(
+                    f,
+                    "rebuilt lookup indexes ({entries_changed} entries changed)"
+                )
+            }
+            RepairAction::DroppedEdge { from, to } => {
+                write!(f, "dropped edge {} — {}", from.0, to.0)
+            }
+            RepairAction::ClampedWeight { from, to, was, now } => write!(
+                f,
+                "clamped weight of edge {} — {} from {was} to {now}",
+                from.0, to.0
+            ),
+            RepairAction::ClampedNodeTick { node, was, now } => {
+                write!(f, "clamped tick of node {} from {was} to {now}", node.0)
+            }
+            RepairAction::ClampedEdgeTick { from, to, was, now } => write!(
+                f,
+                "clamped tick of edge {} — {} from {was} to {now}",
+                from.0, to.0
+            ),
+        }
+    }
+}
+
+/// What [`repair`] did, and what is still wrong afterwards.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RepairReport {
+    pub actions: Vec<RepairAction>,
+    pub remaining: IntegrityReport,
+}
+
+/// Check `colony`'s graph and documents for inconsistencies. Changes
+/// nothing.
+pub fn check(colony: &Colony) -> IntegrityReport {
+    let graph = colony.substrate().graph();
+    let now = colony.substrate().current_tick();
+    let mut report = IntegrityReport::default();
+
+    // Label index against the nodes
+    let nodes = graph.all_nodes();
+    for (label, id) in &graph.label_index_entries() {
+        match graph.get_node(id) {
+            None => report.issues.push(IntegrityIssue::StaleLabelEntry {
+                label: label.clone(),
+                node: *id,
+            }),
+            Some(node) if node.label.to_lowercase() != *label => {
+                report.issues.push(IntegrityIssue::MislabeledEntry {
+                    label: label.clone(),
+                    node: *id,
+                    actual: node.label.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for id in &nodes {
+        let Some(node) = graph.get_node(id) else {
+            continue;
+        };
+        report.nodes_checked += 1;
+        if !graph.find_nodes_by_exact_label(&node.label).contains(id) {
+            report.issues.push(IntegrityIssue::UnindexedNode {
+                node: *id,
+                label: node.label.clone(),
+            });
+        }
+        if !node.position.x.is_finite() || !node.position.y.is_finite() {
+            report.issues.push(IntegrityIssue::InvalidPosition {
+                node: *id,
+                label: node.label.clone(),
+            });
+        }
+        let tick = node.created_tick.max(node.last_accessed_tick);
+        if tick > now {
+            report.issues.push(IntegrityIssue::NodeTickOutOfRange {
+                node: *id,
+                label: node.label.clone(),
+                tick,
+            });
+        }
+    }
+
+    let edges = graph.all_edges();
+    for (from, to, edge) in &edges {
+        report.edges_checked += 1;
+        if graph.get_node(from).is_none() || graph.get_node(to).is_none() {
+            report.issues.push(IntegrityIssue::DanglingEdge {
+                from: *from,
+                to: *to,
+            });
+            continue;
+        }
+        if !(0.0..=1.0).contains(&edge.weight) {
+            report.issues.push(IntegrityIssue::InvalidWeight {
+                from: *from,
+                to: *to,
+                weight: edge.weight,
+            });
+        }
+        let tick = edge.created_tick.max(edge.last_activated_tick);
+        if tick > now {
+            report.issues.push(IntegrityIssue::EdgeTickOutOfRange {
+                from: *from,
+                to: *to,
+                tick,
+            });
+        }
+    }
+
+    // Digested documents against what they presented
+    let documents = colony.substrate().all_documents();
+    let mut digested = 0;
+    for doc in &documents {
+        report.documents_checked += 1;
+        if !doc.digested {
+            continue;
+        }
+        digested += 1;
+        let Some(contribution) = colony.document_contribution(&doc.id) else {
+            continue;
+        };
+        if !contribution.nodes.is_empty()
+            && contribution
+                .nodes
+                .keys()
+                .all(|id| graph.get_node(id).is_none())
+        {
+            report.issues.push(IntegrityIssue::DigestedWithoutConcepts {
+                document: doc.id,
+                title: doc.title.clone(),
+            });
+        }
+    }
+
+    // Reported counts against the graph
+    let stats = colony.stats();
+    for (count, reported, actual) in [
+        ("nodes", stats.graph_nodes, nodes.len()),
+        ("edges", stats.graph_edges, edges.len()),
+        ("digested documents", stats.documents_digested, digested),
+    ] {
+        if reported != actual {
+            report.issues.push(IntegrityIssue::CountMismatch {
+                count: count.to_string(),
+                reported,
+                actual,
+            });
+        }
+    }
+
+    report
+}
+
+/// Fix what [`check`] finds, as far as `policy` allows. Problems that
+/// cannot be fixed mechanically, like a non-finite position, are left and
+/// reported in [`RepairReport::remaining`].
+pub fn repair(colony: &mut Colony, policy: &RepairPolicy) -> RepairReport {
+    let mut actions = Vec::new();
+    let mut report = check(colony);
+
+    if policy.rebuild_indexes && report.issues.iter().any(IntegrityIssue::is_index_drift) {
+        let entries_changed = colony.substrate_mut().graph_mut().rebuild_indexes();
+        actions.push(RepairAction::RebuiltIndexes { entries_changed });
+        report = check(colony);
+    }
+
+    let now = colony.substrate().current_tick();
+    let graph = colony.substrate_mut().graph_mut();
+    for issue in &report.issues {
+        match *issue {
+            IntegrityIssue::DanglingEdge { from, to }
+                if policy.drop_dangling_edges && graph.remove_edge(&from, &to).is_some() =>
+            {
+                actions.push(RepairAction::DroppedEdge { from, to });
+            }
+            IntegrityIssue::InvalidWeight { from, to, weight } if policy.clamp_values => {
+                if let Some(edge) = graph.get_edge_mut(&from, &to) {
+                    edge.weight = if weight.is_nan() {
+                        0.0
+                    } else {
+                        weight.clamp(0.0, 1.0)
+                    };
+                    actions.push(RepairAction::ClampedWeight {
+                        from,
+                        to,
+                        was: weight,
+                        now: edge.weight,
+                    });
+                }
+            }
+            IntegrityIssue::NodeTickOutOfRange { node, tick, .. } if policy.clamp_values => {
+                if let Some(data) = graph.get_node_mut(&node) {
+                    data.created_tick = data.created_tick.min(now);
+                    data.last_accessed_tick = data.last_accessed_tick.min(now);
+                    actions.push(RepairAction::ClampedNodeTick {
+                        node,
+                        was: tick,
+                        now,
+                    });
+                }
+            }
+            IntegrityIssue::EdgeTickOutOfRange { from, to, tick } if policy.clamp_values => {
+                if let Some(edge) = graph.get_edge_mut(&from, &to) {
+                    edge.created_tick = edge.created_tick.min(now);
+                    edge.last_activated_tick = edge.last_activated_tick.min(now);
+                    actions.push(RepairAction::ClampedEdgeTick {
+                        from,
+                        to,
+                        was: tick,
+                        now,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    RepairReport {
+        remaining: if actions.is_empty() {
+            report
+        } else {
+            check(colony)
+        },
+        actions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colony::ColonyConfig;
+    use phago_agents::digester::Digester;
+
+    fn digested_colony(config: ColonyConfig) -> Colony {
+        let mut colony = Colony::from_config(config);
+        colony.ingest_document(
+            "Cells",
+            "The cell membrane controls transport. Membrane proteins act as channels.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(Digester::with_seed(Position::new(0.0, 0.0), 1)));
+        colony.run(5);
+        colony
+    }
+
+    fn node_labelled(colony: &Colony, label: &str) -> NodeId {
+        colony.substrate().graph().find_nodes_by_exact_label(label)[0]
+    }
+
+    fn first_edge(colony: &Colony) -> (NodeId, NodeId) {
+        let graph = colony.substrate().graph();
+        let (from, to, _) = graph.all_edges()[0];
+        (from, to)
+    }
+
+    #[test]
+    fn a_digested_colony_is_consistent() {
+        let mut colony = digested_colony(ColonyConfig::default());
+        let report = check(&colony);
+        assert!(report.is_clean(), "{:?}", report.issues);
+        assert!(report.nodes_checked > 0);
+        assert!(report.edges_checked > 0);
+        assert_eq!(report.documents_checked, 1);
+        assert!(repair(&mut colony, &RepairPolicy::default())
+            .actions
+            .is_empty());
+    }
+
+    #[test]
+    fn relabelled_nodes_are_index_drift() {
+        let mut colony = digested_colony(ColonyConfig::default());
+        let id = node_labelled(&colony, "membrane");
+        colony
+            .substrate_mut()
+            .graph_mut()
+            .get_node_mut(&id)
+            .unwrap()
+            .label = "Lipid".to_string();
+
+        let issues = check(&colony).issues;
+        assert!(issues.contains(&IntegrityIssue::MislabeledEntry {
+            label: "membrane".to_string(),
+            node: id,
+            actual: "Lipid".to_string(),
+        }));
+        assert!(issues.contains(&IntegrityIssue::UnindexedNode {
+            node: id,
+            label: "Lipid".to_string(),
+        }));
+
+        let report = repair(&mut colony, &RepairPolicy::default());
+        assert_eq!(
+            report.actions,
+            vec![RepairAction::RebuiltIndexes { entries_changed: 2 }]
+        );
+        assert!(report.remaining.is_clean(), "{:?}", report.remaining.issues);
+        let graph = colony.substrate().graph();
+        assert_eq!(graph.find_nodes_by_exact_label("lipid"), vec![id]);
+        assert!(graph.find_nodes_by_exact_label("membrane").is_empty());
+    }
+
+    #[test]
+    fn invalid_weights_and_ticks_are_clamped() {
+        let mut colony = digested_colony(ColonyConfig::default());
+        let now = colony.stats().tick;
+        let (from, to) = first_edge(&colony);
+        let node = node_labelled(&colony, "transport");
+        let graph = colony.substrate_mut().graph_mut();
+        graph.get_edge_mut(&from, &to).unwrap().weight = f64::NAN;
+        graph.get_node_mut(&node).unwrap().last_accessed_tick = now + 100;
+
+        let report = check(&colony);
+        assert_eq!(report.issues.len(), 2, "{:?}", report.issues);
+        assert!(matches!(
+            report.issues[..],
+            [
+                IntegrityIssue::NodeTickOutOfRange { tick, .. },
+                IntegrityIssue::InvalidWeight { weight, .. },
+            ] if tick == now + 100 && weight.is_nan()
+        ));
+        assert_eq!(report.repairable(), 2);
+
+        let report = repair(&mut colony, &RepairPolicy::default());
+        assert!(report.remaining.is_clean(), "{:?}", report.remaining.issues);
+        assert_eq!(report.actions.len(), 2);
+        assert!(report.actions.contains(&RepairAction::ClampedNodeTick {
+            node,
+            was: now + 100,
+            now,
+        }));
+        let graph = colony.substrate().graph();
+        assert_eq!(graph.get_edge(&from, &to).unwrap().weight, 0.0);
+        assert_eq!(graph.get_node(&node).unwrap().last_accessed_tick, now);
+    }
+
+    #[test]
+    fn repairs_follow_the_policy() {
+        let mut colony = digested_colony(ColonyConfig::default());
+        let (from, to) = first_edge(&colony);
+        let graph = colony.substrate_mut().graph_mut();
+        graph.get_edge_mut(&from, &to).unwrap().weight = 3.0;
+
+        let policy = RepairPolicy {
+            clamp_values: false,
+            ..RepairPolicy::default()
+        };
+        let report = repair(&mut colony, &policy);
+        assert!(report.actions.is_empty());
+        assert_eq!(
+            report.remaining.issues,
+            vec![IntegrityIssue::InvalidWeight {
+                from,
+                to,
+                weight: 3.0
+            }]
+        );
+
+        let report = repair(&mut colony, &RepairPolicy::default());
+        assert_eq!(
+            report.actions,
+            vec![RepairAction::ClampedWeight {
+                from,
+                to,
+                was: 3.0,
+                now: 1.0
+            }]
+        );
+    }
+
+    #[test]
+    fn digested_documents_without_concepts_are_reported() {
+        let mut colony = digested_colony(ColonyConfig::default());
+        let doc = colony.substrate().all_documents()[0].id;
+        let nodes: Vec<NodeId> = colony
+            .document_contribution(&doc)
+            .unwrap()
+            .nodes
+            .keys()
+            .copied()
+            .collect();
+        for id in &nodes {
+            colony.substrate_mut().graph_mut().remove_node(id);
+        }
+
+        let report = repair(&mut colony, &RepairPolicy::default());
+        assert!(report.actions.is_empty());
+        assert_eq!(
+            report.remaining.issues,
+            vec![IntegrityIssue::DigestedWithoutConcepts {
+                document: doc,
+                title: "Cells".to_string(),
+            }]
+        );
+        assert_eq!(report.remaining.repairable(), 0);
+    }
+
+    #[test]
+    fn pruned_concepts_leave_document_provenance() {
+        let mut colony = digested_colony(ColonyConfig {
+            node_staleness_ticks: 1,
+            node_prune_threshold: f64::MAX,
+            node_anchor_edge_weight: f64::MAX,
+            ..ColonyConfig::default()
+        });
+        colony.prune_stale_nodes();
+        assert_eq!(colony.substrate().graph().node_count(), 0);
+        let doc = colony.substrate().all_documents()[0].id;
+        assert!(colony.document_contribution(&doc).unwrap().is_empty());
+        assert!(check(&colony).is_clean());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn dangling_edges_are_dropped() {
+        use crate::backend::BackendConfig;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.db");
+        let mut colony = digested_colony(ColonyConfig {
+            backend: BackendConfig::sqlite(&path),
+            ..ColonyConfig::default()
+        });
+        let edges = colony.substrate().graph().edge_count();
+        let node = node_labelled(&colony, "membrane");
+        let missing = NodeId::new();
+        let (from, to) = if node <= missing {
+            (node, missing)
+        } else {
+            (missing, node)
+        };
+
+        // An edge to a node deleted by a tool that ignores foreign keys
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+        conn.execute(
+            "INSERT INTO edges (from_id, to_id, weight) VALUES (?1, ?2, 0.5)",
+            rusqlite::params![from.0.to_string(), to.0.to_string()],
+        )
+        .unwrap();
+
+        let report = check(&colony);
+        assert_eq!(
+            report.issues,
+            vec![IntegrityIssue::DanglingEdge { from, to }]
+        );
+
+        let report = repair(&mut colony, &RepairPolicy::default());
+        assert_eq!(report.actions, vec![RepairAction::DroppedEdge { from, to }]);
+        assert!(report.remaining.is_clean(), "{:?}", report.remaining.issues);
+        assert_eq!(colony.substrate().graph().edge_count(), edges);
+    }
+}
@@ -0,0 +1,225 @@
+//! Inverted index over node labels for TF-IDF retrieval.
+//!
+//! Scoring a query by TF-IDF needs, for each query term, how many nodes
+//! carry it (document frequency) and how often it occurs in each of their
+//! labels (term frequency). Computed from the graph, that is a walk over
+//! every label per query. [`InvertedIndex`] keeps both per term and is
+//! updated as nodes come and go, so a query only touches the postings of
+//! its own terms.
+//!
+//! The substrate maintains the index when nodes are added or removed
+//! through it (see [`SubstrateImpl::add_node`] and
+//! [`SubstrateImpl::remove_node`]). Anything that may have changed the
+//! graph behind its back (see [`Colony::substrate_mut`]) marks the index
+//! stale, and readers fall back to scanning until it is rebuilt.
+//!
+//! Like the graph's own label index, this assumes a node's label does not
+//! change after it is added.
+//!
+//! [`SubstrateImpl::add_node`]: phago_core::substrate::Substrate::add_node
+//! [`SubstrateImpl::remove_node`]: crate::substrate_impl::SubstrateImpl::remove_node
+//! [`Colony::substrate_mut`]: crate::colony::Colony::substrate_mut
+
+use phago_core::tokenize::Tokenizer;
+use phago_core::topology::TopologyGraph;
+use phago_core::types::*;
+use std::collections::HashMap;
+
+/// Term → node → term frequency, over the words of node labels.
+#[derive(Debug, Clone)]
+pub struct InvertedIndex {
+    tokenizer: Tokenizer,
+    postings: HashMap<String, HashMap<NodeId, u32>>,
+    /// Node → the distinct terms of its label, for removal.
+    node_terms: HashMap<NodeId, Vec<String>>,
+    /// The graph may have changed without the index being told.
+    stale: bool,
+}
+
+impl InvertedIndex {
+    /// An empty index splitting labels with `tokenizer`.
+    ///
+    /// Queries must be tokenized the same way for their terms to match.
+    pub fn new(tokenizer: Tokenizer) -> Self {
+        Self {
+            tokenizer,
+            postings: HashMap::new(),
+            node_terms: HashMap::new(),
+            stale: false,
+        }
+    }
+
+    /// Index every node of `graph`.
+    pub fn build(tokenizer: Tokenizer, graph: &dyn TopologyGraph) -> Self {
+        let mut index = Self::new(tokenizer);
+        index.rebuild(graph);
+        index
+    }
+
+    /// An index of `nodes` holding `postings` (term → node → term
+    /// frequency), as [`terms`](Self::terms) listed them for labels split
+    /// with `tokenizer`. Nodes whose labels have no terms count as indexed.
+    pub fn from_postings(
+        tokenizer: Tokenizer,
+        nodes: &[NodeId],
+        postings: HashMap<String, HashMap<NodeId, u32>>,
+    ) -> Self {
+        let mut node_terms: HashMap<NodeId, Vec<String>> =
+            nodes.iter().map(|id| (*id, Vec::new())).collect();
+        for (term, nodes) in &postings {
+            for id in nodes.keys() {
+                node_terms.entry(*id).or_default().push(term.clone());
+            }
+        }
+        Self {
+            tokenizer,
+            postings,
+            node_terms,
+            stale: false,
+        }
+    }
+
+    /// Discard the postings and index every node of `graph` again.
+    pub fn rebuild(&mut self, graph: &dyn TopologyGraph) {
+        self.postings.clear();
+        self.node_terms.clear();
+        for id in graph.all_nodes() {
+            if let Some(node) = graph.get_node(&id) {
+                self.insert(node);
+            }
+        }
+        self.stale = false;
+    }
+
+    /// The tokenizer labels are split with.
+    pub fn tokenizer(&self) -> &Tokenizer {
+        &self.tokenizer
+    }
+
+    /// Index a node's label, replacing any earlier entry for its ID.
+    pub fn insert(&mut self, node: &NodeData) {
+        self.remove(&node.id);
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for word in self.tokenizer.words(&node.label) {
+            *counts.entry(word).or_insert(0) += 1;
+        }
+        let mut terms = Vec::with_capacity(counts.len());
+        for (term, tf) in counts {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(node.id, tf);
+            terms.push(term);
+        }
+        self.node_terms.insert(node.id, terms);
+    }
+
+    /// Drop a node from the postings. Returns false if it was not indexed.
+    pub fn remove(&mut self, id: &NodeId) -> bool {
+        let Some(terms) = self.node_terms.remove(id) else {
+            return false;
+        };
+        for term in terms {
+            if let Some(nodes) = self.postings.get_mut(&term) {
+                nodes.remove(id);
+                if nodes.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+        true
+    }
+
+    /// Nodes whose label contains `term`, with how often it occurs there.
+    pub fn postings(&self, term: &str) -> Option<&HashMap<NodeId, u32>> {
+        self.postings.get(term)
+    }
+
+    /// Every term with its postings, in no particular order.
+    pub fn terms(&self) -> impl Iterator<Item = (&str, &HashMap<NodeId, u32>)> {
+        self.postings
+            .iter()
+            .map(|(term, nodes)| (term.as_str(), nodes))
+    }
+
+    /// Number of nodes whose label contains `term`.
+    pub fn document_frequency(&self, term: &str) -> usize {
+        self.postings.get(term).map_or(0, HashMap::len)
+    }
+
+    /// Number of indexed nodes.
+    pub fn node_count(&self) -> usize {
+        self.node_terms.len()
+    }
+
+    /// Number of distinct terms.
+    pub fn term_count(&self) -> usize {
+        self.postings.len()
+    }
+
+    /// Rebuild from the graph before the index is next used.
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Whether the index must be rebuilt before it can be trusted.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology_impl::PetTopologyGraph;
+
+    fn node(label: &str) -> NodeData {
+        NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        }
+    }
+
+    #[test]
+    fn insert_and_remove_track_frequencies() {
+        let mut index = InvertedIndex::new(Tokenizer::default());
+        let membrane = node("cell membrane");
+        let wall = node("Cell wall of the cell");
+        index.insert(&membrane);
+        index.insert(&wall);
+
+        assert_eq!(index.node_count(), 2);
+        assert_eq!(index.document_frequency("cell"), 2);
+        assert_eq!(index.postings("cell").unwrap()[&wall.id], 2);
+        assert_eq!(index.document_frequency("membrane"), 1);
+
+        assert!(index.remove(&wall.id));
+        assert!(!index.remove(&wall.id));
+        assert_eq!(index.document_frequency("cell"), 1);
+        assert!(index.postings("wall").is_none());
+        assert_eq!(index.node_count(), 1);
+    }
+
+    #[test]
+    fn build_matches_incremental_inserts() {
+        let mut graph = PetTopologyGraph::new();
+        let mut incremental = InvertedIndex::new(Tokenizer::default());
+        for label in ["protein", "protein folding", "ribosome"] {
+            let n = node(label);
+            incremental.insert(&n);
+            graph.add_node(n);
+        }
+        let built = InvertedIndex::build(Tokenizer::default(), &graph);
+        assert_eq!(built.node_count(), incremental.node_count());
+        assert_eq!(built.term_count(), incremental.term_count());
+        for term in ["protein", "folding", "ribosome"] {
+            assert_eq!(built.postings(term), incremental.postings(term));
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Label normalization — what presented labels become as node labels.
+//!
+//! Agents present labels as they find them: a synthesizer insight naming
+//! every concept it bridges, a code signature hundreds of characters long,
+//! the same word in two Unicode forms or with stray whitespace. The colony
+//! normalizes each label once, where a presentation becomes nodes, as its
+//! [`LabelPolicy`] says.
+//!
+//! Labels longer than the policy allows are cut short and end in a hash of
+//! the whole label, so distinct long labels stay distinct nodes. A node
+//! whose label was changed keeps the label it was presented with as an
+//! alias, which exact lookups resolve, and as an annotation from
+//! [`ORIGINAL_LABEL_SOURCE`], which exports can use instead (see
+//! [`LabelForm`]).
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use unicode_normalization::UnicodeNormalization;
+
+/// Source of the annotation that keeps a node's label as presented, when
+/// normalization changed it.
+pub const ORIGINAL_LABEL_SOURCE: &str = "original-label";
+
+/// Characters of the suffix that ends a truncated label: `~` and eight hex
+/// digits.
+const HASH_SUFFIX_CHARS: usize = 9;
+
+/// How stored labels are cased. Lookups ignore case either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelCase {
+    /// Store labels as presented.
+    #[default]
+    Preserve,
+    /// Store labels lowercased.
+    Lower,
+}
+
+/// How presented labels are normalized into node labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabelPolicy {
+    /// Longest label, in characters; longer ones are truncated and end in
+    /// a hash of the whole label. 0 means unlimited (default: 120).
+    #[serde(default = "default_max_chars")]
+    pub max_chars: usize,
+    /// Compose labels to Unicode NFC, so that canonically equivalent
+    /// spellings are one label (default: true).
+    #[serde(default = "default_true")]
+    pub unicode_nfc: bool,
+    /// Trim labels and collapse runs of whitespace to one space (default:
+    /// true).
+    #[serde(default = "default_true")]
+    pub collapse_whitespace: bool,
+    #[serde(default)]
+    pub case: LabelCase,
+}
+
+fn default_max_chars() -> usize {
+    120
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for LabelPolicy {
+    fn default() -> Self {
+        Self {
+            max_chars: default_max_chars(),
+            unicode_nfc: true,
+            collapse_whitespace: true,
+            case: LabelCase::Preserve,
+        }
+    }
+}
+
+impl LabelPolicy {
+    /// A policy that stores labels exactly as presented.
+    pub fn verbatim() -> Self {
+        Self {
+            max_chars: 0,
+            unicode_nfc: false,
+            collapse_whitespace: false,
+            case: LabelCase::Preserve,
+        }
+    }
+
+    /// `label` as this policy stores it.
+    pub fn normalize<'a>(&self, label: &'a str) -> Cow<'a, str> {
+        let mut label = Cow::Borrowed(label);
+        if self.unicode_nfc && !unicode_normalization::is_nfc(&label) {
+            label = Cow::Owned(label.nfc().collect());
+        }
+        if self.collapse_whitespace && has_loose_whitespace(&label) {
+            label = Cow::Owned(label.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+        if self.case == LabelCase::Lower && label.chars().any(char::is_uppercase) {
+            label = Cow::Owned(label.to_lowercase());
+        }
+        if self.max_chars > 0 && label.chars().nth(self.max_chars).is_some() {
+            let keep = self.max_chars.saturating_sub(HASH_SUFFIX_CHARS);
+            let head: String = label.chars().take(keep).collect();
+            label = Cow::Owned(format!("{}~{:08x}", head.trim_end(), fnv1a(&label)));
+        }
+        label
+    }
+}
+
+/// Which label exports name nodes by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelForm {
+    /// The label as stored in the graph.
+    #[default]
+    Stored,
+    /// The label as presented, where normalization changed it.
+    Original,
+}
+
+/// Whether `label` has leading, trailing, repeated or non-space
+/// whitespace.
+fn has_loose_whitespace(label: &str) -> bool {
+    let mut previous_space = true;
+    for c in label.chars() {
+        if c.is_whitespace() {
+            if previous_space || c != ' ' {
+                return true;
+            }
+            previous_space = true;
+        } else {
+            previous_space = false;
+        }
+    }
+    previous_space && !label.is_empty()
+}
+
+/// 32-bit FNV-1a, which unlike the std hashers is the same in every build.
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whitespace_and_unicode_forms_are_normalized() {
+        let policy = LabelPolicy::default();
+        assert_eq!(policy.normalize("  cell \t membrane\n"), "cell membrane");
+        // "é" as `e` and a combining acute accent
+        assert_eq!(policy.normalize("caf\u{65}\u{301}"), "caf\u{e9}");
+        assert!(matches!(policy.normalize("Membrane"), Cow::Borrowed(_)));
+
+        let lower = LabelPolicy {
+            case: LabelCase::Lower,
+            ..LabelPolicy::default()
+        };
+        assert_eq!(lower.normalize("ATP Synthase"), "atp synthase");
+    }
+
+    #[test]
+    fn long_labels_are_truncated_with_a_stable_hash() {
+        let policy = LabelPolicy {
+            max_chars: 20,
+            ..LabelPolicy::default()
+        };
+        let a = policy.normalize("[BRIDGE membrane|transport|mitochondria]");
+        let b = policy.normalize("[BRIDGE membrane|transport|glycolysis]");
+        assert_eq!(a.chars().count(), 20);
+        assert!(a.starts_with("[BRIDGE mem"));
+        assert_ne!(a, b);
+        assert_eq!(
+            a,
+            policy.normalize("[BRIDGE membrane|transport|mitochondria]")
+        );
+        assert_eq!(policy.normalize("short"), "short");
+        assert_eq!(
+            LabelPolicy::verbatim().normalize(&"x".repeat(500)),
+            "x".repeat(500)
+        );
+    }
+}
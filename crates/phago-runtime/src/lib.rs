@@ -29,22 +29,56 @@ pub mod bench;
 pub mod colony;
 pub mod colony_builder;
 pub mod community;
+pub mod compaction;
+mod concept_index;
 pub mod corpus;
 pub mod curriculum;
+pub mod decay;
+pub mod dedup;
 pub mod diff;
+pub mod embedding_cache;
+pub mod embedding_store;
+pub mod exploration;
 pub mod export;
+pub mod extract;
+pub mod graph_algorithms;
+pub mod import;
+pub mod integrity;
+pub mod inverted_index;
+pub mod label_policy;
+mod maintenance;
 pub mod metrics;
+pub mod placement;
+pub mod plugin;
 pub mod prelude;
 pub mod project_context;
+pub mod provenance;
+pub mod publish;
+pub mod query_log;
+pub mod rdf;
+pub mod read_view;
+pub mod region;
+pub mod reinforcement;
+pub mod replay;
+pub mod sampling;
 pub mod session;
+pub mod snapshot;
 pub mod stdp;
 pub mod substrate_impl;
+pub mod synonyms;
 pub mod topology_impl;
+pub mod trace_payload;
 pub mod training_format;
+pub mod transfer;
+pub mod vocabulary;
+mod wiring;
 
 #[cfg(feature = "sqlite")]
 pub mod sqlite_topology;
 
+#[cfg(feature = "bundle")]
+pub mod bundle;
+
 #[cfg(feature = "async")]
 pub mod async_runtime;
 
@@ -0,0 +1,476 @@
+//! Budgeted maintenance — edge decay, degree pruning and dissolution
+//! spread over as many ticks as they need.
+//!
+//! Unbudgeted, every tick decays and prunes every edge and, on dissolution
+//! ticks, modulates every agent's boundary. On a graph of a few hundred
+//! thousand edges that takes seconds, and nothing else gets the colony in
+//! the meantime. With [`ColonyConfig::tick_budget_ms`] each of these runs
+//! as a pass over a list taken when the pass starts, working through it
+//! until the tick's budget is spent and carrying on from there next tick;
+//! a new pass starts once the last one is through. Each pass does at
+//! least [`SLICE`] items per tick, so it always makes progress.
+//!
+//! An edge is decayed once per pass, for every tick since it was last
+//! decayed, at the decay rate each of those ticks had, so rates that change
+//! from tick to tick (adaptive decay) are replayed as they were. A graph
+//! left alone therefore ends up as it would unbudgeted, only later; edges
+//! reinforced in between are decayed from their new weight. Edges added
+//! during a pass wait for the next one, which picks them up from their
+//! creation tick.
+//!
+//! [`ColonyConfig::tick_budget_ms`]: crate::colony::ColonyConfig::tick_budget_ms
+
+use phago_core::topology::TopologyGraph;
+use phago_core::types::{AgentId, EdgeData, NodeId, Tick};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// Items a pass handles between budget checks, and at least per tick.
+pub(crate) const SLICE: usize = 64;
+
+/// Parameters of activity-aware edge decay, as passed to
+/// [`TopologyGraph::decay_edges_activity`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EdgeDecay {
+    pub rate: f64,
+    pub prune_threshold: f64,
+    pub staleness_factor: f64,
+    pub maturation_ticks: u64,
+}
+
+/// Factors on the decay rate of edges decaying unlike the rest, keyed by
+/// endpoints in either order (see [`crate::region`]).
+pub(crate) type EdgeMultipliers = HashMap<(NodeId, NodeId), f64>;
+
+fn multiplier(multipliers: &EdgeMultipliers, from: NodeId, to: NodeId) -> f64 {
+    multipliers
+        .get(&(from, to))
+        .or_else(|| multipliers.get(&(to, from)))
+        .copied()
+        .unwrap_or(1.0)
+}
+
+impl EdgeDecay {
+    /// The fraction of its weight `edge` loses on `tick`, with its rate
+    /// scaled by `multiplier`.
+    fn rate_at(&self, edge: &EdgeData, tick: Tick, multiplier: f64) -> f64 {
+        let age = tick.saturating_sub(edge.created_tick);
+        let rate = if age < self.maturation_ticks {
+            self.rate
+        } else {
+            let staleness = tick.saturating_sub(edge.last_activated_tick) as f64;
+            let activity_factor = 1.0 / (1.0 + edge.co_activations as f64 * 0.5);
+            self.rate * (1.0 + self.staleness_factor * (staleness / 100.0) * activity_factor)
+        };
+        (rate * multiplier).min(0.5)
+    }
+}
+
+/// Activity-aware decay of every edge at once, as
+/// [`TopologyGraph::decay_edges_activity`] does, with the rates of some
+/// edges scaled.
+pub(crate) fn decay_edges_scaled(
+    graph: &mut dyn TopologyGraph,
+    decay: EdgeDecay,
+    multipliers: &EdgeMultipliers,
+    tick: Tick,
+) {
+    let edges: Vec<(NodeId, NodeId)> = graph.all_edges().iter().map(|(a, b, _)| (*a, *b)).collect();
+    for (from, to) in edges {
+        let Some(edge) = graph.get_edge_mut(&from, &to) else {
+            continue;
+        };
+        edge.weight *= 1.0 - decay.rate_at(edge, tick, multiplier(multipliers, from, to));
+        let mature = tick.saturating_sub(edge.created_tick) >= decay.maturation_ticks;
+        if mature && edge.weight < decay.prune_threshold {
+            graph.remove_edge(&from, &to);
+        }
+    }
+}
+
+/// A list of work items and how far through it the colony is.
+#[derive(Debug)]
+pub(crate) struct Pass<T> {
+    items: Vec<T>,
+    next: usize,
+}
+
+impl<T> Default for Pass<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            next: 0,
+        }
+    }
+}
+
+impl<T: Copy> Pass<T> {
+    pub(crate) fn new(items: Vec<T>) -> Self {
+        Self { items, next: 0 }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.next >= self.items.len()
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.items.len().saturating_sub(self.next)
+    }
+
+    /// Hand items to `work` until the pass is done or, once at least
+    /// [`SLICE`] items have been handled, `deadline` has passed.
+    pub(crate) fn run(&mut self, deadline: Option<Instant>, mut work: impl FnMut(T)) {
+        let mut done = 0;
+        while let Some(&item) = self.items.get(self.next) {
+            if done > 0
+                && done % SLICE == 0
+                && deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                break;
+            }
+            self.next += 1;
+            done += 1;
+            work(item);
+        }
+    }
+}
+
+/// Cursors of the budgeted maintenance passes.
+#[derive(Debug)]
+pub(crate) struct Maintenance {
+    budget: Duration,
+    decay: Pass<(NodeId, NodeId)>,
+    /// Edge → the last tick it was decayed for.
+    decayed_through: HashMap<(NodeId, NodeId), Tick>,
+    /// Tick and parameters of the last decay slice.
+    last_decay: Option<(Tick, EdgeDecay)>,
+    /// Edge decay rate by tick, back to the start of the previous pass,
+    /// the oldest tick an edge may still have to be decayed for.
+    rates: BTreeMap<Tick, f64>,
+    /// Tick the current decay pass started at.
+    pass_started: Tick,
+    prune: Pass<NodeId>,
+    dissolution: Pass<AgentId>,
+}
+
+impl Maintenance {
+    pub(crate) fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            decay: Pass::default(),
+            decayed_through: HashMap::new(),
+            last_decay: None,
+            rates: BTreeMap::new(),
+            pass_started: 0,
+            prune: Pass::default(),
+            dissolution: Pass::default(),
+        }
+    }
+
+    /// Time a tick may take before the passes stop for it.
+    pub(crate) fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Edges, nodes and agents the current passes have yet to reach.
+    pub(crate) fn backlog(&self) -> usize {
+        self.decay.remaining() + self.prune.remaining() + self.dissolution.remaining()
+    }
+
+    /// Decay the next edges of the decay pass up to `tick`, pruning mature
+    /// ones that fall below the threshold.
+    pub(crate) fn decay_edges(
+        &mut self,
+        graph: &mut dyn TopologyGraph,
+        decay: EdgeDecay,
+        multipliers: &EdgeMultipliers,
+        tick: Tick,
+        deadline: Option<Instant>,
+    ) {
+        if self.decay.is_done() {
+            let edges: Vec<(NodeId, NodeId)> =
+                graph.all_edges().iter().map(|(a, b, _)| (*a, *b)).collect();
+            // Forget edges that have gone since the last pass
+            let mut decayed_through = HashMap::with_capacity(edges.len());
+            for key in &edges {
+                if let Some(through) = self.decayed_through.remove(key) {
+                    decayed_through.insert(*key, through);
+                }
+            }
+            self.decayed_through = decayed_through;
+            self.decay = Pass::new(edges);
+            self.rates = self.rates.split_off(&self.pass_started);
+            self.pass_started = tick;
+        }
+        self.last_decay = Some((tick, decay));
+        self.rates.insert(tick, decay.rate);
+
+        let decayed_through = &mut self.decayed_through;
+        let rates = &self.rates;
+        self.decay.run(deadline, |(from, to)| {
+            let Some(edge) = graph.get_edge_mut(&from, &to) else {
+                decayed_through.remove(&(from, to));
+                return;
+            };
+            // An edge re-created since it was last seen starts afresh
+            let first = match decayed_through.get(&(from, to)) {
+                Some(&through) => (through + 1).max(edge.created_tick),
+                None => edge.created_tick,
+            };
+            let multiplier = multiplier(multipliers, from, to);
+            // The rate in force at `first`; ticks older than any recorded
+            // take the oldest recorded rate
+            let mut rate = rates
+                .range(..first)
+                .next_back()
+                .or_else(|| rates.first_key_value())
+                .map_or(decay.rate, |(_, &rate)| rate);
+            let mut recorded = rates.range(first..).peekable();
+            for t in first..=tick {
+                while let Some((_, &at)) = recorded.next_if(|(&since, _)| since <= t) {
+                    rate = at;
+                }
+                let decay = EdgeDecay { rate, ..decay };
+                edge.weight *= 1.0 - decay.rate_at(edge, t, multiplier);
+            }
+            let mature = tick.saturating_sub(edge.created_tick) >= decay.maturation_ticks;
+            if mature && edge.weight < decay.prune_threshold {
+                graph.remove_edge(&from, &to);
+                decayed_through.remove(&(from, to));
+            } else {
+                decayed_through.insert((from, to), tick);
+            }
+        });
+    }
+
+    /// Cap the degree of the next nodes of the pruning pass, dropping each
+    /// one's weakest edges beyond `max_degree`.
+    pub(crate) fn prune_to_max_degree(
+        &mut self,
+        graph: &mut dyn TopologyGraph,
+        max_degree: usize,
+        deadline: Option<Instant>,
+    ) {
+        if self.prune.is_done() {
+            self.prune = Pass::new(graph.all_nodes());
+        }
+        self.prune.run(deadline, |node| {
+            let mut edges: Vec<(NodeId, f64)> = graph
+                .neighbors(&node)
+                .into_iter()
+                .map(|(other, edge)| (other, edge.weight))
+                .collect();
+            if edges.len() <= max_degree {
+                return;
+            }
+            edges.sort_by(|a, b| b.1.total_cmp(&a.1));
+            for (other, _) in edges.into_iter().skip(max_degree) {
+                graph.remove_edge(&node, &other);
+            }
+        });
+    }
+
+    /// Start a dissolution round over `agents`, unless one is under way.
+    pub(crate) fn begin_dissolution(&mut self, agents: impl FnOnce() -> Vec<AgentId>) {
+        if self.dissolution.is_done() {
+            self.dissolution = Pass::new(agents());
+        }
+    }
+
+    /// The dissolution round, taken out so the colony can work through it.
+    pub(crate) fn take_dissolution(&mut self) -> Pass<AgentId> {
+        std::mem::take(&mut self.dissolution)
+    }
+
+    pub(crate) fn restore_dissolution(&mut self, round: Pass<AgentId>) {
+        self.dissolution = round;
+    }
+
+    /// Decay every edge up to the last tick maintenance ran at and cap
+    /// every node's degree, however long that takes.
+    pub(crate) fn catch_up(
+        &mut self,
+        graph: &mut dyn TopologyGraph,
+        multipliers: &EdgeMultipliers,
+        max_degree: usize,
+    ) {
+        let Some((tick, decay)) = self.last_decay else {
+            return;
+        };
+        // Finish the pass under way, whose edges may still need the rates
+        // of the one before, then run one for the edges added meanwhile
+        self.decay_edges(graph, decay, multipliers, tick, None);
+        self.decay_edges(graph, decay, multipliers, tick, None);
+        self.prune = Pass::default();
+        self.prune_to_max_degree(graph, max_degree, None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colony::{Colony, ColonyConfig};
+    use phago_core::substrate::Substrate;
+    use phago_core::types::{NodeData, NodeType, Position};
+    use std::collections::BTreeMap;
+
+    fn concept(label: &str) -> NodeData {
+        NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: BTreeMap::new(),
+        }
+    }
+
+    fn edge(weight: f64, co_activations: u64, tick: Tick) -> EdgeData {
+        EdgeData {
+            weight,
+            co_activations,
+            created_tick: tick,
+            last_activated_tick: tick,
+        }
+    }
+
+    /// A hub over the degree cap, and a long chain of edges of varied
+    /// weight and activity, some of which decay away.
+    fn seeded_colony(tick_budget_ms: Option<u64>) -> Colony {
+        let mut colony = Colony::from_config(ColonyConfig {
+            tick_budget_ms,
+            max_edge_degree: 8,
+            maturation_ticks: 5,
+            ..Default::default()
+        });
+        let graph = colony.substrate_mut().graph_mut();
+        let hub = graph.add_node(concept("hub"));
+        for i in 0..20 {
+            let leaf = graph.add_node(concept(&format!("leaf{i}")));
+            let weight = 0.2 + 0.04 * i as f64;
+            graph.set_edge(hub, leaf, edge(weight, 2, 0)).unwrap();
+        }
+        let mut previous = graph.add_node(concept("chain0"));
+        for i in 1..400 {
+            let node = graph.add_node(concept(&format!("chain{i}")));
+            let weight = 0.06 + (i % 7) as f64 * 0.05;
+            graph
+                .set_edge(previous, node, edge(weight, i % 4, 0))
+                .unwrap();
+            previous = node;
+        }
+        colony
+    }
+
+    /// Pairs of nodes joined by fresh, strong edges.
+    fn add_pairs(colony: &mut Colony, count: usize) {
+        let tick = colony.substrate().current_tick();
+        let graph = colony.substrate_mut().graph_mut();
+        for i in 0..count {
+            let a = graph.add_node(concept(&format!("late{i}a")));
+            let b = graph.add_node(concept(&format!("late{i}b")));
+            graph.set_edge(a, b, edge(0.9, 0, tick)).unwrap();
+        }
+    }
+
+    /// Edges by endpoint labels, with their weights.
+    fn edges(colony: &Colony) -> BTreeMap<(String, String), f64> {
+        let graph = colony.substrate().graph();
+        graph
+            .all_edges()
+            .into_iter()
+            .map(|(a, b, e)| {
+                let a = graph.get_node(&a).unwrap().label.clone();
+                let b = graph.get_node(&b).unwrap().label.clone();
+                let key = if a < b { (a, b) } else { (b, a) };
+                (key, e.weight)
+            })
+            .collect()
+    }
+
+    fn assert_same_graph(expected: &Colony, actual: &Colony) {
+        let (expected, actual) = (edges(expected), edges(actual));
+        assert_eq!(
+            expected.keys().collect::<Vec<_>>(),
+            actual.keys().collect::<Vec<_>>()
+        );
+        for (key, weight) in &expected {
+            assert!((weight - actual[key]).abs() < 1e-9, "{key:?}");
+        }
+    }
+
+    #[test]
+    fn tiny_budget_converges_to_the_unbudgeted_graph() {
+        let mut unbudgeted = seeded_colony(None);
+        let mut budgeted = seeded_colony(Some(0));
+        let before = edges(&budgeted).len();
+
+        unbudgeted.run(60);
+        budgeted.run(60);
+        assert_eq!(unbudgeted.stats().maintenance_backlog, 0);
+        assert!(edges(&unbudgeted).len() < before, "nothing was pruned");
+        // One slice per tick leaves edges decayed only up to earlier ticks
+        assert_ne!(edges(&budgeted), edges(&unbudgeted));
+
+        budgeted.finish_maintenance();
+        assert_eq!(budgeted.stats().maintenance_backlog, 0);
+        assert_same_graph(&unbudgeted, &budgeted);
+    }
+
+    #[test]
+    fn edges_added_mid_pass_are_not_skipped() {
+        let mut unbudgeted = seeded_colony(None);
+        let mut budgeted = seeded_colony(Some(0));
+        let ticks_per_pass = budgeted.stats().graph_edges.div_ceil(SLICE) as u64;
+
+        // Halfway through the first pass, after its edges were listed
+        unbudgeted.run(ticks_per_pass / 2);
+        budgeted.run(ticks_per_pass / 2);
+        assert!(budgeted.stats().maintenance_backlog > 0);
+        add_pairs(&mut unbudgeted, SLICE * 3);
+        add_pairs(&mut budgeted, SLICE * 3);
+
+        // Long enough for several passes to start and wrap around
+        unbudgeted.run(5 * ticks_per_pass);
+        budgeted.run(5 * ticks_per_pass);
+        let graph = budgeted.substrate().graph();
+        for (_, _, edge) in graph.all_edges() {
+            if edge.created_tick > 0 {
+                assert!(edge.weight < 0.9, "an added edge was never decayed");
+            }
+        }
+
+        budgeted.finish_maintenance();
+        assert_same_graph(&unbudgeted, &budgeted);
+    }
+
+    #[test]
+    fn missed_ticks_decay_at_the_rates_they_had() {
+        // Rates that change from tick to tick, as adaptive decay's do
+        let decay_at = |tick: Tick| EdgeDecay {
+            rate: 0.01 + 0.004 * (tick % 5) as f64,
+            prune_threshold: 0.05,
+            staleness_factor: 1.0,
+            maturation_ticks: 5,
+        };
+        let multipliers = EdgeMultipliers::new();
+        let mut unbudgeted = seeded_colony(None);
+        let mut budgeted = seeded_colony(Some(0));
+        let mut maintenance = Maintenance::new(Duration::ZERO);
+
+        for tick in 0..60 {
+            let graph = unbudgeted.substrate_mut().graph_mut();
+            decay_edges_scaled(graph, decay_at(tick), &multipliers, tick);
+            let graph = budgeted.substrate_mut().graph_mut();
+            let deadline = Some(Instant::now());
+            maintenance.decay_edges(graph, decay_at(tick), &multipliers, tick, deadline);
+        }
+        assert!(maintenance.backlog() > 0);
+
+        let graph = budgeted.substrate_mut().graph_mut();
+        maintenance.catch_up(graph, &multipliers, usize::MAX);
+        assert_same_graph(&unbudgeted, &budgeted);
+    }
+}
@@ -0,0 +1,81 @@
+//! Vocabulary convergence — whether the agents' vocabularies grow together
+//! as transfers and symbioses happen.
+//!
+//! A [`VocabularyConvergence`] records the [`OverlapSummary`] of the
+//! colony's [`vocabulary_report`](Colony::vocabulary_report) each time it
+//! is asked to, e.g. every few ticks of a run, and prints the series.
+
+use crate::colony::Colony;
+use crate::vocabulary::OverlapSummary;
+use serde::Serialize;
+
+/// Agent terms compared when no other number is given.
+pub const DEFAULT_CONVERGENCE_TERMS: usize = 50;
+
+/// Overlap summaries of a colony's vocabularies, in the order recorded.
+#[derive(Debug, Clone, Serialize)]
+pub struct VocabularyConvergence {
+    /// Terms taken per agent.
+    pub top_n: usize,
+    pub samples: Vec<OverlapSummary>,
+}
+
+impl Default for VocabularyConvergence {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONVERGENCE_TERMS)
+    }
+}
+
+impl VocabularyConvergence {
+    /// Compare up to `top_n` terms of each agent.
+    pub fn new(top_n: usize) -> Self {
+        Self {
+            top_n,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Record the overlap of the colony's vocabularies now.
+    pub fn record(&mut self, colony: &Colony) -> OverlapSummary {
+        let summary = colony.vocabulary_report(self.top_n).summary();
+        self.samples.push(summary);
+        summary
+    }
+
+    /// The latest summary recorded.
+    pub fn latest(&self) -> Option<&OverlapSummary> {
+        self.samples.last()
+    }
+
+    /// Change in mean overlap from the first summary to the latest.
+    pub fn mean_jaccard_change(&self) -> f64 {
+        match (self.samples.first(), self.samples.last()) {
+            (Some(first), Some(last)) => last.mean_jaccard - first.mean_jaccard,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Print the recorded summaries as a table, one row per sample.
+pub fn print_convergence(convergence: &VocabularyConvergence) {
+    println!("── Vocabulary Convergence ──────────────────────────");
+    println!(
+        "  {:>6}  {:>6}  {:>6}  {:>9}  {:>8}  {:>8}",
+        "tick", "agents", "pairs", "mean jacc", "max jacc", "in graph"
+    );
+    for sample in &convergence.samples {
+        println!(
+            "  {:>6}  {:>6}  {:>6}  {:>9.3}  {:>8.3}  {:>7.1}%",
+            sample.tick,
+            sample.agents,
+            sample.pairs,
+            sample.mean_jaccard,
+            sample.max_jaccard,
+            sample.graph_coverage * 100.0
+        );
+    }
+    println!(
+        "  Mean overlap change:        {:+.3}",
+        convergence.mean_jaccard_change()
+    );
+}
@@ -5,9 +5,21 @@
 //! - Dissolution Effect: boundary modulation reinforces knowledge
 //! - Graph Richness: structural complexity of the knowledge graph
 //! - Vocabulary Spread: how well knowledge propagates
+//!
+//! Operational metrics for long-running servers live in [`prometheus`];
+//! agent survival and productivity in [`population`]; how agents'
+//! vocabularies converge over a run in [`convergence`].
+
+pub mod convergence;
+pub mod population;
+pub mod prometheus;
 
-use crate::colony::{Colony, ColonyEvent, ColonySnapshot};
-use phago_core::topology::TopologyGraph;
+pub use convergence::{print_convergence, VocabularyConvergence};
+pub use population::{population_report, PopulationReport};
+pub use prometheus::PromRegistry;
+
+use crate::colony::{AgentSnapshot, Colony, ColonyEvent, ColonySnapshot};
+use crate::snapshot::SnapshotSeries;
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 
@@ -94,10 +106,24 @@ pub fn compute(colony: &Colony) -> ColonyMetrics {
 
 /// Compute metrics from snapshots (for use when agents may be dead).
 pub fn compute_from_snapshots(colony: &Colony, snapshots: &[ColonySnapshot]) -> ColonyMetrics {
-    let transfer = compute_transfer_from_snapshots(colony, snapshots);
+    // Agents of the snapshot with the most agents alive (peak activity)
+    let peak = snapshots
+        .iter()
+        .max_by_key(|s| s.agents.len())
+        .map(|s| s.agents.as_slice());
+    compute_from_peak_agents(colony, peak)
+}
+
+/// Compute metrics from a recorded series, whatever its snapshot mode.
+pub fn compute_from_series(colony: &Colony, series: &SnapshotSeries) -> ColonyMetrics {
+    compute_from_peak_agents(colony, series.peak_agents())
+}
+
+fn compute_from_peak_agents(colony: &Colony, peak: Option<&[AgentSnapshot]>) -> ColonyMetrics {
+    let transfer = compute_transfer_from_snapshots(colony, peak);
     let dissolution = compute_dissolution(colony);
     let graph_richness = compute_graph_richness(colony);
-    let vocabulary_spread = compute_vocabulary_spread_from_snapshots(snapshots);
+    let vocabulary_spread = compute_vocabulary_spread_from_snapshots(peak);
 
     ColonyMetrics {
         transfer,
@@ -163,7 +189,7 @@ fn compute_transfer(colony: &Colony) -> TransferMetrics {
     // Nodes with access_count > 1 were reinforced by multiple agents
     let shared_terms = all_nodes
         .iter()
-        .filter(|nid| graph.get_node(nid).map_or(false, |n| n.access_count > 1))
+        .filter(|nid| graph.get_node(nid).is_some_and(|n| n.access_count > 1))
         .count();
     let shared_term_ratio = if total_terms > 0 {
         shared_terms as f64 / total_terms as f64
@@ -176,8 +202,8 @@ fn compute_transfer(colony: &Colony) -> TransferMetrics {
         .event_history()
         .iter()
         .filter_map(|(_, event)| {
-            if let ColonyEvent::CapabilityIntegrated { terms_count, .. } = event {
-                Some(*terms_count)
+            if let ColonyEvent::CapabilityIntegrated { terms_accepted, .. } = event {
+                Some(*terms_accepted)
             } else {
                 None
             }
@@ -201,7 +227,7 @@ fn compute_transfer(colony: &Colony) -> TransferMetrics {
 
 fn compute_transfer_from_snapshots(
     colony: &Colony,
-    snapshots: &[ColonySnapshot],
+    peak: Option<&[AgentSnapshot]>,
 ) -> TransferMetrics {
     let mut total_exports = 0usize;
     let mut total_integrations = 0usize;
@@ -214,21 +240,11 @@ fn compute_transfer_from_snapshots(
         }
     }
 
-    // Find the snapshot with the most agents alive (peak activity)
-    let best_snapshot = snapshots
-        .iter()
-        .max_by_key(|s| s.agents.len())
-        .or(snapshots.last());
-
-    if let Some(snap) = best_snapshot {
-        if !snap.agents.is_empty() {
-            let sizes = &snap
-                .agents
-                .iter()
-                .map(|a| a.vocabulary_size)
-                .collect::<Vec<_>>();
+    if let Some(agents) = peak {
+        if !agents.is_empty() {
+            let sizes = &agents.iter().map(|a| a.vocabulary_size).collect::<Vec<_>>();
             let total_vocab: usize = sizes.iter().sum();
-            let avg_vocabulary_size = total_vocab as f64 / snap.agents.len() as f64;
+            let avg_vocabulary_size = total_vocab as f64 / agents.len() as f64;
 
             // Approximate shared terms from graph reinforcement
             let graph = colony.substrate().graph();
@@ -236,7 +252,7 @@ fn compute_transfer_from_snapshots(
             let total_terms = all_nodes.len();
             let shared_terms = all_nodes
                 .iter()
-                .filter(|nid| graph.get_node(nid).map_or(false, |n| n.access_count > 1))
+                .filter(|nid| graph.get_node(nid).is_some_and(|n| n.access_count > 1))
                 .count();
             let shared_term_ratio = if total_terms > 0 {
                 shared_terms as f64 / total_terms as f64
@@ -411,14 +427,11 @@ fn compute_vocabulary_spread(colony: &Colony) -> VocabularySpreadMetrics {
 }
 
 fn compute_vocabulary_spread_from_snapshots(
-    snapshots: &[ColonySnapshot],
+    peak: Option<&[AgentSnapshot]>,
 ) -> VocabularySpreadMetrics {
-    // Find the snapshot with the most agents (peak activity)
-    let best_snapshot = snapshots.iter().max_by_key(|s| s.agents.len());
-
-    if let Some(snap) = best_snapshot {
-        if !snap.agents.is_empty() {
-            let sizes: Vec<usize> = snap.agents.iter().map(|a| a.vocabulary_size).collect();
+    if let Some(agents) = peak {
+        if !agents.is_empty() {
+            let sizes: Vec<usize> = agents.iter().map(|a| a.vocabulary_size).collect();
             let max_vocabulary = *sizes.iter().max().unwrap_or(&0);
             let min_vocabulary = *sizes.iter().min().unwrap_or(&0);
             let gini_coefficient = compute_gini(&sizes);
@@ -0,0 +1,502 @@
+//! Population metrics — how long agents live, why they die, and what they
+//! contribute over a lifetime.
+//!
+//! [`population_report`] aggregates the colony's [`AgentLifespan`]s and
+//! fitness counters:
+//! - Kaplan-Meier survival curves per agent type and per generation, with
+//!   agents still alive counted as censored at their current age
+//! - Causes of death, bucketed by the tick the agents died in
+//! - Concepts and edges contributed per agent lifetime, per generation
+//!
+//! Generations come from the colony's [`FitnessTracker`]; agents spawned
+//! with [`Colony::spawn`] are generation 0 until registered otherwise.
+//!
+//! [`FitnessTracker`]: phago_agents::fitness::FitnessTracker
+
+use crate::colony::{AgentLifespan, Colony};
+use phago_core::substrate::Substrate;
+use phago_core::types::{CellHealth, DeathCause, Tick};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Number of tick ranges deaths are bucketed into by [`population_report`].
+pub const DEATH_BUCKETS: u64 = 10;
+
+/// One step of a survival curve: at `ticks`, `deaths` of the `at_risk`
+/// agents still alive died.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SurvivalPoint {
+    pub ticks: u64,
+    pub at_risk: usize,
+    pub deaths: usize,
+    /// Estimated fraction of agents surviving past `ticks`.
+    pub survival: f64,
+}
+
+/// Kaplan-Meier survival of one group of agents.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SurvivalCurve {
+    /// Agent type or generation the curve is for.
+    pub group: String,
+    pub agents: usize,
+    pub deaths: usize,
+    /// Agents still alive, whose lifespans are not known yet.
+    pub censored: usize,
+    /// One point per lifespan at which an agent died, shortest first.
+    pub points: Vec<SurvivalPoint>,
+    /// Lifespan at which survival falls to one half or below; `None` if
+    /// too many agents are still alive to tell.
+    pub median_lifespan: Option<u64>,
+    /// Mean lifespan of the agents that died.
+    pub mean_lifespan: Option<f64>,
+}
+
+/// Deaths in the ticks `from..to`, by cause.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeathBucket {
+    pub from: Tick,
+    pub to: Tick,
+    pub causes: BTreeMap<String, usize>,
+}
+
+/// Summary of a distribution of per-agent values.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Distribution {
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub median: f64,
+    pub p90: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    fn of(mut values: Vec<f64>) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_by(f64::total_cmp);
+        let at = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+        Self {
+            count: values.len(),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            min: values[0],
+            median: at(0.5),
+            p90: at(0.9),
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+/// What the agents of one generation contributed per lifetime. Living
+/// agents count with what they contributed so far.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GenerationProductivity {
+    pub generation: u32,
+    pub agents: usize,
+    pub concepts: Distribution,
+    pub edges: Distribution,
+    /// Concepts and edges per tick alive.
+    pub per_tick: Distribution,
+}
+
+/// Survival, causes of death and productivity of a colony's agents.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PopulationReport {
+    pub tick: Tick,
+    pub alive: usize,
+    pub dead: usize,
+    pub max_generation: u32,
+    pub survival_by_type: Vec<SurvivalCurve>,
+    pub survival_by_generation: Vec<SurvivalCurve>,
+    pub deaths_over_time: Vec<DeathBucket>,
+    pub productivity: Vec<GenerationProductivity>,
+}
+
+/// Population report with deaths bucketed into [`DEATH_BUCKETS`] tick
+/// ranges.
+pub fn population_report(colony: &Colony) -> PopulationReport {
+    let now = colony.substrate().current_tick();
+    population_report_bucketed(colony, now.div_ceil(DEATH_BUCKETS).max(1))
+}
+
+/// Population report with deaths bucketed into ranges of `bucket_ticks`.
+pub fn population_report_bucketed(colony: &Colony, bucket_ticks: u64) -> PopulationReport {
+    let now = colony.substrate().current_tick();
+    let bucket_ticks = bucket_ticks.max(1);
+    let tracker = colony.fitness_tracker();
+    let generation_of = |l: &AgentLifespan| tracker.get(&l.agent_id).map_or(0, |f| f.generation);
+
+    let mut lifespans: Vec<&AgentLifespan> = colony.lifespans().collect();
+    lifespans.sort_by_key(|l| (l.born, l.agent_id.0));
+
+    let mut by_type: BTreeMap<&str, Vec<&AgentLifespan>> = BTreeMap::new();
+    let mut by_generation: BTreeMap<u32, Vec<&AgentLifespan>> = BTreeMap::new();
+    for &lifespan in &lifespans {
+        by_type
+            .entry(lifespan.agent_type.as_str())
+            .or_default()
+            .push(lifespan);
+        by_generation
+            .entry(generation_of(lifespan))
+            .or_default()
+            .push(lifespan);
+    }
+
+    let mut buckets: BTreeMap<Tick, BTreeMap<String, usize>> = BTreeMap::new();
+    for lifespan in &lifespans {
+        if let Some((tick, cause)) = &lifespan.died {
+            *buckets
+                .entry(tick / bucket_ticks)
+                .or_default()
+                .entry(cause_label(cause).to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
+    let productivity = by_generation
+        .iter()
+        .map(|(&generation, members)| {
+            let counters: Vec<_> = members
+                .iter()
+                .filter_map(|l| tracker.get(&l.agent_id).map(|f| (l, f)))
+                .collect();
+            GenerationProductivity {
+                generation,
+                agents: members.len(),
+                concepts: Distribution::of(
+                    counters
+                        .iter()
+                        .map(|(_, f)| f.concepts_added as f64)
+                        .collect(),
+                ),
+                edges: Distribution::of(
+                    counters
+                        .iter()
+                        .map(|(_, f)| f.edges_contributed as f64)
+                        .collect(),
+                ),
+                per_tick: Distribution::of(
+                    counters
+                        .iter()
+                        .map(|(l, f)| {
+                            (f.concepts_added + f.edges_contributed) as f64
+                                / l.ticks(now).max(1) as f64
+                        })
+                        .collect(),
+                ),
+            }
+        })
+        .collect();
+
+    let dead = lifespans.iter().filter(|l| l.died.is_some()).count();
+    PopulationReport {
+        tick: now,
+        alive: lifespans.len() - dead,
+        dead,
+        max_generation: by_generation.keys().last().copied().unwrap_or(0),
+        survival_by_type: by_type
+            .into_iter()
+            .map(|(agent_type, members)| survival_curve(agent_type.to_string(), &members, now))
+            .collect(),
+        survival_by_generation: by_generation
+            .into_iter()
+            .map(|(generation, members)| survival_curve(generation.to_string(), &members, now))
+            .collect(),
+        deaths_over_time: buckets
+            .into_iter()
+            .map(|(bucket, causes)| DeathBucket {
+                from: bucket * bucket_ticks,
+                to: (bucket + 1) * bucket_ticks,
+                causes,
+            })
+            .collect(),
+        productivity,
+    }
+}
+
+/// Kaplan-Meier estimate over `members`, censoring the living at `now`.
+fn survival_curve(group: String, members: &[&AgentLifespan], now: Tick) -> SurvivalCurve {
+    // Lifespan → (deaths, censored) at that lifespan
+    let mut at: BTreeMap<u64, (usize, usize)> = BTreeMap::new();
+    for lifespan in members {
+        let entry = at.entry(lifespan.ticks(now)).or_default();
+        match lifespan.died {
+            Some(_) => entry.0 += 1,
+            None => entry.1 += 1,
+        }
+    }
+
+    let mut at_risk = members.len();
+    let mut survival = 1.0;
+    let mut points = Vec::new();
+    let mut median_lifespan = None;
+    for (ticks, (deaths, censored)) in at {
+        if deaths > 0 {
+            survival *= 1.0 - deaths as f64 / at_risk as f64;
+            points.push(SurvivalPoint {
+                ticks,
+                at_risk,
+                deaths,
+                survival,
+            });
+            if median_lifespan.is_none() && survival <= 0.5 {
+                median_lifespan = Some(ticks);
+            }
+        }
+        at_risk -= deaths + censored;
+    }
+
+    let lived: Vec<u64> = members
+        .iter()
+        .filter(|l| l.died.is_some())
+        .map(|l| l.ticks(now))
+        .collect();
+    SurvivalCurve {
+        group,
+        agents: members.len(),
+        deaths: lived.len(),
+        censored: members.len() - lived.len(),
+        points,
+        median_lifespan,
+        mean_lifespan: (!lived.is_empty())
+            .then(|| lived.iter().sum::<u64>() as f64 / lived.len() as f64),
+    }
+}
+
+/// Short name of a cause of death, as used in reports.
+pub fn cause_label(cause: &DeathCause) -> &'static str {
+    match cause {
+        DeathCause::SelfAssessed(CellHealth::Healthy) => "self_assessed_healthy",
+        DeathCause::SelfAssessed(CellHealth::Stressed) => "self_assessed_stressed",
+        DeathCause::SelfAssessed(CellHealth::Compromised) => "compromised",
+        DeathCause::SelfAssessed(CellHealth::Redundant) => "redundant",
+        DeathCause::SelfAssessed(CellHealth::Senescent) => "senescent",
+        DeathCause::ExternalSignal => "external_signal",
+        DeathCause::RuntimeTermination => "runtime_termination",
+        DeathCause::SymbioticAbsorption(_) => "symbiotic_absorption",
+    }
+}
+
+impl PopulationReport {
+    /// Survival curves as CSV, one row per point, by type then generation.
+    pub fn survival_csv(&self) -> String {
+        let mut csv = String::from("tick,grouping,group,lifespan,at_risk,deaths,survival\n");
+        for (grouping, curves) in [
+            ("type", &self.survival_by_type),
+            ("generation", &self.survival_by_generation),
+        ] {
+            for curve in curves {
+                for point in &curve.points {
+                    let _ = writeln!(
+                        csv,
+                        "{},{},{},{},{},{},{:.4}",
+                        self.tick,
+                        grouping,
+                        curve.group,
+                        point.ticks,
+                        point.at_risk,
+                        point.deaths,
+                        point.survival
+                    );
+                }
+            }
+        }
+        csv
+    }
+
+    /// Deaths per tick range and cause as CSV.
+    pub fn deaths_csv(&self) -> String {
+        let mut csv = String::from("tick,from,to,cause,deaths\n");
+        for bucket in &self.deaths_over_time {
+            for (cause, deaths) in &bucket.causes {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{}",
+                    self.tick, bucket.from, bucket.to, cause, deaths
+                );
+            }
+        }
+        csv
+    }
+
+    /// Per-generation lifespans and productivity as CSV.
+    pub fn generations_csv(&self) -> String {
+        let mut csv = String::from(
+            "tick,generation,agents,deaths,median_lifespan,mean_lifespan,\
+             mean_concepts,mean_edges,median_per_tick,p90_per_tick\n",
+        );
+        for (curve, productivity) in self.survival_by_generation.iter().zip(&self.productivity) {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{:.2},{:.2},{:.4},{:.4}",
+                self.tick,
+                productivity.generation,
+                curve.agents,
+                curve.deaths,
+                curve
+                    .median_lifespan
+                    .map_or(String::new(), |m| m.to_string()),
+                curve
+                    .mean_lifespan
+                    .map_or(String::new(), |m| format!("{m:.2}")),
+                productivity.concepts.mean,
+                productivity.edges.mean,
+                productivity.per_tick.median,
+                productivity.per_tick.p90
+            );
+        }
+        csv
+    }
+}
+
+/// Survival curves printed per grouping by [`print_population_report`].
+const PRINTED_GROUPS: usize = 8;
+
+/// Print a formatted population report to the terminal.
+pub fn print_population_report(report: &PopulationReport) {
+    println!(
+        "── Population at tick {} ─────────────────────────",
+        report.tick
+    );
+    println!(
+        "  Alive / dead:               {} / {}",
+        report.alive, report.dead
+    );
+    println!("  Max generation:             {}", report.max_generation);
+    for (grouping, curves) in [
+        ("Type", &report.survival_by_type),
+        ("Generation", &report.survival_by_generation),
+    ] {
+        for curve in curves.iter().take(PRINTED_GROUPS) {
+            println!(
+                "  {} {:<12} agents={:>3} died={:>3} median={:>5} mean={:>7}",
+                grouping,
+                curve.group,
+                curve.agents,
+                curve.deaths,
+                curve
+                    .median_lifespan
+                    .map_or("-".to_string(), |m| m.to_string()),
+                curve
+                    .mean_lifespan
+                    .map_or("-".to_string(), |m| format!("{m:.1}"))
+            );
+        }
+        if curves.len() > PRINTED_GROUPS {
+            println!("  ... {} more", curves.len() - PRINTED_GROUPS);
+        }
+    }
+    for bucket in &report.deaths_over_time {
+        let causes: Vec<String> = bucket
+            .causes
+            .iter()
+            .map(|(cause, n)| format!("{cause}={n}"))
+            .collect();
+        println!(
+            "  Deaths {:>5}..{:<5}         {}",
+            bucket.from,
+            bucket.to,
+            causes.join(" ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_agents::digester::Digester;
+    use phago_core::types::Position;
+
+    /// `short` digesters idling out after 5 ticks, `long` after 20, and
+    /// one that outlives the run.
+    fn scripted_colony(short: usize, long: usize) -> Colony {
+        let mut colony = Colony::new();
+        for _ in 0..short {
+            colony.spawn(Box::new(
+                Digester::new(Position::new(0.0, 0.0)).with_max_idle(5),
+            ));
+        }
+        for _ in 0..long {
+            colony.spawn(Box::new(
+                Digester::new(Position::new(0.0, 0.0)).with_max_idle(20),
+            ));
+        }
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(1_000),
+        ));
+        colony
+    }
+
+    #[test]
+    fn scripted_lifespans_give_the_expected_medians_and_causes() {
+        let mut colony = scripted_colony(4, 3);
+        colony.run(40);
+        let report = population_report(&colony);
+        assert_eq!((report.alive, report.dead), (1, 7));
+
+        let lifespans: Vec<u64> = {
+            let mut l: Vec<u64> = colony
+                .lifespans()
+                .filter(|l| l.died.is_some())
+                .map(|l| l.ticks(report.tick))
+                .collect();
+            l.sort();
+            l.dedup();
+            l
+        };
+        let (short, long) = (lifespans[0], lifespans[1]);
+        assert_eq!(lifespans.len(), 2);
+        assert!(short < long);
+
+        let curve = &report.survival_by_type[0];
+        assert_eq!(curve.group, "digester");
+        assert_eq!((curve.agents, curve.deaths, curve.censored), (8, 7, 1));
+        // Half of the 8 agents die at the short lifespan
+        assert_eq!(curve.median_lifespan, Some(short));
+        let mean = (4 * short + 3 * long) as f64 / 7.0;
+        assert!((curve.mean_lifespan.unwrap() - mean).abs() < 1e-9);
+        assert_eq!(curve.points.len(), 2);
+        assert!((curve.points[0].survival - 0.5).abs() < 1e-9);
+        assert!((curve.points[1].survival - 0.5 / 4.0).abs() < 1e-9);
+
+        let senescent: usize = report
+            .deaths_over_time
+            .iter()
+            .map(|b| b.causes.get("senescent").copied().unwrap_or(0))
+            .sum();
+        assert_eq!(senescent, 7);
+        assert!(report.deaths_over_time.len() >= 2);
+    }
+
+    #[test]
+    fn survival_splits_by_generation() {
+        let mut colony = scripted_colony(2, 0);
+        let offspring = colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(20),
+        ));
+        colony.fitness_tracker_mut().register(offspring, 1);
+        colony.run(10);
+
+        let report = population_report(&colony);
+        assert_eq!(report.max_generation, 1);
+        let [first, second] = &report.survival_by_generation[..] else {
+            panic!("expected two generations");
+        };
+        assert_eq!(
+            (first.group.as_str(), first.agents, first.deaths),
+            ("0", 3, 2)
+        );
+        assert_eq!(first.median_lifespan, first.points.first().map(|p| p.ticks));
+        assert_eq!((second.agents, second.deaths), (1, 0));
+        assert_eq!(second.median_lifespan, None);
+        assert_eq!(report.productivity.len(), 2);
+        assert_eq!(report.productivity[1].agents, 1);
+
+        let csv = report.generations_csv();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(report.survival_csv().contains(",generation,0,"));
+        assert!(report.deaths_csv().contains(",senescent,2"));
+    }
+}
@@ -0,0 +1,649 @@
+//! Operational metrics in the Prometheus text exposition format.
+//!
+//! A [`PromRegistry`] is shared between a server and the colonies it runs.
+//! Each colony attached with [`Colony::attach_metrics`] pushes its counts
+//! into the registry at the end of every tick, reusing the numbers it
+//! already tracks, and servers render the registry on scrape with
+//! [`PromRegistry::encode`].
+//!
+//! Colony series carry a `space` label so one registry can serve several
+//! colonies (the MCP server runs one per knowledge space). Counters only
+//! ever grow: a colony that is reloaded from disk starts counting again
+//! from zero, and the registry adds its new counts on top.
+//!
+//! [`Colony::attach_metrics`]: crate::colony::Colony::attach_metrics
+
+use crate::colony::{ColonyEvent, ColonyStats};
+use crate::query_log::QuerySource;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Content type of [`PromRegistry::encode`] output.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Bucket bounds (seconds) for tick durations.
+const TICK_BUCKETS: &[f64] = &[
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 1.0,
+];
+
+/// Bucket bounds (seconds) for query latencies.
+const QUERY_BUCKETS: &[f64] = &[0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// A per-space gauge: name, help text and how to read it from the stats.
+type StatGauge = (&'static str, &'static str, fn(&ColonyStats) -> f64);
+
+/// A fixed-bucket histogram.
+#[derive(Debug, Clone)]
+struct Histogram {
+    bounds: &'static [f64],
+    /// Observations per bucket (not cumulative); the last is `+Inf`.
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// The latest counts reported by one colony.
+#[derive(Debug, Clone, Default)]
+struct SpaceState {
+    stats: Option<ColonyStats>,
+    /// `agents_died` at the last report, to turn it into a running total.
+    last_died: usize,
+    agents_died_total: u64,
+    events: BTreeMap<&'static str, u64>,
+    tick_duration: Option<Histogram>,
+    last_save: Option<(SystemTime, Duration)>,
+}
+
+impl SpaceState {
+    fn update_stats(&mut self, stats: &ColonyStats) {
+        // A reloaded colony counts from zero again
+        let died = if stats.agents_died >= self.last_died {
+            stats.agents_died - self.last_died
+        } else {
+            stats.agents_died
+        };
+        self.agents_died_total += died as u64;
+        self.last_died = stats.agents_died;
+        self.stats = Some(stats.clone());
+    }
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    spaces: BTreeMap<String, SpaceState>,
+    queries: BTreeMap<&'static str, Histogram>,
+}
+
+/// Operational metrics shared by a server and its colonies.
+///
+/// Recording takes a short lock and does no graph work; rendering happens
+/// only when [`encode`](Self::encode) is called.
+#[derive(Debug, Default)]
+pub struct PromRegistry {
+    state: Mutex<RegistryState>,
+}
+
+impl PromRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the gauges of `space` without counting a tick, e.g. right
+    /// after a colony is restored.
+    pub fn record_stats(&self, space: &str, stats: &ColonyStats) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .spaces
+            .entry(space.to_string())
+            .or_default()
+            .update_stats(stats);
+    }
+
+    /// Record a completed tick of `space`: its gauges, the events it
+    /// emitted and how long it took.
+    pub fn record_tick(
+        &self,
+        space: &str,
+        stats: &ColonyStats,
+        events: &[ColonyEvent],
+        duration: Duration,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        let space = state.spaces.entry(space.to_string()).or_default();
+        space.update_stats(stats);
+        for event in events {
+            *space.events.entry(event.kind()).or_insert(0) += 1;
+        }
+        space
+            .tick_duration
+            .get_or_insert_with(|| Histogram::new(TICK_BUCKETS))
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record how long a query from `source` took.
+    pub fn record_query(&self, source: QuerySource, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .queries
+            .entry(source.as_str())
+            .or_insert_with(|| Histogram::new(QUERY_BUCKETS))
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record that `space` finished saving at `at` after `duration`.
+    pub fn record_save(&self, space: &str, at: SystemTime, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.spaces.entry(space.to_string()).or_default().last_save = Some((at, duration));
+    }
+
+    /// Render every metric in the Prometheus text format.
+    pub fn encode(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        let gauges: [StatGauge; 12] = [
+            ("phago_colony_tick", "Current colony tick.", |s| {
+                s.tick as f64
+            }),
+            ("phago_graph_nodes", "Nodes in the knowledge graph.", |s| {
+                s.graph_nodes as f64
+            }),
+            ("phago_graph_edges", "Edges in the knowledge graph.", |s| {
+                s.graph_edges as f64
+            }),
+            ("phago_agents_alive", "Agents currently alive.", |s| {
+                s.agents_alive as f64
+            }),
+            ("phago_documents", "Documents ingested.", |s| {
+                s.documents_total as f64
+            }),
+            ("phago_documents_digested", "Documents digested.", |s| {
+                s.documents_digested as f64
+            }),
+            ("phago_signals", "Signals in the substrate.", |s| {
+                s.total_signals as f64
+            }),
+            (
+                "phago_action_failures",
+                "Agent actions that could not be applied.",
+                |s| s.errors.total() as f64,
+            ),
+            (
+                "phago_signal_decay_rate",
+                "Rate signals decayed at in the last tick.",
+                |s| s.decay.signal,
+            ),
+            (
+                "phago_trace_decay_rate",
+                "Rate traces decayed at in the last tick.",
+                |s| s.decay.trace,
+            ),
+            (
+                "phago_edge_decay_rate",
+                "Base rate edges decayed at in the last tick.",
+                |s| s.decay.edge,
+            ),
+            (
+                "phago_exploitation",
+                "How far agents exploit rather than explore (0 to 1).",
+                |s| s.exploitation,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            header(&mut out, name, help, "gauge");
+            for (space, s) in &state.spaces {
+                if let Some(stats) = &s.stats {
+                    sample(&mut out, name, &[("space", space)], value(stats));
+                }
+            }
+        }
+
+        header(
+            &mut out,
+            "phago_agents_died_total",
+            "Agents that have died.",
+            "counter",
+        );
+        for (space, s) in &state.spaces {
+            let value = s.agents_died_total as f64;
+            sample(
+                &mut out,
+                "phago_agents_died_total",
+                &[("space", space)],
+                value,
+            );
+        }
+
+        header(
+            &mut out,
+            "phago_colony_events_total",
+            "Colony events by type.",
+            "counter",
+        );
+        for (space, s) in &state.spaces {
+            for (kind, count) in &s.events {
+                let labels = [("space", space.as_str()), ("type", kind)];
+                sample(
+                    &mut out,
+                    "phago_colony_events_total",
+                    &labels,
+                    *count as f64,
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "phago_tick_duration_seconds",
+            "Time taken by a colony tick.",
+            "histogram",
+        );
+        for (space, s) in &state.spaces {
+            if let Some(histogram) = &s.tick_duration {
+                histogram_samples(
+                    &mut out,
+                    "phago_tick_duration_seconds",
+                    ("space", space),
+                    histogram,
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "phago_query_duration_seconds",
+            "Time taken by a query.",
+            "histogram",
+        );
+        for (source, histogram) in &state.queries {
+            histogram_samples(
+                &mut out,
+                "phago_query_duration_seconds",
+                ("source", source),
+                histogram,
+            );
+        }
+
+        header(
+            &mut out,
+            "phago_last_save_timestamp_seconds",
+            "Unix time the colony was last saved.",
+            "gauge",
+        );
+        for (space, s) in &state.spaces {
+            if let Some((at, _)) = s.last_save {
+                let secs = at
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                sample(
+                    &mut out,
+                    "phago_last_save_timestamp_seconds",
+                    &[("space", space)],
+                    secs,
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "phago_last_save_duration_seconds",
+            "Time taken by the last save.",
+            "gauge",
+        );
+        for (space, s) in &state.spaces {
+            if let Some((_, duration)) = s.last_save {
+                let secs = duration.as_secs_f64();
+                sample(
+                    &mut out,
+                    "phago_last_save_duration_seconds",
+                    &[("space", space)],
+                    secs,
+                );
+            }
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        out.push('{');
+        for (i, (key, value)) in labels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{key}=\"{}\"", escape_label(value));
+        }
+        out.push('}');
+    }
+    let _ = writeln!(out, " {}", format_value(value));
+}
+
+fn histogram_samples(out: &mut String, name: &str, label: (&str, &str), histogram: &Histogram) {
+    let bucket = format!("{name}_bucket");
+    let mut cumulative = 0;
+    for (i, count) in histogram.counts.iter().enumerate() {
+        cumulative += count;
+        let le = histogram
+            .bounds
+            .get(i)
+            .map_or_else(|| "+Inf".to_string(), |b| format_value(*b));
+        sample(out, &bucket, &[label, ("le", &le)], cumulative as f64);
+    }
+    sample(out, &format!("{name}_sum"), &[label], histogram.sum);
+    sample(
+        out,
+        &format!("{name}_count"),
+        &[label],
+        histogram.count as f64,
+    );
+}
+
+/// Escape a label value: backslash, double quote and line feed.
+fn escape_label(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colony::Colony;
+    use phago_agents::digester::Digester;
+    use phago_core::types::Position;
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Arc;
+
+    /// A parsed sample: metric name, labels and value.
+    type Sample = (String, Vec<(String, String)>, f64);
+
+    /// Parse exposition text, checking the format as it goes: every sample
+    /// belongs to a family declared once with HELP and TYPE, and label
+    /// values are properly quoted and escaped.
+    fn parse(text: &str) -> Vec<Sample> {
+        let mut types: HashMap<String, String> = HashMap::new();
+        let mut helped = HashSet::new();
+        let mut samples = Vec::new();
+
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix("# HELP ") {
+                let name = rest.split(' ').next().unwrap();
+                assert!(helped.insert(name.to_string()), "duplicate HELP for {name}");
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = rest.split_once(' ').unwrap();
+                assert!(
+                    ["counter", "gauge", "histogram"].contains(&kind),
+                    "unknown type {kind}"
+                );
+                let previous = types.insert(name.to_string(), kind.to_string());
+                assert!(previous.is_none(), "duplicate TYPE for {name}");
+                continue;
+            }
+
+            let (name, rest) = match line.find('{') {
+                Some(i) => (&line[..i], &line[i..]),
+                None => line.split_at(line.find(' ').unwrap()),
+            };
+            assert!(
+                name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                "bad metric name {name:?}"
+            );
+            let (labels, value) = if let Some(rest) = rest.strip_prefix('{') {
+                parse_labels(rest)
+            } else {
+                (Vec::new(), rest)
+            };
+            let value = match value.trim() {
+                "+Inf" => f64::INFINITY,
+                "-Inf" => f64::NEG_INFINITY,
+                v => v
+                    .parse()
+                    .unwrap_or_else(|_| panic!("bad value in {line:?}")),
+            };
+
+            let family = ["_bucket", "_sum", "_count"]
+                .iter()
+                .find_map(|suffix| {
+                    let base = name.strip_suffix(suffix)?;
+                    (types.get(base)? == "histogram").then_some(base)
+                })
+                .unwrap_or(name);
+            assert!(types.contains_key(family), "{name} has no TYPE line");
+            samples.push((name.to_string(), labels, value));
+        }
+        samples
+    }
+
+    /// Parse `key="value",...}` and return the labels and the rest of the line.
+    fn parse_labels(mut rest: &str) -> (Vec<(String, String)>, &str) {
+        let mut labels = Vec::new();
+        loop {
+            if let Some(after) = rest.strip_prefix('}') {
+                return (labels, after);
+            }
+            let (key, after) = rest.split_once("=\"").expect("label without value");
+            let mut value = String::new();
+            let mut chars = after.char_indices();
+            let end = loop {
+                match chars.next().expect("unterminated label value") {
+                    (_, '\\') => match chars.next().unwrap().1 {
+                        '\\' => value.push('\\'),
+                        '"' => value.push('"'),
+                        'n' => value.push('\n'),
+                        other => panic!("bad escape \\{other}"),
+                    },
+                    (i, '"') => break i,
+                    (_, c) => value.push(c),
+                }
+            };
+            labels.push((key.to_string(), value));
+            rest = after[end + 1..]
+                .strip_prefix(',')
+                .unwrap_or(&after[end + 1..]);
+        }
+    }
+
+    fn value(samples: &[Sample], name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        samples
+            .iter()
+            .find(|(n, l, _)| {
+                n == name
+                    && labels
+                        .iter()
+                        .all(|(k, v)| l.iter().any(|(lk, lv)| lk == k && lv == v))
+            })
+            .map(|(_, _, v)| *v)
+    }
+
+    fn colony_with_document() -> Colony {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Biology",
+            "The cell membrane controls transport. Proteins act as channels.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(3),
+        ));
+        colony
+    }
+
+    #[test]
+    fn encodes_parseable_text_with_escaped_labels() {
+        let registry = Arc::new(PromRegistry::new());
+        let mut colony = colony_with_document();
+        colony.attach_metrics(registry.clone(), "odd \"space\"\\\nname");
+        colony.run(5);
+        registry.record_query(QuerySource::Mcp, Duration::from_millis(3));
+        registry.record_save(
+            "odd \"space\"\\\nname",
+            SystemTime::now(),
+            Duration::from_millis(12),
+        );
+
+        let text = registry.encode();
+        assert!(text.contains(r#"space="odd \"space\"\\\nname""#));
+        let samples = parse(&text);
+
+        let space = "odd \"space\"\\\nname";
+        assert_eq!(
+            value(&samples, "phago_colony_tick", &[("space", space)]),
+            Some(5.0)
+        );
+        assert!(value(&samples, "phago_graph_nodes", &[("space", space)]).unwrap() > 0.0);
+        assert_eq!(
+            value(
+                &samples,
+                "phago_colony_events_total",
+                &[("space", space), ("type", "tick_complete")]
+            ),
+            Some(5.0)
+        );
+        assert_eq!(
+            value(
+                &samples,
+                "phago_tick_duration_seconds_count",
+                &[("space", space)]
+            ),
+            Some(5.0)
+        );
+        assert_eq!(
+            value(
+                &samples,
+                "phago_tick_duration_seconds_bucket",
+                &[("space", space), ("le", "+Inf")]
+            ),
+            Some(5.0)
+        );
+        assert_eq!(
+            value(
+                &samples,
+                "phago_query_duration_seconds_count",
+                &[("source", "mcp")]
+            ),
+            Some(1.0)
+        );
+        assert_eq!(
+            value(
+                &samples,
+                "phago_last_save_duration_seconds",
+                &[("space", space)]
+            ),
+            Some(0.012)
+        );
+    }
+
+    #[test]
+    fn counters_never_decrease_across_ticks() {
+        let registry = Arc::new(PromRegistry::new());
+        let mut colony = colony_with_document();
+        colony.attach_metrics(registry.clone(), "default");
+
+        let counters = |text: &str| -> Vec<Sample> {
+            parse(text)
+                .into_iter()
+                .filter(|(name, _, _)| {
+                    name.ends_with("_total")
+                        || name.ends_with("_count")
+                        || name.ends_with("_bucket")
+                })
+                .collect()
+        };
+
+        let mut previous = counters(&registry.encode());
+        for _ in 0..12 {
+            colony.tick();
+            let current = counters(&registry.encode());
+            for (name, labels, before) in &previous {
+                let labels: Vec<(&str, &str)> = labels
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.as_str()))
+                    .collect();
+                let after = value(&current, name, &labels)
+                    .unwrap_or_else(|| panic!("{name} {labels:?} disappeared"));
+                assert!(
+                    after >= *before,
+                    "{name} {labels:?} went from {before} to {after}"
+                );
+            }
+            previous = current;
+        }
+        // The digester starved and died along the way
+        assert!(
+            value(
+                &previous,
+                "phago_agents_died_total",
+                &[("space", "default")]
+            )
+            .unwrap()
+                >= 1.0
+        );
+
+        // A reloaded colony starts from zero; the total keeps growing
+        let mut reloaded = colony_with_document();
+        reloaded.attach_metrics(registry.clone(), "default");
+        reloaded.run(12);
+        let samples = parse(&registry.encode());
+        assert!(
+            value(&samples, "phago_agents_died_total", &[("space", "default")]).unwrap()
+                >= value(
+                    &previous,
+                    "phago_agents_died_total",
+                    &[("space", "default")]
+                )
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Document placement — choosing positions for documents ingested
+//! without one.
+//!
+//! Where a document sits decides which digesters find it and which
+//! synthesizers see it next to its neighbors. [`place`] chooses a position
+//! for new content under the colony's [`DocPlacement`]:
+//!
+//! - [`SimilarityCluster`](DocPlacement::SimilarityCluster) puts a
+//!   document near the existing documents whose terms overlap most with
+//!   its own, so related documents cluster. A document unlike any other
+//!   starts a cluster of its own on the next free cell of a square spiral
+//!   around the origin.
+//! - [`Grid`](DocPlacement::Grid) fills the cells of the spiral in order.
+//! - [`Manual`](DocPlacement::Manual) leaves positions to the caller;
+//!   documents placed automatically go to the origin.
+//!
+//! Similarity is the Jaccard overlap of the two documents' term sets,
+//! split with the colony's tokenizer. Placement is deterministic: the same
+//! documents in the same order get the same positions.
+
+use phago_core::tokenize::Tokenizer;
+use phago_core::types::{Document, Position};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Most similar documents a new one is placed among.
+const NEIGHBORS: usize = 3;
+
+/// How documents ingested without a position are placed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocPlacement {
+    /// At the origin; callers are expected to pass positions.
+    Manual,
+    /// Within `radius` of the most similar documents and at least
+    /// `min_separation` from every other document. Unrelated documents
+    /// start new clusters `3 * radius` apart.
+    SimilarityCluster { radius: f64, min_separation: f64 },
+    /// On a square spiral of cells `spacing` apart.
+    Grid { spacing: f64 },
+}
+
+impl Default for DocPlacement {
+    fn default() -> Self {
+        DocPlacement::SimilarityCluster {
+            radius: 10.0,
+            min_separation: 2.0,
+        }
+    }
+}
+
+/// Choose a position for `content` among the `existing` documents.
+pub fn place(
+    strategy: &DocPlacement,
+    tokenizer: &Tokenizer,
+    existing: &[&Document],
+    content: &str,
+) -> Position {
+    match *strategy {
+        DocPlacement::Manual => Position::new(0.0, 0.0),
+        DocPlacement::Grid { spacing } => free_cell(existing, spacing, spacing / 2.0),
+        DocPlacement::SimilarityCluster {
+            radius,
+            min_separation,
+        } => {
+            let terms = terms(tokenizer, content);
+            let mut similar: Vec<(f64, Position)> = existing
+                .iter()
+                .map(|d| {
+                    (
+                        jaccard(&terms, &self::terms(tokenizer, &d.content)),
+                        d.position,
+                    )
+                })
+                .filter(|(similarity, _)| *similarity > 0.0)
+                .collect();
+            if similar.is_empty() {
+                return free_cell(existing, 3.0 * radius, 1.5 * radius);
+            }
+            similar.sort_by(|a, b| b.0.total_cmp(&a.0));
+            similar.truncate(NEIGHBORS);
+
+            let total: f64 = similar.iter().map(|(s, _)| s).sum();
+            let target = Position::new(
+                similar.iter().map(|(s, p)| s * p.x).sum::<f64>() / total,
+                similar.iter().map(|(s, p)| s * p.y).sum::<f64>() / total,
+            );
+            separated(existing, target, radius, min_separation)
+        }
+    }
+}
+
+fn terms(tokenizer: &Tokenizer, content: &str) -> HashSet<String> {
+    tokenizer.tokens(content).into_iter().collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+fn clearance(existing: &[&Document], position: Position) -> f64 {
+    existing
+        .iter()
+        .map(|d| d.position.distance_to(&position))
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// The first spiral cell with no document within `clear` of its center.
+fn free_cell(existing: &[&Document], spacing: f64, clear: f64) -> Position {
+    (0..)
+        .map(|i| {
+            let (x, y) = spiral(i);
+            Position::new(x as f64 * spacing, y as f64 * spacing)
+        })
+        .find(|p| clearance(existing, *p) >= clear)
+        .expect("the spiral has a free cell")
+}
+
+/// `target`, or the nearest point within `radius` of it that keeps
+/// `min_separation` from every document; failing that, the candidate with
+/// the most room.
+fn separated(
+    existing: &[&Document],
+    target: Position,
+    radius: f64,
+    min_separation: f64,
+) -> Position {
+    if clearance(existing, target) >= min_separation {
+        return target;
+    }
+    let step = if min_separation > 0.0 {
+        min_separation
+    } else {
+        radius
+    };
+    let rings = (radius / step).ceil().max(1.0) as usize;
+    let mut best = (clearance(existing, target), target);
+    for ring in 1..=rings {
+        let distance = (ring as f64 * step).min(radius);
+        let points = 8 * ring;
+        for k in 0..points {
+            let angle = std::f64::consts::TAU * k as f64 / points as f64;
+            let candidate = Position::new(
+                target.x + distance * angle.cos(),
+                target.y + distance * angle.sin(),
+            );
+            let room = clearance(existing, candidate);
+            if room >= min_separation {
+                return candidate;
+            }
+            if room > best.0 {
+                best = (room, candidate);
+            }
+        }
+    }
+    best.1
+}
+
+/// Cell `index` of a square spiral around the origin: ring `k` holds the
+/// `8k` cells at Chebyshev distance `k`.
+fn spiral(index: usize) -> (i64, i64) {
+    if index == 0 {
+        return (0, 0);
+    }
+    let n = index as i64;
+    let k = (n.isqrt() + 1) / 2;
+    let m = n - (2 * k - 1).pow(2);
+    let side = 2 * k;
+    match m / side {
+        0 => (k, -k + 1 + m),
+        1 => (k - 1 - (m - side), k),
+        2 => (-k, k - 1 - (m - 2 * side)),
+        _ => (-k + 1 + (m - 3 * side), -k),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_core::types::DocumentId;
+
+    fn doc(content: &str, position: Position) -> Document {
+        Document {
+            id: DocumentId::new(),
+            title: String::new(),
+            content: content.into(),
+            position,
+            digested: false,
+            tags: Vec::new(),
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn spiral_visits_each_ring_cell_once() {
+        let cells: HashSet<(i64, i64)> = (0..49).map(spiral).collect();
+        assert_eq!(cells.len(), 49);
+        assert!(cells.iter().all(|(x, y)| x.abs() <= 3 && y.abs() <= 3));
+        assert_eq!(spiral(1), (1, 0));
+        assert_eq!(spiral(8), (1, -1));
+    }
+
+    #[test]
+    fn grid_fills_free_cells_in_order() {
+        let tokenizer = Tokenizer::default();
+        let grid = DocPlacement::Grid { spacing: 4.0 };
+        let first = doc("a", place(&grid, &tokenizer, &[], "a"));
+        assert_eq!(first.position, Position::new(0.0, 0.0));
+        let second = place(&grid, &tokenizer, &[&first], "b");
+        assert_eq!(second, Position::new(4.0, 0.0));
+    }
+
+    #[test]
+    fn similar_content_lands_near_but_apart() {
+        let tokenizer = Tokenizer::default();
+        let strategy = DocPlacement::SimilarityCluster {
+            radius: 10.0,
+            min_separation: 2.0,
+        };
+        let cells = doc("cell membrane protein transport", Position::new(0.0, 0.0));
+        let position = place(
+            &strategy,
+            &tokenizer,
+            &[&cells],
+            "membrane protein channels",
+        );
+        let distance = position.distance_to(&cells.position);
+        assert!((2.0..=10.0).contains(&distance), "{distance}");
+    }
+}
@@ -0,0 +1,537 @@
+//! Colony plugins — lifecycle hooks for extending a colony without
+//! patching [`Colony::tick`].
+//!
+//! A [`ColonyPlugin`] is registered with [`Colony::add_plugin`] and called
+//! at fixed points of every tick, in registration order:
+//!
+//! 1. [`on_tick_start`](ColonyPlugin::on_tick_start), before agents sense
+//!    and decide, with a [`PluginContext`].
+//! 2. [`before_action`](ColonyPlugin::before_action), for each action an
+//!    agent decided on, before the colony carries it out. Later plugins see
+//!    an earlier plugin's replacement; once one skips the action, the rest
+//!    are not asked.
+//! 3. [`on_event`](ColonyPlugin::on_event), for each event of the tick once
+//!    it is complete, in the order [`Colony::tick`] returns them.
+//! 4. [`on_tick_end`](ColonyPlugin::on_tick_end), with the colony's stats.
+//!
+//! Plugins never get `&mut Colony`. They read the substrate and may change
+//! only what the colony does not keep its own books on: signals and the
+//! weights of existing edges. A plugin that only observes leaves a seeded
+//! run exactly as it would have been without it.
+//!
+//! Two plugins ship in-tree: [`JsonlEventLog`] appends every event to a
+//! JSON Lines file, and [`DocumentPolicy`] keeps agents from engulfing
+//! documents whose titles match a pattern.
+//!
+//! ## Custom actions
+//!
+//! Agents outside this workspace can ask for behavior the colony has no
+//! built-in meaning for by returning [`AgentAction::Custom`]. The colony
+//! hands it, after plugins have allowed it, to the [`CustomActionHandler`]
+//! registered for its `kind` with [`Colony::register_action_handler`].
+//! Handlers get an [`ActionContext`] rather than the colony: they may add
+//! nodes, set edges, emit signals, deposit traces and annotate nodes. A
+//! kind nobody handles, and a handler's error, become
+//! [`ColonyEvent::ActionFailed`] events.
+//!
+//! [`Colony::tick`]: crate::colony::Colony::tick
+//! [`Colony::add_plugin`]: crate::colony::Colony::add_plugin
+//! [`Colony::register_action_handler`]: crate::colony::Colony::register_action_handler
+
+use crate::colony::{ColonyEvent, ColonyStats};
+use crate::concept_index::ConceptIndex;
+use crate::substrate_impl::SubstrateImpl;
+use phago_core::substrate::Substrate;
+use phago_core::topology::TopologyError;
+use phago_core::types::*;
+use regex::Regex;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// What the colony does with an agent's action.
+#[derive(Debug, Clone)]
+pub enum ActionDecision {
+    /// Carry it out.
+    Allow,
+    /// Drop it. The agent is not told; an agent that asked to engulf a
+    /// document simply finds nothing engulfed on its next tick.
+    Skip,
+    /// Carry out this action instead.
+    Replace(AgentAction),
+}
+
+/// Read access to the substrate plus the few changes a plugin may make.
+pub struct PluginContext<'a> {
+    substrate: &'a mut SubstrateImpl,
+}
+
+impl<'a> PluginContext<'a> {
+    pub(crate) fn new(substrate: &'a mut SubstrateImpl) -> Self {
+        Self { substrate }
+    }
+
+    /// The tick about to run.
+    pub fn tick(&self) -> Tick {
+        self.substrate.current_tick()
+    }
+
+    /// The substrate, read-only.
+    pub fn substrate(&self) -> &SubstrateImpl {
+        self.substrate
+    }
+
+    /// Emit a signal for agents to sense this tick.
+    pub fn emit_signal(&mut self, signal: Signal) {
+        self.substrate.emit_signal(signal);
+    }
+
+    /// Set the weight of an existing edge, clamped to `0.0..=1.0`. Returns
+    /// false if there is no such edge. Edges are not created here: the
+    /// colony tracks which documents contributed each one.
+    pub fn set_edge_weight(&mut self, from: &NodeId, to: &NodeId, weight: f64) -> bool {
+        if self.substrate.graph().get_edge(from, to).is_none() {
+            return false;
+        }
+        match self.substrate.graph_mut().get_edge_mut(from, to) {
+            Some(edge) => {
+                edge.weight = weight.clamp(0.0, 1.0);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// What a [`CustomActionHandler`] may do to the colony on an agent's behalf.
+pub struct ActionContext<'a> {
+    substrate: &'a mut SubstrateImpl,
+    concept_index: &'a mut ConceptIndex,
+    agent: AgentId,
+    position: Position,
+    kind: &'a str,
+    events: Vec<ColonyEvent>,
+}
+
+impl<'a> ActionContext<'a> {
+    pub(crate) fn new(
+        substrate: &'a mut SubstrateImpl,
+        concept_index: &'a mut ConceptIndex,
+        agent: AgentId,
+        position: Position,
+        kind: &'a str,
+    ) -> Self {
+        Self {
+            substrate,
+            concept_index,
+            agent,
+            position,
+            kind,
+            events: Vec::new(),
+        }
+    }
+
+    /// Events for what the handler did, e.g. annotations.
+    pub(crate) fn into_events(self) -> Vec<ColonyEvent> {
+        self.events
+    }
+
+    /// The tick being run.
+    pub fn tick(&self) -> Tick {
+        self.substrate.current_tick()
+    }
+
+    /// The agent whose action this is.
+    pub fn agent(&self) -> AgentId {
+        self.agent
+    }
+
+    /// Where the agent is.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The substrate, read-only.
+    pub fn substrate(&self) -> &SubstrateImpl {
+        self.substrate
+    }
+
+    /// The node labelled exactly `label`, reinforced, or a new one of
+    /// `node_type` at the agent's position.
+    pub fn add_node(&mut self, label: &str, node_type: NodeType) -> NodeId {
+        let tick = self.tick();
+        let existing = self
+            .substrate
+            .graph()
+            .find_nodes_by_exact_label(label)
+            .first()
+            .copied();
+        if let Some(id) = existing {
+            if let Some(node) = self.substrate.graph_mut().get_node_mut(&id) {
+                node.access_count += 1;
+                node.last_accessed_tick = tick;
+            }
+            return id;
+        }
+        let node = NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type,
+            position: self.position,
+            access_count: 1,
+            created_tick: tick,
+            last_accessed_tick: tick,
+            tags: Default::default(),
+        };
+        self.concept_index.node_added(&node);
+        self.substrate.add_node(node)
+    }
+
+    /// Create the edge between two existing nodes, or set its weight,
+    /// clamped to `0.0..=1.0`.
+    pub fn set_edge(&mut self, from: NodeId, to: NodeId, weight: f64) -> Result<(), TopologyError> {
+        let tick = self.tick();
+        let weight = weight.clamp(0.0, 1.0);
+        if let Some(edge) = self.substrate.graph_mut().get_edge_mut(&from, &to) {
+            edge.weight = weight;
+            edge.last_activated_tick = tick;
+            return Ok(());
+        }
+        let edge = EdgeData {
+            weight,
+            co_activations: 1,
+            created_tick: tick,
+            last_activated_tick: tick,
+        };
+        self.substrate.set_edge(from, to, edge)
+    }
+
+    /// Emit a signal for agents to sense.
+    pub fn emit_signal(&mut self, signal: Signal) {
+        self.substrate.emit_signal(signal);
+    }
+
+    /// Leave a trace at `location`.
+    pub fn deposit_trace(&mut self, location: &SubstrateLocation, trace: Trace) {
+        self.substrate.deposit_trace(location, trace);
+    }
+
+    /// Attach a note to `node`, made by the action's kind. Returns false
+    /// if there is no such node.
+    pub fn annotate(&mut self, node: &NodeId, text: &str) -> bool {
+        let Some(label) = self
+            .substrate
+            .graph()
+            .get_node(node)
+            .map(|n| n.label.clone())
+        else {
+            return false;
+        };
+        let annotation = Annotation {
+            text: text.to_string(),
+            source: self.kind.to_string(),
+            tick: self.tick(),
+        };
+        self.substrate.graph_mut().annotate(node, annotation);
+        self.events.push(ColonyEvent::Annotated {
+            node_id: *node,
+            label,
+            source: self.kind.to_string(),
+        });
+        true
+    }
+}
+
+/// Carries out the [`AgentAction::Custom`] actions of one kind; see
+/// [custom actions](self#custom-actions).
+pub trait CustomActionHandler {
+    /// Carry out an action whose payload is `payload`. An error is
+    /// reported as an [`ColonyEvent::ActionFailed`] event.
+    fn handle(&mut self, ctx: &mut ActionContext<'_>, payload: &[u8]) -> Result<(), String>;
+}
+
+impl<F> CustomActionHandler for F
+where
+    F: FnMut(&mut ActionContext<'_>, &[u8]) -> Result<(), String>,
+{
+    fn handle(&mut self, ctx: &mut ActionContext<'_>, payload: &[u8]) -> Result<(), String> {
+        self(ctx, payload)
+    }
+}
+
+/// Lifecycle hooks invoked by [`Colony::tick`](crate::colony::Colony::tick).
+///
+/// Every hook has a no-op default; implement the ones you need.
+pub trait ColonyPlugin {
+    /// Short name for logs and diagnostics.
+    fn name(&self) -> &str {
+        "plugin"
+    }
+
+    /// Called before agents sense and decide.
+    fn on_tick_start(&mut self, _ctx: &mut PluginContext<'_>) {}
+
+    /// Called for each action an agent decided on, before it is carried
+    /// out.
+    fn before_action(
+        &mut self,
+        _substrate: &SubstrateImpl,
+        _agent: AgentId,
+        _action: &AgentAction,
+    ) -> ActionDecision {
+        ActionDecision::Allow
+    }
+
+    /// Called for each event of a completed tick. `tick` is the tick the
+    /// events are recorded under in the event history.
+    fn on_event(&mut self, _tick: Tick, _event: &ColonyEvent) {}
+
+    /// Called once the tick is complete.
+    fn on_tick_end(&mut self, _stats: &ColonyStats) {}
+}
+
+#[derive(Serialize)]
+struct LoggedEvent<'a> {
+    tick: Tick,
+    event: &'a ColonyEvent,
+}
+
+/// Appends every colony event to a JSON Lines stream, one
+/// `{"tick": .., "event": ..}` object per line.
+///
+/// Write errors can't be returned from a hook, so the first one is kept
+/// (see [`error`](Self::error)) and logging stops.
+pub struct JsonlEventLog<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl JsonlEventLog<BufWriter<File>> {
+    /// Append to the file at `path`, creating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> JsonlEventLog<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    /// The first write error, if logging has stopped.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    fn write_line(&mut self, tick: Tick, event: &ColonyEvent) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, &LoggedEvent { tick, event })?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+impl<W: Write> ColonyPlugin for JsonlEventLog<W> {
+    fn name(&self) -> &str {
+        "jsonl-event-log"
+    }
+
+    fn on_event(&mut self, tick: Tick, event: &ColonyEvent) {
+        if self.error.is_none() {
+            self.error = self.write_line(tick, event).err();
+        }
+    }
+
+    fn on_tick_end(&mut self, _stats: &ColonyStats) {
+        if self.error.is_none() {
+            self.error = self.writer.flush().err();
+        }
+    }
+}
+
+/// Keeps agents from engulfing documents whose titles match a pattern.
+///
+/// Vetoed documents stay undigested in the substrate.
+pub struct DocumentPolicy {
+    title_pattern: Regex,
+    vetoed: u64,
+}
+
+impl DocumentPolicy {
+    /// Veto documents whose titles match `title_pattern`.
+    pub fn new(title_pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            title_pattern: Regex::new(title_pattern)?,
+            vetoed: 0,
+        })
+    }
+
+    /// Engulf attempts vetoed so far.
+    pub fn vetoed(&self) -> u64 {
+        self.vetoed
+    }
+}
+
+impl ColonyPlugin for DocumentPolicy {
+    fn name(&self) -> &str {
+        "document-policy"
+    }
+
+    fn before_action(
+        &mut self,
+        substrate: &SubstrateImpl,
+        _agent: AgentId,
+        action: &AgentAction,
+    ) -> ActionDecision {
+        let AgentAction::EngulfDocument(doc_id) = action else {
+            return ActionDecision::Allow;
+        };
+        match substrate.get_document(doc_id) {
+            Some(doc) if self.title_pattern.is_match(&doc.title) => {
+                self.vetoed += 1;
+                ActionDecision::Skip
+            }
+            _ => ActionDecision::Allow,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colony::Colony;
+    use phago_agents::digester::Digester;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn policy_vetoes_matching_titles_only() {
+        let mut colony = Colony::new();
+        let draft = colony.ingest_document(
+            "DRAFT: membrane notes",
+            "cell membrane protein",
+            Position::new(0.0, 0.0),
+        );
+        let final_doc =
+            colony.ingest_document("Membranes", "cell membrane lipid", Position::new(0.5, 0.0));
+        colony.add_plugin(Box::new(DocumentPolicy::new("^DRAFT").unwrap()));
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony.spawn(Box::new(Digester::new(Position::new(0.5, 0.0))));
+        colony.run(10);
+
+        let substrate = colony.substrate();
+        assert!(!substrate.get_document(&draft).unwrap().digested);
+        assert!(substrate.get_document(&final_doc).unwrap().digested);
+    }
+
+    /// Records every hook call.
+    struct Recorder(Rc<RefCell<Vec<String>>>);
+
+    impl ColonyPlugin for Recorder {
+        fn on_tick_start(&mut self, ctx: &mut PluginContext<'_>) {
+            self.0.borrow_mut().push(format!("start {}", ctx.tick()));
+        }
+
+        fn before_action(
+            &mut self,
+            _substrate: &SubstrateImpl,
+            _agent: AgentId,
+            action: &AgentAction,
+        ) -> ActionDecision {
+            if let AgentAction::Move(_) = action {
+                self.0.borrow_mut().push("move".into());
+                return ActionDecision::Replace(AgentAction::Idle);
+            }
+            ActionDecision::Allow
+        }
+
+        fn on_event(&mut self, tick: Tick, event: &ColonyEvent) {
+            if let ColonyEvent::TickComplete { .. } = event {
+                self.0.borrow_mut().push(format!("complete {tick}"));
+            }
+        }
+
+        fn on_tick_end(&mut self, stats: &ColonyStats) {
+            self.0.borrow_mut().push(format!("end {}", stats.tick));
+        }
+    }
+
+    #[test]
+    fn hooks_run_in_order_and_replacements_apply() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut colony = Colony::new();
+        colony.add_plugin(Box::new(Recorder(calls.clone())));
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+
+        let events = colony.tick();
+        assert!(!events
+            .iter()
+            .any(|e| matches!(e, ColonyEvent::Moved { .. })));
+        assert_eq!(colony.agents()[0].position(), Position::new(0.0, 0.0));
+        assert_eq!(
+            *calls.borrow(),
+            vec!["start 0", "move", "complete 1", "end 1"]
+        );
+    }
+
+    #[test]
+    fn context_adjusts_existing_edges_only() {
+        let mut colony = Colony::new();
+        let substrate = colony.substrate_mut();
+        let node = |label: &str| NodeData {
+            id: NodeId::new(),
+            label: label.into(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        };
+        let a = substrate.add_node(node("cell"));
+        let b = substrate.add_node(node("membrane"));
+        let c = substrate.add_node(node("lipid"));
+        substrate
+            .set_edge(
+                a,
+                b,
+                EdgeData {
+                    weight: 0.5,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+
+        let mut ctx = PluginContext::new(substrate);
+        assert!(ctx.set_edge_weight(&a, &b, 2.0));
+        assert!(!ctx.set_edge_weight(&a, &c, 0.5));
+        assert_eq!(ctx.substrate().get_edge(&a, &b).unwrap().weight, 1.0);
+        assert!(ctx.substrate().get_edge(&a, &c).is_none());
+    }
+
+    #[test]
+    fn event_log_writes_one_line_per_event() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let mut colony = Colony::new();
+        colony.add_plugin(Box::new(JsonlEventLog::open(&path).unwrap()));
+        colony.ingest_document("Doc", "cell membrane protein", Position::new(0.0, 0.0));
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        let events: usize = colony.run(3).iter().map(Vec::len).sum();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = log
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), events);
+        assert_eq!(lines[0]["tick"], 1);
+        assert!(lines[0]["event"].get("Engulfed").is_some());
+        assert_eq!(lines.last().unwrap()["tick"], 3);
+    }
+}
@@ -5,20 +5,85 @@
 //! ```
 
 // Re-export colony
-pub use crate::colony::{Colony, ColonyConfig, ColonyEvent, ColonySnapshot, ColonyStats};
+pub use crate::colony::{
+    Colony, ColonyConfig, ColonyEvent, ColonySnapshot, ColonyStats, EmergenceReport, QuorumRegion,
+};
+
+// Re-export label compaction
+pub use crate::compaction::{
+    CompactionConfig, CompactionPlan, CompactionStrategy, MergeCluster, MergedNode,
+};
+
+// Re-export adaptive decay
+pub use crate::decay::{AdaptiveDecayConfig, DecayController, DecayRates, DecayReport, RateBounds};
+
+// Re-export deduplication
+pub use crate::dedup::{
+    BatchDocument, DedupConfig, DedupPolicy, DuplicateMatch, IngestOutcome, IngestReport, MatchKind,
+};
+
+// Re-export embedding storage
+pub use crate::embedding_store::{EmbeddingStorage, EmbeddingTable};
+pub use crate::inverted_index::InvertedIndex;
 
 // Re-export colony builder
 pub use crate::colony_builder::{BuilderError, ColonyBuilder, PersistentColony};
 
+// Re-export graph algorithms
+pub use crate::graph_algorithms::{
+    find_path, pagerank, personalized_pagerank, ConceptPath, PageRankConfig, PathCost, PathError,
+    PathHop, WeightTransform,
+};
+
+// Re-export document placement
+pub use crate::placement::DocPlacement;
+
+// Re-export plugins
+pub use crate::plugin::{
+    ActionContext, ActionDecision, ColonyPlugin, CustomActionHandler, DocumentPolicy,
+    JsonlEventLog, PluginContext,
+};
+
+// Re-export query log
+pub use crate::query_log::{QueryLog, QueryLogConfig, QueryLogEntry, QuerySource};
+
+// Re-export integrity checks
+pub use crate::integrity::{
+    IntegrityIssue, IntegrityReport, RepairAction, RepairPolicy, RepairReport,
+};
+
+// Re-export substrate regions
+pub use crate::region::{Region, RegionError, RegionPolicy, RegionShape};
+
+// Re-export graph sampling
+pub use crate::sampling::{SampleConfig, SampleCoverage, SampleStrategy};
+
+// Re-export signal coalescing
+pub use crate::substrate_impl::SignalCoalescing;
+
+// Re-export snapshot series
+pub use crate::snapshot::{
+    EdgeChange, SnapshotDelta, SnapshotFrame, SnapshotMode, SnapshotSeries, StatsSnapshot,
+};
+
+// Re-export vocabulary transfer
+pub use crate::transfer::{AcceptanceCurve, TransferConfig};
+
+// Re-export vocabulary introspection
+pub use crate::vocabulary::{
+    AgentVocabulary, OverlapSummary, TermCoverage, VocabularyOverlap, VocabularyReport,
+};
+
 // Re-export session
 pub use crate::session::{
-    load_session, restore_into_colony, save_session, verify_fidelity, GraphState, SerializedEdge,
-    SerializedNode, SessionMetadata,
+    load_session, load_session_with, restore_into_colony, save_session, save_session_with_options,
+    verify_fidelity, GraphState, LoadMode, LoadReport, RestoreReport, SerializedEdge,
+    SerializedNode, SessionError, SessionMetadata, SessionOptions,
 };
 
 // Re-export metrics
 pub use crate::metrics::{
-    ColonyMetrics, DissolutionMetrics, GraphRichnessMetrics, TransferMetrics,
+    ColonyMetrics, DissolutionMetrics, GraphRichnessMetrics, TransferMetrics, VocabularyConvergence,
 };
 
 // Re-export backend configuration
@@ -28,10 +93,16 @@ pub use crate::backend::{create_backend, BackendConfig, BackendError, DynTopolog
 #[cfg(feature = "sqlite")]
 pub use crate::sqlite_topology::SqliteTopologyGraph;
 
+// Re-export backup bundles when feature is enabled
+#[cfg(feature = "bundle")]
+pub use crate::bundle::{Bundle, BundleError, BundleManifest};
+
 // Re-export async runtime when feature is enabled
 #[cfg(feature = "async")]
 pub use crate::async_runtime::{
-    batch_ingest, run_in_local, spawn_simulation_local, AsyncColony, TickTimer,
+    batch_ingest, run_in_local, spawn_simulation_local, AsyncColony, AsyncColonyRunner,
+    OverrunPolicy, RunnerColony, RunnerConfig, RunnerHandle, RunnerStats, RunnerStopped,
+    SharedColony, TickRate, TickTimer,
 };
 
 // Re-export streaming when feature is enabled
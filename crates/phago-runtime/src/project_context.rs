@@ -3,6 +3,7 @@
 //! Scans a project directory for source files and provides
 //! context about the project structure.
 
+use phago_agents::code_digester::CodeLanguage;
 use std::path::{Path, PathBuf};
 
 /// Information about a source file in the project.
@@ -11,18 +12,30 @@ pub struct SourceFile {
     pub path: PathBuf,
     pub relative_path: String,
     pub extension: String,
+    pub language: CodeLanguage,
     pub size_bytes: u64,
 }
 
 /// Scan a directory for Rust source files.
 pub fn scan_rust_files(root: &Path) -> Vec<SourceFile> {
+    scan_source_files(root, &[CodeLanguage::Rust])
+}
+
+/// Scan a directory for source files in any of `languages`,
+/// recognised by file extension.
+pub fn scan_source_files(root: &Path, languages: &[CodeLanguage]) -> Vec<SourceFile> {
     let mut files = Vec::new();
-    scan_recursive(root, root, &mut files);
+    scan_recursive(root, root, languages, &mut files);
     files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
     files
 }
 
-fn scan_recursive(root: &Path, dir: &Path, files: &mut Vec<SourceFile>) {
+fn scan_recursive(
+    root: &Path,
+    dir: &Path,
+    languages: &[CodeLanguage],
+    files: &mut Vec<SourceFile>,
+) {
     let entries = match std::fs::read_dir(dir) {
         Ok(entries) => entries,
         Err(_) => return,
@@ -39,8 +52,15 @@ fn scan_recursive(root: &Path, dir: &Path, files: &mut Vec<SourceFile>) {
         }
 
         if path.is_dir() {
-            scan_recursive(root, &path, files);
-        } else if path.extension().map_or(false, |ext| ext == "rs") {
+            scan_recursive(root, &path, languages, files);
+            continue;
+        }
+        let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+            continue;
+        };
+        if let Some(language) =
+            CodeLanguage::from_extension(extension).filter(|lang| languages.contains(lang))
+        {
             let relative = path
                 .strip_prefix(root)
                 .unwrap_or(&path)
@@ -51,7 +71,8 @@ fn scan_recursive(root: &Path, dir: &Path, files: &mut Vec<SourceFile>) {
             files.push(SourceFile {
                 path: path.clone(),
                 relative_path: relative,
-                extension: "rs".to_string(),
+                extension: extension.to_string(),
+                language,
                 size_bytes: size,
             });
         }
@@ -73,4 +94,34 @@ mod tests {
         assert!(!files.is_empty(), "Should find .rs files in the project");
         assert!(files.iter().any(|f| f.relative_path.contains("colony.rs")));
     }
+
+    #[test]
+    fn scan_filters_by_language() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("pkg")).unwrap();
+        std::fs::create_dir_all(dir.path().join("node_modules/dep")).unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "fn a() {}").unwrap();
+        std::fs::write(dir.path().join("pkg/model.py"), "def b(): pass").unwrap();
+        std::fs::write(dir.path().join("pkg/app.ts"), "function c() {}").unwrap();
+        std::fs::write(dir.path().join("node_modules/dep/index.js"), "").unwrap();
+        std::fs::write(dir.path().join("notes.md"), "").unwrap();
+
+        let files = scan_source_files(
+            dir.path(),
+            &[CodeLanguage::Python, CodeLanguage::JavaScript],
+        );
+        let found: Vec<(&str, CodeLanguage)> = files
+            .iter()
+            .map(|f| (f.relative_path.as_str(), f.language))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("pkg/app.ts", CodeLanguage::JavaScript),
+                ("pkg/model.py", CodeLanguage::Python)
+            ]
+        );
+        assert_eq!(files[0].extension, "ts");
+        assert_eq!(scan_rust_files(dir.path()).len(), 1);
+    }
 }
@@ -0,0 +1,296 @@
+//! Edge provenance — which documents and queries reinforced an edge lately.
+//!
+//! An edge's `co_activations` counts how often it was reinforced, not by
+//! what. [`EdgeProvenance`] keeps, beside the graph, a ring of each edge's
+//! last K reinforcements, each with its [`EdgeSource`] and tick, so "which
+//! documents made this edge strong?" can be answered without re-deriving
+//! document contributions. The colony records into it wherever it wires or
+//! reinforces an edge: co-occurrence wiring of presented fragments, agent
+//! wiring, query-time reinforcement and imported triples. K is
+//! [`ColonyConfig::edge_provenance`](crate::colony::ColonyConfig::edge_provenance);
+//! 0 turns recording off.
+//!
+//! # Memory
+//!
+//! A ring is allocated at its full capacity the first time its edge is
+//! reinforced. Each record takes 32 bytes, and each tracked edge another
+//! 65 bytes of key, ring header and map control byte, in a map kept at
+//! least half full: with K = 8 an edge costs 256 bytes of records and at
+//! most about 150 of map, 330 to 405 bytes in all
+//! ([`EdgeProvenance::memory_bytes`] estimates the total). Runs that
+//! cannot spare that set K lower, or to 0.
+
+use phago_core::topology::TopologyGraph;
+use phago_core::types::{DocumentId, NodeId, Tick};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// What reinforced an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EdgeSource {
+    /// Wiring presented while digesting this document.
+    Document(DocumentId),
+    /// Query-time reinforcement of a retrieved edge.
+    Query,
+    /// Wiring by an agent not digesting a document, or made by hand.
+    Unattributed,
+    /// Triples imported from a named source (see [`crate::import`]);
+    /// [`EdgeProvenance::import_name`] tells which.
+    Import(u32),
+}
+
+/// One reinforcement of an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceRecord {
+    pub source: EdgeSource,
+    pub tick: Tick,
+}
+
+/// Bounded rings of each edge's most recent reinforcements, keyed by the
+/// edge's endpoints in ascending order.
+#[derive(Debug, Clone, Default)]
+pub struct EdgeProvenance {
+    capacity: usize,
+    rings: HashMap<(NodeId, NodeId), VecDeque<SourceRecord>>,
+    /// Names of import sources, indexed by [`EdgeSource::Import`].
+    imports: Vec<String>,
+}
+
+fn pair_key(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+impl EdgeProvenance {
+    /// Keep the last `capacity` reinforcements per edge; 0 keeps none.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            rings: HashMap::new(),
+            imports: Vec::new(),
+        }
+    }
+
+    /// The source of triples imported from `name`, the same every time
+    /// it is asked for.
+    pub fn import_source(&mut self, name: &str) -> EdgeSource {
+        let index = match self.imports.iter().position(|n| n == name) {
+            Some(index) => index,
+            None => {
+                self.imports.push(name.to_string());
+                self.imports.len() - 1
+            }
+        };
+        EdgeSource::Import(index as u32)
+    }
+
+    /// The name of an import `source`; `None` for other sources.
+    pub fn import_name(&self, source: EdgeSource) -> Option<&str> {
+        match source {
+            EdgeSource::Import(index) => self.imports.get(index as usize).map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// Reinforcements kept per edge.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Whether reinforcements are recorded at all.
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Keep `capacity` reinforcements per edge from now on, dropping the
+    /// oldest beyond it.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        if capacity == 0 {
+            self.rings = HashMap::new();
+            return;
+        }
+        for ring in self.rings.values_mut() {
+            while ring.len() > capacity {
+                ring.pop_front();
+            }
+            ring.shrink_to(capacity);
+        }
+    }
+
+    /// Record that `source` reinforced the edge between `a` and `b` at
+    /// `tick`, pushing out its oldest record once the ring is full.
+    pub fn record(&mut self, a: NodeId, b: NodeId, source: EdgeSource, tick: Tick) {
+        if self.capacity == 0 {
+            return;
+        }
+        let capacity = self.capacity;
+        let ring = self
+            .rings
+            .entry(pair_key(a, b))
+            .or_insert_with(|| VecDeque::with_capacity(capacity));
+        if ring.len() == capacity {
+            ring.pop_front();
+        }
+        ring.push_back(SourceRecord { source, tick });
+    }
+
+    /// Forget what reinforced the edge between `a` and `b`, e.g. because
+    /// it was just created where a pruned edge used to be.
+    pub fn forget(&mut self, a: NodeId, b: NodeId) {
+        self.rings.remove(&pair_key(a, b));
+    }
+
+    /// The recorded reinforcements of the edge between `a` and `b`, oldest
+    /// first.
+    pub fn sources(&self, a: NodeId, b: NodeId) -> Vec<SourceRecord> {
+        self.rings
+            .get(&pair_key(a, b))
+            .map(|ring| ring.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Every edge with recorded reinforcements, oldest record first.
+    pub fn iter(&self) -> impl Iterator<Item = ((NodeId, NodeId), &VecDeque<SourceRecord>)> {
+        self.rings.iter().map(|(key, ring)| (*key, ring))
+    }
+
+    /// Edges with recorded reinforcements.
+    pub fn len(&self) -> usize {
+        self.rings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rings.is_empty()
+    }
+
+    /// Drop the records of edges no longer in `graph`.
+    pub fn retain_edges(&mut self, graph: &dyn TopologyGraph) {
+        self.rings
+            .retain(|(a, b), _| graph.get_edge(a, b).is_some());
+        self.shrink();
+    }
+
+    /// Give back the map's memory once half the edges it had room for
+    /// are gone.
+    fn shrink(&mut self) {
+        if self.rings.len() < self.rings.capacity() / 2 {
+            self.rings.shrink_to_fit();
+        }
+    }
+
+    /// Credit what reinforced the edges of `from` to the same edges of
+    /// `into`, e.g. because the nodes were merged, keeping the most recent
+    /// records where both had the edge. Edges between the two are dropped.
+    pub fn replace_node(&mut self, from: NodeId, into: NodeId) {
+        let moved: Vec<(NodeId, NodeId)> = self
+            .rings
+            .keys()
+            .filter(|(a, b)| *a == from || *b == from)
+            .copied()
+            .collect();
+        for (a, b) in moved {
+            let Some(ring) = self.rings.remove(&(a, b)) else {
+                continue;
+            };
+            let other = if a == from { b } else { a };
+            if other == into || other == from {
+                continue;
+            }
+            let merged = match self.rings.remove(&pair_key(into, other)) {
+                Some(existing) => {
+                    let mut records: Vec<SourceRecord> = existing.into_iter().chain(ring).collect();
+                    records.sort_by_key(|r| r.tick);
+                    let skip = records.len().saturating_sub(self.capacity);
+                    let mut merged = VecDeque::with_capacity(self.capacity);
+                    merged.extend(records.into_iter().skip(skip));
+                    merged
+                }
+                None => ring,
+            };
+            self.rings.insert(pair_key(into, other), merged);
+        }
+    }
+
+    /// Drop the records of a document's reinforcements, e.g. because it
+    /// was removed.
+    pub fn forget_document(&mut self, id: &DocumentId) {
+        let source = EdgeSource::Document(*id);
+        self.rings.retain(|_, ring| {
+            ring.retain(|r| r.source != source);
+            !ring.is_empty()
+        });
+        self.shrink();
+    }
+
+    /// Estimated bytes held: the map's buckets, and every ring's records.
+    pub fn memory_bytes(&self) -> usize {
+        let bucket = std::mem::size_of::<((NodeId, NodeId), VecDeque<SourceRecord>)>() + 1;
+        // A map of capacity n holds about 8/7 n buckets
+        let buckets = self.rings.capacity() * 8 / 7;
+        let records: usize = self.rings.values().map(VecDeque::capacity).sum();
+        buckets * bucket + records * std::mem::size_of::<SourceRecord>()
+    }
+
+    /// [`memory_bytes`](Self::memory_bytes) per tracked edge, 0 when none
+    /// are tracked.
+    pub fn bytes_per_edge(&self) -> f64 {
+        if self.rings.is_empty() {
+            0.0
+        } else {
+            self.memory_bytes() as f64 / self.rings.len() as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rings_keep_the_most_recent_records() {
+        let (a, b) = (NodeId::new(), NodeId::new());
+        let doc = DocumentId::new();
+        let mut provenance = EdgeProvenance::new(3);
+        for tick in 0..5 {
+            provenance.record(a, b, EdgeSource::Document(doc), tick);
+        }
+        provenance.record(b, a, EdgeSource::Query, 5);
+
+        let ticks: Vec<Tick> = provenance.sources(a, b).iter().map(|r| r.tick).collect();
+        assert_eq!(ticks, vec![3, 4, 5]);
+        assert_eq!(provenance.sources(b, a)[2].source, EdgeSource::Query);
+
+        provenance.forget_document(&doc);
+        assert_eq!(provenance.sources(a, b).len(), 1);
+        let import = provenance.import_source("curated.csv");
+        assert_eq!(provenance.import_source("curated.csv"), import);
+        assert_eq!(provenance.import_name(import), Some("curated.csv"));
+        assert_eq!(provenance.import_name(EdgeSource::Query), None);
+        provenance.set_capacity(0);
+        provenance.record(a, b, EdgeSource::Query, 6);
+        assert!(provenance.is_empty());
+    }
+
+    #[test]
+    fn merged_nodes_keep_the_latest_records_of_both() {
+        let (kept, merged, other) = (NodeId::new(), NodeId::new(), NodeId::new());
+        let mut provenance = EdgeProvenance::new(2);
+        provenance.record(kept, other, EdgeSource::Query, 1);
+        provenance.record(merged, other, EdgeSource::Unattributed, 2);
+        provenance.record(merged, other, EdgeSource::Query, 3);
+        provenance.record(merged, kept, EdgeSource::Query, 4);
+
+        provenance.replace_node(merged, kept);
+        let ticks: Vec<Tick> = provenance
+            .sources(kept, other)
+            .iter()
+            .map(|r| r.tick)
+            .collect();
+        assert_eq!(ticks, vec![2, 3]);
+        assert_eq!(provenance.len(), 1);
+    }
+}
@@ -0,0 +1,524 @@
+//! Published bundles — a colony's graph as static, read-only files.
+//!
+//! [`publish_bundle`] writes a directory that can be served as is, from a
+//! CDN or a plain file server, and queried without the runtime:
+//!
+//! - `manifest.json` — the format version, the colony's statistics, the
+//!   tokenizer and synonyms queries are read with, and the size of every
+//!   other file;
+//! - `graph.bin` — nodes with their aliases, edges and anomaly scores;
+//! - `index.bin` — the term index's postings (the colony's own, or built
+//!   at export if it has none), so readers need not tokenize every label;
+//! - `embeddings.bin` — node embeddings, only with
+//!   [`BundleConfig::include_embeddings`].
+//!
+//! The binary files start with a magic number and the format version, then
+//! hold bincode. [`PublishedBundle`] reads a bundle back: the graph as a
+//! [`ColonyView`], the index as an [`InvertedIndex`]. Documents, agents,
+//! traces and provenance are not published. `phago_rag::StaticColony`
+//! answers queries from a bundle.
+
+use crate::colony::{Colony, ColonyStats};
+use crate::community::CommunityAlgorithm;
+use crate::inverted_index::InvertedIndex;
+use crate::read_view::ColonyView;
+use crate::region::Region;
+use crate::synonyms::SynonymConfig;
+use crate::topology_impl::PetTopologyGraph;
+use bincode::Options;
+use phago_core::substrate::Substrate;
+use phago_core::tokenize::{Tokenizer, TokenizerConfig};
+use phago_core::topology::TopologyGraph;
+use phago_core::types::{EdgeData, NodeData, NodeId, NodeType, Position, Rect, Tick};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Format version written by this build.
+pub const PUBLISH_FORMAT_VERSION: u32 = 1;
+
+/// File holding the [`PublishManifest`].
+pub const MANIFEST_FILE: &str = "manifest.json";
+/// File holding the graph.
+pub const GRAPH_FILE: &str = "graph.bin";
+/// File holding the term index.
+pub const INDEX_FILE: &str = "index.bin";
+/// File holding node embeddings, if published.
+pub const EMBEDDINGS_FILE: &str = "embeddings.bin";
+
+/// First bytes of every binary file of a bundle.
+const MAGIC: &[u8; 4] = b"PHPB";
+
+/// Errors publishing or reading a bundle.
+#[derive(Debug)]
+pub enum PublishError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// A binary file could not be encoded or decoded.
+    Encoding(String),
+    /// The bundle was written by a newer, unknown format.
+    UnsupportedVersion(u32),
+    /// A file the manifest lists is absent.
+    MissingFile(String),
+    /// A file does not match the manifest, or is not a bundle file.
+    Malformed(String),
+}
+
+impl fmt::Display for PublishError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublishError::Io(e) => write!(f, "published bundle I/O error: {e}"),
+            PublishError::Json(e) => write!(f, "published bundle manifest is not valid JSON: {e}"),
+            PublishError::Encoding(msg) => write!(f, "published bundle encoding error: {msg}"),
+            PublishError::UnsupportedVersion(v) => write!(
+                f,
+                "published bundle format version {v} is newer than supported ({PUBLISH_FORMAT_VERSION})"
+            ),
+            PublishError::MissingFile(name) => write!(f, "published bundle has no '{name}'"),
+            PublishError::Malformed(msg) => write!(f, "malformed published bundle: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for PublishError {}
+
+impl From<io::Error> for PublishError {
+    fn from(e: io::Error) -> Self {
+        PublishError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for PublishError {
+    fn from(e: serde_json::Error) -> Self {
+        PublishError::Json(e)
+    }
+}
+
+/// Where and what [`publish_bundle`] writes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleConfig {
+    /// Directory written to, created if need be. Files of an earlier
+    /// bundle there are replaced.
+    pub dir: PathBuf,
+    /// Also publish node embeddings (default: false).
+    pub include_embeddings: bool,
+}
+
+impl BundleConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            include_embeddings: false,
+        }
+    }
+
+    pub fn with_embeddings(mut self, include: bool) -> Self {
+        self.include_embeddings = include;
+        self
+    }
+}
+
+/// Describes a published bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublishManifest {
+    pub format_version: u32,
+    /// Version of Phago that published the bundle.
+    pub phago_version: String,
+    /// The colony's tick when it was published.
+    pub tick: Tick,
+    /// The colony's [graph revision](Colony::graph_revision) when it was
+    /// published.
+    pub revision: u64,
+    pub stats: ColonyStats,
+    /// How queries against the bundle must be tokenized.
+    pub tokenizer: TokenizerConfig,
+    /// Synonyms queries against the bundle are expanded with.
+    pub synonyms: SynonymConfig,
+    pub community_algorithm: CommunityAlgorithm,
+    #[serde(default)]
+    pub world_bounds: Option<Rect>,
+    #[serde(default)]
+    pub regions: Vec<Region>,
+    /// Size in bytes of every file but the manifest, by name.
+    pub files: BTreeMap<String, u64>,
+}
+
+impl PublishManifest {
+    /// Whether the bundle has node embeddings.
+    pub fn has_embeddings(&self) -> bool {
+        self.files.contains_key(EMBEDDINGS_FILE)
+    }
+}
+
+/// A node as published. Mirrors [`NodeData`], whose serde attributes
+/// bincode cannot read back, with the node's aliases.
+#[derive(Serialize, Deserialize)]
+struct PublishedNode {
+    id: NodeId,
+    label: String,
+    node_type: NodeType,
+    position: Position,
+    access_count: u64,
+    created_tick: u64,
+    last_accessed_tick: u64,
+    tags: Vec<(String, u64)>,
+    aliases: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GraphFile {
+    nodes: Vec<PublishedNode>,
+    /// Endpoints are positions in `nodes`.
+    edges: Vec<(u32, u32, EdgeData)>,
+    anomaly_scores: Vec<(u32, f64)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexFile {
+    postings: Vec<(String, Vec<(NodeId, u32)>)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EmbeddingsFile {
+    embeddings: Vec<(NodeId, Vec<f32>)>,
+}
+
+/// Write `colony`'s graph, term index and, if asked, embeddings as a
+/// bundle in `config.dir`. Returns the manifest written.
+pub fn publish_bundle(
+    colony: &Colony,
+    config: BundleConfig,
+) -> Result<PublishManifest, PublishError> {
+    let substrate = colony.substrate();
+    let graph = substrate.graph();
+    let ids = graph.all_nodes();
+    let position: HashMap<NodeId, u32> = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (*id, i as u32))
+        .collect();
+
+    let nodes = ids
+        .iter()
+        .filter_map(|id| graph.get_node(id))
+        .map(|node| PublishedNode {
+            id: node.id,
+            label: node.label.clone(),
+            node_type: node.node_type.clone(),
+            position: node.position,
+            access_count: node.access_count,
+            created_tick: node.created_tick,
+            last_accessed_tick: node.last_accessed_tick,
+            tags: node.tags.iter().map(|(t, n)| (t.clone(), *n)).collect(),
+            aliases: graph.aliases(&node.id),
+        })
+        .collect();
+    let edges = graph
+        .all_edges()
+        .into_iter()
+        .map(|(from, to, edge)| (position[&from], position[&to], edge.clone()))
+        .collect();
+    let anomaly_scores = colony
+        .anomalies()
+        .into_iter()
+        .filter_map(|(id, score)| position.get(&id).map(|i| (*i, score)))
+        .collect();
+    let graph_file = GraphFile {
+        nodes,
+        edges,
+        anomaly_scores,
+    };
+
+    // The colony's index when it is current, else one built now
+    let tokenizer = colony.tokenizer();
+    let built;
+    let index = match substrate
+        .term_index()
+        .filter(|index| index.tokenizer() == tokenizer)
+    {
+        Some(index) => index,
+        None => {
+            built = InvertedIndex::build(tokenizer.clone(), graph);
+            &built
+        }
+    };
+    let index_file = IndexFile {
+        postings: index
+            .terms()
+            .map(|(term, nodes)| {
+                let mut nodes: Vec<(NodeId, u32)> =
+                    nodes.iter().map(|(id, tf)| (*id, *tf)).collect();
+                nodes.sort();
+                (term.to_string(), nodes)
+            })
+            .collect(),
+    };
+
+    std::fs::create_dir_all(&config.dir)?;
+    let mut files = BTreeMap::new();
+    files.insert(
+        GRAPH_FILE.to_string(),
+        write_file(&config.dir, GRAPH_FILE, &graph_file)?,
+    );
+    files.insert(
+        INDEX_FILE.to_string(),
+        write_file(&config.dir, INDEX_FILE, &index_file)?,
+    );
+    if config.include_embeddings {
+        let embeddings_file = EmbeddingsFile {
+            embeddings: ids
+                .iter()
+                .filter_map(|id| substrate.embedding(id).map(|e| (*id, e)))
+                .collect(),
+        };
+        files.insert(
+            EMBEDDINGS_FILE.to_string(),
+            write_file(&config.dir, EMBEDDINGS_FILE, &embeddings_file)?,
+        );
+    }
+
+    let manifest = PublishManifest {
+        format_version: PUBLISH_FORMAT_VERSION,
+        phago_version: env!("CARGO_PKG_VERSION").to_string(),
+        tick: substrate.current_tick(),
+        revision: colony.graph_revision(),
+        stats: colony.stats(),
+        tokenizer: tokenizer.config().clone(),
+        synonyms: colony.synonyms().clone(),
+        community_algorithm: colony.community_algorithm(),
+        world_bounds: substrate.geometry().bounds,
+        regions: substrate.regions().to_vec(),
+        files,
+    };
+    // Written last, so a reader never finds a manifest ahead of its files
+    write_atomically(
+        &config.dir.join(MANIFEST_FILE),
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+/// Encode `value` into `dir/name`, returning the bytes written.
+fn write_file<T: Serialize>(dir: &Path, name: &str, value: &T) -> Result<u64, PublishError> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&PUBLISH_FORMAT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, value)
+        .map_err(|e| PublishError::Encoding(e.to_string()))?;
+    write_atomically(&dir.join(name), &bytes)?;
+    Ok(bytes.len() as u64)
+}
+
+fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// A published bundle on disk. Opening it reads only the manifest; each
+/// file is read when asked for.
+#[derive(Debug, Clone)]
+pub struct PublishedBundle {
+    dir: PathBuf,
+    manifest: PublishManifest,
+}
+
+impl PublishedBundle {
+    /// Read the manifest in `dir`, checking that every file it lists is
+    /// there at the size it records.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, PublishError> {
+        let dir = dir.as_ref().to_path_buf();
+        let manifest_path = dir.join(MANIFEST_FILE);
+        if !manifest_path.exists() {
+            return Err(PublishError::MissingFile(MANIFEST_FILE.to_string()));
+        }
+        let manifest: PublishManifest = serde_json::from_slice(&std::fs::read(manifest_path)?)?;
+        if manifest.format_version > PUBLISH_FORMAT_VERSION {
+            return Err(PublishError::UnsupportedVersion(manifest.format_version));
+        }
+        for required in [GRAPH_FILE, INDEX_FILE] {
+            if !manifest.files.contains_key(required) {
+                return Err(PublishError::MissingFile(required.to_string()));
+            }
+        }
+        for (name, size) in &manifest.files {
+            let Ok(metadata) = std::fs::metadata(dir.join(name)) else {
+                return Err(PublishError::MissingFile(name.clone()));
+            };
+            if metadata.len() != *size {
+                return Err(PublishError::Malformed(format!(
+                    "'{name}' is {} bytes, the manifest says {size}",
+                    metadata.len()
+                )));
+            }
+        }
+        Ok(Self { dir, manifest })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn manifest(&self) -> &PublishManifest {
+        &self.manifest
+    }
+
+    /// The tokenizer queries against the bundle must use.
+    pub fn tokenizer(&self) -> Tokenizer {
+        Tokenizer::new(self.manifest.tokenizer.clone())
+    }
+
+    /// The published graph, as a view of the colony when it was published.
+    pub fn read_graph(&self) -> Result<ColonyView, PublishError> {
+        let file: GraphFile = self.read_file(GRAPH_FILE)?;
+        let mut graph = PetTopologyGraph::new();
+        let ids: Vec<NodeId> = file.nodes.iter().map(|n| n.id).collect();
+        let node = |i: u32| {
+            ids.get(i as usize)
+                .copied()
+                .ok_or_else(|| PublishError::Malformed(format!("no node at position {i}")))
+        };
+        for published in file.nodes {
+            let id = published.id;
+            graph.add_node(NodeData {
+                id,
+                label: published.label,
+                node_type: published.node_type,
+                position: published.position,
+                access_count: published.access_count,
+                created_tick: published.created_tick,
+                last_accessed_tick: published.last_accessed_tick,
+                tags: published.tags.into_iter().collect(),
+            });
+            for alias in &published.aliases {
+                graph.add_alias(&id, alias);
+            }
+        }
+        for (from, to, edge) in file.edges {
+            let _ = graph.set_edge(node(from)?, node(to)?, edge);
+        }
+        let anomaly_scores = file
+            .anomaly_scores
+            .into_iter()
+            .map(|(i, score)| Ok((node(i)?, score)))
+            .collect::<Result<_, PublishError>>()?;
+        Ok(ColonyView::published(&self.manifest, graph, anomaly_scores))
+    }
+
+    /// The published term index, covering every node of `graph` (the
+    /// bundle's [`read_graph`](Self::read_graph)).
+    pub fn read_index(&self, graph: &dyn TopologyGraph) -> Result<InvertedIndex, PublishError> {
+        let file: IndexFile = self.read_file(INDEX_FILE)?;
+        let postings = file
+            .postings
+            .into_iter()
+            .map(|(term, nodes)| (term, nodes.into_iter().collect()))
+            .collect();
+        Ok(InvertedIndex::from_postings(
+            self.tokenizer(),
+            &graph.all_nodes(),
+            postings,
+        ))
+    }
+
+    /// Published node embeddings, or `None` if the bundle has none.
+    pub fn read_embeddings(&self) -> Result<Option<HashMap<NodeId, Vec<f32>>>, PublishError> {
+        if !self.manifest.has_embeddings() {
+            return Ok(None);
+        }
+        let file: EmbeddingsFile = self.read_file(EMBEDDINGS_FILE)?;
+        Ok(Some(file.embeddings.into_iter().collect()))
+    }
+
+    fn read_file<T: DeserializeOwned>(&self, name: &str) -> Result<T, PublishError> {
+        let bytes = std::fs::read(self.dir.join(name))?;
+        let header = MAGIC.len() + 4;
+        if bytes.len() < header || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(PublishError::Malformed(format!(
+                "'{name}' is not a bundle file"
+            )));
+        }
+        let version = u32::from_le_bytes(bytes[MAGIC.len()..header].try_into().unwrap());
+        if version > PUBLISH_FORMAT_VERSION {
+            return Err(PublishError::UnsupportedVersion(version));
+        }
+        // Length prefixes are untrusted; cap allocations at the file size
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .with_limit(bytes.len() as u64)
+            .deserialize(&bytes[header..])
+            .map_err(|e| PublishError::Encoding(format!("'{name}': {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_agents::digester::Digester;
+
+    fn digested_colony() -> Colony {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Biology",
+            "The cell membrane regulates protein transport into the cell.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony.run(15);
+        colony
+    }
+
+    #[test]
+    fn graph_and_index_round_trip() {
+        let mut colony = digested_colony();
+        let graph = colony.substrate().graph();
+        let membrane = graph.find_nodes_by_exact_label("membrane")[0];
+        colony
+            .substrate_mut()
+            .graph_mut()
+            .add_alias(&membrane, "membranes");
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = publish_bundle(&colony, BundleConfig::new(dir.path())).unwrap();
+        assert!(!manifest.has_embeddings());
+
+        let bundle = PublishedBundle::open(dir.path()).unwrap();
+        assert_eq!(bundle.manifest(), &manifest);
+        let view = bundle.read_graph().unwrap();
+        let (original, published) = (colony.substrate().graph(), view.graph());
+        assert_eq!(published.all_nodes(), original.all_nodes());
+        assert_eq!(published.edge_count(), original.edge_count());
+        for (from, to, edge) in original.all_edges() {
+            let copy = published.get_edge(&from, &to).unwrap();
+            assert_eq!(copy.weight, edge.weight);
+            assert_eq!(copy.co_activations, edge.co_activations);
+        }
+        assert_eq!(published.find_nodes_by_exact_label("membranes"), [membrane]);
+        assert_eq!(view.stats(), &colony.stats());
+
+        let index = bundle.read_index(published).unwrap();
+        let built = InvertedIndex::build(colony.tokenizer().clone(), original);
+        assert_eq!(index.node_count(), built.node_count());
+        assert_eq!(index.postings("cell"), built.postings("cell"));
+    }
+
+    #[test]
+    fn damaged_bundles_are_rejected() {
+        let colony = digested_colony();
+        let dir = tempfile::tempdir().unwrap();
+        publish_bundle(&colony, BundleConfig::new(dir.path())).unwrap();
+
+        std::fs::write(dir.path().join(INDEX_FILE), b"not an index").unwrap();
+        assert!(matches!(
+            PublishedBundle::open(dir.path()),
+            Err(PublishError::Malformed(_))
+        ));
+        std::fs::remove_file(dir.path().join(GRAPH_FILE)).unwrap();
+        assert!(matches!(
+            PublishedBundle::open(dir.path()),
+            Err(PublishError::MissingFile(name)) if name == GRAPH_FILE
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+//! Query log — a bounded record of the queries run against a colony.
+//!
+//! When enabled through [`ColonyConfig::query_log`](crate::colony::ColonyConfig::query_log),
+//! every query run by phago-rag's `QueryEngine` or `hybrid_query` appends
+//! one [`QueryLogEntry`]: the terms, when it ran, where it came from, what
+//! it returned and which edges it traversed or reinforced. The oldest
+//! entries are dropped once the configured entry or byte budget is
+//! exceeded. Entries are saved and restored with sessions.
+//!
+//! The log is written through `&Colony` (hybrid queries do not borrow the
+//! colony mutably), so it keeps its entries behind a mutex.
+
+use phago_core::types::Tick;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where a query came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QuerySource {
+    /// Called directly through the Rust API.
+    #[default]
+    Library,
+    /// The `phago` command-line tool.
+    Cli,
+    /// An MCP tool call.
+    Mcp,
+    /// The web dashboard or its HTTP API.
+    Web,
+}
+
+impl QuerySource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuerySource::Library => "library",
+            QuerySource::Cli => "cli",
+            QuerySource::Mcp => "mcp",
+            QuerySource::Web => "web",
+        }
+    }
+}
+
+/// Query log settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryLogConfig {
+    /// Record queries (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Maximum number of entries kept (0 = unlimited).
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+    /// Approximate maximum bytes kept across entries (0 = unlimited).
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+}
+
+fn default_max_entries() -> usize {
+    10_000
+}
+
+fn default_max_bytes() -> usize {
+    16 * 1024 * 1024
+}
+
+impl Default for QueryLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: default_max_entries(),
+            max_bytes: default_max_bytes(),
+        }
+    }
+}
+
+impl QueryLogConfig {
+    /// Logging enabled with the default bounds.
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// An undirected edge, identified by its endpoint labels.
+pub type EdgeLabels = (String, String);
+
+/// One logged query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryLogEntry {
+    /// Colony tick when the query ran.
+    pub tick: Tick,
+    /// Wall-clock time, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub source: QuerySource,
+    /// The raw query text.
+    pub text: String,
+    /// Terms the query was tokenized into.
+    pub terms: Vec<String>,
+    /// Tags the query was restricted to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Labels of the returned results, best first.
+    pub results: Vec<String>,
+    /// Edges the query followed or scored to reach its results.
+    #[serde(default)]
+    pub traversed_edges: Vec<EdgeLabels>,
+    /// Edges the query strengthened.
+    #[serde(default)]
+    pub reinforced_edges: Vec<EdgeLabels>,
+}
+
+impl QueryLogEntry {
+    /// Start an entry for `text` at `tick`, stamped with the current time.
+    pub fn new(tick: Tick, source: QuerySource, text: &str, terms: Vec<String>) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self {
+            tick,
+            timestamp_ms,
+            source,
+            text: text.to_string(),
+            terms,
+            tags: None,
+            results: Vec::new(),
+            traversed_edges: Vec::new(),
+            reinforced_edges: Vec::new(),
+        }
+    }
+
+    /// Approximate heap and inline size, used for the byte budget.
+    pub fn approx_bytes(&self) -> usize {
+        let strings = |v: &[String]| v.iter().map(String::len).sum::<usize>();
+        let edges = |v: &[EdgeLabels]| v.iter().map(|(a, b)| a.len() + b.len()).sum::<usize>();
+        std::mem::size_of::<Self>()
+            + self.text.len()
+            + strings(&self.terms)
+            + self.tags.as_deref().map_or(0, strings)
+            + strings(&self.results)
+            + edges(&self.traversed_edges)
+            + edges(&self.reinforced_edges)
+    }
+
+    /// Key grouping repeated queries: the terms, or the lowercased text if
+    /// it had none.
+    fn query_key(&self) -> String {
+        if self.terms.is_empty() {
+            self.text.trim().to_lowercase()
+        } else {
+            self.terms.join(" ")
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    entries: VecDeque<QueryLogEntry>,
+    bytes: usize,
+}
+
+/// Bounded, append-only log of queries.
+#[derive(Debug)]
+pub struct QueryLog {
+    inner: Mutex<Entries>,
+    max_entries: usize,
+    max_bytes: usize,
+}
+
+impl QueryLog {
+    /// An empty log with the given bounds (0 = unlimited).
+    pub fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(Entries::default()),
+            max_entries,
+            max_bytes,
+        }
+    }
+
+    /// An empty log bounded as configured.
+    pub fn from_config(config: &QueryLogConfig) -> Self {
+        Self::new(config.max_entries, config.max_bytes)
+    }
+
+    /// An unbounded log holding `entries`, e.g. to aggregate a saved session.
+    pub fn from_entries(entries: Vec<QueryLogEntry>) -> Self {
+        let log = Self::new(0, 0);
+        log.extend(entries);
+        log
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Append an entry, evicting the oldest ones beyond the bounds.
+    pub fn record(&self, entry: QueryLogEntry) {
+        self.extend(std::iter::once(entry));
+    }
+
+    /// Append entries in order, evicting the oldest ones beyond the bounds.
+    pub fn extend(&self, entries: impl IntoIterator<Item = QueryLogEntry>) {
+        let mut inner = self.lock();
+        for entry in entries {
+            inner.bytes += entry.approx_bytes();
+            inner.entries.push_back(entry);
+        }
+        while inner.entries.len() > 1
+            && ((self.max_entries > 0 && inner.entries.len() > self.max_entries)
+                || (self.max_bytes > 0 && inner.bytes > self.max_bytes))
+        {
+            if let Some(old) = inner.entries.pop_front() {
+                inner.bytes -= old.approx_bytes();
+            }
+        }
+    }
+
+    /// Number of entries kept.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Approximate bytes held by the kept entries.
+    pub fn bytes(&self) -> usize {
+        self.lock().bytes
+    }
+
+    /// Copy of the kept entries, oldest first.
+    pub fn entries(&self) -> Vec<QueryLogEntry> {
+        self.lock().entries.iter().cloned().collect()
+    }
+
+    /// Drop every entry.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.bytes = 0;
+    }
+
+    /// The `n` most frequent queries with their counts, most frequent first
+    /// (ties broken alphabetically).
+    pub fn top_queries(&self, n: usize) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for entry in &self.lock().entries {
+            *counts.entry(entry.query_key()).or_insert(0) += 1;
+        }
+        top_n(counts, n)
+    }
+
+    /// Entries that returned no results, oldest first.
+    pub fn zero_result_queries(&self) -> Vec<QueryLogEntry> {
+        self.lock()
+            .entries
+            .iter()
+            .filter(|e| e.results.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// The `n` edges traversed by the most queries, with their counts.
+    /// Endpoints are ordered alphabetically since edges are undirected.
+    pub fn most_traversed_edges(&self, n: usize) -> Vec<(EdgeLabels, usize)> {
+        let mut counts: HashMap<EdgeLabels, usize> = HashMap::new();
+        for entry in &self.lock().entries {
+            for (a, b) in &entry.traversed_edges {
+                let key = if a <= b {
+                    (a.clone(), b.clone())
+                } else {
+                    (b.clone(), a.clone())
+                };
+                *counts.entry(key).or_insert(0) += 1;
+            }
+        }
+        top_n(counts, n)
+    }
+}
+
+impl Clone for QueryLog {
+    fn clone(&self) -> Self {
+        let log = Self::new(self.max_entries, self.max_bytes);
+        log.extend(self.entries());
+        log
+    }
+}
+
+fn top_n<K: Ord>(counts: HashMap<K, usize>, n: usize) -> Vec<(K, usize)> {
+    let mut counts: Vec<(K, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(n);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str, results: &[&str], edges: &[(&str, &str)]) -> QueryLogEntry {
+        let terms = text.split_whitespace().map(str::to_string).collect();
+        let mut e = QueryLogEntry::new(0, QuerySource::Library, text, terms);
+        e.results = results.iter().map(|r| r.to_string()).collect();
+        e.traversed_edges = edges
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect();
+        e
+    }
+
+    #[test]
+    fn aggregates_queries_and_edges() {
+        let log = QueryLog::new(0, 0);
+        log.record(entry("cell membrane", &["cell"], &[("cell", "membrane")]));
+        log.record(entry("cell membrane", &["cell"], &[("membrane", "cell")]));
+        log.record(entry("ribosome", &[], &[]));
+
+        assert_eq!(log.top_queries(1), vec![("cell membrane".to_string(), 2)]);
+        let zero = log.zero_result_queries();
+        assert_eq!(zero.len(), 1);
+        assert_eq!(zero[0].text, "ribosome");
+        assert_eq!(
+            log.most_traversed_edges(5),
+            vec![(("cell".to_string(), "membrane".to_string()), 2)]
+        );
+    }
+
+    #[test]
+    fn evicts_oldest_beyond_entry_and_byte_bounds() {
+        let log = QueryLog::new(2, 0);
+        for text in ["a", "b", "c"] {
+            log.record(entry(text, &[], &[]));
+        }
+        let texts: Vec<String> = log.entries().into_iter().map(|e| e.text).collect();
+        assert_eq!(texts, vec!["b", "c"]);
+
+        let one = entry("cell", &["cell"], &[]).approx_bytes();
+        let log = QueryLog::new(0, one * 2);
+        for _ in 0..5 {
+            log.record(entry("cell", &["cell"], &[]));
+        }
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.bytes(), one * 2);
+    }
+}
@@ -0,0 +1,936 @@
+//! RDF/Turtle export and import of the knowledge graph.
+//!
+//! [`export_turtle`] writes every node as a resource with a stable URI
+//! under [`RdfConfig::base_uri`]: `concept/`, `document/`, `insight/` or
+//! `anomaly/` followed by a slug of the label, so "cell membrane" becomes
+//! `<base>concept/cell-membrane`. Labels that slug alike get `-2`, `-3`, …
+//! in label order. Concepts are typed `skos:Concept`; documents, insights
+//! and anomalies `phago:Document`, `phago:Insight` and `phago:Anomaly`.
+//! Every label is kept verbatim as a `skos:prefLabel`, and the node's
+//! aliases (from compaction or synonyms) as `skos:altLabel`s.
+//!
+//! Each edge is a direct triple — `phago:related_to`, or the relation an
+//! agent wired it with — and, with [`include_weights`], a `phago:Edge`
+//! resource carrying its weight and co-activation count.
+//!
+//! [`import_turtle`] reads such a document back: it understands the
+//! common Turtle syntax (prefixes, predicate and object lists, literals),
+//! rebuilds nodes and weighted edges, and skips predicates it does not
+//! know. Relation names and positions are not restored.
+//!
+//! [`include_weights`]: RdfConfig::include_weights
+
+use crate::colony::Colony;
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Namespace of the `phago:` vocabulary.
+pub const PHAGO_NS: &str = "https://phago.dev/ns#";
+
+pub(crate) const SKOS_NS: &str = "http://www.w3.org/2004/02/skos/core#";
+pub(crate) const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+pub(crate) const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+const XSD_NS: &str = "http://www.w3.org/2001/XMLSchema#";
+
+/// Settings for [`export_turtle`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RdfConfig {
+    /// Prefix of every node and edge URI. A `/` is appended unless it
+    /// already ends in `/` or `#`.
+    pub base_uri: String,
+    /// Emit a `phago:Edge` resource with weight and co-activations for
+    /// every edge.
+    pub include_weights: bool,
+    /// Emit access counts, ticks and the concepts insights were derived
+    /// from.
+    pub include_provenance: bool,
+}
+
+impl Default for RdfConfig {
+    fn default() -> Self {
+        Self {
+            base_uri: "https://phago.dev/graph/".to_string(),
+            include_weights: true,
+            include_provenance: true,
+        }
+    }
+}
+
+/// Export the colony's knowledge graph as a Turtle document.
+pub fn export_turtle(colony: &Colony, config: RdfConfig) -> String {
+    let graph = colony.substrate().graph();
+    let base = if config.base_uri.ends_with('/') || config.base_uri.ends_with('#') {
+        config.base_uri.clone()
+    } else {
+        format!("{}/", config.base_uri)
+    };
+
+    let ids = graph.all_nodes();
+    let mut nodes: Vec<&NodeData> = ids.iter().filter_map(|id| graph.get_node(id)).collect();
+    nodes.sort_by(|a, b| {
+        type_path(&a.node_type)
+            .cmp(type_path(&b.node_type))
+            .then_with(|| a.label.cmp(&b.label))
+            .then(a.created_tick.cmp(&b.created_tick))
+            .then(a.id.cmp(&b.id))
+    });
+    let uris = node_uris(&base, &nodes);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "@prefix phago: <{PHAGO_NS}> .");
+    let _ = writeln!(out, "@prefix skos: <{SKOS_NS}> .");
+    let _ = writeln!(out, "@prefix xsd: <{XSD_NS}> .");
+
+    for node in &nodes {
+        let _ = write!(
+            out,
+            "\n<{}> a {} ;\n    skos:prefLabel {}",
+            uris[&node.id],
+            type_name(&node.node_type),
+            literal(&node.label)
+        );
+        let aliases = graph.aliases(&node.id);
+        if !aliases.is_empty() {
+            let aliases: Vec<String> = aliases.iter().map(|a| literal(a)).collect();
+            let _ = write!(out, " ;\n    skos:altLabel {}", aliases.join(", "));
+        }
+        if config.include_provenance {
+            let _ = write!(
+                out,
+                " ;\n    phago:accessCount {} ;\n    phago:createdTick {} ;\n    phago:lastAccessedTick {}",
+                node.access_count, node.created_tick, node.last_accessed_tick
+            );
+            let sources = colony.insight_provenance(&node.id).unwrap_or_default();
+            let sources: Vec<&str> = sources
+                .iter()
+                .filter_map(|id| uris.get(id))
+                .map(String::as_str)
+                .collect();
+            if !sources.is_empty() {
+                let _ = write!(out, " ;\n    phago:derivedFrom <{}>", sources.join(">, <"));
+            }
+        }
+        out.push_str(" .\n");
+    }
+
+    let mut edges: Vec<(&str, &str, &str, &EdgeData)> = graph
+        .all_edges()
+        .into_iter()
+        .filter_map(|(a, b, edge)| {
+            let (from, relation, to) = if let Some(r) = colony.edge_relation(&a, &b) {
+                (a, r, b)
+            } else if let Some(r) = colony.edge_relation(&b, &a) {
+                (b, r, a)
+            } else {
+                (a, "related_to", b)
+            };
+            Some((
+                uris.get(&from)?.as_str(),
+                relation,
+                uris.get(&to)?.as_str(),
+                edge,
+            ))
+        })
+        .collect();
+    edges.sort_by(|x, y| (x.0, x.2).cmp(&(y.0, y.2)));
+
+    if !edges.is_empty() {
+        out.push('\n');
+    }
+    for (from, relation, to, _) in &edges {
+        let _ = writeln!(out, "<{from}> <{PHAGO_NS}{}> <{to}> .", slug(relation, '_'));
+    }
+
+    if config.include_weights {
+        let mut taken = HashSet::new();
+        for (from, relation, to, edge) in &edges {
+            let stem = format!(
+                "{base}edge/{}--{}",
+                from.rsplit('/').next().unwrap_or_default(),
+                to.rsplit('/').next().unwrap_or_default()
+            );
+            let uri = unique(stem, &mut taken);
+            let _ = write!(
+                out,
+                "\n<{uri}> a phago:Edge ;\n    phago:source <{from}> ;\n    phago:target <{to}> ;\n    phago:relation {} ;\n    phago:weight {} ;\n    phago:coActivations {}",
+                literal(relation),
+                double(edge.weight),
+                edge.co_activations
+            );
+            if config.include_provenance {
+                let _ = write!(
+                    out,
+                    " ;\n    phago:createdTick {} ;\n    phago:lastActivatedTick {}",
+                    edge.created_tick, edge.last_activated_tick
+                );
+            }
+            out.push_str(" .\n");
+        }
+    }
+    out
+}
+
+/// What [`import_turtle`] added to the colony.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TurtleImport {
+    /// Nodes created.
+    pub nodes_added: usize,
+    /// Nodes that matched an existing node of the same label and type.
+    pub nodes_matched: usize,
+    /// Edges created or updated.
+    pub edges: usize,
+    /// Triples whose predicate is not understood.
+    pub ignored: usize,
+}
+
+/// Why a Turtle document could not be read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurtleError {
+    /// 1-based line the problem was found on.
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for TurtleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for TurtleError {}
+
+/// Rebuild nodes and weighted edges from a Turtle document.
+///
+/// Every subject with a `skos:prefLabel` (or `rdfs:label`) becomes a node,
+/// typed by its `rdf:type`, with its `skos:altLabel`s as aliases; a node
+/// with the same label and type already in the colony is reused. Edges come from `phago:Edge` resources, or, for
+/// pairs without one, from any `phago:` predicate linking two nodes, with
+/// weight 1.0. Nothing is added if the document does not parse.
+pub fn import_turtle(colony: &mut Colony, turtle: &str) -> Result<TurtleImport, TurtleError> {
+    let triples = Parser::new(turtle).parse()?;
+    let tick = colony.substrate().current_tick();
+    let mut report = TurtleImport::default();
+
+    let mut subjects: Vec<&str> = Vec::new();
+    let mut properties: HashMap<&str, Vec<(&str, &Term)>> = HashMap::new();
+    for (s, p, o) in &triples {
+        let entry = properties.entry(s.as_str()).or_insert_with(|| {
+            subjects.push(s.as_str());
+            Vec::new()
+        });
+        entry.push((p.as_str(), o));
+    }
+    let value = |subject: &str, predicate: &str| {
+        properties[subject]
+            .iter()
+            .find(|(p, _)| *p == predicate)
+            .map(|(_, o)| *o)
+    };
+    let number = |subject: &str, predicate: &str| {
+        value(subject, predicate).and_then(|o| match o {
+            Term::Literal(v) => v.trim().parse::<f64>().ok(),
+            _ => None,
+        })
+    };
+    let is_edge = |subject: &str| {
+        properties[subject].iter().any(|(p, o)| {
+            *p == RDF_TYPE && matches!(o, Term::Iri(t) if t.strip_prefix(PHAGO_NS) == Some("Edge"))
+        })
+    };
+
+    let mut nodes: HashMap<&str, NodeId> = HashMap::new();
+    for &subject in &subjects {
+        let label = match value(subject, &format!("{SKOS_NS}prefLabel"))
+            .or_else(|| value(subject, RDFS_LABEL))
+        {
+            Some(Term::Literal(label)) if !is_edge(subject) => label.clone(),
+            _ => continue,
+        };
+        let node_type = properties[subject]
+            .iter()
+            .filter(|(p, _)| *p == RDF_TYPE)
+            .find_map(|(_, o)| match o {
+                Term::Iri(t) => node_type(t),
+                _ => None,
+            })
+            .unwrap_or(NodeType::Concept);
+
+        let graph = colony.substrate().graph();
+        let existing = graph
+            .find_nodes_by_exact_label(&label)
+            .into_iter()
+            .find(|id| {
+                graph
+                    .get_node(id)
+                    .is_some_and(|n| n.label == label && n.node_type == node_type)
+            });
+        let id = match existing {
+            Some(id) => {
+                report.nodes_matched += 1;
+                id
+            }
+            None => {
+                report.nodes_added += 1;
+                let count = |p: &str| number(subject, &format!("{PHAGO_NS}{p}")).map(|n| n as u64);
+                colony.substrate_mut().add_node(NodeData {
+                    id: NodeId::new(),
+                    label,
+                    node_type,
+                    position: Position::new(0.0, 0.0),
+                    access_count: count("accessCount").unwrap_or(1),
+                    created_tick: count("createdTick").unwrap_or(tick),
+                    last_accessed_tick: count("lastAccessedTick").unwrap_or(tick),
+                    tags: Default::default(),
+                })
+            }
+        };
+        for (_, alias) in properties[subject]
+            .iter()
+            .filter(|(p, _)| p.strip_prefix(SKOS_NS) == Some("altLabel"))
+        {
+            if let Term::Literal(alias) = alias {
+                colony.substrate_mut().graph_mut().add_alias(&id, alias);
+            }
+        }
+        nodes.insert(subject, id);
+    }
+
+    let node_of = |term: Option<&Term>| match term {
+        Some(Term::Iri(iri)) => nodes.get(iri.as_str()).copied(),
+        _ => None,
+    };
+    let mut edges: HashMap<(NodeId, NodeId), EdgeData> = HashMap::new();
+    for &subject in subjects.iter().filter(|s| is_edge(s)) {
+        let from = node_of(value(subject, &format!("{PHAGO_NS}source")));
+        let to = node_of(value(subject, &format!("{PHAGO_NS}target")));
+        let (Some(from), Some(to)) = (from, to) else {
+            continue;
+        };
+        let count = |p: &str| number(subject, &format!("{PHAGO_NS}{p}")).map(|n| n as u64);
+        edges.insert(
+            pair(from, to),
+            EdgeData {
+                weight: number(subject, &format!("{PHAGO_NS}weight")).unwrap_or(1.0),
+                co_activations: count("coActivations").unwrap_or(1),
+                created_tick: count("createdTick").unwrap_or(tick),
+                last_activated_tick: count("lastActivatedTick").unwrap_or(tick),
+            },
+        );
+    }
+
+    for (s, p, o) in &triples {
+        if known_predicate(p) {
+            continue;
+        }
+        match (nodes.get(s.as_str()), node_of(Some(o))) {
+            (Some(&from), Some(to)) if p.starts_with(PHAGO_NS) => {
+                edges.entry(pair(from, to)).or_insert(EdgeData {
+                    weight: 1.0,
+                    co_activations: 1,
+                    created_tick: tick,
+                    last_activated_tick: tick,
+                });
+            }
+            _ => report.ignored += 1,
+        }
+    }
+
+    for ((from, to), edge) in edges {
+        let _ = colony.substrate_mut().set_edge(from, to, edge);
+        report.edges += 1;
+    }
+    Ok(report)
+}
+
+/// The triples of a Turtle document with prefixes expanded, for readers
+/// of vocabularies other than Phago's own (see [`crate::import`]).
+pub(crate) fn parse_turtle(turtle: &str) -> Result<Vec<(String, String, Term)>, TurtleError> {
+    Parser::new(turtle).parse()
+}
+
+/// Whether [`import_turtle`] reads `predicate` as a node or edge property.
+fn known_predicate(predicate: &str) -> bool {
+    if predicate == RDF_TYPE || predicate == RDFS_LABEL {
+        return true;
+    }
+    if matches!(
+        predicate.strip_prefix(SKOS_NS),
+        Some("prefLabel" | "altLabel")
+    ) {
+        return true;
+    }
+    predicate.strip_prefix(PHAGO_NS).is_some_and(|local| {
+        matches!(
+            local,
+            "accessCount"
+                | "createdTick"
+                | "lastAccessedTick"
+                | "lastActivatedTick"
+                | "derivedFrom"
+                | "source"
+                | "target"
+                | "relation"
+                | "weight"
+                | "coActivations"
+        )
+    })
+}
+
+/// Edges are undirected; key them by ordered endpoints.
+fn pair(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn type_path(node_type: &NodeType) -> &'static str {
+    match node_type {
+        NodeType::Concept => "concept",
+        NodeType::Document => "document",
+        NodeType::Insight => "insight",
+        NodeType::Anomaly => "anomaly",
+    }
+}
+
+fn type_name(node_type: &NodeType) -> &'static str {
+    match node_type {
+        NodeType::Concept => "skos:Concept",
+        NodeType::Document => "phago:Document",
+        NodeType::Insight => "phago:Insight",
+        NodeType::Anomaly => "phago:Anomaly",
+    }
+}
+
+fn node_type(iri: &str) -> Option<NodeType> {
+    if iri == format!("{SKOS_NS}Concept") {
+        return Some(NodeType::Concept);
+    }
+    match iri.strip_prefix(PHAGO_NS)? {
+        "Concept" => Some(NodeType::Concept),
+        "Document" => Some(NodeType::Document),
+        "Insight" => Some(NodeType::Insight),
+        "Anomaly" => Some(NodeType::Anomaly),
+        _ => None,
+    }
+}
+
+/// URIs for `nodes`, which must be in a stable order.
+fn node_uris(base: &str, nodes: &[&NodeData]) -> HashMap<NodeId, String> {
+    let mut taken = HashSet::new();
+    nodes
+        .iter()
+        .map(|n| {
+            let stem = format!("{base}{}/{}", type_path(&n.node_type), slug(&n.label, '-'));
+            (n.id, unique(stem, &mut taken))
+        })
+        .collect()
+}
+
+/// `stem`, or `stem-2`, `stem-3`, … if it is taken.
+fn unique(stem: String, taken: &mut HashSet<String>) -> String {
+    let mut uri = stem.clone();
+    let mut n = 2;
+    while !taken.insert(uri.clone()) {
+        uri = format!("{stem}-{n}");
+        n += 1;
+    }
+    uri
+}
+
+/// Lowercase alphanumeric runs joined by `separator`: "[BRIDGE a|b]"
+/// becomes "bridge-a-b". Only characters that are valid unescaped in an
+/// IRI survive.
+fn slug(label: &str, separator: char) -> String {
+    let mut slug = String::new();
+    for c in label.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with(separator) {
+            slug.push(separator);
+        }
+    }
+    while slug.ends_with(separator) {
+        slug.pop();
+    }
+    if slug.is_empty() {
+        slug.push_str("node");
+    }
+    slug
+}
+
+/// A quoted Turtle string literal.
+fn literal(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04X}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// An `xsd:double` literal that reads back to the same value.
+fn double(value: f64) -> String {
+    let lexical = if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "INF" } else { "-INF" }.to_string()
+    } else {
+        format!("{value:?}")
+    };
+    format!("\"{lexical}\"^^xsd:double")
+}
+
+/// An object of a triple. Subjects and predicates are plain strings.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Term {
+    /// An IRI, or a blank node as `_:label`.
+    Iri(String),
+    /// A literal's lexical value; datatype and language are dropped.
+    Literal(String),
+}
+
+/// A reader for the Turtle subset [`import_turtle`] supports: everything
+/// but anonymous blank nodes (`[ … ]`) and collections (`( … )`).
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    prefixes: HashMap<String, String>,
+    base: String,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+            line: 1,
+            prefixes: HashMap::new(),
+            base: String::new(),
+        }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, TurtleError> {
+        Err(TurtleError {
+            line: self.line,
+            message: message.into(),
+        })
+    }
+
+    fn parse(mut self) -> Result<Vec<(String, String, Term)>, TurtleError> {
+        let mut triples = Vec::new();
+        while self.skip_space() {
+            if self.peek_is('@') {
+                self.directive()?;
+                continue;
+            }
+            let subject = match self.term()? {
+                Term::Iri(iri) => iri,
+                Term::Literal(_) => return self.error("a literal cannot be a subject"),
+            };
+            loop {
+                let predicate = match self.term()? {
+                    Term::Iri(iri) => iri,
+                    Term::Literal(_) => return self.error("a literal cannot be a predicate"),
+                };
+                loop {
+                    let object = self.term()?;
+                    triples.push((subject.clone(), predicate.clone(), object));
+                    if !self.eat(',') {
+                        break;
+                    }
+                }
+                if !self.eat(';') {
+                    break;
+                }
+                // A trailing `;` before the final `.` is allowed
+                self.skip_space();
+                if self.peek_is('.') {
+                    break;
+                }
+            }
+            if !self.eat('.') {
+                return self.error("expected '.' at the end of a statement");
+            }
+        }
+        Ok(triples)
+    }
+
+    /// `@prefix p: <iri> .` or `@base <iri> .`
+    fn directive(&mut self) -> Result<(), TurtleError> {
+        self.chars.next();
+        let keyword = self.word();
+        self.skip_space();
+        match keyword.as_str() {
+            "prefix" => {
+                let name = self.word();
+                let Some(prefix) = name.strip_suffix(':') else {
+                    return self.error(format!("bad prefix name '{name}'"));
+                };
+                self.skip_space();
+                let iri = self.iri()?;
+                self.prefixes.insert(prefix.to_string(), iri);
+            }
+            "base" => self.base = self.iri()?,
+            other => return self.error(format!("unknown directive '@{other}'")),
+        }
+        if !self.eat('.') {
+            return self.error("expected '.' after a directive");
+        }
+        Ok(())
+    }
+
+    fn term(&mut self) -> Result<Term, TurtleError> {
+        if !self.skip_space() {
+            return self.error("unexpected end of input");
+        }
+        match self.chars.peek() {
+            Some('<') => Ok(Term::Iri(self.iri()?)),
+            Some('"') | Some('\'') => {
+                let value = self.string()?;
+                if self.peek_is('@') {
+                    self.chars.next();
+                    self.word();
+                } else if self.peek_is('^') {
+                    self.chars.next();
+                    if !self.eat('^') {
+                        return self.error("expected '^^' before a datatype");
+                    }
+                    self.term()?;
+                }
+                Ok(Term::Literal(value))
+            }
+            Some('[') | Some('(') => {
+                self.error("blank node syntax and collections are not supported")
+            }
+            _ => {
+                let word = self.word();
+                if word.is_empty() {
+                    return self.error("expected a term");
+                }
+                if word == "a" {
+                    return Ok(Term::Iri(RDF_TYPE.to_string()));
+                }
+                if word.starts_with("_:") {
+                    return Ok(Term::Iri(word));
+                }
+                if word == "true"
+                    || word == "false"
+                    || word.starts_with(|c: char| {
+                        c.is_ascii_digit() || c == '-' || c == '+' || c == '.'
+                    })
+                {
+                    return Ok(Term::Literal(word));
+                }
+                match word.split_once(':') {
+                    Some((prefix, local)) => match self.prefixes.get(prefix) {
+                        Some(ns) => Ok(Term::Iri(format!("{ns}{}", local.replace('\\', "")))),
+                        None => self.error(format!("undeclared prefix '{prefix}:'")),
+                    },
+                    None => self.error(format!("unexpected '{word}'")),
+                }
+            }
+        }
+    }
+
+    /// `<…>`, resolved against `@base` if it has no scheme.
+    fn iri(&mut self) -> Result<String, TurtleError> {
+        if !self.eat('<') {
+            return self.error("expected '<'");
+        }
+        let mut iri = String::new();
+        loop {
+            match self.chars.next() {
+                Some('>') => break,
+                Some('\\') => iri.push(self.unicode_escape()?),
+                Some('\n') | None => return self.error("unterminated IRI"),
+                Some(c) => iri.push(c),
+            }
+        }
+        if !iri.contains(':') {
+            iri.insert_str(0, &self.base);
+        }
+        Ok(iri)
+    }
+
+    /// A short or long string in single or double quotes.
+    fn string(&mut self) -> Result<String, TurtleError> {
+        let quote = self.chars.next().unwrap_or('"');
+        let mut long = false;
+        if self.peek_is(quote) {
+            self.chars.next();
+            if !self.peek_is(quote) {
+                // The empty string
+                return Ok(String::new());
+            }
+            self.chars.next();
+            long = true;
+        }
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                None => return self.error("unterminated string"),
+                Some(c) if c == quote => {
+                    if !long {
+                        return Ok(value);
+                    }
+                    if self.peek_is(quote) {
+                        self.chars.next();
+                        if self.peek_is(quote) {
+                            self.chars.next();
+                            return Ok(value);
+                        }
+                        value.push(quote);
+                    }
+                    value.push(quote);
+                }
+                Some('\\') => match self.chars.peek() {
+                    Some('u') | Some('U') => value.push(self.unicode_escape()?),
+                    _ => {
+                        let escaped = match self.chars.next() {
+                            Some('n') => '\n',
+                            Some('r') => '\r',
+                            Some('t') => '\t',
+                            Some('b') => '\u{8}',
+                            Some('f') => '\u{c}',
+                            Some(c @ ('"' | '\'' | '\\')) => c,
+                            _ => return self.error("bad escape in string"),
+                        };
+                        value.push(escaped);
+                    }
+                },
+                Some('\n') if !long => return self.error("line break in a short string"),
+                Some(c) => {
+                    if c == '\n' {
+                        self.line += 1;
+                    }
+                    value.push(c);
+                }
+            }
+        }
+    }
+
+    /// The rest of a `\uXXXX` or `\UXXXXXXXX` escape, after the backslash.
+    fn unicode_escape(&mut self) -> Result<char, TurtleError> {
+        let digits = match self.chars.next() {
+            Some('u') => 4,
+            Some('U') => 8,
+            _ => return self.error("bad escape"),
+        };
+        let hex: String = (0..digits).filter_map(|_| self.chars.next()).collect();
+        match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+            Some(c) => Ok(c),
+            None => self.error(format!("bad unicode escape '{hex}'")),
+        }
+    }
+
+    /// A bare token: a prefixed name, keyword or number. A final `.` is
+    /// left for the statement.
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace()
+                || matches!(
+                    c,
+                    ';' | ',' | '<' | '"' | '#' | '^' | '@' | '[' | ']' | '(' | ')'
+                )
+            {
+                break;
+            }
+            if c == '.' {
+                let mut ahead = self.chars.clone();
+                ahead.next();
+                if !ahead
+                    .peek()
+                    .is_some_and(|n| n.is_alphanumeric() || matches!(n, '_' | '-' | ':' | '.'))
+                {
+                    break;
+                }
+            }
+            word.push(c);
+            self.chars.next();
+        }
+        word
+    }
+
+    /// Skip whitespace and comments; false at the end of input.
+    fn skip_space(&mut self) -> bool {
+        while let Some(&c) = self.chars.peek() {
+            if c == '#' {
+                while self.chars.next_if(|&c| c != '\n').is_some() {}
+            } else if c.is_whitespace() {
+                if c == '\n' {
+                    self.line += 1;
+                }
+                self.chars.next();
+            } else {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn peek_is(&mut self, c: char) -> bool {
+        self.chars.peek() == Some(&c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_space();
+        self.chars.next_if_eq(&c).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(colony: &mut Colony, label: &str, node_type: NodeType) -> NodeId {
+        colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type,
+            position: Position::new(0.0, 0.0),
+            access_count: 3,
+            created_tick: 1,
+            last_accessed_tick: 2,
+            tags: Default::default(),
+        })
+    }
+
+    fn connect(colony: &mut Colony, a: NodeId, b: NodeId, weight: f64) {
+        colony
+            .substrate_mut()
+            .set_edge(
+                a,
+                b,
+                EdgeData {
+                    weight,
+                    co_activations: 4,
+                    created_tick: 1,
+                    last_activated_tick: 5,
+                },
+            )
+            .unwrap();
+    }
+
+    fn weights(colony: &Colony) -> Vec<(String, String, f64)> {
+        let graph = colony.substrate().graph();
+        let label = |id: &NodeId| graph.get_node(id).unwrap().label.clone();
+        let mut weights: Vec<(String, String, f64)> = graph
+            .all_edges()
+            .into_iter()
+            .map(|(a, b, e)| {
+                let (a, b) = (label(&a), label(&b));
+                if a < b {
+                    (a, b, e.weight)
+                } else {
+                    (b, a, e.weight)
+                }
+            })
+            .collect();
+        weights.sort_by(|x, y| (&x.0, &x.1).cmp(&(&y.0, &y.1)));
+        weights
+    }
+
+    #[test]
+    fn slugs_keep_only_iri_safe_characters() {
+        assert_eq!(slug("[BRIDGE a|b]", '-'), "bridge-a-b");
+        assert_eq!(slug("Cell  Membrane", '-'), "cell-membrane");
+        assert_eq!(slug("<>{}", '-'), "node");
+        assert_eq!(literal("say \"hi\"\\\n"), r#""say \"hi\"\\\n""#);
+        assert_eq!(double(0.1), "\"0.1\"^^xsd:double");
+    }
+
+    #[test]
+    fn turtle_round_trips_nodes_and_weights() {
+        let mut colony = Colony::new();
+        let cell = add(&mut colony, "cell membrane", NodeType::Concept);
+        let dash = add(&mut colony, "cell-membrane", NodeType::Concept);
+        let quoted = add(&mut colony, "the \"lipid\"\tbilayer", NodeType::Concept);
+        let bridge = add(&mut colony, "[BRIDGE a|b]", NodeType::Insight);
+        let odd = add(&mut colony, "odd reading", NodeType::Anomaly);
+        connect(&mut colony, cell, dash, 0.123456789);
+        connect(&mut colony, cell, quoted, 1.0 / 3.0);
+        connect(&mut colony, bridge, quoted, 0.9);
+        connect(&mut colony, odd, cell, 0.05);
+        colony
+            .substrate_mut()
+            .graph_mut()
+            .add_alias(&cell, "plasma membrane");
+
+        let turtle = export_turtle(&colony, RdfConfig::default());
+        assert!(turtle.contains("<https://phago.dev/graph/concept/cell-membrane> a skos:Concept"));
+        assert!(turtle.contains("<https://phago.dev/graph/concept/cell-membrane-2>"));
+        assert!(turtle.contains("<https://phago.dev/graph/insight/bridge-a-b> a phago:Insight"));
+        assert!(turtle.contains(r#"skos:prefLabel "the \"lipid\"\tbilayer""#));
+        assert!(turtle.contains(r#"skos:altLabel "plasma membrane""#));
+        for iri in turtle
+            .split('<')
+            .skip(1)
+            .map(|s| &s[..s.find('>').unwrap()])
+        {
+            assert!(!iri.contains([' ', '[', ']', '|', '"', '{', '}']), "{iri}");
+        }
+
+        let mut copy = Colony::new();
+        let report = import_turtle(&mut copy, &turtle).unwrap();
+        assert_eq!(report.nodes_added, 5);
+        assert_eq!(report.edges, 4);
+        assert_eq!(report.ignored, 0);
+        assert_eq!(copy.stats().graph_nodes, colony.stats().graph_nodes);
+        assert_eq!(copy.stats().graph_edges, colony.stats().graph_edges);
+        let restored = copy.substrate().graph();
+        let membrane = restored.find_nodes_by_exact_label("plasma membrane");
+        assert_eq!(
+            restored.get_node(&membrane[0]).unwrap().label,
+            "cell membrane"
+        );
+        for (original, restored) in weights(&colony).iter().zip(weights(&copy)) {
+            assert_eq!((&original.0, &original.1), (&restored.0, &restored.1));
+            assert!((original.2 - restored.2).abs() < 1e-12);
+        }
+
+        // Importing again matches every node instead of duplicating it
+        let again = import_turtle(&mut copy, &turtle).unwrap();
+        assert_eq!(again.nodes_matched, 5);
+        assert_eq!(copy.stats().graph_nodes, 5);
+    }
+
+    #[test]
+    fn import_skips_unknown_predicates() {
+        let turtle = r#"
+            @prefix ex: <http://example.org/> .
+            @prefix skos: <http://www.w3.org/2004/02/skos/core#> .
+            @prefix phago: <https://phago.dev/ns#> .
+            # A concept from elsewhere
+            ex:a a skos:Concept ; skos:prefLabel "alpha"@en ; ex:color "red" .
+            ex:b skos:prefLabel """beta
+            gamma""" ;
+                phago:related_to ex:a .
+        "#;
+        let mut colony = Colony::new();
+        let report = import_turtle(&mut colony, turtle).unwrap();
+        assert_eq!(report.nodes_added, 2);
+        assert_eq!(report.edges, 1);
+        assert_eq!(report.ignored, 1);
+        let graph = colony.substrate().graph();
+        assert_eq!(graph.find_nodes_by_exact_label("alpha").len(), 1);
+
+        let err = import_turtle(&mut Colony::new(), "ex:a ex:b ex:c .").unwrap_err();
+        assert_eq!(err.line, 1);
+    }
+}
@@ -0,0 +1,331 @@
+//! Read views — immutable copies of a colony for queries that should not
+//! wait for ticks.
+//!
+//! A [`ColonyView`] holds the graph, statistics and agents as of one
+//! moment between ticks. It is read without the colony, so any number of
+//! queries can run on it at once while the colony keeps ticking; they see
+//! the state at [`tick`](ColonyView::tick), never anything later.
+//!
+//! Copying the graph is what a view costs. [`ColonyView::refresh`] copies
+//! it only when the graph changed ([`Colony::graph_revision`]) and shares
+//! it with the previous view otherwise; statistics and agents are always
+//! taken fresh. How often a runner refreshes its view is a
+//! [`ViewRefresh`] policy.
+
+use crate::colony::{graph_snapshot, AgentSnapshot, Colony, ColonySnapshot, ColonyStats};
+use crate::community::{CommunityAlgorithm, CommunityTracker};
+use crate::graph_algorithms::{self, ConceptPath, PathCost, PathError};
+use crate::publish::PublishManifest;
+use crate::region::Region;
+use crate::sampling::{self, Sample, SampleConfig};
+use crate::topology_impl::PetTopologyGraph;
+use phago_core::substrate::Substrate;
+use phago_core::topology::TopologyGraph;
+use phago_core::types::{NodeId, Rect, Tick};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often a runner refreshes its read view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewRefresh {
+    /// After every tick and every request served between ticks.
+    EveryTick,
+    /// At most once per interval, so the view is never older than about
+    /// the interval plus one tick.
+    Every(Duration),
+}
+
+/// An immutable copy of a colony, taken between ticks.
+#[derive(Clone)]
+pub struct ColonyView {
+    tick: Tick,
+    revision: u64,
+    taken_at: Instant,
+    graph: Arc<PetTopologyGraph>,
+    communities: Arc<CommunityTracker>,
+    community_algorithm: CommunityAlgorithm,
+    anomaly_scores: HashMap<NodeId, f64>,
+    stats: ColonyStats,
+    agents: Vec<AgentSnapshot>,
+    world_bounds: Option<Rect>,
+    regions: Vec<Region>,
+}
+
+impl ColonyView {
+    /// Copy `colony` as it is now.
+    pub fn capture(colony: &Colony) -> Self {
+        Self::with_graph(colony, Arc::new(copy_graph(colony.substrate().graph())))
+    }
+
+    /// A view of `colony` as it is now, sharing this view's graph if it
+    /// has not changed since.
+    pub fn refresh(&self, colony: &Colony) -> Self {
+        if colony.graph_revision() == self.revision {
+            Self::with_graph(colony, Arc::clone(&self.graph))
+        } else {
+            Self::capture(colony)
+        }
+    }
+
+    fn with_graph(colony: &Colony, graph: Arc<PetTopologyGraph>) -> Self {
+        Self {
+            tick: colony.substrate().current_tick(),
+            revision: colony.graph_revision(),
+            taken_at: Instant::now(),
+            graph,
+            communities: colony.shared_community_tracker(),
+            community_algorithm: colony.community_algorithm(),
+            anomaly_scores: colony.anomalies().into_iter().collect(),
+            stats: colony.stats(),
+            agents: colony.agent_snapshots(),
+            world_bounds: colony.substrate().geometry().bounds,
+            regions: colony.substrate().regions().to_vec(),
+        }
+    }
+
+    /// A view of a published graph (see [`crate::publish`]), as the
+    /// colony was when it was published: no agents, and communities
+    /// numbered afresh.
+    pub(crate) fn published(
+        manifest: &PublishManifest,
+        graph: PetTopologyGraph,
+        anomaly_scores: HashMap<NodeId, f64>,
+    ) -> Self {
+        Self {
+            tick: manifest.tick,
+            revision: manifest.revision,
+            taken_at: Instant::now(),
+            graph: Arc::new(graph),
+            communities: Arc::new(CommunityTracker::new()),
+            community_algorithm: manifest.community_algorithm,
+            anomaly_scores,
+            stats: manifest.stats.clone(),
+            agents: Vec::new(),
+            world_bounds: manifest.world_bounds,
+            regions: manifest.regions.clone(),
+        }
+    }
+
+    /// The tick the view was taken at; no later tick is reflected in it.
+    pub fn tick(&self) -> Tick {
+        self.tick
+    }
+
+    /// The colony's [`graph_revision`](Colony::graph_revision) when the
+    /// view was taken.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Time since the view was taken.
+    pub fn age(&self) -> Duration {
+        self.taken_at.elapsed()
+    }
+
+    pub fn graph(&self) -> &dyn TopologyGraph {
+        self.graph.as_ref()
+    }
+
+    pub fn stats(&self) -> &ColonyStats {
+        &self.stats
+    }
+
+    pub fn agents(&self) -> &[AgentSnapshot] {
+        &self.agents
+    }
+
+    /// Named regions of the field when the view was taken.
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Community IDs as the colony tracked them when the view was taken.
+    pub fn community_tracker(&self) -> &CommunityTracker {
+        &self.communities
+    }
+
+    /// The colony's configured community detection algorithm.
+    pub fn community_algorithm(&self) -> CommunityAlgorithm {
+        self.community_algorithm
+    }
+
+    /// The novelty score of `node`, as [`Colony::anomaly_score`] gave it.
+    pub fn anomaly_score(&self, node: &NodeId) -> Option<f64> {
+        self.anomaly_scores.get(node).copied()
+    }
+
+    /// Scored anomaly nodes as [`Colony::anomalies`] listed them, the
+    /// highest score first.
+    pub fn anomalies(&self) -> Vec<(NodeId, f64)> {
+        let mut anomalies: Vec<(NodeId, f64)> = self
+            .anomaly_scores
+            .iter()
+            .map(|(id, s)| (*id, *s))
+            .collect();
+        anomalies.sort_by(|a, b| b.1.total_cmp(&a.1));
+        anomalies
+    }
+
+    /// The snapshot [`Colony::snapshot`] would have returned at the view's
+    /// tick.
+    pub fn snapshot(&self) -> ColonySnapshot {
+        self.snapshot_of(None)
+    }
+
+    /// The snapshot [`Colony::sample_snapshot`] would have returned at the
+    /// view's tick. Views hold no embeddings, so
+    /// [`with_embeddings`](SampleConfig::with_embeddings) is ignored.
+    pub fn sample_snapshot(&self, config: &SampleConfig) -> ColonySnapshot {
+        self.snapshot_of(Some(&sampling::sample(self.graph(), &config.strategy)))
+    }
+
+    fn snapshot_of(&self, sample: Option<&Sample>) -> ColonySnapshot {
+        let (nodes, edges) = graph_snapshot(
+            self.graph(),
+            &self.communities,
+            &self.anomaly_scores,
+            None,
+            sample,
+        );
+        let coverage = sample.map(|s| s.coverage(self.graph()));
+        ColonySnapshot {
+            tick: self.tick,
+            agents: self.agents.clone(),
+            nodes,
+            edges,
+            stats: self.stats.clone(),
+            world_bounds: self.world_bounds,
+            regions: self.regions.clone(),
+            coverage,
+        }
+    }
+
+    /// Up to `k` cheapest paths between two concepts, as
+    /// [`Colony::find_path`].
+    pub fn find_path(
+        &self,
+        from_label: &str,
+        to_label: &str,
+        cost: PathCost,
+        k: usize,
+    ) -> Result<Vec<ConceptPath>, PathError> {
+        graph_algorithms::find_path(self.graph(), from_label, to_label, cost, k)
+    }
+}
+
+impl std::fmt::Debug for ColonyView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColonyView")
+            .field("tick", &self.tick)
+            .field("revision", &self.revision)
+            .field("nodes", &self.graph.node_count())
+            .field("edges", &self.graph.edge_count())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Every node and edge of `graph`, in memory.
+fn copy_graph(graph: &dyn TopologyGraph) -> PetTopologyGraph {
+    let mut copy = PetTopologyGraph::new();
+    for id in graph.all_nodes() {
+        if let Some(node) = graph.get_node(&id) {
+            copy.add_node(node.clone());
+        }
+    }
+    for (from, to, edge) in graph.all_edges() {
+        let _ = copy.set_edge(from, to, edge.clone());
+    }
+    copy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_agents::digester::Digester;
+    use phago_core::types::Position;
+
+    fn digesting_colony() -> Colony {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Biology",
+            "The cell membrane regulates protein transport into the cell.",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        colony
+    }
+
+    /// Labels and edge weights, in a stable order.
+    fn contents(snapshot: &ColonySnapshot) -> (Vec<String>, Vec<(String, String, f64)>) {
+        let mut labels: Vec<String> = snapshot.nodes.iter().map(|n| n.label.clone()).collect();
+        labels.sort();
+        let mut edges: Vec<(String, String, f64)> = snapshot
+            .edges
+            .iter()
+            .map(|e| (e.from_label.clone(), e.to_label.clone(), e.weight))
+            .collect();
+        edges.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        (labels, edges)
+    }
+
+    #[test]
+    fn view_never_reflects_later_ticks() {
+        let mut colony = digesting_colony();
+        colony.run(15);
+        let view = colony.read_view();
+        let taken = colony.snapshot();
+        assert_eq!(view.tick(), taken.tick);
+        assert!(!taken.nodes.is_empty());
+
+        // The next tick changes the graph under the view
+        colony.ingest_document(
+            "Astronomy",
+            "A nebula is a cloud where stellar fusion ignites new stars.",
+            Position::new(0.0, 0.0),
+        );
+        let revision = colony.graph_revision();
+        while colony.graph_revision() == revision {
+            colony.tick();
+        }
+        assert_ne!(contents(&colony.snapshot()), contents(&taken));
+
+        let seen = view.snapshot();
+        assert_eq!(seen.tick, taken.tick);
+        assert_eq!(seen.stats, taken.stats);
+        assert_eq!(contents(&seen), contents(&taken));
+        assert_ne!(view.revision(), colony.graph_revision());
+    }
+
+    #[test]
+    fn refresh_shares_an_unchanged_graph() {
+        let mut colony = digesting_colony();
+        colony.run(5);
+        let view = colony.read_view();
+
+        let same = view.refresh(&colony);
+        assert!(Arc::ptr_eq(&view.graph, &same.graph));
+
+        colony.run(10);
+        let later = view.refresh(&colony);
+        assert_eq!(later.tick(), colony.stats().tick);
+        assert_eq!(later.stats().graph_nodes, colony.stats().graph_nodes);
+        if later.revision() != view.revision() {
+            assert!(!Arc::ptr_eq(&view.graph, &later.graph));
+        }
+    }
+
+    #[test]
+    fn view_answers_path_queries() {
+        let mut colony = digesting_colony();
+        colony.run(15);
+        let view = colony.read_view();
+        assert_eq!(
+            view.find_path("cell", "membrane", PathCost::default(), 1)
+                .map(|p| p.len()),
+            colony
+                .find_path("cell", "membrane", PathCost::default(), 1)
+                .map(|p| p.len())
+        );
+    }
+}
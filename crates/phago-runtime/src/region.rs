@@ -0,0 +1,256 @@
+//! Substrate regions — named zones of the spatial field with their own
+//! decay and access policies.
+//!
+//! A colony modelling working memory next to long-term memory can lay the
+//! field out spatially: documents ingested into a hot zone decay quickly
+//! unless their concepts are promoted, while an archive zone keeps what
+//! reaches it. A [`Region`] names a [`RegionShape`] and a [`RegionPolicy`]
+//! and is defined with [`SubstrateImpl::define_region`] (or
+//! [`Colony::define_region`], which a replay records).
+//!
+//! Decay looks up the region of what it decays, falling back to the
+//! global rates outside every region:
+//!
+//! - A signal decays by its position, a spatial trace by where it was
+//!   deposited and a trace on a node by the node's position.
+//! - An edge decays at the slower of its endpoints' rates, so an edge to a
+//!   promoted concept is kept as the concept is. Edges have no position of
+//!   their own: moving a node with [`Colony::promote_node`] moves its
+//!   edges with it.
+//!
+//! Where regions overlap, the first one defined applies to decay. Access
+//! is refused by any region that refuses it: agents cannot move into a
+//! region closed to them, and documents lying in a region that does not
+//! allow ingest are stored but never digested. Both are reported as
+//! [`ColonyEvent::ActionFailed`] by the tick that refused them.
+//!
+//! [`SubstrateImpl::define_region`]: crate::substrate_impl::SubstrateImpl::define_region
+//! [`Colony::define_region`]: crate::colony::Colony::define_region
+//! [`Colony::promote_node`]: crate::colony::Colony::promote_node
+//! [`ColonyEvent::ActionFailed`]: crate::colony::ColonyEvent::ActionFailed
+
+use phago_core::types::{Position, Rect};
+use serde::{Deserialize, Serialize};
+
+/// The part of the field a region covers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionShape {
+    Rect(Rect),
+    Circle { center: Position, radius: f64 },
+}
+
+impl RegionShape {
+    /// Whether `position` lies inside or on the edge.
+    pub fn contains(&self, position: &Position) -> bool {
+        match self {
+            RegionShape::Rect(rect) => rect.contains(position),
+            RegionShape::Circle { center, radius } => center.distance_to(position) <= *radius,
+        }
+    }
+
+    /// The middle of the shape, where promoted nodes are put.
+    pub fn center(&self) -> Position {
+        match self {
+            RegionShape::Rect(rect) => Position::new(
+                (rect.min.x + rect.max.x) / 2.0,
+                (rect.min.y + rect.max.y) / 2.0,
+            ),
+            RegionShape::Circle { center, .. } => *center,
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        match self {
+            RegionShape::Rect(rect) => {
+                rect.width().is_finite()
+                    && rect.height().is_finite()
+                    && rect.width() > 0.0
+                    && rect.height() > 0.0
+            }
+            RegionShape::Circle { center, radius } => {
+                center.x.is_finite() && center.y.is_finite() && radius.is_finite() && *radius > 0.0
+            }
+        }
+    }
+}
+
+/// How a region decays what lies in it, and what it lets in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RegionPolicy {
+    /// Factor on the edge decay rate (default: 1.0).
+    #[serde(default = "default_multiplier")]
+    pub edge_decay_multiplier: f64,
+    /// Factor on the trace decay rate (default: 1.0).
+    #[serde(default = "default_multiplier")]
+    pub trace_decay_multiplier: f64,
+    /// Factor on the decay rate of every signal kind (default: 1.0).
+    #[serde(default = "default_multiplier")]
+    pub signal_decay_multiplier: f64,
+    /// Whether documents lying in the region are digested (default: true).
+    #[serde(default = "default_allowed")]
+    pub allow_ingest: bool,
+    /// Whether agents may move into the region (default: true). Agents
+    /// spawned inside may stay and leave.
+    #[serde(default = "default_allowed")]
+    pub allow_agent_entry: bool,
+}
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+fn default_allowed() -> bool {
+    true
+}
+
+impl Default for RegionPolicy {
+    fn default() -> Self {
+        Self {
+            edge_decay_multiplier: 1.0,
+            trace_decay_multiplier: 1.0,
+            signal_decay_multiplier: 1.0,
+            allow_ingest: true,
+            allow_agent_entry: true,
+        }
+    }
+}
+
+impl RegionPolicy {
+    /// Decay every kind of state `multiplier` times as fast.
+    pub fn decaying(multiplier: f64) -> Self {
+        Self {
+            edge_decay_multiplier: multiplier,
+            trace_decay_multiplier: multiplier,
+            signal_decay_multiplier: multiplier,
+            ..Self::default()
+        }
+    }
+
+    fn multipliers(&self) -> [f64; 3] {
+        [
+            self.edge_decay_multiplier,
+            self.trace_decay_multiplier,
+            self.signal_decay_multiplier,
+        ]
+    }
+}
+
+/// A named zone of the spatial field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Region {
+    pub name: String,
+    pub shape: RegionShape,
+    #[serde(default)]
+    pub policy: RegionPolicy,
+}
+
+impl Region {
+    /// A region of `shape` named `name`, checking that the shape and the
+    /// policy's multipliers make sense.
+    pub fn new(
+        name: impl Into<String>,
+        shape: RegionShape,
+        policy: RegionPolicy,
+    ) -> Result<Self, RegionError> {
+        let name = name.into();
+        if name.trim().is_empty() {
+            return Err(RegionError::EmptyName);
+        }
+        if !shape.is_valid() {
+            return Err(RegionError::InvalidShape(name));
+        }
+        if let Some(&m) = policy
+            .multipliers()
+            .iter()
+            .find(|m| !(m.is_finite() && **m >= 0.0))
+        {
+            return Err(RegionError::InvalidMultiplier(m));
+        }
+        Ok(Self {
+            name,
+            shape,
+            policy,
+        })
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        self.shape.contains(position)
+    }
+}
+
+/// Why a region could not be defined or a node not promoted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegionError {
+    /// The region name is empty or whitespace only.
+    EmptyName,
+    /// The shape has non-finite coordinates or no area.
+    InvalidShape(String),
+    /// A decay multiplier is negative or not finite.
+    InvalidMultiplier(f64),
+    /// No region has the name.
+    UnknownRegion(String),
+    /// No node has the label.
+    UnknownConcept(String),
+}
+
+impl std::fmt::Display for RegionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegionError::EmptyName => write!(f, "Region name must not be empty"),
+            RegionError::InvalidShape(name) => {
+                write!(f, "Region '{}' has an invalid shape", name)
+            }
+            RegionError::InvalidMultiplier(m) => {
+                write!(f, "Decay multipliers must be at least 0, got {}", m)
+            }
+            RegionError::UnknownRegion(name) => write!(f, "No region is named '{}'", name),
+            RegionError::UnknownConcept(label) => {
+                write!(f, "No concept matches '{}'", label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_contain_their_edges() {
+        let rect = RegionShape::Rect(Rect::new(Position::new(10.0, 0.0), Position::new(0.0, 5.0)));
+        assert!(rect.contains(&Position::new(10.0, 5.0)));
+        assert!(!rect.contains(&Position::new(10.1, 5.0)));
+        assert_eq!(rect.center(), Position::new(5.0, 2.5));
+
+        let circle = RegionShape::Circle {
+            center: Position::new(1.0, 1.0),
+            radius: 2.0,
+        };
+        assert!(circle.contains(&Position::new(3.0, 1.0)));
+        assert!(!circle.contains(&Position::new(3.0, 2.0)));
+    }
+
+    #[test]
+    fn invalid_regions_are_refused() {
+        let circle = |radius| RegionShape::Circle {
+            center: Position::new(0.0, 0.0),
+            radius,
+        };
+        assert!(Region::new("hot", circle(1.0), RegionPolicy::decaying(4.0)).is_ok());
+        assert_eq!(
+            Region::new(" ", circle(1.0), RegionPolicy::default()),
+            Err(RegionError::EmptyName)
+        );
+        assert_eq!(
+            Region::new("hot", circle(0.0), RegionPolicy::default()),
+            Err(RegionError::InvalidShape("hot".to_string()))
+        );
+        assert_eq!(
+            Region::new("hot", circle(1.0), RegionPolicy::decaying(-1.0)),
+            Err(RegionError::InvalidMultiplier(-1.0))
+        );
+    }
+}
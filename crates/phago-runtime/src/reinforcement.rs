@@ -0,0 +1,235 @@
+//! Hebbian reinforcement rules for co-activation edges.
+//!
+//! Every place that strengthens an existing edge — co-occurrence wiring,
+//! agent-requested wiring and query-time reinforcement — goes through
+//! [`apply_reinforcement`], so the rule selected in
+//! [`ColonyConfig::weight_update`](crate::colony::ColonyConfig::weight_update)
+//! is applied consistently.
+//!
+//! The default capped-additive rule saturates: after enough co-activations
+//! every frequent edge sits at 1.0 and weight no longer ranks them. The
+//! asymptotic rule approaches 1.0 without reaching it, and the normalized
+//! rule makes edges of the same node compete for a fixed weight budget.
+
+use phago_core::topology::TopologyGraph;
+use phago_core::types::{EdgeData, NodeId, Tick};
+use serde::{Deserialize, Serialize};
+
+/// How reinforcing an existing edge changes its weight.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum WeightUpdate {
+    /// `weight = min(weight + amount, 1.0)`.
+    #[default]
+    CappedAdditive,
+    /// Close a fraction `rate * amount` of the remaining distance to 1.0:
+    /// `weight += rate * amount * (1 - weight)`. Never reaches 1.0, so edges
+    /// with more co-activations keep ranking higher.
+    Asymptotic { rate: f64 },
+    /// Capped-additive on the reinforced edge, after which each endpoint whose
+    /// total edge weight exceeds `per_node_budget` scales its other edges
+    /// down by up to the amount just added (synaptic competition).
+    Normalized { per_node_budget: f64 },
+}
+
+/// Reinforce the existing edge between `from` and `to` by `amount`
+/// according to `rule`, counting a co-activation at `tick`.
+///
+/// Returns `false` (and changes nothing) if the edge does not exist.
+pub fn apply_reinforcement(
+    graph: &mut dyn TopologyGraph,
+    from: &NodeId,
+    to: &NodeId,
+    amount: f64,
+    tick: Tick,
+    rule: &WeightUpdate,
+) -> bool {
+    let Some(edge) = graph.get_edge_mut(from, to) else {
+        return false;
+    };
+    let added = reinforce_edge(edge, amount, tick, rule);
+
+    if let WeightUpdate::Normalized { per_node_budget } = *rule {
+        if added > 0.0 {
+            compete(graph, from, to, added, per_node_budget);
+            compete(graph, to, from, added, per_node_budget);
+        }
+    }
+    true
+}
+
+/// Reinforce `edge` by `amount` according to `rule`, counting a
+/// co-activation at `tick`, and return the weight added.
+///
+/// Only the edge itself changes: the competition between sibling edges
+/// under [`WeightUpdate::Normalized`] is left to [`apply_reinforcement`].
+pub fn reinforce_edge(edge: &mut EdgeData, amount: f64, tick: Tick, rule: &WeightUpdate) -> f64 {
+    let before = edge.weight;
+    edge.weight = match *rule {
+        WeightUpdate::CappedAdditive | WeightUpdate::Normalized { .. } => {
+            (before + amount).min(1.0)
+        }
+        WeightUpdate::Asymptotic { rate } => {
+            before + (rate * amount).clamp(0.0, 1.0) * (1.0 - before)
+        }
+    };
+    edge.co_activations += 1;
+    edge.last_activated_tick = tick;
+    edge.weight - before
+}
+
+/// Scale down `node`'s edges other than the one to `winner` so its total
+/// weight moves back toward `budget`, removing at most `added`.
+fn compete(graph: &mut dyn TopologyGraph, node: &NodeId, winner: &NodeId, added: f64, budget: f64) {
+    let neighbors = graph.neighbors(node);
+    let total: f64 = neighbors.iter().map(|(_, e)| e.weight).sum();
+    let excess = total - budget;
+    if excess <= 0.0 {
+        return;
+    }
+    let siblings: Vec<NodeId> = neighbors
+        .iter()
+        .filter(|(id, _)| id != winner)
+        .map(|(id, _)| *id)
+        .collect();
+    let sibling_mass: f64 = neighbors
+        .iter()
+        .filter(|(id, _)| id != winner)
+        .map(|(_, e)| e.weight)
+        .sum();
+    if sibling_mass <= 0.0 {
+        return;
+    }
+
+    let factor = 1.0 - (excess.min(added) / sibling_mass).min(1.0);
+    for sibling in siblings {
+        if let Some(edge) = graph.get_edge_mut(node, &sibling) {
+            edge.weight *= factor;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology_impl::PetTopologyGraph;
+    use phago_core::types::{EdgeData, NodeData, NodeType, Position};
+
+    fn add_node(graph: &mut PetTopologyGraph, label: &str) -> NodeId {
+        graph.add_node(NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        })
+    }
+
+    fn connect(graph: &mut PetTopologyGraph, a: NodeId, b: NodeId, weight: f64) {
+        graph
+            .set_edge(
+                a,
+                b,
+                EdgeData {
+                    weight,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    fn weight(graph: &PetTopologyGraph, a: &NodeId, b: &NodeId) -> f64 {
+        graph.get_edge(a, b).unwrap().weight
+    }
+
+    #[test]
+    fn missing_edges_are_not_created() {
+        let mut graph = PetTopologyGraph::new();
+        let a = add_node(&mut graph, "a");
+        let b = add_node(&mut graph, "b");
+        let rule = WeightUpdate::CappedAdditive;
+        assert!(!apply_reinforcement(&mut graph, &a, &b, 0.1, 1, &rule));
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    /// Reinforce a hub's edges 100 and 10 times under `rule`, returning
+    /// the two resulting weights and the busier edge's co-activation count.
+    fn reinforce_100_vs_10(rule: WeightUpdate) -> (f64, f64, u64) {
+        let mut graph = PetTopologyGraph::new();
+        let hub = add_node(&mut graph, "cell");
+        let common = add_node(&mut graph, "membrane");
+        let rare = add_node(&mut graph, "lipid");
+        connect(&mut graph, hub, common, 0.1);
+        connect(&mut graph, hub, rare, 0.1);
+
+        for tick in 0..100 {
+            apply_reinforcement(&mut graph, &hub, &common, 0.1, tick, &rule);
+            if tick < 10 {
+                apply_reinforcement(&mut graph, &hub, &rare, 0.1, tick, &rule);
+            }
+        }
+        (
+            weight(&graph, &hub, &common),
+            weight(&graph, &hub, &rare),
+            graph.get_edge(&hub, &common).unwrap().co_activations,
+        )
+    }
+
+    #[test]
+    fn asymptotic_keeps_frequent_edges_apart() {
+        // Capped-additive saturates both edges at 1.0
+        let (common, rare, _) = reinforce_100_vs_10(WeightUpdate::CappedAdditive);
+        assert_eq!(common, 1.0);
+        assert_eq!(rare, 1.0);
+
+        let (common, rare, co_activations) =
+            reinforce_100_vs_10(WeightUpdate::Asymptotic { rate: 1.0 });
+        assert!(common < 1.0);
+        assert!(common - rare > 0.2);
+        assert_eq!(co_activations, 101);
+    }
+
+    #[test]
+    fn normalized_weakens_siblings() {
+        let mut graph = PetTopologyGraph::new();
+        let hub = add_node(&mut graph, "cell");
+        let a = add_node(&mut graph, "membrane");
+        let b = add_node(&mut graph, "protein");
+        let c = add_node(&mut graph, "nucleus");
+        connect(&mut graph, hub, a, 0.5);
+        connect(&mut graph, hub, b, 0.5);
+        connect(&mut graph, hub, c, 0.5);
+
+        let rule = WeightUpdate::Normalized {
+            per_node_budget: 1.5,
+        };
+        apply_reinforcement(&mut graph, &hub, &a, 0.2, 1, &rule);
+
+        assert!((weight(&graph, &hub, &a) - 0.7).abs() < 1e-12);
+        assert!((weight(&graph, &hub, &b) - 0.4).abs() < 1e-12);
+        assert!((weight(&graph, &hub, &c) - 0.4).abs() < 1e-12);
+        let total: f64 = graph.neighbors(&hub).iter().map(|(_, e)| e.weight).sum();
+        assert!((total - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn normalized_leaves_nodes_under_budget_alone() {
+        let mut graph = PetTopologyGraph::new();
+        let hub = add_node(&mut graph, "cell");
+        let a = add_node(&mut graph, "membrane");
+        let b = add_node(&mut graph, "protein");
+        connect(&mut graph, hub, a, 0.2);
+        connect(&mut graph, hub, b, 0.2);
+
+        let rule = WeightUpdate::Normalized {
+            per_node_budget: 3.0,
+        };
+        apply_reinforcement(&mut graph, &hub, &a, 0.1, 1, &rule);
+        assert!((weight(&graph, &hub, &a) - 0.3).abs() < 1e-12);
+        assert!((weight(&graph, &hub, &b) - 0.2).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,797 @@
+//! Replay — recording what a colony was given, and running it again.
+//!
+//! With [`ColonyConfig::record_replay`] set, a colony records the minimal
+//! inputs each tick needs to be reproduced: the action every agent
+//! returned, along with the few other answers the runtime asked agents for
+//! (exported vocabulary, death signals, boundary permeability, ...), and
+//! the calls made between ticks — documents ingested or updated, expiries
+//! set, nodes touched and reinforced by queries, agents spawned or taken,
+//! regions defined and nodes promoted into them. The IDs the colony minted
+//! for new nodes and documents are recorded too, so a replay ends up with
+//! the same IDs and the recorded actions apply verbatim.
+//!
+//! A [`Replay`] rebuilds a colony from the recording. Agents are replaced
+//! by puppets answering what the originals answered, so no agent decision
+//! logic runs and the result does not depend on how (or whether) the
+//! original run was seeded. The substrate state after any recorded tick
+//! matches the original's.
+//!
+//! What is not recorded: changes made through
+//! [`Colony::substrate_mut`] or by calls not listed above (merging nodes,
+//! setting synonyms, restoring a session, ...), what plugins and custom
+//! action handlers did (the replayed colony has none), and deadlines that
+//! depend on wall-clock time, such as a tick budget or TTLs in seconds. A
+//! run relying on any of them replays only approximately.
+//!
+//! A recording is saved as JSONL: a header with the colony configuration,
+//! then one [`ReplayStep`] per line.
+
+use crate::backend::BackendConfig;
+use crate::colony::{Colony, ColonyConfig, DocumentExpiry};
+use crate::region::Region;
+use phago_core::agent::Agent;
+use phago_core::primitives::symbiose::AgentProfile;
+use phago_core::primitives::{
+    Apoptose, Digest, DigestionLimits, EmergeConfig, QuorumStatus, Sense,
+};
+use phago_core::signal::SignalKinds;
+use phago_core::substrate::Substrate;
+use phago_core::tokenize::Tokenizer;
+use phago_core::types::*;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// The replay format version this build writes.
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+type BoxedAgent = Box<dyn Agent<Input = String, Fragment = String, Presentation = Vec<String>>>;
+
+/// A call that changed the colony, in the order it was made.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayInput {
+    /// An agent was spawned.
+    Spawn {
+        agent: AgentId,
+        agent_type: String,
+        position: Position,
+    },
+    /// A live agent was taken out of the colony.
+    TakeAgent { agent: AgentId },
+    /// A new document was ingested.
+    Ingest {
+        title: String,
+        content: String,
+        position: Position,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+    },
+    /// The document with `title` was updated to new content.
+    Update {
+        title: String,
+        content: String,
+        position: Position,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+    },
+    /// A duplicate was counted towards the document it duplicates.
+    MergeDuplicate { original: DocumentId },
+    /// A document's expiry was set; an empty one clears it.
+    Expiry {
+        document: DocumentId,
+        expiry: DocumentExpiry,
+    },
+    /// Nodes were marked accessed, e.g. by a query.
+    Touch { nodes: Vec<NodeId> },
+    /// A query reinforced the nodes and edges it retrieved.
+    Reinforce {
+        nodes: Vec<(NodeId, u64)>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tags: Vec<String>,
+        edges: Vec<(NodeId, NodeId, f64)>,
+    },
+    /// A region was defined.
+    DefineRegion { region: Region },
+    /// The concept labelled `label` was promoted into `region`.
+    Promote { label: String, region: String },
+    /// A simulation tick ran, bringing the colony to `tick`.
+    Tick { tick: Tick },
+}
+
+/// What an agent answered the runtime, other than its identity, type,
+/// position and age.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentAnswer {
+    /// The action the agent's tick returned, and where the agent was and
+    /// how old it was afterwards.
+    Tick {
+        action: AgentAction,
+        position: Position,
+        age: Tick,
+    },
+    /// [`Agent::export_vocabulary`].
+    Exported(Option<Vec<u8>>),
+    /// [`Agent::release_undigested`], without the content.
+    Released(Option<DocumentId>),
+    /// [`Agent::integrate_vocabulary`].
+    Integrated(bool),
+    /// Whether [`Agent::evaluate_symbiosis`] chose to integrate.
+    Symbiosis(bool),
+    /// [`Agent::permeability`].
+    Permeability(f64),
+    /// [`Agent::externalize_vocabulary`].
+    Externalized(Vec<String>),
+    /// [`Apoptose::prepare_death_signal`].
+    Death(DeathSignal),
+}
+
+impl AgentAnswer {
+    fn same_kind(&self, other: &AgentAnswer) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// One recorded call, with the IDs the colony minted and the answers
+/// agents gave while carrying it out, each agent's in the order given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayStep {
+    pub input: ReplayInput,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub minted: Vec<Uuid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answers: Vec<(AgentId, AgentAnswer)>,
+}
+
+/// First line of a saved recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReplayHeader {
+    version: u32,
+    config: ColonyConfig,
+}
+
+/// A recorded run: the configuration the colony was created with and
+/// every step since.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub config: ColonyConfig,
+    pub steps: Vec<ReplayStep>,
+}
+
+impl Replay {
+    /// An empty recording of a colony created with `config`.
+    pub fn new(config: ColonyConfig) -> Self {
+        Self {
+            config,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Load a recording saved with [`Replay::save`].
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let reader = BufReader::new(std::fs::File::open(path)?);
+        let mut lines = reader.lines().enumerate();
+        let header: ReplayHeader = loop {
+            match lines.next() {
+                Some((i, line)) => {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        break parse_line(i, &line)?;
+                    }
+                }
+                None => return Err(Error::new(ErrorKind::InvalidData, "empty replay file")),
+            }
+        };
+        if header.version > REPLAY_FORMAT_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "replay format version {} is newer than this build reads ({})",
+                    header.version, REPLAY_FORMAT_VERSION
+                ),
+            ));
+        }
+        let mut steps = Vec::new();
+        for (i, line) in lines {
+            let line = line?;
+            if !line.trim().is_empty() {
+                steps.push(parse_line(i, &line)?);
+            }
+        }
+        Ok(Self {
+            config: header.config,
+            steps,
+        })
+    }
+
+    /// Save the recording as JSONL.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut out = BufWriter::new(std::fs::File::create(path)?);
+        let header = ReplayHeader {
+            version: REPLAY_FORMAT_VERSION,
+            config: self.config.clone(),
+        };
+        serde_json::to_writer(&mut out, &header)?;
+        out.write_all(b"\n")?;
+        for step in &self.steps {
+            serde_json::to_writer(&mut out, step)?;
+            out.write_all(b"\n")?;
+        }
+        out.flush()
+    }
+
+    /// The tick the last recorded tick brought the colony to (0 if none ran).
+    pub fn last_tick(&self) -> Tick {
+        self.steps
+            .iter()
+            .rev()
+            .find_map(|step| match step.input {
+                ReplayInput::Tick { tick } => Some(tick),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Rebuild the colony as it was right after tick `tick` completed, or
+    /// at the end of the recording if that comes first. `run_until(0)`
+    /// applies what was recorded before the first tick.
+    ///
+    /// The colony uses the recorded configuration with an in-memory graph
+    /// and does not record a replay of its own.
+    pub fn run_until(&self, tick: Tick) -> Colony {
+        let mut config = self.config.clone();
+        config.record_replay = false;
+        config.backend = BackendConfig::default();
+        let mut colony = Colony::from_config(config);
+        let script = Script::default();
+        for step in &self.steps {
+            let is_tick = matches!(step.input, ReplayInput::Tick { .. });
+            if is_tick && colony.substrate().current_tick() >= tick {
+                break;
+            }
+            apply(&mut colony, step, &script);
+            if is_tick && colony.substrate().current_tick() >= tick {
+                break;
+            }
+        }
+        colony
+    }
+}
+
+fn parse_line<T: serde::de::DeserializeOwned>(index: usize, line: &str) -> std::io::Result<T> {
+    serde_json::from_str(line).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("replay line {}: {}", index + 1, e),
+        )
+    })
+}
+
+/// Carry out one recorded step, with the puppets answering from it.
+fn apply(colony: &mut Colony, step: &ReplayStep, script: &Script) {
+    {
+        let mut answers = script.lock();
+        answers.clear();
+        for (agent, answer) in &step.answers {
+            answers.entry(*agent).or_default().push_back(answer.clone());
+        }
+    }
+    colony.script_ids(step.minted.iter().copied());
+    match &step.input {
+        ReplayInput::Spawn {
+            agent,
+            agent_type,
+            position,
+        } => {
+            colony.spawn(Box::new(Puppet {
+                id: *agent,
+                agent_type: agent_type.clone(),
+                position: *position,
+                age: 0,
+                permeability: Cell::new(0.0),
+                script: script.clone(),
+            }));
+        }
+        ReplayInput::TakeAgent { agent } => {
+            colony.take_agent(agent);
+        }
+        ReplayInput::Ingest {
+            title,
+            content,
+            position,
+            tags,
+        } => {
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+            colony.ingest_document_tagged(title, content, *position, &tags);
+        }
+        ReplayInput::Update {
+            title,
+            content,
+            position,
+            tags,
+        } => {
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+            colony.ingest_or_update_document_tagged(title, content, *position, &tags);
+        }
+        ReplayInput::MergeDuplicate { original } => colony.merge_duplicate(*original),
+        ReplayInput::Expiry { document, expiry } => {
+            colony.set_document_expiry(document, *expiry);
+        }
+        ReplayInput::Touch { nodes } => colony.touch_nodes(nodes),
+        ReplayInput::Reinforce { nodes, tags, edges } => {
+            colony.reinforce_retrieval(nodes, tags, edges);
+        }
+        ReplayInput::DefineRegion { region } => {
+            let _ = colony.define_region(&region.name, region.shape, region.policy);
+        }
+        ReplayInput::Promote { label, region } => {
+            let _ = colony.promote_node(label, region);
+        }
+        ReplayInput::Tick { .. } => {
+            colony.tick();
+        }
+    }
+    colony.script_ids(std::iter::empty());
+}
+
+/// Answers given while the current step is recorded, shared by every
+/// recording agent of a colony.
+#[derive(Clone, Default)]
+struct AnswerLog(Arc<Mutex<Vec<(AgentId, AgentAnswer)>>>);
+
+impl AnswerLog {
+    fn lock(&self) -> MutexGuard<'_, Vec<(AgentId, AgentAnswer)>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Records a colony's steps; owned by the colony while
+/// [`ColonyConfig::record_replay`] is set.
+pub(crate) struct ReplayRecorder {
+    replay: Replay,
+    /// Steps begun and not yet ended. Calls made while carrying out
+    /// another step are part of that step.
+    depth: usize,
+    minted: Vec<Uuid>,
+    answers: AnswerLog,
+}
+
+impl ReplayRecorder {
+    pub(crate) fn new(config: ColonyConfig) -> Self {
+        Self {
+            replay: Replay::new(config),
+            depth: 0,
+            minted: Vec::new(),
+            answers: AnswerLog::default(),
+        }
+    }
+
+    pub(crate) fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Start recording a step. What agents answered and the IDs minted
+    /// since the last step (outside any recorded call) are dropped.
+    pub(crate) fn begin(&mut self) {
+        if self.depth == 0 {
+            self.minted.clear();
+            self.answers.lock().clear();
+        }
+        self.depth += 1;
+    }
+
+    /// Finish the step begun last; recorded unless it was part of another.
+    pub(crate) fn end(&mut self, input: impl FnOnce() -> ReplayInput) {
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth == 0 {
+            self.replay.steps.push(ReplayStep {
+                input: input(),
+                minted: std::mem::take(&mut self.minted),
+                answers: std::mem::take(&mut *self.answers.lock()),
+            });
+        }
+    }
+
+    pub(crate) fn minted(&mut self, id: Uuid) {
+        self.minted.push(id);
+    }
+
+    /// `agent`, answering as before while its answers are recorded.
+    pub(crate) fn wrap(&self, agent: BoxedAgent) -> BoxedAgent {
+        Box::new(Recording {
+            inner: agent,
+            answers: self.answers.clone(),
+        })
+    }
+}
+
+/// An agent whose answers to the runtime are recorded.
+struct Recording {
+    inner: BoxedAgent,
+    answers: AnswerLog,
+}
+
+impl Recording {
+    fn answer(&self, answer: AgentAnswer) {
+        self.answers.lock().push((self.inner.id(), answer));
+    }
+}
+
+impl Digest for Recording {
+    type Input = String;
+    type Fragment = String;
+    type Presentation = Vec<String>;
+
+    fn engulf(&mut self, input: String) -> DigestionResult {
+        self.inner.engulf(input)
+    }
+
+    fn lyse(&mut self) -> Vec<String> {
+        self.inner.lyse()
+    }
+
+    fn present(&self) -> Vec<String> {
+        self.inner.present()
+    }
+
+    fn digest(&mut self, input: String) -> Vec<String> {
+        self.inner.digest(input)
+    }
+}
+
+impl Apoptose for Recording {
+    fn self_assess(&self) -> CellHealth {
+        self.inner.self_assess()
+    }
+
+    fn should_die(&self) -> bool {
+        self.inner.should_die()
+    }
+
+    fn prepare_death_signal(&self) -> DeathSignal {
+        let signal = self.inner.prepare_death_signal();
+        self.answer(AgentAnswer::Death(signal.clone()));
+        signal
+    }
+}
+
+impl Sense for Recording {
+    fn sense_radius(&self) -> f64 {
+        self.inner.sense_radius()
+    }
+
+    fn subscription(&self) -> SignalKinds {
+        self.inner.subscription()
+    }
+
+    fn sense(&self, substrate: &dyn Substrate) -> Vec<Signal> {
+        self.inner.sense(substrate)
+    }
+
+    fn sense_position(&self) -> Position {
+        self.inner.sense_position()
+    }
+
+    fn gradient(&self, substrate: &dyn Substrate) -> Vec<Gradient> {
+        self.inner.gradient(substrate)
+    }
+
+    fn emit(&self, signal: Signal, substrate: &mut dyn Substrate) {
+        self.inner.emit(signal, substrate)
+    }
+
+    fn orient(&self, gradients: &[Gradient]) -> Orientation {
+        self.inner.orient(gradients)
+    }
+}
+
+impl Agent for Recording {
+    fn id(&self) -> AgentId {
+        self.inner.id()
+    }
+
+    fn position(&self) -> Position {
+        self.inner.position()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.inner.set_position(position)
+    }
+
+    fn agent_type(&self) -> &str {
+        self.inner.agent_type()
+    }
+
+    fn tick(&mut self, substrate: &dyn Substrate) -> AgentAction {
+        let action = self.inner.tick(substrate);
+        self.answer(AgentAnswer::Tick {
+            action: action.clone(),
+            position: self.inner.position(),
+            age: self.inner.age(),
+        });
+        action
+    }
+
+    fn age(&self) -> Tick {
+        self.inner.age()
+    }
+
+    fn release_undigested(&mut self) -> Option<(DocumentId, String)> {
+        let released = self.inner.release_undigested();
+        self.answer(AgentAnswer::Released(released.as_ref().map(|(id, _)| *id)));
+        released
+    }
+
+    fn export_vocabulary(&self) -> Option<Vec<u8>> {
+        let exported = self.inner.export_vocabulary();
+        self.answer(AgentAnswer::Exported(exported.clone()));
+        exported
+    }
+
+    fn integrate_vocabulary(&mut self, data: &[u8]) -> bool {
+        let integrated = self.inner.integrate_vocabulary(data);
+        self.answer(AgentAnswer::Integrated(integrated));
+        integrated
+    }
+
+    fn profile(&self) -> AgentProfile {
+        self.inner.profile()
+    }
+
+    fn evaluate_symbiosis(&self, other: &AgentProfile) -> Option<SymbiosisEval> {
+        let eval = self.inner.evaluate_symbiosis(other);
+        self.answer(AgentAnswer::Symbiosis(
+            eval == Some(SymbiosisEval::Integrate),
+        ));
+        eval
+    }
+
+    fn absorb_symbiont(&mut self, profile: AgentProfile, data: Vec<u8>) -> bool {
+        self.inner.absorb_symbiont(profile, data)
+    }
+
+    fn permeability(&self) -> f64 {
+        let permeability = self.inner.permeability();
+        self.answer(AgentAnswer::Permeability(permeability));
+        permeability
+    }
+
+    fn modulate_boundary(&mut self, context: &BoundaryContext) {
+        self.inner.modulate_boundary(context)
+    }
+
+    fn externalize_vocabulary(&self) -> Vec<String> {
+        let terms = self.inner.externalize_vocabulary();
+        self.answer(AgentAnswer::Externalized(terms.clone()));
+        terms
+    }
+
+    fn internalize_vocabulary(&mut self, terms: &[String]) {
+        self.inner.internalize_vocabulary(terms)
+    }
+
+    fn inherit_vocabulary(&mut self, vocabulary: &[u8], fraction: f64) -> usize {
+        self.inner.inherit_vocabulary(vocabulary, fraction)
+    }
+
+    fn vocabulary_size(&self) -> usize {
+        self.inner.vocabulary_size()
+    }
+
+    fn vocabulary_snapshot(&self, top_n: usize) -> Vec<(String, f64)> {
+        self.inner.vocabulary_snapshot(top_n)
+    }
+
+    fn adopt_emerge_defaults(&mut self, config: &EmergeConfig) {
+        self.inner.adopt_emerge_defaults(config)
+    }
+
+    fn adopt_tokenizer(&mut self, tokenizer: &Tokenizer) {
+        self.inner.adopt_tokenizer(tokenizer)
+    }
+
+    fn adopt_digestion_limits(&mut self, limits: &DigestionLimits) {
+        self.inner.adopt_digestion_limits(limits)
+    }
+
+    fn quorum_status(&self, substrate: &dyn Substrate) -> Option<QuorumStatus> {
+        self.inner.quorum_status(substrate)
+    }
+}
+
+/// Answers for the step being replayed, per agent in the order given.
+#[derive(Clone, Default)]
+struct Script(Arc<Mutex<HashMap<AgentId, VecDeque<AgentAnswer>>>>);
+
+impl Script {
+    fn lock(&self) -> MutexGuard<'_, HashMap<AgentId, VecDeque<AgentAnswer>>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// `agent`'s next answer, if it is of the kind of `like`.
+    fn next(&self, agent: AgentId, like: AgentAnswer) -> Option<AgentAnswer> {
+        let mut answers = self.lock();
+        let queue = answers.get_mut(&agent)?;
+        if queue.front()?.same_kind(&like) {
+            queue.pop_front()
+        } else {
+            None
+        }
+    }
+}
+
+/// Stands in for a recorded agent, answering what it answered.
+struct Puppet {
+    id: AgentId,
+    agent_type: String,
+    position: Position,
+    age: Tick,
+    /// Last recorded permeability, for reads between steps.
+    permeability: Cell<f64>,
+    script: Script,
+}
+
+impl Digest for Puppet {
+    type Input = String;
+    type Fragment = String;
+    type Presentation = Vec<String>;
+
+    fn engulf(&mut self, _input: String) -> DigestionResult {
+        DigestionResult::Engulfed
+    }
+
+    fn lyse(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn present(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+impl Apoptose for Puppet {
+    fn self_assess(&self) -> CellHealth {
+        CellHealth::Healthy
+    }
+
+    fn prepare_death_signal(&self) -> DeathSignal {
+        match self
+            .script
+            .next(self.id, AgentAnswer::Death(empty_signal()))
+        {
+            Some(AgentAnswer::Death(signal)) => signal,
+            _ => DeathSignal {
+                agent_id: self.id,
+                total_ticks: self.age,
+                ..empty_signal()
+            },
+        }
+    }
+}
+
+fn empty_signal() -> DeathSignal {
+    DeathSignal {
+        agent_id: AgentId::from_seed(0),
+        total_ticks: 0,
+        useful_outputs: 0,
+        final_fragments: Vec::new(),
+        cause: DeathCause::RuntimeTermination,
+        had_pending_work: false,
+    }
+}
+
+impl Sense for Puppet {
+    fn sense_radius(&self) -> f64 {
+        0.0
+    }
+
+    fn sense_position(&self) -> Position {
+        self.position
+    }
+
+    fn gradient(&self, _substrate: &dyn Substrate) -> Vec<Gradient> {
+        Vec::new()
+    }
+
+    fn orient(&self, _gradients: &[Gradient]) -> Orientation {
+        Orientation::Stay
+    }
+}
+
+impl Agent for Puppet {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn agent_type(&self) -> &str {
+        &self.agent_type
+    }
+
+    fn tick(&mut self, _substrate: &dyn Substrate) -> AgentAction {
+        let like = AgentAnswer::Tick {
+            action: AgentAction::Idle,
+            position: self.position,
+            age: self.age,
+        };
+        match self.script.next(self.id, like) {
+            Some(AgentAnswer::Tick {
+                action,
+                position,
+                age,
+            }) => {
+                self.position = position;
+                self.age = age;
+                action
+            }
+            _ => {
+                self.age += 1;
+                AgentAction::Idle
+            }
+        }
+    }
+
+    fn age(&self) -> Tick {
+        self.age
+    }
+
+    fn release_undigested(&mut self) -> Option<(DocumentId, String)> {
+        match self.script.next(self.id, AgentAnswer::Released(None)) {
+            Some(AgentAnswer::Released(Some(id))) => Some((id, String::new())),
+            _ => None,
+        }
+    }
+
+    fn export_vocabulary(&self) -> Option<Vec<u8>> {
+        match self.script.next(self.id, AgentAnswer::Exported(None)) {
+            Some(AgentAnswer::Exported(exported)) => exported,
+            _ => None,
+        }
+    }
+
+    fn integrate_vocabulary(&mut self, _data: &[u8]) -> bool {
+        matches!(
+            self.script.next(self.id, AgentAnswer::Integrated(false)),
+            Some(AgentAnswer::Integrated(true))
+        )
+    }
+
+    fn evaluate_symbiosis(&self, _other: &AgentProfile) -> Option<SymbiosisEval> {
+        match self.script.next(self.id, AgentAnswer::Symbiosis(false)) {
+            Some(AgentAnswer::Symbiosis(true)) => Some(SymbiosisEval::Integrate),
+            _ => None,
+        }
+    }
+
+    fn permeability(&self) -> f64 {
+        match self.script.next(self.id, AgentAnswer::Permeability(0.0)) {
+            Some(AgentAnswer::Permeability(permeability)) => {
+                self.permeability.set(permeability);
+                permeability
+            }
+            _ => self.permeability.get(),
+        }
+    }
+
+    fn externalize_vocabulary(&self) -> Vec<String> {
+        match self
+            .script
+            .next(self.id, AgentAnswer::Externalized(Vec::new()))
+        {
+            Some(AgentAnswer::Externalized(terms)) => terms,
+            _ => Vec::new(),
+        }
+    }
+}
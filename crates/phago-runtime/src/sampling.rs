@@ -0,0 +1,494 @@
+//! Graph sampling — reduced snapshots of colonies too large to draw.
+//!
+//! A snapshot of a colony with hundreds of thousands of nodes is more than
+//! a browser can lay out. [`Colony::sample_snapshot`] keeps a subset of
+//! the nodes chosen by a [`SampleStrategy`], and of the edges only those
+//! whose two endpoints were both kept. Insight and Anomaly nodes are
+//! always kept, on top of the strategy's budget: they are what a reader
+//! most needs to see.
+//!
+//! Every sampled snapshot carries a [`SampleCoverage`] saying how much of
+//! the graph it shows, so nobody mistakes a sample for the whole colony.
+//! Sampling is deterministic: the same graph and strategy (and seed, for
+//! [`SampleStrategy::ForestFire`]) always keep the same nodes.
+//!
+//! [`Colony::sample_snapshot`]: crate::colony::Colony::sample_snapshot
+
+use phago_core::topology::TopologyGraph;
+use phago_core::types::{NodeId, NodeType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Chance that a burning node sets one more neighbor alight in
+/// [`SampleStrategy::ForestFire`]; each node burns 2.3 neighbors on
+/// average.
+const FOREST_FIRE_BURN: f64 = 0.7;
+
+/// How to choose the nodes of a sampled snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum SampleStrategy {
+    /// The `n` most accessed nodes.
+    TopByAccess { n: usize },
+    /// The neighborhoods of the concepts labeled `seeds`, out to `hops`
+    /// hops and up to `n` nodes, strongest edges first. The seeds are kept
+    /// even when there are more than `n` of them.
+    EgoNetworks {
+        seeds: Vec<String>,
+        hops: usize,
+        n: usize,
+    },
+    /// Only edges at least `min_edge_weight` strong, and up to `max_nodes`
+    /// of their endpoints, those with the most strong weight first.
+    WeightThreshold {
+        min_edge_weight: f64,
+        max_nodes: usize,
+    },
+    /// Up to `n` nodes burned by forest fires started from random nodes:
+    /// each burning node sets a random few of its neighbors alight, so the
+    /// sample keeps local structure. `seed` fixes the random choices.
+    ForestFire { seed: u64, n: usize },
+}
+
+/// Settings for [`Colony::sample_snapshot`](crate::colony::Colony::sample_snapshot).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleConfig {
+    pub strategy: SampleStrategy,
+    /// Whether sampled nodes carry their embeddings, as in
+    /// [`Colony::snapshot_with_embeddings`](crate::colony::Colony::snapshot_with_embeddings).
+    #[serde(default)]
+    pub with_embeddings: bool,
+}
+
+impl SampleConfig {
+    pub fn new(strategy: SampleStrategy) -> Self {
+        Self {
+            strategy,
+            with_embeddings: false,
+        }
+    }
+
+    /// Keep the embeddings of the sampled nodes.
+    pub fn with_embeddings(mut self) -> Self {
+        self.with_embeddings = true;
+        self
+    }
+}
+
+impl From<SampleStrategy> for SampleConfig {
+    fn from(strategy: SampleStrategy) -> Self {
+        Self::new(strategy)
+    }
+}
+
+/// How much of the graph a sampled snapshot shows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SampleCoverage {
+    pub nodes_sampled: usize,
+    pub nodes_total: usize,
+    pub edges_sampled: usize,
+    pub edges_total: usize,
+    /// Sampled share of the nodes, 0.0–1.0.
+    pub node_fraction: f64,
+    /// Sampled share of the edges, 0.0–1.0.
+    pub edge_fraction: f64,
+    /// Sampled share of all node accesses, 0.0–1.0: how much of what the
+    /// colony actually uses the sample shows.
+    pub access_fraction: f64,
+}
+
+impl SampleCoverage {
+    /// Whether the sample shows the whole graph.
+    pub fn is_complete(&self) -> bool {
+        self.nodes_sampled == self.nodes_total && self.edges_sampled == self.edges_total
+    }
+}
+
+/// The nodes a strategy kept, and the weakest edge it keeps between them.
+pub(crate) struct Sample {
+    pub(crate) nodes: HashSet<NodeId>,
+    pub(crate) min_edge_weight: f64,
+}
+
+impl Sample {
+    pub(crate) fn keeps_edge(&self, from: &NodeId, to: &NodeId, weight: f64) -> bool {
+        weight >= self.min_edge_weight && self.nodes.contains(from) && self.nodes.contains(to)
+    }
+}
+
+/// Choose the nodes of `graph` to keep under `strategy`.
+pub(crate) fn sample(graph: &dyn TopologyGraph, strategy: &SampleStrategy) -> Sample {
+    let mut min_edge_weight = f64::NEG_INFINITY;
+    let mut nodes = match strategy {
+        SampleStrategy::TopByAccess { n } => top_by_access(graph, *n),
+        SampleStrategy::EgoNetworks { seeds, hops, n } => ego_networks(graph, seeds, *hops, *n),
+        SampleStrategy::WeightThreshold {
+            min_edge_weight: min,
+            max_nodes,
+        } => {
+            min_edge_weight = *min;
+            weight_threshold(graph, *min, *max_nodes)
+        }
+        SampleStrategy::ForestFire { seed, n } => forest_fire(graph, *seed, *n),
+    };
+    for id in graph.all_nodes() {
+        let flagged = graph
+            .get_node(&id)
+            .is_some_and(|n| matches!(n.node_type, NodeType::Insight | NodeType::Anomaly));
+        if flagged {
+            nodes.insert(id);
+        }
+    }
+    Sample {
+        nodes,
+        min_edge_weight,
+    }
+}
+
+/// How much of `graph` a sample under `strategy` would show, without
+/// taking the snapshot.
+pub fn coverage(graph: &dyn TopologyGraph, strategy: &SampleStrategy) -> SampleCoverage {
+    sample(graph, strategy).coverage(graph)
+}
+
+impl Sample {
+    pub(crate) fn coverage(&self, graph: &dyn TopologyGraph) -> SampleCoverage {
+        let mut access_total = 0;
+        let mut access_sampled = 0;
+        for id in graph.all_nodes() {
+            let access = graph.get_node(&id).map_or(0, |n| n.access_count);
+            access_total += access;
+            if self.nodes.contains(&id) {
+                access_sampled += access;
+            }
+        }
+        let nodes_sampled = self.nodes.len();
+        let nodes_total = graph.node_count();
+        let edges_sampled = graph
+            .all_edges()
+            .iter()
+            .filter(|(from, to, edge)| self.keeps_edge(from, to, edge.weight))
+            .count();
+        let edges_total = graph.edge_count();
+        SampleCoverage {
+            nodes_sampled,
+            nodes_total,
+            edges_sampled,
+            edges_total,
+            node_fraction: fraction(nodes_sampled as f64, nodes_total as f64),
+            edge_fraction: fraction(edges_sampled as f64, edges_total as f64),
+            access_fraction: fraction(access_sampled as f64, access_total as f64),
+        }
+    }
+}
+
+/// `part / whole`, or all of it when there is nothing.
+fn fraction(part: f64, whole: f64) -> f64 {
+    if whole > 0.0 {
+        part / whole
+    } else {
+        1.0
+    }
+}
+
+/// Every node, in label order (then ID, for equal labels).
+fn nodes_by_label(graph: &dyn TopologyGraph) -> Vec<(NodeId, &str)> {
+    let mut nodes: Vec<(NodeId, &str)> = graph
+        .all_nodes()
+        .into_iter()
+        .filter_map(|id| graph.get_node(&id).map(|n| (id, n.label.as_str())))
+        .collect();
+    nodes.sort_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(&b.0)));
+    nodes
+}
+
+fn top_by_access(graph: &dyn TopologyGraph, n: usize) -> HashSet<NodeId> {
+    let mut nodes: Vec<_> = graph
+        .all_nodes()
+        .into_iter()
+        .filter_map(|id| graph.get_node(&id))
+        .collect();
+    nodes.sort_by(|a, b| {
+        b.access_count
+            .cmp(&a.access_count)
+            .then_with(|| a.label.cmp(&b.label))
+            .then(a.id.cmp(&b.id))
+    });
+    nodes.into_iter().take(n).map(|n| n.id).collect()
+}
+
+fn ego_networks(
+    graph: &dyn TopologyGraph,
+    seeds: &[String],
+    hops: usize,
+    n: usize,
+) -> HashSet<NodeId> {
+    let mut sampled: HashSet<NodeId> = HashSet::new();
+    let mut frontier: Vec<NodeId> = Vec::new();
+    for seed in seeds {
+        for id in graph.find_nodes_by_exact_label(seed) {
+            if sampled.insert(id) {
+                frontier.push(id);
+            }
+        }
+    }
+
+    for _ in 0..hops {
+        if sampled.len() >= n || frontier.is_empty() {
+            break;
+        }
+        // Each neighbor by its strongest edge into the frontier
+        let mut reach: HashMap<NodeId, f64> = HashMap::new();
+        for id in &frontier {
+            for (neighbor, edge) in graph.neighbors(id) {
+                if sampled.contains(&neighbor) {
+                    continue;
+                }
+                let best = reach.entry(neighbor).or_insert(edge.weight);
+                *best = best.max(edge.weight);
+            }
+        }
+        let mut candidates: Vec<(NodeId, f64, &str)> = reach
+            .into_iter()
+            .filter_map(|(id, w)| graph.get_node(&id).map(|n| (id, w, n.label.as_str())))
+            .collect();
+        candidates.sort_by(|a, b| {
+            b.1.total_cmp(&a.1)
+                .then_with(|| a.2.cmp(b.2))
+                .then(a.0.cmp(&b.0))
+        });
+        frontier = candidates
+            .into_iter()
+            .take(n - sampled.len())
+            .map(|(id, _, _)| id)
+            .collect();
+        sampled.extend(frontier.iter().copied());
+    }
+    sampled
+}
+
+fn weight_threshold(
+    graph: &dyn TopologyGraph,
+    min_edge_weight: f64,
+    max_nodes: usize,
+) -> HashSet<NodeId> {
+    let mut strength: HashMap<NodeId, f64> = HashMap::new();
+    for (from, to, edge) in graph.all_edges() {
+        if edge.weight >= min_edge_weight {
+            *strength.entry(from).or_insert(0.0) += edge.weight;
+            *strength.entry(to).or_insert(0.0) += edge.weight;
+        }
+    }
+    let mut nodes: Vec<(NodeId, f64, &str)> = strength
+        .into_iter()
+        .filter_map(|(id, s)| graph.get_node(&id).map(|n| (id, s, n.label.as_str())))
+        .collect();
+    nodes.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then_with(|| a.2.cmp(b.2))
+            .then(a.0.cmp(&b.0))
+    });
+    nodes
+        .into_iter()
+        .take(max_nodes)
+        .map(|(id, _, _)| id)
+        .collect()
+}
+
+/// A seeded linear congruential generator.
+struct Lcg(u64);
+
+impl Lcg {
+    fn new(seed: u64) -> Self {
+        Self(
+            seed.wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407),
+        )
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+        self.0 >> 33
+    }
+
+    /// Uniform in `0..bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        self.next() as usize % bound
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn unit(&mut self) -> f64 {
+        self.next() as f64 / (1u64 << 31) as f64
+    }
+}
+
+fn forest_fire(graph: &dyn TopologyGraph, seed: u64, n: usize) -> HashSet<NodeId> {
+    let order = nodes_by_label(graph);
+    let target = n.min(order.len());
+    let mut rng = Lcg::new(seed);
+    let mut burned: HashSet<NodeId> = HashSet::new();
+
+    while burned.len() < target {
+        // Start a new fire at a random unburned node
+        let unburned: Vec<NodeId> = order
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| !burned.contains(id))
+            .collect();
+        let start = unburned[rng.below(unburned.len())];
+        burned.insert(start);
+        let mut burning = VecDeque::from([start]);
+
+        while let Some(id) = burning.pop_front() {
+            if burned.len() >= target {
+                break;
+            }
+            let mut neighbors: Vec<(NodeId, &str)> = graph
+                .neighbors(&id)
+                .into_iter()
+                .filter(|(neighbor, _)| !burned.contains(neighbor))
+                .filter_map(|(neighbor, _)| {
+                    graph
+                        .get_node(&neighbor)
+                        .map(|n| (neighbor, n.label.as_str()))
+                })
+                .collect();
+            neighbors.sort_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(&b.0)));
+
+            // Geometrically many neighbors catch, chosen at random
+            let mut spread = 0;
+            while rng.unit() < FOREST_FIRE_BURN {
+                spread += 1;
+            }
+            for i in 0..spread.min(neighbors.len()) {
+                let j = i + rng.below(neighbors.len() - i);
+                neighbors.swap(i, j);
+                let (neighbor, _) = neighbors[i];
+                if burned.len() >= target {
+                    break;
+                }
+                burned.insert(neighbor);
+                burning.push_back(neighbor);
+            }
+        }
+    }
+    burned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology_impl::PetTopologyGraph;
+    use phago_core::types::{EdgeData, NodeData, Position};
+
+    fn add_node(
+        graph: &mut PetTopologyGraph,
+        label: &str,
+        node_type: NodeType,
+        access: u64,
+    ) -> NodeId {
+        graph.add_node(NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type,
+            position: Position::new(0.0, 0.0),
+            access_count: access,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        })
+    }
+
+    fn connect(graph: &mut PetTopologyGraph, a: NodeId, b: NodeId, weight: f64) {
+        graph
+            .set_edge(
+                a,
+                b,
+                EdgeData {
+                    weight,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+    }
+
+    /// Two chains of ten concepts joined at their heads, and one insight.
+    fn two_chains() -> (PetTopologyGraph, Vec<NodeId>, Vec<NodeId>, NodeId) {
+        let mut graph = PetTopologyGraph::new();
+        let chain = |graph: &mut PetTopologyGraph, prefix: &str| {
+            let ids: Vec<NodeId> = (0..10)
+                .map(|i| add_node(graph, &format!("{prefix}{i}"), NodeType::Concept, 10 - i))
+                .collect();
+            for pair in ids.windows(2) {
+                connect(graph, pair[0], pair[1], 0.5);
+            }
+            ids
+        };
+        let a = chain(&mut graph, "a");
+        let b = chain(&mut graph, "b");
+        connect(&mut graph, a[0], b[0], 0.9);
+        let insight = add_node(&mut graph, "a0 relates to b0", NodeType::Insight, 0);
+        (graph, a, b, insight)
+    }
+
+    #[test]
+    fn top_by_access_keeps_the_most_used_nodes_and_every_insight() {
+        let (graph, a, b, insight) = two_chains();
+        let kept = sample(&graph, &SampleStrategy::TopByAccess { n: 4 });
+        let expected: HashSet<NodeId> = [a[0], b[0], a[1], b[1], insight].into();
+        assert_eq!(kept.nodes, expected);
+        assert!(kept.keeps_edge(&a[0], &b[0], 0.9));
+        assert!(!kept.keeps_edge(&a[1], &a[2], 0.5));
+    }
+
+    #[test]
+    fn ego_networks_always_keep_their_seeds() {
+        let (graph, a, b, _) = two_chains();
+        let strategy = SampleStrategy::EgoNetworks {
+            seeds: vec!["a5".into(), "B9".into(), "missing".into()],
+            hops: 1,
+            n: 1,
+        };
+        let kept = sample(&graph, &strategy);
+        assert!(kept.nodes.contains(&a[5]));
+        assert!(kept.nodes.contains(&b[9]));
+
+        let strategy = SampleStrategy::EgoNetworks {
+            seeds: vec!["a0".into()],
+            hops: 3,
+            n: 2,
+        };
+        let kept = sample(&graph, &strategy);
+        // The strongest edge is followed first; the insight comes on top
+        assert!(kept.nodes.contains(&a[0]) && kept.nodes.contains(&b[0]));
+        assert_eq!(kept.nodes.len(), 3);
+    }
+
+    #[test]
+    fn weight_threshold_drops_weak_edges() {
+        let (graph, a, b, _) = two_chains();
+        let strategy = SampleStrategy::WeightThreshold {
+            min_edge_weight: 0.8,
+            max_nodes: 10,
+        };
+        let kept = sample(&graph, &strategy);
+        assert!(kept.nodes.contains(&a[0]) && kept.nodes.contains(&b[0]));
+        assert!(!kept.nodes.contains(&a[1]));
+        assert!(!kept.keeps_edge(&a[0], &b[0], 0.5));
+    }
+
+    #[test]
+    fn forest_fire_is_deterministic_under_a_seed() {
+        let (graph, ..) = two_chains();
+        let fire = |seed| sample(&graph, &SampleStrategy::ForestFire { seed, n: 8 }).nodes;
+        assert_eq!(fire(7), fire(7));
+        // Eight burned, plus the insight unless a fire started there
+        assert!((8..=9).contains(&fire(7).len()));
+        assert!((0..20).any(|seed| fire(seed) != fire(7)));
+
+        let all = sample(&graph, &SampleStrategy::ForestFire { seed: 7, n: 100 });
+        assert_eq!(all.nodes.len(), graph.node_count());
+    }
+}
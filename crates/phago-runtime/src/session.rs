@@ -3,14 +3,43 @@
 //! Serializes the knowledge graph (nodes + edges) and agent state to JSON
 //! for persistence across sessions. Agents can be fully restored with their
 //! vocabulary, fitness history, and other internal state.
+//!
+//! ## Format versions
+//!
+//! Sessions record the [`FORMAT_VERSION`] they were written in; files from
+//! before versioning are version 1. Loading upgrades older files one
+//! version at a time through the [`MIGRATIONS`], so sessions from any
+//! earlier release keep loading. [`LoadMode`] decides how strictly:
+//! whether files that need migrating, that carry fields this build does
+//! not know, or that come from a newer release are refused, upgraded, or
+//! loaded as far as possible with a [`LoadReport`] of what was left out.
 
-use crate::colony::Colony;
+use crate::colony::{Colony, DocumentContribution, DocumentExpiry};
+use crate::embedding_store::{EmbeddingStorage, PackedEmbedding};
+use crate::integrity::IntegrityReport;
+use crate::label_policy::{LabelPolicy, ORIGINAL_LABEL_SOURCE};
+use crate::provenance::{EdgeSource, SourceRecord};
+use crate::query_log::QueryLogEntry;
+use crate::region::Region;
+use phago_agents::registry::{AgentRegistry, AgentSpec, RegistryError};
 use phago_agents::serialize::SerializedAgent;
-use phago_core::topology::TopologyGraph;
 use phago_core::types::*;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
+/// The session format version this build writes.
+pub const FORMAT_VERSION: u32 = 3;
+
+/// The version of sessions written before versioning.
+const UNVERSIONED: u32 = 1;
+
+fn unversioned() -> u32 {
+    UNVERSIONED
+}
+
 /// Serializable snapshot of the knowledge graph and agent state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphState {
@@ -19,6 +48,88 @@ pub struct GraphState {
     #[serde(default)]
     pub agents: Vec<SerializedAgent>,
     pub metadata: SessionMetadata,
+    /// Entries of the colony's query log, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query_log: Vec<QueryLogEntry>,
+    /// Ingested documents with what they contributed, so re-ingesting one
+    /// in a later session updates it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<SerializedDocument>,
+    /// Format the saving colony stored embeddings in. Node embeddings are
+    /// saved in it: exactly at full precision, and otherwise within the
+    /// error documented in [`crate::embedding_store`]. Sessions without it
+    /// hold full-precision embeddings.
+    #[serde(default)]
+    pub embedding_storage: EmbeddingStorage,
+    /// Named regions of the field, in the order they were defined.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<Region>,
+    /// The colony's cached term embeddings, least recently used first, if
+    /// the session was saved with them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embedding_cache: Vec<CachedEmbedding>,
+}
+
+/// What a saved session includes besides the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionOptions {
+    /// Save node embeddings (default: yes). Without them a session is
+    /// much smaller, and restored colonies wire without semantic weights
+    /// until embeddings are set again.
+    pub include_embeddings: bool,
+    /// Save the colony's cached term embeddings (default: no), so a
+    /// restored colony's agents need not embed their vocabulary again.
+    /// See [`crate::embedding_cache`].
+    pub include_embedding_cache: bool,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            include_embeddings: true,
+            include_embedding_cache: false,
+        }
+    }
+}
+
+/// A cached term embedding, in the session's embedding storage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedEmbedding {
+    pub term: String,
+    pub embedding: PackedEmbedding,
+}
+
+/// Serializable document with its contribution to the graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedDocument {
+    pub title: String,
+    pub content: String,
+    pub position_x: f64,
+    pub position_y: f64,
+    pub digested: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub version: u32,
+    /// Presentations per node label.
+    #[serde(default)]
+    pub nodes: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub edges: Vec<SerializedContribution>,
+    /// Where each concept occurs in `content`, as byte offsets.
+    #[serde(default, skip_serializing_if = "DocumentPassages::is_empty")]
+    pub passages: DocumentPassages,
+    /// When the document expires, if it has a TTL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry: Option<DocumentExpiry>,
+}
+
+/// Wiring a document added to one edge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedContribution {
+    pub from_label: String,
+    pub to_label: String,
+    pub weight: f64,
+    pub co_activations: u64,
 }
 
 /// Serializable node.
@@ -31,8 +142,21 @@ pub struct SerializedNode {
     pub position_y: f64,
     #[serde(default)]
     pub created_tick: u64,
+    #[serde(default)]
+    pub last_accessed_tick: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<PackedEmbedding>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, u64>,
+    /// Labels of nodes merged into this one, which resolve to it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Notes attached to the node from outside the colony.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    /// Novelty score of a scored anomaly.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub embedding: Option<Vec<f32>>,
+    pub anomaly_score: Option<f64>,
 }
 
 /// Serializable edge.
@@ -46,11 +170,59 @@ pub struct SerializedEdge {
     pub created_tick: u64,
     #[serde(default)]
     pub last_activated_tick: u64,
+    /// What reinforced the edge lately, oldest first (see
+    /// [`crate::provenance`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SerializedEdgeSource>,
+}
+
+/// One recorded reinforcement of an edge, naming documents by title.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum SerializedEdgeSource {
+    Document { title: String, tick: u64 },
+    Query { tick: u64 },
+    Unattributed { tick: u64 },
+    Import { name: String, tick: u64 },
+}
+
+/// The recorded reinforcements of the edge between `a` and `b`, leaving
+/// out documents that no longer exist.
+pub(crate) fn serialize_sources(
+    colony: &Colony,
+    a: &NodeId,
+    b: &NodeId,
+) -> Vec<SerializedEdgeSource> {
+    colony
+        .edge_sources(a, b)
+        .into_iter()
+        .filter_map(|record| {
+            let tick = record.tick;
+            Some(match record.source {
+                EdgeSource::Document(id) => SerializedEdgeSource::Document {
+                    title: colony.substrate().get_document(&id)?.title.clone(),
+                    tick,
+                },
+                EdgeSource::Query => SerializedEdgeSource::Query { tick },
+                EdgeSource::Unattributed => SerializedEdgeSource::Unattributed { tick },
+                EdgeSource::Import(_) => SerializedEdgeSource::Import {
+                    name: colony
+                        .edge_provenance()
+                        .import_name(record.source)?
+                        .to_string(),
+                    tick,
+                },
+            })
+        })
+        .collect()
 }
 
 /// Session metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionMetadata {
+    /// The format version the session was written in.
+    #[serde(default = "unversioned")]
+    pub format_version: u32,
     pub session_id: String,
     pub tick: u64,
     pub node_count: usize,
@@ -58,6 +230,241 @@ pub struct SessionMetadata {
     #[serde(default)]
     pub agent_count: usize,
     pub files_indexed: Vec<String>,
+    /// Specs of the living agents that were spawned from one (see
+    /// [`Colony::population`]), for [`restore_into_colony`] to respawn.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub population: Vec<AgentSpec>,
+}
+
+/// How strictly [`load_session_with`] treats a session that is not exactly
+/// what this build writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadMode {
+    /// Only load sessions in the current format with no unknown fields.
+    Strict,
+    /// Upgrade older sessions; ignore unknown fields but report them.
+    /// Sessions from a newer release are refused.
+    #[default]
+    Migrate,
+    /// As `Migrate`, but also load sessions from a newer release, and leave
+    /// out entries that cannot be read instead of failing.
+    BestEffort,
+}
+
+/// What loading a session had to do to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// The format version the file was written in.
+    pub found_version: u32,
+    /// Migrations applied, by the version each upgraded from.
+    pub migrated_from: Vec<u32>,
+    /// Fields this build does not know, ignored, e.g. `nodes.3.pinned`.
+    pub unknown_fields: Vec<String>,
+    /// Entries left out because they could not be read, with why.
+    pub skipped: Vec<String>,
+}
+
+impl LoadReport {
+    /// Whether the session loaded as written.
+    pub fn is_clean(&self) -> bool {
+        self.migrated_from.is_empty() && self.unknown_fields.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// Why a session could not be loaded.
+#[derive(Debug)]
+pub enum SessionError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a session, or is damaged.
+    Corrupt(String),
+    /// The session was written by a newer release.
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// Strict loading refused a session in an older format.
+    NeedsMigration { found: u32, current: u32 },
+    /// Strict loading refused fields this build does not know.
+    UnknownFields(Vec<String>),
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::Io(e) => write!(f, "could not read session: {}", e),
+            SessionError::Corrupt(e) => write!(f, "session file is corrupt: {}", e),
+            SessionError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "session format version {} is newer than this build supports ({}); \
+                 upgrade phago, or load it best-effort",
+                found, supported
+            ),
+            SessionError::NeedsMigration { found, current } => write!(
+                f,
+                "session format version {} is older than the current {}; load it with migration",
+                found, current
+            ),
+            SessionError::UnknownFields(fields) => {
+                write!(f, "session has unknown fields: {}", fields.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SessionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SessionError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SessionError {
+    fn from(e: std::io::Error) -> Self {
+        SessionError::Io(e)
+    }
+}
+
+impl From<SessionError> for std::io::Error {
+    fn from(e: SessionError) -> Self {
+        match e {
+            SessionError::Io(e) => e,
+            e => std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+/// Upgrades a session from one format version to the next.
+pub struct Migration {
+    /// The version it upgrades from, to `from + 1`.
+    pub from: u32,
+    pub description: &'static str,
+    /// Rewrites the session's JSON in place.
+    pub upgrade: fn(&mut Value) -> Result<(), String>,
+}
+
+/// Every migration, oldest first: one per format version before
+/// [`FORMAT_VERSION`].
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "nodes saved without a last access were last accessed when created",
+        upgrade: v1_last_accessed_at_creation,
+    },
+    Migration {
+        from: 2,
+        description: "labels are normalized by the default label policy",
+        upgrade: v2_normalized_labels,
+    },
+];
+
+/// Version 1 sessions from before access tracking carry no
+/// `last_accessed_tick`; defaulting it to 0 would make every node look
+/// stale and forgettable right after a restore.
+fn v1_last_accessed_at_creation(session: &mut Value) -> Result<(), String> {
+    let Some(nodes) = session.get_mut("nodes").and_then(Value::as_array_mut) else {
+        return Ok(());
+    };
+    for node in nodes.iter_mut().filter_map(Value::as_object_mut) {
+        if !node.contains_key("last_accessed_tick") {
+            let created = node.get("created_tick").cloned().unwrap_or(Value::from(0));
+            node.insert("last_accessed_tick".to_string(), created);
+        }
+    }
+    Ok(())
+}
+
+/// Version 2 sessions stored labels as presented, however long. Normalize
+/// them as the default [`LabelPolicy`] would have, keeping each changed
+/// label as an alias and an annotation of its node, and rename the edges
+/// and document contributions that refer to it.
+fn v2_normalized_labels(session: &mut Value) -> Result<(), String> {
+    let policy = LabelPolicy::default();
+    let mut renamed: HashMap<String, String> = HashMap::new();
+    let nodes = session.get_mut("nodes").and_then(Value::as_array_mut);
+    for node in nodes.into_iter().flatten().filter_map(Value::as_object_mut) {
+        let Some(label) = node
+            .get("label")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let normalized = policy.normalize(&label).into_owned();
+        if normalized == label {
+            continue;
+        }
+        if normalized.to_lowercase() != label.to_lowercase() {
+            push_to(node, "aliases", Value::from(label.clone()));
+        }
+        let tick = node.get("created_tick").cloned().unwrap_or(Value::from(0));
+        push_to(
+            node,
+            "annotations",
+            serde_json::json!({ "text": label, "source": ORIGINAL_LABEL_SOURCE, "tick": tick }),
+        );
+        node.insert("label".to_string(), Value::from(normalized.clone()));
+        renamed.insert(label, normalized);
+    }
+    if renamed.is_empty() {
+        return Ok(());
+    }
+
+    let rename = |value: Option<&mut Value>| {
+        if let Some(value) = value {
+            if let Some(label) = value.as_str().and_then(|l| renamed.get(l)) {
+                *value = Value::from(label.clone());
+            }
+        }
+    };
+    let rename_keys = |map: Option<&mut Value>, merge: fn(&mut Value, Value)| {
+        let Some(map) = map.and_then(Value::as_object_mut) else {
+            return;
+        };
+        for (label, value) in std::mem::take(map) {
+            let label = renamed.get(&label).cloned().unwrap_or(label);
+            match map.get_mut(&label) {
+                Some(existing) => merge(existing, value),
+                None => {
+                    map.insert(label, value);
+                }
+            }
+        }
+    };
+
+    let edges = session.get_mut("edges").and_then(Value::as_array_mut);
+    for edge in edges.into_iter().flatten() {
+        rename(edge.get_mut("from_label"));
+        rename(edge.get_mut("to_label"));
+    }
+    let documents = session.get_mut("documents").and_then(Value::as_array_mut);
+    for doc in documents.into_iter().flatten() {
+        rename_keys(doc.get_mut("nodes"), |count, more| {
+            let sum = count.as_u64().unwrap_or(0) + more.as_u64().unwrap_or(0);
+            *count = Value::from(sum);
+        });
+        let edges = doc.get_mut("edges").and_then(Value::as_array_mut);
+        for edge in edges.into_iter().flatten() {
+            rename(edge.get_mut("from_label"));
+            rename(edge.get_mut("to_label"));
+        }
+        rename_keys(doc.pointer_mut("/passages/concepts"), |spans, more| {
+            if let (Some(spans), Value::Array(more)) = (spans.as_array_mut(), more) {
+                spans.extend(more);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Append `value` to the array `key` of `object`, creating it if need be.
+fn push_to(object: &mut Map<String, Value>, key: &str, value: Value) {
+    match object
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Array(Vec::new()))
+    {
+        Value::Array(values) => values.push(value),
+        other => *other = Value::Array(vec![value]),
+    }
 }
 
 /// Save the colony's knowledge graph to a JSON file.
@@ -79,8 +486,27 @@ pub fn save_session_with_agents(
     path: &Path,
     files_indexed: &[String],
     agents: &[SerializedAgent],
+) -> std::io::Result<()> {
+    save_session_with_options(
+        colony,
+        path,
+        files_indexed,
+        agents,
+        SessionOptions::default(),
+    )
+}
+
+/// Save the colony's knowledge graph and agent state to a JSON file,
+/// including what `options` ask for.
+pub fn save_session_with_options(
+    colony: &Colony,
+    path: &Path,
+    files_indexed: &[String],
+    agents: &[SerializedAgent],
+    options: SessionOptions,
 ) -> std::io::Result<()> {
     let graph = colony.substrate().graph();
+    let embeddings = colony.substrate().embeddings();
     let all_nodes = graph.all_nodes();
 
     let nodes: Vec<SerializedNode> = all_nodes
@@ -93,7 +519,15 @@ pub fn save_session_with_agents(
             position_x: n.position.x,
             position_y: n.position.y,
             created_tick: n.created_tick,
-            embedding: n.embedding.clone(),
+            last_accessed_tick: n.last_accessed_tick,
+            embedding: options
+                .include_embeddings
+                .then(|| embeddings.packed(&n.id).cloned())
+                .flatten(),
+            tags: n.tags.clone(),
+            aliases: graph.aliases(&n.id),
+            annotations: graph.annotations(&n.id),
+            anomaly_score: colony.anomaly_score(&n.id),
         })
         .collect();
 
@@ -110,10 +544,58 @@ pub fn save_session_with_agents(
                 co_activations: edge.co_activations,
                 created_tick: edge.created_tick,
                 last_activated_tick: edge.last_activated_tick,
+                sources: serialize_sources(colony, from, to),
             })
         })
         .collect();
 
+    let label = |id: &NodeId| graph.get_node(id).map(|n| n.label.clone());
+    let mut documents: Vec<SerializedDocument> = colony
+        .substrate()
+        .all_documents()
+        .into_iter()
+        .map(|doc| {
+            let contribution = colony.document_contribution(&doc.id);
+            SerializedDocument {
+                title: doc.title.clone(),
+                content: doc.content.clone(),
+                position_x: doc.position.x,
+                position_y: doc.position.y,
+                digested: doc.digested,
+                tags: doc.tags.clone(),
+                version: doc.version,
+                nodes: contribution
+                    .iter()
+                    .flat_map(|c| &c.nodes)
+                    .filter_map(|(id, count)| Some((label(id)?, *count)))
+                    .collect(),
+                edges: contribution
+                    .iter()
+                    .flat_map(|c| &c.edges)
+                    .filter_map(|((a, b), edge)| {
+                        Some(SerializedContribution {
+                            from_label: label(a)?,
+                            to_label: label(b)?,
+                            weight: edge.weight,
+                            co_activations: edge.co_activations,
+                        })
+                    })
+                    .collect(),
+                passages: colony
+                    .substrate()
+                    .passages(&doc.id)
+                    .cloned()
+                    .unwrap_or_default(),
+                expiry: colony.document_expiry(&doc.id),
+            }
+        })
+        .collect();
+    documents.sort_by(|a, b| a.title.cmp(&b.title).then(a.version.cmp(&b.version)));
+    for doc in &mut documents {
+        doc.edges
+            .sort_by(|a, b| (&a.from_label, &a.to_label).cmp(&(&b.from_label, &b.to_label)));
+    }
+
     let state = GraphState {
         metadata: SessionMetadata {
             session_id: uuid::Uuid::new_v4().to_string(),
@@ -121,15 +603,36 @@ pub fn save_session_with_agents(
             node_count: nodes.len(),
             edge_count: edges.len(),
             agent_count: agents.len(),
+            format_version: FORMAT_VERSION,
             files_indexed: files_indexed.to_vec(),
+            population: colony.population(),
         },
         nodes,
         edges,
         agents: agents.to_vec(),
+        query_log: colony
+            .query_log()
+            .map(|log| log.entries())
+            .unwrap_or_default(),
+        documents,
+        embedding_storage: embeddings.storage(),
+        regions: colony.substrate().regions().to_vec(),
+        embedding_cache: if options.include_embedding_cache {
+            let cache = colony.substrate().embedding_cache();
+            cache
+                .entries()
+                .into_iter()
+                .map(|(term, values)| CachedEmbedding {
+                    term: term.to_string(),
+                    embedding: PackedEmbedding::pack(values, embeddings.storage()),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        },
     };
 
-    let json = serde_json::to_string_pretty(&state)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    let json = serde_json::to_string_pretty(&state).map_err(std::io::Error::other)?;
 
     // Create parent directory if needed
     if let Some(parent) = path.parent() {
@@ -139,16 +642,141 @@ pub fn save_session_with_agents(
     std::fs::write(path, json)
 }
 
-/// Load a saved session from JSON.
-pub fn load_session(path: &Path) -> std::io::Result<GraphState> {
-    let json = std::fs::read_to_string(path)?;
-    serde_json::from_str(&json).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+/// Load a saved session from JSON, upgrading it from an older format if
+/// need be (see [`LoadMode::Migrate`]).
+pub fn load_session(path: &Path) -> Result<GraphState, SessionError> {
+    load_session_with(path, LoadMode::Migrate).map(|(state, _)| state)
+}
+
+/// Load a saved session from JSON as strictly as `mode` says, reporting
+/// what had to be migrated, ignored or left out.
+pub fn load_session_with(
+    path: &Path,
+    mode: LoadMode,
+) -> Result<(GraphState, LoadReport), SessionError> {
+    parse_session(&std::fs::read_to_string(path)?, mode)
+}
+
+/// [`load_session_with`] for JSON already read.
+pub fn parse_session(json: &str, mode: LoadMode) -> Result<(GraphState, LoadReport), SessionError> {
+    let mut value: Value =
+        serde_json::from_str(json).map_err(|e| SessionError::Corrupt(e.to_string()))?;
+    if !value.is_object() {
+        return Err(SessionError::Corrupt("not a JSON object".to_string()));
+    }
+    let found = match value.pointer("/metadata/format_version") {
+        None => UNVERSIONED,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| SessionError::Corrupt(format!("invalid format_version {}", v)))?,
+    };
+    let mut report = LoadReport {
+        found_version: found,
+        ..LoadReport::default()
+    };
+
+    if found > FORMAT_VERSION && mode != LoadMode::BestEffort {
+        return Err(SessionError::UnsupportedVersion {
+            found,
+            supported: FORMAT_VERSION,
+        });
+    }
+    if found < FORMAT_VERSION {
+        if mode == LoadMode::Strict {
+            return Err(SessionError::NeedsMigration {
+                found,
+                current: FORMAT_VERSION,
+            });
+        }
+        for migration in MIGRATIONS.iter().filter(|m| m.from >= found) {
+            (migration.upgrade)(&mut value).map_err(|e| {
+                SessionError::Corrupt(format!(
+                    "migrating from version {} failed: {}",
+                    migration.from, e
+                ))
+            })?;
+            report.migrated_from.push(migration.from);
+        }
+    }
+    if let Some(metadata) = value.get_mut("metadata").and_then(Value::as_object_mut) {
+        // The JSON is in the current format now; newer sessions keep theirs
+        metadata.insert(
+            "format_version".to_string(),
+            found.max(FORMAT_VERSION).into(),
+        );
+    }
+
+    if mode == LoadMode::BestEffort {
+        report.skipped = drop_unreadable_entries(&mut value);
+    }
+    let state: GraphState =
+        serde_ignored::deserialize(value, |path| report.unknown_fields.push(path.to_string()))
+            .map_err(|e| SessionError::Corrupt(e.to_string()))?;
+    if mode == LoadMode::Strict && !report.unknown_fields.is_empty() {
+        return Err(SessionError::UnknownFields(report.unknown_fields));
+    }
+    Ok((state, report))
+}
+
+/// Remove the entries of the session's lists that do not deserialize,
+/// returning where they were and why.
+fn drop_unreadable_entries(session: &mut Value) -> Vec<String> {
+    fn retain_readable<T: DeserializeOwned>(
+        session: &mut Value,
+        key: &str,
+        skipped: &mut Vec<String>,
+    ) {
+        let Some(entries) = session.get_mut(key).and_then(Value::as_array_mut) else {
+            return;
+        };
+        let mut index = 0;
+        entries.retain(|entry| {
+            let readable = serde_json::from_value::<T>(entry.clone())
+                .map_err(|e| skipped.push(format!("{}.{}: {}", key, index, e)))
+                .is_ok();
+            index += 1;
+            readable
+        });
+    }
+
+    let mut skipped = Vec::new();
+    retain_readable::<SerializedNode>(session, "nodes", &mut skipped);
+    retain_readable::<SerializedEdge>(session, "edges", &mut skipped);
+    retain_readable::<SerializedAgent>(session, "agents", &mut skipped);
+    retain_readable::<QueryLogEntry>(session, "query_log", &mut skipped);
+    retain_readable::<SerializedDocument>(session, "documents", &mut skipped);
+    retain_readable::<Region>(session, "regions", &mut skipped);
+    retain_readable::<CachedEmbedding>(session, "embedding_cache", &mut skipped);
+    skipped
+}
+
+/// What [`restore_into_colony`] had to do beyond copying the session in,
+/// for callers to report.
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    /// Node labels the term index was rebuilt over, if it was rebuilt.
+    pub term_index_rebuilt: Option<usize>,
+    /// Population specs the registry could not build, with why.
+    pub respawn_failures: Vec<(AgentSpec, RegistryError)>,
+    /// [`integrity::check`](crate::integrity::check) of the restored colony.
+    pub integrity: IntegrityReport,
 }
 
 /// Restore a graph state into a colony.
-/// Adds all nodes and edges from the saved state.
+/// Adds all nodes and edges from the saved state, and the saved query log
+/// entries if the colony has query logging enabled. Saved embeddings are
+/// converted to the colony's embedding storage where it differs; saved
+/// term embeddings join the colony's embedding cache. The term index is
+/// rebuilt over the restored labels, as the returned [`RestoreReport`]
+/// records. The restored graph is then checked with
+/// [`integrity::check`](crate::integrity::check), and the report includes
+/// what the check found.
 ///
-/// Note: Agents must be restored separately using `state.agents` and
+/// With a `registry`, the population recorded in the session metadata is
+/// respawned from its specs; specs the registry cannot build are skipped
+/// and listed in the report. Agents saved with their full state must be
+/// restored separately using `state.agents` and
 /// `SerializableAgent::from_state()` for each agent type.
 ///
 /// # Example
@@ -157,7 +785,7 @@ pub fn load_session(path: &Path) -> std::io::Result<GraphState> {
 /// use phago_agents::digester::Digester;
 ///
 /// let state = load_session(&path)?;
-/// restore_into_colony(&mut colony, &state);
+/// restore_into_colony(&mut colony, &state, None);
 ///
 /// // Restore agents
 /// for agent_state in &state.agents {
@@ -166,7 +794,11 @@ pub fn load_session(path: &Path) -> std::io::Result<GraphState> {
 ///     }
 /// }
 /// ```
-pub fn restore_into_colony(colony: &mut Colony, state: &GraphState) {
+pub fn restore_into_colony(
+    colony: &mut Colony,
+    state: &GraphState,
+    registry: Option<&AgentRegistry>,
+) -> RestoreReport {
     use phago_core::substrate::Substrate;
     use std::collections::HashMap;
 
@@ -188,9 +820,28 @@ pub fn restore_into_colony(colony: &mut Colony, state: &GraphState) {
             position: Position::new(node.position_x, node.position_y),
             access_count: node.access_count,
             created_tick: node.created_tick,
-            embedding: node.embedding.clone(),
+            last_accessed_tick: node.last_accessed_tick,
+            tags: node.tags.clone(),
         };
         let id = colony.substrate_mut().add_node(data);
+        if let Some(embedding) = &node.embedding {
+            colony
+                .substrate_mut()
+                .embeddings_mut()
+                .insert_packed(id, embedding.clone());
+        }
+        for alias in &node.aliases {
+            colony.substrate_mut().graph_mut().add_alias(&id, alias);
+        }
+        for annotation in &node.annotations {
+            colony
+                .substrate_mut()
+                .graph_mut()
+                .annotate(&id, annotation.clone());
+        }
+        if let Some(score) = node.anomaly_score {
+            colony.set_anomaly_score(&id, score);
+        }
         label_to_id.insert(node.label.clone(), id);
     }
 
@@ -200,7 +851,7 @@ pub fn restore_into_colony(colony: &mut Colony, state: &GraphState) {
             label_to_id.get(&edge.from_label),
             label_to_id.get(&edge.to_label),
         ) {
-            colony.substrate_mut().set_edge(
+            let _ = colony.substrate_mut().set_edge(
                 from_id,
                 to_id,
                 EdgeData {
@@ -213,12 +864,117 @@ pub fn restore_into_colony(colony: &mut Colony, state: &GraphState) {
         }
     }
 
+    if let Some(log) = colony.query_log() {
+        log.extend(state.query_log.iter().cloned());
+    }
+
+    for region in &state.regions {
+        colony.substrate_mut().add_region(region.clone());
+    }
+
+    for cached in &state.embedding_cache {
+        colony
+            .substrate_mut()
+            .embedding_cache_mut()
+            .insert(&cached.term, cached.embedding.unpack());
+    }
+
+    let mut title_to_id: HashMap<&str, DocumentId> = HashMap::new();
+    for doc in &state.documents {
+        let mut contribution = DocumentContribution::default();
+        for (label, count) in &doc.nodes {
+            if let Some(&id) = label_to_id.get(label) {
+                contribution.add_node(id, *count);
+            }
+        }
+        for edge in &doc.edges {
+            if let (Some(&from_id), Some(&to_id)) = (
+                label_to_id.get(&edge.from_label),
+                label_to_id.get(&edge.to_label),
+            ) {
+                contribution.add_edge(from_id, to_id, edge.weight, edge.co_activations);
+            }
+        }
+        let id = DocumentId::new();
+        title_to_id.entry(doc.title.as_str()).or_insert(id);
+        colony.restore_document(
+            Document {
+                id,
+                title: doc.title.clone(),
+                content: doc.content.clone(),
+                position: Position::new(doc.position_x, doc.position_y),
+                digested: doc.digested,
+                tags: doc.tags.clone(),
+                version: doc.version,
+            },
+            contribution,
+        );
+        colony
+            .substrate_mut()
+            .set_passages(id, doc.passages.clone());
+        if let Some(expiry) = doc.expiry {
+            colony.set_document_expiry(&id, expiry);
+        }
+    }
+
+    // Edge provenance names documents, so it goes in after them
+    for edge in state.edges.iter().filter(|e| !e.sources.is_empty()) {
+        if let (Some(&from_id), Some(&to_id)) = (
+            label_to_id.get(&edge.from_label),
+            label_to_id.get(&edge.to_label),
+        ) {
+            let records: Vec<SourceRecord> = edge
+                .sources
+                .iter()
+                .filter_map(|source| {
+                    Some(match source {
+                        SerializedEdgeSource::Document { title, tick } => SourceRecord {
+                            source: EdgeSource::Document(*title_to_id.get(title.as_str())?),
+                            tick: *tick,
+                        },
+                        SerializedEdgeSource::Query { tick } => SourceRecord {
+                            source: EdgeSource::Query,
+                            tick: *tick,
+                        },
+                        SerializedEdgeSource::Unattributed { tick } => SourceRecord {
+                            source: EdgeSource::Unattributed,
+                            tick: *tick,
+                        },
+                        SerializedEdgeSource::Import { name, tick } => SourceRecord {
+                            source: colony.import_source(name),
+                            tick: *tick,
+                        },
+                    })
+                })
+                .collect();
+            colony.restore_edge_sources(from_id, to_id, records);
+        }
+    }
+
     // Advance colony tick to match the saved session
     // so that maturation/staleness calculations remain correct
     let target_tick = state.metadata.tick;
     while colony.stats().tick < target_tick {
         colony.substrate_mut().advance_tick();
     }
+
+    // Nodes went in through the substrate, but the index was invalidated
+    // along the way; rebuild it now instead of on the first tick
+    let mut report = RestoreReport::default();
+    if colony.sync_term_index() {
+        report.term_index_rebuilt = Some(colony.substrate().graph().node_count());
+    }
+
+    if let Some(registry) = registry {
+        for spec in &state.metadata.population {
+            if let Err(e) = colony.spawn_from_spec(registry, spec) {
+                report.respawn_failures.push((spec.clone(), e));
+            }
+        }
+    }
+
+    report.integrity = crate::integrity::check(colony);
+    report
 }
 
 /// Restore agents from a GraphState into a colony.
@@ -295,7 +1051,9 @@ mod tests {
 
         // Restore into new colony
         let mut restored = Colony::new();
-        restore_into_colony(&mut restored, &state);
+        let report = restore_into_colony(&mut restored, &state, None);
+        assert_eq!(report.term_index_rebuilt, Some(state.nodes.len()));
+        assert!(report.integrity.is_clean(), "{:?}", report.integrity);
 
         let (_identical, orig_n, _orig_e, rest_n, rest_e) = verify_fidelity(&colony, &restored);
         assert_eq!(orig_n, rest_n, "Node count should match");
@@ -305,6 +1063,402 @@ mod tests {
         std::fs::remove_file(&tmp).ok();
     }
 
+    #[test]
+    fn roundtrip_preserves_last_accessed_tick() {
+        use phago_core::substrate::Substrate;
+
+        let mut colony = Colony::new();
+        colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "membrane".to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 3,
+            created_tick: 2,
+            last_accessed_tick: 40,
+            tags: Default::default(),
+        });
+
+        let tmp = std::env::temp_dir().join("phago_session_last_accessed.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &state, None);
+        let graph = restored.substrate().graph();
+        let node = graph
+            .get_node(&graph.find_nodes_by_exact_label("membrane")[0])
+            .unwrap();
+        assert_eq!(node.created_tick, 2);
+        assert_eq!(node.last_accessed_tick, 40);
+    }
+
+    #[test]
+    fn roundtrip_preserves_tags() {
+        use phago_core::substrate::Substrate;
+
+        let mut colony = Colony::new();
+        colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "project".to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 3,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: [("work".to_string(), 2), ("personal".to_string(), 1)].into(),
+        });
+
+        let tmp = std::env::temp_dir().join("phago_session_tags.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &state, None);
+        let graph = restored.substrate().graph();
+        let node = graph
+            .get_node(&graph.find_nodes_by_exact_label("project")[0])
+            .unwrap();
+        assert_eq!(node.tags.get("work"), Some(&2));
+        assert_eq!(node.tags.get("personal"), Some(&1));
+    }
+
+    #[test]
+    fn roundtrip_preserves_query_log() {
+        use crate::colony::ColonyConfig;
+        use crate::query_log::{QueryLogConfig, QuerySource};
+
+        let logging = || ColonyConfig {
+            query_log: QueryLogConfig::enabled(),
+            ..Default::default()
+        };
+        let colony = Colony::from_config(logging());
+        let terms = vec!["cell".to_string(), "wall".to_string()];
+        let mut entry = QueryLogEntry::new(4, QuerySource::Mcp, "cell wall", terms);
+        entry.results = vec!["cell".to_string()];
+        colony.log_query(entry.clone());
+
+        let tmp = std::env::temp_dir().join("phago_session_query_log.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+        assert_eq!(state.query_log, vec![entry.clone()]);
+
+        let mut restored = Colony::from_config(logging());
+        restore_into_colony(&mut restored, &state, None);
+        assert_eq!(restored.query_log().unwrap().entries(), vec![entry]);
+
+        // A colony without logging ignores saved entries
+        let mut plain = Colony::new();
+        restore_into_colony(&mut plain, &state, None);
+        assert!(plain.query_log().is_none());
+    }
+
+    #[test]
+    fn roundtrip_preserves_documents() {
+        use phago_agents::digester::Digester;
+
+        let mut colony = Colony::new();
+        colony.ingest_document_tagged(
+            "notes.md",
+            "cell membrane protein transport",
+            Position::new(1.0, 2.0),
+            &["work"],
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(1.0, 2.0)).with_max_idle(50),
+        ));
+        colony.run(10);
+
+        let tmp = std::env::temp_dir().join("phago_session_documents.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+        assert_eq!(state.documents.len(), 1);
+        let doc = &state.documents[0];
+        assert!(doc.digested);
+        assert_eq!(doc.tags, vec!["work"]);
+        assert_eq!(doc.nodes.get("membrane"), Some(&1));
+        assert!(!doc.edges.is_empty());
+        assert_eq!(doc.passages.spans("membrane"), &[TextSpan::new(0, 31)]);
+
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &state, None);
+        let docs = restored.substrate().all_documents();
+        assert_eq!(docs.len(), 1);
+        let id = docs[0].id;
+        let contribution = restored.document_contribution(&id).unwrap();
+        assert_eq!(contribution.nodes.len(), doc.nodes.len());
+        assert_eq!(contribution.edges.len(), doc.edges.len());
+        assert_eq!(restored.substrate().passages(&id), Some(&doc.passages));
+
+        // Re-ingesting it in the restored session updates it
+        let updated = restored.ingest_or_update_document_tagged(
+            "notes.md",
+            "cell membrane",
+            Position::new(1.0, 2.0),
+            &["work"],
+        );
+        assert_eq!(updated, id);
+        assert_eq!(restored.substrate().get_document(&id).unwrap().version, 2);
+    }
+
+    #[test]
+    fn roundtrip_preserves_document_ttls() {
+        let mut colony = Colony::new();
+        let notes = colony.ingest_document("notes.md", "daily standup", Position::new(0.0, 0.0));
+        colony.ingest_document("reference.md", "cell membrane", Position::new(1.0, 0.0));
+        colony.run(3);
+        assert!(colony.set_document_ttl(&notes, 10));
+        assert!(colony.set_document_ttl_seconds(&notes, 3600));
+        let expiry = colony.document_expiry(&notes).unwrap();
+        assert_eq!(expiry.tick, Some(13));
+
+        let tmp = std::env::temp_dir().join("phago_session_ttls.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &state, None);
+        let docs = restored.substrate().all_documents();
+        let id = |title: &str| docs.iter().find(|d| d.title == title).unwrap().id;
+        assert_eq!(restored.document_expiry(&id("notes.md")), Some(expiry));
+        assert_eq!(restored.document_expiry(&id("reference.md")), None);
+        assert_eq!(restored.stats().next_expiry_tick, Some(13));
+    }
+
+    #[test]
+    fn roundtrip_preserves_aliases() {
+        use phago_core::substrate::Substrate;
+
+        let mut colony = Colony::new();
+        let node = |label: &str| NodeData {
+            id: NodeId::new(),
+            label: label.into(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        };
+        let membrane = colony.substrate_mut().add_node(node("membrane"));
+        let membranes = colony.substrate_mut().add_node(node("membranes"));
+        colony.merge_nodes(membrane, &[membranes]).unwrap();
+
+        let tmp = std::env::temp_dir().join("phago_session_aliases.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+        assert_eq!(state.nodes.len(), 1);
+        assert_eq!(state.nodes[0].aliases, vec!["membranes"]);
+
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &state, None);
+        let graph = restored.substrate().graph();
+        let found = graph.find_nodes_by_exact_label("Membranes");
+        assert_eq!(found, graph.find_nodes_by_exact_label("membrane"));
+        assert_eq!(graph.get_node(&found[0]).unwrap().access_count, 2);
+    }
+
+    #[test]
+    fn roundtrip_preserves_annotations() {
+        use phago_core::substrate::Substrate;
+
+        let mut colony = Colony::new();
+        colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "membrane".into(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+        colony
+            .annotate("membrane", "bounds the cell", "mcp")
+            .unwrap();
+
+        let tmp = std::env::temp_dir().join("phago_session_annotations.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+        assert_eq!(state.nodes[0].annotations.len(), 1);
+
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &state, None);
+        let id = restored
+            .substrate()
+            .graph()
+            .find_nodes_by_exact_label("membrane")[0];
+        assert_eq!(restored.annotations(&id), state.nodes[0].annotations);
+        assert_eq!(restored.annotations(&id)[0].text, "bounds the cell");
+    }
+
+    #[test]
+    fn roundtrip_preserves_anomaly_scores() {
+        use phago_core::substrate::Substrate;
+
+        let mut colony = Colony::new();
+        let id = colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "[ANOMALY] odd reading".into(),
+            node_type: NodeType::Anomaly,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+        assert!(colony.set_anomaly_score(&id, 0.75));
+
+        let tmp = std::env::temp_dir().join("phago_session_anomaly_scores.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &state, None);
+        let id = restored
+            .substrate()
+            .graph()
+            .find_nodes_by_exact_label("[ANOMALY] odd reading")[0];
+        assert_eq!(restored.anomaly_score(&id), Some(0.75));
+    }
+
+    /// Save a colony holding one embedded node under `storage`, and load
+    /// the session back.
+    fn embedded_session(
+        storage: EmbeddingStorage,
+        embedding: &[f32],
+        options: SessionOptions,
+    ) -> GraphState {
+        use crate::colony::ColonyConfig;
+        use phago_core::substrate::Substrate;
+
+        let mut colony = Colony::from_config(ColonyConfig {
+            embedding_storage: storage,
+            ..Default::default()
+        });
+        let id = colony.substrate_mut().add_node(NodeData {
+            id: NodeId::new(),
+            label: "membrane".to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        });
+        colony.substrate_mut().set_embedding(id, embedding);
+
+        let tmp = std::env::temp_dir().join(format!("phago_session_embedding_{storage:?}.json"));
+        save_session_with_options(&colony, &tmp, &[], &[], options).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+        state
+    }
+
+    fn restored_embedding(state: &GraphState, storage: EmbeddingStorage) -> Option<Vec<f32>> {
+        use crate::colony::ColonyConfig;
+
+        let mut restored = Colony::from_config(ColonyConfig {
+            embedding_storage: storage,
+            ..Default::default()
+        });
+        restore_into_colony(&mut restored, state, None);
+        let id = restored
+            .substrate()
+            .graph()
+            .find_nodes_by_exact_label("membrane")[0];
+        restored.substrate().embedding(&id)
+    }
+
+    #[test]
+    fn roundtrip_preserves_embeddings_in_their_storage() {
+        let embedding = [0.1f32, -0.7, 0.333_333_34, 1e-7, 0.5];
+
+        let full = embedded_session(EmbeddingStorage::F32, &embedding, SessionOptions::default());
+        assert_eq!(full.embedding_storage, EmbeddingStorage::F32);
+        assert_eq!(
+            restored_embedding(&full, EmbeddingStorage::F32).as_deref(),
+            Some(&embedding[..])
+        );
+
+        for (storage, tolerance) in [
+            (EmbeddingStorage::F16, 1e-3),
+            (EmbeddingStorage::UNIT_I8, 0.5 / 127.0),
+        ] {
+            let state = embedded_session(storage, &embedding, SessionOptions::default());
+            assert_eq!(state.embedding_storage, storage);
+            let packed = state.nodes[0].embedding.as_ref().unwrap();
+            assert_eq!(packed.storage(), storage);
+            // Restoring at full precision keeps exactly what was saved
+            let restored = restored_embedding(&state, EmbeddingStorage::F32).unwrap();
+            assert_eq!(restored, packed.unpack());
+            for (saved, original) in restored.iter().zip(&embedding) {
+                assert!(
+                    (saved - original).abs() <= tolerance,
+                    "{storage:?}: {saved}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sessions_can_leave_out_embeddings() {
+        let state = embedded_session(
+            EmbeddingStorage::F32,
+            &[0.5, 0.5],
+            SessionOptions {
+                include_embeddings: false,
+                ..Default::default()
+            },
+        );
+        assert!(state.nodes[0].embedding.is_none());
+        assert!(restored_embedding(&state, EmbeddingStorage::F32).is_none());
+
+        // Sessions saved before the storage was recorded hold plain arrays
+        let legacy: SerializedNode = serde_json::from_str(
+            r#"{"label":"cell","node_type":"Concept","access_count":1,
+                "position_x":0.0,"position_y":0.0,"embedding":[0.25,-1.0]}"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.embedding.unwrap().unpack(), vec![0.25, -1.0]);
+    }
+
+    #[test]
+    fn embedding_cache_is_saved_when_asked() {
+        let mut colony = Colony::new();
+        let cache = colony.substrate_mut().embedding_cache_mut();
+        cache.insert("membrane", vec![0.5, -0.5]);
+        cache.insert("cell", vec![1.0, 0.0]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+
+        save_session(&colony, &path, &[]).unwrap();
+        assert!(load_session(&path).unwrap().embedding_cache.is_empty());
+
+        let options = SessionOptions {
+            include_embedding_cache: true,
+            ..Default::default()
+        };
+        save_session_with_options(&colony, &path, &[], &[], options).unwrap();
+        let state = load_session(&path).unwrap();
+        let terms: Vec<_> = state.embedding_cache.iter().map(|c| &c.term).collect();
+        assert_eq!(terms, ["membrane", "cell"]);
+
+        let mut restored = Colony::new();
+        restore_into_colony(&mut restored, &state, None);
+        let cache = restored.substrate().embedding_cache();
+        assert_eq!(cache.get("Membrane"), Some(vec![0.5, -0.5]));
+        assert_eq!(cache.get("cell"), Some(vec![1.0, 0.0]));
+    }
+
     #[test]
     fn save_load_with_agent_state() {
         use phago_agents::digester::Digester;
@@ -339,7 +1493,7 @@ mod tests {
 
         // Restore into new colony
         let mut restored = Colony::new();
-        restore_into_colony(&mut restored, &state);
+        restore_into_colony(&mut restored, &state, None);
         let agents_restored = restore_agents(&mut restored, &state);
         assert_eq!(agents_restored, 1, "Should restore one agent");
         assert_eq!(restored.alive_count(), 1, "Colony should have one agent");
@@ -371,4 +1525,81 @@ mod tests {
             "Vocabulary should be preserved"
         );
     }
+
+    #[test]
+    fn roundtrip_respawns_population_from_specs() {
+        use phago_agents::digester::Digester;
+        use serde_json::json;
+
+        let registry = AgentRegistry::new();
+        let specs = [
+            AgentSpec::new(
+                "digester",
+                json!({"position": {"x": 1.0, "y": 2.0}, "max_idle": 80}),
+            ),
+            AgentSpec::new("digester", json!({"seed": 7})),
+            AgentSpec::new(
+                "sentinel",
+                json!({"position": {"x": 3.0, "y": 3.0}, "sense_radius": 15.0,
+                       "anomaly_thresholds": {"low": 0.1, "high": 0.9}}),
+            ),
+            AgentSpec::new(
+                "synthesizer",
+                json!({"position": {"x": -4.0, "y": 0.5}, "emerge": {"quorum_threshold": 1.5}}),
+            ),
+        ];
+        let mut colony = Colony::new();
+        colony.ingest_document("cells", "cell membrane protein", Position::new(0.0, 0.0));
+        for spec in &specs {
+            colony.spawn_from_spec(&registry, spec).unwrap();
+        }
+        // Agents spawned directly are not part of the population
+        colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+        assert_eq!(colony.population(), specs);
+
+        let tmp = std::env::temp_dir().join("phago_session_population.json");
+        save_session(&colony, &tmp, &[]).unwrap();
+        let state = load_session(&tmp).unwrap();
+        std::fs::remove_file(&tmp).ok();
+
+        let mut without = Colony::new();
+        restore_into_colony(&mut without, &state, None);
+        assert_eq!(without.alive_count(), 0);
+
+        // Specs the registry cannot build are skipped and reported
+        let mut unbuilt = Colony::new();
+        let report = restore_into_colony(&mut unbuilt, &state, Some(&AgentRegistry::empty()));
+        assert_eq!(unbuilt.alive_count(), 0);
+        let failed: Vec<&AgentSpec> = report.respawn_failures.iter().map(|(s, _)| s).collect();
+        assert_eq!(failed, specs.iter().collect::<Vec<_>>());
+        assert!(matches!(
+            report.respawn_failures[0].1,
+            RegistryError::UnknownType(_)
+        ));
+
+        let mut restored = Colony::new();
+        let report = restore_into_colony(&mut restored, &state, Some(&registry));
+        assert!(report.respawn_failures.is_empty());
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for agent in restored.agents() {
+            *counts.entry(agent.agent_type()).or_insert(0) += 1;
+        }
+        assert_eq!(
+            counts,
+            BTreeMap::from([("digester", 2), ("sentinel", 1), ("synthesizer", 1)])
+        );
+        assert_eq!(restored.population(), specs);
+
+        let sentinel = restored
+            .agents()
+            .iter()
+            .find(|a| a.agent_type() == "sentinel")
+            .unwrap();
+        assert_eq!(sentinel.position(), Position::new(3.0, 3.0));
+        let params = &restored.agent_spec(&sentinel.id()).unwrap().params;
+        assert_eq!(params["sense_radius"], 15.0);
+        assert_eq!(params["anomaly_thresholds"]["high"], 0.9);
+        let seeded = Digester::with_seed(Position::new(0.0, 0.0), 7).id();
+        assert!(restored.agents().iter().any(|a| a.id() == seeded));
+    }
 }
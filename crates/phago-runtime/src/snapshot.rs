@@ -0,0 +1,506 @@
+//! Recording snapshot series, and loading them back from disk.
+//!
+//! A [`SnapshotSeries`] keeps every snapshot in full, only the stats and
+//! agents, or a full first snapshot followed by graph deltas; see
+//! [`SnapshotMode`]. Long runs of big colonies need one of the latter two:
+//! every full snapshot holds every node and edge.
+//!
+//! Snapshot and event series are written either as a single JSON array or
+//! as JSONL, one record per line. The loaders here accept both, so a run
+//! recorded by any tool can be replayed or visualized later.
+
+use crate::colony::{
+    AgentSnapshot, ColonyEvent, ColonySnapshot, ColonyStats, EdgeSnapshot, NodeSnapshot,
+};
+use crate::region::Region;
+use phago_core::types::{NodeId, Rect, Tick};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// How a [`SnapshotSeries`] stores the snapshots recorded into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotMode {
+    /// Every snapshot in full.
+    #[default]
+    Full,
+    /// Stats and agents only, without nodes and edges.
+    StatsOnly,
+    /// The first snapshot in full, then the nodes and edges added, changed
+    /// and removed since the one before.
+    Delta,
+}
+
+impl std::str::FromStr for SnapshotMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(SnapshotMode::Full),
+            "stats" | "stats-only" => Ok(SnapshotMode::StatsOnly),
+            "delta" => Ok(SnapshotMode::Delta),
+            other => Err(format!(
+                "Unknown snapshot mode '{other}'. Use: full, stats-only, delta"
+            )),
+        }
+    }
+}
+
+/// A snapshot without the graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    pub tick: Tick,
+    pub agents: Vec<AgentSnapshot>,
+    pub stats: ColonyStats,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_bounds: Option<Rect>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<Region>,
+}
+
+/// A snapshot as the graph changes since the previous one.
+///
+/// Nodes are matched by id. Edges are matched by their labels and
+/// referred to by their position in the previous frame's materialized
+/// edges: decay reweights most edges every tick, so a changed edge carries
+/// only its new weight.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+    pub tick: Tick,
+    pub agents: Vec<AgentSnapshot>,
+    pub stats: ColonyStats,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world_bounds: Option<Rect>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<Region>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added_nodes: Vec<NodeSnapshot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_nodes: Vec<NodeSnapshot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_nodes: Vec<NodeId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added_edges: Vec<EdgeSnapshot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_edges: Vec<EdgeChange>,
+    /// Positions of the removed edges.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_edges: Vec<u32>,
+}
+
+/// New values of an edge of the previous frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EdgeChange {
+    /// Position of the edge in the previous frame's materialized edges.
+    pub index: u32,
+    pub weight: f64,
+    /// Set when the co-activation count changed too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_activations: Option<u64>,
+}
+
+/// One recorded snapshot of a [`SnapshotSeries`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapshotFrame {
+    Full(ColonySnapshot),
+    Stats(StatsSnapshot),
+    /// Changes to the frame before.
+    Delta(SnapshotDelta),
+}
+
+impl SnapshotFrame {
+    pub fn tick(&self) -> Tick {
+        match self {
+            SnapshotFrame::Full(s) => s.tick,
+            SnapshotFrame::Stats(s) => s.tick,
+            SnapshotFrame::Delta(d) => d.tick,
+        }
+    }
+
+    pub fn agents(&self) -> &[AgentSnapshot] {
+        match self {
+            SnapshotFrame::Full(s) => &s.agents,
+            SnapshotFrame::Stats(s) => &s.agents,
+            SnapshotFrame::Delta(d) => &d.agents,
+        }
+    }
+
+    pub fn stats(&self) -> &ColonyStats {
+        match self {
+            SnapshotFrame::Full(s) => &s.stats,
+            SnapshotFrame::Stats(s) => &s.stats,
+            SnapshotFrame::Delta(d) => &d.stats,
+        }
+    }
+}
+
+/// Snapshots recorded over a run, stored according to a [`SnapshotMode`].
+///
+/// Record with [`Colony::record_snapshot`](crate::colony::Colony::record_snapshot)
+/// and read back with [`materialize`](Self::materialize), which rebuilds
+/// the snapshot at an index whatever the mode.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotSeries {
+    mode: SnapshotMode,
+    frames: Vec<SnapshotFrame>,
+    /// The graph of the last frame, in materialized order, which the next
+    /// delta is taken against. A series read back from disk starts its
+    /// next delta with a full frame.
+    #[serde(skip)]
+    last_graph: Option<Graph>,
+}
+
+impl SnapshotSeries {
+    pub fn new(mode: SnapshotMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    pub fn mode(&self) -> SnapshotMode {
+        self.mode
+    }
+
+    pub fn frames(&self) -> &[SnapshotFrame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Record `snapshot`, keeping as much of it as the mode calls for.
+    pub fn push(&mut self, snapshot: ColonySnapshot) {
+        let frame = match self.mode {
+            SnapshotMode::Full => SnapshotFrame::Full(snapshot),
+            SnapshotMode::StatsOnly => SnapshotFrame::Stats(StatsSnapshot {
+                tick: snapshot.tick,
+                agents: snapshot.agents,
+                stats: snapshot.stats,
+                world_bounds: snapshot.world_bounds,
+                regions: snapshot.regions,
+            }),
+            SnapshotMode::Delta => self.delta_frame(snapshot),
+        };
+        self.frames.push(frame);
+    }
+
+    /// Record a snapshot taken without the graph. Only stats-only series
+    /// can do without it.
+    pub(crate) fn push_stats(&mut self, snapshot: StatsSnapshot) {
+        debug_assert_eq!(self.mode, SnapshotMode::StatsOnly);
+        self.frames.push(SnapshotFrame::Stats(snapshot));
+    }
+
+    fn delta_frame(&mut self, snapshot: ColonySnapshot) -> SnapshotFrame {
+        let Some(last) = self.last_graph.take() else {
+            self.last_graph = Some(Graph {
+                nodes: snapshot.nodes.clone(),
+                edges: snapshot.edges.clone(),
+            });
+            return SnapshotFrame::Full(snapshot);
+        };
+
+        let mut delta = SnapshotDelta {
+            tick: snapshot.tick,
+            agents: snapshot.agents,
+            stats: snapshot.stats,
+            world_bounds: snapshot.world_bounds,
+            regions: snapshot.regions,
+            added_nodes: Vec::new(),
+            changed_nodes: Vec::new(),
+            removed_nodes: Vec::new(),
+            added_edges: Vec::new(),
+            changed_edges: Vec::new(),
+            removed_edges: Vec::new(),
+        };
+        let mut next = Graph::default();
+
+        // Nodes and edges still there keep their places; new ones follow
+        let current: HashMap<NodeId, &NodeSnapshot> =
+            snapshot.nodes.iter().map(|n| (n.id, n)).collect();
+        for old in &last.nodes {
+            match current.get(&old.id) {
+                Some(&node) => {
+                    if node != old {
+                        delta.changed_nodes.push(node.clone());
+                    }
+                    next.nodes.push(node.clone());
+                }
+                None => delta.removed_nodes.push(old.id),
+            }
+        }
+        let known: HashSet<NodeId> = last.nodes.iter().map(|n| n.id).collect();
+        delta.added_nodes = snapshot
+            .nodes
+            .iter()
+            .filter(|n| !known.contains(&n.id))
+            .cloned()
+            .collect();
+        next.nodes.extend(delta.added_nodes.iter().cloned());
+
+        let current: HashMap<(&str, &str), &EdgeSnapshot> =
+            snapshot.edges.iter().map(|e| (edge_key(e), e)).collect();
+        for (index, old) in last.edges.iter().enumerate() {
+            let index = index as u32;
+            match current.get(&edge_key(old)) {
+                Some(&edge) => {
+                    if edge != old {
+                        delta.changed_edges.push(EdgeChange {
+                            index,
+                            weight: edge.weight,
+                            co_activations: (edge.co_activations != old.co_activations)
+                                .then_some(edge.co_activations),
+                        });
+                    }
+                    next.edges.push(edge.clone());
+                }
+                None => delta.removed_edges.push(index),
+            }
+        }
+        let known: HashSet<(&str, &str)> = last.edges.iter().map(edge_key).collect();
+        delta.added_edges = snapshot
+            .edges
+            .iter()
+            .filter(|e| !known.contains(&edge_key(e)))
+            .cloned()
+            .collect();
+        next.edges.extend(delta.added_edges.iter().cloned());
+
+        self.last_graph = Some(next);
+        SnapshotFrame::Delta(delta)
+    }
+
+    /// The snapshot recorded at `idx`, rebuilt from the frames up to it.
+    ///
+    /// Stats-only frames come back with no nodes or edges. Frames rebuilt
+    /// from deltas equal the recorded snapshot up to order: nodes and edges
+    /// keep the order they first appeared in.
+    pub fn materialize(&self, idx: usize) -> Option<ColonySnapshot> {
+        self.frames.get(idx)?;
+        let start = self.frames[..=idx]
+            .iter()
+            .rposition(|frame| !matches!(frame, SnapshotFrame::Delta(_)))
+            .unwrap_or(0);
+        let mut materializer = Materializer::default();
+        let mut snapshot = None;
+        for frame in &self.frames[start..=idx] {
+            snapshot = Some(materializer.apply(frame));
+        }
+        snapshot
+    }
+
+    /// Every snapshot, materialized. Holds the whole series in full; prefer
+    /// [`materialize`](Self::materialize) for big colonies.
+    pub fn to_snapshots(&self) -> Vec<ColonySnapshot> {
+        let mut materializer = Materializer::default();
+        self.frames.iter().map(|f| materializer.apply(f)).collect()
+    }
+
+    /// Agents of the frame with the most agents alive (peak activity).
+    pub fn peak_agents(&self) -> Option<&[AgentSnapshot]> {
+        self.frames
+            .iter()
+            .map(SnapshotFrame::agents)
+            .max_by_key(|agents| agents.len())
+    }
+}
+
+impl From<Vec<ColonySnapshot>> for SnapshotSeries {
+    fn from(snapshots: Vec<ColonySnapshot>) -> Self {
+        Self {
+            mode: SnapshotMode::Full,
+            frames: snapshots.into_iter().map(SnapshotFrame::Full).collect(),
+            last_graph: None,
+        }
+    }
+}
+
+fn edge_key(edge: &EdgeSnapshot) -> (&str, &str) {
+    (&edge.from_label, &edge.to_label)
+}
+
+/// The nodes and edges of a frame.
+#[derive(Debug, Clone, Default)]
+struct Graph {
+    nodes: Vec<NodeSnapshot>,
+    edges: Vec<EdgeSnapshot>,
+}
+
+/// Rebuilds snapshots frame by frame.
+#[derive(Default)]
+struct Materializer {
+    graph: Graph,
+}
+
+impl Materializer {
+    fn apply(&mut self, frame: &SnapshotFrame) -> ColonySnapshot {
+        match frame {
+            SnapshotFrame::Full(snapshot) => {
+                self.graph = Graph {
+                    nodes: snapshot.nodes.clone(),
+                    edges: snapshot.edges.clone(),
+                };
+                snapshot.clone()
+            }
+            SnapshotFrame::Stats(s) => {
+                self.graph = Graph::default();
+                ColonySnapshot {
+                    tick: s.tick,
+                    agents: s.agents.clone(),
+                    nodes: Vec::new(),
+                    edges: Vec::new(),
+                    stats: s.stats.clone(),
+                    world_bounds: s.world_bounds,
+                    regions: s.regions.clone(),
+                    coverage: None,
+                }
+            }
+            SnapshotFrame::Delta(d) => {
+                let graph = &mut self.graph;
+
+                let removed: HashSet<NodeId> = d.removed_nodes.iter().copied().collect();
+                let changed: HashMap<NodeId, &NodeSnapshot> =
+                    d.changed_nodes.iter().map(|n| (n.id, n)).collect();
+                graph.nodes.retain(|n| !removed.contains(&n.id));
+                for node in &mut graph.nodes {
+                    if let Some(&new) = changed.get(&node.id) {
+                        *node = new.clone();
+                    }
+                }
+                graph.nodes.extend(d.added_nodes.iter().cloned());
+
+                // Changes and removals refer to the previous positions
+                for change in &d.changed_edges {
+                    if let Some(edge) = graph.edges.get_mut(change.index as usize) {
+                        edge.weight = change.weight;
+                        if let Some(co_activations) = change.co_activations {
+                            edge.co_activations = co_activations;
+                        }
+                    }
+                }
+                let removed: HashSet<u32> = d.removed_edges.iter().copied().collect();
+                let mut index = 0;
+                graph.edges.retain(|_| {
+                    index += 1;
+                    !removed.contains(&(index - 1))
+                });
+                graph.edges.extend(d.added_edges.iter().cloned());
+
+                ColonySnapshot {
+                    tick: d.tick,
+                    agents: d.agents.clone(),
+                    nodes: graph.nodes.clone(),
+                    edges: graph.edges.clone(),
+                    stats: d.stats.clone(),
+                    world_bounds: d.world_bounds,
+                    regions: d.regions.clone(),
+                    coverage: None,
+                }
+            }
+        }
+    }
+}
+
+/// Load a series of snapshots from a JSON array or a JSONL file.
+pub fn load_snapshots(path: &Path) -> std::io::Result<Vec<ColonySnapshot>> {
+    parse_records(&std::fs::read_to_string(path)?)
+}
+
+/// Load a [`SnapshotSeries`] written as JSON, or a series of full
+/// snapshots as [`load_snapshots`] reads them.
+pub fn load_snapshot_series(path: &Path) -> std::io::Result<SnapshotSeries> {
+    let text = std::fs::read_to_string(path)?;
+    if let Ok(series) = serde_json::from_str::<SnapshotSeries>(&text) {
+        return Ok(series);
+    }
+    parse_records(&text).map(SnapshotSeries::from)
+}
+
+/// Load a series of `(tick, event)` records from a JSON array or a JSONL file.
+pub fn load_events(path: &Path) -> std::io::Result<Vec<(Tick, ColonyEvent)>> {
+    parse_records(&std::fs::read_to_string(path)?)
+}
+
+/// Parse `text` as a JSON array of records, or failing that as JSONL.
+/// Blank lines in JSONL are skipped.
+pub fn parse_records<T: DeserializeOwned>(text: &str) -> std::io::Result<Vec<T>> {
+    if text.trim_start().starts_with('[') {
+        return serde_json::from_str(text).map_err(|e| Error::new(ErrorKind::InvalidData, e));
+    }
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, format!("line {}: {e}", i + 1)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colony::Colony;
+    use phago_agents::digester::Digester;
+    use phago_core::types::Position;
+
+    fn recorded_snapshots() -> Vec<ColonySnapshot> {
+        let mut colony = Colony::new();
+        colony.ingest_document(
+            "Cells",
+            "cell membrane protein transport",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(80),
+        ));
+        (0..3)
+            .map(|_| {
+                colony.run(5);
+                colony.snapshot()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn loads_arrays_and_jsonl() {
+        let snapshots = recorded_snapshots();
+        let array = serde_json::to_string(&snapshots).unwrap();
+        let jsonl: String = snapshots
+            .iter()
+            .map(|s| s.to_json().unwrap() + "\n\n")
+            .collect();
+
+        for (name, text) in [("array", array), ("jsonl", jsonl)] {
+            let path = std::env::temp_dir().join(format!("phago_snapshots_{name}.json"));
+            std::fs::write(&path, text).unwrap();
+            let loaded = load_snapshots(&path).unwrap();
+            std::fs::remove_file(&path).ok();
+
+            assert_eq!(loaded.len(), snapshots.len(), "{name}");
+            for (a, b) in loaded.iter().zip(&snapshots) {
+                assert_eq!(a.to_json().unwrap(), b.to_json().unwrap(), "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn reports_the_failing_line() {
+        let good = recorded_snapshots()[0].to_json().unwrap();
+        let err =
+            parse_records::<ColonySnapshot>(&format!("{good}\n{{\"tick\": 1}}\n")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2:"), "{err}");
+    }
+}
@@ -2,24 +2,138 @@
 //!
 //! Provides persistent storage for large knowledge graphs that don't fit in memory.
 //! Uses SQLite for storage with indexes on node labels and edge endpoints.
+//!
+//! # Caching and write-behind
+//!
+//! The `TopologyGraph` trait hands out references from `&self`, so rows are
+//! loaded lazily into boxed cache entries that stay put until the next
+//! `&mut self` call. Mutations land in the cache first and are marked dirty;
+//! the dirty set is written in a single transaction on [`TopologyGraph::flush`]
+//! (the colony calls it once per tick), before any query that has to consult
+//! the tables, and on drop. Flushing also evicts the least recently used
+//! nodes once the cache grows past `cache_size`.
+//!
+//! Edges are undirected and stored once, with the smaller node ID in `from_id`.
+//!
+//! Node embeddings are not stored here: they live in the substrate's
+//! [`EmbeddingTable`](crate::embedding_store::EmbeddingTable) and persist
+//! with sessions. The `embedding` column is kept so older databases open
+//! unchanged, but is no longer read or written.
 
 #![cfg(feature = "sqlite")]
 
-use phago_core::topology::TopologyGraph;
+use crate::topology_impl::PetTopologyGraph;
+use elsa::sync::FrozenMap;
+use phago_core::topology::{TopologyError, TopologyGraph};
 use phago_core::types::*;
-use rusqlite::{params, Connection, Result as SqlResult};
-use std::collections::HashMap;
+use rusqlite::{params, Connection, Result as SqlResult, Row};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use std::sync::{Arc, Mutex};
 
+/// Current on-disk schema version (stored in `PRAGMA user_version`).
+const SCHEMA_VERSION: i64 = 3;
+
+/// Edge cache budget relative to the node cache size.
+const EDGES_PER_CACHED_NODE: usize = 8;
+
+const NODE_COLUMNS: &str =
+    "id, label, node_type, position_x, position_y, access_count, created_tick, last_accessed_tick, tags";
+const EDGE_COLUMNS: &str =
+    "from_id, to_id, weight, co_activations, created_tick, last_activated_tick";
+
+/// Canonical key for an undirected edge.
+type EdgeKey = (NodeId, NodeId);
+
+fn edge_key(a: NodeId, b: NodeId) -> EdgeKey {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn parse_node_id(s: &str) -> NodeId {
+    NodeId(uuid::Uuid::parse_str(s).unwrap_or_default())
+}
+
+/// Least-recently-used bookkeeping that can be updated through `&self`.
+struct Recency<K> {
+    clock: AtomicU64,
+    stamps: Mutex<HashMap<K, u64>>,
+}
+
+impl<K: Eq + Hash + Copy> Recency<K> {
+    fn new() -> Self {
+        Self {
+            clock: AtomicU64::new(0),
+            stamps: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn touch(&self, key: K) {
+        let stamp = self.clock.fetch_add(1, AtomicOrdering::Relaxed);
+        self.stamps.lock().unwrap().insert(key, stamp);
+    }
+
+    fn forget(&mut self, key: &K) {
+        self.stamps.get_mut().unwrap().remove(key);
+    }
+
+    fn clear(&mut self) {
+        self.stamps.get_mut().unwrap().clear();
+    }
+
+    /// Remove and return the oldest keys so that at most `keep` remain.
+    fn evict(&mut self, keep: usize) -> Vec<K> {
+        let stamps = self.stamps.get_mut().unwrap();
+        if stamps.len() <= keep {
+            return Vec::new();
+        }
+        let mut by_age: Vec<(K, u64)> = stamps.iter().map(|(k, s)| (*k, *s)).collect();
+        by_age.sort_by_key(|(_, s)| *s);
+        let excess = by_age.len() - keep;
+        by_age
+            .into_iter()
+            .take(excess)
+            .map(|(k, _)| {
+                stamps.remove(&k);
+                k
+            })
+            .collect()
+    }
+}
+
+/// Cached entries that differ from what is stored in the database.
+#[derive(Default)]
+struct DirtySet {
+    nodes: HashSet<NodeId>,
+    edges: HashSet<EdgeKey>,
+}
+
+impl DirtySet {
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+}
+
 /// SQLite-backed implementation of the topology graph.
 ///
 /// Stores nodes and edges in SQLite tables with appropriate indexes.
 /// Supports both in-memory and file-backed databases.
 pub struct SqliteTopologyGraph {
     conn: Arc<Mutex<Connection>>,
-    /// Cache for frequently accessed nodes (LRU-style, limited size)
-    node_cache: HashMap<NodeId, NodeData>,
+    /// Hot nodes, loaded on first access.
+    node_cache: FrozenMap<NodeId, Box<NodeData>>,
+    node_recency: Recency<NodeId>,
+    /// Recently read or written edges, keyed canonically.
+    edge_cache: FrozenMap<EdgeKey, Box<EdgeData>>,
+    edge_recency: Recency<EdgeKey>,
+    /// Write-behind buffer.
+    dirty: Mutex<DirtySet>,
     cache_size: usize,
 }
 
@@ -46,12 +160,15 @@ impl SqliteTopologyGraph {
             CREATE TABLE IF NOT EXISTS nodes (
                 id TEXT PRIMARY KEY,
                 label TEXT NOT NULL,
+                label_lower TEXT NOT NULL DEFAULT '',
                 node_type TEXT NOT NULL,
                 position_x REAL NOT NULL,
                 position_y REAL NOT NULL,
                 access_count INTEGER NOT NULL DEFAULT 1,
                 created_tick INTEGER NOT NULL DEFAULT 0,
-                embedding BLOB
+                last_accessed_tick INTEGER NOT NULL DEFAULT 0,
+                embedding BLOB,
+                tags TEXT
             );
 
             CREATE TABLE IF NOT EXISTS edges (
@@ -66,19 +183,90 @@ impl SqliteTopologyGraph {
                 FOREIGN KEY (to_id) REFERENCES nodes(id)
             );
 
+            CREATE TABLE IF NOT EXISTS node_aliases (
+                node_id TEXT NOT NULL,
+                alias_lower TEXT NOT NULL,
+                PRIMARY KEY (node_id, alias_lower),
+                FOREIGN KEY (node_id) REFERENCES nodes(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS node_annotations (
+                node_id TEXT NOT NULL,
+                text TEXT NOT NULL,
+                source TEXT NOT NULL,
+                tick INTEGER NOT NULL,
+                PRIMARY KEY (node_id, text, source, tick),
+                FOREIGN KEY (node_id) REFERENCES nodes(id)
+            );
+
             CREATE INDEX IF NOT EXISTS idx_nodes_label ON nodes(label);
+            CREATE INDEX IF NOT EXISTS idx_node_aliases_alias ON node_aliases(alias_lower);
             CREATE INDEX IF NOT EXISTS idx_edges_from ON edges(from_id);
             CREATE INDEX IF NOT EXISTS idx_edges_to ON edges(to_id);
             "#,
         )?;
 
+        Self::migrate(&conn)?;
+
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
-            node_cache: HashMap::new(),
+            node_cache: FrozenMap::new(),
+            node_recency: Recency::new(),
+            edge_cache: FrozenMap::new(),
+            edge_recency: Recency::new(),
+            dirty: Mutex::new(DirtySet::default()),
             cache_size: 1000,
         })
     }
 
+    /// Bring databases written by older versions up to the current schema.
+    ///
+    /// Version 1 adds the indexed `label_lower` column and stores every edge
+    /// with its endpoints in canonical order. Version 2 adds
+    /// `last_accessed_tick`, seeded from `created_tick`. Version 3 adds the
+    /// `tags` column (JSON object of per-tag access counts).
+    fn migrate(conn: &Connection) -> SqlResult<()> {
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version >= SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        let has_label_lower = conn
+            .prepare("SELECT 1 FROM pragma_table_info('nodes') WHERE name = 'label_lower'")?
+            .exists([])?;
+        if !has_label_lower {
+            conn.execute_batch(
+                "ALTER TABLE nodes ADD COLUMN label_lower TEXT NOT NULL DEFAULT '';",
+            )?;
+        }
+
+        let has_last_accessed = conn
+            .prepare("SELECT 1 FROM pragma_table_info('nodes') WHERE name = 'last_accessed_tick'")?
+            .exists([])?;
+        if !has_last_accessed {
+            conn.execute_batch(
+                "ALTER TABLE nodes ADD COLUMN last_accessed_tick INTEGER NOT NULL DEFAULT 0;
+                 UPDATE nodes SET last_accessed_tick = created_tick;",
+            )?;
+        }
+
+        let has_tags = conn
+            .prepare("SELECT 1 FROM pragma_table_info('nodes') WHERE name = 'tags'")?
+            .exists([])?;
+        if !has_tags {
+            conn.execute_batch("ALTER TABLE nodes ADD COLUMN tags TEXT;")?;
+        }
+
+        conn.execute_batch(&format!(
+            r#"
+            UPDATE nodes SET label_lower = LOWER(label) WHERE label_lower = '';
+            UPDATE OR REPLACE edges SET from_id = to_id, to_id = from_id WHERE from_id > to_id;
+            CREATE INDEX IF NOT EXISTS idx_nodes_label_lower ON nodes(label_lower);
+            PRAGMA user_version = {SCHEMA_VERSION};
+            "#
+        ))
+    }
+
     /// Set the cache size for frequently accessed nodes.
     pub fn with_cache_size(mut self, size: usize) -> Self {
         self.cache_size = size;
@@ -87,6 +275,7 @@ impl SqliteTopologyGraph {
 
     /// Get database statistics.
     pub fn stats(&self) -> SqlResult<(usize, usize)> {
+        self.write_pending()?;
         let conn = self.conn.lock().unwrap();
         let node_count: usize =
             conn.query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))?;
@@ -95,34 +284,32 @@ impl SqliteTopologyGraph {
         Ok((node_count, edge_count))
     }
 
-    /// Clear the in-memory cache.
+    /// Number of nodes currently held in the in-memory cache.
+    pub fn cached_node_count(&self) -> usize {
+        self.node_cache.len()
+    }
+
+    /// Write pending changes and clear the in-memory cache.
     pub fn clear_cache(&mut self) {
-        self.node_cache.clear();
+        self.flush();
+        self.node_cache.as_mut().clear();
+        self.node_recency.clear();
+        self.drop_edge_cache();
     }
 
     /// Iterate over all nodes with their data.
     ///
     /// This is useful for bulk loading from SQLite into another graph backend.
     pub fn iter_nodes(&self) -> impl Iterator<Item = NodeData> + '_ {
+        self.write_pending()
+            .expect("Failed to write pending changes");
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn
-            .prepare("SELECT id, label, node_type, position_x, position_y, access_count, created_tick, embedding FROM nodes")
+            .prepare(&format!("SELECT {NODE_COLUMNS} FROM nodes ORDER BY rowid"))
             .expect("Failed to prepare statement");
 
         let nodes: Vec<NodeData> = stmt
-            .query_map([], |row| {
-                let id_str: String = row.get(0)?;
-                let embedding_bytes: Option<Vec<u8>> = row.get(7)?;
-                Ok(NodeData {
-                    id: NodeId(uuid::Uuid::parse_str(&id_str).unwrap_or_default()),
-                    label: row.get(1)?,
-                    node_type: Self::string_to_node_type(&row.get::<_, String>(2)?),
-                    position: Position::new(row.get(3)?, row.get(4)?),
-                    access_count: row.get(5)?,
-                    created_tick: row.get(6)?,
-                    embedding: Self::deserialize_embedding(embedding_bytes),
-                })
-            })
+            .query_map([], Self::node_from_row)
             .expect("Failed to query nodes")
             .filter_map(|r| r.ok())
             .collect();
@@ -134,31 +321,266 @@ impl SqliteTopologyGraph {
     ///
     /// Returns (from_id, to_id, edge_data) tuples.
     pub fn iter_edges(&self) -> impl Iterator<Item = (NodeId, NodeId, EdgeData)> + '_ {
+        self.write_pending()
+            .expect("Failed to write pending changes");
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn
-            .prepare("SELECT from_id, to_id, weight, co_activations, created_tick, last_activated_tick FROM edges")
+            .prepare(&format!("SELECT {EDGE_COLUMNS} FROM edges ORDER BY rowid"))
             .expect("Failed to prepare statement");
 
         let edges: Vec<(NodeId, NodeId, EdgeData)> = stmt
+            .query_map([], Self::edge_from_row)
+            .expect("Failed to query edges")
+            .filter_map(|r| r.ok())
+            .collect();
+
+        edges.into_iter()
+    }
+
+    /// Copy the whole graph into a petgraph-backed store.
+    ///
+    /// Whole-graph analytics touch every edge anyway, so they run on this
+    /// transient copy instead of issuing one query per hop.
+    pub fn to_in_memory(&self) -> PetTopologyGraph {
+        let mut graph = PetTopologyGraph::new();
+        for node in self.iter_nodes() {
+            graph.add_node(node);
+        }
+        for (from, to, edge) in self.iter_edges() {
+            // Every node was copied above
+            let _ = graph.set_edge(from, to, edge);
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT node_id, alias_lower FROM node_aliases ORDER BY rowid")
+            .expect("Failed to prepare statement");
+        let aliases: Vec<(String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .expect("Failed to query aliases")
+            .filter_map(|r| r.ok())
+            .collect();
+        for (id, alias) in aliases {
+            graph.add_alias(&parse_node_id(&id), &alias);
+        }
+        let mut stmt = conn
+            .prepare("SELECT node_id, text, source, tick FROM node_annotations ORDER BY rowid")
+            .expect("Failed to prepare statement");
+        let annotations: Vec<(String, Annotation)> = stmt
             .query_map([], |row| {
-                let from_str: String = row.get(0)?;
-                let to_str: String = row.get(1)?;
                 Ok((
-                    NodeId(uuid::Uuid::parse_str(&from_str).unwrap_or_default()),
-                    NodeId(uuid::Uuid::parse_str(&to_str).unwrap_or_default()),
-                    EdgeData {
-                        weight: row.get(2)?,
-                        co_activations: row.get(3)?,
-                        created_tick: row.get(4)?,
-                        last_activated_tick: row.get(5)?,
+                    row.get(0)?,
+                    Annotation {
+                        text: row.get(1)?,
+                        source: row.get(2)?,
+                        tick: row.get(3)?,
                     },
                 ))
             })
-            .expect("Failed to query edges")
+            .expect("Failed to query annotations")
             .filter_map(|r| r.ok())
             .collect();
+        for (id, annotation) in annotations {
+            graph.annotate(&parse_node_id(&id), annotation);
+        }
+        graph
+    }
 
-        edges.into_iter()
+    fn node_from_row(row: &Row<'_>) -> SqlResult<NodeData> {
+        let id_str: String = row.get(0)?;
+        let tags_json: Option<String> = row.get(8)?;
+        Ok(NodeData {
+            id: parse_node_id(&id_str),
+            label: row.get(1)?,
+            node_type: Self::string_to_node_type(&row.get::<_, String>(2)?),
+            position: Position::new(row.get(3)?, row.get(4)?),
+            access_count: row.get(5)?,
+            created_tick: row.get(6)?,
+            last_accessed_tick: row.get(7)?,
+            tags: Self::deserialize_tags(tags_json),
+        })
+    }
+
+    fn edge_from_row(row: &Row<'_>) -> SqlResult<(NodeId, NodeId, EdgeData)> {
+        let from_str: String = row.get(0)?;
+        let to_str: String = row.get(1)?;
+        Ok((
+            parse_node_id(&from_str),
+            parse_node_id(&to_str),
+            EdgeData {
+                weight: row.get(2)?,
+                co_activations: row.get(3)?,
+                created_tick: row.get(4)?,
+                last_activated_tick: row.get(5)?,
+            },
+        ))
+    }
+
+    fn query_ids(&self, sql: &str, param: &str) -> Vec<NodeId> {
+        self.write_pending()
+            .expect("Failed to write pending changes");
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare_cached(sql)
+            .expect("Failed to prepare statement");
+        stmt.query_map(params![param], |row| {
+            let id_str: String = row.get(0)?;
+            Ok(parse_node_id(&id_str))
+        })
+        .expect("Failed to query nodes")
+        .filter_map(|r| r.ok())
+        .collect()
+    }
+
+    fn query_pruned(&self, sql: &str, params: impl rusqlite::Params) -> Vec<PrunedConnection> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(sql).expect("Failed to prepare statement");
+        stmt.query_map(params, |row| {
+            let from_str: String = row.get(0)?;
+            let to_str: String = row.get(1)?;
+            Ok(PrunedConnection {
+                from: parse_node_id(&from_str),
+                to: parse_node_id(&to_str),
+                final_weight: row.get(2)?,
+            })
+        })
+        .expect("Failed to query pruned edges")
+        .filter_map(|r| r.ok())
+        .collect()
+    }
+
+    /// Look up a node, loading it into the cache on a miss.
+    fn cached_node(&self, id: &NodeId) -> Option<&NodeData> {
+        let node = match self.node_cache.get(id) {
+            Some(node) => node,
+            None => {
+                let conn = self.conn.lock().unwrap();
+                let loaded = conn
+                    .prepare_cached(&format!("SELECT {NODE_COLUMNS} FROM nodes WHERE id = ?1"))
+                    .and_then(|mut stmt| {
+                        stmt.query_row(params![id.0.to_string()], Self::node_from_row)
+                    })
+                    .ok()?;
+                drop(conn);
+                self.node_cache.insert(*id, Box::new(loaded))
+            }
+        };
+        self.node_recency.touch(*id);
+        Some(node)
+    }
+
+    /// Look up an edge, loading it into the cache on a miss.
+    fn cached_edge(&self, key: EdgeKey) -> Option<&EdgeData> {
+        let edge = match self.edge_cache.get(&key) {
+            Some(edge) => edge,
+            None => {
+                let conn = self.conn.lock().unwrap();
+                let (_, _, loaded) = conn
+                    .prepare_cached(&format!(
+                        "SELECT {EDGE_COLUMNS} FROM edges WHERE from_id = ?1 AND to_id = ?2"
+                    ))
+                    .and_then(|mut stmt| {
+                        stmt.query_row(
+                            params![key.0 .0.to_string(), key.1 .0.to_string()],
+                            Self::edge_from_row,
+                        )
+                    })
+                    .ok()?;
+                drop(conn);
+                self.edge_cache.insert(key, Box::new(loaded))
+            }
+        };
+        self.edge_recency.touch(key);
+        Some(edge)
+    }
+
+    /// Write every dirty cache entry to the database in one transaction.
+    fn write_pending(&self) -> SqlResult<()> {
+        let mut dirty = self.dirty.lock().unwrap();
+        if dirty.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut upsert_node = tx.prepare_cached(
+                "INSERT OR REPLACE INTO nodes (id, label, label_lower, node_type, position_x, position_y, access_count, created_tick, last_accessed_tick, tags)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for id in &dirty.nodes {
+                let Some(data) = self.node_cache.get(id) else {
+                    continue;
+                };
+                upsert_node.execute(params![
+                    id.0.to_string(),
+                    data.label,
+                    data.label.to_lowercase(),
+                    Self::node_type_to_string(&data.node_type),
+                    data.position.x,
+                    data.position.y,
+                    data.access_count,
+                    data.created_tick,
+                    data.last_accessed_tick,
+                    Self::serialize_tags(&data.tags),
+                ])?;
+            }
+
+            let mut upsert_edge = tx.prepare_cached(
+                "INSERT INTO edges (from_id, to_id, weight, co_activations, created_tick, last_activated_tick)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(from_id, to_id) DO UPDATE SET
+                    weight = excluded.weight,
+                    co_activations = excluded.co_activations,
+                    created_tick = excluded.created_tick,
+                    last_activated_tick = excluded.last_activated_tick",
+            )?;
+            for key in &dirty.edges {
+                let Some(data) = self.edge_cache.get(key) else {
+                    continue;
+                };
+                upsert_edge.execute(params![
+                    key.0 .0.to_string(),
+                    key.1 .0.to_string(),
+                    data.weight,
+                    data.co_activations,
+                    data.created_tick,
+                    data.last_activated_tick,
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        dirty.nodes.clear();
+        dirty.edges.clear();
+        Ok(())
+    }
+
+    /// Delete a node's row and every row that refers to it, in one
+    /// transaction.
+    fn delete_node_rows(&self, id: &NodeId) -> SqlResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let id_str = id.0.to_string();
+        tx.execute(
+            "DELETE FROM edges WHERE from_id = ?1 OR to_id = ?1",
+            params![id_str],
+        )?;
+        tx.execute(
+            "DELETE FROM node_aliases WHERE node_id = ?1",
+            params![id_str],
+        )?;
+        tx.execute(
+            "DELETE FROM node_annotations WHERE node_id = ?1",
+            params![id_str],
+        )?;
+        tx.execute("DELETE FROM nodes WHERE id = ?1", params![id_str])?;
+        tx.commit()
+    }
+
+    /// Forget every cached edge; used after bulk SQL updates make them stale.
+    fn drop_edge_cache(&mut self) {
+        self.edge_cache.as_mut().clear();
+        self.edge_recency.clear();
     }
 
     fn node_type_to_string(nt: &NodeType) -> &'static str {
@@ -179,162 +601,138 @@ impl SqliteTopologyGraph {
         }
     }
 
-    fn serialize_embedding(embedding: &Option<Vec<f32>>) -> Option<Vec<u8>> {
-        embedding
-            .as_ref()
-            .map(|e| e.iter().flat_map(|f| f.to_le_bytes()).collect())
+    fn serialize_tags(tags: &BTreeMap<String, u64>) -> Option<String> {
+        if tags.is_empty() {
+            None
+        } else {
+            serde_json::to_string(tags).ok()
+        }
     }
 
-    fn deserialize_embedding(bytes: Option<Vec<u8>>) -> Option<Vec<f32>> {
-        bytes.map(|b| {
-            b.chunks_exact(4)
-                .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-                .collect()
-        })
+    fn deserialize_tags(json: Option<String>) -> BTreeMap<String, u64> {
+        json.and_then(|j| serde_json::from_str(&j).ok())
+            .unwrap_or_default()
     }
 }
 
 impl TopologyGraph for SqliteTopologyGraph {
     fn add_node(&mut self, data: NodeData) -> NodeId {
         let id = data.id;
-        let conn = self.conn.lock().unwrap();
-
-        let embedding_bytes = Self::serialize_embedding(&data.embedding);
-
-        conn.execute(
-            "INSERT OR REPLACE INTO nodes (id, label, node_type, position_x, position_y, access_count, created_tick, embedding)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                id.0.to_string(),
-                data.label,
-                Self::node_type_to_string(&data.node_type),
-                data.position.x,
-                data.position.y,
-                data.access_count,
-                data.created_tick,
-                embedding_bytes,
-            ],
-        ).expect("Failed to insert node");
-
-        // Add to cache if there's room
-        if self.node_cache.len() < self.cache_size {
-            drop(conn);
-            self.node_cache.insert(id, data);
-        }
-
+        self.node_cache.as_mut().insert(id, Box::new(data));
+        self.node_recency.touch(id);
+        self.dirty.get_mut().unwrap().nodes.insert(id);
         id
     }
 
     fn get_node(&self, id: &NodeId) -> Option<&NodeData> {
-        // Check cache first
-        self.node_cache.get(id)
+        self.cached_node(id)
     }
 
     fn get_node_mut(&mut self, id: &NodeId) -> Option<&mut NodeData> {
-        // For mutable access, we need to load into cache first
-        if !self.node_cache.contains_key(id) {
-            let conn = self.conn.lock().unwrap();
-            let node: Option<NodeData> = conn
-                .query_row(
-                    "SELECT id, label, node_type, position_x, position_y, access_count, created_tick, embedding
-                     FROM nodes WHERE id = ?1",
-                    params![id.0.to_string()],
-                    |row| {
-                        let id_str: String = row.get(0)?;
-                        let embedding_bytes: Option<Vec<u8>> = row.get(7)?;
-                        Ok(NodeData {
-                            id: NodeId(uuid::Uuid::parse_str(&id_str).unwrap_or_default()),
-                            label: row.get(1)?,
-                            node_type: Self::string_to_node_type(&row.get::<_, String>(2)?),
-                            position: Position::new(row.get(3)?, row.get(4)?),
-                            access_count: row.get(5)?,
-                            created_tick: row.get(6)?,
-                            embedding: Self::deserialize_embedding(embedding_bytes),
-                        })
-                    },
-                )
-                .ok();
+        self.cached_node(id)?;
+        self.dirty.get_mut().unwrap().nodes.insert(*id);
+        self.node_cache.as_mut().get_mut(id).map(|n| n.as_mut())
+    }
 
-            if let Some(node) = node {
-                drop(conn);
-                self.node_cache.insert(*id, node);
+    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData) -> Result<(), TopologyError> {
+        for id in [from, to] {
+            if self.cached_node(&id).is_none() {
+                return Err(TopologyError::UnknownNode(id));
             }
         }
-
-        self.node_cache.get_mut(id)
+        let key = edge_key(from, to);
+        self.edge_cache.as_mut().insert(key, Box::new(data));
+        self.edge_recency.touch(key);
+        self.dirty.get_mut().unwrap().edges.insert(key);
+        Ok(())
     }
 
-    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData) {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT OR REPLACE INTO edges (from_id, to_id, weight, co_activations, created_tick, last_activated_tick)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![
-                from.0.to_string(),
-                to.0.to_string(),
-                data.weight,
-                data.co_activations,
-                data.created_tick,
-                data.last_activated_tick,
-            ],
-        ).expect("Failed to insert edge");
+    fn get_edge(&self, from: &NodeId, to: &NodeId) -> Option<&EdgeData> {
+        self.cached_edge(edge_key(*from, *to))
     }
 
-    fn get_edge(&self, _from: &NodeId, _to: &NodeId) -> Option<&EdgeData> {
-        // SQLite backend returns owned data, so we can't return a reference
-        // This is a limitation - for persistent backends, we'd need a different pattern
-        None
+    fn get_edge_mut(&mut self, from: &NodeId, to: &NodeId) -> Option<&mut EdgeData> {
+        let key = edge_key(*from, *to);
+        self.cached_edge(key)?;
+        self.dirty.get_mut().unwrap().edges.insert(key);
+        self.edge_cache.as_mut().get_mut(&key).map(|e| e.as_mut())
     }
 
-    fn get_edge_mut(&mut self, _from: &NodeId, _to: &NodeId) -> Option<&mut EdgeData> {
-        // Same limitation as get_edge
-        None
-    }
-
-    fn neighbors(&self, _node: &NodeId) -> Vec<(NodeId, &EdgeData)> {
-        // Can't return references to edges from SQLite
-        // Users should use all_edges or a custom query
-        Vec::new()
+    fn neighbors(&self, node: &NodeId) -> Vec<(NodeId, &EdgeData)> {
+        self.write_pending()
+            .expect("Failed to write pending changes");
+        let rows: Vec<(NodeId, NodeId, EdgeData)> = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn
+                .prepare_cached(&format!(
+                    "SELECT {EDGE_COLUMNS} FROM edges WHERE from_id = ?1
+                     UNION ALL
+                     SELECT {EDGE_COLUMNS} FROM edges WHERE to_id = ?1"
+                ))
+                .expect("Failed to prepare statement");
+            stmt.query_map(params![node.0.to_string()], Self::edge_from_row)
+                .expect("Failed to query neighbors")
+                .filter_map(|r| r.ok())
+                .collect()
+        };
+
+        rows.into_iter()
+            .map(|(from, to, data)| {
+                let key = (from, to);
+                let other = if from == *node { to } else { from };
+                // Cached entries are never older than the row we just read
+                let edge = self.edge_cache.insert(key, Box::new(data));
+                self.edge_recency.touch(key);
+                (other, edge)
+            })
+            .collect()
     }
 
     fn remove_edge(&mut self, from: &NodeId, to: &NodeId) -> Option<EdgeData> {
-        let conn = self.conn.lock().unwrap();
-
-        // First get the edge data
-        let edge: Option<EdgeData> = conn
-            .query_row(
-                "SELECT weight, co_activations, created_tick, last_activated_tick FROM edges WHERE from_id = ?1 AND to_id = ?2",
-                params![from.0.to_string(), to.0.to_string()],
-                |row| {
-                    Ok(EdgeData {
-                        weight: row.get(0)?,
-                        co_activations: row.get(1)?,
-                        created_tick: row.get(2)?,
-                        last_activated_tick: row.get(3)?,
-                    })
-                },
+        let key = edge_key(*from, *to);
+        self.cached_edge(key)?;
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "DELETE FROM edges WHERE from_id = ?1 AND to_id = ?2",
+                params![key.0 .0.to_string(), key.1 .0.to_string()],
             )
-            .ok();
+            .expect("Failed to delete edge");
 
-        // Then delete it
-        conn.execute(
-            "DELETE FROM edges WHERE from_id = ?1 AND to_id = ?2",
-            params![from.0.to_string(), to.0.to_string()],
-        )
-        .ok();
+        let data = self.edge_cache.as_mut().remove(&key).map(|e| *e);
+        self.edge_recency.forget(&key);
+        self.dirty.get_mut().unwrap().edges.remove(&key);
+        data
+    }
+
+    fn remove_node(&mut self, id: &NodeId) -> Option<NodeData> {
+        self.cached_node(id)?;
+        self.delete_node_rows(id).expect("Failed to delete node");
+
+        let data = self.node_cache.as_mut().remove(id).map(|n| *n);
+        self.node_recency.forget(id);
 
-        edge
+        let dirty = self.dirty.get_mut().unwrap();
+        dirty.nodes.remove(id);
+        dirty.edges.retain(|(a, b)| a != id && b != id);
+        self.edge_cache
+            .as_mut()
+            .retain(|(a, b), _| a != id && b != id);
+        data
     }
 
     fn all_nodes(&self) -> Vec<NodeId> {
+        self.write_pending()
+            .expect("Failed to write pending changes");
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn
-            .prepare("SELECT id FROM nodes")
+            .prepare("SELECT id FROM nodes ORDER BY rowid")
             .expect("Failed to prepare statement");
         let nodes: Vec<NodeId> = stmt
             .query_map([], |row| {
                 let id_str: String = row.get(0)?;
-                Ok(NodeId(uuid::Uuid::parse_str(&id_str).unwrap_or_default()))
+                Ok(parse_node_id(&id_str))
             })
             .expect("Failed to query nodes")
             .filter_map(|r| r.ok())
@@ -342,58 +740,51 @@ impl TopologyGraph for SqliteTopologyGraph {
         nodes
     }
 
+    /// Loads every edge into the cache; it is trimmed again on the next flush.
     fn all_edges(&self) -> Vec<(NodeId, NodeId, &EdgeData)> {
-        // Can't return references from SQLite
-        Vec::new()
+        self.iter_edges()
+            .map(|(from, to, data)| (from, to, self.edge_cache.insert((from, to), Box::new(data))))
+            .collect()
     }
 
     fn node_count(&self) -> usize {
-        let conn = self.conn.lock().unwrap();
-        conn.query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))
-            .unwrap_or(0)
+        self.stats().map(|(nodes, _)| nodes).unwrap_or(0)
     }
 
     fn edge_count(&self) -> usize {
-        let conn = self.conn.lock().unwrap();
-        conn.query_row("SELECT COUNT(*) FROM edges", [], |row| row.get(0))
-            .unwrap_or(0)
+        self.stats().map(|(_, edges)| edges).unwrap_or(0)
     }
 
     fn decay_edges(&mut self, rate: f64, prune_threshold: f64) -> Vec<PrunedConnection> {
-        let conn = self.conn.lock().unwrap();
+        self.write_pending()
+            .expect("Failed to write pending changes");
+        self.drop_edge_cache();
 
         // Decay all edge weights
-        conn.execute(
-            "UPDATE edges SET weight = weight * (1.0 - ?1)",
-            params![rate],
-        )
-        .expect("Failed to decay edges");
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE edges SET weight = weight * (1.0 - ?1)",
+                params![rate],
+            )
+            .expect("Failed to decay edges");
 
         // Get edges below threshold
-        let mut stmt = conn
-            .prepare("SELECT from_id, to_id, weight FROM edges WHERE weight < ?1")
-            .expect("Failed to prepare statement");
-
-        let pruned: Vec<PrunedConnection> = stmt
-            .query_map(params![prune_threshold], |row| {
-                let from_str: String = row.get(0)?;
-                let to_str: String = row.get(1)?;
-                Ok(PrunedConnection {
-                    from: NodeId(uuid::Uuid::parse_str(&from_str).unwrap_or_default()),
-                    to: NodeId(uuid::Uuid::parse_str(&to_str).unwrap_or_default()),
-                    final_weight: row.get(2)?,
-                })
-            })
-            .expect("Failed to query pruned edges")
-            .filter_map(|r| r.ok())
-            .collect();
+        let pruned = self.query_pruned(
+            "SELECT from_id, to_id, weight FROM edges WHERE weight < ?1",
+            params![prune_threshold],
+        );
 
         // Delete pruned edges
-        conn.execute(
-            "DELETE FROM edges WHERE weight < ?1",
-            params![prune_threshold],
-        )
-        .expect("Failed to delete pruned edges");
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "DELETE FROM edges WHERE weight < ?1",
+                params![prune_threshold],
+            )
+            .expect("Failed to delete pruned edges");
 
         pruned
     }
@@ -406,189 +797,330 @@ impl TopologyGraph for SqliteTopologyGraph {
         staleness_factor: f64,
         maturation_ticks: u64,
     ) -> Vec<PrunedConnection> {
-        let conn = self.conn.lock().unwrap();
-
-        // For SQLite, we use a simplified approach:
-        // 1. Decay mature edges faster if they haven't been activated recently
-        // 2. Use base_rate for young edges
-
-        // Calculate decay for each edge based on staleness
-        // This is done in Rust since SQLite doesn't have complex math functions
-        let mut stmt = conn
-            .prepare("SELECT from_id, to_id, weight, co_activations, created_tick, last_activated_tick FROM edges")
-            .expect("Failed to prepare statement");
-
-        let edges: Vec<(String, String, f64, u64, u64, u64)> = stmt
-            .query_map([], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, f64>(2)?,
-                    row.get::<_, u64>(3)?,
-                    row.get::<_, u64>(4)?,
-                    row.get::<_, u64>(5)?,
-                ))
-            })
-            .expect("Failed to query edges")
-            .filter_map(|r| r.ok())
-            .collect();
+        self.write_pending()
+            .expect("Failed to write pending changes");
+        self.drop_edge_cache();
+
+        // Same rate formula as PetTopologyGraph, evaluated in one statement:
+        // young edges decay at the base rate, mature ones faster the longer
+        // they go without co-activation. Capped at 50% per tick.
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE edges SET weight = weight * (1.0 - MIN(
+                    CASE WHEN MAX(?2 - created_tick, 0) < ?4 THEN ?1
+                    ELSE ?1 * (1.0 + ?3 * (MAX(?2 - last_activated_tick, 0) / 100.0)
+                                   * (1.0 / (1.0 + co_activations * 0.5)))
+                    END, 0.5))",
+                params![base_rate, current_tick, staleness_factor, maturation_ticks],
+            )
+            .expect("Failed to decay edges");
 
-        drop(stmt);
+        // Only mature edges are pruned (young edges get a grace period)
+        let pruned = self.query_pruned(
+            "SELECT from_id, to_id, weight FROM edges
+             WHERE MAX(?1 - created_tick, 0) >= ?2 AND weight < ?3",
+            params![current_tick, maturation_ticks, prune_threshold],
+        );
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "DELETE FROM edges WHERE MAX(?1 - created_tick, 0) >= ?2 AND weight < ?3",
+                params![current_tick, maturation_ticks, prune_threshold],
+            )
+            .expect("Failed to delete pruned edges");
 
-        let mut pruned = Vec::new();
+        pruned
+    }
 
-        for (from_str, to_str, weight, co_activations, created_tick, last_activated_tick) in edges {
-            let edge_age = current_tick.saturating_sub(created_tick);
+    fn prune_to_max_degree(&mut self, max_degree: usize) -> Vec<PrunedConnection> {
+        self.write_pending()
+            .expect("Failed to write pending changes");
+
+        // Rank each node's edges by weight; an edge goes if either endpoint
+        // ranks it beyond `max_degree`.
+        const RANKED: &str = "SELECT DISTINCT eid FROM (
+                SELECT eid, ROW_NUMBER() OVER (PARTITION BY node ORDER BY weight DESC) AS rank
+                FROM (
+                    SELECT rowid AS eid, from_id AS node, weight FROM edges
+                    UNION ALL
+                    SELECT rowid AS eid, to_id AS node, weight FROM edges
+                )
+            ) WHERE rank > ?1";
 
-            let decay_rate = if edge_age < maturation_ticks {
-                base_rate
-            } else {
-                let staleness = current_tick.saturating_sub(last_activated_tick);
-                let activity_factor = 1.0 / (1.0 + co_activations as f64);
-                base_rate * (1.0 + staleness_factor * staleness as f64 * activity_factor / 100.0)
-            };
+        let pruned = self.query_pruned(
+            &format!("SELECT from_id, to_id, weight FROM edges WHERE rowid IN ({RANKED})"),
+            params![max_degree],
+        );
+        if pruned.is_empty() {
+            return pruned;
+        }
 
-            let new_weight = weight * (1.0 - decay_rate.min(0.5));
-
-            if new_weight < prune_threshold {
-                pruned.push(PrunedConnection {
-                    from: NodeId(uuid::Uuid::parse_str(&from_str).unwrap_or_default()),
-                    to: NodeId(uuid::Uuid::parse_str(&to_str).unwrap_or_default()),
-                    final_weight: new_weight,
-                });
-                conn.execute(
-                    "DELETE FROM edges WHERE from_id = ?1 AND to_id = ?2",
-                    params![from_str, to_str],
-                )
-                .ok();
-            } else {
-                conn.execute(
-                    "UPDATE edges SET weight = ?1 WHERE from_id = ?2 AND to_id = ?3",
-                    params![new_weight, from_str, to_str],
-                )
-                .ok();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction().expect("Failed to begin transaction");
+        {
+            let mut delete = tx
+                .prepare("DELETE FROM edges WHERE from_id = ?1 AND to_id = ?2")
+                .expect("Failed to prepare statement");
+            for p in &pruned {
+                delete
+                    .execute(params![p.from.0.to_string(), p.to.0.to_string()])
+                    .expect("Failed to delete pruned edge");
             }
         }
+        tx.commit().expect("Failed to commit pruning");
+        drop(conn);
 
+        self.drop_edge_cache();
         pruned
     }
 
-    fn prune_to_max_degree(&mut self, max_degree: usize) -> Vec<PrunedConnection> {
-        let conn = self.conn.lock().unwrap();
-        let mut pruned = Vec::new();
-
-        // Get all nodes
-        let nodes: Vec<String> = conn
-            .prepare("SELECT id FROM nodes")
-            .expect("prepare")
-            .query_map([], |row| row.get(0))
-            .expect("query")
-            .filter_map(|r| r.ok())
-            .collect();
+    fn find_nodes_by_label(&self, query: &str) -> Vec<NodeId> {
+        self.query_ids(
+            "SELECT id FROM nodes WHERE instr(label_lower, ?1) > 0 ORDER BY rowid",
+            &query.to_lowercase(),
+        )
+    }
 
-        for node_id in nodes {
-            // Get edges sorted by weight (ascending, so weakest first)
-            let edges: Vec<(String, String, f64)> = conn
-                .prepare(
-                    "SELECT from_id, to_id, weight FROM edges
-                     WHERE from_id = ?1 OR to_id = ?1
-                     ORDER BY weight ASC",
-                )
-                .expect("prepare")
-                .query_map(params![&node_id], |row| {
-                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
-                })
-                .expect("query")
-                .filter_map(|r| r.ok())
-                .collect();
-
-            if edges.len() > max_degree {
-                // Prune the weakest edges
-                let to_prune = edges.len() - max_degree;
-                for (from_str, to_str, weight) in edges.into_iter().take(to_prune) {
-                    pruned.push(PrunedConnection {
-                        from: NodeId(uuid::Uuid::parse_str(&from_str).unwrap_or_default()),
-                        to: NodeId(uuid::Uuid::parse_str(&to_str).unwrap_or_default()),
-                        final_weight: weight,
-                    });
-                    conn.execute(
-                        "DELETE FROM edges WHERE from_id = ?1 AND to_id = ?2",
-                        params![from_str, to_str],
-                    )
-                    .ok();
-                }
+    fn find_nodes_by_exact_label(&self, label: &str) -> Vec<NodeId> {
+        // Served by idx_nodes_label_lower, then idx_node_aliases_alias
+        let label = label.to_lowercase();
+        let mut ids = self.query_ids(
+            "SELECT id FROM nodes WHERE label_lower = ?1 ORDER BY rowid",
+            &label,
+        );
+        for id in self.query_ids(
+            "SELECT node_id FROM node_aliases WHERE alias_lower = ?1 ORDER BY rowid",
+            &label,
+        ) {
+            if !ids.contains(&id) {
+                ids.push(id);
             }
         }
+        ids
+    }
 
-        pruned
+    fn add_alias(&mut self, id: &NodeId, alias: &str) -> bool {
+        let Some(node) = self.cached_node(id) else {
+            return false;
+        };
+        let alias = alias.to_lowercase();
+        if node.label.to_lowercase() == alias {
+            return true;
+        }
+        // The alias row references the node's row
+        if self.write_pending().is_err() {
+            return false;
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO node_aliases (node_id, alias_lower) VALUES (?1, ?2)",
+            params![id.0.to_string(), alias],
+        )
+        .is_ok()
     }
 
-    fn find_nodes_by_label(&self, query: &str) -> Vec<NodeId> {
+    fn aliases(&self, id: &NodeId) -> Vec<String> {
         let conn = self.conn.lock().unwrap();
-        let pattern = format!("%{}%", query.to_lowercase());
         let mut stmt = conn
-            .prepare("SELECT id FROM nodes WHERE LOWER(label) LIKE ?1")
+            .prepare_cached(
+                "SELECT alias_lower FROM node_aliases WHERE node_id = ?1 ORDER BY rowid",
+            )
             .expect("Failed to prepare statement");
+        stmt.query_map(params![id.0.to_string()], |row| row.get(0))
+            .expect("Failed to query aliases")
+            .filter_map(|r| r.ok())
+            .collect()
+    }
 
-        stmt.query_map(params![pattern], |row| {
-            let id_str: String = row.get(0)?;
-            Ok(NodeId(uuid::Uuid::parse_str(&id_str).unwrap_or_default()))
-        })
-        .expect("Failed to query nodes")
-        .filter_map(|r| r.ok())
-        .collect()
+    fn annotate(&mut self, id: &NodeId, annotation: Annotation) -> bool {
+        if self.cached_node(id).is_none() {
+            return false;
+        }
+        // The annotation row references the node's row
+        if self.write_pending().is_err() {
+            return false;
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO node_annotations (node_id, text, source, tick)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                id.0.to_string(),
+                annotation.text,
+                annotation.source,
+                annotation.tick
+            ],
+        )
+        .is_ok_and(|inserted| inserted > 0)
     }
 
-    fn find_nodes_by_exact_label(&self, label: &str) -> Vec<NodeId> {
+    fn annotations(&self, id: &NodeId) -> Vec<Annotation> {
         let conn = self.conn.lock().unwrap();
-        let label_lower = label.to_lowercase();
         let mut stmt = conn
-            .prepare("SELECT id FROM nodes WHERE LOWER(label) = ?1")
+            .prepare_cached(
+                "SELECT text, source, tick FROM node_annotations WHERE node_id = ?1 ORDER BY rowid",
+            )
             .expect("Failed to prepare statement");
-
-        stmt.query_map(params![label_lower], |row| {
-            let id_str: String = row.get(0)?;
-            Ok(NodeId(uuid::Uuid::parse_str(&id_str).unwrap_or_default()))
+        stmt.query_map(params![id.0.to_string()], |row| {
+            Ok(Annotation {
+                text: row.get(0)?,
+                source: row.get(1)?,
+                tick: row.get(2)?,
+            })
         })
-        .expect("Failed to query nodes")
+        .expect("Failed to query annotations")
         .filter_map(|r| r.ok())
         .collect()
     }
 
-    fn shortest_path(&self, _from: &NodeId, _to: &NodeId) -> Option<(Vec<NodeId>, f64)> {
-        // SQLite doesn't support graph algorithms directly
-        // Would need to implement Dijkstra in Rust with SQL queries
+    fn find_nodes_by_label_prefix(&self, prefix: &str) -> Vec<NodeId> {
+        // Range scan on idx_nodes_label_lower; U+10FFFF sorts after any
+        // continuation of the prefix
+        self.query_ids(
+            "SELECT id FROM nodes WHERE label_lower >= ?1 AND label_lower < ?1 || char(1114111)
+             ORDER BY label_lower, rowid",
+            &prefix.to_lowercase(),
+        )
+    }
+
+    fn shortest_path(&self, from: &NodeId, to: &NodeId) -> Option<(Vec<NodeId>, f64)> {
+        self.cached_node(from)?;
+        self.cached_node(to)?;
+
+        // Dijkstra with inverse weight as cost, expanding neighbors on demand
+        #[derive(PartialEq)]
+        struct State {
+            cost: f64,
+            node: NodeId,
+        }
+        impl Eq for State {}
+        impl PartialOrd for State {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for State {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other
+                    .cost
+                    .partial_cmp(&self.cost)
+                    .unwrap_or(Ordering::Equal) // min-heap
+            }
+        }
+
+        let mut dist: HashMap<NodeId, f64> = HashMap::new();
+        let mut prev: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+
+        dist.insert(*from, 0.0);
+        heap.push(State {
+            cost: 0.0,
+            node: *from,
+        });
+
+        while let Some(State { cost, node }) = heap.pop() {
+            if node == *to {
+                let mut path = vec![*to];
+                let mut current = *to;
+                while current != *from {
+                    current = prev[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some((path, cost));
+            }
+
+            if cost > *dist.get(&node).unwrap_or(&f64::INFINITY) {
+                continue;
+            }
+
+            for (next, edge) in self.neighbors(&node) {
+                let next_cost = cost + 1.0 / edge.weight.max(0.001);
+                if next_cost < *dist.get(&next).unwrap_or(&f64::INFINITY) {
+                    dist.insert(next, next_cost);
+                    prev.insert(next, node);
+                    heap.push(State {
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
         None
     }
 
-    fn betweenness_centrality(&self, _sample_size: usize) -> Vec<(NodeId, f64)> {
-        // Complex to implement with SQL
-        Vec::new()
+    fn betweenness_centrality(&self, sample_size: usize) -> Vec<(NodeId, f64)> {
+        self.to_in_memory().betweenness_centrality(sample_size)
     }
 
-    fn bridge_nodes(&self, _top_k: usize) -> Vec<(NodeId, f64)> {
-        Vec::new()
+    fn bridge_nodes(&self, top_k: usize) -> Vec<(NodeId, f64)> {
+        self.to_in_memory().bridge_nodes(top_k)
     }
 
     fn connected_components(&self) -> usize {
-        // Would require union-find or BFS implementation
-        1
+        self.to_in_memory().connected_components()
     }
 
     fn louvain_communities(&self) -> phago_core::louvain::LouvainResult {
-        // Stub: Louvain on SQLite would require loading the full graph
-        phago_core::louvain::LouvainResult {
-            communities: Vec::new(),
-            modularity: 0.0,
-            passes: 0,
+        self.to_in_memory().louvain_communities()
+    }
+
+    fn flush(&mut self) {
+        self.write_pending()
+            .expect("Failed to write pending changes");
+
+        for id in self.node_recency.evict(self.cache_size) {
+            self.node_cache.as_mut().remove(&id);
+        }
+        for key in self
+            .edge_recency
+            .evict(self.cache_size * EDGES_PER_CACHED_NODE)
+        {
+            self.edge_cache.as_mut().remove(&key);
+        }
+        // Bulk loads (all_edges, neighbors) may insert without a recency stamp
+        if self.edge_cache.len() > self.cache_size * EDGES_PER_CACHED_NODE {
+            self.drop_edge_cache();
         }
     }
 }
 
+impl Drop for SqliteTopologyGraph {
+    fn drop(&mut self) {
+        // Best-effort: don't lose buffered writes when the graph goes away
+        let _ = self.write_pending();
+    }
+}
+
 #[cfg(all(test, feature = "sqlite"))]
 mod tests {
     use super::*;
 
+    fn make_node(label: &str) -> NodeData {
+        NodeData {
+            id: NodeId::new(),
+            label: label.to_string(),
+            node_type: NodeType::Concept,
+            position: Position::new(0.0, 0.0),
+            access_count: 1,
+            created_tick: 0,
+            last_accessed_tick: 0,
+            tags: Default::default(),
+        }
+    }
+
+    fn make_edge(weight: f64, tick: u64) -> EdgeData {
+        EdgeData {
+            weight,
+            co_activations: 1,
+            created_tick: tick,
+            last_activated_tick: tick,
+        }
+    }
+
     #[test]
     fn create_in_memory() {
         let graph = SqliteTopologyGraph::new_in_memory().unwrap();
@@ -608,7 +1140,8 @@ mod tests {
                 position: Position::new(i as f64, 0.0),
                 access_count: 1,
                 created_tick: 0,
-                embedding: None,
+                last_accessed_tick: 0,
+                tags: Default::default(),
             });
         }
 
@@ -626,7 +1159,8 @@ mod tests {
             position: Position::new(0.0, 0.0),
             access_count: 1,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
 
         let n2 = graph.add_node(NodeData {
@@ -636,19 +1170,22 @@ mod tests {
             position: Position::new(1.0, 0.0),
             access_count: 1,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
 
-        graph.set_edge(
-            n1,
-            n2,
-            EdgeData {
-                weight: 0.5,
-                co_activations: 1,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        graph
+            .set_edge(
+                n1,
+                n2,
+                EdgeData {
+                    weight: 0.5,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
 
         assert_eq!(graph.edge_count(), 1);
     }
@@ -664,7 +1201,8 @@ mod tests {
             position: Position::new(0.0, 0.0),
             access_count: 1,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
 
         graph.add_node(NodeData {
@@ -674,7 +1212,8 @@ mod tests {
             position: Position::new(1.0, 0.0),
             access_count: 1,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
 
         let results = graph.find_nodes_by_label("cell");
@@ -682,6 +1221,10 @@ mod tests {
 
         let results = graph.find_nodes_by_label("membrane");
         assert_eq!(results.len(), 1);
+
+        // Prefix matches only at the start of the label
+        assert_eq!(graph.find_nodes_by_label_prefix("CELL").len(), 1);
+        assert!(graph.find_nodes_by_label_prefix("membrane").is_empty());
     }
 
     #[test]
@@ -695,7 +1238,8 @@ mod tests {
             position: Position::new(0.0, 0.0),
             access_count: 1,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
 
         let n2 = graph.add_node(NodeData {
@@ -705,20 +1249,23 @@ mod tests {
             position: Position::new(1.0, 0.0),
             access_count: 1,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
 
         // Add a weak edge
-        graph.set_edge(
-            n1,
-            n2,
-            EdgeData {
-                weight: 0.1,
-                co_activations: 1,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        graph
+            .set_edge(
+                n1,
+                n2,
+                EdgeData {
+                    weight: 0.1,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
 
         assert_eq!(graph.edge_count(), 1);
 
@@ -727,4 +1274,154 @@ mod tests {
         assert_eq!(pruned.len(), 1);
         assert_eq!(graph.edge_count(), 0);
     }
+
+    #[test]
+    fn edges_are_undirected_and_visible_as_neighbors() {
+        let mut graph = SqliteTopologyGraph::new_in_memory().unwrap();
+        let a = graph.add_node(make_node("a"));
+        let b = graph.add_node(make_node("b"));
+        let c = graph.add_node(make_node("c"));
+        graph.set_edge(a, b, make_edge(0.5, 0)).unwrap();
+        graph.set_edge(c, a, make_edge(0.7, 0)).unwrap();
+        graph.flush();
+
+        assert_eq!(graph.get_edge(&b, &a).unwrap().weight, 0.5);
+        graph.get_edge_mut(&a, &c).unwrap().weight = 0.9;
+
+        let mut neighbors: Vec<(NodeId, f64)> = graph
+            .neighbors(&a)
+            .into_iter()
+            .map(|(id, e)| (id, e.weight))
+            .collect();
+        neighbors.sort_by(|x, y| x.1.partial_cmp(&y.1).unwrap());
+        assert_eq!(neighbors, vec![(b, 0.5), (c, 0.9)]);
+
+        assert_eq!(graph.remove_edge(&b, &a).unwrap().weight, 0.5);
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn failed_node_removal_panics_and_keeps_its_rows() {
+        let mut graph = SqliteTopologyGraph::new_in_memory().unwrap();
+        let a = graph.add_node(make_node("a"));
+        let b = graph.add_node(make_node("b"));
+        graph.set_edge(a, b, make_edge(0.5, 0)).unwrap();
+        graph.flush();
+        graph
+            .conn
+            .lock()
+            .unwrap()
+            .execute_batch("DROP TABLE node_annotations")
+            .unwrap();
+
+        let removal =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| graph.remove_node(&a)));
+        assert!(removal.is_err());
+        // The edge delete ran first, but was rolled back with the rest
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn exact_label_lookup_is_case_insensitive() {
+        let mut graph = SqliteTopologyGraph::new_in_memory().unwrap();
+        let id = graph.add_node(make_node("Cell Membrane"));
+        graph.add_node(make_node("membrane"));
+
+        assert_eq!(graph.find_nodes_by_exact_label("cell membrane"), vec![id]);
+        assert!(graph.find_nodes_by_exact_label("cell").is_empty());
+    }
+
+    #[test]
+    fn flush_evicts_cold_nodes_but_keeps_them_readable() {
+        let mut graph = SqliteTopologyGraph::new_in_memory()
+            .unwrap()
+            .with_cache_size(10);
+        let ids: Vec<NodeId> = (0..50)
+            .map(|i| graph.add_node(make_node(&format!("n{i}"))))
+            .collect();
+        graph.get_node_mut(&ids[0]).unwrap().access_count = 7;
+        graph.flush();
+
+        assert_eq!(graph.cached_node_count(), 10);
+        assert_eq!(graph.get_node(&ids[0]).unwrap().access_count, 7);
+        assert_eq!(graph.get_node(&ids[25]).unwrap().label, "n25");
+    }
+
+    #[test]
+    fn activity_decay_matches_in_memory_backend() {
+        let mut sql = SqliteTopologyGraph::new_in_memory().unwrap();
+        let mut mem = PetTopologyGraph::new();
+
+        let hub = make_node("hub");
+        sql.add_node(hub.clone());
+        mem.add_node(hub.clone());
+        for i in 0..20u64 {
+            let leaf = make_node(&format!("leaf{i}"));
+            sql.add_node(leaf.clone());
+            mem.add_node(leaf.clone());
+            let edge = EdgeData {
+                weight: 0.05 + i as f64 * 0.05,
+                co_activations: i % 4,
+                created_tick: i * 5,
+                last_activated_tick: i * 5,
+            };
+            sql.set_edge(hub.id, leaf.id, edge.clone()).unwrap();
+            mem.set_edge(hub.id, leaf.id, edge).unwrap();
+        }
+
+        let sql_pruned = sql.decay_edges_activity(0.05, 0.1, 100, 1.5, 50);
+        let mem_pruned = mem.decay_edges_activity(0.05, 0.1, 100, 1.5, 50);
+        assert_eq!(sql_pruned.len(), mem_pruned.len());
+
+        let sql_pruned = sql.prune_to_max_degree(5);
+        let mem_pruned = mem.prune_to_max_degree(5);
+        assert_eq!(sql_pruned.len(), mem_pruned.len());
+        assert_eq!(sql.edge_count(), mem.edge_count());
+
+        for (other, edge) in mem.neighbors(&hub.id) {
+            let stored = sql.get_edge(&hub.id, &other).unwrap();
+            assert!((stored.weight - edge.weight).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn pending_writes_survive_reopen() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("graph.db");
+
+        let (a, b) = {
+            let mut graph = SqliteTopologyGraph::open(&path).unwrap();
+            let a = graph.add_node(make_node("alpha"));
+            let b = graph.add_node(make_node("beta"));
+            graph.set_edge(a, b, make_edge(0.4, 3)).unwrap();
+            (a, b)
+        };
+
+        let graph = SqliteTopologyGraph::open(&path).unwrap();
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.get_node(&a).unwrap().label, "alpha");
+        assert_eq!(graph.get_edge(&a, &b).unwrap().created_tick, 3);
+        assert_eq!(graph.shortest_path(&a, &b).unwrap().0, vec![a, b]);
+    }
+
+    #[test]
+    fn tags_survive_reopen() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("graph.db");
+
+        let (tagged, untagged) = {
+            let mut graph = SqliteTopologyGraph::open(&path).unwrap();
+            let mut node = make_node("project");
+            node.add_tags(&["work".to_string(), "personal".to_string()]);
+            node.add_tags(&["work".to_string()]);
+            (graph.add_node(node), graph.add_node(make_node("budget")))
+        };
+
+        let graph = SqliteTopologyGraph::open(&path).unwrap();
+        let tags = &graph.get_node(&tagged).unwrap().tags;
+        assert_eq!(tags.get("work"), Some(&2));
+        assert_eq!(tags.get("personal"), Some(&1));
+        assert!(graph.get_node(&untagged).unwrap().tags.is_empty());
+    }
 }
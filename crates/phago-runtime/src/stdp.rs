@@ -116,8 +116,7 @@ impl StdpGraph {
 
             // Wire to successors within the window
             let end = (i + 1 + self.config.window).min(sequence.len());
-            for j in (i + 1)..end {
-                let to = sequence[j];
+            for (j, &to) in sequence.iter().enumerate().take(end).skip(i + 1) {
                 let to_idx = self.ensure_node(to);
                 let distance = (j - i) as f64;
 
@@ -238,8 +237,8 @@ impl StdpGraph {
                 .graph
                 .neighbors_directed(current, petgraph::Direction::Outgoing)
             {
-                if !visited.contains_key(&neighbor) {
-                    visited.insert(neighbor, Some(current));
+                if let std::collections::hash_map::Entry::Vacant(e) = visited.entry(neighbor) {
+                    e.insert(Some(current));
                     queue.push_back(neighbor);
                 }
             }
@@ -1,17 +1,32 @@
 //! Concrete implementation of the Substrate trait.
 //!
 //! In-memory substrate with:
-//! - Signal field stored as a Vec (linear scan with distance filtering)
-//! - Knowledge graph backed by PetTopologyGraph
-//! - Trace storage as a HashMap keyed by SubstrateLocation
+//! - Signal field stored as a Vec (linear scan with distance filtering),
+//!   optionally merging nearby signals of a kind and capping their number
+//!   (see [`SignalCoalescing`])
+//! - Knowledge graph behind a boxed TopologyGraph (PetTopologyGraph by default)
+//! - Node embeddings in a side table beside the graph (see [`EmbeddingTable`])
+//! - Term embeddings agents computed, for them to look up (see [`EmbeddingCache`])
+//! - An optional inverted index over node labels (see [`InvertedIndex`])
+//! - Trace storage as a HashMap keyed by SubstrateLocation, with byte
+//!   accounting and an optional budget that evicts the weakest traces
+//! - Named regions of the field decaying at their own rates (see
+//!   [`crate::region`])
 //! - Serialization support for persistence across restarts
 
+use crate::backend::DynTopologyGraph;
+use crate::embedding_cache::EmbeddingCache;
+use crate::embedding_store::EmbeddingTable;
+use crate::inverted_index::InvertedIndex;
+use crate::region::{Region, RegionError, RegionPolicy, RegionShape};
 use crate::topology_impl::PetTopologyGraph;
-use phago_core::substrate::Substrate;
-use phago_core::topology::TopologyGraph;
+use phago_core::signal::SignalKindInfo;
+use phago_core::substrate::{Ambient, Substrate};
+use phago_core::tokenize::Tokenizer;
+use phago_core::topology::{TopologyError, TopologyGraph};
 use phago_core::types::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// In-memory substrate implementation.
 ///
@@ -21,12 +36,86 @@ use std::collections::HashMap;
 /// (for indirect coordination).
 pub struct SubstrateImpl {
     signals: Vec<Signal>,
-    graph: PetTopologyGraph,
+    /// Labels and decay overrides registered for signal kinds.
+    signal_kinds: HashMap<SignalType, SignalKindInfo>,
+    /// How emitted signals merge; `None` keeps every one.
+    coalescing: Option<SignalCoalescing>,
+    signals_merged: u64,
+    signals_evicted: u64,
+    graph: DynTopologyGraph,
+    embeddings: EmbeddingTable,
+    /// Embeddings of terms agents presented, by term.
+    embedding_cache: EmbeddingCache,
+    /// Label postings for TF-IDF retrieval, when enabled.
+    term_index: Option<InvertedIndex>,
     traces: HashMap<TraceLocationKey, Vec<Trace>>,
+    /// Running total of [`trace_size`] over all stored traces.
+    trace_bytes: usize,
+    /// Trace memory budget in bytes; 0 means unlimited.
+    max_trace_bytes: usize,
+    traces_evicted: u64,
     documents: HashMap<DocumentId, Document>,
+    /// Document → where its concepts occur in its content.
+    passages: HashMap<DocumentId, DocumentPassages>,
+    geometry: WorldGeometry,
+    /// Named zones of the field, in the order they were defined.
+    regions: Vec<Region>,
+    /// Colony-wide conditions agents read, set by the colony each tick.
+    ambient: Ambient,
+    /// Bumped on every change that can alter query results.
+    revision: u64,
     tick: Tick,
 }
 
+/// How the substrate combines emitted signals of some kinds so that bulk
+/// emission into one region does not pile up thousands of overlapping
+/// signals; see [`SubstrateImpl::set_signal_coalescing`].
+///
+/// A signal emitted within `merge_radius` of a live signal of the same
+/// kind merges into the nearest one: the intensities add up, to at most
+/// `max_intensity`, the merged signal moves to their intensity-weighted
+/// center, and its [`sources`](Signal::sources) counts the emissions.
+/// Gradients still point toward the region the signals came from, but
+/// their magnitude stops growing once the cap is reached, so a thousand
+/// documents in one spot pull little harder than a handful. Signals that
+/// carry a payload never merge.
+///
+/// Beyond `max_per_kind` live signals of a kind, the weakest are evicted,
+/// the oldest first among equals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalCoalescing {
+    /// Kinds that merge and are capped (default: input signals only).
+    pub kinds: Vec<SignalType>,
+    /// Distance within which signals merge; 0 merges only signals at the
+    /// same position (default: 2.0).
+    pub merge_radius: f64,
+    /// Highest intensity merging builds up (default: 5.0).
+    pub max_intensity: f64,
+    /// Live signals kept per kind; 0 means unlimited (default: 1000).
+    pub max_per_kind: usize,
+}
+
+impl Default for SignalCoalescing {
+    fn default() -> Self {
+        Self {
+            kinds: vec![SignalType::Input],
+            merge_radius: 2.0,
+            max_intensity: 5.0,
+            max_per_kind: 1000,
+        }
+    }
+}
+
+/// The first of `regions` containing `position`.
+fn region_at<'a>(regions: &'a [Region], position: &Position) -> Option<&'a Region> {
+    regions.iter().find(|r| r.contains(position))
+}
+
+/// Approximate memory held by a trace: the struct plus its payload.
+fn trace_size(trace: &Trace) -> usize {
+    std::mem::size_of::<Trace>() + trace.payload.len()
+}
+
 /// Key for trace storage. We need something hashable for SubstrateLocation.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 enum TraceLocationKey {
@@ -51,6 +140,12 @@ impl From<&Position> for OrderedPosition {
     }
 }
 
+impl From<&OrderedPosition> for Position {
+    fn from(p: &OrderedPosition) -> Self {
+        Position::new(p.x as f64 / 10.0, p.y as f64 / 10.0)
+    }
+}
+
 impl From<&SubstrateLocation> for TraceLocationKey {
     fn from(loc: &SubstrateLocation) -> Self {
         match loc {
@@ -64,15 +159,119 @@ impl From<&SubstrateLocation> for TraceLocationKey {
 
 impl SubstrateImpl {
     pub fn new() -> Self {
+        Self::with_graph(Box::new(PetTopologyGraph::new()))
+    }
+
+    /// Create a substrate on top of an existing graph backend.
+    ///
+    /// Use with [`crate::backend::create_backend`] to run on SQLite
+    /// instead of the in-memory petgraph store.
+    pub fn with_graph(graph: DynTopologyGraph) -> Self {
         Self {
             signals: Vec::new(),
-            graph: PetTopologyGraph::new(),
+            signal_kinds: HashMap::new(),
+            coalescing: None,
+            signals_merged: 0,
+            signals_evicted: 0,
+            graph,
+            embeddings: EmbeddingTable::default(),
+            embedding_cache: EmbeddingCache::default(),
+            term_index: None,
             traces: HashMap::new(),
+            trace_bytes: 0,
+            max_trace_bytes: 0,
+            traces_evicted: 0,
             documents: HashMap::new(),
+            passages: HashMap::new(),
+            geometry: WorldGeometry::default(),
+            regions: Vec::new(),
+            ambient: Ambient::default(),
+            revision: 0,
             tick: 0,
         }
     }
 
+    /// Bound (or unbound) the spatial field.
+    pub fn set_geometry(&mut self, geometry: WorldGeometry) {
+        self.geometry = geometry;
+    }
+
+    /// Define a named region of the field, replacing any region of the
+    /// same name. See [`crate::region`].
+    pub fn define_region(
+        &mut self,
+        name: &str,
+        shape: RegionShape,
+        policy: RegionPolicy,
+    ) -> Result<(), RegionError> {
+        self.add_region(Region::new(name, shape, policy)?);
+        Ok(())
+    }
+
+    /// Add a region, replacing any region of the same name in place.
+    pub fn add_region(&mut self, region: Region) {
+        match self.regions.iter_mut().find(|r| r.name == region.name) {
+            Some(existing) => *existing = region,
+            None => self.regions.push(region),
+        }
+    }
+
+    /// Remove the region named `name`, returning it.
+    pub fn remove_region(&mut self, name: &str) -> Option<Region> {
+        let index = self.regions.iter().position(|r| r.name == name)?;
+        Some(self.regions.remove(index))
+    }
+
+    /// Set the colony-wide conditions agents read through
+    /// [`Substrate::ambient`].
+    pub fn set_ambient(&mut self, ambient: Ambient) {
+        self.ambient = ambient;
+    }
+
+    /// Every region, in the order they were defined.
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    pub fn region(&self, name: &str) -> Option<&Region> {
+        self.regions.iter().find(|r| r.name == name)
+    }
+
+    /// The region whose policy applies at `position`: the first defined
+    /// that contains it.
+    pub fn region_at(&self, position: &Position) -> Option<&Region> {
+        region_at(&self.regions, position)
+    }
+
+    /// Factor on the decay rate of the edge between `from` and `to`: the
+    /// smaller of its endpoints' regions' factors, where an endpoint
+    /// outside every region counts 1.0.
+    pub fn edge_decay_multiplier(&self, from: &NodeId, to: &NodeId) -> f64 {
+        [from, to]
+            .iter()
+            .map(|id| {
+                self.graph
+                    .get_node(id)
+                    .and_then(|n| self.region_at(&n.position))
+                    .map_or(1.0, |r| r.policy.edge_decay_multiplier)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// [`edge_decay_multiplier`](Self::edge_decay_multiplier) of every
+    /// edge it is not 1.0 for; empty without regions.
+    pub(crate) fn edge_decay_multipliers(&self) -> HashMap<(NodeId, NodeId), f64> {
+        if self.regions.is_empty() {
+            return HashMap::new();
+        }
+        self.graph
+            .all_edges()
+            .into_iter()
+            .map(|(from, to, _)| ((from, to), self.edge_decay_multiplier(&from, &to)))
+            .filter(|(_, m)| *m != 1.0)
+            .collect()
+    }
+
     /// Get a document by ID (convenience method bypassing trait).
     pub fn get_document(&self, id: &DocumentId) -> Option<&Document> {
         self.documents.get(id)
@@ -83,14 +282,185 @@ impl SubstrateImpl {
         self.documents.values().collect()
     }
 
+    /// Record that concept `label` occurs in `spans` of a document.
+    pub fn add_passages(&mut self, doc: DocumentId, label: &str, spans: &[TextSpan]) {
+        if !spans.is_empty() {
+            self.revision += 1;
+            self.passages.entry(doc).or_default().add(label, spans);
+        }
+    }
+
+    /// Where the concepts of a document occur in its content.
+    pub fn passages(&self, doc: &DocumentId) -> Option<&DocumentPassages> {
+        self.passages.get(doc)
+    }
+
+    /// Replace the passages recorded for a document, e.g. from a saved session.
+    pub fn set_passages(&mut self, doc: DocumentId, passages: DocumentPassages) {
+        self.revision += 1;
+        if passages.is_empty() {
+            self.passages.remove(&doc);
+        } else {
+            self.passages.insert(doc, passages);
+        }
+    }
+
+    /// File every passage recorded under concept `from` under `to`, in all
+    /// documents.
+    pub fn rename_passages(&mut self, from: &str, to: &str) {
+        let mut renamed = false;
+        for passages in self.passages.values_mut() {
+            renamed |= passages.rename(from, to);
+        }
+        if renamed {
+            self.revision += 1;
+        }
+    }
+
+    /// Forget a document's passages, e.g. because its content changed.
+    pub fn clear_passages(&mut self, doc: &DocumentId) {
+        self.revision += 1;
+        self.passages.remove(doc);
+    }
+
+    /// Remove a document along with its passages.
+    pub fn remove_document(&mut self, id: &DocumentId) -> Option<Document> {
+        let doc = self.documents.remove(id)?;
+        self.passages.remove(id);
+        self.revision += 1;
+        Some(doc)
+    }
+
     /// Get a reference to the underlying topology graph.
-    pub fn graph(&self) -> &PetTopologyGraph {
-        &self.graph
+    pub fn graph(&self) -> &(dyn TopologyGraph + Send + Sync) {
+        self.graph.as_ref()
     }
 
     /// Get a mutable reference to the underlying topology graph.
-    pub fn graph_mut(&mut self) -> &mut PetTopologyGraph {
-        &mut self.graph
+    ///
+    /// Counts as a change for [`graph_revision`](Self::graph_revision),
+    /// whether or not the caller ends up modifying anything.
+    pub fn graph_mut(&mut self) -> &mut (dyn TopologyGraph + Send + Sync) {
+        self.revision += 1;
+        self.graph.as_mut()
+    }
+
+    /// Mark nodes as accessed at the current tick. Unknown IDs are ignored.
+    ///
+    /// Not a change for [`graph_revision`](Self::graph_revision): when a
+    /// node was last accessed does not change what queries make of the
+    /// graph, so recalls can touch what they surface without expiring the
+    /// cursors and cached rankings of other recalls.
+    pub fn touch_nodes(&mut self, ids: &[NodeId]) {
+        let tick = self.current_tick();
+        for id in ids {
+            if let Some(node) = self.graph.get_node_mut(id) {
+                node.last_accessed_tick = tick;
+            }
+        }
+    }
+
+    /// Count as a change for [`graph_revision`](Self::graph_revision)
+    /// without changing anything, because what queries make of the graph
+    /// did change.
+    pub fn mark_changed(&mut self) {
+        self.revision += 1;
+    }
+
+    /// Remove a node from the graph together with its embedding and its
+    /// term index entry.
+    pub fn remove_node(&mut self, id: &NodeId) -> Option<NodeData> {
+        self.embeddings.remove(id);
+        if let Some(index) = &mut self.term_index {
+            index.remove(id);
+        }
+        self.graph_mut().remove_node(id)
+    }
+
+    /// Index node labels with `tokenizer` from now on, starting with the
+    /// nodes already in the graph.
+    pub fn enable_term_index(&mut self, tokenizer: Tokenizer) {
+        self.term_index = Some(InvertedIndex::build(tokenizer, self.graph.as_ref()));
+    }
+
+    /// Stop maintaining the term index; queries scan the graph instead.
+    pub fn disable_term_index(&mut self) {
+        self.term_index = None;
+    }
+
+    /// Whether node labels are being indexed.
+    pub fn term_index_enabled(&self) -> bool {
+        self.term_index.is_some()
+    }
+
+    /// The term index, if it is enabled and up to date with the graph.
+    ///
+    /// Nodes added or removed through [`graph_mut`](Self::graph_mut) bypass
+    /// the index; it is withheld until [`sync_term_index`](Self::sync_term_index)
+    /// once it has been invalidated or no longer covers every node.
+    pub fn term_index(&self) -> Option<&InvertedIndex> {
+        self.term_index
+            .as_ref()
+            .filter(|index| !index.is_stale() && index.node_count() == self.graph.node_count())
+    }
+
+    /// Mark the term index for a rebuild, after the graph changed in ways it
+    /// was not told about.
+    pub fn invalidate_term_index(&mut self) {
+        if let Some(index) = &mut self.term_index {
+            index.invalidate();
+        }
+    }
+
+    /// Rebuild the term index from the graph if it is enabled but out of
+    /// date. Returns whether it was rebuilt.
+    pub fn sync_term_index(&mut self) -> bool {
+        let node_count = self.graph.node_count();
+        match &mut self.term_index {
+            Some(index) if index.is_stale() || index.node_count() != node_count => {
+                index.rebuild(self.graph.as_ref());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Node embeddings, kept beside the graph rather than in its nodes.
+    pub fn embeddings(&self) -> &EmbeddingTable {
+        &self.embeddings
+    }
+
+    /// Embeddings are not part of [`graph_revision`](Self::graph_revision):
+    /// they change how new edges are weighted, not query results.
+    pub fn embeddings_mut(&mut self) -> &mut EmbeddingTable {
+        &mut self.embeddings
+    }
+
+    /// The embedding of a node, dequantized.
+    pub fn embedding(&self, id: &NodeId) -> Option<Vec<f32>> {
+        self.embeddings.get(id)
+    }
+
+    /// Give a node an embedding, replacing any earlier one.
+    pub fn set_embedding(&mut self, id: NodeId, embedding: &[f32]) {
+        self.embeddings.insert(id, embedding);
+    }
+
+    /// Embeddings of terms, which agents look up through
+    /// [`Substrate::cached_embedding`].
+    pub fn embedding_cache(&self) -> &EmbeddingCache {
+        &self.embedding_cache
+    }
+
+    pub fn embedding_cache_mut(&mut self) -> &mut EmbeddingCache {
+        &mut self.embedding_cache
+    }
+
+    /// Counter that increases whenever the graph, the documents or their
+    /// passages may have changed. Equal revisions mean query results
+    /// computed at the earlier one are still current.
+    pub fn graph_revision(&self) -> u64 {
+        self.revision
     }
 
     /// Get all signals (for diagnostics/visualization).
@@ -98,11 +468,186 @@ impl SubstrateImpl {
         &self.signals
     }
 
+    /// Register a label, and optionally a decay rate, for a signal kind.
+    /// Returns what was registered for it before.
+    pub fn register_signal_kind(
+        &mut self,
+        kind: SignalType,
+        info: SignalKindInfo,
+    ) -> Option<SignalKindInfo> {
+        self.signal_kinds.insert(kind, info)
+    }
+
+    /// Every registered signal kind.
+    pub fn signal_kinds(&self) -> impl Iterator<Item = (&SignalType, &SignalKindInfo)> {
+        self.signal_kinds.iter()
+    }
+
+    /// How emitted signals merge, if they do.
+    pub fn signal_coalescing(&self) -> Option<&SignalCoalescing> {
+        self.coalescing.as_ref()
+    }
+
+    /// Merge and cap signals as `coalescing` says from now on; `None`
+    /// keeps every emitted signal as it is. Signals already live stay.
+    pub fn set_signal_coalescing(&mut self, coalescing: Option<SignalCoalescing>) {
+        self.coalescing = coalescing;
+    }
+
+    /// Emitted signals merged into a live one.
+    pub fn signals_merged(&self) -> u64 {
+        self.signals_merged
+    }
+
+    /// Signals evicted to stay within the per-kind cap.
+    pub fn signals_evicted(&self) -> u64 {
+        self.signals_evicted
+    }
+
+    /// Merge `signal` into the nearest live signal of its kind within the
+    /// merge radius. Gives it back if there is none.
+    fn merge_signal(&mut self, signal: Signal, coalescing: &SignalCoalescing) -> Option<Signal> {
+        if signal.payload_kind.is_some() || !signal.payload.is_empty() {
+            return Some(signal);
+        }
+        let nearest = self
+            .signals
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.signal_type == signal.signal_type && s.payload_kind.is_none())
+            .map(|(i, s)| (i, self.geometry.distance(&s.position, &signal.position)))
+            .filter(|(_, d)| *d <= coalescing.merge_radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let Some((index, _)) = nearest else {
+            return Some(signal);
+        };
+
+        let live = &mut self.signals[index];
+        let total = live.intensity + signal.intensity;
+        if total > f64::EPSILON {
+            let offset = self.geometry.offset(&live.position, &signal.position);
+            let share = signal.intensity / total;
+            live.position = self.geometry.confine(Position::new(
+                live.position.x + offset.x * share,
+                live.position.y + offset.y * share,
+            ));
+        }
+        live.intensity = total.min(coalescing.max_intensity);
+        live.sources = live.sources.saturating_add(signal.sources);
+        live.tick = live.tick.max(signal.tick);
+        self.signals_merged += 1;
+        None
+    }
+
+    /// Evict the weakest signals of `kind` beyond the per-kind cap.
+    fn enforce_signal_cap(&mut self, kind: &SignalType, max: usize) {
+        let mut live: Vec<(usize, f64, Tick)> = self
+            .signals
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| &s.signal_type == kind)
+            .map(|(i, s)| (i, s.intensity, s.tick))
+            .collect();
+        if live.len() <= max {
+            return;
+        }
+        live.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.2.cmp(&b.2)));
+        let doomed: HashSet<usize> = live[..live.len() - max].iter().map(|e| e.0).collect();
+        self.signals_evicted += doomed.len() as u64;
+        let mut index = 0;
+        self.signals.retain(|_| {
+            index += 1;
+            !doomed.contains(&(index - 1))
+        });
+    }
+
+    /// Number of live signals of each kind, keyed by kind name.
+    pub fn signal_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for signal in &self.signals {
+            *counts.entry(signal.signal_type.to_string()).or_default() += 1;
+        }
+        counts
+    }
+
     /// Total number of traces across all locations.
     pub fn total_trace_count(&self) -> usize {
         self.traces.values().map(|v| v.len()).sum()
     }
 
+    /// All stored traces (for diagnostics/accounting).
+    pub fn all_traces(&self) -> Vec<&Trace> {
+        self.traces.values().flatten().collect()
+    }
+
+    /// Approximate bytes held by stored traces, payloads included.
+    pub fn trace_memory_bytes(&self) -> usize {
+        self.trace_bytes
+    }
+
+    /// The trace memory budget in bytes (0 = unlimited).
+    pub fn max_trace_bytes(&self) -> usize {
+        self.max_trace_bytes
+    }
+
+    /// Cap trace memory at `max` bytes (0 = unlimited). When a deposit
+    /// pushes the total over the cap, the weakest traces are evicted,
+    /// oldest first among equals.
+    pub fn set_max_trace_bytes(&mut self, max: usize) {
+        self.max_trace_bytes = max;
+        self.enforce_trace_budget();
+    }
+
+    /// Number of traces evicted to stay within the memory budget.
+    pub fn traces_evicted(&self) -> u64 {
+        self.traces_evicted
+    }
+
+    fn enforce_trace_budget(&mut self) {
+        if self.max_trace_bytes == 0 || self.trace_bytes <= self.max_trace_bytes {
+            return;
+        }
+
+        let mut candidates: Vec<(f64, Tick, TraceLocationKey, usize, usize)> = self
+            .traces
+            .iter()
+            .flat_map(|(key, traces)| {
+                traces
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| (t.intensity, t.tick, key.clone(), i, trace_size(t)))
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            a.0.partial_cmp(&b.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.1.cmp(&b.1))
+        });
+
+        let mut doomed: HashMap<TraceLocationKey, Vec<usize>> = HashMap::new();
+        for (_, _, key, index, size) in candidates {
+            if self.trace_bytes <= self.max_trace_bytes {
+                break;
+            }
+            self.trace_bytes -= size;
+            self.traces_evicted += 1;
+            doomed.entry(key).or_default().push(index);
+        }
+        for (key, indices) in doomed {
+            if let Some(traces) = self.traces.get_mut(&key) {
+                let mut index = 0;
+                traces.retain(|_| {
+                    let keep = !indices.contains(&index);
+                    index += 1;
+                    keep
+                });
+                if traces.is_empty() {
+                    self.traces.remove(&key);
+                }
+            }
+        }
+    }
+
     /// Get all traces of a given type within a radius of a position.
     pub fn traces_near(
         &self,
@@ -114,6 +659,42 @@ impl SubstrateImpl {
         let cx = (position.x * 10.0).round() as i64;
         let cy = (position.y * 10.0).round() as i64;
 
+        // Offset of a cell from the center, in grid units; the short way
+        // round when the field wraps
+        let wraps = self.geometry.wraps();
+        let center = Position::new(cx as f64 / 10.0, cy as f64 / 10.0);
+        let in_range = |p: &OrderedPosition| {
+            let (dx, dy) = if wraps {
+                let cell = Position::new(p.x as f64 / 10.0, p.y as f64 / 10.0);
+                let d = self.geometry.offset(&center, &cell);
+                ((d.x * 10.0).round() as i64, (d.y * 10.0).round() as i64)
+            } else {
+                (p.x - cx, p.y - cy)
+            };
+            dx.abs() <= r_grid && dy.abs() <= r_grid
+        };
+
+        // With fewer occupied cells than cells in range, visit those
+        // instead, in the order the grid scan would. The grid scan does
+        // not know about the seam, so a wrapping field always comes here.
+        let side = (2 * r_grid + 1) as usize;
+        if wraps || self.traces.len() < side * side {
+            let mut cells: Vec<(&OrderedPosition, &Vec<Trace>)> = self
+                .traces
+                .iter()
+                .filter_map(|(key, traces)| match key {
+                    TraceLocationKey::Spatial(p) if in_range(p) => Some((p, traces)),
+                    _ => None,
+                })
+                .collect();
+            cells.sort_by_key(|(p, _)| (p.x, p.y));
+            return cells
+                .into_iter()
+                .flat_map(|(_, traces)| traces)
+                .filter(|trace| &trace.trace_type == trace_type)
+                .collect();
+        }
+
         let mut results = Vec::new();
         // Scan grid cells within radius
         for dx in -r_grid..=r_grid {
@@ -144,25 +725,53 @@ impl Default for SubstrateImpl {
 impl Substrate for SubstrateImpl {
     // --- Signal field ---
 
+    fn geometry(&self) -> WorldGeometry {
+        self.geometry
+    }
+
     fn signals_near(&self, position: &Position, radius: f64) -> Vec<&Signal> {
         let r2 = radius * radius;
         self.signals
             .iter()
             .filter(|s| {
-                let dx = s.position.x - position.x;
-                let dy = s.position.y - position.y;
-                dx * dx + dy * dy <= r2
+                let d = self.geometry.offset(position, &s.position);
+                d.x * d.x + d.y * d.y <= r2
             })
             .collect()
     }
 
     fn emit_signal(&mut self, signal: Signal) {
-        self.signals.push(signal);
+        let Some(coalescing) = self
+            .coalescing
+            .take_if(|c| c.kinds.contains(&signal.signal_type))
+        else {
+            self.signals.push(signal);
+            return;
+        };
+        let kind = signal.signal_type.clone();
+        if let Some(signal) = self.merge_signal(signal, &coalescing) {
+            self.signals.push(signal);
+            if coalescing.max_per_kind > 0 {
+                self.enforce_signal_cap(&kind, coalescing.max_per_kind);
+            }
+        }
+        self.coalescing = Some(coalescing);
+    }
+
+    fn signal_kind_info(&self, kind: &SignalType) -> Option<&SignalKindInfo> {
+        self.signal_kinds.get(kind)
     }
 
     fn decay_signals(&mut self, rate: f64, removal_threshold: f64) {
         for signal in &mut self.signals {
-            signal.decay(rate);
+            let rate = self
+                .signal_kinds
+                .get(&signal.signal_type)
+                .and_then(|info| info.decay_rate)
+                .unwrap_or(rate);
+            let multiplier = region_at(&self.regions, &signal.position)
+                .map_or(1.0, |r| r.policy.signal_decay_multiplier);
+            signal.decay((rate * multiplier).min(1.0));
         }
         self.signals
             .retain(|s| !s.is_below_threshold(removal_threshold));
@@ -171,6 +780,10 @@ impl Substrate for SubstrateImpl {
     // --- Knowledge graph ---
 
     fn add_node(&mut self, data: NodeData) -> NodeId {
+        self.revision += 1;
+        if let Some(index) = &mut self.term_index {
+            index.insert(&data);
+        }
         self.graph.add_node(data)
     }
 
@@ -178,8 +791,10 @@ impl Substrate for SubstrateImpl {
         self.graph.get_node(id)
     }
 
-    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData) {
-        self.graph.set_edge(from, to, data);
+    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData) -> Result<(), TopologyError> {
+        self.graph.set_edge(from, to, data)?;
+        self.revision += 1;
+        Ok(())
     }
 
     fn get_edge(&self, from: &NodeId, to: &NodeId) -> Option<&EdgeData> {
@@ -191,6 +806,7 @@ impl Substrate for SubstrateImpl {
     }
 
     fn remove_edge(&mut self, from: &NodeId, to: &NodeId) {
+        self.revision += 1;
         self.graph.remove_edge(from, to);
     }
 
@@ -214,7 +830,9 @@ impl Substrate for SubstrateImpl {
 
     fn deposit_trace(&mut self, location: &SubstrateLocation, trace: Trace) {
         let key = TraceLocationKey::from(location);
+        self.trace_bytes += trace_size(&trace);
         self.traces.entry(key).or_default().push(trace);
+        self.enforce_trace_budget();
     }
 
     fn traces_at(&self, location: &SubstrateLocation) -> Vec<&Trace> {
@@ -226,11 +844,26 @@ impl Substrate for SubstrateImpl {
     }
 
     fn decay_traces(&mut self, rate: f64, removal_threshold: f64) {
-        for traces in self.traces.values_mut() {
+        for (key, traces) in self.traces.iter_mut() {
+            let position = match key {
+                TraceLocationKey::Spatial(p) => Some(Position::from(p)),
+                TraceLocationKey::GraphNode(id) => self.graph.get_node(id).map(|n| n.position),
+            };
+            let multiplier = position
+                .and_then(|p| region_at(&self.regions, &p))
+                .map_or(1.0, |r| r.policy.trace_decay_multiplier);
+            let rate = (rate * multiplier).min(1.0);
             for trace in traces.iter_mut() {
                 trace.intensity *= 1.0 - rate;
             }
-            traces.retain(|t| t.intensity >= removal_threshold);
+            let bytes = &mut self.trace_bytes;
+            traces.retain(|t| {
+                let keep = t.intensity >= removal_threshold;
+                if !keep {
+                    *bytes -= trace_size(t);
+                }
+                keep
+            });
         }
         // Remove empty locations
         self.traces.retain(|_, v| !v.is_empty());
@@ -239,6 +872,7 @@ impl Substrate for SubstrateImpl {
     // --- Document storage ---
 
     fn add_document(&mut self, doc: Document) {
+        self.revision += 1;
         self.documents.insert(doc.id, doc);
     }
 
@@ -264,6 +898,10 @@ impl Substrate for SubstrateImpl {
         self.documents.values().collect()
     }
 
+    fn cached_embedding(&self, term: &str) -> Option<Vec<f32>> {
+        self.embedding_cache.get(term)
+    }
+
     // --- Lifecycle ---
 
     fn current_tick(&self) -> Tick {
@@ -273,6 +911,10 @@ impl Substrate for SubstrateImpl {
     fn advance_tick(&mut self) {
         self.tick += 1;
     }
+
+    fn ambient(&self) -> Ambient {
+        self.ambient
+    }
 }
 
 #[cfg(test)]
@@ -299,6 +941,42 @@ mod tests {
         assert_eq!(near.len(), 1);
     }
 
+    #[test]
+    fn signal_payload_round_trips_through_sensing() {
+        use phago_core::signal::{AnomalyWarning, SignalPayload};
+
+        let mut sub = SubstrateImpl::new();
+        let warning = AnomalyWarning {
+            terms: vec!["virus".to_string(), "toxin".to_string()],
+        };
+        sub.emit_signal(make_signal(1.0, 0.0, 1.0).with_payload(&warning));
+        sub.emit_signal(make_signal(0.0, 1.0, 1.0));
+        let mut foreign = make_signal(1.0, 1.0, 1.0);
+        foreign.payload_kind = Some("from_a_newer_build".to_string());
+        foreign.payload = b"{\"unknown\":true}".to_vec();
+        sub.emit_signal(foreign);
+
+        let origin = Position::new(0.0, 0.0);
+        let warned = sub.signals_with_payload_near(&origin, 5.0, AnomalyWarning::KIND);
+        assert_eq!(warned.len(), 1);
+        assert_eq!(warned[0].payload::<AnomalyWarning>(), Some(warning.clone()));
+
+        // Plain and unfamiliar signals decode to nothing but still decay normally
+        let near = sub.signals_near(&origin, 5.0);
+        assert_eq!(near.len(), 3);
+        assert_eq!(
+            near.iter()
+                .filter_map(|s| s.payload::<AnomalyWarning>())
+                .count(),
+            1
+        );
+        sub.decay_signals(0.5, 0.04);
+        assert_eq!(sub.all_signals().len(), 3);
+        assert!(sub.all_signals().iter().all(|s| s.intensity == 0.5));
+        let kept = sub.signals_with_payload_near(&origin, 5.0, AnomalyWarning::KIND);
+        assert_eq!(kept[0].payload::<AnomalyWarning>(), Some(warning));
+    }
+
     #[test]
     fn signal_decay_removes_weak_signals() {
         let mut sub = SubstrateImpl::new();
@@ -310,6 +988,103 @@ mod tests {
         assert_eq!(sub.all_signals().len(), 1); // Only the strong one survives
     }
 
+    #[test]
+    fn nearby_signals_coalesce_and_keep_their_gradient() {
+        use phago_core::signal::compute_gradient;
+
+        let mut sub = SubstrateImpl::new();
+        sub.set_signal_coalescing(Some(SignalCoalescing::default()));
+        // 1,000 documents scattered over a 10×10 region around (50, 50)
+        for i in 0..1000 {
+            let (x, y) = ((i % 20) as f64 * 0.5, (i / 20 % 20) as f64 * 0.5);
+            sub.emit_signal(make_signal(45.0 + x, 45.0 + y, 1.0));
+        }
+        let signals = sub.all_signals();
+        assert!(signals.len() < 50, "{} signals", signals.len());
+        assert_eq!(signals.iter().map(|s| s.sources).sum::<u32>(), 1000);
+        assert!(signals.iter().all(|s| s.intensity <= 5.0));
+        assert_eq!(sub.signals_merged() as usize, 1000 - signals.len());
+
+        // Seen from the origin the field still pulls toward the region
+        let origin = Position::new(0.0, 0.0);
+        let near = sub.signals_near(&origin, 200.0);
+        let gradient = compute_gradient(&near, &origin).unwrap();
+        assert!(gradient.direction.x > 0.6 && gradient.direction.y > 0.6);
+
+        // Other kinds and signals with payloads stay one by one
+        let coalesced = sub.all_signals().len();
+        let mut presence = make_signal(50.0, 50.0, 1.0);
+        presence.signal_type = SignalType::Presence;
+        sub.emit_signal(presence.clone());
+        sub.emit_signal(presence);
+        let mut tagged = make_signal(50.0, 50.0, 1.0);
+        tagged.payload_kind = Some("note".into());
+        sub.emit_signal(tagged);
+        assert_eq!(sub.all_signals().len(), coalesced + 3);
+    }
+
+    #[test]
+    fn signals_beyond_the_cap_evict_the_weakest() {
+        let mut sub = SubstrateImpl::new();
+        sub.set_signal_coalescing(Some(SignalCoalescing {
+            merge_radius: 0.0,
+            max_per_kind: 3,
+            ..SignalCoalescing::default()
+        }));
+        for (i, intensity) in [0.5, 0.9, 0.1, 0.7, 0.3].into_iter().enumerate() {
+            sub.emit_signal(make_signal(i as f64 * 10.0, 0.0, intensity));
+        }
+        let mut left: Vec<f64> = sub.all_signals().iter().map(|s| s.intensity).collect();
+        left.sort_by(f64::total_cmp);
+        assert_eq!(left, vec![0.5, 0.7, 0.9]);
+        assert_eq!(sub.signals_evicted(), 2);
+
+        // Without coalescing every emission is kept
+        sub.set_signal_coalescing(None);
+        sub.emit_signal(make_signal(0.0, 0.0, 0.2));
+        sub.emit_signal(make_signal(0.0, 0.0, 0.2));
+        assert_eq!(sub.all_signals().len(), 5);
+        assert_eq!(sub.signals_merged(), 0);
+    }
+
+    #[test]
+    fn registered_kinds_decay_at_their_own_rate() {
+        use phago_core::signal::{SignalKindInfo, SignalKinds};
+
+        let review = SignalType::custom("review_request");
+        let mut sub = SubstrateImpl::new();
+        sub.register_signal_kind(
+            review.clone(),
+            SignalKindInfo::new("Request for review").with_decay_rate(0.1),
+        );
+        sub.emit_signal(make_signal(1.0, 0.0, 1.0));
+        let mut request = make_signal(0.0, 1.0, 1.0);
+        request.signal_type = review.clone();
+        sub.emit_signal(request);
+
+        let origin = Position::new(0.0, 0.0);
+        let subscribed =
+            sub.signals_of_kinds_near(&origin, 5.0, &SignalKinds::only([review.clone()]));
+        assert_eq!(subscribed.len(), 1);
+        assert_eq!(subscribed[0].signal_type, review);
+        assert_eq!(
+            sub.signal_kind_info(&review)
+                .map(|info| info.label.as_str()),
+            Some("Request for review")
+        );
+        assert_eq!(sub.signal_counts()["review_request"], 1);
+
+        sub.decay_signals(0.5, 0.04);
+        for signal in sub.all_signals() {
+            let expected = if signal.signal_type == review {
+                0.9
+            } else {
+                0.5
+            };
+            assert!((signal.intensity - expected).abs() < 1e-9);
+        }
+    }
+
     #[test]
     fn trace_deposit_and_retrieve() {
         let mut sub = SubstrateImpl::new();
@@ -358,6 +1133,50 @@ mod tests {
         assert_eq!(sub.traces_at(&loc).len(), 1);
     }
 
+    fn payload_trace(intensity: f64, tick: Tick, payload_len: usize) -> Trace {
+        Trace {
+            agent_id: AgentId::new(),
+            trace_type: TraceType::CapabilityDeposit,
+            intensity,
+            tick,
+            payload: vec![b'x'; payload_len],
+        }
+    }
+
+    #[test]
+    fn trace_memory_is_accounted_through_decay() {
+        let mut sub = SubstrateImpl::new();
+        let loc = SubstrateLocation::Spatial(Position::new(0.0, 0.0));
+        let base = std::mem::size_of::<Trace>();
+        sub.deposit_trace(&loc, payload_trace(1.0, 0, 100));
+        sub.deposit_trace(&loc, payload_trace(0.02, 0, 50));
+        assert_eq!(sub.trace_memory_bytes(), 2 * base + 150);
+
+        sub.decay_traces(0.5, 0.02);
+        assert_eq!(sub.trace_memory_bytes(), base + 100);
+    }
+
+    #[test]
+    fn trace_budget_evicts_weakest_then_oldest() {
+        let mut sub = SubstrateImpl::new();
+        let base = std::mem::size_of::<Trace>();
+        sub.set_max_trace_bytes(3 * (base + 100));
+
+        let at = |x: f64| SubstrateLocation::Spatial(Position::new(x, 0.0));
+        sub.deposit_trace(&at(0.0), payload_trace(0.9, 1, 100));
+        sub.deposit_trace(&at(1.0), payload_trace(0.3, 2, 100));
+        sub.deposit_trace(&at(2.0), payload_trace(0.3, 3, 100));
+        assert_eq!(sub.traces_evicted(), 0);
+
+        // Over budget: the older of the two weakest goes
+        sub.deposit_trace(&at(3.0), payload_trace(1.0, 4, 100));
+        assert_eq!(sub.traces_evicted(), 1);
+        assert_eq!(sub.total_trace_count(), 3);
+        assert!(sub.traces_at(&at(1.0)).is_empty());
+        assert_eq!(sub.traces_at(&at(2.0)).len(), 1);
+        assert!(sub.trace_memory_bytes() <= sub.max_trace_bytes());
+    }
+
     #[test]
     fn graph_operations_through_substrate() {
         let mut sub = SubstrateImpl::new();
@@ -369,7 +1188,8 @@ mod tests {
             position: Position::new(0.0, 0.0),
             access_count: 0,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
         let n2 = sub.add_node(NodeData {
             id: NodeId::new(),
@@ -378,7 +1198,8 @@ mod tests {
             position: Position::new(1.0, 0.0),
             access_count: 0,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
 
         sub.set_edge(
@@ -390,13 +1211,39 @@ mod tests {
                 created_tick: 0,
                 last_activated_tick: 0,
             },
-        );
+        )
+        .unwrap();
 
         assert_eq!(sub.node_count(), 2);
         assert_eq!(sub.edge_count(), 1);
         assert_eq!(sub.get_node(&n1).unwrap().label, "cell");
     }
 
+    #[test]
+    fn sensing_wraps_around_a_toroidal_field() {
+        let mut sub = SubstrateImpl::new();
+        sub.emit_signal(make_signal(99.0, 50.0, 1.0));
+        sub.deposit_trace(
+            &SubstrateLocation::Spatial(Position::new(99.5, 50.0)),
+            payload_trace(1.0, 0, 0),
+        );
+        let across_seam = Position::new(0.5, 50.0);
+        assert!(sub.signals_near(&across_seam, 3.0).is_empty());
+        assert!(sub
+            .traces_near(&across_seam, 3.0, &TraceType::CapabilityDeposit)
+            .is_empty());
+
+        let bounds = Rect::new(Position::new(0.0, 0.0), Position::new(100.0, 100.0));
+        sub.set_geometry(WorldGeometry::bounded(bounds, BoundaryBehavior::Wrap));
+        assert_eq!(sub.signals_near(&across_seam, 3.0).len(), 1);
+        assert_eq!(
+            sub.traces_near(&across_seam, 3.0, &TraceType::CapabilityDeposit)
+                .len(),
+            1
+        );
+        assert!((sub.distance(&across_seam, &Position::new(99.0, 50.0)) - 1.5).abs() < 1e-9);
+    }
+
     #[test]
     fn tick_advances() {
         let mut sub = SubstrateImpl::new();
@@ -0,0 +1,364 @@
+//! Synonym dictionaries — one concept under every name it goes by.
+//!
+//! A controlled vocabulary names a concept several ways ("myocardial
+//! infarction", "heart attack", "MI"). Left alone, the colony grows a node
+//! for each form, so reinforcement splits across them and a query using
+//! one form misses documents using another. A [`SynonymMap`] lists each
+//! canonical term with its aliases, and the colony applies it twice:
+//!
+//! - Where a presentation becomes nodes, a label that is an alias becomes
+//!   its canonical term, and the node keeps the alias the way merged
+//!   labels are kept (see [`Colony::merge_nodes`]). Every form reinforces
+//!   one node.
+//! - At query time, [`SynonymMap::expand`] adds the canonical term and its
+//!   aliases to the query's terms, weighted by
+//!   [`SynonymConfig::expansion_weight`] so that a node matching the term
+//!   as typed still ranks first.
+//!
+//! Terms match whole labels, ignoring case. Digesters present single
+//! words, so a multi-word alias canonicalizes the labels of agents that
+//! present phrases, and expands queries containing the phrase. Aliases
+//! shorter than the tokenizer's minimum term length never reach a query.
+//!
+//! Dictionaries are TSV, one canonical term per line followed by its
+//! aliases, or JSON objects from canonical terms to alias lists; see
+//! [`SynonymMap::load`]. [`Colony::set_synonyms`] swaps the dictionary of
+//! a running colony.
+//!
+//! [`Colony::merge_nodes`]: crate::colony::Colony::merge_nodes
+//! [`Colony::set_synonyms`]: crate::colony::Colony::set_synonyms
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Canonical terms and their aliases.
+///
+/// Serializes as an object from canonical terms to their aliases.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(
+    from = "BTreeMap<String, Vec<String>>",
+    into = "BTreeMap<String, Vec<String>>"
+)]
+pub struct SynonymMap {
+    entries: BTreeMap<String, Vec<String>>,
+    /// Every term, lowercase, to its canonical term.
+    canonical: HashMap<String, String>,
+    /// Words in the longest term.
+    max_words: usize,
+}
+
+impl From<BTreeMap<String, Vec<String>>> for SynonymMap {
+    fn from(entries: BTreeMap<String, Vec<String>>) -> Self {
+        let mut map = Self::new();
+        for (canonical, aliases) in entries {
+            map.insert(&canonical, aliases);
+        }
+        map
+    }
+}
+
+impl From<SynonymMap> for BTreeMap<String, Vec<String>> {
+    fn from(map: SynonymMap) -> Self {
+        map.entries
+    }
+}
+
+impl SynonymMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `aliases` to `canonical`. An alias already listed under another
+    /// canonical term moves to this one; aliases equal to `canonical` but
+    /// for case are skipped.
+    pub fn insert<S: AsRef<str>>(&mut self, canonical: &str, aliases: impl IntoIterator<Item = S>) {
+        let canonical = canonical.trim();
+        if canonical.is_empty() {
+            return;
+        }
+        let key = canonical.to_lowercase();
+        let canonical = match self.canonical.get(&key) {
+            Some(existing) if existing.to_lowercase() == key => existing.clone(),
+            // Listed as an alias so far: it becomes a canonical term
+            Some(_) => {
+                self.remove_alias(&key);
+                canonical.to_string()
+            }
+            None => canonical.to_string(),
+        };
+        self.track(&key, &canonical);
+        self.entries.entry(canonical.clone()).or_default();
+
+        for alias in aliases {
+            let alias = alias.as_ref().trim();
+            let alias_key = alias.to_lowercase();
+            if alias.is_empty() || alias_key == key {
+                continue;
+            }
+            if self.is_canonical(&alias_key) {
+                // A canonical term cannot also be an alias
+                continue;
+            }
+            self.remove_alias(&alias_key);
+            self.track(&alias_key, &canonical);
+            self.entries
+                .get_mut(&canonical)
+                .expect("entry was just added")
+                .push(alias.to_string());
+        }
+    }
+
+    /// Whether the lowercase `key` is a canonical term.
+    fn is_canonical(&self, key: &str) -> bool {
+        self.canonical
+            .get(key)
+            .is_some_and(|c| c.to_lowercase() == key)
+    }
+
+    /// Record that the lowercase `key` names `canonical`.
+    fn track(&mut self, key: &str, canonical: &str) {
+        self.max_words = self.max_words.max(key.split_whitespace().count());
+        self.canonical
+            .insert(key.to_string(), canonical.to_string());
+    }
+
+    /// Take the lowercase `key` out of the aliases of whichever canonical
+    /// term lists it.
+    fn remove_alias(&mut self, key: &str) {
+        if let Some(previous) = self.canonical.get(key) {
+            if let Some(aliases) = self.entries.get_mut(previous) {
+                aliases.retain(|a| a.to_lowercase() != key);
+            }
+        }
+    }
+
+    /// Parse a TSV dictionary: each line a canonical term and its aliases,
+    /// separated by tabs. Blank lines and lines starting with `#` are
+    /// skipped.
+    pub fn from_tsv(text: &str) -> Result<Self, SynonymError> {
+        let mut map = Self::new();
+        for (i, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split('\t').map(str::trim);
+            let canonical = fields.next().unwrap_or_default();
+            let aliases: Vec<&str> = fields.filter(|a| !a.is_empty()).collect();
+            if canonical.is_empty() || aliases.is_empty() {
+                return Err(SynonymError::Parse {
+                    line: i + 1,
+                    message: "expected a canonical term and its aliases, separated by tabs"
+                        .to_string(),
+                });
+            }
+            map.insert(canonical, aliases);
+        }
+        Ok(map)
+    }
+
+    /// Parse a JSON dictionary: an object from canonical terms to lists of
+    /// aliases.
+    pub fn from_json(text: &str) -> Result<Self, SynonymError> {
+        serde_json::from_str(text).map_err(|e| SynonymError::Parse {
+            line: e.line(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Load a dictionary from `path`: JSON if its extension is `json`,
+    /// TSV otherwise.
+    pub fn load(path: &Path) -> Result<Self, SynonymError> {
+        let text = std::fs::read_to_string(path).map_err(SynonymError::Io)?;
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+        {
+            Self::from_json(&text)
+        } else {
+            Self::from_tsv(&text)
+        }
+    }
+
+    /// Number of canonical terms.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of aliases, over all canonical terms.
+    pub fn alias_count(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    /// The canonical term `term` names, ignoring case, if the map lists it
+    /// as a canonical term or an alias.
+    pub fn canonical(&self, term: &str) -> Option<&str> {
+        self.canonical
+            .get(&term.trim().to_lowercase())
+            .map(String::as_str)
+    }
+
+    /// The aliases of canonical term `canonical`, as written.
+    pub fn aliases(&self, canonical: &str) -> &[String] {
+        self.entries.get(canonical).map_or(&[], Vec::as_slice)
+    }
+
+    /// Canonical terms with their aliases, in term order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.entries
+            .iter()
+            .map(|(canonical, aliases)| (canonical.as_str(), aliases.as_slice()))
+    }
+
+    /// The lowercase forms of what `terms` name besides themselves, each
+    /// with `weight`: for every term, and every run of consecutive terms
+    /// forming a multi-word term, its canonical term and aliases. Forms
+    /// among `terms` are left out.
+    pub fn expand(&self, terms: &[String], weight: f64) -> Vec<(String, f64)> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut seen: HashSet<String> = terms.iter().map(|t| t.to_lowercase()).collect();
+        let mut expanded = Vec::new();
+        for words in 1..=self.max_words.min(terms.len()) {
+            for window in terms.windows(words) {
+                let phrase = window.join(" ").to_lowercase();
+                let Some(canonical) = self.canonical(&phrase) else {
+                    continue;
+                };
+                // The typed phrase itself is not an expansion
+                seen.insert(phrase);
+                let forms = std::iter::once(canonical)
+                    .chain(self.aliases(canonical).iter().map(String::as_str));
+                for form in forms {
+                    let form = form.to_lowercase();
+                    if seen.insert(form.clone()) {
+                        expanded.push((form, weight));
+                    }
+                }
+            }
+        }
+        expanded
+    }
+}
+
+/// Weight of synonym expansions when no other is configured.
+pub const DEFAULT_EXPANSION_WEIGHT: f64 = 0.5;
+
+/// Synonyms a colony canonicalizes labels and expands queries with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SynonymConfig {
+    /// Canonical terms and their aliases (default: none).
+    #[serde(default)]
+    pub map: SynonymMap,
+    /// How much a node matched through a synonym counts, relative to one
+    /// matched by the term as typed, in (0, 1] (default: 0.5).
+    #[serde(default = "default_expansion_weight")]
+    pub expansion_weight: f64,
+}
+
+fn default_expansion_weight() -> f64 {
+    DEFAULT_EXPANSION_WEIGHT
+}
+
+impl Default for SynonymConfig {
+    fn default() -> Self {
+        Self {
+            map: SynonymMap::default(),
+            expansion_weight: DEFAULT_EXPANSION_WEIGHT,
+        }
+    }
+}
+
+/// Why a synonym dictionary could not be loaded.
+#[derive(Debug)]
+pub enum SynonymError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The dictionary is malformed at a 1-based line.
+    Parse { line: usize, message: String },
+}
+
+impl std::fmt::Display for SynonymError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SynonymError::Io(e) => write!(f, "could not read synonyms: {}", e),
+            SynonymError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for SynonymError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SynonymError::Io(e) => Some(e),
+            SynonymError::Parse { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cardiology() -> SynonymMap {
+        SynonymMap::from_tsv(
+            "# canonical\taliases\n\
+             myocardial infarction\theart attack\tMI\n\
+             \n\
+             hypertension\thighbloodpressure\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn tsv_and_json_dictionaries_agree() {
+        let tsv = cardiology();
+        let json = SynonymMap::from_json(
+            r#"{"myocardial infarction": ["heart attack", "MI"],
+                "hypertension": ["highbloodpressure"]}"#,
+        )
+        .unwrap();
+        assert_eq!(tsv, json);
+        assert_eq!(tsv.len(), 2);
+        assert_eq!(tsv.alias_count(), 3);
+        assert_eq!(tsv.canonical("Heart Attack"), Some("myocardial infarction"));
+        assert_eq!(tsv.canonical("mi"), Some("myocardial infarction"));
+        assert_eq!(tsv.canonical("hypertension"), Some("hypertension"));
+        assert_eq!(tsv.canonical("stroke"), None);
+
+        let err = SynonymMap::from_tsv("ok\talias\nlonely\n").unwrap_err();
+        assert!(matches!(err, SynonymError::Parse { line: 2, .. }));
+    }
+
+    #[test]
+    fn expansion_adds_the_other_forms_once() {
+        let map = cardiology();
+        let terms: Vec<String> = ["treat", "heart", "attack"].map(String::from).to_vec();
+        let expanded: Vec<String> = map
+            .expand(&terms, 0.5)
+            .into_iter()
+            .map(|(t, _)| t)
+            .collect();
+        assert_eq!(expanded, vec!["myocardial infarction", "mi"]);
+
+        let terms = vec!["hypertension".to_string()];
+        assert_eq!(
+            map.expand(&terms, 0.25),
+            vec![("highbloodpressure".to_string(), 0.25)]
+        );
+    }
+
+    #[test]
+    fn an_alias_moves_to_the_last_canonical_term() {
+        let mut map = cardiology();
+        map.insert("cardiac arrest", ["MI"]);
+        assert_eq!(map.canonical("mi"), Some("cardiac arrest"));
+        assert_eq!(map.aliases("myocardial infarction"), ["heart attack"]);
+    }
+}
@@ -2,23 +2,31 @@
 //!
 //! The knowledge graph is the substrate's structural backbone.
 //! This implementation uses petgraph's `Graph` as the backing store
-//! with HashMap indices for O(1) node/edge lookup by ID.
+//! with HashMap indices for O(1) node/edge lookup by ID and a sorted label
+//! index for exact and prefix label lookup.
 
 use petgraph::graph::{Graph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use phago_core::louvain::{self, LouvainResult};
-use phago_core::topology::TopologyGraph;
+use phago_core::topology::{TopologyError, TopologyGraph};
 use phago_core::types::*;
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Petgraph-backed implementation of the topology graph.
 pub struct PetTopologyGraph {
     graph: Graph<NodeData, EdgeData, petgraph::Undirected>,
     /// Map from our NodeId to petgraph's internal index.
     node_index: HashMap<NodeId, NodeIndex>,
-    /// Index from lowercase label to node IDs for O(1) exact lookup.
-    label_index: HashMap<String, Vec<NodeId>>,
+    /// Sorted index from lowercase label to node IDs, for exact and
+    /// prefix lookup.
+    label_index: BTreeMap<String, Vec<NodeId>>,
+    /// Aliases per node, lowercase, in the order they were added.
+    aliases: HashMap<NodeId, Vec<String>>,
+    /// Index from lowercase alias to node IDs, for exact lookup.
+    alias_index: HashMap<String, Vec<NodeId>>,
+    /// Annotations per node, in the order they were added.
+    annotations: HashMap<NodeId, Vec<Annotation>>,
 }
 
 impl PetTopologyGraph {
@@ -26,11 +34,15 @@ impl PetTopologyGraph {
         Self {
             graph: Graph::new_undirected(),
             node_index: HashMap::new(),
-            label_index: HashMap::new(),
+            label_index: BTreeMap::new(),
+            aliases: HashMap::new(),
+            alias_index: HashMap::new(),
+            annotations: HashMap::new(),
         }
     }
 
-    /// O(1) exact label lookup (case-insensitive).
+    /// O(log n) exact label lookup (case-insensitive). Primary labels
+    /// only; the [`TopologyGraph`] method also matches aliases.
     pub fn find_nodes_by_exact_label(&self, label: &str) -> &[NodeId] {
         self.label_index
             .get(&label.to_lowercase())
@@ -39,6 +51,16 @@ impl PetTopologyGraph {
     }
 }
 
+/// The (label, node) entries of a label or alias index.
+fn index_entries<'a>(
+    index: impl IntoIterator<Item = (&'a String, &'a Vec<NodeId>)>,
+) -> HashSet<(&'a str, NodeId)> {
+    index
+        .into_iter()
+        .flat_map(|(label, ids)| ids.iter().map(move |id| (label.as_str(), *id)))
+        .collect()
+}
+
 impl Default for PetTopologyGraph {
     fn default() -> Self {
         Self::new()
@@ -66,12 +88,12 @@ impl TopologyGraph for PetTopologyGraph {
             .map(|idx| &mut self.graph[idx])
     }
 
-    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData) {
+    fn set_edge(&mut self, from: NodeId, to: NodeId, data: EdgeData) -> Result<(), TopologyError> {
         let Some(&from_idx) = self.node_index.get(&from) else {
-            return;
+            return Err(TopologyError::UnknownNode(from));
         };
         let Some(&to_idx) = self.node_index.get(&to) else {
-            return;
+            return Err(TopologyError::UnknownNode(to));
         };
 
         // Check if edge already exists
@@ -80,6 +102,7 @@ impl TopologyGraph for PetTopologyGraph {
         } else {
             self.graph.add_edge(from_idx, to_idx, data);
         }
+        Ok(())
     }
 
     fn get_edge(&self, from: &NodeId, to: &NodeId) -> Option<&EdgeData> {
@@ -122,6 +145,37 @@ impl TopologyGraph for PetTopologyGraph {
         self.graph.remove_edge(edge_idx)
     }
 
+    fn remove_node(&mut self, id: &NodeId) -> Option<NodeData> {
+        let idx = self.node_index.remove(id)?;
+        let last_idx = NodeIndex::new(self.graph.node_count() - 1);
+        let data = self.graph.remove_node(idx)?;
+
+        // petgraph fills the hole with the last node — re-point its index
+        if idx != last_idx {
+            let moved_id = self.graph[idx].id;
+            self.node_index.insert(moved_id, idx);
+        }
+
+        let label_key = data.label.to_lowercase();
+        if let Some(ids) = self.label_index.get_mut(&label_key) {
+            ids.retain(|nid| nid != id);
+            if ids.is_empty() {
+                self.label_index.remove(&label_key);
+            }
+        }
+        self.annotations.remove(id);
+        for alias in self.aliases.remove(id).unwrap_or_default() {
+            if let Some(ids) = self.alias_index.get_mut(&alias) {
+                ids.retain(|nid| nid != id);
+                if ids.is_empty() {
+                    self.alias_index.remove(&alias);
+                }
+            }
+        }
+
+        Some(data)
+    }
+
     fn all_nodes(&self) -> Vec<NodeId> {
         self.graph
             .node_indices()
@@ -326,12 +380,15 @@ impl TopologyGraph for PetTopologyGraph {
         impl Eq for State {}
         impl PartialOrd for State {
             fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-                other.cost.partial_cmp(&self.cost) // min-heap
+                Some(self.cmp(other))
             }
         }
         impl Ord for State {
             fn cmp(&self, other: &Self) -> Ordering {
-                self.partial_cmp(other).unwrap_or(Ordering::Equal)
+                other
+                    .cost
+                    .partial_cmp(&self.cost)
+                    .unwrap_or(Ordering::Equal) // min-heap
             }
         }
 
@@ -522,11 +579,116 @@ impl TopologyGraph for PetTopologyGraph {
     }
 
     fn find_nodes_by_exact_label(&self, label: &str) -> Vec<NodeId> {
-        // Use O(1) index lookup
+        // Use the label index, then aliases
+        let key = label.to_lowercase();
+        let mut ids = self.label_index.get(&key).cloned().unwrap_or_default();
+        for id in self.alias_index.get(&key).into_iter().flatten() {
+            if !ids.contains(id) {
+                ids.push(*id);
+            }
+        }
+        ids
+    }
+
+    fn add_alias(&mut self, id: &NodeId, alias: &str) -> bool {
+        let Some(node) = self.get_node(id) else {
+            return false;
+        };
+        let alias = alias.to_lowercase();
+        if node.label.to_lowercase() == alias {
+            return true;
+        }
+        let aliases = self.aliases.entry(*id).or_default();
+        if !aliases.contains(&alias) {
+            aliases.push(alias.clone());
+            self.alias_index.entry(alias).or_default().push(*id);
+        }
+        true
+    }
+
+    fn aliases(&self, id: &NodeId) -> Vec<String> {
+        self.aliases.get(id).cloned().unwrap_or_default()
+    }
+
+    fn annotate(&mut self, id: &NodeId, annotation: Annotation) -> bool {
+        if !self.node_index.contains_key(id) {
+            return false;
+        }
+        let annotations = self.annotations.entry(*id).or_default();
+        if annotations.contains(&annotation) {
+            return false;
+        }
+        annotations.push(annotation);
+        true
+    }
+
+    fn annotations(&self, id: &NodeId) -> Vec<Annotation> {
+        self.annotations.get(id).cloned().unwrap_or_default()
+    }
+
+    fn label_index_entries(&self) -> Vec<(String, NodeId)> {
         self.label_index
-            .get(&label.to_lowercase())
-            .map(|v| v.clone())
-            .unwrap_or_default()
+            .iter()
+            .flat_map(|(label, ids)| ids.iter().map(move |id| (label.clone(), *id)))
+            .collect()
+    }
+
+    fn rebuild_indexes(&mut self) -> usize {
+        let mut node_index = HashMap::new();
+        let mut label_index: BTreeMap<String, Vec<NodeId>> = BTreeMap::new();
+        for idx in self.graph.node_indices() {
+            let node = &self.graph[idx];
+            node_index.insert(node.id, idx);
+            label_index
+                .entry(node.label.to_lowercase())
+                .or_default()
+                .push(node.id);
+        }
+        self.aliases.retain(|id, _| node_index.contains_key(id));
+        self.annotations.retain(|id, _| node_index.contains_key(id));
+        let mut alias_index: HashMap<String, Vec<NodeId>> = HashMap::new();
+        for (id, aliases) in &self.aliases {
+            for alias in aliases {
+                alias_index.entry(alias.clone()).or_default().push(*id);
+            }
+        }
+
+        let mut changed = node_index
+            .iter()
+            .filter(|(id, idx)| self.node_index.get(id) != Some(idx))
+            .count()
+            + self
+                .node_index
+                .keys()
+                .filter(|id| !node_index.contains_key(id))
+                .count();
+        changed += index_entries(&self.label_index)
+            .symmetric_difference(&index_entries(&label_index))
+            .count();
+        changed += index_entries(&self.alias_index)
+            .symmetric_difference(&index_entries(&alias_index))
+            .count();
+
+        self.node_index = node_index;
+        self.label_index = label_index;
+        self.alias_index = alias_index;
+        changed
+    }
+
+    fn find_nodes_by_label_prefix(&self, prefix: &str) -> Vec<NodeId> {
+        // Labels sharing a prefix are contiguous in the sorted index
+        let prefix = prefix.to_lowercase();
+        self.label_index
+            .range(prefix.clone()..)
+            .take_while(|(label, _)| label.starts_with(&prefix))
+            .flat_map(|(_, ids)| ids.iter().copied())
+            .collect()
+    }
+
+    fn degree(&self, node: &NodeId) -> usize {
+        self.node_index
+            .get(node)
+            .map_or(0, |idx| self.graph.edges(*idx).count())
     }
 
     fn louvain_communities(&self) -> LouvainResult {
@@ -579,7 +741,8 @@ mod tests {
             position: Position::new(0.0, 0.0),
             access_count: 0,
             created_tick: tick,
-            embedding: None,
+            last_accessed_tick: tick,
+            tags: Default::default(),
         }
     }
 
@@ -612,12 +775,92 @@ mod tests {
         let id2 = n2.id;
         graph.add_node(n1);
         graph.add_node(n2);
-        graph.set_edge(id1, id2, make_edge(0));
+        graph.set_edge(id1, id2, make_edge(0)).unwrap();
 
         assert_eq!(graph.edge_count(), 1);
         assert_eq!(graph.get_edge(&id1, &id2).unwrap().weight, 1.0);
     }
 
+    #[test]
+    fn remove_node_drops_edges_and_keeps_indices_valid() {
+        let mut graph = PetTopologyGraph::new();
+        let n1 = make_node("cell", 0);
+        let n2 = make_node("membrane", 0);
+        let n3 = make_node("protein", 0);
+        let (id1, id2, id3) = (n1.id, n2.id, n3.id);
+        graph.add_node(n1);
+        graph.add_node(n2);
+        graph.add_node(n3);
+        graph.set_edge(id1, id2, make_edge(0)).unwrap();
+        graph.set_edge(id2, id3, make_edge(0)).unwrap();
+
+        // Removing the first node moves the last one into its slot
+        let removed = graph.remove_node(&id1).unwrap();
+        assert_eq!(removed.label, "cell");
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+        assert!(graph.get_node(&id1).is_none());
+        assert_eq!(graph.get_node(&id3).unwrap().label, "protein");
+        assert!(graph.get_edge(&id2, &id3).is_some());
+        assert!(graph.find_nodes_by_exact_label("cell").is_empty());
+        assert!(graph.remove_node(&id1).is_none());
+    }
+
+    #[test]
+    fn prefix_lookup_tracks_added_and_removed_nodes() {
+        let mut graph = PetTopologyGraph::new();
+        let cell = make_node("Cell", 0);
+        let cell_id = cell.id;
+        graph.add_node(cell);
+        graph.add_node(make_node("cellular", 0));
+        graph.add_node(make_node("celsius", 0));
+        graph.add_node(make_node("membrane", 0));
+
+        let labels = |graph: &PetTopologyGraph, prefix: &str| -> Vec<String> {
+            graph
+                .find_nodes_by_label_prefix(prefix)
+                .iter()
+                .map(|id| graph.get_node(id).unwrap().label.clone())
+                .collect()
+        };
+        assert_eq!(labels(&graph, "CEL"), vec!["Cell", "cellular", "celsius"]);
+        assert_eq!(labels(&graph, "cell"), vec!["Cell", "cellular"]);
+        assert!(labels(&graph, "cytoplasm").is_empty());
+
+        graph.remove_node(&cell_id);
+        assert_eq!(labels(&graph, "cell"), vec!["cellular"]);
+    }
+
+    #[test]
+    fn rebuild_indexes_follows_relabelled_nodes() {
+        let mut graph = PetTopologyGraph::new();
+        let cell = make_node("cell", 0);
+        let cell_id = cell.id;
+        graph.add_node(cell);
+        graph.add_node(make_node("membrane", 0));
+        graph.add_alias(&cell_id, "cells");
+        assert_eq!(graph.rebuild_indexes(), 0);
+
+        graph.get_node_mut(&cell_id).unwrap().label = "Nucleus".to_string();
+        assert!(graph
+            .label_index_entries()
+            .contains(&("cell".to_string(), cell_id)));
+        assert!(TopologyGraph::find_nodes_by_exact_label(&graph, "nucleus").is_empty());
+
+        assert_eq!(graph.rebuild_indexes(), 2);
+        assert!(graph
+            .label_index_entries()
+            .contains(&("nucleus".to_string(), cell_id)));
+        assert_eq!(
+            TopologyGraph::find_nodes_by_exact_label(&graph, "nucleus"),
+            vec![cell_id]
+        );
+        assert_eq!(
+            TopologyGraph::find_nodes_by_exact_label(&graph, "cells"),
+            vec![cell_id]
+        );
+    }
+
     #[test]
     fn decay_and_prune_edges() {
         let mut graph = PetTopologyGraph::new();
@@ -632,27 +875,31 @@ mod tests {
         graph.add_node(n3);
 
         // Strong edge
-        graph.set_edge(
-            id1,
-            id2,
-            EdgeData {
-                weight: 1.0,
-                co_activations: 10,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        graph
+            .set_edge(
+                id1,
+                id2,
+                EdgeData {
+                    weight: 1.0,
+                    co_activations: 10,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
         // Weak edge
-        graph.set_edge(
-            id2,
-            id3,
-            EdgeData {
-                weight: 0.1,
-                co_activations: 1,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        graph
+            .set_edge(
+                id2,
+                id3,
+                EdgeData {
+                    weight: 0.1,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
 
         // Decay by 50% — strong edge survives, weak edge gets pruned
         let pruned = graph.decay_edges(0.5, 0.08);
@@ -688,8 +935,8 @@ mod tests {
         graph.add_node(n1);
         graph.add_node(n2);
         graph.add_node(n3);
-        graph.set_edge(id1, id2, make_edge(0));
-        graph.set_edge(id1, id3, make_edge(0));
+        graph.set_edge(id1, id2, make_edge(0)).unwrap();
+        graph.set_edge(id1, id3, make_edge(0)).unwrap();
 
         let neighbors = graph.neighbors(&id1);
         assert_eq!(neighbors.len(), 2);
@@ -709,27 +956,31 @@ mod tests {
         graph.add_node(n3);
 
         // Recently activated edge
-        graph.set_edge(
-            id1,
-            id2,
-            EdgeData {
-                weight: 0.5,
-                co_activations: 5,
-                created_tick: 0,
-                last_activated_tick: 95, // activated 5 ticks ago
-            },
-        );
+        graph
+            .set_edge(
+                id1,
+                id2,
+                EdgeData {
+                    weight: 0.5,
+                    co_activations: 5,
+                    created_tick: 0,
+                    last_activated_tick: 95, // activated 5 ticks ago
+                },
+            )
+            .unwrap();
         // Stale edge (same initial weight, same co_activations but not activated recently)
-        graph.set_edge(
-            id2,
-            id3,
-            EdgeData {
-                weight: 0.5,
-                co_activations: 1,
-                created_tick: 0,
-                last_activated_tick: 10, // activated 90 ticks ago
-            },
-        );
+        graph
+            .set_edge(
+                id2,
+                id3,
+                EdgeData {
+                    weight: 0.5,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 10, // activated 90 ticks ago
+                },
+            )
+            .unwrap();
 
         let current_tick = 100;
         graph.decay_edges_activity(0.005, 0.01, current_tick, 4.0, 30);
@@ -759,16 +1010,18 @@ mod tests {
             let nid = n.id;
             graph.add_node(n);
             // Assign decreasing weights so we know which survive
-            graph.set_edge(
-                hub_id,
-                nid,
-                EdgeData {
-                    weight: 1.0 - (i as f64 * 0.02), // 1.0, 0.98, 0.96, ...
-                    co_activations: 1,
-                    created_tick: 0,
-                    last_activated_tick: 0,
-                },
-            );
+            graph
+                .set_edge(
+                    hub_id,
+                    nid,
+                    EdgeData {
+                        weight: 1.0 - (i as f64 * 0.02), // 1.0, 0.98, 0.96, ...
+                        co_activations: 1,
+                        created_tick: 0,
+                        last_activated_tick: 0,
+                    },
+                )
+                .unwrap();
             neighbor_ids.push(nid);
         }
 
@@ -812,16 +1065,18 @@ mod tests {
         for i in 0..n_nodes {
             for j in (i + 1)..n_nodes {
                 let dist = (j - i) as f64;
-                clique.set_edge(
-                    ids[i],
-                    ids[j],
-                    EdgeData {
-                        weight: 1.0 - dist / n_nodes as f64,
-                        co_activations: 1,
-                        created_tick: 0,
-                        last_activated_tick: 0,
-                    },
-                );
+                clique
+                    .set_edge(
+                        ids[i],
+                        ids[j],
+                        EdgeData {
+                            weight: 1.0 - dist / n_nodes as f64,
+                            co_activations: 1,
+                            created_tick: 0,
+                            last_activated_tick: 0,
+                        },
+                    )
+                    .unwrap();
             }
         }
         let initial_edges = clique.edge_count();
@@ -863,36 +1118,42 @@ mod tests {
         graph.add_node(a);
         graph.add_node(b);
         graph.add_node(c);
-        graph.set_edge(
-            id_a,
-            id_b,
-            EdgeData {
-                weight: 1.0,
-                co_activations: 5,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
-        graph.set_edge(
-            id_b,
-            id_c,
-            EdgeData {
-                weight: 1.0,
-                co_activations: 5,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
-        graph.set_edge(
-            id_a,
-            id_c,
-            EdgeData {
-                weight: 1.0,
-                co_activations: 5,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        graph
+            .set_edge(
+                id_a,
+                id_b,
+                EdgeData {
+                    weight: 1.0,
+                    co_activations: 5,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+        graph
+            .set_edge(
+                id_b,
+                id_c,
+                EdgeData {
+                    weight: 1.0,
+                    co_activations: 5,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+        graph
+            .set_edge(
+                id_a,
+                id_c,
+                EdgeData {
+                    weight: 1.0,
+                    co_activations: 5,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
 
         // Cluster 2: d, e, f (fully connected)
         let d = make_node("d", 0);
@@ -904,48 +1165,56 @@ mod tests {
         graph.add_node(d);
         graph.add_node(e);
         graph.add_node(f);
-        graph.set_edge(
-            id_d,
-            id_e,
-            EdgeData {
-                weight: 1.0,
-                co_activations: 5,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
-        graph.set_edge(
-            id_e,
-            id_f,
-            EdgeData {
-                weight: 1.0,
-                co_activations: 5,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
-        graph.set_edge(
-            id_d,
-            id_f,
-            EdgeData {
-                weight: 1.0,
-                co_activations: 5,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        graph
+            .set_edge(
+                id_d,
+                id_e,
+                EdgeData {
+                    weight: 1.0,
+                    co_activations: 5,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+        graph
+            .set_edge(
+                id_e,
+                id_f,
+                EdgeData {
+                    weight: 1.0,
+                    co_activations: 5,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
+        graph
+            .set_edge(
+                id_d,
+                id_f,
+                EdgeData {
+                    weight: 1.0,
+                    co_activations: 5,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
 
         // Weak inter-cluster connection
-        graph.set_edge(
-            id_c,
-            id_d,
-            EdgeData {
-                weight: 0.1,
-                co_activations: 1,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        graph
+            .set_edge(
+                id_c,
+                id_d,
+                EdgeData {
+                    weight: 0.1,
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
 
         let result = graph.louvain_communities();
 
@@ -984,27 +1253,31 @@ mod tests {
         graph.add_node(n3);
 
         // High co_activation edge, but stale
-        graph.set_edge(
-            id1,
-            id2,
-            EdgeData {
-                weight: 0.3,
-                co_activations: 20, // well-established connection
-                created_tick: 0,
-                last_activated_tick: 10,
-            },
-        );
+        graph
+            .set_edge(
+                id1,
+                id2,
+                EdgeData {
+                    weight: 0.3,
+                    co_activations: 20, // well-established connection
+                    created_tick: 0,
+                    last_activated_tick: 10,
+                },
+            )
+            .unwrap();
         // Low co_activation edge, equally stale
-        graph.set_edge(
-            id2,
-            id3,
-            EdgeData {
-                weight: 0.3,
-                co_activations: 1, // weak connection
-                created_tick: 0,
-                last_activated_tick: 10,
-            },
-        );
+        graph
+            .set_edge(
+                id2,
+                id3,
+                EdgeData {
+                    weight: 0.3,
+                    co_activations: 1, // weak connection
+                    created_tick: 0,
+                    last_activated_tick: 10,
+                },
+            )
+            .unwrap();
 
         // Run many decay rounds
         for tick in 100..120 {
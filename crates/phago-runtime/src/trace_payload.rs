@@ -0,0 +1,120 @@
+//! Encoding of trace payloads.
+//!
+//! Capability deposits carry an agent's whole vocabulary as JSON, and the
+//! substrate keeps every deposit until it decays. With the
+//! `trace-compression` feature, payloads are deflated before they are
+//! stored and tagged with a magic prefix. [`decode`] accepts both tagged
+//! payloads and legacy raw ones, so traces written by older builds (or
+//! with the feature off) still integrate.
+
+use std::borrow::Cow;
+
+/// Prefix marking a deflated payload. The leading NUL can never start a
+/// JSON document, so it cannot collide with a legacy payload.
+const COMPRESSED_MAGIC: &[u8] = b"\0PZ1";
+
+/// Encode a raw payload for storage in a trace.
+///
+/// Compresses when the `trace-compression` feature is enabled and the
+/// result is smaller; otherwise returns the payload unchanged.
+pub fn encode(raw: Vec<u8>) -> Vec<u8> {
+    #[cfg(feature = "trace-compression")]
+    {
+        if let Some(compressed) = deflate(&raw) {
+            if compressed.len() + COMPRESSED_MAGIC.len() < raw.len() {
+                let mut tagged = COMPRESSED_MAGIC.to_vec();
+                tagged.extend_from_slice(&compressed);
+                return tagged;
+            }
+        }
+    }
+    raw
+}
+
+/// Decode a stored payload back to its raw bytes.
+///
+/// Legacy uncompressed payloads are returned as-is. Returns `None` for a
+/// compressed payload that is corrupt, or that this build cannot inflate
+/// because the `trace-compression` feature is off.
+pub fn decode(payload: &[u8]) -> Option<Cow<'_, [u8]>> {
+    match payload.strip_prefix(COMPRESSED_MAGIC) {
+        Some(body) => inflate(body).map(Cow::Owned),
+        None => Some(Cow::Borrowed(payload)),
+    }
+}
+
+/// Whether a stored payload is compressed.
+pub fn is_compressed(payload: &[u8]) -> bool {
+    payload.starts_with(COMPRESSED_MAGIC)
+}
+
+#[cfg(feature = "trace-compression")]
+fn deflate(raw: &[u8]) -> Option<Vec<u8>> {
+    use std::io::Write;
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(raw).ok()?;
+    encoder.finish().ok()
+}
+
+#[cfg(feature = "trace-compression")]
+fn inflate(body: &[u8]) -> Option<Vec<u8>> {
+    use std::io::Read;
+    let mut raw = Vec::new();
+    flate2::read::DeflateDecoder::new(body)
+        .read_to_end(&mut raw)
+        .ok()?;
+    Some(raw)
+}
+
+#[cfg(not(feature = "trace-compression"))]
+fn inflate(_body: &[u8]) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phago_core::types::{AgentId, VocabularyCapability};
+
+    fn vocabulary_json(terms: usize) -> Vec<u8> {
+        let cap = VocabularyCapability::new(
+            (0..terms).map(|i| format!("concept_{}", i % 40)).collect(),
+            AgentId::new(),
+            3,
+        );
+        serde_json::to_vec(&cap).unwrap()
+    }
+
+    #[test]
+    fn legacy_payloads_decode_unchanged() {
+        let raw = vocabulary_json(20);
+        assert!(!is_compressed(&raw));
+        assert_eq!(decode(&raw).unwrap().as_ref(), raw.as_slice());
+    }
+
+    #[test]
+    fn encoded_payloads_roundtrip() {
+        let raw = vocabulary_json(200);
+        let stored = encode(raw.clone());
+        assert_eq!(decode(&stored).unwrap().as_ref(), raw.as_slice());
+        assert!(stored.len() <= raw.len());
+        #[cfg(feature = "trace-compression")]
+        {
+            assert!(is_compressed(&stored));
+            assert!(stored.len() * 3 < raw.len());
+        }
+    }
+
+    #[test]
+    fn tiny_payloads_stay_raw() {
+        let raw = b"{}".to_vec();
+        assert_eq!(encode(raw.clone()), raw);
+    }
+
+    #[test]
+    fn corrupt_compressed_payload_is_rejected() {
+        let mut bad = COMPRESSED_MAGIC.to_vec();
+        bad.extend_from_slice(&[0xff, 0xfe, 0xfd]);
+        assert!(decode(&bad).is_none());
+    }
+}
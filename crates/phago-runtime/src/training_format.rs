@@ -1,11 +1,15 @@
 //! Training data format generators.
 //!
 //! Converts curriculum-ordered triples into JSONL and Alpaca
-//! instruction format for language model fine-tuning.
+//! instruction format for language model fine-tuning, or into
+//! chat-style records (system / user / assistant messages) for
+//! instruction-tuning pipelines.
 
+use crate::colony::Colony;
 use crate::curriculum::Curriculum;
 use crate::export::WeightedTriple;
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// A single training example in JSONL format.
 #[derive(Debug, Clone, Serialize)]
@@ -98,3 +102,524 @@ pub fn section_counts(curriculum: &Curriculum) -> (usize, usize, usize) {
         curriculum.periphery.len(),
     )
 }
+
+/// Controls how triples are rendered as chat records.
+///
+/// Patterns substitute `{subject}`, `{object}`, `{predicate}` (with
+/// underscores replaced by spaces), `{section}` and `{weight}`.
+#[derive(Debug, Clone)]
+pub struct ChatTemplate {
+    pub system_prompt: String,
+    /// User message, e.g. "What is the relationship between {subject} and {object}?".
+    pub user_prompt: String,
+    /// Assistant answer for pairs connected in the graph.
+    pub answer: String,
+    /// Assistant answer for negative pairs.
+    pub negative_answer: String,
+    /// Appended to the user message when source documents are known;
+    /// `{sources}` is replaced by their titles.
+    pub context_pattern: String,
+    /// Source documents per concept, used to add context to the prompt.
+    pub sources: Option<SourceContext>,
+    /// Generate unconnected pairs as negative examples.
+    pub negatives: Option<Negatives>,
+}
+
+impl Default for ChatTemplate {
+    fn default() -> Self {
+        Self {
+            system_prompt: "You are a domain expert. Answer questions about how concepts relate."
+                .to_string(),
+            user_prompt: "What is the relationship between {subject} and {object}?".to_string(),
+            answer: "{subject} is {predicate} {object}.".to_string(),
+            negative_answer: "{subject} and {object} are not directly related.".to_string(),
+            context_pattern: "Source documents: {sources}".to_string(),
+            sources: None,
+            negatives: None,
+        }
+    }
+}
+
+impl ChatTemplate {
+    /// Add source-document context to each prompt.
+    pub fn with_sources(mut self, sources: SourceContext) -> Self {
+        self.sources = Some(sources);
+        self
+    }
+
+    /// Add negative pairs to the output.
+    pub fn with_negatives(mut self, negatives: Negatives) -> Self {
+        self.negatives = Some(negatives);
+        self
+    }
+}
+
+/// Negative pair generation: `ratio` negatives per positive triple, drawn
+/// deterministically from `seed`.
+///
+/// A negative pair joins two concepts from different communities that
+/// share no edge, so the pair is plausible-looking but unsupported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Negatives {
+    pub ratio: f64,
+    pub seed: u64,
+}
+
+/// Train/validation split: `val_ratio` of each community's records go to
+/// validation, chosen deterministically from `seed`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValSplit {
+    pub val_ratio: f64,
+    pub seed: u64,
+}
+
+/// Source documents each concept label was found in.
+#[derive(Debug, Clone, Default)]
+pub struct SourceContext {
+    titles: HashMap<String, Vec<String>>,
+    max_sources: usize,
+}
+
+impl SourceContext {
+    /// Index the colony's documents by the graph labels they contain,
+    /// keeping at most `max_sources` titles per pair.
+    pub fn from_colony(colony: &Colony, max_sources: usize) -> Self {
+        let graph = colony.substrate().graph();
+        let labels: HashSet<String> = graph
+            .all_nodes()
+            .iter()
+            .filter_map(|id| graph.get_node(id))
+            .map(|n| n.label.to_lowercase())
+            .collect();
+
+        let mut documents = colony.substrate().all_documents();
+        documents.sort_by(|a, b| a.title.cmp(&b.title));
+
+        let mut titles: HashMap<String, Vec<String>> = HashMap::new();
+        for doc in documents {
+            let words: HashSet<String> = doc
+                .content
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .map(|w| w.to_string())
+                .collect();
+            for label in &labels {
+                if label.split_whitespace().all(|w| words.contains(w)) {
+                    titles
+                        .entry(label.clone())
+                        .or_default()
+                        .push(doc.title.clone());
+                }
+            }
+        }
+        Self {
+            titles,
+            max_sources,
+        }
+    }
+
+    /// Titles of documents mentioning both concepts.
+    pub fn for_pair(&self, subject: &str, object: &str) -> Vec<String> {
+        let (Some(a), Some(b)) = (
+            self.titles.get(&subject.to_lowercase()),
+            self.titles.get(&object.to_lowercase()),
+        ) else {
+            return Vec::new();
+        };
+        a.iter()
+            .filter(|t| b.contains(t))
+            .take(self.max_sources)
+            .cloned()
+            .collect()
+    }
+}
+
+/// One message of a chat record.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// A chat-format training record.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatExample {
+    pub messages: Vec<ChatMessage>,
+    /// `"positive"` for graph triples, `"negative"` for generated pairs.
+    pub label: String,
+    pub section: String,
+    pub weight: f64,
+    /// Community of the subject, used for stratified splits.
+    pub community: Option<usize>,
+}
+
+/// Chat records split into training and validation JSONL.
+#[derive(Debug, Clone)]
+pub struct ChatSplit {
+    pub train: String,
+    pub val: String,
+}
+
+/// Generate chat-format JSONL from a curriculum, in curriculum order
+/// followed by any negative pairs.
+pub fn to_chat_jsonl(curriculum: &Curriculum, template: ChatTemplate) -> String {
+    join_jsonl(chat_examples(curriculum, &template).iter())
+}
+
+/// Generate chat-format JSONL split into train and validation sets,
+/// stratified by the subject's community so every community keeps the
+/// same validation proportion.
+pub fn to_chat_jsonl_split(
+    curriculum: &Curriculum,
+    template: ChatTemplate,
+    split: ValSplit,
+) -> ChatSplit {
+    let examples = chat_examples(curriculum, &template);
+    let val = stratified_val_indices(&examples, split);
+    ChatSplit {
+        train: join_jsonl(
+            examples
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !val.contains(i))
+                .map(|(_, ex)| ex),
+        ),
+        val: join_jsonl(
+            examples
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| val.contains(i))
+                .map(|(_, ex)| ex),
+        ),
+    }
+}
+
+/// Build positive records for every triple plus any negative pairs.
+pub fn chat_examples(curriculum: &Curriculum, template: &ChatTemplate) -> Vec<ChatExample> {
+    let sections = [
+        (&curriculum.foundation, "foundation"),
+        (&curriculum.bridges, "bridge"),
+        (&curriculum.periphery, "periphery"),
+    ];
+    let mut examples = Vec::new();
+    for (triples, section) in sections {
+        for triple in triples {
+            examples.push(chat_example(
+                curriculum, template, triple, section, "positive",
+            ));
+        }
+    }
+
+    if let Some(negatives) = template.negatives {
+        for triple in negative_pairs(curriculum, negatives) {
+            examples.push(chat_example(
+                curriculum, template, &triple, "negative", "negative",
+            ));
+        }
+    }
+    examples
+}
+
+fn chat_example(
+    curriculum: &Curriculum,
+    template: &ChatTemplate,
+    triple: &WeightedTriple,
+    section: &str,
+    label: &str,
+) -> ChatExample {
+    let fill = |pattern: &str| {
+        pattern
+            .replace("{subject}", &triple.subject)
+            .replace("{object}", &triple.object)
+            .replace("{predicate}", &triple.predicate.replace('_', " "))
+            .replace("{section}", section)
+            .replace("{weight}", &format!("{:.2}", triple.weight))
+    };
+
+    let mut user = fill(&template.user_prompt);
+    if let Some(sources) = &template.sources {
+        let titles = sources.for_pair(&triple.subject, &triple.object);
+        if !titles.is_empty() {
+            user.push_str("\n\n");
+            user.push_str(
+                &template
+                    .context_pattern
+                    .replace("{sources}", &titles.join("; ")),
+            );
+        }
+    }
+    let answer = if label == "negative" {
+        &template.negative_answer
+    } else {
+        &template.answer
+    };
+
+    ChatExample {
+        messages: vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: template.system_prompt.clone(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: user,
+            },
+            ChatMessage {
+                role: "assistant".to_string(),
+                content: fill(answer),
+            },
+        ],
+        label: label.to_string(),
+        section: section.to_string(),
+        weight: triple.weight,
+        community: curriculum.communities.get(&triple.subject).copied(),
+    }
+}
+
+/// Sample up to `ratio × positives` unconnected cross-community pairs.
+fn negative_pairs(curriculum: &Curriculum, negatives: Negatives) -> Vec<WeightedTriple> {
+    let triples = curriculum.ordered();
+    let connected: HashSet<(&str, &str)> = triples
+        .iter()
+        .flat_map(|t| {
+            [
+                (t.subject.as_str(), t.object.as_str()),
+                (t.object.as_str(), t.subject.as_str()),
+            ]
+        })
+        .collect();
+
+    // Sorted so sampling depends only on the seed
+    let mut labels: Vec<(&str, usize)> = triples
+        .iter()
+        .flat_map(|t| [t.subject.as_str(), t.object.as_str()])
+        .filter_map(|l| curriculum.communities.get(l).map(|&c| (l, c)))
+        .collect();
+    labels.sort();
+    labels.dedup();
+
+    let target = (triples.len() as f64 * negatives.ratio.max(0.0)).round() as usize;
+    let mut chosen: HashSet<(&str, &str)> = HashSet::new();
+    let mut pairs = Vec::new();
+    if labels.len() < 2 {
+        return pairs;
+    }
+
+    let mut rng = negatives.seed;
+    let mut next = |n: usize| {
+        rng = rng
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (rng >> 33) as usize % n
+    };
+    let max_attempts = target.saturating_mul(50);
+    for _ in 0..max_attempts {
+        if pairs.len() >= target {
+            break;
+        }
+        let (a, ca) = labels[next(labels.len())];
+        let (b, cb) = labels[next(labels.len())];
+        if ca == cb || connected.contains(&(a, b)) || chosen.contains(&(a, b)) {
+            continue;
+        }
+        chosen.insert((a, b));
+        chosen.insert((b, a));
+        pairs.push(WeightedTriple {
+            subject: a.to_string(),
+            predicate: "unrelated_to".to_string(),
+            object: b.to_string(),
+            weight: 0.0,
+            co_activations: 0,
+            tags: Vec::new(),
+            sources: Vec::new(),
+        });
+    }
+    pairs
+}
+
+/// Pick `val_ratio` of each community's records (rounded) for validation.
+fn stratified_val_indices(examples: &[ChatExample], split: ValSplit) -> HashSet<usize> {
+    let mut strata: BTreeMap<Option<usize>, Vec<usize>> = BTreeMap::new();
+    for (i, ex) in examples.iter().enumerate() {
+        strata.entry(ex.community).or_default().push(i);
+    }
+
+    let ratio = split.val_ratio.clamp(0.0, 1.0);
+    let mut rng = split.seed;
+    let mut val = HashSet::new();
+    for mut members in strata.into_values() {
+        for i in (1..members.len()).rev() {
+            rng = rng
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let j = (rng >> 33) as usize % (i + 1);
+            members.swap(i, j);
+        }
+        let take = (members.len() as f64 * ratio).round() as usize;
+        val.extend(members.into_iter().take(take));
+    }
+    val
+}
+
+fn join_jsonl<'a>(examples: impl Iterator<Item = &'a ChatExample>) -> String {
+    examples
+        .filter_map(|ex| serde_json::to_string(ex).ok())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triple(subject: &str, object: &str, weight: f64) -> WeightedTriple {
+        WeightedTriple {
+            subject: subject.to_string(),
+            predicate: "related_to".to_string(),
+            object: object.to_string(),
+            weight,
+            co_activations: 1,
+            tags: Vec::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    /// Two communities of five concepts, fully connected internally, with
+    /// a single bridge between them.
+    fn two_community_curriculum() -> Curriculum {
+        let groups = [
+            ["cell", "membrane", "protein", "lipid", "nucleus"],
+            ["market", "price", "trade", "demand", "supply"],
+        ];
+        let mut communities = HashMap::new();
+        let mut foundation = Vec::new();
+        for (c, group) in groups.iter().enumerate() {
+            for (i, a) in group.iter().enumerate() {
+                communities.insert(a.to_string(), c);
+                for b in &group[i + 1..] {
+                    foundation.push(triple(a, b, 0.8));
+                }
+            }
+        }
+        Curriculum {
+            foundation,
+            bridges: vec![triple("protein", "trade", 0.2)],
+            periphery: Vec::new(),
+            communities,
+        }
+    }
+
+    fn parse(jsonl: &str) -> Vec<serde_json::Value> {
+        jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is valid JSON"))
+            .collect()
+    }
+
+    #[test]
+    fn chat_jsonl_parses_with_templated_messages() {
+        let cur = two_community_curriculum();
+        let records = parse(&to_chat_jsonl(&cur, ChatTemplate::default()));
+        assert_eq!(records.len(), cur.total());
+
+        let first = &records[0];
+        let messages = first["messages"].as_array().unwrap();
+        let roles: Vec<&str> = messages
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, ["system", "user", "assistant"]);
+        assert_eq!(
+            messages[1]["content"],
+            "What is the relationship between cell and membrane?"
+        );
+        assert_eq!(messages[2]["content"], "cell is related to membrane.");
+        assert_eq!(first["label"], "positive");
+        assert_eq!(first["section"], "foundation");
+        assert_eq!(first["community"], 0);
+    }
+
+    #[test]
+    fn source_context_is_added_to_prompt() {
+        let mut titles = HashMap::new();
+        titles.insert("cell".to_string(), vec!["Biology 101".to_string()]);
+        titles.insert(
+            "membrane".to_string(),
+            vec!["Biology 101".to_string(), "Lipids".to_string()],
+        );
+        let sources = SourceContext {
+            titles,
+            max_sources: 3,
+        };
+        let cur = two_community_curriculum();
+        let examples = chat_examples(&cur, &ChatTemplate::default().with_sources(sources));
+        assert!(examples[0].messages[1]
+            .content
+            .ends_with("\n\nSource documents: Biology 101"));
+    }
+
+    #[test]
+    fn negative_pairs_lack_edges_and_cross_communities() {
+        let cur = two_community_curriculum();
+        let template = ChatTemplate::default().with_negatives(Negatives {
+            ratio: 0.5,
+            seed: 7,
+        });
+        let examples = chat_examples(&cur, &template);
+        let negatives: Vec<&ChatExample> =
+            examples.iter().filter(|e| e.label == "negative").collect();
+        assert_eq!(negatives.len(), (cur.total() as f64 * 0.5).round() as usize);
+
+        let edges: HashSet<(String, String)> = cur
+            .ordered()
+            .iter()
+            .flat_map(|t| {
+                [
+                    (t.subject.clone(), t.object.clone()),
+                    (t.object.clone(), t.subject.clone()),
+                ]
+            })
+            .collect();
+        let pairs = negative_pairs(&cur, template.negatives.unwrap());
+        assert_eq!(pairs.len(), negatives.len());
+        for pair in &pairs {
+            assert!(!edges.contains(&(pair.subject.clone(), pair.object.clone())));
+            assert_ne!(
+                cur.communities[&pair.subject],
+                cur.communities[&pair.object]
+            );
+        }
+        assert!(negatives[0].messages[2]
+            .content
+            .ends_with("are not directly related."));
+    }
+
+    #[test]
+    fn split_is_stratified_by_community() {
+        let cur = two_community_curriculum();
+        let split = to_chat_jsonl_split(
+            &cur,
+            ChatTemplate::default(),
+            ValSplit {
+                val_ratio: 0.2,
+                seed: 3,
+            },
+        );
+        let train = parse(&split.train);
+        let val = parse(&split.val);
+        assert_eq!(train.len() + val.len(), cur.total());
+
+        let per_community = |records: &[serde_json::Value], c: u64| {
+            records
+                .iter()
+                .filter(|r| r["community"].as_u64() == Some(c))
+                .count()
+        };
+        for c in 0..2 {
+            let total = per_community(&train, c) + per_community(&val, c);
+            let expected = (total as f64 * 0.2).round() as usize;
+            assert_eq!(per_community(&val, c), expected);
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Provenance-weighted vocabulary transfer.
+//!
+//! An agent integrating a capability deposit used to take every term in
+//! it, so a digester that ate a garbage document spread its terms to every
+//! agent within trace radius, and dissolution then pushed them back into
+//! the graph. The colony now stamps each deposit with the exporter's
+//! fitness and how often the graph had reinforced each term, and an
+//! integrating agent takes only the terms that provenance vouches for:
+//!
+//! ```text
+//! score = 0.4 * min(fitness, 1) + 0.4 * min(reinforcement / 10, 1) + 0.2 * trust
+//! ```
+//!
+//! where `trust` is the integrating agent's own trust from its last
+//! [`BoundaryContext`](phago_core::types::BoundaryContext). The
+//! [`AcceptanceCurve`] turns the score into the probability a term is
+//! taken. Draws are seeded by the exporter, the integrating agent and the
+//! term, so a run is reproducible and agents differ in what they take.
+//! Deposits from exporters below [`TransferConfig::min_source_fitness`]
+//! are refused outright.
+
+use phago_core::types::{AgentId, VocabularyCapability};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Reinforcement at which a term's reinforcement factor saturates.
+const FULL_REINFORCEMENT: f64 = 10.0;
+
+/// How a term's provenance score becomes the chance it is integrated.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AcceptanceCurve {
+    /// Every term is integrated, whatever its score.
+    #[default]
+    All,
+    /// Terms scoring at least `min_score` are integrated, the rest are not.
+    Threshold { min_score: f64 },
+    /// A term is integrated with probability equal to its score.
+    Linear,
+    /// A term is integrated with probability
+    /// `1 / (1 + exp(-steepness * (score - midpoint)))`.
+    Sigmoid { midpoint: f64, steepness: f64 },
+}
+
+impl AcceptanceCurve {
+    /// Probability, in `[0, 1]`, that a term scoring `score` is integrated.
+    pub fn probability(&self, score: f64) -> f64 {
+        match *self {
+            AcceptanceCurve::All => 1.0,
+            AcceptanceCurve::Threshold { min_score } => {
+                if score >= min_score {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            AcceptanceCurve::Linear => score.clamp(0.0, 1.0),
+            AcceptanceCurve::Sigmoid {
+                midpoint,
+                steepness,
+            } => 1.0 / (1.0 + (-steepness * (score - midpoint)).exp()),
+        }
+    }
+}
+
+/// Which offered terms an agent integrates from a capability deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct TransferConfig {
+    /// Deposits from exporters less fit than this are refused; deposits
+    /// without provenance count as fitness 0 (default: 0.0).
+    #[serde(default)]
+    pub min_source_fitness: f64,
+    /// How a term's provenance score becomes the chance it is integrated
+    /// (default: every term).
+    #[serde(default)]
+    pub term_acceptance_curve: AcceptanceCurve,
+}
+
+impl TransferConfig {
+    /// Whether every offered term is integrated, as before provenance.
+    pub fn accepts_all(&self) -> bool {
+        self.min_source_fitness <= 0.0 && self.term_acceptance_curve == AcceptanceCurve::All
+    }
+
+    /// Provenance score, in `[0, 1]`, of `capability.terms[index]` offered
+    /// to an agent with `trust`.
+    pub fn score(&self, capability: &VocabularyCapability, index: usize, trust: f64) -> f64 {
+        let fitness = capability.source_fitness.unwrap_or(0.0).clamp(0.0, 1.0);
+        let reinforcement =
+            (capability.reinforcement_of(index) as f64 / FULL_REINFORCEMENT).min(1.0);
+        0.4 * fitness + 0.4 * reinforcement + 0.2 * trust.clamp(0.0, 1.0)
+    }
+
+    /// The terms of `capability` that `integrator`, with `trust`, takes.
+    pub fn accepted_terms(
+        &self,
+        capability: &VocabularyCapability,
+        integrator: AgentId,
+        trust: f64,
+    ) -> Vec<String> {
+        if self.accepts_all() {
+            return capability.terms.clone();
+        }
+        if capability.source_fitness.unwrap_or(0.0) < self.min_source_fitness {
+            return Vec::new();
+        }
+        capability
+            .terms
+            .iter()
+            .enumerate()
+            .filter(|(index, term)| {
+                let p = self
+                    .term_acceptance_curve
+                    .probability(self.score(capability, *index, trust));
+                p >= 1.0 || draw(capability.origin, integrator, term) < p
+            })
+            .map(|(_, term)| term.clone())
+            .collect()
+    }
+}
+
+/// A uniform draw in `[0, 1)` fixed by exporter, integrator and term.
+fn draw(origin: AgentId, integrator: AgentId, term: &str) -> f64 {
+    let mut hasher = DefaultHasher::new();
+    origin.hash(&mut hasher);
+    integrator.hash(&mut hasher);
+    term.hash(&mut hasher);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capability(fitness: Option<f64>, reinforcement: &[u64]) -> VocabularyCapability {
+        let terms = (0..reinforcement.len())
+            .map(|i| format!("term_{i}"))
+            .collect();
+        let mut capability = VocabularyCapability::new(terms, AgentId::from_seed(1), 2);
+        capability.source_fitness = fitness;
+        capability.term_reinforcement = reinforcement.to_vec();
+        capability
+    }
+
+    #[test]
+    fn default_config_accepts_everything() {
+        let config = TransferConfig::default();
+        let offered = capability(None, &[0, 0, 0]);
+        assert!(config.accepts_all());
+        assert_eq!(
+            config.accepted_terms(&offered, AgentId::from_seed(2), 0.0),
+            offered.terms
+        );
+    }
+
+    #[test]
+    fn unfit_sources_are_refused() {
+        let config = TransferConfig {
+            min_source_fitness: 0.3,
+            ..TransferConfig::default()
+        };
+        let integrator = AgentId::from_seed(2);
+        assert!(config
+            .accepted_terms(&capability(Some(0.1), &[50, 50]), integrator, 1.0)
+            .is_empty());
+        assert!(config
+            .accepted_terms(&capability(None, &[50, 50]), integrator, 1.0)
+            .is_empty());
+        assert_eq!(
+            config
+                .accepted_terms(&capability(Some(0.5), &[0, 0]), integrator, 0.0)
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn threshold_keeps_reinforced_terms() {
+        let config = TransferConfig {
+            min_source_fitness: 0.0,
+            term_acceptance_curve: AcceptanceCurve::Threshold { min_score: 0.6 },
+        };
+        let offered = capability(Some(0.8), &[0, 10, 3, 20]);
+        let accepted = config.accepted_terms(&offered, AgentId::from_seed(2), 0.0);
+        assert_eq!(accepted, vec!["term_1", "term_3"]);
+        // A trusted integrator is less strict
+        let accepted = config.accepted_terms(&offered, AgentId::from_seed(2), 1.0);
+        assert_eq!(accepted, vec!["term_1", "term_2", "term_3"]);
+    }
+
+    #[test]
+    fn probabilistic_acceptance_follows_the_score() {
+        let config = TransferConfig {
+            min_source_fitness: 0.0,
+            term_acceptance_curve: AcceptanceCurve::Linear,
+        };
+        let integrator = AgentId::from_seed(2);
+        let weak = capability(Some(0.1), &[0; 200]);
+        let strong = capability(Some(1.0), &[10; 200]);
+        let weak_taken = config.accepted_terms(&weak, integrator, 0.0).len();
+        let strong_taken = config.accepted_terms(&strong, integrator, 0.0).len();
+        assert!(weak_taken < 30, "weak: {weak_taken}");
+        assert!(strong_taken > 140, "strong: {strong_taken}");
+        // Reproducible for the same agents
+        assert_eq!(
+            config.accepted_terms(&weak, integrator, 0.0),
+            config.accepted_terms(&weak, integrator, 0.0)
+        );
+    }
+
+    #[test]
+    fn sigmoid_is_centred_on_its_midpoint() {
+        let curve = AcceptanceCurve::Sigmoid {
+            midpoint: 0.5,
+            steepness: 12.0,
+        };
+        assert!((curve.probability(0.5) - 0.5).abs() < 1e-9);
+        assert!(curve.probability(0.9) > 0.95);
+        assert!(curve.probability(0.1) < 0.05);
+    }
+}
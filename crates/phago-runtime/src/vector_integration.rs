@@ -9,11 +9,52 @@
 //! mapping of node labels to their embeddings. On query, it computes
 //! cosine similarity against all indexed embeddings.
 //!
+//! The index can also be pushed to an external `phago_vectors::VectorStore`
+//! and searched there. Backends that support hybrid search are queried with
+//! the query text as well as its embedding.
+//!
 //! Feature-gated behind `vectors` in phago-runtime's Cargo.toml.
 
 use phago_core::types::NodeId;
-use phago_embeddings::{Embedder, EmbeddingResult};
+use phago_embeddings::{Embedder, EmbeddingError, EmbeddingResult};
+use phago_vectors::{VectorError, VectorRecord, VectorResult, VectorStore};
 use std::collections::HashMap;
+use std::fmt;
+
+/// Metadata key holding a node's label in an external vector store.
+pub const LABEL_KEY: &str = "label";
+
+/// Why a search of an external vector store failed.
+#[derive(Debug)]
+pub enum StoreSearchError {
+    /// The query could not be embedded.
+    Embedding(EmbeddingError),
+    /// The store rejected the search.
+    Store(VectorError),
+}
+
+impl fmt::Display for StoreSearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreSearchError::Embedding(e) => write!(f, "embedding failed: {}", e),
+            StoreSearchError::Store(e) => write!(f, "vector store search failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StoreSearchError {}
+
+impl From<EmbeddingError> for StoreSearchError {
+    fn from(e: EmbeddingError) -> Self {
+        StoreSearchError::Embedding(e)
+    }
+}
+
+impl From<VectorError> for StoreSearchError {
+    fn from(e: VectorError) -> Self {
+        StoreSearchError::Store(e)
+    }
+}
 
 /// A node entry in the vector index.
 struct VectorEntry {
@@ -41,7 +82,8 @@ impl VectorSubstrate {
 
     /// Index a node's label for vector similarity search.
     ///
-    /// If `existing_embedding` is provided (e.g., from `NodeData.embedding`),
+    /// If `existing_embedding` is provided (e.g., from
+    /// [`SubstrateImpl::embedding`](crate::substrate_impl::SubstrateImpl::embedding)),
     /// it is used directly. Otherwise the embedder generates one.
     pub fn index_node(
         &mut self,
@@ -91,6 +133,55 @@ impl VectorSubstrate {
         Ok(scores)
     }
 
+    /// Upsert every indexed node into `store`, keyed by node ID, with its
+    /// label under [`LABEL_KEY`].
+    pub async fn sync_to_store(&self, store: &dyn VectorStore) -> VectorResult<()> {
+        let records = self
+            .entries
+            .iter()
+            .map(|(id, entry)| {
+                VectorRecord::new(id.0.to_string(), entry.embedding.clone())
+                    .with_metadata(LABEL_KEY, entry.label.clone())
+            })
+            .collect();
+        store.upsert(records).await
+    }
+
+    /// Search nodes synced to `store` (see [`Self::sync_to_store`]) for a
+    /// query text.
+    ///
+    /// Backends with hybrid search blend keyword matches on the text with
+    /// similarity to its embedding, weighted by `alpha` (0.0 keywords only,
+    /// 1.0 embedding only); others get a plain vector search. Returns
+    /// `(label, score)` pairs in the store's ranking.
+    pub async fn search_store(
+        &self,
+        store: &dyn VectorStore,
+        query: &str,
+        k: usize,
+        alpha: f32,
+    ) -> Result<Vec<(String, f32)>, StoreSearchError> {
+        let query_vec = self.embedder.embed(query)?;
+        let results = match store.as_hybrid() {
+            Some(hybrid) => {
+                hybrid
+                    .hybrid_search(query, Some(&query_vec), alpha, k, None)
+                    .await?
+            }
+            None => store.search(&query_vec, k).await?,
+        };
+        Ok(results
+            .into_iter()
+            .map(|r| {
+                let label = match r.metadata.get(LABEL_KEY) {
+                    Some(serde_json::Value::String(label)) => label.clone(),
+                    _ => r.id,
+                };
+                (label, r.score)
+            })
+            .collect())
+    }
+
     /// Get the embedder's dimension.
     pub fn dimension(&self) -> usize {
         self.embedder.dimension()
@@ -158,4 +249,35 @@ mod tests {
         vs.index_node(id, "custom", Some(&emb)).unwrap();
         assert_eq!(vs.indexed_count(), 1);
     }
+
+    #[tokio::test]
+    async fn store_search_prefers_hybrid() {
+        use phago_vectors::InMemoryStore;
+
+        let mut vs = VectorSubstrate::new(Box::new(SimpleEmbedder::new(64)));
+        vs.index_node(NodeId::from_seed(1), "cell membrane", None)
+            .unwrap();
+        vs.index_node(NodeId::from_seed(2), "protein transport", None)
+            .unwrap();
+        vs.index_node(NodeId::from_seed(3), "quantum physics", None)
+            .unwrap();
+
+        let store = InMemoryStore::new(64);
+        vs.sync_to_store(&store).await.unwrap();
+        assert_eq!(store.count().await.unwrap(), 3);
+
+        // Keywords only: an exact match on the label text
+        let results = vs.search_store(&store, "membrane", 3, 0.0).await.unwrap();
+        assert_eq!(results[0], ("cell membrane".to_string(), 1.0));
+        assert!(results[1..].iter().all(|(_, score)| *score == 0.0));
+
+        // Embedding only: the same ranking as the in-process index
+        let results = vs
+            .search_store(&store, "cell biology", 3, 1.0)
+            .await
+            .unwrap();
+        let local = vs.search("cell biology", 3).unwrap();
+        assert_eq!(results[0].0, local[0].0);
+        assert!((results[0].1 - local[0].1).abs() < 1e-5);
+    }
 }
@@ -0,0 +1,264 @@
+//! What the agents know.
+//!
+//! [`Colony::vocabulary_report`](crate::colony::Colony::vocabulary_report)
+//! lists the heaviest terms of every living agent's vocabulary (see
+//! [`Agent::vocabulary_snapshot`]), how much each pair of agents' terms
+//! overlap, and which terms the knowledge graph has a node for. It shows
+//! whether TRANSFER, SYMBIOSE and DISSOLVE spread useful terms or junk.
+//!
+//! Only each agent's `top_n` terms are taken, so the report stays small
+//! however large the vocabularies grow; the overlap is between those.
+
+use phago_core::agent::Agent;
+use phago_core::types::{AgentId, NodeId, Tick};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// An agent's heaviest vocabulary terms.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentVocabulary {
+    pub id: AgentId,
+    pub agent_type: String,
+    /// The whole vocabulary's size, of which `top_terms` are the heaviest.
+    pub vocabulary_size: usize,
+    /// Terms with their weight, heaviest first.
+    pub top_terms: Vec<(String, f64)>,
+}
+
+/// How much two agents' top terms overlap.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VocabularyOverlap {
+    pub a: AgentId,
+    pub b: AgentId,
+    /// Terms both have.
+    pub shared: usize,
+    /// Shared terms over the terms either has (0 when both have none).
+    pub jaccard: f64,
+}
+
+/// Whether the graph has a node for a term.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TermCoverage {
+    pub term: String,
+    /// The node the term is labelled with, a concept if there is one.
+    pub node: Option<NodeId>,
+    /// How often that node was accessed (0 without a node).
+    pub access_count: u64,
+}
+
+/// Per-agent top terms, their pairwise overlap and their graph coverage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VocabularyReport {
+    pub tick: Tick,
+    /// Terms taken per agent.
+    pub top_n: usize,
+    /// Living agents, in spawn order.
+    pub agents: Vec<AgentVocabulary>,
+    /// Every pair of agents, in the order of `agents`.
+    pub overlaps: Vec<VocabularyOverlap>,
+    /// Every term of `agents`, in term order.
+    pub coverage: Vec<TermCoverage>,
+}
+
+/// How far the agents' vocabularies have converged.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OverlapSummary {
+    pub tick: Tick,
+    /// Agents with at least one term.
+    pub agents: usize,
+    /// Pairs of those agents.
+    pub pairs: usize,
+    pub mean_jaccard: f64,
+    pub max_jaccard: f64,
+    /// Fraction of the reported terms the graph has a node for.
+    pub graph_coverage: f64,
+}
+
+impl VocabularyReport {
+    /// Build the report of `agents`, looking up each term's node and its
+    /// access count with `coverage`.
+    pub(crate) fn build<'a, A>(
+        agents: impl IntoIterator<Item = &'a A>,
+        tick: Tick,
+        top_n: usize,
+        coverage: impl Fn(&str) -> Option<(NodeId, u64)>,
+    ) -> Self
+    where
+        A: Agent + ?Sized + 'a,
+    {
+        let agents: Vec<AgentVocabulary> = agents
+            .into_iter()
+            .map(|agent| AgentVocabulary {
+                id: agent.id(),
+                agent_type: agent.agent_type().to_string(),
+                vocabulary_size: agent.vocabulary_size(),
+                top_terms: agent.vocabulary_snapshot(top_n),
+            })
+            .collect();
+
+        let term_sets: Vec<HashSet<&str>> = agents
+            .iter()
+            .map(|a| a.top_terms.iter().map(|(t, _)| t.as_str()).collect())
+            .collect();
+        let mut overlaps = Vec::new();
+        for i in 0..agents.len() {
+            for j in i + 1..agents.len() {
+                let shared = term_sets[i].intersection(&term_sets[j]).count();
+                let union = term_sets[i].len() + term_sets[j].len() - shared;
+                overlaps.push(VocabularyOverlap {
+                    a: agents[i].id,
+                    b: agents[j].id,
+                    shared,
+                    jaccard: if union == 0 {
+                        0.0
+                    } else {
+                        shared as f64 / union as f64
+                    },
+                });
+            }
+        }
+
+        let terms: BTreeMap<&str, Option<(NodeId, u64)>> = term_sets
+            .iter()
+            .flatten()
+            .map(|term| (*term, coverage(term)))
+            .collect();
+        let coverage = terms
+            .into_iter()
+            .map(|(term, node)| TermCoverage {
+                term: term.to_string(),
+                node: node.map(|(id, _)| id),
+                access_count: node.map_or(0, |(_, count)| count),
+            })
+            .collect();
+
+        Self {
+            tick,
+            top_n,
+            agents,
+            overlaps,
+            coverage,
+        }
+    }
+
+    /// The top terms of agent `id`, if it was alive.
+    pub fn agent(&self, id: &AgentId) -> Option<&AgentVocabulary> {
+        self.agents.iter().find(|a| a.id == *id)
+    }
+
+    /// The overlap of agents `a` and `b`, in either order.
+    pub fn overlap(&self, a: &AgentId, b: &AgentId) -> Option<&VocabularyOverlap> {
+        self.overlaps
+            .iter()
+            .find(|o| (o.a == *a && o.b == *b) || (o.a == *b && o.b == *a))
+    }
+
+    /// The overlaps of agent `id` with every other agent.
+    pub fn overlaps_of<'a>(
+        &'a self,
+        id: &'a AgentId,
+    ) -> impl Iterator<Item = &'a VocabularyOverlap> + 'a {
+        self.overlaps
+            .iter()
+            .filter(move |o| o.a == *id || o.b == *id)
+    }
+
+    /// The graph coverage of `term`, if an agent reported it.
+    pub fn coverage_of(&self, term: &str) -> Option<&TermCoverage> {
+        self.coverage
+            .binary_search_by(|c| c.term.as_str().cmp(term))
+            .ok()
+            .map(|i| &self.coverage[i])
+    }
+
+    /// Mean and maximum overlap between the agents that know any terms,
+    /// and how many of the terms are in the graph.
+    pub fn summary(&self) -> OverlapSummary {
+        let known: HashSet<AgentId> = self
+            .agents
+            .iter()
+            .filter(|a| !a.top_terms.is_empty())
+            .map(|a| a.id)
+            .collect();
+        let jaccards: Vec<f64> = self
+            .overlaps
+            .iter()
+            .filter(|o| known.contains(&o.a) && known.contains(&o.b))
+            .map(|o| o.jaccard)
+            .collect();
+        let covered = self.coverage.iter().filter(|c| c.node.is_some()).count();
+        OverlapSummary {
+            tick: self.tick,
+            agents: known.len(),
+            pairs: jaccards.len(),
+            mean_jaccard: if jaccards.is_empty() {
+                0.0
+            } else {
+                jaccards.iter().sum::<f64>() / jaccards.len() as f64
+            },
+            max_jaccard: jaccards.iter().copied().fold(0.0, f64::max),
+            graph_coverage: if self.coverage.is_empty() {
+                0.0
+            } else {
+                covered as f64 / self.coverage.len() as f64
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::colony::Colony;
+    use phago_agents::digester::Digester;
+    use phago_core::types::Position;
+
+    fn digester(seed: u64, text: &str) -> Digester {
+        let mut digester = Digester::with_seed(Position::new(seed as f64, 0.0), seed);
+        digester.digest_text(text.to_string());
+        digester
+    }
+
+    #[test]
+    fn transferred_vocabulary_raises_the_overlap() {
+        let mut colony = Colony::new();
+        let a = colony.spawn(Box::new(digester(
+            1,
+            "The cell membrane controls transport of proteins into the cell.",
+        )));
+        let b = colony.spawn(Box::new(digester(
+            2,
+            "Quantum circuits need error correction and topological qubits.",
+        )));
+
+        let before = colony
+            .vocabulary_report(50)
+            .overlap(&a, &b)
+            .unwrap()
+            .jaccard;
+        assert!(colony.transfer_vocabulary(&a, &b));
+        // Once per source
+        assert!(!colony.transfer_vocabulary(&a, &b));
+        let after = colony
+            .vocabulary_report(50)
+            .overlap(&a, &b)
+            .unwrap()
+            .jaccard;
+
+        assert!(after > before, "{before} -> {after}");
+        assert!(colony.vocabulary_report(50).summary().mean_jaccard > 0.0);
+    }
+
+    #[test]
+    fn reports_keep_only_the_top_terms() {
+        let mut colony = Colony::new();
+        let words: Vec<String> = (0..200).map(|i| format!("term{:03}", i)).collect();
+        let id = colony.spawn(Box::new(digester(1, &words.join(" "))));
+
+        let report = colony.vocabulary_report(3);
+        let agent = report.agent(&id).unwrap();
+        assert_eq!(agent.top_terms.len(), 3);
+        assert!(agent.vocabulary_size > 3);
+        assert!(report.coverage.len() <= 3);
+        assert!(colony.vocabulary_report(0).agents[0].top_terms.is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+//! Deferred wiring for bulk ingestion.
+//!
+//! Wired incrementally, every presentation looks its labels up in the graph
+//! (an exact lookup, then a substring scan of every label) and writes each
+//! co-occurrence edge as soon as it is found. With
+//! [`ColonyConfig::deferred_wiring`](crate::colony::ColonyConfig::deferred_wiring)
+//! the colony instead resolves labels through a [`LabelResolver`] and
+//! collects the tick's co-occurrences in [`PendingWiring`], which folds all
+//! wiring of one pair in memory and writes the edge once.
+//!
+//! Both produce the same graph: labels resolve to the nodes the backend
+//! lookups would return, and a pair's wiring is folded in the order it was
+//! presented, so weights, co-activation counts and document contributions
+//! match. Nothing reads the edges between presentations, so the colony
+//! flushes before any other action runs and at the end of the action phase.
+
+use crate::colony::DocumentContribution;
+use crate::reinforcement::{reinforce_edge, WeightUpdate};
+use phago_core::topology::{TopologyError, TopologyGraph};
+use phago_core::types::*;
+use std::collections::HashMap;
+
+/// Resolves fragment labels to existing nodes, remembering the answers.
+///
+/// Resolution follows the colony's lookup: an exact label (or alias) match,
+/// else the first node, in the backend's order, whose label contains the
+/// fragment's. Answers stay valid while nodes are only added, so the colony
+/// drops the resolver whenever it flushes.
+#[derive(Default)]
+pub(crate) struct LabelResolver {
+    /// Lowercase label → the node it resolved to.
+    resolved: HashMap<String, NodeId>,
+    /// Lowercase labels of all nodes in lookup order, loaded on the first
+    /// substring match.
+    labels: Option<Vec<(String, NodeId)>>,
+}
+
+impl LabelResolver {
+    /// The existing node a fragment labelled `label` belongs to, if any.
+    pub(crate) fn resolve(&mut self, graph: &dyn TopologyGraph, label: &str) -> Option<NodeId> {
+        let key = label.to_lowercase();
+        if let Some(id) = self.resolved.get(&key) {
+            return Some(*id);
+        }
+        let found = graph
+            .find_nodes_by_exact_label(label)
+            .first()
+            .copied()
+            .or_else(|| {
+                self.labels
+                    .get_or_insert_with(|| {
+                        graph
+                            .find_nodes_by_label("")
+                            .into_iter()
+                            .filter_map(|id| {
+                                graph.get_node(&id).map(|n| (n.label.to_lowercase(), id))
+                            })
+                            .collect()
+                    })
+                    .iter()
+                    .find(|(l, _)| l.contains(&key))
+                    .map(|(_, id)| *id)
+            })?;
+        self.resolved.insert(key, found);
+        Some(found)
+    }
+
+    /// Record a node added to the graph, after every node already in it.
+    pub(crate) fn node_added(&mut self, id: NodeId, label: &str) {
+        let key = label.to_lowercase();
+        if let Some(labels) = &mut self.labels {
+            labels.push((key.clone(), id));
+        }
+        self.resolved.insert(key, id);
+    }
+}
+
+/// One co-occurrence to wire.
+struct Wire {
+    /// Index into [`PendingWiring::presentations`].
+    presentation: usize,
+    from: NodeId,
+    to: NodeId,
+    /// Reinforcement if the edge exists by then.
+    amount: f64,
+    /// Weight of a new edge; `None` leaves a missing edge unwired.
+    initial: Option<f64>,
+}
+
+/// Co-occurrences presented this tick, not yet written to the graph.
+#[derive(Default)]
+pub(crate) struct PendingWiring {
+    /// The agent and document behind each presentation, in order.
+    presentations: Vec<(AgentId, Option<DocumentId>)>,
+    /// Wires of each pair, in order of the pair's first wire.
+    groups: Vec<Vec<Wire>>,
+    /// Pair, in ascending order → index into `groups`.
+    pairs: HashMap<(NodeId, NodeId), usize>,
+}
+
+/// What a flush wrote, for the colony to record.
+pub(crate) struct FlushedWiring {
+    /// What each document's presentations contributed, in presentation order.
+    pub(crate) contributions: Vec<(DocumentId, DocumentContribution)>,
+    /// Edges that could not be written, by the agent that presented them.
+    pub(crate) failures: Vec<(AgentId, Vec<TopologyError>)>,
+    /// Each edge written, whether it is new, and the document behind each
+    /// of its wires, in presentation order.
+    pub(crate) wired: Vec<(NodeId, NodeId, bool, Vec<Option<DocumentId>>)>,
+}
+
+fn pair_key(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+impl PendingWiring {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.presentations.is_empty()
+    }
+
+    /// Start a presentation by `agent` of `document`; returns its index.
+    pub(crate) fn begin(&mut self, agent: AgentId, document: Option<DocumentId>) -> usize {
+        self.presentations.push((agent, document));
+        self.presentations.len() - 1
+    }
+
+    /// Whether wiring between `a` and `b` is waiting to be written.
+    pub(crate) fn is_pending(&self, a: NodeId, b: NodeId) -> bool {
+        self.pairs.contains_key(&pair_key(a, b))
+    }
+
+    /// Queue a co-occurrence of `from` and `to` in `presentation`.
+    pub(crate) fn push(
+        &mut self,
+        presentation: usize,
+        from: NodeId,
+        to: NodeId,
+        amount: f64,
+        initial: Option<f64>,
+    ) {
+        let groups = &mut self.groups;
+        let group = *self.pairs.entry(pair_key(from, to)).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        self.groups[group].push(Wire {
+            presentation,
+            from,
+            to,
+            amount,
+            initial,
+        });
+    }
+
+    /// Write every pending pair to `graph` with one update each, applying
+    /// its wires in presentation order as incremental wiring would.
+    pub(crate) fn flush(
+        self,
+        graph: &mut dyn TopologyGraph,
+        tick: Tick,
+        rule: &WeightUpdate,
+    ) -> FlushedWiring {
+        let mut contributions: Vec<DocumentContribution> = self
+            .presentations
+            .iter()
+            .map(|_| DocumentContribution::default())
+            .collect();
+        let mut errors: Vec<Vec<TopologyError>> =
+            self.presentations.iter().map(|_| Vec::new()).collect();
+        let mut wired = Vec::new();
+
+        for wires in self.groups {
+            let existing = graph.get_edge(&wires[0].from, &wires[0].to).cloned();
+            let existed = existing.is_some();
+            let mut edge = existing;
+            let mut added = Vec::with_capacity(wires.len());
+            for wire in &wires {
+                match &mut edge {
+                    Some(edge) => {
+                        let delta = reinforce_edge(edge, wire.amount, tick, rule);
+                        added.push((wire.presentation, delta));
+                    }
+                    None => {
+                        if let Some(weight) = wire.initial {
+                            edge = Some(EdgeData {
+                                weight,
+                                co_activations: 1,
+                                created_tick: tick,
+                                last_activated_tick: tick,
+                            });
+                            added.push((wire.presentation, weight));
+                        }
+                    }
+                }
+            }
+            let Some(edge) = edge else {
+                continue;
+            };
+
+            let (from, to) = (wires[0].from, wires[0].to);
+            let written = if existed {
+                if let Some(current) = graph.get_edge_mut(&from, &to) {
+                    *current = edge;
+                }
+                Ok(())
+            } else {
+                graph.set_edge(from, to, edge)
+            };
+            match written {
+                Ok(()) => {
+                    let documents = added
+                        .iter()
+                        .map(|(presentation, _)| self.presentations[*presentation].1)
+                        .collect();
+                    wired.push((from, to, !existed, documents));
+                    for (presentation, delta) in added {
+                        contributions[presentation].add_edge(from, to, delta, 1);
+                    }
+                }
+                Err(e) => errors[wires[0].presentation].push(e),
+            }
+        }
+
+        let mut flushed = FlushedWiring {
+            contributions: Vec::new(),
+            failures: Vec::new(),
+            wired,
+        };
+        for (((agent, document), contribution), errors) in self
+            .presentations
+            .into_iter()
+            .zip(contributions)
+            .zip(errors)
+        {
+            if let Some(document) = document {
+                if !contribution.is_empty() {
+                    flushed.contributions.push((document, contribution));
+                }
+            }
+            if !errors.is_empty() {
+                flushed.failures.push((agent, errors));
+            }
+        }
+        flushed
+    }
+}
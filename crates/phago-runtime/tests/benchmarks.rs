@@ -8,12 +8,23 @@
 //! 3. Async runtime (throughput comparison)
 //! 4. Graph scaling (node/edge counts)
 //! 5. Semantic wiring overhead
+//! 6. Trace memory (payload compression and the trace budget)
+//! 7. Label suggestions (prefix autocomplete on a 100k-node graph)
+//! 8. Dissolution (boundary modulation on the embedded corpus)
+//! 9. Ingest throughput (incremental vs deferred wiring)
+//! 10. Query latency under load (runner queries vs read views)
+//! 11. Signal coalescing (tick time after bulk ingestion into one region)
+//! 12. Held-out coverage (test-split terms the train split's graph knows)
 
 use phago_agents::digester::Digester;
-use phago_core::types::Position;
-use phago_runtime::bench::{run_benchmark, BenchmarkConfig, BenchmarkSuite};
+use phago_core::substrate::Substrate;
+use phago_core::tokenize::Tokenizer;
+use phago_core::types::{EdgeData, NodeData, NodeId, NodeType, Position};
+use phago_runtime::bench::{measure_ingest, run_benchmark, BenchmarkConfig, BenchmarkSuite};
 use phago_runtime::corpus::Corpus;
 use phago_runtime::prelude::*;
+use phago_runtime::trace_payload;
+use std::collections::HashSet;
 use std::time::Instant;
 
 /// Helper to create a colony with documents and agents.
@@ -468,6 +479,454 @@ fn bench_full_suite() {
     println!();
 }
 
+// ============================================================================
+// BENCHMARK 8: Trace Memory
+// ============================================================================
+
+/// Trace memory observed at the peak of a run.
+#[derive(Default)]
+struct TracePeak {
+    bytes: usize,
+    traces: usize,
+    stored_payload: usize,
+    raw_payload: usize,
+}
+
+/// Run `ticks` ticks, recording trace memory at its peak.
+fn run_tracking_trace_memory(colony: &mut Colony, ticks: u64) -> TracePeak {
+    let mut peak = TracePeak::default();
+    for _ in 0..ticks {
+        colony.tick();
+        let bytes = colony.substrate().trace_memory_bytes();
+        if bytes > peak.bytes {
+            let traces = colony.substrate().all_traces();
+            peak = TracePeak {
+                bytes,
+                traces: traces.len(),
+                stored_payload: traces.iter().map(|t| t.payload.len()).sum(),
+                raw_payload: traces
+                    .iter()
+                    .filter_map(|t| trace_payload::decode(&t.payload))
+                    .map(|p| p.len())
+                    .sum(),
+            };
+        }
+    }
+    peak
+}
+
+#[test]
+fn bench_trace_memory() {
+    println!("\n=== BENCHMARK: Trace Memory (500 ticks, 50 agents) ===\n");
+
+    let ticks = 500;
+    let (docs, agents) = (50, 50);
+
+    // Unbounded: how much memory do traces and their payloads take?
+    let mut colony = setup_colony(docs, agents);
+    let peak = run_tracking_trace_memory(&mut colony, ticks);
+    assert!(peak.stored_payload <= peak.raw_payload);
+
+    // Bounded: cap trace memory at half the unbounded peak
+    let budget = (peak.bytes / 2).max(1);
+    let mut bounded = setup_colony(docs, agents);
+    bounded.substrate_mut().set_max_trace_bytes(budget);
+    let bounded_peak = run_tracking_trace_memory(&mut bounded, ticks);
+    assert!(bounded_peak.bytes <= budget);
+
+    println!(
+        "{:<24} {:>12} {:>8} {:>14} {:>10}",
+        "Config", "Peak bytes", "Traces", "Payload s/raw", "Evicted"
+    );
+    println!("{:-<72}", "");
+    for (name, p, evicted) in [
+        (
+            "Unbounded".to_string(),
+            &peak,
+            colony.stats().traces_evicted,
+        ),
+        (
+            format!("Budget {} bytes", budget),
+            &bounded_peak,
+            bounded.stats().traces_evicted,
+        ),
+    ] {
+        println!(
+            "{:<24} {:>12} {:>8} {:>14} {:>10}",
+            name,
+            p.bytes,
+            p.traces,
+            format!("{}/{}", p.stored_payload, p.raw_payload),
+            evicted
+        );
+    }
+    println!();
+    println!(
+        "  trace-compression: {}",
+        if cfg!(feature = "trace-compression") {
+            "on"
+        } else {
+            "off"
+        }
+    );
+    println!();
+}
+
+// ============================================================================
+// BENCHMARK 9: Label Suggestions
+// ============================================================================
+
+/// Pronounceable synthetic label for node `i` ("bafoki", "bagodu", ...).
+fn synthetic_label(mut i: usize) -> String {
+    const CONSONANTS: &[u8] = b"bcdfghklmnprstvz";
+    const VOWELS: &[u8] = b"aeiou";
+    let mut label = String::new();
+    for _ in 0..4 {
+        label.push(CONSONANTS[i % CONSONANTS.len()] as char);
+        i /= CONSONANTS.len();
+        label.push(VOWELS[i % VOWELS.len()] as char);
+        i /= VOWELS.len();
+    }
+    label
+}
+
+#[test]
+fn bench_label_suggestions() {
+    println!("\n=== BENCHMARK: Label Suggestions (100k nodes) ===\n");
+
+    let node_count = 100_000;
+    let mut colony = Colony::new();
+    let ids: Vec<NodeId> = (0..node_count)
+        .map(|i| {
+            colony.substrate_mut().add_node(NodeData {
+                id: NodeId::new(),
+                label: synthetic_label(i),
+                node_type: NodeType::Concept,
+                position: Position::new(0.0, 0.0),
+                access_count: (i % 97) as u64,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            })
+        })
+        .collect();
+    for i in 0..node_count {
+        for step in [1, 7, 331] {
+            colony
+                .substrate_mut()
+                .set_edge(
+                    ids[i],
+                    ids[(i * 31 + step) % node_count],
+                    EdgeData {
+                        weight: 0.5,
+                        co_activations: 1,
+                        created_tick: 0,
+                        last_activated_tick: 0,
+                    },
+                )
+                .unwrap();
+        }
+    }
+
+    println!("{:<10} {:>10} {:>14}", "Prefix", "Matches", "Avg time (µs)");
+    println!("{:-<36}", "");
+    let rounds = 200;
+    for prefix in ["ba", "baf", "bafo", "bafok"] {
+        let matches = colony
+            .substrate()
+            .graph()
+            .find_nodes_by_label_prefix(prefix)
+            .len();
+        let start = Instant::now();
+        for _ in 0..rounds {
+            let suggestions = colony.suggest_labels(prefix, 10);
+            assert!(suggestions.iter().all(|s| s.label.starts_with(prefix)));
+        }
+        let avg_us = start.elapsed().as_secs_f64() * 1e6 / rounds as f64;
+        println!("{:<10} {:>10} {:>14.1}", prefix, matches, avg_us);
+    }
+    println!();
+}
+
+// ============================================================================
+// BENCHMARK 10: Dissolution
+// ============================================================================
+
+/// Boundary modulation on the 40-document embedded corpus with 50
+/// digesters, 100 ticks.
+///
+/// Before the dissolution phase kept a concept index, each agent looked up
+/// every vocabulary term and scanned every node on every tick; this run
+/// took about 11s in release builds (110 ms/tick), most of it in that
+/// phase. With the index it takes about 3.5s (35 ms/tick) at interval 1
+/// and 1.4s at interval 4.
+#[test]
+fn bench_dissolution() {
+    println!("\n=== BENCHMARK: Dissolution (40 docs, 50 agents, 100 ticks) ===\n");
+
+    let corpus = Corpus::from_embedded().limit(40);
+    println!(
+        "{:<12} {:>10} {:>12} {:>10}",
+        "Interval", "Nodes", "Time (ms)", "ms/tick"
+    );
+    println!("{:-<48}", "");
+    for interval in [1, 2, 4] {
+        let mut colony = Colony::from_config(ColonyConfig {
+            dissolution_interval: interval,
+            ..Default::default()
+        });
+        corpus.ingest_into(&mut colony);
+        for i in 0..50 {
+            let (x, y) = ((i % 10) as f64 * 2.5, (i / 10) as f64 * 5.0);
+            colony.spawn(Box::new(
+                Digester::new(Position::new(x, y)).with_max_idle(100),
+            ));
+        }
+
+        let ticks = 100;
+        let start = Instant::now();
+        colony.run(ticks);
+        let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+        println!(
+            "{:<12} {:>10} {:>12.0} {:>10.1}",
+            interval,
+            colony.stats().graph_nodes,
+            elapsed,
+            elapsed / ticks as f64
+        );
+    }
+    println!();
+}
+
+// ============================================================================
+// BENCHMARK 11: Ingest Throughput
+// ============================================================================
+
+/// Digest the embedded corpus with one digester per document, wiring each
+/// presentation immediately and then once per tick.
+///
+/// In release builds the incremental run digests the 100 documents at about
+/// 17 docs/sec and the deferred run at about 20 docs/sec.
+#[test]
+fn bench_ingest_throughput() {
+    println!("\n=== BENCHMARK: Ingest Throughput (embedded corpus) ===\n");
+
+    let corpus = Corpus::from_embedded();
+    println!(
+        "{:<14} {:>6} {:>7} {:>11} {:>10} {:>8} {:>8}",
+        "Wiring", "Docs", "Ticks", "Time (ms)", "Docs/sec", "Nodes", "Edges"
+    );
+    println!("{:-<70}", "");
+    for (name, deferred_wiring) in [("incremental", false), ("deferred", true)] {
+        let mut colony = Colony::from_config(ColonyConfig {
+            deferred_wiring,
+            ..Default::default()
+        });
+        corpus.ingest_into(&mut colony);
+        for doc in &corpus.documents {
+            colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(100)));
+        }
+
+        let run = measure_ingest(&mut colony, name, 200);
+        println!(
+            "{:<14} {:>6} {:>7} {:>11} {:>10.1} {:>8} {:>8}",
+            run.name,
+            run.documents,
+            run.ticks,
+            run.wall_time_ms,
+            run.docs_per_sec,
+            run.nodes,
+            run.edges
+        );
+    }
+    println!();
+}
+
+// ============================================================================
+// BENCHMARK 12: Query Latency Under Load (requires async feature)
+// ============================================================================
+
+/// The `q`th quantile of sorted latencies.
+#[cfg(feature = "async")]
+fn quantile(sorted: &[std::time::Duration], q: f64) -> std::time::Duration {
+    sorted[((sorted.len() - 1) as f64 * q).round() as usize]
+}
+
+/// Path queries against a colony ticking as fast as it can, sent through
+/// the runner (waiting for the tick in progress) and answered from its
+/// read view (not waiting).
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn bench_query_latency_under_load() {
+    use phago_runtime::async_runtime::{AsyncColonyRunner, RunnerConfig, TickRate};
+    use phago_runtime::graph_algorithms::PathCost;
+    use phago_runtime::read_view::ViewRefresh;
+    use std::time::Duration;
+
+    println!("\n=== BENCHMARK: Query Latency Under Load ===\n");
+
+    const QUERIES: usize = 200;
+    let config = RunnerConfig {
+        view: Some(ViewRefresh::EveryTick),
+        ..RunnerConfig::new(TickRate::Manual)
+    };
+    let (handle, thread) = AsyncColonyRunner::spawn(config, || {
+        let mut colony = setup_colony(50, 8);
+        for i in 0..16 {
+            let position = Position::new((i % 8) as f64 * 6.0, (i / 8) as f64 * 6.0);
+            colony.spawn(Box::new(Digester::new(position).with_max_idle(100_000)));
+        }
+        colony.run(20);
+        Some(colony)
+    });
+    handle.query(|colony| colony.stats().tick).await.unwrap();
+    handle.set_rate(TickRate::PerSecond(10_000.0));
+
+    let mut through_runner = Vec::with_capacity(QUERIES);
+    for _ in 0..QUERIES {
+        let start = Instant::now();
+        let _ = handle
+            .query(|colony| colony.find_path("cell", "protein", PathCost::default(), 1))
+            .await
+            .unwrap();
+        through_runner.push(start.elapsed());
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    let mut through_view = Vec::with_capacity(QUERIES);
+    for _ in 0..QUERIES {
+        let start = Instant::now();
+        let view = handle.view().expect("runner keeps a view");
+        let _ = view.find_path("cell", "protein", PathCost::default(), 1);
+        through_view.push(start.elapsed());
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    let ticks = handle.stats().ticks;
+    handle.stop();
+    thread.join().unwrap();
+
+    through_runner.sort();
+    through_view.sort();
+    println!("{:<10} {:>12} {:>12}", "Path", "p50 (us)", "p99 (us)");
+    println!("{:-<36}", "");
+    for (name, latencies) in [("runner", &through_runner), ("view", &through_view)] {
+        println!(
+            "{:<10} {:>12} {:>12}",
+            name,
+            quantile(latencies, 0.5).as_micros(),
+            quantile(latencies, 0.99).as_micros()
+        );
+    }
+    println!("\n({ticks} ticks ran while querying)\n");
+
+    assert!(ticks > 0);
+    assert!(quantile(&through_view, 0.99) < quantile(&through_runner, 0.99));
+}
+
+// ============================================================================
+// BENCHMARK 13: Signal Coalescing
+// ============================================================================
+
+/// Ingest 2,000 documents into one small region and tick, with input
+/// signals coalesced (the default) and kept one by one.
+#[test]
+fn bench_signal_coalescing() {
+    println!("\n=== BENCHMARK: Signal Coalescing (2000 docs in one region) ===\n");
+
+    let (docs, agents, ticks) = (2000, 20, 50);
+    println!(
+        "{:<12} {:>9} {:>9} {:>9} {:>12}",
+        "Coalescing", "Signals", "Merged", "Evicted", "Tick (us)"
+    );
+    println!("{:-<55}", "");
+    let mut live = Vec::new();
+    for (name, signal_coalescing) in [("on", Some(SignalCoalescing::default())), ("off", None)] {
+        let mut colony = Colony::from_config(ColonyConfig {
+            signal_coalescing,
+            ..Default::default()
+        });
+        for i in 0..docs {
+            let position = Position::new((i % 20) as f64 * 0.5, (i / 20 % 20) as f64 * 0.5);
+            colony.ingest_document(
+                &format!("Doc {i}"),
+                &format!("Cell membrane transport note {i}"),
+                position,
+            );
+        }
+        for i in 0..agents {
+            colony.spawn(Box::new(
+                Digester::new(Position::new(i as f64, 0.0)).with_max_idle(ticks),
+            ));
+        }
+
+        let signals = colony.stats().total_signals;
+        let start = Instant::now();
+        colony.run(ticks);
+        let per_tick = start.elapsed().as_micros() / ticks as u128;
+        let stats = colony.stats();
+        println!(
+            "{:<12} {:>9} {:>9} {:>9} {:>12}",
+            name, signals, stats.signals_merged, stats.signals_evicted, per_tick
+        );
+        live.push(signals);
+    }
+    println!();
+    assert!(live[0] * 10 < live[1]);
+}
+
+// ============================================================================
+// BENCHMARK 14: Held-out Coverage
+// ============================================================================
+
+/// Digest a train split of the embedded corpus and count how many of the
+/// held-out documents' terms the graph has a node for.
+#[test]
+fn bench_held_out_coverage() {
+    println!("\n=== BENCHMARK: Held-out Coverage (embedded corpus, 60 ticks) ===\n");
+
+    let corpus = Corpus::from_embedded().limit(40);
+    let tokenizer = Tokenizer::default();
+    println!(
+        "{:<10} {:>6} {:>6} {:>8} {:>10} {:>10}",
+        "Train", "Docs", "Held", "Nodes", "Held terms", "Covered"
+    );
+    println!("{:-<55}", "");
+    let mut coverage = Vec::new();
+    for fraction in [0.5, 0.8] {
+        let (train, test) = corpus.split(fraction, 42);
+        let mut colony = Colony::new();
+        train.ingest_into(&mut colony);
+        for doc in &train.documents {
+            colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(60)));
+        }
+        colony.run(60);
+
+        let held: HashSet<String> = test
+            .documents
+            .iter()
+            .flat_map(|doc| tokenizer.tokens(&doc.content))
+            .collect();
+        let graph = colony.substrate().graph();
+        let covered = held
+            .iter()
+            .filter(|term| !graph.find_nodes_by_exact_label(term).is_empty())
+            .count();
+        let ratio = covered as f64 / held.len().max(1) as f64;
+        println!(
+            "{:<10} {:>6} {:>6} {:>8} {:>10} {:>9.1}%",
+            fraction,
+            train.len(),
+            test.len(),
+            colony.stats().graph_nodes,
+            held.len(),
+            ratio * 100.0
+        );
+        coverage.push(ratio);
+    }
+    println!();
+    assert!(coverage.iter().all(|c| *c > 0.0));
+}
+
 // ============================================================================
 // SUMMARY
 // ============================================================================
@@ -490,6 +949,13 @@ fn bench_summary() {
     println!("║   5. Agent Serialization   - Export/import overhead              ║");
     println!("║   6. Semantic Wiring       - Similarity computation cost         ║");
     println!("║   7. Full Suite            - Complete comparison table           ║");
+    println!("║   8. Trace Memory          - Payload compression, trace budget   ║");
+    println!("║   9. Label Suggestions     - Prefix autocomplete latency         ║");
+    println!("║  10. Dissolution           - Boundary modulation per tick        ║");
+    println!("║  11. Ingest Throughput     - Incremental vs deferred wiring      ║");
+    println!("║  12. Query Latency         - Runner vs read view under ticking   ║");
+    println!("║  13. Signal Coalescing     - Tick time after bulk ingestion      ║");
+    println!("║  14. Held-out Coverage     - Test-split terms in the train graph ║");
     println!("╚══════════════════════════════════════════════════════════════════╝");
     println!();
 }
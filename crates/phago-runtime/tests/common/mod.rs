@@ -0,0 +1,108 @@
+//! Helpers shared by the integration tests.
+
+use phago_core::agent::Agent;
+use phago_core::primitives::{Apoptose, Digest, Sense};
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use std::collections::VecDeque;
+
+/// Plays a fixed list of actions, one per tick, then repeats `idle`.
+pub struct Scripted {
+    id: AgentId,
+    position: Position,
+    script: VecDeque<AgentAction>,
+    idle: AgentAction,
+    age: Tick,
+}
+
+impl Scripted {
+    pub fn new(script: Vec<AgentAction>, idle: AgentAction) -> Self {
+        Self {
+            id: AgentId::new(),
+            position: Position::new(0.0, 0.0),
+            script: script.into(),
+            idle,
+            age: 0,
+        }
+    }
+}
+
+impl Digest for Scripted {
+    type Input = String;
+    type Fragment = String;
+    type Presentation = Vec<String>;
+
+    fn engulf(&mut self, _input: String) -> DigestionResult {
+        DigestionResult::Engulfed
+    }
+
+    fn lyse(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn present(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+impl Apoptose for Scripted {
+    fn self_assess(&self) -> CellHealth {
+        CellHealth::Healthy
+    }
+
+    fn prepare_death_signal(&self) -> DeathSignal {
+        DeathSignal {
+            agent_id: self.id,
+            total_ticks: self.age,
+            useful_outputs: 0,
+            final_fragments: Vec::new(),
+            cause: DeathCause::SelfAssessed(CellHealth::Redundant),
+            had_pending_work: false,
+        }
+    }
+}
+
+impl Sense for Scripted {
+    fn sense_radius(&self) -> f64 {
+        0.0
+    }
+
+    fn sense_position(&self) -> Position {
+        self.position
+    }
+
+    fn gradient(&self, _substrate: &dyn Substrate) -> Vec<Gradient> {
+        Vec::new()
+    }
+
+    fn orient(&self, _gradients: &[Gradient]) -> Orientation {
+        Orientation::Stay
+    }
+}
+
+impl Agent for Scripted {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn agent_type(&self) -> &str {
+        "scripted"
+    }
+
+    fn tick(&mut self, _substrate: &dyn Substrate) -> AgentAction {
+        self.age += 1;
+        self.script.pop_front().unwrap_or_else(|| self.idle.clone())
+    }
+
+    fn age(&self) -> Tick {
+        self.age
+    }
+}
@@ -0,0 +1,283 @@
+//! Agents written outside the workspace extend the colony through custom
+//! actions, without patching the runtime.
+//!
+//! The bookmarker below is such an agent: it marks the strongest concept
+//! in the graph with a custom `"bookmark"` action, which a handler
+//! registered with the colony carries out by annotating the node.
+
+use phago_agents::digester::Digester;
+use phago_core::agent::Agent;
+use phago_core::primitives::{Apoptose, Digest, Sense};
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyEvent};
+use phago_runtime::plugin::{ActionContext, CustomActionHandler};
+use uuid::Uuid;
+
+const BOOKMARK: &str = "bookmark";
+
+/// Bookmarks the most accessed concept whenever it changes.
+struct Bookmarker {
+    id: AgentId,
+    position: Position,
+    bookmarked: Option<NodeId>,
+    /// Custom action sent instead of a bookmark, once.
+    stray: Option<AgentAction>,
+    age: Tick,
+}
+
+impl Bookmarker {
+    fn new() -> Self {
+        Self {
+            id: AgentId::new(),
+            position: Position::new(0.0, 0.0),
+            bookmarked: None,
+            stray: None,
+            age: 0,
+        }
+    }
+
+    /// The concept presented most often, ties broken by label.
+    fn strongest(substrate: &dyn Substrate) -> Option<NodeId> {
+        substrate
+            .all_nodes()
+            .iter()
+            .filter_map(|id| substrate.get_node(id))
+            .filter(|n| n.node_type == NodeType::Concept)
+            .max_by(|a, b| {
+                a.access_count
+                    .cmp(&b.access_count)
+                    .then_with(|| b.label.cmp(&a.label))
+            })
+            .map(|n| n.id)
+    }
+}
+
+impl Digest for Bookmarker {
+    type Input = String;
+    type Fragment = String;
+    type Presentation = Vec<String>;
+
+    fn engulf(&mut self, _input: String) -> DigestionResult {
+        DigestionResult::Indigestible
+    }
+
+    fn lyse(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn present(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+impl Apoptose for Bookmarker {
+    fn self_assess(&self) -> CellHealth {
+        CellHealth::Healthy
+    }
+
+    fn prepare_death_signal(&self) -> DeathSignal {
+        DeathSignal {
+            agent_id: self.id,
+            total_ticks: self.age,
+            useful_outputs: 0,
+            final_fragments: Vec::new(),
+            cause: DeathCause::SelfAssessed(CellHealth::Redundant),
+            had_pending_work: false,
+        }
+    }
+}
+
+impl Sense for Bookmarker {
+    fn sense_radius(&self) -> f64 {
+        0.0
+    }
+
+    fn sense_position(&self) -> Position {
+        self.position
+    }
+
+    fn gradient(&self, _substrate: &dyn Substrate) -> Vec<Gradient> {
+        Vec::new()
+    }
+
+    fn orient(&self, _gradients: &[Gradient]) -> Orientation {
+        Orientation::Stay
+    }
+}
+
+impl Agent for Bookmarker {
+    fn id(&self) -> AgentId {
+        self.id
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    fn agent_type(&self) -> &str {
+        "bookmarker"
+    }
+
+    fn tick(&mut self, substrate: &dyn Substrate) -> AgentAction {
+        self.age += 1;
+        if let Some(action) = self.stray.take() {
+            return action;
+        }
+        match Self::strongest(substrate) {
+            Some(node) if self.bookmarked != Some(node) => {
+                self.bookmarked = Some(node);
+                AgentAction::Custom {
+                    kind: BOOKMARK.to_string(),
+                    payload: node.0.to_string().into_bytes(),
+                }
+            }
+            _ => AgentAction::Idle,
+        }
+    }
+
+    fn age(&self) -> Tick {
+        self.age
+    }
+}
+
+/// Carries out bookmarks by annotating the node, and leaves a trace where
+/// the bookmarking agent stood.
+struct Bookmarks;
+
+impl CustomActionHandler for Bookmarks {
+    fn handle(&mut self, ctx: &mut ActionContext<'_>, payload: &[u8]) -> Result<(), String> {
+        let id = std::str::from_utf8(payload)
+            .ok()
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .map(NodeId)
+            .ok_or("payload is not a node ID")?;
+        let note = format!("bookmarked by {}", ctx.agent().0);
+        if !ctx.annotate(&id, &note) {
+            return Err(format!("no node {}", id.0));
+        }
+        let trace = Trace {
+            agent_id: ctx.agent(),
+            trace_type: TraceType::Importance,
+            intensity: 1.0,
+            tick: ctx.tick(),
+            payload: payload.to_vec(),
+        };
+        ctx.deposit_trace(&SubstrateLocation::Spatial(ctx.position()), trace);
+        Ok(())
+    }
+}
+
+fn digested_colony() -> Colony {
+    let mut colony = Colony::new();
+    colony.ingest_document(
+        "Membranes",
+        "The cell membrane controls transport. Membrane proteins span the membrane.",
+        Position::new(0.0, 0.0),
+    );
+    colony.spawn(Box::new(Digester::new(Position::new(0.0, 0.0))));
+    colony.run(5);
+    colony
+}
+
+fn failures(events: &[ColonyEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|e| match e {
+            ColonyEvent::ActionFailed {
+                action_kind,
+                reason,
+                ..
+            } => Some(format!("{action_kind}: {reason}")),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn handlers_carry_out_custom_actions_through_a_tick() {
+    let mut colony = digested_colony();
+    colony.register_action_handler(BOOKMARK, Box::new(Bookmarks));
+    let bookmarker = Bookmarker::new();
+    let agent_id = bookmarker.id();
+    colony.spawn(Box::new(bookmarker));
+
+    let strongest = Bookmarker::strongest(colony.substrate()).unwrap();
+    let events = colony.tick();
+    assert!(failures(&events).is_empty(), "{:?}", failures(&events));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        ColonyEvent::Annotated { node_id, source, .. } if *node_id == strongest && source == BOOKMARK
+    )));
+
+    let notes = colony.annotations(&strongest);
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].text, format!("bookmarked by {}", agent_id.0));
+    assert_eq!(notes[0].source, BOOKMARK);
+    let traces = colony
+        .substrate()
+        .traces_at(&SubstrateLocation::Spatial(Position::new(0.0, 0.0)));
+    assert!(traces.iter().any(|t| t.agent_id == agent_id));
+
+    // The strongest concept has not changed, so it is not bookmarked twice
+    colony.tick();
+    assert_eq!(colony.annotations(&strongest).len(), 1);
+}
+
+#[test]
+fn unknown_kinds_and_handler_errors_are_reported() {
+    let mut colony = digested_colony();
+    colony.register_action_handler(BOOKMARK, Box::new(Bookmarks));
+    let mut stray = Bookmarker::new();
+    stray.stray = Some(AgentAction::Custom {
+        kind: "teleport".to_string(),
+        payload: Vec::new(),
+    });
+    let mut malformed = Bookmarker::new();
+    malformed.stray = Some(AgentAction::Custom {
+        kind: BOOKMARK.to_string(),
+        payload: b"not a node".to_vec(),
+    });
+    colony.spawn(Box::new(stray));
+    colony.spawn(Box::new(malformed));
+
+    let failed = failures(&colony.tick());
+    assert!(
+        failed.contains(&"custom: no handler for custom action 'teleport'".to_string()),
+        "{failed:?}"
+    );
+    assert!(
+        failed.contains(&"custom: bookmark: payload is not a node ID".to_string()),
+        "{failed:?}"
+    );
+}
+
+#[test]
+fn closures_can_handle_custom_actions() {
+    let mut colony = digested_colony();
+    colony.register_action_handler(
+        BOOKMARK,
+        Box::new(|ctx: &mut ActionContext<'_>, _payload: &[u8]| {
+            let pin = ctx.add_node("bookmarks", NodeType::Insight);
+            let strongest = Bookmarker::strongest(ctx.substrate()).ok_or("empty graph")?;
+            ctx.set_edge(pin, strongest, 0.9)
+                .map_err(|e| e.to_string())?;
+            Ok(())
+        }),
+    );
+    colony.spawn(Box::new(Bookmarker::new()));
+    let events = colony.tick();
+    assert!(failures(&events).is_empty(), "{:?}", failures(&events));
+
+    let graph = colony.substrate().graph();
+    let pin = graph.find_nodes_by_exact_label("bookmarks")[0];
+    assert_eq!(graph.get_node(&pin).unwrap().node_type, NodeType::Insight);
+    let neighbors = graph.neighbors(&pin);
+    assert_eq!(neighbors.len(), 1);
+    // Set at 0.9, then decayed with every other edge at the end of the tick
+    assert!((0.85..=0.9).contains(&neighbors[0].1.weight));
+}
@@ -0,0 +1,204 @@
+//! Deferred wiring must build the same graph as incremental wiring.
+//!
+//! Replays the same presentations of the embedded corpus twice, once wiring
+//! every presentation immediately and once buffering the wiring per tick,
+//! and compares the resulting nodes, edges, document contributions and
+//! edge provenance.
+//! Scripted agents keep the two runs identical: several of them present in
+//! every tick, so pairs are wired by more than one presentation per flush.
+
+mod common;
+
+use common::Scripted;
+use phago_agents::digester::Digester;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig, ColonyEvent};
+use phago_runtime::corpus::Corpus;
+use phago_runtime::provenance::EdgeSource;
+use phago_runtime::reinforcement::WeightUpdate;
+use std::collections::BTreeMap;
+
+/// Agents replaying the same presentations in each run.
+const AGENTS: usize = 4;
+
+/// Edge weight and co-activations, by endpoint labels in ascending order.
+type EdgeWiring = BTreeMap<(String, String), (f64, u64)>;
+
+/// Each edge's recorded reinforcements: the corpus index of the document
+/// behind each one, if any, and its tick.
+type EdgeSources = BTreeMap<(String, String), Vec<(Option<usize>, Tick)>>;
+
+/// Nodes by label, edges by their endpoint labels and what each document
+/// contributed, with the random IDs replaced by labels.
+#[derive(Debug, PartialEq)]
+struct GraphSummary {
+    nodes: BTreeMap<String, (NodeType, u64, Tick)>,
+    edges: BTreeMap<(String, String), (f64, u64, Tick, Tick)>,
+    contributions: BTreeMap<usize, EdgeWiring>,
+    sources: EdgeSources,
+    wired: usize,
+}
+
+/// Concept labels of each corpus document, extracted once so both runs
+/// present exactly the same fragments.
+fn corpus_labels(corpus: &Corpus) -> Vec<Vec<String>> {
+    corpus
+        .documents
+        .iter()
+        .map(|doc| Digester::new(doc.position).digest_text(doc.content.clone()))
+        .collect()
+}
+
+fn replay(config: ColonyConfig, corpus: &Corpus, labels: &[Vec<String>]) -> GraphSummary {
+    let mut colony = Colony::from_config(config);
+    let doc_ids: Vec<DocumentId> = corpus
+        .documents
+        .iter()
+        .map(|doc| colony.ingest_document(&doc.title, &doc.content, doc.position))
+        .collect();
+
+    for agent in 0..AGENTS {
+        let mut script = Vec::new();
+        for (k, doc_id) in doc_ids.iter().enumerate().skip(agent).step_by(AGENTS) {
+            let position = corpus.documents[k].position;
+            script.push(AgentAction::EngulfDocument(*doc_id));
+            script.push(AgentAction::PresentFragments(
+                labels[k]
+                    .iter()
+                    .map(|label| FragmentPresentation {
+                        label: label.clone(),
+                        source_document: *doc_id,
+                        position,
+                        node_type: NodeType::Concept,
+                        derived_from: Vec::new(),
+                        passages: Vec::new(),
+                        access_weight: 1,
+                        score: None,
+                        namespace: None,
+                    })
+                    .collect(),
+            ));
+        }
+        colony.spawn(Box::new(Scripted::new(script, AgentAction::Idle)));
+    }
+
+    let mut wired = 0;
+    for _ in 0..doc_ids.len() {
+        for event in colony.tick() {
+            if let ColonyEvent::Wired {
+                connection_count, ..
+            } = event
+            {
+                wired += connection_count;
+            }
+        }
+    }
+
+    let graph = colony.substrate().graph();
+    let label = |id: &NodeId| graph.get_node(id).unwrap().label.clone();
+    let pair = |a: &NodeId, b: &NodeId| {
+        let (a, b) = (label(a), label(b));
+        if a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    };
+    let nodes = graph
+        .all_nodes()
+        .iter()
+        .map(|id| {
+            let n = graph.get_node(id).unwrap();
+            (
+                n.label.clone(),
+                (n.node_type.clone(), n.access_count, n.created_tick),
+            )
+        })
+        .collect();
+    let edges = graph
+        .all_edges()
+        .into_iter()
+        .map(|(a, b, e)| {
+            (
+                pair(&a, &b),
+                (
+                    e.weight,
+                    e.co_activations,
+                    e.created_tick,
+                    e.last_activated_tick,
+                ),
+            )
+        })
+        .collect();
+    let contributions = doc_ids
+        .iter()
+        .enumerate()
+        .filter_map(|(k, doc_id)| {
+            let contribution = colony.document_contribution(doc_id)?;
+            let edges = contribution
+                .edges
+                .iter()
+                .map(|((a, b), e)| (pair(a, b), (e.weight, e.co_activations)))
+                .collect();
+            Some((k, edges))
+        })
+        .collect();
+    let sources = colony
+        .edge_provenance()
+        .iter()
+        .map(|((a, b), ring)| {
+            let records = ring
+                .iter()
+                .map(|r| {
+                    let doc = match r.source {
+                        EdgeSource::Document(id) => doc_ids.iter().position(|d| *d == id),
+                        _ => None,
+                    };
+                    (doc, r.tick)
+                })
+                .collect();
+            (pair(&a, &b), records)
+        })
+        .collect();
+    GraphSummary {
+        nodes,
+        edges,
+        contributions,
+        sources,
+        wired,
+    }
+}
+
+fn assert_same_graph(config: ColonyConfig) {
+    let corpus = Corpus::from_embedded().limit(24);
+    let labels = corpus_labels(&corpus);
+    let incremental = replay(config.clone(), &corpus, &labels);
+    let deferred = replay(
+        ColonyConfig {
+            deferred_wiring: true,
+            ..config
+        },
+        &corpus,
+        &labels,
+    );
+
+    assert!(incremental.edges.len() > 100, "{}", incremental.edges.len());
+    // Some pairs were reinforced, not only created
+    assert!(incremental.wired > incremental.edges.len());
+    assert_eq!(incremental.contributions.len(), corpus.documents.len());
+    assert_eq!(incremental.sources.len(), incremental.edges.len());
+    assert_eq!(incremental, deferred);
+}
+
+#[test]
+fn deferred_wiring_matches_incremental_wiring() {
+    assert_same_graph(ColonyConfig::default());
+}
+
+#[test]
+fn deferred_wiring_matches_incremental_wiring_with_asymptotic_updates() {
+    assert_same_graph(ColonyConfig {
+        weight_update: WeightUpdate::Asymptotic { rate: 0.5 },
+        ..ColonyConfig::default()
+    });
+}
@@ -0,0 +1,131 @@
+//! Edge provenance names the documents and queries behind an edge's
+//! recent reinforcements, keeps only the last K of them, survives a
+//! session round trip, and stays within a memory bound per edge.
+
+use phago_agents::digester::Digester;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig};
+use phago_runtime::corpus::Corpus;
+use phago_runtime::provenance::EdgeSource;
+use phago_runtime::session::{load_session, restore_into_colony, save_session};
+
+/// A colony that digested three documents mentioning cells and membranes.
+fn digested(config: ColonyConfig) -> (Colony, Vec<DocumentId>) {
+    let mut colony = Colony::from_config(config);
+    let docs = [
+        (
+            "Membranes",
+            "The cell membrane controls transport into the cell.",
+        ),
+        (
+            "Organelles",
+            "Every cell membrane encloses organelles and cytoplasm.",
+        ),
+        (
+            "Signals",
+            "Receptors on the cell membrane relay signals inward.",
+        ),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, (title, content))| {
+        let position = Position::new(i as f64 * 2.0, 0.0);
+        colony.spawn(Box::new(
+            Digester::with_seed(position, i as u64).with_max_idle(40),
+        ));
+        colony.ingest_document(title, content, position)
+    })
+    .collect();
+    colony.run(40);
+    (colony, docs)
+}
+
+fn edge(colony: &Colony, from: &str, to: &str) -> (NodeId, NodeId) {
+    let graph = colony.substrate().graph();
+    let id = |label| graph.find_nodes_by_exact_label(label)[0];
+    (id(from), id(to))
+}
+
+#[test]
+fn sources_name_the_reinforcing_documents_and_queries() {
+    let (mut colony, docs) = digested(ColonyConfig {
+        edge_provenance: 4,
+        ..Default::default()
+    });
+    let (cell, membrane) = edge(&colony, "cell", "membrane");
+    let sources = colony.edge_sources(&cell, &membrane);
+    assert!(sources
+        .iter()
+        .all(|r| matches!(r.source, EdgeSource::Document(id) if docs.contains(&id))));
+    let documents: std::collections::HashSet<_> = sources.iter().map(|r| r.source).collect();
+    assert!(documents.len() >= 2, "{sources:?}");
+
+    for _ in 0..5 {
+        colony.reinforce_retrieval(&[], &[], &[(cell, membrane, 0.05)]);
+    }
+    let sources = colony.edge_sources(&membrane, &cell);
+    assert_eq!(sources.len(), 4);
+    assert!(sources.iter().all(|r| r.source == EdgeSource::Query));
+}
+
+#[test]
+fn nothing_is_recorded_when_provenance_is_off() {
+    let (colony, _) = digested(ColonyConfig {
+        edge_provenance: 0,
+        ..Default::default()
+    });
+    assert!(colony.substrate().graph().edge_count() > 0);
+    assert!(colony.edge_provenance().is_empty());
+}
+
+#[test]
+fn sources_survive_a_session_round_trip() {
+    let (colony, _) = digested(ColonyConfig::default());
+    let (cell, membrane) = edge(&colony, "cell", "membrane");
+    let title = |colony: &Colony, source| match source {
+        EdgeSource::Document(id) => colony.substrate().get_document(&id).unwrap().title.clone(),
+        other => format!("{other:?}"),
+    };
+    let saved: Vec<(String, Tick)> = colony
+        .edge_sources(&cell, &membrane)
+        .into_iter()
+        .map(|r| (title(&colony, r.source), r.tick))
+        .collect();
+    assert!(!saved.is_empty());
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    save_session(&colony, &path, &[]).unwrap();
+    let mut restored = Colony::new();
+    restore_into_colony(&mut restored, &load_session(&path).unwrap(), None);
+
+    let (cell, membrane) = edge(&restored, "cell", "membrane");
+    let loaded: Vec<(String, Tick)> = restored
+        .edge_sources(&cell, &membrane)
+        .into_iter()
+        .map(|r| (title(&restored, r.source), r.tick))
+        .collect();
+    assert_eq!(loaded, saved);
+}
+
+#[test]
+fn eight_sources_per_edge_stay_under_the_memory_bound() {
+    let corpus = Corpus::from_embedded();
+    let mut colony = Colony::from_config(ColonyConfig {
+        edge_provenance: 8,
+        ..Default::default()
+    });
+    corpus.ingest_into(&mut colony);
+    for (i, doc) in corpus.documents.iter().enumerate() {
+        colony.spawn(Box::new(
+            Digester::with_seed(doc.position, i as u64).with_max_idle(80),
+        ));
+    }
+    colony.run(30);
+
+    let provenance = colony.edge_provenance();
+    assert!(provenance.len() > 100, "{}", provenance.len());
+    // See the memory notes in phago_runtime::provenance
+    let bytes = provenance.bytes_per_edge();
+    assert!(bytes < 420.0, "{bytes:.0} bytes per edge");
+}
@@ -0,0 +1,110 @@
+//! Embeddings agents attach with `EmbedConcepts` reach the concepts they
+//! are of and the colony's embedding cache, which later concepts of a
+//! cached term take theirs from; failed embeddings are reported.
+
+mod common;
+
+use common::Scripted;
+use phago_core::substrate::Substrate;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig, ColonyEvent};
+
+fn presentation(doc_id: DocumentId, labels: &[&str]) -> AgentAction {
+    AgentAction::PresentFragments(
+        labels
+            .iter()
+            .map(|label| FragmentPresentation {
+                label: label.to_string(),
+                source_document: doc_id,
+                position: Position::new(0.0, 0.0),
+                node_type: NodeType::Concept,
+                derived_from: Vec::new(),
+                passages: Vec::new(),
+                access_weight: 1,
+                score: None,
+                namespace: None,
+            })
+            .collect(),
+    )
+}
+
+fn embedding(label: &str, embedding: Result<Vec<f32>, &str>) -> ConceptEmbedding {
+    ConceptEmbedding {
+        label: label.to_string(),
+        embedding: embedding.map_err(str::to_string),
+    }
+}
+
+/// Present membrane and cell, embed them and protein, then present
+/// protein; returns the colony and its failed actions.
+fn run(config: ColonyConfig) -> (Colony, Vec<(String, String)>) {
+    let mut colony = Colony::from_config(config);
+    let doc_id = colony.ingest_document("notes", "a document", Position::new(0.0, 0.0));
+    colony.spawn(Box::new(Scripted::new(
+        vec![
+            AgentAction::EngulfDocument(doc_id),
+            presentation(doc_id, &["membrane", "cell"]),
+            AgentAction::EmbedConcepts(vec![
+                embedding("Membrane", Ok(vec![1.0, 0.0])),
+                embedding("cell", Err("embedder timed out")),
+                embedding("protein", Ok(vec![0.0, 1.0])),
+            ]),
+            presentation(doc_id, &["protein"]),
+        ],
+        AgentAction::Idle,
+    )));
+    let mut failures = Vec::new();
+    for _ in 0..5 {
+        for event in colony.tick() {
+            if let ColonyEvent::ActionFailed {
+                action_kind,
+                reason,
+                ..
+            } = event
+            {
+                failures.push((action_kind, reason));
+            }
+        }
+    }
+    (colony, failures)
+}
+
+fn node_embedding(colony: &Colony, label: &str) -> Option<Vec<f32>> {
+    let id = colony.substrate().graph().find_nodes_by_exact_label(label)[0];
+    colony.substrate().embedding(&id)
+}
+
+#[test]
+fn attached_embeddings_are_cached_for_later_concepts() {
+    let (colony, failures) = run(ColonyConfig::default());
+
+    assert_eq!(node_embedding(&colony, "membrane"), Some(vec![1.0, 0.0]));
+    assert_eq!(node_embedding(&colony, "cell"), None);
+    // Embedded before it was presented, and taken from the cache
+    assert_eq!(node_embedding(&colony, "protein"), Some(vec![0.0, 1.0]));
+
+    let substrate = colony.substrate();
+    assert_eq!(substrate.embedding_cache().len(), 2);
+    assert_eq!(substrate.cached_embedding("MEMBRANE"), Some(vec![1.0, 0.0]));
+    assert_eq!(substrate.cached_embedding("cell"), None);
+
+    assert_eq!(
+        failures,
+        [(
+            "embed_concepts".to_string(),
+            "could not embed 'cell': embedder timed out".to_string()
+        )]
+    );
+}
+
+#[test]
+fn without_a_cache_only_presented_concepts_get_embeddings() {
+    let (colony, _) = run(ColonyConfig {
+        embedding_cache_capacity: 0,
+        ..Default::default()
+    });
+
+    assert_eq!(node_embedding(&colony, "membrane"), Some(vec![1.0, 0.0]));
+    assert_eq!(node_embedding(&colony, "protein"), None);
+    assert!(colony.substrate().embedding_cache().is_empty());
+}
@@ -0,0 +1,90 @@
+//! An exploration schedule anneals digesters from exploring to exploiting.
+//! Until every document is digested it leaves them exploring exactly as
+//! a fixed explore-heavy genome does; afterwards they consolidate what
+//! they digested and end with stronger edges than a fixed exploit-heavy
+//! genome.
+
+use phago_agents::digester::Digester;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig};
+use phago_runtime::exploration::ExplorationSchedule;
+
+const DOCUMENTS: &[&str] = &[
+    "The mitochondria produce energy for the cell through oxidative phosphorylation.",
+    "Ribosomes translate messenger RNA into protein chains inside the cell.",
+    "The cell membrane controls transport of ions and nutrients into the cell.",
+    "Protein folding in the endoplasmic reticulum depends on chaperone proteins.",
+    "Energy from ATP drives membrane transport and protein synthesis.",
+    "Oxidative stress damages mitochondria and membrane lipids over time.",
+];
+
+const TICKS: u64 = 60;
+
+struct Outcome {
+    /// Tick every document had been digested by, if they all were.
+    all_digested: Option<Tick>,
+    mean_edge_weight: f64,
+    /// The colony's exploitation after each tick.
+    exploitation: Vec<f64>,
+}
+
+/// A digester next to each document, all within sensing range of each
+/// other.
+fn run(explore_bias: f64, exploration: Option<ExplorationSchedule>) -> Outcome {
+    let mut colony = Colony::from_config(ColonyConfig {
+        exploration,
+        ..Default::default()
+    });
+    for (i, content) in DOCUMENTS.iter().enumerate() {
+        let position = Position::new(i as f64 * 1.5, 0.0);
+        colony.ingest_document(&format!("Doc {i}"), content, position);
+        colony.spawn(Box::new(
+            Digester::with_seed(position, i as u64)
+                .with_max_idle(TICKS)
+                .with_explore_bias(explore_bias),
+        ));
+    }
+
+    let mut all_digested = None;
+    let mut exploitation = Vec::new();
+    for _ in 0..TICKS {
+        colony.tick();
+        let stats = colony.stats();
+        exploitation.push(stats.exploitation);
+        if all_digested.is_none() && stats.documents_digested == stats.documents_total {
+            all_digested = Some(stats.tick);
+        }
+    }
+    let edges = colony.substrate().graph().all_edges();
+    let mean_edge_weight =
+        edges.iter().map(|(_, _, e)| e.weight).sum::<f64>() / edges.len().max(1) as f64;
+    Outcome {
+        all_digested,
+        mean_edge_weight,
+        exploitation,
+    }
+}
+
+#[test]
+fn adaptive_schedule_explores_until_digested_then_ends_stronger_than_exploiting() {
+    let exploit_heavy = run(0.05, None);
+    let adaptive = run(0.8, Some(ExplorationSchedule::default()));
+
+    assert!(exploit_heavy.exploitation.iter().all(|&e| e == 0.0));
+    let digested = adaptive
+        .all_digested
+        .expect("adaptive colony digested everything");
+    // Exploring like an explore-heavy colony until the last document...
+    let before = &adaptive.exploitation[..digested as usize - 1];
+    assert!(before.iter().all(|&e| e == 0.0), "{before:?}");
+    // ...then exploiting once discovery stops
+    let last = *adaptive.exploitation.last().unwrap();
+    assert!(last > 0.9, "final exploitation {last}");
+
+    assert!(
+        adaptive.mean_edge_weight > exploit_heavy.mean_edge_weight,
+        "adaptive {:.4} vs exploit-heavy {:.4}",
+        adaptive.mean_edge_weight,
+        exploit_heavy.mean_edge_weight
+    );
+}
@@ -0,0 +1,143 @@
+//! A colony with a spawn policy replaces the agents that die by itself:
+//! over a run where agents starve quickly the population stays at the
+//! policy's cap, every replacement descends from a parent and comes a
+//! generation after the one before, and the colony knows every living
+//! agent's genome. Replacements are built through the colony's registry.
+
+use phago_agents::genome::AgentGenome;
+use phago_agents::registry::{AgentRegistry, AgentSpec, DigesterParams};
+use phago_agents::spawn::SpawnPolicySpec;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig, ColonyEvent};
+use serde_json::json;
+
+const DOCUMENTS: &[&str] = &[
+    "The mitochondria produce energy for the cell through oxidative phosphorylation.",
+    "Ribosomes translate messenger RNA into protein chains inside the cell.",
+    "The cell membrane controls transport of ions and nutrients into the cell.",
+    "Energy from ATP drives membrane transport and protein synthesis.",
+];
+
+const CAP: usize = 6;
+
+/// Digesters that give up after a few idle ticks, one per slot of the cap.
+fn colony(spawn_policy: SpawnPolicySpec) -> Colony {
+    let mut colony = Colony::from_config(ColonyConfig {
+        spawn_policy: Some(spawn_policy),
+        ..Default::default()
+    });
+    for (i, content) in DOCUMENTS.iter().enumerate() {
+        colony.ingest_document(
+            &format!("Doc {i}"),
+            content,
+            Position::new(i as f64 * 2.0, 0.0),
+        );
+    }
+    let genome = AgentGenome {
+        max_idle: 5,
+        ..AgentGenome::default_genome()
+    };
+    let registry = AgentRegistry::new();
+    for i in 0..CAP {
+        let params = json!({
+            "position": Position::new(i as f64, 1.0),
+            "seed": i,
+            "genome": genome,
+        });
+        colony
+            .spawn_from_spec(&registry, &AgentSpec::new("digester", params))
+            .unwrap();
+    }
+    colony
+}
+
+#[test]
+fn population_stays_at_the_cap_across_generations() {
+    let spec = SpawnPolicySpec::lamarckian(CAP, 0.15, 0.5).with_seed(1000);
+    let mut colony = colony(spec.clone());
+    assert_eq!(colony.config().spawn_policy, Some(spec));
+    assert_eq!(colony.genomes().len(), CAP);
+
+    let mut spawned = Vec::new();
+    let mut inherited = 0;
+    for _ in 0..300 {
+        for event in colony.tick() {
+            if let ColonyEvent::Spawned {
+                id,
+                parent,
+                generation,
+                ..
+            } = event
+            {
+                inherited += colony.inherited_terms(&id);
+                spawned.push((id, parent, generation));
+            }
+        }
+        assert_eq!(colony.alive_count(), CAP);
+        let alive: Vec<AgentId> = colony.agents().iter().map(|a| a.id()).collect();
+        assert_eq!(colony.genomes().len(), CAP);
+        assert!(alive.iter().all(|id| colony.genomes().contains_key(id)));
+    }
+
+    // Many generations turned over, each bred from a parent
+    assert!(spawned.len() > 3 * CAP, "only {} spawned", spawned.len());
+    assert!(spawned.iter().all(|(_, parent, _)| parent.is_some()));
+    let generations: Vec<u32> = spawned.iter().map(|(_, _, g)| *g).collect();
+    assert!(
+        generations.windows(2).all(|w| w[0] < w[1]),
+        "{generations:?}"
+    );
+    assert_eq!(
+        colony.fitness_tracker().max_generation(),
+        *generations.last().unwrap()
+    );
+
+    // Offspring are registered in the population with their genome
+    let (id, _, generation) = spawned.last().unwrap();
+    assert_eq!(
+        colony.fitness_tracker().get(id).unwrap().generation,
+        *generation
+    );
+    let spec = colony.agent_spec(id).unwrap();
+    assert_eq!(spec.agent_type, "digester");
+    let params: DigesterParams = serde_json::from_value(spec.params.clone()).unwrap();
+    assert_eq!(
+        params.genome.unwrap().max_idle,
+        colony.genomes()[id].max_idle
+    );
+    // The first offspring inherited what their parents had digested
+    assert!(inherited > 0);
+}
+
+#[test]
+fn replacements_the_registry_cannot_build_are_reported() {
+    let spec = SpawnPolicySpec::fitness(CAP, 0.1).with_agent_type("macrophage");
+    let mut colony = colony(spec);
+    let failure = (0..50)
+        .flat_map(|_| colony.tick())
+        .find_map(|event| match event {
+            ColonyEvent::ActionFailed {
+                action_kind,
+                reason,
+                ..
+            } if action_kind == "spawn" => Some(reason),
+            _ => None,
+        });
+    assert!(failure.unwrap().contains("macrophage"));
+    assert!(colony.alive_count() < CAP);
+
+    let mut registry = AgentRegistry::new();
+    registry.register("macrophage", DigesterParams::build);
+    colony.set_agent_registry(registry);
+    let params = json!({"max_idle": 5});
+    colony
+        .spawn_from_spec(&AgentRegistry::new(), &AgentSpec::new("digester", params))
+        .unwrap();
+    let spawned = (0..50)
+        .flat_map(|_| colony.tick())
+        .find_map(|event| match event {
+            ColonyEvent::Spawned { agent_type, .. } => Some(agent_type),
+            _ => None,
+        });
+    assert_eq!(spawned.as_deref(), Some("digester"));
+}
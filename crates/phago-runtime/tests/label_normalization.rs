@@ -0,0 +1,122 @@
+//! Presented labels are normalized into node labels: long ones are cut
+//! short without two of them colliding, and stay findable, and exportable,
+//! under the label they were presented with.
+
+mod common;
+
+use common::Scripted;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig};
+use phago_runtime::export::export_triples_with;
+use phago_runtime::label_policy::{LabelCase, LabelForm, LabelPolicy};
+use std::collections::HashSet;
+
+/// A colony in which one agent has presented `labels` from one document.
+fn presented(config: ColonyConfig, labels: &[(&str, NodeType)]) -> Colony {
+    let mut colony = Colony::from_config(config);
+    let doc_id = colony.ingest_document("notes", "a document", Position::new(0.0, 0.0));
+    let presentation = labels
+        .iter()
+        .map(|(label, node_type)| FragmentPresentation {
+            label: label.to_string(),
+            source_document: doc_id,
+            position: Position::new(0.0, 0.0),
+            node_type: node_type.clone(),
+            derived_from: Vec::new(),
+            passages: Vec::new(),
+            access_weight: 1,
+            score: None,
+            namespace: None,
+        })
+        .collect();
+    colony.spawn(Box::new(Scripted::new(
+        vec![
+            AgentAction::EngulfDocument(doc_id),
+            AgentAction::PresentFragments(presentation),
+        ],
+        AgentAction::Idle,
+    )));
+    colony.run(3);
+    colony
+}
+
+fn long_label(ending: &str) -> String {
+    format!("{}{ending}", "signature ".repeat(50))
+}
+
+#[test]
+fn distinct_long_labels_stay_distinct_nodes() {
+    let (a, b) = (long_label("a"), long_label("b"));
+    assert_eq!(a.chars().count(), 501);
+    let colony = presented(
+        ColonyConfig::default(),
+        &[(&a, NodeType::Concept), (&b, NodeType::Concept)],
+    );
+    let graph = colony.substrate().graph();
+    let labels: HashSet<String> = graph
+        .all_nodes()
+        .iter()
+        .map(|id| graph.get_node(id).unwrap().label.clone())
+        .collect();
+    assert_eq!(labels.len(), 2);
+    let max = LabelPolicy::default().max_chars;
+    assert!(
+        labels.iter().all(|l| l.chars().count() <= max),
+        "{labels:?}"
+    );
+
+    // Exports choose between the stored and the presented labels
+    let stored = export_triples_with(&colony, LabelForm::Stored);
+    assert!(labels.contains(&stored[0].subject));
+    let original = export_triples_with(&colony, LabelForm::Original);
+    let ends = HashSet::from([original[0].subject.clone(), original[0].object.clone()]);
+    assert_eq!(ends, HashSet::from([a, b]));
+}
+
+#[test]
+fn truncated_insights_are_found_by_their_full_label() {
+    let concepts: Vec<String> = (0..30).map(|k| format!("concept{k}")).collect();
+    let insight = format!("[BRIDGE {}]", concepts.join("|"));
+    let colony = presented(ColonyConfig::default(), &[(&insight, NodeType::Insight)]);
+    let graph = colony.substrate().graph();
+
+    let found = graph.find_nodes_by_exact_label(&insight);
+    assert_eq!(found.len(), 1);
+    let node = graph.get_node(&found[0]).unwrap();
+    assert!(node.label.starts_with("[BRIDGE concept0|concept1|"));
+    assert!(node.label.len() < insight.len());
+    assert_eq!(colony.original_label(&found[0]), Some(insight));
+}
+
+#[test]
+fn labels_are_stored_as_the_policy_says() {
+    let verbatim = presented(
+        ColonyConfig {
+            labels: LabelPolicy::verbatim(),
+            ..ColonyConfig::default()
+        },
+        &[("Cell  Membrane", NodeType::Concept)],
+    );
+    let graph = verbatim.substrate().graph();
+    let id = graph.find_nodes_by_exact_label("cell  membrane")[0];
+    assert_eq!(graph.get_node(&id).unwrap().label, "Cell  Membrane");
+    assert_eq!(verbatim.original_label(&id), None);
+
+    let lowered = presented(
+        ColonyConfig {
+            labels: LabelPolicy {
+                case: LabelCase::Lower,
+                ..LabelPolicy::default()
+            },
+            ..ColonyConfig::default()
+        },
+        &[("Cell  Membrane", NodeType::Concept)],
+    );
+    let graph = lowered.substrate().graph();
+    let id = graph.find_nodes_by_exact_label("cell membrane")[0];
+    assert_eq!(graph.get_node(&id).unwrap().label, "cell membrane");
+    assert_eq!(
+        lowered.original_label(&id).as_deref(),
+        Some("Cell  Membrane")
+    );
+}
@@ -2,7 +2,7 @@
 //!
 //! Tests the quality of Louvain community detection against known ground truth.
 
-use phago_core::louvain::{compute_modularity, louvain_communities, LouvainResult};
+use phago_core::louvain::{louvain_communities, LouvainResult};
 use phago_core::types::NodeId;
 use std::collections::HashMap;
 use std::time::Instant;
@@ -75,6 +75,9 @@ fn normalized_mutual_information(ground_truth: &[usize], predicted: &[usize]) ->
     }
 }
 
+/// Node ids, weighted edges by index, and the ground-truth community per node.
+type PlantedPartition = (Vec<NodeId>, Vec<(usize, usize, f64)>, Vec<usize>);
+
 /// Generate a synthetic graph with planted communities.
 /// Returns (node_ids, edges, ground_truth_partition).
 fn generate_planted_partition(
@@ -83,7 +86,7 @@ fn generate_planted_partition(
     p_in: f64,  // Probability of edge within community
     p_out: f64, // Probability of edge between communities
     seed: u64,
-) -> (Vec<NodeId>, Vec<(usize, usize, f64)>, Vec<usize>) {
+) -> PlantedPartition {
     let total_nodes = num_communities * nodes_per_community;
     let node_ids: Vec<NodeId> = (0..total_nodes)
         .map(|i| NodeId::from_seed(i as u64 + seed))
@@ -302,7 +305,7 @@ fn benchmark_modularity_quality() {
 #[test]
 fn benchmark_summary() {
     // Run multiple tests and compute average metrics
-    let configs = vec![
+    let configs = [
         (4, 10, 0.7, 0.05),  // Small, well-separated
         (6, 20, 0.6, 0.03),  // Medium
         (8, 25, 0.5, 0.02),  // Larger
@@ -3,7 +3,6 @@
 use phago_agents::digester::Digester;
 use phago_agents::sentinel::Sentinel;
 use phago_agents::synthesizer::Synthesizer;
-use phago_core::topology::TopologyGraph;
 use phago_core::types::*;
 use phago_runtime::colony::{Colony, ColonyEvent};
 
@@ -128,3 +127,85 @@ fn dissolution_boosts_access_count() {
         "at least one node should have access_count > 1 from dissolution/reinforcement"
     );
 }
+
+#[test]
+fn stale_bridge_insight_is_pruned() {
+    let mut colony = Colony::new();
+
+    // Two document groups that share "protein" as a bridging concept
+    colony.ingest_document(
+        "Membranes",
+        "The cell membrane uses protein channels for transport of ions and molecules.",
+        Position::new(0.0, 0.0),
+    );
+    colony.ingest_document(
+        "Membrane Transport",
+        "Membrane protein pumps drive active transport of ions across the cell.",
+        Position::new(0.5, 0.0),
+    );
+    colony.ingest_document(
+        "Enzymes",
+        "Enzyme kinetics depend on protein folding and substrate binding affinity.",
+        Position::new(8.0, 0.0),
+    );
+    colony.ingest_document(
+        "Catalysis",
+        "Protein enzymes lower activation energy during substrate catalysis.",
+        Position::new(8.5, 0.0),
+    );
+
+    colony.spawn(Box::new(
+        Digester::with_seed(Position::new(0.0, 0.0), 1).with_max_idle(300),
+    ));
+    colony.spawn(Box::new(
+        Digester::with_seed(Position::new(8.0, 0.0), 2).with_max_idle(300),
+    ));
+    colony.spawn(Box::new(Synthesizer::with_seed(Position::new(4.0, 0.0), 3)));
+
+    // Let a bridge insight with provenance form
+    let mut bridge = None;
+    for _ in 0..100 {
+        colony.tick();
+        let graph = colony.substrate().graph();
+        bridge = graph.all_nodes().into_iter().find(|nid| {
+            graph
+                .get_node(nid)
+                .is_some_and(|n| n.label.starts_with("[BRIDGE"))
+                && colony.insight_provenance(nid).is_some()
+        });
+        if bridge.is_some() {
+            break;
+        }
+    }
+    let bridge = bridge.expect("a bridge insight should form");
+    assert!(colony.insight_support(&bridge).unwrap() > 0.0);
+
+    // Prune every edge of the bridging concept
+    let anchor = colony.insight_provenance(&bridge).unwrap()[0];
+    let neighbors: Vec<NodeId> = colony
+        .substrate()
+        .graph()
+        .neighbors(&anchor)
+        .into_iter()
+        .map(|(nid, _)| nid)
+        .collect();
+    for nid in neighbors {
+        colony
+            .substrate_mut()
+            .graph_mut()
+            .remove_edge(&anchor, &nid);
+    }
+
+    let mut pruned = false;
+    for _ in 0..20 {
+        for event in colony.tick() {
+            if let ColonyEvent::InsightPruned { node_id, .. } = event {
+                pruned |= node_id == bridge;
+            }
+        }
+    }
+
+    assert!(pruned, "stale bridge insight should emit InsightPruned");
+    assert!(colony.substrate().graph().get_node(&bridge).is_none());
+    assert!(colony.insight_provenance(&bridge).is_none());
+}
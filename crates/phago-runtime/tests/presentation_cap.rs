@@ -0,0 +1,168 @@
+//! Capping the fragments an agent presents per tick spreads a large
+//! presentation over several ticks without changing the graph it builds.
+
+mod common;
+
+use common::Scripted;
+use phago_agents::digester::Digester;
+use phago_core::agent::Agent;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig, ColonyEvent};
+use std::collections::BTreeSet;
+
+/// What a run built: node labels, edges by endpoint labels and the edges
+/// credited to the document.
+#[derive(Debug, PartialEq)]
+struct Built {
+    nodes: BTreeSet<String>,
+    edges: BTreeSet<(String, String)>,
+    contributed_edges: usize,
+}
+
+struct Run {
+    built: Built,
+    /// Fragments presented by the flooding agent, per tick.
+    presented: Vec<usize>,
+    /// Ticks in which the other agent moved.
+    moved: usize,
+}
+
+fn flood(fragments: usize, cap: usize, ticks: usize) -> Run {
+    let mut colony = Colony::from_config(ColonyConfig {
+        max_fragments_per_agent_per_tick: cap,
+        // Keep every pair's edge to compare
+        max_edge_degree: fragments,
+        ..ColonyConfig::default()
+    });
+    let doc_id = colony.ingest_document("flood", "a long document", Position::new(0.0, 0.0));
+    let presentation = (0..fragments)
+        .map(|k| FragmentPresentation {
+            label: format!("term{k:03}"),
+            source_document: doc_id,
+            position: Position::new(0.0, 0.0),
+            node_type: NodeType::Concept,
+            derived_from: Vec::new(),
+            passages: Vec::new(),
+            access_weight: 1,
+            score: None,
+            namespace: None,
+        })
+        .collect();
+    let flooder = Scripted::new(
+        vec![
+            AgentAction::EngulfDocument(doc_id),
+            AgentAction::PresentFragments(presentation),
+        ],
+        AgentAction::Idle,
+    );
+    let flooder_id = flooder.id();
+    colony.spawn(Box::new(flooder));
+    let mover = Scripted::new(Vec::new(), AgentAction::Move(Position::new(1.0, 1.0)));
+    let mover_id = mover.id();
+    colony.spawn(Box::new(mover));
+
+    // The engulfing tick comes first
+    colony.tick();
+    let mut presented = Vec::new();
+    let mut moved = 0;
+    for _ in 0..ticks {
+        let events = colony.tick();
+        let count: usize = events
+            .iter()
+            .filter_map(|e| match e {
+                ColonyEvent::Presented {
+                    id, fragment_count, ..
+                } if *id == flooder_id => Some(*fragment_count),
+                _ => None,
+            })
+            .sum();
+        presented.push(count);
+        if events
+            .iter()
+            .any(|e| matches!(e, ColonyEvent::Moved { id, .. } if *id == mover_id))
+        {
+            moved += 1;
+        }
+    }
+    assert_eq!(colony.deferred_fragment_count(), 0);
+
+    let graph = colony.substrate().graph();
+    let label = |id: &NodeId| graph.get_node(id).unwrap().label.clone();
+    let nodes = graph.all_nodes().iter().map(label).collect();
+    let edges = graph
+        .all_edges()
+        .into_iter()
+        .map(|(a, b, _)| {
+            let (a, b) = (label(&a), label(&b));
+            if a <= b {
+                (a, b)
+            } else {
+                (b, a)
+            }
+        })
+        .collect();
+    let contributed_edges = colony
+        .document_contribution(&doc_id)
+        .map_or(0, |c| c.edges.len());
+    Run {
+        built: Built {
+            nodes,
+            edges,
+            contributed_edges,
+        },
+        presented,
+        moved,
+    }
+}
+
+#[test]
+fn capped_presentations_spread_over_ticks_and_build_the_same_graph() {
+    let uncapped = flood(500, 0, 10);
+    let capped = flood(500, 50, 10);
+
+    assert_eq!(uncapped.presented[0], 500);
+    assert_eq!(capped.presented, vec![50; 10]);
+    // The other agent acted in every tick of the flood
+    assert_eq!(capped.moved, 10);
+
+    assert_eq!(capped.built.nodes.len(), 500);
+    assert_eq!(capped.built.edges.len(), 500 * 499 / 2);
+    // Pairs across the ticks' chunks are wired too, and credited to the document
+    assert_eq!(capped.built.contributed_edges, capped.built.edges.len());
+    assert_eq!(capped.built, uncapped.built);
+}
+
+#[test]
+fn digesters_can_limit_their_own_presentations() {
+    let mut colony = Colony::new();
+    let text = (0..30)
+        .map(|k| format!("keyword{}", (b'a' + k as u8) as char))
+        .collect::<Vec<_>>()
+        .join(" ");
+    colony.ingest_document("doc", &text, Position::new(0.0, 0.0));
+    let digester = Digester::new(Position::new(0.0, 0.0)).with_max_fragments_per_tick(8);
+    let id = digester.id();
+    colony.spawn(Box::new(digester));
+
+    let mut presented = Vec::new();
+    for _ in 0..12 {
+        for event in colony.tick() {
+            if let ColonyEvent::Presented {
+                id: presenter,
+                fragment_count,
+                ..
+            } = event
+            {
+                if presenter == id {
+                    presented.push(fragment_count);
+                }
+            }
+        }
+    }
+    assert!(presented.len() > 2, "{presented:?}");
+    assert!(presented.iter().all(|&count| count <= 8), "{presented:?}");
+    assert_eq!(
+        presented.iter().sum::<usize>(),
+        colony.substrate().graph().node_count()
+    );
+}
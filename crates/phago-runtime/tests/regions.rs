@@ -0,0 +1,122 @@
+//! Regions give parts of the field their own decay and access: the same
+//! edge reinforced the same way keeps more weight in a cold region than in
+//! a hot one, and a document in a region closed to ingest is never
+//! digested.
+
+use phago_agents::digester::Digester;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyEvent};
+use phago_runtime::region::{RegionPolicy, RegionShape};
+use phago_runtime::session::{load_session, restore_into_colony, save_session};
+
+fn square(x: f64, y: f64, size: f64) -> RegionShape {
+    RegionShape::Rect(Rect::new(
+        Position::new(x, y),
+        Position::new(x + size, y + size),
+    ))
+}
+
+/// Weight of the edge between two labelled concepts, 0 once pruned.
+fn weight(colony: &Colony, from: &str, to: &str) -> f64 {
+    let graph = colony.substrate().graph();
+    let id = |label| graph.find_nodes_by_exact_label(label).first().copied();
+    match (id(from), id(to)) {
+        (Some(from), Some(to)) => graph.get_edge(&from, &to).map_or(0.0, |e| e.weight),
+        _ => 0.0,
+    }
+}
+
+#[test]
+fn the_same_edge_decays_faster_in_a_hot_region() {
+    let mut colony = Colony::new();
+    colony
+        .define_region("hot", square(0.0, 0.0, 10.0), RegionPolicy::decaying(4.0))
+        .unwrap();
+    colony
+        .define_region("cold", square(50.0, 0.0, 10.0), RegionPolicy::decaying(0.1))
+        .unwrap();
+    for (from, to, region) in [("kettle", "steam", "hot"), ("glacier", "ice", "cold")] {
+        colony.connect_concepts(from, to, 0.5, None, true).unwrap();
+        colony.promote_node(from, region).unwrap();
+        colony.promote_node(to, region).unwrap();
+    }
+    assert_eq!(
+        colony
+            .substrate()
+            .region_at(&Position::new(5.0, 5.0))
+            .map(|r| r.name.as_str()),
+        Some("hot")
+    );
+
+    for tick in 1..=200 {
+        colony.tick();
+        if tick % 20 == 0 {
+            colony
+                .connect_concepts("kettle", "steam", 0.2, None, false)
+                .ok();
+            colony
+                .connect_concepts("glacier", "ice", 0.2, None, false)
+                .unwrap();
+        }
+    }
+
+    let hot = weight(&colony, "kettle", "steam");
+    let cold = weight(&colony, "glacier", "ice");
+    assert!(cold > hot + 0.1, "cold {cold} should outweigh hot {hot}");
+}
+
+#[test]
+fn documents_in_a_region_closed_to_ingest_stay_undigested() {
+    let mut colony = Colony::new();
+    colony
+        .define_region(
+            "sealed",
+            square(0.0, 0.0, 10.0),
+            RegionPolicy {
+                allow_ingest: false,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let position = Position::new(5.0, 5.0);
+    let doc = colony.ingest_document(
+        "sealed",
+        "Archived records describe glacier retreat across northern valleys.",
+        position,
+    );
+    colony.spawn(Box::new(Digester::with_seed(position, 1).with_max_idle(30)));
+
+    let events: Vec<ColonyEvent> = colony.run(20).into_iter().flatten().collect();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        ColonyEvent::ActionFailed { action_kind, reason, .. }
+            if action_kind == "engulf_document" && reason.contains("sealed")
+    )));
+    assert!(!colony.substrate().get_document(&doc).unwrap().digested);
+    assert_eq!(colony.stats().graph_nodes, 0);
+}
+
+#[test]
+fn regions_survive_a_session_round_trip() {
+    let mut colony = Colony::new();
+    colony
+        .define_region(
+            "archive",
+            RegionShape::Circle {
+                center: Position::new(20.0, 20.0),
+                radius: 5.0,
+            },
+            RegionPolicy {
+                allow_agent_entry: false,
+                ..RegionPolicy::decaying(0.5)
+            },
+        )
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    save_session(&colony, &path, &[]).unwrap();
+    let mut restored = Colony::new();
+    restore_into_colony(&mut restored, &load_session(&path).unwrap(), None);
+    assert_eq!(restored.substrate().regions(), colony.substrate().regions());
+}
@@ -0,0 +1,188 @@
+//! Sessions written by every earlier release must keep loading.
+//!
+//! `tests/fixtures/sessions` holds one or more sessions per format version,
+//! as that version wrote them. Never edit them: when the format changes,
+//! bump `FORMAT_VERSION`, add a migration, and add a fixture for the new
+//! version.
+
+use phago_runtime::colony::Colony;
+use phago_runtime::label_policy::{LabelPolicy, ORIGINAL_LABEL_SOURCE};
+use phago_runtime::session::{
+    load_session, load_session_with, parse_session, restore_into_colony, LoadMode, SessionError,
+    FORMAT_VERSION, MIGRATIONS,
+};
+use std::path::{Path, PathBuf};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sessions")
+        .join(name)
+}
+
+fn fixtures() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(fixture(""))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn every_fixture_loads_and_restores() {
+    let paths = fixtures();
+    assert!(paths.len() >= 3);
+    for path in paths {
+        let (state, report) = load_session_with(&path, LoadMode::Migrate)
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        assert_eq!(state.metadata.format_version, FORMAT_VERSION);
+        assert!(report.unknown_fields.is_empty(), "{:?}", report);
+        assert!(report.skipped.is_empty(), "{:?}", report);
+
+        let mut colony = Colony::new();
+        restore_into_colony(&mut colony, &state, None);
+        assert_eq!(colony.substrate().graph().node_count(), state.nodes.len());
+        assert_eq!(colony.substrate().graph().edge_count(), state.edges.len());
+    }
+}
+
+#[test]
+fn every_version_has_a_fixture_and_a_migration() {
+    let names: Vec<String> = fixtures()
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    for version in 1..=FORMAT_VERSION {
+        let prefix = format!("v{}", version);
+        assert!(
+            names.iter().any(|name| name == &format!("{}.json", prefix)
+                || name.starts_with(&format!("{}-", prefix))),
+            "no fixture for format version {}",
+            version
+        );
+    }
+    let froms: Vec<u32> = MIGRATIONS.iter().map(|m| m.from).collect();
+    assert_eq!(froms, (1..FORMAT_VERSION).collect::<Vec<_>>());
+}
+
+#[test]
+fn baseline_sessions_migrate_their_access_ticks() {
+    let (state, report) =
+        load_session_with(&fixture("v1-baseline.json"), LoadMode::Migrate).unwrap();
+    assert_eq!(report.found_version, 1);
+    assert_eq!(report.migrated_from, vec![1, 2]);
+
+    let tick = |label: &str| {
+        let node = state.nodes.iter().find(|n| n.label == label).unwrap();
+        (node.created_tick, node.last_accessed_tick)
+    };
+    assert_eq!(tick("mitochondria"), (3, 3));
+    assert_eq!(tick("atp"), (5, 5));
+    assert_eq!(tick("respiration"), (0, 0));
+    let embedding = state.nodes[0].embedding.as_ref().unwrap();
+    assert_eq!(embedding.unpack(), vec![0.25, -0.5, 0.75]);
+}
+
+#[test]
+fn long_labels_are_normalized_on_load() {
+    let (state, report) =
+        load_session_with(&fixture("v2-long-labels.json"), LoadMode::Migrate).unwrap();
+    assert_eq!(report.migrated_from, vec![2]);
+
+    let max = LabelPolicy::default().max_chars;
+    assert!(state.nodes.iter().all(|n| n.label.chars().count() <= max));
+    let signature = state
+        .nodes
+        .iter()
+        .find(|n| n.label.starts_with("pub fn transport_across_membrane"))
+        .unwrap();
+    let original = &signature.aliases[0];
+    assert!(original.ends_with("TransportError>"));
+    assert_eq!(signature.annotations[0].text, *original);
+    assert_eq!(signature.annotations[0].source, ORIGINAL_LABEL_SOURCE);
+    assert!(state.edges.iter().any(|e| e.to_label == signature.label));
+    let document = state
+        .documents
+        .iter()
+        .find(|d| d.title == "transport.rs")
+        .unwrap();
+    assert!(document.nodes.contains_key(&signature.label));
+    assert!(document.passages.concepts.contains_key(&signature.label));
+
+    // Still found by the label it was presented with
+    let mut colony = Colony::new();
+    restore_into_colony(&mut colony, &state, None);
+    let graph = colony.substrate().graph();
+    let found = graph.find_nodes_by_exact_label(original);
+    assert_eq!(found.len(), 1);
+    assert_eq!(graph.get_node(&found[0]).unwrap().label, signature.label);
+}
+
+#[test]
+fn strict_mode_refuses_what_it_would_have_to_change() {
+    assert!(load_session_with(&fixture("v3.json"), LoadMode::Strict)
+        .unwrap()
+        .1
+        .is_clean());
+    assert!(matches!(
+        load_session_with(&fixture("v1-unversioned.json"), LoadMode::Strict),
+        Err(SessionError::NeedsMigration {
+            found: 1,
+            current: FORMAT_VERSION
+        })
+    ));
+
+    let json = std::fs::read_to_string(fixture("v3.json")).unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["nodes"][0]["pinned"] = true.into();
+    let extended = value.to_string();
+    match parse_session(&extended, LoadMode::Strict) {
+        Err(SessionError::UnknownFields(fields)) => assert_eq!(fields, vec!["nodes.0.pinned"]),
+        other => panic!("expected unknown fields, got {:?}", other.map(|(_, r)| r)),
+    }
+    let (_, report) = parse_session(&extended, LoadMode::Migrate).unwrap();
+    assert_eq!(report.unknown_fields, vec!["nodes.0.pinned"]);
+}
+
+#[test]
+fn future_versions_load_only_best_effort() {
+    let json = std::fs::read_to_string(fixture("v3.json")).unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["metadata"]["format_version"] = (FORMAT_VERSION + 1).into();
+    value["edges"][0]["weight"] = "heavy".into();
+    value["nodes"][1]["provenance"] = serde_json::json!(["notes.md"]);
+    let future = value.to_string();
+
+    let err = parse_session(&future, LoadMode::Migrate).unwrap_err();
+    assert!(matches!(
+        err,
+        SessionError::UnsupportedVersion { found, supported: FORMAT_VERSION }
+            if found == FORMAT_VERSION + 1
+    ));
+    assert!(err.to_string().contains("newer than this build supports"));
+
+    let (state, report) = parse_session(&future, LoadMode::BestEffort).unwrap();
+    assert_eq!(report.found_version, FORMAT_VERSION + 1);
+    assert_eq!(report.unknown_fields, vec!["nodes.1.provenance"]);
+    assert_eq!(report.skipped.len(), 1);
+    assert!(report.skipped[0].starts_with("edges.0: "));
+    assert_eq!(
+        state.edges.len(),
+        value["edges"].as_array().unwrap().len() - 1
+    );
+}
+
+#[test]
+fn corrupt_files_are_told_apart_from_missing_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.json");
+    std::fs::write(&path, "{\"nodes\": [").unwrap();
+    assert!(matches!(load_session(&path), Err(SessionError::Corrupt(_))));
+    std::fs::write(&path, "{\"nodes\": []}").unwrap();
+    assert!(matches!(load_session(&path), Err(SessionError::Corrupt(_))));
+    assert!(matches!(
+        load_session(&dir.path().join("missing.json")),
+        Err(SessionError::Io(_))
+    ));
+}
@@ -0,0 +1,129 @@
+//! Snapshot series must rebuild the snapshots they were recorded from.
+//!
+//! Records a colony whose graph grows, reweights, prunes and merges nodes
+//! as full snapshots and as a delta series side by side, then checks every
+//! materialized delta frame against its full snapshot and that the delta
+//! series is well under the size of the full snapshots.
+
+use phago_agents::digester::Digester;
+use phago_runtime::bench::{run_benchmark, BenchmarkConfig};
+use phago_runtime::colony::{Colony, ColonySnapshot};
+use phago_runtime::corpus::Corpus;
+use phago_runtime::snapshot::{SnapshotFrame, SnapshotMode, SnapshotSeries};
+
+const SNAPSHOTS: usize = 50;
+
+fn digesting_colony() -> Colony {
+    let mut colony = Colony::new();
+    let corpus = Corpus::inline_corpus();
+    corpus.ingest_into(&mut colony);
+    for doc in &corpus.documents {
+        colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(80)));
+    }
+    colony
+}
+
+/// `snapshot` with its nodes and edges sorted, to compare them regardless
+/// of order.
+fn sorted(mut snapshot: ColonySnapshot) -> ColonySnapshot {
+    snapshot.nodes.sort_by_key(|n| n.id);
+    snapshot
+        .edges
+        .sort_by(|a, b| (&a.from_label, &a.to_label).cmp(&(&b.from_label, &b.to_label)));
+    snapshot
+}
+
+#[test]
+fn delta_series_materializes_to_the_full_snapshots() {
+    let mut colony = digesting_colony();
+    let mut full = Vec::new();
+    let mut delta = SnapshotSeries::new(SnapshotMode::Delta);
+
+    for i in 0..SNAPSHOTS {
+        colony.run(3);
+        if i % 10 == 9 {
+            // Merge two concepts, removing a node and rewiring its edges
+            let nodes = colony.substrate().graph().all_nodes();
+            colony.merge_nodes(nodes[0], &nodes[1..2]);
+        }
+        full.push(colony.snapshot());
+        colony.record_snapshot(&mut delta);
+    }
+
+    assert_eq!(delta.len(), SNAPSHOTS);
+    assert!(matches!(delta.frames()[0], SnapshotFrame::Full(_)));
+    assert!(delta.frames()[1..]
+        .iter()
+        .all(|frame| matches!(frame, SnapshotFrame::Delta(_))));
+    let removals = delta.frames().iter().any(|frame| match frame {
+        SnapshotFrame::Delta(d) => !d.removed_nodes.is_empty() && !d.removed_edges.is_empty(),
+        _ => false,
+    });
+    assert!(removals, "the run should remove nodes and edges");
+
+    for (idx, snapshot) in full.iter().enumerate() {
+        assert_eq!(
+            sorted(delta.materialize(idx).unwrap()),
+            sorted(snapshot.clone()),
+            "frame {idx}"
+        );
+    }
+    assert_eq!(delta.materialize(SNAPSHOTS), None);
+    let all = delta.to_snapshots();
+    assert_eq!(all.len(), SNAPSHOTS);
+    assert_eq!(
+        all[SNAPSHOTS - 1],
+        delta.materialize(SNAPSHOTS - 1).unwrap()
+    );
+
+    let full_size = serde_json::to_string(&full).unwrap().len();
+    let delta_size = serde_json::to_string(&delta).unwrap().len();
+    // Decay reweights most edges every tick, so most of what is left are
+    // edge weights
+    assert!(
+        delta_size * 5 < full_size * 3,
+        "delta series is {delta_size} bytes, full snapshots {full_size}"
+    );
+
+    // Read back, the series materializes the same way
+    let json = serde_json::to_string(&delta).unwrap();
+    let loaded: SnapshotSeries = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.mode(), SnapshotMode::Delta);
+    assert_eq!(loaded.materialize(17), delta.materialize(17));
+}
+
+#[test]
+fn stats_only_series_keep_no_graph() {
+    let mut colony = digesting_colony();
+    let mut series = SnapshotSeries::new(SnapshotMode::StatsOnly);
+    for _ in 0..5 {
+        colony.run(3);
+        colony.record_snapshot(&mut series);
+    }
+
+    let last = series.materialize(4).unwrap();
+    assert!(last.nodes.is_empty() && last.edges.is_empty());
+    assert_eq!(last.stats, colony.stats());
+    assert_eq!(last.agents.len(), colony.stats().agents_alive);
+    assert!(last.stats.graph_nodes > 0);
+}
+
+#[test]
+fn benchmark_records_in_the_configured_mode() {
+    for mode in [
+        SnapshotMode::Full,
+        SnapshotMode::StatsOnly,
+        SnapshotMode::Delta,
+    ] {
+        let mut colony = digesting_colony();
+        let config = BenchmarkConfig::new("modes", 20)
+            .with_snapshot_interval(5)
+            .with_snapshot_mode(mode);
+        let run = run_benchmark(&mut colony, &config);
+
+        assert_eq!(run.snapshots.mode(), mode);
+        assert_eq!(run.snapshots.len(), 5);
+        let metrics = &run.metrics_timeline.last().unwrap().1;
+        assert!(metrics.vocabulary_spread.max_vocabulary > 0, "{mode:?}");
+    }
+}
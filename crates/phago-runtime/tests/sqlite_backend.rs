@@ -0,0 +1,302 @@
+//! SQLite backend parity tests.
+//!
+//! Builds the same large graph on the in-memory and SQLite backends,
+//! runs the maintenance passes the colony performs each tick, reopens
+//! the SQLite file, and checks that both backends answer queries alike.
+//!
+//! Run with `cargo test -p phago-runtime --features sqlite`.
+
+#![cfg(feature = "sqlite")]
+
+use phago_agents::digester::Digester;
+use phago_core::topology::TopologyGraph;
+use phago_core::types::*;
+use phago_runtime::backend::BackendConfig;
+use phago_runtime::colony::{Colony, ColonyConfig};
+use phago_runtime::sqlite_topology::SqliteTopologyGraph;
+use phago_runtime::topology_impl::PetTopologyGraph;
+use std::collections::HashSet;
+
+const NODES: usize = 5_000;
+const EDGES: usize = 50_000;
+
+/// Small deterministic generator so both backends see identical input.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn unit(&mut self) -> f64 {
+        self.next() as f64 / (1u64 << 31) as f64
+    }
+}
+
+fn build_graph(targets: &mut [&mut dyn TopologyGraph]) -> Vec<NodeId> {
+    let mut rng = Lcg(7);
+    let ids: Vec<NodeId> = (0..NODES)
+        .map(|i| {
+            let node = NodeData {
+                id: NodeId::new(),
+                label: format!("Concept_{i}"),
+                node_type: NodeType::Concept,
+                position: Position::new(i as f64, 0.0),
+                access_count: 1 + (i % 7) as u64,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            };
+            for graph in targets.iter_mut() {
+                graph.add_node(node.clone());
+            }
+            node.id
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    while seen.len() < EDGES {
+        let a = rng.next() as usize % NODES;
+        let b = rng.next() as usize % NODES;
+        if a == b || !seen.insert((a.min(b), a.max(b))) {
+            continue;
+        }
+        let created = rng.next() % 100;
+        let edge = EdgeData {
+            weight: 0.05 + rng.unit() * 0.95,
+            co_activations: rng.next() % 5,
+            created_tick: created,
+            last_activated_tick: created + rng.next() % 20,
+        };
+        for graph in targets.iter_mut() {
+            graph.set_edge(ids[a], ids[b], edge.clone()).unwrap();
+        }
+    }
+    ids
+}
+
+fn neighbor_weights(graph: &dyn TopologyGraph, id: &NodeId) -> Vec<(NodeId, f64)> {
+    let mut out: Vec<(NodeId, f64)> = graph
+        .neighbors(id)
+        .into_iter()
+        .map(|(other, edge)| (other, edge.weight))
+        .collect();
+    out.sort_by_key(|(other, _)| *other);
+    out
+}
+
+#[test]
+fn large_graph_survives_restart_and_matches_in_memory() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("parity.db");
+
+    let mut memory = PetTopologyGraph::new();
+    let mut sqlite = SqliteTopologyGraph::open(&path)
+        .unwrap()
+        .with_cache_size(500);
+    let ids = build_graph(&mut [&mut memory, &mut sqlite]);
+    sqlite.flush();
+
+    assert_eq!(memory.edge_count(), EDGES);
+    assert_eq!(sqlite.edge_count(), EDGES);
+
+    // The per-tick maintenance the colony runs
+    let decayed_mem = memory.decay_edges_activity(0.005, 0.05, 120, 1.5, 50);
+    let decayed_sql = sqlite.decay_edges_activity(0.005, 0.05, 120, 1.5, 50);
+    assert_eq!(decayed_mem.len(), decayed_sql.len());
+
+    let pruned_mem = memory.prune_to_max_degree(15);
+    let pruned_sql = sqlite.prune_to_max_degree(15);
+    assert!(!pruned_mem.is_empty());
+    assert_eq!(pruned_mem.len(), pruned_sql.len());
+
+    // Mutations after maintenance must reach disk via the write-behind buffer
+    memory.get_node_mut(&ids[0]).unwrap().access_count = 99;
+    sqlite.get_node_mut(&ids[0]).unwrap().access_count = 99;
+    drop(sqlite);
+
+    let reopened = SqliteTopologyGraph::open(&path)
+        .unwrap()
+        .with_cache_size(500);
+
+    assert_eq!(reopened.node_count(), memory.node_count());
+    assert_eq!(reopened.edge_count(), memory.edge_count());
+    assert_eq!(reopened.get_node(&ids[0]).unwrap().access_count, 99);
+
+    for i in (0..NODES).step_by(250) {
+        let label = format!("concept_{i}");
+        assert_eq!(
+            reopened.find_nodes_by_exact_label(&label),
+            memory.find_nodes_by_exact_label(&label).to_vec()
+        );
+
+        let expected = neighbor_weights(&memory, &ids[i]);
+        let actual = neighbor_weights(&reopened, &ids[i]);
+        assert_eq!(expected.len(), actual.len(), "degree of node {i}");
+        for ((a_id, a_w), (b_id, b_w)) in expected.iter().zip(&actual) {
+            assert_eq!(a_id, b_id);
+            assert!((a_w - b_w).abs() < 1e-9, "weight mismatch on node {i}");
+        }
+    }
+
+    for (a, b) in [(1, 2), (10, 4_000), (123, 4_321)] {
+        let expected = memory.shortest_path(&ids[a], &ids[b]).map(|(_, cost)| cost);
+        let actual = reopened
+            .shortest_path(&ids[a], &ids[b])
+            .map(|(_, cost)| cost);
+        match (expected, actual) {
+            (Some(e), Some(a)) => assert!((e - a).abs() < 1e-6),
+            (e, a) => assert_eq!(e.is_some(), a.is_some()),
+        }
+    }
+
+    assert_eq!(
+        reopened.connected_components(),
+        memory.connected_components()
+    );
+}
+
+#[test]
+fn colony_runs_on_sqlite_backend() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("colony.db");
+
+    let config = ColonyConfig {
+        backend: BackendConfig::sqlite(&path),
+        ..Default::default()
+    };
+    let (nodes, edges) = {
+        let mut colony = Colony::try_from_config(config).unwrap();
+        colony.ingest_document(
+            "Membranes",
+            "cell membrane protein transport channel receptor",
+            Position::new(0.0, 0.0),
+        );
+        colony.ingest_document(
+            "Synthesis",
+            "cell membrane protein synthesis ribosome enzyme",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(
+            Digester::new(Position::new(0.0, 0.0)).with_max_idle(50),
+        ));
+        colony.run(20);
+
+        let stats = colony.stats();
+        assert!(stats.graph_nodes > 0);
+        assert!(stats.graph_edges > 0);
+        assert!(!colony
+            .substrate()
+            .graph()
+            .find_nodes_by_exact_label("membrane")
+            .is_empty());
+        (stats.graph_nodes, stats.graph_edges)
+    };
+
+    let reopened = SqliteTopologyGraph::open(&path).unwrap();
+    assert_eq!(reopened.node_count(), nodes);
+    assert_eq!(reopened.edge_count(), edges);
+}
+
+#[test]
+fn aliases_resolve_on_both_backends_and_survive_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("aliases.db");
+
+    let mut memory = PetTopologyGraph::new();
+    let mut sqlite = SqliteTopologyGraph::open(&path).unwrap();
+    let node = |label: &str| NodeData {
+        id: NodeId::new(),
+        label: label.into(),
+        node_type: NodeType::Concept,
+        position: Position::new(0.0, 0.0),
+        access_count: 1,
+        created_tick: 0,
+        last_accessed_tick: 0,
+        tags: Default::default(),
+    };
+    let membrane = node("membrane");
+    let lipid = node("lipid");
+    for graph in [&mut memory as &mut dyn TopologyGraph, &mut sqlite] {
+        graph.add_node(membrane.clone());
+        graph.add_node(lipid.clone());
+        assert!(graph.add_alias(&membrane.id, "Membranes"));
+        assert!(graph.add_alias(&membrane.id, "membranes"));
+        assert!(!graph.add_alias(&NodeId::new(), "orphan"));
+        assert_eq!(graph.aliases(&membrane.id), vec!["membranes"]);
+        assert_eq!(
+            graph.find_nodes_by_exact_label("MEMBRANES"),
+            vec![membrane.id]
+        );
+        // Aliases are not labels: prefix lookup stays on labels
+        assert!(graph.find_nodes_by_label_prefix("membranes").is_empty());
+    }
+    drop(sqlite);
+
+    let mut reopened = SqliteTopologyGraph::open(&path).unwrap();
+    assert_eq!(
+        reopened.find_nodes_by_exact_label("membranes"),
+        vec![membrane.id]
+    );
+    assert_eq!(
+        TopologyGraph::find_nodes_by_exact_label(&reopened.to_in_memory(), "membranes"),
+        vec![membrane.id]
+    );
+
+    reopened.remove_node(&membrane.id);
+    memory.remove_node(&membrane.id);
+    assert!(reopened.find_nodes_by_exact_label("membranes").is_empty());
+    assert!(TopologyGraph::find_nodes_by_exact_label(&memory, "membranes").is_empty());
+}
+
+#[test]
+fn annotations_match_on_both_backends_and_survive_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("annotations.db");
+
+    let mut memory = PetTopologyGraph::new();
+    let mut sqlite = SqliteTopologyGraph::open(&path).unwrap();
+    let membrane = NodeData {
+        id: NodeId::new(),
+        label: "membrane".into(),
+        node_type: NodeType::Concept,
+        position: Position::new(0.0, 0.0),
+        access_count: 1,
+        created_tick: 0,
+        last_accessed_tick: 0,
+        tags: Default::default(),
+    };
+    let note = |text: &str, tick: Tick| Annotation {
+        text: text.into(),
+        source: "mcp".into(),
+        tick,
+    };
+    for graph in [&mut memory as &mut dyn TopologyGraph, &mut sqlite] {
+        graph.add_node(membrane.clone());
+        assert!(graph.annotate(&membrane.id, note("bounds the cell", 3)));
+        assert!(graph.annotate(&membrane.id, note("made of lipids", 4)));
+        assert!(!graph.annotate(&membrane.id, note("made of lipids", 4)));
+        assert!(!graph.annotate(&NodeId::new(), note("orphan", 4)));
+        assert_eq!(
+            graph.annotations(&membrane.id),
+            vec![note("bounds the cell", 3), note("made of lipids", 4)]
+        );
+    }
+    drop(sqlite);
+
+    let mut reopened = SqliteTopologyGraph::open(&path).unwrap();
+    assert_eq!(reopened.annotations(&membrane.id).len(), 2);
+    assert_eq!(
+        reopened.to_in_memory().annotations(&membrane.id),
+        memory.annotations(&membrane.id)
+    );
+
+    reopened.remove_node(&membrane.id);
+    memory.remove_node(&membrane.id);
+    assert!(reopened.annotations(&membrane.id).is_empty());
+    assert!(memory.annotations(&membrane.id).is_empty());
+}
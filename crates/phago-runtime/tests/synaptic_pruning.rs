@@ -4,11 +4,14 @@
 //! 1. Preserves frequently co-activated edges
 //! 2. Removes stale edges that were created but never reinforced
 //! 3. Caps per-node degree via competitive pruning
+//! 4. Adapts decay to hold a streamed graph near its edge budget
 
 use phago_agents::digester::Digester;
 use phago_core::topology::TopologyGraph;
 use phago_core::types::*;
-use phago_runtime::colony::Colony;
+use phago_runtime::colony::{Colony, ColonyConfig};
+use phago_runtime::corpus::Corpus;
+use phago_runtime::decay::AdaptiveDecayConfig;
 
 #[test]
 fn stale_edges_decay_while_active_edges_survive() {
@@ -134,7 +137,8 @@ fn competitive_pruning_enforces_degree_cap() {
         position: Position::new(0.0, 0.0),
         access_count: 1,
         created_tick: 0,
-        embedding: None,
+        last_accessed_tick: 0,
+        tags: Default::default(),
     });
 
     for i in 0..40 {
@@ -146,18 +150,21 @@ fn competitive_pruning_enforces_degree_cap() {
             position: Position::new(0.0, 0.0),
             access_count: 1,
             created_tick: 0,
-            embedding: None,
+            last_accessed_tick: 0,
+            tags: Default::default(),
         });
-        graph.set_edge(
-            hub_id,
-            spoke_id,
-            EdgeData {
-                weight: 0.1 + (i as f64) * 0.02, // weights from 0.1 to 0.88
-                co_activations: 1,
-                created_tick: 0,
-                last_activated_tick: 0,
-            },
-        );
+        graph
+            .set_edge(
+                hub_id,
+                spoke_id,
+                EdgeData {
+                    weight: 0.1 + (i as f64) * 0.02, // weights from 0.1 to 0.88
+                    co_activations: 1,
+                    created_tick: 0,
+                    last_activated_tick: 0,
+                },
+            )
+            .unwrap();
     }
 
     assert_eq!(graph.edge_count(), 40);
@@ -186,3 +193,73 @@ fn competitive_pruning_enforces_degree_cap() {
         );
     }
 }
+
+/// Ingest one embedded-corpus document every 10 ticks, each with its own
+/// digester, and record the edge count after every step.
+fn streamed_edge_counts(adaptive_decay: Option<AdaptiveDecayConfig>) -> (Vec<usize>, Colony) {
+    let mut colony = Colony::from_config(ColonyConfig {
+        adaptive_decay,
+        // Keep the pool of young, unprunable edges small
+        maturation_ticks: 10,
+        ..Default::default()
+    });
+    let corpus = Corpus::from_embedded();
+    let mut counts = Vec::new();
+    for step in 0..22 {
+        if let Some(doc) = corpus.documents.get(step) {
+            colony.ingest_document(&doc.title, &doc.content, doc.position);
+            colony.spawn(Box::new(Digester::new(doc.position).with_max_idle(30)));
+        }
+        colony.run(10);
+        counts.push(colony.stats().graph_edges);
+    }
+    (counts, colony)
+}
+
+#[test]
+fn adaptive_decay_holds_edges_near_budget() {
+    let budget = 1500;
+    let (fixed, _) = streamed_edge_counts(None);
+    let (adaptive, colony) = streamed_edge_counts(Some(AdaptiveDecayConfig {
+        max_edges: budget,
+        ..Default::default()
+    }));
+
+    // The fixed rates let the graph keep growing with the stream
+    assert!(*fixed.last().unwrap() > 2 * budget, "fixed: {fixed:?}");
+    // Once edges start maturing, the adaptive colony levels off
+    let late = &adaptive[10..];
+    assert!(
+        late.iter().all(|&edges| edges < budget * 8 / 5),
+        "edges should stay near the budget: {late:?}"
+    );
+    assert!(
+        *adaptive.last().unwrap() * 4 < *fixed.last().unwrap() * 3,
+        "fixed: {fixed:?}\nadaptive: {adaptive:?}"
+    );
+
+    let report = colony.decay_report();
+    assert!(report.adaptive);
+    assert!(
+        (budget / 2..budget * 3 / 2).contains(&report.load.mature_edges),
+        "mature edges {}",
+        report.load.mature_edges
+    );
+}
+
+#[test]
+fn generous_edge_budget_matches_fixed_rates() {
+    let (fixed, _) = streamed_edge_counts(None);
+    let (adaptive, colony) = streamed_edge_counts(Some(AdaptiveDecayConfig {
+        max_edges: 1_000_000,
+        ..Default::default()
+    }));
+    for (f, a) in fixed.iter().zip(&adaptive) {
+        assert!(
+            f.abs_diff(*a) * 10 <= *f,
+            "fixed {fixed:?} vs adaptive {adaptive:?}"
+        );
+    }
+    let rates = colony.decay_report().rates;
+    assert_eq!(rates.edge, colony.config().edge_decay_rate);
+}
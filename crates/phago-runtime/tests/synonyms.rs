@@ -0,0 +1,129 @@
+//! A synonym dictionary makes every name of a concept reinforce one node:
+//! documents using different forms meet under the canonical term, which
+//! keeps the forms as aliases, and a dictionary set on a running colony
+//! merges the nodes already grown for each form.
+
+use phago_agents::digester::Digester;
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig, ColonyEvent};
+use phago_runtime::synonyms::{SynonymConfig, SynonymMap};
+
+/// One document per form of the same concept.
+const DOCUMENTS: &[(&str, &str)] = &[
+    (
+        "canonical",
+        "Hypertension damages arteries and strains the heart muscle over years.",
+    ),
+    (
+        "medical",
+        "Hypertonia damages arteries and strains the heart muscle over years.",
+    ),
+    (
+        "plain",
+        "Highbloodpressure damages arteries and strains the heart muscle over years.",
+    ),
+];
+
+fn dictionary() -> SynonymMap {
+    SynonymMap::from_tsv("hypertension\thypertonia\thighbloodpressure\n").unwrap()
+}
+
+/// Digest every document, each with its own digester.
+fn digest(colony: &mut Colony) {
+    for (i, (title, content)) in DOCUMENTS.iter().enumerate() {
+        let position = Position::new(i as f64 * 20.0, 0.0);
+        colony.ingest_document(title, content, position);
+        colony.spawn(Box::new(
+            Digester::with_seed(position, i as u64).with_max_idle(30),
+        ));
+    }
+    colony.run(20);
+}
+
+/// Concept nodes labelled `label`, ignoring case, not counting aliases.
+fn labelled(colony: &Colony, label: &str) -> Vec<NodeData> {
+    let graph = colony.substrate().graph();
+    graph
+        .all_nodes()
+        .iter()
+        .filter_map(|id| graph.get_node(id))
+        .filter(|n| n.label.eq_ignore_ascii_case(label))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn every_form_reinforces_the_canonical_node() {
+    let mut colony = Colony::from_config(ColonyConfig {
+        synonyms: SynonymConfig {
+            map: dictionary(),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    digest(&mut colony);
+
+    let canonical = labelled(&colony, "hypertension");
+    assert_eq!(canonical.len(), 1);
+    assert!(labelled(&colony, "hypertonia").is_empty());
+    assert!(labelled(&colony, "highbloodpressure").is_empty());
+
+    // As much as the one concept every document shares
+    let arteries = labelled(&colony, "arteries");
+    assert_eq!(arteries.len(), 1);
+    assert_eq!(canonical[0].access_count, arteries[0].access_count);
+
+    let graph = colony.substrate().graph();
+    let mut aliases = graph.aliases(&canonical[0].id);
+    aliases.sort();
+    assert_eq!(aliases, ["highbloodpressure", "hypertonia"]);
+    assert_eq!(
+        graph.find_nodes_by_exact_label("Hypertonia"),
+        vec![canonical[0].id]
+    );
+}
+
+#[test]
+fn setting_synonyms_merges_the_forms_already_in_the_graph() {
+    let mut colony = Colony::new();
+    digest(&mut colony);
+    let before: u64 = ["hypertension", "hypertonia", "highbloodpressure"]
+        .iter()
+        .flat_map(|label| labelled(&colony, label))
+        .map(|n| n.access_count)
+        .sum();
+    let revision = colony.graph_revision();
+
+    let events = colony.set_synonyms(SynonymConfig {
+        map: dictionary(),
+        ..Default::default()
+    });
+    assert_eq!(events.len(), 1);
+    assert!(matches!(
+        &events[0],
+        ColonyEvent::NodesMerged { label, merged, .. }
+            if label == "hypertension" && merged.len() == 2
+    ));
+    assert!(colony.graph_revision() > revision);
+
+    let canonical = labelled(&colony, "hypertension");
+    assert_eq!(canonical.len(), 1);
+    assert_eq!(canonical[0].access_count, before);
+    assert!(labelled(&colony, "hypertonia").is_empty());
+
+    // Without the canonical form in the graph, its node is created
+    let mut colony = Colony::new();
+    colony.ingest_document("medical", DOCUMENTS[1].1, Position::new(0.0, 0.0));
+    colony.spawn(Box::new(
+        Digester::with_seed(Position::new(0.0, 0.0), 1).with_max_idle(30),
+    ));
+    colony.run(20);
+    colony.set_synonyms(SynonymConfig {
+        map: dictionary(),
+        ..Default::default()
+    });
+    let canonical = labelled(&colony, "hypertension");
+    assert_eq!(canonical.len(), 1);
+    assert!(canonical[0].access_count > 0);
+    assert!(labelled(&colony, "hypertonia").is_empty());
+}
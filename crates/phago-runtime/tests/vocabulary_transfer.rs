@@ -0,0 +1,221 @@
+//! Provenance-weighted vocabulary transfer: junk from an unfit exporter
+//! does not spread to the agents around it.
+
+use phago_agents::digester::Digester;
+use phago_agents::fitness::{FitnessFunction, FitnessInput, FitnessTracker};
+use phago_core::types::*;
+use phago_runtime::colony::{Colony, ColonyConfig, ColonyEvent};
+use phago_runtime::transfer::{AcceptanceCurve, TransferConfig};
+use std::collections::HashSet;
+
+const GOOD: [&str; 4] = ["membrane", "protein", "transport", "receptor"];
+const JUNK: [&str; 4] = ["casino", "jackpot", "bonus", "lottery"];
+
+/// Fixed fitness per agent: the good exporter is fit, everyone else is not.
+#[derive(Debug)]
+struct FixedFitness {
+    fit: AgentId,
+}
+
+impl FitnessFunction for FixedFitness {
+    fn fitness(&self, input: &FitnessInput<'_>) -> f64 {
+        if input.counters.agent_id == self.fit {
+            0.9
+        } else {
+            0.05
+        }
+    }
+}
+
+struct Run {
+    colony: Colony,
+    integrator: AgentId,
+    junk_exporter: AgentId,
+    integrations: Vec<ColonyEvent>,
+}
+
+/// Two exporters digest two documents each and deposit their vocabulary;
+/// an integrator spawned between the deposits then picks them up. The
+/// document clusters are out of each other's exporter's sensing range.
+fn run(transfer: TransferConfig, ticks: u64) -> Run {
+    let mut colony = Colony::from_config(ColonyConfig {
+        dissolution_interval: 60,
+        transfer,
+        ..ColonyConfig::default()
+    });
+    let good_exporter = AgentId::from_seed(1);
+    *colony.fitness_tracker_mut() =
+        FitnessTracker::new().with_function(FixedFitness { fit: good_exporter });
+
+    colony.ingest_document(
+        "Membranes",
+        "The membrane protein controls transport. A receptor protein spans the membrane.",
+        Position::new(0.0, 0.0),
+    );
+    colony.ingest_document(
+        "Transport",
+        "Membrane transport needs a carrier protein and a receptor for every signal.",
+        Position::new(0.5, 0.5),
+    );
+    colony.ingest_document(
+        "Win big",
+        "Casino jackpot bonus lottery casino jackpot bonus lottery winner.",
+        Position::new(16.0, 0.0),
+    );
+    colony.ingest_document(
+        "Win bigger",
+        "Lottery bonus casino jackpot lottery bonus casino jackpot prize.",
+        Position::new(16.5, 0.5),
+    );
+
+    colony.spawn(Box::new(
+        Digester::with_seed(Position::new(0.0, 0.0), 1).with_max_idle(500),
+    ));
+    let junk_exporter = colony.spawn(Box::new(
+        Digester::with_seed(Position::new(16.0, 0.0), 2).with_max_idle(500),
+    ));
+
+    let mut events: Vec<ColonyEvent> = colony.run(12).into_iter().flatten().collect();
+    let exported: HashSet<AgentId> = events
+        .iter()
+        .filter_map(|e| match e {
+            ColonyEvent::CapabilityExported { agent_id, .. } => Some(*agent_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(exported, HashSet::from([good_exporter, junk_exporter]));
+
+    let integrator = colony.spawn(Box::new(
+        Digester::with_seed(Position::new(8.0, 0.0), 3).with_max_idle(500),
+    ));
+    events.extend(colony.run(ticks - 12).into_iter().flatten());
+    let integrations = events
+        .into_iter()
+        .filter(|e| matches!(e, ColonyEvent::CapabilityIntegrated { agent_id, .. } if *agent_id == integrator))
+        .collect();
+
+    Run {
+        colony,
+        integrator,
+        junk_exporter,
+        integrations,
+    }
+}
+
+fn filtering() -> TransferConfig {
+    TransferConfig {
+        min_source_fitness: 0.2,
+        term_acceptance_curve: AcceptanceCurve::Threshold { min_score: 0.3 },
+    }
+}
+
+fn vocabulary(colony: &Colony, id: AgentId) -> HashSet<String> {
+    let agent = colony.agents().iter().find(|a| a.id() == id).unwrap();
+    agent.externalize_vocabulary().into_iter().collect()
+}
+
+fn access_count(colony: &Colony, terms: &[&str]) -> u64 {
+    let graph = colony.substrate().graph();
+    terms
+        .iter()
+        .flat_map(|term| graph.find_nodes_by_exact_label(term))
+        .filter_map(|id| graph.get_node(&id))
+        .map(|node| node.access_count)
+        .sum()
+}
+
+#[test]
+fn deposits_carry_exporter_provenance() {
+    let run = run(TransferConfig::default(), 14);
+    let deposits: Vec<VocabularyCapability> = run
+        .colony
+        .substrate()
+        .traces_near(
+            &Position::new(8.0, 0.0),
+            10.0,
+            &TraceType::CapabilityDeposit,
+        )
+        .iter()
+        .map(|trace| {
+            let payload = phago_runtime::trace_payload::decode(&trace.payload).unwrap();
+            serde_json::from_slice(&payload).unwrap()
+        })
+        .collect();
+    assert_eq!(deposits.len(), 2);
+    for deposit in deposits {
+        assert_eq!(deposit.term_reinforcement.len(), deposit.terms.len());
+        assert!(deposit.term_reinforcement.iter().all(|&count| count > 0));
+        let expected = if deposit.origin == run.junk_exporter {
+            0.05
+        } else {
+            0.9
+        };
+        assert_eq!(deposit.source_fitness, Some(expected));
+    }
+}
+
+#[test]
+fn unfit_exporters_do_not_spread_junk() {
+    let open = run(TransferConfig::default(), 14);
+    let filtered = run(filtering(), 14);
+
+    let open_vocabulary = vocabulary(&open.colony, open.integrator);
+    let filtered_vocabulary = vocabulary(&filtered.colony, filtered.integrator);
+    for term in GOOD {
+        assert!(open_vocabulary.contains(term), "{term}");
+        assert!(filtered_vocabulary.contains(term), "{term}");
+    }
+    for term in JUNK {
+        assert!(open_vocabulary.contains(term), "{term}");
+        assert!(!filtered_vocabulary.contains(term), "{term}");
+    }
+
+    // Both deposits were offered; only the fit exporter's terms were taken
+    assert_eq!(filtered.integrations.len(), 2);
+    for event in &filtered.integrations {
+        let ColonyEvent::CapabilityIntegrated {
+            from_agent,
+            terms_accepted,
+            terms_offered,
+            ..
+        } = event
+        else {
+            unreachable!()
+        };
+        assert!(*terms_offered > 0);
+        if *from_agent == filtered.junk_exporter {
+            assert_eq!(*terms_accepted, 0);
+        } else {
+            assert_eq!(terms_accepted, terms_offered);
+        }
+    }
+    for event in &open.integrations {
+        let ColonyEvent::CapabilityIntegrated {
+            terms_accepted,
+            terms_offered,
+            ..
+        } = event
+        else {
+            unreachable!()
+        };
+        assert_eq!(terms_accepted, terms_offered);
+    }
+}
+
+#[test]
+fn dissolution_reinforces_only_trusted_vocabulary() {
+    // Past the first dissolution after the integrator arrived
+    let open = run(TransferConfig::default(), 61);
+    let filtered = run(filtering(), 61);
+
+    assert_eq!(
+        access_count(&filtered.colony, &GOOD),
+        access_count(&open.colony, &GOOD)
+    );
+    assert!(
+        access_count(&filtered.colony, &JUNK) < access_count(&open.colony, &JUNK),
+        "filtered {} vs open {}",
+        access_count(&filtered.colony, &JUNK),
+        access_count(&open.colony, &JUNK)
+    );
+}
@@ -38,6 +38,14 @@
 //! }
 //! ```
 //!
+//! ## Hybrid Search
+//!
+//! Backends that can blend keyword and vector relevance in one query
+//! implement [`HybridCapable`], and return themselves from
+//! [`VectorStore::as_hybrid`] so callers holding a `dyn VectorStore` can
+//! prefer it. Weaviate maps it onto its native hybrid operator; the
+//! in-memory store combines substring matching with vector similarity.
+//!
 //! ## Feature Flags
 //!
 //! ```toml
@@ -60,8 +68,10 @@ pub mod pinecone;
 pub mod weaviate;
 
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use thiserror::Error;
 
 /// Errors that can occur when working with vector stores.
@@ -87,11 +97,21 @@ pub enum VectorError {
 
     #[error("Configuration error: {0}")]
     Config(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// Result type for vector operations.
 pub type VectorResult<T> = Result<T, VectorError>;
 
+/// Metadata filter: a record matches if each key maps to the given value.
+pub type Filter = HashMap<String, serde_json::Value>;
+
+/// Stream of every record in a store, as returned by
+/// [`VectorStore::export_all`].
+pub type RecordStream<'a> = BoxStream<'a, VectorResult<VectorRecord>>;
+
 /// A vector record to store in the database.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorRecord {
@@ -161,7 +181,7 @@ impl Default for VectorStoreConfig {
             dimension: 384,
             collection: "phago".to_string(),
             metric: DistanceMetric::Cosine,
-            backend: BackendConfig::InMemory,
+            backend: BackendConfig::InMemory { persist_path: None },
         }
     }
 }
@@ -181,8 +201,13 @@ pub enum DistanceMetric {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum BackendConfig {
-    /// In-memory vector store (for testing).
-    InMemory,
+    /// In-memory vector store.
+    InMemory {
+        /// Snapshot file, loaded on startup and kept up to date while the
+        /// store is in use. Without one, records are lost on exit.
+        #[serde(default)]
+        persist_path: Option<PathBuf>,
+    },
 
     /// Qdrant vector database.
     #[cfg(feature = "qdrant")]
@@ -191,6 +216,18 @@ pub enum BackendConfig {
         url: String,
         /// API key (optional).
         api_key: Option<String>,
+        /// Named vectors sharing the collection; empty for a single
+        /// unnamed vector of the configured dimension.
+        #[serde(default)]
+        vectors: Vec<qdrant::NamedVector>,
+        /// Named vector the [`VectorStore`] methods use (defaults to the
+        /// first one).
+        #[serde(default)]
+        default_vector: Option<String>,
+        /// Metadata keys to keep payload indexes for, so filtered searches
+        /// on them are indexed.
+        #[serde(default)]
+        indexed_fields: Vec<qdrant::IndexedField>,
     },
 
     /// Pinecone managed service.
@@ -268,25 +305,120 @@ pub trait VectorStore: Send + Sync {
 
     /// Clear all records from the store.
     async fn clear(&self) -> VectorResult<()>;
+
+    /// Stream every record in the store, vectors and metadata included.
+    ///
+    /// Backends fetch records page by page as the stream is polled, so a
+    /// large collection can be dumped without holding it in memory.
+    fn export_all(&self) -> RecordStream<'_>;
+
+    /// This store's hybrid search, if the backend has one.
+    fn as_hybrid(&self) -> Option<&dyn HybridCapable> {
+        None
+    }
+}
+
+/// Keyword and vector search blended in one query.
+///
+/// Implementors also return themselves from [`VectorStore::as_hybrid`].
+#[async_trait]
+pub trait HybridCapable: VectorStore {
+    /// Search for records relevant to `query_text` and, if given, similar
+    /// to `vector`.
+    ///
+    /// `alpha` weights the two in [0, 1]: 0.0 ranks by keywords alone, 1.0
+    /// by vector similarity alone. How the backend arrived at each score is
+    /// reported under the [`EXPLAIN_SCORE_KEY`] metadata key.
+    async fn hybrid_search(
+        &self,
+        query_text: &str,
+        vector: Option<&[f32]>,
+        alpha: f32,
+        k: usize,
+        filter: Option<&Filter>,
+    ) -> VectorResult<Vec<SearchResult>>;
+}
+
+/// Result metadata key under which hybrid searches explain their scores.
+pub const EXPLAIN_SCORE_KEY: &str = "_explain_score";
+
+/// Reject blending weights outside [0, 1].
+pub(crate) fn check_alpha(alpha: f32) -> VectorResult<()> {
+    if (0.0..=1.0).contains(&alpha) {
+        Ok(())
+    } else {
+        Err(VectorError::Config(format!(
+            "hybrid alpha must be in [0, 1], got {}",
+            alpha
+        )))
+    }
+}
+
+/// Stream records a page at a time.
+///
+/// `fetch` receives the cursor returned with the previous page (`None` for
+/// the first one) and returns the next page with the cursor that follows
+/// it, or no cursor once the last page has been fetched.
+#[cfg(any(feature = "qdrant", feature = "pinecone", feature = "weaviate", test))]
+pub(crate) fn paginate<'a, C, F, Fut>(mut fetch: F) -> RecordStream<'a>
+where
+    C: Send + 'a,
+    F: FnMut(Option<C>) -> Fut + Send + 'a,
+    Fut: std::future::Future<Output = VectorResult<(Vec<VectorRecord>, Option<C>)>> + Send + 'a,
+{
+    use futures::stream::{self, StreamExt, TryStreamExt};
+
+    stream::try_unfold(Some(None), move |state: Option<Option<C>>| {
+        let page = state.map(&mut fetch);
+        async move {
+            match page {
+                Some(page) => {
+                    let (records, next) = page.await?;
+                    Ok::<_, VectorError>(Some((records, next.map(Some))))
+                }
+                None => Ok(None),
+            }
+        }
+    })
+    .map_ok(|records| stream::iter(records.into_iter().map(Ok)))
+    .try_flatten()
+    .boxed()
 }
 
 /// Create a vector store from configuration.
 pub async fn create_store(config: VectorStoreConfig) -> VectorResult<Box<dyn VectorStore>> {
     match config.backend {
-        BackendConfig::InMemory => Ok(Box::new(memory::InMemoryStore::with_config(
-            config.dimension,
-            config.metric,
-        ))),
+        BackendConfig::InMemory { persist_path } => {
+            let store = memory::InMemoryStore::with_config(config.dimension, config.metric);
+            let Some(path) = persist_path else {
+                return Ok(Box::new(store));
+            };
+            if path.exists() {
+                store.load(&path)?;
+            }
+            Ok(Box::new(
+                store.auto_persist(path, memory::DEFAULT_PERSIST_EVERY),
+            ))
+        }
 
         #[cfg(feature = "qdrant")]
-        BackendConfig::Qdrant { url, api_key } => {
-            let store = qdrant::QdrantStore::connect(
-                &url,
-                api_key.as_deref(),
-                &config.collection,
-                config.dimension,
-                config.metric,
-            )
+        BackendConfig::Qdrant {
+            url,
+            api_key,
+            vectors,
+            default_vector,
+            indexed_fields,
+        } => {
+            let store = qdrant::QdrantStore::connect_with(qdrant::QdrantOptions {
+                url,
+                api_key,
+                collection: config.collection,
+                dimension: config.dimension,
+                metric: config.metric,
+                vectors,
+                default_vector,
+                indexed_fields,
+            })
             .await?;
             Ok(Box::new(store))
         }
@@ -382,6 +514,7 @@ pub mod util {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::TryStreamExt;
 
     #[test]
     fn test_cosine_similarity() {
@@ -418,4 +551,63 @@ mod tests {
         assert_eq!(record.dimension(), 3);
         assert_eq!(record.metadata.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_paginate_follows_cursors() {
+        // Pages of two records over ids 0..5, the cursor being the next offset
+        let stream = paginate(|cursor: Option<usize>| async move {
+            let start = cursor.unwrap_or(0);
+            let end = (start + 2).min(5);
+            let page = (start..end)
+                .map(|i| VectorRecord::new(i.to_string(), vec![i as f32]))
+                .collect();
+            Ok((page, (end < 5).then_some(end)))
+        });
+        let ids: Vec<String> = stream.map_ok(|r| r.id).try_collect().await.unwrap();
+        assert_eq!(ids, vec!["0", "1", "2", "3", "4"]);
+
+        let failing =
+            paginate(|_: Option<()>| async { Err(VectorError::Api("unavailable".into())) });
+        let result: VectorResult<Vec<VectorRecord>> = failing.try_collect().await;
+        assert!(matches!(result, Err(VectorError::Api(_))));
+    }
+
+    #[cfg(feature = "qdrant")]
+    #[test]
+    fn test_qdrant_config_defaults_to_one_unnamed_vector() {
+        let config: BackendConfig = serde_json::from_value(serde_json::json!({
+            "type": "Qdrant",
+            "url": "http://localhost:6334",
+            "api_key": null,
+        }))
+        .unwrap();
+        let BackendConfig::Qdrant {
+            vectors,
+            default_vector,
+            indexed_fields,
+            ..
+        } = config
+        else {
+            panic!("not a Qdrant config");
+        };
+        assert!(vectors.is_empty());
+        assert_eq!(default_vector, None);
+        assert!(indexed_fields.is_empty());
+
+        let config: BackendConfig = serde_json::from_value(serde_json::json!({
+            "type": "Qdrant",
+            "url": "http://localhost:6334",
+            "vectors": [
+                { "name": "concept", "dimension": 384 },
+                { "name": "chunk", "dimension": 768 },
+            ],
+            "indexed_fields": [{ "name": "doc_id" }, { "name": "page", "kind": "integer" }],
+        }))
+        .unwrap();
+        let BackendConfig::Qdrant { indexed_fields, .. } = config else {
+            panic!("not a Qdrant config");
+        };
+        assert_eq!(indexed_fields[0].kind, qdrant::FieldKind::Keyword);
+        assert_eq!(indexed_fields[1].kind, qdrant::FieldKind::Integer);
+    }
 }
@@ -2,11 +2,62 @@
 //!
 //! This module provides a simple in-memory vector store that uses brute-force
 //! search. It's useful for testing and small-scale applications.
-
-use crate::{DistanceMetric, SearchResult, VectorError, VectorRecord, VectorResult, VectorStore};
+//!
+//! The store can be saved to and loaded from a snapshot file, and can keep
+//! that file up to date in the background (see
+//! [`InMemoryStore::auto_persist`]). A snapshot starts with a magic number
+//! and format version, then holds the bincode-encoded dimension, metric and
+//! records. Metadata values are stored as JSON text, since bincode cannot
+//! decode self-describing values.
+
+use crate::{
+    check_alpha, DistanceMetric, Filter, HybridCapable, RecordStream, SearchResult, VectorError,
+    VectorRecord, VectorResult, VectorStore, EXPLAIN_SCORE_KEY,
+};
 use async_trait::async_trait;
+use bincode::Options;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// First bytes of every snapshot file.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"PHVS";
+/// Snapshot format version, bumped on incompatible changes.
+const SNAPSHOT_VERSION: u32 = 1;
+/// Writes between background snapshots for stores created with a
+/// `persist_path`.
+pub const DEFAULT_PERSIST_EVERY: usize = 100;
+
+type Records = Arc<RwLock<HashMap<String, VectorRecord>>>;
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    dimension: u64,
+    metric: DistanceMetric,
+    records: Vec<SnapshotRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotRecord {
+    id: String,
+    vector: Vec<f32>,
+    metadata: String,
+}
+
+/// Background snapshot settings and state.
+struct AutoPersist {
+    path: PathBuf,
+    every: usize,
+    /// Writes since the last snapshot was started.
+    writes: AtomicUsize,
+    running: AtomicBool,
+    /// Set when another snapshot is needed once the running one finishes.
+    pending: AtomicBool,
+    last_error: Mutex<Option<String>>,
+}
 
 /// In-memory vector store using brute-force search.
 ///
@@ -39,9 +90,10 @@ use std::sync::RwLock;
 /// }
 /// ```
 pub struct InMemoryStore {
-    records: RwLock<HashMap<String, VectorRecord>>,
+    records: Records,
     dimension: usize,
     metric: DistanceMetric,
+    persist: Option<Arc<AutoPersist>>,
 }
 
 impl InMemoryStore {
@@ -49,20 +101,135 @@ impl InMemoryStore {
     ///
     /// Uses cosine similarity by default.
     pub fn new(dimension: usize) -> Self {
-        Self {
-            records: RwLock::new(HashMap::new()),
-            dimension,
-            metric: DistanceMetric::Cosine,
-        }
+        Self::with_config(dimension, DistanceMetric::Cosine)
     }
 
     /// Create a new in-memory store with a specific distance metric.
     pub fn with_config(dimension: usize, metric: DistanceMetric) -> Self {
         Self {
-            records: RwLock::new(HashMap::new()),
+            records: Arc::new(RwLock::new(HashMap::new())),
             dimension,
             metric,
+            persist: None,
+        }
+    }
+
+    /// Snapshot the store to `path` in the background after every
+    /// `every_n_writes` writes, and once more when the store is dropped.
+    ///
+    /// Upserts, deletions and clears each count as one write. A snapshot
+    /// holds a read lock only while the records are encoded, so searches
+    /// carry on meanwhile; the file is written afterwards and replaced
+    /// atomically.
+    pub fn auto_persist(mut self, path: impl Into<PathBuf>, every_n_writes: usize) -> Self {
+        self.persist = Some(Arc::new(AutoPersist {
+            path: path.into(),
+            every: every_n_writes.max(1),
+            writes: AtomicUsize::new(0),
+            running: AtomicBool::new(false),
+            pending: AtomicBool::new(false),
+            last_error: Mutex::new(None),
+        }));
+        self
+    }
+
+    /// Why the most recent background snapshot failed, if it did.
+    pub fn persist_error(&self) -> Option<String> {
+        self.persist
+            .as_ref()
+            .and_then(|p| p.last_error.lock().ok().and_then(|e| e.clone()))
+    }
+
+    /// Write every record to `path`, replacing the file atomically.
+    pub fn save(&self, path: impl AsRef<Path>) -> VectorResult<()> {
+        let bytes = encode_snapshot(&self.records, self.dimension, self.metric)?;
+        write_atomically(path.as_ref(), &bytes)
+    }
+
+    /// Replace the store's records with those saved at `path`. Returns the
+    /// number of records loaded.
+    ///
+    /// Fails with [`VectorError::DimensionMismatch`] or
+    /// [`VectorError::Config`] if the snapshot was taken from a store with
+    /// another dimension or metric, and with [`VectorError::Serialization`]
+    /// if the file is not a readable snapshot.
+    pub fn load(&self, path: impl AsRef<Path>) -> VectorResult<usize> {
+        let bytes = std::fs::read(path.as_ref())?;
+        let snapshot = decode_snapshot(&bytes)?;
+        if snapshot.dimension != self.dimension as u64 {
+            return Err(VectorError::DimensionMismatch {
+                expected: self.dimension,
+                actual: snapshot.dimension as usize,
+            });
+        }
+        if snapshot.metric != self.metric {
+            return Err(VectorError::Config(format!(
+                "Snapshot uses the {:?} metric, store uses {:?}",
+                snapshot.metric, self.metric
+            )));
         }
+
+        let mut records = HashMap::with_capacity(snapshot.records.len());
+        for record in snapshot.records {
+            if record.vector.len() != self.dimension {
+                return Err(VectorError::Serialization(format!(
+                    "Record '{}' has {} dimensions, snapshot declares {}",
+                    record.id,
+                    record.vector.len(),
+                    self.dimension
+                )));
+            }
+            let metadata = serde_json::from_str(&record.metadata)
+                .map_err(|e| VectorError::Serialization(e.to_string()))?;
+            records.insert(
+                record.id.clone(),
+                VectorRecord {
+                    id: record.id,
+                    vector: record.vector,
+                    metadata,
+                },
+            );
+        }
+
+        let count = records.len();
+        *self.records.write().map_err(|e| {
+            VectorError::Connection(format!("Failed to acquire write lock: {}", e))
+        })? = records;
+        Ok(count)
+    }
+
+    /// Count a write and start a background snapshot when one is due.
+    fn record_write(&self) {
+        let Some(persist) = &self.persist else {
+            return;
+        };
+        if persist.writes.fetch_add(1, Ordering::SeqCst) + 1 < persist.every {
+            return;
+        }
+        persist.writes.store(0, Ordering::SeqCst);
+        persist.pending.store(true, Ordering::SeqCst);
+        if persist.running.swap(true, Ordering::SeqCst) {
+            // The running snapshot will go round again
+            return;
+        }
+
+        let persist = Arc::clone(persist);
+        let records = Arc::clone(&self.records);
+        let (dimension, metric) = (self.dimension, self.metric);
+        std::thread::spawn(move || loop {
+            persist.pending.store(false, Ordering::SeqCst);
+            let result = encode_snapshot(&records, dimension, metric)
+                .and_then(|bytes| write_atomically(&persist.path, &bytes));
+            if let Ok(mut last_error) = persist.last_error.lock() {
+                *last_error = result.err().map(|e| e.to_string());
+            }
+            persist.running.store(false, Ordering::SeqCst);
+            if !persist.pending.load(Ordering::SeqCst)
+                || persist.running.swap(true, Ordering::SeqCst)
+            {
+                break;
+            }
+        });
     }
 
     /// Compute similarity/distance between two vectors.
@@ -79,6 +246,83 @@ impl InMemoryStore {
     }
 }
 
+impl Drop for InMemoryStore {
+    /// Take a last snapshot if anything changed since the previous one.
+    fn drop(&mut self) {
+        let Some(persist) = &self.persist else {
+            return;
+        };
+        while persist.running.load(Ordering::SeqCst) {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        if persist.writes.load(Ordering::SeqCst) > 0 || persist.pending.load(Ordering::SeqCst) {
+            let _ = self.save(&persist.path);
+        }
+    }
+}
+
+fn encode_snapshot(
+    records: &RwLock<HashMap<String, VectorRecord>>,
+    dimension: usize,
+    metric: DistanceMetric,
+) -> VectorResult<Vec<u8>> {
+    let mut bytes = SNAPSHOT_MAGIC.to_vec();
+    bytes.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+
+    // Only the encoding happens under the read lock
+    let store = records
+        .read()
+        .map_err(|e| VectorError::Connection(format!("Failed to acquire read lock: {}", e)))?;
+    let mut snapshot = Snapshot {
+        dimension: dimension as u64,
+        metric,
+        records: Vec::with_capacity(store.len()),
+    };
+    for record in store.values() {
+        snapshot.records.push(SnapshotRecord {
+            id: record.id.clone(),
+            vector: record.vector.clone(),
+            metadata: serde_json::to_string(&record.metadata)
+                .map_err(|e| VectorError::Serialization(e.to_string()))?,
+        });
+    }
+    drop(store);
+
+    bincode::serialize_into(&mut bytes, &snapshot)
+        .map_err(|e| VectorError::Serialization(e.to_string()))?;
+    Ok(bytes)
+}
+
+fn decode_snapshot(bytes: &[u8]) -> VectorResult<Snapshot> {
+    let header = SNAPSHOT_MAGIC.len() + 4;
+    if bytes.len() < header || &bytes[..SNAPSHOT_MAGIC.len()] != SNAPSHOT_MAGIC {
+        return Err(VectorError::Serialization(
+            "Not a vector store snapshot".to_string(),
+        ));
+    }
+    let version = u32::from_le_bytes(bytes[SNAPSHOT_MAGIC.len()..header].try_into().unwrap());
+    if version != SNAPSHOT_VERSION {
+        return Err(VectorError::Serialization(format!(
+            "Unsupported snapshot version {} (expected {})",
+            version, SNAPSHOT_VERSION
+        )));
+    }
+    // Length prefixes are untrusted; cap allocations at the file size
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(bytes.len() as u64)
+        .deserialize(&bytes[header..])
+        .map_err(|e| VectorError::Serialization(e.to_string()))
+}
+
+fn write_atomically(path: &Path, bytes: &[u8]) -> VectorResult<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
 #[async_trait]
 impl VectorStore for InMemoryStore {
     fn name(&self) -> &str {
@@ -108,7 +352,9 @@ impl VectorStore for InMemoryStore {
             }
             store.insert(record.id.clone(), record);
         }
+        drop(store);
 
+        self.record_write();
         Ok(())
     }
 
@@ -173,12 +419,7 @@ impl VectorStore for InMemoryStore {
         // Filter and compute scores
         let mut scored: Vec<_> = store
             .values()
-            .filter(|record| {
-                // Check if all filter conditions match
-                filter
-                    .iter()
-                    .all(|(key, value)| record.metadata.get(key).map_or(false, |v| v == value))
-            })
+            .filter(|record| matches_filter(record, filter))
             .map(|record| {
                 let score = self.compute_score(vector, &record.vector);
                 (record, score)
@@ -233,6 +474,9 @@ impl VectorStore for InMemoryStore {
             .map_err(|e| VectorError::Connection(format!("Failed to acquire write lock: {}", e)))?;
 
         store.remove(id);
+        drop(store);
+
+        self.record_write();
         Ok(())
     }
 
@@ -245,7 +489,9 @@ impl VectorStore for InMemoryStore {
         for id in ids {
             store.remove(*id);
         }
+        drop(store);
 
+        self.record_write();
         Ok(())
     }
 
@@ -265,13 +511,138 @@ impl VectorStore for InMemoryStore {
             .map_err(|e| VectorError::Connection(format!("Failed to acquire write lock: {}", e)))?;
 
         store.clear();
+        drop(store);
+
+        self.record_write();
         Ok(())
     }
+
+    fn export_all(&self) -> RecordStream<'_> {
+        let records: VectorResult<Vec<VectorRecord>> = self
+            .records
+            .read()
+            .map(|store| {
+                let mut records: Vec<VectorRecord> = store.values().cloned().collect();
+                records.sort_by(|a, b| a.id.cmp(&b.id));
+                records
+            })
+            .map_err(|e| VectorError::Connection(format!("Failed to acquire read lock: {}", e)));
+        match records {
+            Ok(records) => stream::iter(records.into_iter().map(Ok)).boxed(),
+            Err(e) => stream::once(async move { Err(e) }).boxed(),
+        }
+    }
+
+    fn as_hybrid(&self) -> Option<&dyn HybridCapable> {
+        Some(self)
+    }
+}
+
+/// Whether all filter conditions match the record's metadata.
+fn matches_filter(record: &VectorRecord, filter: &Filter) -> bool {
+    filter
+        .iter()
+        .all(|(key, value)| record.metadata.get(key) == Some(value))
+}
+
+/// Fraction of `terms` found in the record's ID or metadata values,
+/// ignoring case.
+fn keyword_score(record: &VectorRecord, terms: &[String]) -> f32 {
+    if terms.is_empty() {
+        return 0.0;
+    }
+    let mut text = record.id.to_lowercase();
+    for value in record.metadata.values() {
+        text.push(' ');
+        match value {
+            serde_json::Value::String(s) => text.push_str(&s.to_lowercase()),
+            other => text.push_str(&other.to_string().to_lowercase()),
+        }
+    }
+    let found = terms.iter().filter(|t| text.contains(t.as_str())).count();
+    found as f32 / terms.len() as f32
+}
+
+/// A naive hybrid search: the keyword score is the fraction of query words
+/// found as substrings of a record's ID or metadata values, blended with the
+/// vector score of [`VectorStore::search`]. Without a vector, records
+/// matching no query word are left out.
+#[async_trait]
+impl HybridCapable for InMemoryStore {
+    async fn hybrid_search(
+        &self,
+        query_text: &str,
+        vector: Option<&[f32]>,
+        alpha: f32,
+        k: usize,
+        filter: Option<&Filter>,
+    ) -> VectorResult<Vec<SearchResult>> {
+        check_alpha(alpha)?;
+        if let Some(vector) = vector {
+            if vector.len() != self.dimension {
+                return Err(VectorError::DimensionMismatch {
+                    expected: self.dimension,
+                    actual: vector.len(),
+                });
+            }
+        }
+
+        let mut terms: Vec<String> = query_text
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        terms.sort();
+        terms.dedup();
+
+        let store = self
+            .records
+            .read()
+            .map_err(|e| VectorError::Connection(format!("Failed to acquire read lock: {}", e)))?;
+
+        let mut scored: Vec<_> = store
+            .values()
+            .filter(|record| filter.is_none_or(|f| matches_filter(record, f)))
+            .filter_map(|record| {
+                let keyword = keyword_score(record, &terms);
+                let (score, explain) = match vector {
+                    Some(vector) => {
+                        let similarity = self.compute_score(vector, &record.vector);
+                        (
+                            (1.0 - alpha) * keyword + alpha * similarity,
+                            format!("keyword: {:.3}, vector: {:.3}", keyword, similarity),
+                        )
+                    }
+                    None if keyword > 0.0 => (keyword, format!("keyword: {:.3}", keyword)),
+                    None => return None,
+                };
+                Some((record, score, explain))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(scored
+            .into_iter()
+            .take(k)
+            .map(|(record, score, explain)| {
+                let mut metadata = record.metadata.clone();
+                metadata.insert(EXPLAIN_SCORE_KEY.to_string(), explain.into());
+                SearchResult {
+                    id: record.id.clone(),
+                    score,
+                    vector: Some(record.vector.clone()),
+                    metadata,
+                }
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::VectorStoreConfig;
+    use futures::TryStreamExt;
 
     #[tokio::test]
     async fn test_upsert_and_search() {
@@ -340,6 +711,92 @@ mod tests {
         assert!(results.iter().all(|r| r.id != "b"));
     }
 
+    async fn hybrid_fixture() -> InMemoryStore {
+        let store = InMemoryStore::new(2);
+        store
+            .upsert(vec![
+                VectorRecord::new("membrane", vec![0.0, 1.0])
+                    .with_metadata("title", "Cell Membrane transport")
+                    .with_metadata("type", "doc"),
+                VectorRecord::new("ribosome", vec![1.0, 0.0])
+                    .with_metadata("title", "Ribosome assembly")
+                    .with_metadata("type", "doc"),
+                VectorRecord::new("wall", vec![0.6, 0.8])
+                    .with_metadata("title", "Cell wall")
+                    .with_metadata("type", "note"),
+            ])
+            .await
+            .unwrap();
+        store
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_blends_keywords_and_vectors() {
+        let store = hybrid_fixture().await;
+        let hybrid = store.as_hybrid().expect("in-memory store is hybrid");
+        let ids =
+            |results: &[SearchResult]| results.iter().map(|r| r.id.clone()).collect::<Vec<_>>();
+
+        // Pure keywords: both cell records, the full match first
+        let results = hybrid
+            .hybrid_search("cell membrane", Some(&[1.0, 0.0]), 0.0, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&results)[..2], ["membrane", "wall"]);
+        assert_eq!(results[0].score, 1.0);
+        assert_eq!(
+            results[0].metadata[EXPLAIN_SCORE_KEY],
+            "keyword: 1.000, vector: 0.000"
+        );
+
+        // Pure vector: nearest to [1, 0] regardless of the text
+        let results = hybrid
+            .hybrid_search("cell membrane", Some(&[1.0, 0.0]), 1.0, 1, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&results), ["ribosome"]);
+
+        // Halfway: the cell wall is partly both
+        let results = hybrid
+            .hybrid_search("cell membrane", Some(&[1.0, 0.0]), 0.5, 1, None)
+            .await
+            .unwrap();
+        assert_eq!(ids(&results), ["wall"]);
+        assert!((results[0].score - 0.55).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_without_vector_filters_and_validates() {
+        let store = hybrid_fixture().await;
+
+        let results = store
+            .hybrid_search("CELL", None, 0.5, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.id != "ribosome"));
+
+        let mut filter = Filter::new();
+        filter.insert("type".to_string(), serde_json::json!("doc"));
+        let results = store
+            .hybrid_search("cell", None, 0.5, 10, Some(&filter))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "membrane");
+
+        assert!(matches!(
+            store.hybrid_search("cell", None, 1.5, 10, None).await,
+            Err(VectorError::Config(_))
+        ));
+        assert!(matches!(
+            store
+                .hybrid_search("cell", Some(&[1.0, 0.0, 0.0]), 0.5, 10, None)
+                .await,
+            Err(VectorError::DimensionMismatch { .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_count_and_clear() {
         let store = InMemoryStore::new(2);
@@ -358,6 +815,28 @@ mod tests {
         assert_eq!(store.count().await.unwrap(), 0);
     }
 
+    #[tokio::test]
+    async fn test_export_all_round_trips() {
+        let store = InMemoryStore::new(2);
+        store
+            .upsert(vec![
+                VectorRecord::new("b", vec![0.0, 1.0]).with_metadata("label", "membrane"),
+                VectorRecord::new("a", vec![1.0, 0.0]).with_metadata("label", "cell"),
+            ])
+            .await
+            .unwrap();
+
+        let records: Vec<VectorRecord> = store.export_all().try_collect().await.unwrap();
+        let ids: Vec<&str> = records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        let copy = InMemoryStore::new(2);
+        copy.upsert(records).await.unwrap();
+        let restored = copy.get("b").await.unwrap().unwrap();
+        assert_eq!(restored.vector, vec![0.0, 1.0]);
+        assert_eq!(restored.metadata["label"], "membrane");
+    }
+
     #[tokio::test]
     async fn test_dimension_mismatch() {
         let store = InMemoryStore::new(3);
@@ -386,4 +865,117 @@ mod tests {
         let results = store.search(&[0.0, 0.0], 2).await.unwrap();
         assert_eq!(results[0].id, "close"); // Closer vector should rank first
     }
+
+    fn sample_records(n: usize) -> Vec<VectorRecord> {
+        (0..n)
+            .map(|i| {
+                let angle = i as f32 * 0.01;
+                VectorRecord::new(
+                    format!("v{i}"),
+                    vec![angle.cos(), angle.sin(), (i % 7) as f32 * 0.1],
+                )
+                .with_metadata("index", i)
+                .with_metadata("tags", serde_json::json!({ "even": i % 2 == 0 }))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectors.bin");
+
+        let store = InMemoryStore::new(3);
+        store.upsert(sample_records(1000)).await.unwrap();
+        store.save(&path).unwrap();
+
+        let fresh = InMemoryStore::new(3);
+        assert_eq!(fresh.load(&path).unwrap(), 1000);
+        assert_eq!(fresh.count().await.unwrap(), 1000);
+
+        let query = [0.5, 0.5, 0.2];
+        let before = store.search(&query, 10).await.unwrap();
+        let after = fresh.search(&query, 10).await.unwrap();
+        let ids =
+            |results: &[SearchResult]| results.iter().map(|r| r.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&before), ids(&after));
+
+        let original = store.get("v421").await.unwrap().unwrap();
+        let loaded = fresh.get("v421").await.unwrap().unwrap();
+        assert_eq!(loaded.vector, original.vector);
+        assert_eq!(loaded.metadata, original.metadata);
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_mismatched_and_corrupt_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectors.bin");
+        let store = InMemoryStore::new(3);
+        store.upsert(sample_records(10)).await.unwrap();
+        store.save(&path).unwrap();
+
+        let result = InMemoryStore::new(4).load(&path);
+        assert!(matches!(
+            result,
+            Err(VectorError::DimensionMismatch {
+                expected: 4,
+                actual: 3
+            })
+        ));
+        let result = InMemoryStore::with_config(3, DistanceMetric::Euclidean).load(&path);
+        assert!(matches!(result, Err(VectorError::Config(_))));
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.truncate(bytes.len() / 2);
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            InMemoryStore::new(3).load(&path),
+            Err(VectorError::Serialization(_))
+        ));
+
+        // A huge length prefix must not be trusted
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bytes.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&[0xff; 32]);
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            InMemoryStore::new(3).load(&path),
+            Err(VectorError::Serialization(_))
+        ));
+
+        std::fs::write(&path, b"not a snapshot").unwrap();
+        assert!(matches!(
+            InMemoryStore::new(3).load(&path),
+            Err(VectorError::Serialization(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_auto_persist_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectors.bin");
+        let config = VectorStoreConfig {
+            dimension: 3,
+            backend: crate::BackendConfig::InMemory {
+                persist_path: Some(path.clone()),
+            },
+            ..Default::default()
+        };
+
+        {
+            let store = crate::create_store(config.clone()).await.unwrap();
+            for chunk in sample_records(250).chunks(10) {
+                store.upsert(chunk.to_vec()).await.unwrap();
+            }
+            store.delete("v0").await.unwrap();
+        }
+
+        let store = crate::create_store(config).await.unwrap();
+        assert_eq!(store.count().await.unwrap(), 249);
+        assert!(store.get("v0").await.unwrap().is_none());
+        assert_eq!(
+            store.get("v249").await.unwrap().unwrap().metadata["index"],
+            249
+        );
+    }
 }
@@ -36,7 +36,10 @@
 //! }
 //! ```
 
-use crate::{DistanceMetric, SearchResult, VectorError, VectorRecord, VectorResult, VectorStore};
+use crate::{
+    paginate, DistanceMetric, RecordStream, SearchResult, VectorError, VectorRecord, VectorResult,
+    VectorStore,
+};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -112,6 +115,23 @@ struct FetchedVector {
     metadata: HashMap<String, serde_json::Value>,
 }
 
+#[derive(Deserialize)]
+struct ListResponse {
+    #[serde(default)]
+    vectors: Vec<ListedVector>,
+    pagination: Option<Pagination>,
+}
+
+#[derive(Deserialize)]
+struct ListedVector {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct Pagination {
+    next: Option<String>,
+}
+
 #[derive(Serialize)]
 struct DeleteRequest {
     ids: Vec<String>,
@@ -412,4 +432,41 @@ impl VectorStore for PineconeStore {
 
         Ok(())
     }
+
+    fn export_all(&self) -> RecordStream<'_> {
+        paginate(move |token: Option<String>| async move {
+            // List a page of IDs, then fetch their vectors
+            let mut request = self
+                .client
+                .get(format!("{}/vectors/list", self.host))
+                .header("Api-Key", &self.api_key)
+                .query(&[("namespace", ""), ("limit", "100")]);
+            if let Some(ref token) = token {
+                request = request.query(&[("paginationToken", token)]);
+            }
+
+            let response = request
+                .send()
+                .await
+                .map_err(|e| VectorError::Connection(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error = response.text().await.unwrap_or_default();
+                return Err(VectorError::Api(format!("List failed: {}", error)));
+            }
+
+            let list: ListResponse = response
+                .json()
+                .await
+                .map_err(|e| VectorError::Serialization(e.to_string()))?;
+
+            let ids: Vec<&str> = list.vectors.iter().map(|v| v.id.as_str()).collect();
+            let records = if ids.is_empty() {
+                Vec::new()
+            } else {
+                self.get_batch(&ids).await?
+            };
+            Ok((records, list.pagination.and_then(|p| p.next)))
+        })
+    }
 }
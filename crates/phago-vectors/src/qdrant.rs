@@ -36,23 +36,164 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! # Named Vectors and Payload Indexes
+//!
+//! One collection can hold several vector spaces, each under its own name
+//! and with its own dimension, so concept embeddings and document chunk
+//! embeddings can live side by side. [`QdrantStore::upsert_named`] and
+//! [`QdrantStore::search_named`] pick the space; the [`VectorStore`]
+//! methods use the default one. Metadata keys declared as indexed get a
+//! payload index, which `search_with_filter` conditions then hit.
+//!
+//! ```rust,ignore
+//! use phago_vectors::qdrant::{FieldKind, QdrantOptions, QdrantStore};
+//!
+//! let store = QdrantStore::connect_with(
+//!     QdrantOptions::new("http://localhost:6334", "phago", 384)
+//!         .named_vector("concept", 384)
+//!         .named_vector("chunk", 768)
+//!         .index_field("kind", FieldKind::Keyword)
+//!         .index_field("doc_id", FieldKind::Keyword),
+//! ).await?;
+//!
+//! let filter = HashMap::from([
+//!     ("kind".to_string(), json!("chunk")),
+//!     ("doc_id".to_string(), json!("paper-7")),
+//! ]);
+//! let chunks = store.search_named(Some("chunk"), &query, 10, &filter).await?;
+//! ```
 
-use crate::{DistanceMetric, SearchResult, VectorError, VectorRecord, VectorResult, VectorStore};
+use crate::{
+    paginate, DistanceMetric, RecordStream, SearchResult, VectorError, VectorRecord, VectorResult,
+    VectorStore,
+};
 use async_trait::async_trait;
+use qdrant_client::qdrant::vector_output::Vector;
+use qdrant_client::qdrant::vectors_config::Config;
 use qdrant_client::qdrant::{
-    CreateCollectionBuilder, DeletePointsBuilder, Distance, GetPointsBuilder, PointId, PointStruct,
-    PointsIdsList, ScrollPointsBuilder, SearchPointsBuilder, UpsertPointsBuilder,
-    VectorParamsBuilder,
+    Condition, CreateCollectionBuilder, CreateFieldIndexCollectionBuilder, DeletePointsBuilder,
+    Distance, FieldType, Filter, GetPointsBuilder, PointId, PointStruct, PointsIdsList, Range,
+    RetrievedPoint, ScrollPointsBuilder, SearchPointsBuilder, UpsertPointsBuilder,
+    VectorParamsBuilder, Vectors, VectorsConfig, VectorsConfigBuilder, VectorsOutput,
 };
-use qdrant_client::Qdrant;
+use qdrant_client::{Qdrant, QdrantError};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// A named vector space in a collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedVector {
+    /// Vector name, e.g. `"concept"` or `"chunk"`.
+    pub name: String,
+    /// Dimension of the vectors stored under this name.
+    pub dimension: usize,
+}
+
+/// Payload type of an indexed metadata field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    /// Exact-match strings.
+    #[default]
+    Keyword,
+    /// Integers.
+    Integer,
+    /// Floating-point numbers.
+    Float,
+    /// Booleans.
+    Bool,
+}
+
+/// A metadata key Qdrant keeps a payload index for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedField {
+    /// Metadata key.
+    pub name: String,
+    /// Type of the values stored under the key.
+    #[serde(default)]
+    pub kind: FieldKind,
+}
+
+/// Connection and collection layout for [`QdrantStore::connect_with`].
+#[derive(Debug, Clone)]
+pub struct QdrantOptions {
+    /// Qdrant server URL.
+    pub url: String,
+    /// API key (optional).
+    pub api_key: Option<String>,
+    /// Collection name.
+    pub collection: String,
+    /// Dimension of the unnamed vector, used when `vectors` is empty.
+    pub dimension: usize,
+    /// Distance metric of every vector in the collection.
+    pub metric: DistanceMetric,
+    /// Named vectors; empty for a single unnamed vector per point.
+    pub vectors: Vec<NamedVector>,
+    /// Named vector the [`VectorStore`] methods use. Defaults to the first
+    /// of `vectors`.
+    pub default_vector: Option<String>,
+    /// Metadata keys to create payload indexes for.
+    pub indexed_fields: Vec<IndexedField>,
+}
+
+impl QdrantOptions {
+    /// Options for a collection with one unnamed cosine vector per point.
+    pub fn new(url: impl Into<String>, collection: impl Into<String>, dimension: usize) -> Self {
+        Self {
+            url: url.into(),
+            api_key: None,
+            collection: collection.into(),
+            dimension,
+            metric: DistanceMetric::Cosine,
+            vectors: Vec::new(),
+            default_vector: None,
+            indexed_fields: Vec::new(),
+        }
+    }
+
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Add a named vector space.
+    pub fn named_vector(mut self, name: impl Into<String>, dimension: usize) -> Self {
+        self.vectors.push(NamedVector {
+            name: name.into(),
+            dimension,
+        });
+        self
+    }
+
+    pub fn default_vector(mut self, name: impl Into<String>) -> Self {
+        self.default_vector = Some(name.into());
+        self
+    }
+
+    /// Keep a payload index for a metadata key.
+    pub fn index_field(mut self, name: impl Into<String>, kind: FieldKind) -> Self {
+        self.indexed_fields.push(IndexedField {
+            name: name.into(),
+            kind,
+        });
+        self
+    }
+}
+
 /// Qdrant vector database adapter.
 pub struct QdrantStore {
     client: Qdrant,
     collection: String,
     dimension: usize,
     metric: DistanceMetric,
+    vectors: Vec<NamedVector>,
+    default_vector: Option<String>,
 }
 
 impl QdrantStore {
@@ -64,32 +205,200 @@ impl QdrantStore {
         dimension: usize,
         metric: DistanceMetric,
     ) -> VectorResult<Self> {
-        let client = if let Some(key) = api_key {
-            Qdrant::from_url(url)
-                .api_key(key)
-                .build()
-                .map_err(|e| VectorError::Connection(e.to_string()))?
-        } else {
-            Qdrant::from_url(url)
-                .build()
-                .map_err(|e| VectorError::Connection(e.to_string()))?
+        let mut options = QdrantOptions::new(url, collection, dimension).metric(metric);
+        options.api_key = api_key.map(str::to_string);
+        Self::connect_with(options).await
+    }
+
+    /// Connect to a Qdrant server, ensure the collection exists with the
+    /// configured vectors and create any missing payload indexes.
+    ///
+    /// An existing collection must already have every configured vector,
+    /// at the configured dimension.
+    pub async fn connect_with(options: QdrantOptions) -> VectorResult<Self> {
+        let mut builder = Qdrant::from_url(&options.url);
+        if let Some(key) = &options.api_key {
+            builder = builder.api_key(key.clone());
+        }
+        let client = builder
+            .build()
+            .map_err(|e| VectorError::Connection(e.to_string()))?;
+
+        let default_vector = match (&options.default_vector, options.vectors.first()) {
+            (Some(name), _) if !options.vectors.iter().any(|v| &v.name == name) => {
+                return Err(VectorError::Config(format!(
+                    "default vector `{name}` is not one of the named vectors"
+                )));
+            }
+            (Some(name), _) => Some(name.clone()),
+            (None, first) => first.map(|v| v.name.clone()),
+        };
+        let dimension = match &default_vector {
+            Some(name) => options
+                .vectors
+                .iter()
+                .find(|v| &v.name == name)
+                .map_or(options.dimension, |v| v.dimension),
+            None => options.dimension,
         };
 
         let store = Self {
             client,
-            collection: collection.to_string(),
+            collection: options.collection,
             dimension,
-            metric,
+            metric: options.metric,
+            vectors: options.vectors,
+            default_vector,
         };
 
         // Ensure collection exists
-        store.ensure_collection().await?;
+        store.ensure_collection(&options.indexed_fields).await?;
 
         Ok(store)
     }
 
-    /// Ensure the collection exists, creating it if necessary.
-    async fn ensure_collection(&self) -> VectorResult<()> {
+    /// The named vectors of the collection; empty if it stores a single
+    /// unnamed vector.
+    pub fn named_vectors(&self) -> &[NamedVector] {
+        &self.vectors
+    }
+
+    /// Insert or update records under a named vector (`None` for the
+    /// default one).
+    pub async fn upsert_named(
+        &self,
+        vector_name: Option<&str>,
+        records: Vec<VectorRecord>,
+    ) -> VectorResult<()> {
+        let (name, dimension) = self.resolve_vector(vector_name)?;
+        if let Some(record) = records.iter().find(|r| r.vector.len() != dimension) {
+            return Err(VectorError::DimensionMismatch {
+                expected: dimension,
+                actual: record.vector.len(),
+            });
+        }
+
+        let points: Vec<PointStruct> = records
+            .into_iter()
+            .map(|record| {
+                let vectors: Vectors = match name {
+                    Some(name) => HashMap::from([(name.to_string(), record.vector)]).into(),
+                    None => record.vector.into(),
+                };
+                PointStruct::new(record.id, vectors, Self::to_payload(&record.metadata))
+            })
+            .collect();
+
+        self.client
+            .upsert_points(UpsertPointsBuilder::new(&self.collection, points).wait(true))
+            .await
+            .map_err(|e| self.api_error(e))?;
+
+        Ok(())
+    }
+
+    /// Search a named vector (`None` for the default one), keeping only
+    /// records whose metadata equals every entry of `filter`.
+    pub async fn search_named(
+        &self,
+        vector_name: Option<&str>,
+        vector: &[f32],
+        k: usize,
+        filter: &HashMap<String, serde_json::Value>,
+    ) -> VectorResult<Vec<SearchResult>> {
+        let (name, dimension) = self.resolve_vector(vector_name)?;
+        if vector.len() != dimension {
+            return Err(VectorError::DimensionMismatch {
+                expected: dimension,
+                actual: vector.len(),
+            });
+        }
+
+        let mut request = SearchPointsBuilder::new(&self.collection, vector.to_vec(), k as u64)
+            .with_payload(true)
+            .with_vectors(true);
+        if let Some(name) = name {
+            request = request.vector_name(name);
+        }
+        if let Some(filter) = to_filter(filter)? {
+            request = request.filter(filter);
+        }
+
+        let response = self
+            .client
+            .search_points(request)
+            .await
+            .map_err(|e| self.api_error(e))?;
+
+        let results = response
+            .result
+            .into_iter()
+            .map(|point| SearchResult {
+                id: point_id_string(point.id),
+                score: point.score,
+                vector: dense_vector(point.vectors, name),
+                metadata: Self::from_payload(&point.payload),
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// The name and dimension of the vector to use, `None` naming the
+    /// default vector.
+    fn resolve_vector<'a>(
+        &'a self,
+        vector_name: Option<&'a str>,
+    ) -> VectorResult<(Option<&'a str>, usize)> {
+        let Some(name) = vector_name.or(self.default_vector.as_deref()) else {
+            return Ok((None, self.dimension));
+        };
+        if self.vectors.is_empty() {
+            return Err(VectorError::Collection(format!(
+                "collection `{}` stores a single unnamed vector, not `{name}`",
+                self.collection
+            )));
+        }
+        match self.vectors.iter().find(|v| v.name == name) {
+            Some(v) => Ok((Some(name), v.dimension)),
+            None => Err(VectorError::Collection(format!(
+                "collection `{}` has no vector named `{name}` (it has {})",
+                self.collection,
+                self.vector_names()
+            ))),
+        }
+    }
+
+    fn vector_names(&self) -> String {
+        self.vectors
+            .iter()
+            .map(|v| format!("`{}`", v.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Map a client error, describing a rejected vector name as a
+    /// collection error.
+    fn api_error(&self, error: QdrantError) -> VectorError {
+        let message = error.to_string();
+        if message.to_lowercase().contains("vector name") {
+            VectorError::Collection(format!(
+                "collection `{}` rejected the vector name ({message}); configured vectors: {}",
+                self.collection,
+                if self.vectors.is_empty() {
+                    "a single unnamed vector".to_string()
+                } else {
+                    self.vector_names()
+                }
+            ))
+        } else {
+            VectorError::Api(message)
+        }
+    }
+
+    /// Ensure the collection exists, creating it if necessary, and that it
+    /// has payload indexes for `indexed_fields`.
+    async fn ensure_collection(&self, indexed_fields: &[IndexedField]) -> VectorResult<()> {
         let collections = self
             .client
             .list_collections()
@@ -101,25 +410,121 @@ impl QdrantStore {
             .iter()
             .any(|c| c.name == self.collection);
 
-        if !exists {
-            let distance = match self.metric {
-                DistanceMetric::Cosine => Distance::Cosine,
-                DistanceMetric::Euclidean => Distance::Euclid,
-                DistanceMetric::DotProduct => Distance::Dot,
+        let distance = match self.metric {
+            DistanceMetric::Cosine => Distance::Cosine,
+            DistanceMetric::Euclidean => Distance::Euclid,
+            DistanceMetric::DotProduct => Distance::Dot,
+        };
+
+        let indexed = if exists {
+            let info = self
+                .client
+                .collection_info(&self.collection)
+                .await
+                .map_err(|e| VectorError::Collection(e.to_string()))?
+                .result
+                .unwrap_or_default();
+            let vectors = info
+                .config
+                .and_then(|c| c.params)
+                .and_then(|p| p.vectors_config);
+            self.check_vectors(vectors)?;
+            info.payload_schema
+        } else {
+            let request = CreateCollectionBuilder::new(&self.collection);
+            let request = if self.vectors.is_empty() {
+                request.vectors_config(VectorParamsBuilder::new(self.dimension as u64, distance))
+            } else {
+                let mut config = VectorsConfigBuilder::default();
+                for vector in &self.vectors {
+                    config.add_named_vector_params(
+                        &vector.name,
+                        VectorParamsBuilder::new(vector.dimension as u64, distance),
+                    );
+                }
+                request.vectors_config(config)
             };
+            self.client
+                .create_collection(request)
+                .await
+                .map_err(|e| VectorError::Collection(e.to_string()))?;
+            HashMap::new()
+        };
 
+        for field in indexed_fields {
+            if indexed.contains_key(&field.name) {
+                continue;
+            }
+            let field_type = match field.kind {
+                FieldKind::Keyword => FieldType::Keyword,
+                FieldKind::Integer => FieldType::Integer,
+                FieldKind::Float => FieldType::Float,
+                FieldKind::Bool => FieldType::Bool,
+            };
             self.client
-                .create_collection(
-                    CreateCollectionBuilder::new(&self.collection)
-                        .vectors_config(VectorParamsBuilder::new(self.dimension as u64, distance)),
+                .create_field_index(
+                    CreateFieldIndexCollectionBuilder::new(
+                        &self.collection,
+                        &field.name,
+                        field_type,
+                    )
+                    .wait(true),
                 )
                 .await
-                .map_err(|e| VectorError::Collection(e.to_string()))?;
+                .map_err(|e| {
+                    VectorError::Collection(format!(
+                        "could not index payload field `{}`: {e}",
+                        field.name
+                    ))
+                })?;
         }
 
         Ok(())
     }
 
+    /// Check an existing collection has the configured vectors.
+    fn check_vectors(&self, existing: Option<VectorsConfig>) -> VectorResult<()> {
+        let mismatch = |found: String| {
+            VectorError::Collection(format!(
+                "collection `{}` already exists with {found}, which does not match the configured vectors",
+                self.collection
+            ))
+        };
+        match existing.and_then(|c| c.config) {
+            Some(Config::Params(params)) if !self.vectors.is_empty() => Err(mismatch(format!(
+                "a single unnamed vector of dimension {}",
+                params.size
+            ))),
+            Some(Config::ParamsMap(map)) => {
+                let wanted: Vec<(&str, usize)> = if self.vectors.is_empty() {
+                    vec![("", self.dimension)]
+                } else {
+                    self.vectors
+                        .iter()
+                        .map(|v| (v.name.as_str(), v.dimension))
+                        .collect()
+                };
+                let matches = wanted.iter().all(|(name, dimension)| {
+                    map.map
+                        .get(*name)
+                        .is_some_and(|p| p.size as usize == *dimension)
+                });
+                if matches {
+                    Ok(())
+                } else {
+                    let mut found: Vec<String> = map
+                        .map
+                        .iter()
+                        .map(|(name, p)| format!("`{name}` ({})", p.size))
+                        .collect();
+                    found.sort();
+                    Err(mismatch(format!("vectors {}", found.join(", "))))
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Convert metadata to Qdrant payload.
     fn to_payload(
         metadata: &HashMap<String, serde_json::Value>,
@@ -146,6 +551,15 @@ impl QdrantStore {
             .collect()
     }
 
+    /// Convert a retrieved point to a record holding its default vector.
+    fn to_record(&self, point: RetrievedPoint) -> VectorRecord {
+        VectorRecord {
+            id: point_id_string(point.id),
+            vector: dense_vector(point.vectors, self.default_vector.as_deref()).unwrap_or_default(),
+            metadata: Self::from_payload(&point.payload),
+        }
+    }
+
     /// Convert Qdrant payload to metadata.
     fn from_payload(
         payload: &HashMap<String, qdrant_client::qdrant::Value>,
@@ -167,6 +581,63 @@ impl QdrantStore {
     }
 }
 
+/// A point ID as the string it was stored under.
+fn point_id_string(id: Option<PointId>) -> String {
+    match id {
+        Some(PointId {
+            point_id_options: Some(opt),
+        }) => match opt {
+            qdrant_client::qdrant::point_id::PointIdOptions::Uuid(u) => u,
+            qdrant_client::qdrant::point_id::PointIdOptions::Num(n) => n.to_string(),
+        },
+        _ => String::new(),
+    }
+}
+
+/// The dense vector stored under `name` (`None` for the unnamed vector).
+fn dense_vector(vectors: Option<VectorsOutput>, name: Option<&str>) -> Option<Vec<f32>> {
+    match vectors?.get_vector_by_name(name.unwrap_or(""))? {
+        Vector::Dense(dense) => Some(dense.data),
+        _ => None,
+    }
+}
+
+/// A Qdrant filter requiring every metadata entry of `filter` to match.
+///
+/// Strings, integers and booleans become match conditions and other
+/// numbers a range pinned to the value, all of which a payload index on
+/// the key serves.
+fn to_filter(filter: &HashMap<String, serde_json::Value>) -> VectorResult<Option<Filter>> {
+    if filter.is_empty() {
+        return Ok(None);
+    }
+    let conditions = filter
+        .iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(s) => Ok(Condition::matches(key.clone(), s.clone())),
+            serde_json::Value::Bool(b) => Ok(Condition::matches(key.clone(), *b)),
+            serde_json::Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+                (Some(i), _) => Ok(Condition::matches(key.clone(), i)),
+                (None, Some(f)) => Ok(Condition::range(
+                    key.clone(),
+                    Range {
+                        gte: Some(f),
+                        lte: Some(f),
+                        ..Default::default()
+                    },
+                )),
+                (None, None) => Err(VectorError::Config(format!(
+                    "cannot filter on `{key}`: {n} is out of range"
+                ))),
+            },
+            _ => Err(VectorError::Config(format!(
+                "cannot filter on `{key}`: only strings, numbers and booleans are supported"
+            ))),
+        })
+        .collect::<VectorResult<Vec<_>>>()?;
+    Ok(Some(Filter::must(conditions)))
+}
+
 #[async_trait]
 impl VectorStore for QdrantStore {
     fn name(&self) -> &str {
@@ -182,19 +653,7 @@ impl VectorStore for QdrantStore {
     }
 
     async fn upsert(&self, records: Vec<VectorRecord>) -> VectorResult<()> {
-        let points: Vec<PointStruct> = records
-            .into_iter()
-            .map(|record| {
-                PointStruct::new(record.id, record.vector, Self::to_payload(&record.metadata))
-            })
-            .collect();
-
-        self.client
-            .upsert_points(UpsertPointsBuilder::new(&self.collection, points).wait(true))
-            .await
-            .map_err(|e| VectorError::Api(e.to_string()))?;
-
-        Ok(())
+        self.upsert_named(None, records).await
     }
 
     async fn search(&self, vector: &[f32], k: usize) -> VectorResult<Vec<SearchResult>> {
@@ -205,56 +664,9 @@ impl VectorStore for QdrantStore {
         &self,
         vector: &[f32],
         k: usize,
-        _filter: &HashMap<String, serde_json::Value>,
+        filter: &HashMap<String, serde_json::Value>,
     ) -> VectorResult<Vec<SearchResult>> {
-        if vector.len() != self.dimension {
-            return Err(VectorError::DimensionMismatch {
-                expected: self.dimension,
-                actual: vector.len(),
-            });
-        }
-
-        let response = self
-            .client
-            .search_points(
-                SearchPointsBuilder::new(&self.collection, vector.to_vec(), k as u64)
-                    .with_payload(true)
-                    .with_vectors(true),
-            )
-            .await
-            .map_err(|e| VectorError::Api(e.to_string()))?;
-
-        let results = response
-            .result
-            .into_iter()
-            .map(|point| {
-                let id = match point.id {
-                    Some(PointId {
-                        point_id_options: Some(opt),
-                    }) => match opt {
-                        qdrant_client::qdrant::point_id::PointIdOptions::Uuid(u) => u,
-                        qdrant_client::qdrant::point_id::PointIdOptions::Num(n) => n.to_string(),
-                    },
-                    _ => String::new(),
-                };
-
-                let vector = point.vectors.and_then(|v| match v.vectors_options {
-                    Some(qdrant_client::qdrant::vectors_output::VectorsOptions::Vector(vec)) => {
-                        Some(vec.data.clone())
-                    }
-                    _ => None,
-                });
-
-                SearchResult {
-                    id,
-                    score: point.score,
-                    vector,
-                    metadata: Self::from_payload(&point.payload),
-                }
-            })
-            .collect();
-
-        Ok(results)
+        self.search_named(None, vector, k, filter).await
     }
 
     async fn get(&self, id: &str) -> VectorResult<Option<VectorRecord>> {
@@ -278,33 +690,7 @@ impl VectorStore for QdrantStore {
         Ok(response
             .result
             .into_iter()
-            .map(|point| {
-                let id = match point.id {
-                    Some(PointId {
-                        point_id_options: Some(opt),
-                    }) => match opt {
-                        qdrant_client::qdrant::point_id::PointIdOptions::Uuid(u) => u,
-                        qdrant_client::qdrant::point_id::PointIdOptions::Num(n) => n.to_string(),
-                    },
-                    _ => String::new(),
-                };
-
-                let vector = point
-                    .vectors
-                    .and_then(|v| match v.vectors_options {
-                        Some(qdrant_client::qdrant::vectors_output::VectorsOptions::Vector(
-                            vec,
-                        )) => Some(vec.data.clone()),
-                        _ => None,
-                    })
-                    .unwrap_or_default();
-
-                VectorRecord {
-                    id,
-                    vector,
-                    metadata: Self::from_payload(&point.payload),
-                }
-            })
+            .map(|point| self.to_record(point))
             .collect())
     }
 
@@ -384,4 +770,83 @@ impl VectorStore for QdrantStore {
 
         Ok(())
     }
+
+    fn export_all(&self) -> RecordStream<'_> {
+        paginate(move |offset: Option<PointId>| async move {
+            let mut builder = ScrollPointsBuilder::new(&self.collection)
+                .limit(1000)
+                .with_payload(true)
+                .with_vectors(true);
+            if let Some(offset) = offset {
+                builder = builder.offset(offset);
+            }
+
+            let response = self
+                .client
+                .scroll(builder)
+                .await
+                .map_err(|e| VectorError::Api(e.to_string()))?;
+
+            let records = response
+                .result
+                .into_iter()
+                .map(|point| self.to_record(point))
+                .collect();
+            Ok((records, response.next_page_offset))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qdrant_client::qdrant::condition::ConditionOneOf;
+    use qdrant_client::qdrant::r#match::MatchValue;
+    use serde_json::json;
+
+    /// The key and condition of every clause in a filter.
+    fn clauses(filter: Filter) -> HashMap<String, qdrant_client::qdrant::FieldCondition> {
+        filter
+            .must
+            .into_iter()
+            .map(|c| match c.condition_one_of {
+                Some(ConditionOneOf::Field(field)) => (field.key.clone(), field),
+                other => panic!("unexpected condition {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty_filter_is_no_filter() {
+        assert!(to_filter(&HashMap::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn filter_requires_every_entry() {
+        let filter = HashMap::from([
+            ("kind".to_string(), json!("chunk")),
+            ("page".to_string(), json!(3)),
+            ("reviewed".to_string(), json!(true)),
+            ("score".to_string(), json!(0.5)),
+        ]);
+        let clauses = clauses(to_filter(&filter).unwrap().unwrap());
+        assert_eq!(clauses.len(), 4);
+
+        let matched = |key: &str| clauses[key].r#match.clone().unwrap().match_value;
+        assert_eq!(
+            matched("kind"),
+            Some(MatchValue::Keyword("chunk".to_string()))
+        );
+        assert_eq!(matched("page"), Some(MatchValue::Integer(3)));
+        assert_eq!(matched("reviewed"), Some(MatchValue::Boolean(true)));
+
+        let range = clauses["score"].range.unwrap();
+        assert_eq!((range.gte, range.lte), (Some(0.5), Some(0.5)));
+    }
+
+    #[test]
+    fn filter_rejects_structured_values() {
+        let filter = HashMap::from([("tags".to_string(), json!(["a", "b"]))]);
+        assert!(matches!(to_filter(&filter), Err(VectorError::Config(_))));
+    }
 }
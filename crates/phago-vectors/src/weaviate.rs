@@ -32,11 +32,19 @@
 //!
 //!     // Search
 //!     let results = store.search(&[0.1; 384], 5).await?;
+//!
+//!     // Keyword search blended with vector search, half and half
+//!     let results = store
+//!         .hybrid_search("introduction", Some(&[0.1; 384]), 0.5, 5, None)
+//!         .await?;
 //!     Ok(())
 //! }
 //! ```
 
-use crate::{DistanceMetric, SearchResult, VectorError, VectorRecord, VectorResult, VectorStore};
+use crate::{
+    check_alpha, paginate, DistanceMetric, Filter, HybridCapable, RecordStream, SearchResult,
+    VectorError, VectorRecord, VectorResult, VectorStore, EXPLAIN_SCORE_KEY,
+};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -92,9 +100,28 @@ struct WeaviateResult {
 struct AdditionalData {
     id: Option<String>,
     distance: Option<f32>,
+    /// Hybrid search score, as a decimal string.
+    score: Option<String>,
+    #[serde(rename = "explainScore")]
+    explain_score: Option<String>,
     vector: Option<Vec<f32>>,
 }
 
+#[derive(Deserialize)]
+struct ObjectsResponse {
+    #[serde(default)]
+    objects: Vec<ListedObject>,
+}
+
+#[derive(Deserialize)]
+struct ListedObject {
+    id: String,
+    #[serde(default)]
+    properties: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    vector: Vec<f32>,
+}
+
 #[derive(Deserialize)]
 struct ClassSchema {
     class: String,
@@ -345,33 +372,7 @@ impl VectorStore for WeaviateStore {
             .collect::<Vec<_>>()
             .join(", ");
 
-        // Build where clause for filter
-        let where_clause = if filter.is_empty() {
-            String::new()
-        } else {
-            // Build simple equality filters
-            let conditions: Vec<String> = filter
-                .iter()
-                .filter_map(|(k, v)| {
-                    match v {
-                        serde_json::Value::String(s) => Some(format!(
-                            "{{ path: [\"metadata_json\"], operator: Contains, valueText: \"\\\"{}\\\":\\\"{}\\\"\" }}",
-                            k, s
-                        )),
-                        _ => None,
-                    }
-                })
-                .collect();
-
-            if conditions.is_empty() {
-                String::new()
-            } else {
-                format!(
-                    "where: {{ operator: And, operands: [{}] }}",
-                    conditions.join(", ")
-                )
-            }
-        };
+        let where_clause = where_clause(filter);
 
         let query = format!(
             r#"{{
@@ -596,6 +597,206 @@ impl VectorStore for WeaviateStore {
         // Recreate the class
         self.ensure_class().await
     }
+
+    fn as_hybrid(&self) -> Option<&dyn HybridCapable> {
+        Some(self)
+    }
+
+    fn export_all(&self) -> RecordStream<'_> {
+        const PAGE_SIZE: usize = 100;
+
+        // Page through the class with Weaviate's cursor API, which continues
+        // after the UUID of the last object seen
+        paginate(move |after: Option<String>| async move {
+            let mut endpoint = format!(
+                "/v1/objects?class={}&limit={}&include=vector",
+                self.class_name, PAGE_SIZE
+            );
+            if let Some(ref after) = after {
+                endpoint.push_str(&format!("&after={}", after));
+            }
+
+            let response = self
+                .build_request(reqwest::Method::GET, &endpoint)
+                .send()
+                .await
+                .map_err(|e| VectorError::Connection(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error = response.text().await.unwrap_or_default();
+                return Err(VectorError::Api(format!("Export failed: {}", error)));
+            }
+
+            let page: ObjectsResponse = response
+                .json()
+                .await
+                .map_err(|e| VectorError::Serialization(e.to_string()))?;
+
+            let next = if page.objects.len() < PAGE_SIZE {
+                None
+            } else {
+                page.objects.last().map(|o| o.id.clone())
+            };
+
+            let records = page
+                .objects
+                .into_iter()
+                .map(|object| {
+                    let phago_id = object
+                        .properties
+                        .get("phago_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string();
+
+                    let metadata: HashMap<String, serde_json::Value> = object
+                        .properties
+                        .get("metadata_json")
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| serde_json::from_str(s).ok())
+                        .unwrap_or_default();
+
+                    VectorRecord {
+                        id: phago_id,
+                        vector: object.vector,
+                        metadata,
+                    }
+                })
+                .collect();
+            Ok((records, next))
+        })
+    }
+}
+
+/// Hybrid search with Weaviate's `hybrid` operator, which fuses BM25 over
+/// the record ID and metadata text with vector search. Each result's
+/// metadata carries Weaviate's `explainScore` under [`EXPLAIN_SCORE_KEY`].
+#[async_trait]
+impl HybridCapable for WeaviateStore {
+    async fn hybrid_search(
+        &self,
+        query_text: &str,
+        vector: Option<&[f32]>,
+        alpha: f32,
+        k: usize,
+        filter: Option<&Filter>,
+    ) -> VectorResult<Vec<SearchResult>> {
+        check_alpha(alpha)?;
+        let vector_arg = match vector {
+            Some(vector) if vector.len() != self.dimension => {
+                return Err(VectorError::DimensionMismatch {
+                    expected: self.dimension,
+                    actual: vector.len(),
+                });
+            }
+            Some(vector) => format!(
+                ", vector: [{}]",
+                vector
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => String::new(),
+        };
+        // A JSON string literal is also a valid GraphQL one
+        let query_arg = serde_json::to_string(query_text)
+            .map_err(|e| VectorError::Serialization(e.to_string()))?;
+
+        let query = format!(
+            r#"{{
+                Get {{
+                    {class_name}(
+                        hybrid: {{ query: {query_arg}, alpha: {alpha}{vector_arg} }}
+                        limit: {k}
+                        {where_clause}
+                    ) {{
+                        phago_id
+                        metadata_json
+                        _additional {{
+                            id
+                            score
+                            explainScore
+                            vector
+                        }}
+                    }}
+                }}
+            }}"#,
+            class_name = self.class_name,
+            where_clause = filter.map(where_clause).unwrap_or_default(),
+        );
+
+        let response = self.graphql(&query).await?;
+
+        if let Some(errors) = response.errors {
+            if !errors.is_empty() {
+                return Err(VectorError::Api(errors[0].message.clone()));
+            }
+        }
+
+        let results = response
+            .data
+            .and_then(|d| d.get)
+            .and_then(|mut g| g.remove(&self.class_name))
+            .unwrap_or_default();
+
+        Ok(results
+            .into_iter()
+            .map(|r| {
+                let additional = r._additional.unwrap_or_default();
+                let phago_id = r
+                    .properties
+                    .get("phago_id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                let mut metadata: HashMap<String, serde_json::Value> = r
+                    .properties
+                    .get("metadata_json")
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| serde_json::from_str(s).ok())
+                    .unwrap_or_default();
+                if let Some(explain) = additional.explain_score {
+                    metadata.insert(EXPLAIN_SCORE_KEY.to_string(), explain.into());
+                }
+
+                SearchResult {
+                    id: phago_id,
+                    score: additional.score.and_then(|s| s.parse().ok()).unwrap_or(0.0),
+                    vector: additional.vector,
+                    metadata,
+                }
+            })
+            .collect())
+    }
+}
+
+/// The `where` argument for a metadata filter, or nothing if the filter
+/// has no string conditions. Only string values are matched, against the
+/// stored metadata JSON.
+fn where_clause(filter: &Filter) -> String {
+    let conditions: Vec<String> = filter
+        .iter()
+        .filter_map(|(k, v)| {
+            match v {
+                serde_json::Value::String(s) => Some(format!(
+                    "{{ path: [\"metadata_json\"], operator: Contains, valueText: \"\\\"{}\\\":\\\"{}\\\"\" }}",
+                    k, s
+                )),
+                _ => None,
+            }
+        })
+        .collect();
+
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "where: {{ operator: And, operands: [{}] }}",
+            conditions.join(", ")
+        )
+    }
 }
 
 impl Default for AdditionalData {
@@ -603,6 +804,8 @@ impl Default for AdditionalData {
         Self {
             id: None,
             distance: None,
+            score: None,
+            explain_score: None,
             vector: None,
         }
     }
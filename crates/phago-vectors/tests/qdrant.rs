@@ -0,0 +1,178 @@
+//! Named vectors and payload indexes against a live Qdrant server.
+//!
+//! These tests run only when `PHAGO_QDRANT_URL` names a server (e.g.
+//! `http://localhost:6334`); without it they pass without doing anything.
+//! Each test works in its own collection and drops it afterwards.
+
+#![cfg(feature = "qdrant")]
+
+use phago_vectors::qdrant::{FieldKind, QdrantOptions, QdrantStore};
+use phago_vectors::{VectorError, VectorRecord, VectorStore};
+use qdrant_client::Qdrant;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// The server to test against, if one is configured.
+fn server() -> Option<String> {
+    std::env::var("PHAGO_QDRANT_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+}
+
+fn collection(test: &str) -> String {
+    format!("phago_test_{test}_{}", uuid::Uuid::new_v4().simple())
+}
+
+async fn drop_collection(url: &str, collection: &str) {
+    let client = Qdrant::from_url(url).build().unwrap();
+    let _ = client.delete_collection(collection).await;
+}
+
+/// A record with a fresh point ID (Qdrant IDs are UUIDs or integers).
+fn record(vector: Vec<f32>, kind: &str, doc_id: &str) -> VectorRecord {
+    VectorRecord::new(uuid::Uuid::new_v4().to_string(), vector)
+        .with_metadata("kind", kind)
+        .with_metadata("doc_id", doc_id)
+}
+
+fn shared_options(url: &str, collection: &str) -> QdrantOptions {
+    QdrantOptions::new(url, collection, 3)
+        .named_vector("concept", 3)
+        .named_vector("chunk", 4)
+        .index_field("kind", FieldKind::Keyword)
+        .index_field("doc_id", FieldKind::Keyword)
+}
+
+#[tokio::test]
+async fn concepts_and_chunks_share_one_collection() {
+    let Some(url) = server() else {
+        eprintln!("PHAGO_QDRANT_URL not set; skipping");
+        return;
+    };
+    let name = collection("named");
+    let store = QdrantStore::connect_with(shared_options(&url, &name))
+        .await
+        .unwrap();
+    assert_eq!(store.dimension(), 3);
+
+    store
+        .upsert(vec![
+            record(vec![1.0, 0.0, 0.0], "concept", "-"),
+            record(vec![0.0, 1.0, 0.0], "concept", "-"),
+        ])
+        .await
+        .unwrap();
+    store
+        .upsert_named(
+            Some("chunk"),
+            vec![
+                record(vec![1.0, 0.0, 0.0, 0.0], "chunk", "a"),
+                record(vec![0.0, 0.0, 0.0, 1.0], "chunk", "b"),
+            ],
+        )
+        .await
+        .unwrap();
+
+    // The default vector is "concept"; chunk points have none
+    let concepts = store.search(&[1.0, 0.0, 0.0], 10).await.unwrap();
+    assert_eq!(concepts.len(), 2);
+    assert!(concepts.iter().all(|r| r.metadata["kind"] == "concept"));
+    assert_eq!(concepts[0].vector.as_deref(), Some(&[1.0, 0.0, 0.0][..]));
+
+    let chunks = store
+        .search_named(Some("chunk"), &[0.0, 0.0, 0.0, 1.0], 10, &HashMap::new())
+        .await
+        .unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].metadata["doc_id"], "b");
+    assert_eq!(chunks[0].vector.as_ref().map(Vec::len), Some(4));
+
+    assert!(matches!(
+        store
+            .search_named(Some("chunk"), &[1.0, 0.0, 0.0], 1, &HashMap::new())
+            .await,
+        Err(VectorError::DimensionMismatch { expected: 4, .. })
+    ));
+    let missing = store
+        .search_named(Some("paragraph"), &[1.0, 0.0, 0.0], 1, &HashMap::new())
+        .await;
+    match missing {
+        Err(VectorError::Collection(message)) => {
+            assert!(
+                message.contains("paragraph") && message.contains("chunk"),
+                "{message}"
+            )
+        }
+        other => panic!("expected a collection error, got {other:?}"),
+    }
+
+    drop_collection(&url, &name).await;
+}
+
+#[tokio::test]
+async fn filtered_search_uses_the_payload_index() {
+    let Some(url) = server() else {
+        eprintln!("PHAGO_QDRANT_URL not set; skipping");
+        return;
+    };
+    let name = collection("filtered");
+    let store = QdrantStore::connect_with(shared_options(&url, &name))
+        .await
+        .unwrap();
+
+    let client = Qdrant::from_url(&url).build().unwrap();
+    let info = client.collection_info(&name).await.unwrap().result.unwrap();
+    assert!(info.payload_schema.contains_key("kind"));
+    assert!(info.payload_schema.contains_key("doc_id"));
+
+    store
+        .upsert_named(
+            Some("chunk"),
+            (0..6)
+                .map(|i| {
+                    let doc = if i % 2 == 0 { "a" } else { "b" };
+                    record(vec![1.0, i as f32, 0.0, 0.0], "chunk", doc)
+                })
+                .collect(),
+        )
+        .await
+        .unwrap();
+
+    let filter = HashMap::from([
+        ("kind".to_string(), json!("chunk")),
+        ("doc_id".to_string(), json!("b")),
+    ]);
+    let hits = store
+        .search_named(Some("chunk"), &[1.0, 0.0, 0.0, 0.0], 10, &filter)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 3);
+    assert!(hits.iter().all(|h| h.metadata["doc_id"] == "b"));
+
+    drop_collection(&url, &name).await;
+}
+
+#[tokio::test]
+async fn reconnecting_with_other_vectors_is_a_collection_error() {
+    let Some(url) = server() else {
+        eprintln!("PHAGO_QDRANT_URL not set; skipping");
+        return;
+    };
+    let name = collection("mismatch");
+    QdrantStore::connect_with(shared_options(&url, &name))
+        .await
+        .unwrap();
+
+    let renamed = QdrantOptions::new(&url, &name, 3).named_vector("paragraph", 3);
+    assert!(matches!(
+        QdrantStore::connect_with(renamed).await,
+        Err(VectorError::Collection(_))
+    ));
+    let unnamed = QdrantOptions::new(&url, &name, 3);
+    assert!(matches!(
+        QdrantStore::connect_with(unnamed).await,
+        Err(VectorError::Collection(_))
+    ));
+
+    drop_collection(&url, &name).await;
+}
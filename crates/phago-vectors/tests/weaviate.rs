@@ -0,0 +1,105 @@
+//! Hybrid search against a live Weaviate server.
+//!
+//! These tests run only when `PHAGO_WEAVIATE_URL` names a server (e.g.
+//! `http://localhost:8080`); without it they pass without doing anything.
+//! Each test works in its own class and drops it afterwards.
+
+#![cfg(feature = "weaviate")]
+
+use phago_vectors::{
+    Filter, HybridCapable, VectorError, VectorRecord, VectorStore, WeaviateStore, EXPLAIN_SCORE_KEY,
+};
+use serde_json::json;
+
+/// The server to test against, if one is configured.
+fn server() -> Option<String> {
+    std::env::var("PHAGO_WEAVIATE_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+}
+
+/// A fresh class name (Weaviate class names start with a capital).
+fn class(test: &str) -> String {
+    format!("PhagoTest{test}{}", uuid::Uuid::new_v4().simple())
+}
+
+async fn drop_class(url: &str, class: &str) {
+    let _ = reqwest::Client::new()
+        .delete(format!("{}/v1/schema/{}", url.trim_end_matches('/'), class))
+        .send()
+        .await;
+}
+
+async fn seeded_store(url: &str, class: &str) -> WeaviateStore {
+    let store = WeaviateStore::connect(url, None, class, 2).await.unwrap();
+    store
+        .upsert(vec![
+            VectorRecord::new("membrane", vec![0.0, 1.0])
+                .with_metadata("title", "cell membrane transport")
+                .with_metadata("type", "doc"),
+            VectorRecord::new("ribosome", vec![1.0, 0.0])
+                .with_metadata("title", "ribosome assembly")
+                .with_metadata("type", "doc"),
+            VectorRecord::new("wall", vec![0.6, 0.8])
+                .with_metadata("title", "cell wall")
+                .with_metadata("type", "note"),
+        ])
+        .await
+        .unwrap();
+    store
+}
+
+#[tokio::test]
+async fn alpha_moves_results_between_keywords_and_vectors() {
+    let Some(url) = server() else {
+        eprintln!("PHAGO_WEAVIATE_URL not set; skipping");
+        return;
+    };
+    let name = class("Alpha");
+    let store = seeded_store(&url, &name).await;
+    assert!(store.as_hybrid().is_some());
+
+    // Keywords only: the ribosome mentions neither word
+    let keyword = store
+        .hybrid_search("membrane", Some(&[1.0, 0.0]), 0.0, 3, None)
+        .await
+        .unwrap();
+    assert_eq!(keyword[0].id, "membrane");
+    assert_eq!(keyword[0].metadata["title"], "cell membrane transport");
+    assert!(keyword[0].metadata.contains_key(EXPLAIN_SCORE_KEY));
+
+    // Vectors only: nearest to [1, 0] whatever the text says
+    let vector = store
+        .hybrid_search("membrane", Some(&[1.0, 0.0]), 1.0, 1, None)
+        .await
+        .unwrap();
+    assert_eq!(vector[0].id, "ribosome");
+    assert!(vector[0].score > 0.0);
+
+    assert!(matches!(
+        store.hybrid_search("membrane", None, -0.1, 3, None).await,
+        Err(VectorError::Config(_))
+    ));
+
+    drop_class(&url, &name).await;
+}
+
+#[tokio::test]
+async fn hybrid_search_honours_filters() {
+    let Some(url) = server() else {
+        eprintln!("PHAGO_WEAVIATE_URL not set; skipping");
+        return;
+    };
+    let name = class("Filter");
+    let store = seeded_store(&url, &name).await;
+
+    let filter = Filter::from([("type".to_string(), json!("note"))]);
+    let hits = store
+        .hybrid_search("cell", Some(&[0.0, 1.0]), 0.5, 10, Some(&filter))
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, "wall");
+
+    drop_class(&url, &name).await;
+}
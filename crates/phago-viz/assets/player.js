@@ -0,0 +1,457 @@
+// Phago colony player: knowledge graph, agent canvas, event timeline and
+// metrics, scrubbed with a tick slider. Shared by phago-viz's standalone
+// HTML export and the phago-web replay view.
+//
+// Expects D3 v7 and the markup from player.html. Call once per page with
+// the recorded snapshots and the (tick, event) history.
+//
+// Snapshots are an array of full snapshots, or a snapshot series
+// (`{mode, frames}`) whose delta frames are rebuilt when they are shown.
+function snapshotSeries(recorded) {
+  if (Array.isArray(recorded)) return recorded;
+
+  const frames = recorded.frames;
+  const nodes = new Map();
+  let edges = [];
+  let cursor = -1; // frame `nodes` and `edges` currently hold
+
+  // Kept nodes and edges stay in place and new ones follow, as in
+  // SnapshotSeries::materialize; edge changes and removals refer to
+  // positions in the previous frame.
+  function apply(frame) {
+    if (frame.kind !== 'delta') {
+      nodes.clear();
+      (frame.nodes || []).forEach(n => nodes.set(n.id, n));
+      edges = (frame.edges || []).slice();
+      return;
+    }
+    (frame.removed_nodes || []).forEach(id => nodes.delete(id));
+    (frame.changed_nodes || []).forEach(n => nodes.set(n.id, n));
+    (frame.added_nodes || []).forEach(n => nodes.set(n.id, n));
+    (frame.changed_edges || []).forEach(c => {
+      const edge = edges[c.index];
+      if (!edge) return;
+      edges[c.index] = Object.assign({}, edge, {
+        weight: c.weight,
+        co_activations: c.co_activations ?? edge.co_activations,
+      });
+    });
+    const removed = new Set(frame.removed_edges || []);
+    edges = edges.filter((_, i) => !removed.has(i)).concat(frame.added_edges || []);
+  }
+
+  function at(idx) {
+    if (idx < 0) idx += frames.length;
+    if (idx < 0 || idx >= frames.length) return undefined;
+    // Going back, start over from the last full frame
+    let from = cursor + 1;
+    if (idx < cursor || cursor < 0) {
+      from = idx;
+      while (from > 0 && frames[from].kind === 'delta') from--;
+    }
+    for (let i = from; i <= idx; i++) apply(frames[i]);
+    cursor = idx;
+    const frame = frames[idx];
+    return {
+      tick: frame.tick,
+      agents: frame.agents,
+      stats: frame.stats,
+      world_bounds: frame.world_bounds,
+      regions: frame.regions,
+      nodes: [...nodes.values()],
+      edges,
+    };
+  }
+
+  return { length: frames.length, at };
+}
+
+function startPlayer(SNAPSHOTS, EVENTS) {
+  SNAPSHOTS = snapshotSeries(SNAPSHOTS);
+  if (SNAPSHOTS.length === 0) {
+    document.body.innerHTML = '<div style="padding:40px;color:#888">No snapshots recorded.</div>';
+    return;
+  }
+
+  const slider = document.getElementById('tick-slider');
+  const tickLabel = document.getElementById('tick-label');
+  const playBtn = document.getElementById('play-btn');
+  const tooltip = document.getElementById('tooltip');
+
+  slider.max = SNAPSHOTS.length - 1;
+  const lastTick = SNAPSHOTS.at(-1).tick;
+  let currentIdx = SNAPSHOTS.length - 1;
+  slider.value = currentIdx;
+  let playing = false;
+  let playInterval = null;
+
+  function showTooltip(text, x, y) {
+    tooltip.style.display = 'block';
+    tooltip.textContent = text;
+    tooltip.style.left = (x + 10) + 'px';
+    tooltip.style.top = (y - 20) + 'px';
+  }
+  function hideTooltip() { tooltip.style.display = 'none'; }
+
+  // --- Knowledge Graph ---
+  const graphSvg = d3.select('#graph-svg');
+  const graphG = graphSvg.append('g');
+  let graphSim = null;
+  const COMMUNITY_COLORS = [
+    '#4488cc', '#44bb88', '#cc7744', '#aa66cc', '#cc5588',
+    '#66bbcc', '#99aa44', '#7788ee', '#cc9966', '#55aa55'
+  ];
+
+  function updateGraph(snap) {
+    const width = document.getElementById('graph-panel').clientWidth;
+    const height = document.getElementById('graph-panel').clientHeight;
+
+    const nodeMap = {};
+    snap.nodes.forEach((n, i) => { nodeMap[n.label] = i; n.index = i; });
+
+    const links = snap.edges.filter(e => nodeMap[e.from_label] !== undefined && nodeMap[e.to_label] !== undefined)
+      .map(e => ({ source: nodeMap[e.from_label], target: nodeMap[e.to_label], weight: e.weight, co_activations: e.co_activations }));
+
+    // Concepts take their community's color once communities are tracked;
+    // scored anomalies shade from amber (review) to red as their score rises
+    const nodeColor = d => {
+      if (d.node_type === 'Insight') return '#ccaa22';
+      if (d.node_type === 'Anomaly') {
+        return d.anomaly_score === undefined ? '#cc4444' : d3.interpolateRgb('#ddaa44', '#cc2222')(d.anomaly_score);
+      }
+      if (d.community !== undefined) return COMMUNITY_COLORS[d.community % COMMUNITY_COLORS.length];
+      return '#4488cc';
+    };
+
+    // Scored anomalies grow with their score rather than their access count
+    const nodeRadius = d => d.anomaly_score !== undefined
+      ? 4 + d.anomaly_score * 11
+      : Math.max(3, Math.min(d.access_count * 1.5, 15));
+
+    // Links
+    const link = graphG.selectAll('line.graph-link').data(links, (d,i) => i);
+    link.exit().remove();
+    const linkEnter = link.enter().append('line').attr('class', 'graph-link');
+    const linkAll = linkEnter.merge(link)
+      .attr('stroke', '#334466').attr('stroke-opacity', d => Math.min(d.weight, 0.8))
+      .attr('stroke-width', d => Math.max(d.weight * 2, 0.5));
+
+    // Nodes
+    const node = graphG.selectAll('circle.graph-node').data(snap.nodes, d => d.label);
+    node.exit().remove();
+    const nodeEnter = node.enter().append('circle').attr('class', 'graph-node')
+      .on('mouseover', (ev, d) => showTooltip(`${d.label} (${d.node_type}) access:${d.access_count}`
+        + (d.community !== undefined ? ` community:${d.community}` : '')
+        + (d.anomaly_score !== undefined ? ` score:${d.anomaly_score.toFixed(2)}` : ''), ev.pageX, ev.pageY))
+      .on('mouseout', hideTooltip);
+    const nodeAll = nodeEnter.merge(node)
+      .attr('r', nodeRadius)
+      .attr('fill', nodeColor).attr('opacity', 0.85);
+
+    // Labels
+    const label = graphG.selectAll('text.node-label').data(snap.nodes, d => d.label);
+    label.exit().remove();
+    const labelEnter = label.enter().append('text').attr('class', 'node-label');
+    const labelAll = labelEnter.merge(label).text(d => d.label);
+
+    if (graphSim) graphSim.stop();
+    graphSim = d3.forceSimulation(snap.nodes)
+      .force('link', d3.forceLink(links).distance(60))
+      .force('charge', d3.forceManyBody().strength(-40))
+      .force('center', d3.forceCenter(width / 2, height / 2))
+      .on('tick', () => {
+        linkAll.attr('x1', d => d.source.x).attr('y1', d => d.source.y)
+               .attr('x2', d => d.target.x).attr('y2', d => d.target.y);
+        nodeAll.attr('cx', d => d.x).attr('cy', d => d.y);
+        labelAll.attr('x', d => d.x + 8).attr('y', d => d.y + 3);
+      });
+  }
+
+  // --- Agent Canvas ---
+  const agentSvg = d3.select('#agent-svg');
+
+  function updateAgents(snap) {
+    const width = document.getElementById('agent-panel').clientWidth;
+    const height = document.getElementById('agent-panel').clientHeight;
+
+    // Scale to the world bounds, or to the agents and regions in an
+    // unbounded world
+    const regions = snap.regions || [];
+    let minX = Infinity, maxX = -Infinity, minY = Infinity, maxY = -Infinity;
+    const include = (x, y) => {
+      minX = Math.min(minX, x); maxX = Math.max(maxX, x);
+      minY = Math.min(minY, y); maxY = Math.max(maxY, y);
+    };
+    if (snap.world_bounds) {
+      ({ x: minX, y: minY } = snap.world_bounds.min);
+      ({ x: maxX, y: maxY } = snap.world_bounds.max);
+    } else {
+      snap.agents.forEach(a => include(a.position.x, a.position.y));
+      regions.forEach(r => {
+        if (r.shape.rect) {
+          include(r.shape.rect.min.x, r.shape.rect.min.y);
+          include(r.shape.rect.max.x, r.shape.rect.max.y);
+        } else {
+          const { center, radius } = r.shape.circle;
+          include(center.x - radius, center.y - radius);
+          include(center.x + radius, center.y + radius);
+        }
+      });
+    }
+    const pad = 40;
+    const rangeX = Math.max(maxX - minX, 1);
+    const rangeY = Math.max(maxY - minY, 1);
+    const toX = x => pad + (x - minX) / rangeX * (width - 2 * pad);
+    const toY = y => pad + (y - minY) / rangeY * (height - 2 * pad);
+    const scaleX = d => toX(d.position.x);
+    const scaleY = d => toY(d.position.y);
+
+    // Regions, shaded behind the agents: warmer the faster they decay
+    const regionColor = r => {
+      const m = r.policy.edge_decay_multiplier;
+      if (m > 1) return '#cc6644';
+      if (m < 1) return '#4488cc';
+      return '#888888';
+    };
+    const regionTip = r => {
+      const p = r.policy;
+      const closed = [!p.allow_ingest && 'no ingest', !p.allow_agent_entry && 'no agent entry']
+        .filter(Boolean).join(', ');
+      return `${r.name} decay ×${p.edge_decay_multiplier}${closed ? ' — ' + closed : ''}`;
+    };
+    const shade = agentSvg.selectAll('g.region').data(regions, r => r.name);
+    shade.exit().remove();
+    const shadeEnter = shade.enter().insert('g', ':first-child').attr('class', 'region');
+    shadeEnter.append('path');
+    shadeEnter.append('text').attr('font-size', '10px').attr('fill', '#aaa');
+    const shadeAll = shadeEnter.merge(shade)
+      .on('mouseover', (ev, r) => showTooltip(regionTip(r), ev.pageX, ev.pageY))
+      .on('mouseout', hideTooltip);
+    shadeAll.select('path')
+      .attr('d', r => {
+        if (r.shape.rect) {
+          const { min, max } = r.shape.rect;
+          const x0 = toX(min.x), y0 = toY(min.y), x1 = toX(max.x), y1 = toY(max.y);
+          return `M${x0},${y0}H${x1}V${y1}H${x0}Z`;
+        }
+        const { center, radius } = r.shape.circle;
+        const cx = toX(center.x), cy = toY(center.y);
+        const rx = toX(center.x + radius) - cx, ry = toY(center.y + radius) - cy;
+        return `M${cx - rx},${cy}a${rx},${ry} 0 1,0 ${2 * rx},0a${rx},${ry} 0 1,0 ${-2 * rx},0Z`;
+      })
+      .attr('fill', regionColor).attr('fill-opacity', 0.12)
+      .attr('stroke', regionColor).attr('stroke-opacity', 0.4)
+      .attr('stroke-dasharray', r => r.policy.allow_agent_entry ? null : '4,3');
+    shadeAll.select('text')
+      .attr('x', r => r.shape.rect ? toX(r.shape.rect.min.x) + 4 : toX(r.shape.circle.center.x))
+      .attr('y', r => r.shape.rect ? toY(r.shape.rect.min.y) + 12 : toY(r.shape.circle.center.y))
+      .text(r => r.name);
+
+    const agentColor = d => {
+      if (d.agent_type === 'digester') return '#44cc44';
+      if (d.agent_type === 'sentinel') return '#cc4444';
+      if (d.agent_type === 'synthesizer') return '#aa44cc';
+      return '#888888';
+    };
+
+    const circ = agentSvg.selectAll('circle.agent').data(snap.agents, d => d.id.toString());
+    circ.exit().transition().duration(200).attr('r', 0).remove();
+    const circEnter = circ.enter().append('circle').attr('class', 'agent')
+      .attr('r', 0)
+      .on('mouseover', (ev, d) => showTooltip(`${d.agent_type} age:${d.age} perm:${d.permeability.toFixed(2)} vocab:${d.vocabulary_size}`, ev.pageX, ev.pageY))
+      .on('mouseout', hideTooltip);
+    circEnter.merge(circ).transition().duration(300)
+      .attr('cx', scaleX).attr('cy', scaleY)
+      .attr('r', 10)
+      .attr('fill', agentColor)
+      .attr('opacity', d => 0.3 + (1.0 - d.permeability) * 0.7)
+      .attr('stroke', '#ffffff22').attr('stroke-width', 1);
+
+    // Labels
+    const lbl = agentSvg.selectAll('text.agent-label').data(snap.agents, d => d.id.toString());
+    lbl.exit().remove();
+    const lblEnter = lbl.enter().append('text').attr('class', 'agent-label')
+      .attr('font-size', '9px').attr('fill', '#888');
+    lblEnter.merge(lbl).transition().duration(300)
+      .attr('x', d => scaleX(d) + 12).attr('y', d => scaleY(d) + 3)
+      .text(d => d.agent_type.slice(0, 3));
+  }
+
+  // --- Timeline ---
+  const timelineSvg = d3.select('#timeline-svg');
+
+  function initTimeline() {
+    const width = document.getElementById('timeline-panel').clientWidth;
+    const height = document.getElementById('timeline-panel').clientHeight;
+    const pad = { left: 40, right: 20, top: 25, bottom: 20 };
+
+    if (EVENTS.length === 0) return;
+
+    const maxTick = Math.max(...EVENTS.map(e => e[0]));
+    const x = d3.scaleLinear().domain([0, maxTick]).range([pad.left, width - pad.right]);
+
+    // Color by event type
+    const eventColor = e => {
+      const t = e[1];
+      if (t.CapabilityExported) return '#4488cc';
+      if (t.CapabilityIntegrated) return '#44aacc';
+      if (t.Symbiosis) return '#44cc44';
+      if (t.Dissolved) return '#ccaa22';
+      if (t.Died) return '#222222';
+      if (t.Presented) return '#666688';
+      return '#444444';
+    };
+
+    // Y jitter by type
+    const eventY = e => {
+      const t = e[1];
+      if (t.CapabilityExported || t.CapabilityIntegrated) return 0.2;
+      if (t.Symbiosis) return 0.4;
+      if (t.Dissolved) return 0.6;
+      if (t.Died) return 0.8;
+      return 0.5;
+    };
+
+    const significant = EVENTS.filter(e => {
+      const t = e[1];
+      return t.CapabilityExported || t.CapabilityIntegrated || t.Symbiosis || t.Dissolved || t.Died;
+    });
+
+    const yScale = d3.scaleLinear().domain([0, 1]).range([pad.top, height - pad.bottom]);
+
+    timelineSvg.selectAll('circle.event-dot').data(significant)
+      .enter().append('circle').attr('class', 'event-dot')
+      .attr('cx', d => x(d[0]))
+      .attr('cy', d => yScale(eventY(d)) + (Math.random() - 0.5) * 10)
+      .attr('r', 3)
+      .attr('fill', eventColor)
+      .attr('opacity', 0.7)
+      .on('mouseover', (ev, d) => {
+        const t = d[1];
+        const type = Object.keys(t)[0] || 'Event';
+        showTooltip(`Tick ${d[0]}: ${type}`, ev.pageX, ev.pageY);
+      })
+      .on('mouseout', hideTooltip);
+
+    // Tick cursor line
+    timelineSvg.append('line').attr('id', 'tick-cursor')
+      .attr('y1', pad.top).attr('y2', height - pad.bottom)
+      .attr('stroke', '#ff5555').attr('stroke-width', 1.5).attr('opacity', 0.6);
+
+    // Axis
+    timelineSvg.append('g').attr('transform', `translate(0,${height - pad.bottom})`)
+      .call(d3.axisBottom(x).ticks(10)).selectAll('text,line,path').attr('stroke', '#555577').attr('fill', '#555577');
+
+    // Legend
+    const legendData = [
+      ['Transfer', '#4488cc'], ['Symbiosis', '#44cc44'],
+      ['Dissolution', '#ccaa22'], ['Death', '#222222']
+    ];
+    const lg = timelineSvg.append('g').attr('transform', `translate(${width - 200}, 8)`);
+    legendData.forEach((d, i) => {
+      lg.append('circle').attr('cx', i * 50).attr('cy', 0).attr('r', 4).attr('fill', d[1]);
+      lg.append('text').attr('x', i * 50 + 7).attr('y', 3).text(d[0]).attr('fill', '#888').attr('font-size', '9px');
+    });
+  }
+
+  function updateTickCursor(snap) {
+    const width = document.getElementById('timeline-panel').clientWidth;
+    const pad = { left: 40, right: 20 };
+    if (EVENTS.length === 0) return;
+    const maxTick = Math.max(...EVENTS.map(e => e[0]));
+    const x = d3.scaleLinear().domain([0, maxTick]).range([pad.left, width - pad.right]);
+    d3.select('#tick-cursor').attr('x1', x(snap.tick)).attr('x2', x(snap.tick));
+  }
+
+  // --- Metrics ---
+  function updateMetrics(snap) {
+    document.getElementById('m-tick').textContent = snap.tick;
+    // Sampled snapshots show only part of the graph; say how much
+    const cov = snap.coverage;
+    document.getElementById('m-nodes').textContent = cov
+      ? cov.nodes_sampled + ' of ' + cov.nodes_total : snap.stats.graph_nodes;
+    document.getElementById('m-edges').textContent = cov
+      ? cov.edges_sampled + ' of ' + cov.edges_total : snap.stats.graph_edges;
+    document.getElementById('m-agents').textContent = snap.stats.agents_alive;
+    document.getElementById('m-docs').textContent = snap.stats.documents_digested + ' / ' + snap.stats.documents_total;
+
+    // Count events up to this tick
+    let transfers = 0, integrations = 0, symbioses = 0, dissolutions = 0, deaths = 0;
+    EVENTS.forEach(e => {
+      if (e[0] > snap.tick) return;
+      const t = e[1];
+      if (t.CapabilityExported) transfers++;
+      if (t.CapabilityIntegrated) integrations++;
+      if (t.Symbiosis) symbioses++;
+      if (t.Dissolved) dissolutions++;
+      if (t.Died) deaths++;
+    });
+    document.getElementById('m-transfers').textContent = transfers;
+    document.getElementById('m-integrations').textContent = integrations;
+    document.getElementById('m-symbioses').textContent = symbioses;
+    document.getElementById('m-dissolutions').textContent = dissolutions;
+    document.getElementById('m-deaths').textContent = deaths;
+
+    // Live signals per kind; custom kinds share the default color
+    const signals = d3.select('#signal-counts').selectAll('div.stat-row')
+      .data(Object.entries(snap.stats.signals_by_kind || {}), d => d[0]);
+    signals.exit().remove();
+    const row = signals.enter().append('div').attr('class', 'stat-row');
+    const label = row.append('span').attr('class', 'stat-label legend-item');
+    label.append('div').attr('class', 'legend-dot').style('background', d => signalColor(d[0]));
+    label.append('span').text(d => d[0]);
+    row.append('span').attr('class', 'stat-value');
+    row.merge(signals).select('.stat-value').text(d => d[1]);
+  }
+
+  function signalColor(kind) {
+    const colors = {
+      input: '#4488cc', presence: '#666688', quorum: '#44cc44',
+      anomaly: '#cc4444', insight: '#ccaa22', capability: '#44aacc',
+    };
+    return colors[kind] || '#aa66cc';
+  }
+
+  // --- Update all panels ---
+  function update(idx) {
+    if (idx < 0 || idx >= SNAPSHOTS.length) return;
+    currentIdx = idx;
+    slider.value = idx;
+    const snap = SNAPSHOTS.at(idx);
+    tickLabel.textContent = `Tick ${snap.tick} / ${lastTick}`;
+    updateGraph(snap);
+    updateAgents(snap);
+    updateTickCursor(snap);
+    updateMetrics(snap);
+  }
+
+  // --- Controls ---
+  slider.addEventListener('input', () => {
+    update(parseInt(slider.value));
+  });
+
+  playBtn.addEventListener('click', () => {
+    if (playing) {
+      playing = false;
+      clearInterval(playInterval);
+      playBtn.innerHTML = '&#9654; Play';
+    } else {
+      playing = true;
+      playBtn.innerHTML = '&#9646;&#9646; Pause';
+      if (currentIdx >= SNAPSHOTS.length - 1) currentIdx = 0;
+      playInterval = setInterval(() => {
+        if (currentIdx >= SNAPSHOTS.length - 1) {
+          playing = false;
+          clearInterval(playInterval);
+          playBtn.innerHTML = '&#9654; Play';
+          return;
+        }
+        update(currentIdx + 1);
+      }, 500);
+    }
+  });
+
+  // --- Init ---
+  initTimeline();
+  update(SNAPSHOTS.length - 1);
+}
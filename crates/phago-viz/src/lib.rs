@@ -7,408 +7,490 @@
 //! - Agent canvas (2D spatial view)
 //! - Event timeline
 //! - Metrics dashboard with tick slider
+//!
+//! The player itself (stylesheet, markup and playback script) is exposed
+//! separately so other front ends, like the phago-web replay view, can feed
+//! it data from an API instead of embedded constants.
+//!
+//! Snapshot series recorded in delta mode (see
+//! [`phago_runtime::snapshot::SnapshotMode`]) are embedded as they are and
+//! rebuilt in the page one frame at a time, so a long recording of a big
+//! colony never has to be held in full.
+//!
+//! Recorded data is untrusted: labels come straight from ingested
+//! documents. Embedded JSON is escaped so no label can end the script
+//! element, and recordings too large to embed are written to a data file
+//! the page fetches instead.
 
 use phago_core::types::Tick;
 use phago_runtime::colony::{ColonyEvent, ColonySnapshot};
-
-/// Generate a self-contained HTML file with D3.js visualization.
+use phago_runtime::snapshot::{
+    load_events, load_snapshot_series, SnapshotFrame, SnapshotMode, SnapshotSeries,
+};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::path::Path;
+use thiserror::Error;
+
+/// Stylesheet for the player layout.
+pub const PLAYER_CSS: &str = include_str!("../assets/player.css");
+
+/// Player markup: graph and agent panels, sidebar, timeline and controls.
+pub const PLAYER_MARKUP: &str = include_str!("../assets/player.html");
+
+/// Playback script. Defines `startPlayer(snapshots, events)`, which renders
+/// a snapshot series into [`PLAYER_MARKUP`] and wires up the slider and
+/// play button. `snapshots` is an array of full snapshots or a serialized
+/// [`SnapshotSeries`]. Requires D3 v7.
+pub const PLAYER_JS: &str = include_str!("../assets/player.js");
+
+/// D3 build the player is written against.
+pub const D3_URL: &str = "https://d3js.org/d3.v7.min.js";
+
+/// Assemble a player page around a data script.
 ///
-/// The HTML embeds all data as JSON constants and loads D3.js from CDN.
-/// No server, no npm — just open the file in a browser.
-pub fn generate_html(snapshots: &[ColonySnapshot], events: &[(Tick, ColonyEvent)]) -> String {
-    let snapshots_json = serde_json::to_string(snapshots).unwrap_or_else(|_| "[]".to_string());
-    let events_json = serde_json::to_string(events).unwrap_or_else(|_| "[]".to_string());
-
+/// `data_script` runs after [`PLAYER_JS`] is loaded and is responsible for
+/// calling `startPlayer` — with embedded constants, or after fetching the
+/// data from somewhere.
+pub fn player_page(title: &str, data_script: &str) -> String {
     format!(
         r##"<!DOCTYPE html>
 <html lang="en">
 <head>
 <meta charset="UTF-8">
 <meta name="viewport" content="width=device-width, initial-scale=1.0">
-<title>Phago Colony Visualization</title>
+<title>{title}</title>
 <style>
-* {{ margin: 0; padding: 0; box-sizing: border-box; }}
-body {{ background: #1a1a2e; color: #e0e0e0; font-family: 'Courier New', monospace; overflow: hidden; }}
-.container {{ display: grid; grid-template-columns: 1fr 1fr 280px; grid-template-rows: 1fr 200px 60px; height: 100vh; gap: 2px; background: #0f0f23; }}
-.panel {{ background: #1a1a2e; border: 1px solid #333366; border-radius: 4px; overflow: hidden; position: relative; }}
-.panel-title {{ position: absolute; top: 4px; left: 8px; font-size: 11px; color: #7777aa; text-transform: uppercase; letter-spacing: 1px; z-index: 10; }}
-#graph-panel {{ grid-column: 1; grid-row: 1; }}
-#agent-panel {{ grid-column: 2; grid-row: 1; }}
-#sidebar {{ grid-column: 3; grid-row: 1 / 3; padding: 12px; overflow-y: auto; }}
-#timeline-panel {{ grid-column: 1 / 3; grid-row: 2; }}
-#controls {{ grid-column: 1 / 4; grid-row: 3; display: flex; align-items: center; padding: 8px 16px; gap: 16px; }}
-#tick-slider {{ flex: 1; accent-color: #5555ff; }}
-#tick-label {{ font-size: 14px; color: #aaaadd; min-width: 100px; }}
-.stat-row {{ display: flex; justify-content: space-between; padding: 4px 0; border-bottom: 1px solid #222244; font-size: 12px; }}
-.stat-label {{ color: #8888bb; }}
-.stat-value {{ color: #ddddff; font-weight: bold; }}
-.section-title {{ color: #9999cc; font-size: 11px; text-transform: uppercase; letter-spacing: 1px; margin: 12px 0 6px 0; }}
-.legend {{ display: flex; flex-wrap: wrap; gap: 8px; margin: 8px 0; }}
-.legend-item {{ display: flex; align-items: center; gap: 4px; font-size: 10px; }}
-.legend-dot {{ width: 8px; height: 8px; border-radius: 50%; }}
-svg {{ width: 100%; height: 100%; }}
-.node-label {{ font-size: 9px; fill: #aaa; pointer-events: none; }}
-.tooltip {{ position: absolute; background: #222244; border: 1px solid #444488; padding: 6px 10px; border-radius: 4px; font-size: 11px; pointer-events: none; z-index: 100; display: none; }}
-h2 {{ font-size: 14px; color: #bbbbee; margin-bottom: 8px; }}
-#play-btn {{ background: #333366; border: 1px solid #5555aa; color: #ddddff; padding: 6px 16px; border-radius: 4px; cursor: pointer; font-family: inherit; }}
-#play-btn:hover {{ background: #444488; }}
-</style>
+{css}</style>
 </head>
 <body>
-<div class="container">
-  <div class="panel" id="graph-panel">
-    <div class="panel-title">Knowledge Graph</div>
-    <svg id="graph-svg"></svg>
-  </div>
-  <div class="panel" id="agent-panel">
-    <div class="panel-title">Agent Canvas</div>
-    <svg id="agent-svg"></svg>
-  </div>
-  <div class="panel" id="sidebar">
-    <h2>Phago Colony</h2>
-    <div class="section-title">Agents</div>
-    <div class="legend">
-      <div class="legend-item"><div class="legend-dot" style="background:#44cc44"></div> Digester</div>
-      <div class="legend-item"><div class="legend-dot" style="background:#cc4444"></div> Sentinel</div>
-      <div class="legend-item"><div class="legend-dot" style="background:#aa44cc"></div> Synthesizer</div>
-    </div>
-    <div class="section-title">Graph Nodes</div>
-    <div class="legend">
-      <div class="legend-item"><div class="legend-dot" style="background:#4488cc"></div> Concept</div>
-      <div class="legend-item"><div class="legend-dot" style="background:#ccaa22"></div> Insight</div>
-      <div class="legend-item"><div class="legend-dot" style="background:#cc4444"></div> Anomaly</div>
-    </div>
-    <div class="section-title">Metrics</div>
-    <div id="metrics-panel">
-      <div class="stat-row"><span class="stat-label">Tick</span><span class="stat-value" id="m-tick">0</span></div>
-      <div class="stat-row"><span class="stat-label">Nodes</span><span class="stat-value" id="m-nodes">0</span></div>
-      <div class="stat-row"><span class="stat-label">Edges</span><span class="stat-value" id="m-edges">0</span></div>
-      <div class="stat-row"><span class="stat-label">Agents Alive</span><span class="stat-value" id="m-agents">0</span></div>
-      <div class="stat-row"><span class="stat-label">Docs Digested</span><span class="stat-value" id="m-docs">0</span></div>
-    </div>
-    <div class="section-title">Events</div>
-    <div id="event-counts">
-      <div class="stat-row"><span class="stat-label">Transfers</span><span class="stat-value" id="m-transfers">0</span></div>
-      <div class="stat-row"><span class="stat-label">Integrations</span><span class="stat-value" id="m-integrations">0</span></div>
-      <div class="stat-row"><span class="stat-label">Symbioses</span><span class="stat-value" id="m-symbioses">0</span></div>
-      <div class="stat-row"><span class="stat-label">Dissolutions</span><span class="stat-value" id="m-dissolutions">0</span></div>
-      <div class="stat-row"><span class="stat-label">Deaths</span><span class="stat-value" id="m-deaths">0</span></div>
-    </div>
-  </div>
-  <div class="panel" id="timeline-panel">
-    <div class="panel-title">Event Timeline</div>
-    <svg id="timeline-svg"></svg>
-  </div>
-  <div id="controls">
-    <button id="play-btn">&#9654; Play</button>
-    <input type="range" id="tick-slider" min="0" max="0" value="0">
-    <span id="tick-label">Tick 0 / 0</span>
-  </div>
-</div>
-<div class="tooltip" id="tooltip"></div>
-
-<script src="https://d3js.org/d3.v7.min.js"></script>
+{markup}
+<script src="{d3}"></script>
 <script>
-const SNAPSHOTS = {snapshots};
-const EVENTS = {events};
-
-if (SNAPSHOTS.length === 0) {{
-  document.body.innerHTML = '<div style="padding:40px;color:#888">No snapshots recorded.</div>';
-}}
-
-const slider = document.getElementById('tick-slider');
-const tickLabel = document.getElementById('tick-label');
-const playBtn = document.getElementById('play-btn');
-const tooltip = document.getElementById('tooltip');
-
-slider.max = SNAPSHOTS.length - 1;
-let currentIdx = SNAPSHOTS.length - 1;
-slider.value = currentIdx;
-let playing = false;
-let playInterval = null;
-
-function showTooltip(text, x, y) {{
-  tooltip.style.display = 'block';
-  tooltip.textContent = text;
-  tooltip.style.left = (x + 10) + 'px';
-  tooltip.style.top = (y - 20) + 'px';
-}}
-function hideTooltip() {{ tooltip.style.display = 'none'; }}
-
-// --- Knowledge Graph ---
-const graphSvg = d3.select('#graph-svg');
-const graphG = graphSvg.append('g');
-let graphSim = null;
-
-function updateGraph(snap) {{
-  const width = document.getElementById('graph-panel').clientWidth;
-  const height = document.getElementById('graph-panel').clientHeight;
-
-  const nodeMap = {{}};
-  snap.nodes.forEach((n, i) => {{ nodeMap[n.label] = i; n.index = i; }});
-
-  const links = snap.edges.filter(e => nodeMap[e.from_label] !== undefined && nodeMap[e.to_label] !== undefined)
-    .map(e => ({{ source: nodeMap[e.from_label], target: nodeMap[e.to_label], weight: e.weight, co_activations: e.co_activations }}));
-
-  const nodeColor = d => {{
-    if (d.node_type === 'Insight') return '#ccaa22';
-    if (d.node_type === 'Anomaly') return '#cc4444';
-    return '#4488cc';
-  }};
-
-  // Links
-  const link = graphG.selectAll('line.graph-link').data(links, (d,i) => i);
-  link.exit().remove();
-  const linkEnter = link.enter().append('line').attr('class', 'graph-link');
-  const linkAll = linkEnter.merge(link)
-    .attr('stroke', '#334466').attr('stroke-opacity', d => Math.min(d.weight, 0.8))
-    .attr('stroke-width', d => Math.max(d.weight * 2, 0.5));
-
-  // Nodes
-  const node = graphG.selectAll('circle.graph-node').data(snap.nodes, d => d.label);
-  node.exit().remove();
-  const nodeEnter = node.enter().append('circle').attr('class', 'graph-node')
-    .on('mouseover', (ev, d) => showTooltip(`${{d.label}} (${{d.node_type}}) access:${{d.access_count}}`, ev.pageX, ev.pageY))
-    .on('mouseout', hideTooltip);
-  const nodeAll = nodeEnter.merge(node)
-    .attr('r', d => Math.max(3, Math.min(d.access_count * 1.5, 15)))
-    .attr('fill', nodeColor).attr('opacity', 0.85);
-
-  // Labels
-  const label = graphG.selectAll('text.node-label').data(snap.nodes, d => d.label);
-  label.exit().remove();
-  const labelEnter = label.enter().append('text').attr('class', 'node-label');
-  const labelAll = labelEnter.merge(label).text(d => d.label);
-
-  if (graphSim) graphSim.stop();
-  graphSim = d3.forceSimulation(snap.nodes)
-    .force('link', d3.forceLink(links).distance(60))
-    .force('charge', d3.forceManyBody().strength(-40))
-    .force('center', d3.forceCenter(width / 2, height / 2))
-    .on('tick', () => {{
-      linkAll.attr('x1', d => d.source.x).attr('y1', d => d.source.y)
-             .attr('x2', d => d.target.x).attr('y2', d => d.target.y);
-      nodeAll.attr('cx', d => d.x).attr('cy', d => d.y);
-      labelAll.attr('x', d => d.x + 8).attr('y', d => d.y + 3);
-    }});
-}}
-
-// --- Agent Canvas ---
-const agentSvg = d3.select('#agent-svg');
-
-function updateAgents(snap) {{
-  const width = document.getElementById('agent-panel').clientWidth;
-  const height = document.getElementById('agent-panel').clientHeight;
-
-  // Compute scale from agent positions
-  let minX = Infinity, maxX = -Infinity, minY = Infinity, maxY = -Infinity;
-  snap.agents.forEach(a => {{
-    minX = Math.min(minX, a.position.x); maxX = Math.max(maxX, a.position.x);
-    minY = Math.min(minY, a.position.y); maxY = Math.max(maxY, a.position.y);
-  }});
-  const pad = 40;
-  const rangeX = Math.max(maxX - minX, 1);
-  const rangeY = Math.max(maxY - minY, 1);
-  const scaleX = d => pad + (d.position.x - minX) / rangeX * (width - 2 * pad);
-  const scaleY = d => pad + (d.position.y - minY) / rangeY * (height - 2 * pad);
-
-  const agentColor = d => {{
-    if (d.agent_type === 'digester') return '#44cc44';
-    if (d.agent_type === 'sentinel') return '#cc4444';
-    if (d.agent_type === 'synthesizer') return '#aa44cc';
-    return '#888888';
-  }};
-
-  const circ = agentSvg.selectAll('circle.agent').data(snap.agents, d => d.id.toString());
-  circ.exit().transition().duration(200).attr('r', 0).remove();
-  const circEnter = circ.enter().append('circle').attr('class', 'agent')
-    .attr('r', 0)
-    .on('mouseover', (ev, d) => showTooltip(`${{d.agent_type}} age:${{d.age}} perm:${{d.permeability.toFixed(2)}} vocab:${{d.vocabulary_size}}`, ev.pageX, ev.pageY))
-    .on('mouseout', hideTooltip);
-  circEnter.merge(circ).transition().duration(300)
-    .attr('cx', scaleX).attr('cy', scaleY)
-    .attr('r', 10)
-    .attr('fill', agentColor)
-    .attr('opacity', d => 0.3 + (1.0 - d.permeability) * 0.7)
-    .attr('stroke', '#ffffff22').attr('stroke-width', 1);
-
-  // Labels
-  const lbl = agentSvg.selectAll('text.agent-label').data(snap.agents, d => d.id.toString());
-  lbl.exit().remove();
-  const lblEnter = lbl.enter().append('text').attr('class', 'agent-label')
-    .attr('font-size', '9px').attr('fill', '#888');
-  lblEnter.merge(lbl).transition().duration(300)
-    .attr('x', d => scaleX(d) + 12).attr('y', d => scaleY(d) + 3)
-    .text(d => d.agent_type.slice(0, 3));
-}}
-
-// --- Timeline ---
-const timelineSvg = d3.select('#timeline-svg');
-
-function initTimeline() {{
-  const width = document.getElementById('timeline-panel').clientWidth;
-  const height = document.getElementById('timeline-panel').clientHeight;
-  const pad = {{ left: 40, right: 20, top: 25, bottom: 20 }};
-
-  if (EVENTS.length === 0) return;
-
-  const maxTick = Math.max(...EVENTS.map(e => e[0]));
-  const x = d3.scaleLinear().domain([0, maxTick]).range([pad.left, width - pad.right]);
-
-  // Color by event type
-  const eventColor = e => {{
-    const t = e[1];
-    if (t.CapabilityExported) return '#4488cc';
-    if (t.CapabilityIntegrated) return '#44aacc';
-    if (t.Symbiosis) return '#44cc44';
-    if (t.Dissolved) return '#ccaa22';
-    if (t.Died) return '#222222';
-    if (t.Presented) return '#666688';
-    return '#444444';
-  }};
-
-  // Y jitter by type
-  const eventY = e => {{
-    const t = e[1];
-    if (t.CapabilityExported || t.CapabilityIntegrated) return 0.2;
-    if (t.Symbiosis) return 0.4;
-    if (t.Dissolved) return 0.6;
-    if (t.Died) return 0.8;
-    return 0.5;
-  }};
-
-  const significant = EVENTS.filter(e => {{
-    const t = e[1];
-    return t.CapabilityExported || t.CapabilityIntegrated || t.Symbiosis || t.Dissolved || t.Died;
-  }});
-
-  const yScale = d3.scaleLinear().domain([0, 1]).range([pad.top, height - pad.bottom]);
-
-  timelineSvg.selectAll('circle.event-dot').data(significant)
-    .enter().append('circle').attr('class', 'event-dot')
-    .attr('cx', d => x(d[0]))
-    .attr('cy', d => yScale(eventY(d)) + (Math.random() - 0.5) * 10)
-    .attr('r', 3)
-    .attr('fill', eventColor)
-    .attr('opacity', 0.7)
-    .on('mouseover', (ev, d) => {{
-      const t = d[1];
-      const type = Object.keys(t)[0] || 'Event';
-      showTooltip(`Tick ${{d[0]}}: ${{type}}`, ev.pageX, ev.pageY);
-    }})
-    .on('mouseout', hideTooltip);
-
-  // Tick cursor line
-  timelineSvg.append('line').attr('id', 'tick-cursor')
-    .attr('y1', pad.top).attr('y2', height - pad.bottom)
-    .attr('stroke', '#ff5555').attr('stroke-width', 1.5).attr('opacity', 0.6);
-
-  // Axis
-  timelineSvg.append('g').attr('transform', `translate(0,${{height - pad.bottom}})`)
-    .call(d3.axisBottom(x).ticks(10)).selectAll('text,line,path').attr('stroke', '#555577').attr('fill', '#555577');
-
-  // Legend
-  const legendData = [
-    ['Transfer', '#4488cc'], ['Symbiosis', '#44cc44'],
-    ['Dissolution', '#ccaa22'], ['Death', '#222222']
-  ];
-  const lg = timelineSvg.append('g').attr('transform', `translate(${{width - 200}}, 8)`);
-  legendData.forEach((d, i) => {{
-    lg.append('circle').attr('cx', i * 50).attr('cy', 0).attr('r', 4).attr('fill', d[1]);
-    lg.append('text').attr('x', i * 50 + 7).attr('y', 3).text(d[0]).attr('fill', '#888').attr('font-size', '9px');
-  }});
-}}
-
-function updateTickCursor(snap) {{
-  const width = document.getElementById('timeline-panel').clientWidth;
-  const pad = {{ left: 40, right: 20 }};
-  if (EVENTS.length === 0) return;
-  const maxTick = Math.max(...EVENTS.map(e => e[0]));
-  const x = d3.scaleLinear().domain([0, maxTick]).range([pad.left, width - pad.right]);
-  d3.select('#tick-cursor').attr('x1', x(snap.tick)).attr('x2', x(snap.tick));
-}}
-
-// --- Metrics ---
-function updateMetrics(snap) {{
-  document.getElementById('m-tick').textContent = snap.tick;
-  document.getElementById('m-nodes').textContent = snap.stats.graph_nodes;
-  document.getElementById('m-edges').textContent = snap.stats.graph_edges;
-  document.getElementById('m-agents').textContent = snap.stats.agents_alive;
-  document.getElementById('m-docs').textContent = snap.stats.documents_digested + ' / ' + snap.stats.documents_total;
-
-  // Count events up to this tick
-  let transfers = 0, integrations = 0, symbioses = 0, dissolutions = 0, deaths = 0;
-  EVENTS.forEach(e => {{
-    if (e[0] > snap.tick) return;
-    const t = e[1];
-    if (t.CapabilityExported) transfers++;
-    if (t.CapabilityIntegrated) integrations++;
-    if (t.Symbiosis) symbioses++;
-    if (t.Dissolved) dissolutions++;
-    if (t.Died) deaths++;
-  }});
-  document.getElementById('m-transfers').textContent = transfers;
-  document.getElementById('m-integrations').textContent = integrations;
-  document.getElementById('m-symbioses').textContent = symbioses;
-  document.getElementById('m-dissolutions').textContent = dissolutions;
-  document.getElementById('m-deaths').textContent = deaths;
-}}
-
-// --- Update all panels ---
-function update(idx) {{
-  if (idx < 0 || idx >= SNAPSHOTS.length) return;
-  currentIdx = idx;
-  slider.value = idx;
-  const snap = SNAPSHOTS[idx];
-  tickLabel.textContent = `Tick ${{snap.tick}} / ${{SNAPSHOTS[SNAPSHOTS.length - 1].tick}}`;
-  updateGraph(snap);
-  updateAgents(snap);
-  updateTickCursor(snap);
-  updateMetrics(snap);
-}}
-
-// --- Controls ---
-slider.addEventListener('input', () => {{
-  update(parseInt(slider.value));
-}});
-
-playBtn.addEventListener('click', () => {{
-  if (playing) {{
-    playing = false;
-    clearInterval(playInterval);
-    playBtn.innerHTML = '&#9654; Play';
-  }} else {{
-    playing = true;
-    playBtn.innerHTML = '&#9646;&#9646; Pause';
-    if (currentIdx >= SNAPSHOTS.length - 1) currentIdx = 0;
-    playInterval = setInterval(() => {{
-      if (currentIdx >= SNAPSHOTS.length - 1) {{
-        playing = false;
-        clearInterval(playInterval);
-        playBtn.innerHTML = '&#9654; Play';
-        return;
-      }}
-      update(currentIdx + 1);
-    }}, 500);
-  }}
-}});
-
-// --- Init ---
-initTimeline();
-update(SNAPSHOTS.length - 1);
+{player}</script>
+<script>
+{data_script}
 </script>
 </body>
 </html>"##,
-        snapshots = snapshots_json,
-        events = events_json,
+        css = PLAYER_CSS,
+        markup = PLAYER_MARKUP,
+        d3 = D3_URL,
+        player = PLAYER_JS,
     )
 }
 
+const PAGE_TITLE: &str = "Phago Colony Visualization";
+
+/// Errors from building a visualization.
+#[derive(Debug, Error)]
+pub enum VizError {
+    #[error("failed to load recording: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("failed to serialize recording: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    /// The data does not fit in a single file; use [`render`] to write it
+    /// to a separate data file.
+    #[error("recording is {size} bytes, over the {limit} byte limit for embedded data")]
+    TooLarge { size: usize, limit: usize },
+}
+
+/// Settings for [`render`].
+#[derive(Debug, Clone)]
+pub struct VizOptions {
+    /// Largest data payload, in bytes, embedded in the page. Bigger
+    /// recordings are written to [`data_file`](Self::data_file) instead.
+    pub max_embedded_bytes: usize,
+    /// Node labels longer than this many characters are cut short and end
+    /// in `…`.
+    pub max_label_chars: usize,
+    /// Name of the data file, relative to the page, when data is not
+    /// embedded.
+    pub data_file: String,
+}
+
+impl Default for VizOptions {
+    fn default() -> Self {
+        Self {
+            max_embedded_bytes: 32 * 1024 * 1024,
+            max_label_chars: 200,
+            data_file: "data.json".to_string(),
+        }
+    }
+}
+
+/// A rendered visualization.
+#[derive(Debug, Clone)]
+pub struct VizPage {
+    pub html: String,
+    /// Set when the data was too large to embed: the file the page fetches.
+    pub external_data: Option<ExternalData>,
+}
+
+/// Data the page loads at runtime instead of embedding it.
+#[derive(Debug, Clone)]
+pub struct ExternalData {
+    /// File name relative to the page.
+    pub file_name: String,
+    /// `{"snapshots": [...], "events": [...]}`.
+    pub json: String,
+}
+
+impl VizPage {
+    /// Write the page to `path`, and its data file, if any, next to it.
+    ///
+    /// Browsers block `fetch` from `file://` pages, so a page with external
+    /// data has to be served over HTTP (e.g. `python3 -m http.server`).
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, &self.html)?;
+        if let Some(data) = &self.external_data {
+            let dir = path.parent().unwrap_or(Path::new(""));
+            std::fs::write(dir.join(&data.file_name), &data.json)?;
+        }
+        Ok(())
+    }
+}
+
+/// Render a recording as a player page.
+///
+/// Node labels are elided to `options.max_label_chars`. The data is
+/// embedded as JSON constants unless it is larger than
+/// `options.max_embedded_bytes`, in which case it goes into
+/// [`VizPage::external_data`] and the page fetches it.
+///
+/// # Errors
+///
+/// Returns `VizError::Serialize` if the recording cannot be serialized.
+pub fn render(
+    snapshots: &[ColonySnapshot],
+    events: &[(Tick, ColonyEvent)],
+    options: &VizOptions,
+) -> Result<VizPage, VizError> {
+    let snapshots = elide_labels(snapshots, options.max_label_chars);
+    page(serde_json::to_string(&*snapshots)?, events, options)
+}
+
+/// Render a recorded [`SnapshotSeries`] as a player page, as [`render`]
+/// does for full snapshots.
+///
+/// Full series are embedded as a snapshot array, exactly as [`render`]
+/// would; delta and stats-only series are embedded frame by frame.
+pub fn render_series(
+    series: &SnapshotSeries,
+    events: &[(Tick, ColonyEvent)],
+    options: &VizOptions,
+) -> Result<VizPage, VizError> {
+    let frames = elide_frame_labels(series.frames(), options.max_label_chars);
+    let full: Option<Vec<&ColonySnapshot>> = frames
+        .iter()
+        .map(|frame| match frame {
+            SnapshotFrame::Full(snapshot) => Some(snapshot),
+            _ => None,
+        })
+        .collect();
+    let snapshots_json = match full {
+        Some(snapshots) => serde_json::to_string(&snapshots)?,
+        None => serde_json::to_string(&SeriesJson {
+            mode: series.mode(),
+            frames: &frames,
+        })?,
+    };
+    page(snapshots_json, events, options)
+}
+
+/// The serialized form of a [`SnapshotSeries`].
+#[derive(Serialize)]
+struct SeriesJson<'a> {
+    mode: SnapshotMode,
+    frames: &'a [SnapshotFrame],
+}
+
+fn page(
+    snapshots_json: String,
+    events: &[(Tick, ColonyEvent)],
+    options: &VizOptions,
+) -> Result<VizPage, VizError> {
+    let events_json = serde_json::to_string(events)?;
+
+    if snapshots_json.len() + events_json.len() <= options.max_embedded_bytes {
+        let data_script = format!(
+            "const SNAPSHOTS = {};\nconst EVENTS = {};\nstartPlayer(SNAPSHOTS, EVENTS);",
+            script_safe(&snapshots_json),
+            script_safe(&events_json),
+        );
+        return Ok(VizPage {
+            html: player_page(PAGE_TITLE, &data_script),
+            external_data: None,
+        });
+    }
+
+    let url = script_safe(&serde_json::to_string(&options.data_file)?);
+    let data_script = format!(
+        "fetch({url})\n  .then(r => r.json())\n  .then(d => startPlayer(d.snapshots, d.events));"
+    );
+    Ok(VizPage {
+        html: player_page(PAGE_TITLE, &data_script),
+        external_data: Some(ExternalData {
+            file_name: options.data_file.clone(),
+            json: format!("{{\"snapshots\":{snapshots_json},\"events\":{events_json}}}"),
+        }),
+    })
+}
+
+/// Generate a self-contained HTML file with D3.js visualization.
+///
+/// The HTML embeds all data as JSON constants and loads D3.js from CDN.
+/// No server, no npm — just open the file in a browser.
+///
+/// Sampled snapshots ([`Colony::sample_snapshot`]) render like any other;
+/// the metrics panel then shows how many of the graph's nodes and edges
+/// they hold.
+///
+/// [`Colony::sample_snapshot`]: phago_runtime::colony::Colony::sample_snapshot
+///
+/// # Errors
+///
+/// Returns `VizError::TooLarge` if the data exceeds the default
+/// [`VizOptions::max_embedded_bytes`]; use [`render`] for such recordings.
+pub fn generate_html(
+    snapshots: &[ColonySnapshot],
+    events: &[(Tick, ColonyEvent)],
+) -> Result<String, VizError> {
+    single_file(render(snapshots, events, &VizOptions::default())?)
+}
+
+/// Generate a self-contained HTML file from a recorded [`SnapshotSeries`],
+/// as [`generate_html`] does for full snapshots.
+///
+/// # Errors
+///
+/// Returns `VizError::TooLarge` if the data exceeds the default
+/// [`VizOptions::max_embedded_bytes`]; use [`render_series`] for such
+/// recordings.
+pub fn generate_html_series(
+    series: &SnapshotSeries,
+    events: &[(Tick, ColonyEvent)],
+) -> Result<String, VizError> {
+    single_file(render_series(series, events, &VizOptions::default())?)
+}
+
+/// Render the visualization from recorded files.
+///
+/// Both files may be a JSON array or JSONL (see
+/// [`phago_runtime::snapshot`]); the snapshots may also be a serialized
+/// [`SnapshotSeries`]. Without an events file the timeline is empty.
+pub fn render_files(
+    snapshots_path: &Path,
+    events_path: Option<&Path>,
+    options: &VizOptions,
+) -> Result<VizPage, VizError> {
+    let series = load_snapshot_series(snapshots_path)?;
+    let events = match events_path {
+        Some(path) => load_events(path)?,
+        None => Vec::new(),
+    };
+    render_series(&series, &events, options)
+}
+
+/// Generate a self-contained visualization from recorded files, as
+/// [`generate_html`] does for in-memory data.
+pub fn generate_html_from_files(
+    snapshots_path: &Path,
+    events_path: Option<&Path>,
+) -> Result<String, VizError> {
+    single_file(render_files(
+        snapshots_path,
+        events_path,
+        &VizOptions::default(),
+    )?)
+}
+
+fn single_file(page: VizPage) -> Result<String, VizError> {
+    match page.external_data {
+        None => Ok(page.html),
+        Some(data) => Err(VizError::TooLarge {
+            size: data.json.len(),
+            limit: VizOptions::default().max_embedded_bytes,
+        }),
+    }
+}
+
+/// Escape JSON for a `<script>` element.
+///
+/// `<`, `>` and `&` only occur inside JSON strings, where `\u` escapes
+/// keep the value intact while ruling out `</script>` and `<!--`. U+2028
+/// and U+2029 are line terminators to older JavaScript engines.
+fn script_safe(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    for c in json.chars() {
+        match c {
+            '<' => out.push_str("\\u003c"),
+            '>' => out.push_str("\\u003e"),
+            '&' => out.push_str("\\u0026"),
+            '\u{2028}' => out.push_str("\\u2028"),
+            '\u{2029}' => out.push_str("\\u2029"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Snapshots with node and edge labels cut to `max_chars`. Edges are cut
+/// the same way so they still find their nodes; labels sharing a long
+/// prefix end up identical.
+fn elide_labels(snapshots: &[ColonySnapshot], max_chars: usize) -> Cow<'_, [ColonySnapshot]> {
+    let too_long = |label: &String| label.chars().count() > max_chars;
+    let any_too_long = snapshots.iter().any(|s| {
+        s.nodes.iter().any(|n| too_long(&n.label))
+            || s.edges
+                .iter()
+                .any(|e| too_long(&e.from_label) || too_long(&e.to_label))
+    });
+    if !any_too_long {
+        return Cow::Borrowed(snapshots);
+    }
+
+    let elide = |label: &mut String| {
+        if too_long(label) {
+            *label = label.chars().take(max_chars).chain(['…']).collect();
+        }
+    };
+    let mut snapshots = snapshots.to_vec();
+    for snapshot in &mut snapshots {
+        for node in &mut snapshot.nodes {
+            elide(&mut node.label);
+        }
+        for edge in &mut snapshot.edges {
+            elide(&mut edge.from_label);
+            elide(&mut edge.to_label);
+        }
+    }
+    Cow::Owned(snapshots)
+}
+
+/// [`elide_labels`] for series frames.
+fn elide_frame_labels(frames: &[SnapshotFrame], max_chars: usize) -> Cow<'_, [SnapshotFrame]> {
+    let too_long = |label: &String| label.chars().count() > max_chars;
+    let any_too_long = frames.iter().any(|frame| match frame {
+        SnapshotFrame::Full(snapshot) => matches!(
+            elide_labels(std::slice::from_ref(snapshot), max_chars),
+            Cow::Owned(_)
+        ),
+        SnapshotFrame::Stats(_) => false,
+        SnapshotFrame::Delta(delta) => {
+            let nodes = delta.added_nodes.iter().chain(&delta.changed_nodes);
+            let edges = delta.added_edges.iter();
+            nodes.map(|n| &n.label).any(too_long)
+                || edges
+                    .flat_map(|e| [&e.from_label, &e.to_label])
+                    .any(too_long)
+        }
+    });
+    if !any_too_long {
+        return Cow::Borrowed(frames);
+    }
+
+    let elide = |label: &mut String| {
+        if too_long(label) {
+            *label = label.chars().take(max_chars).chain(['…']).collect();
+        }
+    };
+    let mut frames = frames.to_vec();
+    for frame in &mut frames {
+        match frame {
+            SnapshotFrame::Full(snapshot) => {
+                if let Cow::Owned(mut elided) =
+                    elide_labels(std::slice::from_ref(snapshot), max_chars)
+                {
+                    *snapshot = elided.remove(0);
+                }
+            }
+            SnapshotFrame::Stats(_) => {}
+            SnapshotFrame::Delta(delta) => {
+                for node in delta.added_nodes.iter_mut().chain(&mut delta.changed_nodes) {
+                    elide(&mut node.label);
+                }
+                for edge in &mut delta.added_edges {
+                    elide(&mut edge.from_label);
+                    elide(&mut edge.to_label);
+                }
+            }
+        }
+    }
+    Cow::Owned(frames)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use phago_core::types::*;
-    use phago_runtime::colony::{AgentSnapshot, ColonySnapshot, ColonyStats, NodeSnapshot};
+    use phago_runtime::colony::{
+        AgentSnapshot, ColonySnapshot, ColonyStats, EdgeSnapshot, NodeSnapshot,
+    };
+
+    fn graph_snapshot(labels: &[&str]) -> ColonySnapshot {
+        ColonySnapshot {
+            tick: 1,
+            agents: vec![],
+            nodes: labels
+                .iter()
+                .map(|label| NodeSnapshot {
+                    id: NodeId::new(),
+                    label: label.to_string(),
+                    node_type: NodeType::Concept,
+                    position: Position::new(0.0, 0.0),
+                    access_count: 1,
+                    tags: Default::default(),
+                    community: None,
+                    embedding: None,
+                    anomaly_score: None,
+                })
+                .collect(),
+            edges: labels
+                .windows(2)
+                .map(|pair| EdgeSnapshot {
+                    from_label: pair[0].to_string(),
+                    to_label: pair[1].to_string(),
+                    weight: 0.5,
+                    co_activations: 1,
+                })
+                .collect(),
+            stats: ColonyStats {
+                tick: 1,
+                agents_alive: 0,
+                agents_died: 0,
+                total_spawned: 0,
+                graph_nodes: labels.len(),
+                graph_edges: labels.len().saturating_sub(1),
+                total_signals: 0,
+                signals_by_kind: Default::default(),
+                documents_total: 1,
+                documents_digested: 1,
+                trace_bytes: 0,
+                traces_evicted: 0,
+                signals_merged: 0,
+                signals_evicted: 0,
+                errors: Default::default(),
+                decay: Default::default(),
+                maintenance_backlog: 0,
+                documents_expiring: 0,
+                next_expiry_tick: None,
+                exploitation: 0.0,
+            },
+            world_bounds: None,
+            regions: Vec::new(),
+            coverage: None,
+        }
+    }
+
+    /// The JSON assigned to `const name = ...;` in the data script.
+    fn embedded_constant<'a>(html: &'a str, name: &str) -> &'a str {
+        let start = html.find(&format!("const {name} = ")).unwrap() + name.len() + 9;
+        let end = start + html[start..].find(";\n").unwrap();
+        &html[start..end]
+    }
 
     #[test]
     fn html_contains_required_elements() {
@@ -428,6 +510,10 @@ mod tests {
                 node_type: NodeType::Concept,
                 position: Position::new(0.0, 0.0),
                 access_count: 3,
+                tags: Default::default(),
+                community: None,
+                embedding: None,
+                anomaly_score: None,
             }],
             edges: vec![],
             stats: ColonyStats {
@@ -438,12 +524,26 @@ mod tests {
                 graph_nodes: 1,
                 graph_edges: 0,
                 total_signals: 0,
+                signals_by_kind: Default::default(),
                 documents_total: 1,
                 documents_digested: 1,
+                trace_bytes: 0,
+                traces_evicted: 0,
+                signals_merged: 0,
+                signals_evicted: 0,
+                errors: Default::default(),
+                decay: Default::default(),
+                maintenance_backlog: 0,
+                documents_expiring: 0,
+                next_expiry_tick: None,
+                exploitation: 0.0,
             },
+            world_bounds: None,
+            regions: Vec::new(),
+            coverage: None,
         };
 
-        let html = generate_html(&[snapshot], &[]);
+        let html = generate_html(&[snapshot], &[]).unwrap();
         assert!(html.contains("<html"), "should contain html tag");
         assert!(html.contains("d3.v7"), "should reference D3 v7");
         assert!(html.contains("SNAPSHOTS"), "should embed snapshot data");
@@ -451,12 +551,197 @@ mod tests {
         assert!(html.contains("digester"), "should contain agent data");
     }
 
+    #[test]
+    fn player_page_shares_the_player_with_generate_html() {
+        let page = player_page("Replay", "fetch('/api/snapshots');");
+        assert!(page.contains("<title>Replay</title>"));
+        assert!(page.contains("function startPlayer(SNAPSHOTS, EVENTS)"));
+        assert!(page.contains("id=\"tick-slider\""));
+        assert!(page.contains("fetch('/api/snapshots');"));
+        assert!(!page.contains("const SNAPSHOTS"));
+
+        let html = generate_html(&[], &[]).unwrap();
+        assert!(html.contains(PLAYER_JS));
+        assert!(html.contains("startPlayer(SNAPSHOTS, EVENTS);"));
+    }
+
+    #[test]
+    fn html_from_recorded_files() {
+        let dir = std::env::temp_dir();
+        let snapshots_path = dir.join("phago_viz_snapshots.jsonl");
+        let events_path = dir.join("phago_viz_events.json");
+        let snapshot = r#"{"tick":3,"agents":[],"nodes":[{"id":"c3b1c7da-25ef-438d-9f14-a7f65251e539","label":"membrane","node_type":"Concept","position":{"x":0.0,"y":0.0},"access_count":2}],"edges":[],"stats":{"tick":3,"agents_alive":0,"agents_died":0,"total_spawned":0,"graph_nodes":1,"graph_edges":0,"total_signals":0,"documents_total":1,"documents_digested":1}}"#;
+        std::fs::write(&snapshots_path, format!("{snapshot}\n")).unwrap();
+        std::fs::write(
+            &events_path,
+            r#"[[3, {"TickComplete": {"tick": 3, "alive": 0, "dead_this_tick": 0}}]]"#,
+        )
+        .unwrap();
+
+        let html = generate_html_from_files(&snapshots_path, Some(&events_path)).unwrap();
+        assert!(html.contains("membrane"));
+        assert!(html.contains("TickComplete"));
+
+        let missing = dir.join("phago_viz_missing.json");
+        assert!(matches!(
+            generate_html_from_files(&missing, None),
+            Err(VizError::Io(_))
+        ));
+
+        std::fs::remove_file(&snapshots_path).ok();
+        std::fs::remove_file(&events_path).ok();
+    }
+
     #[test]
     fn html_empty_data_does_not_panic() {
-        let html = generate_html(&[], &[]);
+        let html = generate_html(&[], &[]).unwrap();
         assert!(
             html.contains("<html"),
             "should produce valid html even with empty data"
         );
     }
+
+    #[test]
+    fn hostile_labels_stay_inside_the_data_script() {
+        let labels = [
+            "</script><script>alert(1)</script>",
+            "\"quoted\" 'single' `backtick` ${x}",
+            "<!-- comment --> & \u{2028}\u{2029}",
+            "🧬 DNA 𝔘𝔫𝔦𝔠𝔬𝔡𝔢",
+        ];
+        let html = generate_html(&[graph_snapshot(&labels)], &[]).unwrap();
+
+        // Every script element closes where the page means it to: the
+        // last one right after the player is started.
+        let lower = html.to_lowercase();
+        assert_eq!(lower.matches("<script").count(), 3);
+        assert_eq!(lower.matches("</script").count(), 3);
+        assert!(!lower.contains("<!--"));
+        let data_start = html.find("const SNAPSHOTS").unwrap();
+        let data_end = data_start + lower[data_start..].find("</script").unwrap();
+        assert!(html[..data_end]
+            .trim_end()
+            .ends_with("startPlayer(SNAPSHOTS, EVENTS);"));
+
+        let snapshots: Vec<ColonySnapshot> =
+            serde_json::from_str(embedded_constant(&html, "SNAPSHOTS")).unwrap();
+        let round_tripped: Vec<&str> = snapshots[0]
+            .nodes
+            .iter()
+            .map(|n| n.label.as_str())
+            .collect();
+        assert_eq!(round_tripped, labels);
+        assert_eq!(snapshots[0].edges[0].from_label, labels[0]);
+        let events: Vec<(Tick, ColonyEvent)> =
+            serde_json::from_str(embedded_constant(&html, "EVENTS")).unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn long_labels_are_elided_on_nodes_and_edges() {
+        let long = "x".repeat(500);
+        let options = VizOptions {
+            max_label_chars: 10,
+            ..VizOptions::default()
+        };
+        let page = render(&[graph_snapshot(&[&long, "cell"])], &[], &options).unwrap();
+
+        let snapshots: Vec<ColonySnapshot> =
+            serde_json::from_str(embedded_constant(&page.html, "SNAPSHOTS")).unwrap();
+        let elided = format!("{}…", "x".repeat(10));
+        assert_eq!(snapshots[0].nodes[0].label, elided);
+        assert_eq!(snapshots[0].nodes[1].label, "cell");
+        assert_eq!(snapshots[0].edges[0].from_label, elided);
+    }
+
+    #[test]
+    fn sampled_snapshots_carry_their_coverage_into_the_page() {
+        let mut colony = phago_runtime::colony::Colony::new();
+        colony.ingest_document(
+            "Cells",
+            "cell membrane protein transport",
+            Position::new(0.0, 0.0),
+        );
+        colony.spawn(Box::new(phago_runtime::prelude::Digester::new(
+            Position::new(0.0, 0.0),
+        )));
+        colony.run(10);
+        let strategy = phago_runtime::sampling::SampleStrategy::TopByAccess { n: 2 };
+        let sampled = colony.sample_snapshot(&strategy.into());
+
+        let html = generate_html(&[sampled], &[]).unwrap();
+        let embedded: serde_json::Value =
+            serde_json::from_str(embedded_constant(&html, "SNAPSHOTS")).unwrap();
+        let coverage = &embedded[0]["coverage"];
+        assert_eq!(coverage["nodes_sampled"], 2);
+        assert_eq!(coverage["nodes_total"], colony.snapshot().nodes.len());
+        assert_eq!(embedded[0]["nodes"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn full_series_render_like_snapshot_arrays() {
+        let snapshots = vec![graph_snapshot(&["cell", "membrane"])];
+        let series = SnapshotSeries::from(snapshots.clone());
+        assert_eq!(
+            generate_html_series(&series, &[]).unwrap(),
+            generate_html(&snapshots, &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn delta_series_are_embedded_frame_by_frame() {
+        let long = "x".repeat(500);
+        let mut series = SnapshotSeries::new(SnapshotMode::Delta);
+        series.push(graph_snapshot(&["cell"]));
+        let mut grown = graph_snapshot(&["cell", &long]);
+        grown.nodes[0].id = series.materialize(0).unwrap().nodes[0].id;
+        grown.tick = 2;
+        series.push(grown);
+        let options = VizOptions {
+            max_label_chars: 10,
+            ..VizOptions::default()
+        };
+        let page = render_series(&series, &[], &options).unwrap();
+
+        let embedded: serde_json::Value =
+            serde_json::from_str(embedded_constant(&page.html, "SNAPSHOTS")).unwrap();
+        assert_eq!(embedded["mode"], "delta");
+        assert_eq!(embedded["frames"][0]["kind"], "full");
+        let delta = &embedded["frames"][1];
+        assert_eq!(delta["kind"], "delta");
+        let elided = format!("{}…", "x".repeat(10));
+        assert_eq!(delta["added_nodes"][0]["label"], elided.as_str());
+        assert_eq!(delta["added_edges"][0]["to_label"], elided.as_str());
+        assert!(delta.get("changed_nodes").is_none());
+    }
+
+    #[test]
+    fn oversized_recordings_are_fetched_from_a_data_file() {
+        let snapshot = graph_snapshot(&["membrane", "</script>"]);
+        let options = VizOptions {
+            max_embedded_bytes: 64,
+            ..VizOptions::default()
+        };
+        let page = render(std::slice::from_ref(&snapshot), &[], &options).unwrap();
+
+        assert!(!page.html.contains("const SNAPSHOTS"));
+        assert!(page.html.contains("fetch(\"data.json\")"));
+        let data = page
+            .external_data
+            .as_ref()
+            .expect("data written separately");
+        assert_eq!(data.file_name, "data.json");
+
+        let dir = std::env::temp_dir().join("phago_viz_external_data");
+        std::fs::create_dir_all(&dir).unwrap();
+        page.write(&dir.join("colony.html")).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("data.json")).unwrap()).unwrap();
+        assert_eq!(written["snapshots"][0]["nodes"][1]["label"], "</script>");
+        assert!(written["events"].as_array().unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+
+        // Small enough to embed with the defaults
+        assert!(generate_html(&[snapshot], &[]).is_ok());
+    }
 }
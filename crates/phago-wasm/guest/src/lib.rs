@@ -0,0 +1,220 @@
+//! # Phago WASM Guest
+//!
+//! A minimal `no_std` agent built on phago-core's `alloc` feature.
+//!
+//! It exists to prove that the core types and the Digest, Sense and
+//! Apoptose traits are usable from a guest module:
+//!
+//! ```text
+//! cargo build -p phago-wasm-guest --target wasm32-unknown-unknown
+//! ```
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use phago_core::prelude::*;
+use phago_core::primitives::{Apoptose, Digest, Sense};
+use phago_core::signal::compute_gradient;
+
+/// Ticks without useful output before the cell reports itself senescent.
+const MAX_IDLE_TICKS: u64 = 10;
+
+/// A guest cell that digests text into keyword concepts.
+pub struct KeywordCell {
+    id: AgentId,
+    position: Position,
+    engulfed: Option<String>,
+    fragments: Vec<String>,
+    idle_ticks: u64,
+    useful_outputs: u64,
+}
+
+impl KeywordCell {
+    pub fn new(position: Position) -> Self {
+        Self {
+            id: AgentId::new(),
+            position,
+            engulfed: None,
+            fragments: Vec::new(),
+            idle_ticks: 0,
+            useful_outputs: 0,
+        }
+    }
+
+    pub fn id(&self) -> AgentId {
+        self.id
+    }
+
+    /// Advance one tick without input.
+    pub fn idle(&mut self) {
+        self.idle_ticks += 1;
+    }
+}
+
+impl Digest for KeywordCell {
+    type Input = String;
+    type Fragment = String;
+    type Presentation = Vec<NodeData>;
+
+    fn engulf(&mut self, input: String) -> DigestionResult {
+        if self.engulfed.is_some() {
+            return DigestionResult::Busy;
+        }
+        if input.trim().is_empty() {
+            return DigestionResult::Indigestible;
+        }
+        self.engulfed = Some(input);
+        DigestionResult::Engulfed
+    }
+
+    fn lyse(&mut self) -> Vec<String> {
+        let Some(text) = self.engulfed.take() else {
+            return Vec::new();
+        };
+        let mut terms: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() >= 3)
+            .map(|w| w.to_lowercase())
+            .collect();
+        terms.sort();
+        terms.dedup();
+
+        self.fragments = terms.clone();
+        self.idle_ticks = 0;
+        self.useful_outputs += 1;
+        terms
+    }
+
+    fn present(&self) -> Vec<NodeData> {
+        self.fragments
+            .iter()
+            .enumerate()
+            .map(|(i, term)| NodeData {
+                id: NodeId::from_seed(i as u64),
+                label: term.to_string(),
+                node_type: NodeType::Concept,
+                position: self.position,
+                access_count: 1,
+                created_tick: 0,
+                last_accessed_tick: 0,
+                tags: Default::default(),
+            })
+            .collect()
+    }
+}
+
+impl Sense for KeywordCell {
+    fn sense_radius(&self) -> f64 {
+        10.0
+    }
+
+    fn sense_position(&self) -> Position {
+        self.position
+    }
+
+    fn gradient(&self, substrate: &dyn Substrate) -> Vec<Gradient> {
+        let signals = self.sense(substrate);
+        let refs: Vec<&Signal> = signals.iter().collect();
+        compute_gradient(&refs, &self.position)
+            .into_iter()
+            .collect()
+    }
+
+    fn orient(&self, gradients: &[Gradient]) -> Orientation {
+        match gradients
+            .iter()
+            .max_by(|a, b| a.magnitude.total_cmp(&b.magnitude))
+        {
+            Some(g) => Orientation::Toward(Position::new(
+                self.position.x + g.direction.x,
+                self.position.y + g.direction.y,
+            )),
+            None => Orientation::Explore,
+        }
+    }
+}
+
+impl Apoptose for KeywordCell {
+    fn self_assess(&self) -> CellHealth {
+        if self.idle_ticks >= MAX_IDLE_TICKS {
+            CellHealth::Senescent
+        } else {
+            CellHealth::Healthy
+        }
+    }
+
+    fn prepare_death_signal(&self) -> DeathSignal {
+        DeathSignal {
+            agent_id: self.id,
+            total_ticks: self.idle_ticks,
+            useful_outputs: self.useful_outputs,
+            final_fragments: self
+                .fragments
+                .iter()
+                .map(|t| t.as_bytes().to_vec())
+                .collect(),
+            cause: DeathCause::SelfAssessed(self.self_assess()),
+            had_pending_work: self.engulfed.is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digests_text_into_concepts() {
+        let mut cell = KeywordCell::new(Position::new(1.0, 2.0));
+        let nodes = cell.digest("Cell membrane, cell wall".to_string());
+
+        let labels: Vec<&str> = nodes.iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, vec!["cell", "membrane", "wall"]);
+        assert!(nodes.iter().all(|n| n.position == Position::new(1.0, 2.0)));
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        let mut cell = KeywordCell::new(Position::new(0.0, 0.0));
+        assert_eq!(
+            cell.engulf("   ".to_string()),
+            DigestionResult::Indigestible
+        );
+    }
+
+    #[test]
+    fn follows_the_strongest_gradient() {
+        let cell = KeywordCell::new(Position::new(0.0, 0.0));
+        let signal = Signal::new(
+            SignalType::Input,
+            1.0,
+            Position::new(3.0, 0.0),
+            AgentId::from_seed(1),
+            0,
+        );
+        let gradient = compute_gradient(&[&signal], &Position::new(0.0, 0.0)).unwrap();
+
+        match cell.orient(&[gradient]) {
+            Orientation::Toward(target) => assert_eq!(target, Position::new(1.0, 0.0)),
+            other => panic!("expected to move, got {other:?}"),
+        }
+        assert!(matches!(cell.orient(&[]), Orientation::Explore));
+    }
+
+    #[test]
+    fn dies_of_senescence() {
+        let mut cell = KeywordCell::new(Position::new(0.0, 0.0));
+        cell.digest("membrane".to_string());
+        for _ in 0..MAX_IDLE_TICKS {
+            cell.idle();
+        }
+
+        assert!(cell.should_die());
+        let death = cell.trigger_apoptosis();
+        assert_eq!(death.useful_outputs, 1);
+        assert_eq!(death.final_fragments, vec![b"membrane".to_vec()]);
+    }
+}
@@ -0,0 +1,158 @@
+//! Bearer-token authentication.
+//!
+//! A state built with [`AppState::with_auth`] only answers requests that
+//! carry one of its tokens as `Authorization: Bearer <token>`; WebSocket
+//! upgrades, which browsers cannot give headers, may pass it as `?token=`
+//! instead. Requests without a known token get `401 Unauthorized`.
+//!
+//! Each token has a [`Role`]. Read tokens may use every endpoint that
+//! leaves the colony as it is, queries included; the endpoints that change
+//! it (the same ones a read-only server refuses) answer them with `403
+//! Forbidden`, and so do WebSocket tick commands.
+//!
+//! `/healthz` and the dashboard's pages are served to anyone: the pages
+//! ask for a token and attach it to their own requests.
+
+use crate::state::AppState;
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// What a token may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Read the colony and query it.
+    Read,
+    /// Also ingest, tick and run.
+    Write,
+}
+
+impl std::str::FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read" => Ok(Role::Read),
+            "write" => Ok(Role::Write),
+            other => Err(format!("unknown role '{}' (expected read or write)", other)),
+        }
+    }
+}
+
+/// The tokens a server accepts, each with its role.
+#[derive(Clone, Default)]
+pub struct AuthTokens {
+    tokens: Vec<(String, Role)>,
+}
+
+impl std::fmt::Debug for AuthTokens {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthTokens")
+            .field("tokens", &self.tokens.len())
+            .finish()
+    }
+}
+
+impl AuthTokens {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept `token` with `role`.
+    pub fn with_token(mut self, token: impl Into<String>, role: Role) -> Self {
+        self.tokens.push((token.into(), role));
+        self
+    }
+
+    /// Tokens written `ROLE:TOKEN`, as in `write:s3cret`.
+    pub fn parse<S: AsRef<str>>(specs: &[S]) -> Result<Self, String> {
+        let mut tokens = Self::new();
+        for spec in specs {
+            let spec = spec.as_ref().trim();
+            let (role, token) = spec
+                .split_once(':')
+                .ok_or_else(|| "tokens are written ROLE:TOKEN, as in write:s3cret".to_string())?;
+            if token.is_empty() {
+                return Err(format!("empty token for role '{}'", role));
+            }
+            tokens = tokens.with_token(token, role.parse()?);
+        }
+        Ok(tokens)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// The role of `token`, if it is one of these.
+    pub fn role_of(&self, token: &str) -> Option<Role> {
+        // Every token is compared in full, so timing tells nothing
+        self.tokens.iter().fold(None, |found, (known, role)| {
+            if constant_time_eq(known.as_bytes(), token.as_bytes()) {
+                Some(*role)
+            } else {
+                found
+            }
+        })
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// The token a request carries: its bearer token, or for a WebSocket
+/// upgrade its `token` query parameter.
+fn request_token(request: &Request) -> Option<String> {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string());
+    if bearer.is_some() {
+        return bearer;
+    }
+    let upgrade = request
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    if !upgrade {
+        return None;
+    }
+    Query::<HashMap<String, String>>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|Query(mut params)| params.remove("token"))
+}
+
+/// Turn away requests without a known token (401), and pass the token's
+/// [`Role`] on to the handlers in the request's extensions. Lets every
+/// request through if the server has no tokens configured.
+pub async fn authenticate(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(tokens) = state.auth() else {
+        return next.run(request).await;
+    };
+    match request_token(&request).and_then(|token| tokens.role_of(&token)) {
+        Some(role) => {
+            request.extensions_mut().insert(role);
+            next.run(request).await
+        }
+        None => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(json!({ "error": "missing or unknown token" })),
+        )
+            .into_response(),
+    }
+}
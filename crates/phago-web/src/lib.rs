@@ -6,7 +6,7 @@
 //!
 //! ```bash
 //! # Start the web server
-//! cargo run -p phago-web -- --port 3000
+//! cargo run -p phago-web -- --port 3000 --token write:s3cret
 //!
 //! # Open http://localhost:3000 in your browser
 //! ```
@@ -15,18 +15,77 @@
 //!
 //! | Method | Path | Description |
 //! |--------|------|-------------|
+//! | GET | `/healthz` | Liveness probe (always 200 while serving) |
+//! | GET | `/readyz` | Readiness probe (503 while restoring or shutting down) |
+//! | GET | `/metrics` | Prometheus metrics |
 //! | GET | `/api/stats` | Colony statistics |
-//! | GET | `/api/nodes` | All graph nodes |
-//! | GET | `/api/edges` | All graph edges |
+//! | GET | `/api/nodes` | All graph nodes, or a sample (`?sample=`, as for `/api/snapshot`) |
+//! | GET | `/api/edges` | All graph edges, or those of a sample |
 //! | GET | `/api/agents` | Active agents |
-//! | GET | `/api/snapshot` | Full colony snapshot |
-//! | POST | `/api/query` | Hybrid query |
-//! | POST | `/api/ingest` | Ingest document |
+//! | GET | `/api/snapshot` | Full colony snapshot, or a sample with its coverage (`?sample=top\|ego\|weight\|forest&n=`) |
+//! | GET | `/api/snapshots` | Recorded snapshots (`?from_tick=&to_tick=`) |
+//! | GET | `/api/events` | Event history for the recorded range |
+//! | GET | `/api/export/viz` | Recording as standalone phago-viz HTML |
+//! | GET | `/replay` | Replay player for the recording |
+//! | POST | `/api/query` | Hybrid query, paged by `cursor` |
+//! | GET | `/api/query/cache` | Query cache hits and misses |
+//! | POST | `/api/ingest` | Ingest a JSON document, or queue multipart file uploads as a job |
+//! | GET | `/api/ingest/:job` | Ingestion job progress (files processed, concepts added, failures) |
 //! | POST | `/api/tick` | Run simulation tick(s) |
-//! | POST | `/api/run` | Run N ticks |
+//! | POST | `/api/run` | Start a background run of N ticks (`speed` = max ticks/s) |
+//! | GET | `/api/run/:id` | Run progress (ticks completed, events, ETA) |
+//! | DELETE | `/api/run/:id` | Cancel a run at the next tick boundary |
 //! | WS | `/ws/events` | Real-time event stream |
+//!
+//! ## Shutdown
+//!
+//! On SIGINT or SIGTERM the server stops accepting connections, background
+//! runs stop after the current tick with state `interrupted`, `/api/tick`
+//! requests report `interrupted: true` with `ticks_completed`, and the
+//! colony is saved to the `--db` path before the process exits.
+//!
+//! ## Uploads
+//!
+//! `POST /api/ingest` with `multipart/form-data` accepts any number of
+//! `file` parts (text and markdown, HTML, and PDF with the `pdf` feature)
+//! plus an optional `ticks` field. The files are spooled to disk and the
+//! server answers `202 Accepted` with a job ID at once. Ingest requests are
+//! rate limited per client (`429 Too Many Requests`, with `Retry-After`)
+//! and bodies above the upload limit get `413 Payload Too Large`; see
+//! [`UploadLimits`].
+//!
+//! ## Read-only mode
+//!
+//! A state built with [`AppState::with_read_only`] answers `POST
+//! /api/ingest`, `POST /api/tick`, `POST /api/run` and `DELETE
+//! /api/run/:id` with `403 Forbidden`, and ignores WebSocket tick
+//! commands. Queries are still served.
+//!
+//! ## Static bundles
+//!
+//! `phago-web --static-bundle <dir>` ([`AppState::with_static_bundle`])
+//! serves a bundle written by
+//! [`publish_bundle`](phago_runtime::publish::publish_bundle) instead of a
+//! colony: statistics, nodes, edges, snapshots, paths and queries come
+//! from the bundle, there are no agents, and the server is read-only.
+//!
+//! ## Authentication
+//!
+//! A state built with [`AppState::with_auth`] answers only requests that
+//! carry one of its tokens (`Authorization: Bearer`, or `?token=` on the
+//! WebSocket), with `401 Unauthorized` otherwise. Read tokens get `403
+//! Forbidden` where a read-only server would; see [`auth`]. `/healthz`
+//! and the dashboard pages need no token. The `phago-web` binary takes
+//! tokens from `--token ROLE:TOKEN` or `PHAGO_WEB_TOKENS`, and runs
+//! without them only when started with `--no-auth`.
 
+pub mod auth;
 pub mod routes;
 pub mod state;
 
-pub use state::AppState;
+pub use auth::{AuthTokens, Role};
+pub use state::{
+    AppState, ColonyStore, FileState, FileStatus, IngestJobStatus, JobState, Readiness,
+    RecordingConfig, RunConflict, RunEvent, RunOutcome, RunState, RunStatus, ShutdownReport,
+    ShutdownToken, SpooledFile, UploadLimits,
+};
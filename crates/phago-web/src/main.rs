@@ -1,12 +1,15 @@
 //! Phago Web Dashboard - Real-time colony visualization.
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
+use phago_runtime::read_view::ViewRefresh;
 
+mod auth;
 mod routes;
 mod state;
 
-pub use state::AppState;
+pub use auth::AuthTokens;
+pub use state::{AppState, RecordingConfig, UploadLimits};
 
 #[derive(Parser, Debug)]
 #[command(name = "phago-web")]
@@ -23,6 +26,46 @@ struct Cli {
     /// Path to SQLite database (optional persistence)
     #[arg(short, long)]
     db: Option<String>,
+
+    /// Serve a published bundle directory read-only instead of a colony
+    /// (--db is ignored)
+    #[arg(long, value_name = "DIR")]
+    static_bundle: Option<String>,
+
+    /// Record a replay snapshot every N ticks (0 disables recording)
+    #[arg(long, default_value = "1")]
+    record_interval: u64,
+
+    /// Maximum number of replay snapshots kept in memory
+    #[arg(long, default_value = "500")]
+    record_capacity: usize,
+
+    /// Cache up to N query results until the graph changes (0 disables)
+    #[arg(long, default_value = "0")]
+    query_cache: usize,
+
+    /// Largest ingest upload accepted, in MiB
+    #[arg(long, default_value = "16")]
+    max_upload_mb: usize,
+
+    /// Ingest requests allowed per client per minute (0 disables the limit)
+    #[arg(long, default_value = "60")]
+    ingest_rate: u32,
+
+    /// Refresh the read view answering stats, snapshots and paths at most
+    /// every N ms (0 refreshes after every tick)
+    #[arg(long, default_value = "0")]
+    view_refresh_ms: u64,
+
+    /// Accept a token, written ROLE:TOKEN with role read or write
+    /// (repeatable; comma-separated in PHAGO_WEB_TOKENS)
+    #[arg(long = "token", env = "PHAGO_WEB_TOKENS", value_delimiter = ',')]
+    tokens: Vec<String>,
+
+    /// Serve every request without a token, as for local development
+    /// (any tokens are ignored)
+    #[arg(long)]
+    no_auth: bool,
 }
 
 #[tokio::main]
@@ -32,19 +75,100 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
     let addr = format!("{}:{}", cli.host, cli.port);
+    let tokens = AuthTokens::parse(&cli.tokens).map_err(anyhow::Error::msg)?;
+    if tokens.is_empty() && !cli.no_auth {
+        bail!("No tokens configured: pass --token ROLE:TOKEN (or set PHAGO_WEB_TOKENS), or --no-auth to serve without authentication");
+    }
 
     println!("Starting Phago Web Dashboard...");
     println!("Open http://{} in your browser", addr);
 
     // Create app state
-    let state = AppState::new(cli.db)?;
+    let state = match &cli.static_bundle {
+        Some(dir) => {
+            println!("Serving the published bundle in {} read-only", dir);
+            AppState::with_static_bundle(dir)?
+        }
+        None => AppState::with_query_cache(
+            cli.db,
+            RecordingConfig {
+                interval: cli.record_interval,
+                capacity: cli.record_capacity,
+            },
+            cli.query_cache,
+        )?,
+    }
+    .with_upload_limits(UploadLimits {
+        max_upload_bytes: cli.max_upload_mb * 1024 * 1024,
+        requests_per_minute: cli.ingest_rate,
+    })
+    .with_view_refresh(Some(match cli.view_refresh_ms {
+        0 => ViewRefresh::EveryTick,
+        ms => ViewRefresh::Every(std::time::Duration::from_millis(ms)),
+    }));
+    let state = if cli.no_auth {
+        eprintln!("WARNING: authentication is disabled (--no-auth). Anyone who can reach");
+        eprintln!(
+            "WARNING: http://{} can read, ingest into and run this colony.",
+            addr
+        );
+        state
+    } else {
+        state.with_auth(tokens)
+    };
 
     // Build router
-    let app = routes::create_router(state);
+    let app = routes::create_router(state.clone());
 
-    // Start server
+    // Start server; on SIGINT/SIGTERM stop accepting connections and let
+    // in-flight requests finish (tick runs stop after the current tick)
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    // Client addresses key the per-client ingest rate limit
+    let service = app.into_make_service_with_connect_info::<std::net::SocketAddr>();
+    axum::serve(listener, service)
+        .with_graceful_shutdown(shutdown_signal(state.clone()))
+        .await?;
+
+    let report = state.shutdown_and_save().await?;
+    match report.saved_to {
+        Some(path) => tracing::info!(
+            "Shut down at tick {}: saved {} nodes and {} edges to {}",
+            report.tick,
+            report.nodes,
+            report.edges,
+            path.display()
+        ),
+        None => tracing::info!(
+            "Shut down at tick {} (no --db configured, state not saved)",
+            report.tick
+        ),
+    }
 
     Ok(())
 }
+
+/// Resolve on Ctrl-C or SIGTERM, then tell the colony worker to wind down.
+async fn shutdown_signal(state: AppState) {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("Shutdown requested, finishing in-flight ticks");
+    state.begin_shutdown();
+}
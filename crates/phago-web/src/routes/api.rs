@@ -1,28 +1,114 @@
 //! REST API endpoints for colony interaction.
 
-use crate::state::AppState;
-use axum::{extract::State, http::StatusCode, Json};
-use phago_core::types::Position;
+use crate::auth::Role;
+use crate::state::{AppState, IngestJobStatus, RunOutcome, RunStatus, SpooledFile, TickRange};
+use axum::{
+    extract::{
+        multipart::MultipartError, ConnectInfo, FromRequest, Multipart, Path, Query, Request, State,
+    },
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use phago::rag::QueryCacheStats;
+use phago_core::types::{AgentId, Position, Tick};
 use phago_runtime::colony::{
-    AgentSnapshot, ColonySnapshot, ColonyStats, EdgeSnapshot, NodeSnapshot,
+    AgentSnapshot, ColonyEvent, ColonySnapshot, ColonyStats, EdgeSnapshot, LabelSuggestion,
+    NodeSnapshot,
 };
+use phago_runtime::graph_algorithms::{ConceptPath, PathCost, PathError};
+use phago_runtime::sampling::{SampleConfig, SampleStrategy};
+use phago_runtime::vocabulary::{AgentVocabulary, TermCoverage, VocabularyReport};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use tokio::io::AsyncWriteExt;
+
+/// Reject requests that change the colony when the server is read-only,
+/// or when they were authenticated with a read token.
+pub async fn require_writable(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(error) = write_refusal(&state, request.extensions().get::<Role>()) {
+        return (StatusCode::FORBIDDEN, Json(json!({ "error": error }))).into_response();
+    }
+    next.run(request).await
+}
+
+/// Why a request authenticated as `role` may not change the colony, if it
+/// may not.
+pub fn write_refusal(state: &AppState, role: Option<&Role>) -> Option<&'static str> {
+    if state.is_read_only() {
+        Some("the colony is read-only")
+    } else if role == Some(&Role::Read) {
+        Some("this token may only read")
+    } else {
+        None
+    }
+}
+
+/// Turn away ingest requests over the client's rate (429) or with a
+/// declared body above the upload limit (413). Bodies without a declared
+/// length are cut off at the limit while they stream in.
+pub async fn limit_ingest(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    // Clients are told apart by address when the server knows it
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip().to_string())
+        .unwrap_or_else(|| "local".to_string());
+    if let Err(retry_after) = state.check_rate(&client) {
+        let seconds = retry_after.as_secs_f64().ceil() as u64;
+        let body = json!({ "error": "too many ingest requests", "retry_after_secs": seconds });
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, seconds.to_string())],
+            Json(body),
+        )
+            .into_response();
+    }
+
+    let max = state.upload_limits().max_upload_bytes;
+    let declared = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if declared.is_some_and(|length| length > max) {
+        return too_large(max).into_response();
+    }
+    next.run(request).await
+}
+
+fn too_large(max: usize) -> (StatusCode, Json<Value>) {
+    let body = json!({ "error": format!("upload exceeds the limit of {max} bytes") });
+    (StatusCode::PAYLOAD_TOO_LARGE, Json(body))
+}
 
 /// Get colony statistics.
 pub async fn get_stats(State(state): State<AppState>) -> Json<ColonyStats> {
     Json(state.stats().await)
 }
 
-/// Get all graph nodes.
-pub async fn get_nodes(State(state): State<AppState>) -> Json<Vec<NodeSnapshot>> {
-    let snapshot = state.snapshot().await;
-    Json(snapshot.nodes)
+/// Get all graph nodes, or a sample of them.
+pub async fn get_nodes(
+    State(state): State<AppState>,
+    Query(params): Query<SampleParams>,
+) -> Result<Json<Vec<NodeSnapshot>>, (StatusCode, Json<Value>)> {
+    let snapshot = state.sampled_snapshot(params.config()?).await;
+    Ok(Json(snapshot.nodes))
 }
 
-/// Get all graph edges.
-pub async fn get_edges(State(state): State<AppState>) -> Json<Vec<EdgeSnapshot>> {
-    let snapshot = state.snapshot().await;
-    Json(snapshot.edges)
+/// Get all graph edges, or those between a sample of the nodes.
+pub async fn get_edges(
+    State(state): State<AppState>,
+    Query(params): Query<SampleParams>,
+) -> Result<Json<Vec<EdgeSnapshot>>, (StatusCode, Json<Value>)> {
+    let snapshot = state.sampled_snapshot(params.config()?).await;
+    Ok(Json(snapshot.edges))
 }
 
 /// Get all active agents.
@@ -31,9 +117,221 @@ pub async fn get_agents(State(state): State<AppState>) -> Json<Vec<AgentSnapshot
     Json(snapshot.agents)
 }
 
-/// Get full colony snapshot.
-pub async fn get_snapshot(State(state): State<AppState>) -> Json<ColonySnapshot> {
-    Json(state.snapshot().await)
+/// Most terms a vocabulary request may ask for.
+const MAX_VOCABULARY_TERMS: usize = 1000;
+
+/// Vocabulary query parameters.
+#[derive(Debug, Deserialize)]
+pub struct VocabularyParams {
+    /// Terms to list, the heaviest first.
+    #[serde(default = "default_vocabulary_terms")]
+    pub top: usize,
+}
+
+fn default_vocabulary_terms() -> usize {
+    25
+}
+
+/// How much an agent's top terms overlap with another agent's.
+#[derive(Debug, Serialize)]
+pub struct AgentOverlap {
+    pub agent: AgentId,
+    pub shared: usize,
+    pub jaccard: f64,
+}
+
+/// An agent's top terms, their overlap with every other agent's and
+/// whether the graph has them.
+#[derive(Debug, Serialize)]
+pub struct VocabularyResponse {
+    pub tick: Tick,
+    #[serde(flatten)]
+    pub vocabulary: AgentVocabulary,
+    pub overlaps: Vec<AgentOverlap>,
+    /// Graph coverage of the agent's terms, in the order of `top_terms`.
+    pub coverage: Vec<TermCoverage>,
+}
+
+impl VocabularyResponse {
+    /// The part of `report` about agent `id`, if it is alive.
+    pub fn from_report(report: &VocabularyReport, id: &AgentId) -> Option<Self> {
+        let vocabulary = report.agent(id)?.clone();
+        let overlaps = report
+            .overlaps_of(id)
+            .map(|o| AgentOverlap {
+                agent: if o.a == *id { o.b } else { o.a },
+                shared: o.shared,
+                jaccard: o.jaccard,
+            })
+            .collect();
+        let coverage = vocabulary
+            .top_terms
+            .iter()
+            .filter_map(|(term, _)| report.coverage_of(term).cloned())
+            .collect();
+        Some(Self {
+            tick: report.tick,
+            vocabulary,
+            overlaps,
+            coverage,
+        })
+    }
+}
+
+/// What an agent knows: its heaviest terms, how they overlap with the
+/// other agents' and which of them the graph has.
+pub async fn get_agent_vocabulary(
+    State(state): State<AppState>,
+    Path(id): Path<AgentId>,
+    Query(params): Query<VocabularyParams>,
+) -> Result<Json<VocabularyResponse>, (StatusCode, Json<Value>)> {
+    let top = params.top.min(MAX_VOCABULARY_TERMS);
+    state
+        .vocabulary_report(top)
+        .await
+        .and_then(|report| VocabularyResponse::from_report(&report, &id))
+        .map(Json)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "no such agent is alive" })),
+            )
+        })
+}
+
+/// Get full colony snapshot, or a sampled one carrying its coverage.
+pub async fn get_snapshot(
+    State(state): State<AppState>,
+    Query(params): Query<SampleParams>,
+) -> Result<Json<ColonySnapshot>, (StatusCode, Json<Value>)> {
+    Ok(Json(state.sampled_snapshot(params.config()?).await))
+}
+
+/// Graph sampling query parameters: `sample` is `top` (`n`), `ego`
+/// (`seeds`, `hops`, `n`), `weight` (`min_weight`, `n`) or `forest`
+/// (`seed`, `n`). Without `sample` the whole graph is returned.
+#[derive(Debug, Deserialize)]
+pub struct SampleParams {
+    pub sample: Option<String>,
+    #[serde(default = "default_sample_size")]
+    pub n: usize,
+    /// Comma-separated seed concepts for `ego`.
+    pub seeds: Option<String>,
+    #[serde(default = "default_sample_hops")]
+    pub hops: usize,
+    pub min_weight: Option<f64>,
+    #[serde(default)]
+    pub seed: u64,
+}
+
+fn default_sample_size() -> usize {
+    500
+}
+
+fn default_sample_hops() -> usize {
+    2
+}
+
+impl SampleParams {
+    /// The sampling these parameters ask for; 400 if they are incomplete.
+    fn config(self) -> Result<Option<SampleConfig>, (StatusCode, Json<Value>)> {
+        let bad_request = |e: &str| (StatusCode::BAD_REQUEST, Json(json!({ "error": e })));
+        let strategy = match self.sample.as_deref() {
+            None => return Ok(None),
+            Some("top") => SampleStrategy::TopByAccess { n: self.n },
+            Some("ego") => {
+                let seeds: Vec<String> = self
+                    .seeds
+                    .iter()
+                    .flat_map(|seeds| seeds.split(','))
+                    .map(|seed| seed.trim().to_string())
+                    .filter(|seed| !seed.is_empty())
+                    .collect();
+                if seeds.is_empty() {
+                    return Err(bad_request("'seeds' is required for ego samples"));
+                }
+                SampleStrategy::EgoNetworks {
+                    seeds,
+                    hops: self.hops,
+                    n: self.n,
+                }
+            }
+            Some("weight") => SampleStrategy::WeightThreshold {
+                min_edge_weight: self
+                    .min_weight
+                    .ok_or_else(|| bad_request("'min_weight' is required for weight samples"))?,
+                max_nodes: self.n,
+            },
+            Some("forest") => SampleStrategy::ForestFire {
+                seed: self.seed,
+                n: self.n,
+            },
+            Some(other) => {
+                return Err(bad_request(&format!(
+                    "unknown sample '{other}' (expected top, ego, weight or forest)"
+                )))
+            }
+        };
+        Ok(Some(strategy.into()))
+    }
+}
+
+/// Tick bounds for reading recorded history (both inclusive, both optional).
+#[derive(Debug, Deserialize)]
+pub struct TickRangeParams {
+    pub from_tick: Option<Tick>,
+    pub to_tick: Option<Tick>,
+}
+
+impl From<TickRangeParams> for TickRange {
+    fn from(params: TickRangeParams) -> Self {
+        TickRange {
+            from_tick: params.from_tick,
+            to_tick: params.to_tick,
+        }
+    }
+}
+
+/// Get recorded snapshots within a tick range.
+pub async fn get_snapshots(
+    State(state): State<AppState>,
+    Query(params): Query<TickRangeParams>,
+) -> Json<Vec<ColonySnapshot>> {
+    Json(state.recording(params.into()).await.snapshots)
+}
+
+/// Get the event history covering the recorded snapshots in a tick range.
+pub async fn get_events(
+    State(state): State<AppState>,
+    Query(params): Query<TickRangeParams>,
+) -> Json<Vec<(Tick, ColonyEvent)>> {
+    Json(state.recording(params.into()).await.events)
+}
+
+/// Download the recording as a standalone phago-viz HTML file. Responds
+/// 413 if the range is too large to embed in one file.
+pub async fn export_viz(
+    State(state): State<AppState>,
+    Query(params): Query<TickRangeParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let recording = state.recording(params.into()).await;
+    let html = phago_viz::generate_html(&recording.snapshots, &recording.events).map_err(|e| {
+        let status = match e {
+            phago_viz::VizError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(json!({ "error": e.to_string() })))
+    })?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"phago-viz.html\"",
+            ),
+        ],
+        html,
+    ))
 }
 
 /// Query request body.
@@ -44,6 +342,9 @@ pub struct QueryRequest {
     pub max_results: usize,
     #[serde(default = "default_alpha")]
     pub alpha: f64,
+    /// `next_cursor` of the previous page; absent for the first page.
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
 fn default_max_results() -> usize {
@@ -68,16 +369,33 @@ pub struct QueryResponse {
     pub results: Vec<QueryResultItem>,
     pub total_nodes: usize,
     pub total_edges: usize,
+    /// Send back as `cursor` for the next page; absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// The graph changed since `cursor` was issued, so `results` are a
+    /// fresh first page.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cursor_expired: bool,
 }
 
-/// Query the knowledge graph.
+/// Query the knowledge graph, a page of `max_results` at a time. Responds
+/// 400 if `alpha` is outside [0, 1], `max_results` is zero, or `cursor`
+/// was not issued for this query.
 pub async fn query(
     State(state): State<AppState>,
     Json(req): Json<QueryRequest>,
-) -> Json<QueryResponse> {
-    let result = state.query(req.query, req.max_results, req.alpha).await;
+) -> Result<Json<QueryResponse>, (StatusCode, Json<Value>)> {
+    let result = state
+        .query(req.query, req.max_results, req.alpha, req.cursor)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": e.to_string() })),
+            )
+        })?;
 
-    Json(QueryResponse {
+    Ok(Json(QueryResponse {
         results: result
             .results
             .into_iter()
@@ -90,7 +408,84 @@ pub async fn query(
             .collect(),
         total_nodes: result.total_nodes,
         total_edges: result.total_edges,
-    })
+        next_cursor: result.next_cursor,
+        cursor_expired: result.cursor_expired,
+    }))
+}
+
+/// Query cache statistics; `null` when the server runs without a cache.
+pub async fn query_cache(State(state): State<AppState>) -> Json<Option<QueryCacheStats>> {
+    Json(state.query_cache_stats())
+}
+
+/// Autocomplete parameters.
+#[derive(Debug, Deserialize)]
+pub struct SuggestParams {
+    /// Label prefix typed so far.
+    #[serde(default)]
+    pub q: String,
+    #[serde(default = "default_suggest_limit")]
+    pub limit: usize,
+}
+
+fn default_suggest_limit() -> usize {
+    10
+}
+
+/// Suggest concept labels completing a prefix.
+pub async fn suggest(
+    State(state): State<AppState>,
+    Query(params): Query<SuggestParams>,
+) -> Json<Vec<LabelSuggestion>> {
+    Json(state.suggest(params.q, params.limit).await)
+}
+
+/// Path query parameters.
+#[derive(Debug, Deserialize)]
+pub struct PathParams {
+    pub from: String,
+    pub to: String,
+    /// strongest (default), hops, inverse or complement.
+    #[serde(default)]
+    pub cost: Option<String>,
+    #[serde(default = "default_path_count")]
+    pub k: usize,
+}
+
+fn default_path_count() -> usize {
+    1
+}
+
+/// Paths between two concepts, or why there are none.
+#[derive(Debug, Serialize)]
+pub struct PathResponse {
+    pub paths: Vec<ConceptPath>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<PathError>,
+}
+
+/// Find the cheapest paths between two concepts, explained hop by hop.
+pub async fn find_path(
+    State(state): State<AppState>,
+    Query(params): Query<PathParams>,
+) -> Result<Json<PathResponse>, (StatusCode, Json<Value>)> {
+    let cost: PathCost = match params.cost.as_deref() {
+        Some(cost) => cost
+            .parse()
+            .map_err(|e: String| (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))))?,
+        None => PathCost::default(),
+    };
+    let response = match state
+        .find_path(params.from, params.to, cost, params.k)
+        .await
+    {
+        Ok(paths) => PathResponse { paths, error: None },
+        Err(e) => PathResponse {
+            paths: Vec::new(),
+            error: Some(e),
+        },
+    };
+    Ok(Json(response))
 }
 
 /// Ingest request body.
@@ -117,11 +512,28 @@ pub struct IngestResponse {
     pub tick: u64,
 }
 
-/// Ingest a document.
-pub async fn ingest(
-    State(state): State<AppState>,
-    Json(req): Json<IngestRequest>,
-) -> Result<Json<IngestResponse>, StatusCode> {
+/// Ingest a JSON document and wait for it to be digested, or spool
+/// multipart file uploads and queue them as a job (202 with its status).
+pub async fn ingest(State(state): State<AppState>, request: Request) -> Response {
+    let multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("multipart/form-data"));
+    if multipart {
+        match Multipart::from_request(request, &state).await {
+            Ok(multipart) => ingest_files(&state, multipart).await.into_response(),
+            Err(rejection) => rejection.into_response(),
+        }
+    } else {
+        match Json::<IngestRequest>::from_request(request, &state).await {
+            Ok(Json(req)) => Json(ingest_document(&state, req).await).into_response(),
+            Err(rejection) => rejection.into_response(),
+        }
+    }
+}
+
+async fn ingest_document(state: &AppState, req: IngestRequest) -> IngestResponse {
     let pos = req
         .position
         .map(|(x, y)| Position::new(x, y))
@@ -129,12 +541,90 @@ pub async fn ingest(
 
     let result = state.ingest(req.title, req.content, pos, req.ticks).await;
 
-    Ok(Json(IngestResponse {
+    IngestResponse {
         document_id: result.document_id,
         nodes_created: result.nodes_created,
         edges_created: result.edges_created,
         tick: result.tick,
-    }))
+    }
+}
+
+/// Stream every uploaded file to the spool, then queue them. A `ticks`
+/// field sets the ticks run after each file.
+async fn ingest_files(
+    state: &AppState,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<IngestJobStatus>), (StatusCode, Json<Value>)> {
+    let spool = tempfile::Builder::new()
+        .prefix("phago-upload-")
+        .tempdir()
+        .map_err(spool_error)?;
+    let mut files = Vec::new();
+    let mut ticks = default_ticks();
+
+    let upload_error = |e: MultipartError| match e.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => too_large(state.upload_limits().max_upload_bytes),
+        status => (status, Json(json!({ "error": e.body_text() }))),
+    };
+
+    while let Some(mut field) = multipart.next_field().await.map_err(upload_error)? {
+        if field.name() == Some("ticks") {
+            let text = field.text().await.map_err(upload_error)?;
+            ticks = text.trim().parse().map_err(|_| {
+                let body = json!({ "error": format!("invalid ticks '{text}'") });
+                (StatusCode::BAD_REQUEST, Json(body))
+            })?;
+            continue;
+        }
+        let Some(name) = field.file_name().map(upload_name) else {
+            continue;
+        };
+
+        // One directory per file keeps the name, which extraction falls
+        // back on for the title
+        let dir = spool.path().join(files.len().to_string());
+        tokio::fs::create_dir(&dir).await.map_err(spool_error)?;
+        let path = dir.join(&name);
+        let mut out = tokio::fs::File::create(&path).await.map_err(spool_error)?;
+        while let Some(chunk) = field.chunk().await.map_err(upload_error)? {
+            out.write_all(&chunk).await.map_err(spool_error)?;
+        }
+        out.flush().await.map_err(spool_error)?;
+        files.push(SpooledFile { name, path });
+    }
+
+    if files.is_empty() {
+        let body = json!({ "error": "no files uploaded" });
+        return Err((StatusCode::BAD_REQUEST, Json(body)));
+    }
+    let status = state.enqueue_ingest(files, spool, ticks);
+    Ok((StatusCode::ACCEPTED, Json(status)))
+}
+
+/// The uploaded file name without any directories.
+fn upload_name(name: &str) -> String {
+    std::path::Path::new(name)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .filter(|n| !n.is_empty())
+        .unwrap_or("upload.txt")
+        .to_string()
+}
+
+fn spool_error(e: std::io::Error) -> (StatusCode, Json<Value>) {
+    let body = json!({ "error": format!("could not spool upload: {e}") });
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(body))
+}
+
+/// Progress of an ingestion job.
+pub async fn get_ingest_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<u64>,
+) -> Result<Json<IngestJobStatus>, StatusCode> {
+    state
+        .ingest_job(job_id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
 }
 
 /// Tick request body.
@@ -148,23 +638,84 @@ fn default_tick_count() -> u64 {
     1
 }
 
+/// Response to a tick run: colony statistics plus how many of the
+/// requested ticks ran before any shutdown interrupted the run.
+#[derive(Debug, Serialize)]
+pub struct RunResponse {
+    #[serde(flatten)]
+    pub stats: ColonyStats,
+    pub ticks_requested: u64,
+    pub ticks_completed: u64,
+    pub interrupted: bool,
+}
+
+impl RunResponse {
+    async fn after(state: &AppState, outcome: RunOutcome) -> Self {
+        Self {
+            stats: state.stats().await,
+            ticks_requested: outcome.requested,
+            ticks_completed: outcome.completed,
+            interrupted: outcome.interrupted(),
+        }
+    }
+}
+
 /// Run tick(s).
 pub async fn tick(
     State(state): State<AppState>,
     Json(req): Json<TickRequest>,
-) -> Json<ColonyStats> {
-    state.run(req.count).await;
-    Json(state.stats().await)
+) -> Json<RunResponse> {
+    let outcome = state.run(req.count).await;
+    Json(RunResponse::after(&state, outcome).await)
 }
 
 /// Run request body.
 #[derive(Debug, Deserialize)]
 pub struct RunRequest {
     pub ticks: u64,
+    /// Maximum ticks per second (default: unthrottled).
+    #[serde(default)]
+    pub speed: Option<f64>,
 }
 
-/// Run multiple ticks.
-pub async fn run(State(state): State<AppState>, Json(req): Json<RunRequest>) -> Json<ColonyStats> {
-    state.run(req.ticks).await;
-    Json(state.stats().await)
+/// Start a background run of N ticks. Responds 202 with the run's status,
+/// or 409 with the active `run_id` if a run is already in progress.
+pub async fn run(
+    State(state): State<AppState>,
+    Json(req): Json<RunRequest>,
+) -> (StatusCode, Json<Value>) {
+    match state.start_run(req.ticks, req.speed) {
+        Ok(status) => (StatusCode::ACCEPTED, Json(json!(status))),
+        Err(conflict) => (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "a run is already in progress",
+                "run_id": conflict.active_run_id,
+            })),
+        ),
+    }
+}
+
+/// Progress of a background run.
+pub async fn get_run(
+    State(state): State<AppState>,
+    Path(run_id): Path<u64>,
+) -> Result<Json<RunStatus>, StatusCode> {
+    state
+        .run_status(run_id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Cancel a background run after the tick in progress and return its
+/// final status.
+pub async fn cancel_run(
+    State(state): State<AppState>,
+    Path(run_id): Path<u64>,
+) -> Result<Json<RunStatus>, StatusCode> {
+    state
+        .cancel_run(run_id)
+        .await
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
 }
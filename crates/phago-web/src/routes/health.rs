@@ -0,0 +1,22 @@
+//! Liveness and readiness probes for load balancers and orchestrators.
+
+use crate::state::{AppState, Readiness};
+use axum::{extract::State, http::StatusCode, Json};
+use serde_json::{json, Value};
+
+/// Liveness: 200 whenever the server loop is answering requests.
+pub async fn healthz() -> (StatusCode, Json<Value>) {
+    (StatusCode::OK, Json(json!({ "status": "ok" })))
+}
+
+/// Readiness: 200 once the colony has been restored, 503 while restoring,
+/// after a failed restore, and during shutdown.
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+    let readiness = state.readiness();
+    let status = if readiness == Readiness::Ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(json!({ "status": readiness.as_str() })))
+}
@@ -0,0 +1,15 @@
+//! Prometheus scrape endpoint.
+
+use crate::state::AppState;
+use axum::{extract::State, http::header};
+use phago_runtime::metrics::prometheus::CONTENT_TYPE;
+
+/// Colony, tick, query and save metrics in the Prometheus text format.
+pub async fn metrics(
+    State(state): State<AppState>,
+) -> ([(header::HeaderName, &'static str); 1], String) {
+    (
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        state.metrics().encode(),
+    )
+}
@@ -1,11 +1,16 @@
 //! HTTP and WebSocket routes for the web dashboard.
 
 mod api;
+mod health;
+mod metrics;
+mod replay;
 mod ws;
 
-use crate::AppState;
+use crate::{auth, AppState};
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, post},
     Router,
 };
 use std::path::PathBuf;
@@ -21,19 +26,53 @@ pub fn create_router(state: AppState) -> Router {
             manifest.join("static")
         });
 
+    // Requests need a token when the server has some
+    let authenticated = middleware::from_fn_with_state(state.clone(), auth::authenticate);
+    // Requests that change the colony, refused on a read-only server
+    let writable = middleware::from_fn_with_state(state.clone(), api::require_writable);
+    // Ingest requests are rate limited and size capped per client
+    let limited = middleware::from_fn_with_state(state.clone(), api::limit_ingest);
+    let body_limit = DefaultBodyLimit::max(state.upload_limits().max_upload_bytes);
+
     Router::new()
+        // Probes
+        .route("/readyz", get(health::readyz))
+        .route("/metrics", get(metrics::metrics))
         // API routes
         .route("/api/stats", get(api::get_stats))
         .route("/api/nodes", get(api::get_nodes))
         .route("/api/edges", get(api::get_edges))
         .route("/api/agents", get(api::get_agents))
+        .route("/api/agents/:id/vocabulary", get(api::get_agent_vocabulary))
         .route("/api/query", post(api::query))
-        .route("/api/ingest", post(api::ingest))
-        .route("/api/tick", post(api::tick))
-        .route("/api/run", post(api::run))
+        .route("/api/query/cache", get(api::query_cache))
+        .route("/api/suggest", get(api::suggest))
+        .route("/api/path", get(api::find_path))
+        .route(
+            "/api/ingest",
+            post(api::ingest)
+                .layer(body_limit)
+                .route_layer(limited)
+                .route_layer(writable.clone()),
+        )
+        .route("/api/ingest/:job", get(api::get_ingest_job))
+        .route("/api/tick", post(api::tick).route_layer(writable.clone()))
+        .route("/api/run", post(api::run).route_layer(writable.clone()))
+        .route(
+            "/api/run/:id",
+            get(api::get_run).merge(delete(api::cancel_run).route_layer(writable)),
+        )
         .route("/api/snapshot", get(api::get_snapshot))
+        .route("/api/snapshots", get(api::get_snapshots))
+        .route("/api/events", get(api::get_events))
+        .route("/api/export/viz", get(api::export_viz))
         // WebSocket for live events
         .route("/ws/events", get(ws::events_handler))
+        .route_layer(authenticated)
+        // Open to anyone: liveness, and pages that ask for a token
+        .route("/healthz", get(health::healthz))
+        // Replay view for recorded runs
+        .route("/replay", get(replay::replay_page))
         // Static files (serve index.html as fallback)
         .fallback_service(ServeDir::new(static_dir).append_index_html_on_directories(true))
         // CORS for development
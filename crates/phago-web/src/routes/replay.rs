@@ -0,0 +1,41 @@
+//! Replay view — the phago-viz player fed from the recording endpoints.
+
+use axum::response::Html;
+
+/// Fetches the recording for the page's tick range with the dashboard's
+/// token, starts the player and adds a download button for the
+/// standalone export.
+const LOADER_JS: &str = r#"
+const range = window.location.search;
+const token = localStorage.getItem('phagoToken');
+const authFetch = (url) =>
+  fetch(url, { headers: token ? { Authorization: `Bearer ${token}` } : {} });
+Promise.all([
+  authFetch('/api/snapshots' + range).then(r => r.json()),
+  authFetch('/api/events' + range).then(r => r.json()),
+]).then(([snapshots, events]) => {
+  startPlayer(snapshots, events);
+  const controls = document.getElementById('controls');
+  if (!controls) return;
+  const download = document.createElement('button');
+  download.id = 'download-btn';
+  download.textContent = 'Download HTML';
+  download.onclick = async () => {
+    const blob = await authFetch('/api/export/viz' + range).then(r => r.blob());
+    const link = document.createElement('a');
+    link.href = URL.createObjectURL(blob);
+    link.download = 'phago-replay.html';
+    link.click();
+    URL.revokeObjectURL(link.href);
+  };
+  const live = document.createElement('button');
+  live.textContent = 'Live';
+  live.onclick = () => { window.location.href = '/'; };
+  controls.append(download, live);
+});
+"#;
+
+/// Serve the replay page.
+pub async fn replay_page() -> Html<String> {
+    Html(phago_viz::player_page("Phago Replay", LOADER_JS))
+}
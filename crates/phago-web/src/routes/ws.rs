@@ -1,5 +1,8 @@
 //! WebSocket handler for real-time colony events.
 
+use super::api::write_refusal;
+use crate::auth::Role;
+use crate::state::RunEvent;
 use crate::AppState;
 use axum::{
     extract::{
@@ -7,6 +10,7 @@ use axum::{
         State,
     },
     response::IntoResponse,
+    Extension,
 };
 use tokio::sync::broadcast;
 
@@ -14,13 +18,17 @@ use tokio::sync::broadcast;
 pub async fn events_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
+    role: Option<Extension<Role>>,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, state))
+    let role = role.map(|Extension(role)| role);
+    ws.on_upgrade(move |socket| handle_socket(socket, state, role))
 }
 
-/// Handle a WebSocket connection.
-async fn handle_socket(mut socket: WebSocket, state: AppState) {
+/// Handle a WebSocket connection authenticated as `role`, if the server
+/// authenticates.
+async fn handle_socket(mut socket: WebSocket, state: AppState, role: Option<Role>) {
     let mut rx = state.subscribe();
+    let mut runs = state.subscribe_runs();
 
     // Send initial snapshot
     {
@@ -30,13 +38,18 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
             "data": snapshot
         });
         if let Ok(json) = serde_json::to_string(&msg) {
-            let _ = socket.send(Message::Text(json.into())).await;
+            let _ = socket.send(Message::Text(json)).await;
         }
     }
 
-    // Stream events as they arrive
+    // Stream events as they arrive, until the client leaves or the server shuts down
+    let shutdown = state.shutdown_token().clone();
     loop {
         tokio::select! {
+            _ = shutdown.triggered() => {
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
             // Receive events from the broadcast channel
             result = rx.recv() => {
                 match result {
@@ -46,7 +59,7 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
                             "data": event
                         });
                         if let Ok(json) = serde_json::to_string(&msg) {
-                            if socket.send(Message::Text(json.into())).await.is_err() {
+                            if socket.send(Message::Text(json)).await.is_err() {
                                 break;
                             }
                         }
@@ -60,6 +73,30 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
                     }
                 }
             }
+            // Background run lifecycle
+            result = runs.recv() => {
+                match result {
+                    Ok(event) => {
+                        let msg = match event {
+                            RunEvent::Started(status) => serde_json::json!({
+                                "type": "run_started",
+                                "data": status
+                            }),
+                            RunEvent::Finished(status) => serde_json::json!({
+                                "type": "run_finished",
+                                "data": status
+                            }),
+                        };
+                        if let Ok(json) = serde_json::to_string(&msg) {
+                            if socket.send(Message::Text(json)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
             // Handle incoming messages (e.g., ping/pong, commands)
             msg = socket.recv() => {
                 match msg {
@@ -68,7 +105,18 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
                         if let Ok(cmd) = serde_json::from_str::<ClientCommand>(&text) {
                             match cmd {
                                 ClientCommand::Tick { count } => {
-                                    state.run(count.unwrap_or(1)).await;
+                                    match write_refusal(&state, role.as_ref()) {
+                                        None => {
+                                            state.run(count.unwrap_or(1)).await;
+                                        }
+                                        Some(error) => {
+                                            let msg = serde_json::json!({
+                                                "type": "error",
+                                                "data": error
+                                            });
+                                            let _ = socket.send(Message::Text(msg.to_string())).await;
+                                        }
+                                    }
                                 }
                                 ClientCommand::Snapshot => {
                                     let snapshot = state.snapshot().await;
@@ -77,7 +125,7 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
                                         "data": snapshot
                                     });
                                     if let Ok(json) = serde_json::to_string(&msg) {
-                                        let _ = socket.send(Message::Text(json.into())).await;
+                                        let _ = socket.send(Message::Text(json)).await;
                                     }
                                 }
                             }
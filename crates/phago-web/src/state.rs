@@ -1,33 +1,545 @@
 //! Application state for the web server.
 //!
-//! Uses a dedicated thread for Colony operations since Colony contains
-//! trait objects that are not Send+Sync.
+//! The colony lives on an [`AsyncColonyRunner`] thread, since Colony
+//! contains trait objects that are not Send+Sync. The runner ticks only
+//! when asked; requests are served between ticks.
+//!
+//! The worker restores the colony from the configured SQLite database
+//! before it reports ready, and saves it back when the server shuts down.
+//! A [`ShutdownToken`] shared with the worker makes long tick runs stop
+//! after the tick in progress.
+//!
+//! The colony reports to a [`PromRegistry`] rendered by `/metrics`.
+//!
+//! Background runs ([`AppState::start_run`]) feed the worker one tick at a
+//! time, so other requests are served between ticks. At most one runs at
+//! a time; it can be throttled to a tick rate and cancelled between ticks.
+//!
+//! With a query cache ([`AppState::with_query_cache`]) repeated queries are
+//! answered without re-ranking until the colony's graph changes.
+//!
+//! A colony kept somewhere other than a SQLite database is loaded and saved
+//! through a [`ColonyStore`] ([`AppState::with_store`]). Other front ends
+//! can serve the same colony through [`AppState::shared_colony`]; the
+//! events of their requests reach the dashboard's subscribers too.
+//!
+//! Uploaded files are spooled to disk and ingested by background jobs
+//! ([`AppState::enqueue_ingest`]), one job at a time in arrival order, so
+//! the request returns before the colony has digested them. Uploads are
+//! limited per client by an [`UploadLimits`] rate and size.
+//!
+//! Statistics, snapshots and path queries are answered from the runner's
+//! read view ([`AppState::with_view_refresh`]), so they run while the
+//! colony ticks instead of waiting for a long run to yield.
+//!
+//! A state serving a published bundle ([`AppState::with_static_bundle`])
+//! answers statistics, snapshots, paths and queries from a
+//! [`StaticColony`] instead, and is read-only.
 
+use crate::auth::AuthTokens;
 use anyhow::Result;
-use phago_core::types::Position;
-use phago_runtime::colony::{Colony, ColonyConfig, ColonyEvent, ColonySnapshot, ColonyStats};
-use std::sync::mpsc;
-use std::thread;
-use tokio::sync::{broadcast, oneshot};
-
-/// Commands sent to the colony worker thread.
-enum ColonyCommand {
-    GetStats(oneshot::Sender<ColonyStats>),
-    GetSnapshot(oneshot::Sender<ColonySnapshot>),
-    RunTicks(u64, oneshot::Sender<Vec<Vec<ColonyEvent>>>),
-    Ingest {
-        title: String,
-        content: String,
-        position: Position,
-        ticks: u64,
-        response: oneshot::Sender<IngestResult>,
-    },
-    Query {
-        query: String,
-        max_results: usize,
-        alpha: f64,
-        response: oneshot::Sender<QueryResult>,
-    },
+use phago::rag::{
+    HybridConfig, HybridConfigError, HybridPage, QueryCache, QueryCacheStats, StaticColony,
+    StaticQueryError,
+};
+use phago_core::types::{Position, Tick};
+use phago_runtime::async_runtime::{
+    AsyncColonyRunner, RunnerColony, RunnerConfig, RunnerHandle, RunnerStopped, SharedColony,
+    TickRate,
+};
+use phago_runtime::colony::{
+    Colony, ColonyConfig, ColonyEvent, ColonySnapshot, ColonyStats, LabelSuggestion,
+};
+use phago_runtime::colony_builder::{ColonyBuilder, PersistentColony};
+use phago_runtime::graph_algorithms::{ConceptPath, PathCost, PathError};
+use phago_runtime::metrics::PromRegistry;
+use phago_runtime::query_log::QuerySource;
+use phago_runtime::read_view::{ColonyView, ViewRefresh};
+use phago_runtime::sampling::SampleConfig;
+use phago_runtime::vocabulary::VocabularyReport;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Cooperative shutdown signal shared by the server, the request
+/// handlers and the colony worker.
+#[derive(Clone)]
+pub struct ShutdownToken {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self {
+            tx: Arc::new(watch::channel(false).0),
+        }
+    }
+}
+
+impl ShutdownToken {
+    /// Request shutdown. Idempotent.
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    /// Whether shutdown has been requested.
+    pub fn is_triggered(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Resolve once shutdown has been requested.
+    pub async fn triggered(&self) {
+        let mut rx = self.tx.subscribe();
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Lifecycle of the colony worker, as reported by `/readyz`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Readiness {
+    /// Restoring state from the database; not serving yet.
+    Restoring,
+    Ready,
+    /// Shutdown requested; finishing in-flight work.
+    ShuttingDown,
+    /// The colony could not be restored.
+    Failed,
+}
+
+impl Readiness {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Readiness::Restoring => "restoring",
+            Readiness::Ready => "ready",
+            Readiness::ShuttingDown => "shutting_down",
+            Readiness::Failed => "failed",
+        }
+    }
+}
+
+const RESTORING: u8 = 0;
+const READY: u8 = 1;
+const FAILED: u8 = 2;
+
+/// How many of the requested ticks actually ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunOutcome {
+    pub requested: u64,
+    pub completed: u64,
+    /// Colony events emitted by the completed ticks.
+    pub events: usize,
+}
+
+impl RunOutcome {
+    /// True if shutdown stopped the run before all ticks completed.
+    pub fn interrupted(&self) -> bool {
+        self.completed < self.requested
+    }
+}
+
+/// Lifecycle of a background run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunState {
+    Running,
+    /// All requested ticks ran.
+    Completed,
+    /// Cancelled through [`AppState::cancel_run`].
+    Cancelled,
+    /// Stopped by server shutdown.
+    Interrupted,
+}
+
+/// Progress of a background run, as reported by `/api/run/:id`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunStatus {
+    pub run_id: u64,
+    pub state: RunState,
+    pub ticks_requested: u64,
+    pub ticks_completed: u64,
+    pub events_emitted: usize,
+    /// Governor limit in ticks per second (`None` = as fast as possible).
+    pub speed: Option<f64>,
+    pub elapsed_ms: u64,
+    /// Estimated time to completion, once it can be estimated.
+    pub eta_ms: Option<u64>,
+}
+
+/// Run lifecycle notifications broadcast to WebSocket clients.
+#[derive(Debug, Clone)]
+pub enum RunEvent {
+    Started(RunStatus),
+    Finished(RunStatus),
+}
+
+/// Returned by [`AppState::start_run`] while another run is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunConflict {
+    pub active_run_id: u64,
+}
+
+/// Mutable progress shared between a run task and status readers.
+#[derive(Debug)]
+struct RunProgress {
+    run_id: u64,
+    state: RunState,
+    requested: u64,
+    completed: u64,
+    events: usize,
+    speed: Option<f64>,
+    started: Instant,
+    finished: Option<Instant>,
+}
+
+impl RunProgress {
+    fn status(&self) -> RunStatus {
+        let elapsed = self.finished.unwrap_or_else(Instant::now) - self.started;
+        let remaining = self.requested - self.completed;
+        let eta = (self.state == RunState::Running).then(|| {
+            let measured = (self.completed > 0)
+                .then(|| elapsed.mul_f64(remaining as f64 / self.completed as f64));
+            let governed = self
+                .speed
+                .map(|speed| Duration::from_secs_f64(remaining as f64 / speed));
+            measured.into_iter().chain(governed).max()
+        });
+        RunStatus {
+            run_id: self.run_id,
+            state: self.state,
+            ticks_requested: self.requested,
+            ticks_completed: self.completed,
+            events_emitted: self.events,
+            speed: self.speed,
+            elapsed_ms: elapsed.as_millis() as u64,
+            eta_ms: eta.flatten().map(|d| d.as_millis() as u64),
+        }
+    }
+}
+
+/// The run in progress: its task and the token that cancels it.
+struct ActiveRun {
+    run_id: u64,
+    cancel: CancellationToken,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Finished runs kept for status queries.
+const RUN_HISTORY: usize = 32;
+
+/// Background runs: the active one plus recent history.
+#[derive(Default)]
+struct RunRegistry {
+    next_id: u64,
+    active: Option<ActiveRun>,
+    runs: BTreeMap<u64, Arc<Mutex<RunProgress>>>,
+}
+
+/// Limits on `POST /api/ingest`, enforced per client.
+#[derive(Debug, Clone, Copy)]
+pub struct UploadLimits {
+    /// Largest request body accepted, in bytes (default: 16 MiB).
+    pub max_upload_bytes: usize,
+    /// Ingest requests each client may make per minute; 0 disables rate
+    /// limiting (default: 60).
+    pub requests_per_minute: u32,
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        Self {
+            max_upload_bytes: 16 * 1024 * 1024,
+            requests_per_minute: 60,
+        }
+    }
+}
+
+/// Token buckets of ingest requests, one per client.
+#[derive(Default)]
+struct RateLimiter {
+    /// Tokens left and when they were last topped up, by client.
+    buckets: HashMap<String, (f64, Instant)>,
+}
+
+impl RateLimiter {
+    /// Take a token for `client`, or say how long until one is available.
+    fn acquire(&mut self, client: &str, per_minute: u32) -> Result<(), Duration> {
+        if per_minute == 0 {
+            return Ok(());
+        }
+        let capacity = per_minute as f64;
+        let rate = capacity / 60.0;
+        let now = Instant::now();
+        // A bucket that has refilled is no different from a new one
+        self.buckets.retain(|_, (tokens, last)| {
+            *tokens + now.duration_since(*last).as_secs_f64() * rate < capacity
+        });
+        let (tokens, last) = self
+            .buckets
+            .entry(client.to_string())
+            .or_insert((capacity, now));
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * rate).min(capacity);
+        *last = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - *tokens) / rate))
+        }
+    }
+}
+
+/// A file received by `POST /api/ingest`, spooled to disk until its job
+/// ingests it.
+pub struct SpooledFile {
+    /// File name as uploaded; its extension picks the text extractor.
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Lifecycle of an ingestion job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    /// Waiting for earlier jobs to finish.
+    Queued,
+    Running,
+    /// Every file was ingested or failed.
+    Completed,
+    /// Stopped by server shutdown; the remaining files were not ingested.
+    Interrupted,
+}
+
+/// What became of one uploaded file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileState {
+    Pending,
+    Ingested,
+    Failed,
+}
+
+/// Progress of one file of an ingestion job.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStatus {
+    pub name: String,
+    pub state: FileState,
+    pub concepts_added: usize,
+    /// Why the file could not be ingested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Progress of an ingestion job, as reported by `/api/ingest/:job`.
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestJobStatus {
+    pub job_id: u64,
+    pub state: JobState,
+    pub files_total: usize,
+    pub files_processed: usize,
+    pub concepts_added: usize,
+    pub files: Vec<FileStatus>,
+}
+
+/// Finished ingestion jobs kept for status queries.
+const JOB_HISTORY: usize = 32;
+
+/// Ingestion jobs, queued and recent.
+#[derive(Default)]
+struct JobRegistry {
+    next_id: u64,
+    jobs: BTreeMap<u64, Arc<Mutex<IngestJobStatus>>>,
+}
+
+impl JobRegistry {
+    /// Forget the oldest finished jobs beyond [`JOB_HISTORY`]. Queued and
+    /// running jobs are kept until they finish.
+    fn evict_finished(&mut self) {
+        let finished: Vec<u64> = self
+            .jobs
+            .iter()
+            .filter(|(_, job)| {
+                matches!(
+                    job.lock().unwrap().state,
+                    JobState::Completed | JobState::Interrupted
+                )
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        let excess = finished.len().saturating_sub(JOB_HISTORY);
+        for id in &finished[..excess] {
+            self.jobs.remove(id);
+        }
+    }
+}
+
+/// What the worker saved, on shutdown or through [`AppState::save`].
+#[derive(Debug, Clone)]
+pub struct ShutdownReport {
+    /// Database or file the colony was saved to, if persistence is
+    /// configured.
+    pub saved_to: Option<PathBuf>,
+    pub tick: Tick,
+    pub nodes: usize,
+    pub edges: usize,
+}
+
+/// How the dashboard records snapshots for replay.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordingConfig {
+    /// Record a snapshot every N ticks; 0 disables recording (default: 1).
+    pub interval: u64,
+    /// Maximum snapshots kept; the oldest are dropped first (default: 500).
+    pub capacity: usize,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            interval: 1,
+            capacity: 500,
+        }
+    }
+}
+
+/// Inclusive tick bounds for reading back a recording.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TickRange {
+    pub from_tick: Option<Tick>,
+    pub to_tick: Option<Tick>,
+}
+
+impl TickRange {
+    fn contains(&self, tick: Tick) -> bool {
+        self.from_tick.is_none_or(|from| tick >= from) && self.to_tick.is_none_or(|to| tick <= to)
+    }
+}
+
+/// Recorded snapshots and the events that happened between them.
+pub struct Recording {
+    pub snapshots: Vec<ColonySnapshot>,
+    pub events: Vec<(Tick, ColonyEvent)>,
+}
+
+/// Ring buffer of snapshots, filled as the worker runs ticks.
+struct SnapshotRecorder {
+    config: RecordingConfig,
+    snapshots: VecDeque<ColonySnapshot>,
+}
+
+impl SnapshotRecorder {
+    fn new(config: RecordingConfig) -> Self {
+        Self {
+            config,
+            snapshots: VecDeque::new(),
+        }
+    }
+
+    /// Record the colony if the current tick falls on the interval.
+    fn observe(&mut self, colony: &Colony) {
+        let tick = colony.stats().tick;
+        if self.config.interval == 0
+            || self.config.capacity == 0
+            || !tick.is_multiple_of(self.config.interval)
+        {
+            return;
+        }
+        if self.snapshots.len() >= self.config.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(colony.snapshot());
+    }
+
+    /// Snapshots in `range`, plus the colony's events from the first of
+    /// them up to the end of the range.
+    fn read(&self, colony: &Colony, range: TickRange) -> Recording {
+        let snapshots: Vec<ColonySnapshot> = self
+            .snapshots
+            .iter()
+            .filter(|s| range.contains(s.tick))
+            .cloned()
+            .collect();
+        let events = match snapshots.first() {
+            Some(first) => {
+                let events_range = TickRange {
+                    from_tick: Some(first.tick),
+                    to_tick: range.to_tick,
+                };
+                colony
+                    .event_history()
+                    .iter()
+                    .filter(|(tick, _)| events_range.contains(*tick))
+                    .cloned()
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        Recording { snapshots, events }
+    }
+}
+
+/// Loads and saves the colony for an [`AppState`] that does not keep it in
+/// a SQLite database. Both run on the colony worker thread.
+pub trait ColonyStore: Send + 'static {
+    /// Build the colony to serve. Called once, before the worker reports
+    /// ready.
+    fn load(&mut self) -> Result<Colony>;
+
+    /// Save the colony and return where it went.
+    fn save(&mut self, colony: &Colony) -> Result<PathBuf>;
+}
+
+/// Where the worker gets its colony from.
+enum ColonySource {
+    Database(Option<String>),
+    Store(Box<dyn ColonyStore>),
+}
+
+/// What the colony runner owns: the colony with its database or store,
+/// the snapshot recorder and the query cache.
+struct WebColony {
+    persistent: PersistentColony,
+    store: Option<Box<dyn ColonyStore>>,
+    recorder: SnapshotRecorder,
+    cache: Option<Arc<QueryCache>>,
+}
+
+impl WebColony {
+    fn save(&mut self) -> Result<ShutdownReport> {
+        let saved_to = match &mut self.store {
+            Some(store) => Some(store.save(self.persistent.colony())?),
+            None => {
+                self.persistent.save()?;
+                self.persistent.persistence_path().map(PathBuf::from)
+            }
+        };
+        let stats = self.persistent.stats();
+        Ok(ShutdownReport {
+            saved_to,
+            tick: stats.tick,
+            nodes: stats.graph_nodes,
+            edges: stats.graph_edges,
+        })
+    }
+}
+
+impl RunnerColony for WebColony {
+    fn colony(&self) -> &Colony {
+        self.persistent.colony()
+    }
+
+    fn colony_mut(&mut self) -> &mut Colony {
+        self.persistent.colony_mut()
+    }
+
+    fn after_tick(&mut self, _events: &[ColonyEvent]) {
+        self.recorder.observe(self.persistent.colony());
+    }
 }
 
 /// Result of an ingest operation.
@@ -43,6 +555,10 @@ pub struct QueryResult {
     pub results: Vec<QueryHit>,
     pub total_nodes: usize,
     pub total_edges: usize,
+    /// Cursor for the next page, if there is one.
+    pub next_cursor: Option<String>,
+    /// The cursor followed had expired; `results` are a fresh first page.
+    pub cursor_expired: bool,
 }
 
 pub struct QueryHit {
@@ -55,115 +571,286 @@ pub struct QueryHit {
 /// Shared application state.
 #[derive(Clone)]
 pub struct AppState {
-    /// Channel to send commands to the colony worker.
-    cmd_tx: mpsc::Sender<ColonyCommand>,
-    /// Broadcast channel for colony events.
-    pub event_tx: broadcast::Sender<ColonyEvent>,
+    /// Handle to the colony runner; also broadcasts colony events.
+    runner: RunnerHandle<WebColony>,
+    /// Broadcast channel for background run starts and finishes.
+    run_tx: broadcast::Sender<RunEvent>,
+    runs: Arc<Mutex<RunRegistry>>,
+    shutdown: ShutdownToken,
+    readiness: Arc<AtomicU8>,
+    metrics: Arc<PromRegistry>,
+    query_cache: Option<Arc<QueryCache>>,
+    /// Reject requests that change the colony.
+    read_only: bool,
+    /// Published bundle served in place of the colony, if any.
+    static_colony: Option<Arc<StaticColony>>,
+    /// Tokens requests must carry, if any.
+    auth: Option<Arc<AuthTokens>>,
+    upload_limits: UploadLimits,
+    rate_limiter: Arc<Mutex<RateLimiter>>,
+    jobs: Arc<Mutex<JobRegistry>>,
+    /// Held by the job being ingested; waiters get it in arrival order.
+    job_queue: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl AppState {
     /// Create a new app state, optionally with SQLite persistence.
-    pub fn new(_db_path: Option<String>) -> Result<Self> {
-        let (cmd_tx, cmd_rx) = mpsc::channel();
-        let (event_tx, _) = broadcast::channel(1000);
-        let event_tx_clone = event_tx.clone();
-
-        // Spawn dedicated thread for Colony operations
-        thread::spawn(move || {
-            let mut colony = Colony::from_config(ColonyConfig::default());
-
-            while let Ok(cmd) = cmd_rx.recv() {
-                match cmd {
-                    ColonyCommand::GetStats(response) => {
-                        let _ = response.send(colony.stats());
-                    }
-                    ColonyCommand::GetSnapshot(response) => {
-                        let _ = response.send(colony.snapshot());
-                    }
-                    ColonyCommand::RunTicks(ticks, response) => {
-                        let all_events = colony.run(ticks);
-                        // Broadcast events
-                        for events in &all_events {
-                            for event in events {
-                                let _ = event_tx_clone.send(event.clone());
-                            }
-                        }
-                        let _ = response.send(all_events);
-                    }
-                    ColonyCommand::Ingest {
-                        title,
-                        content,
-                        position,
-                        ticks,
-                        response,
-                    } => {
-                        use phago::prelude::Digester;
-
-                        let before_nodes = colony.stats().graph_nodes;
-                        let before_edges = colony.stats().graph_edges;
-
-                        let doc_id = colony.ingest_document(&title, &content, position);
-                        colony.spawn(Box::new(Digester::new(position).with_max_idle(30)));
-
-                        let all_events = colony.run(ticks);
-                        for events in &all_events {
-                            for event in events {
-                                let _ = event_tx_clone.send(event.clone());
-                            }
-                        }
+    pub fn new(db_path: Option<String>) -> Result<Self> {
+        Self::with_recording(db_path, RecordingConfig::default())
+    }
 
-                        let after_nodes = colony.stats().graph_nodes;
-                        let after_edges = colony.stats().graph_edges;
+    /// Create a new app state that records snapshots for replay as configured.
+    ///
+    /// Returns immediately; the colony is restored from `db_path` on the
+    /// worker thread, and [`readiness`](Self::readiness) reports
+    /// [`Readiness::Ready`] once it has loaded.
+    pub fn with_recording(db_path: Option<String>, recording: RecordingConfig) -> Result<Self> {
+        Self::with_query_cache(db_path, recording, 0)
+    }
 
-                        let _ = response.send(IngestResult {
-                            document_id: format!("{}", doc_id.0),
-                            nodes_created: after_nodes.saturating_sub(before_nodes),
-                            edges_created: after_edges.saturating_sub(before_edges),
-                            tick: colony.stats().tick,
-                        });
-                    }
-                    ColonyCommand::Query {
-                        query,
-                        max_results,
-                        alpha,
-                        response,
-                    } => {
-                        use phago::rag::{hybrid_query, HybridConfig};
-
-                        let config = HybridConfig {
-                            alpha,
-                            max_results,
-                            candidate_multiplier: 3,
-                        };
-                        let results = hybrid_query(&colony, &query, &config);
-                        let stats = colony.stats();
-
-                        let _ = response.send(QueryResult {
-                            results: results
-                                .into_iter()
-                                .map(|r| QueryHit {
-                                    label: r.label,
-                                    score: r.final_score,
-                                    tfidf_score: r.tfidf_score,
-                                    graph_score: r.graph_score,
-                                })
-                                .collect(),
-                            total_nodes: stats.graph_nodes,
-                            total_edges: stats.graph_edges,
-                        });
-                    }
+    /// Like [`with_recording`](Self::with_recording), also caching up to
+    /// `query_cache` query results while the graph is unchanged (0 disables
+    /// the cache).
+    pub fn with_query_cache(
+        db_path: Option<String>,
+        recording: RecordingConfig,
+        query_cache: usize,
+    ) -> Result<Self> {
+        Ok(Self::spawn(
+            ColonySource::Database(db_path),
+            recording,
+            query_cache,
+        ))
+    }
+
+    /// Like [`with_query_cache`](Self::with_query_cache), loading the
+    /// colony from `store` and saving it back there.
+    pub fn with_store(
+        store: impl ColonyStore,
+        recording: RecordingConfig,
+        query_cache: usize,
+    ) -> Result<Self> {
+        Ok(Self::spawn(
+            ColonySource::Store(Box::new(store)),
+            recording,
+            query_cache,
+        ))
+    }
+
+    fn spawn(source: ColonySource, recording: RecordingConfig, query_cache: usize) -> Self {
+        let shutdown = ShutdownToken::default();
+        let readiness = Arc::new(AtomicU8::new(RESTORING));
+        let worker_readiness = readiness.clone();
+        let metrics = Arc::new(PromRegistry::new());
+        let worker_metrics = metrics.clone();
+        let query_cache = (query_cache > 0).then(|| Arc::new(QueryCache::new(query_cache)));
+        let worker_cache = query_cache.clone();
+
+        // The runner restores the colony on its own thread
+        let config = RunnerConfig {
+            view: Some(ViewRefresh::EveryTick),
+            ..RunnerConfig::new(TickRate::Manual)
+        };
+        let (runner, _thread) = AsyncColonyRunner::spawn(config, move || {
+            let mut builder = ColonyBuilder::new()
+                .with_config(ColonyConfig::default())
+                .auto_save(true);
+            let mut store = None;
+            match source {
+                ColonySource::Database(Some(path)) => {
+                    builder = builder.with_persistence(path);
                 }
+                ColonySource::Database(None) => {}
+                ColonySource::Store(s) => store = Some(s),
             }
+            let restored =
+                builder
+                    .build()
+                    .map_err(anyhow::Error::from)
+                    .and_then(|mut persistent| {
+                        if let Some(store) = &mut store {
+                            *persistent.colony_mut() = store.load()?;
+                        }
+                        Ok(persistent)
+                    });
+            let mut persistent = match restored {
+                Ok(persistent) => persistent,
+                Err(e) => {
+                    tracing::error!("failed to restore colony: {}", e);
+                    worker_readiness.store(FAILED, Ordering::SeqCst);
+                    return None;
+                }
+            };
+            persistent
+                .colony_mut()
+                .attach_metrics(worker_metrics, "default");
+            let mut recorder = SnapshotRecorder::new(recording);
+            recorder.observe(persistent.colony());
+            worker_readiness.store(READY, Ordering::SeqCst);
+            Some(WebColony {
+                persistent,
+                store,
+                recorder,
+                cache: worker_cache,
+            })
         });
 
-        Ok(Self { cmd_tx, event_tx })
+        Self {
+            runner,
+            run_tx: broadcast::channel(64).0,
+            runs: Arc::new(Mutex::new(RunRegistry::default())),
+            shutdown,
+            readiness,
+            metrics,
+            query_cache,
+            read_only: false,
+            static_colony: None,
+            auth: None,
+            upload_limits: UploadLimits::default(),
+            rate_limiter: Arc::new(Mutex::new(RateLimiter::default())),
+            jobs: Arc::new(Mutex::new(JobRegistry::default())),
+            job_queue: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// Serve the bundle published in `dir` (see
+    /// [`publish_bundle`](phago_runtime::publish::publish_bundle)),
+    /// read-only. Fails if the bundle cannot be read.
+    pub fn with_static_bundle(dir: impl AsRef<Path>) -> Result<Self> {
+        let bundle = StaticColony::open(dir)?;
+        bundle.load()?;
+        let mut state = Self::spawn(ColonySource::Database(None), RecordingConfig::default(), 0);
+        state.static_colony = Some(Arc::new(bundle));
+        Ok(state.with_read_only(true))
+    }
+
+    /// The published bundle served, if any.
+    pub fn static_colony(&self) -> Option<&StaticColony> {
+        self.static_colony.as_deref()
+    }
+
+    /// Reject ingests, ticks and runs with `403 Forbidden`; reads are
+    /// still served.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Answer only requests carrying one of `tokens`; see [`crate::auth`].
+    pub fn with_auth(mut self, tokens: AuthTokens) -> Self {
+        self.auth = Some(Arc::new(tokens));
+        self
+    }
+
+    /// The tokens requests must carry, or `None` if requests need none.
+    pub fn auth(&self) -> Option<&AuthTokens> {
+        self.auth.as_deref()
+    }
+
+    /// Answer reads from a view refreshed as `refresh` says (every tick by
+    /// default), or from the colony itself between ticks with `None`.
+    pub fn with_view_refresh(self, refresh: Option<ViewRefresh>) -> Self {
+        self.runner.set_view_refresh(refresh);
+        self
+    }
+
+    /// Limit the size and per-client rate of ingest requests.
+    pub fn with_upload_limits(mut self, limits: UploadLimits) -> Self {
+        self.upload_limits = limits;
+        self
+    }
+
+    pub fn upload_limits(&self) -> UploadLimits {
+        self.upload_limits
+    }
+
+    /// Count an ingest request against `client`'s rate limit. Fails with
+    /// the time until the client may try again.
+    pub fn check_rate(&self, client: &str) -> Result<(), Duration> {
+        let per_minute = self.upload_limits.requests_per_minute;
+        self.rate_limiter
+            .lock()
+            .unwrap()
+            .acquire(client, per_minute)
+    }
+
+    /// A handle for serving this colony from another front end, such as an
+    /// MCP server. Its requests are served in turn with the dashboard's.
+    pub fn shared_colony(&self) -> SharedColony {
+        self.runner.shared()
+    }
+
+    /// Query cache hits and misses, if the cache is enabled.
+    pub fn query_cache_stats(&self) -> Option<QueryCacheStats> {
+        self.query_cache.as_ref().map(|cache| cache.stats())
+    }
+
+    /// The registry the colony reports its metrics to.
+    pub fn metrics(&self) -> &PromRegistry {
+        &self.metrics
+    }
+
+    /// Current lifecycle state of the colony worker.
+    pub fn readiness(&self) -> Readiness {
+        if self.shutdown.is_triggered() {
+            return Readiness::ShuttingDown;
+        }
+        match self.readiness.load(Ordering::SeqCst) {
+            READY => Readiness::Ready,
+            FAILED => Readiness::Failed,
+            _ => Readiness::Restoring,
+        }
+    }
+
+    /// The token that stops tick runs when shutdown is requested.
+    pub fn shutdown_token(&self) -> &ShutdownToken {
+        &self.shutdown
+    }
+
+    /// Request shutdown: readiness turns to `ShuttingDown` and running
+    /// tick loops stop after the tick in progress.
+    pub fn begin_shutdown(&self) {
+        self.shutdown.trigger();
+    }
+
+    /// Save the colony to the configured database or store, between
+    /// requests.
+    pub async fn save(&self) -> Result<ShutdownReport> {
+        self.runner
+            .with(|web| web.save())
+            .await
+            .map_err(|_| anyhow::anyhow!("colony worker is not running"))?
+    }
+
+    /// Request shutdown, wait for in-flight work, then save the colony to
+    /// the configured database or store and stop the worker.
+    pub async fn shutdown_and_save(&self) -> Result<ShutdownReport> {
+        self.begin_shutdown();
+        let report = self.save().await;
+        self.runner.stop();
+        report
+    }
+
+    /// The view reads are answered from: the published bundle's when
+    /// serving one, else the runner's.
+    fn read_view(&self) -> Option<Arc<ColonyView>> {
+        match &self.static_colony {
+            Some(bundle) => bundle.view().ok().cloned().map(Arc::new),
+            None => self.runner.view(),
+        }
     }
 
     /// Get colony statistics.
     pub async fn stats(&self) -> ColonyStats {
-        let (tx, rx) = oneshot::channel();
-        let _ = self.cmd_tx.send(ColonyCommand::GetStats(tx));
-        rx.await.unwrap_or_else(|_| ColonyStats {
+        if let Some(view) = self.read_view() {
+            return view.stats().clone();
+        }
+        let stats = self.runner.query(|colony| colony.stats()).await;
+        stats.unwrap_or(ColonyStats {
             tick: 0,
             agents_alive: 0,
             agents_died: 0,
@@ -171,16 +858,47 @@ impl AppState {
             graph_nodes: 0,
             graph_edges: 0,
             total_signals: 0,
+            signals_by_kind: Default::default(),
             documents_total: 0,
             documents_digested: 0,
+            trace_bytes: 0,
+            traces_evicted: 0,
+            signals_merged: 0,
+            signals_evicted: 0,
+            errors: Default::default(),
+            decay: Default::default(),
+            maintenance_backlog: 0,
+            documents_expiring: 0,
+            next_expiry_tick: None,
+            exploitation: 0.0,
         })
     }
 
     /// Get colony snapshot.
     pub async fn snapshot(&self) -> ColonySnapshot {
-        let (tx, rx) = oneshot::channel();
-        let _ = self.cmd_tx.send(ColonyCommand::GetSnapshot(tx));
-        rx.await.unwrap_or_else(|_| ColonySnapshot {
+        self.sampled_snapshot(None).await
+    }
+
+    /// Get a colony snapshot of the nodes `sample` keeps, or of the whole
+    /// graph without one.
+    pub async fn sampled_snapshot(&self, sample: Option<SampleConfig>) -> ColonySnapshot {
+        let snapshot = match self.read_view() {
+            Some(view) => tokio::task::spawn_blocking(move || match &sample {
+                Some(config) => view.sample_snapshot(config),
+                None => view.snapshot(),
+            })
+            .await
+            .map_err(|_| RunnerStopped),
+            None => {
+                self.runner
+                    .query(move |colony| match &sample {
+                        Some(config) => colony.sample_snapshot(config),
+                        None => colony.snapshot(),
+                    })
+                    .await
+            }
+        };
+        snapshot.unwrap_or_else(|_| ColonySnapshot {
             tick: 0,
             agents: vec![],
             nodes: vec![],
@@ -193,17 +911,179 @@ impl AppState {
                 graph_nodes: 0,
                 graph_edges: 0,
                 total_signals: 0,
+                signals_by_kind: Default::default(),
                 documents_total: 0,
                 documents_digested: 0,
+                trace_bytes: 0,
+                traces_evicted: 0,
+                signals_merged: 0,
+                signals_evicted: 0,
+                errors: Default::default(),
+                decay: Default::default(),
+                maintenance_backlog: 0,
+                documents_expiring: 0,
+                next_expiry_tick: None,
+                exploitation: 0.0,
             },
+            world_bounds: None,
+            regions: Vec::new(),
+            coverage: None,
         })
     }
 
-    /// Run N ticks.
-    pub async fn run(&self, ticks: u64) -> Vec<Vec<ColonyEvent>> {
-        let (tx, rx) = oneshot::channel();
-        let _ = self.cmd_tx.send(ColonyCommand::RunTicks(ticks, tx));
-        rx.await.unwrap_or_default()
+    /// Run N ticks, stopping early if shutdown is requested. Other
+    /// requests are served between the ticks.
+    pub async fn run(&self, ticks: u64) -> RunOutcome {
+        let mut outcome = RunOutcome {
+            requested: ticks,
+            completed: 0,
+            events: 0,
+        };
+        while outcome.completed < ticks && !self.shutdown.is_triggered() {
+            let Ok(events) = self.runner.tick().await else {
+                break;
+            };
+            outcome.events += events.len();
+            outcome.completed += 1;
+        }
+        outcome
+    }
+
+    /// Start running `ticks` ticks in the background, at most `speed` ticks
+    /// per second if set. Must be called within a Tokio runtime.
+    ///
+    /// Fails with the active run's id if a run is already in progress.
+    pub fn start_run(&self, ticks: u64, speed: Option<f64>) -> Result<RunStatus, RunConflict> {
+        let speed = speed.filter(|s| s.is_finite() && *s > 0.0);
+        let mut runs = self.runs.lock().unwrap();
+        if let Some(active) = &runs.active {
+            return Err(RunConflict {
+                active_run_id: active.run_id,
+            });
+        }
+
+        runs.next_id += 1;
+        let run_id = runs.next_id;
+        let progress = Arc::new(Mutex::new(RunProgress {
+            run_id,
+            state: RunState::Running,
+            requested: ticks,
+            completed: 0,
+            events: 0,
+            speed,
+            started: Instant::now(),
+            finished: None,
+        }));
+        runs.runs.insert(run_id, progress.clone());
+        while runs.runs.len() > RUN_HISTORY {
+            runs.runs.pop_first();
+        }
+
+        let status = progress.lock().unwrap().status();
+        let _ = self.run_tx.send(RunEvent::Started(status.clone()));
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn(self.clone().drive_run(progress, cancel.clone()));
+        runs.active = Some(ActiveRun {
+            run_id,
+            cancel,
+            task: Some(task),
+        });
+        Ok(status)
+    }
+
+    /// Feed the worker one tick at a time until the run is done, cancelled
+    /// or interrupted by shutdown, pacing ticks to the governor.
+    async fn drive_run(self, progress: Arc<Mutex<RunProgress>>, cancel: CancellationToken) {
+        let (run_id, requested, speed) = {
+            let p = progress.lock().unwrap();
+            (p.run_id, p.requested, p.speed)
+        };
+        let interval = speed.map(|s| Duration::from_secs_f64(1.0 / s));
+        let mut next_tick = tokio::time::Instant::now();
+
+        let mut completed = 0;
+        let state = loop {
+            if completed == requested {
+                break RunState::Completed;
+            }
+            if let Some(interval) = interval {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(next_tick) => {}
+                    _ = cancel.cancelled() => {}
+                    _ = self.shutdown.triggered() => {}
+                }
+                next_tick += interval;
+            }
+            if cancel.is_cancelled() {
+                break RunState::Cancelled;
+            }
+            if self.shutdown.is_triggered() {
+                break RunState::Interrupted;
+            }
+
+            let outcome = self.run(1).await;
+            if outcome.completed == 0 {
+                break RunState::Interrupted;
+            }
+            completed += 1;
+            let mut p = progress.lock().unwrap();
+            p.completed = completed;
+            p.events += outcome.events;
+        };
+
+        let status = {
+            let mut p = progress.lock().unwrap();
+            p.state = state;
+            p.finished = Some(Instant::now());
+            p.status()
+        };
+        {
+            let mut runs = self.runs.lock().unwrap();
+            if runs.active.as_ref().is_some_and(|a| a.run_id == run_id) {
+                runs.active = None;
+            }
+        }
+        let _ = self.run_tx.send(RunEvent::Finished(status));
+    }
+
+    /// Progress of a run started by [`start_run`](Self::start_run), if it
+    /// is active or among the recently finished runs.
+    pub fn run_status(&self, run_id: u64) -> Option<RunStatus> {
+        let runs = self.runs.lock().unwrap();
+        let progress = runs.runs.get(&run_id)?;
+        let status = progress.lock().unwrap().status();
+        Some(status)
+    }
+
+    /// Id of the run in progress, if any.
+    pub fn active_run(&self) -> Option<u64> {
+        self.runs.lock().unwrap().active.as_ref().map(|a| a.run_id)
+    }
+
+    /// Cancel a run at the next tick boundary and wait for it to stop.
+    ///
+    /// Returns the run's final status, or `None` if the run is unknown.
+    /// Cancelling a finished run just reports its status.
+    pub async fn cancel_run(&self, run_id: u64) -> Option<RunStatus> {
+        let task = {
+            let mut runs = self.runs.lock().unwrap();
+            match runs.active.as_mut() {
+                Some(active) if active.run_id == run_id => {
+                    active.cancel.cancel();
+                    active.task.take()
+                }
+                _ => None,
+            }
+        };
+        if let Some(task) = task {
+            let _ = task.await;
+        }
+        self.run_status(run_id)
+    }
+
+    /// Subscribe to background run starts and finishes.
+    pub fn subscribe_runs(&self) -> broadcast::Receiver<RunEvent> {
+        self.run_tx.subscribe()
     }
 
     /// Ingest a document.
@@ -214,40 +1094,300 @@ impl AppState {
         position: Position,
         ticks: u64,
     ) -> IngestResult {
-        let (tx, rx) = oneshot::channel();
-        let _ = self.cmd_tx.send(ColonyCommand::Ingest {
-            title,
-            content,
-            position,
-            ticks,
-            response: tx,
-        });
-        rx.await.unwrap_or_else(|_| IngestResult {
-            document_id: "error".to_string(),
-            nodes_created: 0,
-            edges_created: 0,
-            tick: 0,
+        use phago::prelude::Digester;
+
+        let ingested = self
+            .runner
+            .with(move |web| {
+                let colony = web.colony_mut();
+                let before = colony.stats();
+                let doc_id = colony.ingest_document(&title, &content, position);
+                colony.spawn(Box::new(Digester::new(position).with_max_idle(30)));
+                (doc_id, before)
+            })
+            .await;
+        let Ok((doc_id, before)) = ingested else {
+            return IngestResult {
+                document_id: "error".to_string(),
+                nodes_created: 0,
+                edges_created: 0,
+                tick: 0,
+            };
+        };
+
+        self.run(ticks).await;
+        let after = self.stats().await;
+        IngestResult {
+            document_id: format!("{}", doc_id.0),
+            nodes_created: after.graph_nodes.saturating_sub(before.graph_nodes),
+            edges_created: after.graph_edges.saturating_sub(before.graph_edges),
+            tick: after.tick,
+        }
+    }
+
+    /// Queue `files` for ingestion, `ticks` ticks after each, and return
+    /// the job's status right away. Must be called within a Tokio runtime.
+    ///
+    /// `spool` holds the files and is removed once the job is done.
+    pub fn enqueue_ingest(
+        &self,
+        files: Vec<SpooledFile>,
+        spool: tempfile::TempDir,
+        ticks: u64,
+    ) -> IngestJobStatus {
+        let mut jobs = self.jobs.lock().unwrap();
+        jobs.next_id += 1;
+        let job_id = jobs.next_id;
+        let progress = Arc::new(Mutex::new(IngestJobStatus {
+            job_id,
+            state: JobState::Queued,
+            files_total: files.len(),
+            files_processed: 0,
+            concepts_added: 0,
+            files: files
+                .iter()
+                .map(|file| FileStatus {
+                    name: file.name.clone(),
+                    state: FileState::Pending,
+                    concepts_added: 0,
+                    error: None,
+                })
+                .collect(),
+        }));
+        jobs.jobs.insert(job_id, progress.clone());
+        jobs.evict_finished();
+
+        let status = progress.lock().unwrap().clone();
+        tokio::spawn(self.clone().drive_ingest_job(progress, files, spool, ticks));
+        status
+    }
+
+    /// Wait for earlier jobs, then extract and ingest the files one at a
+    /// time, stopping if shutdown is requested.
+    async fn drive_ingest_job(
+        self,
+        progress: Arc<Mutex<IngestJobStatus>>,
+        files: Vec<SpooledFile>,
+        spool: tempfile::TempDir,
+        ticks: u64,
+    ) {
+        let _turn = self.job_queue.lock().await;
+        progress.lock().unwrap().state = JobState::Running;
+
+        for (i, file) in files.into_iter().enumerate() {
+            if self.shutdown.is_triggered() {
+                progress.lock().unwrap().state = JobState::Interrupted;
+                return;
+            }
+            let path = file.path;
+            let extracted = tokio::task::spawn_blocking(move || {
+                phago_runtime::extract::extract_text(&path).map_err(|e| e.to_string())
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+            let outcome = match extracted {
+                Ok(doc) => {
+                    let result = self
+                        .ingest(doc.title, doc.content, Position::new(0.0, 0.0), ticks)
+                        .await;
+                    if result.document_id == "error" {
+                        Err("colony worker is not running".to_string())
+                    } else {
+                        Ok(result.nodes_created)
+                    }
+                }
+                Err(e) => Err(e),
+            };
+
+            let mut p = progress.lock().unwrap();
+            match outcome {
+                Ok(concepts) => {
+                    p.files[i].state = FileState::Ingested;
+                    p.files[i].concepts_added = concepts;
+                    p.concepts_added += concepts;
+                }
+                Err(e) => {
+                    p.files[i].state = FileState::Failed;
+                    p.files[i].error = Some(e);
+                }
+            }
+            p.files_processed += 1;
+        }
+
+        progress.lock().unwrap().state = JobState::Completed;
+        drop(spool);
+    }
+
+    /// Progress of an ingestion job, if it is queued, running or among
+    /// the recent ones.
+    pub fn ingest_job(&self, job_id: u64) -> Option<IngestJobStatus> {
+        let jobs = self.jobs.lock().unwrap();
+        let progress = jobs.jobs.get(&job_id)?;
+        let status = progress.lock().unwrap().clone();
+        Some(status)
+    }
+
+    /// Query the knowledge graph, one page of `max_results` at a time;
+    /// `cursor` continues from a previous page. Fails if `alpha` or
+    /// `max_results` are out of range or the cursor belongs to another query.
+    pub async fn query(
+        &self,
+        query: String,
+        max_results: usize,
+        alpha: f64,
+        cursor: Option<String>,
+    ) -> Result<QueryResult, HybridConfigError> {
+        let config = HybridConfig::builder()
+            .alpha(alpha)
+            .max_results(max_results)
+            .source(QuerySource::Web)
+            .build()?;
+        if let Some(bundle) = self.static_colony.clone() {
+            return Self::query_static(bundle, query, config, cursor).await;
+        }
+        let result = self
+            .runner
+            .with(move |web| {
+                let colony = web.persistent.colony_mut();
+                let cursor = cursor.as_deref();
+                let page = match &web.cache {
+                    Some(cache) => {
+                        phago::rag::hybrid_query_page_cached(colony, cache, &query, &config, cursor)
+                    }
+                    None => phago::rag::hybrid_query_page(colony, &query, &config, cursor),
+                };
+                page.map(|page| {
+                    phago::rag::touch_results(colony, &page.results);
+                    let stats = colony.stats();
+                    QueryResult {
+                        results: page
+                            .results
+                            .into_iter()
+                            .map(|r| QueryHit {
+                                label: r.label,
+                                score: r.final_score,
+                                tfidf_score: r.tfidf_score,
+                                graph_score: r.graph_score,
+                            })
+                            .collect(),
+                        total_nodes: stats.graph_nodes,
+                        total_edges: stats.graph_edges,
+                        next_cursor: page.next_cursor,
+                        cursor_expired: page.cursor_expired,
+                    }
+                })
+            })
+            .await;
+        result.unwrap_or_else(|_| {
+            Ok(QueryResult {
+                results: vec![],
+                total_nodes: 0,
+                total_edges: 0,
+                next_cursor: None,
+                cursor_expired: false,
+            })
         })
     }
 
-    /// Query the knowledge graph.
-    pub async fn query(&self, query: String, max_results: usize, alpha: f64) -> QueryResult {
-        let (tx, rx) = oneshot::channel();
-        let _ = self.cmd_tx.send(ColonyCommand::Query {
-            query,
-            max_results,
-            alpha,
-            response: tx,
-        });
-        rx.await.unwrap_or_else(|_| QueryResult {
-            results: vec![],
-            total_nodes: 0,
-            total_edges: 0,
+    /// [`query`](Self::query), answered from the published bundle.
+    async fn query_static(
+        bundle: Arc<StaticColony>,
+        query: String,
+        config: HybridConfig,
+        cursor: Option<String>,
+    ) -> Result<QueryResult, HybridConfigError> {
+        let stats = bundle.manifest().stats.clone();
+        let page = tokio::task::spawn_blocking(move || {
+            bundle.hybrid_query_page(&query, &config, cursor.as_deref())
+        })
+        .await;
+        let page = match page {
+            Ok(Ok(page)) => page,
+            Ok(Err(StaticQueryError::Config(e))) => return Err(e),
+            Ok(Err(StaticQueryError::Bundle(e))) => {
+                tracing::error!("failed to read the static bundle: {}", e);
+                HybridPage {
+                    results: vec![],
+                    next_cursor: None,
+                    cursor_expired: false,
+                }
+            }
+            Err(_) => HybridPage {
+                results: vec![],
+                next_cursor: None,
+                cursor_expired: false,
+            },
+        };
+        Ok(QueryResult {
+            results: page
+                .results
+                .into_iter()
+                .map(|r| QueryHit {
+                    label: r.label,
+                    score: r.final_score,
+                    tfidf_score: r.tfidf_score,
+                    graph_score: r.graph_score,
+                })
+                .collect(),
+            total_nodes: stats.graph_nodes,
+            total_edges: stats.graph_edges,
+            next_cursor: page.next_cursor,
+            cursor_expired: page.cursor_expired,
+        })
+    }
+
+    /// Recorded snapshots (and the events between them) within `range`.
+    pub async fn recording(&self, range: TickRange) -> Recording {
+        let recording = self
+            .runner
+            .with(move |web| web.recorder.read(web.persistent.colony(), range))
+            .await;
+        recording.unwrap_or_else(|_| Recording {
+            snapshots: vec![],
+            events: vec![],
         })
     }
 
+    /// Labels completing `prefix`, most useful first.
+    pub async fn suggest(&self, prefix: String, limit: usize) -> Vec<LabelSuggestion> {
+        let suggestions = self
+            .runner
+            .query(move |colony| colony.suggest_labels(&prefix, limit))
+            .await;
+        suggestions.unwrap_or_default()
+    }
+
+    /// The colony's vocabulary report over `top_n` terms per agent.
+    pub async fn vocabulary_report(&self, top_n: usize) -> Option<VocabularyReport> {
+        self.runner
+            .query(move |colony| colony.vocabulary_report(top_n))
+            .await
+            .ok()
+    }
+
+    /// Up to `k` cheapest paths between two concepts.
+    pub async fn find_path(
+        &self,
+        from: String,
+        to: String,
+        cost: PathCost,
+        k: usize,
+    ) -> Result<Vec<ConceptPath>, PathError> {
+        let paths = match self.read_view() {
+            Some(view) => tokio::task::spawn_blocking(move || view.find_path(&from, &to, cost, k))
+                .await
+                .map_err(|_| RunnerStopped),
+            None => {
+                self.runner
+                    .query(move |colony| colony.find_path(&from, &to, cost, k))
+                    .await
+            }
+        };
+        paths.unwrap_or_else(|_| Ok(Vec::new()))
+    }
+
     /// Subscribe to events.
     pub fn subscribe(&self) -> broadcast::Receiver<ColonyEvent> {
-        self.event_tx.subscribe()
+        self.runner.subscribe()
     }
 }
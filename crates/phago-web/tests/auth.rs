@@ -0,0 +1,125 @@
+//! Bearer-token authentication, and what read and write tokens may do.
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use common::{get_as, send, wait_ready_as};
+use phago_web::routes::create_router;
+use phago_web::{AppState, AuthTokens, Role};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tower::ServiceExt;
+
+const READ: &str = "reader-token";
+const WRITE: &str = "writer-token";
+
+fn app() -> Router {
+    let tokens = AuthTokens::new()
+        .with_token(READ, Role::Read)
+        .with_token(WRITE, Role::Write);
+    create_router(AppState::new(None).unwrap().with_auth(tokens))
+}
+
+fn ingest(token: &str) -> Request<Body> {
+    let body = json!({ "title": "Cells", "content": "cell membrane protein", "ticks": 1 });
+    Request::post("/api/ingest")
+        .header(header::AUTHORIZATION, format!("Bearer {token}"))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn read_tokens_read_but_may_not_ingest() {
+    let app = app();
+    wait_ready_as(&app, Some(READ)).await;
+
+    let (status, _) = send(&app, get_as("/api/stats", Some(READ))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = send(&app, ingest(READ)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "this token may only read");
+}
+
+#[tokio::test]
+async fn write_tokens_read_and_ingest() {
+    let app = app();
+    wait_ready_as(&app, Some(READ)).await;
+
+    let (status, _) = send(&app, get_as("/api/stats", Some(WRITE))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = send(&app, ingest(WRITE)).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+}
+
+#[tokio::test]
+async fn requests_without_a_known_token_are_unauthorized() {
+    let app = app();
+
+    let response = app
+        .clone()
+        .oneshot(get_as("/api/stats", None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+    let (status, _) = send(&app, get_as("/api/stats", Some("guess"))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    // The query token is only for WebSockets
+    let (status, _) = send(&app, get_as(&format!("/api/stats?token={READ}"), None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Liveness and the dashboard page need no token
+    let (status, _) = send(&app, get_as("/healthz", None)).await;
+    assert_eq!(status, StatusCode::OK);
+    let response = app.clone().oneshot(get_as("/", None)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+/// The status code a real server answers a WebSocket handshake for `uri`
+/// with.
+async fn handshake_status(app: Router, uri: &str) -> u16 {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let request = format!(
+        "GET {uri} HTTP/1.1\r\nHost: {addr}\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\
+         Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = vec![0; 64];
+    let n = stream.read(&mut response).await.unwrap();
+    let status_line = String::from_utf8_lossy(&response[..n]).to_string();
+    status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .unwrap_or_else(|| panic!("no status in {status_line:?}"))
+}
+
+#[tokio::test]
+async fn websocket_upgrades_need_a_valid_token() {
+    assert_eq!(handshake_status(app(), "/ws/events").await, 401);
+    assert_eq!(handshake_status(app(), "/ws/events?token=guess").await, 401);
+    assert_eq!(
+        handshake_status(app(), &format!("/ws/events?token={READ}")).await,
+        101
+    );
+}
+
+#[test]
+fn tokens_parse_with_their_roles() {
+    let tokens = AuthTokens::parse(&["read:abc", "write:x:y"]).unwrap();
+    assert_eq!(tokens.role_of("abc"), Some(Role::Read));
+    assert_eq!(tokens.role_of("x:y"), Some(Role::Write));
+    assert_eq!(tokens.role_of("ab"), None);
+    assert!(!format!("{:?}", tokens).contains("abc"));
+
+    assert!(AuthTokens::parse(&["abc"]).is_err());
+    assert!(AuthTokens::parse(&["admin:abc"]).is_err());
+    assert!(AuthTokens::parse(&["read:"]).is_err());
+}
@@ -0,0 +1,56 @@
+//! Helpers shared by the integration tests for driving the router.
+
+// Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use serde_json::Value;
+use std::time::Duration;
+use tower::ServiceExt;
+
+/// Send `request` and parse the response body as JSON, `Null` if it is
+/// not JSON (e.g. the empty body of a refused request).
+pub async fn send(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+pub fn get(uri: &str) -> Request<Body> {
+    get_as(uri, None)
+}
+
+/// A GET request carrying `token` as a bearer token, if any.
+pub fn get_as(uri: &str, token: Option<&str>) -> Request<Body> {
+    let mut request = Request::get(uri);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+    }
+    request.body(Body::empty()).unwrap()
+}
+
+pub fn post_json(uri: &str, body: Value) -> Request<Body> {
+    Request::post(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// Poll `/readyz` until it reports ready.
+pub async fn wait_ready(app: &Router) {
+    wait_ready_as(app, None).await
+}
+
+/// [`wait_ready`], authenticating with `token`.
+pub async fn wait_ready_as(app: &Router, token: Option<&str>) {
+    for _ in 0..200 {
+        if send(app, get_as("/readyz", token)).await.0 == StatusCode::OK {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("colony never became ready");
+}
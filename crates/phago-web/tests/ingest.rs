@@ -0,0 +1,185 @@
+//! Multipart uploads through `POST /api/ingest`, ingested as background
+//! jobs, and the limits on ingest requests.
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use common::{get, send, wait_ready};
+use phago_web::routes::create_router;
+use phago_web::{AppState, UploadLimits};
+use serde_json::Value;
+use std::time::Duration;
+use tower::ServiceExt;
+
+const BOUNDARY: &str = "phago-test-boundary";
+
+/// A multipart ingest request uploading `files` as `(name, content)`.
+fn upload(files: &[(&str, &str)], ticks: u64) -> Request<Body> {
+    let mut body = String::new();
+    for (name, content) in files {
+        body.push_str(&format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{name}\"\r\n\
+             Content-Type: text/plain\r\n\r\n{content}\r\n"
+        ));
+    }
+    body.push_str(&format!(
+        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"ticks\"\r\n\r\n{ticks}\r\n--{BOUNDARY}--\r\n"
+    ));
+    Request::post("/api/ingest")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}"),
+        )
+        .body(Body::from(body))
+        .unwrap()
+}
+
+/// Poll a job until it is neither queued nor running.
+async fn wait_for_job(app: &Router, job_id: u64) -> Value {
+    for _ in 0..500 {
+        let (status, job) = send(app, get(&format!("/api/ingest/{job_id}"))).await;
+        assert_eq!(status, StatusCode::OK);
+        if job["state"] != "queued" && job["state"] != "running" {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("job {job_id} never finished");
+}
+
+#[tokio::test]
+async fn uploaded_files_are_ingested_by_a_job() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+    let before = state.stats().await.documents_total;
+
+    let request = upload(
+        &[
+            (
+                "biology.md",
+                "# Biology\n\nThe cell membrane regulates protein transport into the cell.",
+            ),
+            (
+                "astronomy.txt",
+                "A nebula is a cloud where stellar fusion ignites new stars in a galaxy.",
+            ),
+        ],
+        10,
+    );
+    let (status, job) = send(&app, request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(job["files_total"], 2);
+
+    let job = wait_for_job(&app, job["job_id"].as_u64().unwrap()).await;
+    assert_eq!(job["state"], "completed", "{job}");
+    assert_eq!(job["files_processed"], 2);
+    assert!(job["concepts_added"].as_u64().unwrap() > 0, "{job}");
+    for file in job["files"].as_array().unwrap() {
+        assert_eq!(file["state"], "ingested", "{file}");
+    }
+    assert_eq!(job["files"][1]["name"], "astronomy.txt");
+
+    let (_, stats) = send(&app, get("/api/stats")).await;
+    assert_eq!(
+        stats["documents_total"].as_u64().unwrap(),
+        before as u64 + 2
+    );
+
+    let response = app.clone().oneshot(get("/api/ingest/999")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn unreadable_files_fail_without_failing_the_job() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+
+    let request = upload(
+        &[
+            (
+                "scan.html",
+                "<html><body><img src=\"page.png\"></body></html>",
+            ),
+            ("notes.txt", "membrane transport protein channel"),
+        ],
+        5,
+    );
+    let (status, job) = send(&app, request).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    let job = wait_for_job(&app, job["job_id"].as_u64().unwrap()).await;
+    assert_eq!(job["state"], "completed");
+    assert_eq!(job["files"][0]["state"], "failed");
+    assert!(job["files"][0]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("skipped"));
+    assert_eq!(job["files"][1]["state"], "ingested");
+    assert_eq!(state.stats().await.documents_total, 1);
+}
+
+#[tokio::test]
+async fn only_finished_jobs_are_forgotten() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+
+    // One more job than the history keeps, the last still queued
+    let mut ids = Vec::new();
+    for i in 0..33 {
+        let name = format!("doc{i}.txt");
+        let (status, job) = send(&app, upload(&[(&name, "cell membrane protein")], 1)).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        ids.push(job["job_id"].as_u64().unwrap());
+    }
+    for &id in &ids {
+        assert_eq!(wait_for_job(&app, id).await["state"], "completed");
+    }
+
+    // Now all finished, the next job pushes out the oldest
+    let (status, _) = send(&app, upload(&[("last.txt", "cell membrane")], 1)).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let response = app
+        .clone()
+        .oneshot(get(&format!("/api/ingest/{}", ids[0])))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(
+        send(&app, get(&format!("/api/ingest/{}", ids[1]))).await.0,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn ingest_limits_answer_413_and_429() {
+    let state = AppState::new(None)
+        .unwrap()
+        .with_upload_limits(UploadLimits {
+            max_upload_bytes: 1024,
+            requests_per_minute: 2,
+        });
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+
+    // Caught while streaming, as the request declares no length
+    let big = "cell ".repeat(400);
+    let (status, body) = send(&app, upload(&[("big.txt", &big)], 1)).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(body["error"].as_str().unwrap().contains("1024"), "{body}");
+
+    let (status, _) = send(&app, upload(&[("small.txt", "cell membrane")], 1)).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+
+    let response = app
+        .clone()
+        .oneshot(upload(&[("small.txt", "cell membrane")], 1))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key(header::RETRY_AFTER));
+}
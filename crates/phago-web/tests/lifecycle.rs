@@ -0,0 +1,376 @@
+//! Readiness, restore and graceful-shutdown behaviour of the web server.
+
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use common::{get, post_json, send, wait_ready};
+use phago_core::types::Position;
+use phago_web::routes::create_router;
+use phago_web::{AppState, RunEvent};
+use serde_json::Value;
+use std::time::Duration;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn healthz_and_readiness_transitions() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+
+    let (status, body) = send(&app, get("/healthz")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+
+    wait_ready(&app).await;
+    let (_, body) = send(&app, get("/readyz")).await;
+    assert_eq!(body["status"], "ready");
+
+    state.begin_shutdown();
+    let (status, body) = send(&app, get("/readyz")).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "shutting_down");
+
+    // Liveness is unaffected by shutdown
+    assert_eq!(send(&app, get("/healthz")).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn shutdown_saves_and_restart_restores() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("web.db").to_string_lossy().to_string();
+
+    let state = AppState::new(Some(db.clone())).unwrap();
+    state
+        .ingest(
+            "Biology".to_string(),
+            "The cell membrane regulates protein transport into the cell.".to_string(),
+            Position::new(0.0, 0.0),
+            30,
+        )
+        .await;
+    let nodes = state.stats().await.graph_nodes;
+    assert!(nodes > 0);
+
+    let report = state.shutdown_and_save().await.unwrap();
+    assert_eq!(report.nodes, nodes);
+    assert_eq!(report.saved_to.unwrap().to_string_lossy(), db);
+
+    let restored = AppState::new(Some(db)).unwrap();
+    let app = create_router(restored.clone());
+    wait_ready(&app).await;
+    assert_eq!(restored.stats().await.graph_nodes, nodes);
+}
+
+/// Poll a background run until it leaves the `running` state.
+async fn wait_finished(app: &Router, run_id: u64) -> Value {
+    for _ in 0..1000 {
+        let (_, body) = send(app, get(&format!("/api/run/{run_id}"))).await;
+        if body["state"] != "running" {
+            return body;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("run {run_id} never finished");
+}
+
+#[tokio::test]
+async fn run_interrupted_by_shutdown_returns_partial_result() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+
+    let requested = 1_000_000_000u64;
+    let (status, body) = send(
+        &app,
+        post_json("/api/run", serde_json::json!({ "ticks": requested })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let run_id = body["run_id"].as_u64().unwrap();
+
+    // Let the run get going, then shut down
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    state.begin_shutdown();
+
+    let body = tokio::time::timeout(Duration::from_secs(10), wait_finished(&app, run_id))
+        .await
+        .expect("run should end promptly after shutdown");
+    assert_eq!(body["state"], "interrupted");
+    assert_eq!(body["ticks_requested"], requested);
+    let completed = body["ticks_completed"].as_u64().unwrap();
+    assert!(completed < requested);
+    assert_eq!(state.stats().await.tick, completed);
+
+    // New runs after shutdown do no work
+    let (_, body) = send(
+        &app,
+        post_json("/api/run", serde_json::json!({ "ticks": 5 })),
+    )
+    .await;
+    let body = wait_finished(&app, body["run_id"].as_u64().unwrap()).await;
+    assert_eq!(body["ticks_completed"], 0);
+    assert_eq!(body["state"], "interrupted");
+}
+
+#[tokio::test]
+async fn background_run_reports_progress_and_cancels() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+    let mut run_events = state.subscribe_runs();
+
+    // Throttled to 50 ticks/s, so this would take hours
+    let (status, body) = send(
+        &app,
+        post_json(
+            "/api/run",
+            serde_json::json!({ "ticks": 1_000_000, "speed": 50.0 }),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    assert_eq!(body["state"], "running");
+    let run_id = body["run_id"].as_u64().unwrap();
+    assert!(matches!(run_events.recv().await.unwrap(), RunEvent::Started(s) if s.run_id == run_id));
+
+    // Only one run at a time
+    let (status, body) = send(
+        &app,
+        post_json("/api/run", serde_json::json!({ "ticks": 5 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["run_id"], run_id);
+
+    // Other endpoints keep answering while the run is in progress
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let (status, _) = tokio::time::timeout(Duration::from_secs(1), send(&app, get("/api/stats")))
+        .await
+        .expect("stats should not wait for the run");
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(&app, get(&format!("/api/run/{run_id}"))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["state"], "running");
+    let completed = body["ticks_completed"].as_u64().unwrap();
+    assert!(
+        (1..=30).contains(&completed),
+        "governed run completed {completed}"
+    );
+    assert!(body["eta_ms"].as_u64().unwrap() > 60_000);
+
+    let request = Request::delete(format!("/api/run/{run_id}"))
+        .body(Body::empty())
+        .unwrap();
+    let (status, body) = send(&app, request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["state"], "cancelled");
+    let completed = body["ticks_completed"].as_u64().unwrap();
+    assert_eq!(state.stats().await.tick, completed);
+    assert!(
+        matches!(run_events.recv().await.unwrap(), RunEvent::Finished(s) if s.run_id == run_id)
+    );
+
+    // A new run can start once the previous one is gone
+    let (status, body) = send(
+        &app,
+        post_json("/api/run", serde_json::json!({ "ticks": 3 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let body = wait_finished(&app, body["run_id"].as_u64().unwrap()).await;
+    assert_eq!(body["state"], "completed");
+    assert_eq!(body["ticks_completed"], 3);
+    assert_eq!(state.stats().await.tick, completed + 3);
+
+    let response = app.clone().oneshot(get("/api/run/999")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn invalid_query_settings_are_rejected() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state);
+    wait_ready(&app).await;
+
+    let (status, body) = send(
+        &app,
+        post_json(
+            "/api/query",
+            serde_json::json!({"query": "cell", "alpha": 7.3}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("alpha"));
+
+    let (status, _) = send(
+        &app,
+        post_json(
+            "/api/query",
+            serde_json::json!({"query": "cell", "max_results": 0}),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        post_json("/api/query", serde_json::json!({"query": "cell"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["results"].is_array());
+}
+
+#[tokio::test]
+async fn repeated_queries_hit_the_cache_until_the_graph_changes() {
+    let state = AppState::with_query_cache(None, Default::default(), 16).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+    state
+        .ingest(
+            "Biology".to_string(),
+            "The cell membrane regulates protein transport into the cell.".to_string(),
+            Position::new(0.0, 0.0),
+            30,
+        )
+        .await;
+
+    let query = || post_json("/api/query", serde_json::json!({"query": "cell membrane"}));
+    let (_, first) = send(&app, query()).await;
+    let (_, second) = send(&app, query()).await;
+    assert_eq!(first, second);
+    let (_, stats) = send(&app, get("/api/query/cache")).await;
+    assert_eq!(
+        (stats["hits"].as_u64(), stats["misses"].as_u64()),
+        (Some(1), Some(1))
+    );
+
+    state.run(1).await;
+    send(&app, query()).await;
+    let (_, stats) = send(&app, get("/api/query/cache")).await;
+    assert_eq!(stats["misses"], 2);
+
+    let uncached = create_router(AppState::new(None).unwrap());
+    wait_ready(&uncached).await;
+    assert!(send(&uncached, get("/api/query/cache")).await.1.is_null());
+}
+
+#[tokio::test]
+async fn query_pages_follow_cursors_until_the_graph_changes() {
+    let state = AppState::with_query_cache(None, Default::default(), 16).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+    state
+        .ingest(
+            "Biology".to_string(),
+            "The cell membrane regulates protein transport into the cell. \
+             Membrane channels and receptors move ions, sugars and signals."
+                .to_string(),
+            Position::new(0.0, 0.0),
+            30,
+        )
+        .await;
+
+    let query = "cell membrane protein transport channels receptors ions signals";
+    let page = |cursor: Option<&str>| {
+        post_json(
+            "/api/query",
+            serde_json::json!({"query": query, "max_results": 2, "cursor": cursor}),
+        )
+    };
+    let labels = |body: &Value| -> Vec<String> {
+        body["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["label"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let (_, all) = send(
+        &app,
+        post_json(
+            "/api/query",
+            serde_json::json!({"query": query, "max_results": 100}),
+        ),
+    )
+    .await;
+    assert!(all["next_cursor"].is_null());
+
+    let mut paged = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let (status, body) = send(&app, page(cursor.as_deref())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["cursor_expired"].is_null());
+        paged.extend(labels(&body));
+        match body["next_cursor"].as_str() {
+            Some(next) => cursor = Some(next.to_string()),
+            None => break,
+        }
+    }
+    assert!(paged.len() > 2, "{:?}", paged);
+    assert_eq!(paged, labels(&all));
+
+    // A tick changes the graph, expiring the first page's cursor
+    let (_, first) = send(&app, page(None)).await;
+    let cursor = first["next_cursor"].as_str().unwrap().to_string();
+    state.run(1).await;
+    let (status, body) = send(&app, page(Some(&cursor))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["cursor_expired"], true);
+    let (_, fresh) = send(&app, page(None)).await;
+    assert_eq!(labels(&body), labels(&fresh));
+
+    let (status, body) = send(&app, page(Some("bogus"))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("cursor"));
+}
+
+#[tokio::test]
+async fn snapshots_can_be_sampled() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+    state
+        .ingest(
+            "Biology".to_string(),
+            "The cell membrane regulates protein transport into the cell.".to_string(),
+            Position::new(0.0, 0.0),
+            30,
+        )
+        .await;
+
+    let (status, full) = send(&app, get("/api/snapshot")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(full.get("coverage").is_none());
+    let total = full["nodes"].as_array().unwrap().len();
+    assert!(total > 2);
+
+    let (status, sampled) = send(&app, get("/api/snapshot?sample=top&n=2")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(sampled["nodes"].as_array().unwrap().len(), 2);
+    assert_eq!(sampled["coverage"]["nodes_total"], total);
+    assert_eq!(sampled["coverage"]["nodes_sampled"], 2);
+
+    let (_, nodes) = send(&app, get("/api/nodes?sample=ego&seeds=membrane&hops=1&n=3")).await;
+    let labels: Vec<&str> = nodes
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["label"].as_str().unwrap())
+        .collect();
+    assert!(labels.contains(&"membrane"), "{labels:?}");
+
+    for uri in [
+        "/api/snapshot?sample=ego",
+        "/api/snapshot?sample=everything",
+    ] {
+        let (status, body) = send(&app, get(uri)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        assert!(body["error"].is_string());
+    }
+}
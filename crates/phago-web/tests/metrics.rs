@@ -0,0 +1,126 @@
+//! The `/metrics` scrape endpoint.
+
+mod common;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use common::{get, post_json, send, wait_ready};
+use phago_web::routes::create_router;
+use phago_web::AppState;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use tower::ServiceExt;
+
+/// Send `request` and return its status, content type and body as text.
+async fn send_text(app: &Router, request: Request<Body>) -> (StatusCode, Option<String>, String) {
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string());
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+/// Samples by `name{labels}`, checking that each metric family is
+/// declared once and every sample line has a numeric value.
+fn parse(text: &str) -> HashMap<String, f64> {
+    let mut declared = HashSet::new();
+    let mut samples = HashMap::new();
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("# TYPE ") {
+            let name = rest.split(' ').next().unwrap();
+            assert!(
+                declared.insert(name.to_string()),
+                "duplicate TYPE for {name}"
+            );
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        let (series, value) = line.rsplit_once(' ').unwrap();
+        let value = match value {
+            "+Inf" => f64::INFINITY,
+            v => v
+                .parse()
+                .unwrap_or_else(|_| panic!("bad value in {line:?}")),
+        };
+        let name = series.split('{').next().unwrap();
+        assert!(
+            declared
+                .iter()
+                .any(|family| name == family || name.starts_with(&format!("{family}_"))),
+            "{name} has no TYPE line"
+        );
+        assert!(
+            samples.insert(series.to_string(), value).is_none(),
+            "duplicate {series}"
+        );
+    }
+    samples
+}
+
+#[tokio::test]
+async fn metrics_track_ticks_and_queries() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state);
+    wait_ready(&app).await;
+
+    let (status, content_type, body) = send_text(&app, get("/metrics")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type
+        .unwrap()
+        .starts_with("text/plain; version=0.0.4"));
+    let before = parse(&body);
+    assert_eq!(before[r#"phago_colony_tick{space="default"}"#], 0.0);
+
+    let ingest =
+        json!({"title": "Biology", "content": "cell membrane protein transport", "ticks": 5});
+    assert_eq!(
+        send(&app, post_json("/api/ingest", ingest)).await.0,
+        StatusCode::OK
+    );
+    let query = json!({"query": "cell membrane"});
+    assert_eq!(
+        send(&app, post_json("/api/query", query)).await.0,
+        StatusCode::OK
+    );
+
+    let after = parse(&send_text(&app, get("/metrics")).await.2);
+    assert_eq!(after[r#"phago_colony_tick{space="default"}"#], 5.0);
+    assert!(after[r#"phago_graph_nodes{space="default"}"#] > 0.0);
+    assert_eq!(after[r#"phago_documents_digested{space="default"}"#], 1.0);
+    assert_eq!(
+        after[r#"phago_tick_duration_seconds_count{space="default"}"#],
+        5.0
+    );
+    assert_eq!(
+        after[r#"phago_query_duration_seconds_count{source="web"}"#],
+        1.0
+    );
+
+    // Counters carry on from where they were
+    assert_eq!(
+        send(&app, post_json("/api/tick", json!({"count": 3})))
+            .await
+            .0,
+        StatusCode::OK
+    );
+    let later = parse(&send_text(&app, get("/metrics")).await.2);
+    for (series, value) in &after {
+        if series.contains("_total{") || series.contains("_count{") || series.contains("_bucket{") {
+            assert!(later[series] >= *value, "{series} decreased");
+        }
+    }
+    assert_eq!(
+        later[r#"phago_colony_events_total{space="default",type="tick_complete"}"#],
+        8.0
+    );
+}
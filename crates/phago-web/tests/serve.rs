@@ -0,0 +1,169 @@
+//! One colony served to the dashboard and to MCP clients at once, as
+//! `phago serve` does.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{get, post_json, send, wait_ready};
+use phago_mcp::tools::PhagoTools;
+use phago_mcp::worker::{ColonyHandle, DEFAULT_SPACE};
+use phago_runtime::colony::{Colony, ColonyEvent};
+use phago_web::routes::create_router;
+use phago_web::{AppState, ColonyStore, RecordingConfig};
+use rmcp::model::{CallToolRequestParams, CallToolResult};
+use rmcp::service::{RoleClient, RunningService};
+use rmcp::ServiceExt as _;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Connect an MCP client to tools serving `state`'s colony as the default
+/// space, over an in-memory pipe.
+async fn mcp_client(state: &AppState, read_only: bool) -> RunningService<RoleClient, ()> {
+    let handle = ColonyHandle::spawn(None)
+        .with_read_only(read_only)
+        .with_shared_space(DEFAULT_SPACE, state.shared_colony());
+    let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let server = PhagoTools::new(handle).serve(server_io).await.unwrap();
+        let _ = server.waiting().await;
+    });
+    ().serve(client_io).await.unwrap()
+}
+
+async fn remember(
+    client: &RunningService<RoleClient, ()>,
+    title: &str,
+    content: &str,
+) -> Result<CallToolResult, rmcp::ServiceError> {
+    let arguments = json!({ "title": title, "content": content, "ticks": 15 });
+    client
+        .call_tool(CallToolRequestParams {
+            meta: None,
+            name: "phago_remember".into(),
+            arguments: arguments.as_object().cloned(),
+            task: None,
+        })
+        .await
+}
+
+fn labels(nodes: &Value) -> Vec<&str> {
+    nodes
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["label"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn remembered_documents_show_up_on_the_dashboard() {
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+    let mut events = state.subscribe();
+
+    let client = mcp_client(&state, false).await;
+    remember(
+        &client,
+        "Biology",
+        "The cell membrane regulates protein transport into the cell.",
+    )
+    .await
+    .unwrap();
+
+    let (status, nodes) = send(&app, get("/api/nodes")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(labels(&nodes).contains(&"membrane"), "{nodes}");
+
+    // The ticks run for the MCP request reach the dashboard's subscribers
+    let mut ticks = 0;
+    while let Ok(event) = events.try_recv() {
+        if matches!(event, ColonyEvent::TickComplete { .. }) {
+            ticks += 1;
+        }
+    }
+    assert_eq!(ticks, 15);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn read_only_side_rejects_changes_the_other_side_makes() {
+    let state = AppState::new(None).unwrap().with_read_only(true);
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+
+    let ingest = json!({ "title": "Stars", "content": "galaxy nebula stellar fusion" });
+    let (status, body) = send(&app, post_json("/api/ingest", ingest)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "the colony is read-only");
+    assert_eq!(
+        send(&app, post_json("/api/tick", json!({ "count": 1 })))
+            .await
+            .0,
+        StatusCode::FORBIDDEN
+    );
+
+    let client = mcp_client(&state, false).await;
+    remember(&client, "Stars", "galaxy nebula stellar fusion")
+        .await
+        .unwrap();
+    let (status, nodes) = send(&app, get("/api/nodes")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(labels(&nodes).contains(&"galaxy"), "{nodes}");
+    client.cancel().await.unwrap();
+
+    // And the other way round
+    let state = AppState::new(None).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+    let client = mcp_client(&state, true).await;
+    assert!(remember(&client, "Stars", "galaxy nebula").await.is_err());
+    let ingest = json!({ "title": "Stars", "content": "galaxy nebula stellar fusion" });
+    assert_eq!(
+        send(&app, post_json("/api/ingest", ingest)).await.0,
+        StatusCode::OK
+    );
+    client.cancel().await.unwrap();
+}
+
+/// Keeps the colony's node count instead of the colony.
+struct CountingStore {
+    saved: Arc<Mutex<Vec<usize>>>,
+}
+
+impl ColonyStore for CountingStore {
+    fn load(&mut self) -> anyhow::Result<Colony> {
+        Ok(Colony::new())
+    }
+
+    fn save(&mut self, colony: &Colony) -> anyhow::Result<PathBuf> {
+        self.saved.lock().unwrap().push(colony.stats().graph_nodes);
+        Ok(PathBuf::from("counting"))
+    }
+}
+
+#[tokio::test]
+async fn stores_are_saved_on_demand_and_on_shutdown() {
+    let saved = Arc::new(Mutex::new(Vec::new()));
+    let store = CountingStore {
+        saved: saved.clone(),
+    };
+    let state = AppState::with_store(store, RecordingConfig::default(), 0).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+
+    let report = state.save().await.unwrap();
+    assert_eq!(report.saved_to, Some(PathBuf::from("counting")));
+
+    let client = mcp_client(&state, false).await;
+    remember(&client, "Biology", "cell membrane protein transport")
+        .await
+        .unwrap();
+    client.cancel().await.unwrap();
+
+    let report = state.shutdown_and_save().await.unwrap();
+    assert!(report.nodes > 0);
+    assert_eq!(*saved.lock().unwrap(), vec![0, report.nodes]);
+}
@@ -0,0 +1,79 @@
+//! Serving a published bundle: the dashboard answers stats, nodes, paths
+//! and queries from the bundle as the colony would, and refuses writes.
+
+mod common;
+
+use axum::http::StatusCode;
+use common::{get, post_json, send, wait_ready};
+use phago::agents::digester::Digester;
+use phago::rag::hybrid::{hybrid_query, HybridConfig};
+use phago_core::types::Position;
+use phago_runtime::colony::Colony;
+use phago_runtime::export::{publish_bundle, BundleConfig};
+use phago_web::routes::create_router;
+use phago_web::AppState;
+use serde_json::json;
+
+fn digested_colony() -> Colony {
+    let mut colony = Colony::new();
+    let documents = [
+        "The cell membrane controls transport of ions into the cell.",
+        "Mitochondria produce energy for the cell from glucose.",
+        "Energy from mitochondria drives membrane transport of ions.",
+    ];
+    for (i, content) in documents.iter().enumerate() {
+        let position = Position::new(i as f64 * 5.0, 0.0);
+        colony.ingest_document(&format!("Doc {i}"), content, position);
+        colony.spawn(Box::new(Digester::new(position).with_max_idle(80)));
+    }
+    colony.run(30);
+    colony
+}
+
+#[tokio::test]
+async fn published_bundles_are_served_read_only() {
+    let colony = digested_colony();
+    let dir = tempfile::tempdir().unwrap();
+    publish_bundle(&colony, BundleConfig::new(dir.path())).unwrap();
+
+    let state = AppState::with_static_bundle(dir.path()).unwrap();
+    assert!(state.is_read_only());
+    let app = create_router(state);
+    wait_ready(&app).await;
+
+    let stats = colony.stats();
+    let (status, body) = send(&app, get("/api/stats")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["graph_nodes"], stats.graph_nodes);
+    assert_eq!(body["graph_edges"], stats.graph_edges);
+    let (_, nodes) = send(&app, get("/api/nodes")).await;
+    assert_eq!(nodes.as_array().unwrap().len(), stats.graph_nodes);
+
+    let config = HybridConfig::builder().alpha(0.5).build().unwrap();
+    let live: Vec<String> = hybrid_query(&colony, "membrane transport", &config)
+        .unwrap()
+        .into_iter()
+        .map(|r| r.label)
+        .collect();
+    assert!(!live.is_empty());
+    let (status, body) = send(
+        &app,
+        post_json("/api/query", json!({ "query": "membrane transport" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let served: Vec<&str> = body["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["label"].as_str().unwrap())
+        .collect();
+    assert_eq!(served, live);
+
+    let (status, body) = send(&app, get("/api/path?from=membrane&to=energy")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body["paths"].as_array().unwrap().is_empty(), "{body}");
+
+    let (status, _) = send(&app, post_json("/api/tick", json!({ "count": 1 }))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
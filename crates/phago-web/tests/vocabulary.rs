@@ -0,0 +1,96 @@
+//! What agents know, as `/api/agents/:id/vocabulary` reports it.
+
+mod common;
+
+use axum::http::StatusCode;
+use axum::Router;
+use common::{get, send, wait_ready};
+use phago::agents::digester::Digester;
+use phago_core::types::{AgentId, Position};
+use phago_runtime::colony::Colony;
+use phago_web::routes::create_router;
+use phago_web::{AppState, ColonyStore, RecordingConfig};
+use std::path::PathBuf;
+
+fn digester(seed: u64, text: &str) -> Digester {
+    let mut digester = Digester::with_seed(Position::new(seed as f64, 0.0), seed);
+    digester.digest_text(text.to_string());
+    digester
+}
+
+/// Serves two digesters that have read about different things.
+struct TwoDigesters;
+
+impl ColonyStore for TwoDigesters {
+    fn load(&mut self) -> anyhow::Result<Colony> {
+        let mut colony = Colony::new();
+        colony.spawn(Box::new(digester(
+            1,
+            "The cell membrane controls transport of proteins into the cell.",
+        )));
+        colony.spawn(Box::new(digester(
+            2,
+            "Quantum circuits need error correction and topological qubits.",
+        )));
+        Ok(colony)
+    }
+
+    fn save(&mut self, _colony: &Colony) -> anyhow::Result<PathBuf> {
+        Ok(PathBuf::from("nowhere"))
+    }
+}
+
+/// The overlap with `other` that `/api/agents/{id}/vocabulary` reports.
+async fn overlap(app: &Router, id: AgentId, other: AgentId) -> f64 {
+    let (status, body) = send(app, get(&format!("/api/agents/{}/vocabulary", id.0))).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body["overlaps"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|o| o["agent"] == other.0.to_string())
+        .and_then(|o| o["jaccard"].as_f64())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn transferred_vocabulary_raises_the_reported_overlap() {
+    let state = AppState::with_store(TwoDigesters, RecordingConfig::default(), 0).unwrap();
+    let app = create_router(state.clone());
+    wait_ready(&app).await;
+    let (a, b) = (AgentId::from_seed(1), AgentId::from_seed(2));
+
+    let before = overlap(&app, b, a).await;
+    let transferred = state
+        .shared_colony()
+        .with(move |colony| colony.transfer_vocabulary(&a, &b))
+        .await
+        .unwrap();
+    assert!(transferred);
+    let after = overlap(&app, b, a).await;
+
+    assert!(after > before, "{before} -> {after}");
+}
+
+#[tokio::test]
+async fn vocabularies_list_the_top_terms_and_their_coverage() {
+    let state = AppState::with_store(TwoDigesters, RecordingConfig::default(), 0).unwrap();
+    let app = create_router(state);
+    wait_ready(&app).await;
+
+    let id = AgentId::from_seed(1);
+    let (status, body) = send(&app, get(&format!("/api/agents/{}/vocabulary?top=2", id.0))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["agent_type"], "digester");
+    let terms = body["top_terms"].as_array().unwrap();
+    assert_eq!(terms.len(), 2);
+    assert!(body["vocabulary_size"].as_u64().unwrap() > 2);
+    // Nothing was presented to the graph
+    let coverage = body["coverage"].as_array().unwrap();
+    assert_eq!(coverage.len(), 2);
+    assert!(coverage.iter().all(|c| c["node"].is_null()));
+
+    let unknown = AgentId::from_seed(3);
+    let (status, _) = send(&app, get(&format!("/api/agents/{}/vocabulary", unknown.0))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -24,11 +24,8 @@
 //! colony.run(30);
 //!
 //! // Query with hybrid scoring
-//! let results = hybrid_query(&colony, "cell membrane", &HybridConfig {
-//!     alpha: 0.5,
-//!     max_results: 5,
-//!     candidate_multiplier: 3,
-//! });
+//! let config = HybridConfig::builder().alpha(0.5).max_results(5).build().unwrap();
+//! let results = hybrid_query(&colony, "cell membrane", &config).unwrap();
 //!
 //! for r in results {
 //!     println!("{} (score: {:.3})", r.label, r.final_score);
@@ -85,17 +82,27 @@
 //!     title: "Doc".into(),
 //!     content: "Content here".into(),
 //!     ticks: Some(15),
+//!     tags: vec!["work".into()],
+//!     ttl_ticks: None,
+//!     ttl_seconds: None,
 //! });
 //!
 //! // Query via MCP
-//! let results = phago_recall(&colony, &RecallRequest {
+//! let results = phago_recall(&mut colony, &RecallRequest {
 //!     query: "search terms".into(),
 //!     max_results: 5,
 //!     alpha: 0.5,
-//! });
+//!     tags: Some(vec!["work".into()]),
+//!     include_passages: true,
+//!     max_passages: 3,
+//!     cursor: None,
+//!     // Or have the passages assembled into cited context
+//!     assemble: false,
+//!     assembly: AssembleConfig::default(),
+//! }).unwrap();
 //!
 //! // Explore graph structure
-//! let stats = phago_explore(&colony, &ExploreRequest::Stats);
+//! let stats = phago_explore(&colony, &ExploreRequest::Stats { sample: None });
 //! ```
 //!
 //! ## Session Persistence
@@ -114,7 +121,7 @@
 //! // Load session
 //! let state = load_session(Path::new("session.json")).unwrap();
 //! let mut restored = Colony::new();
-//! restore_into_colony(&mut restored, &state);
+//! restore_into_colony(&mut restored, &state, None);
 //! ```
 
 // Re-export all subcrates
@@ -148,6 +155,9 @@ pub mod prelude {
 
     // Core traits
     pub use phago_core::agent::Agent;
+    pub use phago_core::primitives::{
+        DigestionLimits, EmergeConfig, FragmentRanking, QuorumStatus,
+    };
     pub use phago_core::substrate::Substrate;
     pub use phago_core::topology::TopologyGraph;
 
@@ -156,28 +166,65 @@ pub mod prelude {
 
     // Agents
     pub use phago_agents::digester::Digester;
-    pub use phago_agents::fitness::{AgentFitness, FitnessTracker};
+    pub use phago_agents::fitness::{
+        AgentFitness, BuiltinFitness, FitnessFunction, FitnessTracker,
+    };
     pub use phago_agents::genome::AgentGenome;
-    pub use phago_agents::sentinel::Sentinel;
+    pub use phago_agents::sentinel::{AnomalyGrade, AnomalyThresholds, PatrolStrategy, Sentinel};
     pub use phago_agents::synthesizer::Synthesizer;
 
     // Runtime
-    pub use phago_runtime::colony::{Colony, ColonyEvent, ColonyStats};
-    pub use phago_runtime::metrics::ColonyMetrics;
+    pub use phago_runtime::colony::{
+        Colony, ColonyEvent, ColonyStats, EmergenceReport, QuorumRegion,
+    };
+    pub use phago_runtime::community::{
+        Community, CommunityAlgorithm, CommunityConfig, CommunityResult, CommunityTracker,
+    };
+    pub use phago_runtime::compaction::{CompactionConfig, CompactionPlan, CompactionStrategy};
+    pub use phago_runtime::dedup::{
+        BatchDocument, DedupConfig, DedupPolicy, DuplicateMatch, IngestOutcome, IngestReport,
+        MatchKind,
+    };
+    pub use phago_runtime::graph_algorithms::{
+        find_path, pagerank, personalized_pagerank, ConceptPath, PageRankConfig, PathCost,
+        PathError, PathHop, WeightTransform,
+    };
+    pub use phago_runtime::metrics::{ColonyMetrics, VocabularyConvergence};
+    pub use phago_runtime::plugin::{
+        ActionContext, ActionDecision, ColonyPlugin, CustomActionHandler, DocumentPolicy,
+        JsonlEventLog, PluginContext,
+    };
+    pub use phago_runtime::query_log::{QueryLog, QueryLogConfig, QueryLogEntry, QuerySource};
+    pub use phago_runtime::region::{Region, RegionPolicy, RegionShape};
     pub use phago_runtime::session::{
         load_session, restore_into_colony, save_session, GraphState, SessionMetadata,
     };
+    pub use phago_runtime::snapshot::{SnapshotMode, SnapshotSeries};
+    pub use phago_runtime::transfer::{AcceptanceCurve, TransferConfig};
+    pub use phago_runtime::vocabulary::{AgentVocabulary, OverlapSummary, VocabularyReport};
 
     // RAG
     pub use phago_rag::mcp::{
-        phago_explore, phago_recall, phago_remember, ExploreRequest, ExploreResponse,
-        RecallRequest, RecallResponse, RememberRequest, RememberResponse,
+        phago_explore, phago_recall, phago_recall_cached, phago_remember, CentralityMethod,
+        ExploreRequest, ExploreResponse, RecallRequest, RecallResponse, RememberRequest,
+        RememberResponse,
     };
     pub use phago_rag::query::{Query, QueryResult};
-    pub use phago_rag::{hybrid_query, HybridConfig, HybridResult};
+    pub use phago_rag::{
+        assemble_context, assemble_context_with, ApproxTokenCounter, AssembleConfig, CitationStyle,
+        RagContext, TokenCounter,
+    };
+    pub use phago_rag::{
+        hybrid_query, hybrid_query_cached, hybrid_query_page, hybrid_query_page_cached,
+        touch_results, CommunityBoostScorer, DefaultGraphScorer, DegreeNormalizedScorer,
+        GraphScorer, HybridConfig, HybridConfigBuilder, HybridConfigError, HybridPage,
+        HybridResult, PersonalizedPageRankScorer, QueryCache, QueryCacheStats, ScoringView,
+    };
 
     // Semantic embeddings (requires "semantic" feature)
     #[cfg(feature = "semantic")]
+    pub use phago_agents::embedding_queue::EmbeddingQueue;
+    #[cfg(feature = "semantic")]
     pub use phago_agents::semantic_digester::{SemanticConcept, SemanticConfig, SemanticDigester};
 
     #[cfg(feature = "semantic")]
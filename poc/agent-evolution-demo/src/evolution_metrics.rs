@@ -1,20 +1,22 @@
 //! Evolution-specific metrics for agent-evolution branch.
 //!
 //! Tracks specialization emergence, genome divergence, and
-//! fitness trajectories across generations.
+//! fitness trajectories across generations. Population size, generations
+//! and lifespans come from [`phago_runtime::metrics::population_report`].
 
 use phago_agents::fitness::AgentFitness;
 use phago_agents::genome::AgentGenome;
+use phago_core::substrate::Substrate;
+use phago_core::types::AgentId;
+use phago_runtime::colony::Colony;
 use serde::Serialize;
 
 /// Metrics for one snapshot in time during evolution.
 #[derive(Debug, Clone, Serialize)]
 pub struct EvolutionSnapshot {
     pub tick: u64,
-    pub population: usize,
     pub mean_fitness: f64,
     pub max_fitness: f64,
-    pub max_generation: u32,
     /// Average pairwise genome distance (specialization index).
     pub genome_divergence: f64,
     /// Mean sense_radius across population.
@@ -23,6 +25,8 @@ pub struct EvolutionSnapshot {
     pub mean_max_idle: f64,
     /// Mean explore_bias across population.
     pub mean_explore_bias: f64,
+    /// Mean number of terms agents inherited from their parent's vocabulary.
+    pub mean_inherited_vocabulary: f64,
 }
 
 /// Compute genome divergence: average pairwise distance between all genomes.
@@ -81,13 +85,14 @@ fn cv(values: Vec<f64>) -> f64 {
     variance.sqrt() / mean.abs()
 }
 
-/// Build an evolution snapshot from current state.
+/// Build an evolution snapshot from current state. `inherited` holds the
+/// number of terms each living agent inherited at birth.
 pub fn build_snapshot(
     tick: u64,
     genomes: &[AgentGenome],
     fitness_data: &[&AgentFitness],
+    inherited: &[usize],
 ) -> EvolutionSnapshot {
-    let population = genomes.len();
     let mean_fitness = if fitness_data.is_empty() {
         0.0
     } else {
@@ -97,7 +102,6 @@ pub fn build_snapshot(
         .iter()
         .map(|f| f.fitness)
         .fold(0.0f64, f64::max);
-    let max_generation = fitness_data.iter().map(|f| f.generation).max().unwrap_or(0);
 
     let divergence = genome_divergence(genomes);
 
@@ -117,19 +121,45 @@ pub fn build_snapshot(
         genomes.iter().map(|g| g.explore_bias).sum::<f64>() / genomes.len() as f64
     };
 
+    let mean_inherited_vocabulary = if inherited.is_empty() {
+        0.0
+    } else {
+        inherited.iter().sum::<usize>() as f64 / inherited.len() as f64
+    };
+
     EvolutionSnapshot {
         tick,
-        population,
         mean_fitness,
         max_fitness,
-        max_generation,
         genome_divergence: divergence,
         mean_sense_radius,
         mean_max_idle,
         mean_explore_bias,
+        mean_inherited_vocabulary,
     }
 }
 
+/// Build an evolution snapshot from a colony's own state: the genomes,
+/// fitness and inherited vocabulary of its living agents.
+pub fn snapshot(colony: &Colony) -> EvolutionSnapshot {
+    let alive: Vec<AgentId> = colony.agents().iter().map(|a| a.id()).collect();
+    let genomes: Vec<AgentGenome> = alive
+        .iter()
+        .filter_map(|id| colony.genomes().get(id).cloned())
+        .collect();
+    let fitness_data: Vec<&AgentFitness> = alive
+        .iter()
+        .filter_map(|id| colony.fitness_tracker().get(id))
+        .collect();
+    let inherited: Vec<usize> = alive.iter().map(|id| colony.inherited_terms(id)).collect();
+    build_snapshot(
+        colony.substrate().current_tick(),
+        &genomes,
+        &fitness_data,
+        &inherited,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +178,17 @@ mod tests {
         assert!(genome_divergence(&genomes) > 0.0);
     }
 
+    #[test]
+    fn snapshot_averages_inherited_vocabulary() {
+        let genomes = vec![AgentGenome::default_genome(); 3];
+        let snap = build_snapshot(10, &genomes, &[], &[0, 4, 8]);
+        assert!((snap.mean_inherited_vocabulary - 4.0).abs() < 1e-10);
+        assert_eq!(
+            build_snapshot(10, &[], &[], &[]).mean_inherited_vocabulary,
+            0.0
+        );
+    }
+
     #[test]
     fn specialization_grows_with_diversity() {
         let g = AgentGenome::default_genome();
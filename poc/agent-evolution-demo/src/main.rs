@@ -7,20 +7,27 @@
 //! 1. Run 1000-tick sim with STATIC population: 11 agents, default params, no spawning
 //! 2. Run 1000-tick sim with EVOLVING population: start 5, spawn on death, cap 15
 //! 3. Run 1000-tick sim with RANDOM spawn: same rate, random genomes (control)
-//! 4. Compare graph richness, clustering, vocabulary spread at ticks 200, 500, 1000
+//! 4. Run 1000-tick sim with LAMARCKIAN spawn: as 2, offspring inherit half
+//!    of the fittest agent's vocabulary
+//! 5. Compare graph richness, clustering, vocabulary spread at ticks 200, 500, 1000
+//!
+//! Every condition seeds its agents alike, so conditions 2 and 4 form an
+//! A/B test of vocabulary inheritance. The colony breeds replacements
+//! itself under each condition's spawn policy; this demo only configures
+//! the conditions and reports on them.
 
 mod evolution_metrics;
 
-use phago_agents::digester::Digester;
-use phago_agents::fitness::FitnessTracker;
 use phago_agents::genome::AgentGenome;
-use phago_agents::spawn::{FitnessSpawnPolicy, NoSpawnPolicy, RandomSpawnPolicy, SpawnPolicy};
-use phago_core::agent::Agent;
+use phago_agents::registry::{AgentRegistry, AgentSpec};
+use phago_agents::spawn::SpawnPolicySpec;
 use phago_core::types::*;
-use phago_runtime::colony::{Colony, ColonyEvent, ColonySnapshot};
+use phago_runtime::colony::{Colony, ColonyConfig, ColonySnapshot};
 use phago_runtime::corpus::Corpus;
 use phago_runtime::metrics;
-use std::collections::HashMap;
+use phago_runtime::metrics::population::print_population_report;
+use phago_runtime::metrics::PopulationReport;
+use serde_json::json;
 
 fn main() {
     println!("╔══════════════════════════════════════════════════════╗");
@@ -32,41 +39,66 @@ fn main() {
     let corpus = Corpus::from_embedded();
     let total_ticks = 300u64;
     let checkpoint_ticks = vec![100, 200, 300];
+    let seed = 42;
 
     // --- Condition 1: Static Population ---
     println!("── Condition 1: Static Population (11 agents) ────────");
-    let (_static_snapshots, static_checkpoints, static_evo_snapshots) = run_condition(
-        "static",
-        &corpus,
-        total_ticks,
-        &checkpoint_ticks,
-        11,
-        &mut NoSpawnPolicy,
-        0.0,
-    );
+    let (_static_snapshots, static_checkpoints, static_evo_snapshots, static_population) =
+        run_condition(
+            "static",
+            &corpus,
+            total_ticks,
+            &checkpoint_ticks,
+            11,
+            None,
+            seed,
+        );
 
     // --- Condition 2: Evolving Population ---
     println!("── Condition 2: Evolving Population (5→15, mutation) ──");
-    let (evolved_snapshots, evolved_checkpoints, evolved_evo_snapshots) = run_condition(
-        "evolved",
-        &corpus,
-        total_ticks,
-        &checkpoint_ticks,
-        5,
-        &mut FitnessSpawnPolicy::new(15, 0.15),
-        0.15,
-    );
+    let (evolved_snapshots, evolved_checkpoints, evolved_evo_snapshots, evolved_population) =
+        run_condition(
+            "evolved",
+            &corpus,
+            total_ticks,
+            &checkpoint_ticks,
+            5,
+            Some(SpawnPolicySpec::fitness(15, 0.15)),
+            seed,
+        );
 
     // --- Condition 3: Random Spawn (control) ---
     println!("── Condition 3: Random Spawn (5→15, random genomes) ──");
-    let (_random_snapshots, random_checkpoints, random_evo_snapshots) = run_condition(
-        "random",
+    let (_random_snapshots, random_checkpoints, random_evo_snapshots, random_population) =
+        run_condition(
+            "random",
+            &corpus,
+            total_ticks,
+            &checkpoint_ticks,
+            5,
+            Some(SpawnPolicySpec {
+                // Only spreads the initial genomes; offspring are random
+                mutation_rate: 0.5,
+                ..SpawnPolicySpec::random(15)
+            }),
+            seed,
+        );
+
+    // --- Condition 4: Lamarckian Spawn (A/B against condition 2) ---
+    println!("── Condition 4: Lamarckian (5→15, inherited vocab) ───");
+    let (
+        _lamarckian_snapshots,
+        lamarckian_checkpoints,
+        lamarckian_evo_snapshots,
+        lamarckian_population,
+    ) = run_condition(
+        "lamarckian",
         &corpus,
         total_ticks,
         &checkpoint_ticks,
         5,
-        &mut RandomSpawnPolicy::new(15),
-        0.5,
+        Some(SpawnPolicySpec::lamarckian(15, 0.15, 0.5)),
+        seed,
     );
 
     // --- Comparison ---
@@ -89,6 +121,7 @@ fn main() {
             ("Static", &static_checkpoints),
             ("Evolved", &evolved_checkpoints),
             ("Random", &random_checkpoints),
+            ("Lamarckian", &lamarckian_checkpoints),
         ] {
             if let Some(m) = checkpoints.get(tick_idx) {
                 println!(
@@ -108,13 +141,53 @@ fn main() {
     // --- Evolution-specific metrics ---
     println!("── Evolution Metrics ─────────────────────────────────");
     println!();
-    for snap in &evolved_evo_snapshots {
-        println!("  Tick {:>4}: pop={:>2} gen={:>2} fit={:.3} div={:.3} sense={:.1} idle={:.0} explore={:.2}",
-            snap.tick, snap.population, snap.max_generation,
-            snap.mean_fitness, snap.genome_divergence,
-            snap.mean_sense_radius, snap.mean_max_idle, snap.mean_explore_bias);
+    for (name, evo_snapshots, population) in [
+        ("Evolved", &evolved_evo_snapshots, &evolved_population),
+        (
+            "Lamarckian",
+            &lamarckian_evo_snapshots,
+            &lamarckian_population,
+        ),
+    ] {
+        println!("  {}:", name);
+        for (snap, report) in evo_snapshots.iter().zip(population) {
+            println!("  Tick {:>4}: pop={:>2} gen={:>2} fit={:.3} div={:.3} sense={:.1} idle={:.0} explore={:.2} inherit={:.1}",
+                snap.tick, report.alive, report.max_generation,
+                snap.mean_fitness, snap.genome_divergence,
+                snap.mean_sense_radius, snap.mean_max_idle, snap.mean_explore_bias,
+                snap.mean_inherited_vocabulary);
+        }
+        if let Some(report) = population.last() {
+            println!();
+            print_population_report(report);
+        }
+        println!();
+    }
+
+    // --- Vocabulary inheritance A/B ---
+    if let (Some(ev), Some(lm)) = (evolved_checkpoints.last(), lamarckian_checkpoints.last()) {
+        println!(
+            "── Vocabulary Inheritance A/B (seed {}) ─────────────",
+            seed
+        );
+        println!(
+            "  Nodes: without={} with={} ({:+})",
+            ev.graph_richness.node_count,
+            lm.graph_richness.node_count,
+            lm.graph_richness.node_count as i64 - ev.graph_richness.node_count as i64
+        );
+        println!(
+            "  Edges: without={} with={} ({:+})",
+            ev.graph_richness.edge_count,
+            lm.graph_richness.edge_count,
+            lm.graph_richness.edge_count as i64 - ev.graph_richness.edge_count as i64
+        );
+        println!(
+            "  Clustering: without={:.3} with={:.3}",
+            ev.graph_richness.clustering_coefficient, lm.graph_richness.clustering_coefficient
+        );
+        println!();
     }
-    println!();
 
     // --- Hypothesis test ---
     let final_evolved = evolved_checkpoints.last();
@@ -155,21 +228,26 @@ fn main() {
 
     // CSV
     let mut csv = String::new();
-    csv.push_str("tick,condition,nodes,edges,density,clustering,avg_degree,genome_divergence\n");
+    csv.push_str(
+        "tick,condition,nodes,edges,density,clustering,avg_degree,genome_divergence,inherited_vocabulary\n",
+    );
     for (tick_idx, tick) in checkpoint_ticks.iter().enumerate() {
         for (name, checkpoints, evo_snaps) in [
             ("static", &static_checkpoints, &static_evo_snapshots),
             ("evolved", &evolved_checkpoints, &evolved_evo_snapshots),
             ("random", &random_checkpoints, &random_evo_snapshots),
+            (
+                "lamarckian",
+                &lamarckian_checkpoints,
+                &lamarckian_evo_snapshots,
+            ),
         ] {
             if let Some(m) = checkpoints.get(tick_idx) {
-                let div = evo_snaps
-                    .iter()
-                    .find(|s| s.tick == *tick)
-                    .map(|s| s.genome_divergence)
-                    .unwrap_or(0.0);
+                let evo = evo_snaps.iter().find(|s| s.tick == *tick);
+                let div = evo.map(|s| s.genome_divergence).unwrap_or(0.0);
+                let inherited = evo.map(|s| s.mean_inherited_vocabulary).unwrap_or(0.0);
                 csv.push_str(&format!(
-                    "{},{},{},{},{:.4},{:.4},{:.2},{:.4}\n",
+                    "{},{},{},{},{:.4},{:.4},{:.2},{:.4},{:.2}\n",
                     tick,
                     name,
                     m.graph_richness.node_count,
@@ -177,7 +255,8 @@ fn main() {
                     m.graph_richness.density,
                     m.graph_richness.clustering_coefficient,
                     m.graph_richness.avg_degree,
-                    div
+                    div,
+                    inherited
                 ));
             }
         }
@@ -190,8 +269,20 @@ fn main() {
     println!();
     println!("  CSV: poc/agent-evolution-demo/output/agent-evolution-benchmark.csv");
 
+    // Population reports, every checkpoint of every condition
+    for (name, population) in [
+        ("static", &static_population),
+        ("evolved", &evolved_population),
+        ("random", &random_population),
+        ("lamarckian", &lamarckian_population),
+    ] {
+        write_population_csvs(name, population);
+    }
+    println!("  CSV: poc/agent-evolution-demo/output/population-<condition>-*.csv");
+
     // HTML visualization (use evolved run)
-    let html = phago_viz::generate_html(&evolved_snapshots, &[]);
+    let html =
+        phago_viz::generate_html(&evolved_snapshots, &[]).expect("Failed to render visualization");
     std::fs::write(
         "poc/agent-evolution-demo/output/agent-evolution.html",
         &html,
@@ -202,100 +293,83 @@ fn main() {
     println!("══════════════════════════════════════════════════════");
 }
 
-/// Run one experimental condition and collect metrics.
+/// Write a condition's population reports as one survival, deaths and
+/// generations CSV each, checkpoints one after another.
+fn write_population_csvs(condition: &str, reports: &[PopulationReport]) {
+    for table in ["survival", "deaths", "generations"] {
+        let mut csv = String::new();
+        for (i, report) in reports.iter().enumerate() {
+            let rows = match table {
+                "survival" => report.survival_csv(),
+                "deaths" => report.deaths_csv(),
+                _ => report.generations_csv(),
+            };
+            // Keep the header of the first checkpoint only
+            let skip = if i == 0 { 0 } else { 1 };
+            for line in rows.lines().skip(skip) {
+                csv.push_str(line);
+                csv.push('\n');
+            }
+        }
+        std::fs::write(
+            format!("poc/agent-evolution-demo/output/population-{condition}-{table}.csv"),
+            &csv,
+        )
+        .ok();
+    }
+}
+
+/// Run one experimental condition and collect metrics. Initial genomes
+/// are spread by half the policy's mutation rate; agent IDs derive from
+/// `seed`, offspring's from the seeds after them.
 fn run_condition(
     name: &str,
     corpus: &Corpus,
     total_ticks: u64,
     checkpoint_ticks: &[u64],
     initial_agents: usize,
-    spawn_policy: &mut dyn SpawnPolicy,
-    mutation_rate: f64,
+    spawn_policy: Option<SpawnPolicySpec>,
+    seed: u64,
 ) -> (
     Vec<ColonySnapshot>,
     Vec<metrics::ColonyMetrics>,
     Vec<evolution_metrics::EvolutionSnapshot>,
+    Vec<PopulationReport>,
 ) {
-    let mut colony = Colony::new();
+    let mutation_rate = spawn_policy.as_ref().map_or(0.0, |spec| spec.mutation_rate);
+    let mut colony = Colony::from_config(ColonyConfig {
+        spawn_policy: spawn_policy.map(|spec| spec.with_seed(seed + initial_agents as u64)),
+        ..Default::default()
+    });
     corpus.ingest_into(&mut colony);
 
-    // Track genomes per agent
-    let mut agent_genomes: HashMap<AgentId, AgentGenome> = HashMap::new();
-    let mut fitness_tracker = FitnessTracker::new();
-
     // Spawn initial agents with default or slightly mutated genomes
+    let registry = AgentRegistry::new();
     for i in 0..initial_agents {
         let genome = if mutation_rate > 0.0 {
             AgentGenome::default_genome().mutate(mutation_rate * 0.5, i as u64)
         } else {
             AgentGenome::default_genome()
         };
-
-        let pos = Position::new((i % 5) as f64 * 5.0, (i / 5) as f64 * 5.0);
-        let digester = Digester::new(pos).with_max_idle(genome.max_idle);
-        let id = digester.id();
-        agent_genomes.insert(id, genome);
-        fitness_tracker.register(id, 0);
-        colony.spawn(Box::new(digester));
+        let params = json!({
+            "position": Position::new((i % 5) as f64 * 5.0, (i / 5) as f64 * 5.0),
+            "seed": seed + i as u64,
+            "genome": genome,
+        });
+        colony
+            .spawn_from_spec(&registry, &AgentSpec::new("digester", params))
+            .expect("digesters are built in");
     }
 
     let mut snapshots = Vec::new();
     let mut checkpoint_metrics = Vec::new();
     let mut evo_snapshots = Vec::new();
-    let mut _total_spawned = 0u64;
+    let mut population_reports = Vec::new();
 
     snapshots.push(colony.snapshot());
 
     for tick in 1..=total_ticks {
-        let events = colony.tick();
-
-        // Track fitness from events
-        let alive_ids: Vec<AgentId> = colony.agents().iter().map(|a| a.id()).collect();
-        fitness_tracker.tick_all(&alive_ids);
-
-        for event in &events {
-            match event {
-                ColonyEvent::Presented {
-                    id, fragment_count, ..
-                } => {
-                    fitness_tracker.record_concepts(id, *fragment_count as u64);
-                }
-                ColonyEvent::Wired {
-                    id,
-                    connection_count,
-                } => {
-                    fitness_tracker.record_edges(id, *connection_count as u64);
-                }
-                ColonyEvent::Died { signal } => {
-                    // On death, try to spawn replacement
-                    let fittest = fitness_tracker.fittest(&alive_ids);
-                    let fittest_genome = fittest.and_then(|f| agent_genomes.get(&f.agent_id));
-                    let fittest_pos = fittest.and_then(|f| {
-                        colony
-                            .agents()
-                            .iter()
-                            .find(|a| a.id() == f.agent_id)
-                            .map(|a| a.position())
-                    });
-
-                    if let Some((genome, pos)) = spawn_policy.on_death(
-                        signal.agent_id,
-                        colony.alive_count(),
-                        fittest_genome,
-                        fittest_pos,
-                    ) {
-                        let generation = fitness_tracker.next_generation();
-                        let digester = Digester::new(pos).with_max_idle(genome.max_idle);
-                        let id = digester.id();
-                        agent_genomes.insert(id, genome);
-                        fitness_tracker.register(id, generation);
-                        colony.spawn(Box::new(digester));
-                        _total_spawned += 1;
-                    }
-                }
-                _ => {}
-            }
-        }
+        colony.tick();
 
         // Collect snapshots every 50 ticks
         if tick % 50 == 0 {
@@ -304,21 +378,9 @@ fn run_condition(
 
         // Checkpoints
         if checkpoint_ticks.contains(&tick) {
-            let m = metrics::compute_from_snapshots(&colony, &snapshots);
-            checkpoint_metrics.push(m);
-
-            // Evolution snapshot
-            let alive_ids: Vec<AgentId> = colony.agents().iter().map(|a| a.id()).collect();
-            let genomes: Vec<AgentGenome> = alive_ids
-                .iter()
-                .filter_map(|id| agent_genomes.get(id).cloned())
-                .collect();
-            let fitness_data: Vec<&phago_agents::fitness::AgentFitness> = alive_ids
-                .iter()
-                .filter_map(|id| fitness_tracker.get(id))
-                .collect();
-            let evo_snap = evolution_metrics::build_snapshot(tick, &genomes, &fitness_data);
-            evo_snapshots.push(evo_snap);
+            checkpoint_metrics.push(metrics::compute_from_snapshots(&colony, &snapshots));
+            evo_snapshots.push(evolution_metrics::snapshot(&colony));
+            population_reports.push(metrics::population_report(&colony));
         }
     }
 
@@ -328,5 +390,32 @@ fn run_condition(
         name, stats.graph_nodes, stats.graph_edges, stats.agents_alive, stats.total_spawned
     );
 
-    (snapshots, checkpoint_metrics, evo_snapshots)
+    (
+        snapshots,
+        checkpoint_metrics,
+        evo_snapshots,
+        population_reports,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inheritance_shows_up_in_the_evolution_series() {
+        let corpus = Corpus::from_embedded();
+        let run = |policy: SpawnPolicySpec| {
+            let (_, checkpoints, evo, population) =
+                run_condition("test", &corpus, 150, &[150], 5, Some(policy), 7);
+            assert_eq!(population[0].tick, 150);
+            (checkpoints[0].graph_richness.edge_count, evo[0].clone())
+        };
+        let (edges_without, without) = run(SpawnPolicySpec::fitness(15, 0.15));
+        let (edges_with, with) = run(SpawnPolicySpec::lamarckian(15, 0.15, 0.5));
+
+        assert!(edges_without > 0 && edges_with > 0);
+        assert_eq!(without.mean_inherited_vocabulary, 0.0);
+        assert!(with.mean_inherited_vocabulary > 0.0);
+    }
 }
@@ -0,0 +1,34 @@
+"""Membrane transport model: a small Python fixture for cross-language retrieval."""
+
+from dataclasses import dataclass
+from typing import List
+
+GRADIENT_SCALE = 0.5
+
+
+@dataclass
+class IonChannel:
+    selectivity: str
+    conductance: float
+
+    def open(self):
+        return self.conductance > 0.0
+
+    def permeability(self, gradient):
+        return self.conductance * gradient * GRADIENT_SCALE
+
+
+class MembraneTransport:
+    def __init__(self, channels: List[IonChannel]):
+        self.channels = channels
+
+    def pump(self, gradient):
+        return sum(c.permeability(gradient) for c in self.channels if c.open())
+
+    @staticmethod
+    def osmosis(pressure):
+        return pressure * GRADIENT_SCALE
+
+
+def diffuse(transport, gradient):
+    return transport.pump(gradient)
@@ -4,17 +4,17 @@
 //! provides more contextually relevant code retrieval than static text search.
 //!
 //! Protocol:
-//! 1. Index phago-core crate (~15 .rs files, dogfooding)
-//! 2. CodeDigester extracts function names, types, imports
+//! 1. Index the workspace's .rs files (dogfooding) plus a small Python fixture
+//! 2. CodeDigester extracts function names, types, imports (per language),
+//!    qualifying definitions with their file
 //! 3. Run colony 100 ticks → build code knowledge graph
 //! 4. Query "Agent" → shows related types, functions, files
 //! 5. Save session → load session → verify fidelity
 //! 6. Compare graph retrieval vs grep baseline
 
-use phago_agents::code_digester;
-use phago_agents::digester::Digester;
-use phago_core::types::Position;
-use phago_rag::code_query;
+use phago_agents::code_digester::{self, CodeDigester, CodeLanguage};
+use phago_core::types::{split_qualified_label, Position};
+use phago_rag::code_query::{self, CodeQueryResult};
 use phago_runtime::colony::Colony;
 use phago_runtime::project_context;
 use phago_runtime::session;
@@ -34,7 +34,9 @@ fn main() {
         .parent()
         .unwrap();
     println!("── Phase 1: Scanning Project ──────────────────────────");
-    let source_files = project_context::scan_rust_files(project_root);
+    // A Python fixture (listed first so a digester starts next to it)
+    // shows retrieval works across languages
+    let source_files = scan(project_root);
     println!("  Project root: {}", project_root.display());
     println!("  Source files found: {}", source_files.len());
     for f in &source_files {
@@ -59,10 +61,8 @@ fn main() {
             continue;
         }
 
-        let doc_text = code_digester::elements_to_document(&elements, &sf.relative_path);
-        let x = (i % 5) as f64 * 5.0;
-        let y = (i / 5) as f64 * 5.0;
-        colony.ingest_document(&sf.relative_path, &doc_text, Position::new(x, y));
+        // Digested from source, so definitions know their file
+        colony.ingest_document(&sf.relative_path, &source, grid_position(i));
         file_names.push(sf.relative_path.clone());
 
         println!(
@@ -110,16 +110,10 @@ fn main() {
 
     // --- Phase 3: Run colony ---
     println!("── Phase 3: Colony Digestion (100 ticks) ──────────────");
-    for i in 0..source_files.len().min(20) {
-        let x = (i % 5) as f64 * 5.0;
-        let y = (i / 5) as f64 * 5.0;
-        colony.spawn(Box::new(
-            Digester::new(Position::new(x, y)).with_max_idle(80),
-        ));
-    }
+    spawn_digesters(&mut colony, source_files.len());
 
     let mut snapshots = vec![colony.snapshot()];
-    for tick in 1..=100 {
+    for tick in 1..=TICKS {
         colony.tick();
         if tick % 10 == 0 {
             snapshots.push(colony.snapshot());
@@ -135,21 +129,7 @@ fn main() {
 
     // --- Phase 4: Code queries ---
     println!("── Phase 4: Code Knowledge Queries ────────────────────");
-    let queries = vec![
-        ("Agent", vec!["agent", "id", "position", "tick"]),
-        ("Colony", vec!["colony", "spawn", "tick", "agents"]),
-        ("Substrate", vec!["substrate", "signals", "graph", "node"]),
-        ("Digester", vec!["digester", "digest", "engulf", "present"]),
-        ("NodeData", vec!["node", "label", "type", "access"]),
-        ("TopologyGraph", vec!["graph", "node", "edge", "neighbors"]),
-        ("Position", vec!["position", "new"]),
-        ("transfer", vec!["transfer", "vocabulary", "capability"]),
-        (
-            "apoptosis",
-            vec!["apoptosis", "death", "signal", "senescence"],
-        ),
-        ("membrane", vec!["membrane", "permeability", "boundary"]),
-    ];
+    let queries = queries();
 
     let mut graph_scores = Vec::new();
     let mut grep_scores = Vec::new();
@@ -159,10 +139,7 @@ fn main() {
 
         // Graph-based retrieval
         let graph_results = code_query::code_query(&colony, query, 10);
-        let graph_retrieved: Vec<String> = graph_results
-            .iter()
-            .map(|r| r.label.to_lowercase())
-            .collect();
+        let graph_retrieved = retrieved_labels(&graph_results);
         let graph_p5 = precision_at_k(&graph_retrieved, &relevant, 5);
 
         // Grep baseline: simple substring match on all node labels
@@ -190,7 +167,9 @@ fn main() {
                     .join(", ");
                 println!(
                     "      {} (score: {:.0}, related: {})",
-                    r.label, r.score, related_str
+                    r.qualified_label(),
+                    r.score,
+                    related_str
                 );
             }
         } else {
@@ -231,7 +210,7 @@ fn main() {
 
     // Restore and verify
     let mut restored_colony = Colony::new();
-    session::restore_into_colony(&mut restored_colony, &loaded_state);
+    session::restore_into_colony(&mut restored_colony, &loaded_state, None);
     let (identical, orig_n, orig_e, rest_n, rest_e) =
         session::verify_fidelity(&colony, &restored_colony);
     println!(
@@ -287,7 +266,8 @@ fn main() {
     )
     .ok();
 
-    let html = phago_viz::generate_html(&snapshots, colony.event_history());
+    let html = phago_viz::generate_html(&snapshots, colony.event_history())
+        .expect("Failed to render visualization");
     std::fs::write("poc/agentic-memory-demo/output/agentic-memory.html", &html).ok();
 
     println!("  CSV:  poc/agentic-memory-demo/output/agentic-memory-benchmark.csv");
@@ -297,15 +277,86 @@ fn main() {
     println!("══════════════════════════════════════════════════════");
 }
 
+/// Colony ticks the demo runs.
+const TICKS: u64 = 100;
+
+/// Source files to index under `root`: the workspace's Rust files, after
+/// a Python fixture (listed first so a digester starts next to it) that
+/// shows retrieval works across languages.
+fn scan(root: &Path) -> Vec<project_context::SourceFile> {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    let mut source_files = project_context::scan_source_files(&fixtures, &[CodeLanguage::Python]);
+    source_files.extend(project_context::scan_source_files(
+        root,
+        &[CodeLanguage::Rust],
+    ));
+    source_files
+}
+
+/// Where the `i`th file is ingested.
+fn grid_position(i: usize) -> Position {
+    Position::new((i % 5) as f64 * 5.0, (i / 5) as f64 * 5.0)
+}
+
+/// Spawn code digesters over the first files.
+fn spawn_digesters(colony: &mut Colony, files: usize) {
+    for i in 0..files.min(20) {
+        colony.spawn(Box::new(
+            CodeDigester::new(grid_position(i)).with_max_idle(80),
+        ));
+    }
+}
+
+/// Queries and the (lowercase) labels of results relevant to them.
+fn queries() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![
+        ("Agent", vec!["agent", "id", "position", "tick"]),
+        ("Colony", vec!["colony", "spawn", "tick", "agents"]),
+        ("Substrate", vec!["substrate", "signals", "graph", "node"]),
+        ("Digester", vec!["digester", "digest", "engulf", "present"]),
+        ("NodeData", vec!["node", "label", "type", "access"]),
+        ("TopologyGraph", vec!["graph", "node", "edge", "neighbors"]),
+        ("Position", vec!["position", "new"]),
+        ("transfer", vec!["transfer", "vocabulary", "capability"]),
+        (
+            "apoptosis",
+            vec!["apoptosis", "death", "signal", "senescence"],
+        ),
+        ("membrane", vec!["membrane", "permeability", "boundary"]),
+        // Answered by the Python fixture
+        (
+            "MembraneTransport",
+            vec![
+                "membranetransport",
+                "pump",
+                "osmosis",
+                "ionchannel",
+                "diffuse",
+            ],
+        ),
+        (
+            "IonChannel",
+            vec!["ionchannel", "permeability", "open", "dataclass"],
+        ),
+    ]
+}
+
+/// Plain labels of query results, lowercase, in rank order.
+fn retrieved_labels(results: &[CodeQueryResult]) -> Vec<String> {
+    results.iter().map(|r| r.label.to_lowercase()).collect()
+}
+
 /// Simple grep baseline: find node labels containing the query.
 fn grep_baseline(colony: &Colony, query: &str, max_results: usize) -> Vec<String> {
-    use phago_core::topology::TopologyGraph;
     let graph = colony.substrate().graph();
-    let found = graph.find_nodes_by_label(query);
-    found
+    let query = query.to_lowercase();
+    graph
+        .find_nodes_by_label(&query)
         .iter()
+        .filter_map(|nid| graph.get_node(nid))
+        .map(|n| split_qualified_label(&n.label).1.to_string())
+        .filter(|label| label.to_lowercase().contains(&query))
         .take(max_results)
-        .filter_map(|nid| graph.get_node(nid).map(|n| n.label.clone()))
         .collect()
 }
 
@@ -321,3 +372,59 @@ fn precision_at_k(retrieved: &[String], relevant: &HashSet<String>, k: usize) ->
         .count();
     hits as f64 / top_k.len() as f64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Labels that occur all over a codebase and say nothing about a query.
+    const GENERIC: [&str; 10] = [
+        "new", "id", "default", "from", "get", "crates", "src", "source", "tests", "use",
+    ];
+
+    /// A colony over the crates defining `Agent` and `Position`, digested
+    /// as the demo digests the workspace.
+    fn indexed_colony() -> Colony {
+        let crates = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../crates");
+        let mut colony = Colony::new();
+        let mut files = 0;
+        for name in ["phago-core", "phago-agents"] {
+            for sf in project_context::scan_source_files(&crates.join(name), &[CodeLanguage::Rust])
+            {
+                let source = std::fs::read_to_string(&sf.path).unwrap();
+                // Relative to the crates, so each crate's lib.rs is its own file
+                let path = format!("{name}/{}", sf.relative_path);
+                colony.ingest_document(&path, &source, grid_position(files));
+                files += 1;
+            }
+        }
+        spawn_digesters(&mut colony, files);
+        // Until every file is in, so that what ranks first does not
+        // depend on which files the digesters happened to reach
+        for _ in 0..3 * TICKS {
+            colony.tick();
+            if colony.stats().documents_digested == files {
+                break;
+            }
+        }
+        colony
+    }
+
+    #[test]
+    fn generic_identifiers_do_not_dominate_the_top_five() {
+        let colony = indexed_colony();
+        for query in ["Agent", "Position"] {
+            let results = code_query::code_query(&colony, query, 10);
+            let retrieved = retrieved_labels(&results);
+            let top: Vec<&String> = retrieved.iter().take(5).collect();
+            assert!(
+                top.iter().all(|l| !GENERIC.contains(&l.as_str())),
+                "{query}: {top:?}"
+            );
+
+            // A definition of the name itself comes first, under its file
+            assert_eq!(results[0].label, query.to_lowercase());
+            assert!(results[0].namespace.is_some(), "{query}: {top:?}");
+        }
+    }
+}
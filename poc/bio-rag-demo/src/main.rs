@@ -9,17 +9,25 @@
 //! 3. Execute same queries on frozen copy (static) — no reinforcement
 //! 4. Execute same queries with TF-IDF keyword matching
 //! 5. Output: P@5 improving per round vs flat vs fixed
+//!
+//! `--scorer NAME` scores the hybrid phase with another graph scorer (see
+//! `phago_rag::graph_scorer::NAMES`); `--scorer all` compares them all.
+//! `--corpus-name NAME` retrieves from another embedded corpus (see
+//! `phago_runtime::corpus::EMBEDDED_CORPORA`); the queries and their
+//! relevant documents are written for `biology`.
 
 use phago_agents::digester::Digester;
 use phago_rag::baseline::{random_query, static_graph_query, tfidf_query};
+use phago_rag::graph_scorer::{self, GraphScorer};
 use phago_rag::hybrid::{hybrid_query, HybridConfig};
 use phago_rag::scoring::{self, AggregateScores};
 use phago_rag::{Query, QueryEngine};
 use phago_runtime::bench::{self, BenchmarkConfig};
 use phago_runtime::colony::Colony;
-use phago_runtime::corpus::Corpus;
+use phago_runtime::corpus::{Corpus, EMBEDDED_CORPORA};
 use serde::Deserialize;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 struct QueryDef {
@@ -27,19 +35,71 @@ struct QueryDef {
     relevant: Vec<String>,
 }
 
+/// The graph scorers named by `--scorer`, or the default one.
+fn scorers_from_args() -> Vec<(&'static str, Arc<dyn GraphScorer>)> {
+    let args: Vec<String> = std::env::args().collect();
+    let requested = args
+        .iter()
+        .position(|a| a == "--scorer")
+        .and_then(|i| args.get(i + 1))
+        .map_or("default", String::as_str);
+    let names: Vec<&'static str> = match requested {
+        "all" => graph_scorer::NAMES.to_vec(),
+        name => match graph_scorer::NAMES.iter().find(|n| **n == name) {
+            Some(name) => vec![*name],
+            None => {
+                eprintln!(
+                    "Unknown scorer '{}'; expected one of {} or all",
+                    name,
+                    graph_scorer::NAMES.join(", ")
+                );
+                std::process::exit(2);
+            }
+        },
+    };
+    names
+        .into_iter()
+        .filter_map(|name| graph_scorer::named(name).map(|scorer| (name, scorer)))
+        .collect()
+}
+
+/// The embedded corpus named by `--corpus-name`, biology by default.
+fn corpus_from_args() -> Corpus {
+    let args: Vec<String> = std::env::args().collect();
+    let name = args
+        .iter()
+        .position(|a| a == "--corpus-name")
+        .and_then(|i| args.get(i + 1))
+        .map_or("biology", String::as_str);
+    Corpus::from_embedded_named(name).unwrap_or_else(|| {
+        eprintln!(
+            "Unknown corpus '{}'; expected one of {}",
+            name,
+            EMBEDDED_CORPORA.join(", ")
+        );
+        std::process::exit(2);
+    })
+}
+
 fn main() {
+    let scorers = scorers_from_args();
+    let corpus = corpus_from_args();
+
     println!("╔══════════════════════════════════════════════════════╗");
     println!("║  Bio-RAG: Self-Reinforcing Knowledge Graph Retrieval ║");
     println!("╚══════════════════════════════════════════════════════╝");
     println!();
 
     // --- Load corpus and queries ---
-    let corpus = Corpus::from_embedded();
     println!(
-        "Corpus: {} documents, {} categories",
+        "Corpus '{}': {} documents, {} categories",
+        corpus.name,
         corpus.len(),
         corpus.categories().len()
     );
+    if corpus.name != "biology" {
+        println!("  (the queries are written for the biology corpus; expect low precision)");
+    }
 
     let queries_json = include_str!("../data/queries.json");
     let queries: Vec<QueryDef> =
@@ -158,40 +218,49 @@ fn main() {
     // --- Phase 5: Hybrid scoring (TF-IDF + graph re-ranking) ---
     println!("── Phase 5: Hybrid Scoring (TF-IDF + Graph) ────────────");
     let alphas = [0.3, 0.5, 0.7];
-    let mut best_hybrid_agg: Option<(f64, AggregateScores)> = None;
-    for alpha in &alphas {
+    let mut best_hybrid_agg: Option<(&str, f64, AggregateScores)> = None;
+    for ((scorer_name, scorer), alpha) in scorers
+        .iter()
+        .flat_map(|scorer| alphas.iter().map(move |alpha| (scorer, alpha)))
+    {
         let hybrid_config = HybridConfig {
-            alpha: *alpha,
-            max_results: 10,
-            candidate_multiplier: 3,
+            graph_scorer: scorer.clone(),
+            ..HybridConfig::builder()
+                .alpha(*alpha)
+                .max_results(10)
+                .build()
+                .expect("valid hybrid config")
         };
         let mut hybrid_scores = Vec::new();
         for qdef in &queries {
             let relevant: HashSet<String> = qdef.relevant.iter().cloned().collect();
-            let results = hybrid_query(&colony, &qdef.query, &hybrid_config);
+            let results = hybrid_query(&colony, &qdef.query, &hybrid_config)
+                .expect("hybrid config was validated");
             let retrieved: Vec<String> = results.iter().map(|r| r.label.clone()).collect();
             let score = scoring::score_query(&qdef.query, &retrieved, &relevant);
             hybrid_scores.push(score);
         }
         let agg = scoring::aggregate(&hybrid_scores);
         println!(
-            "  Hybrid (α={:.1}): P@5={:.3} P@10={:.3} MRR={:.3} NDCG@10={:.3}",
+            "  Hybrid ({}, α={:.1}): P@5={:.3} P@10={:.3} MRR={:.3} NDCG@10={:.3}",
+            scorer_name,
             alpha,
             agg.mean_precision_at_5,
             agg.mean_precision_at_10,
             agg.mean_mrr,
             agg.mean_ndcg_at_10
         );
-        if best_hybrid_agg.as_ref().map_or(true, |(_, best)| {
-            agg.mean_precision_at_5 > best.mean_precision_at_5
-        }) {
-            best_hybrid_agg = Some((*alpha, agg));
+        if best_hybrid_agg
+            .as_ref()
+            .is_none_or(|(_, _, best)| agg.mean_precision_at_5 > best.mean_precision_at_5)
+        {
+            best_hybrid_agg = Some((scorer_name, *alpha, agg));
         }
     }
-    let (best_alpha, hybrid_agg) = best_hybrid_agg.unwrap();
+    let (best_scorer, best_alpha, hybrid_agg) = best_hybrid_agg.unwrap();
     println!(
-        "  Best: α={:.1} P@5={:.3}",
-        best_alpha, hybrid_agg.mean_precision_at_5
+        "  Best: {} α={:.1} P@5={:.3}",
+        best_scorer, best_alpha, hybrid_agg.mean_precision_at_5
     );
     println!();
 
@@ -267,8 +336,8 @@ fn main() {
         tfidf_agg.mean_precision_at_5
     );
     println!(
-        "  Hybrid:     P@5={:.3} (α={:.1}, TF-IDF+Graph)",
-        hybrid_agg.mean_precision_at_5, best_alpha
+        "  Hybrid:     P@5={:.3} ({}, α={:.1}, TF-IDF+Graph)",
+        hybrid_agg.mean_precision_at_5, best_scorer, best_alpha
     );
     println!(
         "  Random:     P@5={:.3} (baseline)",
@@ -324,7 +393,8 @@ fn main() {
         tfidf_agg.mean_ndcg_at_10
     ));
     csv.push_str(&format!(
-        "1,hybrid_{:.1},{:.4},{:.4},{:.4},{:.4}\n",
+        "1,hybrid_{}_{:.1},{:.4},{:.4},{:.4},{:.4}\n",
+        best_scorer,
         best_alpha,
         hybrid_agg.mean_precision_at_5,
         hybrid_agg.mean_precision_at_10,
@@ -343,7 +413,8 @@ fn main() {
     println!("  Benchmark CSV: poc/bio-rag-demo/output/bio-rag-benchmark.csv");
 
     // HTML visualization
-    let html = phago_viz::generate_html(&digestion_run.snapshots, colony.event_history());
+    let html = phago_viz::generate_html_series(&digestion_run.snapshots, colony.event_history())
+        .expect("Failed to render visualization");
     std::fs::write("poc/bio-rag-demo/output/bio-rag.html", &html).expect("Failed to write HTML");
     println!("  Visualization: poc/bio-rag-demo/output/bio-rag.html");
 
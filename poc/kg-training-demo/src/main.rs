@@ -9,29 +9,56 @@
 //! 3. Detect communities via label propagation
 //! 4. Generate curriculum-ordered JSONL
 //! 5. Measure NMI vs ground truth
+//!
+//! Pass `--chat` to also emit chat-format train/val JSONL with negative
+//! pairs and source-document context, and `--corpus-name NAME` to train on
+//! another embedded corpus (see `phago_runtime::corpus::EMBEDDED_CORPORA`).
 
 use phago_agents::digester::Digester;
 use phago_runtime::bench::{self, BenchmarkConfig};
 use phago_runtime::colony::Colony;
 use phago_runtime::community;
-use phago_runtime::corpus::Corpus;
+use phago_runtime::corpus::{Corpus, EMBEDDED_CORPORA};
 use phago_runtime::curriculum;
 use phago_runtime::export;
 use phago_runtime::training_format;
 
+/// The embedded corpus named by `--corpus-name`, biology by default.
+fn corpus_from_args() -> Corpus {
+    let args: Vec<String> = std::env::args().collect();
+    let name = args
+        .iter()
+        .position(|a| a == "--corpus-name")
+        .and_then(|i| args.get(i + 1))
+        .map_or("biology", String::as_str);
+    Corpus::from_embedded_named(name).unwrap_or_else(|| {
+        eprintln!(
+            "Unknown corpus '{}'; expected one of {}",
+            name,
+            EMBEDDED_CORPORA.join(", ")
+        );
+        std::process::exit(2);
+    })
+}
+
 fn main() {
+    let emit_chat = std::env::args().any(|a| a == "--chat");
+    let corpus = corpus_from_args().limit(40);
+
     println!("╔══════════════════════════════════════════════════════╗");
     println!("║  KG-Training: Hebbian Graph → Training Data         ║");
     println!("╚══════════════════════════════════════════════════════╝");
     println!();
 
     // --- Phase 1: Build colony ---
-    let corpus = Corpus::from_embedded().limit(40);
     let _ground_truth = corpus.ground_truth();
+    let corpus_stats = corpus.stats();
     println!(
-        "Corpus: {} documents, {} categories",
+        "Corpus '{}': {} documents, {} categories, mean category overlap {:.3}",
+        corpus.name,
         corpus.len(),
-        corpus.categories().len()
+        corpus.categories().len(),
+        corpus_stats.mean_category_overlap()
     );
 
     let mut colony = Colony::new();
@@ -219,6 +246,26 @@ fn main() {
     )
     .ok();
 
+    // Chat-format JSONL (instruction tuning)
+    if emit_chat {
+        let template = training_format::ChatTemplate::default()
+            .with_sources(training_format::SourceContext::from_colony(&colony, 3))
+            .with_negatives(training_format::Negatives {
+                ratio: 0.5,
+                seed: 42,
+            });
+        let split = training_format::to_chat_jsonl_split(
+            &cur,
+            template,
+            training_format::ValSplit {
+                val_ratio: 0.1,
+                seed: 42,
+            },
+        );
+        std::fs::write("poc/kg-training-demo/output/chat-train.jsonl", &split.train).ok();
+        std::fs::write("poc/kg-training-demo/output/chat-val.jsonl", &split.val).ok();
+    }
+
     // CSV benchmark
     let mut csv = String::new();
     csv.push_str("metric,value\n");
@@ -247,11 +294,16 @@ fn main() {
     .ok();
 
     // HTML visualization
-    let html = phago_viz::generate_html(&run.snapshots, colony.event_history());
+    let html = phago_viz::generate_html_series(&run.snapshots, colony.event_history())
+        .expect("Failed to render visualization");
     std::fs::write("poc/kg-training-demo/output/kg-training.html", &html).ok();
 
     println!("  JSONL (curriculum): poc/kg-training-demo/output/curriculum-ordered.jsonl");
     println!("  JSONL (random):     poc/kg-training-demo/output/random-ordered.jsonl");
+    if emit_chat {
+        println!("  JSONL (chat train): poc/kg-training-demo/output/chat-train.jsonl");
+        println!("  JSONL (chat val):   poc/kg-training-demo/output/chat-val.jsonl");
+    }
     println!("  CSV:                poc/kg-training-demo/output/kg-training-benchmark.csv");
     println!("  HTML:               poc/kg-training-demo/output/kg-training.html");
     println!();
@@ -9,6 +9,7 @@ use phago_agents::sentinel::Sentinel;
 use phago_agents::synthesizer::Synthesizer;
 use phago_core::types::*;
 use phago_runtime::colony::{Colony, ColonyEvent, ColonySnapshot};
+use phago_runtime::metrics::VocabularyConvergence;
 
 fn main() {
     println!("╔══════════════════════════════════════════════════════╗");
@@ -96,7 +97,7 @@ fn main() {
     println!();
 
     // Digesters — one near each document cluster
-    let digester_positions = vec![
+    let digester_positions = [
         Position::new(0.0, 0.0),
         Position::new(5.0, 0.0),
         Position::new(0.0, 5.0),
@@ -145,9 +146,11 @@ fn main() {
     let mut total_symbioses = 0u64;
     let mut total_dissolutions = 0u64;
     let mut snapshots: Vec<ColonySnapshot> = Vec::new();
+    let mut convergence = VocabularyConvergence::default();
 
     // Take initial snapshot
     snapshots.push(colony.snapshot());
+    convergence.record(&colony);
 
     for tick_num in 1..=120 {
         let events = colony.tick();
@@ -214,13 +217,15 @@ fn main() {
                 ColonyEvent::CapabilityIntegrated {
                     agent_id,
                     from_agent,
-                    terms_count,
+                    terms_accepted,
+                    terms_offered,
                 } => {
                     println!(
-                        "  [tick {:>3}] INTEGRATE: Agent {:.8} absorbed {} terms from {:.8}",
+                        "  [tick {:>3}] INTEGRATE: Agent {:.8} absorbed {}/{} terms from {:.8}",
                         tick_num,
                         agent_id.0.to_string(),
-                        terms_count,
+                        terms_accepted,
+                        terms_offered,
                         from_agent.0.to_string()
                     );
                     total_integrations += 1;
@@ -259,10 +264,13 @@ fn main() {
             }
         }
 
-        // Take snapshot every 5 ticks
+        // Take snapshot every 5 ticks, and see how far vocabularies converged
         if tick_num % 5 == 0 {
             snapshots.push(colony.snapshot());
         }
+        if tick_num % 20 == 0 {
+            convergence.record(&colony);
+        }
     }
 
     // --- Results ---
@@ -288,7 +296,6 @@ fn main() {
     println!();
 
     // Show top concepts (nodes with highest access count)
-    use phago_core::topology::TopologyGraph;
     let graph = colony.substrate().graph();
     let mut concept_nodes = Vec::new();
     let mut insight_nodes = Vec::new();
@@ -309,7 +316,7 @@ fn main() {
             }
         }
     }
-    concept_nodes.sort_by(|a, b| b.1.cmp(&a.1));
+    concept_nodes.sort_by_key(|c| std::cmp::Reverse(c.1));
 
     println!("  Top Concepts (by reinforcement):");
     for (label, count) in concept_nodes.iter().take(10) {
@@ -385,9 +392,12 @@ fn main() {
     println!();
     let metrics = phago_runtime::metrics::compute_from_snapshots(&colony, &snapshots);
     phago_runtime::metrics::print_report(&metrics);
+    println!();
+    phago_runtime::metrics::print_convergence(&convergence);
 
     // --- Phase 5: HTML Visualization ---
-    let html = phago_viz::generate_html(&snapshots, colony.event_history());
+    let html = phago_viz::generate_html(&snapshots, colony.event_history())
+        .expect("Failed to render visualization");
 
     // Write to output directory
     std::fs::create_dir_all("output").ok();